@@ -0,0 +1,162 @@
+//! Compressed store for finished transfer records
+//!
+//!   bridge-cli compression init --keypair owner.json [--max-depth 20 ...]
+//!   bridge-cli compression crank --keypair payer.json [--limit 100]
+//!
+//! `init` allocates a new tree and points the store at it, which is also
+//! how a full tree is replaced. `crank` moves finished transfer records
+//! into the tree; anyone may run it, and the records' rent goes to the
+//! bridge owner. Proofs for compressed records come from the indexer's
+//! `compressedTransfer` query.
+
+use std::path::{Path, PathBuf};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::{bail, Context as _, Result};
+use clap::Subcommand;
+use solana_bridge::compression::{COMPRESSION_PROGRAM, NOOP_PROGRAM};
+use solana_bridge::{accounts, instruction, CompressedStore};
+use solana_bridge_sdk::{compression, pda};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_program;
+
+use crate::support::Context;
+
+/// compress_transfer_record instructions per transaction
+const PER_TRANSACTION: usize = 4;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Allocate a new tree and point the compressed store at it (owner)
+    Init {
+        /// Bridge owner; also pays for the tree account
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// The tree holds 2^max_depth records
+        #[arg(long, default_value_t = 20)]
+        max_depth: u32,
+
+        /// Concurrent changes a proof may lag behind
+        #[arg(long, default_value_t = 256)]
+        max_buffer: u32,
+
+        /// Top levels stored on-chain, shortening proofs
+        #[arg(long, default_value_t = 10)]
+        canopy: u32,
+    },
+
+    /// Compress finished transfer records (anyone)
+    Crank {
+        /// Pays the fees
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Most records to compress
+        #[arg(long, default_value_t = 100)]
+        limit: usize,
+    },
+}
+
+pub fn run(ctx: &Context, command: Command) -> Result<()> {
+    match command {
+        Command::Init {
+            keypair,
+            max_depth,
+            max_buffer,
+            canopy,
+        } => init(ctx, &keypair, max_depth, max_buffer, canopy),
+        Command::Crank { keypair, limit } => crank(ctx, &keypair, limit),
+    }
+}
+
+fn init(ctx: &Context, keypair: &Path, max_depth: u32, max_buffer: u32, canopy: u32) -> Result<()> {
+    let owner = read_keypair(keypair)?;
+    let program_id = ctx.solana.program_id();
+    let tree = Keypair::new();
+    let size = compression::tree_account_size(max_depth, max_buffer, canopy);
+    let lamports = ctx.solana.rent_exempt(size)?;
+
+    let create = compression::create_tree(
+        &owner.pubkey(),
+        &tree.pubkey(),
+        max_depth,
+        max_buffer,
+        canopy,
+        lamports,
+    );
+    let init = Instruction {
+        program_id,
+        accounts: accounts::InitCompressedStore {
+            owner: owner.pubkey(),
+            bridge_state: pda::bridge_state(&program_id),
+            compressed_store: pda::compressed_store(&program_id),
+            merkle_tree: tree.pubkey(),
+            tree_authority: pda::tree_authority(&program_id),
+            compression_program: COMPRESSION_PROGRAM,
+            noop_program: NOOP_PROGRAM,
+            admin_log: pda::admin_log(&program_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::InitCompressedStore {
+            max_depth,
+            max_buffer_size: max_buffer,
+        }
+        .data(),
+    };
+
+    let signature = ctx
+        .solana
+        .send_instructions_signed(&[create, init], &owner, &[&tree])?;
+    println!(
+        "Compressed store now {} ({size} bytes, {lamports} lamports rent): {signature}",
+        tree.pubkey()
+    );
+    Ok(())
+}
+
+fn crank(ctx: &Context, keypair: &Path, limit: usize) -> Result<()> {
+    let payer = read_keypair(keypair)?;
+    let program_id = ctx.solana.program_id();
+    let owner = ctx.solana.bridge_state()?.owner;
+    let Some(store) = ctx
+        .solana
+        .fetch::<CompressedStore>(&pda::compressed_store(&program_id))?
+    else {
+        bail!("No compressed store yet; run `compression init`");
+    };
+
+    let finished: Vec<_> = ctx
+        .solana
+        .transfer_accounts(Vec::new())?
+        .into_iter()
+        .filter(|(_, record)| record.is_final())
+        .take(limit)
+        .collect();
+    let room = (1u64 << store.max_depth) - store.leaves;
+    if finished.len() as u64 > room {
+        println!("Tree has room for {room} more; run `compression init` for a new one after");
+    }
+
+    let mut compressed = 0;
+    for chunk in finished[..finished.len().min(room as usize)].chunks(PER_TRANSACTION) {
+        let instructions: Vec<Instruction> = chunk
+            .iter()
+            .map(|(address, _)| compression::compress(&program_id, &owner, &store.tree, address))
+            .collect();
+        let signature = ctx
+            .solana
+            .send_instructions(&instructions, &payer)
+            .with_context(|| format!("Failed after compressing {compressed} record(s)"))?;
+        compressed += chunk.len();
+        println!("Compressed {} record(s): {signature}", chunk.len());
+    }
+    println!("{compressed} record(s) compressed into {}", store.tree);
+    Ok(())
+}
+
+fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+}
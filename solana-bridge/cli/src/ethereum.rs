@@ -0,0 +1,151 @@
+//! Ethereum bridge reads over plain JSON-RPC
+//!
+//! Only what tracing a transfer needs: whether a Solana transfer was
+//! minted (processedNonces) and finding the Lock/Burn behind an inbound
+//! nonce. Event fields are decoded by hand from the ABI layout.
+
+use std::time::Duration;
+
+use anyhow::{bail, Context, Result};
+use bridge_core::codec;
+use reqwest::blocking::Client;
+use serde_json::{json, Value};
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Blocks per eth_getLogs request (providers cap the range)
+const LOG_RANGE: u64 = 5_000;
+
+/// A Lock or Burn on the Ethereum bridge
+pub struct Deposit {
+    pub kind: &'static str,
+    pub tx_hash: String,
+    pub block: u64,
+    pub sender: String,
+    pub amount: u64,
+    pub solana_address: String,
+}
+
+pub struct Ethereum {
+    url: String,
+    bridge: String,
+    client: Client,
+}
+
+impl Ethereum {
+    pub fn new(url: &str, bridge: &str) -> Result<Self> {
+        Ok(Self {
+            url: url.to_string(),
+            bridge: bridge.to_lowercase(),
+            client: Client::builder().timeout(TIMEOUT).build()?,
+        })
+    }
+
+    fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()?
+            .error_for_status()?
+            .json()?;
+        if let Some(error) = response.get("error") {
+            bail!("{method}: {error}");
+        }
+        Ok(response["result"].take())
+    }
+
+    pub fn block_number(&self) -> Result<u64> {
+        parse_quantity(&self.rpc("eth_blockNumber", json!([]))?)
+    }
+
+    /// Whether the bridge has minted the Solana transfer `transfer_id`
+    pub fn is_processed(&self, transfer_id: &[u8; 32]) -> Result<bool> {
+        let data = format!(
+            "0x{}{}",
+            hex::encode(codec::selector("processedNonces(bytes32)")),
+            hex::encode(transfer_id)
+        );
+        let result = self.rpc("eth_call", json!([{ "to": self.bridge, "data": data }, "latest"]))?;
+        let bytes = decode_hex(&result)?;
+        Ok(bytes.last().is_some_and(|b| *b != 0))
+    }
+
+    /// The Lock or Burn with `nonce` in blocks `from..=to`, if any
+    pub fn find_deposit(&self, nonce: u64, from: u64, to: u64) -> Result<Option<Deposit>> {
+        let lock = topic("Lock(address,uint256,uint256,string)");
+        let burn = topic("Burn(address,uint256,uint256,string)");
+
+        let mut start = from;
+        while start <= to {
+            let end = (start + LOG_RANGE - 1).min(to);
+            let logs = self.rpc(
+                "eth_getLogs",
+                json!([{
+                    "address": self.bridge,
+                    "fromBlock": format!("{start:#x}"),
+                    "toBlock": format!("{end:#x}"),
+                    "topics": [[lock, burn]],
+                }]),
+            )?;
+
+            for log in logs.as_array().into_iter().flatten() {
+                let data = decode_hex(&log["data"])?;
+                if word_u64(&data, 1)? != nonce {
+                    continue;
+                }
+                let sender = log["topics"][1].as_str().context("Log without sender topic")?;
+                return Ok(Some(Deposit {
+                    kind: if log["topics"][0].as_str() == Some(lock.as_str()) {
+                        "Lock"
+                    } else {
+                        "Burn"
+                    },
+                    tx_hash: log["transactionHash"].as_str().unwrap_or_default().to_string(),
+                    block: parse_quantity(&log["blockNumber"])?,
+                    sender: format!("0x{}", &sender[sender.len() - 40..]),
+                    amount: word_u64(&data, 0)?,
+                    solana_address: abi_string(&data, 2)?,
+                }));
+            }
+            start = end + 1;
+        }
+        Ok(None)
+    }
+}
+
+fn topic(signature: &str) -> String {
+    format!("0x{}", hex::encode(codec::event_topic(signature)))
+}
+
+fn decode_hex(value: &Value) -> Result<Vec<u8>> {
+    let text = value.as_str().context("Expected a hex string")?;
+    Ok(hex::decode(text.trim_start_matches("0x"))?)
+}
+
+fn parse_quantity(value: &Value) -> Result<u64> {
+    let text = value.as_str().context("Expected a hex quantity")?;
+    Ok(u64::from_str_radix(text.trim_start_matches("0x"), 16)?)
+}
+
+/// ABI word `index` of `data` as a u64 (Solana amounts and nonces fit)
+fn word_u64(data: &[u8], index: usize) -> Result<u64> {
+    let word = data
+        .get(index * 32..(index + 1) * 32)
+        .context("Log data too short")?;
+    if word[..24].iter().any(|b| *b != 0) {
+        bail!("Value in word {index} does not fit in u64");
+    }
+    Ok(u64::from_be_bytes(word[24..].try_into()?))
+}
+
+/// The dynamic `string` whose offset is ABI word `index`
+fn abi_string(data: &[u8], index: usize) -> Result<String> {
+    let offset = word_u64(data, index)? as usize;
+    let len = word_u64(data, offset / 32)? as usize;
+    let bytes = data
+        .get(offset + 32..offset + 32 + len)
+        .context("Log data too short")?;
+    Ok(String::from_utf8_lossy(bytes).into_owned())
+}
@@ -0,0 +1,165 @@
+//! Demo token faucet (localnet/devnet)
+//!
+//!   bridge-cli faucet init --keypair owner.json [--drip 100 --cooldown 60]
+//!   bridge-cli faucet drip --keypair wallet.json
+//!   bridge-cli faucet show
+//!
+//! `init` creates the demo token through the demo-faucet program and
+//! registers it with the bridge, so the bridge owner signs it once.
+//! `drip` mints some to any wallet, which can lock it right away.
+
+use std::path::{Path, PathBuf};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use demo_faucet::Faucet;
+use solana_bridge_sdk::pda;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_program;
+
+use crate::support::Context;
+
+/// Base units per whole demo token
+const UNIT: u64 = 10u64.pow(demo_faucet::DECIMALS as u32);
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Create the demo token and register it with the bridge (owner)
+    Init {
+        /// Bridge owner; also pays for the accounts
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Whole tokens per drip
+        #[arg(long, default_value_t = 100)]
+        drip: u64,
+
+        /// Seconds a wallet waits between drips
+        #[arg(long, default_value_t = 60)]
+        cooldown: i64,
+    },
+
+    /// Mint demo tokens to a wallet (anyone)
+    Drip {
+        /// Receives the tokens and pays the fees
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+
+    /// Show the demo token and drip settings
+    Show,
+}
+
+pub fn run(ctx: &Context, command: Command) -> Result<()> {
+    match command {
+        Command::Init {
+            keypair,
+            drip,
+            cooldown,
+        } => init(ctx, &keypair, drip, cooldown),
+        Command::Drip { keypair } => drip(ctx, &keypair),
+        Command::Show => show(ctx),
+    }
+}
+
+fn init(ctx: &Context, keypair: &Path, drip: u64, cooldown: i64) -> Result<()> {
+    let owner = read_keypair(keypair)?;
+    let program_id = ctx.solana.program_id();
+    if program_id != solana_bridge::ID {
+        bail!("The demo faucet registers its token with the bridge at {}", solana_bridge::ID);
+    }
+    let Some(drip_amount) = drip.checked_mul(UNIT) else {
+        bail!("--drip {drip} is too large");
+    };
+    let (faucet, mint) = addresses();
+
+    let instruction = Instruction {
+        program_id: demo_faucet::ID,
+        accounts: demo_faucet::accounts::Initialize {
+            owner: owner.pubkey(),
+            faucet,
+            mint,
+            bridge_state: pda::bridge_state(&program_id),
+            admin_log: pda::admin_log(&program_id),
+            token_config: pda::token_config(&program_id, &mint),
+            bridge_program: program_id,
+            token_program: anchor_spl::token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: demo_faucet::instruction::Initialize {
+            drip_amount,
+            cooldown,
+        }
+        .data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &owner)?;
+    println!("Demo token {mint} registered with the bridge: {signature}");
+    Ok(())
+}
+
+fn drip(ctx: &Context, keypair: &Path) -> Result<()> {
+    let user = read_keypair(keypair)?;
+    let (faucet, mint) = addresses();
+    if ctx.solana.fetch::<Faucet>(&faucet)?.is_none() {
+        bail!("No demo faucet yet; run `faucet init`");
+    }
+
+    let token_account = get_associated_token_address(&user.pubkey(), &mint);
+    let instruction = Instruction {
+        program_id: demo_faucet::ID,
+        accounts: demo_faucet::accounts::Drip {
+            user: user.pubkey(),
+            faucet,
+            mint,
+            drip_record: Pubkey::find_program_address(
+                &[b"drip", user.pubkey().as_ref()],
+                &demo_faucet::ID,
+            )
+            .0,
+            user_token_account: token_account,
+            token_program: anchor_spl::token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: demo_faucet::instruction::Drip {}.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &user)?;
+    println!("Dripped demo tokens into {token_account}: {signature}");
+    Ok(())
+}
+
+fn show(ctx: &Context) -> Result<()> {
+    let (address, mint) = addresses();
+    let Some(faucet) = ctx.solana.fetch::<Faucet>(&address)? else {
+        bail!("No demo faucet yet; run `faucet init`");
+    };
+    println!("Faucet:    {address}");
+    println!("Mint:      {mint} ({} decimals)", demo_faucet::DECIMALS);
+    println!("Authority: {}", faucet.authority);
+    println!(
+        "Drip:      {} every {}s",
+        faucet.drip_amount as f64 / UNIT as f64,
+        faucet.cooldown
+    );
+    println!("Dripped:   {}", faucet.dripped as f64 / UNIT as f64);
+    Ok(())
+}
+
+/// The faucet config and demo mint PDAs
+fn addresses() -> (Pubkey, Pubkey) {
+    let faucet = Pubkey::find_program_address(&[b"faucet"], &demo_faucet::ID).0;
+    let mint = Pubkey::find_program_address(&[b"demo_mint"], &demo_faucet::ID).0;
+    (faucet, mint)
+}
+
+fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+}
@@ -0,0 +1,457 @@
+//! Guardian council rotation as an air-gapped ceremony
+//!
+//!   1. propose-rotation (online): build the rotate_guardian_council
+//!      transaction on a durable nonce, so it doesn't expire while the
+//!      payload travels, and write it to a JSON file
+//!   2. sign-rotation (offline, once per council member): decode and show
+//!      what the message actually does, then add this key's signature
+//!   3. submit-rotation (online): check every signature and send
+//!
+//! The payload's `members`/`threshold` are for reading only; signers are
+//! shown what's decoded from the message, and a payload whose summary
+//! doesn't match its message is rejected.
+//!
+//! `heartbeat` is the operators' liveness signal: run it from each
+//! guardian's and the relayer's host (e.g. cron, once per epoch). It keeps
+//! the key's liveness registry entry current, and when the bridge requires
+//! guardian heartbeats, mint and unlock stop once they lapse. `liveness`
+//! reads the registry back.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, Discriminator, InstructionData, ToAccountMetas};
+use anyhow::{bail, Context as _, Result};
+use base64::Engine;
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use solana_bridge::{accounts, instruction, GuardianSet, OperatorLiveness};
+use solana_bridge_sdk::liveness::{LivenessReport, OperatorStatus, Verdict};
+use solana_bridge_sdk::pda;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::Message;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signature, Signer};
+use solana_sdk::system_instruction::SystemInstruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use crate::support::Context;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Build an unsigned council rotation payload (online)
+    ProposeRotation {
+        /// New council members
+        #[arg(long = "member", required = true, value_delimiter = ',')]
+        members: Vec<Pubkey>,
+
+        /// New signatures required
+        #[arg(long)]
+        threshold: u8,
+
+        /// Current members who will sign; at least the current threshold
+        #[arg(long = "signer", required = true, value_delimiter = ',')]
+        signers: Vec<Pubkey>,
+
+        /// Pays the fee and advances the nonce; signs at submission
+        #[arg(long)]
+        fee_payer: Pubkey,
+
+        /// Durable nonce account, with the fee payer as its authority
+        #[arg(long)]
+        nonce_account: Pubkey,
+
+        #[arg(long, default_value = "rotation.json")]
+        out: PathBuf,
+    },
+
+    /// Review a payload and add a signature to it (offline)
+    SignRotation {
+        payload: PathBuf,
+
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Sign without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Check a fully signed payload and send it (online)
+    SubmitRotation {
+        payload: PathBuf,
+
+        /// Fee payer keypair, if it hasn't signed the payload already
+        #[arg(long)]
+        keypair: Option<PathBuf>,
+    },
+
+    /// Post a liveness heartbeat as a guardian or the owner
+    Heartbeat {
+        /// Guardian or owner keypair; also pays the fee
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+
+    /// Show when each operator last posted a heartbeat
+    Liveness {
+        /// Seconds without a heartbeat before an operator counts as gone
+        #[arg(long, default_value_t = 7200)]
+        max_age: i64,
+    },
+}
+
+/// What travels between the ceremony's machines
+#[derive(Serialize, Deserialize)]
+struct Payload {
+    program_id: String,
+    members: Vec<String>,
+    threshold: u8,
+    /// base64 of the serialized message
+    message: String,
+    /// signer pubkey -> signature (base58)
+    signatures: BTreeMap<String, String>,
+}
+
+/// The rotation a message performs
+struct Rotation {
+    members: Vec<Pubkey>,
+    threshold: u8,
+    council_signers: Vec<Pubkey>,
+    nonce_account: Pubkey,
+}
+
+pub fn run(ctx: &Context, command: Command) -> Result<()> {
+    match command {
+        Command::ProposeRotation {
+            members,
+            threshold,
+            signers,
+            fee_payer,
+            nonce_account,
+            out,
+        } => propose(ctx, members, threshold, signers, fee_payer, nonce_account, &out),
+        Command::SignRotation {
+            payload,
+            keypair,
+            yes,
+        } => sign(&payload, &keypair, yes),
+        Command::SubmitRotation { payload, keypair } => submit(ctx, &payload, keypair.as_deref()),
+        Command::Heartbeat { keypair } => heartbeat(ctx, &keypair),
+        Command::Liveness { max_age } => liveness(ctx, max_age),
+    }
+}
+
+fn propose(
+    ctx: &Context,
+    members: Vec<Pubkey>,
+    threshold: u8,
+    signers: Vec<Pubkey>,
+    fee_payer: Pubkey,
+    nonce_account: Pubkey,
+    out: &Path,
+) -> Result<()> {
+    if threshold == 0 || threshold as usize > members.len() {
+        bail!("Threshold must be between 1 and the number of members");
+    }
+
+    let council = ctx.solana.guardian_council()?;
+    if let Some(outsider) = signers.iter().find(|s| !council.members.contains(s)) {
+        bail!("{outsider} is not a current council member");
+    }
+    if signers.len() < council.threshold as usize {
+        bail!(
+            "The current council needs {} signers, got {}",
+            council.threshold,
+            signers.len()
+        );
+    }
+
+    let program_id = ctx.solana.program_id();
+    let mut metas = accounts::CouncilAction {
+        bridge_state: pda::bridge_state(&program_id),
+        guardian_council: pda::guardian_council(&program_id),
+    }
+    .to_account_metas(None);
+    metas.extend(signers.iter().map(|s| AccountMeta::new_readonly(*s, true)));
+    let rotate = Instruction {
+        program_id,
+        accounts: metas,
+        data: instruction::RotateGuardianCouncil {
+            members: members.clone(),
+            threshold,
+        }
+        .data(),
+    };
+
+    let mut message =
+        Message::new_with_nonce(vec![rotate], Some(&fee_payer), &nonce_account, &fee_payer);
+    message.recent_blockhash = ctx.solana.nonce_blockhash(&nonce_account)?;
+
+    let payload = Payload {
+        program_id: program_id.to_string(),
+        members: members.iter().map(Pubkey::to_string).collect(),
+        threshold,
+        message: base64::engine::general_purpose::STANDARD.encode(message.serialize()),
+        signatures: BTreeMap::new(),
+    };
+    write_payload(out, &payload)?;
+
+    println!("Wrote {}", out.display());
+    println!("Signers: {}", required_signers(&message).len());
+    for signer in required_signers(&message) {
+        println!("  {signer}");
+    }
+    Ok(())
+}
+
+fn sign(path: &Path, keypair_path: &Path, yes: bool) -> Result<()> {
+    let mut payload = read_payload(path)?;
+    let message = decode_message(&payload)?;
+    let rotation = verify(&payload, &message)?;
+
+    let keypair = read_keypair_file(keypair_path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", keypair_path.display()))?;
+    let signer = keypair.pubkey();
+    if !required_signers(&message).contains(&signer) {
+        bail!("{signer} is not a signer of this message");
+    }
+
+    print_rotation(&rotation, &message);
+    if !yes && !confirm(&format!("Sign as {signer}?"))? {
+        bail!("Not signed");
+    }
+
+    let signature = keypair.sign_message(&message.serialize());
+    payload
+        .signatures
+        .insert(signer.to_string(), signature.to_string());
+    write_payload(path, &payload)?;
+
+    let missing = missing_signers(&payload, &message);
+    println!("Signed as {signer}; {} signature(s) still missing", missing.len());
+    Ok(())
+}
+
+fn submit(ctx: &Context, path: &Path, keypair_path: Option<&Path>) -> Result<()> {
+    let mut payload = read_payload(path)?;
+    let message = decode_message(&payload)?;
+    let rotation = verify(&payload, &message)?;
+    if payload.program_id != ctx.solana.program_id().to_string() {
+        bail!("Payload is for program {}", payload.program_id);
+    }
+
+    if let Some(keypair_path) = keypair_path {
+        let keypair = read_keypair_file(keypair_path)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", keypair_path.display()))?;
+        payload
+            .signatures
+            .entry(keypair.pubkey().to_string())
+            .or_insert_with(|| keypair.sign_message(&message.serialize()).to_string());
+    }
+
+    let missing = missing_signers(&payload, &message);
+    if !missing.is_empty() {
+        let missing: Vec<String> = missing.iter().map(Pubkey::to_string).collect();
+        bail!("Missing signatures from {}", missing.join(", "));
+    }
+
+    let signatures = required_signers(&message)
+        .iter()
+        .map(|signer| Ok(Signature::from_str(&payload.signatures[&signer.to_string()])?))
+        .collect::<Result<Vec<_>>>()?;
+    let transaction = Transaction {
+        signatures,
+        message,
+    };
+    transaction
+        .verify()
+        .context("A signature does not match the message")?;
+
+    print_rotation(&rotation, &transaction.message);
+    let signature = ctx.solana.send(&transaction)?;
+    println!("Council rotated: {signature}");
+    Ok(())
+}
+
+fn heartbeat(ctx: &Context, keypair_path: &Path) -> Result<()> {
+    let operator = read_keypair_file(keypair_path)
+        .map_err(|e| anyhow::anyhow!("{}: {e}", keypair_path.display()))?;
+    let program_id = ctx.solana.program_id();
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::Heartbeat {
+            operator: operator.pubkey(),
+            guardian_set: pda::guardian_set(&program_id),
+            bridge_state: pda::bridge_state(&program_id),
+            liveness: pda::liveness(&program_id, &operator.pubkey()),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::Heartbeat {}.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &operator)?;
+    let state = ctx.solana.bridge_state()?;
+    println!(
+        "Heartbeat posted for epoch {} (required every {} epochs): {signature}",
+        state.last_heartbeat_epoch, state.heartbeat_epochs
+    );
+    Ok(())
+}
+
+fn liveness(ctx: &Context, max_age: i64) -> Result<()> {
+    let program_id = ctx.solana.program_id();
+    let state = ctx.solana.bridge_state()?;
+    let set: Option<GuardianSet> = ctx.solana.fetch(&pda::guardian_set(&program_id))?;
+    let (threshold, guardians) = set.map_or((0, vec![]), |set| (set.threshold, set.guardians));
+    let now = ctx.solana.block_time()?;
+
+    let mut operators = vec![state.owner];
+    operators.extend(guardians.iter().copied().filter(|g| *g != state.owner));
+    let mut statuses = Vec::with_capacity(operators.len());
+    for operator in operators {
+        let entry: Option<OperatorLiveness> =
+            ctx.solana.fetch(&pda::liveness(&program_id, &operator))?;
+        let guardian = guardians.contains(&operator);
+        let role = if guardian { "guardian" } else { "relayer" };
+        match &entry {
+            Some(entry) => println!(
+                "{operator} ({role}): last heartbeat {}s ago, {} in total",
+                now - entry.last_seen,
+                entry.beats
+            ),
+            None => println!("{operator} ({role}): never posted a heartbeat"),
+        }
+        statuses.push(OperatorStatus {
+            operator,
+            guardian,
+            last_seen: entry.as_ref().map(|e| e.last_seen),
+            live: entry.is_some_and(|e| e.is_live(now, max_age)),
+        });
+    }
+
+    let report = LivenessReport {
+        owner: state.owner,
+        operators: statuses,
+        threshold,
+        now,
+    };
+    let verdict = match report.verdict() {
+        Verdict::Live => "live (a stuck transfer is the bridge being slow)",
+        Verdict::Degraded => "degraded (the relayer or a guardian quorum is gone)",
+        Verdict::OperatorsGone => "operators gone",
+    };
+    println!(
+        "{} of {threshold} guardians live, relayer {}: {verdict}",
+        report.live_guardians(),
+        if report.relayer_live() { "live" } else { "gone" }
+    );
+    Ok(())
+}
+
+fn read_payload(path: &Path) -> Result<Payload> {
+    let text = std::fs::read_to_string(path).with_context(|| path.display().to_string())?;
+    Ok(serde_json::from_str(&text)?)
+}
+
+fn write_payload(path: &Path, payload: &Payload) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(payload)? + "\n")
+        .with_context(|| path.display().to_string())
+}
+
+fn decode_message(payload: &Payload) -> Result<Message> {
+    let bytes = base64::engine::general_purpose::STANDARD.decode(&payload.message)?;
+    Ok(bincode::deserialize(&bytes)?)
+}
+
+fn required_signers(message: &Message) -> &[Pubkey] {
+    &message.account_keys[..message.header.num_required_signatures as usize]
+}
+
+fn missing_signers(payload: &Payload, message: &Message) -> Vec<Pubkey> {
+    required_signers(message)
+        .iter()
+        .filter(|s| !payload.signatures.contains_key(&s.to_string()))
+        .copied()
+        .collect()
+}
+
+/**
+ * Check the message is exactly "advance nonce, rotate the council" and
+ * matches the payload's summary
+ */
+fn verify(payload: &Payload, message: &Message) -> Result<Rotation> {
+    let program_id = Pubkey::from_str(&payload.program_id)?;
+    let [advance, rotate] = message.instructions.as_slice() else {
+        bail!("Expected exactly two instructions (advance nonce, rotate)");
+    };
+
+    let key = |index: u8| message.account_keys[index as usize];
+    if key(advance.program_id_index) != system_program::ID
+        || !matches!(
+            bincode::deserialize(&advance.data),
+            Ok(SystemInstruction::AdvanceNonceAccount)
+        )
+    {
+        bail!("First instruction must advance the durable nonce");
+    }
+    if key(rotate.program_id_index) != program_id {
+        bail!("Second instruction does not call the bridge program");
+    }
+
+    let data = rotate.data.as_slice();
+    if data.len() < 8 || data[..8] != instruction::RotateGuardianCouncil::DISCRIMINATOR {
+        bail!("Second instruction is not rotate_guardian_council");
+    }
+    let args = instruction::RotateGuardianCouncil::deserialize(&mut &data[8..])?;
+
+    let Some((&council, signers)) = rotate.accounts.split_first() else {
+        bail!("rotate_guardian_council has no accounts");
+    };
+    if key(council) != pda::guardian_council(&program_id) {
+        bail!("rotate_guardian_council targets the wrong council account");
+    }
+
+    let members: Vec<String> = args.members.iter().map(Pubkey::to_string).collect();
+    if members != payload.members || args.threshold != payload.threshold {
+        bail!("Payload summary does not match its message");
+    }
+
+    Ok(Rotation {
+        members: args.members,
+        threshold: args.threshold,
+        council_signers: signers.iter().map(|i| key(*i)).collect(),
+        nonce_account: key(advance.accounts[0]),
+    })
+}
+
+fn print_rotation(rotation: &Rotation, message: &Message) {
+    println!("Rotate the guardian council");
+    println!(
+        "  New members ({} of {} to act):",
+        rotation.threshold,
+        rotation.members.len()
+    );
+    for member in &rotation.members {
+        println!("    {member}");
+    }
+    println!("  Signed by current members:");
+    for signer in &rotation.council_signers {
+        println!("    {signer}");
+    }
+    println!("  Fee payer:     {}", message.account_keys[0]);
+    println!("  Nonce account: {}", rotation.nonce_account);
+    println!("  Nonce value:   {}", message.recent_blockhash);
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
@@ -0,0 +1,270 @@
+//! Bridge CLI
+//!
+//! Support commands, for answering "where is my transfer?":
+//!
+//!   bridge-cli status <transfer-id|nonce>   on-chain record and the relayer's view
+//!   bridge-cli pending                      transfers pending past the SLA
+//!   bridge-cli trace <nonce>                walk both chains to find the stall
+//!   bridge-cli reemit <transfer-id|nonce>   re-emit the event the relayer missed
+//!   bridge-cli recipient <address|name>     check an Ethereum recipient (EIP-55, ENS)
+//!   bridge-cli eth-tx <tx-hash>             what an Ethereum transaction became on Solana
+//!   bridge-cli profiles                     built-in route profiles (--profile)
+//!
+//! and guardian council rotation as an offline signing ceremony
+//! (`bridge-cli guardians ...`, see guardians.rs), and state snapshots for
+//! audits and forensics (`bridge-cli snapshot ...`, see snapshot.rs), which
+//! `bridge-cli migrate` replays into a new deployment (see migrate.rs).
+//! `bridge-cli replay` rebuilds nonces, vault balances and user stats
+//! from the program's whole history and diffs them against the live
+//! accounts (see replay.rs).
+//! `bridge-cli upgrade-authority ...` shows who can upgrade the program,
+//! hands that to governance and records the verified build (see
+//! upgrade.rs), and `bridge-cli
+//! compression ...` moves finished transfer records into the compressed
+//! store (see compression.rs). On localnet or devnet, `bridge-cli faucet
+//! ...` hands out a demo token registered with the bridge (see faucet.rs).
+//!
+//! Endpoints come from flags or the same environment variables the
+//! relayer reads (SOLANA_RPC_URL, ETHEREUM_RPC_URL, ...). A built-in
+//! route profile (`--profile devnet-sepolia` or BRIDGE_PROFILE, see
+//! `bridge-cli profiles`) fills in whichever of those aren't set.
+
+mod compression;
+mod ethereum;
+mod faucet;
+mod guardians;
+mod migrate;
+mod relayer;
+mod replay;
+mod snapshot;
+mod solana;
+mod support;
+mod upgrade;
+
+use std::path::PathBuf;
+
+use anyhow::Result;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use solana_bridge::TransferDirection;
+use solana_sdk::pubkey::Pubkey;
+
+#[derive(Parser)]
+#[command(name = "bridge-cli", version, about = "Solana bridge operator tooling")]
+struct Cli {
+    #[command(flatten)]
+    endpoints: Endpoints,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Args)]
+pub struct Endpoints {
+    /// Built-in route profile for the endpoints not given (see `profiles`)
+    #[arg(long, env = "BRIDGE_PROFILE", global = true)]
+    pub profile: Option<String>,
+
+    /// Defaults to the profile's, else a local validator
+    #[arg(long, env = "SOLANA_RPC_URL", global = true)]
+    pub rpc_url: Option<String>,
+
+    /// Defaults to the bridge's deployed program ID
+    #[arg(long, env = "SOLANA_PROGRAM_ID", global = true)]
+    pub program_id: Option<Pubkey>,
+
+    /// Relayer status API
+    #[arg(long, env = "RELAYER_API_URL", default_value = "http://127.0.0.1:8080", global = true)]
+    pub relayer_api: String,
+
+    #[arg(long, env = "ETHEREUM_RPC_URL", global = true)]
+    pub ethereum_rpc_url: Option<String>,
+
+    #[arg(long, env = "ETHEREUM_BRIDGE_ADDRESS", global = true)]
+    pub ethereum_bridge: Option<String>,
+
+    /// Chain registry ID of the EVM chain ETHEREUM_RPC_URL points at
+    /// (defaults to the profile's, else 1)
+    #[arg(long, env = "ETHEREUM_CHAIN_ID", global = true)]
+    pub ethereum_chain_id: Option<u64>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Direction {
+    Outbound,
+    Inbound,
+}
+
+impl From<Direction> for TransferDirection {
+    fn from(direction: Direction) -> Self {
+        match direction {
+            Direction::Outbound => TransferDirection::Outbound,
+            Direction::Inbound => TransferDirection::Inbound,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Show a transfer's on-chain record and the relayer's view of it
+    Status {
+        /// Transfer ID (hex) or nonce
+        transfer: String,
+
+        /// Which side a nonce refers to
+        #[arg(long, value_enum, default_value_t = Direction::Outbound)]
+        direction: Direction,
+    },
+
+    /// List transfers still pending after the SLA
+    Pending {
+        /// Seconds since initiation before a pending transfer counts as stuck
+        #[arg(long, default_value_t = 1800)]
+        older_than: i64,
+    },
+
+    /// Walk both chains and report where a transfer stalled
+    Trace {
+        nonce: u64,
+
+        #[arg(long, value_enum, default_value_t = Direction::Outbound)]
+        direction: Direction,
+
+        /// First Ethereum block to search for an inbound Lock/Burn
+        #[arg(long)]
+        from_block: Option<u64>,
+    },
+
+    /// Have the program emit a transfer's event again, for a relayer that missed it
+    Reemit {
+        /// Transfer ID (hex) or nonce
+        transfer: String,
+
+        /// Which side a nonce refers to
+        #[arg(long, value_enum, default_value_t = Direction::Outbound)]
+        direction: Direction,
+
+        /// Pays the transaction fee
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+
+    /// Check an Ethereum recipient and print its checksummed address
+    Recipient {
+        /// Address (checksummed, lowercase or uppercase) or ENS name
+        input: String,
+    },
+
+    /// Show the inbound transfers an Ethereum transaction carried
+    EthTx {
+        /// Transaction hash (hex)
+        tx_hash: String,
+    },
+
+    /// Guardian council rotation ceremony
+    Guardians {
+        #[command(subcommand)]
+        command: guardians::Command,
+    },
+
+    /// Export bridge state, or diff two exports
+    Snapshot {
+        #[command(subcommand)]
+        command: snapshot::Command,
+    },
+
+    /// Seed this deployment (--program-id) from a snapshot of an old one
+    Migrate {
+        /// JSON snapshot of the old deployment
+        snapshot: PathBuf,
+
+        /// Owner of the new deployment
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Print the steps without sending them
+        #[arg(long)]
+        dry_run: bool,
+
+        /// Close the import afterwards; no import works after this
+        #[arg(long)]
+        seal: bool,
+    },
+
+    /// Rebuild state from the program's history and diff it against the live accounts
+    Replay {
+        /// Archival (Bigtable-backed) RPC to read history from; defaults
+        /// to --rpc-url, which may have pruned old transactions
+        #[arg(long, env = "SOLANA_HISTORY_URL")]
+        history_url: Option<String>,
+
+        /// Also write the report, divergences included, as JSON
+        #[arg(long)]
+        out: Option<PathBuf>,
+    },
+
+    /// Move finished transfer records into a compressed Merkle tree
+    Compression {
+        #[command(subcommand)]
+        command: compression::Command,
+    },
+
+    /// Show, hand to governance or remove the upgrade authority; record the verified build
+    UpgradeAuthority {
+        #[command(subcommand)]
+        command: upgrade::Command,
+    },
+
+    /// Demo token faucet for localnet and devnet
+    Faucet {
+        #[command(subcommand)]
+        command: faucet::Command,
+    },
+
+    /// List the built-in route profiles
+    Profiles,
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    let ctx = support::Context::new(&cli.endpoints)?;
+
+    match cli.command {
+        Command::Status {
+            transfer,
+            direction,
+        } => support::status(&ctx, &transfer, direction.into()),
+        Command::Pending { older_than } => support::pending(&ctx, older_than),
+        Command::Trace {
+            nonce,
+            direction: Direction::Outbound,
+            ..
+        } => support::trace_outbound(&ctx, nonce),
+        Command::Trace {
+            nonce,
+            direction: Direction::Inbound,
+            from_block,
+        } => support::trace_inbound(&ctx, nonce, from_block),
+        Command::Reemit {
+            transfer,
+            direction,
+            keypair,
+        } => support::reemit(&ctx, &transfer, direction.into(), &keypair),
+        Command::Recipient { input } => support::recipient(&ctx, &input),
+        Command::EthTx { tx_hash } => support::eth_tx(&ctx, &tx_hash),
+        Command::Guardians { command } => guardians::run(&ctx, command),
+        Command::Snapshot { command } => snapshot::run(&ctx, command),
+        Command::Migrate {
+            snapshot,
+            keypair,
+            dry_run,
+            seal,
+        } => migrate::run(&ctx, &snapshot, &keypair, dry_run, seal),
+        Command::Replay { history_url, out } => {
+            replay::run(&ctx, history_url.as_deref(), out.as_deref())
+        }
+        Command::Compression { command } => compression::run(&ctx, command),
+        Command::UpgradeAuthority { command } => upgrade::run(&ctx, command),
+        Command::Faucet { command } => faucet::run(&ctx, command),
+        Command::Profiles => support::profiles(),
+    }
+}
@@ -0,0 +1,571 @@
+//! Seed a new deployment from a snapshot of the old one
+//!
+//!   bridge-cli snapshot export --program-id <old> --out old.json
+//!   bridge-cli migrate old.json --program-id <new> --keypair owner.json [--seal]
+//!
+//! Configs are replayed through the ordinary owner instructions (chains,
+//! tokens, fee tiers, relayer rewards, inboxes); what only a migration
+//! may write goes through the import instructions: route shards and
+//! sequences, processed nonces, inbox cursors and user stats. Steps
+//! already done on the new deployment are skipped, so a failed run can
+//! be repeated.
+//!
+//! Everything else (guardians, per-token restrictions, optimistic and
+//! pause-bond settings, ...) is listed at the end to set by hand.
+//! --seal closes the import for good once the new deployment checks out.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::{bail, Context as _, Result};
+use serde_json::Value;
+use solana_bridge::{
+    accounts, instruction, ChainKind, ChainParams, FeeTier, Finality, Migration, UserStats,
+};
+use solana_bridge_sdk::pda;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signer};
+use solana_sdk::system_program;
+
+use crate::snapshot::{self, Entry};
+use crate::support::Context;
+
+/// Processed nonces per import_state transaction
+const NONCE_CHUNK: usize = 100;
+
+/// Account types the migration replays or imports
+const MIGRATED: &[&str] = &[
+    "BridgeState",
+    "ChainConfig",
+    "TokenConfig",
+    "FeeTiers",
+    "RelayerRewardConfig",
+    "Inbox",
+    "RouteSequence",
+    "UserStats",
+];
+
+/// Account types that are history, not state to carry over
+const HISTORY: &[&str] = &[
+    "AdminLog",
+    "Outbox",
+    "EventHistory",
+    "InstanceTransfer",
+    "InstanceReceipt",
+    "TransferRecord",
+    "TransferMemo",
+    "SmartWalletRecipient",
+    "Attestation",
+    "RewardEpoch",
+    "RelayerEpochStats",
+    "CheckpointLog",
+    "Checkpoint",
+    "AdminProposal",
+    "TokenAccount",
+    "Migration",
+    "UpgradeAuthorityRecord",
+    "BuildRecord",
+];
+
+pub fn run(
+    ctx: &Context,
+    snapshot: &Path,
+    keypair: &Path,
+    dry_run: bool,
+    seal: bool,
+) -> Result<()> {
+    let snapshot = snapshot::read_json(snapshot)?;
+    let source = Pubkey::from_str(&snapshot.program_id)?;
+    let owner =
+        read_keypair_file(keypair).map_err(|e| anyhow::anyhow!("{}: {e}", keypair.display()))?;
+    let program_id = ctx.solana.program_id();
+    if source == program_id {
+        bail!("Snapshot is of {program_id} itself; pass the new deployment's --program-id");
+    }
+
+    let migration: Option<Migration> = ctx.solana.fetch(&pda::migration(&program_id))?;
+    if migration.as_ref().is_some_and(|m| m.sealed) {
+        bail!("Import on {program_id} is already sealed");
+    }
+
+    let plan = Plan {
+        ctx,
+        program_id,
+        owner: owner.pubkey(),
+        source,
+    };
+    let mut steps = Vec::new();
+    plan.bridge_state(&snapshot.accounts, &mut steps)?;
+    plan.route_sequences(&snapshot.accounts, &mut steps)?;
+    plan.configs(&snapshot.accounts, &mut steps)?;
+    plan.user_stats(&snapshot.accounts, &mut steps)?;
+    if seal {
+        steps.push(plan.seal());
+    }
+
+    for (description, instruction) in &steps {
+        if dry_run {
+            println!("would {description}");
+            continue;
+        }
+        let signature = ctx
+            .solana
+            .send_instructions(std::slice::from_ref(instruction), &owner)
+            .with_context(|| format!("Failed to {description}"))?;
+        println!("{description}: {signature}");
+    }
+    println!("{} step(s) {}", steps.len(), if dry_run { "planned" } else { "done" });
+
+    report_manual(&snapshot.accounts);
+    Ok(())
+}
+
+type Step = (String, Instruction);
+
+struct Plan<'a> {
+    ctx: &'a Context,
+    program_id: Pubkey,
+    owner: Pubkey,
+    source: Pubkey,
+}
+
+impl Plan<'_> {
+    fn instruction(
+        &self,
+        accounts: impl ToAccountMetas,
+        data: impl InstructionData,
+    ) -> Instruction {
+        Instruction {
+            program_id: self.program_id,
+            accounts: accounts.to_account_metas(None),
+            data: data.data(),
+        }
+    }
+
+    fn bridge_state(&self, entries: &[Entry], steps: &mut Vec<Step>) -> Result<()> {
+        let Some(old) = entries.iter().find(|e| e.kind == "BridgeState") else {
+            bail!("Snapshot has no BridgeState");
+        };
+        let bridge_state = pda::bridge_state(&self.program_id);
+        let admin_log = pda::admin_log(&self.program_id);
+
+        if !self.ctx.solana.exists(&bridge_state)? {
+            steps.push((
+                "initialize the bridge".to_string(),
+                self.instruction(
+                    accounts::Initialize {
+                        bridge_state,
+                        admin_log,
+                        outbox: pda::outbox(&self.program_id),
+                        owner: self.owner,
+                        system_program: system_program::ID,
+                    },
+                    instruction::Initialize {
+                        instance_id: solana_bridge::instance::DEFAULT_INSTANCE,
+                    },
+                ),
+            ));
+        }
+
+        // Deployments from before route sequences have no shards
+        let route_shards = old.fields["route_shards"].as_u64().unwrap_or(0) as u32;
+        let mut processed: Vec<u64> = old.fields["processed_nonces"]
+            .as_array()
+            .context("BridgeState without processed_nonces")?
+            .iter()
+            .map(|n| n.as_u64().context("Processed nonce is not a u64"))
+            .collect::<Result<_>>()?;
+        // Deployments from before pruning have no floor
+        let processed_floor = old.fields["processed_floor"].as_u64().unwrap_or(0);
+        processed.retain(|n| *n >= processed_floor);
+        if let Some(current) = self.ctx.solana.fetch::<solana_bridge::BridgeState>(&bridge_state)? {
+            processed.retain(|n| !current.is_processed(*n));
+        }
+
+        // Always at least one call: it records the source program
+        let chunks: Vec<&[u64]> = if processed.is_empty() {
+            vec![&[][..]]
+        } else {
+            processed.chunks(NONCE_CHUNK).collect()
+        };
+        for chunk in chunks {
+            steps.push((
+                format!(
+                    "import {route_shards} route shards and {} processed nonces",
+                    chunk.len()
+                ),
+                self.instruction(
+                    accounts::ImportState {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        migration: pda::migration(&self.program_id),
+                        system_program: system_program::ID,
+                    },
+                    instruction::ImportState {
+                        source_program: self.source,
+                        route_shards,
+                        processed_floor,
+                        processed_nonces: chunk.to_vec(),
+                    },
+                ),
+            ));
+        }
+
+        if old.fields["idempotent_delivery"] == Value::Bool(true) {
+            steps.push((
+                "turn on idempotent delivery".to_string(),
+                self.instruction(
+                    accounts::PauseBridge {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                    },
+                    instruction::SetIdempotentDelivery { enabled: true },
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Each route continues its old sequence, under its old shard
+    fn route_sequences(&self, entries: &[Entry], steps: &mut Vec<Step>) -> Result<()> {
+        for entry in entries.iter().filter(|e| e.kind == "RouteSequence") {
+            let f = &entry.fields;
+            let mint = Pubkey::from_str(str_field(f, "mint")?)?;
+            let dest_chain_id = u64_field(f, "dest_chain_id")?;
+            let route_sequence = pda::route_sequence(&self.program_id, &mint, dest_chain_id);
+            if self.ctx.solana.exists(&route_sequence)? {
+                continue;
+            }
+            let last = u64_field(f, "last")?;
+            steps.push((
+                format!("import the {mint} route to chain {dest_chain_id} at {last}"),
+                self.instruction(
+                    accounts::ImportRouteSequence {
+                        owner: self.owner,
+                        bridge_state: pda::bridge_state(&self.program_id),
+                        admin_log: pda::admin_log(&self.program_id),
+                        migration: pda::migration(&self.program_id),
+                        route_sequence,
+                        system_program: system_program::ID,
+                    },
+                    instruction::ImportRouteSequence {
+                        mint,
+                        dest_chain_id,
+                        shard: u64_field(f, "shard")? as u32,
+                        last,
+                    },
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn configs(&self, entries: &[Entry], steps: &mut Vec<Step>) -> Result<()> {
+        let program_id = &self.program_id;
+        let bridge_state = pda::bridge_state(program_id);
+        let admin_log = pda::admin_log(program_id);
+
+        for entry in entries.iter().filter(|e| e.kind == "ChainConfig") {
+            let f = &entry.fields;
+            let chain_id = u64_field(f, "chain_id")?;
+            let chain_config = pda::chain_config(program_id, chain_id);
+            if self.ctx.solana.exists(&chain_config)? {
+                continue;
+            }
+            let params = ChainParams {
+                finality: finality(str_field(f, "finality")?)?,
+                required_confirmations: u64_field(f, "required_confirmations")? as u32,
+                base_fee: u64_field(f, "base_fee")?,
+                fee_bps: u64_field(f, "fee_bps")? as u16,
+                min_amount: u64_field(f, "min_amount")?,
+            };
+            steps.push((
+                format!("register chain {chain_id}"),
+                self.instruction(
+                    accounts::RegisterChain {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        chain_config,
+                        system_program: system_program::ID,
+                    },
+                    instruction::RegisterChain {
+                        chain_id,
+                        kind: chain_kind(str_field(f, "kind")?)?,
+                        params,
+                    },
+                ),
+            ));
+
+            let update = || accounts::UpdateChain {
+                owner: self.owner,
+                bridge_state,
+                admin_log,
+                chain_config,
+            };
+            let prefix = str_field(f, "address_prefix")?;
+            if !prefix.is_empty() {
+                steps.push((
+                    format!("set chain {chain_id} address prefix to {prefix}"),
+                    self.instruction(
+                        update(),
+                        instruction::SetAddressPrefix {
+                            chain_id,
+                            prefix: prefix.to_string(),
+                        },
+                    ),
+                ));
+            }
+            if f["enabled"] == Value::Bool(false) {
+                steps.push((
+                    format!("disable chain {chain_id}"),
+                    self.instruction(
+                        update(),
+                        instruction::SetChainEnabled {
+                            chain_id,
+                            enabled: false,
+                        },
+                    ),
+                ));
+            }
+        }
+
+        for entry in entries.iter().filter(|e| e.kind == "TokenConfig") {
+            let mint = Pubkey::from_str(str_field(&entry.fields, "mint")?)?;
+            let token_config = pda::token_config(program_id, &mint);
+            if self.ctx.solana.exists(&token_config)? {
+                continue;
+            }
+            steps.push((
+                format!("register token {mint}"),
+                self.instruction(
+                    accounts::RegisterToken {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        mint,
+                        token_config,
+                        system_program: system_program::ID,
+                    },
+                    instruction::RegisterToken {},
+                ),
+            ));
+        }
+
+        if let Some(entry) = entries.iter().find(|e| e.kind == "FeeTiers") {
+            let tiers = entry.fields["tiers"]
+                .as_array()
+                .context("FeeTiers without tiers")?
+                .iter()
+                .map(|t| {
+                    Ok(FeeTier {
+                        min_volume: u64_field(t, "min_volume")?,
+                        discount_bps: u64_field(t, "discount_bps")? as u16,
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+            steps.push((
+                format!("set {} fee tiers", tiers.len()),
+                self.instruction(
+                    accounts::SetFeeTiers {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        fee_tiers: pda::fee_tiers(program_id),
+                        system_program: system_program::ID,
+                    },
+                    instruction::SetFeeTiers { tiers },
+                ),
+            ));
+        }
+
+        if let Some(entry) = entries.iter().find(|e| e.kind == "RelayerRewardConfig") {
+            steps.push((
+                "set relayer rewards".to_string(),
+                self.instruction(
+                    accounts::SetRelayerRewards {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        reward_config: pda::reward_config(program_id),
+                        system_program: system_program::ID,
+                    },
+                    instruction::SetRelayerRewards {
+                        epoch_duration: i64_field(&entry.fields, "epoch_duration")?,
+                        reward_per_transfer: u64_field(&entry.fields, "reward_per_transfer")?,
+                    },
+                ),
+            ));
+        }
+
+        for entry in entries.iter().filter(|e| e.kind == "Inbox") {
+            let f = &entry.fields;
+            let chain_id = u64_field(f, "chain_id")?;
+            let next_sequence = u64_field(f, "next_sequence")?;
+            let delivered = u128::from_str_radix(str_field(f, "delivered")?, 16)?;
+            let inbox = pda::inbox(program_id, chain_id);
+            steps.push((
+                format!("configure the chain {chain_id} inbox"),
+                self.instruction(
+                    accounts::ConfigureInbox {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        inbox,
+                        system_program: system_program::ID,
+                    },
+                    instruction::ConfigureInbox {
+                        chain_id,
+                        next_sequence,
+                        window: u64_field(f, "window")? as u8,
+                    },
+                ),
+            ));
+            steps.push((
+                format!("import the chain {chain_id} inbox cursor at {next_sequence}"),
+                self.instruction(
+                    accounts::ImportInbox {
+                        owner: self.owner,
+                        bridge_state,
+                        admin_log,
+                        migration: pda::migration(program_id),
+                        inbox,
+                    },
+                    instruction::ImportInbox {
+                        chain_id,
+                        next_sequence,
+                        delivered,
+                    },
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn user_stats(&self, entries: &[Entry], steps: &mut Vec<Step>) -> Result<()> {
+        for entry in entries.iter().filter(|e| e.kind == "UserStats") {
+            let f = &entry.fields;
+            let user = Pubkey::from_str(str_field(f, "user")?)?;
+            let user_stats = pda::user_stats(&self.program_id, &user);
+            let current: Option<UserStats> = self.ctx.solana.fetch(&user_stats)?;
+            if current.is_some_and(|s| s.volume != 0 || s.transfers != 0) {
+                continue;
+            }
+            steps.push((
+                format!("import stats for {user}"),
+                self.instruction(
+                    accounts::ImportUserStats {
+                        owner: self.owner,
+                        bridge_state: pda::bridge_state(&self.program_id),
+                        migration: pda::migration(&self.program_id),
+                        user_stats,
+                        system_program: system_program::ID,
+                    },
+                    instruction::ImportUserStats {
+                        user,
+                        volume: u64_field(f, "volume")?,
+                        transfers: u64_field(f, "transfers")?,
+                    },
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    fn seal(&self) -> Step {
+        (
+            "seal the import".to_string(),
+            self.instruction(
+                accounts::SealImport {
+                    owner: self.owner,
+                    bridge_state: pda::bridge_state(&self.program_id),
+                    admin_log: pda::admin_log(&self.program_id),
+                    migration: pda::migration(&self.program_id),
+                    system_program: system_program::ID,
+                },
+                instruction::SealImport {},
+            ),
+        )
+    }
+}
+
+/// List what the snapshot holds that wasn't carried over
+fn report_manual(entries: &[Entry]) {
+    let mut manual: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in entries {
+        let kind = entry.kind.as_str();
+        if !MIGRATED.contains(&kind) && !HISTORY.contains(&kind) {
+            *manual.entry(kind).or_default() += 1;
+        }
+    }
+
+    let customized_tokens = entries
+        .iter()
+        .filter(|e| e.kind == "TokenConfig")
+        .filter(|e| {
+            let f = &e.fields;
+            f["restricted"] == Value::Bool(true)
+                || f["paused"] == Value::Bool(true)
+                || f["accounting"] != "Raw"
+                || f["trust_model"]
+                    .as_str()
+                    .is_some_and(|m| m != "OwnerSignature")
+                || f["ibc_denoms"].as_array().is_some_and(|d| !d.is_empty())
+                || f["tier_limits"].as_array().is_some_and(|t| !t.is_empty())
+                || f["allowed_extensions"] != 0
+                || !f["bridged_decimals"].is_null()
+        })
+        .count();
+
+    if manual.is_empty() && customized_tokens == 0 {
+        return;
+    }
+    println!("Not migrated; set these up by hand:");
+    for (kind, count) in manual {
+        println!("  {count} x {kind}");
+    }
+    if customized_tokens > 0 {
+        println!("  token settings beyond registration on {customized_tokens} token(s)");
+    }
+}
+
+fn u64_field(fields: &Value, name: &str) -> Result<u64> {
+    fields[name]
+        .as_u64()
+        .with_context(|| format!("Missing or invalid {name}"))
+}
+
+fn i64_field(fields: &Value, name: &str) -> Result<i64> {
+    fields[name]
+        .as_i64()
+        .with_context(|| format!("Missing or invalid {name}"))
+}
+
+fn str_field<'a>(fields: &'a Value, name: &str) -> Result<&'a str> {
+    fields[name]
+        .as_str()
+        .with_context(|| format!("Missing or invalid {name}"))
+}
+
+fn chain_kind(name: &str) -> Result<ChainKind> {
+    Ok(match name {
+        "Evm" => ChainKind::Evm,
+        "Stellar" => ChainKind::Stellar,
+        "Cosmos" => ChainKind::Cosmos,
+        "Bitcoin" => ChainKind::Bitcoin,
+        other => bail!("Unknown chain kind {other}"),
+    })
+}
+
+fn finality(name: &str) -> Result<Finality> {
+    Ok(match name {
+        "Confirmations" => Finality::Confirmations,
+        "L1Batch" => Finality::L1Batch,
+        other => bail!("Unknown finality {other}"),
+    })
+}
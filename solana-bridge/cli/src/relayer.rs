@@ -0,0 +1,36 @@
+//! Relayer status API client (relayer/src/api.js)
+
+use std::time::Duration;
+
+use anyhow::Result;
+use reqwest::blocking::Client;
+use reqwest::StatusCode;
+use serde_json::Value;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct RelayerApi {
+    url: String,
+    client: Client,
+}
+
+impl RelayerApi {
+    pub fn new(url: &str) -> Result<Self> {
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client: Client::builder().timeout(TIMEOUT).build()?,
+        })
+    }
+
+    /// The relayer's record of a transfer, if it has seen it
+    pub fn transfer(&self, transfer_id: &[u8; 32]) -> Result<Option<Value>> {
+        let response = self
+            .client
+            .get(format!("{}/transfers/{}", self.url, hex::encode(transfer_id)))
+            .send()?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.json()?))
+    }
+}
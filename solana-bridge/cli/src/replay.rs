@@ -0,0 +1,545 @@
+//! Rebuild the bridge's state from its history and check the live accounts
+//!
+//!   bridge-cli replay [--history-url <archival RPC>] [--out report.json]
+//!
+//! Every successful transaction that mentions the program is read back,
+//! oldest first, and its events, vault token balances and import
+//! instructions replayed into what the accounts should hold now:
+//!
+//!   outbound nonces  each lock or burn takes its route's next sequence,
+//!                    so every route's sequences run 1, 2, 3, ... (or on
+//!                    from an imported route's last) without a gap;
+//!                    nonces from the old global counter (shard 0) up to
+//!                    BridgeState.nonce are taken exactly once
+//!   inbound nonces   each is delivered once, every delivered one is
+//!                    processed, and nothing is processed (or below
+//!                    processed_floor) that was never delivered or imported
+//!   vaults           the token accounts the bridge authority holds only
+//!                    change inside the program's transactions, and each
+//!                    ends at its live balance
+//!   user stats       `transfers` is the user's locks and burns plus any
+//!                    imported count
+//!
+//! Anything else is a divergence; they're printed, written to --out as
+//! JSON, and fail the command. UserStats volume isn't replayed: it counts
+//! gross amounts, and lock events carry the net, converted one. Wrapped
+//! supply isn't either; `bridge-cli snapshot` covers the mints.
+//!
+//! Public RPCs prune old transactions, so point --history-url at an
+//! archival (Bigtable-backed) node; the replay refuses a history that
+//! doesn't start at `initialize`. Live state is read first and history
+//! replayed up to that slot, but a transaction landing while the
+//! accounts are read still shows up as a divergence, so rerun before
+//! treating one as real.
+
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, Discriminator};
+use anyhow::{bail, Context as _, Result};
+use serde::Serialize;
+use solana_account_decoder::UiAccountData;
+use solana_bridge::{instruction, BridgeState, RouteSequence, UserStats, SEQUENCE_BITS};
+use solana_bridge_sdk::events::parse_logs;
+use solana_bridge_sdk::{pda, BridgeEvent};
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::option_serializer::OptionSerializer;
+use solana_transaction_status::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionStatusMeta, UiTransactionTokenBalance,
+};
+
+use crate::solana::Solana;
+use crate::support::Context;
+
+/// Nonces listed in one divergence before the rest are counted
+const MAX_LISTED: usize = 10;
+
+/// Vault -> (mint, amount)
+type Balances = BTreeMap<String, (String, u64)>;
+
+#[derive(Serialize)]
+struct Report {
+    program_id: String,
+    /// Live state was read at this slot; history is replayed up to it
+    slot: u64,
+    transactions: usize,
+    divergences: Vec<Divergence>,
+}
+
+#[derive(Serialize)]
+struct Divergence {
+    /// outbound, inbound, vault or user_stats
+    check: &'static str,
+    detail: String,
+}
+
+impl Divergence {
+    fn new(check: &'static str, detail: String) -> Self {
+        Self { check, detail }
+    }
+}
+
+pub fn run(ctx: &Context, history_url: Option<&str>, out: Option<&Path>) -> Result<()> {
+    let program_id = ctx.solana.program_id();
+    let archive = history_url.map(|url| Solana::new(url, program_id));
+    let history = archive.as_ref().unwrap_or(&ctx.solana);
+
+    let slot = ctx.solana.slot()?;
+    let live = Live::read(&ctx.solana)?;
+
+    let signatures: Vec<_> = history
+        .signatures()?
+        .into_iter()
+        .filter(|status| status.slot <= slot && status.err.is_none())
+        .collect();
+    println!("Replaying {} transactions up to slot {slot}", signatures.len());
+
+    let mut replay = Replay::new(program_id);
+    for (i, status) in signatures.iter().enumerate() {
+        let signature: Signature = status.signature.parse()?;
+        replay
+            .apply(&status.signature, history.transaction(&signature)?)
+            .with_context(|| status.signature.clone())?;
+        if (i + 1) % 1000 == 0 {
+            eprintln!("  {}/{}", i + 1, signatures.len());
+        }
+    }
+    if !replay.initialized {
+        bail!("No `initialize` in the program's history; is {program_id} deployed here?");
+    }
+
+    println!(
+        "{} outbound transfers, {} inbound deliveries, {} vaults, {} users",
+        replay.outbound.len(),
+        replay.delivered.len(),
+        replay.vaults.len(),
+        replay.transfers.len()
+    );
+    let report = Report {
+        program_id: program_id.to_string(),
+        slot,
+        transactions: replay.transactions,
+        divergences: replay.diff(&live),
+    };
+    for divergence in &report.divergences {
+        println!("  [{}] {}", divergence.check, divergence.detail);
+    }
+    if let Some(out) = out {
+        std::fs::write(out, serde_json::to_string_pretty(&report)?)?;
+        println!("Wrote the report to {}", out.display());
+    }
+
+    if !report.divergences.is_empty() {
+        bail!("{} divergences between history and live state", report.divergences.len());
+    }
+    println!("History matches live state");
+    Ok(())
+}
+
+/// The accounts the replay is checked against
+struct Live {
+    state: BridgeState,
+    vaults: Balances,
+    user_stats: Vec<UserStats>,
+}
+
+impl Live {
+    fn read(solana: &Solana) -> Result<Self> {
+        let mut vaults = Balances::new();
+        for vault in solana.vaults()? {
+            let UiAccountData::Json(parsed) = &vault.account.data else {
+                continue;
+            };
+            let info = &parsed.parsed["info"];
+            let amount = info["tokenAmount"]["amount"]
+                .as_str()
+                .and_then(|amount| amount.parse().ok())
+                .with_context(|| format!("vault {} has no amount", vault.pubkey))?;
+            let mint = info["mint"].as_str().unwrap_or_default().to_string();
+            vaults.insert(vault.pubkey, (mint, amount));
+        }
+
+        let user_stats = solana
+            .program_accounts()?
+            .into_iter()
+            .filter(|(_, account)| account.data.starts_with(&UserStats::DISCRIMINATOR))
+            .map(|(_, account)| Ok(UserStats::try_deserialize(&mut account.data.as_slice())?))
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            state: solana.bridge_state()?,
+            vaults,
+            user_stats,
+        })
+    }
+}
+
+/// What the history says the accounts should hold
+struct Replay {
+    program_id: Pubkey,
+    /// The bridge authority, which holds the vaults
+    authority: String,
+    initialized: bool,
+    transactions: usize,
+    /// Outbound nonce -> the transaction that took it
+    outbound: BTreeMap<u64, String>,
+    /// Shard -> last sequence set by import_route_sequence
+    imported_routes: BTreeMap<u64, u64>,
+    /// Inbound nonce -> the transaction that delivered it
+    delivered: BTreeMap<u64, String>,
+    imported_floor: u64,
+    imported_processed: BTreeSet<u64>,
+    /// Balances after the last transaction that touched each vault
+    vaults: Balances,
+    /// User -> locks and burns, plus any imported count
+    transfers: BTreeMap<Pubkey, u64>,
+    divergences: Vec<Divergence>,
+}
+
+impl Replay {
+    fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            authority: pda::bridge_authority(&program_id).to_string(),
+            initialized: false,
+            transactions: 0,
+            outbound: BTreeMap::new(),
+            imported_routes: BTreeMap::new(),
+            delivered: BTreeMap::new(),
+            imported_floor: 0,
+            imported_processed: BTreeSet::new(),
+            vaults: Balances::new(),
+            transfers: BTreeMap::new(),
+            divergences: Vec::new(),
+        }
+    }
+
+    fn apply(
+        &mut self,
+        signature: &str,
+        transaction: EncodedConfirmedTransactionWithStatusMeta,
+    ) -> Result<()> {
+        let meta = transaction.transaction.meta.context("the RPC returned no status meta")?;
+        let decoded = transaction
+            .transaction
+            .transaction
+            .decode()
+            .context("undecodable transaction")?;
+        let mut keys = decoded.message.static_account_keys().to_vec();
+        if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+            for address in loaded.writable.iter().chain(&loaded.readonly) {
+                keys.push(address.parse()?);
+            }
+        }
+
+        // Imports are read from the program's top-level instructions,
+        // which is how `bridge-cli migrate` sends them
+        let instructions: Vec<&[u8]> = decoded
+            .message
+            .instructions()
+            .iter()
+            .filter(|ix| keys.get(ix.program_id_index as usize) == Some(&self.program_id))
+            .map(|ix| ix.data.as_slice())
+            .collect();
+        if !self.initialized {
+            // Deploying the program mentions it too
+            if instructions.is_empty() {
+                return Ok(());
+            }
+            if !instructions
+                .iter()
+                .any(|data| data.starts_with(&instruction::Initialize::DISCRIMINATOR))
+            {
+                bail!(
+                    "History starts after `initialize`; the node has pruned older \
+                     transactions (pass --history-url with an archival RPC)"
+                );
+            }
+            self.initialized = true;
+        }
+        self.transactions += 1;
+
+        // devnet_force_mint (devnet builds only) emits MintEvent for a
+        // nonce it doesn't process
+        let force_mint = hash(b"global:devnet_force_mint").to_bytes();
+        let mut simulated = false;
+        for data in instructions {
+            if data.starts_with(&force_mint[..8]) {
+                simulated = true;
+            } else {
+                self.apply_import(data)?;
+            }
+        }
+
+        let logs = match &meta.log_messages {
+            OptionSerializer::Some(logs) => logs.as_slice(),
+            _ => &[],
+        };
+        self.apply_events(signature, parse_logs(&self.program_id, logs)?, simulated);
+        self.apply_balances(signature, &keys, &meta);
+        Ok(())
+    }
+
+    fn apply_import(&mut self, data: &[u8]) -> Result<()> {
+        if let Some(args) = data.strip_prefix(&instruction::ImportState::DISCRIMINATOR) {
+            let import = instruction::ImportState::try_from_slice(args)?;
+            self.imported_floor = self.imported_floor.max(import.processed_floor);
+            self.imported_processed.extend(import.processed_nonces);
+        } else if let Some(args) =
+            data.strip_prefix(&instruction::ImportRouteSequence::DISCRIMINATOR)
+        {
+            let import = instruction::ImportRouteSequence::try_from_slice(args)?;
+            self.imported_routes.insert(import.shard as u64, import.last);
+        } else if let Some(args) = data.strip_prefix(&instruction::ImportUserStats::DISCRIMINATOR) {
+            let import = instruction::ImportUserStats::try_from_slice(args)?;
+            *self.transfers.entry(import.user).or_default() += import.transfers;
+        }
+        Ok(())
+    }
+
+    fn apply_events(&mut self, signature: &str, events: Vec<BridgeEvent>, simulated: bool) {
+        // A LockEvent and its CompactLockEvent (EventEncoding::Both) are
+        // one transfer, and a claim's MintEvent/UnlockEvent repeats a
+        // delivery made earlier
+        let mut outbound = BTreeMap::new();
+        let mut delivered = BTreeSet::new();
+        let mut claimed = BTreeSet::new();
+        for event in events {
+            match event {
+                BridgeEvent::Lock(e) => {
+                    outbound.insert(e.nonce, e.from);
+                }
+                BridgeEvent::CompactLock(e) => {
+                    outbound.insert(e.nonce, e.from);
+                }
+                BridgeEvent::Burn(e) => {
+                    outbound.insert(e.nonce, e.from);
+                }
+                BridgeEvent::Mint(e) if !simulated => {
+                    delivered.insert(e.nonce);
+                }
+                BridgeEvent::Unlock(e) => {
+                    delivered.insert(e.nonce);
+                }
+                BridgeEvent::TransferClaimable(e) => {
+                    delivered.insert(e.nonce);
+                }
+                BridgeEvent::DeliveryQuarantined(e) => {
+                    delivered.insert(e.nonce);
+                }
+                BridgeEvent::TransferClaimed(e) => {
+                    claimed.insert(e.nonce);
+                }
+                _ => {}
+            }
+        }
+
+        for (nonce, from) in outbound {
+            match self.outbound.entry(nonce) {
+                Entry::Occupied(first) => self.divergences.push(Divergence::new(
+                    "outbound",
+                    format!("nonce {nonce} taken twice, by {} and {signature}", first.get()),
+                )),
+                Entry::Vacant(slot) => {
+                    slot.insert(signature.to_string());
+                    *self.transfers.entry(from).or_default() += 1;
+                }
+            }
+        }
+        for nonce in delivered.difference(&claimed) {
+            match self.delivered.entry(*nonce) {
+                Entry::Occupied(first) => self.divergences.push(Divergence::new(
+                    "inbound",
+                    format!("nonce {nonce} delivered twice, by {} and {signature}", first.get()),
+                )),
+                Entry::Vacant(slot) => {
+                    slot.insert(signature.to_string());
+                }
+            }
+        }
+    }
+
+    /// Vault balances must pick up where the last transaction left them
+    fn apply_balances(&mut self, signature: &str, keys: &[Pubkey], meta: &UiTransactionStatusMeta) {
+        let pre = vault_balances(&meta.pre_token_balances, keys, &self.authority);
+        let post = vault_balances(&meta.post_token_balances, keys, &self.authority);
+
+        for (vault, (mint, amount)) in &pre {
+            let detail = match self.vaults.get(vault) {
+                Some((_, last)) if last != amount => format!(
+                    "{vault} ({mint}) went from {last} to {amount} outside the program, \
+                     before {signature}"
+                ),
+                None if *amount != 0 => format!(
+                    "{vault} ({mint}) held {amount} before its first bridge transaction, \
+                     {signature}"
+                ),
+                _ => continue,
+            };
+            self.divergences.push(Divergence::new("vault", detail));
+        }
+
+        // Closed accounts have no post balance
+        for vault in pre.keys() {
+            if !post.contains_key(vault) {
+                self.vaults.remove(vault);
+            }
+        }
+        self.vaults.extend(post);
+    }
+
+    fn diff(self, live: &Live) -> Vec<Divergence> {
+        let mut divergences = self.divergences;
+        let state = &live.state;
+
+        let untaken: Vec<u64> = (1..=state.nonce)
+            .filter(|nonce| !self.outbound.contains_key(nonce))
+            .collect();
+        if !untaken.is_empty() {
+            divergences.push(Divergence::new(
+                "outbound",
+                format!(
+                    "the counter is at {} but no transfer took {}",
+                    state.nonce,
+                    list(&untaken)
+                ),
+            ));
+        }
+        let first_routed = RouteSequence::nonce_at(1, 0);
+        let past: Vec<u64> = self
+            .outbound
+            .range(state.nonce + 1..first_routed)
+            .map(|(n, _)| *n)
+            .collect();
+        if !past.is_empty() {
+            divergences.push(Divergence::new(
+                "outbound",
+                format!("the counter is at {} but transfers took {}", state.nonce, list(&past)),
+            ));
+        }
+
+        let mut routes: BTreeMap<u64, Vec<u64>> = BTreeMap::new();
+        for nonce in self.outbound.range(first_routed..).map(|(n, _)| *n) {
+            let sequence = nonce & ((1 << SEQUENCE_BITS) - 1);
+            routes.entry(nonce >> SEQUENCE_BITS).or_default().push(sequence);
+        }
+        for (shard, sequences) in routes {
+            let last = sequences.last().copied().unwrap_or_default();
+            // An imported route's earlier transfers are the old deployment's
+            let first = self.imported_routes.get(&shard).map_or(1, |last| last + 1);
+            let untaken: Vec<u64> = (first..=last)
+                .filter(|sequence| sequences.binary_search(sequence).is_err())
+                .collect();
+            if !untaken.is_empty() {
+                divergences.push(Divergence::new(
+                    "outbound",
+                    format!(
+                        "route shard {shard} reached sequence {last} but no transfer took {}",
+                        list(&untaken)
+                    ),
+                ));
+            }
+        }
+
+        let unprocessed: Vec<u64> = self
+            .delivered
+            .keys()
+            .copied()
+            .filter(|nonce| !state.is_processed(*nonce))
+            .collect();
+        if !unprocessed.is_empty() {
+            divergences.push(Divergence::new(
+                "inbound",
+                format!("delivered but not processed: {}", list(&unprocessed)),
+            ));
+        }
+        let undelivered: Vec<u64> = (0..state.processed_floor)
+            .chain(state.processed_nonces.iter().copied())
+            .filter(|nonce| {
+                !self.delivered.contains_key(nonce)
+                    && *nonce >= self.imported_floor
+                    && !self.imported_processed.contains(nonce)
+            })
+            .collect();
+        if !undelivered.is_empty() {
+            divergences.push(Divergence::new(
+                "inbound",
+                format!("processed but never delivered or imported: {}", list(&undelivered)),
+            ));
+        }
+
+        for (vault, (mint, amount)) in &live.vaults {
+            let replayed = self.vaults.get(vault).map_or(0, |(_, amount)| *amount);
+            if replayed != *amount {
+                divergences.push(Divergence::new(
+                    "vault",
+                    format!("{vault} ({mint}) holds {amount}; history says {replayed}"),
+                ));
+            }
+        }
+        for (vault, (mint, amount)) in &self.vaults {
+            if *amount != 0 && !live.vaults.contains_key(vault) {
+                divergences.push(Divergence::new(
+                    "vault",
+                    format!("{vault} ({mint}) should hold {amount} but isn't the bridge's now"),
+                ));
+            }
+        }
+
+        let mut transfers = self.transfers;
+        for stats in &live.user_stats {
+            let replayed = transfers.remove(&stats.user).unwrap_or(0);
+            if replayed != stats.transfers {
+                divergences.push(Divergence::new(
+                    "user_stats",
+                    format!(
+                        "{} has {} transfers; history says {replayed}",
+                        stats.user, stats.transfers
+                    ),
+                ));
+            }
+        }
+        for (user, count) in transfers {
+            divergences.push(Divergence::new(
+                "user_stats",
+                format!("{user} made {count} transfers but has no UserStats"),
+            ));
+        }
+
+        divergences
+    }
+}
+
+/// Balances of the token accounts `authority` owns, by address
+fn vault_balances(
+    balances: &OptionSerializer<Vec<UiTransactionTokenBalance>>,
+    keys: &[Pubkey],
+    authority: &str,
+) -> Balances {
+    let OptionSerializer::Some(balances) = balances else {
+        return Balances::new();
+    };
+    balances
+        .iter()
+        .filter(|balance| {
+            matches!(&balance.owner, OptionSerializer::Some(owner) if owner == authority)
+        })
+        .filter_map(|balance| {
+            let vault = keys.get(balance.account_index as usize)?;
+            let amount = balance.ui_token_amount.amount.parse().ok()?;
+            Some((vault.to_string(), (balance.mint.clone(), amount)))
+        })
+        .collect()
+}
+
+/// The first few of `nonces`, and how many more there are
+fn list(nonces: &[u64]) -> String {
+    let shown: Vec<String> = nonces.iter().take(MAX_LISTED).map(u64::to_string).collect();
+    match nonces.len().saturating_sub(MAX_LISTED) {
+        0 => shown.join(", "),
+        more => format!("{} and {more} more", shown.join(", ")),
+    }
+}
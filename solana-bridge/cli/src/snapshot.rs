@@ -0,0 +1,794 @@
+//! Bridge state snapshots, for audits, migrations and incident forensics
+//!
+//!   bridge-cli snapshot export --out before.json     every bridge account, decoded
+//!   bridge-cli snapshot export --format csv ...      same, one row per account
+//!   bridge-cli snapshot diff before.json after.json  what changed in between
+//!
+//! A snapshot holds every account the program owns plus the token accounts
+//! the bridge authority holds (escrows and fee vaults). Finished transfer
+//! records are left out unless --all-transfers: they never change again
+//! and are most of the accounts.
+//!
+//! Accounts are decoded by discriminator into named fields, and each
+//! carries a hash of its raw data (of the parsed account, for vaults), so
+//! a change the decoding doesn't show still turns up in a diff. Diffs
+//! read JSON snapshots; CSV is for spreadsheets.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::{Context as _, Result};
+use clap::{Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use solana_account_decoder::UiAccountData;
+use solana_bridge::{
+    AdminLog, AdminProposal, AllowedSender, Attestation, BondedPauseState, BridgeState, BuildRecord,
+    ChainConfig, Checkpoint, CheckpointLog, CompressedStore, ConversionRate, DeliveryFeeConfig,
+    DepositAddress, EjectedRelayer, EscrowMigration, EventHistory, FeeExemption, FeeRoute, FeeTiers,
+    GasReimbursement, GasReport, GlobalStats, GuardianCouncil, GuardianSet, Inbox, InstanceReceipt,
+    InstanceTransfer, ListingConfig, MessageTarget, MetadataSync, Migration, OptimisticConfig,
+    Outbox, OwnerMultisig, PauseBondConfig, PriceBreaker, RelayerEpochStats, RelayerRewardConfig,
+    ReserveAttestation, RewardEpoch, RouteSequence, SenderAllowlist, SlaConfig,
+    SmartWalletRecipient, SolFeeConfig, TermsAcceptance, TermsConfig, TokenConfig, TokenListing,
+    TransferMemo, TransferRecord, TransferRoute, TransferStatus, TrustModel,
+    UpgradeAuthorityRecord, UserStats, WalletDerivation, WatcherInfo, WithdrawalAllowlist,
+    WrappedAsset, YieldStrategy, ZkVerifier,
+};
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::solana::Solana;
+use crate::support::Context;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Read every bridge account and write it to a file
+    Export {
+        #[arg(long, default_value = "snapshot.json")]
+        out: PathBuf,
+
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+
+        /// Include completed, refunded, vetoed and cancelled transfers
+        #[arg(long)]
+        all_transfers: bool,
+    },
+
+    /// Show what changed between two JSON snapshots
+    Diff { before: PathBuf, after: PathBuf },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Json,
+    Csv,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    pub program_id: String,
+    pub slot: u64,
+    /// Unix time the snapshot was taken
+    pub taken_at: i64,
+    pub accounts: Vec<Entry>,
+}
+
+#[derive(Serialize, Deserialize, PartialEq)]
+pub struct Entry {
+    pub address: String,
+    /// Account type, "TokenAccount" for vaults, "Unknown" if undecodable
+    pub kind: String,
+    pub lamports: u64,
+    pub data_len: usize,
+    /// SHA-256 of the account data (base58)
+    pub data_hash: String,
+    pub fields: Value,
+}
+
+pub fn run(ctx: &Context, command: Command) -> Result<()> {
+    match command {
+        Command::Export {
+            out,
+            format,
+            all_transfers,
+        } => {
+            let snapshot = take(&ctx.solana, all_transfers)?;
+            match format {
+                Format::Json => write_json(&out, &snapshot)?,
+                Format::Csv => write_csv(&out, &snapshot)?,
+            }
+            println!(
+                "Wrote {} accounts at slot {} to {}",
+                snapshot.accounts.len(),
+                snapshot.slot,
+                out.display()
+            );
+            Ok(())
+        }
+        Command::Diff { before, after } => {
+            print_diff(&read_json(&before)?, &read_json(&after)?);
+            Ok(())
+        }
+    }
+}
+
+/// Read the bridge's accounts as of now
+pub fn take(solana: &Solana, all_transfers: bool) -> Result<Snapshot> {
+    let slot = solana.slot()?;
+    let mut accounts = Vec::new();
+
+    for (address, account) in solana.program_accounts()? {
+        if !all_transfers && is_finished_transfer(&account.data) {
+            continue;
+        }
+        let (kind, fields) = decode(&account.data).unwrap_or(("Unknown", json!({})));
+        accounts.push(Entry {
+            address: address.to_string(),
+            kind: kind.to_string(),
+            lamports: account.lamports,
+            data_len: account.data.len(),
+            data_hash: hash(&account.data).to_string(),
+            fields,
+        });
+    }
+
+    for vault in solana.vaults()? {
+        let UiAccountData::Json(parsed) = &vault.account.data else {
+            continue;
+        };
+        let info = &parsed.parsed["info"];
+        let fields = json!({
+            "mint": info["mint"],
+            "owner": info["owner"],
+            "amount": info["tokenAmount"]["amount"],
+            "state": info["state"],
+        });
+        accounts.push(Entry {
+            address: vault.pubkey,
+            kind: "TokenAccount".to_string(),
+            lamports: vault.account.lamports,
+            data_len: parsed.space as usize,
+            data_hash: hash(fields.to_string().as_bytes()).to_string(),
+            fields,
+        });
+    }
+
+    accounts.sort_by(|a, b| (&a.kind, &a.address).cmp(&(&b.kind, &b.address)));
+    Ok(Snapshot {
+        program_id: solana.program_id().to_string(),
+        slot,
+        taken_at: unix_now(),
+        accounts,
+    })
+}
+
+fn is_finished_transfer(data: &[u8]) -> bool {
+    data.starts_with(&TransferRecord::DISCRIMINATOR)
+        && TransferRecord::try_deserialize(&mut &data[..]).is_ok_and(|record| {
+            matches!(
+                record.status,
+                TransferStatus::Completed
+                    | TransferStatus::Refunded
+                    | TransferStatus::Vetoed
+                    | TransferStatus::Cancelled
+            )
+        })
+}
+
+fn unix_now() -> i64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn keys(keys: &[Pubkey]) -> Vec<String> {
+    keys.iter().map(Pubkey::to_string).collect()
+}
+
+/// Try each account type by discriminator; an account that matches but
+/// won't deserialize (e.g. an old layout) keeps its type with the error
+macro_rules! decoders {
+    ($data:expr; $($ty:ident($a:ident) => $fields:expr),* $(,)?) => {{
+        let data: &[u8] = $data;
+        $(
+            if data.starts_with(&$ty::DISCRIMINATOR) {
+                let fields = match $ty::try_deserialize(&mut &data[..]) {
+                    Ok($a) => $fields,
+                    Err(e) => json!({ "decode_error": e.to_string() }),
+                };
+                return Some((stringify!($ty), fields));
+            }
+        )*
+        None
+    }};
+}
+
+/// Account type and fields, by discriminator
+fn decode(data: &[u8]) -> Option<(&'static str, Value)> {
+    if data.starts_with(&Outbox::DISCRIMINATOR) {
+        // Zero-copy ring buffer; only the cursor is worth showing
+        let next_sequence = data
+            .get(8..16)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes);
+        return Some(("Outbox", json!({ "next_sequence": next_sequence })));
+    }
+    if data.starts_with(&EventHistory::DISCRIMINATOR) {
+        // Zero-copy ring buffer too: the token and the cursor
+        let mint = data
+            .get(8..40)
+            .and_then(|bytes| Pubkey::try_from(bytes).ok())
+            .map(|mint| mint.to_string());
+        let next_sequence = data
+            .get(40..48)
+            .and_then(|bytes| bytes.try_into().ok())
+            .map(u64::from_le_bytes);
+        return Some(("EventHistory", json!({ "mint": mint, "next_sequence": next_sequence })));
+    }
+
+    decoders!(data;
+        BridgeState(a) => json!({
+            "owner": a.owner.to_string(),
+            "paused": a.paused,
+            "pending_owner": a.pending_owner.to_string(),
+            "processed_nonces": a.processed_nonces,
+            "idempotent_delivery": a.idempotent_delivery,
+            "processed_floor": a.processed_floor,
+            "heartbeat_epochs": a.heartbeat_epochs,
+            "last_heartbeat_epoch": a.last_heartbeat_epoch,
+            "event_encoding": format!("{:?}", a.event_encoding),
+            "fee_manager": a.fee_manager.to_string(),
+            "claim_delivery": a.claim_delivery,
+            "pass_mint": a.pass_mint.to_string(),
+            "pass_discount_bps": a.pass_discount_bps,
+            "pass_tier_boost": a.pass_tier_boost,
+            "instance_id": a.instance_id,
+            "route_shards": a.route_shards,
+        }),
+        AdminLog(a) => json!({
+            "total": a.total,
+            "entries": a.entries.iter().map(|e| json!({
+                "actor": e.actor.to_string(),
+                "action": format!("{:?}", e.action),
+                "timestamp": e.timestamp,
+            })).collect::<Vec<_>>(),
+        }),
+        ZkVerifier(a) => json!({ "ic_points": a.ic.len() }),
+        GuardianSet(a) => json!({
+            "index": a.index,
+            "threshold": a.threshold,
+            "guardians": keys(&a.guardians),
+        }),
+        GuardianCouncil(a) => json!({
+            "threshold": a.threshold,
+            "members": keys(&a.members),
+            "halted": a.halted,
+        }),
+        OptimisticConfig(a) => json!({
+            "challenge_period": a.challenge_period,
+            "bond_amount": a.bond_amount,
+            "protocol_cut_bps": a.protocol_cut_bps,
+            "treasury": a.treasury.to_string(),
+        }),
+        Attestation(a) => json!({
+            "relayer": a.relayer.to_string(),
+            "recipient": a.recipient.to_string(),
+            "amount": a.amount,
+            "nonce": a.nonce,
+            "relayer_bond": a.relayer_bond,
+            "posted_at": a.posted_at,
+            "executable_at": a.executable_at,
+            "status": format!("{:?}", a.status),
+            "challenger": a.challenger.to_string(),
+            "challenger_bond": a.challenger_bond,
+            "evidence_hash": hex::encode(a.evidence_hash),
+        }),
+        TransferRecord(a) => json!({
+            "transfer_id": hex::encode(a.transfer_id),
+            "direction": format!("{:?}", a.direction),
+            "nonce": a.nonce,
+            "status": format!("{:?}", a.status),
+            "remote_chain_id": a.remote_chain_id,
+            "local_account": a.local_account.to_string(),
+            "remote_address": a.remote_address,
+            "recipient_name": a.recipient_name,
+            "amount": a.amount,
+            "mint": a.mint.to_string(),
+            "escrowed": a.escrowed,
+            "deadline": a.deadline,
+            "receipt": a.receipt,
+            "recovery": a.recovery.to_string(),
+            "fee": a.fee,
+            "sla_rebate": a.sla_rebate,
+            "route_sequence": a.route_sequence,
+            "created_at": a.created_at,
+            "updated_at": a.updated_at,
+            "created_slot": a.created_slot,
+            "processed_slot": a.processed_slot,
+            "processed_at": a.processed_at,
+            "processed_by": a.processed_by.to_string(),
+        }),
+        WatcherInfo(a) => json!({ "watcher": a.watcher.to_string() }),
+        EjectedRelayer(a) => json!({
+            "relayer": a.relayer.to_string(),
+            "reason": a.reason,
+            "fraud_nonce": a.fraud_nonce,
+            "ejected_at": a.ejected_at,
+        }),
+        ChainConfig(a) => json!({
+            "chain_id": a.chain_id,
+            "kind": format!("{:?}", a.kind),
+            "enabled": a.enabled,
+            "finality": format!("{:?}", a.finality),
+            "required_confirmations": a.required_confirmations,
+            "base_fee": a.base_fee,
+            "fee_bps": a.fee_bps,
+            "min_amount": a.min_amount,
+            "address_prefix": a.address_prefix,
+            "halted": a.halted,
+        }),
+        FeeRoute(a) => json!({
+            "mint": a.mint.to_string(),
+            "chain_id": a.chain_id,
+            "base_fee": a.base_fee,
+            "fee_bps": a.fee_bps,
+        }),
+        SolFeeConfig(a) => json!({
+            "mint": a.mint.to_string(),
+            "feed_id": format!("0x{}", hex::encode(a.feed_id)),
+            "sol_feed_id": format!("0x{}", hex::encode(a.sol_feed_id)),
+            "markup_bps": a.markup_bps,
+        }),
+        DeliveryFeeConfig(a) => json!({
+            "chain_id": a.chain_id,
+            "native_feed_id": format!("0x{}", hex::encode(a.native_feed_id)),
+            "native_decimals": a.native_decimals,
+            "sol_feed_id": format!("0x{}", hex::encode(a.sol_feed_id)),
+            "gas_limit": a.gas_limit,
+            "gas_price": a.gas_price,
+            "gas_price_updated_at": a.gas_price_updated_at,
+            "markup_bps": a.markup_bps,
+        }),
+        PriceBreaker(a) => json!({
+            "mint": a.mint.to_string(),
+            "feed_id": format!("0x{}", hex::encode(a.feed_id)),
+            "max_move_bps": a.max_move_bps,
+            "window": a.window,
+            "cooldown": a.cooldown,
+            "reference_price": a.reference_price,
+            "reference_exponent": a.reference_exponent,
+            "reference_at": a.reference_at,
+            "tripped_until": a.tripped_until,
+        }),
+        RouteSequence(a) => json!({
+            "mint": a.mint.to_string(),
+            "dest_chain_id": a.dest_chain_id,
+            "last": a.last,
+            "shard": a.shard,
+        }),
+        SlaConfig(a) => json!({
+            "max_latency_slots": a.max_latency_slots,
+            "rebate_bps": a.rebate_bps,
+        }),
+        TermsConfig(a) => json!({
+            "version": a.version,
+            "terms_hash": format!("0x{}", hex::encode(a.terms_hash)),
+            "updated_at": a.updated_at,
+        }),
+        TermsAcceptance(a) => json!({
+            "wallet": a.wallet.to_string(),
+            "version": a.version,
+            "terms_hash": format!("0x{}", hex::encode(a.terms_hash)),
+            "accepted_at": a.accepted_at,
+        }),
+        SenderAllowlist(a) => json!({
+            "all_tokens": a.all_tokens,
+            "mints": keys(&a.mints),
+            "updated_at": a.updated_at,
+        }),
+        AllowedSender(a) => json!({
+            "sender": a.sender.to_string(),
+            "added_at": a.added_at,
+        }),
+        FeeExemption(a) => json!({
+            "address": a.address.to_string(),
+            "granted_by": a.granted_by.to_string(),
+            "granted_at": a.granted_at,
+        }),
+        GasReport(a) => json!({
+            "nonce": a.nonce,
+            "relayer": a.relayer.to_string(),
+            "gas_paid": a.gas_paid,
+            "reimbursed": a.reimbursed,
+            "reported_at": a.reported_at,
+        }),
+        GasReimbursement(a) => json!({
+            "relayer": a.relayer.to_string(),
+            "owed": a.owed,
+            "reports": a.reports,
+            "gas_paid": a.gas_paid,
+            "claimed": a.claimed,
+        }),
+        CompressedStore(a) => json!({
+            "tree": a.tree.to_string(),
+            "max_depth": a.max_depth,
+            "max_buffer_size": a.max_buffer_size,
+            "leaves": a.leaves,
+            "compressed": a.compressed,
+        }),
+        UpgradeAuthorityRecord(a) => json!({
+            "authority": a.authority.map(|k| k.to_string()),
+            "held_by_owner": a.held_by_owner,
+            "deployed_slot": a.deployed_slot,
+            "recorded_at": a.recorded_at,
+        }),
+        BuildRecord(a) => json!({
+            "hash": hex::encode(a.hash),
+            "version": a.version,
+            "deployed_slot": a.deployed_slot,
+            "recorded_at": a.recorded_at,
+        }),
+        MessageTarget(a) => json!({
+            "chain_id": a.chain_id,
+            "target": hex::encode(a.target),
+            "allowed_at": a.allowed_at,
+        }),
+        GlobalStats(a) => json!({
+            "tvl_usd": a.tvl_usd,
+            "window_start": a.window_start,
+            "inflow_usd": a.inflow_usd,
+            "outflow_usd": a.outflow_usd,
+            "last_inflow_usd": a.last_inflow_usd,
+            "last_outflow_usd": a.last_outflow_usd,
+            "updated_at": a.updated_at,
+            "tokens": a.tokens.iter().map(|t| json!({
+                "mint": t.mint.to_string(),
+                "escrow": t.escrow.to_string(),
+                "feed_id": hex::encode(t.feed_id),
+                "balance": t.balance,
+            })).collect::<Vec<_>>(),
+        }),
+        TransferRoute(a) => json!({
+            "transfer_id": hex::encode(a.transfer_id),
+            "nonce": a.nonce,
+            "hops": a.route.hops,
+            "final_chain_id": a.route.final_chain_id,
+            "final_recipient": a.route.final_recipient,
+        }),
+        TokenConfig(a) => json!({
+            "mint": a.mint.to_string(),
+            "ibc_denoms": a.ibc_denoms.iter().map(|d| json!({
+                "chain_id": d.chain_id,
+                "denom": d.denom,
+            })).collect::<Vec<_>>(),
+            "restricted": a.restricted,
+            "credential_program": a.credential_program.to_string(),
+            "tier_limits": a.tier_limits,
+            "allowed_extensions": a.allowed_extensions,
+            "accounting": format!("{:?}", a.accounting),
+            "paused": a.paused,
+            "trust_model": match a.trust_model {
+                TrustModel::WormholeVaa { emitter } => {
+                    format!("WormholeVaa({})", hex::encode(emitter))
+                }
+                model => format!("{model:?}"),
+            },
+            "bridged_decimals": a.bridged_decimals,
+            "dust_policy": format!("{:?}", a.dust_policy),
+        }),
+        ConversionRate(a) => json!({
+            "mint": a.mint.to_string(),
+            "oracle": a.oracle.to_string(),
+            "rate": a.rate,
+            "updated_at": a.updated_at,
+            "max_age": a.max_age,
+        }),
+        OwnerMultisig(a) => json!({
+            "owners": keys(&a.owners),
+            "threshold": a.threshold,
+            "proposal_count": a.proposal_count,
+        }),
+        AdminProposal(a) => json!({
+            "id": a.id,
+            "proposer": a.proposer.to_string(),
+            "instruction_data": hex::encode(&a.instruction_data),
+            "accounts": a.accounts.iter().map(|m| m.pubkey.to_string()).collect::<Vec<_>>(),
+            "approvals": keys(&a.approvals),
+            "executed": a.executed,
+            "created_at": a.created_at,
+        }),
+        RelayerRewardConfig(a) => json!({
+            "start_time": a.start_time,
+            "epoch_duration": a.epoch_duration,
+            "reward_per_transfer": a.reward_per_transfer,
+            "max_gas_per_transfer": a.max_gas_per_transfer,
+        }),
+        RewardEpoch(a) => json!({
+            "epoch": a.epoch,
+            "total_reward": a.total_reward,
+            "total_transfers": a.total_transfers,
+        }),
+        RelayerEpochStats(a) => json!({
+            "epoch": a.epoch,
+            "relayer": a.relayer.to_string(),
+            "transfers": a.transfers,
+            "submitted": a.submitted,
+            "total_latency_slots": a.total_latency_slots,
+            "failures": a.failures,
+        }),
+        FeeTiers(a) => json!({
+            "tiers": a.tiers.iter().map(|t| json!({
+                "min_volume": t.min_volume,
+                "discount_bps": t.discount_bps,
+            })).collect::<Vec<_>>(),
+        }),
+        UserStats(a) => json!({
+            "user": a.user.to_string(),
+            "volume": a.volume,
+            "transfers": a.transfers,
+        }),
+        PauseBondConfig(a) => json!({
+            "bond_amount": a.bond_amount,
+            "reward": a.reward,
+            "expiry_slots": a.expiry_slots,
+        }),
+        BondedPauseState(a) => json!({
+            "pauser": a.pauser.to_string(),
+            "bond": a.bond,
+            "paused_at": a.paused_at,
+            "expires_at_slot": a.expires_at_slot,
+        }),
+        WrappedAsset(a) => json!({
+            "mint": a.mint.to_string(),
+            "source_chain_id": a.source_chain_id,
+            "source_token": format!("0x{}", hex::encode(a.source_token)),
+            "source_decimals": a.source_decimals,
+            "decimals": a.decimals,
+        }),
+        MetadataSync(a) => json!({
+            "mint": a.mint.to_string(),
+            "eth_block": a.eth_block,
+            "synced_at": a.synced_at,
+        }),
+        ListingConfig(a) => json!({
+            "bond": a.bond,
+            "veto_window": a.veto_window,
+            "max_per_window": a.max_per_window,
+            "rate_window": a.rate_window,
+            "window_start": a.window_start,
+            "window_count": a.window_count,
+        }),
+        TokenListing(a) => json!({
+            "lister": a.lister.to_string(),
+            "source_token": format!("0x{}", hex::encode(a.source_token)),
+            "source_decimals": a.source_decimals,
+            "decimals": a.decimals,
+            "source_name": a.source_name,
+            "source_symbol": a.source_symbol,
+            "uri": a.uri,
+            "bond": a.bond,
+            "listed_at": a.listed_at,
+            "activates_at": a.activates_at,
+        }),
+        Inbox(a) => json!({
+            "chain_id": a.chain_id,
+            "next_sequence": a.next_sequence,
+            "window": a.window,
+            "delivered": format!("{:032x}", a.delivered),
+        }),
+        ReserveAttestation(a) => json!({
+            "mint": a.mint.to_string(),
+            "eth_locked_amount": a.eth_locked_amount,
+            "block_number": a.block_number,
+            "wrapped_supply": a.wrapped_supply,
+            "posted_at": a.posted_at,
+            "slot": a.slot,
+        }),
+        CheckpointLog(a) => json!({
+            "count": a.count,
+            "last_root": hex::encode(a.last_root),
+            "last_slot": a.last_slot,
+            "inbound_nonce_end": a.inbound_nonce_end,
+        }),
+        Checkpoint(a) => json!({
+            "index": a.index,
+            "root": hex::encode(a.root),
+            "prev_root": hex::encode(a.prev_root),
+            "transfer_count": a.transfer_count,
+            "inbound_volume": a.inbound_volume,
+            "outbound_volume": a.outbound_volume,
+            "slot": a.slot,
+            "created_at": a.created_at,
+        }),
+        Migration(a) => json!({
+            "source_program": a.source_program.to_string(),
+            "imported_nonces": a.imported_nonces,
+            "imported_users": a.imported_users,
+            "sealed": a.sealed,
+        }),
+        EscrowMigration(a) => json!({
+            "mint": a.mint.to_string(),
+            "new_bridge_program": a.new_bridge_program.to_string(),
+            "new_vault": a.new_vault.to_string(),
+            "executable_at": a.executable_at,
+            "migrated_at": a.migrated_at,
+            "amount": a.amount,
+            "route_shards": a.route_shards,
+            "processed_floor": a.processed_floor,
+        }),
+        InstanceTransfer(a) => json!({
+            "id": a.id,
+            "mint": a.mint.to_string(),
+            "dest_instance": a.dest_instance,
+            "dest_vault": a.dest_vault.to_string(),
+            "amount": a.amount,
+            "sent_at": a.sent_at,
+        }),
+        InstanceReceipt(a) => json!({
+            "source_instance": a.source_instance,
+            "source_record": a.source_record.to_string(),
+            "amount": a.amount,
+            "received_at": a.received_at,
+        }),
+        DepositAddress(a) => json!({
+            "user": a.user.to_string(),
+            "dest_chain_id": a.dest_chain_id,
+            "recipient": a.recipient,
+            "registered_at": a.registered_at,
+            "sweeps": a.sweeps,
+        }),
+        TransferMemo(a) => json!({
+            "transfer_id": hex::encode(a.transfer_id),
+            "nonce": a.nonce,
+            "memo": hex::encode(&a.memo),
+        }),
+        SmartWalletRecipient(a) => json!({
+            "transfer_id": hex::encode(a.transfer_id),
+            "nonce": a.nonce,
+            "derivation": match a.derivation {
+                WalletDerivation::Create2 {
+                    factory,
+                    salt,
+                    init_code_hash,
+                } => json!({
+                    "create2": {
+                        "factory": format!("0x{}", hex::encode(factory)),
+                        "salt": hex::encode(salt),
+                        "init_code_hash": hex::encode(init_code_hash),
+                    }
+                }),
+            },
+        }),
+        WithdrawalAllowlist(a) => json!({
+            "user": a.user.to_string(),
+            "authority": a.authority.to_string(),
+            "addresses": a.addresses.iter().map(|e| json!({
+                "dest_chain_id": e.dest_chain_id,
+                "address": hex::encode(e.address),
+                "active_at": e.active_at,
+            })).collect::<Vec<_>>(),
+        }),
+        YieldStrategy(a) => json!({
+            "mint": a.mint.to_string(),
+            "venues": a.venues.iter().map(|e| json!({
+                "adapter": e.adapter.to_string(),
+                "max_amount": e.max_amount,
+                "active_at": e.active_at,
+                "deployed": e.deployed,
+            })).collect::<Vec<_>>(),
+            "deployed": a.deployed,
+            "harvested": a.harvested,
+            "lost": a.lost,
+        }),
+    )
+}
+
+pub fn read_json(path: &Path) -> Result<Snapshot> {
+    let text = std::fs::read_to_string(path).with_context(|| path.display().to_string())?;
+    serde_json::from_str(&text)
+        .with_context(|| format!("{} is not a JSON snapshot", path.display()))
+}
+
+fn write_json(path: &Path, snapshot: &Snapshot) -> Result<()> {
+    std::fs::write(path, serde_json::to_string_pretty(snapshot)? + "\n")
+        .with_context(|| path.display().to_string())
+}
+
+/// One row per account; decoded fields as a JSON cell
+fn write_csv(path: &Path, snapshot: &Snapshot) -> Result<()> {
+    let mut out = String::from("address,kind,lamports,data_len,data_hash,fields\n");
+    for entry in &snapshot.accounts {
+        out.push_str(&format!(
+            "{},{},{},{},{},{}\n",
+            entry.address,
+            entry.kind,
+            entry.lamports,
+            entry.data_len,
+            entry.data_hash,
+            csv_quote(&entry.fields.to_string())
+        ));
+    }
+    std::fs::write(path, out).with_context(|| path.display().to_string())
+}
+
+fn csv_quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('"', "\"\""))
+}
+
+fn print_diff(before: &Snapshot, after: &Snapshot) {
+    if before.program_id != after.program_id {
+        println!(
+            "Note: comparing program {} with {}",
+            before.program_id, after.program_id
+        );
+    }
+    println!("Slot {} -> {}", before.slot, after.slot);
+
+    let old: BTreeMap<&str, &Entry> =
+        before.accounts.iter().map(|e| (e.address.as_str(), e)).collect();
+    let new: BTreeMap<&str, &Entry> =
+        after.accounts.iter().map(|e| (e.address.as_str(), e)).collect();
+    let (mut added, mut removed, mut changed) = (0, 0, 0);
+
+    for entry in &after.accounts {
+        match old.get(entry.address.as_str()) {
+            None => {
+                added += 1;
+                println!("+ {} {}", entry.kind, entry.address);
+            }
+            Some(previous) if *previous != entry => {
+                changed += 1;
+                println!("~ {} {}", entry.kind, entry.address);
+                print_changes(previous, entry);
+            }
+            Some(_) => {}
+        }
+    }
+    for entry in &before.accounts {
+        if !new.contains_key(entry.address.as_str()) {
+            removed += 1;
+            println!("- {} {}", entry.kind, entry.address);
+        }
+    }
+
+    println!("{added} added, {removed} removed, {changed} changed");
+}
+
+fn print_changes(before: &Entry, after: &Entry) {
+    if before.kind != after.kind {
+        println!("    kind: {} -> {}", before.kind, after.kind);
+    }
+    if before.lamports != after.lamports {
+        println!("    lamports: {} -> {}", before.lamports, after.lamports);
+    }
+
+    let empty = serde_json::Map::new();
+    let old = before.fields.as_object().unwrap_or(&empty);
+    let new = after.fields.as_object().unwrap_or(&empty);
+    let mut names: Vec<&String> = old.keys().chain(new.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    let mut shown = false;
+    for name in names {
+        let (a, b) = (old.get(name), new.get(name));
+        if a != b {
+            shown = true;
+            println!("    {name}: {} -> {}", show(a), show(b));
+        }
+    }
+    if !shown && before.data_hash != after.data_hash {
+        println!("    data changed outside the decoded fields");
+    }
+}
+
+fn show(value: Option<&Value>) -> String {
+    match value {
+        Some(value) => value.to_string(),
+        None => "(none)".to_string(),
+    }
+}
@@ -0,0 +1,273 @@
+//! Bridge accounts over Solana RPC
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use anyhow::Result;
+use solana_account_decoder::parse_token::spl_token_ids;
+use solana_account_decoder::UiAccountEncoding;
+use solana_bridge::{BridgeState, GuardianCouncil, SourceTx, TransferDirection, TransferRecord};
+use solana_bridge_sdk::pda;
+use solana_client::nonce_utils;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcProgramAccountsConfig, RpcTransactionConfig,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::TokenAccountsFilter;
+use solana_client::rpc_response::{RpcConfirmedTransactionStatusWithSignature, RpcKeyedAccount};
+use solana_sdk::account::Account;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signature, Signer};
+use solana_sdk::transaction::Transaction;
+use solana_transaction_status::{EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding};
+
+/// Where `transfer_id` starts in a TransferRecord (after the discriminator)
+const TRANSFER_ID_OFFSET: usize = 8;
+
+/// Signatures per getSignaturesForAddress page (the RPC maximum)
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+pub struct Solana {
+    rpc: RpcClient,
+    program_id: Pubkey,
+}
+
+impl Solana {
+    pub fn new(url: &str, program_id: Pubkey) -> Self {
+        Self {
+            rpc: RpcClient::new_with_commitment(url.to_string(), CommitmentConfig::confirmed()),
+            program_id,
+        }
+    }
+
+    pub fn bridge_state(&self) -> Result<BridgeState> {
+        self.fetch(&pda::bridge_state(&self.program_id))?
+            .ok_or_else(|| anyhow::anyhow!("Bridge {} is not initialized", self.program_id))
+    }
+
+    pub fn program_id(&self) -> Pubkey {
+        self.program_id
+    }
+
+    pub fn guardian_council(&self) -> Result<GuardianCouncil> {
+        self.fetch(&pda::guardian_council(&self.program_id))?
+            .ok_or_else(|| anyhow::anyhow!("No guardian council has been created"))
+    }
+
+    /// The blockhash stored in a durable nonce account
+    pub fn nonce_blockhash(&self, nonce_account: &Pubkey) -> Result<Hash> {
+        let account = nonce_utils::get_account_with_commitment(
+            &self.rpc,
+            nonce_account,
+            self.rpc.commitment(),
+        )?;
+        Ok(nonce_utils::data_from_account(&account)?.blockhash())
+    }
+
+    pub fn slot(&self) -> Result<u64> {
+        Ok(self.rpc.get_slot()?)
+    }
+
+    /// Cluster time at the current slot
+    pub fn block_time(&self) -> Result<i64> {
+        Ok(self.rpc.get_block_time(self.slot()?)?)
+    }
+
+    /// Every account the program owns
+    pub fn program_accounts(&self) -> Result<Vec<(Pubkey, Account)>> {
+        let config = RpcProgramAccountsConfig {
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        Ok(self
+            .rpc
+            .get_program_accounts_with_config(&self.program_id, config)?)
+    }
+
+    /// Token accounts held by the bridge authority (escrows, fee vaults), jsonParsed
+    pub fn vaults(&self) -> Result<Vec<RpcKeyedAccount>> {
+        let authority = pda::bridge_authority(&self.program_id);
+        let mut vaults = Vec::new();
+        for token_program in spl_token_ids() {
+            let filter = TokenAccountsFilter::ProgramId(token_program);
+            vaults.extend(self.rpc.get_token_accounts_by_owner(&authority, filter)?);
+        }
+        Ok(vaults)
+    }
+
+    /**
+     * Every transaction that mentions the program, oldest first
+     *
+     * Only as far back as the node keeps; an archival (Bigtable-backed)
+     * RPC has the whole history.
+     */
+    pub fn signatures(&self) -> Result<Vec<RpcConfirmedTransactionStatusWithSignature>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+        loop {
+            let config = GetConfirmedSignaturesForAddress2Config {
+                before,
+                until: None,
+                limit: Some(SIGNATURE_PAGE_SIZE),
+                commitment: Some(self.rpc.commitment()),
+            };
+            let page = self
+                .rpc
+                .get_signatures_for_address_with_config(&self.program_id, config)?;
+            let full = page.len() == SIGNATURE_PAGE_SIZE;
+            before = page.last().map(|last| last.signature.parse()).transpose()?;
+            signatures.extend(page);
+            if !full {
+                break;
+            }
+        }
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    /// A transaction with its status meta, base64 so it decodes
+    pub fn transaction(
+        &self,
+        signature: &Signature,
+    ) -> Result<EncodedConfirmedTransactionWithStatusMeta> {
+        let config = RpcTransactionConfig {
+            encoding: Some(UiTransactionEncoding::Base64),
+            commitment: Some(self.rpc.commitment()),
+            max_supported_transaction_version: Some(0),
+        };
+        Ok(self.rpc.get_transaction_with_config(signature, config)?)
+    }
+
+    pub fn send(&self, transaction: &Transaction) -> Result<Signature> {
+        Ok(self.rpc.send_and_confirm_transaction(transaction)?)
+    }
+
+    /// Sign `instructions` with `payer` alone and send them
+    pub fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+    ) -> Result<Signature> {
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &[payer],
+            self.rpc.get_latest_blockhash()?,
+        );
+        self.send(&transaction)
+    }
+
+    /// Sign with `payer` and `signers` (e.g. a new account's keypair) and send
+    pub fn send_instructions_signed(
+        &self,
+        instructions: &[Instruction],
+        payer: &Keypair,
+        signers: &[&Keypair],
+    ) -> Result<Signature> {
+        let mut all: Vec<&Keypair> = vec![payer];
+        all.extend_from_slice(signers);
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &all,
+            self.rpc.get_latest_blockhash()?,
+        );
+        self.send(&transaction)
+    }
+
+    pub fn rent_exempt(&self, size: usize) -> Result<u64> {
+        Ok(self.rpc.get_minimum_balance_for_rent_exemption(size)?)
+    }
+
+    pub fn exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .is_some())
+    }
+
+    pub fn transfer(
+        &self,
+        direction: TransferDirection,
+        nonce: u64,
+    ) -> Result<Option<TransferRecord>> {
+        let address = match direction {
+            TransferDirection::Outbound => pda::outbound_transfer(&self.program_id, nonce),
+            TransferDirection::Inbound => pda::inbound_transfer(&self.program_id, nonce),
+        };
+        self.fetch(&address)
+    }
+
+    /// The inbound transfers recorded against a source-chain transaction
+    pub fn source_tx(&self, tx_hash: &[u8; 32]) -> Result<Option<SourceTx>> {
+        self.fetch(&pda::source_tx(&self.program_id, tx_hash))
+    }
+
+    pub fn transfer_by_id(&self, transfer_id: &[u8; 32]) -> Result<Option<TransferRecord>> {
+        let filter =
+            RpcFilterType::Memcmp(Memcmp::new_base58_encoded(TRANSFER_ID_OFFSET, transfer_id));
+        Ok(self.transfers(vec![filter])?.into_iter().next())
+    }
+
+    /// Every TransferRecord matching `filters`
+    pub fn transfers(&self, filters: Vec<RpcFilterType>) -> Result<Vec<TransferRecord>> {
+        Ok(self
+            .transfer_accounts(filters)?
+            .into_iter()
+            .map(|(_, record)| record)
+            .collect())
+    }
+
+    /// Every TransferRecord matching `filters`, with its address
+    pub fn transfer_accounts(
+        &self,
+        mut filters: Vec<RpcFilterType>,
+    ) -> Result<Vec<(Pubkey, TransferRecord)>> {
+        filters.push(RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &TransferRecord::DISCRIMINATOR,
+        )));
+        let config = RpcProgramAccountsConfig {
+            filters: Some(filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        self.rpc
+            .get_program_accounts_with_config(&self.program_id, config)?
+            .into_iter()
+            .map(|(address, account)| {
+                Ok((address, TransferRecord::try_deserialize(&mut account.data.as_slice())?))
+            })
+            .collect()
+    }
+
+    /// An account's raw data, if it exists
+    pub fn data(&self, address: &Pubkey) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .map(|account| account.data))
+    }
+
+    pub fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value;
+        match account {
+            Some(account) => Ok(Some(T::try_deserialize(&mut account.data.as_slice())?)),
+            None => Ok(None),
+        }
+    }
+}
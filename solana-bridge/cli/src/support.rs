@@ -0,0 +1,579 @@
+//! Support commands: status, pending, trace, reemit, recipient, eth-tx, profiles
+//!
+//! A transfer passes three places: the source chain, the relayer and the
+//! destination chain. `trace` checks each in order and names the first
+//! one the transfer hasn't got past; `reemit` gets a transfer the relayer
+//! never saw in front of it again. `recipient` checks an Ethereum
+//! recipient before anyone locks funds for it, and `eth-tx` starts from
+//! the Ethereum transaction a user has instead of a nonce.
+
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::{bail, Context as _, Result};
+use serde_json::Value;
+use solana_bridge::{
+    accounts, chain_ids, instruction, transfer_message_hash, TransferDirection, TransferRecord,
+    TransferStatus, SEQUENCE_BITS,
+};
+use solana_bridge_sdk::ethereum::{is_ens_name, resolve_recipient, EnsResolver};
+use solana_bridge_sdk::pda;
+use solana_bridge_sdk::profile::{route_profile, route_profiles};
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+
+use crate::ethereum::Ethereum;
+use crate::relayer::RelayerApi;
+use crate::solana::Solana;
+use crate::Endpoints;
+
+/// How far back `trace` looks for an inbound deposit by default (blocks)
+const DEFAULT_LOOKBACK: u64 = 50_000;
+
+/// Solana RPC without a flag, environment variable or profile
+const DEFAULT_RPC_URL: &str = "http://127.0.0.1:8899";
+
+pub struct Context {
+    pub solana: Solana,
+    pub relayer: RelayerApi,
+    pub ethereum: Option<Ethereum>,
+    pub ethereum_chain_id: u64,
+    /// For ENS, which needs no bridge address
+    pub ethereum_rpc_url: Option<String>,
+}
+
+impl Context {
+    pub fn new(endpoints: &Endpoints) -> Result<Self> {
+        let profile = endpoints.profile.as_deref().map(route_profile).transpose()?;
+        let rpc_url = endpoints
+            .rpc_url
+            .clone()
+            .or_else(|| profile.as_ref().map(|p| p.solana.rpc_url.clone()))
+            .unwrap_or_else(|| DEFAULT_RPC_URL.to_string());
+        let ethereum_rpc_url = endpoints
+            .ethereum_rpc_url
+            .clone()
+            .or_else(|| profile.as_ref().map(|p| p.ethereum.rpc_url.clone()));
+        let ethereum_chain_id = endpoints
+            .ethereum_chain_id
+            .or_else(|| profile.as_ref().map(|p| p.ethereum.registry_chain_id))
+            .unwrap_or(chain_ids::ETHEREUM);
+
+        let ethereum = match (&ethereum_rpc_url, &endpoints.ethereum_bridge) {
+            (Some(url), Some(bridge)) => Some(Ethereum::new(url, bridge)?),
+            _ => None,
+        };
+
+        Ok(Self {
+            solana: Solana::new(&rpc_url, endpoints.program_id.unwrap_or(solana_bridge::ID)),
+            relayer: RelayerApi::new(&endpoints.relayer_api)?,
+            ethereum,
+            ethereum_chain_id,
+            ethereum_rpc_url,
+        })
+    }
+
+    /// The relayer's view, or None (with a note) if the API can't be reached
+    fn relayer_transfer(&self, transfer_id: &[u8; 32]) -> Option<Value> {
+        match self.relayer.transfer(transfer_id) {
+            Ok(transfer) => transfer,
+            Err(e) => {
+                println!("  ? Relayer API unreachable: {e}");
+                None
+            }
+        }
+    }
+}
+
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+fn age(seconds: i64) -> String {
+    match seconds {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86400 => format!("{}h{:02}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d{:02}h", s / 86400, s % 86400 / 3600),
+    }
+}
+
+fn is_pending(status: TransferStatus) -> bool {
+    matches!(
+        status,
+        TransferStatus::Initiated
+            | TransferStatus::Attested
+            | TransferStatus::Delayed
+            | TransferStatus::Claimable
+    )
+}
+
+fn direction_name(direction: TransferDirection) -> &'static str {
+    match direction {
+        TransferDirection::Outbound => "outbound",
+        TransferDirection::Inbound => "inbound",
+    }
+}
+
+fn parse_transfer_id(text: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(text.trim_start_matches("0x"))?;
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(id) => Ok(id),
+        Err(_) => bail!("A transfer ID is 32 bytes of hex"),
+    }
+}
+
+fn parse_tx_hash(text: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(text.trim_start_matches("0x"))?;
+    match <[u8; 32]>::try_from(bytes) {
+        Ok(hash) => Ok(hash),
+        Err(_) => bail!("A transaction hash is 32 bytes of hex"),
+    }
+}
+
+fn print_record(record: &TransferRecord) {
+    println!("On-chain record");
+    println!("  Transfer ID:  0x{}", hex::encode(record.transfer_id));
+    println!("  Direction:    {}", direction_name(record.direction));
+    println!("  Nonce:        {}", record.nonce);
+    println!("  Status:       {:?}", record.status);
+    println!("  Amount:       {}", record.amount);
+    println!("  Remote chain: {}", record.remote_chain_id);
+    println!("  Solana side:  {}", record.local_account);
+    if !record.remote_address.is_empty() {
+        println!("  Recipient:    {}", record.remote_address);
+    }
+    if !record.recipient_name.is_empty() {
+        println!("  Name:         {}", record.recipient_name);
+    }
+    println!("  Created:      {} ago", age(now() - record.created_at));
+    println!("  Updated:      {} ago", age(now() - record.updated_at));
+    if record.processed_slot > 0 {
+        println!(
+            "  Processed:    slot {} by {}",
+            record.processed_slot, record.processed_by
+        );
+    }
+}
+
+fn print_relayer(transfer: &Value) {
+    println!("Relayer");
+    println!("  Status:       {}", transfer["status"].as_str().unwrap_or("?"));
+    for (label, key) in [("Source tx", "sourceTx"), ("Dest tx", "destTx"), ("Error", "error")] {
+        if let Some(value) = transfer[key].as_str() {
+            println!("  {:<13} {value}", format!("{label}:"));
+        }
+    }
+    for entry in transfer["history"].as_array().into_iter().flatten() {
+        println!(
+            "    {}  {}",
+            entry["at"].as_str().unwrap_or("?"),
+            entry["status"].as_str().unwrap_or("?")
+        );
+    }
+}
+
+/**
+ * `status <transfer-id|nonce>`
+ *
+ * A nonce reads the record PDA directly; a transfer ID is looked up
+ * across all records.
+ */
+pub fn status(ctx: &Context, transfer: &str, direction: TransferDirection) -> Result<()> {
+    let record = match transfer.parse::<u64>() {
+        Ok(nonce) => ctx.solana.transfer(direction, nonce)?,
+        Err(_) => ctx.solana.transfer_by_id(&parse_transfer_id(transfer)?)?,
+    };
+
+    let transfer_id = match &record {
+        Some(record) => {
+            print_record(record);
+            record.transfer_id
+        }
+        None => {
+            println!("No on-chain record (never created, or pruned after completion)");
+            match parse_transfer_id(transfer) {
+                Ok(id) => id,
+                Err(_) => return Ok(()),
+            }
+        }
+    };
+
+    match ctx.relayer_transfer(&transfer_id) {
+        Some(relayed) => print_relayer(&relayed),
+        None => println!("Relayer has no record of 0x{}", hex::encode(transfer_id)),
+    }
+    Ok(())
+}
+
+/**
+ * `reemit <transfer-id|nonce> --keypair <payer>`
+ *
+ * Has the program emit the transfer's event again from its record, for
+ * when the relayer missed the original log. Anyone can pay for it.
+ */
+pub fn reemit(
+    ctx: &Context,
+    transfer: &str,
+    direction: TransferDirection,
+    keypair: &Path,
+) -> Result<()> {
+    let record = match transfer.parse::<u64>() {
+        Ok(nonce) => ctx.solana.transfer(direction, nonce)?,
+        Err(_) => ctx.solana.transfer_by_id(&parse_transfer_id(transfer)?)?,
+    }
+    .context("No on-chain record to re-emit (never created, or pruned after completion)")?;
+    let payer =
+        read_keypair_file(keypair).map_err(|e| anyhow::anyhow!("{}: {e}", keypair.display()))?;
+
+    let program_id = ctx.solana.program_id();
+    let transfer_record = match record.direction {
+        TransferDirection::Outbound => pda::outbound_transfer(&program_id, record.nonce),
+        TransferDirection::Inbound => pda::inbound_transfer(&program_id, record.nonce),
+    };
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::Reemit {
+            bridge_state: pda::bridge_state(&program_id),
+            transfer_record,
+        }
+        .to_account_metas(None),
+        data: instruction::Reemit {
+            transfer_id: record.transfer_id,
+        }
+        .data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &payer)?;
+    println!("Re-emitted 0x{}: {signature}", hex::encode(record.transfer_id));
+    Ok(())
+}
+
+/**
+ * `recipient <address|ens-name>`
+ *
+ * Prints the checksummed address a lock should use. Lowercase and
+ * uppercase addresses are accepted as typed, mixed case must pass
+ * EIP-55, and ENS names resolve through ETHEREUM_RPC_URL.
+ */
+pub fn recipient(ctx: &Context, input: &str) -> Result<()> {
+    let resolver = ctx.ethereum_rpc_url.as_deref().map(EnsResolver::new);
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+    let address = runtime.block_on(resolve_recipient(input, resolver.as_ref()))?;
+
+    if is_ens_name(input.trim()) {
+        println!("{} -> {address}", input.trim());
+    } else if input.trim().trim_start_matches("0x") != &address.to_string()[2..] {
+        println!("{address} (no checksum in the input; compare it with the source)");
+    } else {
+        println!("{address} (checksum valid)");
+    }
+    Ok(())
+}
+
+/**
+ * `eth-tx <tx-hash>`: what an Ethereum transaction became on Solana
+ *
+ * Reads the SourceTx the delivering relayer recorded, so it only knows
+ * about delivered transfers; one still in flight shows up in `trace`.
+ */
+pub fn eth_tx(ctx: &Context, tx_hash: &str) -> Result<()> {
+    let hash = parse_tx_hash(tx_hash)?;
+    let Some(source_tx) = ctx.solana.source_tx(&hash)? else {
+        println!("No inbound transfer recorded for 0x{}", hex::encode(hash));
+        println!("(not delivered yet, or delivered by a relayer that doesn't record it)");
+        return Ok(());
+    };
+
+    println!("0x{} on chain {}", hex::encode(hash), source_tx.chain_id);
+    for nonce in source_tx.nonces {
+        match ctx.solana.transfer(TransferDirection::Inbound, nonce)? {
+            Some(record) => print_record(&record),
+            None => println!("Inbound nonce {nonce}: record pruned after completion"),
+        }
+    }
+    Ok(())
+}
+
+/// `profiles`: the built-in route profiles and what each fills in
+pub fn profiles() -> Result<()> {
+    for (name, profile) in route_profiles()? {
+        let (solana, ethereum) = (&profile.solana, &profile.ethereum);
+        println!("{name}: {}", profile.description);
+        println!("  Solana:   {} ({})", solana.cluster, solana.rpc_url);
+        println!(
+            "  EVM:      {} ({}), chain {} registered as {}, {} confirmations",
+            ethereum.network,
+            ethereum.rpc_url,
+            ethereum.evm_chain_id,
+            ethereum.registry_chain_id,
+            ethereum.confirmations
+        );
+        if solana.airdrop_sol > 0.0 {
+            println!("  Faucet:   airdrops of {} SOL", solana.airdrop_sol);
+        }
+        if let Some(url) = &ethereum.faucet_url {
+            println!("  Faucet:   {url}");
+        }
+    }
+    Ok(())
+}
+
+/**
+ * `pending`: transfers initiated more than `older_than` seconds ago
+ * that haven't reached a final status
+ *
+ * Outbound records stay Initiated on Solana after delivery, so those
+ * also count as done once the relayer or Ethereum says so.
+ */
+pub fn pending(ctx: &Context, older_than: i64) -> Result<()> {
+    let now = now();
+    let mut candidates: Vec<TransferRecord> = ctx
+        .solana
+        .transfers(Vec::new())?
+        .into_iter()
+        .filter(|r| is_pending(r.status) && now - r.created_at >= older_than)
+        .collect();
+    candidates.sort_by_key(|r| r.created_at);
+
+    let mut stuck = 0;
+    for record in &candidates {
+        let relayer = match ctx.relayer.transfer(&record.transfer_id) {
+            Ok(Some(t)) => t["status"].as_str().unwrap_or("?").to_string(),
+            Ok(None) => "unseen".to_string(),
+            Err(_) => "unreachable".to_string(),
+        };
+        if record.direction == TransferDirection::Outbound {
+            let delivered = match &ctx.ethereum {
+                Some(ethereum) if record.remote_chain_id == ctx.ethereum_chain_id => {
+                    ethereum.is_processed(&record.transfer_id)?
+                }
+                _ => relayer == "completed",
+            };
+            if delivered {
+                continue;
+            }
+        }
+
+        if stuck == 0 {
+            println!(
+                "{:<9} {:>8}  {:<10} {:>7}  {:>20}  {:<12} TRANSFER ID",
+                "DIRECTION", "NONCE", "STATUS", "AGE", "AMOUNT", "RELAYER"
+            );
+        }
+        stuck += 1;
+        println!(
+            "{:<9} {:>8}  {:<10} {:>7}  {:>20}  {:<12} 0x{}",
+            direction_name(record.direction),
+            record.nonce,
+            format!("{:?}", record.status),
+            age(now - record.created_at),
+            record.amount,
+            relayer,
+            hex::encode(record.transfer_id)
+        );
+    }
+
+    if stuck == 0 {
+        println!("No transfers pending for more than {}", age(older_than));
+    } else {
+        println!("{stuck} stuck transfer(s)");
+    }
+    Ok(())
+}
+
+/// Why the relayer hasn't finished, from its transfer status
+fn relayer_verdict(transfer: &Value, source: &str, destination: &str) -> String {
+    let status = transfer["status"].as_str().unwrap_or("?");
+    match status {
+        "detected" => format!("relayer is waiting for {source} finality"),
+        "finalized" => format!("relayer is preparing the {destination} transaction"),
+        "submitted" => format!(
+            "{destination} transaction {} submitted but not confirmed",
+            transfer["destTx"].as_str().unwrap_or("?")
+        ),
+        "failed" => format!(
+            "relayer failed: {}",
+            transfer["error"].as_str().unwrap_or("unknown error")
+        ),
+        "reorged" => format!("the {source} transaction was dropped by a reorg"),
+        "completed" => format!("relayer reports completed, but {destination} shows no delivery"),
+        other => format!("relayer status {other}"),
+    }
+}
+
+/**
+ * `trace <nonce>` for Solana -> destination
+ */
+pub fn trace_outbound(ctx: &Context, nonce: u64) -> Result<()> {
+    println!("Outbound transfer {nonce}");
+
+    // 1. Source: Solana
+    let Some(record) = ctx.solana.transfer(TransferDirection::Outbound, nonce)? else {
+        println!("  ✗ Solana: no transfer record");
+        // Only the old global counter (shard 0) says how far nonces got
+        let latest = ctx.solana.bridge_state()?.nonce;
+        if nonce >> SEQUENCE_BITS == 0 && nonce > latest {
+            println!("Stalled at: not initiated (latest outbound nonce is {latest})");
+        } else {
+            println!("Stalled at: unknown (not initiated, record pruned or never created)");
+        }
+        return Ok(());
+    };
+    println!(
+        "  ✓ Solana: {:?} {} ago, {} to {} on chain {}",
+        record.status,
+        age(now() - record.created_at),
+        record.amount,
+        record.remote_address,
+        record.remote_chain_id
+    );
+    if matches!(
+        record.status,
+        TransferStatus::Refunded | TransferStatus::Vetoed | TransferStatus::Cancelled
+    ) {
+        println!("Ended on Solana: {:?}", record.status);
+        return Ok(());
+    }
+
+    // 2. Relayer
+    let relayed = ctx.relayer_transfer(&record.transfer_id);
+    match &relayed {
+        Some(t) => println!("  ✓ Relayer: {}", t["status"].as_str().unwrap_or("?")),
+        None => println!("  ✗ Relayer: never saw this transfer"),
+    }
+
+    // 3. Destination
+    let delivered = match &ctx.ethereum {
+        Some(ethereum) if record.remote_chain_id == ctx.ethereum_chain_id => {
+            let processed = ethereum.is_processed(&record.transfer_id)?;
+            if processed {
+                println!("  ✓ Ethereum: minted");
+            } else {
+                println!("  ✗ Ethereum: not minted");
+            }
+            Some(processed)
+        }
+        _ => {
+            println!(
+                "  ? Chain {}: not checked (only the configured Ethereum chain is)",
+                record.remote_chain_id
+            );
+            None
+        }
+    };
+
+    match (delivered, &relayed) {
+        (Some(true), _) => println!("Completed"),
+        (_, None) => println!(
+            "Stalled at: relayer pickup (is the relayer running and following this program? \
+             If it missed the log, `bridge-cli reemit {nonce}`)"
+        ),
+        (Some(false), Some(t)) => {
+            println!("Stalled at: {}", relayer_verdict(t, "Solana", "destination"))
+        }
+        (None, Some(t)) => println!("Relayer status: {}", t["status"].as_str().unwrap_or("?")),
+    }
+    Ok(())
+}
+
+/**
+ * `trace <nonce> --direction inbound` for Ethereum -> Solana
+ */
+pub fn trace_inbound(ctx: &Context, nonce: u64, from_block: Option<u64>) -> Result<()> {
+    println!("Inbound transfer {nonce}");
+
+    let record = ctx.solana.transfer(TransferDirection::Inbound, nonce)?;
+    let processed = ctx.solana.bridge_state()?.is_processed(nonce);
+
+    // 1. Source: Ethereum
+    let mut transfer_id = record.as_ref().map(|r| r.transfer_id);
+    let mut deposit_found = None;
+    match &ctx.ethereum {
+        Some(ethereum) => {
+            let latest = ethereum.block_number()?;
+            let from = from_block.unwrap_or(latest.saturating_sub(DEFAULT_LOOKBACK));
+            match ethereum.find_deposit(nonce, from, latest)? {
+                Some(deposit) => {
+                    println!(
+                        "  ✓ Ethereum: {} of {} by {} to {} in {} ({} confirmations)",
+                        deposit.kind,
+                        deposit.amount,
+                        deposit.sender,
+                        deposit.solana_address,
+                        deposit.tx_hash,
+                        latest.saturating_sub(deposit.block) + 1
+                    );
+                    match Pubkey::from_str(&deposit.solana_address) {
+                        Ok(recipient) => {
+                            transfer_id.get_or_insert(transfer_message_hash(
+                                &recipient,
+                                deposit.amount,
+                                nonce,
+                            ));
+                        }
+                        Err(_) => {
+                            println!("Stalled at: recipient is not a Solana address");
+                            return Ok(());
+                        }
+                    }
+                    deposit_found = Some(true);
+                }
+                None => {
+                    println!("  ✗ Ethereum: no Lock/Burn with nonce {nonce} since block {from}");
+                    deposit_found = Some(false);
+                }
+            }
+        }
+        None => {
+            println!("  ? Ethereum: not checked (set ETHEREUM_RPC_URL and ETHEREUM_BRIDGE_ADDRESS)")
+        }
+    }
+
+    // 2. Relayer
+    let relayed = transfer_id.and_then(|id| ctx.relayer_transfer(&id));
+    match &relayed {
+        Some(t) => println!("  ✓ Relayer: {}", t["status"].as_str().unwrap_or("?")),
+        None => println!("  ✗ Relayer: never saw this transfer"),
+    }
+
+    // 3. Destination: Solana
+    match &record {
+        Some(record) => println!(
+            "  {} Solana: {:?}, updated {} ago",
+            if record.status == TransferStatus::Completed { "✓" } else { "✗" },
+            record.status,
+            age(now() - record.updated_at)
+        ),
+        None if processed => println!("  ✓ Solana: completed (record pruned)"),
+        None => println!("  ✗ Solana: nothing delivered"),
+    }
+
+    let status = record.as_ref().map(|r| r.status);
+    if status == Some(TransferStatus::Claimable) {
+        println!("Waiting: delivered, for the recipient to claim");
+    } else if processed || status == Some(TransferStatus::Completed) {
+        println!("Completed");
+    } else if status == Some(TransferStatus::Attested) {
+        println!("Waiting: attested, in the challenge period");
+    } else if status == Some(TransferStatus::Delayed) {
+        println!("Stalled at: challenged, waiting for guardians to resolve");
+    } else if let Some(status) = status.filter(|s| !is_pending(*s)) {
+        println!("Ended on Solana: {status:?}");
+    } else if deposit_found == Some(false) {
+        println!("Stalled at: source (no matching deposit; try an earlier --from-block)");
+    } else if let Some(t) = &relayed {
+        println!("Stalled at: {}", relayer_verdict(t, "Ethereum", "Solana"));
+    } else {
+        println!("Stalled at: relayer pickup (is the relayer following the Ethereum bridge?)");
+    }
+    Ok(())
+}
@@ -0,0 +1,311 @@
+//! Who can upgrade the bridge program
+//!
+//!   bridge-cli upgrade-authority show
+//!   bridge-cli upgrade-authority transfer --keypair deployer.json
+//!   bridge-cli upgrade-authority sync --keypair payer.json
+//!   bridge-cli upgrade-authority finalize --keypair owner.json
+//!   bridge-cli upgrade-authority record-build --hash <hex> --version v1.2.0 --keypair owner.json
+//!
+//! `transfer` hands the upgrade authority from the deployer key to the
+//! bridge owner, so upgrades go through the same governance as every
+//! other admin action. `finalize` removes it for good; it needs the owner
+//! to sign, so a multisig or Squads owner proposes `finalize_program`
+//! instead of running it here.
+//!
+//! `record-build` stores the verified-build hash (solana-verify's) of the
+//! deployed program on-chain for integrators to check against; it refuses
+//! a hash that isn't what is deployed right now.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anchor_lang::prelude::ProgramData;
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anyhow::{bail, Result};
+use clap::Subcommand;
+use solana_bridge::{accounts, hex_encode, instruction, BuildRecord, UpgradeAuthorityRecord};
+use solana_bridge_sdk::build::{parse_program_hash, program_hash};
+use solana_bridge_sdk::pda;
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_program;
+
+use crate::support::Context;
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Show the upgrade authority, live and as recorded on-chain
+    Show,
+
+    /// Hand the upgrade authority to the bridge owner
+    Transfer {
+        /// Current upgrade authority; also pays the fee
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+
+    /// Refresh the on-chain record from the loader (permissionless)
+    Sync {
+        /// Pays the fee (and the record's rent the first time)
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+
+    /// Make the program immutable; cannot be undone
+    Finalize {
+        /// Bridge owner, which must hold the upgrade authority
+        #[arg(long)]
+        keypair: PathBuf,
+
+        /// Finalize without asking for confirmation
+        #[arg(long)]
+        yes: bool,
+    },
+
+    /// Record the verified-build hash of the deployed program
+    RecordBuild {
+        /// `solana-verify get-executable-hash` of the audited build (hex)
+        #[arg(long)]
+        hash: String,
+
+        /// Version label, e.g. the audited release tag
+        #[arg(long)]
+        version: String,
+
+        /// Bridge owner; also pays the record's rent the first time
+        #[arg(long)]
+        keypair: PathBuf,
+    },
+}
+
+pub fn run(ctx: &Context, command: Command) -> Result<()> {
+    match command {
+        Command::Show => show(ctx),
+        Command::Transfer { keypair } => transfer(ctx, &keypair),
+        Command::Sync { keypair } => sync(ctx, &keypair),
+        Command::Finalize { keypair, yes } => finalize(ctx, &keypair, yes),
+        Command::RecordBuild {
+            hash,
+            version,
+            keypair,
+        } => record_build(ctx, &hash, version, &keypair),
+    }
+}
+
+fn show(ctx: &Context) -> Result<()> {
+    let program_id = ctx.solana.program_id();
+    let owner = ctx.solana.bridge_state()?.owner;
+    let live = program_data(ctx)?;
+    let recorded: Option<UpgradeAuthorityRecord> =
+        ctx.solana.fetch(&pda::upgrade_authority(&program_id))?;
+
+    println!("Program:           {program_id}");
+    println!("Bridge owner:      {owner}");
+    match live.upgrade_authority_address {
+        Some(authority) if authority == owner => {
+            println!("Upgrade authority: {authority} (the bridge owner)")
+        }
+        Some(authority) => println!("Upgrade authority: {authority} (NOT the bridge owner)"),
+        None => println!("Upgrade authority: none, the program is final"),
+    }
+    println!("Last deployed:     slot {}", live.slot);
+
+    match recorded {
+        None => println!("On-chain record:   none; run `upgrade-authority sync`"),
+        Some(record) if record.authority != live.upgrade_authority_address => println!(
+            "On-chain record:   stale ({}); run `upgrade-authority sync`",
+            describe(record.authority)
+        ),
+        Some(record) => println!(
+            "On-chain record:   up to date (recorded at {})",
+            record.recorded_at
+        ),
+    }
+
+    let deployed_hash = deployed_program_hash(ctx)?;
+    let build: Option<BuildRecord> = ctx.solana.fetch(&pda::build_record(&program_id))?;
+    println!("Deployed build:    {}", hex_encode(&deployed_hash));
+    match build {
+        None => println!("Verified build:    none recorded"),
+        Some(build) if !build.is_current(live.slot) || build.hash != deployed_hash => println!(
+            "Verified build:    stale ({} for the deploy at slot {})",
+            build.version, build.deployed_slot
+        ),
+        Some(build) => println!("Verified build:    {} matches", build.version),
+    }
+    Ok(())
+}
+
+fn transfer(ctx: &Context, keypair_path: &Path) -> Result<()> {
+    let authority = read_keypair(keypair_path)?;
+    let program_id = ctx.solana.program_id();
+    let owner = ctx.solana.bridge_state()?.owner;
+    let live = program_data(ctx)?;
+    if live.upgrade_authority_address != Some(authority.pubkey()) {
+        bail!(
+            "{} is not the upgrade authority ({})",
+            authority.pubkey(),
+            describe(live.upgrade_authority_address)
+        );
+    }
+
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::TransferUpgradeAuthority {
+            authority: authority.pubkey(),
+            owner,
+            bridge_state: pda::bridge_state(&program_id),
+            program: program_id,
+            program_data: pda::program_data(&program_id),
+            upgrade_record: pda::upgrade_authority(&program_id),
+            admin_log: pda::admin_log(&program_id),
+            bpf_loader: bpf_loader_upgradeable::id(),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::TransferUpgradeAuthority {}.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &authority)?;
+    println!("Upgrade authority is now the bridge owner {owner}: {signature}");
+    Ok(())
+}
+
+fn sync(ctx: &Context, keypair_path: &Path) -> Result<()> {
+    let payer = read_keypair(keypair_path)?;
+    let program_id = ctx.solana.program_id();
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::SyncUpgradeAuthority {
+            payer: payer.pubkey(),
+            bridge_state: pda::bridge_state(&program_id),
+            program: program_id,
+            program_data: pda::program_data(&program_id),
+            upgrade_record: pda::upgrade_authority(&program_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::SyncUpgradeAuthority {}.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &payer)?;
+    println!("Upgrade authority record synced: {signature}");
+    Ok(())
+}
+
+fn finalize(ctx: &Context, keypair_path: &Path, yes: bool) -> Result<()> {
+    let owner = read_keypair(keypair_path)?;
+    let program_id = ctx.solana.program_id();
+    if ctx.solana.bridge_state()?.owner != owner.pubkey() {
+        bail!("{} is not the bridge owner", owner.pubkey());
+    }
+    let live = program_data(ctx)?;
+    if live.upgrade_authority_address != Some(owner.pubkey()) {
+        bail!(
+            "The bridge owner must hold the upgrade authority first ({}); \
+             run `upgrade-authority transfer`",
+            describe(live.upgrade_authority_address)
+        );
+    }
+    if !ctx.solana.exists(&pda::upgrade_authority(&program_id))? {
+        bail!("No upgrade authority record yet; run `upgrade-authority sync` first");
+    }
+
+    let prompt = format!("Make {program_id} immutable? No one can ever upgrade it again.");
+    if !yes && !confirm(&prompt)? {
+        bail!("Aborted");
+    }
+
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::FinalizeProgram {
+            owner: owner.pubkey(),
+            bridge_state: pda::bridge_state(&program_id),
+            program: program_id,
+            program_data: pda::program_data(&program_id),
+            upgrade_record: pda::upgrade_authority(&program_id),
+            admin_log: pda::admin_log(&program_id),
+            bpf_loader: bpf_loader_upgradeable::id(),
+        }
+        .to_account_metas(None),
+        data: instruction::FinalizeProgram {}.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &owner)?;
+    println!("Program finalized: {signature}");
+    Ok(())
+}
+
+fn record_build(ctx: &Context, hash: &str, version: String, keypair_path: &Path) -> Result<()> {
+    let owner = read_keypair(keypair_path)?;
+    let program_id = ctx.solana.program_id();
+    if ctx.solana.bridge_state()?.owner != owner.pubkey() {
+        bail!("{} is not the bridge owner", owner.pubkey());
+    }
+    let hash = parse_program_hash(hash)?;
+    let deployed_hash = deployed_program_hash(ctx)?;
+    if hash != deployed_hash {
+        bail!(
+            "{} is not the deployed program ({})",
+            hex_encode(&hash),
+            hex_encode(&deployed_hash)
+        );
+    }
+
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::RecordBuild {
+            owner: owner.pubkey(),
+            bridge_state: pda::bridge_state(&program_id),
+            program: program_id,
+            program_data: pda::program_data(&program_id),
+            build_record: pda::build_record(&program_id),
+            admin_log: pda::admin_log(&program_id),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RecordBuild { hash, version }.data(),
+    };
+
+    let signature = ctx.solana.send_instructions(&[instruction], &owner)?;
+    println!("Build recorded: {signature}");
+    Ok(())
+}
+
+/// solana-verify's hash of the deployed executable
+fn deployed_program_hash(ctx: &Context) -> Result<[u8; 32]> {
+    let program_id = ctx.solana.program_id();
+    let data = ctx
+        .solana
+        .data(&pda::program_data(&program_id))?
+        .ok_or_else(|| anyhow::anyhow!("{program_id} is not an upgradeable program"))?;
+    Ok(program_hash(&data)?.1)
+}
+
+fn program_data(ctx: &Context) -> Result<ProgramData> {
+    let program_id = ctx.solana.program_id();
+    ctx.solana
+        .fetch(&pda::program_data(&program_id))?
+        .ok_or_else(|| anyhow::anyhow!("{program_id} is not an upgradeable program"))
+}
+
+fn describe(authority: Option<Pubkey>) -> String {
+    match authority {
+        Some(authority) => format!("held by {authority}"),
+        None => "finalized".to_string(),
+    }
+}
+
+fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+}
+
+fn confirm(prompt: &str) -> Result<bool> {
+    print!("{prompt} [y/N] ");
+    std::io::stdout().flush()?;
+    let mut answer = String::new();
+    std::io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes"))
+}
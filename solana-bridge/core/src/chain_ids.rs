@@ -0,0 +1,25 @@
+//! Chain identifiers
+//!
+//! EVM chains use their EIP-155 chain ID. Non-EVM chains use ids
+//! above u32::MAX so they can never collide with an EVM chain.
+
+pub const SOLANA: u64 = 1 << 32;
+pub const ETHEREUM: u64 = 1;
+pub const OPTIMISM: u64 = 10;
+pub const BASE: u64 = 8453;
+pub const ARBITRUM: u64 = 42161;
+pub const STELLAR: u64 = (1 << 32) + 1;
+pub const COSMOS_HUB: u64 = (1 << 32) + 2;
+pub const BITCOIN: u64 = (1 << 32) + 3;
+
+/// Every id above by name, for clients that can't link this crate
+pub const ALL: [(&str, u64); 8] = [
+    ("SOLANA", SOLANA),
+    ("ETHEREUM", ETHEREUM),
+    ("OPTIMISM", OPTIMISM),
+    ("BASE", BASE),
+    ("ARBITRUM", ARBITRUM),
+    ("STELLAR", STELLAR),
+    ("COSMOS_HUB", COSMOS_HUB),
+    ("BITCOIN", BITCOIN),
+];
@@ -0,0 +1,758 @@
+//! EVM encoding: keccak256, abi.encodePacked and RLP
+//!
+//! Everything a transfer hash is built from has to match the EVM bridge
+//! byte for byte, or signatures and replay checks silently disagree
+//! across chains. The tests pin each function to output from Solidity or
+//! from mainnet, and the relayer mirrors this file in relayer/src/codec.js.
+//!
+//! The RLP side only decodes: receipts and block headers come from the
+//! EVM chain, and the bridge never needs to produce them.
+
+use alloc::vec::Vec;
+
+/// Malformed or non-canonical RLP
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidRlp;
+
+pub type Result<T> = core::result::Result<T, InvalidRlp>;
+
+fn ensure(condition: bool) -> Result<()> {
+    if condition {
+        Ok(())
+    } else {
+        Err(InvalidRlp)
+    }
+}
+
+#[cfg(target_os = "solana")]
+extern "C" {
+    fn sol_keccak256(vals: *const u8, val_len: u64, hash_result: *mut u8) -> u64;
+}
+
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    keccak256v(&[data])
+}
+
+/// keccak256 of `parts` concatenated, without copying them together
+pub fn keccak256v(parts: &[&[u8]]) -> [u8; 32] {
+    let mut hash = [0u8; 32];
+    #[cfg(target_os = "solana")]
+    // SAFETY: the syscall reads `parts` as (pointer, length) pairs, which
+    // is the layout of &[u8], and writes exactly 32 bytes
+    unsafe {
+        sol_keccak256(parts.as_ptr() as *const u8, parts.len() as u64, hash.as_mut_ptr());
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        use sha3::{Digest, Keccak256};
+        let mut hasher = Keccak256::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        hash.copy_from_slice(&hasher.finalize());
+    }
+    hash
+}
+
+/// Solidity event topic, e.g. `event_topic("Transfer(address,address,uint256)")`
+pub fn event_topic(signature: &str) -> [u8; 32] {
+    keccak256(signature.as_bytes())
+}
+
+/// Solidity function selector: the first four bytes of the signature hash
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// A u64 as a uint256 word: 32 bytes, big-endian
+pub fn uint256(value: u64) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/**
+ * abi.encodePacked
+ *
+ * Values are appended in order at their natural width with no padding,
+ * except that uint256 is always a full 32-byte word. The transfer hashes
+ * below run on every lock and mint, so they hash their fields in place
+ * instead; this is for everything else.
+ */
+#[derive(Default)]
+pub struct Packed(Vec<u8>);
+
+impl Packed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn uint256(mut self, value: u64) -> Self {
+        self.0.extend_from_slice(&uint256(value));
+        self
+    }
+
+    pub fn uint64(mut self, value: u64) -> Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn uint32(mut self, value: u32) -> Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub fn bytes32(mut self, value: &[u8; 32]) -> Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub fn address(mut self, value: &[u8; 20]) -> Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    /// `string` and `bytes` are both their raw bytes
+    pub fn bytes(mut self, value: &[u8]) -> Self {
+        self.0.extend_from_slice(value);
+        self
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+
+    pub fn hash(&self) -> [u8; 32] {
+        keccak256(&self.0)
+    }
+}
+
+/**
+ * Message hash of an inbound transfer
+ *
+ *   keccak256(abi.encodePacked(bytes32 recipient, uint256 amount, uint256 nonce))
+ */
+pub fn transfer_message_hash(recipient: &[u8; 32], amount: u64, nonce: u64) -> [u8; 32] {
+    keccak256v(&[recipient.as_slice(), &uint256(amount), &uint256(nonce)])
+}
+
+/**
+ * What inbound evidence (a VAA payload, a proof's public inputs) commits to
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, uint32 guardianSetIndex,
+ *     bytes32 token, bytes32 recipient, uint256 amount, uint256 nonce))
+ *
+ * `token` is the Solana mint the transfer releases, so evidence for one
+ * token or source chain can't release another with the same amount and
+ * nonce. The guardian set index pins evidence to the set it was produced
+ * under, so it stops verifying once the set rotates. transfer_message_hash
+ * stays the transfer's ID, and replays stay keyed by the source nonce.
+ */
+pub fn inbound_message_hash(
+    source_chain_id: u64,
+    guardian_set_index: u32,
+    token: &[u8; 32],
+    recipient: &[u8; 32],
+    amount: u64,
+    nonce: u64,
+) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        &guardian_set_index.to_be_bytes(),
+        token,
+        recipient,
+        &uint256(amount),
+        &uint256(nonce),
+    ])
+}
+
+/**
+ * What the relayer signs to mint an outbound transfer on the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(address to, uint256 amount, bytes32 transferId))
+ */
+pub fn mint_request_hash(to: &[u8; 20], amount: u64, transfer_id: &[u8; 32]) -> [u8; 32] {
+    keccak256v(&[to.as_slice(), &uint256(amount), transfer_id])
+}
+
+/**
+ * What the relayer signs to carry a pause or unpause to the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bool paused, uint64 sequence))
+ *
+ * `sequence` is the status message's outbox sequence, so the EVM side
+ * can drop one that arrives after a newer one.
+ */
+pub fn bridge_status_hash(source_chain_id: u64, paused: bool, sequence: u64) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        &[paused as u8],
+        &sequence.to_be_bytes(),
+    ])
+}
+
+/**
+ * What the relayer signs to carry a Solana escrow balance to the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 mint,
+ *     uint256 amount, uint64 slot, uint64 sequence))
+ *
+ * `amount` is what the bridge held of `mint` at `slot`; `sequence` is
+ * the message's outbox sequence, as for bridge_status_hash.
+ */
+pub fn vault_balance_hash(
+    source_chain_id: u64,
+    mint: &[u8; 32],
+    amount: u64,
+    slot: u64,
+    sequence: u64,
+) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        mint,
+        &uint256(amount),
+        &slot.to_be_bytes(),
+        &sequence.to_be_bytes(),
+    ])
+}
+
+/**
+ * What the relayer signs to deliver a message to a contract on another chain
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 sender,
+ *     uint64 destChainId, bytes32 target, uint64 sequence, bytes payload))
+ *
+ * `target` is the contract, left-padded to 32 bytes; `sequence` is the
+ * message's outbox sequence, which the receiving side replays against.
+ */
+pub fn message_hash(
+    source_chain_id: u64,
+    sender: &[u8; 32],
+    dest_chain_id: u64,
+    target: &[u8; 32],
+    sequence: u64,
+    payload: &[u8],
+) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        sender,
+        &dest_chain_id.to_be_bytes(),
+        target,
+        &sequence.to_be_bytes(),
+        payload,
+    ])
+}
+
+/**
+ * What an attestor signs to vouch for a finalized Solana lock
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 transferId,
+ *     address to, uint256 amount, uint64 slot, bytes32 blockhash,
+ *     bytes txSignature))
+ *
+ * The slot, its blockhash and the signature of the locking transaction
+ * pin the lock to one finalized block, so an attestation can be checked
+ * against any Solana RPC, not only the relayer's.
+ */
+pub fn lock_proof_hash(
+    source_chain_id: u64,
+    transfer_id: &[u8; 32],
+    to: &[u8; 20],
+    amount: u64,
+    slot: u64,
+    blockhash: &[u8; 32],
+    tx_signature: &[u8],
+) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        transfer_id,
+        to,
+        &uint256(amount),
+        &slot.to_be_bytes(),
+        blockhash,
+        tx_signature,
+    ])
+}
+
+/// The EIP-191 hash that `signMessage`/`ecrecover` actually sign over a 32-byte hash
+pub fn eth_signed_message_hash(hash: &[u8; 32]) -> [u8; 32] {
+    keccak256v(&[b"\x19Ethereum Signed Message:\n32".as_slice(), hash])
+}
+
+/**
+ * ID of an outbound transfer
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, uint64 destChainId,
+ *     uint32 guardianSetIndex, uint64 nonce, bytes32 token, uint64 amount,
+ *     bytes32 sender, string recipient))
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_id(
+    source_chain_id: u64,
+    dest_chain_id: u64,
+    guardian_set_index: u32,
+    nonce: u64,
+    token: &[u8; 32],
+    amount: u64,
+    sender: &[u8; 32],
+    recipient: &str,
+) -> [u8; 32] {
+    keccak256v(&[
+        source_chain_id.to_be_bytes().as_slice(),
+        &dest_chain_id.to_be_bytes(),
+        &guardian_set_index.to_be_bytes(),
+        &nonce.to_be_bytes(),
+        token,
+        &amount.to_be_bytes(),
+        sender,
+        recipient.as_bytes(),
+    ])
+}
+
+/**
+ * One RLP item, borrowing its payload from the input
+ *
+ * Decoding is strict: non-canonical encodings (a single byte below 0x80
+ * behind a prefix, long-form lengths under 56, leading zeros in lengths
+ * or integers) are rejected, so every value has exactly one encoding
+ * and a hash over the raw bytes can't be sidestepped.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rlp<'a> {
+    Bytes(&'a [u8]),
+    List(&'a [u8]),
+}
+
+impl<'a> Rlp<'a> {
+    /// Decode an item that must span all of `data`
+    pub fn decode(data: &'a [u8]) -> Result<Self> {
+        let (item, rest) = Self::split(data)?;
+        ensure(rest.is_empty())?;
+        Ok(item)
+    }
+
+    /// Decode the first item of `data`, returning it and what follows
+    pub fn split(data: &'a [u8]) -> Result<(Self, &'a [u8])> {
+        let (&prefix, rest) = data.split_first().ok_or(InvalidRlp)?;
+        match prefix {
+            0x00..=0x7f => Ok((Rlp::Bytes(&data[..1]), rest)),
+            0x80..=0xb7 => {
+                let (payload, rest) = take(rest, (prefix - 0x80) as usize)?;
+                ensure(payload.len() != 1 || payload[0] >= 0x80)?;
+                Ok((Rlp::Bytes(payload), rest))
+            }
+            0xb8..=0xbf => {
+                let (len, rest) = long_length(rest, (prefix - 0xb7) as usize)?;
+                let (payload, rest) = take(rest, len)?;
+                Ok((Rlp::Bytes(payload), rest))
+            }
+            0xc0..=0xf7 => {
+                let (payload, rest) = take(rest, (prefix - 0xc0) as usize)?;
+                Ok((Rlp::List(payload), rest))
+            }
+            0xf8..=0xff => {
+                let (len, rest) = long_length(rest, (prefix - 0xf7) as usize)?;
+                let (payload, rest) = take(rest, len)?;
+                Ok((Rlp::List(payload), rest))
+            }
+        }
+    }
+
+    pub fn bytes(self) -> Result<&'a [u8]> {
+        match self {
+            Rlp::Bytes(payload) => Ok(payload),
+            Rlp::List(_) => Err(InvalidRlp),
+        }
+    }
+
+    pub fn items(self) -> Result<Vec<Rlp<'a>>> {
+        let Rlp::List(mut payload) = self else {
+            return Err(InvalidRlp);
+        };
+        let mut items = Vec::new();
+        while !payload.is_empty() {
+            let (item, rest) = Self::split(payload)?;
+            items.push(item);
+            payload = rest;
+        }
+        Ok(items)
+    }
+
+    /// A byte string of exactly `N` bytes (hashes, addresses)
+    pub fn array<const N: usize>(self) -> Result<[u8; N]> {
+        self.bytes()?.try_into().map_err(|_| InvalidRlp)
+    }
+
+    /// A big-endian integer with no leading zeros; zero is the empty string
+    pub fn u64(self) -> Result<u64> {
+        let bytes = self.bytes()?;
+        ensure(bytes.len() <= 8)?;
+        ensure(bytes.first() != Some(&0))?;
+        Ok(bytes.iter().fold(0, |value, b| (value << 8) | *b as u64))
+    }
+}
+
+fn take(data: &[u8], len: usize) -> Result<(&[u8], &[u8])> {
+    ensure(data.len() >= len)?;
+    Ok(data.split_at(len))
+}
+
+/// Length of a long-form string or list, which must not fit the short form
+fn long_length(data: &[u8], len_of_len: usize) -> Result<(usize, &[u8])> {
+    let (bytes, rest) = take(data, len_of_len)?;
+    ensure(bytes[0] != 0)?;
+    let len = bytes.iter().fold(0u64, |value, b| (value << 8) | *b as u64);
+    ensure(len > 55)?;
+    let len = usize::try_from(len).map_err(|_| InvalidRlp)?;
+    Ok((len, rest))
+}
+
+/// One log entry of a receipt
+pub struct Log<'a> {
+    pub address: [u8; 20],
+    pub topics: Vec<[u8; 32]>,
+    pub data: &'a [u8],
+}
+
+impl<'a> Log<'a> {
+    fn from_rlp(item: Rlp<'a>) -> Result<Self> {
+        let [address, topics, data] = fields::<3>(item)?;
+        Ok(Self {
+            address: address.array()?,
+            topics: topics
+                .items()?
+                .into_iter()
+                .map(Rlp::array)
+                .collect::<Result<_>>()?,
+            data: data.bytes()?,
+        })
+    }
+}
+
+/**
+ * A transaction receipt as stored in the receipts trie
+ *
+ * Typed receipts (EIP-2718) are the type byte followed by the RLP list;
+ * legacy receipts are the bare list and have type 0. Pre-Byzantium
+ * receipts carry a state root instead of a status and are rejected.
+ */
+pub struct Receipt<'a> {
+    pub tx_type: u8,
+    pub success: bool,
+    pub cumulative_gas_used: u64,
+    pub logs: Vec<Log<'a>>,
+}
+
+impl<'a> Receipt<'a> {
+    pub fn decode(raw: &'a [u8]) -> Result<Self> {
+        let (tx_type, body) = match raw.first() {
+            Some(&tx_type) if tx_type <= 0x7f => (tx_type, &raw[1..]),
+            _ => (0, raw),
+        };
+        let [status, cumulative_gas_used, _bloom, logs] = fields::<4>(Rlp::decode(body)?)?;
+        let status = status.u64()?;
+        ensure(status <= 1)?;
+        Ok(Self {
+            tx_type,
+            success: status == 1,
+            cumulative_gas_used: cumulative_gas_used.u64()?,
+            logs: logs
+                .items()?
+                .into_iter()
+                .map(Log::from_rlp)
+                .collect::<Result<_>>()?,
+        })
+    }
+}
+
+/**
+ * The block header fields a proof checks against
+ *
+ * Later forks append fields (base fee, withdrawals root, blob gas); they
+ * are covered by `hash` but not decoded.
+ */
+pub struct Header {
+    pub hash: [u8; 32],
+    pub parent_hash: [u8; 32],
+    pub state_root: [u8; 32],
+    pub transactions_root: [u8; 32],
+    pub receipts_root: [u8; 32],
+    pub number: u64,
+    pub timestamp: u64,
+}
+
+impl Header {
+    pub fn decode(raw: &[u8]) -> Result<Self> {
+        let fields = Rlp::decode(raw)?.items()?;
+        // Every header since Frontier has at least 15 fields
+        ensure(fields.len() >= 15)?;
+        Ok(Self {
+            hash: keccak256(raw),
+            parent_hash: fields[0].array()?,
+            state_root: fields[3].array()?,
+            transactions_root: fields[4].array()?,
+            receipts_root: fields[5].array()?,
+            number: fields[8].u64()?,
+            timestamp: fields[11].u64()?,
+        })
+    }
+}
+
+/// A list that must have exactly `N` items
+fn fields<const N: usize>(item: Rlp) -> Result<[Rlp; N]> {
+    item.items()?.try_into().map_err(|_| InvalidRlp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(text: &str) -> Vec<u8> {
+        (0..text.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&text[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn hex32(text: &str) -> [u8; 32] {
+        hex(text).try_into().unwrap()
+    }
+
+    /// `prefix || 256 zero bytes (logs bloom) || suffix`
+    fn with_empty_bloom(prefix: &str, suffix: &str) -> Vec<u8> {
+        let mut raw = hex(prefix);
+        raw.extend_from_slice(&[0u8; 256]);
+        raw.extend(hex(suffix));
+        raw
+    }
+
+    const RELAYER: &str = "70997970c51812dc3a010c7d01b50e0d17dc79c8";
+
+    #[test]
+    fn keccak_matches_solidity() {
+        assert_eq!(
+            keccak256(b""),
+            hex32("c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470")
+        );
+        assert_eq!(
+            event_topic("Transfer(address,address,uint256)"),
+            hex32("ddf252ad1be2c89b69c2b068fc378daa952ba7f163c4a11628f55a4df523b3ef")
+        );
+        assert_eq!(selector("transfer(address,uint256)"), [0xa9, 0x05, 0x9c, 0xbb]);
+    }
+
+    #[test]
+    fn transfer_hashes_match_solidity() {
+        let mut recipient = [0u8; 32];
+        for (i, b) in recipient.iter_mut().enumerate() {
+            *b = i as u8 + 1;
+        }
+        assert_eq!(
+            transfer_message_hash(&recipient, 1_000_000, 7),
+            hex32("e9cfdf54bbb0d8b7e6b288e00c83c6bd80c03898c9ca4c8768fb1aaee1d1db13")
+        );
+        assert_eq!(
+            inbound_message_hash(1, 3, &[2; 32], &recipient, 1_000_000, 7),
+            hex32("1804a5950a62ef63a60a6e002949bdcd048a7fd3dd1224e29edc8e98dd8a555a")
+        );
+
+        let to: [u8; 20] = hex(RELAYER).try_into().unwrap();
+        let mint = mint_request_hash(&to, 1_000_000, &[0xab; 32]);
+        assert_eq!(
+            mint,
+            hex32("f5e6b827b9f5ee2fa493c7dce50cc04a25b588cf251bac367dbb40038b3900dc")
+        );
+        assert_eq!(
+            eth_signed_message_hash(&mint),
+            hex32("0b9ba9b2f41eb39ea998ddcda14d9a69ebf66898b187ce9a45b14f44261bd2e2")
+        );
+
+        assert_eq!(
+            transfer_id(
+                900,
+                1,
+                0,
+                7,
+                &[2; 32],
+                1_000_000,
+                &[3; 32],
+                "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+            ),
+            hex32("2d516a088e7df6aa74b4cf86875b5a9036537eb36fabb5a256502cb485ab2311")
+        );
+
+        assert_eq!(
+            bridge_status_hash(1 << 32, true, 7),
+            hex32("13a876176c1de10acadc628099bab7e608926690e4eae23174ac6460d832fac8")
+        );
+        assert_eq!(
+            bridge_status_hash(1 << 32, false, 8),
+            hex32("bc3df8b9bf0c026752079e32445cd125826e18a4b300b2eb34d0d80b191c6c8c")
+        );
+        assert_eq!(
+            vault_balance_hash(1 << 32, &[0xab; 32], 1_000_000, 4242, 9),
+            hex32("ff7b190784419338ad731b635189168e978890bd3e510fba51f94ceb5b145f3d")
+        );
+        assert_eq!(
+            message_hash(1 << 32, &[0xab; 32], 1, &[0x12; 32], 9, b"hello"),
+            hex32("b1794ca065215df3ccbce21ee02821db711271937d47cf950a1c102ade578787")
+        );
+
+        let proof = lock_proof_hash(
+            1 << 32,
+            &[0xab; 32],
+            &to,
+            1_000_000,
+            4242,
+            &[0x11; 32],
+            &[0x22; 64],
+        );
+        assert_eq!(
+            proof,
+            hex32("548b8565bd939f75a032373e9da80af8137ea5a8f6f253d2f3734148c5e3baf4")
+        );
+        assert_eq!(
+            eth_signed_message_hash(&proof),
+            hex32("f9600cdb378fd27b67f4982b9958977e9979e8dd837d2305aa2b86ba64e35c7f")
+        );
+    }
+
+    #[test]
+    fn packed_matches_the_in_place_hashes() {
+        let to = [7u8; 20];
+        let id = [9u8; 32];
+        assert_eq!(
+            Packed::new().address(&to).uint256(5).bytes32(&id).hash(),
+            mint_request_hash(&to, 5, &id)
+        );
+        assert_eq!(
+            Packed::new()
+                .uint64(1)
+                .uint64(2)
+                .uint32(5)
+                .uint64(3)
+                .bytes32(&id)
+                .uint64(4)
+                .bytes32(&id)
+                .bytes(b"0xabc")
+                .hash(),
+            transfer_id(1, 2, 5, 3, &id, 4, &id, "0xabc")
+        );
+    }
+
+    #[test]
+    fn rlp_basics() {
+        assert_eq!(Rlp::decode(&hex("83646f67")).unwrap(), Rlp::Bytes(b"dog"));
+        assert_eq!(Rlp::decode(&hex("0f")).unwrap().u64().unwrap(), 15);
+        assert_eq!(Rlp::decode(&hex("80")).unwrap().u64().unwrap(), 0);
+        assert_eq!(Rlp::decode(&hex("820400")).unwrap().u64().unwrap(), 1024);
+
+        let raw = hex("c88363617483646f67");
+        let list = Rlp::decode(&raw).unwrap().items().unwrap();
+        assert_eq!(list, vec![Rlp::Bytes(b"cat"), Rlp::Bytes(b"dog")]);
+        assert_eq!(Rlp::decode(&hex("c0")).unwrap().items().unwrap(), vec![]);
+
+        let lorem = b"Lorem ipsum dolor sit amet, consectetur adipisicing elit";
+        let mut encoded = vec![0xb8, lorem.len() as u8];
+        encoded.extend_from_slice(lorem);
+        assert_eq!(Rlp::decode(&encoded).unwrap().bytes().unwrap(), lorem);
+    }
+
+    #[test]
+    fn rlp_rejects_non_canonical_input() {
+        fn parse(raw: &[u8]) -> Result<()> {
+            let item = Rlp::decode(raw)?;
+            if let Rlp::List(_) = item {
+                item.items()?;
+            }
+            Ok(())
+        }
+
+        for bad in [
+            "",           // empty
+            "8100",       // single byte below 0x80 behind a prefix
+            "b801ff",     // long form for a short string
+            "b90000",     // leading zero in the length
+            "83646f",     // truncated
+            "83646f6767", // trailing bytes
+            "c3836361",   // list item runs past the list
+        ] {
+            assert!(parse(&hex(bad)).is_err(), "{bad}");
+        }
+        assert!(Rlp::decode(&hex("820004")).unwrap().u64().is_err());
+        assert!(Rlp::decode(&hex("89010000000000000000")).unwrap().u64().is_err());
+    }
+
+    #[test]
+    fn decodes_a_typed_receipt_with_a_lock_log() {
+        let raw = with_empty_bloom(
+            "02f901a601825208b90100",
+            concat!(
+                "f89df89b945fbdb2315678afecb367f032d93f642f64180aa3f842",
+                "a0e9651924f077c2297fe5511b78d8c3ab5c25068ddc2f1a7aed0a6e22c052d01a",
+                "a000000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8",
+                "b840",
+                "00000000000000000000000000000000000000000000000000000000000f4240",
+                "0000000000000000000000000000000000000000000000000000000000000007",
+            ),
+        );
+        let receipt = Receipt::decode(&raw).unwrap();
+        assert_eq!(receipt.tx_type, 2);
+        assert!(receipt.success);
+        assert_eq!(receipt.cumulative_gas_used, 21_000);
+        assert_eq!(receipt.logs.len(), 1);
+
+        let log = &receipt.logs[0];
+        assert_eq!(log.address.to_vec(), hex("5fbdb2315678afecb367f032d93f642f64180aa3"));
+        assert_eq!(log.topics[0], event_topic("Lock(address,uint256,uint256,string)"));
+        assert_eq!(log.topics[1][12..].to_vec(), hex(RELAYER));
+        assert_eq!(log.data[24..32], 1_000_000u64.to_be_bytes());
+        assert_eq!(log.data[56..64], 7u64.to_be_bytes());
+    }
+
+    #[test]
+    fn decodes_a_failed_legacy_receipt() {
+        let raw = with_empty_bloom("f901088082c350b90100", "c0");
+        let receipt = Receipt::decode(&raw).unwrap();
+        assert_eq!(receipt.tx_type, 0);
+        assert!(!receipt.success);
+        assert_eq!(receipt.cumulative_gas_used, 50_000);
+        assert!(receipt.logs.is_empty());
+    }
+
+    #[test]
+    fn decodes_the_mainnet_genesis_header() {
+        let raw = with_empty_bloom(
+            concat!(
+                "f90214",
+                "a00000000000000000000000000000000000000000000000000000000000000000",
+                "a01dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347",
+                "940000000000000000000000000000000000000000",
+                "a0d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544",
+                "a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                "a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421",
+                "b90100",
+            ),
+            concat!(
+                "850400000000808213888080",
+                "a011bbe8db4e347b4e8c937c1c8370e4b5ed33adb3db69cbdb7a38e1e50b1b82fa",
+                "a00000000000000000000000000000000000000000000000000000000000000000",
+                "880000000000000042",
+            ),
+        );
+        let header = Header::decode(&raw).unwrap();
+        assert_eq!(
+            header.hash,
+            hex32("d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3")
+        );
+        assert_eq!(header.parent_hash, [0; 32]);
+        assert_eq!(
+            header.state_root,
+            hex32("d7f8974fb5ac78d9ac099b9ad5018bedc2ce0a72dad1827a1709da30580f0544")
+        );
+        assert_eq!(header.receipts_root, header.transactions_root);
+        assert_eq!(header.number, 0);
+        assert_eq!(header.timestamp, 0);
+    }
+}
@@ -0,0 +1,56 @@
+//! Bridge core: what every side of the bridge has to agree on
+//!
+//! Chain IDs, outbox entry kinds, and the EVM encoding that transfer IDs
+//! and signed messages are built from. The program re-exports all of it
+//! (`solana_bridge::codec`, `solana_bridge::chain_ids`), so the SDK,
+//! indexer and CLI get the same definitions whether they depend on this
+//! crate or on the program.
+//!
+//! Anchor accounts and events stay in the program: the IDL is generated
+//! from it, and TypeScript clients decode against the IDL.
+//!
+//! No std, so the program links it as-is; keccak256 is the syscall on
+//! chain and sha3 everywhere else.
+//!
+//! The relayer is JavaScript and reads constants.json, which `main.rs`
+//! prints from this crate; tests/constants.rs fails if it is stale:
+//!
+//!   cargo run -p bridge-core > core/constants.json
+//!
+//! relayer/src/codec.js still mirrors `codec` by hand and is pinned by
+//! the shared test vectors (test-vectors/vectors.json).
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+pub mod chain_ids;
+pub mod codec;
+pub mod transfer;
+
+use alloc::format;
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use transfer::OutboxKind;
+
+/**
+ * constants.json
+ *
+ * Chain IDs are decimal strings, so the ones above 2^53 survive
+ * JSON.parse; the relayer turns them into BigInts.
+ */
+pub fn constants_json() -> String {
+    let chain_ids: Vec<String> = chain_ids::ALL
+        .iter()
+        .map(|(name, id)| format!("    \"{name}\": \"{id}\""))
+        .collect();
+    let outbox_kinds: Vec<String> = OutboxKind::ALL
+        .iter()
+        .map(|kind| format!("    \"{}\": {}", kind.name(), *kind as u8))
+        .collect();
+    format!(
+        "{{\n  \"chainIds\": {{\n{}\n  }},\n  \"outboxKind\": {{\n{}\n  }}\n}}\n",
+        chain_ids.join(",\n"),
+        outbox_kinds.join(",\n")
+    )
+}
@@ -0,0 +1,5 @@
+//! Print constants.json; redirect into core/constants.json to regenerate it
+
+fn main() {
+    print!("{}", bridge_core::constants_json());
+}
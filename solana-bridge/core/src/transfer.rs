@@ -0,0 +1,37 @@
+//! What an outbox entry carries
+//!
+//! Stored as a u8 in the program's OutboxEntry and read back by the
+//! relayer, so variants are only ever appended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum OutboxKind {
+    Lock,
+    Burn,
+    /// The bridge paused or resumed (see Outbox::push_status)
+    BridgeStatus,
+    /// A guardian-attested escrow balance (see Outbox::push_vault_balance)
+    VaultBalance,
+    /// A message to an allowed contract (see Outbox::push_message)
+    Message,
+}
+
+impl OutboxKind {
+    pub const ALL: [OutboxKind; 5] = [
+        OutboxKind::Lock,
+        OutboxKind::Burn,
+        OutboxKind::BridgeStatus,
+        OutboxKind::VaultBalance,
+        OutboxKind::Message,
+    ];
+
+    /// Name in constants.json
+    pub fn name(self) -> &'static str {
+        match self {
+            OutboxKind::Lock => "LOCK",
+            OutboxKind::Burn => "BURN",
+            OutboxKind::BridgeStatus => "BRIDGE_STATUS",
+            OutboxKind::VaultBalance => "VAULT_BALANCE",
+            OutboxKind::Message => "MESSAGE",
+        }
+    }
+}
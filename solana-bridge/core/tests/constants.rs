@@ -0,0 +1,11 @@
+//! The relayer's copy of the constants must be what this crate defines
+
+#[test]
+fn constants_json_is_current() {
+    assert_eq!(
+        include_str!("../constants.json"),
+        bridge_core::constants_json(),
+        "chain IDs or outbox kinds changed; regenerate constants.json \
+         (`cargo run -p bridge-core > core/constants.json`) and commit it"
+    );
+}
@@ -0,0 +1,245 @@
+//! Geyser plugin for the bridge program
+//!
+//! Runs inside a validator (or RPC node) and streams over gRPC (see
+//! proto/geyser.proto):
+//!
+//! - events:   decoded from the logs of successful bridge transactions
+//! - accounts: every write to an account the program owns
+//! - slots:    status changes, to tell when the above are final
+//!
+//! Relayers see a transfer as soon as the node processes its slot,
+//! instead of polling a public RPC. Load it with
+//! `--geyser-plugin-config config.json` (see config.example.json).
+
+mod server;
+
+use std::net::SocketAddr;
+use std::str::FromStr;
+use std::time::Duration;
+
+use serde::Deserialize;
+use solana_bridge_sdk::events::parse_logs;
+use solana_geyser_plugin_interface::geyser_plugin_interface::{
+    GeyserPlugin, GeyserPluginError, ReplicaAccountInfoVersions, ReplicaTransactionInfoVersions,
+    Result, SlotStatus,
+};
+use solana_sdk::pubkey::Pubkey;
+use tokio::runtime::Runtime;
+use tokio::sync::{broadcast, oneshot};
+
+pub use server::proto;
+use proto::{update, AccountUpdate, Event, SlotUpdate, Update};
+
+/// Updates buffered per subscriber before it's cut off
+const DEFAULT_CHANNEL_CAPACITY: usize = 100_000;
+
+#[derive(Deserialize)]
+struct Config {
+    /// Defaults to the bridge's deployed program ID
+    program_id: Option<String>,
+    bind_address: SocketAddr,
+    #[serde(default = "default_channel_capacity")]
+    channel_capacity: usize,
+}
+
+fn default_channel_capacity() -> usize {
+    DEFAULT_CHANNEL_CAPACITY
+}
+
+#[derive(Debug)]
+struct Running {
+    program_id: Pubkey,
+    updates: broadcast::Sender<Update>,
+    shutdown: oneshot::Sender<()>,
+    runtime: Runtime,
+}
+
+#[derive(Debug, Default)]
+pub struct BridgeGeyserPlugin {
+    running: Option<Running>,
+}
+
+impl BridgeGeyserPlugin {
+    fn send(&self, update: update::Update) {
+        if let Some(running) = &self.running {
+            // Fails only when nobody is subscribed
+            let _ = running.updates.send(Update {
+                update: Some(update),
+            });
+        }
+    }
+}
+
+fn config_error(msg: impl ToString) -> GeyserPluginError {
+    GeyserPluginError::ConfigFileReadError {
+        msg: msg.to_string(),
+    }
+}
+
+impl GeyserPlugin for BridgeGeyserPlugin {
+    fn name(&self) -> &'static str {
+        "solana-bridge-geyser"
+    }
+
+    fn on_load(&mut self, config_file: &str, _is_reload: bool) -> Result<()> {
+        let config = std::fs::read_to_string(config_file)?;
+        let config: Config = serde_json::from_str(&config).map_err(config_error)?;
+        let program_id = match &config.program_id {
+            Some(id) => Pubkey::from_str(id).map_err(config_error)?,
+            None => solana_bridge_sdk::PROGRAM_ID,
+        };
+
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .thread_name("bridge-geyser")
+            .enable_all()
+            .build()
+            .map_err(|e| GeyserPluginError::Custom(Box::new(e)))?;
+        let (updates, _) = broadcast::channel(config.channel_capacity);
+        let (shutdown, shutdown_signal) = oneshot::channel();
+
+        let server_updates = updates.clone();
+        runtime.spawn(async move {
+            if let Err(e) = server::serve(config.bind_address, server_updates, shutdown_signal).await
+            {
+                log::error!("Bridge gRPC server stopped: {e}");
+            }
+        });
+        log::info!("Streaming {program_id} on {}", config.bind_address);
+
+        self.running = Some(Running {
+            program_id,
+            updates,
+            shutdown,
+            runtime,
+        });
+        Ok(())
+    }
+
+    fn on_unload(&mut self) {
+        if let Some(running) = self.running.take() {
+            let _ = running.shutdown.send(());
+            running.runtime.shutdown_timeout(Duration::from_secs(5));
+        }
+    }
+
+    fn update_account(
+        &self,
+        account: ReplicaAccountInfoVersions,
+        slot: u64,
+        is_startup: bool,
+    ) -> Result<()> {
+        // Startup replays the whole snapshot; clients load current state
+        // over RPC instead
+        let Some(running) = &self.running else {
+            return Ok(());
+        };
+        if is_startup {
+            return Ok(());
+        }
+
+        let (pubkey, owner, lamports, data, write_version) = match account {
+            ReplicaAccountInfoVersions::V0_0_1(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_2(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+            ReplicaAccountInfoVersions::V0_0_3(a) => {
+                (a.pubkey, a.owner, a.lamports, a.data, a.write_version)
+            }
+        };
+        if owner != running.program_id.as_ref() {
+            return Ok(());
+        }
+
+        self.send(update::Update::Account(AccountUpdate {
+            slot,
+            pubkey: pubkey.to_vec(),
+            lamports,
+            data: data.to_vec(),
+            write_version,
+        }));
+        Ok(())
+    }
+
+    fn notify_transaction(
+        &self,
+        transaction: ReplicaTransactionInfoVersions,
+        slot: u64,
+    ) -> Result<()> {
+        let Some(running) = &self.running else {
+            return Ok(());
+        };
+
+        let (signature, is_vote, tx, meta) = match transaction {
+            ReplicaTransactionInfoVersions::V0_0_1(t) => {
+                (t.signature, t.is_vote, t.transaction, t.transaction_status_meta)
+            }
+            ReplicaTransactionInfoVersions::V0_0_2(t) => {
+                (t.signature, t.is_vote, t.transaction, t.transaction_status_meta)
+            }
+        };
+        if is_vote || meta.status.is_err() {
+            return Ok(());
+        }
+        if !tx
+            .message()
+            .account_keys()
+            .iter()
+            .any(|key| *key == running.program_id)
+        {
+            return Ok(());
+        }
+        let Some(logs) = &meta.log_messages else {
+            return Ok(());
+        };
+
+        let events = parse_logs(&running.program_id, logs).map_err(|e| {
+            GeyserPluginError::TransactionUpdateError {
+                msg: format!("{signature}: {e}"),
+            }
+        })?;
+        for (index, event) in events.iter().enumerate() {
+            self.send(update::Update::Event(Event {
+                slot,
+                signature: signature.as_ref().to_vec(),
+                index: index as u32,
+                name: event.name().to_string(),
+                data: event.data(),
+            }));
+        }
+        Ok(())
+    }
+
+    fn update_slot_status(&self, slot: u64, parent: Option<u64>, status: SlotStatus) -> Result<()> {
+        let status = match status {
+            SlotStatus::Processed => proto::SlotStatus::Processed,
+            SlotStatus::Confirmed => proto::SlotStatus::Confirmed,
+            SlotStatus::Rooted => proto::SlotStatus::Rooted,
+        };
+        self.send(update::Update::Slot(SlotUpdate {
+            slot,
+            parent,
+            status: status as i32,
+        }));
+        Ok(())
+    }
+
+    fn account_data_notifications_enabled(&self) -> bool {
+        true
+    }
+
+    fn transaction_notifications_enabled(&self) -> bool {
+        true
+    }
+}
+
+/// # Safety
+///
+/// Called by the validator's plugin loader, which takes ownership of the
+/// returned plugin.
+#[no_mangle]
+#[allow(improper_ctypes_definitions)]
+pub unsafe extern "C" fn _create_plugin() -> *mut dyn GeyserPlugin {
+    Box::into_raw(Box::<BridgeGeyserPlugin>::default())
+}
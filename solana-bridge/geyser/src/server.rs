@@ -0,0 +1,71 @@
+//! gRPC side
+//!
+//! One broadcast channel fans updates out to every subscriber. A
+//! subscriber that falls `channel_capacity` updates behind is cut off
+//! with DATA_LOSS instead of holding the validator back; it should
+//! reconnect and fill the gap over RPC.
+
+use std::net::SocketAddr;
+use std::pin::Pin;
+
+use tokio::sync::{broadcast, oneshot};
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::BroadcastStream;
+use tokio_stream::{Stream, StreamExt};
+use tonic::{Request, Response, Status};
+
+pub mod proto {
+    tonic::include_proto!("bridge.geyser.v1");
+}
+
+use proto::bridge_stream_server::{BridgeStream, BridgeStreamServer};
+use proto::{update, SubscribeRequest, Update};
+
+type UpdateStream = Pin<Box<dyn Stream<Item = Result<Update, Status>> + Send>>;
+
+struct Service {
+    updates: broadcast::Sender<Update>,
+}
+
+#[tonic::async_trait]
+impl BridgeStream for Service {
+    type SubscribeStream = UpdateStream;
+
+    async fn subscribe(
+        &self,
+        request: Request<SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let filter = request.into_inner();
+        let stream = BroadcastStream::new(self.updates.subscribe()).filter_map(move |item| {
+            match item {
+                Ok(update) => wanted(&filter, &update).then_some(Ok(update)),
+                Err(BroadcastStreamRecvError::Lagged(missed)) => Some(Err(Status::data_loss(
+                    format!("Subscriber fell {missed} updates behind"),
+                ))),
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+fn wanted(filter: &SubscribeRequest, update: &Update) -> bool {
+    match &update.update {
+        Some(update::Update::Event(_)) => filter.events,
+        Some(update::Update::Account(_)) => filter.accounts,
+        Some(update::Update::Slot(_)) => filter.slots,
+        None => false,
+    }
+}
+
+pub async fn serve(
+    address: SocketAddr,
+    updates: broadcast::Sender<Update>,
+    shutdown: oneshot::Receiver<()>,
+) -> Result<(), tonic::transport::Error> {
+    tonic::transport::Server::builder()
+        .add_service(BridgeStreamServer::new(Service { updates }))
+        .serve_with_shutdown(address, async {
+            let _ = shutdown.await;
+        })
+        .await
+}
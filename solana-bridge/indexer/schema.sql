@@ -0,0 +1,158 @@
+-- Bridge indexer schema
+--
+-- Applied on startup; every statement is idempotent. u64 values are
+-- NUMERIC(20) since BIGINT is signed.
+
+-- Where the event stream resumes from
+CREATE TABLE IF NOT EXISTS indexer_cursor (
+    id          BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    signature   TEXT NOT NULL,
+    slot        BIGINT NOT NULL
+);
+
+-- Every decoded event, raw (discriminator || borsh, as logged)
+CREATE TABLE IF NOT EXISTS events (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        BIGINT NOT NULL,
+    name        TEXT NOT NULL,
+    data        BYTEA NOT NULL,
+    PRIMARY KEY (signature, event_index)
+);
+CREATE INDEX IF NOT EXISTS events_name_slot ON events (name, slot);
+
+-- One row per transfer, following TransferRecord's status:
+-- initiated, attested, delayed, claimable, completed, vetoed, cancelled, refunded
+CREATE TABLE IF NOT EXISTS transfers (
+    direction       TEXT NOT NULL,          -- outbound | inbound
+    nonce           NUMERIC(20) NOT NULL,
+    transfer_id     TEXT NOT NULL,          -- hex
+    kind            TEXT NOT NULL,          -- lock | burn | mint | unlock | attestation
+    sender          TEXT,
+    recipient       TEXT NOT NULL,
+    amount          NUMERIC(20) NOT NULL,
+    dest_chain_id   NUMERIC(20),
+    status          TEXT NOT NULL,
+    created_slot    BIGINT NOT NULL,
+    created_tx      TEXT NOT NULL,
+    updated_slot    BIGINT NOT NULL,
+    updated_tx      TEXT NOT NULL,
+    PRIMARY KEY (direction, nonce)
+);
+CREATE INDEX IF NOT EXISTS transfers_sender ON transfers (sender);
+CREATE INDEX IF NOT EXISTS transfers_recipient ON transfers (recipient);
+CREATE INDEX IF NOT EXISTS transfers_status ON transfers (status);
+
+-- Bridge fees charged on locks, relayer rewards and gas reimbursements paid out
+CREATE TABLE IF NOT EXISTS fees (
+    signature   TEXT NOT NULL,
+    event_index INTEGER NOT NULL,
+    slot        BIGINT NOT NULL,
+    kind        TEXT NOT NULL,              -- bridge_fee | relayer_reward | gas_reimbursement
+    account     TEXT NOT NULL,              -- payer or relayer
+    amount      NUMERIC(20) NOT NULL,
+    nonce       NUMERIC(20),                -- bridge_fee: the lock
+    fee_tier    SMALLINT,                   -- bridge_fee: rebate tier
+    epoch       NUMERIC(20),                -- relayer_reward: reward epoch
+    PRIMARY KEY (signature, event_index)
+);
+
+-- Owner actions, from the on-chain admin log ring
+CREATE TABLE IF NOT EXISTS admin_actions (
+    sequence    BIGINT PRIMARY KEY,
+    actor       TEXT NOT NULL,
+    action      TEXT NOT NULL,
+    timestamp   TIMESTAMPTZ NOT NULL
+);
+
+-- Token, relayer and timing, for per-token volume and relayer latency
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS mint TEXT;
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS relayer TEXT;
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ;
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS completed_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS transfers_created_at ON transfers (created_at);
+
+-- Finished transfer records moved into the compressed store, one row per
+-- tree leaf (TransferRecordCompressed); proofs are rebuilt from a tree's
+-- leaves (see graphql.rs)
+CREATE TABLE IF NOT EXISTS compressed_transfers (
+    tree        TEXT NOT NULL,
+    leaf_index  BIGINT NOT NULL,
+    max_depth   INTEGER NOT NULL,
+    leaf        BYTEA NOT NULL,
+    direction   TEXT NOT NULL,
+    nonce       NUMERIC(20) NOT NULL,
+    transfer_id TEXT NOT NULL,              -- hex
+    record      BYTEA NOT NULL,             -- Borsh TransferRecord
+    signature   TEXT NOT NULL,
+    slot        BIGINT NOT NULL,
+    PRIMARY KEY (tree, leaf_index)
+);
+CREATE INDEX IF NOT EXISTS compressed_transfers_nonce ON compressed_transfers (direction, nonce);
+
+-- Recipient as support searches for it: EVM hex addresses lowercased, so
+-- a checksummed, lowercase or compact-event address all match; anything
+-- else (Solana, Stellar) as is. text_pattern_ops serves prefix searches
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS recipient_key TEXT GENERATED ALWAYS AS (
+    CASE WHEN recipient ~* '^0x[0-9a-f]+$' THEN lower(recipient) ELSE recipient END
+) STORED;
+CREATE INDEX IF NOT EXISTS transfers_recipient_key ON transfers (recipient_key text_pattern_ops);
+
+-- Token and time of each fee row, for the accounting export (export.rs);
+-- kind also covers insurance_accrual (forfeited pause bonds),
+-- insurance_payout (incident rewards) and delivery_fee (destination gas
+-- prepaid on a lock), all in lamports like relayer payouts, and
+-- sla_rebate (bridge fee owed back on a late transfer, in the token).
+-- Bridge fees indexed before these columns take them from their lock;
+-- other older rows stay undated until the history is re-indexed
+ALTER TABLE fees ADD COLUMN IF NOT EXISTS mint TEXT;
+ALTER TABLE fees ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ;
+UPDATE fees SET mint = t.mint, created_at = t.created_at
+FROM transfers t
+WHERE fees.kind = 'bridge_fee' AND fees.created_at IS NULL
+  AND t.direction = 'outbound' AND t.nonce = fees.nonce;
+CREATE INDEX IF NOT EXISTS fees_created_at ON fees (created_at);
+
+-- Frontend or router an outbound transfer was tagged with (the program's
+-- IntegratorStats ID), for per-integrator volume; NULL if untagged
+ALTER TABLE transfers ADD COLUMN IF NOT EXISTS integrator_id BIGINT;
+CREATE INDEX IF NOT EXISTS transfers_integrator_id ON transfers (integrator_id)
+WHERE integrator_id IS NOT NULL;
+
+-- Block time of each event, for retention (retention.rs); events indexed
+-- before this column stay undated and are never compacted
+ALTER TABLE events ADD COLUMN IF NOT EXISTS created_at TIMESTAMPTZ;
+CREATE INDEX IF NOT EXISTS events_created_at ON events (created_at);
+
+-- Daily rollups of the detail rows compaction removed after RETENTION_DAYS
+-- (see retention.rs). Days are UTC. A day's rows are rolled up and deleted
+-- in one transaction, so each row is counted in exactly one tier. Key
+-- columns can't be NULL: an unknown or lamport mint is '', an untagged
+-- transfer integrator 0
+CREATE TABLE IF NOT EXISTS transfers_daily (
+    day             DATE NOT NULL,
+    mint            TEXT NOT NULL,
+    direction       TEXT NOT NULL,
+    status          TEXT NOT NULL,          -- settled statuses only
+    integrator_id   BIGINT NOT NULL,
+    transfers       BIGINT NOT NULL,
+    volume          NUMERIC NOT NULL,
+    PRIMARY KEY (day, mint, direction, status, integrator_id)
+);
+
+CREATE TABLE IF NOT EXISTS fees_daily (
+    day         DATE NOT NULL,
+    mint        TEXT NOT NULL,
+    kind        TEXT NOT NULL,
+    entries     BIGINT NOT NULL,
+    amount      NUMERIC NOT NULL,
+    PRIMARY KEY (day, mint, kind)
+);
+
+CREATE TABLE IF NOT EXISTS events_daily (
+    day         DATE NOT NULL,
+    name        TEXT NOT NULL,
+    events      BIGINT NOT NULL,
+    PRIMARY KEY (day, name)
+);
@@ -0,0 +1,81 @@
+//! Admin log follower
+//!
+//! Owner actions aren't events: each is appended to the AdminLog ring
+//! account. We read the account on (re)connect and on every change
+//! (accountSubscribe), and insert the entries not indexed yet. Entries
+//! that rotated out of the ring while the indexer was down are lost;
+//! that's logged.
+
+use anchor_lang::AccountDeserialize;
+use anyhow::Result;
+use futures::StreamExt;
+use solana_account_decoder::UiAccountEncoding;
+use solana_bridge::{AdminLog, ADMIN_LOG_CAPACITY};
+use solana_bridge_sdk::pda;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcAccountInfoConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tokio_postgres::Client;
+
+use crate::db;
+
+/// Follow the admin log until the subscription fails
+pub async fn follow(
+    rpc_url: &str,
+    ws_url: &str,
+    program_id: &Pubkey,
+    client: &Client,
+) -> Result<()> {
+    let address = pda::admin_log(program_id);
+    let commitment = CommitmentConfig::confirmed();
+    let rpc = RpcClient::new_with_commitment(rpc_url.to_string(), commitment);
+    let mut next = db::next_admin_sequence(client).await?;
+
+    let pubsub = PubsubClient::new(ws_url).await?;
+    let (mut updates, _unsubscribe) = pubsub
+        .account_subscribe(
+            &address,
+            Some(RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(commitment),
+                ..RpcAccountInfoConfig::default()
+            }),
+        )
+        .await?;
+
+    // Whatever happened before the subscription was open
+    next = store(client, &rpc.get_account_data(&address).await?, next).await?;
+
+    while let Some(update) = updates.next().await {
+        if let Some(data) = update.value.data.decode() {
+            next = store(client, &data, next).await?;
+        }
+    }
+
+    anyhow::bail!("admin log subscription closed")
+}
+
+/// Insert entries from `next` on; returns the new `next`
+async fn store(client: &Client, data: &[u8], next: u64) -> Result<u64> {
+    let log = AdminLog::try_deserialize(&mut &data[..])?;
+    let oldest = log.total.saturating_sub(log.entries.len() as u64);
+    if next < oldest {
+        tracing::warn!("Admin log entries {next}..{oldest} rotated out before they were indexed");
+    }
+
+    for sequence in next.max(oldest)..log.total {
+        let entry = &log.entries[(sequence % ADMIN_LOG_CAPACITY as u64) as usize];
+        db::insert_admin_action(
+            client,
+            sequence,
+            &entry.actor.to_string(),
+            &format!("{:?}", entry.action),
+            entry.timestamp,
+        )
+        .await?;
+    }
+
+    Ok(next.max(log.total))
+}
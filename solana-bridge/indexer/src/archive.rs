@@ -0,0 +1,260 @@
+//! Event archive
+//!
+//! Every ARCHIVE_INTERVAL_SECONDS, the events indexed since the last
+//! archive are bundled, published to IPFS or Arweave, and the bundle's
+//! SHA-256 is anchored on-chain with `record_archive`. Archives chain
+//! through their hashes (see `Archive`), so an auditor can fetch every
+//! bundle from its locator and check the history without trusting this
+//! indexer or its database. A bundle is JSON:
+//!
+//!   {
+//!     "program_id": "...", "index": 7, "prev_hash": "<hex>",
+//!     "first_slot": 1000, "last_slot": 1999,
+//!     "events": [{ "signature", "event_index", "slot", "name", "data" }],
+//!     "checkpoints": [{ "index", "root", "transfer_count", "slot" }]
+//!   }
+//!
+//! `data` is the event as logged (base64 of discriminator || borsh), so
+//! each one can be matched against its transaction; `checkpoints` are
+//! the range's CheckpointCreated events, decoded.
+//!
+//! Only slots before the cursor's are bundled, since the rest of the
+//! cursor's slot may not be indexed yet. Where the next bundle starts
+//! comes from the on-chain `ArchiveLog`, so a round that fails to publish
+//! or record is simply redone.
+
+use std::time::Duration;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anyhow::{Context as _, Result};
+use base64::Engine;
+use reqwest::multipart::{Form, Part};
+use serde_json::{json, Value};
+use solana_bridge::{accounts, hex_encode, instruction, ArchiveLog};
+use solana_bridge_sdk::{pda, BridgeEvent};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+use tokio_postgres::Client;
+
+use crate::db;
+
+/// Most events in one bundle; a slot is never split across bundles
+const MAX_BUNDLE_EVENTS: usize = 5_000;
+
+/// Where bundles are published
+#[derive(Clone, Copy)]
+pub enum Target {
+    /// An IPFS node's HTTP API (`/api/v0/add`); the bundle is pinned
+    Ipfs,
+    /// An Arweave upload gateway that takes the raw bundle and answers `{"id": ...}`
+    Arweave,
+}
+
+impl std::str::FromStr for Target {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "ipfs" => Ok(Target::Ipfs),
+            "arweave" => Ok(Target::Arweave),
+            _ => anyhow::bail!("ARCHIVE_TARGET must be ipfs or arweave, not {s}"),
+        }
+    }
+}
+
+pub struct Config {
+    pub interval: Duration,
+    pub target: Target,
+    pub url: String,
+    /// Signs `record_archive`; must be the ArchiveLog's archiver
+    pub archiver: Keypair,
+}
+
+/// Archive every `interval` until an error
+pub async fn run(
+    config: &Config,
+    rpc_url: &str,
+    program_id: &Pubkey,
+    client: &Client,
+) -> Result<()> {
+    let rpc = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    let http = reqwest::Client::new();
+    let mut ticker = tokio::time::interval(config.interval);
+
+    loop {
+        ticker.tick().await;
+        archive_once(config, &rpc, &http, program_id, client).await?;
+    }
+}
+
+/// Bundle, publish and record what's been indexed since the last archive
+async fn archive_once(
+    config: &Config,
+    rpc: &RpcClient,
+    http: &reqwest::Client,
+    program_id: &Pubkey,
+    client: &Client,
+) -> Result<()> {
+    let address = pda::archive_log(program_id);
+    let Some(account) = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await?
+        .value
+    else {
+        tracing::warn!("No archive log yet; the owner has to run set_archiver first");
+        return Ok(());
+    };
+    let log = ArchiveLog::try_deserialize(&mut &account.data[..])?;
+    if log.archiver != config.archiver.pubkey() {
+        anyhow::bail!(
+            "ARCHIVER_KEYPAIR is {}, but the archiver is {}",
+            config.archiver.pubkey(),
+            log.archiver
+        );
+    }
+
+    let Some(cursor) = db::cursor(client).await? else {
+        return Ok(());
+    };
+    let first_slot = if log.count == 0 { 0 } else { log.last_slot + 1 };
+    let Some(mut last_slot) = cursor.slot.checked_sub(1).filter(|&last| last >= first_slot) else {
+        return Ok(());
+    };
+
+    let limit = MAX_BUNDLE_EVENTS as i64 + 1;
+    let mut events = db::events_between(client, first_slot, last_slot, limit).await?;
+    if events.len() > MAX_BUNDLE_EVENTS {
+        let cut = events[MAX_BUNDLE_EVENTS].slot as u64;
+        if cut > events[0].slot as u64 {
+            last_slot = cut - 1;
+            events.retain(|event| event.slot as u64 <= last_slot);
+        } else {
+            // One slot holds more than a bundle's worth; it goes in whole
+            last_slot = cut;
+            events = db::events_between(client, first_slot, last_slot, i64::MAX).await?;
+        }
+    }
+    if events.is_empty() {
+        return Ok(());
+    }
+
+    let bundle = bundle(program_id, &log, first_slot, last_slot, &events)?;
+    let digest = hash(&bundle).to_bytes();
+    let locator = publish(config, http, &bundle).await?;
+
+    let instruction = Instruction {
+        program_id: *program_id,
+        accounts: accounts::RecordArchive {
+            authority: config.archiver.pubkey(),
+            bridge_state: pda::bridge_state(program_id),
+            archive_log: address,
+            archive: pda::archive(program_id, log.count),
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: instruction::RecordArchive {
+            hash: digest,
+            first_slot,
+            last_slot,
+            locator: locator.clone(),
+        }
+        .data(),
+    };
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&config.archiver.pubkey()),
+        &[&config.archiver],
+        rpc.get_latest_blockhash().await?,
+    );
+    let signature = rpc.send_and_confirm_transaction(&transaction).await?;
+
+    tracing::info!(
+        "Archive {} (slots {first_slot}..={last_slot}, {} events): {} at {locator} in {signature}",
+        log.count,
+        events.len(),
+        hex_encode(&digest)
+    );
+    Ok(())
+}
+
+/// The bundle for `first_slot..=last_slot`, serialized
+fn bundle(
+    program_id: &Pubkey,
+    log: &ArchiveLog,
+    first_slot: u64,
+    last_slot: u64,
+    events: &[db::StoredEvent],
+) -> Result<Vec<u8>> {
+    let engine = base64::engine::general_purpose::STANDARD;
+    let mut checkpoints = Vec::new();
+    for event in events {
+        let decoded = BridgeEvent::decode(&event.data)?;
+        if let Some(BridgeEvent::CheckpointCreated(checkpoint)) = decoded {
+            checkpoints.push(json!({
+                "index": checkpoint.index,
+                "root": hex_encode(&checkpoint.root),
+                "transfer_count": checkpoint.transfer_count,
+                "slot": checkpoint.slot,
+            }));
+        }
+    }
+
+    let bundle = json!({
+        "program_id": program_id.to_string(),
+        "index": log.count,
+        "prev_hash": hex_encode(&log.last_hash),
+        "first_slot": first_slot,
+        "last_slot": last_slot,
+        "events": events
+            .iter()
+            .map(|event| json!({
+                "signature": event.signature,
+                "event_index": event.event_index,
+                "slot": event.slot,
+                "name": event.name,
+                "data": engine.encode(&event.data),
+            }))
+            .collect::<Vec<_>>(),
+        "checkpoints": checkpoints,
+    });
+    Ok(serde_json::to_vec(&bundle)?)
+}
+
+/// Publish the bundle; returns its locator ("ipfs://<cid>" or "ar://<id>")
+async fn publish(config: &Config, http: &reqwest::Client, bundle: &[u8]) -> Result<String> {
+    let url = config.url.trim_end_matches('/');
+    match config.target {
+        Target::Ipfs => {
+            let part = Part::bytes(bundle.to_vec()).file_name("bundle.json");
+            let response: Value = http
+                .post(format!("{url}/api/v0/add?cid-version=1&pin=true"))
+                .multipart(Form::new().part("file", part))
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let cid = response["Hash"].as_str().context("IPFS add returned no Hash")?;
+            Ok(format!("ipfs://{cid}"))
+        }
+        Target::Arweave => {
+            let response: Value = http
+                .post(url)
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(bundle.to_vec())
+                .send()
+                .await?
+                .error_for_status()?
+                .json()
+                .await?;
+            let id = response["id"].as_str().context("Arweave upload returned no id")?;
+            Ok(format!("ar://{id}"))
+        }
+    }
+}
@@ -0,0 +1,349 @@
+//! Postgres access
+//!
+//! Every write is idempotent (ON CONFLICT), so replaying a transaction
+//! after a crash (the event stream is at-least-once) changes nothing.
+//! u64s are bound as text and cast, since Postgres has no unsigned type.
+
+use std::str::FromStr;
+
+use anyhow::Result;
+use solana_bridge::hex_encode;
+use solana_bridge_sdk::Cursor;
+use solana_sdk::signature::Signature;
+use tokio_postgres::{Client, GenericClient, NoTls};
+
+const SCHEMA: &str = include_str!("../schema.sql");
+
+pub const OUTBOUND: &str = "outbound";
+pub const INBOUND: &str = "inbound";
+
+/// Statuses a transfer can still leave
+pub const PENDING_STATUSES: &str = "('initiated', 'attested', 'delayed', 'claimable')";
+
+pub async fn connect(url: &str) -> Result<Client> {
+    let (client, connection) = tokio_postgres::connect(url, NoTls).await?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await {
+            tracing::error!("Postgres connection closed: {e}");
+        }
+    });
+    Ok(client)
+}
+
+pub async fn apply_schema(client: &Client) -> Result<()> {
+    client.batch_execute(SCHEMA).await?;
+    Ok(())
+}
+
+pub async fn cursor(client: &Client) -> Result<Option<Cursor>> {
+    let Some(row) = client
+        .query_opt("SELECT signature, slot FROM indexer_cursor", &[])
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(Cursor {
+        signature: Signature::from_str(row.get(0))?,
+        slot: row.get::<_, i64>(1) as u64,
+    }))
+}
+
+pub async fn save_cursor(client: &impl GenericClient, cursor: &Cursor) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO indexer_cursor (signature, slot) VALUES ($1, $2)
+             ON CONFLICT (id) DO UPDATE SET signature = EXCLUDED.signature, slot = EXCLUDED.slot",
+            &[&cursor.signature.to_string(), &(cursor.slot as i64)],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Which event a row came from
+pub struct Source {
+    pub signature: String,
+    pub event_index: i32,
+    pub slot: i64,
+    /// Block time (unix seconds)
+    pub block_time: i64,
+}
+
+pub async fn insert_event(
+    client: &impl GenericClient,
+    source: &Source,
+    name: &str,
+    data: &[u8],
+) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO events (signature, event_index, slot, name, data, created_at)
+             VALUES ($1, $2, $3, $4, $5, to_timestamp($6))
+             ON CONFLICT DO NOTHING",
+            &[
+                &source.signature,
+                &source.event_index,
+                &source.slot,
+                &name,
+                &data,
+                &(source.block_time as f64),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+pub struct Transfer {
+    pub direction: &'static str,
+    pub nonce: u64,
+    pub transfer_id: [u8; 32],
+    pub kind: &'static str,
+    pub sender: Option<String>,
+    pub recipient: String,
+    pub amount: u64,
+    pub dest_chain_id: Option<u64>,
+    pub mint: Option<String>,
+    pub relayer: Option<String>,
+    pub status: &'static str,
+}
+
+/// Insert a transfer, or move an existing one to `transfer.status`
+pub async fn upsert_transfer(
+    client: &impl GenericClient,
+    source: &Source,
+    transfer: &Transfer,
+) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO transfers (
+                 direction, nonce, transfer_id, kind, sender, recipient, amount, dest_chain_id,
+                 status, created_slot, created_tx, updated_slot, updated_tx,
+                 mint, relayer, created_at, updated_at, completed_at
+             )
+             VALUES ($1, $2::TEXT::NUMERIC, $3, $4, $5, $6, $7::TEXT::NUMERIC, $8::TEXT::NUMERIC,
+                     $9, $10, $11, $10, $11, $12, $13, to_timestamp($14), to_timestamp($14),
+                     CASE WHEN $9 = 'completed' THEN to_timestamp($14) END)
+             ON CONFLICT (direction, nonce) DO UPDATE SET
+                 status = EXCLUDED.status,
+                 updated_slot = EXCLUDED.updated_slot,
+                 updated_tx = EXCLUDED.updated_tx,
+                 updated_at = EXCLUDED.updated_at,
+                 completed_at = COALESCE(transfers.completed_at, EXCLUDED.completed_at),
+                 mint = COALESCE(transfers.mint, EXCLUDED.mint),
+                 relayer = COALESCE(transfers.relayer, EXCLUDED.relayer)",
+            &[
+                &transfer.direction,
+                &transfer.nonce.to_string(),
+                &hex_encode(&transfer.transfer_id),
+                &transfer.kind,
+                &transfer.sender,
+                &transfer.recipient,
+                &transfer.amount.to_string(),
+                &transfer.dest_chain_id.map(|id| id.to_string()),
+                &transfer.status,
+                &source.slot,
+                &source.signature,
+                &transfer.mint,
+                &transfer.relayer,
+                &(source.block_time as f64),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+/// Move a known transfer to `status`; unknown transfers are left alone
+pub async fn set_transfer_status(
+    client: &impl GenericClient,
+    source: &Source,
+    direction: &str,
+    nonce: u64,
+    status: &str,
+) -> Result<()> {
+    let updated = client
+        .execute(
+            "UPDATE transfers SET
+                 status = $3,
+                 updated_slot = $4,
+                 updated_tx = $5,
+                 updated_at = to_timestamp($6),
+                 completed_at = COALESCE(
+                     completed_at,
+                     CASE WHEN $3 = 'completed' THEN to_timestamp($6) END
+                 )
+             WHERE direction = $1 AND nonce = $2::TEXT::NUMERIC",
+            &[
+                &direction,
+                &nonce.to_string(),
+                &status,
+                &source.slot,
+                &source.signature,
+                &(source.block_time as f64),
+            ],
+        )
+        .await?;
+    if updated == 0 {
+        tracing::warn!("No {direction} transfer {nonce} to mark {status}");
+    }
+    Ok(())
+}
+
+/// Record the integrator an outbound transfer was tagged with
+pub async fn set_integrator(
+    client: &impl GenericClient,
+    nonce: u64,
+    integrator_id: u32,
+) -> Result<()> {
+    client
+        .execute(
+            "UPDATE transfers SET integrator_id = $2
+             WHERE direction = $1 AND nonce = $3::TEXT::NUMERIC",
+            &[&OUTBOUND, &(integrator_id as i64), &nonce.to_string()],
+        )
+        .await?;
+    Ok(())
+}
+
+pub struct Fee {
+    pub kind: &'static str,
+    pub account: String,
+    /// Token the fee is in; None for lamports
+    pub mint: Option<String>,
+    pub amount: u64,
+    pub nonce: Option<u64>,
+    pub fee_tier: Option<u8>,
+    pub epoch: Option<u64>,
+}
+
+pub async fn insert_fee(client: &impl GenericClient, source: &Source, fee: &Fee) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO fees (
+                 signature, event_index, slot, kind, account, amount, nonce, fee_tier, epoch,
+                 mint, created_at
+             )
+             VALUES ($1, $2, $3, $4, $5, $6::TEXT::NUMERIC, $7::TEXT::NUMERIC, $8,
+                     $9::TEXT::NUMERIC, $10, to_timestamp($11))
+             ON CONFLICT DO NOTHING",
+            &[
+                &source.signature,
+                &source.event_index,
+                &source.slot,
+                &fee.kind,
+                &fee.account,
+                &fee.amount.to_string(),
+                &fee.nonce.map(|nonce| nonce.to_string()),
+                &fee.fee_tier.map(i16::from),
+                &fee.epoch.map(|epoch| epoch.to_string()),
+                &fee.mint,
+                &(source.block_time as f64),
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+pub struct CompressedTransfer {
+    pub tree: String,
+    pub leaf_index: u64,
+    pub max_depth: u32,
+    pub leaf: [u8; 32],
+    pub direction: &'static str,
+    pub nonce: u64,
+    pub transfer_id: [u8; 32],
+    pub record: Vec<u8>,
+}
+
+pub async fn insert_compressed_transfer(
+    client: &impl GenericClient,
+    source: &Source,
+    compressed: &CompressedTransfer,
+) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO compressed_transfers (
+                 tree, leaf_index, max_depth, leaf, direction, nonce, transfer_id, record,
+                 signature, slot
+             )
+             VALUES ($1, $2, $3, $4, $5, $6::TEXT::NUMERIC, $7, $8, $9, $10)
+             ON CONFLICT DO NOTHING",
+            &[
+                &compressed.tree,
+                &(compressed.leaf_index as i64),
+                &(compressed.max_depth as i32),
+                &compressed.leaf.as_slice(),
+                &compressed.direction,
+                &compressed.nonce.to_string(),
+                &hex_encode(&compressed.transfer_id),
+                &compressed.record,
+                &source.signature,
+                &source.slot,
+            ],
+        )
+        .await?;
+    Ok(())
+}
+
+/// The admin log sequence to index next
+pub async fn next_admin_sequence(client: &Client) -> Result<u64> {
+    let row = client
+        .query_one("SELECT COALESCE(MAX(sequence) + 1, 0) FROM admin_actions", &[])
+        .await?;
+    Ok(row.get::<_, i64>(0) as u64)
+}
+
+pub async fn insert_admin_action(
+    client: &Client,
+    sequence: u64,
+    actor: &str,
+    action: &str,
+    timestamp: i64,
+) -> Result<()> {
+    client
+        .execute(
+            "INSERT INTO admin_actions (sequence, actor, action, timestamp)
+             VALUES ($1, $2, $3, to_timestamp($4))
+             ON CONFLICT DO NOTHING",
+            &[&(sequence as i64), &actor, &action, &(timestamp as f64)],
+        )
+        .await?;
+    Ok(())
+}
+
+/// An event as stored in `events`
+pub struct StoredEvent {
+    pub signature: String,
+    pub event_index: i32,
+    pub slot: i64,
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+/// Up to `limit` events in `first_slot..=last_slot`, in a stable order
+pub async fn events_between(
+    client: &Client,
+    first_slot: u64,
+    last_slot: u64,
+    limit: i64,
+) -> Result<Vec<StoredEvent>> {
+    let rows = client
+        .query(
+            "SELECT signature, event_index, slot, name, data FROM events
+             WHERE slot BETWEEN $1 AND $2
+             ORDER BY slot, signature, event_index
+             LIMIT $3",
+            &[&(first_slot as i64), &(last_slot as i64), &limit],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| StoredEvent {
+            signature: row.get(0),
+            event_index: row.get(1),
+            slot: row.get(2),
+            name: row.get(3),
+            data: row.get(4),
+        })
+        .collect())
+}
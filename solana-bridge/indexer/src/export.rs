@@ -0,0 +1,301 @@
+//! Accounting export
+//!
+//!   solana-bridge-indexer export --from 2026-01-01 --to 2026-01-31 > january.csv
+//!   solana-bridge-indexer export --from 2026-01-01 --to 2026-01-31 --format ofx
+//!
+//! What the bridge earned and paid out, per token and UTC day, from the
+//! `fees` table and its daily rollups (`fees_daily`, see retention.rs):
+//!
+//! - bridge_fee:        charged on locks, in the locked token or SOL (credit)
+//! - relayer_reward:    epoch rewards paid to relayers, in SOL (debit)
+//! - gas_reimbursement: relayer gas paid back, in SOL (debit)
+//! - insurance_accrual: forfeited pause bonds, into the insurance fund (credit)
+//! - insurance_payout:  incident rewards, out of it (debit)
+//!
+//! Amounts are whole tokens, scaled by each mint's decimals (read from
+//! the chain), next to the raw base units. CSV has a row per day, token
+//! and category. OFX has a statement per token with a transaction per
+//! day and category, for bookkeeping tools that import bank statements;
+//! its account ID is the mint's first 22 characters (the OFX limit).
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::str::FromStr;
+
+use anyhow::{bail, Context as _, Result};
+use chrono::{NaiveDate, Utc};
+use clap::ValueEnum;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use tokio_postgres::Client;
+
+/// Token column for amounts in lamports
+const SOL: &str = "SOL";
+const SOL_DECIMALS: u8 = 9;
+
+/// Longest OFX ACCTID
+const OFX_ACCOUNT_ID_LEN: usize = 22;
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum Format {
+    Csv,
+    Ofx,
+}
+
+/// One day's total for one token and category
+struct Line {
+    day: NaiveDate,
+    token: String,
+    category: String,
+    entries: i64,
+    /// Base units
+    amount: u128,
+}
+
+impl Line {
+    /// Money in (fees, forfeited bonds) rather than out
+    fn is_credit(&self) -> bool {
+        matches!(self.category.as_str(), "bridge_fee" | "insurance_accrual")
+    }
+}
+
+/// Write the report for `from..=to` to stdout
+pub async fn run(
+    client: &Client,
+    rpc_url: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+    format: Format,
+) -> Result<()> {
+    let Some(end) = to.succ_opt().filter(|end| from < *end) else {
+        bail!("--from {from} is after --to {to}");
+    };
+
+    let undated: i64 = client
+        .query_one("SELECT COUNT(*) FROM fees WHERE created_at IS NULL", &[])
+        .await?
+        .get(0);
+    if undated > 0 {
+        eprintln!(
+            "warning: {undated} fee rows were indexed before fees were dated and are left \
+             out; re-index from genesis to include them"
+        );
+    }
+
+    let rows = client
+        .query(
+            "SELECT day, token, kind, SUM(entries)::BIGINT, SUM(amount)::TEXT
+             FROM (
+                 SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, COALESCE(mint, $3) AS token,
+                        kind, 1::BIGINT AS entries, amount
+                 FROM fees
+                 WHERE created_at >= ($1::DATE::TIMESTAMP AT TIME ZONE 'UTC')
+                   AND created_at < ($2::DATE::TIMESTAMP AT TIME ZONE 'UTC')
+                 UNION ALL
+                 SELECT day, COALESCE(NULLIF(mint, ''), $3), kind, entries, amount
+                 FROM fees_daily
+                 WHERE day >= $1::DATE AND day < $2::DATE
+             ) tiers
+             GROUP BY 1, 2, 3
+             ORDER BY 1, 2, 3",
+            &[&from, &end, &SOL],
+        )
+        .await?;
+    let mut lines = Vec::with_capacity(rows.len());
+    for row in &rows {
+        lines.push(Line {
+            day: row.get(0),
+            token: row.get(1),
+            category: row.get(2),
+            entries: row.get(3),
+            amount: row.get::<_, String>(4).parse()?,
+        });
+    }
+
+    let decimals = decimals(&RpcClient::new(rpc_url.to_string()), &lines).await?;
+    let report = match format {
+        Format::Csv => csv(&lines, &decimals),
+        Format::Ofx => ofx(&lines, &decimals, from, to),
+    };
+    print!("{report}");
+    Ok(())
+}
+
+/// Decimals of every token in `lines`
+async fn decimals(rpc: &RpcClient, lines: &[Line]) -> Result<BTreeMap<String, u8>> {
+    let mut decimals = BTreeMap::new();
+    for line in lines {
+        if decimals.contains_key(&line.token) {
+            continue;
+        }
+        let value = if line.token == SOL {
+            SOL_DECIMALS
+        } else {
+            let mint = Pubkey::from_str(&line.token)?;
+            rpc.get_token_supply(&mint)
+                .await
+                .with_context(|| format!("Can't read the decimals of {mint}"))?
+                .decimals
+        };
+        decimals.insert(line.token.clone(), value);
+    }
+    Ok(decimals)
+}
+
+fn csv(lines: &[Line], decimals: &BTreeMap<String, u8>) -> String {
+    let mut out = String::from("date,token,category,direction,entries,amount,base_units\n");
+    for line in lines {
+        let _ = writeln!(
+            out,
+            "{},{},{},{},{},{},{}",
+            line.day,
+            line.token,
+            line.category,
+            if line.is_credit() { "credit" } else { "debit" },
+            line.entries,
+            scaled(line.amount, decimals[&line.token]),
+            line.amount
+        );
+    }
+    out
+}
+
+fn ofx(lines: &[Line], decimals: &BTreeMap<String, u8>, from: NaiveDate, to: NaiveDate) -> String {
+    let mut by_token: BTreeMap<&str, Vec<&Line>> = BTreeMap::new();
+    for line in lines {
+        by_token.entry(&line.token).or_default().push(line);
+    }
+
+    let mut out = String::from(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"no\"?>\n\
+         <?OFX OFXHEADER=\"200\" VERSION=\"220\" SECURITY=\"NONE\" OLDFILEUID=\"NONE\" \
+         NEWFILEUID=\"NONE\"?>\n<OFX>\n",
+    );
+    let _ = writeln!(
+        out,
+        "<SIGNONMSGSRSV1><SONRS><STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\
+         <DTSERVER>{}</DTSERVER><LANGUAGE>ENG</LANGUAGE></SONRS></SIGNONMSGSRSV1>",
+        Utc::now().format("%Y%m%d%H%M%S")
+    );
+    out.push_str("<BANKMSGSRSV1>\n");
+
+    for (index, (token, lines)) in by_token.iter().enumerate() {
+        let decimals = decimals[*token];
+        let account: String = token.chars().take(OFX_ACCOUNT_ID_LEN).collect();
+        let _ = writeln!(
+            out,
+            "<STMTTRNRS><TRNUID>{}</TRNUID><STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>\n\
+             <STMTRS><CURDEF>XXX</CURDEF>\n\
+             <BANKACCTFROM><BANKID>SOLBRIDGE</BANKID><ACCTID>{account}</ACCTID>\
+             <ACCTTYPE>CHECKING</ACCTTYPE></BANKACCTFROM>\n\
+             <BANKTRANLIST><DTSTART>{}</DTSTART><DTEND>{}</DTEND>",
+            index + 1,
+            from.format("%Y%m%d"),
+            to.format("%Y%m%d")
+        );
+
+        let mut net: i128 = 0;
+        for line in lines {
+            let sign = if line.is_credit() { "" } else { "-" };
+            net += if line.is_credit() {
+                line.amount as i128
+            } else {
+                -(line.amount as i128)
+            };
+            let _ = writeln!(
+                out,
+                "<STMTTRN><TRNTYPE>{}</TRNTYPE><DTPOSTED>{}</DTPOSTED><TRNAMT>{sign}{}</TRNAMT>\
+                 <FITID>{}-{}</FITID><NAME>{}</NAME><MEMO>{} entries, {} base units</MEMO></STMTTRN>",
+                if line.is_credit() { "CREDIT" } else { "DEBIT" },
+                line.day.format("%Y%m%d"),
+                scaled(line.amount, decimals),
+                line.day.format("%Y%m%d"),
+                line.category,
+                line.category,
+                line.entries,
+                line.amount
+            );
+        }
+
+        let sign = if net < 0 { "-" } else { "" };
+        let _ = writeln!(
+            out,
+            "</BANKTRANLIST>\n<LEDGERBAL><BALAMT>{sign}{}</BALAMT><DTASOF>{}</DTASOF></LEDGERBAL>\n\
+             </STMTRS></STMTTRNRS>",
+            scaled(net.unsigned_abs(), decimals),
+            to.format("%Y%m%d")
+        );
+    }
+
+    out.push_str("</BANKMSGSRSV1>\n</OFX>\n");
+    out
+}
+
+/// `amount` base units as a decimal with `decimals` places
+fn scaled(amount: u128, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+    let unit = 10u128.pow(decimals as u32);
+    format!(
+        "{}.{:0width$}",
+        amount / unit,
+        amount % unit,
+        width = decimals as usize
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(day: u32, token: &str, category: &str, amount: u128) -> Line {
+        Line {
+            day: NaiveDate::from_ymd_opt(2026, 1, day).unwrap(),
+            token: token.to_string(),
+            category: category.to_string(),
+            entries: 2,
+            amount,
+        }
+    }
+
+    #[test]
+    fn scales_by_decimals() {
+        assert_eq!(scaled(1_500_000_000, 9), "1.500000000");
+        assert_eq!(scaled(42, 6), "0.000042");
+        assert_eq!(scaled(42, 0), "42");
+    }
+
+    #[test]
+    fn csv_has_a_row_per_day_token_and_category() {
+        let lines = [
+            line(1, "USDC", "bridge_fee", 2_500_000),
+            line(1, SOL, "relayer_reward", 10_000_000),
+        ];
+        let decimals = BTreeMap::from([("USDC".to_string(), 6), (SOL.to_string(), 9)]);
+        assert_eq!(
+            csv(&lines, &decimals),
+            "date,token,category,direction,entries,amount,base_units\n\
+             2026-01-01,USDC,bridge_fee,credit,2,2.500000,2500000\n\
+             2026-01-01,SOL,relayer_reward,debit,2,0.010000000,10000000\n"
+        );
+    }
+
+    #[test]
+    fn ofx_nets_each_token() {
+        let lines = [
+            line(1, SOL, "insurance_accrual", 3_000_000_000),
+            line(2, SOL, "gas_reimbursement", 1_000_000_000),
+            line(2, SOL, "insurance_payout", 4_000_000_000),
+        ];
+        let decimals = BTreeMap::from([(SOL.to_string(), 9)]);
+        let day = |d| NaiveDate::from_ymd_opt(2026, 1, d).unwrap();
+        let report = ofx(&lines, &decimals, day(1), day(31));
+
+        assert_eq!(report.matches("<STMTTRNRS>").count(), 1);
+        assert!(report.contains("<TRNAMT>3.000000000</TRNAMT>"));
+        assert!(report.contains("<TRNAMT>-1.000000000</TRNAMT>"));
+        assert!(report.contains("<FITID>20260102-insurance_payout</FITID>"));
+        assert!(report.contains("<BALAMT>-2.000000000</BALAMT>"));
+    }
+}
@@ -0,0 +1,540 @@
+//! GraphQL API over the indexed history
+//!
+//! Read-only queries on the `transfers` table:
+//!
+//! - transfers / transfer: by wallet, status and direction, or by nonce
+//! - transfersTo:          by recipient address or its prefix, for support
+//! - dailyVolume:          transfer count and amount per token per day
+//! - relayerLatency:       p50/p90/p99 seconds from attestation to mint
+//! - transferCounts:       pending vs. settled transfers
+//! - compressedTransfer:   a compressed record with its Merkle proof
+//! - dailyEvents:          events logged per name per day
+//!
+//! The aggregates (dailyVolume, transferCounts, dailyEvents) also read the
+//! daily rollups left by compaction (see retention.rs), so they cover the
+//! whole history; the other queries only see the retained detail.
+//!
+//! u64 values (nonces, amounts) are decimal strings, since GraphQL's Int
+//! is 32-bit. GraphiQL is served on GET /graphql.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use async_graphql::http::GraphiQLSource;
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Enum, Object, Schema, SimpleObject};
+use async_graphql_axum::GraphQL;
+use axum::response::Html;
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, NaiveDate, Utc};
+use solana_bridge::hex_encode;
+use solana_bridge_sdk::compression::prove;
+use tokio_postgres::{Client, Row};
+
+use crate::db::{INBOUND, OUTBOUND, PENDING_STATUSES};
+
+/// Most rows one `transfers` query returns
+const MAX_PAGE: i64 = 500;
+
+/// Shortest recipient prefix `transfersTo` searches by
+const MIN_RECIPIENT_PREFIX: usize = 8;
+
+const TRANSFER_COLUMNS: &str = "direction, nonce::TEXT, transfer_id, kind, sender, recipient,
+    mint, amount::TEXT, dest_chain_id::TEXT, status, relayer, created_slot, created_tx,
+    updated_slot, updated_tx, created_at, completed_at";
+
+#[derive(Enum, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Outbound,
+    Inbound,
+}
+
+impl Direction {
+    fn as_str(self) -> &'static str {
+        match self {
+            Direction::Outbound => OUTBOUND,
+            Direction::Inbound => INBOUND,
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct Transfer {
+    direction: String,
+    nonce: String,
+    /// Hex
+    transfer_id: String,
+    /// lock | burn | mint | unlock | attestation
+    kind: String,
+    sender: Option<String>,
+    recipient: String,
+    mint: Option<String>,
+    amount: String,
+    dest_chain_id: Option<String>,
+    /// initiated | attested | delayed | claimable | completed | vetoed | cancelled | refunded
+    status: String,
+    /// Relayer that posted the attestation, if any
+    relayer: Option<String>,
+    created_slot: i64,
+    created_tx: String,
+    updated_slot: i64,
+    updated_tx: String,
+    created_at: Option<DateTime<Utc>>,
+    completed_at: Option<DateTime<Utc>>,
+}
+
+impl From<&Row> for Transfer {
+    fn from(row: &Row) -> Self {
+        Self {
+            direction: row.get(0),
+            nonce: row.get(1),
+            transfer_id: row.get(2),
+            kind: row.get(3),
+            sender: row.get(4),
+            recipient: row.get(5),
+            mint: row.get(6),
+            amount: row.get(7),
+            dest_chain_id: row.get(8),
+            status: row.get(9),
+            relayer: row.get(10),
+            created_slot: row.get(11),
+            created_tx: row.get(12),
+            updated_slot: row.get(13),
+            updated_tx: row.get(14),
+            created_at: row.get(15),
+            completed_at: row.get(16),
+        }
+    }
+}
+
+#[derive(SimpleObject)]
+pub struct DailyVolume {
+    day: NaiveDate,
+    mint: Option<String>,
+    direction: String,
+    transfers: i64,
+    /// Sum of amounts, in base units
+    volume: String,
+}
+
+#[derive(SimpleObject)]
+pub struct DailyEvents {
+    day: NaiveDate,
+    name: String,
+    events: i64,
+}
+
+#[derive(SimpleObject)]
+pub struct RelayerLatency {
+    relayer: String,
+    transfers: i64,
+    p50_seconds: f64,
+    p90_seconds: f64,
+    p99_seconds: f64,
+}
+
+#[derive(SimpleObject)]
+pub struct TransferCounts {
+    /// initiated, attested, delayed or claimable
+    pending: i64,
+    completed: i64,
+    vetoed: i64,
+    cancelled: i64,
+}
+
+/// A record in the compressed store, with what `verify_compressed_transfer` takes
+#[derive(SimpleObject)]
+pub struct CompressedTransfer {
+    tree: String,
+    leaf_index: i64,
+    /// Hex of the Borsh-serialized TransferRecord
+    record: String,
+    /// Hex; the tree's root after the last leaf indexed
+    root: String,
+    /// Hex sibling nodes, leaf first
+    proof: Vec<String>,
+}
+
+pub struct Query;
+
+#[Object]
+impl Query {
+    /// Newest first; `wallet` matches the sender or the recipient
+    async fn transfers(
+        &self,
+        ctx: &Context<'_>,
+        wallet: Option<String>,
+        status: Option<String>,
+        direction: Option<Direction>,
+        #[graphql(default = 50)] first: i64,
+        #[graphql(default = 0)] offset: i64,
+    ) -> async_graphql::Result<Vec<Transfer>> {
+        let client = ctx.data::<Arc<Client>>()?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {TRANSFER_COLUMNS} FROM transfers
+                     WHERE ($1::TEXT IS NULL OR sender = $1 OR recipient = $1)
+                       AND ($2::TEXT IS NULL OR status = $2)
+                       AND ($3::TEXT IS NULL OR direction = $3)
+                     ORDER BY created_slot DESC, nonce DESC
+                     LIMIT $4 OFFSET $5"
+                ),
+                &[
+                    &wallet,
+                    &status,
+                    &direction.map(Direction::as_str),
+                    &first.clamp(0, MAX_PAGE),
+                    &offset.max(0),
+                ],
+            )
+            .await?;
+        Ok(rows.iter().map(Transfer::from).collect())
+    }
+
+    /// Transfers to `recipient`, newest first, whatever stage they're at
+    ///
+    /// An Ethereum address in any case, or a Solana or Stellar one, whole
+    /// or its first 8+ characters ("I sent to 0xabc…, where is it?").
+    /// Outbound rows are on their way to that address; inbound ones are
+    /// arriving on Solana. `pending: true` keeps transfers still in flight
+    /// (initiated, attested, delayed, claimable); `false` the settled ones.
+    async fn transfers_to(
+        &self,
+        ctx: &Context<'_>,
+        recipient: String,
+        pending: Option<bool>,
+        #[graphql(default = 50)] first: i64,
+        #[graphql(default = 0)] offset: i64,
+    ) -> async_graphql::Result<Vec<Transfer>> {
+        let key = recipient_key(&recipient)?;
+        let client = ctx.data::<Arc<Client>>()?;
+        let rows = client
+            .query(
+                &format!(
+                    "SELECT {TRANSFER_COLUMNS} FROM transfers
+                     WHERE recipient_key LIKE $1
+                       AND ($2::BOOL IS NULL OR (status IN {PENDING_STATUSES}) = $2)
+                     ORDER BY created_slot DESC, nonce DESC
+                     LIMIT $3 OFFSET $4"
+                ),
+                &[
+                    &format!("{key}%"),
+                    &pending,
+                    &first.clamp(0, MAX_PAGE),
+                    &offset.max(0),
+                ],
+            )
+            .await?;
+        Ok(rows.iter().map(Transfer::from).collect())
+    }
+
+    async fn transfer(
+        &self,
+        ctx: &Context<'_>,
+        direction: Direction,
+        nonce: String,
+    ) -> async_graphql::Result<Option<Transfer>> {
+        let nonce: u64 = nonce.parse()?;
+        let client = ctx.data::<Arc<Client>>()?;
+        let row = client
+            .query_opt(
+                &format!(
+                    "SELECT {TRANSFER_COLUMNS} FROM transfers
+                     WHERE direction = $1 AND nonce = $2::TEXT::NUMERIC"
+                ),
+                &[&direction.as_str(), &nonce.to_string()],
+            )
+            .await?;
+        Ok(row.as_ref().map(Transfer::from))
+    }
+
+    /// Over the last `days` UTC days, newest first; vetoed, cancelled and
+    /// refunded transfers don't count
+    async fn daily_volume(
+        &self,
+        ctx: &Context<'_>,
+        mint: Option<String>,
+        #[graphql(default = 30)] days: i32,
+    ) -> async_graphql::Result<Vec<DailyVolume>> {
+        let client = ctx.data::<Arc<Client>>()?;
+        let rows = client
+            .query(
+                "SELECT day, mint, direction, SUM(transfers)::BIGINT, SUM(volume)::TEXT
+                 FROM (
+                     SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, mint, direction,
+                            1::BIGINT AS transfers, amount AS volume
+                     FROM transfers
+                     WHERE created_at >= now() - make_interval(days => $2)
+                       AND status NOT IN ('vetoed', 'cancelled', 'refunded')
+                       AND ($1::TEXT IS NULL OR mint = $1)
+                     UNION ALL
+                     SELECT day, NULLIF(mint, ''), direction, transfers, volume
+                     FROM transfers_daily
+                     WHERE day >= (now() - make_interval(days => $2))::DATE
+                       AND status NOT IN ('vetoed', 'cancelled', 'refunded')
+                       AND ($1::TEXT IS NULL OR mint = $1)
+                 ) tiers
+                 GROUP BY 1, 2, 3
+                 ORDER BY 1 DESC, 2, 3",
+                &[&mint, &days],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DailyVolume {
+                day: row.get(0),
+                mint: row.get(1),
+                direction: row.get(2),
+                transfers: row.get(3),
+                volume: row.get(4),
+            })
+            .collect())
+    }
+
+    /// Time from AttestationPosted to AttestationExecuted, per relayer,
+    /// for transfers completed in the last `days` days
+    ///
+    /// Direct owner/guardian mints complete in the transaction that
+    /// first shows them on Solana, so they have no latency to measure.
+    /// Percentiles can't be rolled up, so compacted days don't count.
+    async fn relayer_latency(
+        &self,
+        ctx: &Context<'_>,
+        #[graphql(default = 7)] days: i32,
+    ) -> async_graphql::Result<Vec<RelayerLatency>> {
+        let client = ctx.data::<Arc<Client>>()?;
+        let rows = client
+            .query(
+                "WITH latency AS (
+                     SELECT relayer, EXTRACT(EPOCH FROM completed_at - created_at)::FLOAT8 AS seconds
+                     FROM transfers
+                     WHERE relayer IS NOT NULL
+                       AND completed_at >= now() - make_interval(days => $1)
+                 )
+                 SELECT relayer, COUNT(*),
+                        percentile_cont(0.5) WITHIN GROUP (ORDER BY seconds),
+                        percentile_cont(0.9) WITHIN GROUP (ORDER BY seconds),
+                        percentile_cont(0.99) WITHIN GROUP (ORDER BY seconds)
+                 FROM latency
+                 GROUP BY relayer
+                 ORDER BY relayer",
+                &[&days],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| RelayerLatency {
+                relayer: row.get(0),
+                transfers: row.get(1),
+                p50_seconds: row.get(2),
+                p90_seconds: row.get(3),
+                p99_seconds: row.get(4),
+            })
+            .collect())
+    }
+
+    /// The tree is rebuilt from every leaf indexed for it, so the proof is
+    /// against the newest root the indexer has seen; the program accepts
+    /// it while that root is still in the tree's changelog buffer
+    async fn compressed_transfer(
+        &self,
+        ctx: &Context<'_>,
+        direction: Direction,
+        nonce: String,
+    ) -> async_graphql::Result<Option<CompressedTransfer>> {
+        let nonce: u64 = nonce.parse()?;
+        let client = ctx.data::<Arc<Client>>()?;
+        let Some(row) = client
+            .query_opt(
+                "SELECT tree, leaf_index, max_depth, record FROM compressed_transfers
+                 WHERE direction = $1 AND nonce = $2::TEXT::NUMERIC",
+                &[&direction.as_str(), &nonce.to_string()],
+            )
+            .await?
+        else {
+            return Ok(None);
+        };
+        let tree: String = row.get(0);
+        let leaf_index: i64 = row.get(1);
+        let max_depth: i32 = row.get(2);
+        let record: Vec<u8> = row.get(3);
+
+        let rows = client
+            .query(
+                "SELECT leaf_index, leaf FROM compressed_transfers
+                 WHERE tree = $1 ORDER BY leaf_index",
+                &[&tree],
+            )
+            .await?;
+        let mut leaves: Vec<[u8; 32]> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            // A gap means an event was missed; any proof would be wrong
+            if row.get::<_, i64>(0) != leaves.len() as i64 {
+                return Err(format!("Tree {tree} is missing leaf {}", leaves.len()).into());
+            }
+            leaves.push(row.get::<_, &[u8]>(1).try_into()?);
+        }
+        let (root, proof) = prove(&leaves, leaf_index as usize, max_depth as u32)
+            .ok_or("Leaf index out of range")?;
+
+        Ok(Some(CompressedTransfer {
+            tree,
+            leaf_index,
+            record: hex_encode(&record),
+            root: hex_encode(&root),
+            proof: proof.iter().map(|node| hex_encode(node)).collect(),
+        }))
+    }
+
+    async fn transfer_counts(
+        &self,
+        ctx: &Context<'_>,
+        direction: Option<Direction>,
+    ) -> async_graphql::Result<TransferCounts> {
+        let client = ctx.data::<Arc<Client>>()?;
+        let row = client
+            .query_one(
+                &format!(
+                    "WITH counts AS (
+                         SELECT status, COUNT(*) AS n FROM transfers
+                         WHERE $1::TEXT IS NULL OR direction = $1
+                         GROUP BY status
+                         UNION ALL
+                         SELECT status, SUM(transfers)::BIGINT FROM transfers_daily
+                         WHERE $1::TEXT IS NULL OR direction = $1
+                         GROUP BY status
+                     )
+                     SELECT COALESCE(SUM(n) FILTER (WHERE status IN {PENDING_STATUSES}), 0)::BIGINT,
+                            COALESCE(SUM(n) FILTER (WHERE status = 'completed'), 0)::BIGINT,
+                            COALESCE(SUM(n) FILTER (WHERE status = 'vetoed'), 0)::BIGINT,
+                            COALESCE(SUM(n) FILTER (WHERE status = 'cancelled'), 0)::BIGINT
+                     FROM counts"
+                ),
+                &[&direction.map(Direction::as_str)],
+            )
+            .await?;
+        Ok(TransferCounts {
+            pending: row.get(0),
+            completed: row.get(1),
+            vetoed: row.get(2),
+            cancelled: row.get(3),
+        })
+    }
+
+    /// Over the last `days` UTC days, newest first; `name` as in BridgeEvent
+    /// (e.g. "LockEvent")
+    async fn daily_events(
+        &self,
+        ctx: &Context<'_>,
+        name: Option<String>,
+        #[graphql(default = 30)] days: i32,
+    ) -> async_graphql::Result<Vec<DailyEvents>> {
+        let client = ctx.data::<Arc<Client>>()?;
+        let rows = client
+            .query(
+                "SELECT day, name, SUM(events)::BIGINT
+                 FROM (
+                     SELECT (created_at AT TIME ZONE 'UTC')::DATE AS day, name, 1::BIGINT AS events
+                     FROM events
+                     WHERE created_at >= now() - make_interval(days => $2)
+                       AND ($1::TEXT IS NULL OR name = $1)
+                     UNION ALL
+                     SELECT day, name, events FROM events_daily
+                     WHERE day >= (now() - make_interval(days => $2))::DATE
+                       AND ($1::TEXT IS NULL OR name = $1)
+                 ) tiers
+                 GROUP BY 1, 2
+                 ORDER BY 1 DESC, 2",
+                &[&name, &days],
+            )
+            .await?;
+        Ok(rows
+            .iter()
+            .map(|row| DailyEvents {
+                day: row.get(0),
+                name: row.get(1),
+                events: row.get(2),
+            })
+            .collect())
+    }
+}
+
+/**
+ * A recipient as stored in `recipient_key` (see schema.sql)
+ *
+ * Takes what support gets pasted: surrounding whitespace, a trailing
+ * "…" or "...", EVM addresses in any case. Only address characters are
+ * accepted, so the result is safe to use as a LIKE prefix.
+ */
+fn recipient_key(recipient: &str) -> Result<String, String> {
+    let trimmed = recipient
+        .trim()
+        .trim_end_matches('…')
+        .trim_end_matches("...");
+    let key = match trimmed.strip_prefix("0x").or_else(|| trimmed.strip_prefix("0X")) {
+        Some(hex) if hex.chars().all(|c| c.is_ascii_hexdigit()) => {
+            format!("0x{}", hex.to_ascii_lowercase())
+        }
+        Some(_) => return Err(format!("{recipient} is not a hex address")),
+        None if trimmed.chars().all(|c| c.is_ascii_alphanumeric()) => trimmed.to_string(),
+        None => return Err(format!("{recipient} is not an address")),
+    };
+    if key.len() < MIN_RECIPIENT_PREFIX {
+        return Err(format!(
+            "Give at least {MIN_RECIPIENT_PREFIX} characters of the recipient"
+        ));
+    }
+    Ok(key)
+}
+
+pub async fn serve(client: Arc<Client>, port: u16) -> Result<()> {
+    let schema = Schema::build(Query, EmptyMutation, EmptySubscription)
+        .data(client)
+        .finish();
+    let app = Router::new().route(
+        "/graphql",
+        get(graphiql).post_service(GraphQL::new(schema)),
+    );
+
+    let listener = tokio::net::TcpListener::bind(("0.0.0.0", port)).await?;
+    tracing::info!("GraphQL listening on :{port}/graphql");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn graphiql() -> Html<String> {
+    Html(GraphiQLSource::build().endpoint("/graphql").finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evm_recipients_match_in_any_case() {
+        let key = "0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed";
+        for pasted in [
+            "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+            "0X5AAEB6053F3E94C9B9A09F33669435E7EF1BEAED",
+            "  0x5aaeb6053f3e94c9b9a09f33669435e7ef1beaed\n",
+        ] {
+            assert_eq!(recipient_key(pasted).unwrap(), key);
+        }
+    }
+
+    #[test]
+    fn prefixes_drop_the_ellipsis() {
+        assert_eq!(recipient_key("0x5aAeb605…").unwrap(), "0x5aaeb605");
+        assert_eq!(recipient_key("0x5aAeb605...").unwrap(), "0x5aaeb605");
+        assert_eq!(recipient_key("9WzDXwBbmkg8…").unwrap(), "9WzDXwBbmkg8");
+    }
+
+    #[test]
+    fn rejects_short_and_non_address_input() {
+        assert!(recipient_key("0xabc…").is_err());
+        assert!(recipient_key("0x5aAeb605g").is_err());
+        assert!(recipient_key("9WzDXwBb%").is_err());
+        assert!(recipient_key("9WzDXwBb_mkg").is_err());
+    }
+}
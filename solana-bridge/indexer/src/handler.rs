@@ -0,0 +1,335 @@
+//! Events -> rows
+//!
+//! Every event is kept raw in `events`. Transfer lifecycle events also
+//! move the transfer's row through the same statuses as its on-chain
+//! TransferRecord; fees, relayer payouts and insurance fund movements
+//! go to `fees`, and records moved into the compressed store to
+//! `compressed_transfers`.
+
+use anyhow::Result;
+use anchor_lang::AnchorDeserialize;
+use solana_bridge::{
+    hex_encode, transfer_message_hash, ChallengeOutcome, TransferDirection, TransferRecord,
+};
+use solana_bridge_sdk::{BridgeEvent, StreamedEvent};
+use tokio_postgres::GenericClient;
+
+use crate::db::{self, CompressedTransfer, Fee, Source, Transfer, INBOUND, OUTBOUND};
+
+pub async fn index_event(
+    client: &impl GenericClient,
+    streamed: &StreamedEvent,
+    event_index: i32,
+    block_time: i64,
+) -> Result<()> {
+    let source = Source {
+        signature: streamed.signature.to_string(),
+        event_index,
+        slot: streamed.slot as i64,
+        block_time,
+    };
+    let event = &streamed.event;
+    db::insert_event(client, &source, event.name(), &event.data()).await?;
+
+    match event {
+        BridgeEvent::Lock(e) => {
+            let transfer = Transfer {
+                direction: OUTBOUND,
+                nonce: e.nonce,
+                transfer_id: e.transfer_id,
+                kind: "lock",
+                sender: Some(e.from.to_string()),
+                recipient: e.recipient.clone(),
+                amount: e.amount,
+                dest_chain_id: Some(e.dest_chain_id),
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "initiated",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+            if e.integrator_id != 0 {
+                db::set_integrator(client, e.nonce, e.integrator_id).await?;
+            }
+
+            let fee = Fee {
+                kind: "bridge_fee",
+                account: e.from.to_string(),
+                mint: Some(e.mint.to_string()),
+                amount: e.fee,
+                nonce: Some(e.nonce),
+                fee_tier: Some(e.fee_tier),
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        BridgeEvent::CompactLock(e) => {
+            // Raw address bytes; the destination chain's kind says how to read them
+            let transfer = Transfer {
+                direction: OUTBOUND,
+                nonce: e.nonce,
+                transfer_id: e.transfer_id,
+                kind: "lock",
+                sender: Some(e.from.to_string()),
+                recipient: format!("0x{}", hex_encode(&e.recipient)),
+                amount: e.amount,
+                dest_chain_id: Some(e.dest_chain_id),
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "initiated",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+            if e.integrator_id != 0 {
+                db::set_integrator(client, e.nonce, e.integrator_id).await?;
+            }
+
+            let fee = Fee {
+                kind: "bridge_fee",
+                account: e.from.to_string(),
+                mint: Some(e.mint.to_string()),
+                amount: e.fee,
+                nonce: Some(e.nonce),
+                fee_tier: Some(e.fee_tier),
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        BridgeEvent::Burn(e) => {
+            let transfer = Transfer {
+                direction: OUTBOUND,
+                nonce: e.nonce,
+                transfer_id: e.transfer_id,
+                kind: "burn",
+                sender: Some(e.from.to_string()),
+                recipient: e.recipient.clone(),
+                amount: e.amount,
+                dest_chain_id: Some(e.dest_chain_id),
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "initiated",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+            if e.integrator_id != 0 {
+                db::set_integrator(client, e.nonce, e.integrator_id).await?;
+            }
+        }
+        BridgeEvent::Mint(e) => {
+            let transfer = Transfer {
+                direction: INBOUND,
+                nonce: e.nonce,
+                transfer_id: transfer_message_hash(&e.to, e.amount, e.nonce),
+                kind: "mint",
+                sender: None,
+                recipient: e.to.to_string(),
+                amount: e.amount,
+                dest_chain_id: None,
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "completed",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+        }
+        BridgeEvent::TransferClaimable(e) => {
+            let transfer = Transfer {
+                direction: INBOUND,
+                nonce: e.nonce,
+                transfer_id: e.transfer_id,
+                kind: "mint",
+                sender: None,
+                recipient: e.recipient.to_string(),
+                amount: e.amount,
+                dest_chain_id: None,
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "claimable",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+        }
+        BridgeEvent::DeliveryQuarantined(e) => {
+            let transfer = Transfer {
+                direction: INBOUND,
+                nonce: e.nonce,
+                transfer_id: e.transfer_id,
+                kind: if e.escrowed { "unlock" } else { "mint" },
+                sender: None,
+                recipient: e.recipient.to_string(),
+                amount: e.amount,
+                dest_chain_id: None,
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "claimable",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+        }
+        BridgeEvent::Unlock(e) => {
+            let transfer = Transfer {
+                direction: INBOUND,
+                nonce: e.nonce,
+                transfer_id: transfer_message_hash(&e.to, e.amount, e.nonce),
+                kind: "unlock",
+                sender: None,
+                recipient: e.to.to_string(),
+                amount: e.amount,
+                dest_chain_id: None,
+                mint: Some(e.mint.to_string()),
+                relayer: None,
+                status: "completed",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+        }
+        BridgeEvent::AttestationPosted(e) => {
+            let transfer = Transfer {
+                direction: INBOUND,
+                nonce: e.nonce,
+                transfer_id: transfer_message_hash(&e.recipient, e.amount, e.nonce),
+                kind: "attestation",
+                sender: None,
+                recipient: e.recipient.to_string(),
+                amount: e.amount,
+                dest_chain_id: None,
+                mint: Some(e.mint.to_string()),
+                relayer: Some(e.relayer.to_string()),
+                status: "attested",
+            };
+            db::upsert_transfer(client, &source, &transfer).await?;
+        }
+        BridgeEvent::AttestationChallenged(e) => {
+            db::set_transfer_status(client, &source, INBOUND, e.nonce, "delayed").await?;
+        }
+        BridgeEvent::ChallengeResolved(e) => {
+            let status = match e.outcome {
+                ChallengeOutcome::Fraud => "vetoed",
+                ChallengeOutcome::Valid => "attested",
+            };
+            db::set_transfer_status(client, &source, INBOUND, e.nonce, status).await?;
+        }
+        BridgeEvent::AttestationExecuted(e) => {
+            db::set_transfer_status(client, &source, INBOUND, e.nonce, "completed").await?;
+        }
+        BridgeEvent::TransferVetoed(e) => {
+            db::set_transfer_status(client, &source, INBOUND, e.nonce, "vetoed").await?;
+        }
+        BridgeEvent::VetoLifted(e) => {
+            db::set_transfer_status(client, &source, INBOUND, e.nonce, "attested").await?;
+        }
+        BridgeEvent::TransferCancelled(e) => {
+            let direction = match e.direction {
+                TransferDirection::Outbound => OUTBOUND,
+                TransferDirection::Inbound => INBOUND,
+            };
+            db::set_transfer_status(client, &source, direction, e.nonce, "cancelled").await?;
+        }
+        BridgeEvent::TransferReclaimed(e) => {
+            db::set_transfer_status(client, &source, OUTBOUND, e.nonce, "refunded").await?;
+        }
+        BridgeEvent::TransferAcknowledged(e) => {
+            db::set_transfer_status(client, &source, OUTBOUND, e.nonce, "completed").await?;
+        }
+        BridgeEvent::TransferRefunded(e) => {
+            db::set_transfer_status(client, &source, OUTBOUND, e.nonce, "refunded").await?;
+        }
+        // A lock fee paid in SOL; its LockEvent booked none in the token
+        BridgeEvent::FeePaidInSol(e) => {
+            let fee = Fee {
+                kind: "bridge_fee",
+                account: e.from.to_string(),
+                mint: None,
+                amount: e.lamports,
+                nonce: Some(e.nonce),
+                fee_tier: None,
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        BridgeEvent::DeliveryFeePrepaid(e) => {
+            let fee = Fee {
+                kind: "delivery_fee",
+                account: e.from.to_string(),
+                mint: None,
+                amount: e.lamports,
+                nonce: Some(e.nonce),
+                fee_tier: None,
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        // Owed back out of the token's bridge fees; booked when credited
+        BridgeEvent::SlaRebateCredited(e) => {
+            let fee = Fee {
+                kind: "sla_rebate",
+                account: e.sender.to_string(),
+                mint: Some(e.mint.to_string()),
+                amount: e.rebate,
+                nonce: Some(e.nonce),
+                fee_tier: None,
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        BridgeEvent::RelayerRewardsClaimed(e) => {
+            let fee = Fee {
+                kind: "relayer_reward",
+                account: e.relayer.to_string(),
+                mint: None,
+                amount: e.amount,
+                nonce: None,
+                fee_tier: None,
+                epoch: Some(e.epoch),
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        BridgeEvent::GasReimbursementClaimed(e) => {
+            let fee = Fee {
+                kind: "gas_reimbursement",
+                account: e.relayer.to_string(),
+                mint: None,
+                amount: e.amount,
+                nonce: None,
+                fee_tier: None,
+                epoch: None,
+            };
+            db::insert_fee(client, &source, &fee).await?;
+        }
+        // A frivolous pause forfeits its bond to the insurance fund; a
+        // confirmed incident pays the pauser's reward out of it
+        BridgeEvent::BondedPauseResolved(e) => {
+            let (kind, amount) = if e.incident {
+                ("insurance_payout", e.reward)
+            } else {
+                ("insurance_accrual", e.bond)
+            };
+            if amount > 0 {
+                let fee = Fee {
+                    kind,
+                    account: e.pauser.to_string(),
+                    mint: None,
+                    amount,
+                    nonce: None,
+                    fee_tier: None,
+                    epoch: None,
+                };
+                db::insert_fee(client, &source, &fee).await?;
+            }
+        }
+        BridgeEvent::TransferRecordCompressed(e) => {
+            let record = TransferRecord::deserialize(&mut e.record.as_slice())?;
+            let compressed = CompressedTransfer {
+                tree: e.tree.to_string(),
+                leaf_index: e.leaf_index,
+                max_depth: e.max_depth,
+                leaf: e.leaf,
+                direction: match record.direction {
+                    TransferDirection::Outbound => OUTBOUND,
+                    TransferDirection::Inbound => INBOUND,
+                },
+                nonce: record.nonce,
+                transfer_id: e.transfer_id,
+                record: e.record.clone(),
+            };
+            db::insert_compressed_transfer(client, &source, &compressed).await?;
+        }
+        // Kept in `events` only
+        _ => {}
+    }
+
+    Ok(())
+}
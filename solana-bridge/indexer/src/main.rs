@@ -0,0 +1,282 @@
+//! Bridge indexer
+//!
+//! Walks the program's transaction history, then follows it live over
+//! WebSocket, writing every event into Postgres (see schema.sql):
+//!
+//! - transfers:     one row per transfer, tracking its status
+//! - fees:          bridge fees, relayer payouts, insurance fund movements
+//! - admin_actions: the owner's actions, from the admin log account
+//! - compressed_transfers: records moved into the compressed store
+//! - events:        everything, raw
+//! - *_daily:       rollups of compacted rows (see retention.rs)
+//!
+//! The stream cursor is committed with each event's rows, so a restart
+//! resumes exactly where it stopped. The tables are served read-only
+//! over GraphQL (see graphql.rs) on GRAPHQL_PORT.
+//!
+//!   DATABASE_URL=postgres://localhost/bridge solana-bridge-indexer
+//!
+//! `export` writes an accounting report from the same database instead
+//! (see export.rs). With ARCHIVE_INTERVAL_SECONDS set, the indexed events
+//! are also published to IPFS or Arweave for auditors (see archive.rs).
+//! With RETENTION_DAYS set, older rows are rolled up into daily aggregates
+//! (see retention.rs); `compact` runs that once, by hand.
+
+mod admin_log;
+mod archive;
+mod db;
+mod export;
+mod graphql;
+mod handler;
+mod retention;
+
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Result;
+use chrono::NaiveDate;
+use clap::{Parser, Subcommand};
+use futures::StreamExt;
+use solana_bridge_sdk::EventStream;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Signature};
+use tokio_postgres::Client;
+use tracing_subscriber::EnvFilter;
+
+#[derive(Parser)]
+#[command(name = "solana-bridge-indexer", about = "Bridge event indexer")]
+struct Cli {
+    /// Index (the default) or:
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Per-token, per-day fees and payouts for bookkeeping, to stdout
+    Export {
+        /// First day (UTC) included
+        #[arg(long)]
+        from: NaiveDate,
+
+        /// Last day (UTC) included
+        #[arg(long)]
+        to: NaiveDate,
+
+        #[arg(long, value_enum, default_value_t = export::Format::Csv)]
+        format: export::Format,
+    },
+    /// Roll up and delete the rows older than RETENTION_DAYS now
+    Compact,
+}
+
+/// Wait before reconnecting after an error
+const RETRY_DELAY: Duration = Duration::from_secs(5);
+
+struct Config {
+    database_url: String,
+    rpc_url: String,
+    ws_url: String,
+    program_id: Pubkey,
+    graphql_port: u16,
+    /// Set when ARCHIVE_INTERVAL_SECONDS is
+    archive: Option<archive::Config>,
+    /// Set when RETENTION_DAYS is
+    retention: Option<retention::Config>,
+}
+
+impl Config {
+    fn from_env() -> Result<Self> {
+        let var = |name: &str, default: &str| {
+            std::env::var(name).unwrap_or_else(|_| default.to_string())
+        };
+
+        let archive_interval: u64 = var("ARCHIVE_INTERVAL_SECONDS", "0").parse()?;
+        Ok(Self {
+            database_url: std::env::var("DATABASE_URL")
+                .map_err(|_| anyhow::anyhow!("DATABASE_URL is required"))?,
+            rpc_url: var("SOLANA_RPC_URL", "http://127.0.0.1:8899"),
+            ws_url: var("SOLANA_WS_URL", "ws://127.0.0.1:8900"),
+            program_id: match std::env::var("SOLANA_PROGRAM_ID") {
+                Ok(id) => Pubkey::from_str(&id)?,
+                Err(_) => solana_bridge::ID,
+            },
+            graphql_port: var("GRAPHQL_PORT", "8081").parse()?,
+            archive: match archive_interval {
+                0 => None,
+                seconds => {
+                    let keypair = std::env::var("ARCHIVER_KEYPAIR")
+                        .map_err(|_| anyhow::anyhow!("ARCHIVER_KEYPAIR is required to archive"))?;
+                    Some(archive::Config {
+                        interval: Duration::from_secs(seconds),
+                        target: var("ARCHIVE_TARGET", "ipfs").parse()?,
+                        url: var("ARCHIVE_URL", "http://127.0.0.1:5001"),
+                        archiver: read_keypair_file(&keypair)
+                            .map_err(|e| anyhow::anyhow!("{keypair}: {e}"))?,
+                    })
+                }
+            },
+            retention: match var("RETENTION_DAYS", "0").parse()? {
+                0 => None,
+                retention_days => Some(retention::Config {
+                    retention_days,
+                    interval: Duration::from_secs(
+                        var("COMPACTION_INTERVAL_SECONDS", "3600").parse()?,
+                    ),
+                    archived_only: archive_interval > 0,
+                }),
+            },
+        })
+    }
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new("info"));
+    tracing_subscriber::fmt().with_env_filter(filter).init();
+
+    let cli = Cli::parse();
+    let mut config = Config::from_env()?;
+    let mut client = db::connect(&config.database_url).await?;
+    db::apply_schema(&client).await?;
+
+    match cli.command {
+        Some(Command::Export { from, to, format }) => {
+            return export::run(&client, &config.rpc_url, from, to, format).await;
+        }
+        Some(Command::Compact) => {
+            let retention = config
+                .retention
+                .as_ref()
+                .ok_or_else(|| anyhow::anyhow!("RETENTION_DAYS is required to compact"))?;
+            let rpc = RpcClient::new(config.rpc_url.clone());
+            let days = retention::compact(retention, &rpc, &config.program_id, &mut client).await?;
+            tracing::info!("Compacted {days} days");
+            return Ok(());
+        }
+        None => {}
+    }
+
+    let admin_client = db::connect(&config.database_url).await?;
+    let rpc_url = config.rpc_url.clone();
+    let ws_url = config.ws_url.clone();
+    let program_id = config.program_id;
+    let graphql_client = Arc::new(db::connect(&config.database_url).await?);
+    let graphql_port = config.graphql_port;
+    tokio::spawn(async move {
+        if let Err(e) = graphql::serve(graphql_client, graphql_port).await {
+            tracing::error!("GraphQL server: {e:#}");
+        }
+    });
+
+    tokio::spawn(async move {
+        loop {
+            if let Err(e) = admin_log::follow(&rpc_url, &ws_url, &program_id, &admin_client).await {
+                tracing::error!("Admin log follower: {e:#}");
+            }
+            tokio::time::sleep(RETRY_DELAY).await;
+        }
+    });
+
+    if let Some(archive_config) = config.archive.take() {
+        let archive_client = db::connect(&config.database_url).await?;
+        let rpc_url = config.rpc_url.clone();
+        tokio::spawn(async move {
+            loop {
+                let result =
+                    archive::run(&archive_config, &rpc_url, &program_id, &archive_client).await;
+                if let Err(e) = result {
+                    tracing::error!("Archiver: {e:#}");
+                }
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        });
+    }
+
+    if let Some(retention_config) = config.retention.take() {
+        let mut retention_client = db::connect(&config.database_url).await?;
+        let rpc_url = config.rpc_url.clone();
+        tokio::spawn(async move {
+            loop {
+                let result =
+                    retention::run(&retention_config, &rpc_url, &program_id, &mut retention_client)
+                        .await;
+                if let Err(e) = result {
+                    tracing::error!("Compaction: {e:#}");
+                }
+                tokio::time::sleep(RETRY_DELAY).await;
+            }
+        });
+    }
+
+    loop {
+        if let Err(e) = index_events(&config, &mut client).await {
+            tracing::error!("Event stream: {e:#}");
+        }
+        tokio::time::sleep(RETRY_DELAY).await;
+    }
+}
+
+/// Stream events from the saved cursor (or genesis) until an error
+async fn index_events(config: &Config, client: &mut Client) -> Result<()> {
+    let mut stream =
+        EventStream::new(&config.rpc_url, &config.ws_url).program_id(config.program_id);
+    stream = match db::cursor(client).await? {
+        Some(cursor) => {
+            tracing::info!("Resuming after {} (slot {})", cursor.signature, cursor.slot);
+            stream.resume_from(cursor)
+        }
+        None => {
+            tracing::info!("No cursor, backfilling from genesis");
+            stream.from_genesis()
+        }
+    };
+
+    let events = stream.subscribe();
+    futures::pin_mut!(events);
+    let rpc = RpcClient::new(config.rpc_url.clone());
+    let mut block_time: Option<(u64, i64)> = None;
+
+    // Events of one transaction arrive together; number them in order
+    let mut last_signature: Option<Signature> = None;
+    let mut event_index = 0;
+
+    while let Some(item) = events.next().await {
+        let item = item?;
+        event_index = if last_signature == Some(item.signature) {
+            event_index + 1
+        } else {
+            0
+        };
+        last_signature = Some(item.signature);
+
+        // Events arrive slot by slot, so one lookup per slot is enough
+        let time = match block_time {
+            Some((slot, time)) if slot == item.slot => time,
+            _ => {
+                let time = match rpc.get_block_time(item.slot).await {
+                    Ok(time) => time,
+                    Err(e) => {
+                        tracing::warn!("No block time for slot {}: {e}", item.slot);
+                        SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs() as i64
+                    }
+                };
+                block_time = Some((item.slot, time));
+                time
+            }
+        };
+
+        let transaction = client.transaction().await?;
+        handler::index_event(&transaction, &item, event_index, time).await?;
+        if let Some(cursor) = &item.cursor {
+            db::save_cursor(&transaction, cursor).await?;
+        }
+        transaction.commit().await?;
+
+        tracing::debug!("{} in {} (slot {})", item.event.name(), item.signature, item.slot);
+    }
+
+    anyhow::bail!("event stream ended")
+}
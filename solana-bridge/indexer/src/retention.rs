@@ -0,0 +1,226 @@
+//! Retention and compaction
+//!
+//! With RETENTION_DAYS set, rows older than that are rolled up into daily
+//! aggregates and deleted, every COMPACTION_INTERVAL_SECONDS, so the
+//! database grows with the number of days rather than of transfers:
+//!
+//! - transfers -> transfers_daily: count and volume per token, direction,
+//!   status and integrator. Transfers still pending stay, whatever their
+//!   age, and are rolled up once they settle.
+//! - fees      -> fees_daily: entries and amount per token and kind
+//! - events    -> events_daily: count per event name
+//!
+//! Days are UTC and compacted whole, oldest first, each in one
+//! transaction, so a row is always in exactly one tier and an interrupted
+//! run just resumes. The GraphQL aggregates and the accounting export
+//! read both tiers; row queries (transfers, transfersTo) and relayer
+//! latency percentiles only cover the retained detail.
+//!
+//! When archiving is on, events are only deleted once an archive bundle
+//! covers their slot, so every event still reaches auditors.
+//! compressed_transfers (proofs need every leaf) and admin_actions are
+//! never compacted.
+
+use std::time::Duration;
+
+use anchor_lang::AccountDeserialize;
+use anyhow::Result;
+use chrono::{Days, NaiveDate, Utc};
+use solana_bridge::ArchiveLog;
+use solana_bridge_sdk::pda;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use tokio_postgres::{Client, GenericClient};
+
+use crate::db::PENDING_STATUSES;
+
+pub struct Config {
+    /// Days of full detail kept, today included
+    pub retention_days: u32,
+    pub interval: Duration,
+    /// Events must be archived (archive.rs) before they're deleted
+    pub archived_only: bool,
+}
+
+/// Compact every `interval` until an error
+pub async fn run(
+    config: &Config,
+    rpc_url: &str,
+    program_id: &Pubkey,
+    client: &mut Client,
+) -> Result<()> {
+    let rpc = RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+    let mut ticker = tokio::time::interval(config.interval);
+
+    loop {
+        ticker.tick().await;
+        compact(config, &rpc, program_id, client).await?;
+    }
+}
+
+/// Roll up and delete every day before the retention window; returns
+/// how many days were compacted
+pub async fn compact(
+    config: &Config,
+    rpc: &RpcClient,
+    program_id: &Pubkey,
+    client: &mut Client,
+) -> Result<u32> {
+    let before = cutoff(Utc::now().date_naive(), config.retention_days);
+    let last_event_slot = if config.archived_only {
+        Some(archived_through(rpc, program_id).await?.map_or(-1, |slot| slot as i64))
+    } else {
+        None
+    };
+
+    let mut days = 0;
+    while let Some(day) = oldest_day(client, before, last_event_slot).await? {
+        let transaction = client.transaction().await?;
+        let (transfers, fees, events) = compact_day(&transaction, day, last_event_slot).await?;
+        transaction.commit().await?;
+        tracing::info!("Compacted {day}: {transfers} transfers, {fees} fees, {events} events");
+        days += 1;
+    }
+    Ok(days)
+}
+
+/// First day not kept in full: `retention_days` days back from `today`,
+/// which is always kept
+fn cutoff(today: NaiveDate, retention_days: u32) -> NaiveDate {
+    today - Days::new(u64::from(retention_days.max(1)) - 1)
+}
+
+/// Last slot an archive bundle covers, if any bundle was recorded
+async fn archived_through(rpc: &RpcClient, program_id: &Pubkey) -> Result<Option<u64>> {
+    let address = pda::archive_log(program_id);
+    let Some(account) = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await?
+        .value
+    else {
+        return Ok(None);
+    };
+    let log = ArchiveLog::try_deserialize(&mut &account.data[..])?;
+    Ok((log.count > 0).then_some(log.last_slot))
+}
+
+/// Oldest day before `before` with anything left to compact
+async fn oldest_day(
+    client: &Client,
+    before: NaiveDate,
+    last_event_slot: Option<i64>,
+) -> Result<Option<NaiveDate>> {
+    let row = client
+        .query_one(
+            &format!(
+                "SELECT LEAST(
+                     (SELECT MIN((created_at AT TIME ZONE 'UTC')::DATE) FROM transfers
+                      WHERE created_at < ($1::DATE::TIMESTAMP AT TIME ZONE 'UTC')
+                        AND status NOT IN {PENDING_STATUSES}),
+                     (SELECT MIN((created_at AT TIME ZONE 'UTC')::DATE) FROM fees
+                      WHERE created_at < ($1::DATE::TIMESTAMP AT TIME ZONE 'UTC')),
+                     (SELECT MIN((created_at AT TIME ZONE 'UTC')::DATE) FROM events
+                      WHERE created_at < ($1::DATE::TIMESTAMP AT TIME ZONE 'UTC')
+                        AND ($2::BIGINT IS NULL OR slot <= $2))
+                 )"
+            ),
+            &[&before, &last_event_slot],
+        )
+        .await?;
+    Ok(row.get(0))
+}
+
+/// Move `day`'s settled transfers, fees and (archived) events into the
+/// daily tables; returns how many of each
+async fn compact_day(
+    client: &impl GenericClient,
+    day: NaiveDate,
+    last_event_slot: Option<i64>,
+) -> Result<(u64, u64, u64)> {
+    const ON_DAY: &str = "created_at >= ($1::DATE::TIMESTAMP AT TIME ZONE 'UTC')
+        AND created_at < (($1::DATE + 1)::TIMESTAMP AT TIME ZONE 'UTC')";
+
+    let transfers = client
+        .query_one(
+            &format!(
+                "WITH gone AS (
+                     DELETE FROM transfers
+                     WHERE {ON_DAY} AND status NOT IN {PENDING_STATUSES}
+                     RETURNING mint, direction, status, integrator_id, amount
+                 ), rolled AS (
+                     INSERT INTO transfers_daily
+                         (day, mint, direction, status, integrator_id, transfers, volume)
+                     SELECT $1::DATE, COALESCE(mint, ''), direction, status,
+                            COALESCE(integrator_id, 0), COUNT(*), SUM(amount)
+                     FROM gone
+                     GROUP BY 2, 3, 4, 5
+                     ON CONFLICT (day, mint, direction, status, integrator_id) DO UPDATE SET
+                         transfers = transfers_daily.transfers + EXCLUDED.transfers,
+                         volume = transfers_daily.volume + EXCLUDED.volume
+                 )
+                 SELECT COUNT(*) FROM gone"
+            ),
+            &[&day],
+        )
+        .await?
+        .get::<_, i64>(0);
+
+    let fees = client
+        .query_one(
+            &format!(
+                "WITH gone AS (
+                     DELETE FROM fees WHERE {ON_DAY}
+                     RETURNING mint, kind, amount
+                 ), rolled AS (
+                     INSERT INTO fees_daily (day, mint, kind, entries, amount)
+                     SELECT $1::DATE, COALESCE(mint, ''), kind, COUNT(*), SUM(amount)
+                     FROM gone
+                     GROUP BY 2, 3
+                     ON CONFLICT (day, mint, kind) DO UPDATE SET
+                         entries = fees_daily.entries + EXCLUDED.entries,
+                         amount = fees_daily.amount + EXCLUDED.amount
+                 )
+                 SELECT COUNT(*) FROM gone"
+            ),
+            &[&day],
+        )
+        .await?
+        .get::<_, i64>(0);
+
+    let events = client
+        .query_one(
+            &format!(
+                "WITH gone AS (
+                     DELETE FROM events
+                     WHERE {ON_DAY} AND ($2::BIGINT IS NULL OR slot <= $2)
+                     RETURNING name
+                 ), rolled AS (
+                     INSERT INTO events_daily (day, name, events)
+                     SELECT $1::DATE, name, COUNT(*) FROM gone
+                     GROUP BY 2
+                     ON CONFLICT (day, name) DO UPDATE SET
+                         events = events_daily.events + EXCLUDED.events
+                 )
+                 SELECT COUNT(*) FROM gone"
+            ),
+            &[&day, &last_event_slot],
+        )
+        .await?
+        .get::<_, i64>(0);
+
+    Ok((transfers as u64, fees as u64, events as u64))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keeps_whole_days_including_today() {
+        let today = NaiveDate::from_ymd_opt(2026, 3, 31).unwrap();
+        assert_eq!(cutoff(today, 1), today);
+        assert_eq!(cutoff(today, 0), today);
+        assert_eq!(cutoff(today, 90), NaiveDate::from_ymd_opt(2026, 1, 1).unwrap());
+    }
+}
@@ -0,0 +1,253 @@
+//! Demo Faucet Program
+//!
+//! A demo SPL token for trying the bridge on localnet or devnet: anyone
+//! can drip some to their wallet and lock it right away. Like a testnet
+//! ETH faucet, but the token is already registered with the bridge.
+//!
+//! `initialize` creates the token (a PDA mint the faucet controls) and
+//! registers it with the bridge through CPI, so the bridge owner signs
+//! it once. `drip` then mints `drip_amount` to the caller at most once
+//! per `cooldown` seconds.
+//!
+//! Never deploy this to mainnet: the token is free for anyone to mint.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::token::{self, Mint, MintTo, Token, TokenAccount};
+use solana_bridge::program::SolanaBridge;
+
+declare_id!("6yh88qyW2yzxZZa8h4HS1aufgQKmePwTmD3TEB27YmGo");
+
+/// Decimals of the demo token
+pub const DECIMALS: u8 = 9;
+
+#[program]
+pub mod demo_faucet {
+    use super::*;
+
+    /**
+     * Create the demo token and register it with the bridge
+     *
+     * Signed by the bridge owner, which the bridge's `register_token`
+     * requires; the owner also becomes the faucet's authority.
+     */
+    pub fn initialize(ctx: Context<Initialize>, drip_amount: u64, cooldown: i64) -> Result<()> {
+        require!(drip_amount > 0 && cooldown >= 0, FaucetError::InvalidConfig);
+
+        let faucet = &mut ctx.accounts.faucet;
+        faucet.authority = ctx.accounts.owner.key();
+        faucet.mint = ctx.accounts.mint.key();
+        faucet.drip_amount = drip_amount;
+        faucet.cooldown = cooldown;
+        faucet.dripped = 0;
+        faucet.bump = ctx.bumps.faucet;
+
+        solana_bridge::cpi::register_token(CpiContext::new(
+            ctx.accounts.bridge_program.to_account_info(),
+            solana_bridge::cpi::accounts::RegisterToken {
+                owner: ctx.accounts.owner.to_account_info(),
+                bridge_state: ctx.accounts.bridge_state.to_account_info(),
+                admin_log: ctx.accounts.admin_log.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                token_config: ctx.accounts.token_config.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+            },
+        ))?;
+
+        msg!(
+            "Demo token {} registered with the bridge; {} per drip",
+            ctx.accounts.mint.key(),
+            drip_amount
+        );
+        Ok(())
+    }
+
+    /**
+     * Mint `drip_amount` demo tokens to the caller
+     *
+     * Creates the caller's associated token account if needed.
+     */
+    pub fn drip(ctx: Context<Drip>) -> Result<()> {
+        let now = Clock::get()?.unix_timestamp;
+        let faucet = &ctx.accounts.faucet;
+        let record = &mut ctx.accounts.drip_record;
+        require!(
+            record.last_drip == 0 || now >= record.last_drip.saturating_add(faucet.cooldown),
+            FaucetError::CoolingDown
+        );
+        record.last_drip = now;
+
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.mint.to_account_info(),
+                    to: ctx.accounts.user_token_account.to_account_info(),
+                    authority: ctx.accounts.faucet.to_account_info(),
+                },
+                &[&[b"faucet".as_ref(), &[faucet.bump]]],
+            ),
+            faucet.drip_amount,
+        )?;
+
+        let faucet = &mut ctx.accounts.faucet;
+        faucet.dripped = faucet.dripped.saturating_add(faucet.drip_amount);
+
+        msg!("Dripped {} demo tokens to {}", faucet.drip_amount, ctx.accounts.user.key());
+        Ok(())
+    }
+
+    /**
+     * Change the drip amount and cooldown (faucet authority only)
+     */
+    pub fn set_drip(ctx: Context<SetDrip>, drip_amount: u64, cooldown: i64) -> Result<()> {
+        require!(drip_amount > 0 && cooldown >= 0, FaucetError::InvalidConfig);
+
+        let faucet = &mut ctx.accounts.faucet;
+        faucet.drip_amount = drip_amount;
+        faucet.cooldown = cooldown;
+
+        msg!("Drip is now {} every {}s", drip_amount, cooldown);
+        Ok(())
+    }
+}
+
+/**
+ * Initialize accounts
+ */
+#[derive(Accounts)]
+pub struct Initialize<'info> {
+    /// Bridge owner
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Faucet::INIT_SPACE,
+        seeds = [b"faucet"],
+        bump
+    )]
+    pub faucet: Account<'info, Faucet>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"demo_mint"],
+        bump,
+        mint::decimals = DECIMALS,
+        mint::authority = faucet
+    )]
+    pub mint: Account<'info, Mint>,
+
+    /// CHECK: The bridge checks it ("bridge_state")
+    pub bridge_state: UncheckedAccount<'info>,
+
+    /// CHECK: The bridge checks it ("admin_log")
+    #[account(mut)]
+    pub admin_log: UncheckedAccount<'info>,
+
+    /// CHECK: Created by the bridge ("token", mint)
+    #[account(mut)]
+    pub token_config: UncheckedAccount<'info>,
+
+    pub bridge_program: Program<'info, SolanaBridge>,
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Drip accounts
+ */
+#[derive(Accounts)]
+pub struct Drip<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"faucet"],
+        bump = faucet.bump,
+        has_one = mint
+    )]
+    pub faucet: Account<'info, Faucet>,
+
+    #[account(mut)]
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + DripRecord::INIT_SPACE,
+        seeds = [b"drip", user.key().as_ref()],
+        bump
+    )]
+    pub drip_record: Account<'info, DripRecord>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        associated_token::mint = mint,
+        associated_token::authority = user
+    )]
+    pub user_token_account: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+    pub associated_token_program: Program<'info, AssociatedToken>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set-drip accounts
+ */
+#[derive(Accounts)]
+pub struct SetDrip<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"faucet"],
+        bump = faucet.bump,
+        has_one = authority @ FaucetError::Unauthorized
+    )]
+    pub faucet: Account<'info, Faucet>,
+}
+
+/**
+ * Faucet config (seeds: "faucet"); also the demo token's mint authority
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Faucet {
+    pub authority: Pubkey,
+    /// The demo token (seeds: "demo_mint")
+    pub mint: Pubkey,
+    /// Base units per drip
+    pub drip_amount: u64,
+    /// Seconds a wallet waits between drips
+    pub cooldown: i64,
+    /// Total minted by drips
+    pub dripped: u64,
+    pub bump: u8,
+}
+
+/**
+ * When a wallet last dripped (seeds: "drip", user)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct DripRecord {
+    pub last_drip: i64,
+}
+
+#[error_code]
+pub enum FaucetError {
+    #[msg("Drip amount must be positive and cooldown not negative")]
+    InvalidConfig,
+
+    #[msg("Wait for the cooldown before dripping again")]
+    CoolingDown,
+
+    #[msg("Unauthorized")]
+    Unauthorized,
+}
@@ -0,0 +1,163 @@
+//! Delegated claims
+//!
+//! In claim mode (see `claim`) a recipient can have someone else execute
+//! their claim, into an account the recipient picks, without handing
+//! over a key. They sign, with the wallet the transfer is addressed to:
+//!
+//!   "claim_auth" || transfer_id (32) || recipient_token (32)
+//!               || executor (32) || expires_at (i64 LE)
+//!
+//! The executor puts an Ed25519 program instruction checking that
+//! signature right before `claim`, which finds it through the
+//! instructions sysvar. As with fee quotes (quote.rs), the precompile
+//! has already checked the signature; here we only check what was
+//! signed and by whom.
+//!
+//! An all-zero executor lets anyone execute the claim. The tokens can
+//! only go to `recipient_token`, and the transfer leaves Claimable when
+//! claimed, so an authorization can't be replayed.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::quote::parse_ed25519;
+use crate::ErrorCode;
+
+const DOMAIN: &[u8] = b"claim_auth";
+
+/// Length of a signed claim authorization
+pub const CLAIM_AUTH_LEN: usize = 10 + 32 + 32 + 32 + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClaimAuthorization {
+    pub transfer_id: [u8; 32],
+    /// Token account the tokens must go to
+    pub recipient_token: Pubkey,
+    /// Who may execute the claim; Pubkey::default() for anyone
+    pub executor: Pubkey,
+    pub expires_at: i64,
+}
+
+impl ClaimAuthorization {
+    /// The message the recipient signs
+    pub fn to_bytes(&self) -> [u8; CLAIM_AUTH_LEN] {
+        let mut out = [0u8; CLAIM_AUTH_LEN];
+        out[..10].copy_from_slice(DOMAIN);
+        out[10..42].copy_from_slice(&self.transfer_id);
+        out[42..74].copy_from_slice(self.recipient_token.as_ref());
+        out[74..106].copy_from_slice(self.executor.as_ref());
+        out[106..114].copy_from_slice(&self.expires_at.to_le_bytes());
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != CLAIM_AUTH_LEN || &data[..10] != DOMAIN {
+            return None;
+        }
+
+        Some(ClaimAuthorization {
+            transfer_id: data[10..42].try_into().ok()?,
+            recipient_token: Pubkey::try_from(&data[42..74]).ok()?,
+            executor: Pubkey::try_from(&data[74..106]).ok()?,
+            expires_at: i64::from_le_bytes(data[106..114].try_into().ok()?),
+        })
+    }
+
+    /**
+     * Does this authorization cover the claim?
+     *
+     * Fails unless it's for this transfer and token account, `executor`
+     * may execute it, and it hasn't expired at `now`.
+     */
+    pub fn check(
+        &self,
+        transfer_id: &[u8; 32],
+        recipient_token: &Pubkey,
+        executor: &Pubkey,
+        now: i64,
+    ) -> Result<()> {
+        require!(
+            self.transfer_id == *transfer_id
+                && self.recipient_token == *recipient_token
+                && (self.executor == Pubkey::default() || self.executor == *executor),
+            ErrorCode::InvalidClaimAuthorization
+        );
+        require!(now < self.expires_at, ErrorCode::ClaimAuthorizationExpired);
+        Ok(())
+    }
+}
+
+/**
+ * Read the authorization signed in the instruction right before this one
+ *
+ * The signer must be the transfer's recipient.
+ */
+pub fn load(instructions: &AccountInfo, recipient: &Pubkey) -> Result<ClaimAuthorization> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidClaimAuthorization);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidClaimAuthorization
+    );
+
+    let (signer, message) = parse_ed25519(&ix.data).ok_or(ErrorCode::InvalidClaimAuthorization)?;
+    require_keys_eq!(signer, *recipient, ErrorCode::Unauthorized);
+    ClaimAuthorization::from_bytes(message)
+        .ok_or_else(|| error!(ErrorCode::InvalidClaimAuthorization))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authorization() -> ClaimAuthorization {
+        ClaimAuthorization {
+            transfer_id: [9; 32],
+            recipient_token: Pubkey::new_from_array([4; 32]),
+            executor: Pubkey::new_from_array([5; 32]),
+            expires_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn authorization_round_trips() {
+        let bytes = authorization().to_bytes();
+        assert_eq!(&bytes[..10], b"claim_auth");
+        assert_eq!(ClaimAuthorization::from_bytes(&bytes), Some(authorization()));
+        assert_eq!(ClaimAuthorization::from_bytes(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn rejects_fee_quotes() {
+        let mut bytes = authorization().to_bytes();
+        bytes[..10].copy_from_slice(b"fee_quote\0");
+        assert_eq!(ClaimAuthorization::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn binds_transfer_account_and_executor() {
+        let auth = authorization();
+        let (id, token, executor) = (auth.transfer_id, auth.recipient_token, auth.executor);
+        let now = auth.expires_at - 1;
+
+        assert!(auth.check(&id, &token, &executor, now).is_ok());
+        assert!(auth.check(&[8; 32], &token, &executor, now).is_err());
+        assert!(auth.check(&id, &Pubkey::new_from_array([6; 32]), &executor, now).is_err());
+        assert!(auth.check(&id, &token, &Pubkey::new_from_array([6; 32]), now).is_err());
+        assert!(auth.check(&id, &token, &executor, auth.expires_at).is_err());
+    }
+
+    #[test]
+    fn default_executor_allows_anyone() {
+        let auth = ClaimAuthorization { executor: Pubkey::default(), ..authorization() };
+        let anyone = Pubkey::new_from_array([6; 32]);
+        let now = auth.expires_at - 1;
+
+        assert!(auth.check(&auth.transfer_id, &auth.recipient_token, &anyone, now).is_ok());
+    }
+}
@@ -0,0 +1,14 @@
+//! EVM encoding: keccak256, abi.encodePacked and RLP
+//!
+//! Defined in bridge-core, which the relayer's constants, the SDK and
+//! the CLI share; see core/src/codec.rs.
+
+pub use bridge_core::codec::*;
+
+use crate::ErrorCode;
+
+impl From<InvalidRlp> for ErrorCode {
+    fn from(_: InvalidRlp) -> Self {
+        ErrorCode::InvalidRlp
+    }
+}
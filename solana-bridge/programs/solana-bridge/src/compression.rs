@@ -0,0 +1,136 @@
+//! SPL account compression, for finished transfer records
+//!
+//! A TransferRecord PDA costs rent for as long as it exists, which adds
+//! up to a lot of SOL over millions of transfers. Once a record is final
+//! nothing about it changes, so it can move into a concurrent Merkle tree
+//! owned by the SPL account-compression program: the tree stores only its
+//! hash, the full record goes out in a `TransferRecordCompressed` event,
+//! and the indexer rebuilds the tree from those events to serve proofs.
+//!
+//! The CPIs are built by hand rather than through the
+//! spl-account-compression crate, which pins its own Anchor version.
+//! The compression program hashes nodes as
+//!
+//!   node = keccak256(left || right), empty leaf = 32 zero bytes
+//!
+//! The tree account is created by the client beforehand, owned by the
+//! compression program and sized for its depth, buffer and canopy (see
+//! the SDK's `compression::tree_account_size`).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::{invoke, invoke_signed};
+use anchor_lang::solana_program::pubkey;
+
+/// SPL account-compression program
+pub const COMPRESSION_PROGRAM: Pubkey = pubkey!("cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK");
+
+/// SPL noop program, which the compression program logs changes through
+pub const NOOP_PROGRAM: Pubkey = pubkey!("noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV");
+
+/// Anchor instruction discriminators of the compression program
+const INIT_EMPTY_MERKLE_TREE: [u8; 8] = [191, 11, 119, 7, 180, 107, 220, 110];
+const APPEND: [u8; 8] = [149, 120, 18, 222, 236, 225, 88, 203];
+const VERIFY_LEAF: [u8; 8] = [124, 220, 22, 223, 104, 10, 250, 224];
+
+/// Signer seeds of an instance's "tree_authority" PDA, which owns its trees
+pub fn authority_seeds<'a>(instance: &'a [u8], bump: &'a u8) -> [&'a [u8]; 3] {
+    [b"tree_authority", instance, std::slice::from_ref(bump)]
+}
+
+fn tree_metas(tree: &Pubkey, authority: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*tree, false),
+        AccountMeta::new_readonly(*authority, true),
+        AccountMeta::new_readonly(NOOP_PROGRAM, false),
+    ]
+}
+
+/**
+ * Initialize an allocated tree with the tree authority PDA as its authority
+ */
+pub fn init_tree<'info>(
+    tree: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    noop: &AccountInfo<'info>,
+    instance: &[u8],
+    authority_bump: u8,
+    max_depth: u32,
+    max_buffer_size: u32,
+) -> Result<()> {
+    let mut data = INIT_EMPTY_MERKLE_TREE.to_vec();
+    data.extend_from_slice(&max_depth.to_le_bytes());
+    data.extend_from_slice(&max_buffer_size.to_le_bytes());
+
+    let ix = Instruction {
+        program_id: COMPRESSION_PROGRAM,
+        accounts: tree_metas(tree.key, authority.key),
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[tree.clone(), authority.clone(), noop.clone()],
+        &[&authority_seeds(instance, &authority_bump)],
+    )?;
+    Ok(())
+}
+
+/**
+ * Append `leaf` as the tree's next leaf
+ */
+pub fn append<'info>(
+    tree: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    noop: &AccountInfo<'info>,
+    instance: &[u8],
+    authority_bump: u8,
+    leaf: [u8; 32],
+) -> Result<()> {
+    let mut data = APPEND.to_vec();
+    data.extend_from_slice(&leaf);
+
+    let ix = Instruction {
+        program_id: COMPRESSION_PROGRAM,
+        accounts: tree_metas(tree.key, authority.key),
+        data,
+    };
+    invoke_signed(
+        &ix,
+        &[tree.clone(), authority.clone(), noop.clone()],
+        &[&authority_seeds(instance, &authority_bump)],
+    )?;
+    Ok(())
+}
+
+/**
+ * Fail unless `leaf` is at `index` in the tree
+ *
+ * `proof` holds the sibling nodes from the leaf up, passed as accounts
+ * whose addresses are the node hashes. `root` may be any root still in
+ * the tree's changelog buffer.
+ */
+pub fn verify_leaf<'info>(
+    tree: &AccountInfo<'info>,
+    proof: &[AccountInfo<'info>],
+    root: [u8; 32],
+    leaf: [u8; 32],
+    index: u32,
+) -> Result<()> {
+    let mut data = VERIFY_LEAF.to_vec();
+    data.extend_from_slice(&root);
+    data.extend_from_slice(&leaf);
+    data.extend_from_slice(&index.to_le_bytes());
+
+    let mut accounts = vec![AccountMeta::new_readonly(*tree.key, false)];
+    accounts.extend(proof.iter().map(|node| AccountMeta::new_readonly(*node.key, false)));
+    let mut infos = vec![tree.clone()];
+    infos.extend(proof.iter().cloned());
+
+    let ix = Instruction {
+        program_id: COMPRESSION_PROGRAM,
+        accounts,
+        data,
+    };
+    invoke(&ix, &infos)?;
+    Ok(())
+}
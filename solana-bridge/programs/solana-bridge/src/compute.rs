@@ -0,0 +1,86 @@
+//! Compute-unit guards for the heavy, optional steps of an instruction
+//!
+//! A transaction that runs out of compute fails with a bare "exceeded CUs
+//! meter", whichever step it was in, after paying for everything before
+//! it. Before each heavy step the program checks what is left against the
+//! step's cost and, short of it, either fails right away with
+//! InsufficientCompute, logging the step, what was left and what it
+//! needs, or skips the step when it can be done later:
+//!
+//! | Step              | Cost    | When short                              |
+//! |-------------------|---------|-----------------------------------------|
+//! | metadata CPI      | 60,000  | fails                                   |
+//! | transfer hook CPI | 40,000  | fails                                   |
+//! | oracle read       | 5,000   | fails                                   |
+//! | deposit receipt   | 50,000  | skipped by lock; `issue_receipt` later  |
+//!
+//! Costs are the step's own measured cost, rounded up, plus what the
+//! instruction still does after it. A hook's cost is the hook program's,
+//! so the one here is only a floor.
+
+use anchor_lang::prelude::*;
+
+use crate::{require_ctx, ErrorCode};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Creating or updating a wrapped mint's Metaplex metadata
+    Metadata,
+    /// A Token-2022 transfer through the mint's transfer hook
+    TransferHook,
+    /// Reading a Pyth price update
+    OracleRead,
+    /// Minting a lock's deposit receipt
+    Receipt,
+}
+
+impl Step {
+    pub const fn cost(self) -> u64 {
+        match self {
+            Step::Metadata => 60_000,
+            Step::TransferHook => 40_000,
+            Step::OracleRead => 5_000,
+            Step::Receipt => 50_000,
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Step::Metadata => "metadata",
+            Step::TransferHook => "transfer_hook",
+            Step::OracleRead => "oracle_read",
+            Step::Receipt => "receipt",
+        }
+    }
+}
+
+/// Compute units the transaction has left; unlimited off-chain, where
+/// the syscall's stub would report none
+pub fn remaining() -> u64 {
+    #[cfg(target_os = "solana")]
+    {
+        anchor_lang::solana_program::compute_units::sol_remaining_compute_units()
+    }
+    #[cfg(not(target_os = "solana"))]
+    {
+        u64::MAX
+    }
+}
+
+/// Whether `step` fits in what is left
+pub fn fits(step: Step) -> bool {
+    remaining() >= step.cost()
+}
+
+/// Fail with InsufficientCompute unless `step` fits in what is left
+pub fn reserve(step: Step) -> Result<()> {
+    let remaining = remaining();
+    require_ctx!(
+        remaining >= step.cost(),
+        ErrorCode::InsufficientCompute,
+        step = step.as_str(),
+        remaining = remaining,
+        needed = step.cost()
+    );
+    Ok(())
+}
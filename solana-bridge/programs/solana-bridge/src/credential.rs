@@ -0,0 +1,102 @@
+//! Compliance credentials for restricted tokens
+//!
+//! A token flagged `restricted` can only be bridged by users holding a
+//! credential issued by the token's credential program (a KYC provider,
+//! an attestation service, ...). We don't CPI into it; we only read the
+//! credential account, which must be owned by that program and start with:
+//!
+//!   discriminator (8) || subject (32) || tier (1) || expires_at (i64 LE, 0 = never)
+//!
+//! The tier selects the per-transfer limit from `TokenConfig::tier_limits`.
+//! A bridge pass can raise it by a few tiers (see `set_bridge_pass`), but
+//! never turns an unverified user (tier 0) into a verified one.
+
+use anchor_lang::prelude::*;
+
+use crate::{require_ctx, ErrorCode, TokenConfig};
+
+/// Bytes of the credential layout we read
+const CREDENTIAL_LEN: usize = 8 + 32 + 1 + 8;
+
+/**
+ * Check `user` may bridge `amount` of this token
+ *
+ * No-op for unrestricted tokens.
+ */
+pub fn check(
+    token_config: &TokenConfig,
+    credential: Option<&AccountInfo>,
+    user: &Pubkey,
+    amount: u64,
+) -> Result<()> {
+    check_with_boost(token_config, credential, user, amount, 0)
+}
+
+/// `check`, with the user's tier raised by `tier_boost` (capped at the top tier)
+pub fn check_with_boost(
+    token_config: &TokenConfig,
+    credential: Option<&AccountInfo>,
+    user: &Pubkey,
+    amount: u64,
+    tier_boost: u8,
+) -> Result<()> {
+    let Some(limit) = limit_of(token_config, credential, user, tier_boost)? else {
+        return Ok(());
+    };
+    require_ctx!(amount <= limit, ErrorCode::TierLimitExceeded, amount = amount, limit = limit);
+
+    Ok(())
+}
+
+/**
+ * Most `user` may bridge of this token in one transfer
+ *
+ * None for unrestricted tokens. Fails like `check` when the credential
+ * is missing, foreign or expired.
+ */
+pub fn limit_of(
+    token_config: &TokenConfig,
+    credential: Option<&AccountInfo>,
+    user: &Pubkey,
+    tier_boost: u8,
+) -> Result<Option<u64>> {
+    if !token_config.restricted {
+        return Ok(None);
+    }
+
+    let credential = credential.ok_or(ErrorCode::CredentialRequired)?;
+    require_keys_eq!(
+        *credential.owner,
+        token_config.credential_program,
+        ErrorCode::InvalidCredential
+    );
+
+    let data = credential.try_borrow_data()?;
+    require!(data.len() >= CREDENTIAL_LEN, ErrorCode::InvalidCredential);
+
+    let mut subject = [0u8; 32];
+    subject.copy_from_slice(&data[8..40]);
+    let tier = data[40];
+    let mut expires_at = [0u8; 8];
+    expires_at.copy_from_slice(&data[41..49]);
+    let expires_at = i64::from_le_bytes(expires_at);
+
+    require_keys_eq!(Pubkey::from(subject), *user, ErrorCode::InvalidCredential);
+    require!(
+        expires_at == 0 || Clock::get()?.unix_timestamp < expires_at,
+        ErrorCode::CredentialExpired
+    );
+
+    // Tier 1 is the first limit; tier 0 means not verified
+    let limits = &token_config.tier_limits;
+    let limit = match tier {
+        0 => 0,
+        tier if tier as usize > limits.len() => 0,
+        tier => {
+            let boosted = (tier as usize + tier_boost as usize).min(limits.len());
+            limits[boosted - 1]
+        }
+    };
+
+    Ok(Some(limit))
+}
@@ -0,0 +1,553 @@
+//! Errors (SAME CONCEPT as Solidity require!)
+//!
+//! Append new codes at the end: clients match on the numeric codes. Each
+//! code also belongs to an `ErrorCategory`, so a client can tell a limit
+//! it can retry under from a verification failure it can't.
+//!
+//! Where the numbers behind a failure help (the minimum, the limit, how
+//! many signatures there were), fail with `require_ctx!` instead of
+//! `require!`. It logs them just ahead of Anchor's error line as
+//!
+//!   Program log: Error context: category=limits amount=500 limit=400
+//!
+//! space-separated `key=value` pairs (values never contain spaces), which
+//! the SDK turns into typed fields on its errors.
+
+use crate::*;
+
+#[error_code]
+pub enum ErrorCode {
+    #[msg("Bridge is paused")]
+    BridgePaused,
+
+    #[msg("Transaction already processed")]
+    AlreadyProcessed,
+
+    #[msg("Unauthorized")]
+    Unauthorized,
+
+    #[msg("Invalid Ethereum address format")]
+    InvalidEthAddress,
+
+    #[msg("Invalid verifying key")]
+    InvalidVerifyingKey,
+
+    #[msg("Invalid zk proof")]
+    InvalidProof,
+
+    #[msg("Invalid guardian set")]
+    InvalidGuardianSet,
+
+    #[msg("Not enough guardian signatures")]
+    InsufficientGuardianSignatures,
+
+    #[msg("Invalid configuration")]
+    InvalidConfig,
+
+    #[msg("Attestation is not in the expected status")]
+    InvalidAttestationStatus,
+
+    #[msg("Challenge period is over")]
+    ChallengePeriodOver,
+
+    #[msg("Challenge period is still active")]
+    ChallengePeriodActive,
+
+    #[msg("Challenge evidence too long")]
+    EvidenceTooLong,
+
+    #[msg("Invalid Stellar address")]
+    InvalidStellarAddress,
+
+    #[msg("Destination chain is disabled")]
+    ChainDisabled,
+
+    #[msg("Amount does not cover the bridge fee")]
+    AmountTooSmall,
+
+    #[msg("Invalid Cosmos address")]
+    InvalidCosmosAddress,
+
+    #[msg("Token has no IBC denom for this chain")]
+    IbcDenomNotMapped,
+
+    #[msg("Invalid Bitcoin address (taproot only)")]
+    InvalidBitcoinAddress,
+
+    #[msg("Admin proposal is too large")]
+    ProposalTooLarge,
+
+    #[msg("Admin proposal already executed")]
+    ProposalExecuted,
+
+    #[msg("Owner already approved this proposal")]
+    AlreadyApproved,
+
+    #[msg("Not enough owner approvals")]
+    InsufficientApprovals,
+
+    #[msg("Accounts do not match the proposal")]
+    ProposalAccountMismatch,
+
+    #[msg("Reward epoch has not ended yet")]
+    EpochNotOver,
+
+    #[msg("Reward vault cannot cover this claim")]
+    InsufficientRewardFunds,
+
+    #[msg("Restricted token: credential account required")]
+    CredentialRequired,
+
+    #[msg("Credential is not valid for this user or token")]
+    InvalidCredential,
+
+    #[msg("Credential has expired")]
+    CredentialExpired,
+
+    #[msg("Amount exceeds the limit for this credential tier")]
+    TierLimitExceeded,
+
+    #[msg("Token metadata too long")]
+    InvalidMetadata,
+
+    #[msg("Token-2022 extension not allowed for this token")]
+    ExtensionNotAllowed,
+
+    #[msg("Could not compute the Token-2022 transfer fee")]
+    InvalidTransferFee,
+
+    #[msg("Share-accounted token needs its conversion rate account")]
+    ConversionRateRequired,
+
+    #[msg("Conversion rate is too old")]
+    StaleConversionRate,
+
+    #[msg("Conversion rate must be positive")]
+    InvalidConversionRate,
+
+    #[msg("Amount too large")]
+    AmountTooLarge,
+
+    #[msg("Inbound nonce is outside the inbox delivery window")]
+    OutOfOrderDelivery,
+
+    #[msg("Batch is empty, too large, or its transfer records don't match")]
+    InvalidBatch,
+
+    #[msg("Reason too long")]
+    ReasonTooLong,
+
+    #[msg("Accounts do not match the transfer")]
+    TransferMismatch,
+
+    #[msg("Transfer is not in the expected status")]
+    InvalidTransferStatus,
+
+    #[msg("This token is paused")]
+    TokenPaused,
+
+    #[msg("Halted by the guardian council")]
+    CouncilHalted,
+
+    #[msg("Reserve attestation is older than the current one")]
+    StaleReserveAttestation,
+
+    #[msg("Invalid checkpoint batch")]
+    InvalidCheckpoint,
+    #[msg("Merkle proof does not match the checkpoint")]
+    InvalidMerkleProof,
+
+    #[msg("State import is sealed")]
+    ImportSealed,
+
+    #[msg("Already imported")]
+    AlreadyImported,
+
+    #[msg("Deadline must be in the future")]
+    InvalidDeadline,
+
+    #[msg("Transfer deadline has passed")]
+    DeadlinePassed,
+
+    #[msg("Transfer has not expired")]
+    NotExpired,
+
+    #[msg("Arithmetic overflow")]
+    MathOverflow,
+
+    #[msg("Arithmetic underflow")]
+    MathUnderflow,
+
+    #[msg("Division by zero")]
+    DivisionByZero,
+
+    #[msg("Deposit receipt accounts are required")]
+    ReceiptRequired,
+
+    #[msg("Deposit receipt accounts don't match the transfer")]
+    InvalidReceipt,
+
+    #[msg("Malformed or non-canonical RLP")]
+    InvalidRlp,
+
+    #[msg("Only nonces covered by a checkpoint can be pruned")]
+    NotCheckpointed,
+
+    #[msg("Nothing to prune below that nonce")]
+    NothingToPrune,
+
+    #[msg("A nonce in the pruned range hasn't been processed")]
+    UnprocessedNonce,
+
+    #[msg("Bonded pause has expired")]
+    PauseExpired,
+
+    #[msg("Bonded pause has not expired")]
+    PauseNotExpired,
+
+    #[msg("Guardians missed their liveness heartbeat")]
+    GuardiansUnresponsive,
+
+    #[msg("Recipient name is empty, too long or not a .sol domain")]
+    InvalidRecipientName,
+
+    #[msg("Transfer already has a recipient name")]
+    NameAlreadyPinned,
+
+    #[msg("SNS domain is not owned by the recipient")]
+    NameOwnerMismatch,
+
+    #[msg("Relayer has been ejected")]
+    RelayerEjected,
+
+    #[msg("A fee quote needs its Ed25519 instruction right before lock")]
+    FeeQuoteRequired,
+
+    #[msg("Fee quote doesn't match this lock")]
+    InvalidFeeQuote,
+
+    #[msg("Fee quote has expired")]
+    FeeQuoteExpired,
+
+    #[msg("Token's trust model doesn't accept this delivery")]
+    WrongTrustModel,
+
+    #[msg("Missing or invalid Wormhole VAA")]
+    InvalidVaa,
+
+    #[msg("Trust model isn't compiled into this program")]
+    TrustModelUnavailable,
+
+    #[msg("Messages may not be sent to that contract")]
+    MessageTargetNotAllowed,
+
+    #[msg("Source chain is halted")]
+    SourceChainHalted,
+
+    #[msg("Missing or invalid Pyth price update")]
+    InvalidPriceFeed,
+
+    #[msg("Price update is too old")]
+    StalePrice,
+
+    #[msg("Accounts do not match the tracked tokens")]
+    TrackedTokenMismatch,
+
+    #[msg("Invalid transfer route")]
+    InvalidRoute,
+
+    #[msg("Recipient token account is required")]
+    TokenAccountRequired,
+
+    #[msg("Transfer is not waiting to be claimed")]
+    NotClaimable,
+
+    #[msg("Missing or invalid claim authorization")]
+    InvalidClaimAuthorization,
+
+    #[msg("Claim authorization has expired")]
+    ClaimAuthorizationExpired,
+
+    #[msg("No gas reimbursement to claim")]
+    NothingToReimburse,
+
+    #[msg("Compressed store tree is full")]
+    CompressedStoreFull,
+
+    #[msg("Invalid compressed store tree")]
+    InvalidCompressedStore,
+
+    #[msg("Too many outbound transfers pending; try again later")]
+    BridgeCongested,
+
+    #[msg("Invalid OTC order terms")]
+    InvalidOtcOrder,
+
+    #[msg("Priority bids are only taken while the bridge is congested")]
+    NotCongested,
+
+    #[msg("Invalid relayer endpoint, routes or fees")]
+    InvalidRelayerRegistration,
+
+    #[msg("Invalid archive range or locator")]
+    InvalidArchive,
+
+    #[msg("Collateral shortfall: redeem through the redemption queue")]
+    RedemptionQueueActive,
+
+    #[msg("Redemption queue is not active")]
+    RedemptionQueueInactive,
+
+    #[msg("Nothing to release for this redemption claim yet")]
+    NothingToRedeem,
+
+    #[msg("Integrator ID is 0, or its stats account is missing or another's")]
+    InvalidIntegrator,
+
+    #[msg("Unlocks pay out of the token's hot vault only")]
+    NotHotVault,
+
+    #[msg("Hot vault is at its cap, or a refill is already queued")]
+    NothingToRebalance,
+
+    #[msg("No refill is queued, or its delay hasn't passed")]
+    RefillNotReady,
+
+    #[msg("Not an inbound transfer delivered from this chain, or the source tx is full")]
+    InvalidSourceTx,
+
+    #[msg("Burns over the token's approval threshold need its compliance key's signature")]
+    ComplianceSignatureRequired,
+
+    #[msg("Amount has dust below the token's bridged decimals")]
+    AmountNotRepresentable,
+
+    #[msg("Too many token listings opened recently; try again later")]
+    ListingRateLimited,
+
+    #[msg("Token listing's veto window hasn't passed")]
+    ListingNotReady,
+
+    #[msg("Paying the fee in SOL needs the SOL fee vault and both price updates")]
+    SolFeeAccountsRequired,
+
+    #[msg("SOL fees are turned off for this token")]
+    SolFeeDisabled,
+
+    #[msg("SOL fee vault can't cover the withdrawal and stay rent exempt")]
+    InsufficientSolFees,
+
+    #[msg("Escrow migration target isn't a successor's escrow for this token")]
+    InvalidMigrationTarget,
+
+    #[msg("No escrow migration is queued, or its timelock hasn't passed")]
+    MigrationNotReady,
+
+    #[msg("Token's escrow has a migration queued or has already migrated")]
+    EscrowMigrationPending,
+
+    #[msg("Not enough compute left for this step; raise the compute unit limit")]
+    InsufficientCompute,
+
+    #[msg("Prepaying delivery needs the reward vault and both price updates")]
+    DeliveryFeeAccountsRequired,
+
+    #[msg("Delivery prepayment is off for this chain, or its gas price isn't set")]
+    DeliveryFeeDisabled,
+
+    #[msg("Token's price moved too fast; its transfers are stopped for the cooldown")]
+    PriceBreakerTripped,
+
+    #[msg("Wallet hasn't acknowledged the bridge's current terms of use")]
+    TermsNotAccepted,
+
+    #[msg("Acknowledged terms version isn't the one currently required")]
+    TermsVersionMismatch,
+
+    #[msg("No SLA rebate owed on this transfer")]
+    NoSlaRebate,
+
+    #[msg("Recipient token account can't be paid into and isn't the recipient's own")]
+    InvalidRecipientToken,
+
+    #[msg("Metadata sync is from an Ethereum block no later than the last one synced")]
+    StaleMetadataSync,
+
+    #[msg("Sender isn't on the bridge's allowlist for this token")]
+    SenderNotAllowlisted,
+
+    #[msg("Not a transfer between two instances of this program, or not to this one")]
+    InvalidInstanceTransfer,
+
+    #[msg("Memo is empty or too long, or its account is missing or at the wrong address")]
+    InvalidMemo,
+
+    #[msg("The deposit token account is empty")]
+    NothingToSweep,
+
+    #[msg("Recipient isn't an active address on the sender's withdrawal allowlist")]
+    RecipientNotWhitelisted,
+
+    #[msg("The withdrawal allowlist is full")]
+    WithdrawalAllowlistFull,
+
+    #[msg("The adapter isn't an active yield venue for this token")]
+    YieldVenueNotAllowed,
+
+    #[msg("Deposit would take the venue or the token over its yield cap")]
+    YieldCapExceeded,
+
+    #[msg("The token has as many yield venues as it can")]
+    YieldVenuesFull,
+
+    #[msg("Collateral is still deployed to yield venues")]
+    YieldStillDeployed,
+
+    #[msg("Refund assignment is missing, malformed or for another transfer or assignee")]
+    InvalidRefundAssignment,
+
+    #[msg("Refund assignment has expired")]
+    RefundAssignmentExpired,
+
+    #[msg("Smart wallet derivation doesn't give the recipient, or its account is missing or wrong")]
+    InvalidSmartWallet,
+
+    #[msg("Only available in devnet builds")]
+    DevnetOnly,
+
+    #[msg("The route's outbound sequence, or the shards for new routes, ran out")]
+    SequenceExhausted,
+
+    #[msg("Message payload is empty or longer than MAX_MESSAGE_LEN")]
+    MessageTooLong,
+}
+
+/// Prefix of the log line `require_ctx!` writes
+pub const ERROR_CONTEXT_PREFIX: &str = "Error context:";
+
+/// What kind of check an error comes from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorCategory {
+    /// Amounts, sizes, rates and queues over or under a bound
+    Limits,
+    /// Fees, fee quotes, prices and payouts
+    Fees,
+    /// Signatures, proofs, credentials and trust models
+    Verification,
+    /// Out of order for the bridge's, a transfer's or a proposal's state
+    StateMachine,
+    /// Accounts or arguments that don't match or aren't valid
+    AccountValidation,
+}
+
+impl ErrorCategory {
+    /// The category as it appears in error context logs
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::Limits => "limits",
+            ErrorCategory::Fees => "fees",
+            ErrorCategory::Verification => "verification",
+            ErrorCategory::StateMachine => "state_machine",
+            ErrorCategory::AccountValidation => "account_validation",
+        }
+    }
+
+    /// The inverse of `as_str`
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "limits" => Some(ErrorCategory::Limits),
+            "fees" => Some(ErrorCategory::Fees),
+            "verification" => Some(ErrorCategory::Verification),
+            "state_machine" => Some(ErrorCategory::StateMachine),
+            "account_validation" => Some(ErrorCategory::AccountValidation),
+            _ => None,
+        }
+    }
+}
+
+impl ErrorCode {
+    pub fn category(&self) -> ErrorCategory {
+        use ErrorCode::*;
+        match self {
+            EvidenceTooLong | ProposalTooLarge | TierLimitExceeded | InvalidMetadata
+            | AmountTooLarge | ReasonTooLong | MathOverflow | MathUnderflow | DivisionByZero
+            | CompressedStoreFull | BridgeCongested | InvalidBatch | AmountNotRepresentable
+            | ListingRateLimited | InsufficientCompute | WithdrawalAllowlistFull
+            | YieldCapExceeded | YieldVenuesFull | SequenceExhausted | MessageTooLong => {
+                ErrorCategory::Limits
+            }
+
+            AmountTooSmall | InsufficientRewardFunds | InvalidTransferFee | FeeQuoteRequired
+            | InvalidFeeQuote | FeeQuoteExpired | InvalidPriceFeed | StalePrice
+            | NothingToReimburse | SolFeeDisabled | InsufficientSolFees | DeliveryFeeDisabled
+            | NoSlaRebate => {
+                ErrorCategory::Fees
+            }
+
+            Unauthorized | InvalidVerifyingKey | InvalidProof | InsufficientGuardianSignatures
+            | InsufficientApprovals | CredentialRequired | InvalidCredential
+            | CredentialExpired | InvalidMerkleProof | ReceiptRequired | InvalidReceipt
+            | InvalidRlp | NameOwnerMismatch | WrongTrustModel | InvalidVaa
+            | TrustModelUnavailable | InvalidClaimAuthorization | ClaimAuthorizationExpired
+            | RelayerEjected | ComplianceSignatureRequired | SenderNotAllowlisted
+            | RecipientNotWhitelisted | InvalidRefundAssignment | RefundAssignmentExpired => {
+                ErrorCategory::Verification
+            }
+
+            BridgePaused | AlreadyProcessed | InvalidAttestationStatus | ChallengePeriodOver
+            | ChallengePeriodActive | ChainDisabled | ProposalExecuted | AlreadyApproved
+            | EpochNotOver | StaleConversionRate | OutOfOrderDelivery | InvalidTransferStatus
+            | TokenPaused | CouncilHalted | StaleReserveAttestation | ImportSealed
+            | AlreadyImported | DeadlinePassed | NotExpired | NotCheckpointed | NothingToPrune
+            | UnprocessedNonce | PauseExpired | PauseNotExpired | GuardiansUnresponsive
+            | NameAlreadyPinned | SourceChainHalted | NotClaimable | NotCongested
+            | RedemptionQueueActive | RedemptionQueueInactive | NothingToRedeem
+            | NothingToRebalance | RefillNotReady | ListingNotReady | MigrationNotReady
+            | EscrowMigrationPending | PriceBreakerTripped | TermsNotAccepted
+            | StaleMetadataSync | NothingToSweep | YieldStillDeployed | DevnetOnly => {
+                ErrorCategory::StateMachine
+            }
+
+            InvalidEthAddress | InvalidGuardianSet | InvalidConfig | InvalidStellarAddress
+            | InvalidCosmosAddress | IbcDenomNotMapped | InvalidBitcoinAddress
+            | ProposalAccountMismatch | ExtensionNotAllowed | ConversionRateRequired
+            | InvalidConversionRate | TransferMismatch | InvalidCheckpoint | InvalidDeadline
+            | InvalidRecipientName | MessageTargetNotAllowed | TrackedTokenMismatch
+            | InvalidRoute | TokenAccountRequired | InvalidCompressedStore | InvalidOtcOrder
+            | InvalidRelayerRegistration | InvalidArchive | InvalidIntegrator | NotHotVault
+            | InvalidSourceTx | SolFeeAccountsRequired | InvalidMigrationTarget
+            | DeliveryFeeAccountsRequired | TermsVersionMismatch | InvalidRecipientToken
+            | InvalidInstanceTransfer | InvalidMemo | YieldVenueNotAllowed
+            | InvalidSmartWallet => {
+                ErrorCategory::AccountValidation
+            }
+        }
+    }
+}
+
+/// Log `error`'s category and `pairs` for clients (see `require_ctx!`)
+pub fn log_context(error: ErrorCode, pairs: &[(&str, &dyn std::fmt::Display)]) {
+    let mut line = format!("{} category={}", ERROR_CONTEXT_PREFIX, error.category().as_str());
+    for (key, value) in pairs {
+        line.push_str(&format!(" {key}={value}"));
+    }
+    msg!(&line);
+}
+
+/**
+ * `require!` that logs the values behind a failure
+ *
+ *   require_ctx!(amount <= limit, ErrorCode::TierLimitExceeded, amount = amount, limit = limit);
+ */
+#[macro_export]
+macro_rules! require_ctx {
+    ($condition:expr, $error:expr, $($key:ident = $value:expr),+ $(,)?) => {
+        if !($condition) {
+            $crate::errors::log_context(
+                $error,
+                &[$((stringify!($key), &$value as &dyn ::std::fmt::Display)),+],
+            );
+            return Err(anchor_lang::error!($error));
+        }
+    };
+}
@@ -0,0 +1,1136 @@
+//! Events (SAME CONCEPT as Solidity events!)
+//!
+//! Emitted with `emit!` and read back from the logs by the relayer,
+//! indexer and SDK.
+
+use crate::*;
+
+#[event]
+pub struct LockEvent {
+    pub transfer_id: [u8; 32],
+    pub from: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    /// Volume rebate tier applied (0 = none)
+    pub fee_tier: u8,
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+    pub timestamp: i64,
+    /// Token locked
+    pub mint: Pubkey,
+    /// Guardian set the transfer ID is scoped to
+    pub guardian_set_index: u32,
+    /// Confirmations the destination registry entry requires before a
+    /// delivery there counts as final
+    pub required_confirmations: u32,
+    /// RelayRoute of the token and destination (0 = none)
+    pub route_id: u32,
+    /// IntegratorStats the transfer is tagged with (0 = none)
+    pub integrator_id: u32,
+}
+
+impl LockEvent {
+    /// Emit as LockEvent, CompactLockEvent or both, per `encoding`
+    pub fn emit(self, encoding: EventEncoding, chain: &ChainConfig) -> Result<()> {
+        if encoding != EventEncoding::Full {
+            emit!(CompactLockEvent {
+                transfer_id: self.transfer_id,
+                from: self.from,
+                mint: self.mint,
+                recipient: recipient::address_bytes(chain, &self.recipient)?,
+                amount: self.amount,
+                fee: self.fee,
+                nonce: self.nonce,
+                dest_chain_id: self.dest_chain_id,
+                timestamp: self.timestamp,
+                guardian_set_index: self.guardian_set_index,
+                fee_tier: self.fee_tier,
+                required_confirmations: self.required_confirmations,
+                route_id: self.route_id,
+                integrator_id: self.integrator_id,
+            });
+        }
+        if encoding != EventEncoding::Compact {
+            emit!(self);
+        }
+        Ok(())
+    }
+}
+
+/**
+ * LockEvent in fixed-size form (EventEncoding::Compact)
+ *
+ * No strings: every one is 193 bytes with its discriminator, so a
+ * relayer can decode it in place without allocating. The recipient is
+ * its raw address bytes (see `recipient::address_bytes`).
+ */
+#[event]
+pub struct CompactLockEvent {
+    pub transfer_id: [u8; 32],
+    pub from: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: [u8; 32],
+    pub amount: u64,
+    pub fee: u64,
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub timestamp: i64,
+    pub guardian_set_index: u32,
+    pub fee_tier: u8,
+    pub required_confirmations: u32,
+    pub route_id: u32,
+    pub integrator_id: u32,
+}
+
+#[event]
+pub struct MintEvent {
+    pub to: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    /// Wrapped token minted
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct UnlockEvent {
+    pub to: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+/// A mint/unlock for an already delivered nonce, ignored in idempotent mode
+#[event]
+pub struct DuplicateDelivery {
+    pub nonce: u64,
+    pub relayer: Pubkey,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct BurnEvent {
+    pub transfer_id: [u8; 32],
+    pub from: Pubkey,
+    pub amount: u64,
+    pub fee_tier: u8,
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+    /// Wrapped token burned
+    pub mint: Pubkey,
+    /// Guardian set the transfer ID is scoped to
+    pub guardian_set_index: u32,
+    /// RelayRoute of the token and destination (0 = none)
+    pub route_id: u32,
+    /// IntegratorStats the transfer is tagged with (0 = none)
+    pub integrator_id: u32,
+}
+
+/**
+ * Extra data for burns redeemed on Bitcoin
+ *
+ * The custodian's signers build and co-sign a transaction paying
+ * `amount_sats` to `script_pubkey`; the nonce goes in an OP_RETURN
+ * so the payout can be matched back to this burn.
+ */
+#[event]
+pub struct BitcoinWithdrawalEvent {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub amount_sats: u64,
+    pub script_pubkey: Vec<u8>,
+    pub recipient: String,
+}
+
+/// A lock or burn's place in its token and destination's RouteSequence
+#[event]
+pub struct RouteSequenced {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub mint: Pubkey,
+    pub dest_chain_id: u64,
+    pub sequence: u64,
+}
+
+#[event]
+pub struct AttestationPosted {
+    pub nonce: u64,
+    pub relayer: Pubkey,
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct AttestationChallenged {
+    pub nonce: u64,
+    pub challenger: Pubkey,
+    pub evidence: Vec<u8>,
+}
+
+#[event]
+pub struct BondDeposited {
+    pub nonce: u64,
+    pub depositor: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct ChallengeResolved {
+    pub nonce: u64,
+    pub outcome: ChallengeOutcome,
+    pub winner: Pubkey,
+    pub payout: u64,
+    pub protocol_cut: u64,
+}
+
+#[event]
+pub struct AttestationExecuted {
+    pub nonce: u64,
+    pub relayer: Pubkey,
+    pub bond_returned: u64,
+}
+
+#[event]
+pub struct TransferVetoed {
+    pub nonce: u64,
+    pub watcher: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct VetoLifted {
+    pub nonce: u64,
+}
+
+/**
+ * Extra data for locks to Cosmos chains
+ *
+ * Everything the relayer needs to build the ICS-20 FungibleTokenPacketData.
+ */
+#[event]
+pub struct IbcTransferEvent {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub denom: String,
+    pub amount: u64,
+    pub sender: Pubkey,
+    pub receiver: String,
+}
+
+#[event]
+pub struct AdminActionProposed {
+    pub proposal_id: u64,
+    pub proposer: Pubkey,
+}
+
+#[event]
+pub struct AdminActionApproved {
+    pub proposal_id: u64,
+    pub owner: Pubkey,
+    pub approvals: u8,
+}
+
+#[event]
+pub struct AdminActionExecuted {
+    pub proposal_id: u64,
+}
+
+#[event]
+pub struct RelayerRewardsClaimed {
+    pub epoch: u64,
+    pub relayer: Pubkey,
+    pub transfers: u64,
+    pub amount: u64,
+    /// The epoch's final performance counters (see `RelayerEpochStats`)
+    pub submitted: u64,
+    pub total_latency_slots: u64,
+    pub failures: u64,
+}
+
+#[event]
+pub struct BondedPauseRaised {
+    pub pauser: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct BondedPauseResolved {
+    pub pauser: Pubkey,
+    pub incident: bool,
+    pub bond: u64,
+    pub reward: u64,
+}
+
+#[event]
+pub struct WrappedMintCreated {
+    pub mint: Pubkey,
+    pub source_token: [u8; 20],
+    pub source_decimals: u8,
+    pub decimals: u8,
+}
+
+/// Guardians synced a wrapped mint's metadata to its ERC-20 (sync_metadata)
+#[event]
+pub struct MetadataSynced {
+    pub mint: Pubkey,
+    pub source_token: [u8; 20],
+    /// Metaplex name, "Wrapped <name>"
+    pub name: String,
+    pub symbol: String,
+    pub source_decimals: u8,
+    pub eth_block: u64,
+}
+
+/// An ERC-20 listed with a bond; activatable from `activates_at` (list_token)
+#[event]
+pub struct TokenListed {
+    pub lister: Pubkey,
+    pub source_token: [u8; 20],
+    pub source_decimals: u8,
+    pub decimals: u8,
+    pub bond: u64,
+    pub activates_at: i64,
+}
+
+/// Guardians vetoed a listing; the bond went to the insurance fund
+#[event]
+pub struct TokenListingVetoed {
+    pub lister: Pubkey,
+    pub source_token: [u8; 20],
+    pub bond: u64,
+}
+
+/// A listing's wrapped mint was created and its bond returned
+#[event]
+pub struct TokenListingActivated {
+    pub lister: Pubkey,
+    pub source_token: [u8; 20],
+    pub mint: Pubkey,
+    pub bond: u64,
+}
+
+#[event]
+pub struct ConversionRateUpdated {
+    pub mint: Pubkey,
+    pub rate: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct TransferCancelled {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub direction: TransferDirection,
+    pub amount: u64,
+    pub reason: String,
+}
+
+/// An outbound transfer was delivered on its destination (`ack_outbound`)
+#[event]
+pub struct TransferAcknowledged {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    /// Hash of the delivering transaction (zeros if unknown)
+    pub dest_tx: [u8; 32],
+    pub acknowledged_by: Pubkey,
+}
+
+/// A leg its destination rejected went back to the sender (`settle_batch`)
+#[event]
+pub struct TransferRefunded {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub sender: Pubkey,
+    /// In token units (after share conversion)
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+#[event]
+pub struct TransferReclaimed {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    pub amount: u64,
+    pub mint: Pubkey,
+}
+
+/// A stuck transfer's refund right changed hands (see refund_assign.rs)
+#[event]
+pub struct RefundAssigned {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    /// The refund owner before
+    pub from: Pubkey,
+    pub to: Pubkey,
+    /// Paid to `from`, in the transfer's token
+    pub price: u64,
+}
+
+#[event]
+pub struct CouncilHaltChanged {
+    pub halted: bool,
+}
+
+#[event]
+pub struct ReserveAttested {
+    pub mint: Pubkey,
+    pub eth_locked_amount: u64,
+    pub block_number: u64,
+    pub wrapped_supply: u64,
+}
+
+/// A guardian-attested escrow balance; the relayer carries it to the EVM side
+#[event]
+pub struct VaultBalanceQueued {
+    pub mint: Pubkey,
+    /// The escrow (hot vault, if split) the balance was read from
+    pub escrow: Pubkey,
+    /// Hot and cold vaults together, in bridged units
+    pub balance: u64,
+    pub slot: u64,
+    /// Outbox sequence; the EVM bridge ignores balances older than its last
+    pub sequence: u64,
+    /// vault_balance_hash, what the relayer signs
+    pub message_hash: [u8; 32],
+}
+
+#[event]
+pub struct RedemptionQueueUpdated {
+    pub mint: Pubkey,
+    pub active: bool,
+    pub paid_ratio: u64,
+    pub claimed: u64,
+}
+
+#[event]
+pub struct RedemptionQueued {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub owner: Pubkey,
+    pub amount: u64,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+}
+
+/// Pay `amount` to `recipient`; (mint, claim_id, released) identifies the payout
+#[event]
+pub struct RedemptionReleased {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub amount: u64,
+    /// The claim's total released, this payout included
+    pub released: u64,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+}
+
+#[event]
+pub struct RedemptionConfirmed {
+    pub mint: Pubkey,
+    pub claim_id: u64,
+    pub amount: u64,
+    pub eth_block: u64,
+    /// Paid in full and closed
+    pub closed: bool,
+}
+
+#[event]
+pub struct TokenOracleRefreshed {
+    pub mint: Pubkey,
+    /// CollateralStatus
+    pub status: u8,
+    /// oracle_flags
+    pub flags: u8,
+    pub collateral_bps: u64,
+}
+
+/// Excess of the hot vault swept into the cold vault
+#[event]
+pub struct VaultSwept {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub hot_balance: u64,
+}
+
+#[event]
+pub struct VaultRefillQueued {
+    pub mint: Pubkey,
+    pub amount: u64,
+    /// Hot balance the vault policy aims for
+    pub target: u64,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct VaultRefilled {
+    pub mint: Pubkey,
+    /// Received by the hot vault
+    pub amount: u64,
+    pub hot_balance: u64,
+    /// Refilled this epoch, this refill included
+    pub refilled_in_epoch: u64,
+}
+
+#[event]
+pub struct VaultRefillCancelled {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub cancelled_by: Pubkey,
+}
+
+#[event]
+pub struct YieldVenueAllowed {
+    pub mint: Pubkey,
+    pub adapter: Pubkey,
+    pub max_amount: u64,
+    /// When deposits up to `max_amount` are allowed
+    pub active_at: i64,
+}
+
+/// Deposits to the venue stop; it is dropped once nothing is deployed to it
+#[event]
+pub struct YieldVenueRemoved {
+    pub mint: Pubkey,
+    pub adapter: Pubkey,
+    pub deployed: u64,
+    pub removed_by: Pubkey,
+}
+
+#[event]
+pub struct YieldDeposited {
+    pub mint: Pubkey,
+    pub adapter: Pubkey,
+    pub amount: u64,
+    /// Deployed to the venue after the deposit
+    pub venue_deployed: u64,
+    /// Deployed across the token's venues after the deposit
+    pub deployed: u64,
+}
+
+#[event]
+pub struct YieldRecalled {
+    pub mint: Pubkey,
+    pub adapter: Pubkey,
+    /// Returned to the cold vault
+    pub principal: u64,
+    /// Paid to the insurance fund
+    pub yield_amount: u64,
+    /// Principal the venue didn't return on a full recall, written off
+    pub lost: u64,
+    pub venue_deployed: u64,
+    pub recalled_by: Pubkey,
+}
+
+#[event]
+pub struct CheckpointCreated {
+    pub index: u64,
+    pub root: [u8; 32],
+    pub transfer_count: u32,
+    pub slot: u64,
+}
+
+#[event]
+pub struct ArchiveRecorded {
+    pub index: u64,
+    pub hash: [u8; 32],
+    pub first_slot: u64,
+    pub last_slot: u64,
+    pub locator: String,
+}
+
+#[event]
+pub struct NoncesPruned {
+    /// The new `processed_floor`
+    pub floor: u64,
+    pub removed: u64,
+}
+
+#[event]
+pub struct BondedPauseConfirmed {
+    pub pauser: Pubkey,
+    pub expires_at_slot: u64,
+}
+
+#[event]
+pub struct BondedPauseExpired {
+    pub pauser: Pubkey,
+    /// Returned to the pauser
+    pub bond: u64,
+}
+
+/// The bridge paused or resumed; the relayer carries it to the EVM side
+#[event]
+pub struct BridgeStatusQueued {
+    pub paused: bool,
+    /// Outbox sequence; the EVM bridge ignores statuses older than its last
+    pub sequence: u64,
+    /// bridge_status_hash, what the relayer signs
+    pub message_hash: [u8; 32],
+}
+
+/// A message to an allowed contract; the relayer carries it to `dest_chain_id`
+#[event]
+pub struct MessageSent {
+    pub sender: Pubkey,
+    pub dest_chain_id: u64,
+    /// Contract address, left-padded to 32 bytes
+    pub target: [u8; 32],
+    pub payload: Vec<u8>,
+    /// Outbox sequence
+    pub sequence: u64,
+    /// message_hash, what the relayer signs
+    pub message_hash: [u8; 32],
+}
+
+#[event]
+pub struct GuardianHeartbeatPosted {
+    pub guardian: Pubkey,
+    pub epoch: u64,
+}
+
+/// An operator refreshed its liveness entry (`heartbeat`)
+#[event]
+pub struct HeartbeatPosted {
+    pub operator: Pubkey,
+    pub guardian: bool,
+    pub epoch: u64,
+    pub timestamp: i64,
+}
+
+/// A relay route's health was scored (`report_route_health`)
+#[event]
+pub struct RouteHealthReported {
+    pub route_id: u32,
+    pub score: u8,
+    /// None on the route's first report
+    pub previous_score: Option<u8>,
+    pub status: RouteStatus,
+    pub report: RouteHealthReport,
+    pub heartbeat_age: u64,
+    pub reporter: Pubkey,
+}
+
+#[event]
+pub struct RecipientNamePinned {
+    pub transfer_id: [u8; 32],
+    pub direction: TransferDirection,
+    pub nonce: u64,
+    pub name: String,
+}
+
+#[event]
+pub struct SourceTxRecorded {
+    pub tx_hash: [u8; 32],
+    pub chain_id: u64,
+    pub nonce: u64,
+    pub transfer_id: [u8; 32],
+}
+
+/// A lock's amount had dust below the token's bridged decimals
+#[event]
+pub struct DustHandled {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub mint: Pubkey,
+    pub policy: DustPolicy,
+    /// Refunded to the sender, or taken into the fee vault
+    pub dust: u64,
+}
+
+/// A lock's fee was paid in lamports into the SOL fee vault
+#[event]
+pub struct FeePaidInSol {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub from: Pubkey,
+    pub mint: Pubkey,
+    /// The fee in token base units, had it been paid in the token
+    pub fee: u64,
+    pub lamports: u64,
+}
+
+/// A lock prepaid its delivery on the destination chain, into the
+/// relayer reward vault
+#[event]
+pub struct DeliveryFeePrepaid {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub from: Pubkey,
+    pub dest_chain_id: u64,
+    /// What the lamports cover on the destination
+    pub gas_limit: u64,
+    pub gas_price: u64,
+    pub lamports: u64,
+}
+
+#[event]
+pub struct DeliveryGasPriceSet {
+    pub chain_id: u64,
+    pub gas_price: u64,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct PriceBreakerTripped {
+    pub mint: Pubkey,
+    /// The window's first price, and the one that tripped it (both
+    /// scaled by 10^exponent)
+    pub reference_price: i64,
+    pub price: i64,
+    pub exponent: i32,
+    pub move_bps: u64,
+    /// Transfers of the token fail until this time
+    pub tripped_until: i64,
+}
+
+/// An acknowledged transfer took longer than the SLA; its sender can
+/// claim `rebate` with `claim_sla_rebate`
+#[event]
+pub struct SlaRebateCredited {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    /// Lock to acknowledgement
+    pub latency_slots: u64,
+    pub rebate: u64,
+}
+
+#[event]
+pub struct SlaRebateClaimed {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct SlaSet {
+    pub max_latency_slots: u64,
+    pub rebate_bps: u16,
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct TermsSet {
+    pub version: u32,
+    pub terms_hash: [u8; 32],
+    pub set_by: Pubkey,
+}
+
+#[event]
+pub struct TermsAcknowledged {
+    pub wallet: Pubkey,
+    pub version: u32,
+    pub terms_hash: [u8; 32],
+}
+
+#[event]
+pub struct SenderAllowlistSet {
+    pub all_tokens: bool,
+    pub mints: Vec<Pubkey>,
+    pub set_by: Pubkey,
+}
+
+/// A sender was added to (`allowed`) or removed from the allowlist
+#[event]
+pub struct SenderAllowlisted {
+    pub sender: Pubkey,
+    pub allowed: bool,
+}
+
+#[event]
+pub struct DepositAddressRegistered {
+    pub user: Pubkey,
+    pub deposit_address: Pubkey,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+}
+
+#[event]
+pub struct DepositSwept {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub user: Pubkey,
+    pub deposit_address: Pubkey,
+    pub mint: Pubkey,
+    /// What the deposit token account held, before fees
+    pub amount: u64,
+    pub cranker: Pubkey,
+}
+
+#[event]
+pub struct DepositWithdrawn {
+    pub user: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct WithdrawalAddressAdded {
+    pub user: Pubkey,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+    /// When transfers may start going to it
+    pub active_at: i64,
+}
+
+#[event]
+pub struct WithdrawalAddressRemoved {
+    pub user: Pubkey,
+    pub dest_chain_id: u64,
+    pub recipient: String,
+}
+
+#[event]
+pub struct WithdrawalAuthoritySet {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+}
+
+#[event]
+pub struct EscrowMigrationQueued {
+    pub mint: Pubkey,
+    pub new_bridge_program: Pubkey,
+    pub new_vault: Pubkey,
+    pub executable_at: i64,
+}
+
+#[event]
+pub struct EscrowMigrationCancelled {
+    pub mint: Pubkey,
+    pub new_bridge_program: Pubkey,
+    pub cancelled_by: Pubkey,
+}
+
+/// A token's escrow moved to its successor; the token is paused here
+#[event]
+pub struct EscrowMigrated {
+    pub mint: Pubkey,
+    pub new_bridge_program: Pubkey,
+    pub new_vault: Pubkey,
+    /// What the new vault received, hot and cold
+    pub amount: u64,
+    /// Replay-protection state for the successor's import_state
+    pub route_shards: u32,
+    pub processed_floor: u64,
+    pub processed_nonces: u32,
+}
+
+/// Escrow sent to another instance of the program (send_to_instance)
+#[event]
+pub struct InstanceTransferSent {
+    pub id: u64,
+    pub mint: Pubkey,
+    pub dest_instance: u64,
+    pub dest_vault: Pubkey,
+    pub amount: u64,
+    pub sent_by: Pubkey,
+}
+
+/// This instance recorded escrow another one sent it
+#[event]
+pub struct InstanceTransferReceived {
+    pub source_instance: u64,
+    pub id: u64,
+    pub mint: Pubkey,
+    pub vault: Pubkey,
+    pub amount: u64,
+}
+
+/// A lock went through without the receipt it asked for, short of
+/// compute; the sender can mint it with issue_receipt
+#[event]
+pub struct ReceiptDeferred {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+}
+
+#[event]
+pub struct RecoveryAddressSet {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    /// Refunds of the transfer go to this address's token account
+    pub recovery: Pubkey,
+}
+
+#[event]
+pub struct MemoAttached {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub sender: Pubkey,
+    /// Opaque to the bridge; sealed to the recipient's key by the SDK
+    pub memo: Vec<u8>,
+}
+
+/// A lock's recipient is a smart wallet that may not be deployed yet
+#[event]
+pub struct SmartWalletRecipientSet {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub recipient: String,
+    pub derivation: WalletDerivation,
+}
+
+#[event]
+pub struct RelayerEjected {
+    pub relayer: Pubkey,
+    pub reason: String,
+    /// Set for an automatic ejection after a challenge
+    pub fraud_nonce: Option<u64>,
+    /// Owner, quorum submitter or challenge resolver
+    pub ejected_by: Pubkey,
+}
+
+#[event]
+pub struct RelayerReinstated {
+    pub relayer: Pubkey,
+}
+
+/**
+ * A stored transfer, emitted again by `reemit`
+ *
+ * Carries what the transfer record holds, which covers everything a
+ * relayer needs from the original LockEvent/BurnEvent (or MintEvent/
+ * UnlockEvent) but not the fee details.
+ */
+#[event]
+pub struct TransferReemitted {
+    pub transfer_id: [u8; 32],
+    pub direction: TransferDirection,
+    pub nonce: u64,
+    pub status: TransferStatus,
+    /// Destination chain (outbound) or source chain (inbound)
+    pub remote_chain_id: u64,
+    /// Sender (outbound) or recipient (inbound) on Solana
+    pub local_account: Pubkey,
+    /// Recipient on the destination chain; empty for inbound transfers
+    pub remote_address: String,
+    pub amount: u64,
+    /// Outbound only: token locked or burned
+    pub mint: Pubkey,
+    /// Outbound only: locked (true) or burned (false)
+    pub escrowed: bool,
+    pub created_at: i64,
+    pub created_slot: u64,
+}
+
+#[event]
+pub struct ChainStatusChanged {
+    pub chain_id: u64,
+    pub halted: bool,
+    /// Guardian, quorum submitter or owner
+    pub by: Pubkey,
+}
+
+#[event]
+pub struct GlobalStatsUpdated {
+    /// Micro-dollars, as stored in GlobalStats
+    pub tvl_usd: u64,
+    pub inflow_usd: u64,
+    pub outflow_usd: u64,
+    pub window_start: i64,
+    pub updated_at: i64,
+}
+
+#[event]
+pub struct TransferRouted {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    /// First hop: the lock's or burn's destination
+    pub dest_chain_id: u64,
+    pub hops: Vec<u64>,
+    pub final_chain_id: u64,
+    pub final_recipient: String,
+}
+
+#[event]
+pub struct FeeExemptionGranted {
+    pub address: Pubkey,
+    /// Owner or fee manager that granted it
+    pub granted_by: Pubkey,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct FeeExemptionRevoked {
+    pub address: Pubkey,
+    /// Owner or fee manager that revoked it
+    pub revoked_by: Pubkey,
+    pub timestamp: i64,
+}
+
+/// An inbound mint delivered in claim mode, waiting for `claim`
+#[event]
+pub struct TransferClaimable {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+}
+
+/**
+ * An inbound delivery the recipient's token account couldn't take,
+ * recorded as Claimable instead (see quarantine.rs)
+ */
+#[event]
+pub struct DeliveryQuarantined {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub recipient: Pubkey,
+    pub mint: Pubkey,
+    /// Owed in the token (after share conversion, for unlocks)
+    pub amount: u64,
+    /// The account the relayer passed
+    pub token_account: Pubkey,
+    pub reason: QuarantineReason,
+    /// An unlock, left in escrow for `claim_unlock`; otherwise a mint,
+    /// for `claim`
+    pub escrowed: bool,
+}
+
+#[event]
+pub struct TransferClaimed {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub recipient: Pubkey,
+    /// Token account the tokens were minted or released to
+    pub token_account: Pubkey,
+    /// Recipient, or whoever claimed on their behalf
+    pub claimer: Pubkey,
+    pub amount: u64,
+}
+
+#[event]
+pub struct GasReported {
+    pub nonce: u64,
+    pub relayer: Pubkey,
+    pub gas_paid: u64,
+    /// Lamports credited to the relayer's reimbursement balance
+    pub reimbursed: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct GasReimbursementClaimed {
+    pub relayer: Pubkey,
+    pub amount: u64,
+    /// Lifetime totals after this claim
+    pub gas_paid: u64,
+    pub claimed: u64,
+}
+
+#[event]
+pub struct UpgradeAuthorityChanged {
+    pub previous: Option<Pubkey>,
+    /// None once the program is finalized
+    pub authority: Option<Pubkey>,
+    pub held_by_owner: bool,
+    pub timestamp: i64,
+}
+
+/// Governance vouched for the deployed program's build (record_build)
+#[event]
+pub struct BuildRecorded {
+    pub hash: [u8; 32],
+    pub version: String,
+    pub deployed_slot: u64,
+    pub timestamp: i64,
+}
+
+/// A finished transfer record moved into the compressed store
+#[event]
+pub struct TransferRecordCompressed {
+    pub tree: Pubkey,
+    pub max_depth: u32,
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    pub transfer_id: [u8; 32],
+    /// The record, Borsh-serialized; `leaf` is its keccak256
+    pub record: Vec<u8>,
+}
+
+#[event]
+pub struct OtcOrderCreated {
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub counter_chain_id: u64,
+    pub counter_asset: String,
+    pub counter_amount: u64,
+    pub pay_to: String,
+    pub expiry: i64,
+}
+
+#[event]
+pub struct OtcOrderFilled {
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub taker: Pubkey,
+    /// Released to the taker, before any Token-2022 transfer fee
+    pub amount: u64,
+    pub counter_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct OtcOrderCancelled {
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriorityBidPlaced {
+    pub nonce: u64,
+    pub sender: Pubkey,
+    /// The bid's total tip after this raise
+    pub tip: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct PriorityTipCollected {
+    pub nonce: u64,
+    /// To the relayer reward vault
+    pub relayer_share: u64,
+    pub treasury_share: u64,
+    /// Back to the sender, for a transfer that was refunded
+    pub refunded: u64,
+}
+
+#[event]
+pub struct RelayerRegistered {
+    pub relayer: Pubkey,
+    pub endpoint_url: String,
+    /// Empty = every route
+    pub supported_routes: Vec<u32>,
+    pub fee_schedule: RelayerFeeSchedule,
+    /// False when an existing registration was updated
+    pub new: bool,
+}
+
+#[event]
+pub struct RelayerDeregistered {
+    pub relayer: Pubkey,
+}
+
+#[event]
+pub struct IntegratorRegistered {
+    pub integrator_id: u32,
+    pub authority: Pubkey,
+}
@@ -0,0 +1,62 @@
+//! Side-by-side bridge instances in one deployment
+//!
+//! Test, staging and partner bridges can share a program ID. Each is an
+//! instance with its own u64 ID, kept in its BridgeState with the seed
+//! below (stored so clients resolving accounts from the IDL can read
+//! it), and every PDA the program derives or signs for carries that
+//! seed right after its prefix:
+//!
+//!   ["bridge_state", seed(id)]
+//!   ["token", seed(id), mint]
+//!   ["bridge", seed(id), bump]
+//!
+//! so two instances never share state, escrows, guardians or nonces.
+//! Instance 0's seed is empty: its addresses are the ones from before
+//! instances existed, and a deployment needs no migration to keep
+//! serving it. Any other instance adds the ID's 8 little-endian bytes.
+
+use anchor_lang::prelude::*;
+
+/// The instance a deployment had before there were others
+pub const DEFAULT_INSTANCE: u64 = 0;
+
+/// Seed instance `instance_id` adds to every PDA (empty for instance 0)
+pub fn seed(instance_id: &u64) -> &[u8] {
+    if *instance_id == DEFAULT_INSTANCE {
+        &[]
+    } else {
+        // Little-endian on SBF, like every other integer seed here
+        bytemuck::bytes_of(instance_id)
+    }
+}
+
+/// Bridge state of instance `instance_id`
+pub fn bridge_state(program_id: &Pubkey, instance_id: u64) -> Pubkey {
+    Pubkey::find_program_address(&[b"bridge_state", seed(&instance_id)], program_id).0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn instance_zero_keeps_its_addresses() {
+        let program_id = crate::ID;
+        assert_eq!(
+            bridge_state(&program_id, DEFAULT_INSTANCE),
+            Pubkey::find_program_address(&[b"bridge_state"], &program_id).0
+        );
+    }
+
+    #[test]
+    fn other_instances_get_their_own() {
+        let program_id = crate::ID;
+        let staging = bridge_state(&program_id, 1);
+        assert_ne!(staging, bridge_state(&program_id, DEFAULT_INSTANCE));
+        assert_ne!(staging, bridge_state(&program_id, 2));
+        assert_eq!(
+            staging,
+            Pubkey::find_program_address(&[b"bridge_state", &1u64.to_le_bytes()], &program_id).0
+        );
+    }
+}
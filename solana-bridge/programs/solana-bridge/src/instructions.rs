@@ -0,0 +1,8488 @@
+//! Instruction accounts
+//!
+//! The `#[derive(Accounts)]` context of every instruction, in the order
+//! the handlers appear in lib.rs. Anchor needs the handlers themselves
+//! inside the `#[program]` module, so they stay there.
+
+use crate::*;
+
+/**
+ * Initialize accounts
+ */
+#[derive(Accounts)]
+#[instruction(instance_id: u64)]
+pub struct Initialize<'info> {
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + BridgeState::INIT_SPACE,
+        seeds = [b"bridge_state", instance::seed(&instance_id)],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AdminLog::INIT_SPACE,
+        seeds = [b"admin_log", instance::seed(&instance_id)],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + std::mem::size_of::<Outbox>(),
+        seeds = [b"outbox", instance::seed(&instance_id)],
+        bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Cache-bumps accounts
+ */
+#[derive(Accounts)]
+pub struct CacheBumps<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: PDA that owns the escrow and fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    /// CHECK: Only its address is used
+    #[account(
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub outbox: UncheckedAccount<'info>,
+}
+
+/**
+ * Lock accounts
+ *
+ * Similar to your Solidity function parameters,
+ * but in Solana you must explicitly declare all accounts
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, dest_chain_id: u64)]
+pub struct Lock<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// The token's sequence to this chain, which numbers the transfer
+    #[account(
+        mut,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            RouteSequence::nonce_at(route_sequence.shard, route_sequence.last + 1)
+                .to_le_bytes()
+                .as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the transfer ID
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub user_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: The token's fee route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: UncheckedAccount<'info>,
+
+    /// CHECK: The token's relay route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: The token's price circuit breaker; empty unless one was configured
+    #[account(
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: UncheckedAccount<'info>,
+
+    /// CHECK: The token's event history; empty unless one was created
+    #[account(
+        mut,
+        seeds = [b"event_history", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub event_history: UncheckedAccount<'info>,
+
+    /// CHECK: The user's fee exemption; empty unless one was granted
+    #[account(
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub fee_exemption: UncheckedAccount<'info>,
+
+    /// CHECK: The deployment's terms of use; empty unless it requires any
+    #[account(
+        seeds = [b"terms", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub terms_config: UncheckedAccount<'info>,
+
+    /// CHECK: The user's acknowledgement of the terms; empty until given
+    #[account(
+        seeds = [b"terms_ack", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub terms_acceptance: UncheckedAccount<'info>,
+
+    /// CHECK: The closed user group settings; empty unless senders are gated
+    #[account(
+        seeds = [b"sender_allowlist", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sender_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: The user's allowlist entry; empty unless the owner added them
+    #[account(
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+
+    /// CHECK: The user's withdrawal allowlist; empty unless they made one
+    #[account(
+        seeds = [
+            b"withdrawal_allowlist",
+            bridge_state.instance_seed.as_slice(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub withdrawal_allowlist: UncheckedAccount<'info>,
+
+    /// Guardians whose fee quotes are honored; pass with `instructions`
+    /// to pay a signed quote instead of the fee table
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub quote_signers: Option<Account<'info, GuardianSet>>,
+
+    /// CHECK: Instructions sysvar, to find the quote's Ed25519 instruction
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// The user's bridge pass token account, for pass-holder benefits
+    #[account(
+        token::mint = bridge_state.pass_mint,
+        token::authority = user
+    )]
+    pub bridge_pass: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow and fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Deposit receipt mint (["receipt", nonce]), created here; optional
+    #[account(mut)]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: User's associated token account for the receipt, created here
+    #[account(mut)]
+    pub receipt_token: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+
+    pub associated_token_program: Option<Program<'info, AssociatedToken>>,
+
+    /// Stats of the integrator the transfer is tagged with, if it is
+    #[account(mut)]
+    pub integrator: Option<Account<'info, IntegratorStats>>,
+
+    /// Pass to pay the fee in SOL, with the vault and both price updates
+    #[account(
+        seeds = [b"sol_fee", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub sol_fee_config: Option<Account<'info, SolFeeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_fee_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sol_fee_vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: Pyth price update for the token; checked in pyth::load_price
+    pub token_price: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Pyth price update for SOL; checked in pyth::load_price
+    pub sol_price: Option<UncheckedAccount<'info>>,
+
+    /// Pass to prepay delivery on the destination, with the reward vault,
+    /// the native token's price update and SOL's
+    #[account(
+        seeds = [
+            b"delivery_fee",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub delivery_fee_config: Option<Account<'info, DeliveryFeeConfig>>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: Pyth price update for the destination's native token;
+    /// checked in pyth::load_price
+    pub native_price: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Memo account (["memo", nonce]), created here; only with a memo
+    #[account(mut)]
+    pub transfer_memo: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Smart wallet account (["smart_wallet", nonce]), created here;
+    /// only with a smart wallet recipient
+    #[account(mut)]
+    pub smart_wallet: Option<UncheckedAccount<'info>>,
+}
+
+/**
+ * Batched lock accounts (same as Lock, Ethereum only; transfer
+ * records come in as remaining accounts)
+ */
+#[derive(Accounts)]
+pub struct LockBatch<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the transfer ID
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub user_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: The token's fee route to Ethereum; may not exist
+    #[account(
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: UncheckedAccount<'info>,
+
+    /// CHECK: The token's relay route to Ethereum; may not exist
+    #[account(
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: The token's price circuit breaker; empty unless one was configured
+    #[account(
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: UncheckedAccount<'info>,
+
+    /// CHECK: The token's event history; empty unless one was created
+    #[account(
+        mut,
+        seeds = [b"event_history", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub event_history: UncheckedAccount<'info>,
+
+    /// The token's sequence to this chain, which numbers the transfer
+    #[account(
+        mut,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    /// CHECK: The user's fee exemption; empty unless one was granted
+    #[account(
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub fee_exemption: UncheckedAccount<'info>,
+
+    /// CHECK: The deployment's terms of use; empty unless it requires any
+    #[account(
+        seeds = [b"terms", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub terms_config: UncheckedAccount<'info>,
+
+    /// CHECK: The user's acknowledgement of the terms; empty until given
+    #[account(
+        seeds = [b"terms_ack", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub terms_acceptance: UncheckedAccount<'info>,
+
+    /// CHECK: The closed user group settings; empty unless senders are gated
+    #[account(
+        seeds = [b"sender_allowlist", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sender_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: The user's allowlist entry; empty unless the owner added them
+    #[account(
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+
+    /// CHECK: The user's withdrawal allowlist; empty unless they made one
+    #[account(
+        seeds = [
+            b"withdrawal_allowlist",
+            bridge_state.instance_seed.as_slice(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub withdrawal_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// The user's bridge pass token account, for pass-holder benefits
+    #[account(
+        token::mint = bridge_state.pass_mint,
+        token::authority = user
+    )]
+    pub bridge_pass: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow and fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Issue-receipt accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct IssueReceipt<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Deposit receipt mint (["receipt", nonce]), created here
+    #[account(mut)]
+    pub receipt_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Sender's associated token account for the receipt, created here
+    #[account(mut)]
+    pub receipt_token: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that mints deposit receipts
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_2022_program: Program<'info, Token2022>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Mint accounts
+ *
+ * Named MintWrapped so it doesn't shadow the SPL `Mint` account type.
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, nonce: u64)]
+pub struct MintWrapped<'info> {
+    /// CHECK: User receiving tokens
+    #[account(mut)]
+    pub user: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Source chain's inbox; empty if the chain has none (see Inbox)
+    #[account(
+        mut,
+        seeds = [
+            b"inbox",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inbox: UncheckedAccount<'info>,
+
+    /// Source chain; nothing is delivered from it while it's halted
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub source_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set; a quorum of its signers may mint without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RewardEpoch::INIT_SPACE,
+        seeds = [
+            b"reward_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RelayerEpochStats::INIT_SPACE,
+        seeds = [
+            b"relayer_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub relayer_stats: Account<'info, RelayerEpochStats>,
+
+    /// Only wrapped mints the bridge can mint count
+    #[account(
+        mut,
+        mint::authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    /// The mint the verifier's evidence names (see InboundTransfer)
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump,
+        constraint = token_config.mint == wrapped_mint.key() @ ErrorCode::TransferMismatch
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Posted VAA for WormholeVaa tokens; validated by verifiers::WormholeVaa
+    pub vaa: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: The recipient's token account; checked in quarantine::check.
+    /// None in claim mode, where the recipient picks the account (see `claim`)
+    #[account(mut)]
+    pub user_token: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Unlock accounts
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, nonce: u64)]
+pub struct Unlock<'info> {
+    /// CHECK: User receiving tokens
+    pub user: AccountInfo<'info>,
+
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Source chain's inbox; empty if the chain has none (see Inbox)
+    #[account(
+        mut,
+        seeds = [
+            b"inbox",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inbox: UncheckedAccount<'info>,
+
+    /// Source chain; nothing is delivered from it while it's halted
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub source_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set; a quorum of its signers may unlock without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RewardEpoch::INIT_SPACE,
+        seeds = [
+            b"reward_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + RelayerEpochStats::INIT_SPACE,
+        seeds = [
+            b"relayer_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref(),
+            authority.key().as_ref()
+        ],
+        bump
+    )]
+    pub relayer_stats: Account<'info, RelayerEpochStats>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// The mint the verifier's evidence names (see InboundTransfer)
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump,
+        constraint = token_config.mint == mint.key() @ ErrorCode::TransferMismatch
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Posted VAA for WormholeVaa tokens; validated by verifiers::WormholeVaa
+    pub vaa: Option<UncheckedAccount<'info>>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// CHECK: The token's price circuit breaker; empty unless one was configured
+    #[account(
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: UncheckedAccount<'info>,
+
+    /// CHECK: The token's event history; empty unless one was created
+    #[account(
+        mut,
+        seeds = [b"event_history", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub event_history: UncheckedAccount<'info>,
+
+    /// CHECK: The recipient's token account; checked in quarantine::check
+    #[account(mut)]
+    pub user_token: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Verifying key accounts
+ */
+#[derive(Accounts)]
+pub struct SetVerifyingKey<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ZkVerifier::INIT_SPACE,
+        seeds = [b"zk_verifier", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub zk_verifier: Account<'info, ZkVerifier>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Mint-with-proof accounts
+ *
+ * The submitter can be anyone, so the destination token account
+ * is pinned to the proven recipient.
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, nonce: u64)]
+pub struct MintWithProof<'info> {
+    #[account(mut)]
+    pub submitter: Signer<'info>,
+
+    /// CHECK: Recipient committed to in the proof
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"zk_verifier", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub zk_verifier: Account<'info, ZkVerifier>,
+
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Source chain's inbox; empty if the chain has none (see Inbox)
+    #[account(
+        mut,
+        seeds = [
+            b"inbox",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inbox: UncheckedAccount<'info>,
+
+    /// Source chain; nothing is delivered from it while it's halted
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub source_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the evidence
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = 8 + RewardEpoch::INIT_SPACE,
+        seeds = [
+            b"reward_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        init_if_needed,
+        payer = submitter,
+        space = 8 + RelayerEpochStats::INIT_SPACE,
+        seeds = [
+            b"relayer_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref(),
+            submitter.key().as_ref()
+        ],
+        bump
+    )]
+    pub relayer_stats: Account<'info, RelayerEpochStats>,
+
+    /// Only wrapped mints the bridge can mint count
+    #[account(
+        mut,
+        mint::authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    /// The mint the proof's public inputs name (see InboundTransfer)
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump,
+        constraint = token_config.mint == wrapped_mint.key() @ ErrorCode::TransferMismatch
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint,
+        token::authority = user
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Devnet force-mint accounts (anyone; `devnet` feature only)
+ */
+#[derive(Accounts)]
+pub struct DevnetForceMint<'info> {
+    pub integrator: Signer<'info>,
+
+    /// CHECK: Recipient; the simulated transfer's `to`
+    pub user: AccountInfo<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// Only wrapped mints the bridge can mint count
+    #[account(
+        mut,
+        mint::authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint,
+        token::authority = user
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/**
+ * Burn accounts
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, dest_chain_id: u64)]
+pub struct BurnTokens<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// The token's sequence to this chain, which numbers the transfer
+    #[account(
+        mut,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            RouteSequence::nonce_at(route_sequence.shard, route_sequence.last + 1)
+                .to_le_bytes()
+                .as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the transfer ID
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    #[account(mut)]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: The token's relay route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: The token's redemption queue; may not exist (see RedemptionQueue)
+    #[account(
+        seeds = [
+            b"redemption_queue",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub redemption_queue: UncheckedAccount<'info>,
+
+    /// CHECK: The closed user group settings; empty unless senders are gated
+    #[account(
+        seeds = [b"sender_allowlist", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sender_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: The user's allowlist entry; empty unless the owner added them
+    #[account(
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+
+    /// CHECK: The user's withdrawal allowlist; empty unless they made one
+    #[account(
+        seeds = [
+            b"withdrawal_allowlist",
+            bridge_state.instance_seed.as_slice(),
+            user.key().as_ref()
+        ],
+        bump
+    )]
+    pub withdrawal_allowlist: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint,
+        token::authority = user
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    /// Stats of the integrator the transfer is tagged with, if it is
+    #[account(mut)]
+    pub integrator: Option<Account<'info, IntegratorStats>>,
+
+    /// CHECK: The token's compliance co-signing policy; may not exist (see BurnApproval)
+    #[account(
+        seeds = [
+            b"burn_approval",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub burn_approval: UncheckedAccount<'info>,
+
+    /// The token's compliance key, for burns over its approval threshold
+    pub compliance_signer: Option<Signer<'info>>,
+}
+
+/**
+ * Pause/Unpause accounts
+ *
+ * The outbox takes the BridgeStatus message for the EVM side.
+ */
+#[derive(Accounts)]
+pub struct SetPaused<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+}
+
+/**
+ * Owner settings accounts (bridge-wide flags and parameters)
+ */
+#[derive(Accounts)]
+pub struct PauseBridge<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+}
+
+/**
+ * Guardian set accounts
+ */
+#[derive(Accounts)]
+pub struct SetGuardians<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + GuardianSet::INIT_SPACE,
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Optimistic config accounts
+ */
+#[derive(Accounts)]
+pub struct SetOptimisticConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + OptimisticConfig::INIT_SPACE,
+        seeds = [b"optimistic_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub optimistic_config: Account<'info, OptimisticConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Post-attestation accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct PostAttestation<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"optimistic_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub optimistic_config: Account<'info, OptimisticConfig>,
+
+    /// The token attested; its mint is the only one execution may mint
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), token_config.mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + Attestation::INIT_SPACE,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    /// CHECK: Must not exist: ejected relayers can't post
+    #[account(
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), relayer.key().as_ref()],
+        bump
+    )]
+    pub ejection: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Challenge accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ChallengeAttestation<'info> {
+    #[account(mut)]
+    pub challenger: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Challenge resolution accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ResolveChallenge<'info> {
+    /// Submits the guardians' decision; pays for the ejection record on fraud
+    #[account(mut)]
+    pub resolver: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        seeds = [b"optimistic_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub optimistic_config: Account<'info, OptimisticConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Relayer that posted the attestation (receives bond)
+    #[account(mut, address = attestation.relayer)]
+    pub relayer: AccountInfo<'info>,
+
+    /// CHECK: Watcher that challenged the attestation (receives bond)
+    #[account(mut, address = attestation.challenger)]
+    pub challenger: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    /// CHECK: Receives the protocol cut of slashed bonds
+    #[account(mut, address = optimistic_config.treasury)]
+    pub treasury: AccountInfo<'info>,
+
+    /// Any open epoch stats of the relayer; a Fraud outcome counts as a failure
+    #[account(mut)]
+    pub relayer_stats: Option<Account<'info, RelayerEpochStats>>,
+
+    /// CHECK: Relayer's ejection record; created on a Fraud outcome
+    #[account(
+        mut,
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), attestation.relayer.as_ref()],
+        bump
+    )]
+    pub ejection: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Execute-attestation accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ExecuteAttestation<'info> {
+    /// Anyone; pays for this epoch's reward accounts if needed
+    #[account(mut)]
+    pub executor: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Source chain's inbox; empty if the chain has none (see Inbox)
+    #[account(
+        mut,
+        seeds = [
+            b"inbox",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub inbox: UncheckedAccount<'info>,
+
+    /// Source chain; nothing is delivered from it while it's halted
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            chain_ids::ETHEREUM.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub source_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the evidence
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = 8 + RewardEpoch::INIT_SPACE,
+        seeds = [
+            b"reward_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        init_if_needed,
+        payer = executor,
+        space = 8 + RelayerEpochStats::INIT_SPACE,
+        seeds = [
+            b"relayer_epoch",
+            bridge_state.instance_seed.as_slice(),
+            reward_config.current_epoch().to_le_bytes().as_ref(),
+            attestation.relayer.as_ref()
+        ],
+        bump
+    )]
+    pub relayer_stats: Account<'info, RelayerEpochStats>,
+
+    /// CHECK: Relayer that posted the attestation (bond is returned)
+    #[account(mut, address = attestation.relayer)]
+    pub relayer: AccountInfo<'info>,
+
+    /// CHECK: Must not exist: an ejected relayer's bonds are frozen
+    #[account(
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), attestation.relayer.as_ref()],
+        bump
+    )]
+    pub ejection: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    /// CHECK: Attested recipient
+    #[account(address = attestation.recipient)]
+    pub user: AccountInfo<'info>,
+
+    /// The mint the attestation was posted for
+    #[account(
+        mut,
+        mint::authority = bridge_authority,
+        constraint = wrapped_mint.key() == attestation.mint @ ErrorCode::TransferMismatch
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint,
+        token::authority = user
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Add-watcher accounts
+ */
+#[derive(Accounts)]
+#[instruction(watcher: Pubkey)]
+pub struct AddWatcher<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + WatcherInfo::INIT_SPACE,
+        seeds = [b"watcher", bridge_state.instance_seed.as_slice(), watcher.as_ref()],
+        bump
+    )]
+    pub watcher_info: Account<'info, WatcherInfo>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-watcher accounts
+ */
+#[derive(Accounts)]
+#[instruction(watcher: Pubkey)]
+pub struct RemoveWatcher<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"watcher", bridge_state.instance_seed.as_slice(), watcher.as_ref()],
+        bump
+    )]
+    pub watcher_info: Account<'info, WatcherInfo>,
+}
+
+/**
+ * Veto accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct Veto<'info> {
+    pub watcher: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"watcher", bridge_state.instance_seed.as_slice(), watcher.key().as_ref()],
+        bump
+    )]
+    pub watcher_info: Account<'info, WatcherInfo>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+}
+
+/**
+ * Lift-veto accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct LiftVeto<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Account<'info, Attestation>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+}
+
+/**
+ * Register-chain accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct RegisterChain<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + ChainConfig::INIT_SPACE,
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Update-chain accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct UpdateChain<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+}
+
+/**
+ * Withdraw-fees accounts
+ */
+#[derive(Accounts)]
+pub struct WithdrawFees<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Register-token accounts
+ */
+#[derive(Accounts)]
+pub struct RegisterToken<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + TokenConfig::INIT_SPACE,
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Update-token accounts
+ */
+#[derive(Accounts)]
+pub struct UpdateToken<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), token_config.mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+}
+
+/**
+ * Create-owner-multisig accounts
+ */
+#[derive(Accounts)]
+pub struct CreateOwnerMultisig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + OwnerMultisig::INIT_SPACE,
+        seeds = [b"owner_multisig", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+
+    /// CHECK: Becomes the bridge owner; signs executed proposals
+    #[account(
+        seeds = [b"multisig_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub multisig_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Propose-admin-action accounts
+ */
+#[derive(Accounts)]
+pub struct ProposeAdminAction<'info> {
+    #[account(mut)]
+    pub proposer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"owner_multisig", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+
+    #[account(
+        init,
+        payer = proposer,
+        space = 8 + AdminProposal::INIT_SPACE,
+        seeds = [
+            b"admin_proposal",
+            bridge_state.instance_seed.as_slice(),
+            owner_multisig.proposal_count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Approve-admin-action accounts
+ */
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ApproveAdminAction<'info> {
+    pub owner: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"owner_multisig", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_proposal",
+            bridge_state.instance_seed.as_slice(),
+            proposal_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+}
+
+/**
+ * Execute-admin-action accounts
+ *
+ * The proposal's own accounts follow as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(proposal_id: u64)]
+pub struct ExecuteAdminAction<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"owner_multisig", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub owner_multisig: Account<'info, OwnerMultisig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"admin_proposal",
+            bridge_state.instance_seed.as_slice(),
+            proposal_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub proposal: Account<'info, AdminProposal>,
+
+    #[account(
+        mut,
+        seeds = [b"multisig_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub multisig_authority: SystemAccount<'info>,
+}
+
+/**
+ * Transfer-ownership accounts
+ */
+#[derive(Accounts)]
+pub struct TransferOwnership<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+}
+
+/**
+ * Accept-ownership accounts
+ */
+#[derive(Accounts)]
+pub struct AcceptOwnership<'info> {
+    pub new_owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+}
+
+/**
+ * Transfer-upgrade-authority accounts
+ */
+#[derive(Accounts)]
+pub struct TransferUpgradeAuthority<'info> {
+    /// The program's current upgrade authority
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// CHECK: Only its address is used; becomes the upgrade authority
+    #[account(address = bridge_state.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub program: Program<'info, crate::program::SolanaBridge>,
+
+    #[account(
+        mut,
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + UpgradeAuthorityRecord::INIT_SPACE,
+        seeds = [b"upgrade_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub upgrade_record: Account<'info, UpgradeAuthorityRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Finalize-program accounts
+ *
+ * The upgrade record must exist; `transfer_upgrade_authority` or
+ * `sync_upgrade_authority` creates it.
+ */
+#[derive(Accounts)]
+pub struct FinalizeProgram<'info> {
+    /// Bridge owner, which must also be the upgrade authority
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub program: Program<'info, crate::program::SolanaBridge>,
+
+    #[account(
+        mut,
+        constraint = program.programdata_address()? == Some(program_data.key())
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        mut,
+        seeds = [b"upgrade_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub upgrade_record: Account<'info, UpgradeAuthorityRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// CHECK: The BPF upgradeable loader
+    #[account(address = bpf_loader_upgradeable::ID)]
+    pub bpf_loader: UncheckedAccount<'info>,
+}
+
+/**
+ * Sync-upgrade-authority accounts
+ */
+#[derive(Accounts)]
+pub struct SyncUpgradeAuthority<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub program: Program<'info, crate::program::SolanaBridge>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + UpgradeAuthorityRecord::INIT_SPACE,
+        seeds = [b"upgrade_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub upgrade_record: Account<'info, UpgradeAuthorityRecord>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Record-build accounts
+ */
+#[derive(Accounts)]
+pub struct RecordBuild<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub program: Program<'info, crate::program::SolanaBridge>,
+
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program_data: Account<'info, ProgramData>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + BuildRecord::INIT_SPACE,
+        seeds = [b"build_record", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub build_record: Account<'info, BuildRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Relayer-reward config accounts
+ */
+#[derive(Accounts)]
+pub struct SetRelayerRewards<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + RelayerRewardConfig::INIT_SPACE,
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim-relayer-rewards accounts
+ */
+#[derive(Accounts)]
+#[instruction(epoch: u64)]
+pub struct ClaimRelayerRewards<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        seeds = [
+            b"reward_epoch",
+            bridge_state.instance_seed.as_slice(),
+            epoch.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub reward_epoch: Account<'info, RewardEpoch>,
+
+    #[account(
+        mut,
+        close = relayer,
+        seeds = [
+            b"relayer_epoch",
+            bridge_state.instance_seed.as_slice(),
+            epoch.to_le_bytes().as_ref(),
+            relayer.key().as_ref()
+        ],
+        bump
+    )]
+    pub relayer_stats: Account<'info, RelayerEpochStats>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Fee-tier accounts
+ */
+#[derive(Accounts)]
+pub struct SetFeeTiers<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + FeeTiers::INIT_SPACE,
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Pause-bond config accounts
+ */
+#[derive(Accounts)]
+pub struct SetPauseBondConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PauseBondConfig::INIT_SPACE,
+        seeds = [b"pause_bond_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub pause_bond_config: Account<'info, PauseBondConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Bonded-pause accounts
+ *
+ * Only one bonded pause can be open at a time.
+ */
+#[derive(Accounts)]
+pub struct BondedPause<'info> {
+    #[account(mut)]
+    pub pauser: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"pause_bond_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub pause_bond_config: Account<'info, PauseBondConfig>,
+
+    #[account(
+        init,
+        payer = pauser,
+        space = 8 + BondedPauseState::INIT_SPACE,
+        seeds = [b"bonded_pause", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bonded_pause: Account<'info, BondedPauseState>,
+
+    #[account(
+        mut,
+        seeds = [b"pause_bond_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Bonded-pause resolution accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct ResolveBondedPause<'info> {
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"pause_bond_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub pause_bond_config: Account<'info, PauseBondConfig>,
+
+    #[account(
+        mut,
+        close = pauser,
+        seeds = [b"bonded_pause", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bonded_pause: Account<'info, BondedPauseState>,
+
+    /// CHECK: Account that raised the pause (gets rent and maybe the bond)
+    #[account(mut, address = bonded_pause.pauser)]
+    pub pauser: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pause_bond_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub insurance_fund: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Create-wrapped-mint accounts
+ */
+#[derive(Accounts)]
+#[instruction(source_token: [u8; 20], source_decimals: u8, decimals: u8)]
+pub struct CreateWrappedMint<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        seeds = [b"wrapped_mint", bridge_state.instance_seed.as_slice(), source_token.as_ref()],
+        bump,
+        mint::decimals = decimals,
+        mint::authority = bridge_authority,
+        mint::freeze_authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + WrappedAsset::INIT_SPACE,
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    /// CHECK: Created by the token-metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), wrapped_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: PDA mint, freeze and metadata update authority
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Update-wrapped-metadata accounts
+ */
+#[derive(Accounts)]
+pub struct UpdateWrappedMetadata<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_asset.mint.as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    /// CHECK: Owned by the token-metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), wrapped_asset.mint.as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: PDA metadata update authority
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+}
+
+/**
+ * Sync-metadata accounts
+ */
+#[derive(Accounts)]
+pub struct SyncMetadata<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers attests the ERC-20 read
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_asset.mint.as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + MetadataSync::INIT_SPACE,
+        seeds = [
+            b"metadata_sync",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_asset.mint.as_ref()
+        ],
+        bump
+    )]
+    pub metadata_sync: Account<'info, MetadataSync>,
+
+    /// CHECK: Owned by the token-metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), wrapped_asset.mint.as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: PDA metadata update authority
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Listing-config accounts
+ */
+#[derive(Accounts)]
+pub struct SetListingConfig<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ListingConfig::INIT_SPACE,
+        seeds = [b"listing_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub listing_config: Account<'info, ListingConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * List-token accounts
+ */
+#[derive(Accounts)]
+#[instruction(source_token: [u8; 20])]
+pub struct ListToken<'info> {
+    #[account(mut)]
+    pub lister: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"listing_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub listing_config: Account<'info, ListingConfig>,
+
+    #[account(
+        init,
+        payer = lister,
+        space = 8 + TokenListing::INIT_SPACE,
+        seeds = [b"token_listing", bridge_state.instance_seed.as_slice(), source_token.as_ref()],
+        bump
+    )]
+    pub listing: Account<'info, TokenListing>,
+
+    /// CHECK: The wrapped mint PDA; must not exist yet
+    #[account(
+        seeds = [b"wrapped_mint", bridge_state.instance_seed.as_slice(), source_token.as_ref()],
+        bump,
+        constraint = wrapped_mint.data_is_empty()
+    )]
+    pub wrapped_mint: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Listing-veto accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct VetoListing<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        close = lister,
+        seeds = [
+            b"token_listing",
+            bridge_state.instance_seed.as_slice(),
+            listing.source_token.as_ref()
+        ],
+        bump
+    )]
+    pub listing: Account<'info, TokenListing>,
+
+    /// CHECK: Account that listed the token (gets the listing's rent back)
+    #[account(mut, address = listing.lister)]
+    pub lister: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"insurance_fund", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub insurance_fund: SystemAccount<'info>,
+}
+
+/**
+ * Listing-activation accounts
+ */
+#[derive(Accounts)]
+pub struct ActivateListing<'info> {
+    /// Anyone; pays for the wrapped mint and its accounts
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        close = lister,
+        seeds = [
+            b"token_listing",
+            bridge_state.instance_seed.as_slice(),
+            listing.source_token.as_ref()
+        ],
+        bump
+    )]
+    pub listing: Account<'info, TokenListing>,
+
+    /// CHECK: Account that listed the token (gets the bond and rent back)
+    #[account(mut, address = listing.lister)]
+    pub lister: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = payer,
+        seeds = [
+            b"wrapped_mint",
+            bridge_state.instance_seed.as_slice(),
+            listing.source_token.as_ref()
+        ],
+        bump,
+        mint::decimals = listing.decimals,
+        mint::authority = bridge_authority,
+        mint::freeze_authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + WrappedAsset::INIT_SPACE,
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    /// Registers the wrapped mint, so it bridges without register_token
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + TokenConfig::INIT_SPACE,
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Created by the token-metadata program
+    #[account(
+        mut,
+        seeds = [b"metadata", token_metadata_program.key().as_ref(), wrapped_mint.key().as_ref()],
+        bump,
+        seeds::program = token_metadata_program.key()
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: PDA mint, freeze and metadata update authority
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_metadata_program: Program<'info, Metadata>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/**
+ * Token-accounting accounts
+ */
+#[derive(Accounts)]
+pub struct SetTokenAccounting<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), token_config.mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ConversionRate::INIT_SPACE,
+        seeds = [
+            b"conversion_rate",
+            bridge_state.instance_seed.as_slice(),
+            token_config.mint.as_ref()
+        ],
+        bump
+    )]
+    pub conversion_rate: Account<'info, ConversionRate>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Conversion-rate update accounts
+ */
+#[derive(Accounts)]
+pub struct UpdateConversionRate<'info> {
+    pub oracle: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"conversion_rate",
+            bridge_state.instance_seed.as_slice(),
+            conversion_rate.mint.as_ref()
+        ],
+        bump
+    )]
+    pub conversion_rate: Account<'info, ConversionRate>,
+}
+
+/**
+ * Configure-inbox accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct ConfigureInbox<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Inbox::INIT_SPACE,
+        seeds = [b"inbox", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub inbox: Account<'info, Inbox>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Configure-throttle accounts
+ */
+#[derive(Accounts)]
+pub struct ConfigureThrottle<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Throttle::INIT_SPACE,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: Account<'info, Throttle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Configure-priority-tips accounts
+ */
+#[derive(Accounts)]
+pub struct ConfigurePriorityTips<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PriorityConfig::INIT_SPACE,
+        seeds = [b"priority_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub priority_config: Account<'info, PriorityConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Bid-priority accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct BidPriority<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Outbound throttle; bids are only taken while it says congested
+    #[account(
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"priority_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub priority_config: Account<'info, PriorityConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = sender,
+        space = 8 + PriorityBid::INIT_SPACE,
+        seeds = [
+            b"priority_bid",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bid: Account<'info, PriorityBid>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Collect-priority-tip accounts (permissionless)
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct CollectPriorityTip<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        seeds = [b"priority_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub priority_config: Account<'info, PriorityConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"priority_bid",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump,
+        close = sender
+    )]
+    pub bid: Account<'info, PriorityBid>,
+
+    /// CHECK: Gets the bid's rent back, and the tip if the transfer was refunded
+    #[account(mut, address = bid.sender)]
+    pub sender: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_vault: SystemAccount<'info>,
+
+    /// CHECK: Takes the treasury's share
+    #[account(mut, address = priority_config.treasury)]
+    pub treasury: UncheckedAccount<'info>,
+}
+
+/**
+ * Cancel-pending accounts
+ *
+ * Inbound cancellations need attestation, bond_vault and relayer;
+ * outbound ones mint, token_config, refund_token and token_program, plus
+ * bridge_token (and conversion_rate for share tokens) for locks, and the
+ * receipt accounts if the sender took a deposit receipt.
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CancelPending<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(mut)]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"attestation",
+            bridge_state.instance_seed.as_slice(),
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub attestation: Option<Account<'info, Attestation>>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"bond_vault",
+            bridge_state.instance_seed.as_slice(),
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub bond_vault: Option<SystemAccount<'info>>,
+
+    /// CHECK: Must be the attestation's relayer (checked in the handler)
+    #[account(mut)]
+    pub relayer: Option<UncheckedAccount<'info>>,
+
+    #[account(mut)]
+    pub mint: Option<InterfaceAccount<'info, InterfaceMint>>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), transfer_record.mint.as_ref()],
+        bump
+    )]
+    pub token_config: Option<Account<'info, TokenConfig>>,
+
+    #[account(
+        seeds = [
+            b"conversion_rate",
+            bridge_state.instance_seed.as_slice(),
+            transfer_record.mint.as_ref()
+        ],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    /// Token account the refund goes to: the sender's, or the lock's
+    /// recovery address's
+    #[account(mut)]
+    pub refund_token: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    #[account(mut)]
+    pub bridge_token: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: PDA that owns the escrow and mints wrapped tokens
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Option<Interface<'info, TokenInterface>>,
+
+    pub system_program: Program<'info, System>,
+
+    /// CHECK: Deposit receipt mint, if the transfer has one (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sender's receipt token account (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_token: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+/**
+ * Attest/ack-outbound accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct AttestOutbound<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound delivery SLA; empty unless one was set (see SlaConfig)
+    #[account(
+        seeds = [b"sla", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sla_config: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set; a quorum of its signers may attest without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that burns deposit receipts as their permanent delegate
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    /// CHECK: Deposit receipt mint, if the transfer has one (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sender's receipt token account (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_token: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+/**
+ * Reclaim-expired accounts
+ *
+ * Transfer-hook mints take their extra accounts as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ReclaimExpired<'info> {
+    pub sender: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    /// Token account the refund goes to: the sender's, or the recovery
+    /// address's if the lock named one (checked in the handler)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub refund_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut)]
+    pub bridge_token: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: PDA that owns the escrow and mints wrapped tokens
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Deposit receipt mint, if the transfer has one (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_mint: Option<UncheckedAccount<'info>>,
+
+    /// CHECK: Sender's receipt token account (checked in receipt::retire)
+    #[account(mut)]
+    pub receipt_token: Option<UncheckedAccount<'info>>,
+
+    pub token_2022_program: Option<Program<'info, Token2022>>,
+}
+
+/**
+ * Assign-refund accounts
+ *
+ * Transfer-hook mints take their extra accounts as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct AssignRefund<'info> {
+    pub assignee: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(address = transfer_record.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// Pays the price
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = assignee,
+        token::token_program = token_program
+    )]
+    pub assignee_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Receives the price; owned by the current refund owner (checked in the handler)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub holder_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    /// CHECK: Instructions sysvar, to find the holder's signed assignment
+    /// (see refund_assign.rs)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: UncheckedAccount<'info>,
+}
+
+/**
+ * Settle-batch accounts
+ *
+ * Remaining accounts: the legs' transfer record PDAs in outcome order,
+ * then any transfer-hook accounts for the refund.
+ */
+#[derive(Accounts)]
+pub struct SettleBatch<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound delivery SLA; empty unless one was set (see SlaConfig)
+    #[account(
+        seeds = [b"sla", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sla_config: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    /// Token account the refunds go to, the sender's or the legs' recovery
+    /// address's (checked against the legs)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub refund_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Create-guardian-council accounts
+ */
+#[derive(Accounts)]
+pub struct CreateGuardianCouncil<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + GuardianCouncil::INIT_SPACE,
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: Account<'info, GuardianCouncil>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Council accounts
+ *
+ * Council signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CouncilAction<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: Account<'info, GuardianCouncil>,
+}
+
+/**
+ * Post-reserve-attestation accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct PostReserveAttestation<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + ReserveAttestation::INIT_SPACE,
+        seeds = [b"reserve", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub reserve_attestation: Account<'info, ReserveAttestation>,
+
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = 8 + RedemptionQueue::INIT_SPACE,
+        seeds = [b"redemption_queue", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Reserve-status view accounts
+ */
+#[derive(Accounts)]
+pub struct ReserveStatusView<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"reserve", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub reserve_attestation: Account<'info, ReserveAttestation>,
+}
+
+/**
+ * Attest-vault-balance accounts
+ *
+ * Guardian signers are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct AttestVaultBalance<'info> {
+    pub relayer: Signer<'info>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    /// The token's escrow (its hot vault, if split)
+    #[account(
+        token::mint = mint,
+        token::authority = bridge_authority
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// Required when the escrow is split
+    #[account(
+        token::mint = mint,
+        token::authority = cold_authority
+    )]
+    pub cold_vault: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    /// CHECK: PDA that owns cold vaults
+    #[account(
+        seeds = [b"cold_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub cold_authority: AccountInfo<'info>,
+
+    /// CHECK: The token's yield strategy; may be empty (checked in the handler)
+    #[account(
+        seeds = [b"yield_strategy", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub yield_strategy: UncheckedAccount<'info>,
+}
+
+/**
+ * Enqueue-redemption accounts
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, dest_chain_id: u64)]
+pub struct EnqueueRedemption<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(mut)]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"redemption_queue",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+
+    #[account(
+        init,
+        payer = user,
+        space = 8 + RedemptionClaim::INIT_SPACE,
+        seeds = [
+            b"redemption_claim",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref(),
+            redemption_queue.next_claim_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub claim: Account<'info, RedemptionClaim>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint,
+        token::authority = user
+    )]
+    pub user_token: Account<'info, TokenAccount>,
+
+    pub token_program: Program<'info, Token>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Release-redemption accounts
+ */
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct ReleaseRedemption<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption_queue", bridge_state.instance_seed.as_slice(), claim.mint.as_ref()],
+        bump
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"redemption_claim",
+            bridge_state.instance_seed.as_slice(),
+            claim.mint.as_ref(),
+            claim_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub claim: Account<'info, RedemptionClaim>,
+}
+
+/**
+ * Confirm-redemption accounts
+ *
+ * Guardian signers, standing in for the owner, are the remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(claim_id: u64)]
+pub struct ConfirmRedemption<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers may confirm without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"redemption_queue", bridge_state.instance_seed.as_slice(), claim.mint.as_ref()],
+        bump
+    )]
+    pub redemption_queue: Account<'info, RedemptionQueue>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"redemption_claim",
+            bridge_state.instance_seed.as_slice(),
+            claim.mint.as_ref(),
+            claim_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub claim: Account<'info, RedemptionClaim>,
+
+    /// Gets the claim's rent back once it's paid in full
+    #[account(mut, address = claim.owner)]
+    pub claim_owner: SystemAccount<'info>,
+}
+
+/**
+ * Init-token-oracle accounts
+ */
+#[derive(Accounts)]
+pub struct InitTokenOracle<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<TokenOracle>(),
+        seeds = [
+            b"token_oracle",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub token_oracle: AccountLoader<'info, TokenOracle>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Init-event-history accounts
+ */
+#[derive(Accounts)]
+pub struct InitEventHistory<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Only registered tokens get a history
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + std::mem::size_of::<EventHistory>(),
+        seeds = [b"event_history", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub event_history: AccountLoader<'info, EventHistory>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Init-route-sequence accounts
+ */
+#[derive(Accounts)]
+#[instruction(dest_chain_id: u64)]
+pub struct InitRouteSequence<'info> {
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Hands the sequence its shard
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// Only registered tokens, to supported chains, get a sequence
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init,
+        payer = payer,
+        space = 8 + RouteSequence::INIT_SPACE,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Refresh-token-oracle accounts
+ */
+#[derive(Accounts)]
+pub struct RefreshTokenOracle<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_asset.source_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub origin_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council; may not exist
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: The token's reserve attestation; may not exist
+    #[account(
+        seeds = [b"reserve", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub reserve_attestation: UncheckedAccount<'info>,
+
+    /// CHECK: The token's redemption queue; may not exist
+    #[account(
+        seeds = [
+            b"redemption_queue",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub redemption_queue: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"token_oracle",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub token_oracle: AccountLoader<'info, TokenOracle>,
+}
+
+/**
+ * Configure-vaults accounts
+ */
+#[derive(Accounts)]
+pub struct ConfigureVaults<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// The token's escrow
+    #[account(
+        token::mint = mint,
+        token::authority = bridge_authority
+    )]
+    pub hot_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        token::mint = mint,
+        token::authority = cold_authority
+    )]
+    pub cold_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    /// CHECK: PDA that owns cold vaults
+    #[account(
+        seeds = [b"cold_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub cold_authority: AccountInfo<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + VaultConfig::INIT_SPACE,
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set-vault-policy accounts
+ */
+#[derive(Accounts)]
+pub struct SetVaultPolicy<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+}
+
+/**
+ * Set-burn-approval accounts
+ */
+#[derive(Accounts)]
+pub struct SetBurnApproval<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    pub mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + BurnApproval::INIT_SPACE,
+        seeds = [b"burn_approval", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub burn_approval: Account<'info, BurnApproval>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Rebalance-vault accounts
+ *
+ * Transfer-hook accounts, if the mint has a hook, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct RebalanceVault<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = vault_config.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    #[account(mut, address = vault_config.hot_vault)]
+    pub hot_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut, address = vault_config.cold_vault)]
+    pub cold_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Execute-refill accounts
+ *
+ * Transfer-hook accounts, if the mint has a hook, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct ExecuteRefill<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = vault_config.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    #[account(mut, address = vault_config.hot_vault)]
+    pub hot_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut, address = vault_config.cold_vault)]
+    pub cold_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns cold vaults
+    #[account(
+        seeds = [b"cold_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub cold_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Cancel-refill accounts
+ *
+ * Guardian signers, standing in for the owner, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CancelRefill<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers may cancel without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+}
+
+/**
+ * Allow-yield-venue accounts
+ */
+#[derive(Accounts)]
+pub struct AllowYieldVenue<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// Only a split escrow has a cold vault to deploy from
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    /// CHECK: The adapter program; only its address is recorded
+    #[account(executable)]
+    pub adapter: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + YieldStrategy::INIT_SPACE,
+        seeds = [
+            b"yield_strategy",
+            bridge_state.instance_seed.as_slice(),
+            vault_config.mint.as_ref()
+        ],
+        bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-yield-venue accounts
+ *
+ * Guardian signers, standing in for the owner, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct RemoveYieldVenue<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers may remove without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"yield_strategy",
+            bridge_state.instance_seed.as_slice(),
+            yield_strategy.mint.as_ref()
+        ],
+        bump = yield_strategy.bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+}
+
+/**
+ * Deposit-yield accounts
+ *
+ * The venue's own accounts, which the adapter is handed, are the
+ * remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct DepositYield<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = vault_config.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    #[account(mut, address = vault_config.cold_vault)]
+    pub cold_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns cold vaults
+    #[account(
+        seeds = [b"cold_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub cold_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_strategy", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump = yield_strategy.bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+
+    /// CHECK: The venue's adapter program; checked against the strategy in the handler
+    #[account(executable)]
+    pub adapter: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs for the venue's staging account and positions
+    #[account(
+        seeds = [
+            b"yield_venue",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            adapter.key().as_ref()
+        ],
+        bump
+    )]
+    pub venue_authority: AccountInfo<'info>,
+
+    /// Where deposits pass through on their way into the venue, and recalls on the way out
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = venue_authority
+    )]
+    pub staging: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Recall-yield accounts
+ *
+ * Guardian signers, standing in for the owner, come first in the
+ * remaining accounts; the venue's own accounts, which the adapter is
+ * handed, follow them.
+ */
+#[derive(Accounts)]
+pub struct RecallYield<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers may recall without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = vault_config.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), vault_config.mint.as_ref()],
+        bump
+    )]
+    pub vault_config: Account<'info, VaultConfig>,
+
+    #[account(mut, address = vault_config.cold_vault)]
+    pub cold_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        seeds = [b"yield_strategy", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump = yield_strategy.bump
+    )]
+    pub yield_strategy: Account<'info, YieldStrategy>,
+
+    /// CHECK: The venue's adapter program; checked against the strategy in the handler
+    #[account(executable)]
+    pub adapter: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that signs for the venue's staging account and positions
+    #[account(
+        seeds = [
+            b"yield_venue",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            adapter.key().as_ref()
+        ],
+        bump
+    )]
+    pub venue_authority: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = venue_authority
+    )]
+    pub staging: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        seeds = [b"insurance_fund", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub insurance_fund: SystemAccount<'info>,
+
+    /// The insurance fund's account for the token, which yield is paid into
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = insurance_fund
+    )]
+    pub insurance_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Transfer-status view accounts
+ */
+#[derive(Accounts)]
+pub struct TransferStatusView<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: The transfer's record PDA; may be empty (checked in the handler)
+    pub transfer_record: UncheckedAccount<'info>,
+
+    /// CHECK: The transfer's attestation PDA, if any (checked in the handler)
+    pub attestation: Option<UncheckedAccount<'info>>,
+}
+
+/**
+ * Lock-quote view accounts (the read-only half of Lock)
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, amount: u64, dest_chain_id: u64, user: Pubkey)]
+pub struct QuoteLockView<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// The token's sequence to this chain, which numbers the next lock
+    #[account(
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    /// CHECK: The token's fee route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// CHECK: The user's stats; empty before their first transfer
+    #[account(
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: The user's fee exemption; empty unless one was granted
+    #[account(
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub fee_exemption: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+}
+
+/**
+ * Bridge-info view accounts
+ *
+ * Chain configs to report are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct BridgeInfoView<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// Omit if `set_fee_tiers` was never called
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Option<Account<'info, FeeTiers>>,
+}
+
+/**
+ * Checkpoint accounts
+ *
+ * Transfer records to commit are passed as remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CreateCheckpoint<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + CheckpointLog::INIT_SPACE,
+        seeds = [b"checkpoint_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub checkpoint_log: Account<'info, CheckpointLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + Checkpoint::INIT_SPACE,
+        seeds = [
+            b"checkpoint",
+            bridge_state.instance_seed.as_slice(),
+            checkpoint_log.count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub checkpoint: Account<'info, Checkpoint>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Prune-transfer-record accounts
+ */
+#[derive(Accounts)]
+#[instruction(checkpoint_index: u64)]
+pub struct PruneTransferRecord<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        seeds = [
+            b"checkpoint",
+            bridge_state.instance_seed.as_slice(),
+            checkpoint_index.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub checkpoint: Account<'info, Checkpoint>,
+
+    #[account(
+        mut,
+        close = owner
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+}
+
+/**
+ * Set-archiver accounts
+ */
+#[derive(Accounts)]
+pub struct SetArchiver<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + ArchiveLog::INIT_SPACE,
+        seeds = [b"archive_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub archive_log: Account<'info, ArchiveLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Record-archive accounts
+ */
+#[derive(Accounts)]
+pub struct RecordArchive<'info> {
+    /// The archiver, or the owner
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"archive_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub archive_log: Account<'info, ArchiveLog>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + Archive::INIT_SPACE,
+        seeds = [
+            b"archive",
+            bridge_state.instance_seed.as_slice(),
+            archive_log.count.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub archive: Account<'info, Archive>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Init-compressed-store accounts
+ */
+#[derive(Accounts)]
+pub struct InitCompressedStore<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + CompressedStore::INIT_SPACE,
+        seeds = [b"compressed_store", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub compressed_store: Account<'info, CompressedStore>,
+
+    /// CHECK: Allocated by the client; the compression program checks its size
+    #[account(mut, owner = compression::COMPRESSION_PROGRAM)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that is every tree's authority
+    #[account(
+        seeds = [b"tree_authority", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SPL account-compression program
+    #[account(address = compression::COMPRESSION_PROGRAM)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL noop program
+    #[account(address = compression::NOOP_PROGRAM)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Compress-transfer-record accounts
+ */
+#[derive(Accounts)]
+pub struct CompressTransferRecord<'info> {
+    /// CHECK: Receives the record's rent
+    #[account(mut, address = bridge_state.owner)]
+    pub owner: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"compressed_store", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub compressed_store: Account<'info, CompressedStore>,
+
+    /// CHECK: The compression program checks it; must be the store's tree
+    #[account(mut, address = compressed_store.tree)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: PDA that is every tree's authority
+    #[account(
+        seeds = [b"tree_authority", bridge_state.instance_seed.as_slice()],
+        bump = compressed_store.tree_authority_bump
+    )]
+    pub tree_authority: UncheckedAccount<'info>,
+
+    /// CHECK: SPL account-compression program
+    #[account(address = compression::COMPRESSION_PROGRAM)]
+    pub compression_program: UncheckedAccount<'info>,
+
+    /// CHECK: SPL noop program
+    #[account(address = compression::NOOP_PROGRAM)]
+    pub noop_program: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        close = owner
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+}
+
+/**
+ * Verify-compressed-transfer accounts
+ *
+ * Pass the proof nodes as remaining accounts, from the leaf up.
+ */
+#[derive(Accounts)]
+pub struct VerifyCompressedTransfer<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Checked by the compression program
+    #[account(owner = compression::COMPRESSION_PROGRAM)]
+    pub merkle_tree: UncheckedAccount<'info>,
+
+    /// CHECK: SPL account-compression program
+    #[account(address = compression::COMPRESSION_PROGRAM)]
+    pub compression_program: UncheckedAccount<'info>,
+}
+
+/**
+ * Prune accounts
+ */
+#[derive(Accounts)]
+pub struct Prune<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"checkpoint_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub checkpoint_log: Account<'info, CheckpointLog>,
+}
+
+/**
+ * State import accounts
+ */
+#[derive(Accounts)]
+pub struct ImportState<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Migration::INIT_SPACE,
+        seeds = [b"migration", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * User-stats import accounts
+ */
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct ImportUserStats<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"migration", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Route-sequence import accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, dest_chain_id: u64)]
+pub struct ImportRouteSequence<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        seeds = [b"migration", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + RouteSequence::INIT_SPACE,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Inbox import accounts (the inbox must be configured first)
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct ImportInbox<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        seeds = [b"migration", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    #[account(
+        mut,
+        seeds = [b"inbox", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub inbox: Account<'info, Inbox>,
+}
+
+/**
+ * Seal-import accounts
+ */
+#[derive(Accounts)]
+pub struct SealImport<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + Migration::INIT_SPACE,
+        seeds = [b"migration", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub migration: Account<'info, Migration>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Queue-escrow-migration accounts
+ */
+#[derive(Accounts)]
+pub struct QueueEscrowMigration<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// The successor's escrow; its owner is checked in the handler
+    #[account(token::mint = mint)]
+    pub new_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + EscrowMigration::INIT_SPACE,
+        seeds = [b"escrow_migration", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_migration: Account<'info, EscrowMigration>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Cancel-escrow-migration accounts
+ *
+ * Guardian signers, standing in for the owner, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct CancelEscrowMigration<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: Guardian set; a quorum of its signers may cancel without the owner
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"escrow_migration",
+            bridge_state.instance_seed.as_slice(),
+            escrow_migration.mint.as_ref()
+        ],
+        bump
+    )]
+    pub escrow_migration: Account<'info, EscrowMigration>,
+}
+
+/**
+ * Migrate-escrow accounts
+ *
+ * Transfer-hook accounts, if the mint has a hook, are the remaining accounts.
+ */
+#[derive(Accounts)]
+pub struct MigrateEscrow<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = escrow_migration.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"escrow_migration", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub escrow_migration: Account<'info, EscrowMigration>,
+
+    /// The token's escrow (its hot vault, if split)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(mut, address = escrow_migration.new_vault)]
+    pub new_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// Required when the escrow is split
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = cold_authority,
+        token::token_program = token_program
+    )]
+    pub cold_vault: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    /// CHECK: PDA that owns cold vaults
+    #[account(
+        seeds = [b"cold_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub cold_authority: AccountInfo<'info>,
+
+    /// CHECK: The token's yield strategy; may be empty (checked in the handler)
+    #[account(
+        seeds = [b"yield_strategy", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub yield_strategy: UncheckedAccount<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Send-to-instance accounts
+ *
+ * Guardian signers and any transfer-hook accounts are the remaining
+ * accounts.
+ */
+#[derive(Accounts)]
+#[instruction(id: u64)]
+pub struct SendToInstance<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// CHECK: Guardian set; a quorum of its signers co-signs the transfer
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + InstanceTransfer::INIT_SPACE,
+        seeds = [
+            b"instance_transfer",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub instance_transfer: Account<'info, InstanceTransfer>,
+
+    /// The token's escrow (its hot vault, if split)
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// The receiving instance's escrow; its owner is checked in the handler
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub dest_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Receive-instance-transfer accounts
+ */
+#[derive(Accounts)]
+#[instruction(source_instance: u64, mint: Pubkey, id: u64)]
+pub struct ReceiveInstanceTransfer<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// The sending instance's record of the transfer
+    #[account(
+        seeds = [
+            b"instance_transfer",
+            instance::seed(&source_instance),
+            mint.as_ref(),
+            id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub source_record: Account<'info, InstanceTransfer>,
+
+    /// This instance's escrow the transfer went to
+    #[account(
+        token::authority = bridge_authority
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + InstanceReceipt::INIT_SPACE,
+        seeds = [
+            b"instance_receipt",
+            bridge_state.instance_seed.as_slice(),
+            source_record.key().as_ref()
+        ],
+        bump
+    )]
+    pub instance_receipt: Account<'info, InstanceReceipt>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Bonded-pause confirmation accounts
+ *
+ * The confirming guardian signs as a remaining account.
+ */
+#[derive(Accounts)]
+pub struct ConfirmBondedPause<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        seeds = [b"pause_bond_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub pause_bond_config: Account<'info, PauseBondConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"bonded_pause", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bonded_pause: Account<'info, BondedPauseState>,
+}
+
+/**
+ * Bonded-pause expiry accounts
+ */
+#[derive(Accounts)]
+pub struct ExpireBondedPause<'info> {
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        close = pauser,
+        seeds = [b"bonded_pause", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bonded_pause: Account<'info, BondedPauseState>,
+
+    /// CHECK: Account that raised the pause (gets the bond and rent back)
+    #[account(mut, address = bonded_pause.pauser)]
+    pub pauser: AccountInfo<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"pause_bond_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bond_vault: SystemAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Heartbeat accounts (the operator pays for its liveness entry)
+ */
+#[derive(Accounts)]
+pub struct Heartbeat<'info> {
+    #[account(mut)]
+    pub operator: Signer<'info>,
+
+    /// CHECK: Guardian set PDA; empty until guardians are set
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = operator,
+        space = 8 + OperatorLiveness::INIT_SPACE,
+        seeds = [b"liveness", bridge_state.instance_seed.as_slice(), operator.key().as_ref()],
+        bump
+    )]
+    pub liveness: Account<'info, OperatorLiveness>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Route health report accounts (the reporter pays for the route's entry)
+ */
+#[derive(Accounts)]
+#[instruction(route_id: u32)]
+pub struct ReportRouteHealth<'info> {
+    #[account(mut)]
+    pub reporter: Signer<'info>,
+
+    /// CHECK: Guardian set PDA; empty until guardians are set
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// CHECK: The relayer's (owner's) liveness entry; empty if it never beat
+    #[account(
+        seeds = [b"liveness", bridge_state.instance_seed.as_slice(), bridge_state.owner.as_ref()],
+        bump
+    )]
+    pub relayer_liveness: UncheckedAccount<'info>,
+
+    #[account(
+        init_if_needed,
+        payer = reporter,
+        space = 8 + RouteHealth::INIT_SPACE,
+        seeds = [
+            b"route_health",
+            bridge_state.instance_seed.as_slice(),
+            route_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_health: Account<'info, RouteHealth>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Guardian heartbeat accounts
+ */
+#[derive(Accounts)]
+pub struct GuardianHeartbeat<'info> {
+    pub guardian: Signer<'info>,
+
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        mut,
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+}
+
+/**
+ * Pin-recipient-name accounts
+ *
+ * Any transfer record the program owns; which one is up to the caller.
+ */
+#[derive(Accounts)]
+pub struct PinRecipientName<'info> {
+    pub signer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(mut)]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: SNS name account of an inbound .sol name (checked in names::sns_owner)
+    pub sns_domain: Option<UncheckedAccount<'info>>,
+}
+
+/**
+ * Record-source-tx accounts
+ */
+#[derive(Accounts)]
+#[instruction(tx_hash: [u8; 32])]
+pub struct RecordSourceTx<'info> {
+    /// The relayer that delivered the transfer, or the owner
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + SourceTx::INIT_SPACE,
+        seeds = [b"source_tx", bridge_state.instance_seed.as_slice(), tx_hash.as_ref()],
+        bump
+    )]
+    pub source_tx: Account<'info, SourceTx>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Eject-relayer accounts
+ */
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct EjectRelayer<'info> {
+    /// The owner, or whoever submits a guardian quorum's ejection
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: Account<'info, GuardianSet>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + EjectedRelayer::INIT_SPACE,
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), relayer.as_ref()],
+        bump
+    )]
+    pub ejection: Account<'info, EjectedRelayer>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Register-relayer accounts
+ */
+#[derive(Accounts)]
+pub struct RegisterRelayer<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + RelayerRegistration::INIT_SPACE,
+        seeds = [b"relayer", bridge_state.instance_seed.as_slice(), relayer.key().as_ref()],
+        bump
+    )]
+    pub registration: Account<'info, RelayerRegistration>,
+
+    /// CHECK: The relayer's ejection record; must not exist
+    #[account(
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), relayer.key().as_ref()],
+        bump
+    )]
+    pub ejection: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Deregister-relayer accounts
+ */
+#[derive(Accounts)]
+pub struct DeregisterRelayer<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"relayer", bridge_state.instance_seed.as_slice(), relayer.key().as_ref()],
+        bump,
+        close = relayer
+    )]
+    pub registration: Account<'info, RelayerRegistration>,
+}
+
+/**
+ * Register-integrator accounts
+ */
+#[derive(Accounts)]
+#[instruction(integrator_id: u32)]
+pub struct RegisterIntegrator<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + IntegratorStats::INIT_SPACE,
+        seeds = [
+            b"integrator",
+            bridge_state.instance_seed.as_slice(),
+            integrator_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub integrator: Account<'info, IntegratorStats>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Reinstate-relayer accounts
+ */
+#[derive(Accounts)]
+#[instruction(relayer: Pubkey)]
+pub struct ReinstateRelayer<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"ejected", bridge_state.instance_seed.as_slice(), relayer.as_ref()],
+        bump
+    )]
+    pub ejection: Account<'info, EjectedRelayer>,
+}
+
+/**
+ * Set-fee-route accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, chain_id: u64)]
+pub struct SetFeeRoute<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// The chain must be registered
+    #[account(
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// And the token
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + FeeRoute::INIT_SPACE,
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: Account<'info, FeeRoute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-fee-route accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, chain_id: u64)]
+pub struct RemoveFeeRoute<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: Account<'info, FeeRoute>,
+}
+
+/**
+ * Set-SOL-fee accounts
+ */
+#[derive(Accounts)]
+pub struct SetSolFee<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// The token must be registered
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SolFeeConfig::INIT_SPACE,
+        seeds = [b"sol_fee", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub sol_fee_config: Account<'info, SolFeeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Withdraw-SOL-fees accounts
+ */
+#[derive(Accounts)]
+pub struct WithdrawSolFees<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        seeds = [b"sol_fee_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sol_fee_vault: SystemAccount<'info>,
+
+    /// CHECK: Any account; only receives lamports
+    #[account(mut)]
+    pub destination: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set-delivery-fee accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct SetDeliveryFee<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// The chain must be registered
+    #[account(
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + DeliveryFeeConfig::INIT_SPACE,
+        seeds = [
+            b"delivery_fee",
+            bridge_state.instance_seed.as_slice(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub delivery_fee_config: Account<'info, DeliveryFeeConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set-delivery-gas-price accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct SetDeliveryGasPrice<'info> {
+    /// Owner or fee manager
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"delivery_fee",
+            bridge_state.instance_seed.as_slice(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub delivery_fee_config: Account<'info, DeliveryFeeConfig>,
+}
+
+/**
+ * Set-price-breaker accounts
+ */
+#[derive(Accounts)]
+pub struct SetPriceBreaker<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    /// The token must be registered
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + PriceBreaker::INIT_SPACE,
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: Account<'info, PriceBreaker>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Poke-price-breaker accounts
+ */
+#[derive(Accounts)]
+pub struct PokePriceBreaker<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        mut,
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: Account<'info, PriceBreaker>,
+
+    /// CHECK: Pyth price update for the token; checked in pyth::load_price
+    pub price_update: UncheckedAccount<'info>,
+}
+
+/**
+ * Set-terms accounts
+ */
+#[derive(Accounts)]
+pub struct SetTerms<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + TermsConfig::INIT_SPACE,
+        seeds = [b"terms", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub terms_config: Account<'info, TermsConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Acknowledge-terms accounts
+ */
+#[derive(Accounts)]
+pub struct AcknowledgeTerms<'info> {
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"terms", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub terms_config: Account<'info, TermsConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = wallet,
+        space = 8 + TermsAcceptance::INIT_SPACE,
+        seeds = [b"terms_ack", bridge_state.instance_seed.as_slice(), wallet.key().as_ref()],
+        bump
+    )]
+    pub terms_acceptance: Account<'info, TermsAcceptance>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Set-SLA accounts
+ */
+#[derive(Accounts)]
+pub struct SetSla<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SlaConfig::INIT_SPACE,
+        seeds = [b"sla", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sla_config: Account<'info, SlaConfig>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim-SLA-rebate accounts
+ *
+ * Transfer-hook mints take their extra accounts as remaining accounts.
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ClaimSlaRebate<'info> {
+    pub sender: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(address = transfer_record.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = sender,
+        token::token_program = token_program
+    )]
+    pub sender_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Set-sender-allowlist accounts
+ */
+#[derive(Accounts)]
+pub struct SetSenderAllowlist<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + SenderAllowlist::INIT_SPACE,
+        seeds = [b"sender_allowlist", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sender_allowlist: Account<'info, SenderAllowlist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Allow-sender accounts
+ */
+#[derive(Accounts)]
+#[instruction(sender: Pubkey)]
+pub struct AllowSender<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init,
+        payer = owner,
+        space = 8 + AllowedSender::INIT_SPACE,
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), sender.as_ref()],
+        bump
+    )]
+    pub allowed_sender: Account<'info, AllowedSender>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Disallow-sender accounts
+ */
+#[derive(Accounts)]
+#[instruction(sender: Pubkey)]
+pub struct DisallowSender<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), sender.as_ref()],
+        bump
+    )]
+    pub allowed_sender: Account<'info, AllowedSender>,
+}
+
+/**
+ * Register-deposit-address accounts
+ */
+#[derive(Accounts)]
+#[instruction(dest_chain_id: u64)]
+pub struct RegisterDepositAddress<'info> {
+    #[account(mut)]
+    pub user: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = user,
+        space = 8 + DepositAddress::INIT_SPACE,
+        seeds = [b"deposit", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump
+    )]
+    pub deposit_address: Account<'info, DepositAddress>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Sweep-deposit accounts (as Lock, with the deposit address in place of
+ * the user's signature; the cranker pays for the records)
+ */
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct SweepDeposit<'info> {
+    #[account(mut)]
+    pub cranker: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"deposit", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump = deposit_address.bump
+    )]
+    pub deposit_address: Account<'info, DepositAddress>,
+
+    /// The deposit address's token account being swept
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = deposit_address,
+        token::token_program = token_program
+    )]
+    pub deposit_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            deposit_address.dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// The token's sequence to this chain, which numbers the transfer
+    #[account(
+        mut,
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            deposit_address.dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    #[account(
+        init,
+        payer = cranker,
+        space = 8 + TransferRecord::INIT_SPACE,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            RouteSequence::nonce_at(route_sequence.shard, route_sequence.last + 1)
+                .to_le_bytes()
+                .as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+
+    /// CHECK: Guardian set PDA (may be empty); its index scopes the transfer ID
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    #[account(
+        init_if_needed,
+        payer = cranker,
+        space = 8 + UserStats::INIT_SPACE,
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub user_stats: Account<'info, UserStats>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: The token's fee route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            deposit_address.dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: UncheckedAccount<'info>,
+
+    /// CHECK: The token's relay route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            mint.key().as_ref(),
+            deposit_address.dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        mut,
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: The token's price circuit breaker; empty unless one was configured
+    #[account(
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: UncheckedAccount<'info>,
+
+    /// CHECK: The token's event history; empty unless one was created
+    #[account(
+        mut,
+        seeds = [b"event_history", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub event_history: UncheckedAccount<'info>,
+
+    /// CHECK: The user's fee exemption; empty unless one was granted
+    #[account(
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub fee_exemption: UncheckedAccount<'info>,
+
+    /// CHECK: The deployment's terms of use; empty unless it requires any
+    #[account(
+        seeds = [b"terms", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub terms_config: UncheckedAccount<'info>,
+
+    /// CHECK: The user's acknowledgement of the terms; empty until given
+    #[account(
+        seeds = [b"terms_ack", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub terms_acceptance: UncheckedAccount<'info>,
+
+    /// CHECK: The closed user group settings; empty unless senders are gated
+    #[account(
+        seeds = [b"sender_allowlist", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub sender_allowlist: UncheckedAccount<'info>,
+
+    /// CHECK: The user's allowlist entry; empty unless the owner added them
+    #[account(
+        seeds = [b"allowed_sender", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub allowed_sender: UncheckedAccount<'info>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub fee_vault: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow and fee vault
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Withdraw-deposit accounts
+ */
+#[derive(Accounts)]
+pub struct WithdrawDeposit<'info> {
+    pub user: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"deposit", bridge_state.instance_seed.as_slice(), user.key().as_ref()],
+        bump = deposit_address.bump
+    )]
+    pub deposit_address: Account<'info, DepositAddress>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = deposit_address,
+        token::token_program = token_program
+    )]
+    pub deposit_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// Where the tokens go; any account of the mint
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub destination: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Add-withdrawal-address accounts (the user creates the list; after
+ * that only its authority can change it)
+ */
+#[derive(Accounts)]
+#[instruction(user: Pubkey, dest_chain_id: u64)]
+pub struct AddWithdrawalAddress<'info> {
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = authority,
+        space = 8 + WithdrawalAllowlist::INIT_SPACE,
+        seeds = [b"withdrawal_allowlist", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub withdrawal_allowlist: Account<'info, WithdrawalAllowlist>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-withdrawal-address accounts
+ */
+#[derive(Accounts)]
+#[instruction(user: Pubkey, dest_chain_id: u64)]
+pub struct RemoveWithdrawalAddress<'info> {
+    pub authority: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal_allowlist", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump = withdrawal_allowlist.bump
+    )]
+    pub withdrawal_allowlist: Account<'info, WithdrawalAllowlist>,
+}
+
+/**
+ * Set-withdrawal-authority accounts
+ */
+#[derive(Accounts)]
+#[instruction(user: Pubkey)]
+pub struct SetWithdrawalAuthority<'info> {
+    pub authority: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"withdrawal_allowlist", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump = withdrawal_allowlist.bump
+    )]
+    pub withdrawal_allowlist: Account<'info, WithdrawalAllowlist>,
+}
+
+/**
+ * Set-relay-route accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, chain_id: u64)]
+pub struct SetRelayRoute<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// The chain must be registered
+    #[account(
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// And the token
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + RelayRoute::INIT_SPACE,
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: Account<'info, RelayRoute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-relay-route accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, chain_id: u64)]
+pub struct RemoveRelayRoute<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub relay_route: Account<'info, RelayRoute>,
+}
+
+/**
+ * Allow-message-target accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64, target: [u8; 32])]
+pub struct AllowMessageTarget<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// The chain must be registered
+    #[account(
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + MessageTarget::INIT_SPACE,
+        seeds = [
+            b"message_target",
+            bridge_state.instance_seed.as_slice(),
+            chain_id.to_le_bytes().as_ref(),
+            target.as_ref()
+        ],
+        bump
+    )]
+    pub message_target: Account<'info, MessageTarget>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Remove-message-target accounts
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64, target: [u8; 32])]
+pub struct RemoveMessageTarget<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        mut,
+        close = owner,
+        seeds = [
+            b"message_target",
+            bridge_state.instance_seed.as_slice(),
+            chain_id.to_le_bytes().as_ref(),
+            target.as_ref()
+        ],
+        bump
+    )]
+    pub message_target: Account<'info, MessageTarget>,
+}
+
+/**
+ * Send-message accounts
+ */
+#[derive(Accounts)]
+#[instruction(dest_chain_id: u64, target: [u8; 32])]
+pub struct SendMessage<'info> {
+    pub sender: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// CHECK: Allowlist entry for the pair; empty if it was never allowed or was removed
+    #[account(
+        seeds = [
+            b"message_target",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref(),
+            target.as_ref()
+        ],
+        bump
+    )]
+    pub message_target: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"outbox", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.outbox_bump
+    )]
+    pub outbox: AccountLoader<'info, Outbox>,
+}
+
+/**
+ * Re-emit accounts (permissionless)
+ *
+ * Any TransferRecord will do: only this program can create one, so its
+ * contents are what the program itself recorded.
+ */
+#[derive(Accounts)]
+pub struct Reemit<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    pub transfer_record: Account<'info, TransferRecord>,
+}
+
+/**
+ * Chain-status accounts (a guardian, a guardian quorum or the owner)
+ */
+#[derive(Accounts)]
+#[instruction(chain_id: u64)]
+pub struct ChainStatus<'info> {
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// CHECK: Guardian set PDA; empty until guardians are set
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [b"chain", bridge_state.instance_seed.as_slice(), chain_id.to_le_bytes().as_ref()],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+}
+
+/**
+ * Freeze/thaw-wrapped-account accounts
+ */
+#[derive(Accounts)]
+pub struct FreezeWrappedAccount<'info> {
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    /// Only mints the bridge created can be frozen through it
+    #[account(
+        seeds = [
+            b"wrapped_asset",
+            bridge_state.instance_seed.as_slice(),
+            wrapped_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub wrapped_asset: Account<'info, WrappedAsset>,
+
+    #[account(mint::freeze_authority = bridge_authority)]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint
+    )]
+    pub token_account: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA freeze authority
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/**
+ * Price-feed accounts (start, update or stop tracking a token in GlobalStats)
+ */
+#[derive(Accounts)]
+pub struct SetPriceFeed<'info> {
+    #[account(mut)]
+    pub owner: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"admin_log", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub admin_log: Account<'info, AdminLog>,
+
+    #[account(
+        init_if_needed,
+        payer = owner,
+        space = 8 + GlobalStats::INIT_SPACE,
+        seeds = [b"global_stats", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        token::mint = mint,
+        token::authority = bridge_authority
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Global-stats crank accounts (permissionless)
+ *
+ * Remaining accounts: for each tracked token, in order, its escrow and
+ * a fresh Pyth price update for its feed.
+ */
+#[derive(Accounts)]
+pub struct UpdateGlobalStats<'info> {
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"global_stats", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub global_stats: Account<'info, GlobalStats>,
+}
+
+/**
+ * Lock-preview view accounts (Lock without the funds)
+ */
+#[derive(Accounts)]
+#[instruction(amount: u64, mint: Pubkey, dest_chain_id: u64, recipient: String, user: Pubkey)]
+pub struct PreviewLockView<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    /// SPL Token or Token-2022 mint
+    #[account(address = mint)]
+    pub token_mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// The token's sequence to this chain, which numbers the next lock
+    #[account(
+        seeds = [
+            b"sequence",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub route_sequence: Account<'info, RouteSequence>,
+
+    /// CHECK: The token's fee route to this chain; may not exist
+    #[account(
+        seeds = [
+            b"fee",
+            bridge_state.instance_seed.as_slice(),
+            mint.as_ref(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub fee_route: UncheckedAccount<'info>,
+
+    #[account(
+        seeds = [b"fee_tiers", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub fee_tiers: Account<'info, FeeTiers>,
+
+    /// CHECK: The user's stats; empty before their first transfer
+    #[account(
+        seeds = [b"user_stats", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub user_stats: UncheckedAccount<'info>,
+
+    /// CHECK: The user's fee exemption; empty unless one was granted
+    #[account(
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), user.as_ref()],
+        bump
+    )]
+    pub fee_exemption: UncheckedAccount<'info>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::check
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// The user's bridge pass token account, for pass-holder benefits
+    #[account(
+        token::mint = bridge_state.pass_mint,
+        token::authority = user
+    )]
+    pub bridge_pass: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+
+    /// Required for share-accounted tokens
+    #[account(
+        seeds = [b"conversion_rate", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub conversion_rate: Option<Account<'info, ConversionRate>>,
+
+    /// CHECK: Guardian set PDA, for the transfer ID; empty until guardians are set
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+}
+
+/**
+ * Rate-limits view accounts
+ */
+#[derive(Accounts)]
+#[instruction(mint: Pubkey, dest_chain_id: u64, user: Pubkey)]
+pub struct RateLimitsView<'info> {
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            dest_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub chain_config: Account<'info, ChainConfig>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// CHECK: Outbound throttle; empty unless one was configured (see Throttle)
+    #[account(
+        seeds = [b"throttle", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub throttle: UncheckedAccount<'info>,
+
+    /// CHECK: Only read for restricted tokens; validated in credential::limit_of
+    pub credential: Option<UncheckedAccount<'info>>,
+
+    /// The user's bridge pass token account, for the tier boost
+    #[account(
+        token::mint = bridge_state.pass_mint,
+        token::authority = user
+    )]
+    pub bridge_pass: Option<InterfaceAccount<'info, InterfaceTokenAccount>>,
+}
+
+/**
+ * Transfer-route accounts
+ *
+ * Remaining accounts: the chain config of every hop, then of the final chain.
+ */
+#[derive(Accounts)]
+pub struct SetTransferRoute<'info> {
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"out",
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        init,
+        payer = sender,
+        space = 8 + TransferRoute::INIT_SPACE,
+        seeds = [
+            b"route",
+            bridge_state.instance_seed.as_slice(),
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_route: Account<'info, TransferRoute>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Fee-exemption grant accounts
+ */
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct GrantFeeExemption<'info> {
+    /// Owner or fee manager
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init,
+        payer = authority,
+        space = 8 + FeeExemption::INIT_SPACE,
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), address.as_ref()],
+        bump
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Fee-exemption revoke accounts
+ */
+#[derive(Accounts)]
+#[instruction(address: Pubkey)]
+pub struct RevokeFeeExemption<'info> {
+    /// Owner or fee manager
+    #[account(mut)]
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        close = authority,
+        seeds = [b"fee_exempt", bridge_state.instance_seed.as_slice(), address.as_ref()],
+        bump
+    )]
+    pub fee_exemption: Account<'info, FeeExemption>,
+}
+
+/**
+ * Claim accounts
+ *
+ * Anyone may claim; only the recipient, or an executor they signed an
+ * authorization for (see claim_auth.rs), may pick a token account the
+ * recipient doesn't own.
+ */
+#[derive(Accounts)]
+pub struct Claim<'info> {
+    pub claimer: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        address = transfer_record.mint,
+        mint::authority = bridge_authority
+    )]
+    pub wrapped_mint: Account<'info, Mint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), wrapped_mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = wrapped_mint
+    )]
+    pub recipient_token: Account<'info, TokenAccount>,
+
+    /// CHECK: PDA authority for minting
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Program<'info, Token>,
+
+    /// CHECK: Instructions sysvar, to find a delegated claim's Ed25519
+    /// instruction (see claim_auth.rs)
+    #[account(address = anchor_lang::solana_program::sysvar::instructions::ID)]
+    pub instructions: Option<UncheckedAccount<'info>>,
+}
+
+/**
+ * Claim-unlock accounts (transfer-hook accounts go in the remaining accounts)
+ *
+ * Anyone may claim; only the recipient may pick a token account they
+ * don't own.
+ */
+#[derive(Accounts)]
+pub struct ClaimUnlock<'info> {
+    pub claimer: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            transfer_record.nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    #[account(address = transfer_record.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = bridge_authority,
+        token::token_program = token_program
+    )]
+    pub bridge_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// CHECK: The token's price circuit breaker; empty unless one was configured
+    #[account(
+        seeds = [b"price_breaker", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub price_breaker: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::token_program = token_program
+    )]
+    pub recipient_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns the escrow
+    #[account(
+        seeds = [b"bridge", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.authority_bump
+    )]
+    pub bridge_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Gas-report accounts
+ */
+#[derive(Accounts)]
+#[instruction(nonce: u64)]
+pub struct ReportGas<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        seeds = [b"reward_config", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_config: Account<'info, RelayerRewardConfig>,
+
+    #[account(
+        seeds = [
+            b"transfer".as_ref(),
+            bridge_state.instance_seed.as_slice(),
+            b"in",
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub transfer_record: Account<'info, TransferRecord>,
+
+    #[account(
+        init,
+        payer = relayer,
+        space = 8 + GasReport::INIT_SPACE,
+        seeds = [
+            b"gas_report",
+            bridge_state.instance_seed.as_slice(),
+            nonce.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub gas_report: Account<'info, GasReport>,
+
+    #[account(
+        init_if_needed,
+        payer = relayer,
+        space = 8 + GasReimbursement::INIT_SPACE,
+        seeds = [
+            b"gas_reimbursement",
+            bridge_state.instance_seed.as_slice(),
+            relayer.key().as_ref()
+        ],
+        bump
+    )]
+    pub gas_reimbursement: Account<'info, GasReimbursement>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Claim-gas-reimbursement accounts
+ */
+#[derive(Accounts)]
+pub struct ClaimGasReimbursement<'info> {
+    #[account(mut)]
+    pub relayer: Signer<'info>,
+
+    /// Instance the accounts below belong to
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"gas_reimbursement",
+            bridge_state.instance_seed.as_slice(),
+            relayer.key().as_ref()
+        ],
+        bump
+    )]
+    pub gas_reimbursement: Account<'info, GasReimbursement>,
+
+    #[account(
+        mut,
+        seeds = [b"reward_vault", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub reward_vault: SystemAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Create-OTC-order accounts
+ *
+ * Remaining accounts: any transfer-hook accounts for the escrow deposit.
+ */
+#[derive(Accounts)]
+#[instruction(order_id: u64, amount: u64, terms: OtcTerms)]
+pub struct CreateOtcOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        init_if_needed,
+        payer = maker,
+        space = 8 + OtcBook::INIT_SPACE,
+        seeds = [b"otc_book", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_book: Account<'info, OtcBook>,
+
+    #[account(
+        init,
+        payer = maker,
+        space = 8 + OtcOrder::INIT_SPACE,
+        seeds = [
+            b"otc_order",
+            bridge_state.instance_seed.as_slice(),
+            order_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub order: Account<'info, OtcOrder>,
+
+    /// Chain the taker pays on; `pay_to` is checked against its format
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            terms.counter_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub counter_chain: Account<'info, ChainConfig>,
+
+    /// SPL Token or Token-2022 mint
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = maker,
+        token::token_program = token_program
+    )]
+    pub maker_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// The mint's OTC escrow, kept apart from the bridge's collateral
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = otc_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns OTC escrows; nothing but OTC orders signs for it
+    #[account(
+        seeds = [b"otc_escrow", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
+
+/**
+ * Fill-OTC-order accounts
+ *
+ * Remaining accounts: guardian signers, if the order's verifier takes a
+ * quorum, and any transfer-hook accounts for the release.
+ */
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct FillOtcOrder<'info> {
+    /// Submits the proof; the owner/relayer for OwnerSignature orders
+    pub authority: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"otc_book", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_book: Account<'info, OtcBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"otc_order",
+            bridge_state.instance_seed.as_slice(),
+            order_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        close = maker
+    )]
+    pub order: Account<'info, OtcOrder>,
+
+    /// CHECK: Gets the order's rent back
+    #[account(mut, address = order.maker)]
+    pub maker: UncheckedAccount<'info>,
+
+    /// CHECK: Taker the payment proof names; receives the escrow
+    pub taker: UncheckedAccount<'info>,
+
+    /// Chain the taker paid on; nothing fills from it while it's halted
+    #[account(
+        seeds = [
+            b"chain",
+            bridge_state.instance_seed.as_slice(),
+            order.terms.counter_chain_id.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    pub counter_chain: Account<'info, ChainConfig>,
+
+    /// CHECK: Guardian council PDA; empty until the council is created
+    #[account(
+        seeds = [b"guardian_council", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_council: UncheckedAccount<'info>,
+
+    /// CHECK: Guardian set; its quorum vouches for GuardianQuorum orders
+    #[account(
+        seeds = [b"guardian_set", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub guardian_set: UncheckedAccount<'info>,
+
+    /// CHECK: Posted VAA for WormholeVaa orders; validated by verifiers::WormholeVaa
+    pub vaa: Option<UncheckedAccount<'info>>,
+
+    #[account(address = order.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = taker,
+        token::token_program = token_program
+    )]
+    pub taker_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: The token's hot/cold vault config; empty unless the escrow is split
+    #[account(
+        seeds = [b"vault", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub vault_config: UncheckedAccount<'info>,
+
+    /// The mint's OTC escrow, kept apart from the bridge's collateral
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = otc_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns OTC escrows; nothing but OTC orders signs for it
+    #[account(
+        seeds = [b"otc_escrow", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
+
+/**
+ * Cancel-OTC-order accounts
+ *
+ * Remaining accounts: any transfer-hook accounts for the refund.
+ */
+#[derive(Accounts)]
+#[instruction(order_id: u64)]
+pub struct CancelOtcOrder<'info> {
+    #[account(mut)]
+    pub maker: Signer<'info>,
+
+    #[account(
+        seeds = [b"bridge_state", bridge_state.instance_seed.as_slice()],
+        bump = bridge_state.bump
+    )]
+    pub bridge_state: Account<'info, BridgeState>,
+
+    #[account(
+        mut,
+        seeds = [b"otc_book", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_book: Account<'info, OtcBook>,
+
+    #[account(
+        mut,
+        seeds = [
+            b"otc_order",
+            bridge_state.instance_seed.as_slice(),
+            order_id.to_le_bytes().as_ref()
+        ],
+        bump,
+        close = maker
+    )]
+    pub order: Account<'info, OtcOrder>,
+
+    #[account(address = order.mint)]
+    pub mint: InterfaceAccount<'info, InterfaceMint>,
+
+    #[account(
+        seeds = [b"token", bridge_state.instance_seed.as_slice(), mint.key().as_ref()],
+        bump
+    )]
+    pub token_config: Account<'info, TokenConfig>,
+
+    /// Maker's token account the escrow goes back to
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = maker,
+        token::token_program = token_program
+    )]
+    pub maker_token: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// The mint's OTC escrow, kept apart from the bridge's collateral
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = otc_authority,
+        token::token_program = token_program
+    )]
+    pub escrow: InterfaceAccount<'info, InterfaceTokenAccount>,
+
+    /// CHECK: PDA that owns OTC escrows; nothing but OTC orders signs for it
+    #[account(
+        seeds = [b"otc_escrow", bridge_state.instance_seed.as_slice()],
+        bump
+    )]
+    pub otc_authority: AccountInfo<'info>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -1,21 +1,74 @@
-/**
- * Solana Bridge Program
- *
- * Cross-chain bridge between Solana and Ethereum.
- *
- * This program implements the SAME lock/mint and burn/unlock pattern
- * as your EVM bridge, but in Rust using the Anchor framework.
- *
- * Key differences from Solidity:
- * - State stored in separate accounts (not in contract)
- * - Explicit account declarations required
- * - Uses Rust syntax instead of Solidity
- *
- * But the LOGIC is identical to your EVM bridge!
- */
+//! Solana Bridge Program
+//!
+//! Cross-chain bridge between Solana and Ethereum.
+//!
+//! This program implements the SAME lock/mint and burn/unlock pattern
+//! as your EVM bridge, but in Rust using the Anchor framework.
+//!
+//! Key differences from Solidity:
+//! - State stored in separate accounts (not in contract)
+//! - Explicit account declarations required
+//! - Uses Rust syntax instead of Solidity
+//!
+//! But the LOGIC is identical to your EVM bridge!
+
+// Instruction arguments, and the cpi helpers Anchor generates from them
+#![allow(clippy::too_many_arguments)]
 
 use anchor_lang::prelude::*;
-use anchor_spl::token::{self, Mint, Token, TokenAccount, Transfer, MintTo, Burn};
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::keccak;
+use anchor_lang::solana_program::program::invoke_signed;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::AssociatedToken;
+use anchor_spl::metadata::{
+    self as token_metadata, mpl_token_metadata::types::DataV2, CreateMetadataAccountsV3,
+    Metadata, MetadataAccount, UpdateMetadataAccountsV2,
+};
+use anchor_spl::token::{self, Mint, Token, TokenAccount, MintTo, Burn};
+use anchor_spl::token_2022::Token2022;
+use anchor_spl::token_interface::{
+    self, Mint as InterfaceMint, TokenAccount as InterfaceTokenAccount, TokenInterface,
+};
+
+pub mod claim_auth;
+pub mod codec;
+pub mod compression;
+mod compute;
+mod credential;
+pub mod errors;
+pub mod events;
+pub mod instance;
+pub mod instructions;
+pub mod math;
+mod merkle;
+pub mod names;
+mod pyth;
+mod quarantine;
+pub mod quote;
+mod receipt;
+pub mod refund_assign;
+mod recipient;
+pub mod route_health;
+pub mod smart_wallet;
+pub mod state;
+mod token_ext;
+mod upgrade;
+pub mod verifiers;
+mod yield_adapter;
+
+// Named, not a glob: anchor's prelude exports an ErrorCode of its own
+pub use errors::{ErrorCategory, ErrorCode};
+pub use events::*;
+pub use instructions::*;
+pub use route_health::{RouteHealthReport, RouteStatus};
+pub use smart_wallet::WalletDerivation;
+pub use state::*;
+
+use token_ext::{token_extensions, TokenTransfer};
+use verifiers::groth16::Groth16Proof;
+use verifiers::{InboundTransfer, LightClient, Optimistic, Relayed, Verifier};
 
 declare_id!("Fg6PaFpoGXkYsidMpWTK6W2BeZ7FEfcYkg476zPFsLnS");
 
@@ -28,14 +81,46 @@ pub mod solana_bridge {
      *
      * Similar to your Solidity constructor
      * Sets up initial state and owner
+     *
+     * `instance_id` picks the instance (see `instance`): 0 is the
+     * deployment's original bridge, anything else a separate one
+     * alongside it.
      */
-    pub fn initialize(ctx: Context<Initialize>) -> Result<()> {
+    pub fn initialize(ctx: Context<Initialize>, instance_id: u64) -> Result<()> {
         let bridge_state = &mut ctx.accounts.bridge_state;
         bridge_state.owner = ctx.accounts.owner.key();
         bridge_state.nonce = 0;
         bridge_state.paused = false;
+        bridge_state.instance_id = instance_id;
+        bridge_state.instance_seed = instance::seed(&instance_id).to_vec();
+        bridge_state.bump = ctx.bumps.bridge_state;
+        bridge_state.authority_bump = Pubkey::find_program_address(
+            &[b"bridge", instance::seed(&instance_id)],
+            ctx.program_id,
+        )
+        .1;
+        bridge_state.outbox_bump = ctx.bumps.outbox;
+
+        ctx.accounts.outbox.load_init()?;
+
+        msg!("Bridge instance {} initialized by {}", instance_id, ctx.accounts.owner.key());
+        Ok(())
+    }
 
-        msg!("Bridge initialized by {}", ctx.accounts.owner.key());
+    /**
+     * Store the bridge's PDA bumps in BridgeState (anyone)
+     *
+     * Lock, mint, burn and unlock check the bridge state, authority and
+     * outbox PDAs against the stored bumps instead of searching for them
+     * on every call. `initialize` sets them; a deployment from before
+     * they existed runs this once after upgrading, or those fail with
+     * ConstraintSeeds.
+     */
+    pub fn cache_bumps(ctx: Context<CacheBumps>) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+        bridge_state.bump = ctx.bumps.bridge_state;
+        bridge_state.authority_bump = ctx.bumps.bridge_authority;
+        bridge_state.outbox_bump = ctx.bumps.outbox;
         Ok(())
     }
 
@@ -49,55 +134,756 @@ pub mod solana_bridge {
      *   }
      *
      * Solana (SAME CONCEPT, different syntax):
-     *   pub fn lock(amount, eth_recipient)
+     *   pub fn lock(amount, dest_chain_id, recipient, deadline, integrator_id, recovery, memo,
+     *               smart_wallet)
+     *
+     * The recipient is in the destination chain's native format
+     * (0x... for EVM chains, G... for Stellar). With a `deadline` (unix
+     * time), a transfer the relayer hasn't attested by then can be
+     * taken back with `reclaim_expired`.
+     *
+     * With a `recovery` address, refunds (reclaimed after the deadline,
+     * cancelled by guardians, failed on the destination) go to its token
+     * account instead of the sender's, for custodial and program senders
+     * whose own account can't take them back (RecoveryAddressSet).
+     *
+     * A `memo` (up to MAX_MEMO_LEN bytes, with the `transfer_memo`
+     * account) is kept in a TransferMemo and emitted in MemoAttached as
+     * is. The bridge never reads it; the SDK encrypts it to the
+     * recipient's key, so a settlement reference isn't public.
+     *
+     * With `smart_wallet` (and the `smart_wallet` account), the recipient
+     * is a counterfactual EVM wallet: the derivation must give its
+     * address, and is kept in a SmartWalletRecipient so the relayer can
+     * deploy the wallet before delivering (see smart_wallet.rs).
+     *
+     * Pass the receipt accounts to also get a deposit receipt, a
+     * non-transferable token that stands for the position until it's
+     * attested or refunded (see receipt.rs). Without the compute left to
+     * mint it, the lock goes through without one (ReceiptDeferred) and
+     * the sender mints it afterwards with `issue_receipt`.
+     *
+     * Pass the guardian set and instructions sysvar, after an Ed25519
+     * instruction with a relayer's signed quote, to pay exactly the
+     * quoted fee instead (see quote.rs).
+     *
+     * While the outbound queue is deep the fee carries a congestion
+     * surcharge, and past the limit the lock fails with BridgeCongested
+     * (see Throttle). A signed quote is exact and skips the surcharge.
+     *
+     * A frontend tags the lock with its `integrator_id`, passing that
+     * ID's IntegratorStats, to have the volume attributed to it.
+     *
+     * Pass the token's SolFeeConfig, the SOL fee vault and fresh Pyth
+     * updates for the token and SOL to pay the fee in lamports instead,
+     * so the whole amount crosses (FeePaidInSol).
+     *
+     * Pass the destination's DeliveryFeeConfig, the relayer reward vault
+     * and fresh Pyth updates for the destination's native token and SOL
+     * to also prepay the delivery there in lamports, for a recipient with
+     * no gas on that chain (DeliveryFeePrepaid).
+     *
+     * A deployment that requires terms of use only takes locks from
+     * wallets that acknowledged the current version (see TermsConfig),
+     * and one in closed user group mode only from allowlisted senders
+     * (see SenderAllowlist). A user with a withdrawal allowlist can only
+     * lock to the addresses on it (see WithdrawalAllowlist).
      */
-    pub fn lock(
-        ctx: Context<Lock>,
+    pub fn lock<'info>(
+        ctx: Context<'_, '_, '_, 'info, Lock<'info>>,
         amount: u64,
-        eth_recipient: String,
+        dest_chain_id: u64,
+        recipient: String,
+        deadline: Option<i64>,
+        integrator_id: Option<u32>,
+        recovery: Option<Pubkey>,
+        memo: Option<Vec<u8>>,
+        smart_wallet: Option<WalletDerivation>,
     ) -> Result<()> {
-        let bridge_state = &mut ctx.accounts.bridge_state;
+        let bridge_state = &ctx.accounts.bridge_state;
 
         // Check not paused (same as your Solidity require(!paused))
         require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        PriceBreaker::check(&ctx.accounts.price_breaker, Clock::get()?.unix_timestamp)?;
+        TermsConfig::check(
+            &ctx.accounts.terms_config,
+            &ctx.accounts.terms_acceptance,
+            &ctx.accounts.user.key(),
+        )?;
+        SenderAllowlist::check(
+            &ctx.accounts.sender_allowlist,
+            &ctx.accounts.allowed_sender,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.user.key(),
+        )?;
 
-        // Validate Ethereum address format (0x...)
-        require!(
-            eth_recipient.starts_with("0x") && eth_recipient.len() == 42,
-            ErrorCode::InvalidEthAddress
+        // Validate recipient for the destination chain
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+        if let Some(derivation) = &smart_wallet {
+            derivation.check(chain_config, &recipient)?;
+        }
+        WithdrawalAllowlist::check(
+            &ctx.accounts.withdrawal_allowlist,
+            chain_config,
+            &recipient,
+            Clock::get()?.unix_timestamp,
+        )?;
+
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
         );
 
-        // Transfer tokens to bridge (SAME AS: token.transferFrom)
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Transfer {
+        let (pass_discount_bps, tier_boost) =
+            bridge_state.pass_benefits(ctx.accounts.bridge_pass.as_deref());
+        credential::check_with_boost(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &ctx.accounts.user.key(),
+            amount,
+            tier_boost,
+        )?;
+
+        // A deep outbound queue costs extra, then refuses (see Throttle)
+        let surcharge_bps = Throttle::admit(&ctx.accounts.throttle, 1)?;
+
+        // Token- and destination-specific fee (relayer cost differs a lot
+        // between routes), less the volume rebate for what the user bridged
+        // before this transfer or the bridge pass discount, whichever is
+        // larger. A signed quote replaces all of it (see quote.rs).
+        let route = FeeRoute::load(&ctx.accounts.fee_route)?;
+        let user_stats = &mut ctx.accounts.user_stats;
+        let (fee_tier, fee) = match (&ctx.accounts.quote_signers, &ctx.accounts.instructions) {
+            (Some(signers), Some(instructions)) => (
+                0,
+                quote::load(instructions, signers)?.fee_for(
+                    &ctx.accounts.mint.key(),
+                    amount,
+                    dest_chain_id,
+                    Clock::get()?.unix_timestamp,
+                )?,
+            ),
+            (None, None) => {
+                let (fee_tier, discount_bps) = ctx.accounts.fee_tiers.tier_for(user_stats.volume);
+                let discount_bps = discount_bps.max(pass_discount_bps);
+                let fee = chain_config.fee(route.as_ref(), amount, discount_bps)?;
+                (fee_tier, math::add(fee, math::bps_of(amount, surcharge_bps)?)?)
+            }
+            _ => return err!(ErrorCode::FeeQuoteRequired),
+        };
+        let fee = if FeeExemption::is_exempt(&ctx.accounts.fee_exemption)? { 0 } else { fee };
+        user_stats.record(ctx.accounts.user.key(), amount)?;
+        let integrator = ctx.accounts.integrator.as_deref_mut();
+        let integrator_id = IntegratorStats::attribute(integrator, integrator_id, amount)?;
+        let decimals = ctx.accounts.mint.decimals;
+
+        // Paid in SOL, the fee's value comes out of the user's lamports and
+        // the whole amount crosses (see SolFeeConfig)
+        let sol_fee = match &ctx.accounts.sol_fee_config {
+            Some(config) => {
+                let (Some(vault), Some(token_price), Some(sol_price)) = (
+                    ctx.accounts.sol_fee_vault.as_ref(),
+                    ctx.accounts.token_price.as_ref(),
+                    ctx.accounts.sol_price.as_ref(),
+                ) else {
+                    return err!(ErrorCode::SolFeeAccountsRequired);
+                };
+                require!(config.feed_id != [0u8; 32], ErrorCode::SolFeeDisabled);
+
+                let now = Clock::get()?.unix_timestamp;
+                let token_price = pyth::load_price(token_price, &config.feed_id, now)?;
+                let sol_price = pyth::load_price(sol_price, &config.sol_feed_id, now)?;
+                let lamports = config.lamports_for(fee, decimals, &token_price, &sol_price)?;
+                if lamports > 0 {
+                    let cpi_ctx = CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.user.to_account_info(),
+                            to: vault.to_account_info(),
+                        },
+                    );
+                    system_program::transfer(cpi_ctx, lamports)?;
+                }
+                Some((fee, lamports))
+            }
+            None => None,
+        };
+        let fee = if sol_fee.is_some() { 0 } else { fee };
+
+        // The destination's gas, paid here so relayers deliver to recipients
+        // holding none of it (see DeliveryFeeConfig)
+        let delivery_fee = match &ctx.accounts.delivery_fee_config {
+            Some(config) => {
+                let (Some(vault), Some(native_price), Some(sol_price)) = (
+                    ctx.accounts.reward_vault.as_ref(),
+                    ctx.accounts.native_price.as_ref(),
+                    ctx.accounts.sol_price.as_ref(),
+                ) else {
+                    return err!(ErrorCode::DeliveryFeeAccountsRequired);
+                };
+                require!(
+                    config.native_feed_id != [0u8; 32] && config.gas_price > 0,
+                    ErrorCode::DeliveryFeeDisabled
+                );
+
+                let now = Clock::get()?.unix_timestamp;
+                let native_price = pyth::load_price(native_price, &config.native_feed_id, now)?;
+                let sol_price = pyth::load_price(sol_price, &config.sol_feed_id, now)?;
+                let lamports = config.lamports_for(&native_price, &sol_price)?;
+                if lamports > 0 {
+                    let cpi_ctx = CpiContext::new(
+                        ctx.accounts.system_program.to_account_info(),
+                        system_program::Transfer {
+                            from: ctx.accounts.user.to_account_info(),
+                            to: vault.to_account_info(),
+                        },
+                    );
+                    system_program::transfer(cpi_ctx, lamports)?;
+                }
+                Some((config.gas_limit, config.gas_price, lamports))
+            }
+            None => None,
+        };
+
+        require_ctx!(amount > fee, ErrorCode::AmountTooSmall, amount = amount, fee = fee);
+
+        // Token-2022 mints must only use allowlisted extensions
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+
+        // Dust the other side can't carry is refunded, kept or refused (see DustPolicy)
+        let token_config = &ctx.accounts.token_config;
+        let (escrowed, dust) = token_config.split_dust(math::sub(amount, fee)?, decimals)?;
+        let vault_amount = match token_config.dust_policy {
+            DustPolicy::Treasury => math::add(fee, dust)?,
+            _ => fee,
+        };
+
+        if vault_amount > 0 {
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
                 from: ctx.accounts.user_token.to_account_info(),
-                to: ctx.accounts.bridge_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
                 authority: ctx.accounts.user.to_account_info(),
-            },
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, vault_amount, decimals, &[])?;
+        }
+
+        // Transfer tokens to bridge (SAME AS: token.transferFrom)
+        // With a Token-2022 transfer fee the escrow receives less than
+        // was sent; only what arrived is released on the other side.
+        let net_amount = TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.user_token.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.bridge_token.to_account_info(),
+            authority: ctx.accounts.user.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(&extensions, escrowed, decimals, &[])?;
+
+        // Rebasing tokens cross chains in shares, not raw amounts
+        let net_amount = ctx
+            .accounts
+            .token_config
+            .to_bridged(ctx.accounts.conversion_rate.as_deref(), net_amount)?;
+        require!(net_amount > 0, ErrorCode::AmountTooSmall);
+
+        // Next nonce of the token's route (SAME AS: nonce++, one counter per route)
+        let (current_nonce, sequence) = ctx.accounts.route_sequence.take()?;
+
+        // The ID is scoped to the guardian set the transfer is emitted under
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+        let transfer_id = transfer_id(
+            chain_ids::SOLANA,
+            dest_chain_id,
+            guardian_set_index,
+            current_nonce,
+            &ctx.accounts.mint.key(),
+            net_amount,
+            &ctx.accounts.user.key(),
+            &recipient,
         );
-        token::transfer(cpi_ctx, amount)?;
 
-        // Increment nonce (SAME AS: nonce++)
-        bridge_state.nonce += 1;
-        let current_nonce = bridge_state.nonce;
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        transfer_record.open_outbound(
+            transfer_id,
+            current_nonce,
+            dest_chain_id,
+            ctx.accounts.user.key(),
+            recipient.clone(),
+            net_amount,
+        )?;
+        transfer_record.mint = ctx.accounts.mint.key();
+        transfer_record.escrowed = true;
+        transfer_record.fee = fee;
+        transfer_record.set_deadline(deadline)?;
+        let recovery = transfer_record.set_recovery(recovery);
+
+        let route_id = RelayRoute::id_of(&ctx.accounts.relay_route)?;
+        ctx.accounts.outbox.load_mut()?.push(
+            OutboxKind::Lock,
+            transfer_record,
+            ctx.accounts.mint.key(),
+            route_id,
+        )?;
+        transfer_record.route_sequence = sequence;
+        EventHistory::record(&ctx.accounts.event_history, transfer_record)?;
+
+        if let Some(receipt_mint) = &ctx.accounts.receipt_mint {
+            let (Some(receipt_token), Some(token_2022_program), Some(associated_token_program)) = (
+                ctx.accounts.receipt_token.as_ref(),
+                ctx.accounts.token_2022_program.as_ref(),
+                ctx.accounts.associated_token_program.as_ref(),
+            ) else {
+                return err!(ErrorCode::ReceiptRequired);
+            };
+
+            // The receipt only mirrors the position, so it can wait
+            if compute::fits(compute::Step::Receipt) {
+                receipt::IssueReceipt {
+                    mint: receipt_mint.to_account_info(),
+                    token: receipt_token.to_account_info(),
+                    owner: ctx.accounts.user.to_account_info(),
+                    bridge_authority: ctx.accounts.bridge_authority.to_account_info(),
+                    token_program: token_2022_program.to_account_info(),
+                    associated_token_program: associated_token_program.to_account_info(),
+                    system_program: ctx.accounts.system_program.to_account_info(),
+                }
+                .execute(
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    current_nonce,
+                    &[&[
+                        b"bridge",
+                        instance::seed(&ctx.accounts.bridge_state.instance_id),
+                        &[ctx.accounts.bridge_state.authority_bump],
+                    ]],
+                )?;
+                ctx.accounts.transfer_record.receipt = true;
+            } else {
+                emit!(ReceiptDeferred {
+                    transfer_id,
+                    nonce: current_nonce,
+                    sender: ctx.accounts.user.key(),
+                });
+            }
+        }
 
-        // Emit event (SAME AS: emit Lock(...))
-        emit!(LockEvent {
+        // Emit event (SAME AS: emit Lock(...)); the record keeps its own
+        // copy of the recipient, so the argument moves into the event
+        let transfer_record = &ctx.accounts.transfer_record;
+        LockEvent {
+            transfer_id,
             from: ctx.accounts.user.key(),
-            amount,
+            amount: net_amount,
+            fee,
+            fee_tier,
             nonce: current_nonce,
-            eth_recipient: eth_recipient.clone(),
-            timestamp: Clock::get()?.unix_timestamp,
+            dest_chain_id,
+            recipient,
+            timestamp: transfer_record.created_at,
+            mint: ctx.accounts.mint.key(),
+            guardian_set_index,
+            required_confirmations: chain_config.required_confirmations,
+            route_id,
+            integrator_id,
+        }
+        .emit(ctx.accounts.bridge_state.event_encoding, chain_config)?;
+
+        emit!(RouteSequenced {
+            transfer_id,
+            nonce: current_nonce,
+            mint: ctx.accounts.mint.key(),
+            dest_chain_id,
+            sequence,
         });
 
+        if dust > 0 {
+            emit!(DustHandled {
+                transfer_id,
+                nonce: current_nonce,
+                mint: ctx.accounts.mint.key(),
+                policy: ctx.accounts.token_config.dust_policy,
+                dust,
+            });
+        }
+
+        if let Some((fee, lamports)) = sol_fee {
+            emit!(FeePaidInSol {
+                transfer_id,
+                nonce: current_nonce,
+                from: ctx.accounts.user.key(),
+                mint: ctx.accounts.mint.key(),
+                fee,
+                lamports,
+            });
+        }
+
+        if let Some(recovery) = recovery {
+            emit!(RecoveryAddressSet {
+                transfer_id,
+                nonce: current_nonce,
+                sender: ctx.accounts.user.key(),
+                recovery,
+            });
+        }
+
+        if let Some(memo) = memo {
+            let Some(transfer_memo) = &ctx.accounts.transfer_memo else {
+                return err!(ErrorCode::InvalidMemo);
+            };
+            TransferMemo::create(
+                &transfer_memo.to_account_info(),
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                transfer_id,
+                current_nonce,
+                memo.clone(),
+            )?;
+            emit!(MemoAttached {
+                transfer_id,
+                nonce: current_nonce,
+                sender: ctx.accounts.user.key(),
+                memo,
+            });
+        }
+
+        if let Some(derivation) = smart_wallet {
+            let Some(smart_wallet) = &ctx.accounts.smart_wallet else {
+                return err!(ErrorCode::InvalidSmartWallet);
+            };
+            SmartWalletRecipient::create(
+                &smart_wallet.to_account_info(),
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                transfer_id,
+                current_nonce,
+                derivation,
+            )?;
+            emit!(SmartWalletRecipientSet {
+                transfer_id,
+                nonce: current_nonce,
+                recipient: ctx.accounts.transfer_record.remote_address.clone(),
+                derivation,
+            });
+        }
+
+        if let Some((gas_limit, gas_price, lamports)) = delivery_fee {
+            emit!(DeliveryFeePrepaid {
+                transfer_id,
+                nonce: current_nonce,
+                from: ctx.accounts.user.key(),
+                dest_chain_id,
+                gas_limit,
+                gas_price,
+                lamports,
+            });
+        }
+
+        // Cosmos chains receive an ICS-20 transfer, which needs the IBC denom
+        if chain_config.kind == ChainKind::Cosmos {
+            let denom = ctx
+                .accounts
+                .token_config
+                .ibc_denom(dest_chain_id)
+                .ok_or(ErrorCode::IbcDenomNotMapped)?;
+
+            emit!(IbcTransferEvent {
+                transfer_id,
+                nonce: current_nonce,
+                dest_chain_id,
+                denom: denom.to_string(),
+                amount: net_amount,
+                sender: ctx.accounts.user.key(),
+                receiver: transfer_record.remote_address.clone(),
+            });
+        }
+
         msg!(
-            "Locked {} tokens for {} (nonce: {})",
-            amount,
-            eth_recipient,
-            current_nonce
+            "Locked {} tokens for {} on chain {} (nonce: {}, id: {})",
+            net_amount,
+            transfer_record.remote_address,
+            dest_chain_id,
+            current_nonce,
+            hex_encode(&transfer_id)
+        );
+
+        Ok(())
+    }
+
+    /**
+     * Lock to many Ethereum recipients in one transaction
+     *
+     * For payroll and airdrops. Every entry is a normal lock (fee, limits,
+     * its own nonce, LockEvent and outbox entry), with consecutive nonces.
+     * Remaining accounts: the entries' transfer record PDAs
+     * (["transfer", "out", nonce]) in order, then any transfer-hook accounts.
+     */
+    pub fn lock_batch<'info>(ctx: Context<'_, '_, '_, 'info, LockBatch<'info>>, entries: Vec<LockBatchEntry>) -> Result<()> {
+        require!(
+            !entries.is_empty() && entries.len() <= MAX_LOCK_BATCH,
+            ErrorCode::InvalidBatch
+        );
+        require!(ctx.remaining_accounts.len() >= entries.len(), ErrorCode::InvalidBatch);
+        let (records, hook_accounts) = ctx.remaining_accounts.split_at(entries.len());
+
+        let bridge_state = &ctx.accounts.bridge_state;
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        PriceBreaker::check(&ctx.accounts.price_breaker, Clock::get()?.unix_timestamp)?;
+        TermsConfig::check(
+            &ctx.accounts.terms_config,
+            &ctx.accounts.terms_acceptance,
+            &ctx.accounts.user.key(),
+        )?;
+        SenderAllowlist::check(
+            &ctx.accounts.sender_allowlist,
+            &ctx.accounts.allowed_sender,
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let decimals = ctx.accounts.mint.decimals;
+        let user = ctx.accounts.user.key();
+        let mint = ctx.accounts.mint.key();
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+
+        // One rebate tier for the whole batch, based on volume before it
+        let (fee_tier, discount_bps) = ctx.accounts.fee_tiers.tier_for(ctx.accounts.user_stats.volume);
+        let (pass_discount_bps, tier_boost) =
+            bridge_state.pass_benefits(ctx.accounts.bridge_pass.as_deref());
+        let discount_bps = discount_bps.max(pass_discount_bps);
+        let route = FeeRoute::load(&ctx.accounts.fee_route)?;
+        let exempt = FeeExemption::is_exempt(&ctx.accounts.fee_exemption)?;
+        let route_id = RelayRoute::id_of(&ctx.accounts.relay_route)?;
+        let surcharge_bps = Throttle::admit(&ctx.accounts.throttle, entries.len() as u64)?;
+        let now = Clock::get()?.unix_timestamp;
+        let mut total_fee = 0;
+
+        for (entry, record_info) in entries.into_iter().zip(records) {
+            recipient::validate(chain_config, &entry.recipient)?;
+            WithdrawalAllowlist::check(
+                &ctx.accounts.withdrawal_allowlist,
+                chain_config,
+                &entry.recipient,
+                now,
+            )?;
+            require_ctx!(
+                entry.amount >= chain_config.min_amount,
+                ErrorCode::AmountTooSmall,
+                amount = entry.amount,
+                minimum = chain_config.min_amount
+            );
+
+            credential::check_with_boost(
+                &ctx.accounts.token_config,
+                ctx.accounts.credential.as_deref(),
+                &user,
+                entry.amount,
+                tier_boost,
+            )?;
+
+            let fee = if exempt {
+                0
+            } else {
+                let fee = chain_config.fee(route.as_ref(), entry.amount, discount_bps)?;
+                math::add(fee, math::bps_of(entry.amount, surcharge_bps)?)?
+            };
+            require_ctx!(
+                entry.amount > fee,
+                ErrorCode::AmountTooSmall,
+                amount = entry.amount,
+                fee = fee
+            );
+            let token_config = &ctx.accounts.token_config;
+            let (escrowed, dust) =
+                token_config.split_dust(math::sub(entry.amount, fee)?, decimals)?;
+            let vault_amount = match token_config.dust_policy {
+                DustPolicy::Treasury => math::add(fee, dust)?,
+                _ => fee,
+            };
+            total_fee = math::add(total_fee, vault_amount)?;
+            ctx.accounts.user_stats.record(user, entry.amount)?;
+
+            let net_amount = TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.user_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.bridge_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+                extra_accounts: hook_accounts,
+            }
+            .execute(&extensions, escrowed, decimals, &[])?;
+
+            let net_amount = ctx
+                .accounts
+                .token_config
+                .to_bridged(ctx.accounts.conversion_rate.as_deref(), net_amount)?;
+            require!(net_amount > 0, ErrorCode::AmountTooSmall);
+
+            let (current_nonce, sequence) = ctx.accounts.route_sequence.take()?;
+
+            let transfer_id = transfer_id(
+                chain_ids::SOLANA,
+                chain_ids::ETHEREUM,
+                guardian_set_index,
+                current_nonce,
+                &mint,
+                net_amount,
+                &user,
+                &entry.recipient,
+            );
+
+            let mut transfer_record = TransferRecord::create_outbound(
+                record_info,
+                &ctx.accounts.user.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                instance::seed(&bridge_state.instance_id),
+                current_nonce,
+            )?;
+            transfer_record.open_outbound(
+                transfer_id,
+                current_nonce,
+                chain_ids::ETHEREUM,
+                user,
+                entry.recipient.clone(),
+                net_amount,
+            )?;
+            transfer_record.mint = mint;
+            transfer_record.escrowed = true;
+            transfer_record.fee = fee;
+            transfer_record.route_sequence = sequence;
+            transfer_record.save(record_info)?;
+
+            ctx.accounts
+                .outbox
+                .load_mut()?
+                .push(OutboxKind::Lock, &transfer_record, mint, route_id)?;
+            EventHistory::record(&ctx.accounts.event_history, &transfer_record)?;
+
+            LockEvent {
+                transfer_id,
+                from: user,
+                amount: net_amount,
+                fee,
+                fee_tier,
+                nonce: current_nonce,
+                dest_chain_id: chain_ids::ETHEREUM,
+                recipient: entry.recipient,
+                timestamp: transfer_record.created_at,
+                mint,
+                guardian_set_index,
+                required_confirmations: chain_config.required_confirmations,
+                route_id,
+                integrator_id: 0,
+            }
+            .emit(bridge_state.event_encoding, chain_config)?;
+
+            emit!(RouteSequenced {
+                transfer_id,
+                nonce: current_nonce,
+                mint,
+                dest_chain_id: chain_ids::ETHEREUM,
+                sequence,
+            });
+
+            if dust > 0 {
+                emit!(DustHandled {
+                    transfer_id,
+                    nonce: current_nonce,
+                    mint,
+                    policy: ctx.accounts.token_config.dust_policy,
+                    dust,
+                });
+            }
+
+            msg!(
+                "Locked {} tokens for {} on chain {} (nonce: {}, id: {})",
+                net_amount,
+                transfer_record.remote_address,
+                chain_ids::ETHEREUM,
+                current_nonce,
+                hex_encode(&transfer_id)
+            );
+        }
+
+        if total_fee > 0 {
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.user_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+                extra_accounts: hook_accounts,
+            }
+            .execute(&extensions, total_fee, decimals, &[])?;
+        }
+
+        Ok(())
+    }
+
+    /**
+     * Mint the deposit receipt a lock deferred (sender only)
+     *
+     * For a lock that ran short of compute for its receipt
+     * (ReceiptDeferred), or was sent without one. Only while the transfer
+     * is still waiting for the relayer; from then on there is nothing
+     * for the receipt to stand for.
+     */
+    pub fn issue_receipt(ctx: Context<IssueReceipt>, nonce: u64) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require_keys_eq!(
+            ctx.accounts.sender.key(),
+            record.local_account,
+            ErrorCode::Unauthorized
         );
+        require!(
+            record.escrowed && record.status == TransferStatus::Initiated,
+            ErrorCode::InvalidTransferStatus
+        );
+        require!(!record.receipt, ErrorCode::InvalidReceipt);
+
+        receipt::IssueReceipt {
+            mint: ctx.accounts.receipt_mint.to_account_info(),
+            token: ctx.accounts.receipt_token.to_account_info(),
+            owner: ctx.accounts.sender.to_account_info(),
+            bridge_authority: ctx.accounts.bridge_authority.to_account_info(),
+            token_program: ctx.accounts.token_2022_program.to_account_info(),
+            associated_token_program: ctx.accounts.associated_token_program.to_account_info(),
+            system_program: ctx.accounts.system_program.to_account_info(),
+        }
+        .execute(
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            nonce,
+            &[&[
+                b"bridge",
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.accounts.bridge_state.authority_bump],
+            ]],
+        )?;
+        ctx.accounts.transfer_record.receipt = true;
 
+        msg!("Receipt issued for outbound transfer {}", nonce);
         Ok(())
     }
 
@@ -115,8 +901,8 @@ pub mod solana_bridge {
      * Solana (SAME CONCEPT):
      *   pub fn mint(amount, nonce)
      */
-    pub fn mint(
-        ctx: Context<Mint>,
+    pub fn mint<'info>(
+        ctx: Context<'_, '_, '_, 'info, MintWrapped<'info>>,
         amount: u64,
         nonce: u64,
     ) -> Result<()> {
@@ -124,45 +910,158 @@ pub mod solana_bridge {
 
         // Check not paused
         require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
 
-        // Check not already processed (SAME AS: require(!processedNonces[nonce]))
-        require!(
-            !bridge_state.processed_nonces.contains(&nonce),
-            ErrorCode::AlreadyProcessed
-        );
+        // Check not already processed (SAME AS: require(!processedNonces[nonce])),
+        // or in idempotent mode succeed without doing anything
+        if bridge_state.is_duplicate_delivery(nonce, &ctx.accounts.transfer_record)? {
+            ctx.accounts
+                .relayer_stats
+                .record_duplicate(&ctx.accounts.reward_config, ctx.accounts.authority.key())?;
+            emit!(DuplicateDelivery {
+                nonce,
+                relayer: ctx.accounts.authority.key(),
+                mint: ctx.accounts.wrapped_mint.key(),
+            });
+            return Ok(());
+        }
 
-        // Verify caller is owner/relayer (signature verification)
-        require!(
-            ctx.accounts.authority.key() == bridge_state.owner,
-            ErrorCode::Unauthorized
-        );
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        ctx.accounts.source_chain.check_not_halted()?;
 
-        // Mint tokens (SAME AS: wrappedToken.mint(to, amount))
-        let seeds = &[
-            b"bridge".as_ref(),
-            &[ctx.bumps.bridge_authority],
-        ];
-        let signer = &[&seeds[..]];
+        // Enforce the source chain's delivery order, if it has an inbox
+        Inbox::deliver(&ctx.accounts.inbox.to_account_info(), nonce)?;
 
-        let cpi_ctx = CpiContext::new_with_signer(
-            ctx.accounts.token_program.to_account_info(),
-            MintTo {
-                mint: ctx.accounts.wrapped_mint.to_account_info(),
-                to: ctx.accounts.user_token.to_account_info(),
-                authority: ctx.accounts.bridge_authority.to_account_info(),
+        // Verify the transfer the way the token's trust model says:
+        // owner/relayer, guardian quorum (signers in the remaining
+        // accounts) or a posted Wormhole VAA
+        let guardian_set = ctx.accounts.guardian_set.to_account_info();
+        verifiers::verify_relayed(
+            &ctx.accounts.token_config.trust_model,
+            Relayed {
+                authority: ctx.accounts.authority.key(),
+                owner: bridge_state.owner,
+                guardian_set: &guardian_set,
+                signers: ctx.remaining_accounts,
+                vaa: ctx.accounts.vaa.as_deref(),
             },
-            signer,
-        );
-        token::mint_to(cpi_ctx, amount)?;
+            &InboundTransfer {
+                source_chain: ctx.accounts.source_chain.chain_id,
+                guardian_set_index: GuardianSet::index_of(&guardian_set)?,
+                token: ctx.accounts.wrapped_mint.key().to_bytes(),
+                recipient: ctx.accounts.user.key(),
+                amount,
+                nonce,
+            },
+        )?;
+
+        credential::check(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &ctx.accounts.user.key(),
+            amount,
+        )?;
+
+        // Mint tokens (SAME AS: wrappedToken.mint(to, amount)), unless
+        // the transfer waits for the recipient to claim it, by choice or
+        // because their token account can't take it
+        let claim_delivery = bridge_state.claim_delivery;
+        let mut quarantined = None;
+        if !claim_delivery {
+            let user_token = ctx
+                .accounts
+                .user_token
+                .as_ref()
+                .ok_or(ErrorCode::TokenAccountRequired)?;
+            quarantined = quarantine::check(
+                user_token,
+                &ctx.accounts.user.key(),
+                &ctx.accounts.wrapped_mint.key(),
+                &ctx.accounts.token_program.key(),
+            )?
+            .map(|reason| (reason, user_token.key()));
+            if quarantined.is_none() {
+                token::mint_to(
+                    CpiContext::new_with_signer(
+                        ctx.accounts.token_program.to_account_info(),
+                        MintTo {
+                            mint: ctx.accounts.wrapped_mint.to_account_info(),
+                            to: user_token.to_account_info(),
+                            authority: ctx.accounts.bridge_authority.to_account_info(),
+                        },
+                        &[&[
+                            b"bridge",
+                            instance::seed(&bridge_state.instance_id),
+                            &[bridge_state.authority_bump],
+                        ]],
+                    ),
+                    amount,
+                )?;
+            }
+        }
 
         // Mark as processed (SAME AS: processedNonces[nonce] = true)
         bridge_state.processed_nonces.push(nonce);
+        ctx.accounts.transfer_record.open_inbound(nonce, ctx.accounts.user.key(), amount)?;
+        ctx.accounts.transfer_record.mark_processed(ctx.accounts.authority.key())?;
+        accrue_relayer_reward(
+            &ctx.accounts.reward_config,
+            &mut ctx.accounts.reward_epoch,
+            &mut ctx.accounts.relayer_stats,
+            ctx.accounts.authority.key(),
+            ctx.accounts.transfer_record.latency_slots(),
+        )?;
+
+        if let Some((reason, token_account)) = quarantined {
+            let transfer_record = &mut ctx.accounts.transfer_record;
+            transfer_record.mint = ctx.accounts.wrapped_mint.key();
+            transfer_record.set_status(TransferStatus::Claimable)?;
+
+            emit!(DeliveryQuarantined {
+                transfer_id: transfer_record.transfer_id,
+                nonce,
+                recipient: ctx.accounts.user.key(),
+                mint: transfer_record.mint,
+                amount,
+                token_account,
+                reason,
+                escrowed: false,
+            });
+
+            msg!(
+                "Quarantined: {} tokens for {} (nonce: {}, {:?})",
+                amount,
+                ctx.accounts.user.key(),
+                nonce,
+                reason
+            );
+            return Ok(());
+        }
+
+        if claim_delivery {
+            let transfer_record = &mut ctx.accounts.transfer_record;
+            transfer_record.mint = ctx.accounts.wrapped_mint.key();
+            transfer_record.set_status(TransferStatus::Claimable)?;
+
+            emit!(TransferClaimable {
+                transfer_id: transfer_record.transfer_id,
+                nonce,
+                recipient: ctx.accounts.user.key(),
+                mint: transfer_record.mint,
+                amount,
+            });
+
+            msg!("Claimable: {} tokens for {} (nonce: {})", amount, ctx.accounts.user.key(), nonce);
+            return Ok(());
+        }
 
         // Emit event
         emit!(MintEvent {
             to: ctx.accounts.user.key(),
             amount,
             nonce,
+            mint: ctx.accounts.wrapped_mint.key(),
         });
 
         msg!("Minted {} tokens to {} (nonce: {})", amount, ctx.accounts.user.key(), nonce);
@@ -171,296 +1070,8354 @@ pub mod solana_bridge {
     }
 
     /**
-     * Burn wrapped tokens (same as your Solidity burn function!)
+     * Mint a transfer delivered in claim mode (see `set_claim_delivery`),
+     * or quarantined because the recipient's token account couldn't take it
      *
-     * Your Solidity:
-     *   function burn(uint256 amount, string calldata stellarAddress) external {
-     *       wrappedToken.burn(msg.sender, amount);
-     *       emit Burn(msg.sender, amount, nonce, stellarAddress);
-     *   }
+     * The recipient may claim into any token account of the wrapped
+     * mint. Anyone else may claim for them, but only into an account
+     * the recipient owns; creating the recipient's associated token
+     * account first, and paying its rent, is up to the claimer.
      *
-     * Solana (SAME CONCEPT):
-     *   pub fn burn(amount, eth_recipient)
+     * Pass the instructions sysvar, after an Ed25519 instruction with
+     * the recipient's signed authorization, to claim on their behalf
+     * into the account they chose instead (see claim_auth.rs).
      */
-    pub fn burn(
-        ctx: Context<BurnTokens>,
-        amount: u64,
-        eth_recipient: String,
-    ) -> Result<()> {
-        let bridge_state = &mut ctx.accounts.bridge_state;
-
-        // Check not paused
-        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+    pub fn claim(ctx: Context<Claim>, transfer_id: [u8; 32]) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        ctx.accounts.bridge_state.check_guardians_live(Clock::get()?.epoch)?;
 
-        // Validate Ethereum address
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        require!(transfer_record.transfer_id == transfer_id, ErrorCode::TransferMismatch);
         require!(
-            eth_recipient.starts_with("0x") && eth_recipient.len() == 42,
-            ErrorCode::InvalidEthAddress
+            transfer_record.status == TransferStatus::Claimable && !transfer_record.escrowed,
+            ErrorCode::NotClaimable
         );
 
-        // Burn tokens (SAME AS: wrappedToken.burn(msg.sender, amount))
-        let cpi_ctx = CpiContext::new(
-            ctx.accounts.token_program.to_account_info(),
-            Burn {
-                mint: ctx.accounts.wrapped_mint.to_account_info(),
-                from: ctx.accounts.user_token.to_account_info(),
-                authority: ctx.accounts.user.to_account_info(),
-            },
-        );
-        token::burn(cpi_ctx, amount)?;
+        let recipient = transfer_record.local_account;
+        let claimer = ctx.accounts.claimer.key();
+        let recipient_token = &ctx.accounts.recipient_token;
+        match &ctx.accounts.instructions {
+            Some(instructions) => claim_auth::load(instructions, &recipient)?.check(
+                &transfer_id,
+                &recipient_token.key(),
+                &claimer,
+                Clock::get()?.unix_timestamp,
+            )?,
+            None => require!(
+                claimer == recipient || recipient_token.owner == recipient,
+                ErrorCode::Unauthorized
+            ),
+        }
 
-        // Increment nonce
-        bridge_state.nonce += 1;
-        let current_nonce = bridge_state.nonce;
+        token::mint_to(
+            CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                MintTo {
+                    mint: ctx.accounts.wrapped_mint.to_account_info(),
+                    to: ctx.accounts.recipient_token.to_account_info(),
+                    authority: ctx.accounts.bridge_authority.to_account_info(),
+                },
+                &[&[
+                    b"bridge",
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    &[ctx.accounts.bridge_state.authority_bump],
+                ]],
+            ),
+            transfer_record.amount,
+        )?;
+        transfer_record.set_status(TransferStatus::Completed)?;
 
-        // Emit event (SAME AS: emit Burn(...))
-        emit!(BurnEvent {
-            from: ctx.accounts.user.key(),
-            amount,
-            nonce: current_nonce,
-            eth_recipient: eth_recipient.clone(),
+        emit!(TransferClaimed {
+            transfer_id,
+            nonce: transfer_record.nonce,
+            recipient,
+            token_account: ctx.accounts.recipient_token.key(),
+            claimer,
+            amount: transfer_record.amount,
+        });
+        emit!(MintEvent {
+            to: recipient,
+            amount: transfer_record.amount,
+            nonce: transfer_record.nonce,
+            mint: transfer_record.mint,
         });
 
         msg!(
-            "Burned {} tokens from {} for {} (nonce: {})",
-            amount,
-            ctx.accounts.user.key(),
-            eth_recipient,
-            current_nonce
+            "Claimed {} tokens for {} (nonce: {})",
+            transfer_record.amount,
+            recipient,
+            transfer_record.nonce
         );
-
         Ok(())
     }
 
     /**
-     * Pause the bridge (same as your Solidity pause!)
+     * Release a quarantined unlock from escrow (see quarantine.rs)
+     *
+     * As with `claim`, the recipient may claim into any token account of
+     * the mint and anyone else only into one the recipient owns. Pays
+     * out like unlock: from the hot vault, net of any transfer fee, and
+     * not while the token's price breaker is tripped.
      */
-    pub fn pause(ctx: Context<PauseBridge>) -> Result<()> {
-        let bridge_state = &mut ctx.accounts.bridge_state;
+    pub fn claim_unlock<'info>(ctx: Context<'_, '_, '_, 'info, ClaimUnlock<'info>>, transfer_id: [u8; 32]) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        ctx.accounts.bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        VaultConfig::check_hot(&ctx.accounts.vault_config, &ctx.accounts.bridge_token.key())?;
+        PriceBreaker::check(&ctx.accounts.price_breaker, Clock::get()?.unix_timestamp)?;
 
+        let transfer_record = &ctx.accounts.transfer_record;
+        require!(transfer_record.transfer_id == transfer_id, ErrorCode::TransferMismatch);
         require!(
-            ctx.accounts.owner.key() == bridge_state.owner,
+            transfer_record.status == TransferStatus::Claimable && transfer_record.escrowed,
+            ErrorCode::NotClaimable
+        );
+
+        let recipient = transfer_record.local_account;
+        let nonce = transfer_record.nonce;
+        let claimer = ctx.accounts.claimer.key();
+        require!(
+            claimer == recipient || ctx.accounts.recipient_token.owner == recipient,
             ErrorCode::Unauthorized
         );
 
-        bridge_state.paused = true;
-        msg!("Bridge paused");
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let received = TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.bridge_token.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.recipient_token.to_account_info(),
+            authority: ctx.accounts.bridge_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(
+            &extensions,
+            transfer_record.amount,
+            ctx.accounts.mint.decimals,
+            &[&[
+                b"bridge",
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.accounts.bridge_state.authority_bump],
+            ]],
+        )?;
+        ctx.accounts.transfer_record.set_status(TransferStatus::Completed)?;
+
+        emit!(TransferClaimed {
+            transfer_id,
+            nonce,
+            recipient,
+            token_account: ctx.accounts.recipient_token.key(),
+            claimer,
+            amount: received,
+        });
+        emit!(UnlockEvent {
+            to: recipient,
+            mint: ctx.accounts.mint.key(),
+            amount: received,
+            nonce,
+        });
+
+        msg!("Claimed {} unlocked tokens for {} (nonce: {})", received, recipient, nonce);
         Ok(())
     }
 
     /**
-     * Unpause the bridge
+     * Release escrowed tokens (the inbound side of lock)
+     *
+     * Verified like mint, by the token's trust model: normally the
+     * owner/relayer submits it once the burn on the source chain is
+     * final. For Token-2022 mints with a transfer fee the recipient pays
+     * the fee, so they get `amount` minus the fee; transfer-hook accounts
+     * go in the remaining accounts, alongside any guardian signers.
+     * For share-accounted tokens `amount` is in shares. Once the token's
+     * escrow is split (see `configure_vaults`), it pays out of the hot
+     * vault only. Nothing is released while the token's price breaker
+     * is tripped (see PriceBreaker). If the recipient's own token account
+     * can't take the tokens they stay in escrow, claimable with
+     * `claim_unlock` (see quarantine.rs).
      */
-    pub fn unpause(ctx: Context<PauseBridge>) -> Result<()> {
+    pub fn unlock<'info>(
+        ctx: Context<'_, '_, '_, 'info, Unlock<'info>>,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
         let bridge_state = &mut ctx.accounts.bridge_state;
 
-        require!(
-            ctx.accounts.owner.key() == bridge_state.owner,
-            ErrorCode::Unauthorized
-        );
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        if bridge_state.is_duplicate_delivery(nonce, &ctx.accounts.transfer_record)? {
+            ctx.accounts
+                .relayer_stats
+                .record_duplicate(&ctx.accounts.reward_config, ctx.accounts.authority.key())?;
+            emit!(DuplicateDelivery {
+                nonce,
+                relayer: ctx.accounts.authority.key(),
+                mint: ctx.accounts.mint.key(),
+            });
+            return Ok(());
+        }
 
-        bridge_state.paused = false;
-        msg!("Bridge unpaused");
-        Ok(())
-    }
-}
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        ctx.accounts.source_chain.check_not_halted()?;
 
-// ============================================================================
-// Account Structures
-// ============================================================================
-
-/**
- * Initialize accounts
- */
-#[derive(Accounts)]
-pub struct Initialize<'info> {
-    #[account(
-        init,
-        payer = owner,
-        space = 8 + BridgeState::INIT_SPACE,
-        seeds = [b"bridge_state"],
-        bump
-    )]
-    pub bridge_state: Account<'info, BridgeState>,
-
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    pub system_program: Program<'info, System>,
-}
+        // Enforce the source chain's delivery order, if it has an inbox
+        Inbox::deliver(&ctx.accounts.inbox.to_account_info(), nonce)?;
+        let guardian_set = ctx.accounts.guardian_set.to_account_info();
+        verifiers::verify_relayed(
+            &ctx.accounts.token_config.trust_model,
+            Relayed {
+                authority: ctx.accounts.authority.key(),
+                owner: bridge_state.owner,
+                guardian_set: &guardian_set,
+                signers: ctx.remaining_accounts,
+                vaa: ctx.accounts.vaa.as_deref(),
+            },
+            &InboundTransfer {
+                source_chain: ctx.accounts.source_chain.chain_id,
+                guardian_set_index: GuardianSet::index_of(&guardian_set)?,
+                token: ctx.accounts.mint.key().to_bytes(),
+                recipient: ctx.accounts.user.key(),
+                amount,
+                nonce,
+            },
+        )?;
 
-/**
- * Lock accounts
- *
- * Similar to your Solidity function parameters,
- * but in Solana you must explicitly declare all accounts
- */
-#[derive(Accounts)]
-pub struct Lock<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"bridge_state"],
-        bump
-    )]
-    pub bridge_state: Account<'info, BridgeState>,
-
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,
-
-    #[account(mut)]
-    pub bridge_token: Account<'info, TokenAccount>,
-
-    pub token_program: Program<'info, Token>,
-}
+        credential::check(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &ctx.accounts.user.key(),
+            amount,
+        )?;
 
-/**
- * Mint accounts
- */
-#[derive(Accounts)]
-pub struct Mint<'info> {
-    /// CHECK: User receiving tokens
-    #[account(mut)]
-    pub user: AccountInfo<'info>,
-
-    #[account(mut)]
-    pub authority: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"bridge_state"],
-        bump
-    )]
-    pub bridge_state: Account<'info, BridgeState>,
-
-    #[account(mut)]
-    pub wrapped_mint: Account<'info, Mint>,
-
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,
-
-    /// CHECK: PDA authority for minting
-    #[account(
-        seeds = [b"bridge"],
-        bump
-    )]
-    pub bridge_authority: AccountInfo<'info>,
-
-    pub token_program: Program<'info, Token>,
-}
+        VaultConfig::check_hot(&ctx.accounts.vault_config, &ctx.accounts.bridge_token.key())?;
+        PriceBreaker::check(&ctx.accounts.price_breaker, Clock::get()?.unix_timestamp)?;
 
-/**
- * Burn accounts
- */
-#[derive(Accounts)]
-pub struct BurnTokens<'info> {
-    #[account(mut)]
-    pub user: Signer<'info>,
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
 
-    #[account(
-        mut,
-        seeds = [b"bridge_state"],
-        bump
-    )]
-    pub bridge_state: Account<'info, BridgeState>,
+        // `amount` is in shares for rebasing tokens
+        let token_amount = ctx
+            .accounts
+            .token_config
+            .from_bridged(ctx.accounts.conversion_rate.as_deref(), amount)?;
 
-    #[account(mut)]
-    pub wrapped_mint: Account<'info, Mint>,
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&bridge_state.instance_id),
+            &[bridge_state.authority_bump],
+        ];
+        let signer = &[&seeds[..]];
 
-    #[account(mut)]
-    pub user_token: Account<'info, TokenAccount>,
+        // A recipient account that can't take it leaves it in escrow
+        let quarantined = quarantine::check(
+            &ctx.accounts.user_token,
+            &ctx.accounts.user.key(),
+            &ctx.accounts.mint.key(),
+            &ctx.accounts.token_program.key(),
+        )?;
+        let received = match quarantined {
+            Some(_) => token_amount,
+            None => TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.bridge_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, token_amount, ctx.accounts.mint.decimals, signer)?,
+        };
 
-    pub token_program: Program<'info, Token>,
-}
+        bridge_state.processed_nonces.push(nonce);
+        ctx.accounts.transfer_record.open_inbound(nonce, ctx.accounts.user.key(), received)?;
+        ctx.accounts.transfer_record.mark_processed(ctx.accounts.authority.key())?;
+        EventHistory::record(&ctx.accounts.event_history, &ctx.accounts.transfer_record)?;
+        accrue_relayer_reward(
+            &ctx.accounts.reward_config,
+            &mut ctx.accounts.reward_epoch,
+            &mut ctx.accounts.relayer_stats,
+            ctx.accounts.authority.key(),
+            ctx.accounts.transfer_record.latency_slots(),
+        )?;
 
-/**
- * Pause/Unpause accounts
- */
-#[derive(Accounts)]
-pub struct PauseBridge<'info> {
-    #[account(mut)]
-    pub owner: Signer<'info>,
-
-    #[account(
-        mut,
-        seeds = [b"bridge_state"],
-        bump
-    )]
-    pub bridge_state: Account<'info, BridgeState>,
-}
+        if let Some(reason) = quarantined {
+            let transfer_record = &mut ctx.accounts.transfer_record;
+            transfer_record.mint = ctx.accounts.mint.key();
+            transfer_record.escrowed = true;
+            transfer_record.set_status(TransferStatus::Claimable)?;
 
-// ============================================================================
-// State Account (like your Solidity contract storage)
-// ============================================================================
-
-/**
- * Bridge state
- *
- * In Solidity, this would be:
- *   contract Bridge {
- *       address public owner;
- *       uint256 public nonce;
- *       bool public paused;
- *       mapping(uint256 => bool) public processedNonces;
- *   }
- *
- * In Solana, state lives in a separate account
- */
-#[account]
-#[derive(InitSpace)]
-pub struct BridgeState {
-    pub owner: Pubkey,
-    pub nonce: u64,
-    pub paused: bool,
-    #[max_len(10000)]
-    pub processed_nonces: Vec<u64>,
-}
+            emit!(DeliveryQuarantined {
+                transfer_id: transfer_record.transfer_id,
+                nonce,
+                recipient: ctx.accounts.user.key(),
+                mint: transfer_record.mint,
+                amount: token_amount,
+                token_account: ctx.accounts.user_token.key(),
+                reason,
+                escrowed: true,
+            });
 
-// ============================================================================
-// Events (SAME CONCEPT as Solidity events!)
-// ============================================================================
-
-#[event]
-pub struct LockEvent {
-    pub from: Pubkey,
-    pub amount: u64,
-    pub nonce: u64,
-    pub eth_recipient: String,
-    pub timestamp: i64,
-}
+            msg!(
+                "Quarantined: {} tokens for {} (nonce: {}, {:?})",
+                token_amount,
+                ctx.accounts.user.key(),
+                nonce,
+                reason
+            );
+            return Ok(());
+        }
 
-#[event]
-pub struct MintEvent {
-    pub to: Pubkey,
-    pub amount: u64,
-    pub nonce: u64,
-}
+        emit!(UnlockEvent {
+            to: ctx.accounts.user.key(),
+            mint: ctx.accounts.mint.key(),
+            amount: received,
+            nonce,
+        });
 
-#[event]
-pub struct BurnEvent {
-    pub from: Pubkey,
-    pub amount: u64,
-    pub nonce: u64,
-    pub eth_recipient: String,
-}
+        msg!("Unlocked {} tokens to {} (nonce: {})", received, ctx.accounts.user.key(), nonce);
+
+        Ok(())
+    }
+
+    /**
+     * Store the Groth16 verifying key for the zk inbound path
+     *
+     * The circuit proves that an Ethereum Lock event is included in a
+     * finalized block; its two public inputs are the high and low
+     * 128 bits of the inbound message hash (see `InboundTransfer`).
+     */
+    pub fn set_verifying_key(
+        ctx: Context<SetVerifyingKey>,
+        alpha_g1: [u8; 64],
+        beta_g2: [u8; 128],
+        gamma_g2: [u8; 128],
+        delta_g2: [u8; 128],
+        ic: Vec<[u8; 64]>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            ic.len() == ZK_PUBLIC_INPUTS + 1,
+            ErrorCode::InvalidVerifyingKey
+        );
 
-// ============================================================================
-// Errors (SAME CONCEPT as Solidity require!)
-// ============================================================================
+        let zk_verifier = &mut ctx.accounts.zk_verifier;
+        zk_verifier.alpha_g1 = alpha_g1;
+        zk_verifier.beta_g2 = beta_g2;
+        zk_verifier.gamma_g2 = gamma_g2;
+        zk_verifier.delta_g2 = delta_g2;
+        zk_verifier.ic = ic;
 
-#[error_code]
-pub enum ErrorCode {
-    #[msg("Bridge is paused")]
-    BridgePaused,
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetVerifyingKey)?;
+
+        msg!("Verifying key updated");
+        Ok(())
+    }
 
-    #[msg("Transaction already processed")]
-    AlreadyProcessed,
+    /**
+     * Mint wrapped tokens with a Groth16 proof instead of a relayer signature
+     *
+     * Anyone may submit: the proof itself attests that the Ethereum lock
+     * (token, recipient, amount, nonce) is final, so no trusted caller is needed.
+     * Much cheaper in compute than verifying headers with a light client.
+     * Only for tokens on the LightClient trust model.
+     */
+    pub fn mint_with_proof(
+        ctx: Context<MintWithProof>,
+        amount: u64,
+        nonce: u64,
+        proof: Groth16Proof,
+    ) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
 
-    #[msg("Unauthorized")]
-    Unauthorized,
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        require!(
+            !bridge_state.is_processed(nonce),
+            ErrorCode::AlreadyProcessed
+        );
+        verifiers::require_model(&ctx.accounts.token_config.trust_model, TrustModel::LightClient)?;
 
-    #[msg("Invalid Ethereum address format")]
-    InvalidEthAddress,
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        ctx.accounts.source_chain.check_not_halted()?;
+
+        // Enforce the source chain's delivery order, if it has an inbox
+        Inbox::deliver(&ctx.accounts.inbox.to_account_info(), nonce)?;
+
+        // Bind the proof to this exact token, recipient, amount and nonce
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+        LightClient {
+            key: &ctx.accounts.zk_verifier,
+            proof: &proof,
+        }
+        .verify(&InboundTransfer {
+            source_chain: ctx.accounts.source_chain.chain_id,
+            guardian_set_index,
+            token: ctx.accounts.wrapped_mint.key().to_bytes(),
+            recipient: ctx.accounts.user.key(),
+            amount,
+            nonce,
+        })?;
+
+        credential::check(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &ctx.accounts.user.key(),
+            amount,
+        )?;
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                to: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        bridge_state.processed_nonces.push(nonce);
+        ctx.accounts.transfer_record.open_inbound(nonce, ctx.accounts.user.key(), amount)?;
+        ctx.accounts.transfer_record.mark_processed(ctx.accounts.submitter.key())?;
+        accrue_relayer_reward(
+            &ctx.accounts.reward_config,
+            &mut ctx.accounts.reward_epoch,
+            &mut ctx.accounts.relayer_stats,
+            ctx.accounts.submitter.key(),
+            ctx.accounts.transfer_record.latency_slots(),
+        )?;
+
+        emit!(MintEvent {
+            to: ctx.accounts.user.key(),
+            amount,
+            nonce,
+            mint: ctx.accounts.wrapped_mint.key(),
+        });
+
+        msg!("Minted {} tokens to {} with zk proof (nonce: {})", amount, ctx.accounts.user.key(), nonce);
+
+        Ok(())
+    }
+
+    /**
+     * Mint wrapped tokens with no relayer, signature or proof (devnet builds only)
+     *
+     * For integration partners building the receiving side before a
+     * relayer serves them: anyone can mint any registered wrapped token
+     * to a recipient and get the same MintEvent a delivered mint emits.
+     * Pause checks apply as for `mint`, but nothing is recorded against
+     * `nonce`: no TransferRecord, no processed nonce, no relayer reward,
+     * so simulated mints never collide with real deliveries.
+     *
+     * Only enabled with the `devnet` feature
+     * (`anchor build -- --features devnet`); the default and mainnet
+     * builds reject it with DevnetOnly. Anchor can't compile an
+     * instruction out, so it stays in every IDL.
+     */
+    pub fn devnet_force_mint(
+        ctx: Context<DevnetForceMint>,
+        amount: u64,
+        nonce: u64,
+    ) -> Result<()> {
+        require!(cfg!(feature = "devnet"), ErrorCode::DevnetOnly);
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                to: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        emit!(MintEvent {
+            to: ctx.accounts.user.key(),
+            amount,
+            nonce,
+            mint: ctx.accounts.wrapped_mint.key(),
+        });
+
+        msg!(
+            "Force-minted {} tokens to {} on devnet (nonce: {}, by {})",
+            amount,
+            ctx.accounts.user.key(),
+            nonce,
+            ctx.accounts.integrator.key()
+        );
+        Ok(())
+    }
+
+    /**
+     * Burn wrapped tokens (same as your Solidity burn function!)
+     *
+     * Your Solidity:
+     *   function burn(uint256 amount, string calldata stellarAddress) external {
+     *       wrappedToken.burn(msg.sender, amount);
+     *       emit Burn(msg.sender, amount, nonce, stellarAddress);
+     *   }
+     *
+     * Solana (SAME CONCEPT):
+     *   pub fn burn(amount, dest_chain_id, recipient, deadline, integrator_id)
+     *
+     * The destination is normally the token's origin chain; for wrapped
+     * BTC it is Bitcoin itself, paid out by the custodian. `deadline`
+     * and `integrator_id` work as for `lock`; a reclaimed burn is minted
+     * back. If the token has a burn approval policy, burns over its
+     * threshold also need `compliance_signer` (see BurnApproval). Where
+     * senders are allowlisted, only they can burn (see SenderAllowlist),
+     * and a user's withdrawal allowlist binds burns as it does locks.
+     */
+    pub fn burn(
+        ctx: Context<BurnTokens>,
+        amount: u64,
+        dest_chain_id: u64,
+        recipient: String,
+        deadline: Option<i64>,
+        integrator_id: Option<u32>,
+    ) -> Result<()> {
+        let bridge_state = &ctx.accounts.bridge_state;
+
+        // Check not paused
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        // Short of collateral, burns go through the redemption queue
+        require!(
+            !RedemptionQueue::is_active(&ctx.accounts.redemption_queue)?,
+            ErrorCode::RedemptionQueueActive
+        );
+        SenderAllowlist::check(
+            &ctx.accounts.sender_allowlist,
+            &ctx.accounts.allowed_sender,
+            &ctx.accounts.wrapped_mint.key(),
+            &ctx.accounts.user.key(),
+        )?;
+
+        // Validate recipient for the destination chain
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+        WithdrawalAllowlist::check(
+            &ctx.accounts.withdrawal_allowlist,
+            chain_config,
+            &recipient,
+            Clock::get()?.unix_timestamp,
+        )?;
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
+        );
+
+        // Burns carry no fee yet (so no surcharge either), but still
+        // count towards the user's tier and the throttle's depth
+        Throttle::admit(&ctx.accounts.throttle, 1)?;
+        let user_stats = &mut ctx.accounts.user_stats;
+        let (fee_tier, _) = ctx.accounts.fee_tiers.tier_for(user_stats.volume);
+        user_stats.record(ctx.accounts.user.key(), amount)?;
+        let integrator = ctx.accounts.integrator.as_deref_mut();
+        let integrator_id = IntegratorStats::attribute(integrator, integrator_id, amount)?;
+        BurnApproval::check(
+            &ctx.accounts.burn_approval,
+            amount,
+            ctx.accounts.compliance_signer.as_ref(),
+        )?;
+
+        // Burn tokens (SAME AS: wrappedToken.burn(msg.sender, amount))
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                from: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        );
+        token::burn(cpi_ctx, amount)?;
+
+        // Next nonce of the token's route
+        let (current_nonce, sequence) = ctx.accounts.route_sequence.take()?;
+
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+        let transfer_id = transfer_id(
+            chain_ids::SOLANA,
+            dest_chain_id,
+            guardian_set_index,
+            current_nonce,
+            &ctx.accounts.wrapped_mint.key(),
+            amount,
+            &ctx.accounts.user.key(),
+            &recipient,
+        );
+
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        transfer_record.open_outbound(
+            transfer_id,
+            current_nonce,
+            dest_chain_id,
+            ctx.accounts.user.key(),
+            recipient.clone(),
+            amount,
+        )?;
+        transfer_record.mint = ctx.accounts.wrapped_mint.key();
+        transfer_record.set_deadline(deadline)?;
+        transfer_record.route_sequence = sequence;
+
+        let route_id = RelayRoute::id_of(&ctx.accounts.relay_route)?;
+        ctx.accounts.outbox.load_mut()?.push(
+            OutboxKind::Burn,
+            transfer_record,
+            ctx.accounts.wrapped_mint.key(),
+            route_id,
+        )?;
+
+        // Emit event (SAME AS: emit Burn(...)); the recipient moves in, as in lock
+        let transfer_record = &ctx.accounts.transfer_record;
+        emit!(BurnEvent {
+            transfer_id,
+            from: ctx.accounts.user.key(),
+            amount,
+            fee_tier,
+            nonce: current_nonce,
+            dest_chain_id,
+            recipient,
+            mint: ctx.accounts.wrapped_mint.key(),
+            guardian_set_index,
+            route_id,
+            integrator_id,
+        });
+
+        emit!(RouteSequenced {
+            transfer_id,
+            nonce: current_nonce,
+            mint: ctx.accounts.wrapped_mint.key(),
+            dest_chain_id,
+            sequence,
+        });
+
+        // The BTC custodian signs a transaction paying this exact output
+        if chain_config.kind == ChainKind::Bitcoin {
+            let output_key = recipient::taproot_output_key(
+                &transfer_record.remote_address,
+                &chain_config.address_prefix,
+            )
+                .ok_or(ErrorCode::InvalidBitcoinAddress)?;
+
+            emit!(BitcoinWithdrawalEvent {
+                transfer_id,
+                nonce: current_nonce,
+                amount_sats: amount,
+                script_pubkey: taproot_script_pubkey(&output_key),
+                recipient: transfer_record.remote_address.clone(),
+            });
+        }
+
+        msg!(
+            "Burned {} tokens from {} for {} on chain {} (nonce: {}, id: {})",
+            amount,
+            ctx.accounts.user.key(),
+            transfer_record.remote_address,
+            dest_chain_id,
+            current_nonce,
+            hex_encode(&transfer_id)
+        );
+
+        Ok(())
+    }
+
+    /**
+     * Pause the bridge (same as your Solidity pause!)
+     *
+     * Queues a BridgeStatus message so the EVM bridge pauses too; every
+     * other pause and resume (bonded pauses included) does the same.
+     */
+    pub fn pause(ctx: Context<SetPaused>) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.paused = true;
+        bridge_state.owner_paused = true;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::Pause)?;
+        let (sequence, message_hash) = ctx.accounts.outbox.load_mut()?.push_status(true)?;
+        emit!(BridgeStatusQueued {
+            paused: true,
+            sequence,
+            message_hash,
+        });
+
+        msg!("Bridge paused");
+        Ok(())
+    }
+
+    /**
+     * Turn idempotent delivery on or off
+     *
+     * When on, a mint or unlock for a nonce that was already delivered
+     * succeeds without doing anything (emitting DuplicateDelivery)
+     * instead of failing with AlreadyProcessed. At-least-once relayers
+     * then don't pay for failed repeats, and a batch isn't aborted by
+     * one duplicate.
+     */
+    pub fn set_idempotent_delivery(ctx: Context<PauseBridge>, enabled: bool) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.idempotent_delivery = enabled;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetIdempotentDelivery)?;
+
+        msg!("Idempotent delivery {}", if enabled { "on" } else { "off" });
+        Ok(())
+    }
+
+    /**
+     * Turn claim delivery on or off
+     *
+     * When on, a verified mint records the transfer as Claimable instead
+     * of minting, and the recipient (or anyone on their behalf) mints it
+     * with `claim`. Relayers then don't create recipient token accounts,
+     * and recipients choose the account they receive into. Unlocks are
+     * unaffected.
+     */
+    pub fn set_claim_delivery(ctx: Context<PauseBridge>, enabled: bool) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.claim_delivery = enabled;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetClaimDelivery)?;
+
+        msg!("Claim delivery {}", if enabled { "on" } else { "off" });
+        Ok(())
+    }
+
+    /**
+     * Choose which events lock and lock_batch emit
+     *
+     * High-throughput deployments can switch to CompactLockEvent, which
+     * is smaller in the logs and decodes without allocating; Both emits
+     * each lock twice while relayers move over. Burns and the other
+     * events are unaffected.
+     */
+    pub fn set_event_encoding(ctx: Context<PauseBridge>, encoding: EventEncoding) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.event_encoding = encoding;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetEventEncoding)?;
+
+        msg!("Lock events: {:?}", encoding);
+        Ok(())
+    }
+
+    /**
+     * Appoint the fee manager (Pubkey::default() to remove it)
+     *
+     * The fee manager grants and revokes fee exemptions alongside the
+     * owner, so those can be delegated to a treasury team without
+     * handing over the rest of the admin surface.
+     */
+    pub fn set_fee_manager(ctx: Context<PauseBridge>, fee_manager: Pubkey) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.fee_manager = fee_manager;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetFeeManager)?;
+
+        msg!("Fee manager set to {}", fee_manager);
+        Ok(())
+    }
+
+    /**
+     * Waive the bridge fee on `address`'s locks (owner or fee manager)
+     *
+     * For the protocol's own flows, like market makers and the LP
+     * rebalancer. Applies to lock, lock_batch and signed quotes, and
+     * shows up in quote_lock and preview_lock.
+     */
+    pub fn grant_fee_exemption(ctx: Context<GrantFeeExemption>, address: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            ctx.accounts.bridge_state.is_fee_manager(&authority),
+            ErrorCode::Unauthorized
+        );
+
+        let timestamp = Clock::get()?.unix_timestamp;
+        let fee_exemption = &mut ctx.accounts.fee_exemption;
+        fee_exemption.address = address;
+        fee_exemption.granted_by = authority;
+        fee_exemption.granted_at = timestamp;
+
+        emit!(FeeExemptionGranted {
+            address,
+            granted_by: authority,
+            timestamp,
+        });
+
+        msg!("Fee exemption granted to {}", address);
+        Ok(())
+    }
+
+    /**
+     * Revoke `address`'s fee exemption (owner or fee manager)
+     */
+    pub fn revoke_fee_exemption(ctx: Context<RevokeFeeExemption>, address: Pubkey) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            ctx.accounts.bridge_state.is_fee_manager(&authority),
+            ErrorCode::Unauthorized
+        );
+
+        emit!(FeeExemptionRevoked {
+            address,
+            revoked_by: authority,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("Fee exemption revoked from {}", address);
+        Ok(())
+    }
+
+    /**
+     * Configure the bridge pass (Pubkey::default() mint to turn it off)
+     *
+     * Holding any amount of `pass_mint` (usually an NFT collection's
+     * SPL Token or Token-2022 mint) takes `discount_bps` off the lock fee,
+     * instead of the volume rebate when that's larger (10_000 waives the
+     * fee), and raises the holder's credential tier on restricted tokens
+     * by `tier_boost`. Holders pass their token account as `bridge_pass`
+     * to lock, lock_batch and preview_lock; signed quotes and quote_lock
+     * don't apply it.
+     */
+    pub fn set_bridge_pass(
+        ctx: Context<PauseBridge>,
+        pass_mint: Pubkey,
+        discount_bps: u16,
+        tier_boost: u8,
+    ) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(discount_bps <= 10_000, ErrorCode::InvalidConfig);
+
+        bridge_state.pass_mint = pass_mint;
+        bridge_state.pass_discount_bps = discount_bps;
+        bridge_state.pass_tier_boost = tier_boost;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetBridgePass)?;
+
+        msg!(
+            "Bridge pass {}: {} bps off, +{} tiers",
+            pass_mint,
+            discount_bps,
+            tier_boost
+        );
+        Ok(())
+    }
+
+    /**
+     * Unpause the bridge
+     */
+    pub fn unpause(ctx: Context<SetPaused>) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.paused = false;
+        bridge_state.owner_paused = false;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::Unpause)?;
+        let (sequence, message_hash) = ctx.accounts.outbox.load_mut()?.push_status(false)?;
+        emit!(BridgeStatusQueued {
+            paused: false,
+            sequence,
+            message_hash,
+        });
+
+        msg!("Bridge unpaused");
+        Ok(())
+    }
+
+    /**
+     * Set the guardian set
+     *
+     * Guardians resolve disputes in the optimistic path. Each update
+     * bumps the set index so old signatures can be told apart.
+     */
+    pub fn set_guardians(
+        ctx: Context<SetGuardians>,
+        guardians: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !guardians.is_empty() && guardians.len() <= MAX_GUARDIANS,
+            ErrorCode::InvalidGuardianSet
+        );
+        require!(
+            threshold > 0 && threshold as usize <= guardians.len(),
+            ErrorCode::InvalidGuardianSet
+        );
+
+        let guardian_set = &mut ctx.accounts.guardian_set;
+        guardian_set.index += 1;
+        guardian_set.guardians = guardians;
+        guardian_set.threshold = threshold;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetGuardians)?;
+
+        msg!(
+            "Guardian set {} updated ({} of {})",
+            guardian_set.index,
+            threshold,
+            guardian_set.guardians.len()
+        );
+        Ok(())
+    }
+
+    /**
+     * Configure the optimistic settlement path
+     *
+     * `protocol_cut_bps` of every slashed bond goes to the treasury;
+     * the rest goes to the winner of the challenge.
+     */
+    pub fn set_optimistic_config(
+        ctx: Context<SetOptimisticConfig>,
+        challenge_period: i64,
+        bond_amount: u64,
+        protocol_cut_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(challenge_period > 0, ErrorCode::InvalidConfig);
+        require!(protocol_cut_bps <= 10_000, ErrorCode::InvalidConfig);
+
+        // Bond vaults are plain system accounts, so a bond must at least
+        // cover their rent exemption
+        require!(
+            bond_amount >= Rent::get()?.minimum_balance(0),
+            ErrorCode::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.optimistic_config;
+        config.challenge_period = challenge_period;
+        config.bond_amount = bond_amount;
+        config.protocol_cut_bps = protocol_cut_bps;
+        config.treasury = treasury;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetOptimisticConfig)?;
+
+        msg!(
+            "Optimistic config: {}s challenge period, {} lamport bond, {} bps cut",
+            challenge_period,
+            bond_amount,
+            protocol_cut_bps
+        );
+        Ok(())
+    }
+
+    /**
+     * Post an attested inbound transfer (optimistic path)
+     *
+     * The relayer escrows a SOL bond in the attestation's bond vault.
+     * If nobody challenges before `executable_at`, anyone can execute the
+     * mint, of the token whose config was passed here and nothing else.
+     */
+    pub fn post_attestation(
+        ctx: Context<PostAttestation>,
+        nonce: u64,
+        recipient: Pubkey,
+        amount: u64,
+    ) -> Result<()> {
+        let bridge_state = &ctx.accounts.bridge_state;
+
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(
+            !bridge_state.is_processed(nonce),
+            ErrorCode::AlreadyProcessed
+        );
+        verifiers::require_model(&ctx.accounts.token_config.trust_model, TrustModel::Optimistic)?;
+        EjectedRelayer::check_not_ejected(&ctx.accounts.ejection)?;
+
+        let bond = ctx.accounts.optimistic_config.bond_amount;
+        let now = Clock::get()?.unix_timestamp;
+
+        let attestation = &mut ctx.accounts.attestation;
+        attestation.relayer = ctx.accounts.relayer.key();
+        attestation.mint = ctx.accounts.token_config.mint;
+        attestation.recipient = recipient;
+        attestation.amount = amount;
+        attestation.nonce = nonce;
+        attestation.relayer_bond = bond;
+        attestation.posted_at = now;
+        attestation.executable_at =
+            math::add_seconds(now, ctx.accounts.optimistic_config.challenge_period)?;
+        attestation.status = AttestationStatus::Pending;
+        attestation.challenger = Pubkey::default();
+        attestation.challenger_bond = 0;
+        attestation.evidence_hash = [0u8; 32];
+
+        // A record left over from a rejected attestation is simply reused
+        ctx.accounts.transfer_record.open_inbound(nonce, recipient, amount)?;
+        ctx.accounts.transfer_record.set_status(TransferStatus::Attested)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.relayer.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        emit!(AttestationPosted {
+            nonce,
+            relayer: ctx.accounts.relayer.key(),
+            mint: ctx.accounts.token_config.mint,
+            recipient,
+            amount,
+            executable_at: ctx.accounts.attestation.executable_at,
+        });
+        emit!(BondDeposited {
+            nonce,
+            depositor: ctx.accounts.relayer.key(),
+            amount: bond,
+        });
+
+        msg!("Attestation posted for nonce {}", nonce);
+        Ok(())
+    }
+
+    /**
+     * Challenge a pending attestation (fraud proof)
+     *
+     * Any watcher can freeze an attestation during its challenge window
+     * by matching the relayer's bond. Guardians then decide who was right.
+     */
+    pub fn challenge(
+        ctx: Context<ChallengeAttestation>,
+        nonce: u64,
+        evidence: Vec<u8>,
+    ) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+
+        require!(
+            attestation.status == AttestationStatus::Pending,
+            ErrorCode::InvalidAttestationStatus
+        );
+        require!(
+            Clock::get()?.unix_timestamp < attestation.executable_at,
+            ErrorCode::ChallengePeriodOver
+        );
+        require!(evidence.len() <= MAX_EVIDENCE_LEN, ErrorCode::EvidenceTooLong);
+
+        let bond = attestation.relayer_bond;
+        attestation.status = AttestationStatus::Challenged;
+        attestation.challenger = ctx.accounts.challenger.key();
+        attestation.challenger_bond = bond;
+        attestation.evidence_hash = keccak::hash(&evidence).to_bytes();
+
+        // Held until guardians resolve the challenge
+        ctx.accounts.transfer_record.set_status(TransferStatus::Delayed)?;
+
+        // Challenger matches the relayer's bond
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.challenger.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        emit!(AttestationChallenged {
+            nonce,
+            challenger: ctx.accounts.challenger.key(),
+            evidence,
+        });
+        emit!(BondDeposited {
+            nonce,
+            depositor: ctx.accounts.challenger.key(),
+            amount: bond,
+        });
+
+        msg!("Attestation for nonce {} challenged", nonce);
+        Ok(())
+    }
+
+    /**
+     * Resolve a challenge (guardians only)
+     *
+     * Requires `threshold` guardian signatures, passed as signer
+     * remaining accounts. The loser's bond, minus the protocol cut,
+     * goes to the winner:
+     * - Fraud: attestation is discarded, challenger gets both bonds,
+     *   and the relayer is ejected as by eject_relayer (a last
+     *   guardian stays in the set, but ejected)
+     * - Valid: relayer gets the challenger's bond, attestation is
+     *   executable again
+     */
+    pub fn resolve_challenge(
+        ctx: Context<ResolveChallenge>,
+        nonce: u64,
+        outcome: ChallengeOutcome,
+    ) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+        require!(
+            ctx.accounts.attestation.status == AttestationStatus::Challenged,
+            ErrorCode::InvalidAttestationStatus
+        );
+
+        let (loser_bond, winner_bond, winner) = match outcome {
+            ChallengeOutcome::Fraud => (
+                ctx.accounts.attestation.relayer_bond,
+                ctx.accounts.attestation.challenger_bond,
+                ctx.accounts.challenger.to_account_info(),
+            ),
+            ChallengeOutcome::Valid => (
+                ctx.accounts.attestation.challenger_bond,
+                0,
+                ctx.accounts.relayer.to_account_info(),
+            ),
+        };
+
+        let protocol_cut =
+            math::bps_of(loser_bond, ctx.accounts.optimistic_config.protocol_cut_bps)?;
+        let payout = math::add(math::sub(loser_bond, protocol_cut)?, winner_bond)?;
+
+        let nonce_bytes = nonce.to_le_bytes();
+        let seeds = &[
+            b"bond_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            nonce_bytes.as_ref(),
+            &[ctx.bumps.bond_vault],
+        ];
+        let signer = &[&seeds[..]];
+
+        if protocol_cut > 0 {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                system_program::Transfer {
+                    from: ctx.accounts.bond_vault.to_account_info(),
+                    to: ctx.accounts.treasury.to_account_info(),
+                },
+                signer,
+            );
+            system_program::transfer(cpi_ctx, protocol_cut)?;
+        }
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                to: winner.clone(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, payout)?;
+
+        match outcome {
+            ChallengeOutcome::Fraud => {
+                if let Some(stats) = ctx.accounts.relayer_stats.as_mut() {
+                    require_keys_eq!(
+                        stats.relayer,
+                        ctx.accounts.attestation.relayer,
+                        ErrorCode::Unauthorized
+                    );
+                    math::increment(&mut stats.failures)?;
+                }
+
+                // A proven fraud ejects the relayer on the spot. It leaves
+                // the guardian set too, unless it's the last guardian: the
+                // ejection still stops it relaying, and set_guardians can
+                // replace the set without undoing the slash
+                let relayer = ctx.accounts.attestation.relayer;
+                if ctx.accounts.guardian_set.guardians.len() > 1 {
+                    ctx.accounts.guardian_set.remove(&relayer)?;
+                }
+                if EjectedRelayer::create(
+                    &ctx.accounts.ejection.to_account_info(),
+                    &ctx.accounts.resolver.to_account_info(),
+                    &ctx.accounts.system_program.to_account_info(),
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    ctx.bumps.ejection,
+                    relayer,
+                    nonce,
+                )? {
+                    emit!(RelayerEjected {
+                        relayer,
+                        reason: "Attestation resolved as fraud".to_string(),
+                        fraud_nonce: Some(nonce),
+                        ejected_by: ctx.accounts.resolver.key(),
+                    });
+                }
+
+                // Rent goes to the challenger too; the nonce can be re-attested
+                ctx.accounts
+                    .attestation
+                    .close(ctx.accounts.challenger.to_account_info())?;
+                ctx.accounts.transfer_record.set_status(TransferStatus::Vetoed)?;
+            }
+            ChallengeOutcome::Valid => {
+                let attestation = &mut ctx.accounts.attestation;
+                attestation.status = AttestationStatus::Pending;
+                attestation.challenger_bond = 0;
+                ctx.accounts.transfer_record.set_status(TransferStatus::Attested)?;
+            }
+        }
+
+        emit!(ChallengeResolved {
+            nonce,
+            outcome,
+            winner: winner.key(),
+            payout,
+            protocol_cut,
+        });
+
+        msg!("Challenge for nonce {} resolved", nonce);
+        Ok(())
+    }
+
+    /**
+     * Execute an unchallenged attestation after its challenge period
+     *
+     * Permissionless: mints to the attested recipient and returns
+     * the relayer's bond. Only for tokens on the Optimistic trust model.
+     */
+    pub fn execute_attestation(ctx: Context<ExecuteAttestation>, nonce: u64) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        require!(
+            !bridge_state.is_processed(nonce),
+            ErrorCode::AlreadyProcessed
+        );
+        verifiers::require_model(&ctx.accounts.token_config.trust_model, TrustModel::Optimistic)?;
+
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        EjectedRelayer::check_not_ejected(&ctx.accounts.ejection)?;
+        ctx.accounts.source_chain.check_not_halted()?;
+
+        // Enforce the source chain's delivery order, if it has an inbox
+        Inbox::deliver(&ctx.accounts.inbox.to_account_info(), nonce)?;
+
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+        let attestation = &mut ctx.accounts.attestation;
+        Optimistic {
+            attestation,
+            now: Clock::get()?.unix_timestamp,
+        }
+        .verify(&InboundTransfer {
+            source_chain: ctx.accounts.source_chain.chain_id,
+            guardian_set_index,
+            token: ctx.accounts.wrapped_mint.key().to_bytes(),
+            recipient: attestation.recipient,
+            amount: attestation.amount,
+            nonce,
+        })?;
+
+        let amount = attestation.amount;
+        let bond = attestation.relayer_bond;
+        attestation.status = AttestationStatus::Executed;
+        attestation.relayer_bond = 0;
+
+        credential::check(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &ctx.accounts.user.key(),
+            amount,
+        )?;
+
+        // Return the relayer's bond
+        let nonce_bytes = nonce.to_le_bytes();
+        let vault_seeds = &[
+            b"bond_vault".as_ref(),
+            instance::seed(&bridge_state.instance_id),
+            nonce_bytes.as_ref(),
+            &[ctx.bumps.bond_vault],
+        ];
+        let signer: &[&[&[u8]]] = &[&vault_seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                to: ctx.accounts.relayer.to_account_info(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            MintTo {
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                to: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::mint_to(cpi_ctx, amount)?;
+
+        bridge_state.processed_nonces.push(nonce);
+        ctx.accounts.transfer_record.mark_processed(ctx.accounts.relayer.key())?;
+        accrue_relayer_reward(
+            &ctx.accounts.reward_config,
+            &mut ctx.accounts.reward_epoch,
+            &mut ctx.accounts.relayer_stats,
+            ctx.accounts.relayer.key(),
+            ctx.accounts.transfer_record.latency_slots(),
+        )?;
+
+        emit!(MintEvent {
+            to: ctx.accounts.user.key(),
+            amount,
+            nonce,
+            mint: ctx.accounts.wrapped_mint.key(),
+        });
+        emit!(AttestationExecuted {
+            nonce,
+            relayer: ctx.accounts.relayer.key(),
+            bond_returned: bond,
+        });
+
+        msg!("Executed attestation: minted {} tokens (nonce: {})", amount, nonce);
+        Ok(())
+    }
+
+    /**
+     * Register a watcher
+     *
+     * Watchers are a low-trust role: they can only freeze individual
+     * pending transfers, never move funds.
+     */
+    pub fn add_watcher(ctx: Context<AddWatcher>, watcher: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.watcher_info.watcher = watcher;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::AddWatcher)?;
+
+        msg!("Watcher {} added", watcher);
+        Ok(())
+    }
+
+    /**
+     * Remove a watcher
+     */
+    pub fn remove_watcher(ctx: Context<RemoveWatcher>, watcher: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RemoveWatcher)?;
+
+        msg!("Watcher {} removed", watcher);
+        Ok(())
+    }
+
+    /**
+     * Veto a pending transfer (watchers only)
+     *
+     * Freezes just this attestation so it cannot execute, and raises an
+     * alert. Only guardians can lift the veto.
+     */
+    pub fn veto(ctx: Context<Veto>, nonce: u64) -> Result<()> {
+        let attestation = &mut ctx.accounts.attestation;
+
+        require!(
+            attestation.status == AttestationStatus::Pending,
+            ErrorCode::InvalidAttestationStatus
+        );
+
+        attestation.status = AttestationStatus::Vetoed;
+        ctx.accounts.transfer_record.set_status(TransferStatus::Vetoed)?;
+
+        emit!(TransferVetoed {
+            nonce,
+            watcher: ctx.accounts.watcher.key(),
+            recipient: attestation.recipient,
+            amount: attestation.amount,
+        });
+
+        msg!("Transfer {} vetoed by {}", nonce, ctx.accounts.watcher.key());
+        Ok(())
+    }
+
+    /**
+     * Lift a veto (guardians only)
+     *
+     * Guardian signers are passed as remaining accounts.
+     */
+    pub fn lift_veto(ctx: Context<LiftVeto>, nonce: u64) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+
+        let attestation = &mut ctx.accounts.attestation;
+        require!(
+            attestation.status == AttestationStatus::Vetoed,
+            ErrorCode::InvalidAttestationStatus
+        );
+
+        attestation.status = AttestationStatus::Pending;
+        ctx.accounts.transfer_record.set_status(TransferStatus::Attested)?;
+
+        emit!(VetoLifted { nonce });
+
+        msg!("Veto on transfer {} lifted", nonce);
+        Ok(())
+    }
+
+    /**
+     * Register a destination chain
+     *
+     * `kind` decides how recipients for this chain are validated,
+     * `params` how long relayers wait and what users pay.
+     */
+    pub fn register_chain(
+        ctx: Context<RegisterChain>,
+        chain_id: u64,
+        kind: ChainKind,
+        params: ChainParams,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        params.validate(kind)?;
+
+        let chain_config = &mut ctx.accounts.chain_config;
+        chain_config.chain_id = chain_id;
+        chain_config.kind = kind;
+        chain_config.enabled = true;
+        chain_config.apply(&params);
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RegisterChain)?;
+
+        msg!("Chain {} registered", chain_id);
+        Ok(())
+    }
+
+    /**
+     * Update finality and fee parameters of a chain
+     */
+    pub fn set_chain_params(
+        ctx: Context<UpdateChain>,
+        chain_id: u64,
+        params: ChainParams,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        params.validate(ctx.accounts.chain_config.kind)?;
+
+        ctx.accounts.chain_config.apply(&params);
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetChainParams)?;
+
+        msg!("Chain {} params updated", chain_id);
+        Ok(())
+    }
+
+    /**
+     * Set the bech32 address prefix of a chain
+     *
+     * "cosmos", "osmo", ... for Cosmos chains; "bc" / "tb" for Bitcoin.
+     */
+    pub fn set_address_prefix(
+        ctx: Context<UpdateChain>,
+        chain_id: u64,
+        prefix: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !prefix.is_empty() && prefix.len() <= MAX_ADDRESS_PREFIX_LEN,
+            ErrorCode::InvalidConfig
+        );
+
+        ctx.accounts.chain_config.address_prefix = prefix;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetAddressPrefix)?;
+
+        msg!("Chain {} address prefix updated", chain_id);
+        Ok(())
+    }
+
+    /**
+     * Register a token that can be locked
+     */
+    pub fn register_token(ctx: Context<RegisterToken>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.token_config.mint = ctx.accounts.mint.key();
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RegisterToken)?;
+
+        msg!("Token {} registered", ctx.accounts.mint.key());
+        Ok(())
+    }
+
+    /**
+     * Map a token to its IBC denom on a Cosmos chain
+     *
+     * e.g. "transfer/channel-141/usdc" on the Hub. Replaces any
+     * existing mapping for the same chain.
+     */
+    pub fn set_ibc_denom(
+        ctx: Context<UpdateToken>,
+        chain_id: u64,
+        denom: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !denom.is_empty() && denom.len() <= MAX_IBC_DENOM_LEN,
+            ErrorCode::InvalidConfig
+        );
+
+        let token_config = &mut ctx.accounts.token_config;
+        match token_config.ibc_denoms.iter_mut().find(|d| d.chain_id == chain_id) {
+            Some(entry) => entry.denom = denom,
+            None => {
+                require!(
+                    token_config.ibc_denoms.len() < MAX_IBC_DENOMS,
+                    ErrorCode::InvalidConfig
+                );
+                token_config.ibc_denoms.push(IbcDenom { chain_id, denom });
+            }
+        }
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetIbcDenom)?;
+
+        msg!("IBC denom for chain {} updated", chain_id);
+        Ok(())
+    }
+
+    /**
+     * Enable or disable a destination chain
+     */
+    pub fn set_chain_enabled(
+        ctx: Context<UpdateChain>,
+        chain_id: u64,
+        enabled: bool,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.chain_config.enabled = enabled;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetChainEnabled)?;
+
+        msg!("Chain {} enabled: {}", chain_id, enabled);
+        Ok(())
+    }
+
+    /**
+     * Withdraw collected bridge fees
+     */
+    pub fn withdraw_fees<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawFees<'info>>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.fee_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.bridge_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(&extensions, amount, ctx.accounts.mint.decimals, signer)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::WithdrawFees)?;
+
+        msg!("Withdrew {} in fees", amount);
+        Ok(())
+    }
+
+    /**
+     * Hand admin rights to a built-in M-of-N owner multisig
+     *
+     * The bridge owner becomes the multisig authority PDA, so every admin
+     * instruction from then on goes through propose_admin_action / approve /
+     * execute. Fund the authority PDA with SOL if proposals create accounts.
+     */
+    pub fn create_owner_multisig(
+        ctx: Context<CreateOwnerMultisig>,
+        owners: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !owners.is_empty() && owners.len() <= MAX_MULTISIG_OWNERS,
+            ErrorCode::InvalidConfig
+        );
+        require!(
+            threshold > 0 && threshold as usize <= owners.len(),
+            ErrorCode::InvalidConfig
+        );
+        for (i, key) in owners.iter().enumerate() {
+            require!(!owners[..i].contains(key), ErrorCode::InvalidConfig);
+        }
+
+        let multisig = &mut ctx.accounts.owner_multisig;
+        multisig.owners = owners;
+        multisig.threshold = threshold;
+        multisig.proposal_count = 0;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::CreateOwnerMultisig)?;
+
+        ctx.accounts.bridge_state.owner = ctx.accounts.multisig_authority.key();
+
+        msg!(
+            "Bridge owner is now a {}-of-{} multisig",
+            threshold,
+            ctx.accounts.owner_multisig.owners.len()
+        );
+        Ok(())
+    }
+
+    /**
+     * Propose an admin instruction (multisig owners only)
+     *
+     * `instruction_data` and `accounts` are an instruction of this program
+     * with the multisig authority as `owner`. The proposer's approval
+     * is counted immediately.
+     */
+    pub fn propose_admin_action(
+        ctx: Context<ProposeAdminAction>,
+        instruction_data: Vec<u8>,
+        accounts: Vec<ProposedAccount>,
+    ) -> Result<()> {
+        let proposer = ctx.accounts.proposer.key();
+        let multisig = &mut ctx.accounts.owner_multisig;
+
+        require!(multisig.owners.contains(&proposer), ErrorCode::Unauthorized);
+        require!(
+            instruction_data.len() <= MAX_PROPOSAL_DATA_LEN
+                && accounts.len() <= MAX_PROPOSAL_ACCOUNTS,
+            ErrorCode::ProposalTooLarge
+        );
+
+        let proposal_id = multisig.proposal_count;
+        math::increment(&mut multisig.proposal_count)?;
+
+        let proposal = &mut ctx.accounts.proposal;
+        proposal.id = proposal_id;
+        proposal.proposer = proposer;
+        proposal.instruction_data = instruction_data;
+        proposal.accounts = accounts;
+        proposal.approvals = vec![proposer];
+        proposal.executed = false;
+        proposal.created_at = Clock::get()?.unix_timestamp;
+
+        emit!(AdminActionProposed {
+            proposal_id,
+            proposer,
+        });
+
+        msg!("Admin proposal {} created by {}", proposal_id, proposer);
+        Ok(())
+    }
+
+    /**
+     * Approve an admin proposal (multisig owners only)
+     */
+    pub fn approve(ctx: Context<ApproveAdminAction>, proposal_id: u64) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(
+            ctx.accounts.owner_multisig.owners.contains(&owner),
+            ErrorCode::Unauthorized
+        );
+        require!(!proposal.executed, ErrorCode::ProposalExecuted);
+        require!(!proposal.approvals.contains(&owner), ErrorCode::AlreadyApproved);
+
+        proposal.approvals.push(owner);
+
+        emit!(AdminActionApproved {
+            proposal_id,
+            owner,
+            approvals: proposal.approvals.len() as u8,
+        });
+
+        msg!("Admin proposal {} approved by {}", proposal_id, owner);
+        Ok(())
+    }
+
+    /**
+     * Execute an approved admin proposal
+     *
+     * Permissionless once `threshold` owners approved. Remaining accounts
+     * are the proposal's accounts in order, followed by this program;
+     * the multisig authority PDA signs the inner instruction.
+     */
+    pub fn execute(ctx: Context<ExecuteAdminAction>, proposal_id: u64) -> Result<()> {
+        let multisig = &ctx.accounts.owner_multisig;
+        let proposal = &mut ctx.accounts.proposal;
+
+        require!(!proposal.executed, ErrorCode::ProposalExecuted);
+
+        // Only approvals from current owners count
+        let approvals = proposal
+            .approvals
+            .iter()
+            .filter(|key| multisig.owners.contains(key))
+            .count();
+        require!(
+            approvals >= multisig.threshold as usize,
+            ErrorCode::InsufficientApprovals
+        );
+
+        require!(
+            ctx.remaining_accounts.len() >= proposal.accounts.len(),
+            ErrorCode::ProposalAccountMismatch
+        );
+        let mut metas = Vec::with_capacity(proposal.accounts.len());
+        for (proposed, info) in proposal.accounts.iter().zip(ctx.remaining_accounts) {
+            require_keys_eq!(proposed.pubkey, info.key(), ErrorCode::ProposalAccountMismatch);
+            metas.push(AccountMeta {
+                pubkey: proposed.pubkey,
+                is_signer: proposed.is_signer,
+                is_writable: proposed.is_writable,
+            });
+        }
+
+        let instruction = Instruction {
+            program_id: crate::ID,
+            accounts: metas,
+            data: proposal.instruction_data.clone(),
+        };
+        proposal.executed = true;
+
+        invoke_signed(
+            &instruction,
+            ctx.remaining_accounts,
+            &[&[
+                b"multisig_authority".as_ref(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.bumps.multisig_authority],
+            ]],
+        )?;
+
+        emit!(AdminActionExecuted { proposal_id });
+
+        msg!("Admin proposal {} executed", proposal_id);
+        Ok(())
+    }
+
+    /**
+     * Start handing the bridge to a new owner (step 1 of 2)
+     *
+     * The new owner can be a keypair or a PDA such as a Squads vault;
+     * admin instructions only need `owner` to sign, which a vault does
+     * when its proposal executes.
+     */
+    pub fn transfer_ownership(ctx: Context<TransferOwnership>, new_owner: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.bridge_state.pending_owner = new_owner;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::TransferOwnership)?;
+
+        msg!("Ownership transfer to {} started", new_owner);
+        Ok(())
+    }
+
+    /**
+     * Accept ownership (step 2 of 2, signed by the pending owner)
+     *
+     * Proves the new owner can actually sign before the old one lets go.
+     */
+    pub fn accept_ownership(ctx: Context<AcceptOwnership>) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            bridge_state.pending_owner != Pubkey::default()
+                && ctx.accounts.new_owner.key() == bridge_state.pending_owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.owner = bridge_state.pending_owner;
+        bridge_state.pending_owner = Pubkey::default();
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.new_owner.key(), AdminAction::AcceptOwnership)?;
+
+        msg!("Bridge owner is now {}", ctx.accounts.new_owner.key());
+        Ok(())
+    }
+
+    /**
+     * Hand the program's upgrade authority to the bridge owner
+     *
+     * Signed by the current upgrade authority, usually the deployer key.
+     * Afterwards an upgrade needs whatever governs the bridge (the owner
+     * multisig or a Squads vault) to sign, like any other admin action.
+     * The owner doesn't sign here; `accept_ownership` already proved it
+     * can.
+     */
+    pub fn transfer_upgrade_authority(ctx: Context<TransferUpgradeAuthority>) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let owner = ctx.accounts.bridge_state.owner;
+        let program_data = &ctx.accounts.program_data;
+        require!(
+            program_data.upgrade_authority_address == Some(authority),
+            ErrorCode::Unauthorized
+        );
+        require_keys_neq!(authority, owner, ErrorCode::InvalidConfig);
+
+        upgrade::set_authority(
+            &program_data.to_account_info(),
+            &ctx.accounts.authority.to_account_info(),
+            Some(&ctx.accounts.owner.to_account_info()),
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .upgrade_record
+            .record(Some(owner), &owner, program_data.slot, now);
+
+        ctx.accounts
+            .admin_log
+            .record(authority, AdminAction::TransferUpgradeAuthority)?;
+
+        emit!(UpgradeAuthorityChanged {
+            previous: Some(authority),
+            authority: Some(owner),
+            held_by_owner: true,
+            timestamp: now,
+        });
+
+        msg!("Upgrade authority is now the bridge owner {}", owner);
+        Ok(())
+    }
+
+    /**
+     * Make the program immutable (owner only, irreversible)
+     *
+     * Sets the upgrade authority to None, so no one can ever upgrade the
+     * program again: not governance, not a compromised key, and not a
+     * fix for a bug found later. The owner must already be the upgrade
+     * authority (see `transfer_upgrade_authority`), so this goes through
+     * the same governance as every other admin action.
+     */
+    pub fn finalize_program(ctx: Context<FinalizeProgram>) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        require!(owner == ctx.accounts.bridge_state.owner, ErrorCode::Unauthorized);
+        let program_data = &ctx.accounts.program_data;
+        require!(
+            program_data.upgrade_authority_address == Some(owner),
+            ErrorCode::Unauthorized
+        );
+
+        upgrade::set_authority(
+            &program_data.to_account_info(),
+            &ctx.accounts.owner.to_account_info(),
+            None,
+        )?;
+
+        let now = Clock::get()?.unix_timestamp;
+        ctx.accounts
+            .upgrade_record
+            .record(None, &owner, program_data.slot, now);
+
+        ctx.accounts
+            .admin_log
+            .record(owner, AdminAction::FinalizeProgram)?;
+
+        emit!(UpgradeAuthorityChanged {
+            previous: Some(owner),
+            authority: None,
+            held_by_owner: false,
+            timestamp: now,
+        });
+
+        msg!("Program finalized; it can no longer be upgraded");
+        Ok(())
+    }
+
+    /**
+     * Record the verified-build hash of the deployed program (owner only)
+     *
+     * `hash` is what `solana-verify get-program-hash` reports for the
+     * audited source; the record is tied to the current deploy slot, so
+     * an upgrade leaves it stale until governance records again. The
+     * program can't hash its own executable within the compute budget,
+     * so the owner vouches and clients check (see the SDK's build module).
+     */
+    pub fn record_build(ctx: Context<RecordBuild>, hash: [u8; 32], version: String) -> Result<()> {
+        let owner = ctx.accounts.owner.key();
+        require!(owner == ctx.accounts.bridge_state.owner, ErrorCode::Unauthorized);
+        require!(
+            hash != [0; 32] && !version.is_empty() && version.len() <= MAX_BUILD_VERSION_LEN,
+            ErrorCode::InvalidConfig
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let record = &mut ctx.accounts.build_record;
+        record.hash = hash;
+        record.version = version;
+        record.deployed_slot = ctx.accounts.program_data.slot;
+        record.recorded_at = now;
+
+        ctx.accounts
+            .admin_log
+            .record(owner, AdminAction::RecordBuild)?;
+
+        emit!(BuildRecorded {
+            hash,
+            version: record.version.clone(),
+            deployed_slot: record.deployed_slot,
+            timestamp: now,
+        });
+
+        msg!("Build {} recorded: {}", record.version, hex_encode(&hash));
+        Ok(())
+    }
+
+    /**
+     * Copy the program's current upgrade authority into its record
+     *
+     * Permissionless. Picks up authority changes made directly through
+     * the loader, and which owner holds it after an ownership transfer.
+     */
+    pub fn sync_upgrade_authority(ctx: Context<SyncUpgradeAuthority>) -> Result<()> {
+        let owner = ctx.accounts.bridge_state.owner;
+        let program_data = &ctx.accounts.program_data;
+        let authority = program_data.upgrade_authority_address;
+        let now = Clock::get()?.unix_timestamp;
+
+        let record = &mut ctx.accounts.upgrade_record;
+        let first = record.recorded_at == 0;
+        let previous = record.record(authority, &owner, program_data.slot, now);
+
+        if !first && previous != authority {
+            emit!(UpgradeAuthorityChanged {
+                previous,
+                authority,
+                held_by_owner: record.held_by_owner,
+                timestamp: now,
+            });
+        }
+
+        match authority {
+            Some(authority) => msg!(
+                "Upgrade authority: {} (bridge owner: {})",
+                authority,
+                record.held_by_owner
+            ),
+            None => msg!("Upgrade authority: none, the program is final"),
+        }
+        Ok(())
+    }
+
+    /**
+     * Configure relayer rewards
+     *
+     * Every completed inbound transfer adds `reward_per_transfer` lamports
+     * to the current epoch's bucket. Rewards are paid from the "reward_vault"
+     * PDA, which anyone can top up with a plain SOL transfer.
+     */
+    pub fn set_relayer_rewards(
+        ctx: Context<SetRelayerRewards>,
+        epoch_duration: i64,
+        reward_per_transfer: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(epoch_duration > 0, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.reward_config;
+        // Changing the duration would renumber past epochs, so it is fixed once set
+        if config.start_time == 0 {
+            config.start_time = Clock::get()?.unix_timestamp;
+            config.epoch_duration = epoch_duration;
+        } else {
+            require!(config.epoch_duration == epoch_duration, ErrorCode::InvalidConfig);
+        }
+        config.reward_per_transfer = reward_per_transfer;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetRelayerRewards)?;
+
+        msg!(
+            "Relayer rewards: {} lamports per transfer, {}s epochs",
+            reward_per_transfer,
+            epoch_duration
+        );
+        Ok(())
+    }
+
+    /**
+     * Claim a relayer's share of a finished epoch's reward bucket
+     *
+     * Pro-rata by completed transfers: bucket * mine / total.
+     * The relayer's stats account is closed, so each epoch pays once.
+     */
+    pub fn claim_relayer_rewards(ctx: Context<ClaimRelayerRewards>, epoch: u64) -> Result<()> {
+        require!(
+            epoch < ctx.accounts.reward_config.current_epoch(),
+            ErrorCode::EpochNotOver
+        );
+
+        let bucket = &ctx.accounts.reward_epoch;
+        let stats = &ctx.accounts.relayer_stats;
+        let transfers = stats.transfers;
+        let amount = math::mul_div(bucket.total_reward, transfers, bucket.total_transfers)?;
+
+        // The vault itself must stay rent exempt
+        let vault = &ctx.accounts.reward_vault;
+        require!(
+            vault.lamports() >= math::add(amount, Rent::get()?.minimum_balance(0))?,
+            ErrorCode::InsufficientRewardFunds
+        );
+
+        let seeds = &[
+            b"reward_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.reward_vault],
+        ];
+        let signer: &[&[&[u8]]] = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.relayer.to_account_info(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        emit!(RelayerRewardsClaimed {
+            epoch,
+            relayer: ctx.accounts.relayer.key(),
+            transfers,
+            amount,
+            submitted: stats.submitted,
+            total_latency_slots: stats.total_latency_slots,
+            failures: stats.failures,
+        });
+
+        msg!("Relayer {} claimed {} lamports for epoch {}", ctx.accounts.relayer.key(), amount, epoch);
+        Ok(())
+    }
+
+    /**
+     * Cap what one gas report is reimbursed (0 turns reimbursement off)
+     */
+    pub fn set_gas_reimbursement(
+        ctx: Context<SetRelayerRewards>,
+        max_gas_per_transfer: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.reward_config.max_gas_per_transfer = max_gas_per_transfer;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetGasReimbursement)?;
+
+        msg!("Gas reimbursement: up to {} lamports per transfer", max_gas_per_transfer);
+        Ok(())
+    }
+
+    /**
+     * Report the gas a relayer paid delivering inbound transfer `nonce`
+     *
+     * Only the relayer that completed it can report, once, after the
+     * transfer landed (right after the mint or unlock, or in a later
+     * transaction). `gas_paid` is the relayer's own figure in lamports
+     * (transaction and priority fees, rent for accounts it created); it
+     * is kept on-chain in a `GasReport`, and up to `max_gas_per_transfer`
+     * of it is credited to the relayer's reimbursement balance.
+     */
+    pub fn report_gas(ctx: Context<ReportGas>, nonce: u64, gas_paid: u64) -> Result<()> {
+        let relayer = ctx.accounts.relayer.key();
+        let record = &ctx.accounts.transfer_record;
+        require_keys_eq!(record.processed_by, relayer, ErrorCode::Unauthorized);
+        require!(
+            matches!(record.status, TransferStatus::Completed | TransferStatus::Claimable),
+            ErrorCode::InvalidTransferStatus
+        );
+
+        let reimbursed = gas_paid.min(ctx.accounts.reward_config.max_gas_per_transfer);
+        let timestamp = Clock::get()?.unix_timestamp;
+
+        let report = &mut ctx.accounts.gas_report;
+        report.nonce = nonce;
+        report.relayer = relayer;
+        report.gas_paid = gas_paid;
+        report.reimbursed = reimbursed;
+        report.reported_at = timestamp;
+
+        let balance = &mut ctx.accounts.gas_reimbursement;
+        balance.relayer = relayer;
+        balance.owed = math::add(balance.owed, reimbursed)?;
+        balance.reports = math::add(balance.reports, 1)?;
+        balance.gas_paid = math::add(balance.gas_paid, gas_paid)?;
+
+        emit!(GasReported {
+            nonce,
+            relayer,
+            gas_paid,
+            reimbursed,
+            timestamp,
+        });
+
+        msg!("Relayer {} paid {} lamports for nonce {}", relayer, gas_paid, nonce);
+        Ok(())
+    }
+
+    /**
+     * Pay out a relayer's gas reimbursement balance
+     *
+     * From the reward vault, like relayer rewards; the owner funds it
+     * out of collected fees.
+     */
+    pub fn claim_gas_reimbursement(ctx: Context<ClaimGasReimbursement>) -> Result<()> {
+        let amount = ctx.accounts.gas_reimbursement.owed;
+        require!(amount > 0, ErrorCode::NothingToReimburse);
+
+        // The vault itself must stay rent exempt
+        let vault = &ctx.accounts.reward_vault;
+        require!(
+            vault.lamports() >= math::add(amount, Rent::get()?.minimum_balance(0))?,
+            ErrorCode::InsufficientRewardFunds
+        );
+
+        let seeds = &[
+            b"reward_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.reward_vault],
+        ];
+        let signer: &[&[&[u8]]] = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.reward_vault.to_account_info(),
+                to: ctx.accounts.relayer.to_account_info(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        let balance = &mut ctx.accounts.gas_reimbursement;
+        balance.owed = 0;
+        balance.claimed = math::add(balance.claimed, amount)?;
+
+        emit!(GasReimbursementClaimed {
+            relayer: balance.relayer,
+            amount,
+            gas_paid: balance.gas_paid,
+            claimed: balance.claimed,
+        });
+
+        msg!("Relayer {} claimed {} lamports of gas", balance.relayer, amount);
+        Ok(())
+    }
+
+    /**
+     * Set the volume rebate tiers
+     *
+     * Tiers are sorted by `min_volume`; a user's highest reached tier
+     * takes `discount_bps` off the lock fee. An empty list disables rebates.
+     */
+    pub fn set_fee_tiers(ctx: Context<SetFeeTiers>, tiers: Vec<FeeTier>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(tiers.len() <= MAX_FEE_TIERS, ErrorCode::InvalidConfig);
+        for (i, tier) in tiers.iter().enumerate() {
+            require!(tier.discount_bps <= 10_000, ErrorCode::InvalidConfig);
+            if i > 0 {
+                require!(
+                    tier.min_volume > tiers[i - 1].min_volume,
+                    ErrorCode::InvalidConfig
+                );
+            }
+        }
+
+        let count = tiers.len();
+        ctx.accounts.fee_tiers.tiers = tiers;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetFeeTiers)?;
+
+        msg!("Fee tiers updated ({} tiers)", count);
+        Ok(())
+    }
+
+    /**
+     * Flag a token as restricted (compliance hook)
+     *
+     * Restricted tokens need a credential from `credential_program` for
+     * lock and mint; the credential's tier picks the per-transfer limit
+     * from `tier_limits`. Leave `restricted` off to skip the check entirely.
+     */
+    pub fn set_token_restrictions(
+        ctx: Context<UpdateToken>,
+        restricted: bool,
+        credential_program: Pubkey,
+        tier_limits: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(tier_limits.len() <= MAX_CREDENTIAL_TIERS, ErrorCode::InvalidConfig);
+        require!(
+            !restricted || credential_program != Pubkey::default(),
+            ErrorCode::InvalidConfig
+        );
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.restricted = restricted;
+        token_config.credential_program = credential_program;
+        token_config.tier_limits = tier_limits;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetTokenRestrictions)?;
+
+        msg!("Token {} restricted: {}", ctx.accounts.token_config.mint, restricted);
+        Ok(())
+    }
+
+    /**
+     * Configure permissionless bonded pauses
+     *
+     * `reward` is paid from the insurance fund PDA when guardians
+     * confirm the incident; a bond of 0 disables bonded pauses. With
+     * `expiry_slots` set, a bonded pause lapses that many slots after it
+     * was raised or last confirmed by a guardian (0 = never).
+     */
+    pub fn set_pause_bond_config(
+        ctx: Context<SetPauseBondConfig>,
+        bond_amount: u64,
+        reward: u64,
+        expiry_slots: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        // Same as attestation bonds: the vault must stay rent exempt
+        require!(
+            bond_amount == 0 || bond_amount >= Rent::get()?.minimum_balance(0),
+            ErrorCode::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.pause_bond_config;
+        config.bond_amount = bond_amount;
+        config.reward = reward;
+        config.expiry_slots = expiry_slots;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetPauseBondConfig)?;
+
+        msg!(
+            "Bonded pause: {} lamport bond, {} lamport reward, expires after {} slots",
+            bond_amount,
+            reward,
+            expiry_slots
+        );
+        Ok(())
+    }
+
+    /**
+     * Pause the bridge by posting a SOL bond (anyone)
+     *
+     * Lets anyone who spots an incident stop the bridge without waiting
+     * for the owner. Guardians then decide whether it was justified.
+     */
+    pub fn bonded_pause(ctx: Context<BondedPause>) -> Result<()> {
+        let bond = ctx.accounts.pause_bond_config.bond_amount;
+        require!(bond > 0, ErrorCode::InvalidConfig);
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.pauser.to_account_info(),
+                to: ctx.accounts.bond_vault.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        let bonded_pause = &mut ctx.accounts.bonded_pause;
+        bonded_pause.pauser = ctx.accounts.pauser.key();
+        bonded_pause.bond = bond;
+        let clock = Clock::get()?;
+        bonded_pause.paused_at = clock.unix_timestamp;
+        bonded_pause.expires_at_slot =
+            ctx.accounts.pause_bond_config.expiry_slot(clock.slot)?;
+
+        ctx.accounts.bridge_state.paused = true;
+        let (sequence, message_hash) = ctx.accounts.outbox.load_mut()?.push_status(true)?;
+        emit!(BridgeStatusQueued {
+            paused: true,
+            sequence,
+            message_hash,
+        });
+
+        emit!(BondedPauseRaised {
+            pauser: ctx.accounts.pauser.key(),
+            bond,
+        });
+
+        msg!("Bridge paused by {} with a {} lamport bond", ctx.accounts.pauser.key(), bond);
+        Ok(())
+    }
+
+    /**
+     * Resolve a bonded pause (guardians only)
+     *
+     * Guardian signers are passed as remaining accounts.
+     * - Incident: the pauser gets the bond back plus the reward (capped by
+     *   what the insurance fund holds); the bridge stays paused
+     * - Frivolous: the bond goes to the insurance fund and the bridge
+     *   resumes (unless the owner paused it too)
+     */
+    pub fn resolve_bonded_pause(ctx: Context<ResolveBondedPause>, incident: bool) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+
+        let bond = ctx.accounts.bonded_pause.bond;
+        let instance_id = ctx.accounts.bridge_state.instance_id;
+        let vault_seeds = &[
+            b"pause_bond_vault".as_ref(),
+            instance::seed(&instance_id),
+            &[ctx.bumps.bond_vault],
+        ];
+
+        let (bond_to, reward) = if incident {
+            let available = ctx
+                .accounts
+                .insurance_fund
+                .lamports()
+                .saturating_sub(Rent::get()?.minimum_balance(0));
+            let reward = ctx.accounts.pause_bond_config.reward.min(available);
+
+            if reward > 0 {
+                let fund_seeds = &[
+                    b"insurance_fund".as_ref(),
+                    instance::seed(&instance_id),
+                    &[ctx.bumps.insurance_fund],
+                ];
+                let signer: &[&[&[u8]]] = &[&fund_seeds[..]];
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: ctx.accounts.insurance_fund.to_account_info(),
+                        to: ctx.accounts.pauser.to_account_info(),
+                    },
+                    signer,
+                );
+                system_program::transfer(cpi_ctx, reward)?;
+            }
+
+            (ctx.accounts.pauser.to_account_info(), reward)
+        } else {
+            if ctx.accounts.bridge_state.lift_bonded_pause() {
+                let (sequence, message_hash) =
+                    ctx.accounts.outbox.load_mut()?.push_status(false)?;
+                emit!(BridgeStatusQueued {
+                    paused: false,
+                    sequence,
+                    message_hash,
+                });
+            }
+            (ctx.accounts.insurance_fund.to_account_info(), 0)
+        };
+
+        let signer: &[&[&[u8]]] = &[&vault_seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                to: bond_to,
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        emit!(BondedPauseResolved {
+            pauser: ctx.accounts.pauser.key(),
+            incident,
+            bond,
+            reward,
+        });
+
+        msg!("Bonded pause resolved (incident: {})", incident);
+        Ok(())
+    }
+
+    /**
+     * Keep a bonded pause in force for another `expiry_slots` (a guardian)
+     *
+     * The dead-man's switch on bonded pauses: one that no guardian
+     * confirms lapses, so a pause raised while the guardians are away
+     * can't hold the bridge indefinitely. The guardian signs as a
+     * remaining account.
+     */
+    pub fn confirm_bonded_pause(ctx: Context<ConfirmBondedPause>) -> Result<()> {
+        require!(
+            count_guardian_signers(&ctx.accounts.guardian_set, ctx.remaining_accounts) > 0,
+            ErrorCode::InsufficientGuardianSignatures
+        );
+
+        let slot = Clock::get()?.slot;
+        let bonded_pause = &mut ctx.accounts.bonded_pause;
+        require!(!bonded_pause.is_expired(slot), ErrorCode::PauseExpired);
+        bonded_pause.expires_at_slot = ctx.accounts.pause_bond_config.expiry_slot(slot)?;
+
+        emit!(BondedPauseConfirmed {
+            pauser: bonded_pause.pauser,
+            expires_at_slot: bonded_pause.expires_at_slot,
+        });
+
+        msg!("Bonded pause confirmed until slot {}", bonded_pause.expires_at_slot);
+        Ok(())
+    }
+
+    /**
+     * Lift a bonded pause no guardian confirmed in time (anyone)
+     *
+     * Nobody ruled on the pause, so the pauser gets the bond back (with
+     * no reward) and the bridge resumes, unless the owner paused it too.
+     */
+    pub fn expire_bonded_pause(ctx: Context<ExpireBondedPause>) -> Result<()> {
+        let bonded_pause = &ctx.accounts.bonded_pause;
+        require!(
+            bonded_pause.is_expired(Clock::get()?.slot),
+            ErrorCode::PauseNotExpired
+        );
+
+        let bond = bonded_pause.bond;
+        let vault_seeds = &[
+            b"pause_bond_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bond_vault],
+        ];
+        let signer: &[&[&[u8]]] = &[&vault_seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.bond_vault.to_account_info(),
+                to: ctx.accounts.pauser.to_account_info(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        let resumed = ctx.accounts.bridge_state.lift_bonded_pause();
+        if resumed {
+            let (sequence, message_hash) = ctx.accounts.outbox.load_mut()?.push_status(false)?;
+            emit!(BridgeStatusQueued {
+                paused: false,
+                sequence,
+                message_hash,
+            });
+        }
+
+        emit!(BondedPauseExpired {
+            pauser: ctx.accounts.pauser.key(),
+            bond,
+        });
+
+        if resumed {
+            msg!("Bonded pause expired; bridge resumed");
+        } else {
+            msg!("Bonded pause expired; bridge stays paused by the owner");
+        }
+        Ok(())
+    }
+
+    /**
+     * Require a guardian heartbeat every `epochs` epochs (0 = off)
+     *
+     * The other half of the dead-man's switch: if no guardian posts a
+     * heartbeat for longer than that, every inbound release (mint,
+     * unlock, the proof, attestation and claim paths) fails with
+     * GuardiansUnresponsive until one does. The clock starts at the
+     * current epoch.
+     */
+    pub fn set_heartbeat_interval(ctx: Context<PauseBridge>, epochs: u64) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+
+        require!(
+            ctx.accounts.owner.key() == bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        bridge_state.heartbeat_epochs = epochs;
+        bridge_state.last_heartbeat_epoch = Clock::get()?.epoch;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetHeartbeatInterval)?;
+
+        msg!("Guardian heartbeat required every {} epochs", epochs);
+        Ok(())
+    }
+
+    /**
+     * Post a liveness heartbeat (any guardian in the guardian set)
+     *
+     * Kept for existing callers; `heartbeat` does the same and also
+     * records the guardian in the liveness registry.
+     */
+    pub fn guardian_heartbeat(ctx: Context<GuardianHeartbeat>) -> Result<()> {
+        let guardian = ctx.accounts.guardian.key();
+        require!(
+            ctx.accounts.guardian_set.guardians.contains(&guardian),
+            ErrorCode::Unauthorized
+        );
+
+        let epoch = Clock::get()?.epoch;
+        ctx.accounts.bridge_state.last_heartbeat_epoch = epoch;
+
+        emit!(GuardianHeartbeatPosted { guardian, epoch });
+
+        msg!("Guardian {} heartbeat (epoch {})", guardian, epoch);
+        Ok(())
+    }
+
+    /**
+     * Post a heartbeat to the operator's liveness entry (guardians and
+     * the owner/relayer)
+     *
+     * Creates the entry on the first call. A guardian's heartbeat also
+     * resets the dead-man's switch, as `guardian_heartbeat` does.
+     */
+    pub fn heartbeat(ctx: Context<Heartbeat>) -> Result<()> {
+        let operator = ctx.accounts.operator.key();
+        let guardian =
+            GuardianSet::is_member(&ctx.accounts.guardian_set.to_account_info(), &operator)?;
+        require!(
+            guardian || operator == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        if guardian {
+            ctx.accounts.bridge_state.last_heartbeat_epoch = clock.epoch;
+        }
+
+        let liveness = &mut ctx.accounts.liveness;
+        if liveness.beats == 0 {
+            liveness.operator = operator;
+            liveness.first_seen = clock.unix_timestamp;
+        }
+        liveness.guardian = guardian;
+        liveness.last_seen = clock.unix_timestamp;
+        liveness.last_slot = clock.slot;
+        liveness.last_epoch = clock.epoch;
+        math::increment(&mut liveness.beats)?;
+
+        emit!(HeartbeatPosted {
+            operator,
+            guardian,
+            epoch: clock.epoch,
+            timestamp: clock.unix_timestamp,
+        });
+
+        msg!("Operator {} heartbeat (epoch {})", operator, clock.epoch);
+        Ok(())
+    }
+
+    /**
+     * Post a relay route's measured health and score it (guardians and
+     * the owner/relayer; see route_health.rs)
+     *
+     * The heartbeat age comes from the relayer's liveness entry, not the
+     * report; a relayer that never beat counts as down.
+     */
+    pub fn report_route_health(
+        ctx: Context<ReportRouteHealth>,
+        route_id: u32,
+        report: RouteHealthReport,
+    ) -> Result<()> {
+        let reporter = ctx.accounts.reporter.key();
+        let guardian =
+            GuardianSet::is_member(&ctx.accounts.guardian_set.to_account_info(), &reporter)?;
+        require!(
+            guardian || reporter == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let clock = Clock::get()?;
+        let liveness = ctx.accounts.relayer_liveness.to_account_info();
+        let heartbeat_age = if liveness.data_is_empty() {
+            u64::MAX
+        } else {
+            let entry = OperatorLiveness::try_deserialize(&mut &liveness.try_borrow_data()?[..])?;
+            clock.unix_timestamp.saturating_sub(entry.last_seen).max(0) as u64
+        };
+        let score = route_health::score(heartbeat_age, &report);
+
+        let health = &mut ctx.accounts.route_health;
+        let previous_score = (health.updated_at != 0).then_some(health.score);
+        health.route_id = route_id;
+        health.score = score;
+        health.report = report;
+        health.heartbeat_age = heartbeat_age;
+        health.reporter = reporter;
+        health.updated_at = clock.unix_timestamp;
+        health.updated_slot = clock.slot;
+
+        emit!(RouteHealthReported {
+            route_id,
+            score,
+            previous_score,
+            status: health.status(),
+            report,
+            heartbeat_age,
+            reporter,
+        });
+
+        msg!("Route {} health {} ({:?})", route_id, score, health.status());
+        Ok(())
+    }
+
+    /**
+     * Mark a source chain halted, or clear the mark
+     *
+     * For sequencer outages and rollbacks: while a chain is halted,
+     * mint and unlock from it fail with SourceChainHalted, so nothing is
+     * delivered on state that may yet be rolled back. Any one guardian
+     * can halt a chain; clearing takes a guardian quorum (signers in the
+     * remaining accounts). The owner can do either.
+     */
+    pub fn chain_status(ctx: Context<ChainStatus>, chain_id: u64, halted: bool) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let guardian_set = ctx.accounts.guardian_set.to_account_info();
+
+        if authority == ctx.accounts.bridge_state.owner {
+            ctx.accounts.admin_log.record(authority, AdminAction::SetChainStatus)?;
+        } else if halted {
+            require!(
+                GuardianSet::is_member(&guardian_set, &authority)?,
+                ErrorCode::Unauthorized
+            );
+        } else {
+            require!(
+                GuardianSet::has_quorum(&guardian_set, ctx.remaining_accounts)?,
+                ErrorCode::Unauthorized
+            );
+        }
+
+        ctx.accounts.chain_config.halted = halted;
+
+        emit!(ChainStatusChanged {
+            chain_id,
+            halted,
+            by: authority,
+        });
+
+        msg!("Chain {} {}", chain_id, if halted { "halted" } else { "resumed" });
+        Ok(())
+    }
+
+    /**
+     * Create the wrapped mint for an ERC-20, with Metaplex metadata
+     *
+     * The mint is a PDA of the source token, minted by the bridge
+     * authority. Name and symbol come from the ERC-20 ("Wrapped <name>");
+     * the ERC-20's decimals are recorded next to the SPL decimals so
+     * amounts can be converted (SPL mints top out at 9 decimals or so).
+     */
+    pub fn create_wrapped_mint(
+        ctx: Context<CreateWrappedMint>,
+        source_token: [u8; 20],
+        source_decimals: u8,
+        decimals: u8,
+        source_name: String,
+        source_symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let data = wrapped_metadata(&source_name, &source_symbol, uri)?;
+        compute::reserve(compute::Step::Metadata)?;
+
+        let wrapped_asset = &mut ctx.accounts.wrapped_asset;
+        wrapped_asset.mint = ctx.accounts.wrapped_mint.key();
+        wrapped_asset.source_chain_id = chain_ids::ETHEREUM;
+        wrapped_asset.source_token = source_token;
+        wrapped_asset.source_decimals = source_decimals;
+        wrapped_asset.decimals = decimals;
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                mint_authority: ctx.accounts.bridge_authority.to_account_info(),
+                payer: ctx.accounts.owner.to_account_info(),
+                update_authority: ctx.accounts.bridge_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer,
+        );
+        token_metadata::create_metadata_accounts_v3(cpi_ctx, data, true, true, None)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::CreateWrappedMint)?;
+
+        emit!(WrappedMintCreated {
+            mint: ctx.accounts.wrapped_mint.key(),
+            source_token,
+            source_decimals,
+            decimals,
+        });
+
+        msg!("Wrapped mint {} created for {}", ctx.accounts.wrapped_mint.key(), source_symbol);
+        Ok(())
+    }
+
+    /**
+     * Correct a wrapped mint's metadata (e.g. after an ERC-20 rebrand)
+     */
+    pub fn update_wrapped_metadata(
+        ctx: Context<UpdateWrappedMetadata>,
+        source_name: String,
+        source_symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let data = wrapped_metadata(&source_name, &source_symbol, uri)?;
+        compute::reserve(compute::Step::Metadata)?;
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token_metadata::update_metadata_accounts_v2(cpi_ctx, None, Some(data), None, None)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::UpdateWrappedMetadata)?;
+
+        msg!("Metadata for {} updated", ctx.accounts.wrapped_asset.mint);
+        Ok(())
+    }
+
+    /**
+     * Apply an ERC-20's current name, symbol and decimals to its wrapped
+     * mint (anyone, with a guardian quorum)
+     *
+     * Relayers read the token at Ethereum block `eth_block` and submit
+     * what changed; the guardians signing as remaining accounts have
+     * each read it from their own node. The Metaplex URI is kept. A
+     * read no newer than the last one applied fails with
+     * StaleMetadataSync. `source_decimals` only corrects the record:
+     * the SPL mint's decimals are fixed, so it can't drop below them.
+     */
+    pub fn sync_metadata(
+        ctx: Context<SyncMetadata>,
+        source_name: String,
+        source_symbol: String,
+        source_decimals: u8,
+        eth_block: u64,
+    ) -> Result<()> {
+        require!(
+            GuardianSet::has_quorum(
+                &ctx.accounts.guardian_set.to_account_info(),
+                ctx.remaining_accounts,
+            )?,
+            ErrorCode::Unauthorized
+        );
+        let wrapped_asset = &mut ctx.accounts.wrapped_asset;
+        require!(source_decimals >= wrapped_asset.decimals, ErrorCode::InvalidConfig);
+
+        let sync = &mut ctx.accounts.metadata_sync;
+        require_ctx!(
+            eth_block > sync.eth_block,
+            ErrorCode::StaleMetadataSync,
+            eth_block = eth_block,
+            last = sync.eth_block
+        );
+        sync.mint = wrapped_asset.mint;
+        sync.eth_block = eth_block;
+        sync.synced_at = Clock::get()?.unix_timestamp;
+        wrapped_asset.source_decimals = source_decimals;
+
+        let current = MetadataAccount::try_deserialize(
+            &mut &ctx.accounts.metadata.try_borrow_data()?[..],
+        )?;
+        let uri = current.uri.trim_end_matches('\0').to_string();
+        let data = wrapped_metadata(&source_name, &source_symbol, uri)?;
+        let name = data.name.clone();
+        compute::reserve(compute::Step::Metadata)?;
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            UpdateMetadataAccountsV2 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                update_authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token_metadata::update_metadata_accounts_v2(cpi_ctx, None, Some(data), None, None)?;
+
+        emit!(MetadataSynced {
+            mint: wrapped_asset.mint,
+            source_token: wrapped_asset.source_token,
+            name,
+            symbol: source_symbol,
+            source_decimals,
+            eth_block,
+        });
+
+        msg!("Metadata for {} synced from Ethereum block {}", wrapped_asset.mint, eth_block);
+        Ok(())
+    }
+
+    /**
+     * Configure permissionless token listing (owner only)
+     *
+     * Listings post `bond` lamports and wait `veto_window` seconds for a
+     * guardian veto; at most `max_per_window` open per `rate_window`
+     * seconds. A bond of 0 turns permissionless listing off; the owner
+     * can always list with create_wrapped_mint.
+     */
+    pub fn set_listing_config(
+        ctx: Context<SetListingConfig>,
+        bond: u64,
+        veto_window: i64,
+        max_per_window: u16,
+        rate_window: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            bond == 0 || (veto_window > 0 && max_per_window > 0 && rate_window > 0),
+            ErrorCode::InvalidConfig
+        );
+
+        let config = &mut ctx.accounts.listing_config;
+        config.bond = bond;
+        config.veto_window = veto_window;
+        config.max_per_window = max_per_window;
+        config.rate_window = rate_window;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetListingConfig)?;
+
+        msg!(
+            "Token listing: {} lamport bond, {}s veto window, {} per {}s",
+            bond,
+            veto_window,
+            max_per_window,
+            rate_window
+        );
+        Ok(())
+    }
+
+    /**
+     * List an ERC-20 for bridging by posting the listing bond (anyone)
+     *
+     * Takes what create_wrapped_mint would. Nothing is created yet: the
+     * listing waits out the veto window, during which guardians check the
+     * token and its decimals, and is then activated with activate_listing.
+     */
+    pub fn list_token(
+        ctx: Context<ListToken>,
+        source_token: [u8; 20],
+        source_decimals: u8,
+        decimals: u8,
+        source_name: String,
+        source_symbol: String,
+        uri: String,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        let bond = ctx.accounts.listing_config.bond;
+        require!(bond > 0, ErrorCode::InvalidConfig);
+        require!(decimals <= source_decimals, ErrorCode::InvalidConfig);
+        // Checked now, so activation can't fail on it later
+        wrapped_metadata(&source_name, &source_symbol, uri.clone())?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let config = &mut ctx.accounts.listing_config;
+        config.open_listing(now)?;
+        let activates_at = math::add_seconds(now, config.veto_window)?;
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.lister.to_account_info(),
+                to: ctx.accounts.listing.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, bond)?;
+
+        let listing = &mut ctx.accounts.listing;
+        listing.lister = ctx.accounts.lister.key();
+        listing.source_token = source_token;
+        listing.source_decimals = source_decimals;
+        listing.decimals = decimals;
+        listing.source_name = source_name;
+        listing.source_symbol = source_symbol;
+        listing.uri = uri;
+        listing.bond = bond;
+        listing.listed_at = now;
+        listing.activates_at = activates_at;
+
+        emit!(TokenListed {
+            lister: listing.lister,
+            source_token,
+            source_decimals,
+            decimals,
+            bond,
+            activates_at,
+        });
+
+        msg!("{} listed with a {} lamport bond", listing.source_symbol, bond);
+        Ok(())
+    }
+
+    /**
+     * Veto a token listing before it is activated (guardians only)
+     *
+     * The bond goes to the insurance fund; the listing closes, its rent
+     * going back to the lister. Guardian signers are passed as remaining
+     * accounts.
+     */
+    pub fn veto_listing(ctx: Context<VetoListing>) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+
+        // The listing account holds the bond, over its rent
+        let bond = ctx.accounts.listing.bond;
+        let listing = ctx.accounts.listing.to_account_info();
+        **listing.try_borrow_mut_lamports()? = math::sub(listing.lamports(), bond)?;
+        let fund = ctx.accounts.insurance_fund.to_account_info();
+        **fund.try_borrow_mut_lamports()? = math::add(fund.lamports(), bond)?;
+
+        emit!(TokenListingVetoed {
+            lister: ctx.accounts.listing.lister,
+            source_token: ctx.accounts.listing.source_token,
+            bond,
+        });
+
+        msg!("Listing of {} vetoed", ctx.accounts.listing.source_symbol);
+        Ok(())
+    }
+
+    /**
+     * Create the wrapped mint of a listing past its veto window (anyone)
+     *
+     * Same mint, WrappedAsset and metadata as create_wrapped_mint, plus
+     * the mint's TokenConfig as register_token would make it, so the
+     * token bridges right away; the payer covers their rent. The listing
+     * closes and the lister gets the bond back.
+     */
+    pub fn activate_listing(ctx: Context<ActivateListing>) -> Result<()> {
+        let listing = &ctx.accounts.listing;
+        require!(
+            Clock::get()?.unix_timestamp >= listing.activates_at,
+            ErrorCode::ListingNotReady
+        );
+
+        let data =
+            wrapped_metadata(&listing.source_name, &listing.source_symbol, listing.uri.clone())?;
+        compute::reserve(compute::Step::Metadata)?;
+
+        let wrapped_asset = &mut ctx.accounts.wrapped_asset;
+        wrapped_asset.mint = ctx.accounts.wrapped_mint.key();
+        wrapped_asset.source_chain_id = chain_ids::ETHEREUM;
+        wrapped_asset.source_token = listing.source_token;
+        wrapped_asset.source_decimals = listing.source_decimals;
+        wrapped_asset.decimals = listing.decimals;
+        ctx.accounts.token_config.mint = ctx.accounts.wrapped_mint.key();
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_metadata_program.to_account_info(),
+            CreateMetadataAccountsV3 {
+                metadata: ctx.accounts.metadata.to_account_info(),
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                mint_authority: ctx.accounts.bridge_authority.to_account_info(),
+                payer: ctx.accounts.payer.to_account_info(),
+                update_authority: ctx.accounts.bridge_authority.to_account_info(),
+                system_program: ctx.accounts.system_program.to_account_info(),
+                rent: ctx.accounts.rent.to_account_info(),
+            },
+            signer,
+        );
+        token_metadata::create_metadata_accounts_v3(cpi_ctx, data, true, true, None)?;
+
+        emit!(WrappedMintCreated {
+            mint: ctx.accounts.wrapped_mint.key(),
+            source_token: listing.source_token,
+            source_decimals: listing.source_decimals,
+            decimals: listing.decimals,
+        });
+        emit!(TokenListingActivated {
+            lister: listing.lister,
+            source_token: listing.source_token,
+            mint: ctx.accounts.wrapped_mint.key(),
+            bond: listing.bond,
+        });
+
+        msg!(
+            "Wrapped mint {} created for listed {}",
+            ctx.accounts.wrapped_mint.key(),
+            listing.source_symbol
+        );
+        Ok(())
+    }
+
+    /**
+     * Freeze a wrapped token account (e.g. tokens stolen in an exploit)
+     *
+     * The bridge authority is the freeze authority of every wrapped mint
+     * it creates; the account stays frozen until governance decides what
+     * happens to it and thaw_wrapped_account is called.
+     */
+    pub fn freeze_wrapped_account(ctx: Context<FreezeWrappedAccount>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::FreezeAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::freeze_account(cpi_ctx)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::FreezeWrappedAccount)?;
+
+        msg!("Wrapped token account {} frozen", ctx.accounts.token_account.key());
+        Ok(())
+    }
+
+    /**
+     * Thaw a wrapped token account frozen with freeze_wrapped_account
+     */
+    pub fn thaw_wrapped_account(ctx: Context<FreezeWrappedAccount>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.token_program.to_account_info(),
+            token::ThawAccount {
+                account: ctx.accounts.token_account.to_account_info(),
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+            },
+            signer,
+        );
+        token::thaw_account(cpi_ctx)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ThawWrappedAccount)?;
+
+        msg!("Wrapped token account {} thawed", ctx.accounts.token_account.key());
+        Ok(())
+    }
+
+    /**
+     * Allowlist Token-2022 extensions for a token
+     *
+     * `allowed` is a set of token_extensions flags. Lock, unlock and fee
+     * withdrawals reject a mint carrying any extension outside it.
+     */
+    pub fn set_allowed_extensions(ctx: Context<UpdateToken>, allowed: u16) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(allowed & !token_extensions::ALL == 0, ErrorCode::InvalidConfig);
+
+        ctx.accounts.token_config.allowed_extensions = allowed;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetAllowedExtensions)?;
+
+        msg!(
+            "Token {} allowed extensions: {:#06x}",
+            ctx.accounts.token_config.mint,
+            allowed
+        );
+        Ok(())
+    }
+
+    /**
+     * Choose raw or share accounting for a token
+     *
+     * Switching to Shares creates the token's ConversionRate account at
+     * 1:1; `oracle` then posts the real rate with update_conversion_rate.
+     * Rates older than `max_age` seconds block lock and unlock.
+     */
+    pub fn set_token_accounting(
+        ctx: Context<SetTokenAccounting>,
+        accounting: TokenAccounting,
+        oracle: Pubkey,
+        max_age: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(max_age > 0, ErrorCode::InvalidConfig);
+
+        let conversion_rate = &mut ctx.accounts.conversion_rate;
+        if conversion_rate.rate == 0 {
+            conversion_rate.mint = ctx.accounts.token_config.mint;
+            conversion_rate.rate = RATE_SCALE;
+            conversion_rate.updated_at = Clock::get()?.unix_timestamp;
+        }
+        conversion_rate.oracle = oracle;
+        conversion_rate.max_age = max_age;
+
+        ctx.accounts.token_config.accounting = accounting;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetTokenAccounting)?;
+
+        msg!("Token {} accounting updated", ctx.accounts.token_config.mint);
+        Ok(())
+    }
+
+    /**
+     * Post the token amount per share for a share-accounted token
+     */
+    pub fn update_conversion_rate(ctx: Context<UpdateConversionRate>, rate: u64) -> Result<()> {
+        require!(
+            ctx.accounts.oracle.key() == ctx.accounts.conversion_rate.oracle,
+            ErrorCode::Unauthorized
+        );
+        require!(rate > 0, ErrorCode::InvalidConversionRate);
+
+        let conversion_rate = &mut ctx.accounts.conversion_rate;
+        conversion_rate.rate = rate;
+        conversion_rate.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(ConversionRateUpdated {
+            mint: conversion_rate.mint,
+            rate,
+            timestamp: conversion_rate.updated_at,
+        });
+
+        msg!("Conversion rate for {}: {}", conversion_rate.mint, rate);
+        Ok(())
+    }
+
+    /**
+     * Set up (or reset) the inbox for a source chain
+     *
+     * From then on inbound nonces from that chain must be delivered in
+     * order starting at `next_sequence`, or up to `window` ahead of the
+     * oldest undelivered one (0 = strictly in order).
+     */
+    pub fn configure_inbox(
+        ctx: Context<ConfigureInbox>,
+        chain_id: u64,
+        next_sequence: u64,
+        window: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!((window as u32) < u128::BITS, ErrorCode::InvalidConfig);
+
+        let inbox = &mut ctx.accounts.inbox;
+        inbox.chain_id = chain_id;
+        inbox.next_sequence = next_sequence;
+        inbox.window = window;
+        inbox.delivered = 0;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ConfigureInbox)?;
+
+        msg!("Inbox for chain {} starts at {} (window {})", chain_id, next_sequence, window);
+        Ok(())
+    }
+
+    /**
+     * Set the outbound throttle's thresholds (owner only)
+     *
+     * `pending` is the depth to start from: the outbound transfers still
+     * unacknowledged, counted off-chain (the indexer's initiated and
+     * attested ones). 0 for `surcharge_at` or `reject_at` turns that
+     * stage off. Calling it again resets the depth too.
+     */
+    pub fn configure_throttle(
+        ctx: Context<ConfigureThrottle>,
+        pending: u64,
+        surcharge_at: u64,
+        surcharge_bps: u16,
+        reject_at: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(surcharge_bps <= 10_000, ErrorCode::InvalidConfig);
+        require!(
+            surcharge_at == 0 || reject_at == 0 || surcharge_at < reject_at,
+            ErrorCode::InvalidConfig
+        );
+
+        let throttle = &mut ctx.accounts.throttle;
+        throttle.pending = pending;
+        throttle.surcharge_at = surcharge_at;
+        throttle.surcharge_bps = surcharge_bps;
+        throttle.reject_at = reject_at;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ConfigureThrottle)?;
+
+        msg!(
+            "Throttle: {} pending, +{} bps from {}, rejecting past {}",
+            pending,
+            surcharge_bps,
+            surcharge_at,
+            reject_at
+        );
+        Ok(())
+    }
+
+    /**
+     * Set how priority tips are split (owner only)
+     *
+     * `relayer_share_bps` of each tip goes to the relayer reward vault,
+     * the rest to `treasury`. Bids are only taken once this is set.
+     */
+    pub fn configure_priority_tips(
+        ctx: Context<ConfigurePriorityTips>,
+        relayer_share_bps: u16,
+        treasury: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(relayer_share_bps <= 10_000, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.priority_config;
+        config.relayer_share_bps = relayer_share_bps;
+        config.treasury = treasury;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ConfigurePriorityTips)?;
+
+        msg!("Priority tips: {} bps to relayers, rest to {}", relayer_share_bps, treasury);
+        Ok(())
+    }
+
+    /**
+     * Tip to have a queued outbound transfer relayed sooner (sender only)
+     *
+     * Only while the throttle says the queue is congested, and only
+     * before the transfer is attested. `tip` lamports are added to the
+     * transfer's bid; relayers work through the outbox by bid, highest
+     * first (see PriorityBid).
+     */
+    pub fn bid_priority(ctx: Context<BidPriority>, nonce: u64, tip: u64) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(tip > 0, ErrorCode::AmountTooSmall);
+
+        let record = &ctx.accounts.transfer_record;
+        require_keys_eq!(record.local_account, ctx.accounts.sender.key(), ErrorCode::Unauthorized);
+        require!(
+            record.status == TransferStatus::Initiated,
+            ErrorCode::InvalidTransferStatus
+        );
+        require!(
+            Throttle::is_congested(&ctx.accounts.throttle)?,
+            ErrorCode::NotCongested
+        );
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.sender.to_account_info(),
+                to: ctx.accounts.bid.to_account_info(),
+            },
+        );
+        system_program::transfer(cpi_ctx, tip)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let bid = &mut ctx.accounts.bid;
+        bid.nonce = nonce;
+        bid.sender = ctx.accounts.sender.key();
+        bid.tip = math::add(bid.tip, tip)?;
+        bid.updated_at = now;
+
+        emit!(PriorityBidPlaced {
+            nonce,
+            sender: bid.sender,
+            tip: bid.tip,
+            timestamp: now,
+        });
+
+        msg!("Priority bid on transfer {} raised to {} lamports", nonce, bid.tip);
+        Ok(())
+    }
+
+    /**
+     * Pay out a priority bid once its transfer is settled (anyone)
+     *
+     * Attested or delivered: the tip is split between the relayer reward
+     * vault and the treasury. Refunded or cancelled: it goes back to the
+     * sender. Either way the bid closes, its rent going to the sender.
+     */
+    pub fn collect_priority_tip(ctx: Context<CollectPriorityTip>, nonce: u64) -> Result<()> {
+        let tip = ctx.accounts.bid.tip;
+        let (relayer_share, treasury_share) = match ctx.accounts.transfer_record.status {
+            TransferStatus::Attested | TransferStatus::Completed => {
+                let relayer_share =
+                    math::bps_of(tip, ctx.accounts.priority_config.relayer_share_bps)?;
+                (relayer_share, math::sub(tip, relayer_share)?)
+            }
+            TransferStatus::Refunded | TransferStatus::Cancelled | TransferStatus::Vetoed => (0, 0),
+            _ => return err!(ErrorCode::InvalidTransferStatus),
+        };
+
+        // The bid account holds the tip; `close` sends what's left (the
+        // rent, and for a refund the tip) to the sender
+        let bid = ctx.accounts.bid.to_account_info();
+        let paid_out = math::add(relayer_share, treasury_share)?;
+        **bid.try_borrow_mut_lamports()? = math::sub(bid.lamports(), paid_out)?;
+        let vault = ctx.accounts.reward_vault.to_account_info();
+        **vault.try_borrow_mut_lamports()? = math::add(vault.lamports(), relayer_share)?;
+        let treasury = ctx.accounts.treasury.to_account_info();
+        **treasury.try_borrow_mut_lamports()? = math::add(treasury.lamports(), treasury_share)?;
+
+        emit!(PriorityTipCollected {
+            nonce,
+            relayer_share,
+            treasury_share,
+            refunded: math::sub(tip, paid_out)?,
+        });
+
+        msg!(
+            "Priority tip on transfer {}: {} to relayers, {} to treasury",
+            nonce,
+            relayer_share,
+            treasury_share
+        );
+        Ok(())
+    }
+
+    /**
+     * Cancel a transfer still waiting in a delay (guardians only)
+     *
+     * Inbound: an attestation in its challenge window (or vetoed) is
+     * dropped, so nothing leaves escrow; the relayer gets its bond back,
+     * since a cancellation is not a fraud finding. Outbound: a transfer
+     * the relayer hasn't completed is refunded to the sender, out of
+     * escrow for locks or re-minted for burns.
+     *
+     * Pass the accounts for the transfer's direction, `None` for the rest.
+     * Guardian signers are passed as remaining accounts.
+     */
+    pub fn cancel_pending(
+        ctx: Context<CancelPending>,
+        transfer_id: [u8; 32],
+        reason: String,
+    ) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+        require!(reason.len() <= MAX_CANCEL_REASON_LEN, ErrorCode::ReasonTooLong);
+
+        let record = &ctx.accounts.transfer_record;
+        require!(record.transfer_id == transfer_id, ErrorCode::TransferMismatch);
+        let nonce_bytes = record.nonce.to_le_bytes();
+
+        let amount = match record.direction {
+            TransferDirection::Inbound => {
+                let attestation = ctx
+                    .accounts
+                    .attestation
+                    .as_mut()
+                    .ok_or(ErrorCode::TransferMismatch)?;
+                require!(
+                    matches!(
+                        attestation.status,
+                        AttestationStatus::Pending | AttestationStatus::Vetoed
+                    ),
+                    ErrorCode::InvalidAttestationStatus
+                );
+                attestation.status = AttestationStatus::Cancelled;
+
+                let relayer = ctx.accounts.relayer.as_ref().ok_or(ErrorCode::TransferMismatch)?;
+                let bond_vault = ctx.accounts.bond_vault.as_ref().ok_or(ErrorCode::TransferMismatch)?;
+                require_keys_eq!(relayer.key(), attestation.relayer, ErrorCode::TransferMismatch);
+
+                let instance = instance::seed(&ctx.accounts.bridge_state.instance_id);
+                let (_, vault_bump) = Pubkey::find_program_address(
+                    &[b"bond_vault", instance, &nonce_bytes],
+                    &crate::ID,
+                );
+                let signer: &[&[&[u8]]] = &[&[
+                    b"bond_vault",
+                    instance,
+                    &nonce_bytes,
+                    &[vault_bump],
+                ]];
+                let cpi_ctx = CpiContext::new_with_signer(
+                    ctx.accounts.system_program.to_account_info(),
+                    system_program::Transfer {
+                        from: bond_vault.to_account_info(),
+                        to: relayer.to_account_info(),
+                    },
+                    signer,
+                );
+                system_program::transfer(cpi_ctx, attestation.relayer_bond)?;
+
+                attestation.amount
+            }
+            TransferDirection::Outbound => {
+                require!(
+                    record.status == TransferStatus::Initiated,
+                    ErrorCode::InvalidTransferStatus
+                );
+
+                let (Some(mint), Some(token_config), Some(refund_token), Some(token_program)) = (
+                    ctx.accounts.mint.as_ref(),
+                    ctx.accounts.token_config.as_ref(),
+                    ctx.accounts.refund_token.as_ref(),
+                    ctx.accounts.token_program.as_ref(),
+                ) else {
+                    return err!(ErrorCode::TransferMismatch);
+                };
+                require_keys_eq!(mint.key(), record.mint, ErrorCode::TransferMismatch);
+                require_keys_eq!(refund_token.mint, record.mint, ErrorCode::TransferMismatch);
+                require_keys_eq!(
+                    refund_token.owner,
+                    record.refund_owner(),
+                    ErrorCode::TransferMismatch
+                );
+
+                let seeds = &[
+                    b"bridge".as_ref(),
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    &[ctx.bumps.bridge_authority],
+                ];
+                let signer = &[&seeds[..]];
+
+                if record.receipt {
+                    receipt::retire(
+                        instance::seed(&ctx.accounts.bridge_state.instance_id),
+                        record.nonce,
+                        &record.local_account,
+                        ctx.accounts.receipt_mint.as_ref().map(|a| a.to_account_info()),
+                        ctx.accounts.receipt_token.as_ref().map(|a| a.to_account_info()),
+                        ctx.accounts.token_2022_program.as_ref().map(|a| a.to_account_info()),
+                        ctx.accounts.bridge_authority.to_account_info(),
+                        signer,
+                    )?;
+                }
+
+                if record.escrowed {
+                    let bridge_token = ctx
+                        .accounts
+                        .bridge_token
+                        .as_ref()
+                        .ok_or(ErrorCode::TransferMismatch)?;
+                    require_keys_eq!(
+                        bridge_token.owner,
+                        ctx.accounts.bridge_authority.key(),
+                        ErrorCode::TransferMismatch
+                    );
+
+                    let token_amount = token_config
+                        .from_bridged(ctx.accounts.conversion_rate.as_deref(), record.amount)?;
+                    let extensions = token_ext::inspect(
+                        &mint.to_account_info(),
+                        token_config.allowed_extensions,
+                    )?;
+                    TokenTransfer {
+                        token_program: token_program.to_account_info(),
+                        from: bridge_token.to_account_info(),
+                        mint: mint.to_account_info(),
+                        to: refund_token.to_account_info(),
+                        authority: ctx.accounts.bridge_authority.to_account_info(),
+                        extra_accounts: &[],
+                    }
+                    .execute(&extensions, token_amount, mint.decimals, signer)?
+                } else {
+                    let cpi_ctx = CpiContext::new_with_signer(
+                        token_program.to_account_info(),
+                        token_interface::MintTo {
+                            mint: mint.to_account_info(),
+                            to: refund_token.to_account_info(),
+                            authority: ctx.accounts.bridge_authority.to_account_info(),
+                        },
+                        signer,
+                    );
+                    token_interface::mint_to(cpi_ctx, record.amount)?;
+                    record.amount
+                }
+            }
+        };
+
+        let record = &mut ctx.accounts.transfer_record;
+        record.set_status(TransferStatus::Cancelled)?;
+        if record.direction == TransferDirection::Outbound {
+            Throttle::release(&ctx.accounts.throttle)?;
+        }
+
+        emit!(TransferCancelled {
+            transfer_id,
+            nonce: record.nonce,
+            direction: record.direction,
+            amount,
+            reason: reason.clone(),
+        });
+
+        msg!(
+            "Transfer {} cancelled by guardians: {}",
+            hex_encode(&transfer_id),
+            reason
+        );
+        Ok(())
+    }
+
+    /**
+     * Attest an outbound transfer before delivering it (owner/relayer)
+     *
+     * Moves the record from Initiated to Attested, which closes off
+     * `reclaim_expired`. Relayers call this for transfers with a
+     * deadline before releasing funds on the destination; once the
+     * deadline has passed it fails, and the transfer is left for the
+     * sender to reclaim. Guardian signers in the remaining accounts can
+     * stand in for the owner, as for mint. A deposit receipt is burned
+     * here, since the position is no longer pending.
+     */
+    pub fn attest_outbound(ctx: Context<AttestOutbound>, nonce: u64) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let record = &mut ctx.accounts.transfer_record;
+        require!(
+            record.status == TransferStatus::Initiated,
+            ErrorCode::InvalidTransferStatus
+        );
+        let now = Clock::get()?.unix_timestamp;
+        require!(!record.is_expired(now), ErrorCode::DeadlinePassed);
+
+        if record.receipt {
+            receipt::retire(
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                nonce,
+                &record.local_account,
+                ctx.accounts.receipt_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.receipt_token.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.token_2022_program.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.bridge_authority.to_account_info(),
+                &[&[
+                    b"bridge",
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    &[ctx.accounts.bridge_state.authority_bump],
+                ]],
+            )?;
+        }
+
+        record.set_status(TransferStatus::Attested)?;
+
+        msg!("Outbound transfer {} attested", nonce);
+        Ok(())
+    }
+
+    /**
+     * Acknowledge an outbound transfer's delivery (owner/relayer)
+     *
+     * The relayer sends this back once the destination has released the
+     * funds, moving the record from Initiated or Attested to Completed:
+     * the sender sees on Solana that the transfer arrived, and it can no
+     * longer be reclaimed, deadline or not. Guardian signers can stand
+     * in for the owner, as for attest_outbound, and a deposit receipt
+     * still outstanding is burned. A transfer is acknowledged once; a
+     * repeat fails with InvalidTransferStatus. `dest_tx` is the hash of
+     * the delivering transaction, for TransferAcknowledged.
+     */
+    pub fn ack_outbound(
+        ctx: Context<AttestOutbound>,
+        nonce: u64,
+        dest_tx: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let record = &mut ctx.accounts.transfer_record;
+        require!(
+            matches!(record.status, TransferStatus::Initiated | TransferStatus::Attested),
+            ErrorCode::InvalidTransferStatus
+        );
+
+        // Attesting burned it already
+        if record.receipt && record.status == TransferStatus::Initiated {
+            receipt::retire(
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                nonce,
+                &record.local_account,
+                ctx.accounts.receipt_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.receipt_token.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.token_2022_program.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.bridge_authority.to_account_info(),
+                &[&[
+                    b"bridge",
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    &[ctx.accounts.bridge_state.authority_bump],
+                ]],
+            )?;
+        }
+
+        record.mark_processed(ctx.accounts.authority.key())?;
+        Throttle::release(&ctx.accounts.throttle)?;
+
+        emit!(TransferAcknowledged {
+            transfer_id: record.transfer_id,
+            nonce,
+            dest_chain_id: record.remote_chain_id,
+            dest_tx,
+            acknowledged_by: ctx.accounts.authority.key(),
+        });
+
+        let rebate = record.credit_sla_rebate(&ctx.accounts.sla_config)?;
+        if rebate > 0 {
+            emit!(SlaRebateCredited {
+                transfer_id: record.transfer_id,
+                nonce,
+                sender: record.local_account,
+                mint: record.mint,
+                latency_slots: record.latency_slots(),
+                rebate,
+            });
+        }
+
+        msg!("Outbound transfer {} acknowledged", nonce);
+        Ok(())
+    }
+
+    /**
+     * Settle a batch leg by leg once its deliveries are known (owner/relayer)
+     *
+     * For lock_batch and other multi-recipient transfers where only some
+     * legs fail on the destination (a blacklisted recipient, a reverted
+     * mint): delivered legs are acknowledged as with `ack_outbound`, and
+     * only the failed ones go back to the sender out of escrow, in one
+     * transfer, ending as Refunded. Each leg must be a lock of `mint`
+     * refunded to the refund account's owner (the sender or the lock's
+     * recovery address), still Initiated or Attested and
+     * without a deposit receipt. Assets settle separately (one call per
+     * mint), and legs left out stay pending.
+     */
+    pub fn settle_batch<'info>(ctx: Context<'_, '_, '_, 'info, SettleBatch<'info>>, outcomes: Vec<LegOutcome>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !outcomes.is_empty() && outcomes.len() <= MAX_LOCK_BATCH,
+            ErrorCode::InvalidBatch
+        );
+        require!(ctx.remaining_accounts.len() >= outcomes.len(), ErrorCode::InvalidBatch);
+        let (records, hook_accounts) = ctx.remaining_accounts.split_at(outcomes.len());
+
+        let authority = ctx.accounts.authority.key();
+        let mint = ctx.accounts.mint.key();
+        let sender = ctx.accounts.refund_token.owner;
+        let token_config = &ctx.accounts.token_config;
+        let mut refund = 0;
+        let mut refunded = 0;
+
+        for (outcome, record_info) in outcomes.iter().zip(records) {
+            let (address, _) = Pubkey::find_program_address(
+                &[
+                    b"transfer",
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    b"out",
+                    &outcome.nonce.to_le_bytes(),
+                ],
+                &crate::ID,
+            );
+            require_keys_eq!(record_info.key(), address, ErrorCode::InvalidBatch);
+            require_keys_eq!(*record_info.owner, crate::ID, ErrorCode::InvalidBatch);
+
+            // Saved before the next leg loads, so a repeated leg fails here
+            let mut record =
+                TransferRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+            require!(
+                record.mint == mint
+                    && record.refund_owner() == sender
+                    && record.escrowed
+                    && !record.receipt,
+                ErrorCode::TransferMismatch
+            );
+            require!(
+                matches!(record.status, TransferStatus::Initiated | TransferStatus::Attested),
+                ErrorCode::InvalidTransferStatus
+            );
+
+            if outcome.delivered {
+                record.mark_processed(authority)?;
+                emit!(TransferAcknowledged {
+                    transfer_id: record.transfer_id,
+                    nonce: outcome.nonce,
+                    dest_chain_id: record.remote_chain_id,
+                    dest_tx: outcome.dest_tx,
+                    acknowledged_by: authority,
+                });
+                let rebate = record.credit_sla_rebate(&ctx.accounts.sla_config)?;
+                if rebate > 0 {
+                    emit!(SlaRebateCredited {
+                        transfer_id: record.transfer_id,
+                        nonce: outcome.nonce,
+                        sender: record.local_account,
+                        mint,
+                        latency_slots: record.latency_slots(),
+                        rebate,
+                    });
+                }
+            } else {
+                let amount = token_config
+                    .from_bridged(ctx.accounts.conversion_rate.as_deref(), record.amount)?;
+                refund = math::add(refund, amount)?;
+                refunded += 1;
+                record.set_status(TransferStatus::Refunded)?;
+                emit!(TransferRefunded {
+                    transfer_id: record.transfer_id,
+                    nonce: outcome.nonce,
+                    dest_chain_id: record.remote_chain_id,
+                    sender,
+                    amount,
+                    mint,
+                });
+            }
+            record.save(record_info)?;
+            Throttle::release(&ctx.accounts.throttle)?;
+        }
+
+        if refund > 0 {
+            let extensions = token_ext::inspect(
+                &ctx.accounts.mint.to_account_info(),
+                token_config.allowed_extensions,
+            )?;
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.bridge_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.refund_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+                extra_accounts: hook_accounts,
+            }
+            .execute(
+                &extensions,
+                refund,
+                ctx.accounts.mint.decimals,
+                &[&[
+                    b"bridge",
+                    instance::seed(&ctx.accounts.bridge_state.instance_id),
+                    &[ctx.accounts.bridge_state.authority_bump],
+                ]],
+            )?;
+        }
+
+        msg!(
+            "Settled {} legs for {}: {} refunded ({} tokens)",
+            outcomes.len(),
+            sender,
+            refunded,
+            refund
+        );
+        Ok(())
+    }
+
+    /**
+     * Take back an outbound transfer whose deadline passed unattested
+     *
+     * Only the sender can reclaim, into their own token account or the
+     * recovery address's if the lock named one; a recovery address (or
+     * whoever bought the refund, see `assign_refund`) can reclaim too.
+     * Locks are paid back out
+     * of escrow, burns are minted back, and the record ends as Refunded.
+     * `bridge_token` is only needed for locks, `conversion_rate` only
+     * for share-accounted tokens, the receipt accounts only if the
+     * sender took a deposit receipt (it's burned).
+     */
+    pub fn reclaim_expired<'info>(ctx: Context<'_, '_, '_, 'info, ReclaimExpired<'info>>, nonce: u64) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+
+        let record = &ctx.accounts.transfer_record;
+        let sender = ctx.accounts.sender.key();
+        require!(
+            sender == record.local_account
+                || (record.recovery != Pubkey::default() && sender == record.recovery),
+            ErrorCode::Unauthorized
+        );
+        require_keys_eq!(ctx.accounts.mint.key(), record.mint, ErrorCode::TransferMismatch);
+        require_keys_eq!(
+            ctx.accounts.refund_token.owner,
+            record.refund_owner(),
+            ErrorCode::TransferMismatch
+        );
+        require!(
+            record.is_expired(Clock::get()?.unix_timestamp),
+            ErrorCode::NotExpired
+        );
+
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.bridge_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        if record.receipt {
+            receipt::retire(
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                nonce,
+                &record.local_account,
+                ctx.accounts.receipt_mint.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.receipt_token.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.token_2022_program.as_ref().map(|a| a.to_account_info()),
+                ctx.accounts.bridge_authority.to_account_info(),
+                signer,
+            )?;
+        }
+
+        let mint = &ctx.accounts.mint;
+        let amount = if record.escrowed {
+            let bridge_token = ctx
+                .accounts
+                .bridge_token
+                .as_ref()
+                .ok_or(ErrorCode::TransferMismatch)?;
+            require_keys_eq!(
+                bridge_token.owner,
+                ctx.accounts.bridge_authority.key(),
+                ErrorCode::TransferMismatch
+            );
+
+            let token_config = &ctx.accounts.token_config;
+            let token_amount = token_config
+                .from_bridged(ctx.accounts.conversion_rate.as_deref(), record.amount)?;
+            let extensions =
+                token_ext::inspect(&mint.to_account_info(), token_config.allowed_extensions)?;
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: bridge_token.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.refund_token.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, token_amount, mint.decimals, signer)?
+        } else {
+            let cpi_ctx = CpiContext::new_with_signer(
+                ctx.accounts.token_program.to_account_info(),
+                token_interface::MintTo {
+                    mint: mint.to_account_info(),
+                    to: ctx.accounts.refund_token.to_account_info(),
+                    authority: ctx.accounts.bridge_authority.to_account_info(),
+                },
+                signer,
+            );
+            token_interface::mint_to(cpi_ctx, record.amount)?;
+            record.amount
+        };
+
+        let record = &mut ctx.accounts.transfer_record;
+        record.set_status(TransferStatus::Refunded)?;
+        Throttle::release(&ctx.accounts.throttle)?;
+
+        emit!(TransferReclaimed {
+            transfer_id: record.transfer_id,
+            nonce,
+            sender: record.local_account,
+            amount,
+            mint: record.mint,
+        });
+
+        msg!("Outbound transfer {} reclaimed by {}", nonce, sender);
+        Ok(())
+    }
+
+    /**
+     * Buy the refund right of a stuck outbound transfer
+     *
+     * The refund owner signs a `RefundAssignment` (see refund_assign.rs)
+     * in an Ed25519 instruction right before this one. The assignee pays
+     * its price to the holder's token account and becomes the refund
+     * owner: reclaim_expired, cancellations and batch refunds pay them
+     * from then on. Only transfers with a deadline qualify, and not ones
+     * with a deposit receipt, which is already a tradable claim.
+     */
+    pub fn assign_refund<'info>(ctx: Context<'_, '_, '_, 'info, AssignRefund<'info>>, nonce: u64) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+
+        let record = &ctx.accounts.transfer_record;
+        require!(
+            record.status == TransferStatus::Initiated && record.deadline != 0 && !record.receipt,
+            ErrorCode::InvalidTransferStatus
+        );
+
+        let holder = record.refund_owner();
+        let assignee = ctx.accounts.assignee.key();
+        require_keys_neq!(assignee, holder, ErrorCode::InvalidRefundAssignment);
+        let assignment = refund_assign::load(&ctx.accounts.instructions, &holder)?;
+        assignment.check(&record.transfer_id, &assignee, Clock::get()?.unix_timestamp)?;
+        require_keys_eq!(ctx.accounts.holder_token.owner, holder, ErrorCode::TransferMismatch);
+
+        if assignment.price > 0 {
+            let mint = &ctx.accounts.mint;
+            let extensions = token_ext::inspect(
+                &mint.to_account_info(),
+                ctx.accounts.token_config.allowed_extensions,
+            )?;
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.assignee_token.to_account_info(),
+                mint: mint.to_account_info(),
+                to: ctx.accounts.holder_token.to_account_info(),
+                authority: ctx.accounts.assignee.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, assignment.price, mint.decimals, &[])?;
+        }
+
+        let record = &mut ctx.accounts.transfer_record;
+        record.recovery = assignee;
+        record.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RefundAssigned {
+            transfer_id: record.transfer_id,
+            nonce,
+            from: holder,
+            to: assignee,
+            price: assignment.price,
+        });
+
+        msg!("Refund of outbound transfer {} assigned to {}", nonce, assignee);
+        Ok(())
+    }
+
+    /**
+     * Pause a single token
+     *
+     * Stops lock, unlock, mint and burn for this token only, e.g. when
+     * a wrapped asset depegs, without halting the rest of the bridge.
+     */
+    pub fn pause_token(ctx: Context<UpdateToken>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.token_config.paused = true;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::PauseToken)?;
+
+        msg!("Token {} paused", ctx.accounts.token_config.mint);
+        Ok(())
+    }
+
+    /**
+     * Unpause a single token
+     */
+    pub fn unpause_token(ctx: Context<UpdateToken>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.token_config.paused = false;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::UnpauseToken)?;
+
+        msg!("Token {} unpaused", ctx.accounts.token_config.mint);
+        Ok(())
+    }
+
+    /**
+     * Set the decimals a token is carried in elsewhere, and its dust policy
+     *
+     * A lock's amount below `bridged_decimals` can't be delivered (e.g.
+     * a 9-decimal token to Stellar's 7); `policy` refunds it, takes it
+     * into the fee vault or rejects the lock (see DustPolicy). Either
+     * way the dust is reported in a DustHandled event. `None` carries
+     * the mint's own decimals, so nothing is dust.
+     */
+    pub fn set_dust_policy(
+        ctx: Context<UpdateToken>,
+        bridged_decimals: Option<u8>,
+        policy: DustPolicy,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let token_config = &mut ctx.accounts.token_config;
+        token_config.bridged_decimals = bridged_decimals;
+        token_config.dust_policy = policy;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetDustPolicy)?;
+
+        msg!(
+            "Token {} bridged decimals: {:?}, dust: {:?}",
+            token_config.mint,
+            bridged_decimals,
+            policy
+        );
+        Ok(())
+    }
+
+    /**
+     * Choose what vouches for a token's inbound transfers (see verifiers)
+     *
+     * Takes effect for the next delivery; transfers already attested
+     * under the Optimistic model can't be executed once it changes.
+     */
+    pub fn set_trust_model(ctx: Context<UpdateToken>, model: TrustModel) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(model.available(), ErrorCode::TrustModelUnavailable);
+
+        ctx.accounts.token_config.trust_model = model;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetTrustModel)?;
+
+        msg!("Token {} trust model: {:?}", ctx.accounts.token_config.mint, model);
+        Ok(())
+    }
+
+    /**
+     * Create the guardian council (one time)
+     *
+     * A last-resort kill switch held by keys other than the owner's: the
+     * council can halt mint and unlock, and only the council can clear
+     * the halt or change its own members. The owner can't touch it once
+     * it exists, so a stolen owner key can't turn it off.
+     */
+    pub fn create_guardian_council(
+        ctx: Context<CreateGuardianCouncil>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        GuardianCouncil::validate(&members, threshold)?;
+        require!(
+            !members.contains(&ctx.accounts.bridge_state.owner),
+            ErrorCode::InvalidGuardianSet
+        );
+
+        let council = &mut ctx.accounts.guardian_council;
+        council.members = members;
+        council.threshold = threshold;
+        council.halted = false;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::CreateGuardianCouncil)?;
+
+        msg!(
+            "Guardian council created ({} of {})",
+            threshold,
+            ctx.accounts.guardian_council.members.len()
+        );
+        Ok(())
+    }
+
+    /**
+     * Halt all inbound mints and unlocks (council only)
+     *
+     * Council signers are passed as remaining accounts.
+     */
+    pub fn council_halt(ctx: Context<CouncilAction>) -> Result<()> {
+        let council = &mut ctx.accounts.guardian_council;
+        let signed = count_signers(&council.members, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= council.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = council.threshold
+        );
+
+        council.halted = true;
+
+        emit!(CouncilHaltChanged { halted: true });
+
+        msg!("Guardian council halted the bridge");
+        Ok(())
+    }
+
+    /**
+     * Clear the council halt (council only)
+     */
+    pub fn council_clear(ctx: Context<CouncilAction>) -> Result<()> {
+        let council = &mut ctx.accounts.guardian_council;
+        let signed = count_signers(&council.members, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= council.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = council.threshold
+        );
+
+        council.halted = false;
+
+        emit!(CouncilHaltChanged { halted: false });
+
+        msg!("Guardian council cleared the halt");
+        Ok(())
+    }
+
+    /**
+     * Replace the council's members (council only)
+     */
+    pub fn rotate_guardian_council(
+        ctx: Context<CouncilAction>,
+        members: Vec<Pubkey>,
+        threshold: u8,
+    ) -> Result<()> {
+        let council = &mut ctx.accounts.guardian_council;
+        let signed = count_signers(&council.members, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= council.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = council.threshold
+        );
+        GuardianCouncil::validate(&members, threshold)?;
+
+        council.members = members;
+        council.threshold = threshold;
+
+        msg!("Guardian council rotated ({} of {})", threshold, council.members.len());
+        Ok(())
+    }
+
+    /**
+     * Post a proof-of-reserve attestation for a wrapped token
+     *
+     * Records how much collateral is locked on Ethereum for `mint`, as of
+     * `block_number`, next to the wrapped supply at the time of posting.
+     * Attestations must move forward: an older block can't replace a
+     * newer one. Each one also reconciles the token's redemption queue,
+     * turning it on when the reserve falls short of the supply (see
+     * RedemptionQueue). Guardian signers are passed as remaining accounts.
+     */
+    pub fn post_reserve_attestation(
+        ctx: Context<PostReserveAttestation>,
+        eth_locked_amount: u64,
+        block_number: u64,
+    ) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+
+        let reserve = &mut ctx.accounts.reserve_attestation;
+        require!(
+            reserve.block_number == 0 || block_number > reserve.block_number,
+            ErrorCode::StaleReserveAttestation
+        );
+
+        let clock = Clock::get()?;
+        reserve.mint = ctx.accounts.mint.key();
+        reserve.eth_locked_amount = eth_locked_amount;
+        reserve.block_number = block_number;
+        reserve.wrapped_supply = ctx.accounts.mint.supply;
+        reserve.posted_at = clock.unix_timestamp;
+        reserve.slot = clock.slot;
+
+        emit!(ReserveAttested {
+            mint: reserve.mint,
+            eth_locked_amount,
+            block_number,
+            wrapped_supply: reserve.wrapped_supply,
+        });
+
+        let queue = &mut ctx.accounts.redemption_queue;
+        let was_active = queue.active;
+        queue.mint = reserve.mint;
+        queue.reconcile(eth_locked_amount, reserve.wrapped_supply, block_number)?;
+        if queue.active || was_active {
+            emit!(RedemptionQueueUpdated {
+                mint: queue.mint,
+                active: queue.active,
+                paid_ratio: queue.paid_ratio,
+                claimed: queue.claimed,
+            });
+        }
+
+        msg!(
+            "Reserve attested: {} locked at block {}, {} wrapped",
+            eth_locked_amount,
+            block_number,
+            reserve.wrapped_supply
+        );
+        Ok(())
+    }
+
+    /**
+     * Wrapped supply vs. the latest attested reserve
+     *
+     * Read-only; call with `.view()`. Supply is read live from the mint.
+     */
+    pub fn reserve_status(ctx: Context<ReserveStatusView>) -> Result<ReserveStatus> {
+        Ok(ctx
+            .accounts
+            .reserve_attestation
+            .status(ctx.accounts.mint.supply))
+    }
+
+    /**
+     * Send an escrow balance to the EVM side (guardian quorum)
+     *
+     * The other half of proof-of-reserve: the program reads what the
+     * escrow holds of `mint` (hot and cold vaults together, if split,
+     * plus what is deployed to yield venues), converts it to bridged
+     * units and queues it in the outbox as a VaultBalance message. The
+     * relayer carries it to the EVM bridge, which weighs it against the
+     * supply it minted. Guardians vouch that
+     * `escrow` is the bridge's escrow for the token, since the fee vault
+     * has the same owner; they are passed as remaining accounts.
+     */
+    pub fn attest_vault_balance(ctx: Context<AttestVaultBalance>) -> Result<()> {
+        let guardian_set = &ctx.accounts.guardian_set;
+        let signed = count_guardian_signers(guardian_set, ctx.remaining_accounts);
+        require_ctx!(
+            signed >= guardian_set.threshold as usize,
+            ErrorCode::InsufficientGuardianSignatures,
+            signed = signed,
+            threshold = guardian_set.threshold
+        );
+
+        let vault_config = ctx.accounts.vault_config.to_account_info();
+        VaultConfig::check_hot(&vault_config, &ctx.accounts.escrow.key())?;
+        let mut balance = ctx.accounts.escrow.amount;
+        if !vault_config.data_is_empty() {
+            let config = VaultConfig::try_deserialize(&mut &vault_config.try_borrow_data()?[..])?;
+            let cold_vault = ctx.accounts.cold_vault.as_ref().ok_or(ErrorCode::InvalidConfig)?;
+            require_keys_eq!(cold_vault.key(), config.cold_vault, ErrorCode::InvalidConfig);
+            balance = math::add(balance, cold_vault.amount)?;
+        }
+        // Deployed to yield venues, still owed back to the cold vault
+        let deployed = YieldStrategy::deployed_of(&ctx.accounts.yield_strategy.to_account_info())?;
+        let balance = math::add(balance, deployed)?;
+        let balance = ctx
+            .accounts
+            .token_config
+            .to_bridged(ctx.accounts.conversion_rate.as_deref(), balance)?;
+
+        let mint = ctx.accounts.mint.key();
+        let escrow = ctx.accounts.escrow.key();
+        let (sequence, message_hash) = ctx
+            .accounts
+            .outbox
+            .load_mut()?
+            .push_vault_balance(mint, escrow, balance)?;
+        let slot = Clock::get()?.slot;
+
+        emit!(VaultBalanceQueued {
+            mint,
+            escrow,
+            balance,
+            slot,
+            sequence,
+            message_hash,
+        });
+
+        msg!("Vault balance of {} queued: {} (outbox #{})", mint, balance, sequence);
+        Ok(())
+    }
+
+    /**
+     * Burn wrapped tokens into the redemption queue
+     *
+     * What `burn` becomes while the token is short of collateral: the
+     * tokens are burned now and the claim is paid on `dest_chain_id` in
+     * installments, as `release_redemption` allows.
+     */
+    pub fn enqueue_redemption(
+        ctx: Context<EnqueueRedemption>,
+        amount: u64,
+        dest_chain_id: u64,
+        recipient: String,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        let queue = &mut ctx.accounts.redemption_queue;
+        require!(queue.active, ErrorCode::RedemptionQueueInactive);
+
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
+        );
+
+        let cpi_ctx = CpiContext::new(
+            ctx.accounts.token_program.to_account_info(),
+            Burn {
+                mint: ctx.accounts.wrapped_mint.to_account_info(),
+                from: ctx.accounts.user_token.to_account_info(),
+                authority: ctx.accounts.user.to_account_info(),
+            },
+        );
+        token::burn(cpi_ctx, amount)?;
+
+        let claim = &mut ctx.accounts.claim;
+        claim.claim_id = queue.next_claim_id;
+        claim.mint = queue.mint;
+        claim.owner = ctx.accounts.user.key();
+        claim.dest_chain_id = dest_chain_id;
+        claim.recipient = recipient;
+        claim.amount = amount;
+        claim.released = 0;
+        claim.confirmed = 0;
+        claim.created_at = Clock::get()?.unix_timestamp;
+        math::increment(&mut queue.next_claim_id)?;
+        queue.claimed = math::add(queue.claimed, amount)?;
+
+        emit!(RedemptionQueued {
+            mint: claim.mint,
+            claim_id: claim.claim_id,
+            owner: claim.owner,
+            amount,
+            dest_chain_id,
+            recipient: claim.recipient.clone(),
+        });
+
+        msg!("Redemption {} queued: {} burned", claim.claim_id, amount);
+        Ok(())
+    }
+
+    /**
+     * Release what a redemption claim can draw at the queue's ratio
+     *
+     * Anyone may call this. The payout is made on the claim's chain from
+     * the RedemptionReleased event, and stays in flight (out of the
+     * reserve the ratio is computed from) until `confirm_redemption`.
+     */
+    pub fn release_redemption(ctx: Context<ReleaseRedemption>, claim_id: u64) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+
+        let queue = &mut ctx.accounts.redemption_queue;
+        let claim = &mut ctx.accounts.claim;
+        let amount = claim.releasable(queue.paid_ratio)?;
+        require!(amount > 0, ErrorCode::NothingToRedeem);
+
+        claim.released = math::add(claim.released, amount)?;
+        queue.released = math::add(queue.released, amount)?;
+        queue.in_flight = math::add(queue.in_flight, amount)?;
+
+        emit!(RedemptionReleased {
+            mint: claim.mint,
+            claim_id,
+            amount,
+            released: claim.released,
+            dest_chain_id: claim.dest_chain_id,
+            recipient: claim.recipient.clone(),
+        });
+
+        msg!("Redemption {}: {} of {} released", claim_id, claim.released, claim.amount);
+        Ok(())
+    }
+
+    /**
+     * Confirm a redemption claim's released payouts as paid
+     *
+     * By the owner or a guardian quorum, once the payouts have landed as
+     * of `eth_block`. A claim paid in full is closed.
+     */
+    pub fn confirm_redemption(
+        ctx: Context<ConfirmRedemption>,
+        claim_id: u64,
+        eth_block: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let queue = &mut ctx.accounts.redemption_queue;
+        let claim = &mut ctx.accounts.claim;
+        let amount = math::sub(claim.released, claim.confirmed)?;
+        require!(amount > 0, ErrorCode::NothingToRedeem);
+
+        claim.confirmed = claim.released;
+        queue.in_flight = math::sub(queue.in_flight, amount)?;
+        queue.confirmed_block = queue.confirmed_block.max(eth_block);
+
+        let closed = claim.confirmed == claim.amount;
+        if closed {
+            queue.claimed = math::sub(queue.claimed, claim.amount)?;
+            queue.released = math::sub(queue.released, claim.amount)?;
+        }
+
+        emit!(RedemptionConfirmed {
+            mint: claim.mint,
+            claim_id,
+            amount,
+            eth_block,
+            closed,
+        });
+
+        if closed {
+            ctx.accounts
+                .claim
+                .close(ctx.accounts.claim_owner.to_account_info())?;
+        }
+        msg!("Redemption {}: {} confirmed paid", claim_id, amount);
+        Ok(())
+    }
+
+    /**
+     * Create a wrapped token's TokenOracle (anyone, paying its rent)
+     *
+     * Writes the origin; call `refresh_token_oracle` for the rest.
+     */
+    pub fn init_token_oracle(ctx: Context<InitTokenOracle>) -> Result<()> {
+        let asset = &ctx.accounts.wrapped_asset;
+        let mut oracle = ctx.accounts.token_oracle.load_init()?;
+        oracle.version = TOKEN_ORACLE_VERSION;
+        oracle.status = CollateralStatus::Unattested as u8;
+        oracle.mint = asset.mint;
+        oracle.origin_chain_id = asset.source_chain_id;
+        oracle.origin_decimals = asset.source_decimals;
+        oracle.origin_address[12..].copy_from_slice(&asset.source_token);
+
+        msg!("Token oracle created for {}", asset.mint);
+        Ok(())
+    }
+
+    /**
+     * Create a token's EventHistory (anyone, paying its rent)
+     *
+     * From then on lock, lock_batch and unlock of the token append to it.
+     */
+    pub fn init_event_history(ctx: Context<InitEventHistory>) -> Result<()> {
+        let mut history = ctx.accounts.event_history.load_init()?;
+        history.mint = ctx.accounts.mint.key();
+
+        msg!("Event history created for {}", history.mint);
+        Ok(())
+    }
+
+    /**
+     * Create a token's RouteSequence to `dest_chain_id` (anyone, paying
+     * its rent)
+     *
+     * The pair can lock and burn from then on, numbered from 1 in the
+     * next free shard.
+     */
+    pub fn init_route_sequence(ctx: Context<InitRouteSequence>, dest_chain_id: u64) -> Result<()> {
+        let bridge_state = &mut ctx.accounts.bridge_state;
+        let shard = bridge_state.route_shards.checked_add(1).ok_or(ErrorCode::MathOverflow)?;
+        require!(shard <= MAX_ROUTE_SHARD, ErrorCode::SequenceExhausted);
+        bridge_state.route_shards = shard;
+
+        let sequence = &mut ctx.accounts.route_sequence;
+        sequence.mint = ctx.accounts.mint.key();
+        sequence.dest_chain_id = dest_chain_id;
+        sequence.last = 0;
+        sequence.shard = shard;
+
+        msg!(
+            "Route sequence created for {} to chain {} (shard {})",
+            sequence.mint,
+            dest_chain_id,
+            shard
+        );
+        Ok(())
+    }
+
+    /**
+     * Refresh a wrapped token's TokenOracle (permissionless crank)
+     *
+     * Reads pause state, the latest reserve attestation, the redemption
+     * queue and the live supply into the oracle.
+     */
+    pub fn refresh_token_oracle(ctx: Context<RefreshTokenOracle>) -> Result<()> {
+        let mut flags = 0;
+        if ctx.accounts.bridge_state.paused {
+            flags |= oracle_flags::BRIDGE_PAUSED;
+        }
+        if ctx.accounts.token_config.paused {
+            flags |= oracle_flags::TOKEN_PAUSED;
+        }
+        if ctx.accounts.origin_chain.halted {
+            flags |= oracle_flags::ORIGIN_CHAIN_HALTED;
+        }
+        if GuardianCouncil::is_halted(&ctx.accounts.guardian_council)? {
+            flags |= oracle_flags::COUNCIL_HALTED;
+        }
+
+        let supply = ctx.accounts.wrapped_mint.supply;
+        let reserve = ReserveAttestation::load(&ctx.accounts.reserve_attestation)?;
+        let status = match &reserve {
+            None => CollateralStatus::Unattested,
+            Some(_) if RedemptionQueue::is_active(&ctx.accounts.redemption_queue)? => {
+                CollateralStatus::Redeeming
+            }
+            Some(reserve) if reserve.eth_locked_amount < supply => {
+                CollateralStatus::Undercollateralized
+            }
+            Some(_) => CollateralStatus::Healthy,
+        };
+
+        let clock = Clock::get()?;
+        let mut oracle = ctx.accounts.token_oracle.load_mut()?;
+        oracle.version = TOKEN_ORACLE_VERSION;
+        oracle.status = status as u8;
+        oracle.flags = flags;
+        oracle.wrapped_supply = supply;
+        if let Some(reserve) = &reserve {
+            oracle.attested_reserve = reserve.eth_locked_amount;
+            oracle.reserve_block = reserve.block_number;
+            oracle.reserve_posted_at = reserve.posted_at;
+        }
+        oracle.collateral_bps = TokenOracle::collateral_bps(oracle.attested_reserve, supply);
+        oracle.updated_at = clock.unix_timestamp;
+        oracle.slot = clock.slot;
+
+        emit!(TokenOracleRefreshed {
+            mint: oracle.mint,
+            status: oracle.status,
+            flags,
+            collateral_bps: oracle.collateral_bps,
+        });
+        Ok(())
+    }
+
+    /**
+     * Split a token's escrow into a hot and a cold vault (owner only)
+     *
+     * `hot_vault` is the escrow unlocks pay out of from then on;
+     * `cold_vault` has to be created beforehand, owned by the cold vault
+     * authority (PDA "cold_vault"). Calling it again changes `hot_cap`
+     * and `refill_delay`; the vaults stay. `rebalance_vault` then sweeps
+     * what the hot vault holds over `hot_cap` into the cold one.
+     */
+    pub fn configure_vaults(
+        ctx: Context<ConfigureVaults>,
+        hot_cap: u64,
+        refill_delay: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(hot_cap > 0 && refill_delay > 0, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.vault_config;
+        if config.mint == Pubkey::default() {
+            config.mint = ctx.accounts.mint.key();
+            config.hot_vault = ctx.accounts.hot_vault.key();
+            config.cold_vault = ctx.accounts.cold_vault.key();
+        } else {
+            require_keys_eq!(
+                config.hot_vault,
+                ctx.accounts.hot_vault.key(),
+                ErrorCode::InvalidConfig
+            );
+            require_keys_eq!(
+                config.cold_vault,
+                ctx.accounts.cold_vault.key(),
+                ErrorCode::InvalidConfig
+            );
+        }
+        config.hot_cap = hot_cap;
+        config.refill_delay = refill_delay;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ConfigureVaults)?;
+
+        msg!(
+            "Vaults for {}: hot capped at {}, refills wait {}s",
+            config.mint,
+            hot_cap,
+            refill_delay
+        );
+        Ok(())
+    }
+
+    /**
+     * Set how `rebalance_vault` steers a token's hot vault (owner only)
+     *
+     * The hot vault aims for `target_bps` of hot + cold, never more than
+     * `hot_cap` (0 = just `hot_cap`), and refills move at most
+     * `max_refill_per_epoch` per Solana epoch (0 = no limit).
+     */
+    pub fn set_vault_policy(
+        ctx: Context<SetVaultPolicy>,
+        target_bps: u16,
+        max_refill_per_epoch: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(target_bps as u64 <= math::BPS_DENOMINATOR, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.vault_config;
+        config.target_bps = target_bps;
+        config.max_refill_per_epoch = max_refill_per_epoch;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetVaultPolicy)?;
+
+        msg!(
+            "Vault policy for {}: hot at {} bps, refills up to {} per epoch",
+            config.mint,
+            target_bps,
+            max_refill_per_epoch
+        );
+        Ok(())
+    }
+
+    /**
+     * Require a compliance co-signature on a restricted token's large burns (owner only)
+     *
+     * Burns over `threshold` must also be signed by `compliance_key`;
+     * `Pubkey::default()` turns the requirement off again.
+     */
+    pub fn set_burn_approval(
+        ctx: Context<SetBurnApproval>,
+        compliance_key: Pubkey,
+        threshold: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(ctx.accounts.token_config.restricted, ErrorCode::InvalidConfig);
+
+        let approval = &mut ctx.accounts.burn_approval;
+        approval.mint = ctx.accounts.mint.key();
+        approval.compliance_key = compliance_key;
+        approval.threshold = threshold;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetBurnApproval)?;
+
+        msg!(
+            "Burns of {} over {} need {}'s signature",
+            approval.mint,
+            threshold,
+            compliance_key
+        );
+        Ok(())
+    }
+
+    /**
+     * Bring a token's hot vault back to its target (permissionless crank)
+     *
+     * The target is set by the vault policy (see VaultConfig). Over it,
+     * the excess is swept into the cold vault right away. Under it, a
+     * refill from the cold vault is queued instead, within what's left
+     * of this epoch's refill limit, for `execute_refill` once
+     * `refill_delay` has passed; one refill is queued at a time.
+     */
+    pub fn rebalance_vault<'info>(ctx: Context<'_, '_, '_, 'info, RebalanceVault<'info>>) -> Result<()> {
+        let config = &mut ctx.accounts.vault_config;
+        let hot_balance = ctx.accounts.hot_vault.amount;
+        let cold_balance = ctx.accounts.cold_vault.amount;
+        let target = config.target(hot_balance, cold_balance)?;
+
+        if hot_balance > target {
+            let amount = hot_balance - target;
+            let seeds = &[
+                b"bridge".as_ref(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.accounts.bridge_state.authority_bump],
+            ];
+            let signer = &[&seeds[..]];
+
+            let extensions = token_ext::inspect(
+                &ctx.accounts.mint.to_account_info(),
+                ctx.accounts.token_config.allowed_extensions,
+            )?;
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.hot_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.cold_vault.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, amount, ctx.accounts.mint.decimals, signer)?;
+
+            emit!(VaultSwept {
+                mint: config.mint,
+                amount,
+                hot_balance: target,
+            });
+            msg!("Swept {} of {} into the cold vault", amount, config.mint);
+            return Ok(());
+        }
+
+        let amount = (target - hot_balance)
+            .min(cold_balance)
+            .min(config.refill_budget(Clock::get()?.epoch));
+        require!(amount > 0 && config.refill_amount == 0, ErrorCode::NothingToRebalance);
+
+        config.refill_amount = amount;
+        config.refill_at = math::add_seconds(Clock::get()?.unix_timestamp, config.refill_delay)?;
+
+        emit!(VaultRefillQueued {
+            mint: config.mint,
+            amount,
+            target,
+            executable_at: config.refill_at,
+        });
+        msg!("Refill of {} {} queued until {}", amount, config.mint, config.refill_at);
+        Ok(())
+    }
+
+    /**
+     * Move a queued refill from the cold vault to the hot vault
+     *
+     * Permissionless, once the refill's delay has passed. Tops the hot
+     * vault up to its target at most, in case it filled up meanwhile,
+     * and within this epoch's refill limit.
+     */
+    pub fn execute_refill<'info>(ctx: Context<'_, '_, '_, 'info, ExecuteRefill<'info>>) -> Result<()> {
+        let config = &mut ctx.accounts.vault_config;
+        let clock = Clock::get()?;
+        require!(
+            config.refill_amount > 0 && clock.unix_timestamp >= config.refill_at,
+            ErrorCode::RefillNotReady
+        );
+
+        let hot_balance = ctx.accounts.hot_vault.amount;
+        let cold_balance = ctx.accounts.cold_vault.amount;
+        let target = config.target(hot_balance, cold_balance)?;
+        let amount = config
+            .refill_amount
+            .min(target.saturating_sub(hot_balance))
+            .min(cold_balance)
+            .min(config.refill_budget(clock.epoch));
+        config.refill_amount = 0;
+        config.refill_at = 0;
+        config.record_refill(clock.epoch, amount)?;
+
+        let received = if amount > 0 {
+            let seeds = &[
+                b"cold_vault".as_ref(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.bumps.cold_authority],
+            ];
+            let signer = &[&seeds[..]];
+
+            let extensions = token_ext::inspect(
+                &ctx.accounts.mint.to_account_info(),
+                ctx.accounts.token_config.allowed_extensions,
+            )?;
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.cold_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.hot_vault.to_account_info(),
+                authority: ctx.accounts.cold_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, amount, ctx.accounts.mint.decimals, signer)?
+        } else {
+            0
+        };
+
+        emit!(VaultRefilled {
+            mint: config.mint,
+            amount: received,
+            hot_balance: math::add(hot_balance, received)?,
+            refilled_in_epoch: config.refilled_in_epoch,
+        });
+        msg!("Refilled the hot vault of {} with {}", config.mint, received);
+        Ok(())
+    }
+
+    /**
+     * Drop a queued refill (owner or guardian quorum)
+     *
+     * For when the hot vault is being drained: what is still cold stays
+     * there. Guardian signers are the remaining accounts.
+     */
+    pub fn cancel_refill(ctx: Context<CancelRefill>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let config = &mut ctx.accounts.vault_config;
+        let amount = config.refill_amount;
+        require!(amount > 0, ErrorCode::RefillNotReady);
+        config.refill_amount = 0;
+        config.refill_at = 0;
+
+        emit!(VaultRefillCancelled {
+            mint: config.mint,
+            amount,
+            cancelled_by: ctx.accounts.authority.key(),
+        });
+        msg!("Refill of {} {} cancelled", amount, config.mint);
+        Ok(())
+    }
+
+    /**
+     * Allow deploying a token's cold collateral to a yield venue (owner only)
+     *
+     * `adapter` is the venue's adapter program (see yield_adapter.rs).
+     * A new venue, or a higher `max_amount` for one already allowed,
+     * takes deposits YIELD_VENUE_DELAY from now; until then the owner or
+     * guardians can remove it. A lower `max_amount` applies at once.
+     */
+    pub fn allow_yield_venue(ctx: Context<AllowYieldVenue>, max_amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        let adapter = ctx.accounts.adapter.key();
+        require!(
+            max_amount > 0 && adapter != crate::ID && adapter != system_program::ID,
+            ErrorCode::InvalidConfig
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let strategy = &mut ctx.accounts.yield_strategy;
+        if strategy.mint == Pubkey::default() {
+            strategy.mint = ctx.accounts.vault_config.mint;
+            strategy.bump = ctx.bumps.yield_strategy;
+        }
+        let active_at = match strategy.venue_mut(&adapter) {
+            Ok(venue) => {
+                if max_amount > venue.max_amount {
+                    venue.active_at = math::add_seconds(now, YIELD_VENUE_DELAY)?;
+                }
+                venue.max_amount = max_amount;
+                venue.active_at
+            }
+            Err(_) => {
+                require!(strategy.venues.len() < MAX_YIELD_VENUES, ErrorCode::YieldVenuesFull);
+                let active_at = math::add_seconds(now, YIELD_VENUE_DELAY)?;
+                strategy.venues.push(YieldVenue {
+                    adapter,
+                    max_amount,
+                    active_at,
+                    deployed: 0,
+                });
+                active_at
+            }
+        };
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::AllowYieldVenue)?;
+
+        emit!(YieldVenueAllowed {
+            mint: strategy.mint,
+            adapter,
+            max_amount,
+            active_at,
+        });
+        msg!(
+            "Yield venue {} for {}: up to {} from {}",
+            adapter,
+            strategy.mint,
+            max_amount,
+            active_at
+        );
+        Ok(())
+    }
+
+    /**
+     * Stop deposits to a yield venue (owner or guardian quorum)
+     *
+     * A venue with nothing deployed is dropped; otherwise it winds down
+     * and is dropped by the recall that empties it. Guardian signers are
+     * the remaining accounts.
+     */
+    pub fn remove_yield_venue(ctx: Context<RemoveYieldVenue>, adapter: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let strategy = &mut ctx.accounts.yield_strategy;
+        let venue = strategy.venue_mut(&adapter)?;
+        venue.max_amount = 0;
+        let deployed = venue.deployed;
+        strategy.prune();
+
+        emit!(YieldVenueRemoved {
+            mint: strategy.mint,
+            adapter,
+            deployed,
+            removed_by: ctx.accounts.authority.key(),
+        });
+        msg!("Yield venue {} for {} removed, {} still deployed", adapter, strategy.mint, deployed);
+        Ok(())
+    }
+
+    /**
+     * Deploy `amount` of a token's cold vault to an active yield venue (owner only)
+     *
+     * Within the venue's `max_amount`, and within MAX_YIELD_BPS of the
+     * cold vault and what is deployed together. The amount moves into
+     * the venue's staging account and the adapter deposits it from
+     * there, signed by the venue authority, never the cold vault's.
+     */
+    pub fn deposit_yield<'info>(ctx: Context<'_, '_, '_, 'info, DepositYield<'info>>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+
+        let now = Clock::get()?.unix_timestamp;
+        let adapter = ctx.accounts.adapter.key();
+        let headroom = ctx
+            .accounts
+            .yield_strategy
+            .headroom(ctx.accounts.cold_vault.amount)?;
+        let venue = ctx.accounts.yield_strategy.venue_mut(&adapter)?;
+        require!(
+            venue.max_amount > 0 && now >= venue.active_at,
+            ErrorCode::YieldVenueNotAllowed
+        );
+        let venue_room = venue.max_amount.saturating_sub(venue.deployed);
+        require_ctx!(
+            amount <= venue_room && amount <= headroom,
+            ErrorCode::YieldCapExceeded,
+            amount = amount,
+            venue_room = venue_room,
+            headroom = headroom
+        );
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let seeds = &[
+            b"cold_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.cold_authority],
+        ];
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.cold_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.staging.to_account_info(),
+            authority: ctx.accounts.cold_authority.to_account_info(),
+            extra_accounts: &[],
+        }
+        .execute(&extensions, amount, ctx.accounts.mint.decimals, &[&seeds[..]])?;
+
+        let mint = ctx.accounts.mint.key();
+        let seeds = &[
+            b"yield_venue".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            mint.as_ref(),
+            adapter.as_ref(),
+            &[ctx.bumps.venue_authority],
+        ];
+        yield_adapter::AdapterAccounts {
+            adapter: ctx.accounts.adapter.to_account_info(),
+            staging: ctx.accounts.staging.to_account_info(),
+            venue_authority: ctx.accounts.venue_authority.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            venue_accounts: ctx.remaining_accounts,
+        }
+        .deposit(amount, &[&seeds[..]])?;
+
+        let strategy = &mut ctx.accounts.yield_strategy;
+        strategy.deployed = math::add(strategy.deployed, amount)?;
+        let venue = strategy.venue_mut(&adapter)?;
+        venue.deployed = math::add(venue.deployed, amount)?;
+        let venue_deployed = venue.deployed;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::DepositYield)?;
+
+        emit!(YieldDeposited {
+            mint,
+            adapter,
+            amount,
+            venue_deployed,
+            deployed: strategy.deployed,
+        });
+        msg!("Deployed {} of {} to yield venue {}", amount, mint, adapter);
+        Ok(())
+    }
+
+    /**
+     * Bring collateral back from a yield venue (owner or guardian quorum)
+     *
+     * No delay, so it works as an emergency exit. The adapter withdraws
+     * up to `amount` of the underlying (u64::MAX = all) into staging,
+     * and everything in staging leaves it: up to the venue's deployed
+     * principal back to the cold vault, the rest as yield to the
+     * insurance fund. On a full recall, principal the venue didn't
+     * return is written off as lost.
+     */
+    pub fn recall_yield<'info>(ctx: Context<'_, '_, '_, 'info, RecallYield<'info>>, amount: u64) -> Result<()> {
+        let guardians = ctx
+            .remaining_accounts
+            .iter()
+            .take_while(|account| account.is_signer)
+            .count();
+        let (guardian_signers, venue_accounts) = ctx.remaining_accounts.split_at(guardians);
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    guardian_signers,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let mint = ctx.accounts.mint.key();
+        let adapter = ctx.accounts.adapter.key();
+        let deployed = ctx.accounts.yield_strategy.venue_mut(&adapter)?.deployed;
+        let seeds = &[
+            b"yield_venue".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            mint.as_ref(),
+            adapter.as_ref(),
+            &[ctx.bumps.venue_authority],
+        ];
+        let signer = &[&seeds[..]];
+
+        yield_adapter::AdapterAccounts {
+            adapter: ctx.accounts.adapter.to_account_info(),
+            staging: ctx.accounts.staging.to_account_info(),
+            venue_authority: ctx.accounts.venue_authority.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            token_program: ctx.accounts.token_program.to_account_info(),
+            venue_accounts,
+        }
+        .withdraw(amount, signer)?;
+        ctx.accounts.staging.reload()?;
+
+        let received = ctx.accounts.staging.amount;
+        let principal = received.min(deployed);
+        let yield_amount = received - principal;
+        let lost = if amount == u64::MAX { deployed - principal } else { 0 };
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let decimals = ctx.accounts.mint.decimals;
+        if principal > 0 {
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.staging.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.cold_vault.to_account_info(),
+                authority: ctx.accounts.venue_authority.to_account_info(),
+                extra_accounts: &[],
+            }
+            .execute(&extensions, principal, decimals, signer)?;
+        }
+        if yield_amount > 0 {
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.staging.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.insurance_token.to_account_info(),
+                authority: ctx.accounts.venue_authority.to_account_info(),
+                extra_accounts: &[],
+            }
+            .execute(&extensions, yield_amount, decimals, signer)?;
+        }
+
+        let strategy = &mut ctx.accounts.yield_strategy;
+        let returned = math::add(principal, lost)?;
+        strategy.deployed = math::sub(strategy.deployed, returned)?;
+        strategy.harvested = math::add(strategy.harvested, yield_amount)?;
+        strategy.lost = math::add(strategy.lost, lost)?;
+        let venue = strategy.venue_mut(&adapter)?;
+        venue.deployed = math::sub(venue.deployed, returned)?;
+        let venue_deployed = venue.deployed;
+        strategy.prune();
+
+        emit!(YieldRecalled {
+            mint,
+            adapter,
+            principal,
+            yield_amount,
+            lost,
+            venue_deployed,
+            recalled_by: ctx.accounts.authority.key(),
+        });
+        msg!(
+            "Recalled {} of {} from yield venue {}, {} yield to the insurance fund",
+            principal,
+            mint,
+            adapter,
+            yield_amount
+        );
+        Ok(())
+    }
+
+    /**
+     * Start, update or stop tracking a token in GlobalStats
+     *
+     * `feed_id` is the token's Pyth USD price feed; all zeros stops
+     * tracking it. The escrow's balance now is the baseline, so tokens
+     * already escrowed aren't booked as inflow.
+     */
+    pub fn set_price_feed(ctx: Context<SetPriceFeed>, feed_id: [u8; 32]) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let mint = ctx.accounts.mint.key();
+        let stats = &mut ctx.accounts.global_stats;
+        stats.tokens.retain(|token| token.mint != mint);
+        if feed_id != [0u8; 32] {
+            require!(stats.tokens.len() < MAX_TRACKED_TOKENS, ErrorCode::InvalidConfig);
+            stats.tokens.push(TrackedToken {
+                mint,
+                decimals: ctx.accounts.mint.decimals,
+                escrow: ctx.accounts.escrow.key(),
+                feed_id,
+                balance: ctx.accounts.escrow.amount,
+            });
+        }
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetPriceFeed)?;
+
+        msg!("Price feed for {} updated", mint);
+        Ok(())
+    }
+
+    /**
+     * Refresh GlobalStats (permissionless crank)
+     *
+     * Remaining accounts: each tracked token's escrow, then a fresh Pyth
+     * price update for its feed, in the order the tokens are tracked.
+     * TVL is every escrow at today's price; each escrow's change since
+     * the last update is booked, at today's price, as inflow or outflow.
+     */
+    pub fn update_global_stats(ctx: Context<UpdateGlobalStats>) -> Result<()> {
+        let stats = &mut ctx.accounts.global_stats;
+        require!(
+            ctx.remaining_accounts.len() == stats.tokens.len() * 2,
+            ErrorCode::TrackedTokenMismatch
+        );
+
+        let clock = Clock::get()?;
+        stats.roll_window(clock.unix_timestamp);
+
+        let (mut tvl_usd, mut inflow_usd, mut outflow_usd) = (0, 0, 0);
+        for (token, accounts) in stats.tokens.iter_mut().zip(ctx.remaining_accounts.chunks(2)) {
+            require_keys_eq!(accounts[0].key(), token.escrow, ErrorCode::TrackedTokenMismatch);
+            let balance =
+                InterfaceTokenAccount::try_deserialize(&mut &accounts[0].try_borrow_data()?[..])?
+                    .amount;
+            let price = pyth::load_price(&accounts[1], &token.feed_id, clock.unix_timestamp)?;
+            let value = |amount| {
+                math::usd_value(amount, token.decimals, price.price as u64, price.exponent)
+            };
+
+            tvl_usd = math::add(tvl_usd, value(balance)?)?;
+            if balance >= token.balance {
+                inflow_usd = math::add(inflow_usd, value(balance - token.balance)?)?;
+            } else {
+                outflow_usd = math::add(outflow_usd, value(token.balance - balance)?)?;
+            }
+            token.balance = balance;
+        }
+
+        stats.tvl_usd = tvl_usd;
+        stats.inflow_usd = math::add(stats.inflow_usd, inflow_usd)?;
+        stats.outflow_usd = math::add(stats.outflow_usd, outflow_usd)?;
+        stats.updated_at = clock.unix_timestamp;
+        stats.slot = clock.slot;
+
+        emit!(GlobalStatsUpdated {
+            tvl_usd,
+            inflow_usd: stats.inflow_usd,
+            outflow_usd: stats.outflow_usd,
+            window_start: stats.window_start,
+            updated_at: clock.unix_timestamp,
+        });
+
+        msg!("TVL ${}.{:06}", tvl_usd / 1_000_000, tvl_usd % 1_000_000);
+        Ok(())
+    }
+
+    /**
+     * Where a transfer stands, in one simulated instruction
+     *
+     * Read-only; call with `.view()` (or simulate and read the return
+     * data, or CPI). Pass the transfer's record PDA (["transfer", "out"
+     * or "in", nonce]) and, for optimistic inbound transfers, its
+     * attestation; a pruned or never-created record comes back with no
+     * status rather than an error.
+     */
+    pub fn get_transfer_status(
+        ctx: Context<TransferStatusView>,
+        transfer_id: [u8; 32],
+    ) -> Result<TransferStatusInfo> {
+        let mut info = TransferStatusInfo {
+            transfer_id,
+            status: None,
+            direction: None,
+            nonce: 0,
+            amount: 0,
+            remote_chain_id: 0,
+            updated_at: 0,
+            deadline: 0,
+            attestation_status: None,
+            executable_at: 0,
+        };
+
+        let record_info = ctx.accounts.transfer_record.to_account_info();
+        if record_info.data_is_empty() {
+            return Ok(info);
+        }
+        require_keys_eq!(*record_info.owner, crate::ID, ErrorCode::TransferMismatch);
+        let record = TransferRecord::try_deserialize(&mut &record_info.try_borrow_data()?[..])?;
+        require!(record.transfer_id == transfer_id, ErrorCode::TransferMismatch);
+
+        info.status = Some(record.status);
+        info.direction = Some(record.direction);
+        info.nonce = record.nonce;
+        info.amount = record.amount;
+        info.remote_chain_id = record.remote_chain_id;
+        info.updated_at = record.updated_at;
+        info.deadline = record.deadline;
+
+        if let Some(attestation_info) = &ctx.accounts.attestation {
+            let nonce_bytes = record.nonce.to_le_bytes();
+            let instance = instance::seed(&ctx.accounts.bridge_state.instance_id);
+            let (address, _) =
+                Pubkey::find_program_address(&[b"attestation", instance, &nonce_bytes], &crate::ID);
+            require_keys_eq!(attestation_info.key(), address, ErrorCode::TransferMismatch);
+
+            if record.direction == TransferDirection::Inbound && !attestation_info.data_is_empty() {
+                let attestation =
+                    Attestation::try_deserialize(&mut &attestation_info.try_borrow_data()?[..])?;
+                info.attestation_status = Some(attestation.status);
+                info.executable_at = attestation.executable_at;
+            }
+        }
+
+        Ok(info)
+    }
+
+    /**
+     * Emit a stored transfer's event again
+     *
+     * Permissionless, for relayers that missed the original log (an RPC
+     * gap, truncated logs): anyone can replay a transfer record, and
+     * the record is what the program wrote, so nothing needs trusting.
+     * Outbound transfers also repeat the original "Locked"/"Burned" log
+     * line that log-parsing relayers watch for. The destination's
+     * replay protection makes relaying one twice harmless.
+     */
+    pub fn reemit(ctx: Context<Reemit>, transfer_id: [u8; 32]) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require!(record.transfer_id == transfer_id, ErrorCode::TransferMismatch);
+
+        emit!(TransferReemitted {
+            transfer_id,
+            direction: record.direction,
+            nonce: record.nonce,
+            status: record.status,
+            remote_chain_id: record.remote_chain_id,
+            local_account: record.local_account,
+            remote_address: record.remote_address.clone(),
+            amount: record.amount,
+            mint: record.mint,
+            escrowed: record.escrowed,
+            created_at: record.created_at,
+            created_slot: record.created_slot,
+        });
+
+        match record.direction {
+            TransferDirection::Outbound if record.escrowed => msg!(
+                "Locked {} tokens for {} on chain {} (nonce: {}, id: {})",
+                record.amount,
+                record.remote_address,
+                record.remote_chain_id,
+                record.nonce,
+                hex_encode(&transfer_id)
+            ),
+            TransferDirection::Outbound => msg!(
+                "Burned {} tokens from {} for {} on chain {} (nonce: {}, id: {})",
+                record.amount,
+                record.local_account,
+                record.remote_address,
+                record.remote_chain_id,
+                record.nonce,
+                hex_encode(&transfer_id)
+            ),
+            TransferDirection::Inbound => {
+                msg!("Re-emitted inbound transfer (nonce: {})", record.nonce)
+            }
+        }
+        Ok(())
+    }
+
+    /**
+     * What a lock would cost right now, in one simulated instruction
+     *
+     * Read-only; call with `.view()`. Fails like `lock` would if the
+     * bridge, token or destination is paused, disabled or below its
+     * minimum, so aggregators can quote a route without sending a
+     * transaction. `user` picks the volume rebate tier; pass their
+     * user-stats PDA even if it doesn't exist yet. The fee is
+     * deterministic for a given slot (it's `lock`'s own computation);
+     * a Token-2022 transfer fee or share conversion still applies on top
+     * of `net_amount`. Signed quotes (quote.rs) can pin it.
+     */
+    pub fn quote_lock(
+        ctx: Context<QuoteLockView>,
+        mint: Pubkey,
+        amount: u64,
+        dest_chain_id: u64,
+        user: Pubkey,
+    ) -> Result<LockQuote> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
+        );
+
+        let route = FeeRoute::load(&ctx.accounts.fee_route)?;
+        let volume = UserStats::volume_of(&ctx.accounts.user_stats)?;
+        let (fee_tier, discount_bps) = ctx.accounts.fee_tiers.tier_for(volume);
+        let surcharge_bps = Throttle::surcharge_of(&ctx.accounts.throttle)?;
+        let fee = if FeeExemption::is_exempt(&ctx.accounts.fee_exemption)? {
+            0
+        } else {
+            let fee = chain_config.fee(route.as_ref(), amount, discount_bps)?;
+            math::add(fee, math::bps_of(amount, surcharge_bps)?)?
+        };
+        require_ctx!(amount > fee, ErrorCode::AmountTooSmall, amount = amount, fee = fee);
+
+        Ok(LockQuote {
+            mint,
+            amount,
+            fee,
+            fee_tier,
+            net_amount: amount - fee,
+            dest_chain_id,
+            user,
+            next_nonce: ctx.accounts.route_sequence.next_nonce()?,
+        })
+    }
+
+    /**
+     * Exactly what a lock would do, without moving funds
+     *
+     * Read-only; call with `.view()`. Runs `lock`'s checks in `lock`'s
+     * order (pauses, destination, recipient, minimum, credential and tier
+     * limit, fee, Token-2022 extensions) and fails with the error `lock`
+     * would, so a wallet can render its confirmation screen from one
+     * simulation. Unlike `quote_lock` the outcome is final: the Token-2022
+     * transfer fee and share conversion are applied and the transfer ID
+     * is computed, for the nonce the lock gets if nothing lands first.
+     */
+    pub fn preview_lock(
+        ctx: Context<PreviewLockView>,
+        amount: u64,
+        mint: Pubkey,
+        dest_chain_id: u64,
+        recipient: String,
+        user: Pubkey,
+    ) -> Result<LockPreview> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        let token_config = &ctx.accounts.token_config;
+        require!(!token_config.paused, ErrorCode::TokenPaused);
+
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
+        );
+
+        let (pass_discount_bps, tier_boost) = ctx
+            .accounts
+            .bridge_state
+            .pass_benefits(ctx.accounts.bridge_pass.as_deref());
+        let credential = ctx.accounts.credential.as_deref();
+        credential::check_with_boost(token_config, credential, &user, amount, tier_boost)?;
+
+        let route = FeeRoute::load(&ctx.accounts.fee_route)?;
+        let volume = UserStats::volume_of(&ctx.accounts.user_stats)?;
+        let (fee_tier, discount_bps) = ctx.accounts.fee_tiers.tier_for(volume);
+        let discount_bps = discount_bps.max(pass_discount_bps);
+        let surcharge_bps = Throttle::surcharge_of(&ctx.accounts.throttle)?;
+        let fee = if FeeExemption::is_exempt(&ctx.accounts.fee_exemption)? {
+            0
+        } else {
+            let fee = chain_config.fee(route.as_ref(), amount, discount_bps)?;
+            math::add(fee, math::bps_of(amount, surcharge_bps)?)?
+        };
+        require_ctx!(amount > fee, ErrorCode::AmountTooSmall, amount = amount, fee = fee);
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.token_mint.to_account_info(),
+            token_config.allowed_extensions,
+        )?;
+        let sent = amount - fee;
+        let token_fee = extensions.withheld_fee(sent)?;
+        let bridged_amount = token_config
+            .to_bridged(ctx.accounts.conversion_rate.as_deref(), math::sub(sent, token_fee)?)?;
+        require!(bridged_amount > 0, ErrorCode::AmountTooSmall);
+
+        let nonce = ctx.accounts.route_sequence.next_nonce()?;
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+
+        Ok(LockPreview {
+            mint,
+            amount,
+            fee,
+            fee_tier,
+            token_fee,
+            bridged_amount,
+            dest_chain_id,
+            user,
+            nonce,
+            transfer_id: transfer_id(
+                chain_ids::SOLANA,
+                dest_chain_id,
+                guardian_set_index,
+                nonce,
+                &mint,
+                bridged_amount,
+                &user,
+                &recipient,
+            ),
+        })
+    }
+
+    /**
+     * How much `user` can send of `mint` to `dest_chain_id` right now
+     *
+     * Read-only; call with `.view()`. Lets a wallet bound its amount
+     * input, or disable it, before the user signs a transfer that can
+     * only fail. Fails like `lock` when a restricted token's credential
+     * is missing or expired.
+     */
+    pub fn get_rate_limits(
+        ctx: Context<RateLimitsView>,
+        mint: Pubkey,
+        dest_chain_id: u64,
+        user: Pubkey,
+    ) -> Result<RateLimitStatus> {
+        let token_config = &ctx.accounts.token_config;
+        let chain_config = &ctx.accounts.chain_config;
+        let blocked = ctx.accounts.bridge_state.paused
+            || token_config.paused
+            || !chain_config.enabled;
+
+        let (_, tier_boost) = ctx
+            .accounts
+            .bridge_state
+            .pass_benefits(ctx.accounts.bridge_pass.as_deref());
+        let credential = ctx.accounts.credential.as_deref();
+        let max_amount = credential::limit_of(token_config, credential, &user, tier_boost)?;
+
+        let throttle_headroom = Throttle::headroom_of(&ctx.accounts.throttle)?;
+        let surcharge_bps = if throttle_headroom == Some(0) {
+            0
+        } else {
+            Throttle::surcharge_of(&ctx.accounts.throttle)?
+        };
+
+        Ok(RateLimitStatus {
+            mint,
+            dest_chain_id,
+            user,
+            blocked,
+            min_amount: chain_config.min_amount,
+            max_amount,
+            throttle_headroom,
+            surcharge_bps,
+        })
+    }
+
+    /**
+     * What this deployment is and supports, for SDKs and aggregators
+     *
+     * Read-only; call with `.view()`. Reports the program version, the
+     * interface version, the `features` switched on, the trust models
+     * compiled in, and the fee schedule: the volume rebate tiers and
+     * the config of each chain passed as a remaining account (up to
+     * MAX_INFO_CHAINS; ChainConfig PDAs, see `pda::chain_config`).
+     */
+    pub fn get_bridge_info(ctx: Context<BridgeInfoView>) -> Result<BridgeInfo> {
+        require!(
+            ctx.remaining_accounts.len() <= MAX_INFO_CHAINS,
+            ErrorCode::InvalidConfig
+        );
+        let mut chains = Vec::with_capacity(ctx.remaining_accounts.len());
+        for info in ctx.remaining_accounts {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidConfig);
+            chains.push(ChainConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?);
+        }
+
+        let bridge_state = &ctx.accounts.bridge_state;
+        Ok(BridgeInfo {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            interface_version: INTERFACE_VERSION,
+            features: bridge_state.feature_bits(),
+            verifiers: verifiers::available_bits(),
+            paused: bridge_state.paused,
+            event_encoding: bridge_state.event_encoding,
+            fee_tiers: ctx
+                .accounts
+                .fee_tiers
+                .as_ref()
+                .map_or_else(Vec::new, |fee_tiers| fee_tiers.tiers.clone()),
+            chains,
+        })
+    }
+
+    /**
+     * Commit finished transfers to a Merkle checkpoint
+     *
+     * Pass up to MAX_CHECKPOINT_BATCH transfer records, all in a final
+     * state, as remaining accounts. The root over their leaves (see
+     * `TransferRecord::checkpoint_leaf`), the volume they carry and the
+     * previous checkpoint's root go into a new `Checkpoint`. Once a
+     * record is in a checkpoint it can be pruned; replay protection
+     * doesn't depend on it (that's `processed_nonces`, itself pruned
+     * with `prune` once a checkpoint covers it).
+     */
+    pub fn checkpoint(ctx: Context<CreateCheckpoint>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            !ctx.remaining_accounts.is_empty()
+                && ctx.remaining_accounts.len() <= MAX_CHECKPOINT_BATCH,
+            ErrorCode::InvalidCheckpoint
+        );
+
+        let mut leaves = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut seen: Vec<Pubkey> = Vec::with_capacity(ctx.remaining_accounts.len());
+        let mut inbound_volume: u64 = 0;
+        let mut outbound_volume: u64 = 0;
+        let mut inbound_nonce_end = ctx.accounts.checkpoint_log.inbound_nonce_end;
+        for info in ctx.remaining_accounts {
+            require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidCheckpoint);
+            require!(!seen.contains(info.key), ErrorCode::InvalidCheckpoint);
+            seen.push(*info.key);
+
+            let record = TransferRecord::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+            require!(record.is_final(), ErrorCode::InvalidTransferStatus);
+
+            match record.direction {
+                TransferDirection::Inbound => {
+                    inbound_volume = math::add(inbound_volume, record.amount)?;
+                    inbound_nonce_end = inbound_nonce_end.max(math::add(record.nonce, 1)?);
+                }
+                TransferDirection::Outbound => {
+                    outbound_volume = math::add(outbound_volume, record.amount)?;
+                }
+            }
+            leaves.push(record.checkpoint_leaf());
+        }
+
+        let log = &mut ctx.accounts.checkpoint_log;
+        let clock = Clock::get()?;
+        let checkpoint = &mut ctx.accounts.checkpoint;
+        checkpoint.index = log.count;
+        checkpoint.root = merkle::root(&leaves);
+        checkpoint.prev_root = log.last_root;
+        checkpoint.transfer_count = leaves.len() as u32;
+        checkpoint.inbound_volume = inbound_volume;
+        checkpoint.outbound_volume = outbound_volume;
+        checkpoint.slot = clock.slot;
+        checkpoint.created_at = clock.unix_timestamp;
+
+        math::increment(&mut log.count)?;
+        log.last_root = checkpoint.root;
+        log.last_slot = clock.slot;
+        log.inbound_nonce_end = inbound_nonce_end;
+
+        emit!(CheckpointCreated {
+            index: checkpoint.index,
+            root: checkpoint.root,
+            transfer_count: checkpoint.transfer_count,
+            slot: checkpoint.slot,
+        });
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::Checkpoint)?;
+
+        msg!(
+            "Checkpoint {} over {} transfers: {}",
+            ctx.accounts.checkpoint.index,
+            ctx.accounts.checkpoint.transfer_count,
+            hex_encode(&ctx.accounts.checkpoint.root)
+        );
+        Ok(())
+    }
+
+    /**
+     * Close a transfer record that's covered by a checkpoint
+     *
+     * `proof` is the record's Merkle proof against the checkpoint root.
+     * Rent goes to the owner.
+     */
+    pub fn prune_transfer_record(
+        ctx: Context<PruneTransferRecord>,
+        checkpoint_index: u64,
+        proof: Vec<[u8; 32]>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let record = &ctx.accounts.transfer_record;
+        require!(
+            merkle::verify(record.checkpoint_leaf(), &proof, &ctx.accounts.checkpoint.root),
+            ErrorCode::InvalidMerkleProof
+        );
+        require!(record.sla_rebate == 0, ErrorCode::InvalidTransferStatus);
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::PruneTransferRecord)?;
+
+        msg!(
+            "Pruned transfer record {} (checkpoint {})",
+            hex_encode(&ctx.accounts.transfer_record.transfer_id),
+            checkpoint_index
+        );
+        Ok(())
+    }
+
+    /**
+     * Appoint the key that records event archives (owner only)
+     *
+     * The indexer's archival job signs `record_archive` with it, so the
+     * owner key doesn't have to sit on the indexer host.
+     */
+    pub fn set_archiver(ctx: Context<SetArchiver>, archiver: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts.archive_log.archiver = archiver;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetArchiver)?;
+
+        msg!("Archiver set to {}", archiver);
+        Ok(())
+    }
+
+    /**
+     * Anchor an off-chain event bundle (archiver or owner)
+     *
+     * `hash` is the SHA-256 of the bundle published at `locator`, which
+     * covers `first_slot..=last_slot`. Ranges must follow on from the
+     * previous archive's, and each archive chains to the one before
+     * through `prev_hash`, so gaps and rewrites both show.
+     */
+    pub fn record_archive(
+        ctx: Context<RecordArchive>,
+        hash: [u8; 32],
+        first_slot: u64,
+        last_slot: u64,
+        locator: String,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        let log = &mut ctx.accounts.archive_log;
+        require!(
+            authority == log.archiver || authority == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            first_slot <= last_slot
+                && (log.count == 0 || first_slot > log.last_slot)
+                && !locator.is_empty()
+                && locator.len() <= MAX_ARCHIVE_LOCATOR_LEN,
+            ErrorCode::InvalidArchive
+        );
+
+        let archive = &mut ctx.accounts.archive;
+        archive.index = log.count;
+        archive.hash = hash;
+        archive.prev_hash = log.last_hash;
+        archive.first_slot = first_slot;
+        archive.last_slot = last_slot;
+        archive.locator = locator;
+        archive.recorded_at = Clock::get()?.unix_timestamp;
+
+        math::increment(&mut log.count)?;
+        log.last_hash = hash;
+        log.last_slot = last_slot;
+
+        emit!(ArchiveRecorded {
+            index: archive.index,
+            hash,
+            first_slot,
+            last_slot,
+            locator: archive.locator.clone(),
+        });
+
+        msg!("Archive {}: {} at {}", archive.index, hex_encode(&hash), archive.locator);
+        Ok(())
+    }
+
+    /**
+     * Point the compressed store at a new, empty tree (owner only)
+     *
+     * The client allocates `merkle_tree` first, owned by the compression
+     * program and sized for `max_depth` and `max_buffer_size` (a pair the
+     * compression program accepts, such as 14/64 or 20/256). Call it again
+     * with a fresh tree once one fills up; records in the old tree stay
+     * provable there.
+     */
+    pub fn init_compressed_store(
+        ctx: Context<InitCompressedStore>,
+        max_depth: u32,
+        max_buffer_size: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!((3..=30).contains(&max_depth), ErrorCode::InvalidCompressedStore);
+
+        compression::init_tree(
+            &ctx.accounts.merkle_tree.to_account_info(),
+            &ctx.accounts.tree_authority.to_account_info(),
+            &ctx.accounts.noop_program.to_account_info(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            ctx.bumps.tree_authority,
+            max_depth,
+            max_buffer_size,
+        )?;
+
+        let store = &mut ctx.accounts.compressed_store;
+        store.tree = ctx.accounts.merkle_tree.key();
+        store.max_depth = max_depth;
+        store.max_buffer_size = max_buffer_size;
+        store.leaves = 0;
+        store.tree_authority_bump = ctx.bumps.tree_authority;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::InitCompressedStore)?;
+
+        msg!(
+            "Compressed store now {} (depth {}, buffer {})",
+            ctx.accounts.merkle_tree.key(),
+            max_depth,
+            max_buffer_size
+        );
+        Ok(())
+    }
+
+    /**
+     * Move a finished transfer record into the compressed store (anyone)
+     *
+     * Appends the record's leaf (`TransferRecord::compressed_leaf`) to the
+     * store's tree, logs the full record in `TransferRecordCompressed` for
+     * the indexer and closes the PDA. As with `prune_transfer_record`,
+     * rent goes to the owner and replay protection doesn't depend on the
+     * record.
+     */
+    pub fn compress_transfer_record(ctx: Context<CompressTransferRecord>) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require!(record.is_final(), ErrorCode::InvalidTransferStatus);
+        // The rebate is owed on the record, so it has to be claimed first
+        require!(record.sla_rebate == 0, ErrorCode::InvalidTransferStatus);
+        require!(
+            !ctx.accounts.compressed_store.is_full(),
+            ErrorCode::CompressedStoreFull
+        );
+
+        let leaf = record.compressed_leaf()?;
+        compression::append(
+            &ctx.accounts.merkle_tree.to_account_info(),
+            &ctx.accounts.tree_authority.to_account_info(),
+            &ctx.accounts.noop_program.to_account_info(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            ctx.accounts.compressed_store.tree_authority_bump,
+            leaf,
+        )?;
+
+        let store = &mut ctx.accounts.compressed_store;
+        let leaf_index = store.leaves;
+        math::increment(&mut store.leaves)?;
+        math::increment(&mut store.compressed)?;
+
+        emit!(TransferRecordCompressed {
+            tree: store.tree,
+            max_depth: store.max_depth,
+            leaf_index,
+            leaf,
+            transfer_id: record.transfer_id,
+            record: record.try_to_vec()?,
+        });
+
+        msg!(
+            "Compressed transfer record {} into leaf {}",
+            hex_encode(&record.transfer_id),
+            leaf_index
+        );
+        Ok(())
+    }
+
+    /**
+     * Check that a compressed transfer record is in a tree
+     *
+     * `record` is the Borsh-serialized record from its
+     * `TransferRecordCompressed` event. The indexer serves it with the
+     * proof (passed as remaining accounts, leaf first) and a recent root.
+     * Fails unless the record is at `leaf_index`.
+     */
+    pub fn verify_compressed_transfer<'info>(
+        ctx: Context<'_, '_, '_, 'info, VerifyCompressedTransfer<'info>>,
+        record: Vec<u8>,
+        root: [u8; 32],
+        leaf_index: u32,
+    ) -> Result<()> {
+        let leaf = keccak::hash(&record).to_bytes();
+        compression::verify_leaf(
+            &ctx.accounts.merkle_tree.to_account_info(),
+            ctx.remaining_accounts,
+            root,
+            leaf,
+            leaf_index,
+        )?;
+
+        let record = TransferRecord::try_from_slice(&record)?;
+        msg!(
+            "Transfer {} ({:?}) is at leaf {} of {}",
+            hex_encode(&record.transfer_id),
+            record.status,
+            leaf_index,
+            ctx.accounts.merkle_tree.key()
+        );
+        Ok(())
+    }
+
+    /**
+     * Drop old inbound nonces from the replay set (anyone)
+     *
+     * Raises `processed_floor` towards `before_nonce` by at most `limit`
+     * nonces; everything below the floor counts as processed without
+     * taking a slot in `processed_nonces`. Every nonce skipped over must
+     * already be processed, so nothing still in flight is treated as
+     * delivered, and `before_nonce` can't pass the newest inbound
+     * transfer in a checkpoint, so replay records only go once the
+     * transfers they cover are committed on-chain.
+     */
+    pub fn prune(ctx: Context<Prune>, before_nonce: u64, limit: u16) -> Result<()> {
+        require!(
+            before_nonce <= ctx.accounts.checkpoint_log.inbound_nonce_end,
+            ErrorCode::NotCheckpointed
+        );
+
+        let bridge_state = &mut ctx.accounts.bridge_state;
+        let floor = bridge_state.processed_floor;
+        let new_floor = before_nonce.min(floor.saturating_add(limit as u64));
+        require!(new_floor > floor, ErrorCode::NothingToPrune);
+
+        // Stored nonces are unique and all at or above the floor, so the
+        // range is fully processed exactly when all of it is removed
+        let stored = bridge_state.processed_nonces.len();
+        bridge_state.processed_nonces.retain(|n| *n >= new_floor);
+        let removed = (stored - bridge_state.processed_nonces.len()) as u64;
+        require!(removed == new_floor - floor, ErrorCode::UnprocessedNonce);
+        bridge_state.processed_floor = new_floor;
+
+        emit!(NoncesPruned {
+            floor: new_floor,
+            removed,
+        });
+
+        msg!("Pruned {} processed nonces; floor is now {}", removed, new_floor);
+        Ok(())
+    }
+
+    /**
+     * Seed a new deployment with an old one's nonces (migrations only)
+     *
+     * Takes over the old program's `route_shards`, so routes opened here
+     * get shards the old one never used, moves the processed floor up to
+     * its `processed_floor`, and adds `processed_nonces` to the replay
+     * set, so inbound transfers the old program completed can't be
+     * replayed here. Send in chunks; every import is refused once
+     * seal_import has run.
+     */
+    pub fn import_state(
+        ctx: Context<ImportState>,
+        source_program: Pubkey,
+        route_shards: u32,
+        processed_floor: u64,
+        processed_nonces: Vec<u64>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        let first = ctx.accounts.migration.source_program == Pubkey::default();
+        ctx.accounts.migration.begin(source_program)?;
+
+        let bridge_state = &mut ctx.accounts.bridge_state;
+        // The old shards come over before any route opens here, or a
+        // shard would number two routes
+        if first {
+            require!(bridge_state.route_shards == 0, ErrorCode::InvalidConfig);
+            require!(route_shards <= MAX_ROUTE_SHARD, ErrorCode::InvalidConfig);
+            bridge_state.route_shards = route_shards;
+        }
+        require!(route_shards <= bridge_state.route_shards, ErrorCode::InvalidConfig);
+        if processed_floor > bridge_state.processed_floor {
+            bridge_state.processed_floor = processed_floor;
+            bridge_state.processed_nonces.retain(|n| *n >= processed_floor);
+        }
+
+        let mut added = 0u64;
+        for processed in processed_nonces {
+            if !bridge_state.is_processed(processed) {
+                bridge_state.processed_nonces.push(processed);
+                added += 1;
+            }
+        }
+        let migration = &mut ctx.accounts.migration;
+        migration.imported_nonces = math::add(migration.imported_nonces, added)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ImportState)?;
+
+        msg!(
+            "Imported {} route shards and {} processed nonces from {}",
+            route_shards,
+            added,
+            source_program
+        );
+        Ok(())
+    }
+
+    /**
+     * Carry a route's sequence over from the old deployment (migrations only)
+     *
+     * The route keeps its shard and continues after `last`, so its next
+     * lock or burn here takes the nonce the old program would have given
+     * it. The shard must be one import_state took over; each route is
+     * imported once, before anyone opens it here.
+     */
+    pub fn import_route_sequence(
+        ctx: Context<ImportRouteSequence>,
+        mint: Pubkey,
+        dest_chain_id: u64,
+        shard: u32,
+        last: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.migration.check_open()?;
+        require!(
+            shard > 0 && shard <= ctx.accounts.bridge_state.route_shards,
+            ErrorCode::InvalidConfig
+        );
+        require!(last < 1 << SEQUENCE_BITS, ErrorCode::InvalidConfig);
+
+        let sequence = &mut ctx.accounts.route_sequence;
+        sequence.mint = mint;
+        sequence.dest_chain_id = dest_chain_id;
+        sequence.last = last;
+        sequence.shard = shard;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ImportState)?;
+
+        msg!(
+            "Imported route sequence for {} to chain {} at {} (shard {})",
+            mint,
+            dest_chain_id,
+            last,
+            shard
+        );
+        Ok(())
+    }
+
+    /**
+     * Carry a user's stats over from the old deployment (migrations only)
+     *
+     * Once per user, before the user has bridged here. Not in the admin
+     * log, which would be flooded; the count is kept in Migration.
+     */
+    pub fn import_user_stats(
+        ctx: Context<ImportUserStats>,
+        user: Pubkey,
+        volume: u64,
+        transfers: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.migration.check_open()?;
+
+        let stats = &mut ctx.accounts.user_stats;
+        require!(stats.volume == 0 && stats.transfers == 0, ErrorCode::AlreadyImported);
+        stats.user = user;
+        stats.volume = volume;
+        stats.transfers = transfers;
+        math::increment(&mut ctx.accounts.migration.imported_users)?;
+
+        msg!("Imported stats for {}: {} over {} transfers", user, volume, transfers);
+        Ok(())
+    }
+
+    /**
+     * Carry a chain's inbox cursor over (migrations only)
+     *
+     * configure_inbox sets the window but clears the delivered bits;
+     * without them, a nonce the old deployment delivered ahead of the
+     * cursor would block the inbox for good.
+     */
+    pub fn import_inbox(
+        ctx: Context<ImportInbox>,
+        chain_id: u64,
+        next_sequence: u64,
+        delivered: u128,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        ctx.accounts.migration.check_open()?;
+
+        let inbox = &mut ctx.accounts.inbox;
+        require!(
+            delivered.checked_shr(inbox.window as u32 + 1).unwrap_or(0) == 0
+                && delivered & 1 == 0,
+            ErrorCode::InvalidConfig
+        );
+        inbox.next_sequence = next_sequence;
+        inbox.delivered = delivered;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ImportState)?;
+
+        msg!("Imported inbox for chain {} at {}", chain_id, next_sequence);
+        Ok(())
+    }
+
+    /**
+     * End the import for good
+     *
+     * After this no import instruction works, so replay protection and
+     * stats can't be rewritten later. Fine to call with nothing imported.
+     */
+    pub fn seal_import(ctx: Context<SealImport>) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        let migration = &mut ctx.accounts.migration;
+        require!(!migration.sealed, ErrorCode::ImportSealed);
+        migration.sealed = true;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SealImport)?;
+
+        msg!(
+            "Import sealed: {} nonces, {} users",
+            migration.imported_nonces,
+            migration.imported_users
+        );
+        Ok(())
+    }
+
+    /**
+     * Queue moving a token's escrow to a successor deployment (owner only)
+     *
+     * For a V2 that replaces this program: `new_vault` must be the
+     * successor's escrow for the token, owned by its bridge authority
+     * (PDA "bridge" of `new_bridge_program`). migrate_escrow can run
+     * ESCROW_MIGRATION_DELAY later; until then the owner or a guardian
+     * quorum can cancel it, and users see it coming.
+     */
+    pub fn queue_escrow_migration(
+        ctx: Context<QueueEscrowMigration>,
+        new_bridge_program: Pubkey,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            new_bridge_program != crate::ID && new_bridge_program != Pubkey::default(),
+            ErrorCode::InvalidMigrationTarget
+        );
+        require_keys_eq!(
+            ctx.accounts.new_vault.owner,
+            EscrowMigration::successor_authority(&new_bridge_program),
+            ErrorCode::InvalidMigrationTarget
+        );
+
+        let migration = &mut ctx.accounts.escrow_migration;
+        require!(
+            migration.executable_at == 0 && migration.migrated_at == 0,
+            ErrorCode::EscrowMigrationPending
+        );
+        let executable_at =
+            math::add_seconds(Clock::get()?.unix_timestamp, ESCROW_MIGRATION_DELAY)?;
+        migration.mint = ctx.accounts.mint.key();
+        migration.new_bridge_program = new_bridge_program;
+        migration.new_vault = ctx.accounts.new_vault.key();
+        migration.executable_at = executable_at;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::QueueEscrowMigration)?;
+
+        emit!(EscrowMigrationQueued {
+            mint: migration.mint,
+            new_bridge_program,
+            new_vault: migration.new_vault,
+            executable_at,
+        });
+
+        msg!(
+            "Escrow of {} migrates to {} from {}",
+            migration.mint,
+            new_bridge_program,
+            executable_at
+        );
+        Ok(())
+    }
+
+    /**
+     * Drop a queued escrow migration (owner or guardian quorum)
+     *
+     * Guardian signers are the remaining accounts. The migration can be
+     * queued again, restarting the timelock.
+     */
+    pub fn cancel_escrow_migration(ctx: Context<CancelEscrowMigration>) -> Result<()> {
+        require!(
+            ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner
+                || GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+
+        let migration = &mut ctx.accounts.escrow_migration;
+        require!(
+            migration.executable_at > 0 && migration.migrated_at == 0,
+            ErrorCode::MigrationNotReady
+        );
+        migration.executable_at = 0;
+
+        emit!(EscrowMigrationCancelled {
+            mint: migration.mint,
+            new_bridge_program: migration.new_bridge_program,
+            cancelled_by: ctx.accounts.authority.key(),
+        });
+        msg!("Escrow migration of {} cancelled", migration.mint);
+        Ok(())
+    }
+
+    /**
+     * Move a token's whole escrow to its successor once the timelock is over (owner only)
+     *
+     * `new_bridge_program` restates the queued target. The escrow, and
+     * the cold vault if the escrow is split, go to the queued new vault,
+     * and the token is paused here for good: the successor takes over
+     * its locks and unlocks. Yield venues have to be recalled first. The
+     * bridge's route shards and processed floor are recorded and
+     * published for the successor's import_state, which takes the
+     * processed nonces from this program's BridgeState and the routes'
+     * sequences from their RouteSequences.
+     */
+    pub fn migrate_escrow<'info>(ctx: Context<'_, '_, '_, 'info, MigrateEscrow<'info>>, new_bridge_program: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        let now = Clock::get()?.unix_timestamp;
+        let migration = &ctx.accounts.escrow_migration;
+        require!(
+            migration.executable_at > 0
+                && migration.migrated_at == 0
+                && now >= migration.executable_at,
+            ErrorCode::MigrationNotReady
+        );
+        require_keys_eq!(
+            migration.new_bridge_program,
+            new_bridge_program,
+            ErrorCode::InvalidMigrationTarget
+        );
+
+        // A split escrow goes whole, so the cold vault has to come along,
+        // and nothing may be left out in yield venues
+        require!(
+            YieldStrategy::deployed_of(&ctx.accounts.yield_strategy.to_account_info())? == 0,
+            ErrorCode::YieldStillDeployed
+        );
+        let vault_config = ctx.accounts.vault_config.to_account_info();
+        VaultConfig::check_hot(&vault_config, &ctx.accounts.escrow.key())?;
+        let cold_vault = if vault_config.data_is_empty() {
+            None
+        } else {
+            let config = VaultConfig::try_deserialize(&mut &vault_config.try_borrow_data()?[..])?;
+            let cold_vault = ctx.accounts.cold_vault.as_ref().ok_or(ErrorCode::InvalidConfig)?;
+            require_keys_eq!(cold_vault.key(), config.cold_vault, ErrorCode::InvalidConfig);
+            Some(cold_vault)
+        };
+
+        ctx.accounts.token_config.paused = true;
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let decimals = ctx.accounts.mint.decimals;
+        let mut amount = 0u64;
+
+        let escrowed = ctx.accounts.escrow.amount;
+        if escrowed > 0 {
+            let seeds = &[
+                b"bridge".as_ref(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.accounts.bridge_state.authority_bump],
+            ];
+            let received = TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.escrow.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.new_vault.to_account_info(),
+                authority: ctx.accounts.bridge_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, escrowed, decimals, &[&seeds[..]])?;
+            amount = math::add(amount, received)?;
+        }
+
+        if let Some(cold_vault) = cold_vault.filter(|vault| vault.amount > 0) {
+            let seeds = &[
+                b"cold_vault".as_ref(),
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.bumps.cold_authority],
+            ];
+            let received = TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: cold_vault.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.new_vault.to_account_info(),
+                authority: ctx.accounts.cold_authority.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, cold_vault.amount, decimals, &[&seeds[..]])?;
+            amount = math::add(amount, received)?;
+        }
+
+        let bridge_state = &ctx.accounts.bridge_state;
+        let migration = &mut ctx.accounts.escrow_migration;
+        migration.migrated_at = now;
+        migration.amount = amount;
+        migration.route_shards = bridge_state.route_shards;
+        migration.processed_floor = bridge_state.processed_floor;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::MigrateEscrow)?;
+
+        emit!(EscrowMigrated {
+            mint: migration.mint,
+            new_bridge_program,
+            new_vault: migration.new_vault,
+            amount,
+            route_shards: migration.route_shards,
+            processed_floor: migration.processed_floor,
+            processed_nonces: bridge_state.processed_nonces.len() as u32,
+        });
+
+        msg!("Migrated {} of {}'s escrow to {}", amount, migration.mint, new_bridge_program);
+        Ok(())
+    }
+
+    /**
+     * Move escrow to another instance of this program (owner and a
+     * guardian quorum)
+     *
+     * For rebalancing side-by-side instances (e.g. staging to
+     * production) without routing through Ethereum: `amount` goes from
+     * this token's escrow straight into `dest_vault`, which must be
+     * owned by instance `dest_instance`'s bridge authority, with no fee.
+     * Both the owner and a quorum of guardian signers in the remaining
+     * accounts have to sign. `id` is the caller's and is unique per
+     * token; the InstanceTransfer it names is what the other instance
+     * reads to record the transfer.
+     */
+    pub fn send_to_instance<'info>(
+        ctx: Context<'_, '_, '_, 'info, SendToInstance<'info>>,
+        id: u64,
+        amount: u64,
+        dest_instance: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner
+                && GuardianSet::has_quorum(
+                    &ctx.accounts.guardian_set.to_account_info(),
+                    ctx.remaining_accounts,
+                )?,
+            ErrorCode::Unauthorized
+        );
+        require!(amount > 0, ErrorCode::AmountTooSmall);
+        require!(
+            dest_instance != ctx.accounts.bridge_state.instance_id,
+            ErrorCode::InvalidInstanceTransfer
+        );
+        let (dest_authority, _) =
+            Pubkey::find_program_address(&[b"bridge", instance::seed(&dest_instance)], &crate::ID);
+        require_keys_eq!(
+            ctx.accounts.dest_vault.owner,
+            dest_authority,
+            ErrorCode::InvalidInstanceTransfer
+        );
+        VaultConfig::check_hot(&ctx.accounts.vault_config, &ctx.accounts.escrow.key())?;
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let seeds = &[
+            b"bridge".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.accounts.bridge_state.authority_bump],
+        ];
+        let received = TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.dest_vault.to_account_info(),
+            authority: ctx.accounts.bridge_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(&extensions, amount, ctx.accounts.mint.decimals, &[&seeds[..]])?;
+
+        let transfer = &mut ctx.accounts.instance_transfer;
+        transfer.id = id;
+        transfer.mint = ctx.accounts.mint.key();
+        transfer.dest_instance = dest_instance;
+        transfer.dest_vault = ctx.accounts.dest_vault.key();
+        transfer.amount = received;
+        transfer.sent_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SendToInstance)?;
+
+        emit!(InstanceTransferSent {
+            id,
+            mint: transfer.mint,
+            dest_instance,
+            dest_vault: transfer.dest_vault,
+            amount: received,
+            sent_by: ctx.accounts.owner.key(),
+        });
+
+        msg!("Sent {} of {} to instance {}", received, transfer.mint, dest_instance);
+        Ok(())
+    }
+
+    /**
+     * Record escrow another instance sent this one (owner only)
+     *
+     * `source_record` is the sender's InstanceTransfer, at its address
+     * for `source_instance`, the token and `id`. Only this program can
+     * have written it, so it is trusted once it names this instance and
+     * one of its escrows. Each transfer is recorded once.
+     */
+    pub fn receive_instance_transfer(
+        ctx: Context<ReceiveInstanceTransfer>,
+        source_instance: u64,
+        mint: Pubkey,
+        id: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            source_instance != ctx.accounts.bridge_state.instance_id,
+            ErrorCode::InvalidInstanceTransfer
+        );
+
+        let sent = &ctx.accounts.source_record;
+        require!(
+            sent.dest_instance == ctx.accounts.bridge_state.instance_id
+                && sent.dest_vault == ctx.accounts.escrow.key(),
+            ErrorCode::InvalidInstanceTransfer
+        );
+
+        let receipt = &mut ctx.accounts.instance_receipt;
+        receipt.source_instance = source_instance;
+        receipt.source_record = sent.key();
+        receipt.amount = sent.amount;
+        receipt.received_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ReceiveFromInstance)?;
+
+        emit!(InstanceTransferReceived {
+            source_instance,
+            id,
+            mint,
+            vault: sent.dest_vault,
+            amount: sent.amount,
+        });
+
+        msg!("Recorded {} of {} from instance {}", sent.amount, mint, source_instance);
+        Ok(())
+    }
+
+    /**
+     * Pin the name a transfer's recipient was resolved from
+     *
+     * - Outbound: the sender pins the ENS name the EVM recipient came
+     *   from, usually in the same transaction as the lock. ENS can't be
+     *   read from Solana, so this records the sender's own resolution.
+     * - Inbound: anyone pins the .sol domain the relayer resolved the
+     *   recipient from, passing its SNS name account, which must be owned
+     *   by the recipient now.
+     *
+     * A record's name is pinned once.
+     */
+    pub fn pin_recipient_name(ctx: Context<PinRecipientName>, name: String) -> Result<()> {
+        require!(
+            !name.is_empty() && name.len() <= names::MAX_RECIPIENT_NAME_LEN,
+            ErrorCode::InvalidRecipientName
+        );
+        let record = &mut ctx.accounts.transfer_record;
+        require!(record.recipient_name.is_empty(), ErrorCode::NameAlreadyPinned);
+
+        match record.direction {
+            TransferDirection::Outbound => require_keys_eq!(
+                ctx.accounts.signer.key(),
+                record.local_account,
+                ErrorCode::Unauthorized
+            ),
+            TransferDirection::Inbound => {
+                let domain = ctx
+                    .accounts
+                    .sns_domain
+                    .as_ref()
+                    .ok_or(ErrorCode::InvalidRecipientName)?;
+                require_keys_eq!(
+                    names::sns_owner(domain, &name)?,
+                    record.local_account,
+                    ErrorCode::NameOwnerMismatch
+                );
+            }
+        }
+
+        record.recipient_name = name;
+        record.updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(RecipientNamePinned {
+            transfer_id: record.transfer_id,
+            direction: record.direction,
+            nonce: record.nonce,
+            name: record.recipient_name.clone(),
+        });
+
+        msg!("Transfer {} recipient name: {}", record.nonce, record.recipient_name);
+        Ok(())
+    }
+
+    /**
+     * Record which source-chain transaction an inbound transfer came from
+     *
+     * By the relayer that delivered it, after the delivery, or by the
+     * owner to fill in older ones. Adds the transfer's nonce to the
+     * transaction's SourceTx, so clients can look transfers up by source
+     * transaction hash. Recording a transfer again does nothing.
+     */
+    pub fn record_source_tx(ctx: Context<RecordSourceTx>, tx_hash: [u8; 32]) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require!(
+            ctx.accounts.authority.key() == record.processed_by
+                || ctx.accounts.authority.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            record.direction == TransferDirection::Inbound && record.processed_at != 0,
+            ErrorCode::InvalidSourceTx
+        );
+
+        let source_tx = &mut ctx.accounts.source_tx;
+        if source_tx.nonces.is_empty() {
+            source_tx.tx_hash = tx_hash;
+            source_tx.chain_id = record.remote_chain_id;
+        }
+        require!(source_tx.chain_id == record.remote_chain_id, ErrorCode::InvalidSourceTx);
+        if source_tx.nonces.contains(&record.nonce) {
+            return Ok(());
+        }
+        require!(
+            source_tx.nonces.len() < MAX_SOURCE_TX_TRANSFERS,
+            ErrorCode::InvalidSourceTx
+        );
+        source_tx.nonces.push(record.nonce);
+
+        emit!(SourceTxRecorded {
+            tx_hash,
+            chain_id: source_tx.chain_id,
+            nonce: record.nonce,
+            transfer_id: record.transfer_id,
+        });
+
+        msg!("Inbound nonce {} came from tx 0x{}", record.nonce, hex_encode(&tx_hash));
+        Ok(())
+    }
+
+    /**
+     * Route an outbound transfer on past its destination chain
+     *
+     * The sender names the chains after the destination and the final
+     * recipient, usually in the same transaction as the lock or burn;
+     * cooperating deployments relay the transfer on from the
+     * TransferRouted event, so the user initiates once. Pass the chain
+     * config of every hop, then of the final chain, as remaining
+     * accounts. A route is set once, before the transfer is attested.
+     */
+    pub fn set_transfer_route(ctx: Context<SetTransferRoute>, route: RoutePayload) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require_keys_eq!(ctx.accounts.sender.key(), record.local_account, ErrorCode::Unauthorized);
+        require!(
+            record.status == TransferStatus::Initiated,
+            ErrorCode::InvalidTransferStatus
+        );
+
+        let chains = ctx
+            .remaining_accounts
+            .iter()
+            .map(|info| {
+                require_keys_eq!(*info.owner, crate::ID, ErrorCode::InvalidRoute);
+                ChainConfig::try_deserialize(&mut &info.try_borrow_data()?[..])
+            })
+            .collect::<Result<Vec<_>>>()?;
+        route.validate(record.remote_chain_id, &chains)?;
+
+        emit!(TransferRouted {
+            transfer_id: record.transfer_id,
+            nonce: record.nonce,
+            dest_chain_id: record.remote_chain_id,
+            hops: route.hops.clone(),
+            final_chain_id: route.final_chain_id,
+            final_recipient: route.final_recipient.clone(),
+        });
+
+        msg!(
+            "Transfer {} routed to {} on chain {}",
+            record.nonce,
+            route.final_recipient,
+            route.final_chain_id
+        );
+
+        let transfer_route = &mut ctx.accounts.transfer_route;
+        transfer_route.transfer_id = record.transfer_id;
+        transfer_route.nonce = record.nonce;
+        transfer_route.route = route;
+        Ok(())
+    }
+
+    /**
+     * Eject a relayer for misbehavior (owner, or a guardian quorum)
+     *
+     * Removes it from the guardian set, bars it from posting attestations
+     * and freezes the bonds of its pending ones: they can't execute, only
+     * be challenged or cancelled. A guardian quorum signs as remaining
+     * accounts; `authority` pays for the ejection record either way.
+     */
+    pub fn eject_relayer(
+        ctx: Context<EjectRelayer>,
+        relayer: Pubkey,
+        reason: String,
+    ) -> Result<()> {
+        require!(reason.len() <= MAX_EJECT_REASON_LEN, ErrorCode::ReasonTooLong);
+
+        let authority = ctx.accounts.authority.key();
+        if authority == ctx.accounts.bridge_state.owner {
+            ctx.accounts.admin_log.record(authority, AdminAction::EjectRelayer)?;
+        } else {
+            let guardian_set = &ctx.accounts.guardian_set;
+            require!(
+                count_guardian_signers(guardian_set, ctx.remaining_accounts)
+                    >= guardian_set.threshold as usize,
+                ErrorCode::Unauthorized
+            );
+        }
+
+        ctx.accounts.guardian_set.remove(&relayer)?;
+        let ejection = &mut ctx.accounts.ejection;
+        ejection.relayer = relayer;
+        ejection.reason = reason;
+        ejection.fraud_nonce = None;
+        ejection.ejected_at = Clock::get()?.unix_timestamp;
+
+        emit!(RelayerEjected {
+            relayer,
+            reason: ejection.reason.clone(),
+            fraud_nonce: None,
+            ejected_by: authority,
+        });
+
+        msg!("Relayer {} ejected: {}", relayer, ejection.reason);
+        Ok(())
+    }
+
+    /**
+     * Register (or update) the caller as a relayer, with its terms
+     *
+     * Anyone not ejected can. `endpoint_url` is where the relayer takes
+     * requests (http or https), `supported_routes` the RelayRoute IDs it
+     * serves (empty = all) and `fee_schedule` what it charges. Nothing
+     * here is enforced on transfers; it's for discovery.
+     */
+    pub fn register_relayer(
+        ctx: Context<RegisterRelayer>,
+        endpoint_url: String,
+        supported_routes: Vec<u32>,
+        fee_schedule: RelayerFeeSchedule,
+    ) -> Result<()> {
+        EjectedRelayer::check_not_ejected(&ctx.accounts.ejection)?;
+        require!(
+            endpoint_url.len() <= MAX_RELAYER_ENDPOINT_LEN
+                && (endpoint_url.starts_with("https://") || endpoint_url.starts_with("http://")),
+            ErrorCode::InvalidRelayerRegistration
+        );
+        require!(
+            supported_routes.len() <= MAX_RELAYER_ROUTES
+                && !supported_routes.contains(&0)
+                && fee_schedule.fee_bps <= 10_000,
+            ErrorCode::InvalidRelayerRegistration
+        );
+
+        let now = Clock::get()?.unix_timestamp;
+        let registration = &mut ctx.accounts.registration;
+        let new = registration.registered_at == 0;
+        if new {
+            registration.relayer = ctx.accounts.relayer.key();
+            registration.registered_at = now;
+        }
+        registration.endpoint_url = endpoint_url;
+        registration.supported_routes = supported_routes;
+        registration.fee_schedule = fee_schedule;
+        registration.updated_at = now;
+
+        emit!(RelayerRegistered {
+            relayer: registration.relayer,
+            endpoint_url: registration.endpoint_url.clone(),
+            supported_routes: registration.supported_routes.clone(),
+            fee_schedule,
+            new,
+        });
+
+        msg!("Relayer {} registered at {}", registration.relayer, registration.endpoint_url);
+        Ok(())
+    }
+
+    /**
+     * Take the caller's relayer registration down, refunding its rent
+     */
+    pub fn deregister_relayer(ctx: Context<DeregisterRelayer>) -> Result<()> {
+        let relayer = ctx.accounts.relayer.key();
+        emit!(RelayerDeregistered { relayer });
+        msg!("Relayer {} deregistered", relayer);
+        Ok(())
+    }
+
+    /**
+     * Claim an integrator ID for attributing transfers (see IntegratorStats)
+     *
+     * First come, first served; 0 is reserved for untagged transfers.
+     */
+    pub fn register_integrator(ctx: Context<RegisterIntegrator>, integrator_id: u32) -> Result<()> {
+        require!(integrator_id != 0, ErrorCode::InvalidIntegrator);
+
+        let integrator = &mut ctx.accounts.integrator;
+        integrator.integrator_id = integrator_id;
+        integrator.authority = ctx.accounts.authority.key();
+
+        emit!(IntegratorRegistered {
+            integrator_id,
+            authority: integrator.authority,
+        });
+
+        msg!("Integrator {} registered to {}", integrator_id, integrator.authority);
+        Ok(())
+    }
+
+    /**
+     * Lift an ejection (owner only)
+     *
+     * Unfreezes the relayer's pending bonds and lets it post again; it
+     * rejoins the guardian set only through set_guardians.
+     */
+    pub fn reinstate_relayer(ctx: Context<ReinstateRelayer>, relayer: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::ReinstateRelayer)?;
+
+        emit!(RelayerReinstated { relayer });
+
+        msg!("Relayer {} reinstated", relayer);
+        Ok(())
+    }
+
+    /**
+     * Set the fee for one token to one destination chain (owner only)
+     *
+     * Replaces the chain's default `base_fee`/`fee_bps` for locks of
+     * `mint` to `chain_id`.
+     */
+    pub fn set_fee_route(
+        ctx: Context<SetFeeRoute>,
+        mint: Pubkey,
+        chain_id: u64,
+        base_fee: u64,
+        fee_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(fee_bps <= 10_000, ErrorCode::InvalidConfig);
+
+        let route = &mut ctx.accounts.fee_route;
+        route.mint = mint;
+        route.chain_id = chain_id;
+        route.base_fee = base_fee;
+        route.fee_bps = fee_bps;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetFeeRoute)?;
+
+        msg!(
+            "Fee for {} to chain {}: {} + {} bps",
+            mint,
+            chain_id,
+            base_fee,
+            fee_bps
+        );
+        Ok(())
+    }
+
+    /**
+     * Drop a token's fee route; its locks pay the chain default again
+     */
+    pub fn remove_fee_route(
+        ctx: Context<RemoveFeeRoute>,
+        mint: Pubkey,
+        chain_id: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RemoveFeeRoute)?;
+
+        msg!("Fee route for {} to chain {} removed", mint, chain_id);
+        Ok(())
+    }
+
+    /**
+     * Let locks of a token pay their fee in SOL (owner only)
+     *
+     * `feed_id` and `sol_feed_id` are the Pyth USD price feeds of the
+     * token and of SOL; a `feed_id` of all zeros turns SOL fees off for
+     * the token. `markup_bps` is charged on top of the converted fee.
+     */
+    pub fn set_sol_fee(
+        ctx: Context<SetSolFee>,
+        feed_id: [u8; 32],
+        sol_feed_id: [u8; 32],
+        markup_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(markup_bps as u64 <= math::BPS_DENOMINATOR, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.sol_fee_config;
+        config.mint = ctx.accounts.mint.key();
+        config.feed_id = feed_id;
+        config.sol_feed_id = sol_feed_id;
+        config.markup_bps = markup_bps;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetSolFee)?;
+
+        msg!(
+            "SOL fees for {}: {} (+{} bps)",
+            config.mint,
+            if feed_id == [0u8; 32] { "off" } else { "on" },
+            markup_bps
+        );
+        Ok(())
+    }
+
+    /**
+     * Withdraw fees paid in SOL (owner only)
+     *
+     * The vault keeps its rent-exempt minimum.
+     */
+    pub fn withdraw_sol_fees(ctx: Context<WithdrawSolFees>, amount: u64) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let vault = &ctx.accounts.sol_fee_vault;
+        require_ctx!(
+            vault.lamports() >= math::add(amount, Rent::get()?.minimum_balance(0))?,
+            ErrorCode::InsufficientSolFees,
+            amount = amount,
+            balance = vault.lamports()
+        );
+
+        let seeds = &[
+            b"sol_fee_vault".as_ref(),
+            instance::seed(&ctx.accounts.bridge_state.instance_id),
+            &[ctx.bumps.sol_fee_vault],
+        ];
+        let signer: &[&[&[u8]]] = &[&seeds[..]];
+        let cpi_ctx = CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: ctx.accounts.sol_fee_vault.to_account_info(),
+                to: ctx.accounts.destination.to_account_info(),
+            },
+            signer,
+        );
+        system_program::transfer(cpi_ctx, amount)?;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::WithdrawSolFees)?;
+
+        msg!("Withdrew {} lamports in SOL fees", amount);
+        Ok(())
+    }
+
+    /**
+     * Let locks to a chain prepay delivery there (owner only)
+     *
+     * `native_feed_id` and `sol_feed_id` are the Pyth USD price feeds of
+     * the chain's native token and of SOL; a `native_feed_id` of all zeros
+     * turns prepayment off. `gas_limit` is what one delivery takes, and
+     * `markup_bps` covers the gas price rising before it lands. Prepayment
+     * stays off until a gas price is set (`set_delivery_gas_price`).
+     */
+    pub fn set_delivery_fee(
+        ctx: Context<SetDeliveryFee>,
+        chain_id: u64,
+        native_feed_id: [u8; 32],
+        native_decimals: u8,
+        sol_feed_id: [u8; 32],
+        gas_limit: u64,
+        markup_bps: u16,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(markup_bps as u64 <= math::BPS_DENOMINATOR, ErrorCode::InvalidConfig);
+
+        let config = &mut ctx.accounts.delivery_fee_config;
+        config.chain_id = chain_id;
+        config.native_feed_id = native_feed_id;
+        config.native_decimals = native_decimals;
+        config.sol_feed_id = sol_feed_id;
+        config.gas_limit = gas_limit;
+        config.markup_bps = markup_bps;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetDeliveryFee)?;
+
+        msg!(
+            "Delivery prepayment to chain {}: {} ({} gas, +{} bps)",
+            chain_id,
+            if native_feed_id == [0u8; 32] { "off" } else { "on" },
+            gas_limit,
+            markup_bps
+        );
+        Ok(())
+    }
+
+    /**
+     * Update the gas price delivery prepayments to a chain are quoted at
+     * (owner or fee manager)
+     *
+     * In the smallest units of the chain's native token per gas (wei for
+     * EVM chains); the fee manager's keeper follows the chain's own.
+     */
+    pub fn set_delivery_gas_price(
+        ctx: Context<SetDeliveryGasPrice>,
+        chain_id: u64,
+        gas_price: u64,
+    ) -> Result<()> {
+        let authority = ctx.accounts.authority.key();
+        require!(
+            ctx.accounts.bridge_state.is_fee_manager(&authority),
+            ErrorCode::Unauthorized
+        );
+
+        let config = &mut ctx.accounts.delivery_fee_config;
+        config.gas_price = gas_price;
+        config.gas_price_updated_at = Clock::get()?.unix_timestamp;
+
+        emit!(DeliveryGasPriceSet {
+            chain_id,
+            gas_price,
+            set_by: authority,
+        });
+        Ok(())
+    }
+
+    /**
+     * Configure a token's price circuit breaker (owner only)
+     *
+     * `feed_id` is the token's Pyth USD feed; all zeros turns the breaker
+     * off. A price more than `max_move_bps` from the first one of a
+     * `window` (seconds) trips it for `cooldown` seconds. Setting it
+     * again starts a new window and clears a trip.
+     */
+    pub fn set_price_breaker(
+        ctx: Context<SetPriceBreaker>,
+        feed_id: [u8; 32],
+        max_move_bps: u16,
+        window: i64,
+        cooldown: i64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            max_move_bps > 0
+                && max_move_bps as u64 <= math::BPS_DENOMINATOR
+                && window > 0
+                && cooldown >= 0,
+            ErrorCode::InvalidConfig
+        );
+
+        let breaker = &mut ctx.accounts.price_breaker;
+        breaker.mint = ctx.accounts.mint.key();
+        breaker.feed_id = feed_id;
+        breaker.max_move_bps = max_move_bps;
+        breaker.window = window;
+        breaker.cooldown = cooldown;
+        breaker.reference_at = 0;
+        breaker.tripped_until = 0;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetPriceBreaker)?;
+
+        msg!(
+            "Price breaker for {}: {} ({} bps in {}s, {}s cooldown)",
+            breaker.mint,
+            if feed_id == [0u8; 32] { "off" } else { "on" },
+            max_move_bps,
+            window,
+            cooldown
+        );
+        Ok(())
+    }
+
+    /**
+     * Feed a token's price breaker a fresh Pyth price (permissionless crank)
+     *
+     * Keepers call this often, and on every large move: the breaker only
+     * sees the prices it's given. Trips emit PriceBreakerTripped; a
+     * breaker already tripped keeps its cooldown.
+     */
+    pub fn poke_price_breaker(ctx: Context<PokePriceBreaker>) -> Result<()> {
+        let breaker = &mut ctx.accounts.price_breaker;
+        let now = Clock::get()?.unix_timestamp;
+        if now < breaker.tripped_until {
+            return Ok(());
+        }
+
+        let price = pyth::load_price(&ctx.accounts.price_update, &breaker.feed_id, now)?;
+        if let Some(move_bps) = breaker.observe(&price, now)? {
+            emit!(PriceBreakerTripped {
+                mint: breaker.mint,
+                reference_price: breaker.reference_price,
+                price: price.price,
+                exponent: price.exponent,
+                move_bps,
+                tripped_until: breaker.tripped_until,
+            });
+            msg!("Price breaker for {} tripped: moved {} bps", breaker.mint, move_bps);
+        }
+        Ok(())
+    }
+
+    /**
+     * Require wallets to acknowledge terms of use before their first lock
+     * (owner only)
+     *
+     * `terms_hash` identifies the document. Raising `version` makes every
+     * wallet acknowledge again; 0 requires nothing.
+     */
+    pub fn set_terms(ctx: Context<SetTerms>, version: u32, terms_hash: [u8; 32]) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let terms = &mut ctx.accounts.terms_config;
+        terms.version = version;
+        terms.terms_hash = terms_hash;
+        terms.updated_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetTerms)?;
+
+        emit!(TermsSet {
+            version,
+            terms_hash,
+            set_by: ctx.accounts.owner.key(),
+        });
+        Ok(())
+    }
+
+    /**
+     * Acknowledge the bridge's terms of use (any wallet, once per version)
+     *
+     * `version` must be the one currently required, so a wallet only
+     * ever acknowledges the document it was shown. Locks check the
+     * acknowledgement while the deployment requires one (see TermsConfig).
+     */
+    pub fn acknowledge_terms(ctx: Context<AcknowledgeTerms>, version: u32) -> Result<()> {
+        let terms = &ctx.accounts.terms_config;
+        require_ctx!(
+            version != 0 && version == terms.version,
+            ErrorCode::TermsVersionMismatch,
+            version = version,
+            required = terms.version
+        );
+
+        let acceptance = &mut ctx.accounts.terms_acceptance;
+        acceptance.wallet = ctx.accounts.wallet.key();
+        acceptance.version = version;
+        acceptance.terms_hash = terms.terms_hash;
+        acceptance.accepted_at = Clock::get()?.unix_timestamp;
+
+        emit!(TermsAcknowledged {
+            wallet: acceptance.wallet,
+            version,
+            terms_hash: terms.terms_hash,
+        });
+        Ok(())
+    }
+
+    /**
+     * Switch closed user group mode for the whole bridge or for some
+     * tokens (owner only)
+     *
+     * With `all_tokens`, or for the tokens in `mints`, only allowlisted
+     * senders can lock and burn. `all_tokens` off and no mints gates
+     * nothing. Senders are added with `allow_sender`.
+     */
+    pub fn set_sender_allowlist(
+        ctx: Context<SetSenderAllowlist>,
+        all_tokens: bool,
+        mints: Vec<Pubkey>,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(mints.len() <= MAX_ALLOWLISTED_MINTS, ErrorCode::InvalidConfig);
+
+        let allowlist = &mut ctx.accounts.sender_allowlist;
+        allowlist.all_tokens = all_tokens;
+        allowlist.mints = mints.clone();
+        allowlist.updated_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetSenderAllowlist)?;
+
+        emit!(SenderAllowlistSet {
+            all_tokens,
+            mints,
+            set_by: ctx.accounts.owner.key(),
+        });
+        Ok(())
+    }
+
+    /// Put `sender` on the allowlist (owner only)
+    pub fn allow_sender(ctx: Context<AllowSender>, sender: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let entry = &mut ctx.accounts.allowed_sender;
+        entry.sender = sender;
+        entry.added_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::AllowSender)?;
+
+        emit!(SenderAllowlisted {
+            sender,
+            allowed: true,
+        });
+        Ok(())
+    }
+
+    /// Take `sender` off the allowlist, refunding its entry's rent (owner only)
+    pub fn disallow_sender(ctx: Context<DisallowSender>, sender: Pubkey) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::DisallowSender)?;
+
+        emit!(SenderAllowlisted {
+            sender,
+            allowed: false,
+        });
+        Ok(())
+    }
+
+    /**
+     * Point the caller's deposit address at a destination
+     *
+     * The deposit address is the PDA ["deposit", user]; anything sent to
+     * its token accounts afterwards is locked to `recipient` on
+     * `dest_chain_id` by `sweep_deposit`. Calling again changes the
+     * destination for what is swept from then on.
+     */
+    pub fn register_deposit_address(
+        ctx: Context<RegisterDepositAddress>,
+        dest_chain_id: u64,
+        recipient: String,
+    ) -> Result<()> {
+        let chain_config = &ctx.accounts.chain_config;
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+
+        let user = ctx.accounts.user.key();
+        let deposit_address = &mut ctx.accounts.deposit_address;
+        if deposit_address.user == Pubkey::default() {
+            deposit_address.user = user;
+            deposit_address.registered_at = Clock::get()?.unix_timestamp;
+            deposit_address.bump = ctx.bumps.deposit_address;
+        }
+        deposit_address.dest_chain_id = dest_chain_id;
+        deposit_address.recipient = recipient.clone();
+
+        emit!(DepositAddressRegistered {
+            user,
+            deposit_address: deposit_address.key(),
+            dest_chain_id,
+            recipient,
+        });
+        Ok(())
+    }
+
+    /**
+     * Lock what a deposit address holds of `mint` to its destination
+     * (permissionless)
+     *
+     * A crank for "just send to this address" bridging. The whole
+     * balance of `deposit_token` is locked as `user` would lock it with
+     * `lock`: the user's fee tier, exemption, credential limits, terms
+     * and allowlist apply, the record is theirs and refunds go to them.
+     * The cranker only pays the record's rent. There is no quote, SOL
+     * fee, delivery prepayment or receipt; the bridge pass isn't seen.
+     * Remaining accounts: transfer-hook accounts, if any.
+     */
+    pub fn sweep_deposit<'info>(ctx: Context<'_, '_, '_, 'info, SweepDeposit<'info>>, user: Pubkey) -> Result<()> {
+        let bridge_state = &ctx.accounts.bridge_state;
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        PriceBreaker::check(&ctx.accounts.price_breaker, Clock::get()?.unix_timestamp)?;
+        TermsConfig::check(&ctx.accounts.terms_config, &ctx.accounts.terms_acceptance, &user)?;
+        SenderAllowlist::check(
+            &ctx.accounts.sender_allowlist,
+            &ctx.accounts.allowed_sender,
+            &ctx.accounts.mint.key(),
+            &user,
+        )?;
+
+        let amount = ctx.accounts.deposit_token.amount;
+        require!(amount > 0, ErrorCode::NothingToSweep);
+
+        // Checked again: the chain's rules may have changed since registration
+        let chain_config = &ctx.accounts.chain_config;
+        let dest_chain_id = ctx.accounts.deposit_address.dest_chain_id;
+        let recipient = ctx.accounts.deposit_address.recipient.clone();
+        require!(chain_config.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(chain_config, &recipient)?;
+        require_ctx!(
+            amount >= chain_config.min_amount,
+            ErrorCode::AmountTooSmall,
+            amount = amount,
+            minimum = chain_config.min_amount
+        );
+        credential::check_with_boost(
+            &ctx.accounts.token_config,
+            ctx.accounts.credential.as_deref(),
+            &user,
+            amount,
+            0,
+        )?;
+
+        let surcharge_bps = Throttle::admit(&ctx.accounts.throttle, 1)?;
+        let route = FeeRoute::load(&ctx.accounts.fee_route)?;
+        let (fee_tier, discount_bps) =
+            ctx.accounts.fee_tiers.tier_for(ctx.accounts.user_stats.volume);
+        let fee = if FeeExemption::is_exempt(&ctx.accounts.fee_exemption)? {
+            0
+        } else {
+            let fee = chain_config.fee(route.as_ref(), amount, discount_bps)?;
+            math::add(fee, math::bps_of(amount, surcharge_bps)?)?
+        };
+        require_ctx!(amount > fee, ErrorCode::AmountTooSmall, amount = amount, fee = fee);
+        ctx.accounts.user_stats.record(user, amount)?;
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let decimals = ctx.accounts.mint.decimals;
+        let token_config = &ctx.accounts.token_config;
+        let (escrowed, dust) = token_config.split_dust(math::sub(amount, fee)?, decimals)?;
+        let vault_amount = match token_config.dust_policy {
+            DustPolicy::Treasury => math::add(fee, dust)?,
+            _ => fee,
+        };
+
+        let bump = ctx.accounts.deposit_address.bump;
+        let deposit_seeds: &[&[u8]] = &[
+            b"deposit",
+            instance::seed(&bridge_state.instance_id),
+            user.as_ref(),
+            &[bump],
+        ];
+        if vault_amount > 0 {
+            TokenTransfer {
+                token_program: ctx.accounts.token_program.to_account_info(),
+                from: ctx.accounts.deposit_token.to_account_info(),
+                mint: ctx.accounts.mint.to_account_info(),
+                to: ctx.accounts.fee_vault.to_account_info(),
+                authority: ctx.accounts.deposit_address.to_account_info(),
+                extra_accounts: ctx.remaining_accounts,
+            }
+            .execute(&extensions, vault_amount, decimals, &[deposit_seeds])?;
+        }
+        let net_amount = TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.deposit_token.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.bridge_token.to_account_info(),
+            authority: ctx.accounts.deposit_address.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(&extensions, escrowed, decimals, &[deposit_seeds])?;
+
+        let net_amount = ctx
+            .accounts
+            .token_config
+            .to_bridged(ctx.accounts.conversion_rate.as_deref(), net_amount)?;
+        require!(net_amount > 0, ErrorCode::AmountTooSmall);
+
+        let (current_nonce, sequence) = ctx.accounts.route_sequence.take()?;
+        let mint = ctx.accounts.mint.key();
+        let guardian_set_index =
+            GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?;
+        let transfer_id = transfer_id(
+            chain_ids::SOLANA,
+            dest_chain_id,
+            guardian_set_index,
+            current_nonce,
+            &mint,
+            net_amount,
+            &user,
+            &recipient,
+        );
+
+        let transfer_record = &mut ctx.accounts.transfer_record;
+        transfer_record.open_outbound(
+            transfer_id,
+            current_nonce,
+            dest_chain_id,
+            user,
+            recipient.clone(),
+            net_amount,
+        )?;
+        transfer_record.mint = mint;
+        transfer_record.escrowed = true;
+        transfer_record.fee = fee;
+
+        let route_id = RelayRoute::id_of(&ctx.accounts.relay_route)?;
+        ctx.accounts
+            .outbox
+            .load_mut()?
+            .push(OutboxKind::Lock, transfer_record, mint, route_id)?;
+        transfer_record.route_sequence = sequence;
+        EventHistory::record(&ctx.accounts.event_history, transfer_record)?;
+        math::increment(&mut ctx.accounts.deposit_address.sweeps)?;
+
+        let transfer_record = &ctx.accounts.transfer_record;
+        LockEvent {
+            transfer_id,
+            from: user,
+            amount: net_amount,
+            fee,
+            fee_tier,
+            nonce: current_nonce,
+            dest_chain_id,
+            recipient,
+            timestamp: transfer_record.created_at,
+            mint,
+            guardian_set_index,
+            required_confirmations: chain_config.required_confirmations,
+            route_id,
+            integrator_id: 0,
+        }
+        .emit(ctx.accounts.bridge_state.event_encoding, chain_config)?;
+
+        emit!(RouteSequenced {
+            transfer_id,
+            nonce: current_nonce,
+            mint,
+            dest_chain_id,
+            sequence,
+        });
+
+        if dust > 0 {
+            emit!(DustHandled {
+                transfer_id,
+                nonce: current_nonce,
+                mint,
+                policy: ctx.accounts.token_config.dust_policy,
+                dust,
+            });
+        }
+
+        emit!(DepositSwept {
+            transfer_id,
+            nonce: current_nonce,
+            user,
+            deposit_address: ctx.accounts.deposit_address.key(),
+            mint,
+            amount,
+            cranker: ctx.accounts.cranker.key(),
+        });
+
+        msg!(
+            "Swept {} tokens from {}'s deposit address for {} on chain {} (nonce: {})",
+            amount,
+            user,
+            transfer_record.remote_address,
+            dest_chain_id,
+            current_nonce
+        );
+        Ok(())
+    }
+
+    /**
+     * Take back what the caller's deposit address holds of a token
+     *
+     * For tokens the bridge doesn't list, or that were sent by mistake:
+     * the whole balance of `deposit_token` goes to `destination`. Remaining
+     * accounts: transfer-hook accounts, if any.
+     */
+    pub fn withdraw_deposit<'info>(ctx: Context<'_, '_, '_, 'info, WithdrawDeposit<'info>>) -> Result<()> {
+        let amount = ctx.accounts.deposit_token.amount;
+        require!(amount > 0, ErrorCode::NothingToSweep);
+
+        let extensions =
+            token_ext::inspect(&ctx.accounts.mint.to_account_info(), token_extensions::ALL)?;
+        let user = ctx.accounts.user.key();
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.deposit_token.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.destination.to_account_info(),
+            authority: ctx.accounts.deposit_address.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(
+            &extensions,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[&[
+                b"deposit",
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                user.as_ref(),
+                &[ctx.accounts.deposit_address.bump],
+            ]],
+        )?;
+
+        emit!(DepositWithdrawn {
+            user,
+            mint: ctx.accounts.mint.key(),
+            amount,
+        });
+        Ok(())
+    }
+
+    /**
+     * Approve `recipient` on `dest_chain_id` as a destination for
+     * `user`'s locks and burns
+     *
+     * The first call, signed by the user, creates their withdrawal
+     * allowlist with the address active at once; from then on locks and
+     * burns only go to its active addresses. Later additions are signed
+     * by the list's authority and wait WITHDRAWAL_ADDRESS_DELAY. Adding
+     * an address already on the list changes nothing.
+     */
+    pub fn add_withdrawal_address(
+        ctx: Context<AddWithdrawalAddress>,
+        user: Pubkey,
+        dest_chain_id: u64,
+        recipient: String,
+    ) -> Result<()> {
+        let chain_config = &ctx.accounts.chain_config;
+        recipient::validate(chain_config, &recipient)?;
+        let address = recipient::address_bytes(chain_config, &recipient)?;
+
+        let now = Clock::get()?.unix_timestamp;
+        let authority = ctx.accounts.authority.key();
+        let allowlist = &mut ctx.accounts.withdrawal_allowlist;
+        let active_at = if allowlist.user == Pubkey::default() {
+            require_keys_eq!(authority, user, ErrorCode::Unauthorized);
+            allowlist.user = user;
+            allowlist.authority = user;
+            allowlist.bump = ctx.bumps.withdrawal_allowlist;
+            now
+        } else {
+            require_keys_eq!(authority, allowlist.authority, ErrorCode::Unauthorized);
+            math::add_seconds(now, WITHDRAWAL_ADDRESS_DELAY)?
+        };
+
+        if allowlist
+            .addresses
+            .iter()
+            .any(|entry| entry.dest_chain_id == dest_chain_id && entry.address == address)
+        {
+            return Ok(());
+        }
+        require!(
+            allowlist.addresses.len() < MAX_WITHDRAWAL_ADDRESSES,
+            ErrorCode::WithdrawalAllowlistFull
+        );
+        allowlist.addresses.push(WithdrawalAddress {
+            dest_chain_id,
+            address,
+            active_at,
+        });
+
+        emit!(WithdrawalAddressAdded {
+            user,
+            dest_chain_id,
+            recipient,
+            active_at,
+        });
+        Ok(())
+    }
+
+    /**
+     * Take `recipient` on `dest_chain_id` off `user`'s withdrawal
+     * allowlist (its authority only)
+     *
+     * Takes effect at once, pending or not. The list stays in force even
+     * when emptied, refusing every lock and burn.
+     */
+    pub fn remove_withdrawal_address(
+        ctx: Context<RemoveWithdrawalAddress>,
+        user: Pubkey,
+        dest_chain_id: u64,
+        recipient: String,
+    ) -> Result<()> {
+        let address = recipient::address_bytes(&ctx.accounts.chain_config, &recipient)?;
+        let allowlist = &mut ctx.accounts.withdrawal_allowlist;
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            allowlist.authority,
+            ErrorCode::Unauthorized
+        );
+
+        let before = allowlist.addresses.len();
+        allowlist
+            .addresses
+            .retain(|entry| entry.dest_chain_id != dest_chain_id || entry.address != address);
+        require!(
+            allowlist.addresses.len() < before,
+            ErrorCode::RecipientNotWhitelisted
+        );
+
+        emit!(WithdrawalAddressRemoved {
+            user,
+            dest_chain_id,
+            recipient,
+        });
+        Ok(())
+    }
+
+    /**
+     * Hand `user`'s withdrawal allowlist to a new authority (its current
+     * authority only)
+     *
+     * Typically a custodian or multisig, so that a compromised wallet key
+     * can't change the list.
+     */
+    pub fn set_withdrawal_authority(
+        ctx: Context<SetWithdrawalAuthority>,
+        user: Pubkey,
+        authority: Pubkey,
+    ) -> Result<()> {
+        let allowlist = &mut ctx.accounts.withdrawal_allowlist;
+        require_keys_eq!(
+            ctx.accounts.authority.key(),
+            allowlist.authority,
+            ErrorCode::Unauthorized
+        );
+        allowlist.authority = authority;
+
+        emit!(WithdrawalAuthoritySet { user, authority });
+        Ok(())
+    }
+
+    /**
+     * Set the outbound delivery SLA (owner only)
+     *
+     * Transfers acknowledged more than `max_latency_slots` after their
+     * lock earn the sender `rebate_bps` of their fee back (see SlaConfig);
+     * 0 slots turns it off. Only applies to acknowledgements from now on.
+     */
+    pub fn set_sla(ctx: Context<SetSla>, max_latency_slots: u64, rebate_bps: u16) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(
+            rebate_bps as u64 <= math::BPS_DENOMINATOR,
+            ErrorCode::InvalidConfig
+        );
+
+        let sla = &mut ctx.accounts.sla_config;
+        sla.max_latency_slots = max_latency_slots;
+        sla.rebate_bps = rebate_bps;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetSla)?;
+
+        emit!(SlaSet {
+            max_latency_slots,
+            rebate_bps,
+            set_by: ctx.accounts.owner.key(),
+        });
+        Ok(())
+    }
+
+    /**
+     * Claim the fee rebate a late transfer earned (sender only)
+     *
+     * Paid out of the token's fee vault, to the sender's token account.
+     */
+    pub fn claim_sla_rebate<'info>(ctx: Context<'_, '_, '_, 'info, ClaimSlaRebate<'info>>, nonce: u64) -> Result<()> {
+        let record = &ctx.accounts.transfer_record;
+        require_keys_eq!(
+            ctx.accounts.sender.key(),
+            record.local_account,
+            ErrorCode::Unauthorized
+        );
+        let amount = record.sla_rebate;
+        require!(amount > 0, ErrorCode::NoSlaRebate);
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.fee_vault.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.sender_token.to_account_info(),
+            authority: ctx.accounts.bridge_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(
+            &extensions,
+            amount,
+            ctx.accounts.mint.decimals,
+            &[&[
+                b"bridge",
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.bumps.bridge_authority],
+            ]],
+        )?;
+
+        let record = &mut ctx.accounts.transfer_record;
+        record.sla_rebate = 0;
+
+        emit!(SlaRebateClaimed {
+            transfer_id: record.transfer_id,
+            nonce,
+            sender: record.local_account,
+            mint: record.mint,
+            amount,
+        });
+        Ok(())
+    }
+
+    /**
+     * Give one token to one destination chain a relay route (owner only)
+     *
+     * Locks and burns of `mint` to `chain_id` then carry `route_id` in
+     * their events and outbox entry, for relayers that only serve some
+     * routes (see RELAY_ROUTES in the relayer). IDs are the operators'
+     * to allocate; several pairs may share one.
+     */
+    pub fn set_relay_route(
+        ctx: Context<SetRelayRoute>,
+        mint: Pubkey,
+        chain_id: u64,
+        route_id: u32,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+        require!(route_id != 0, ErrorCode::InvalidConfig);
+
+        let route = &mut ctx.accounts.relay_route;
+        route.mint = mint;
+        route.chain_id = chain_id;
+        route.route_id = route_id;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::SetRelayRoute)?;
+
+        msg!("Transfers of {} to chain {} on route {}", mint, chain_id, route_id);
+        Ok(())
+    }
+
+    /**
+     * Drop a token's relay route; its transfers get route 0 again
+     */
+    pub fn remove_relay_route(
+        ctx: Context<RemoveRelayRoute>,
+        mint: Pubkey,
+        chain_id: u64,
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RemoveRelayRoute)?;
+
+        msg!("Relay route for {} to chain {} removed", mint, chain_id);
+        Ok(())
+    }
+
+    /**
+     * Allow messages to `target` on `chain_id` (owner only)
+     *
+     * `target` is the contract's address in 32 bytes, EVM addresses
+     * left-padded with zeros. Until message passing leaves its early
+     * rollout, a message may only be sent to an allowed target, so the
+     * bridge can't be used to spoof calls into arbitrary contracts.
+     */
+    pub fn allow_message_target(
+        ctx: Context<AllowMessageTarget>,
+        chain_id: u64,
+        target: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        let allowed = &mut ctx.accounts.message_target;
+        allowed.chain_id = chain_id;
+        allowed.target = target;
+        allowed.allowed_at = Clock::get()?.unix_timestamp;
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::AllowMessageTarget)?;
+
+        msg!("Messages to {} on chain {} allowed", hex_encode(&target), chain_id);
+        Ok(())
+    }
+
+    /**
+     * Take a target off the message allowlist
+     */
+    pub fn remove_message_target(
+        ctx: Context<RemoveMessageTarget>,
+        chain_id: u64,
+        target: [u8; 32],
+    ) -> Result<()> {
+        require!(
+            ctx.accounts.owner.key() == ctx.accounts.bridge_state.owner,
+            ErrorCode::Unauthorized
+        );
+
+        ctx.accounts
+            .admin_log
+            .record(ctx.accounts.owner.key(), AdminAction::RemoveMessageTarget)?;
+
+        msg!("Messages to {} on chain {} no longer allowed", hex_encode(&target), chain_id);
+        Ok(())
+    }
+
+    /**
+     * Send `payload` to `target` on `dest_chain_id`
+     *
+     * The pair must be on the allowlist (allow_message_target). The
+     * message goes into the outbox as a Message entry under the sender's
+     * key, and the payload is logged in MessageSent for the relayer.
+     */
+    pub fn send_message(
+        ctx: Context<SendMessage>,
+        dest_chain_id: u64,
+        target: [u8; 32],
+        payload: Vec<u8>,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(ctx.accounts.chain_config.enabled, ErrorCode::ChainDisabled);
+        MessageTarget::check_allowed(&ctx.accounts.message_target.to_account_info())?;
+        require!(
+            !payload.is_empty() && payload.len() <= MAX_MESSAGE_LEN,
+            ErrorCode::MessageTooLong
+        );
+
+        let sender = ctx.accounts.sender.key();
+        let (sequence, message_hash) = ctx
+            .accounts
+            .outbox
+            .load_mut()?
+            .push_message(sender, dest_chain_id, target, &payload)?;
+
+        emit!(MessageSent {
+            sender,
+            dest_chain_id,
+            target,
+            payload,
+            sequence,
+            message_hash,
+        });
+
+        msg!(
+            "Message to {} on chain {} queued (outbox #{})",
+            hex_encode(&target),
+            dest_chain_id,
+            sequence
+        );
+        Ok(())
+    }
+
+    /**
+     * Escrow tokens as an OTC order for a direct cross-chain swap
+     *
+     * `order_id` must be the book's next ID. The maker's tokens go into
+     * the mint's OTC escrow (owned by the "otc_escrow" PDA, not the bridge
+     * authority) until a taker proves payment on the counter chain
+     * (`fill_otc_order`) or, after the expiry, the maker cancels. With a
+     * split escrow, no order may be larger than the hot cap.
+     */
+    pub fn create_otc_order<'info>(
+        ctx: Context<'_, '_, '_, 'info, CreateOtcOrder<'info>>,
+        order_id: u64,
+        amount: u64,
+        terms: OtcTerms,
+    ) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+
+        let book = &mut ctx.accounts.otc_book;
+        require!(order_id == book.next_order_id, ErrorCode::InvalidOtcOrder);
+
+        let now = Clock::get()?.unix_timestamp;
+        require!(
+            amount > 0 && terms.counter_amount > 0 && terms.expiry > now,
+            ErrorCode::InvalidOtcOrder
+        );
+        require!(
+            terms.counter_asset.len() <= MAX_REMOTE_ADDRESS_LEN,
+            ErrorCode::InvalidOtcOrder
+        );
+        require!(ctx.accounts.counter_chain.enabled, ErrorCode::ChainDisabled);
+        recipient::validate(&ctx.accounts.counter_chain, &terms.pay_to)?;
+        VaultConfig::check_hot_cap(&ctx.accounts.vault_config, amount)?;
+
+        // Payments can only be vouched for by the relayed models
+        require!(
+            matches!(
+                terms.verifier,
+                TrustModel::OwnerSignature
+                    | TrustModel::GuardianQuorum
+                    | TrustModel::WormholeVaa { .. }
+            ),
+            ErrorCode::WrongTrustModel
+        );
+        // Wormhole proofs only come from Ethereum (see verifiers::WormholeVaa)
+        require!(
+            !matches!(terms.verifier, TrustModel::WormholeVaa { .. })
+                || terms.counter_chain_id == chain_ids::ETHEREUM,
+            ErrorCode::WrongTrustModel
+        );
+        require!(terms.verifier.available(), ErrorCode::TrustModelUnavailable);
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        let escrowed = TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.maker_token.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.escrow.to_account_info(),
+            authority: ctx.accounts.maker.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(&extensions, amount, ctx.accounts.mint.decimals, &[])?;
+        require!(escrowed > 0, ErrorCode::AmountTooSmall);
+
+        book.next_order_id = math::add(order_id, 1)?;
+        book.open = math::add(book.open, 1)?;
+
+        let order = &mut ctx.accounts.order;
+        order.order_id = order_id;
+        order.maker = ctx.accounts.maker.key();
+        order.mint = ctx.accounts.mint.key();
+        order.amount = escrowed;
+        order.terms = terms;
+        order.created_at = now;
+
+        emit!(OtcOrderCreated {
+            order_id,
+            maker: order.maker,
+            mint: order.mint,
+            amount: escrowed,
+            counter_chain_id: order.terms.counter_chain_id,
+            counter_asset: order.terms.counter_asset.clone(),
+            counter_amount: order.terms.counter_amount,
+            pay_to: order.terms.pay_to.clone(),
+            expiry: order.terms.expiry,
+        });
+
+        msg!("OTC order {} escrowed {} for {}", order_id, escrowed, order.maker);
+        Ok(())
+    }
+
+    /**
+     * Release an OTC order's escrow to the taker who paid for it
+     *
+     * Anyone may submit the proof: the order's verifier has to vouch
+     * for a payment of `counter_amount` to `taker` with the order ID as
+     * nonce (guardian signers in the remaining accounts, or a posted
+     * VAA). The order closes, its rent going back to the maker. Not
+     * while the token is paused, the council has halted the bridge or the
+     * counter chain is halted, nor over a split escrow's hot cap.
+     */
+    pub fn fill_otc_order<'info>(ctx: Context<'_, '_, '_, 'info, FillOtcOrder<'info>>, order_id: u64) -> Result<()> {
+        let bridge_state = &ctx.accounts.bridge_state;
+        require!(!bridge_state.paused, ErrorCode::BridgePaused);
+        require!(!ctx.accounts.token_config.paused, ErrorCode::TokenPaused);
+        GuardianCouncil::check_not_halted(&ctx.accounts.guardian_council.to_account_info())?;
+        bridge_state.check_guardians_live(Clock::get()?.epoch)?;
+        ctx.accounts.counter_chain.check_not_halted()?;
+
+        let order = &ctx.accounts.order;
+        VaultConfig::check_hot_cap(&ctx.accounts.vault_config, order.amount)?;
+        let taker = ctx.accounts.taker.key();
+        verifiers::verify_relayed(
+            &order.terms.verifier,
+            Relayed {
+                authority: ctx.accounts.authority.key(),
+                owner: bridge_state.owner,
+                guardian_set: &ctx.accounts.guardian_set.to_account_info(),
+                signers: ctx.remaining_accounts,
+                vaa: ctx.accounts.vaa.as_deref(),
+            },
+            &order.payment(
+                taker,
+                bridge_state.instance_id,
+                GuardianSet::index_of(&ctx.accounts.guardian_set.to_account_info())?,
+            ),
+        )?;
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.taker_token.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(
+            &extensions,
+            order.amount,
+            ctx.accounts.mint.decimals,
+            &[&[
+                b"otc_escrow",
+                instance::seed(&bridge_state.instance_id),
+                &[ctx.bumps.otc_authority],
+            ]],
+        )?;
+
+        let book = &mut ctx.accounts.otc_book;
+        book.open = book.open.saturating_sub(1);
+
+        emit!(OtcOrderFilled {
+            order_id,
+            maker: order.maker,
+            taker,
+            amount: order.amount,
+            counter_amount: order.terms.counter_amount,
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!("OTC order {} filled by {}", order_id, taker);
+        Ok(())
+    }
+
+    /**
+     * Take back an OTC order's escrow after its expiry (maker only)
+     *
+     * Until the expiry the order is binding, so a taker who paid on the
+     * counter chain can't be cancelled out from under. The order stays
+     * fillable after it until the maker cancels.
+     */
+    pub fn cancel_otc_order<'info>(ctx: Context<'_, '_, '_, 'info, CancelOtcOrder<'info>>, order_id: u64) -> Result<()> {
+        require!(!ctx.accounts.bridge_state.paused, ErrorCode::BridgePaused);
+
+        let order = &ctx.accounts.order;
+        require_keys_eq!(order.maker, ctx.accounts.maker.key(), ErrorCode::Unauthorized);
+        let now = Clock::get()?.unix_timestamp;
+        require!(now > order.terms.expiry, ErrorCode::NotExpired);
+
+        let extensions = token_ext::inspect(
+            &ctx.accounts.mint.to_account_info(),
+            ctx.accounts.token_config.allowed_extensions,
+        )?;
+        TokenTransfer {
+            token_program: ctx.accounts.token_program.to_account_info(),
+            from: ctx.accounts.escrow.to_account_info(),
+            mint: ctx.accounts.mint.to_account_info(),
+            to: ctx.accounts.maker_token.to_account_info(),
+            authority: ctx.accounts.otc_authority.to_account_info(),
+            extra_accounts: ctx.remaining_accounts,
+        }
+        .execute(
+            &extensions,
+            order.amount,
+            ctx.accounts.mint.decimals,
+            &[&[
+                b"otc_escrow",
+                instance::seed(&ctx.accounts.bridge_state.instance_id),
+                &[ctx.bumps.otc_authority],
+            ]],
+        )?;
+
+        let book = &mut ctx.accounts.otc_book;
+        book.open = book.open.saturating_sub(1);
+
+        emit!(OtcOrderCancelled {
+            order_id,
+            maker: order.maker,
+            amount: order.amount,
+            timestamp: now,
+        });
+
+        msg!("OTC order {} cancelled", order_id);
+        Ok(())
+    }
 }
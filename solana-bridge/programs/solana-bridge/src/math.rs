@@ -0,0 +1,215 @@
+//! Checked arithmetic for amounts, fees and counters
+//!
+//! The release profile keeps overflow checks on, but those abort the
+//! transaction with a bare panic. These helpers fail with an error code
+//! instead, and a u128 intermediate is never cast back to u64 without
+//! checking that it fits.
+
+use anchor_lang::prelude::*;
+
+use crate::ErrorCode;
+
+/// Basis points in 100%
+pub const BPS_DENOMINATOR: u64 = 10_000;
+
+pub fn add(a: u64, b: u64) -> Result<u64> {
+    a.checked_add(b).ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+pub fn sub(a: u64, b: u64) -> Result<u64> {
+    a.checked_sub(b).ok_or_else(|| error!(ErrorCode::MathUnderflow))
+}
+
+/// Add one to a counter in place, returning the new value
+pub fn increment(counter: &mut u64) -> Result<u64> {
+    *counter = add(*counter, 1)?;
+    Ok(*counter)
+}
+
+/// Add seconds to a unix timestamp
+pub fn add_seconds(timestamp: i64, seconds: i64) -> Result<i64> {
+    timestamp
+        .checked_add(seconds)
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/// `a * b / denominator`, rounded down, with a u128 intermediate
+pub fn mul_div(a: u64, b: u64, denominator: u64) -> Result<u64> {
+    require!(denominator != 0, ErrorCode::DivisionByZero);
+    let result = a as u128 * b as u128 / denominator as u128;
+    u64::try_from(result).map_err(|_| error!(ErrorCode::MathOverflow))
+}
+
+/// `bps` basis points of `amount`, rounded down
+pub fn bps_of(amount: u64, bps: u16) -> Result<u64> {
+    require!(bps as u64 <= BPS_DENOMINATOR, ErrorCode::InvalidConfig);
+    mul_div(amount, bps as u64, BPS_DENOMINATOR)
+}
+
+/**
+ * Outbound fee: `base_fee` plus `fee_bps` of `amount`, less a
+ * `discount_bps` rebate on the whole fee
+ */
+pub fn transfer_fee(amount: u64, base_fee: u64, fee_bps: u16, discount_bps: u16) -> Result<u64> {
+    let fee = add(base_fee, bps_of(amount, fee_bps)?)?;
+    sub(fee, bps_of(fee, discount_bps)?)
+}
+
+/// Decimals of USD amounts (micro-dollars)
+pub const USD_DECIMALS: i32 = 6;
+
+/**
+ * USD value, in micro-dollars and rounded down, of `amount` base units
+ * of a `decimals`-decimal token priced at `price * 10^exponent` dollars
+ */
+pub fn usd_value(amount: u64, decimals: u8, price: u64, exponent: i32) -> Result<u64> {
+    let scale = exponent + USD_DECIMALS - decimals as i32;
+    let gross = amount as u128 * price as u128;
+    let value = if scale >= 0 {
+        10u128.checked_pow(scale as u32).and_then(|factor| gross.checked_mul(factor))
+    } else {
+        // A divisor too large for u128 leaves nothing
+        Some(10u128.checked_pow(scale.unsigned_abs()).map_or(0, |divisor| gross / divisor))
+    };
+    value
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/**
+ * `amount` base units of a `decimals`-decimal token priced at
+ * `price * 10^exponent`, in base units of a `to_decimals`-decimal token
+ * priced at `to_price * 10^to_exponent`, rounded up
+ *
+ * Rounds up so a fee converted into another token is never underpaid.
+ */
+pub fn convert(
+    amount: u64,
+    decimals: u8,
+    price: u64,
+    exponent: i32,
+    to_decimals: u8,
+    to_price: u64,
+    to_exponent: i32,
+) -> Result<u64> {
+    require!(to_price != 0, ErrorCode::DivisionByZero);
+    let scale = exponent - to_exponent + to_decimals as i32 - decimals as i32;
+    let gross = amount as u128 * price as u128;
+    let value = if scale >= 0 {
+        10u128
+            .checked_pow(scale as u32)
+            .and_then(|factor| gross.checked_mul(factor))
+            .map(|scaled| scaled.div_ceil(to_price as u128))
+    } else {
+        // A divisor too large for u128 leaves at most one unit
+        Some(
+            10u128
+                .checked_pow(scale.unsigned_abs())
+                .and_then(|divisor| divisor.checked_mul(to_price as u128))
+                .map_or((gross > 0) as u128, |divisor| gross.div_ceil(divisor)),
+        )
+    };
+    value
+        .and_then(|value| u64::try_from(value).ok())
+        .ok_or_else(|| error!(ErrorCode::MathOverflow))
+}
+
+/**
+ * Part of `amount` (in `decimals`) too small to carry in `bridged_decimals`
+ *
+ * Zero when the other side has as many decimals or more.
+ */
+pub fn dust(amount: u64, decimals: u8, bridged_decimals: u8) -> u64 {
+    let Some(shift) = decimals.checked_sub(bridged_decimals) else {
+        return 0;
+    };
+    // A unit too large for u64 leaves nothing representable
+    10u64.checked_pow(shift as u32).map_or(amount, |unit| amount % unit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_and_sub_at_the_edges() {
+        assert_eq!(add(u64::MAX - 1, 1).unwrap(), u64::MAX);
+        assert!(add(u64::MAX, 1).is_err());
+        assert_eq!(sub(0, 0).unwrap(), 0);
+        assert!(sub(0, 1).is_err());
+    }
+
+    #[test]
+    fn increment_stops_at_max() {
+        let mut counter = u64::MAX - 1;
+        assert_eq!(increment(&mut counter).unwrap(), u64::MAX);
+        assert!(increment(&mut counter).is_err());
+        assert_eq!(counter, u64::MAX);
+    }
+
+    #[test]
+    fn add_seconds_overflows_cleanly() {
+        assert_eq!(add_seconds(i64::MAX - 5, 5).unwrap(), i64::MAX);
+        assert!(add_seconds(i64::MAX, 1).is_err());
+    }
+
+    #[test]
+    fn mul_div_uses_a_wide_intermediate() {
+        // u64::MAX * u64::MAX doesn't fit in u64, but the quotient does
+        assert_eq!(mul_div(u64::MAX, u64::MAX, u64::MAX).unwrap(), u64::MAX);
+        assert_eq!(mul_div(u64::MAX, 3, 4).unwrap(), u64::MAX / 4 * 3 + 2);
+        assert!(mul_div(u64::MAX, 2, 1).is_err());
+        assert!(mul_div(1, 1, 0).is_err());
+    }
+
+    #[test]
+    fn bps_bounds() {
+        assert_eq!(bps_of(u64::MAX, 10_000).unwrap(), u64::MAX);
+        assert_eq!(bps_of(u64::MAX, 0).unwrap(), 0);
+        assert_eq!(bps_of(9_999, 1).unwrap(), 0);
+        assert!(bps_of(1, 10_001).is_err());
+    }
+
+    #[test]
+    fn transfer_fee_bounds() {
+        assert_eq!(transfer_fee(1_000_000, 1_000, 5, 0).unwrap(), 1_500);
+        assert_eq!(transfer_fee(1_000_000, 1_000, 5, 10_000).unwrap(), 0);
+        assert_eq!(transfer_fee(u64::MAX, 0, 10_000, 0).unwrap(), u64::MAX);
+        assert!(transfer_fee(u64::MAX, 1, 10_000, 0).is_err());
+    }
+
+    #[test]
+    fn dust_below_the_bridged_decimals() {
+        // 9-decimal SPL token to Stellar's 7
+        assert_eq!(dust(10_000_050, 9, 7), 50);
+        assert_eq!(dust(10_000_000, 9, 7), 0);
+        assert_eq!(dust(123, 6, 18), 0);
+        assert_eq!(dust(123, 6, 6), 0);
+        assert_eq!(dust(u64::MAX, 40, 0), u64::MAX);
+    }
+
+    #[test]
+    fn usd_value_scales_by_price_exponent_and_decimals() {
+        // 1 USDC (6 decimals) at $0.99995
+        assert_eq!(usd_value(1_000_000, 6, 99_995_000, -8).unwrap(), 999_950);
+        // 2.5 SOL (9 decimals) at $150.12345678, rounded down
+        assert_eq!(usd_value(2_500_000_000, 9, 15_012_345_678, -8).unwrap(), 375_308_641);
+        assert_eq!(usd_value(1, 0, 1, 0).unwrap(), 1_000_000);
+        assert_eq!(usd_value(1, 30, 1, -30).unwrap(), 0);
+        assert!(usd_value(u64::MAX, 0, 1, 10).is_err());
+    }
+
+    #[test]
+    fn convert_rounds_up_between_priced_tokens() {
+        // 1.5 USDC at $1.00 in lamports at $150.00
+        assert_eq!(
+            convert(1_500_000, 6, 100_000_000, -8, 9, 15_000_000_000, -8).unwrap(),
+            10_000_000
+        );
+        assert_eq!(convert(10, 0, 1, 0, 0, 3, 0).unwrap(), 4);
+        assert_eq!(convert(1, 30, 1, 0, 0, 1, 0).unwrap(), 1);
+        assert_eq!(convert(0, 30, 1, 0, 0, 1, 0).unwrap(), 0);
+        assert!(convert(1, 0, 1, 0, 0, 0, 0).is_err());
+        assert!(convert(u64::MAX, 0, u64::MAX, 10, 0, 1, 0).is_err());
+    }
+}
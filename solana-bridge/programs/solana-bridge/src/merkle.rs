@@ -0,0 +1,48 @@
+//! Keccak Merkle trees for checkpoints
+//!
+//! Pairs are hashed in sorted order (smaller hash first), the same
+//! convention as OpenZeppelin's MerkleProof, so a proof is just the list
+//! of siblings with no left/right flags. An odd node at the end of a level
+//! is carried up unchanged.
+
+use anchor_lang::solana_program::keccak;
+
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        keccak::hashv(&[a, b]).to_bytes()
+    } else {
+        keccak::hashv(&[b, a]).to_bytes()
+    }
+}
+
+/**
+ * Root over `leaves`, in order; all zeros for an empty tree
+ */
+pub fn root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    if leaves.is_empty() {
+        return [0u8; 32];
+    }
+
+    let mut level = leaves.to_vec();
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .map(|pair| match pair {
+                [a, b] => hash_pair(a, b),
+                [a] => *a,
+                _ => unreachable!(),
+            })
+            .collect();
+    }
+    level[0]
+}
+
+/**
+ * Check that `leaf` is in the tree with `root`
+ */
+pub fn verify(leaf: [u8; 32], proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+    let computed = proof
+        .iter()
+        .fold(leaf, |node, sibling| hash_pair(&node, sibling));
+    computed == *root
+}
@@ -0,0 +1,60 @@
+//! Recipient names pinned to transfer records
+//!
+//! Clients resolve human-readable names before a transfer: ENS for EVM
+//! recipients, SNS (.sol domains) for Solana ones. The record keeps the
+//! name next to the address it resolved to, so an audit can tell "sent
+//! to alice.eth" from "sent to a pasted address".
+//!
+//! SNS lives on Solana, so a .sol name is checked here: the domain's name
+//! account must be the one derived from the name and owned by the
+//! recipient. Only second-level domains (`alice.sol`) are supported, and
+//! a tokenized domain resolves to its escrow, not the NFT holder.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::hash::hashv;
+use anchor_lang::solana_program::pubkey;
+
+use crate::ErrorCode;
+
+/// SPL Name Service program
+pub const NAME_SERVICE_PROGRAM: Pubkey = pubkey!("namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX");
+
+/// Parent name account of every .sol domain
+pub const SOL_TLD: Pubkey = pubkey!("58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx");
+
+const HASH_PREFIX: &str = "SPL Name Service";
+
+/// Name record header: parent (32), owner (32), class (32)
+const HEADER_LEN: usize = 96;
+
+/// Longest name a record pins (ENS names are usually far shorter)
+pub const MAX_RECIPIENT_NAME_LEN: usize = 64;
+
+/// The `alice` of `alice.sol`, if `name` is a second-level .sol domain
+pub fn sol_label(name: &str) -> Option<&str> {
+    name.strip_suffix(".sol")
+        .filter(|label| !label.is_empty() && !label.contains('.'))
+}
+
+/// Name account of `label`.sol
+pub fn sns_domain_address(label: &str) -> Pubkey {
+    let hashed = hashv(&[HASH_PREFIX.as_bytes(), label.as_bytes()]);
+    Pubkey::find_program_address(
+        &[hashed.as_ref(), Pubkey::default().as_ref(), SOL_TLD.as_ref()],
+        &NAME_SERVICE_PROGRAM,
+    )
+    .0
+}
+
+/// Owner of the .sol domain `name`, read from its name account `domain`
+pub fn sns_owner(domain: &AccountInfo, name: &str) -> Result<Pubkey> {
+    let label = sol_label(name).ok_or(ErrorCode::InvalidRecipientName)?;
+    require_keys_eq!(domain.key(), sns_domain_address(label), ErrorCode::InvalidRecipientName);
+    require_keys_eq!(*domain.owner, NAME_SERVICE_PROGRAM, ErrorCode::InvalidRecipientName);
+
+    let data = domain.try_borrow_data()?;
+    require!(data.len() >= HEADER_LEN, ErrorCode::InvalidRecipientName);
+    let mut owner = [0u8; 32];
+    owner.copy_from_slice(&data[32..64]);
+    Ok(Pubkey::new_from_array(owner))
+}
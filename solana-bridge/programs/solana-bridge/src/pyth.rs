@@ -0,0 +1,130 @@
+//! Pyth prices, read from the receiver program's price update accounts
+//!
+//! Prices are pulled: a crank posts a fresh update through the Pyth
+//! receiver (which checks the Wormhole-signed Merkle proof) and passes the
+//! resulting PriceUpdateV2 account. We only check its owner, that it was
+//! fully verified, its feed and its age:
+//!
+//!   discriminator (8) || write_authority (32)
+//!   || verification_level (Partial: 0, num_signatures (1) | Full: 1)
+//!   || feed_id (32) || price (i64) || conf (u64) || exponent (i32)
+//!   || publish_time (i64) || prev_publish_time (i64)
+//!   || ema_price (i64) || ema_conf (u64) || posted_slot (u64)
+//!
+//! all little-endian (Borsh).
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::pubkey;
+
+use crate::{compute, require_ctx, ErrorCode};
+
+/// Pyth Solana receiver program
+pub const RECEIVER_PROGRAM: Pubkey = pubkey!("rec5EKMGg6MxZYaMdyBfgwp4d5rB9T1VQH5pJv5LtFJ");
+
+/// Anchor discriminator of PriceUpdateV2
+const DISCRIMINATOR: [u8; 8] = [34, 241, 35, 99, 157, 126, 244, 205];
+
+/// Oldest price (seconds) the stats crank accepts
+pub const MAX_PRICE_AGE: i64 = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Price {
+    pub feed_id: [u8; 32],
+    pub price: i64,
+    pub conf: u64,
+    pub exponent: i32,
+    pub publish_time: i64,
+}
+
+impl Price {
+    /// A fully verified update's price; None for partial or malformed ones
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.get(..8)? != DISCRIMINATOR {
+            return None;
+        }
+        // Partially verified updates carry a signature count after the tag
+        let body = match data.get(40)? {
+            1 => data.get(41..)?,
+            _ => return None,
+        };
+        if body.len() < 32 + 8 + 8 + 4 + 8 {
+            return None;
+        }
+
+        Some(Price {
+            feed_id: body[..32].try_into().unwrap(),
+            price: i64::from_le_bytes(body[32..40].try_into().unwrap()),
+            conf: u64::from_le_bytes(body[40..48].try_into().unwrap()),
+            exponent: i32::from_le_bytes(body[48..52].try_into().unwrap()),
+            publish_time: i64::from_le_bytes(body[52..60].try_into().unwrap()),
+        })
+    }
+}
+
+/**
+ * Price of `feed_id` from the update account `info`
+ *
+ * Fails unless the receiver owns it, it was fully verified, it's for
+ * `feed_id`, the price is positive and it's no older than MAX_PRICE_AGE.
+ */
+pub fn load_price(info: &AccountInfo, feed_id: &[u8; 32], now: i64) -> Result<Price> {
+    compute::reserve(compute::Step::OracleRead)?;
+    require_keys_eq!(*info.owner, RECEIVER_PROGRAM, ErrorCode::InvalidPriceFeed);
+
+    let data = info.try_borrow_data()?;
+    let price = Price::from_bytes(&data).ok_or(ErrorCode::InvalidPriceFeed)?;
+    require!(
+        price.feed_id == *feed_id && price.price > 0,
+        ErrorCode::InvalidPriceFeed
+    );
+    let age = now.saturating_sub(price.publish_time);
+    require_ctx!(
+        age <= MAX_PRICE_AGE,
+        ErrorCode::StalePrice,
+        age = age,
+        max_age = MAX_PRICE_AGE
+    );
+    Ok(price)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn update(level: &[u8], price: i64, exponent: i32) -> Vec<u8> {
+        let mut data = DISCRIMINATOR.to_vec();
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(level);
+        data.extend_from_slice(&[0xef; 32]);
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&25_000u64.to_le_bytes());
+        data.extend_from_slice(&exponent.to_le_bytes());
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&1_699_999_999i64.to_le_bytes());
+        data.extend_from_slice(&price.to_le_bytes());
+        data.extend_from_slice(&25_000u64.to_le_bytes());
+        data.extend_from_slice(&250_000_000u64.to_le_bytes());
+        data
+    }
+
+    #[test]
+    fn reads_a_fully_verified_update() {
+        let price = Price::from_bytes(&update(&[1], 99_995_000, -8)).unwrap();
+        assert_eq!(price.feed_id, [0xef; 32]);
+        assert_eq!(price.price, 99_995_000);
+        assert_eq!(price.conf, 25_000);
+        assert_eq!(price.exponent, -8);
+        assert_eq!(price.publish_time, 1_700_000_000);
+    }
+
+    #[test]
+    fn rejects_partial_and_malformed_updates() {
+        assert_eq!(Price::from_bytes(&update(&[0, 5], 1, -8)), None);
+
+        let mut wrong_discriminator = update(&[1], 1, -8);
+        wrong_discriminator[0] ^= 1;
+        assert_eq!(Price::from_bytes(&wrong_discriminator), None);
+
+        assert_eq!(Price::from_bytes(&update(&[1], 1, -8)[..100]), None);
+    }
+}
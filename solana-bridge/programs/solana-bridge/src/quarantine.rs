@@ -0,0 +1,66 @@
+//! Quarantine for inbound deliveries whose recipient can't take them
+//!
+//! Mint and unlock push the tokens into the token account the relayer
+//! passes. When that is the recipient's associated token account and it
+//! can't be paid into, because it was closed, moved to another owner or
+//! frozen, retrying the delivery fails the same way every time. Instead
+//! the transfer is verified and recorded as Claimable, a
+//! `DeliveryQuarantined` event says why, and the recipient claims it
+//! into an account that works (`claim` for mints, `claim_unlock` for
+//! unlocks). Until then a minted transfer simply isn't minted, and an
+//! unlocked one stays in the escrow.
+//!
+//! Any other unusable account is the relayer's mistake and still fails
+//! the delivery, so a relayer can't quarantine a transfer the recipient's
+//! own account would have taken.
+
+use anchor_lang::prelude::*;
+use anchor_spl::associated_token::get_associated_token_address_with_program_id;
+use anchor_spl::token_interface::TokenAccount;
+
+use crate::{ErrorCode, QuarantineReason};
+
+/**
+ * Why a delivery to `recipient` can't be paid into `token`, or None if
+ * it can
+ *
+ * Fails with InvalidRecipientToken when `token` is unusable but isn't
+ * the recipient's associated token account for `mint`.
+ */
+pub fn check(
+    token: &AccountInfo,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Option<QuarantineReason>> {
+    let Some(reason) = reason_of(token, recipient, mint, token_program)? else {
+        return Ok(None);
+    };
+    require_keys_eq!(
+        token.key(),
+        get_associated_token_address_with_program_id(recipient, mint, token_program),
+        ErrorCode::InvalidRecipientToken
+    );
+    Ok(Some(reason))
+}
+
+fn reason_of(
+    token: &AccountInfo,
+    recipient: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<Option<QuarantineReason>> {
+    if token.data_is_empty() || token.owner != token_program {
+        return Ok(Some(QuarantineReason::Closed));
+    }
+    let Ok(account) = TokenAccount::try_deserialize(&mut &token.try_borrow_data()?[..]) else {
+        return Ok(Some(QuarantineReason::Closed));
+    };
+    if account.mint != *mint || account.owner != *recipient {
+        return Ok(Some(QuarantineReason::WrongOwner));
+    }
+    if account.is_frozen() {
+        return Ok(Some(QuarantineReason::Frozen));
+    }
+    Ok(None)
+}
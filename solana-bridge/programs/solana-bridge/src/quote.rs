@@ -0,0 +1,219 @@
+//! Signed fee quotes
+//!
+//! The fee table can change between the moment a user is shown a fee and
+//! the moment their lock lands. A relayer can instead quote the fee
+//! off-chain and sign, with its guardian key:
+//!
+//!   "fee_quote" || mint (32) || amount (u64 LE) || fee (u64 LE)
+//!              || dest_chain_id (u64 LE) || expires_at (i64 LE)
+//!
+//! The user puts an Ed25519 program instruction checking that signature
+//! right before `lock`, which finds it through the instructions sysvar
+//! and charges exactly the quoted fee until `expires_at`. The precompile
+//! has already checked the signature if the transaction got this far;
+//! here we only check what was signed and by whom.
+//!
+//! A quote isn't tied to a user: anyone may lock the same amount of the
+//! same token to the same chain at the quoted fee before it expires.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::{require_ctx, ErrorCode, GuardianSet};
+
+const DOMAIN: &[u8] = b"fee_quote";
+
+/// Length of a signed quote message
+pub const QUOTE_LEN: usize = 9 + 32 + 8 + 8 + 8 + 8;
+
+/// Ed25519 instruction header: count (1), padding (1), 7 u16 offsets
+const ED25519_HEADER_LEN: usize = 16;
+
+/// Offsets pointing into the Ed25519 instruction itself
+const CURRENT_INSTRUCTION: u16 = u16::MAX;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FeeQuote {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    pub dest_chain_id: u64,
+    pub expires_at: i64,
+}
+
+impl FeeQuote {
+    /// The message the relayer signs
+    pub fn to_bytes(&self) -> [u8; QUOTE_LEN] {
+        let mut out = [0u8; QUOTE_LEN];
+        out[..9].copy_from_slice(DOMAIN);
+        out[9..41].copy_from_slice(self.mint.as_ref());
+        out[41..49].copy_from_slice(&self.amount.to_le_bytes());
+        out[49..57].copy_from_slice(&self.fee.to_le_bytes());
+        out[57..65].copy_from_slice(&self.dest_chain_id.to_le_bytes());
+        out[65..73].copy_from_slice(&self.expires_at.to_le_bytes());
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != QUOTE_LEN || &data[..9] != DOMAIN {
+            return None;
+        }
+        let u64_at = |at: usize| u64::from_le_bytes(data[at..at + 8].try_into().unwrap());
+
+        Some(FeeQuote {
+            mint: Pubkey::try_from(&data[9..41]).ok()?,
+            amount: u64_at(41),
+            fee: u64_at(49),
+            dest_chain_id: u64_at(57),
+            expires_at: u64_at(65) as i64,
+        })
+    }
+
+    /**
+     * The quoted fee, if this quote covers the lock
+     *
+     * Fails unless mint, amount and destination match exactly and the
+     * quote hasn't expired at `now`.
+     */
+    pub fn fee_for(&self, mint: &Pubkey, amount: u64, dest_chain_id: u64, now: i64) -> Result<u64> {
+        require!(
+            self.mint == *mint && self.amount == amount && self.dest_chain_id == dest_chain_id,
+            ErrorCode::InvalidFeeQuote
+        );
+        require_ctx!(
+            now < self.expires_at,
+            ErrorCode::FeeQuoteExpired,
+            now = now,
+            expires_at = self.expires_at
+        );
+        Ok(self.fee)
+    }
+}
+
+/**
+ * Signer and message of a single-signature Ed25519 instruction
+ *
+ * Only the layout clients produce is accepted: one signature, with key,
+ * signature and message all inside the instruction's own data.
+ */
+pub fn parse_ed25519(data: &[u8]) -> Option<(Pubkey, &[u8])> {
+    if data.len() < ED25519_HEADER_LEN || data[0] != 1 {
+        return None;
+    }
+    let offset = |i: usize| u16::from_le_bytes([data[2 + 2 * i], data[3 + 2 * i]]);
+    let [_, signature_ix, key_at, key_ix, message_at, message_len, message_ix] =
+        [0, 1, 2, 3, 4, 5, 6].map(offset);
+    if [signature_ix, key_ix, message_ix] != [CURRENT_INSTRUCTION; 3] {
+        return None;
+    }
+
+    let key = data.get(key_at as usize..key_at as usize + 32)?;
+    let message = data.get(message_at as usize..message_at as usize + message_len as usize)?;
+    Some((Pubkey::try_from(key).ok()?, message))
+}
+
+/**
+ * Read the quote signed in the instruction right before this one
+ *
+ * The signer must be in the guardian set, so an ejected relayer's
+ * quotes stop working with the ejection.
+ */
+pub fn load(instructions: &AccountInfo, signers: &GuardianSet) -> Result<FeeQuote> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::FeeQuoteRequired);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::FeeQuoteRequired
+    );
+
+    let (signer, message) = parse_ed25519(&ix.data).ok_or(ErrorCode::InvalidFeeQuote)?;
+    require!(signers.guardians.contains(&signer), ErrorCode::Unauthorized);
+    FeeQuote::from_bytes(message).ok_or_else(|| error!(ErrorCode::InvalidFeeQuote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote() -> FeeQuote {
+        FeeQuote {
+            mint: Pubkey::new_from_array([7; 32]),
+            amount: 1_000_000,
+            fee: 1_500,
+            dest_chain_id: 1,
+            expires_at: 1_700_000_000,
+        }
+    }
+
+    /// The layout `Ed25519Program.createInstructionWithPublicKey` produces
+    fn ed25519_data(key: &Pubkey, message: &[u8]) -> Vec<u8> {
+        let key_at = ED25519_HEADER_LEN as u16;
+        let signature_at = key_at + 32;
+        let message_at = signature_at + 64;
+        let mut data = vec![1, 0];
+        for value in [
+            signature_at,
+            CURRENT_INSTRUCTION,
+            key_at,
+            CURRENT_INSTRUCTION,
+            message_at,
+            message.len() as u16,
+            CURRENT_INSTRUCTION,
+        ] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(key.as_ref());
+        data.extend_from_slice(&[0; 64]);
+        data.extend_from_slice(message);
+        data
+    }
+
+    #[test]
+    fn quote_round_trips() {
+        let bytes = quote().to_bytes();
+        assert_eq!(&bytes[..9], b"fee_quote");
+        assert_eq!(FeeQuote::from_bytes(&bytes), Some(quote()));
+        assert_eq!(FeeQuote::from_bytes(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn rejects_other_domains() {
+        let mut bytes = quote().to_bytes();
+        bytes[0] = b'F';
+        assert_eq!(FeeQuote::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn parses_single_signature_instructions() {
+        let key = Pubkey::new_from_array([3; 32]);
+        let message = quote().to_bytes();
+        let data = ed25519_data(&key, &message);
+
+        assert_eq!(parse_ed25519(&data), Some((key, &message[..])));
+    }
+
+    #[test]
+    fn rejects_offsets_into_other_instructions() {
+        let key = Pubkey::new_from_array([3; 32]);
+        let mut data = ed25519_data(&key, &quote().to_bytes());
+        // message_instruction_index -> instruction 0
+        data[14..16].copy_from_slice(&0u16.to_le_bytes());
+        assert_eq!(parse_ed25519(&data), None);
+
+        let mut data = ed25519_data(&key, &quote().to_bytes());
+        data[0] = 2;
+        assert_eq!(parse_ed25519(&data), None);
+    }
+
+    #[test]
+    fn rejects_truncated_messages() {
+        let key = Pubkey::new_from_array([3; 32]);
+        let data = ed25519_data(&key, &quote().to_bytes());
+        assert_eq!(parse_ed25519(&data[..data.len() - 1]), None);
+    }
+}
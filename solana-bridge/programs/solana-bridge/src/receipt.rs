@@ -0,0 +1,196 @@
+//! Deposit receipts for locked positions
+//!
+//! A lock can ask for a receipt: a one-off Token-2022 mint (PDA
+//! ["receipt", nonce], 0 decimals) with a single token in the sender's
+//! associated token account. Wallets list it like any other token, so a
+//! pending bridge position shows up without bridge-specific UI.
+//!
+//! - Non-transferable: the receipt stays with the sender, so holding it
+//!   is the same as being the one the lock refunds to.
+//! - Permanent delegate (the bridge authority): the bridge burns it when
+//!   the relayer attests the transfer or the sender is refunded, without
+//!   the sender signing.
+//!
+//! The mint authority is dropped after the one token is minted.
+
+use anchor_lang::prelude::*;
+use anchor_lang::system_program;
+use anchor_spl::associated_token::{self, get_associated_token_address_with_program_id};
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::ExtensionType,
+    instruction::AuthorityType,
+};
+use anchor_spl::token_2022_extensions::{
+    non_transferable_mint_initialize, permanent_delegate_initialize,
+    NonTransferableMintInitialize, PermanentDelegateInitialize,
+};
+use anchor_spl::token_interface::{self, Burn, InitializeMint2, MintTo, SetAuthority};
+
+use crate::ErrorCode;
+
+/// Receipt mint PDA for an outbound nonce of the instance with seed `instance`
+pub fn address(instance: &[u8], nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[b"receipt", instance, &nonce.to_le_bytes()], &crate::ID)
+}
+
+/**
+ * Accounts to mint a receipt
+ */
+pub struct IssueReceipt<'info> {
+    /// Receipt mint PDA; must not exist yet
+    pub mint: AccountInfo<'info>,
+    /// Sender's associated token account for the receipt mint
+    pub token: AccountInfo<'info>,
+    /// The sender; pays for both accounts
+    pub owner: AccountInfo<'info>,
+    pub bridge_authority: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    pub associated_token_program: AccountInfo<'info>,
+    pub system_program: AccountInfo<'info>,
+}
+
+impl<'info> IssueReceipt<'info> {
+    pub fn execute(self, instance: &[u8], nonce: u64, authority_seeds: &[&[&[u8]]]) -> Result<()> {
+        require_keys_eq!(*self.token_program.key, spl_token_2022::ID, ErrorCode::InvalidReceipt);
+        let (address, bump) = address(instance, nonce);
+        require_keys_eq!(self.mint.key(), address, ErrorCode::InvalidReceipt);
+        require_keys_eq!(
+            self.token.key(),
+            get_associated_token_address_with_program_id(
+                self.owner.key,
+                &address,
+                &spl_token_2022::ID
+            ),
+            ErrorCode::InvalidReceipt
+        );
+
+        let space = ExtensionType::try_calculate_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::NonTransferable,
+            ExtensionType::PermanentDelegate,
+        ])?;
+        let nonce_bytes = nonce.to_le_bytes();
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                self.system_program.clone(),
+                system_program::CreateAccount {
+                    from: self.owner.clone(),
+                    to: self.mint.clone(),
+                },
+                &[&[b"receipt", instance, &nonce_bytes, &[bump]]],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &spl_token_2022::ID,
+        )?;
+
+        // Extensions go in before the mint is initialized
+        non_transferable_mint_initialize(CpiContext::new(
+            self.token_program.clone(),
+            NonTransferableMintInitialize {
+                token_program_id: self.token_program.clone(),
+                mint: self.mint.clone(),
+            },
+        ))?;
+        permanent_delegate_initialize(
+            CpiContext::new(
+                self.token_program.clone(),
+                PermanentDelegateInitialize {
+                    token_program_id: self.token_program.clone(),
+                    mint: self.mint.clone(),
+                },
+            ),
+            self.bridge_authority.key,
+        )?;
+        token_interface::initialize_mint2(
+            CpiContext::new(
+                self.token_program.clone(),
+                InitializeMint2 {
+                    mint: self.mint.clone(),
+                },
+            ),
+            0,
+            self.bridge_authority.key,
+            None,
+        )?;
+
+        associated_token::create(CpiContext::new(
+            self.associated_token_program.clone(),
+            associated_token::Create {
+                payer: self.owner.clone(),
+                associated_token: self.token.clone(),
+                authority: self.owner.clone(),
+                mint: self.mint.clone(),
+                system_program: self.system_program.clone(),
+                token_program: self.token_program.clone(),
+            },
+        ))?;
+
+        token_interface::mint_to(
+            CpiContext::new_with_signer(
+                self.token_program.clone(),
+                MintTo {
+                    mint: self.mint.clone(),
+                    to: self.token.clone(),
+                    authority: self.bridge_authority.clone(),
+                },
+                authority_seeds,
+            ),
+            1,
+        )?;
+        token_interface::set_authority(
+            CpiContext::new_with_signer(
+                self.token_program,
+                SetAuthority {
+                    current_authority: self.bridge_authority,
+                    account_or_mint: self.mint,
+                },
+                authority_seeds,
+            ),
+            AuthorityType::MintTokens,
+            None,
+        )
+    }
+}
+
+/**
+ * Burn the receipt of an outbound transfer that was attested or refunded
+ *
+ * The accounts are optional in the instructions that call this; all
+ * three must be present when the transfer has a receipt.
+ */
+pub fn retire<'info>(
+    instance: &[u8],
+    nonce: u64,
+    owner: &Pubkey,
+    mint: Option<AccountInfo<'info>>,
+    token: Option<AccountInfo<'info>>,
+    token_program: Option<AccountInfo<'info>>,
+    bridge_authority: AccountInfo<'info>,
+    authority_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    let (Some(mint), Some(token), Some(token_program)) = (mint, token, token_program) else {
+        return err!(ErrorCode::ReceiptRequired);
+    };
+    require_keys_eq!(*token_program.key, spl_token_2022::ID, ErrorCode::InvalidReceipt);
+    let (address, _) = address(instance, nonce);
+    require_keys_eq!(mint.key(), address, ErrorCode::InvalidReceipt);
+    require_keys_eq!(
+        token.key(),
+        get_associated_token_address_with_program_id(owner, &address, &spl_token_2022::ID),
+        ErrorCode::InvalidReceipt
+    );
+
+    token_interface::burn(
+        CpiContext::new_with_signer(
+            token_program,
+            Burn {
+                mint,
+                from: token,
+                authority: bridge_authority,
+            },
+            authority_seeds,
+        ),
+        1,
+    )
+}
@@ -0,0 +1,289 @@
+//! Recipient validation per destination chain
+//!
+//! Recipients arrive as strings in the destination chain's native
+//! format. We only check shape and checksums here; whether the account
+//! exists is the destination side's problem.
+
+use anchor_lang::prelude::*;
+
+use crate::{ChainConfig, ChainKind, ErrorCode};
+
+/// Stellar strkey version byte for ed25519 public keys ("G..." addresses)
+const STELLAR_ACCOUNT_VERSION: u8 = 6 << 3;
+
+/// Bech32 data-part alphabet (BIP-173)
+const BECH32_CHARSET: &[u8; 32] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+/// Polymod constant for the original bech32 checksum
+const BECH32_CONST: u32 = 1;
+
+/// Polymod constant for bech32m (BIP-350, segwit v1+)
+const BECH32M_CONST: u32 = 0x2bc8_30a3;
+
+/**
+ * Validate `recipient` for the given destination chain
+ */
+pub fn validate(chain: &ChainConfig, recipient: &str) -> Result<()> {
+    match chain.kind {
+        ChainKind::Evm => require!(is_evm_address(recipient), ErrorCode::InvalidEthAddress),
+        ChainKind::Stellar => require!(
+            is_stellar_account(recipient),
+            ErrorCode::InvalidStellarAddress
+        ),
+        ChainKind::Cosmos => require!(
+            is_cosmos_account(recipient, &chain.address_prefix),
+            ErrorCode::InvalidCosmosAddress
+        ),
+        ChainKind::Bitcoin => require!(
+            taproot_output_key(recipient, &chain.address_prefix).is_some(),
+            ErrorCode::InvalidBitcoinAddress
+        ),
+    }
+    Ok(())
+}
+
+/**
+ * Raw bytes of a valid `recipient`, right-aligned in 32
+ *
+ * EVM addresses and 20-byte Cosmos accounts are left-padded with zeros;
+ * a Stellar account is its ed25519 key and a Bitcoin address its taproot
+ * output key. The destination chain's kind says how to re-encode them.
+ */
+pub fn address_bytes(chain: &ChainConfig, recipient: &str) -> Result<[u8; 32]> {
+    let mut out = [0u8; 32];
+    match chain.kind {
+        ChainKind::Evm => {
+            let digits = recipient
+                .strip_prefix("0x")
+                .filter(|digits| digits.len() == 40)
+                .ok_or(ErrorCode::InvalidEthAddress)?
+                .as_bytes();
+            let nibble = |c: u8| (c as char).to_digit(16).ok_or(ErrorCode::InvalidEthAddress);
+            for (byte, pair) in out[12..].iter_mut().zip(digits.chunks(2)) {
+                *byte = (nibble(pair[0])? << 4 | nibble(pair[1])?) as u8;
+            }
+        }
+        ChainKind::Stellar => {
+            require!(
+                is_stellar_account(recipient),
+                ErrorCode::InvalidStellarAddress
+            );
+            let decoded =
+                base32_decode(recipient.as_bytes()).ok_or(ErrorCode::InvalidStellarAddress)?;
+            out.copy_from_slice(&decoded[1..33]);
+        }
+        ChainKind::Cosmos => {
+            let data = bech32_decode(recipient, BECH32_CONST)
+                .filter(|(hrp, _)| *hrp == chain.address_prefix)
+                .and_then(|(_, words)| convert_bits(&words, 5, 8))
+                .filter(|data| data.len() == 20 || data.len() == 32)
+                .ok_or(ErrorCode::InvalidCosmosAddress)?;
+            out[32 - data.len()..].copy_from_slice(&data);
+        }
+        ChainKind::Bitcoin => {
+            out = taproot_output_key(recipient, &chain.address_prefix)
+                .ok_or(ErrorCode::InvalidBitcoinAddress)?;
+        }
+    }
+    Ok(out)
+}
+
+/// 0x-prefixed, 20-byte address
+pub fn is_evm_address(address: &str) -> bool {
+    address.starts_with("0x") && address.len() == 42
+}
+
+/**
+ * Stellar account ID (strkey)
+ *
+ * base32(version || ed25519 key (32) || crc16-xmodem (2, little-endian)),
+ * which is always 56 characters starting with 'G'.
+ */
+pub fn is_stellar_account(address: &str) -> bool {
+    if address.len() != 56 || !address.starts_with('G') {
+        return false;
+    }
+
+    let decoded = match base32_decode(address.as_bytes()) {
+        Some(decoded) if decoded.len() == 35 => decoded,
+        _ => return false,
+    };
+
+    if decoded[0] != STELLAR_ACCOUNT_VERSION {
+        return false;
+    }
+
+    let checksum = crc16_xmodem(&decoded[..33]);
+    decoded[33..] == checksum.to_le_bytes()
+}
+
+/**
+ * Cosmos SDK account (bech32)
+ *
+ * The human-readable part must be the chain's prefix ("cosmos", "osmo", ...),
+ * and the payload a 20-byte account or 32-byte module/contract address.
+ */
+pub fn is_cosmos_account(address: &str, prefix: &str) -> bool {
+    let Some((hrp, words)) = bech32_decode(address, BECH32_CONST) else {
+        return false;
+    };
+
+    match convert_bits(&words, 5, 8) {
+        Some(data) => hrp == prefix && (data.len() == 20 || data.len() == 32),
+        None => false,
+    }
+}
+
+/**
+ * Taproot (segwit v1) output key of a Bitcoin address
+ *
+ * Only P2TR is accepted: bech32m, witness version 1, 32-byte program,
+ * and the network prefix of the chain ("bc" mainnet, "tb" testnet).
+ */
+pub fn taproot_output_key(address: &str, prefix: &str) -> Option<[u8; 32]> {
+    let (hrp, words) = bech32_decode(address, BECH32M_CONST)?;
+    if hrp != prefix || words.first() != Some(&1) {
+        return None;
+    }
+
+    let program = convert_bits(&words[1..], 5, 8)?;
+    program.try_into().ok()
+}
+
+/**
+ * Decode a bech32-family string, returning (hrp, 5-bit data words)
+ *
+ * `checksum_const` selects the variant: 1 for bech32, 0x2bc830a3 for bech32m.
+ * Mixed case is rejected, as BIP-173 requires.
+ */
+fn bech32_decode(address: &str, checksum_const: u32) -> Option<(String, Vec<u8>)> {
+    if address.len() > 90 {
+        return None;
+    }
+
+    let has_lower = address.bytes().any(|c| c.is_ascii_lowercase());
+    let has_upper = address.bytes().any(|c| c.is_ascii_uppercase());
+    if has_lower && has_upper {
+        return None;
+    }
+    let address = address.to_ascii_lowercase();
+
+    let separator = address.rfind('1')?;
+    let (hrp, data) = (&address[..separator], &address[separator + 1..]);
+    if hrp.is_empty() || data.len() < 6 || !hrp.bytes().all(|c| (33..=126).contains(&c)) {
+        return None;
+    }
+
+    let mut values = Vec::with_capacity(data.len());
+    for c in data.bytes() {
+        let value = BECH32_CHARSET.iter().position(|x| *x == c)?;
+        values.push(value as u8);
+    }
+
+    if bech32_polymod(hrp.as_bytes(), &values) != checksum_const {
+        return None;
+    }
+
+    values.truncate(values.len() - 6);
+    Some((hrp.to_string(), values))
+}
+
+fn bech32_polymod(hrp: &[u8], values: &[u8]) -> u32 {
+    const GENERATOR: [u32; 5] = [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3];
+
+    let mut chk: u32 = 1;
+    let mut step = |value: u8| {
+        let top = chk >> 25;
+        chk = ((chk & 0x1ff_ffff) << 5) ^ value as u32;
+        for (i, generator) in GENERATOR.iter().enumerate() {
+            if (top >> i) & 1 == 1 {
+                chk ^= generator;
+            }
+        }
+    };
+
+    // Expanded hrp: high bits, a zero, then low bits
+    for c in hrp {
+        step(c >> 5);
+    }
+    step(0);
+    for c in hrp {
+        step(c & 31);
+    }
+    for value in values {
+        step(*value);
+    }
+
+    chk
+}
+
+/// Regroup `from`-bit words into `to`-bit words (no padding allowed)
+fn convert_bits(data: &[u8], from: u32, to: u32) -> Option<Vec<u8>> {
+    let mut acc: u32 = 0;
+    let mut bits: u32 = 0;
+    let max_value: u32 = (1 << to) - 1;
+    let max_acc: u32 = (1 << (from + to - 1)) - 1;
+    let mut out = Vec::with_capacity(data.len() * from as usize / to as usize);
+
+    for value in data {
+        acc = ((acc << from) | *value as u32) & max_acc;
+        bits += from;
+        while bits >= to {
+            bits -= to;
+            out.push(((acc >> bits) & max_value) as u8);
+        }
+    }
+
+    // Leftover bits must be fewer than one input word and all zero
+    if bits >= from || ((acc << (to - bits)) & max_value) != 0 {
+        return None;
+    }
+
+    Some(out)
+}
+
+/// RFC 4648 base32 without padding
+fn base32_decode(input: &[u8]) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+
+    for c in input {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    // Trailing bits must be zero in a canonical encoding
+    if buffer != 0 {
+        return None;
+    }
+
+    Some(out)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
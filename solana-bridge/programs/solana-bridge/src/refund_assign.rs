@@ -0,0 +1,155 @@
+//! Assignable refund rights
+//!
+//! An outbound transfer with a deadline that the relayer hasn't attested
+//! can be reclaimed once the deadline passes. During a relayer outage
+//! that right is all a sender has, and they may not want to wait for it.
+//! Whoever holds it (see `TransferRecord::refund_owner`) can sell it:
+//! they sign, with that wallet,
+//!
+//!   "refund_assign" || transfer_id (32) || assignee (32)
+//!                   || price (u64 LE) || expires_at (i64 LE)
+//!
+//! and the assignee puts an Ed25519 program instruction checking that
+//! signature right before `assign_refund`, which pays `price` of the
+//! transfer's token to the holder and makes the assignee the refund
+//! owner. If the transfer is delivered after all, there is nothing to
+//! refund; that is the risk the price is discounted for.
+//!
+//! An all-zero assignee lets anyone buy the right at `price`. Once it
+//! is assigned, the old holder's signatures no longer match the refund
+//! owner, so they can't be replayed.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::ed25519_program;
+use anchor_lang::solana_program::sysvar::instructions::{
+    load_current_index_checked, load_instruction_at_checked,
+};
+
+use crate::quote::parse_ed25519;
+use crate::ErrorCode;
+
+const DOMAIN: &[u8] = b"refund_assign";
+
+/// Length of a signed refund assignment
+pub const REFUND_ASSIGNMENT_LEN: usize = 13 + 32 + 32 + 8 + 8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RefundAssignment {
+    pub transfer_id: [u8; 32],
+    /// Who becomes the refund owner; Pubkey::default() for anyone
+    pub assignee: Pubkey,
+    /// Paid to the holder, in the transfer's token
+    pub price: u64,
+    pub expires_at: i64,
+}
+
+impl RefundAssignment {
+    /// The message the holder signs
+    pub fn to_bytes(&self) -> [u8; REFUND_ASSIGNMENT_LEN] {
+        let mut out = [0u8; REFUND_ASSIGNMENT_LEN];
+        out[..13].copy_from_slice(DOMAIN);
+        out[13..45].copy_from_slice(&self.transfer_id);
+        out[45..77].copy_from_slice(self.assignee.as_ref());
+        out[77..85].copy_from_slice(&self.price.to_le_bytes());
+        out[85..93].copy_from_slice(&self.expires_at.to_le_bytes());
+        out
+    }
+
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        if data.len() != REFUND_ASSIGNMENT_LEN || &data[..13] != DOMAIN {
+            return None;
+        }
+
+        Some(RefundAssignment {
+            transfer_id: data[13..45].try_into().ok()?,
+            assignee: Pubkey::try_from(&data[45..77]).ok()?,
+            price: u64::from_le_bytes(data[77..85].try_into().ok()?),
+            expires_at: i64::from_le_bytes(data[85..93].try_into().ok()?),
+        })
+    }
+
+    /**
+     * Does this assignment cover the sale?
+     *
+     * Fails unless it's for this transfer, `assignee` may take it, and
+     * it hasn't expired at `now`.
+     */
+    pub fn check(&self, transfer_id: &[u8; 32], assignee: &Pubkey, now: i64) -> Result<()> {
+        require!(
+            self.transfer_id == *transfer_id
+                && (self.assignee == Pubkey::default() || self.assignee == *assignee),
+            ErrorCode::InvalidRefundAssignment
+        );
+        require!(now < self.expires_at, ErrorCode::RefundAssignmentExpired);
+        Ok(())
+    }
+}
+
+/**
+ * Read the assignment signed in the instruction right before this one
+ *
+ * The signer must be the transfer's refund owner.
+ */
+pub fn load(instructions: &AccountInfo, holder: &Pubkey) -> Result<RefundAssignment> {
+    let current = load_current_index_checked(instructions)?;
+    require!(current > 0, ErrorCode::InvalidRefundAssignment);
+    let ix = load_instruction_at_checked(current as usize - 1, instructions)?;
+    require_keys_eq!(
+        ix.program_id,
+        ed25519_program::ID,
+        ErrorCode::InvalidRefundAssignment
+    );
+
+    let (signer, message) = parse_ed25519(&ix.data).ok_or(ErrorCode::InvalidRefundAssignment)?;
+    require_keys_eq!(signer, *holder, ErrorCode::Unauthorized);
+    RefundAssignment::from_bytes(message)
+        .ok_or_else(|| error!(ErrorCode::InvalidRefundAssignment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assignment() -> RefundAssignment {
+        RefundAssignment {
+            transfer_id: [9; 32],
+            assignee: Pubkey::new_from_array([5; 32]),
+            price: 950_000,
+            expires_at: 1_700_000_000,
+        }
+    }
+
+    #[test]
+    fn assignment_round_trips() {
+        let bytes = assignment().to_bytes();
+        assert_eq!(&bytes[..13], b"refund_assign");
+        assert_eq!(RefundAssignment::from_bytes(&bytes), Some(assignment()));
+        assert_eq!(RefundAssignment::from_bytes(&bytes[1..]), None);
+    }
+
+    #[test]
+    fn rejects_claim_authorizations() {
+        let mut bytes = assignment().to_bytes();
+        bytes[..13].copy_from_slice(b"claim_auth\0\0\0");
+        assert_eq!(RefundAssignment::from_bytes(&bytes), None);
+    }
+
+    #[test]
+    fn binds_transfer_and_assignee() {
+        let auth = assignment();
+        let now = auth.expires_at - 1;
+
+        assert!(auth.check(&auth.transfer_id, &auth.assignee, now).is_ok());
+        assert!(auth.check(&[8; 32], &auth.assignee, now).is_err());
+        assert!(auth.check(&auth.transfer_id, &Pubkey::new_from_array([6; 32]), now).is_err());
+        assert!(auth.check(&auth.transfer_id, &auth.assignee, auth.expires_at).is_err());
+    }
+
+    #[test]
+    fn default_assignee_allows_anyone() {
+        let auth = RefundAssignment { assignee: Pubkey::default(), ..assignment() };
+        let anyone = Pubkey::new_from_array([6; 32]);
+
+        assert!(auth.check(&auth.transfer_id, &anyone, auth.expires_at - 1).is_ok());
+    }
+}
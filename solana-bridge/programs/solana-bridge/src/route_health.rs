@@ -0,0 +1,132 @@
+//! Per-route health scores
+//!
+//! A transfer gets stuck long before anything on-chain says so: the
+//! relayer stops beating, its queue for the route grows, acks slow down,
+//! or reconciliation keeps finding transfers the listeners missed. The
+//! relayer measures the last three per relay route and a crank (the
+//! relayer or a guardian) posts them with `report_route_health`. The
+//! program adds the age of the relayer's last heartbeat from its
+//! OperatorLiveness entry, which the reporter can't misstate, and scores
+//! the route 0-100 into a RouteHealth account the SDK reads.
+//!
+//! Each input costs up to its weight in points, nothing at or below its
+//! "ok" threshold and all of them at its "down" one, linearly between:
+//!
+//!   heartbeat age      35   120 s .. 900 s
+//!   queue depth        25   16 .. 256 transfers
+//!   ack latency        25   300 s .. 3600 s
+//!   reconciliation     15   0 .. 10 transfers picked up; all if it failed
+
+use anchor_lang::prelude::*;
+
+/// Scores from here up are healthy
+pub const HEALTHY_SCORE: u8 = 80;
+
+/// Scores from here up (and below HEALTHY_SCORE) are degraded
+pub const DEGRADED_SCORE: u8 = 50;
+
+/// (weight, ok, down) of each input
+const HEARTBEAT: (u64, u64, u64) = (35, 120, 900);
+const QUEUE: (u64, u64, u64) = (25, 16, 256);
+const ACK_LATENCY: (u64, u64, u64) = (25, 300, 3600);
+const RECONCILIATION: (u64, u64, u64) = (15, 0, 10);
+
+/// What the relayer measured on one route
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, Default, PartialEq, Eq, InitSpace)]
+pub struct RouteHealthReport {
+    /// Outbound transfers on the route still waiting for delivery
+    pub queue_depth: u32,
+    /// Average seconds from lock to delivery over the route's recent transfers
+    pub ack_latency_secs: u32,
+    /// Transfers the last reconciliation pass had to relay itself
+    pub reconciled: u32,
+    /// Did the last reconciliation pass run to the end?
+    pub reconcile_ok: bool,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum RouteStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+impl RouteStatus {
+    pub fn of(score: u8) -> Self {
+        if score >= HEALTHY_SCORE {
+            RouteStatus::Healthy
+        } else if score >= DEGRADED_SCORE {
+            RouteStatus::Degraded
+        } else {
+            RouteStatus::Unhealthy
+        }
+    }
+}
+
+fn penalty((weight, ok, down): (u64, u64, u64), value: u64) -> u64 {
+    if value <= ok {
+        0
+    } else if value >= down {
+        weight
+    } else {
+        weight * (value - ok) / (down - ok)
+    }
+}
+
+/// Score a route from `report` and the relayer's heartbeat age (seconds)
+pub fn score(heartbeat_age: u64, report: &RouteHealthReport) -> u8 {
+    let reconciled = if report.reconcile_ok {
+        report.reconciled as u64
+    } else {
+        RECONCILIATION.2
+    };
+    let lost = penalty(HEARTBEAT, heartbeat_age)
+        + penalty(QUEUE, report.queue_depth as u64)
+        + penalty(ACK_LATENCY, report.ack_latency_secs as u64)
+        + penalty(RECONCILIATION, reconciled);
+    (100 - lost) as u8
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(queue_depth: u32, ack_latency_secs: u32) -> RouteHealthReport {
+        RouteHealthReport {
+            queue_depth,
+            ack_latency_secs,
+            reconciled: 0,
+            reconcile_ok: true,
+        }
+    }
+
+    #[test]
+    fn a_quiet_route_scores_full() {
+        assert_eq!(score(30, &report(0, 60)), 100);
+        assert_eq!(RouteStatus::of(100), RouteStatus::Healthy);
+    }
+
+    #[test]
+    fn penalties_scale_between_thresholds() {
+        // Halfway to "down" on the queue costs half its weight
+        assert_eq!(score(0, &report(136, 0)), 100 - 12);
+        assert_eq!(score(0, &report(10_000, 0)), 75);
+        assert_eq!(score(900, &report(256, 3600)), 15);
+    }
+
+    #[test]
+    fn failed_reconciliation_costs_its_whole_weight() {
+        let failed = RouteHealthReport {
+            reconcile_ok: false,
+            ..report(0, 0)
+        };
+        assert_eq!(score(0, &failed), 85);
+    }
+
+    #[test]
+    fn a_silent_relayer_degrades_the_route() {
+        assert_eq!(score(500, &report(0, 0)), 100 - 17);
+        assert_eq!(RouteStatus::of(score(u64::MAX, &report(0, 0))), RouteStatus::Degraded);
+        assert_eq!(RouteStatus::of(score(u64::MAX, &report(256, 0))), RouteStatus::Unhealthy);
+    }
+}
@@ -0,0 +1,4136 @@
+//! Program state (like your Solidity contract storage)
+//!
+//! Every account the program owns, the types stored in them, and the
+//! helpers that read and update them.
+
+use crate::*;
+use anchor_lang::Discriminator;
+use names::MAX_RECIPIENT_NAME_LEN;
+
+pub use bridge_core::chain_ids;
+pub use bridge_core::transfer::OutboxKind;
+
+/**
+ * Bridge state
+ *
+ * In Solidity, this would be:
+ *   contract Bridge {
+ *       address public owner;
+ *       uint256 public nonce;
+ *       bool public paused;
+ *       mapping(uint256 => bool) public processedNonces;
+ *   }
+ *
+ * In Solana, state lives in a separate account
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct BridgeState {
+    pub owner: Pubkey,
+    /// Outbound nonce counter from before per-route sequences; lock,
+    /// lock_batch and burn now take theirs from a RouteSequence
+    pub nonce: u64,
+    pub paused: bool,
+    /// Set by transfer_ownership until the new owner accepts
+    pub pending_owner: Pubkey,
+    #[max_len(10000)]
+    pub processed_nonces: Vec<u64>,
+    /// Repeated mints/unlocks succeed as no-ops instead of failing
+    pub idempotent_delivery: bool,
+    /// Every inbound nonce below this is processed (moved out of
+    /// `processed_nonces` by `prune`)
+    pub processed_floor: u64,
+    /// Bumps of this account, the bridge authority and the outbox, so
+    /// hot paths check those PDAs without searching (see `cache_bumps`)
+    pub bump: u8,
+    pub authority_bump: u8,
+    pub outbox_bump: u8,
+    /// Epochs mint and unlock keep working without a guardian heartbeat (0 = off)
+    pub heartbeat_epochs: u64,
+    pub last_heartbeat_epoch: u64,
+    /// Which lock events to emit (see `set_event_encoding`)
+    pub event_encoding: EventEncoding,
+    /// May grant and revoke fee exemptions alongside the owner
+    pub fee_manager: Pubkey,
+    /// Mints record a claim for the recipient instead of minting (see `claim`)
+    pub claim_delivery: bool,
+    /// Holders of this mint's tokens get the pass benefits below
+    /// (Pubkey::default() = no bridge pass; see `set_bridge_pass`)
+    pub pass_mint: Pubkey,
+    pub pass_discount_bps: u16,
+    pub pass_tier_boost: u8,
+    /// Which of the deployment's bridges this is (see `instance`)
+    pub instance_id: u64,
+    /// `instance::seed(instance_id)`, which every PDA of the instance
+    /// carries; stored so clients resolving accounts from the IDL can
+    /// read it
+    #[max_len(8)]
+    pub instance_seed: Vec<u8>,
+    /// Shards handed to RouteSequences so far (see `init_route_sequence`)
+    pub route_shards: u32,
+    /// The owner paused the bridge (`pause`), so a bonded pause ending
+    /// doesn't resume it; `paused` alone may be a bonded pause
+    pub owner_paused: bool,
+}
+
+impl BridgeState {
+    /// Whether inbound `nonce` was delivered (SAME AS: processedNonces[nonce])
+    pub fn is_processed(&self, nonce: u64) -> bool {
+        nonce < self.processed_floor || self.processed_nonces.contains(&nonce)
+    }
+
+    /// Fail if heartbeats are required and none was posted in time
+    pub fn check_guardians_live(&self, epoch: u64) -> Result<()> {
+        require!(
+            self.heartbeat_epochs == 0
+                || epoch <= self.last_heartbeat_epoch.saturating_add(self.heartbeat_epochs),
+            ErrorCode::GuardiansUnresponsive
+        );
+        Ok(())
+    }
+
+    /**
+     * Whether inbound `nonce` was already delivered
+     *
+     * A repeat is AlreadyProcessed unless idempotent delivery is on. If
+     * the transfer record is gone (pruned) it errors anyway, so the
+     * repeat doesn't leave an empty record behind.
+     */
+    pub fn is_duplicate_delivery(&self, nonce: u64, record: &TransferRecord) -> Result<bool> {
+        if !self.is_processed(nonce) {
+            return Ok(false);
+        }
+        require!(
+            self.idempotent_delivery && record.nonce == nonce && record.transfer_id != [0; 32],
+            ErrorCode::AlreadyProcessed
+        );
+        Ok(true)
+    }
+
+    /**
+     * End a bonded pause, leaving any pause of the owner's in force
+     *
+     * Returns whether the bridge resumed (and a BridgeStatus has to go out).
+     */
+    pub fn lift_bonded_pause(&mut self) -> bool {
+        let resumed = self.paused && !self.owner_paused;
+        self.paused = self.owner_paused;
+        resumed
+    }
+
+    /// Whether `key` may manage fee exemptions (the owner or fee manager)
+    pub fn is_fee_manager(&self, key: &Pubkey) -> bool {
+        *key == self.owner || *key == self.fee_manager
+    }
+
+    /**
+     * (fee discount, credential tier boost) earned by a bridge pass
+     *
+     * Nothing without a pass or with an empty one (a sold NFT leaves an
+     * empty account behind). The account's mint and owner are checked
+     * by the instruction's constraints.
+     */
+    pub fn pass_benefits(&self, pass: Option<&InterfaceTokenAccount>) -> (u16, u8) {
+        match pass {
+            Some(pass) if pass.amount > 0 => (self.pass_discount_bps, self.pass_tier_boost),
+            _ => (0, 0),
+        }
+    }
+
+    /// `features` bits of what is switched on
+    pub fn feature_bits(&self) -> u64 {
+        [
+            (self.idempotent_delivery, features::IDEMPOTENT_DELIVERY),
+            (self.claim_delivery, features::CLAIM_DELIVERY),
+            (self.pass_mint != Pubkey::default(), features::BRIDGE_PASS),
+            (self.heartbeat_epochs > 0, features::GUARDIAN_HEARTBEAT),
+        ]
+        .iter()
+        .filter(|(on, _)| *on)
+        .fold(0, |bits, (_, bit)| bits | bit)
+    }
+}
+
+/// Admin log entries kept before the oldest is overwritten
+pub const ADMIN_LOG_CAPACITY: usize = 64;
+
+/**
+ * Append-only log of privileged actions (ring buffer)
+ *
+ * Once full, entry `total % ADMIN_LOG_CAPACITY` is the oldest and is
+ * overwritten next, so the latest 64 actions are always on-chain.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct AdminLog {
+    /// Actions recorded since initialization
+    pub total: u64,
+    #[max_len(ADMIN_LOG_CAPACITY)]
+    pub entries: Vec<AdminLogEntry>,
+}
+
+impl AdminLog {
+    pub fn record(&mut self, actor: Pubkey, action: AdminAction) -> Result<()> {
+        let entry = AdminLogEntry {
+            actor,
+            action,
+            timestamp: Clock::get()?.unix_timestamp,
+        };
+
+        if self.entries.len() < ADMIN_LOG_CAPACITY {
+            self.entries.push(entry);
+        } else {
+            let slot = (self.total % ADMIN_LOG_CAPACITY as u64) as usize;
+            self.entries[slot] = entry;
+        }
+        math::increment(&mut self.total)?;
+
+        Ok(())
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct AdminLogEntry {
+    pub actor: Pubkey,
+    pub action: AdminAction,
+    pub timestamp: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AdminAction {
+    Pause,
+    Unpause,
+    SetVerifyingKey,
+    SetGuardians,
+    SetOptimisticConfig,
+    AddWatcher,
+    RemoveWatcher,
+    RegisterChain,
+    SetChainParams,
+    SetChainEnabled,
+    SetAddressPrefix,
+    RegisterToken,
+    SetIbcDenom,
+    WithdrawFees,
+    CreateOwnerMultisig,
+    TransferOwnership,
+    AcceptOwnership,
+    SetRelayerRewards,
+    SetFeeTiers,
+    SetTokenRestrictions,
+    SetPauseBondConfig,
+    CreateWrappedMint,
+    UpdateWrappedMetadata,
+    SetAllowedExtensions,
+    SetTokenAccounting,
+    ConfigureInbox,
+    PauseToken,
+    UnpauseToken,
+    CreateGuardianCouncil,
+    Checkpoint,
+    PruneTransferRecord,
+    ImportState,
+    SealImport,
+    SetIdempotentDelivery,
+    SetHeartbeatInterval,
+    EjectRelayer,
+    ReinstateRelayer,
+    SetFeeRoute,
+    RemoveFeeRoute,
+    SetTrustModel,
+    AllowMessageTarget,
+    RemoveMessageTarget,
+    SetEventEncoding,
+    SetChainStatus,
+    FreezeWrappedAccount,
+    ThawWrappedAccount,
+    SetPriceFeed,
+    SetFeeManager,
+    SetClaimDelivery,
+    SetBridgePass,
+    SetGasReimbursement,
+    TransferUpgradeAuthority,
+    FinalizeProgram,
+    InitCompressedStore,
+    SetRelayRoute,
+    RemoveRelayRoute,
+    ConfigureThrottle,
+    ConfigurePriorityTips,
+    SetArchiver,
+    ConfigureVaults,
+    SetVaultPolicy,
+    SetBurnApproval,
+    SetDustPolicy,
+    RecordBuild,
+    SetListingConfig,
+    SetSolFee,
+    WithdrawSolFees,
+    QueueEscrowMigration,
+    MigrateEscrow,
+    SetDeliveryFee,
+    SetPriceBreaker,
+    SetTerms,
+    SetSla,
+    SetSenderAllowlist,
+    AllowSender,
+    DisallowSender,
+    SendToInstance,
+    ReceiveFromInstance,
+    AllowYieldVenue,
+    DepositYield,
+}
+
+/**
+ * Groth16 verifying key for the zk inbound path
+ *
+ * Points are big-endian, in the same encoding as the EVM precompiles.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ZkVerifier {
+    pub alpha_g1: [u8; 64],
+    pub beta_g2: [u8; 128],
+    pub gamma_g2: [u8; 128],
+    pub delta_g2: [u8; 128],
+    #[max_len(3)]
+    pub ic: Vec<[u8; 64]>,
+}
+
+/// Public inputs to the lock-inclusion circuit (message hash hi/lo limbs)
+pub const ZK_PUBLIC_INPUTS: usize = 2;
+
+/**
+ * Message hash for an inbound transfer
+ *
+ * Same as Solidity:
+ *   keccak256(abi.encodePacked(bytes32 recipient, uint256 amount, uint256 nonce))
+ */
+pub fn transfer_message_hash(recipient: &Pubkey, amount: u64, nonce: u64) -> [u8; 32] {
+    codec::transfer_message_hash(&recipient.to_bytes(), amount, nonce)
+}
+
+/**
+ * Deterministic ID of an outbound transfer
+ *
+ * keccak256(source chain || destination chain || guardian set index ||
+ *           nonce || token mint || amount || sender || recipient)
+ * with integers big-endian (the set index 4 bytes, the rest 8) and the
+ * recipient as its UTF-8 string, so relayers and the destination side can
+ * recompute it from the event.
+ *
+ * The destination keys replay protection on this ID and its guardians sign
+ * over it, so the set index scopes both: a signature collected under one
+ * set can never authorize a transfer emitted under another, whatever
+ * attestation format the next set adopts.
+ */
+#[allow(clippy::too_many_arguments)]
+pub fn transfer_id(
+    source_chain_id: u64,
+    dest_chain_id: u64,
+    guardian_set_index: u32,
+    nonce: u64,
+    token: &Pubkey,
+    amount: u64,
+    sender: &Pubkey,
+    recipient: &str,
+) -> [u8; 32] {
+    codec::transfer_id(
+        source_chain_id,
+        dest_chain_id,
+        guardian_set_index,
+        nonce,
+        &token.to_bytes(),
+        amount,
+        &sender.to_bytes(),
+        recipient,
+    )
+}
+
+/// Lowercase hex, for IDs in log lines
+pub fn hex_encode(bytes: &[u8]) -> String {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut out = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        out.push(DIGITS[(byte >> 4) as usize] as char);
+        out.push(DIGITS[(byte & 0x0f) as usize] as char);
+    }
+    out
+}
+
+/// Maximum number of guardians in a set
+pub const MAX_GUARDIANS: usize = 19;
+
+/// Maximum size of challenge evidence (kept in the event, hashed on-chain)
+pub const MAX_EVIDENCE_LEN: usize = 512;
+
+/**
+ * Guardian set
+ *
+ * `index` increases on every update so signatures can be
+ * scoped to the set that produced them.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianSet {
+    pub index: u32,
+    pub threshold: u8,
+    #[max_len(19)]
+    pub guardians: Vec<Pubkey>,
+}
+
+impl GuardianSet {
+    /// The set behind the guardian set PDA `info`, if one was ever set
+    fn load(info: &AccountInfo) -> Result<Option<GuardianSet>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(Some(GuardianSet::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+
+    /// Do `accounts` carry a quorum of the set behind `info` (if it exists)?
+    pub fn has_quorum(info: &AccountInfo, accounts: &[AccountInfo]) -> Result<bool> {
+        Ok(Self::load(info)?
+            .is_some_and(|set| count_guardian_signers(&set, accounts) >= set.threshold as usize))
+    }
+
+    /// Is `key` in the set behind `info` (if it exists)?
+    pub fn is_member(info: &AccountInfo, key: &Pubkey) -> Result<bool> {
+        Ok(Self::load(info)?.is_some_and(|set| set.guardians.contains(key)))
+    }
+
+    /// Index of the set behind `info`; 0 until one is first set
+    pub fn index_of(info: &AccountInfo) -> Result<u32> {
+        Ok(Self::load(info)?.map_or(0, |set| set.index))
+    }
+
+    /**
+     * Drop `key` from the set, if it's in it
+     *
+     * The threshold shrinks with the set if it has to. The last guardian
+     * can't be removed; replace the set with set_guardians instead.
+     */
+    pub fn remove(&mut self, key: &Pubkey) -> Result<bool> {
+        let Some(position) = self.guardians.iter().position(|g| g == key) else {
+            return Ok(false);
+        };
+        require!(self.guardians.len() > 1, ErrorCode::InvalidGuardianSet);
+
+        self.guardians.remove(position);
+        self.threshold = self.threshold.min(self.guardians.len() as u8);
+        self.index += 1;
+        Ok(true)
+    }
+}
+
+/**
+ * Optimistic settlement parameters
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct OptimisticConfig {
+    /// Seconds an attestation can be challenged before it executes
+    pub challenge_period: i64,
+    /// Lamports a relayer (and a challenger) must put up
+    pub bond_amount: u64,
+    /// Share of a slashed bond kept by the protocol
+    pub protocol_cut_bps: u16,
+    /// Receives the protocol cut
+    pub treasury: Pubkey,
+}
+
+/**
+ * Optimistically attested inbound transfer
+ *
+ * Bonds are escrowed separately in a system-owned vault PDA
+ * (seeds: "bond_vault", nonce) so payouts never touch this account's rent.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Attestation {
+    pub relayer: Pubkey,
+    /// Wrapped mint the transfer releases; execution must mint this one
+    pub mint: Pubkey,
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+    pub relayer_bond: u64,
+    pub posted_at: i64,
+    pub executable_at: i64,
+    pub status: AttestationStatus,
+    pub challenger: Pubkey,
+    pub challenger_bond: u64,
+    pub evidence_hash: [u8; 32],
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum AttestationStatus {
+    Pending,
+    Challenged,
+    Executed,
+    Vetoed,
+    Cancelled,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ChallengeOutcome {
+    /// The attestation was fraudulent; the relayer is slashed
+    Fraud,
+    /// The attestation was correct; the challenger is slashed
+    Valid,
+}
+
+/// Longest recipient any supported chain accepts (bech32 limit)
+pub const MAX_REMOTE_ADDRESS_LEN: usize = 90;
+
+/**
+ * Lifecycle of a single transfer (one PDA per transfer)
+ *
+ * Outbound (lock/burn): seeds "transfer", "out", nonce.
+ * Inbound (mint paths): seeds "transfer", "in", source nonce.
+ * Lets clients answer "where is my transfer?" with one account read.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TransferRecord {
+    /// Outbound: see `transfer_id`. Inbound: `transfer_message_hash`
+    pub transfer_id: [u8; 32],
+    pub direction: TransferDirection,
+    pub nonce: u64,
+    pub status: TransferStatus,
+    /// Destination chain (outbound) or source chain (inbound)
+    pub remote_chain_id: u64,
+    /// Sender (outbound) or recipient (inbound) on Solana
+    pub local_account: Pubkey,
+    /// Recipient on the destination chain; empty for inbound transfers
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub remote_address: String,
+    pub amount: u64,
+    pub created_at: i64,
+    pub updated_at: i64,
+    /// Slot, time and relayer that completed the transfer (delivered it
+    /// inbound, acknowledged its delivery outbound)
+    pub processed_slot: u64,
+    pub processed_at: i64,
+    pub processed_by: Pubkey,
+    /// Token locked or burned (outbound), or the wrapped mint an inbound
+    /// claim is for
+    pub mint: Pubkey,
+    /// Outbound: locked in escrow (true) or burned (false). Inbound: a
+    /// quarantined unlock, still in escrow (see `claim_unlock`)
+    pub escrowed: bool,
+    /// Outbound only: attest by this time or the sender may reclaim; 0 = none
+    pub deadline: i64,
+    /// Outbound only: the sender holds a deposit receipt for this transfer
+    pub receipt: bool,
+    /// Name the recipient was resolved from (ENS outbound, .sol inbound);
+    /// empty if none was pinned (see `pin_recipient_name`)
+    #[max_len(MAX_RECIPIENT_NAME_LEN)]
+    pub recipient_name: String,
+    /// Slot the record was opened in (0 for records older than this field)
+    pub created_slot: u64,
+    /// Outbound only: owner of the account refunds go to instead of the
+    /// sender's; default = the sender (see `refund_owner`)
+    pub recovery: Pubkey,
+    /// Outbound only: bridge fee paid in the token (0 if paid in SOL)
+    pub fee: u64,
+    /// Outbound only: fee rebate owed for missing the SLA, until the
+    /// sender claims it (see SlaConfig)
+    pub sla_rebate: u64,
+    /// Outbound only: place in its token and destination's RouteSequence;
+    /// 0 for transfers from before route sequences
+    pub route_sequence: u64,
+}
+
+impl TransferRecord {
+    /**
+     * Create an outbound record PDA by hand
+     *
+     * For instructions that open a variable number of records (lock_batch),
+     * which can't declare them as `init` accounts.
+     */
+    pub fn create_outbound<'info>(
+        info: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program_info: &AccountInfo<'info>,
+        instance: &[u8],
+        nonce: u64,
+    ) -> Result<TransferRecord> {
+        let nonce_bytes = nonce.to_le_bytes();
+        let (address, bump) =
+            Pubkey::find_program_address(&[b"transfer", instance, b"out", &nonce_bytes], &crate::ID);
+        require_keys_eq!(info.key(), address, ErrorCode::InvalidBatch);
+
+        let space = 8 + TransferRecord::INIT_SPACE;
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                &[&[b"transfer", instance, b"out", &nonce_bytes, &[bump]]],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let mut data = info.try_borrow_mut_data()?;
+        data[..8].copy_from_slice(&TransferRecord::DISCRIMINATOR);
+        TransferRecord::try_deserialize(&mut &data[..])
+    }
+
+    /// Write a record created with `create_outbound` back to its account
+    pub fn save(&self, info: &AccountInfo) -> Result<()> {
+        self.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+
+    /// Fill in an outbound record (lock/burn)
+    pub fn open_outbound(
+        &mut self,
+        transfer_id: [u8; 32],
+        nonce: u64,
+        dest_chain_id: u64,
+        sender: Pubkey,
+        recipient: String,
+        amount: u64,
+    ) -> Result<()> {
+        self.transfer_id = transfer_id;
+        self.direction = TransferDirection::Outbound;
+        self.nonce = nonce;
+        self.remote_chain_id = dest_chain_id;
+        self.local_account = sender;
+        self.remote_address = recipient;
+        self.amount = amount;
+        let clock = Clock::get()?;
+        self.created_at = clock.unix_timestamp;
+        self.created_slot = clock.slot;
+        self.set_status(TransferStatus::Initiated)
+    }
+
+    /// Fill in an inbound record (source chain is Ethereum for now)
+    pub fn open_inbound(&mut self, nonce: u64, recipient: Pubkey, amount: u64) -> Result<()> {
+        self.transfer_id = transfer_message_hash(&recipient, amount, nonce);
+        self.direction = TransferDirection::Inbound;
+        self.nonce = nonce;
+        self.remote_chain_id = chain_ids::ETHEREUM;
+        self.local_account = recipient;
+        self.remote_address = String::new();
+        self.amount = amount;
+        let clock = Clock::get()?;
+        self.created_at = clock.unix_timestamp;
+        self.created_slot = clock.slot;
+        Ok(())
+    }
+
+    /// Set an outbound transfer's deadline, which must be in the future
+    pub fn set_deadline(&mut self, deadline: Option<i64>) -> Result<()> {
+        if let Some(deadline) = deadline {
+            require!(deadline > self.created_at, ErrorCode::InvalidDeadline);
+            self.deadline = deadline;
+        }
+        Ok(())
+    }
+
+    /// Send an outbound transfer's refunds to `recovery` rather than the
+    /// sender; naming the sender or the default key changes nothing
+    pub fn set_recovery(&mut self, recovery: Option<Pubkey>) -> Option<Pubkey> {
+        let recovery = recovery.filter(|r| *r != Pubkey::default() && *r != self.local_account)?;
+        self.recovery = recovery;
+        Some(recovery)
+    }
+
+    /// Who a refund of this outbound transfer is paid to
+    pub fn refund_owner(&self) -> Pubkey {
+        if self.recovery == Pubkey::default() {
+            self.local_account
+        } else {
+            self.recovery
+        }
+    }
+
+    /// Has the deadline passed without the relayer attesting?
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.deadline != 0 && now > self.deadline && self.status == TransferStatus::Initiated
+    }
+
+    pub fn set_status(&mut self, status: TransferStatus) -> Result<()> {
+        self.status = status;
+        self.updated_at = Clock::get()?.unix_timestamp;
+        Ok(())
+    }
+
+    /// Complete a transfer, recording when and by whom (inbound: who
+    /// delivered it; outbound: who acknowledged its delivery)
+    pub fn mark_processed(&mut self, relayer: Pubkey) -> Result<()> {
+        let clock = Clock::get()?;
+        self.processed_slot = clock.slot;
+        self.processed_at = clock.unix_timestamp;
+        self.processed_by = relayer;
+        self.set_status(TransferStatus::Completed)
+    }
+
+    /**
+     * Slots from the record being opened to the transfer completing
+     *
+     * A direct mint or unlock opens and completes the record in one
+     * instruction, so only transfers first seen earlier (an optimistic
+     * attestation) show a delay. For outbound transfers it is lock to
+     * acknowledgement. Records without `created_slot` give 0.
+     */
+    pub fn latency_slots(&self) -> u64 {
+        if self.created_slot == 0 {
+            return 0;
+        }
+        self.processed_slot.saturating_sub(self.created_slot)
+    }
+
+    /// Owe the sender the rebate a just-acknowledged transfer earned
+    /// under the SLA behind `sla`, if any; returns it
+    pub fn credit_sla_rebate(&mut self, sla: &AccountInfo) -> Result<u64> {
+        let rebate = SlaConfig::rebate_for(sla, self)?;
+        self.sla_rebate = rebate;
+        Ok(rebate)
+    }
+
+    /// Nothing more can happen to the transfer
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self.status,
+            TransferStatus::Completed
+                | TransferStatus::Refunded
+                | TransferStatus::Vetoed
+                | TransferStatus::Cancelled
+        )
+    }
+
+    /**
+     * Checkpoint leaf:
+     *   keccak256(transfer_id || direction || nonce || status || amount)
+     */
+    pub fn checkpoint_leaf(&self) -> [u8; 32] {
+        keccak::hashv(&[
+            &self.transfer_id,
+            &[self.direction as u8],
+            &self.nonce.to_le_bytes(),
+            &[self.status as u8],
+            &self.amount.to_le_bytes(),
+        ])
+        .to_bytes()
+    }
+
+    /**
+     * Leaf in the compressed store: keccak256 of the record's Borsh
+     * serialization (no discriminator), as logged in
+     * `TransferRecordCompressed`
+     */
+    pub fn compressed_leaf(&self) -> Result<[u8; 32]> {
+        Ok(keccak::hash(&self.try_to_vec()?).to_bytes())
+    }
+}
+
+/// Longest memo a lock can carry (a sealed box adds 48 bytes to the text)
+pub const MAX_MEMO_LEN: usize = 256;
+
+/**
+ * Memo a lock carries for its recipient (seeds: "memo", nonce)
+ *
+ * Opaque to the bridge: the SDK seals it client-side to the recipient's
+ * X25519 key (see the SDK's `memo` module), so a settlement reference can
+ * travel with the transfer without being readable on either chain. The
+ * account is sized to the memo, and `MemoAttached` carries the same
+ * bytes for indexers.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TransferMemo {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    #[max_len(MAX_MEMO_LEN)]
+    pub memo: Vec<u8>,
+}
+
+impl TransferMemo {
+    /// Memo account PDA for an outbound nonce of the instance with seed `instance`
+    pub fn address(instance: &[u8], nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"memo", instance, &nonce.to_le_bytes()], &crate::ID)
+    }
+
+    /**
+     * Create the memo account of outbound transfer `nonce`, paid by `payer`
+     *
+     * `info` must be the nonce's memo PDA; `memo` must be 1 to
+     * MAX_MEMO_LEN bytes.
+     */
+    pub fn create<'info>(
+        info: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program_info: &AccountInfo<'info>,
+        instance: &[u8],
+        transfer_id: [u8; 32],
+        nonce: u64,
+        memo: Vec<u8>,
+    ) -> Result<()> {
+        require!(!memo.is_empty() && memo.len() <= MAX_MEMO_LEN, ErrorCode::InvalidMemo);
+        let (address, bump) = Self::address(instance, nonce);
+        require_keys_eq!(info.key(), address, ErrorCode::InvalidMemo);
+
+        let space = 8 + 32 + 8 + 4 + memo.len();
+        let nonce_bytes = nonce.to_le_bytes();
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                &[&[b"memo", instance, &nonce_bytes, &[bump]]],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let account = TransferMemo { transfer_id, nonce, memo };
+        account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
+/**
+ * How a lock's recipient is derived, for a smart wallet that may not be
+ * deployed yet (seeds: "smart_wallet", nonce)
+ *
+ * Checked against the recipient when the lock is made (see
+ * smart_wallet.rs); the relayer reads it to deploy the wallet before
+ * delivering, if the wallet has no code by then.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct SmartWalletRecipient {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub derivation: WalletDerivation,
+}
+
+impl SmartWalletRecipient {
+    /// Smart wallet account PDA for an outbound nonce of the instance with seed `instance`
+    pub fn address(instance: &[u8], nonce: u64) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[b"smart_wallet", instance, &nonce.to_le_bytes()], &crate::ID)
+    }
+
+    /// Create the smart wallet account of outbound transfer `nonce`, paid by `payer`
+    pub fn create<'info>(
+        info: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program_info: &AccountInfo<'info>,
+        instance: &[u8],
+        transfer_id: [u8; 32],
+        nonce: u64,
+        derivation: WalletDerivation,
+    ) -> Result<()> {
+        let (address, bump) = Self::address(instance, nonce);
+        require_keys_eq!(info.key(), address, ErrorCode::InvalidSmartWallet);
+
+        let space = 8 + Self::INIT_SPACE;
+        let nonce_bytes = nonce.to_le_bytes();
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                &[&[b"smart_wallet", instance, &nonce_bytes, &[bump]]],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let account = SmartWalletRecipient { transfer_id, nonce, derivation };
+        account.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TransferDirection {
+    Outbound,
+    Inbound,
+}
+
+/// Longest reason cancel_pending records
+pub const MAX_CANCEL_REASON_LEN: usize = 128;
+
+/// Most entries a lock_batch can carry
+pub const MAX_LOCK_BATCH: usize = 16;
+
+/// One recipient of a lock_batch (amount before fees)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LockBatchEntry {
+    pub amount: u64,
+    pub recipient: String,
+}
+
+/// How one leg of a batch fared on its destination (settle_batch)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LegOutcome {
+    pub nonce: u64,
+    /// false if the destination rejected it; the leg is refunded
+    pub delivered: bool,
+    /// Delivering transaction (zeros if unknown or failed)
+    pub dest_tx: [u8; 32],
+}
+
+/// Entries the outbox keeps before overwriting the oldest
+pub const OUTBOX_CAPACITY: usize = 64;
+
+/**
+ * Outbound transfer queue, readable with a single getAccountInfo
+ *
+ * lock and burn append a compact entry under a sequence number that only
+ * goes up. Relayers remember the next sequence they expect and read the
+ * ring (entry = sequence % OUTBOX_CAPACITY); if they fall more than a
+ * full ring behind, the TransferRecord PDAs still have every transfer.
+ * The recipient string is in the TransferRecord too (["transfer", "out", nonce]).
+ * Pausing and resuming the bridge queue a BridgeStatus entry in the same ring,
+ * attest_vault_balance a VaultBalance entry and send_message a Message entry.
+ */
+#[account(zero_copy)]
+pub struct Outbox {
+    /// Sequence number the next entry gets
+    pub next_sequence: u64,
+    pub entries: [OutboxEntry; OUTBOX_CAPACITY],
+}
+
+impl Outbox {
+    pub fn push(
+        &mut self,
+        kind: OutboxKind,
+        record: &TransferRecord,
+        mint: Pubkey,
+        route_id: u32,
+    ) -> Result<u64> {
+        let sequence = self.next_sequence;
+        self.entries[sequence as usize % OUTBOX_CAPACITY] = OutboxEntry {
+            sequence,
+            nonce: record.nonce,
+            dest_chain_id: record.remote_chain_id,
+            amount: record.amount,
+            slot: Clock::get()?.slot,
+            timestamp: record.created_at,
+            transfer_id: record.transfer_id,
+            sender: record.local_account,
+            mint,
+            kind: kind as u8,
+            _padding: [0; 3],
+            route_id,
+        };
+        math::increment(&mut self.next_sequence)?;
+        Ok(sequence)
+    }
+
+    /**
+     * Queue a BridgeStatus message telling the EVM side to pause or resume
+     *
+     * Not a transfer: `amount` is 1 for paused and 0 for resumed, and
+     * `transfer_id` holds the bridge_status_hash the relayer signs.
+     * Returns the sequence and that hash.
+     */
+    pub fn push_status(&mut self, paused: bool) -> Result<(u64, [u8; 32])> {
+        let sequence = self.next_sequence;
+        let clock = Clock::get()?;
+        let message_hash = codec::bridge_status_hash(chain_ids::SOLANA, paused, sequence);
+        self.entries[sequence as usize % OUTBOX_CAPACITY] = OutboxEntry {
+            sequence,
+            nonce: 0,
+            dest_chain_id: chain_ids::ETHEREUM,
+            amount: paused as u64,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            transfer_id: message_hash,
+            sender: Pubkey::default(),
+            mint: Pubkey::default(),
+            kind: OutboxKind::BridgeStatus as u8,
+            _padding: [0; 3],
+            route_id: 0,
+        };
+        math::increment(&mut self.next_sequence)?;
+        Ok((sequence, message_hash))
+    }
+
+    /**
+     * Queue a VaultBalance message carrying `mint`'s escrow balance to the
+     * EVM side
+     *
+     * Not a transfer: `amount` is the balance, `sender` the escrow it was
+     * read from, and `transfer_id` holds the vault_balance_hash the
+     * relayer signs. Returns the sequence and that hash.
+     */
+    pub fn push_vault_balance(
+        &mut self,
+        mint: Pubkey,
+        escrow: Pubkey,
+        balance: u64,
+    ) -> Result<(u64, [u8; 32])> {
+        let sequence = self.next_sequence;
+        let clock = Clock::get()?;
+        let message_hash = codec::vault_balance_hash(
+            chain_ids::SOLANA,
+            &mint.to_bytes(),
+            balance,
+            clock.slot,
+            sequence,
+        );
+        self.entries[sequence as usize % OUTBOX_CAPACITY] = OutboxEntry {
+            sequence,
+            nonce: 0,
+            dest_chain_id: chain_ids::ETHEREUM,
+            amount: balance,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            transfer_id: message_hash,
+            sender: escrow,
+            mint,
+            kind: OutboxKind::VaultBalance as u8,
+            _padding: [0; 3],
+            route_id: 0,
+        };
+        math::increment(&mut self.next_sequence)?;
+        Ok((sequence, message_hash))
+    }
+
+    /**
+     * Queue a Message for `target` on `dest_chain_id`
+     *
+     * Not a transfer: `amount` is the payload length, `mint` holds the
+     * target (left-padded, as allowed) and `transfer_id` the message_hash
+     * the relayer signs. The payload itself is only in the MessageSent
+     * event. Returns the sequence and that hash.
+     */
+    pub fn push_message(
+        &mut self,
+        sender: Pubkey,
+        dest_chain_id: u64,
+        target: [u8; 32],
+        payload: &[u8],
+    ) -> Result<(u64, [u8; 32])> {
+        let sequence = self.next_sequence;
+        let clock = Clock::get()?;
+        let message_hash = codec::message_hash(
+            chain_ids::SOLANA,
+            &sender.to_bytes(),
+            dest_chain_id,
+            &target,
+            sequence,
+            payload,
+        );
+        self.entries[sequence as usize % OUTBOX_CAPACITY] = OutboxEntry {
+            sequence,
+            nonce: 0,
+            dest_chain_id,
+            amount: payload.len() as u64,
+            slot: clock.slot,
+            timestamp: clock.unix_timestamp,
+            transfer_id: message_hash,
+            sender,
+            mint: Pubkey::new_from_array(target),
+            kind: OutboxKind::Message as u8,
+            _padding: [0; 3],
+            route_id: 0,
+        };
+        math::increment(&mut self.next_sequence)?;
+        Ok((sequence, message_hash))
+    }
+}
+
+#[zero_copy]
+pub struct OutboxEntry {
+    pub sequence: u64,
+    pub nonce: u64,
+    pub dest_chain_id: u64,
+    pub amount: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub transfer_id: [u8; 32],
+    pub sender: Pubkey,
+    pub mint: Pubkey,
+    /// OutboxKind
+    pub kind: u8,
+    pub _padding: [u8; 3],
+    /// The transfer's RelayRoute ID (0 = none); was padding, so older
+    /// entries read as 0
+    pub route_id: u32,
+}
+
+/// Entries a token's EventHistory keeps before overwriting the oldest
+pub const HISTORY_CAPACITY: usize = 32;
+
+/**
+ * A token's recent transfers, readable without logs (seeds: "event_history", mint)
+ *
+ * Lock, lock_batch and unlock append a compact copy of each transfer
+ * they emit an event for, so an integration without log access can
+ * read the token's last HISTORY_CAPACITY transfers from one account
+ * (entry = sequence % HISTORY_CAPACITY) and `reemit` any it needs in
+ * full. Anyone can create one with `init_event_history`; until then
+ * the token has no history and nothing is recorded.
+ */
+#[account(zero_copy)]
+pub struct EventHistory {
+    pub mint: Pubkey,
+    /// Sequence number the next entry gets
+    pub next_sequence: u64,
+    pub entries: [HistoryEntry; HISTORY_CAPACITY],
+}
+
+impl EventHistory {
+    /// Append `record` to the history behind `info`, if the token has one
+    pub fn record(info: &AccountInfo, record: &TransferRecord) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let mut data = info.try_borrow_mut_data()?;
+        require!(
+            data.starts_with(&EventHistory::DISCRIMINATOR),
+            ErrorCode::Unauthorized
+        );
+        // Loaded by hand: an AccountLoader needs the account for 'info
+        let history: &mut EventHistory = anchor_lang::__private::bytemuck::from_bytes_mut(
+            &mut data[8..8 + std::mem::size_of::<EventHistory>()],
+        );
+        history.push(record)?;
+        Ok(())
+    }
+
+    pub fn push(&mut self, record: &TransferRecord) -> Result<u64> {
+        let sequence = self.next_sequence;
+        self.entries[sequence as usize % HISTORY_CAPACITY] = HistoryEntry {
+            sequence,
+            nonce: record.nonce,
+            remote_chain_id: record.remote_chain_id,
+            amount: record.amount,
+            slot: record.created_slot,
+            timestamp: record.created_at,
+            transfer_id: record.transfer_id,
+            account: record.local_account,
+            direction: record.direction as u8,
+            _padding: [0; 7],
+        };
+        math::increment(&mut self.next_sequence)?;
+        Ok(sequence)
+    }
+}
+
+#[zero_copy]
+pub struct HistoryEntry {
+    pub sequence: u64,
+    pub nonce: u64,
+    /// Destination chain of a lock, source chain of an unlock
+    pub remote_chain_id: u64,
+    pub amount: u64,
+    pub slot: u64,
+    pub timestamp: i64,
+    pub transfer_id: [u8; 32],
+    /// Sender of a lock, recipient of an unlock
+    pub account: Pubkey,
+    /// TransferDirection
+    pub direction: u8,
+    pub _padding: [u8; 7],
+}
+
+/**
+ * Transfer status
+ *
+ * Initiated -> Attested -> Delayed -> Completed / Refunded / Vetoed.
+ * Delayed is entered while a challenge is open; Refunded only applies
+ * to outbound transfers that never complete on the destination, taken
+ * back by the sender after their deadline (reclaim_expired).
+ * Guardians can move a pending transfer to Cancelled (cancel_pending).
+ * Inbound mints delivered in claim mode wait in Claimable until the
+ * recipient (or anyone on their behalf) calls `claim`.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TransferStatus {
+    /// Source-side funds taken, waiting for the relayer
+    Initiated,
+    /// Relayer attested, waiting out the challenge period
+    Attested,
+    /// Held: challenged and waiting for guardians
+    Delayed,
+    Completed,
+    Refunded,
+    /// Vetoed by a watcher or rejected as fraud
+    Vetoed,
+    /// Cancelled by guardians; outbound funds went back to the sender
+    Cancelled,
+    /// Inbound, delivered in claim mode or quarantined: verified, waiting
+    /// for `claim` (or `claim_unlock`)
+    Claimable,
+}
+
+/// Why an inbound delivery was quarantined (see quarantine.rs)
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuarantineReason {
+    /// The recipient's token account doesn't exist (never created, or closed)
+    Closed,
+    /// It belongs to someone else now
+    WrongOwner,
+    Frozen,
+}
+
+/**
+ * Count distinct guardians that signed this transaction
+ */
+pub fn count_guardian_signers(guardian_set: &GuardianSet, accounts: &[AccountInfo]) -> usize {
+    count_signers(&guardian_set.guardians, accounts)
+}
+
+/**
+ * Count distinct `members` that signed this transaction
+ */
+pub fn count_signers(members: &[Pubkey], accounts: &[AccountInfo]) -> usize {
+    let mut signers: Vec<Pubkey> = Vec::new();
+    for account in accounts {
+        if account.is_signer && members.contains(account.key) && !signers.contains(account.key) {
+            signers.push(*account.key);
+        }
+    }
+    signers.len()
+}
+
+/**
+ * Watcher registration (existence of the PDA grants the role)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct WatcherInfo {
+    pub watcher: Pubkey,
+}
+
+pub const MAX_ADDRESS_PREFIX_LEN: usize = 16;
+
+/**
+ * Chain registry entry (one PDA per destination chain)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ChainConfig {
+    pub chain_id: u64,
+    pub kind: ChainKind,
+    pub enabled: bool,
+    /// How relayers decide an event on this chain is final
+    pub finality: Finality,
+    /// Blocks to wait after the event (or after the L1 batch, for rollups)
+    pub required_confirmations: u32,
+    /// Flat fee per transfer, in token base units
+    pub base_fee: u64,
+    /// Proportional fee on top of `base_fee`
+    pub fee_bps: u16,
+    /// Smallest transfer worth delivering (e.g. above BTC dust and miner fees)
+    pub min_amount: u64,
+    /// Bech32 prefix (Cosmos and Bitcoin chains only)
+    #[max_len(16)]
+    pub address_prefix: String,
+    /// Guardians marked the chain halted; nothing from it is delivered
+    pub halted: bool,
+}
+
+impl ChainConfig {
+    /**
+     * Fee on an outbound `amount`, after a `discount_bps` volume rebate
+     *
+     * The token's `route` to this chain, if it has one, replaces the
+     * chain's default fee.
+     */
+    pub fn fee(&self, route: Option<&FeeRoute>, amount: u64, discount_bps: u16) -> Result<u64> {
+        let (base_fee, fee_bps) =
+            route.map_or((self.base_fee, self.fee_bps), |r| (r.base_fee, r.fee_bps));
+        math::transfer_fee(amount, base_fee, fee_bps, discount_bps)
+    }
+
+    /// Fail while the chain is halted (for inbound transfers from it)
+    pub fn check_not_halted(&self) -> Result<()> {
+        require!(!self.halted, ErrorCode::SourceChainHalted);
+        Ok(())
+    }
+
+    pub fn apply(&mut self, params: &ChainParams) {
+        self.finality = params.finality;
+        self.required_confirmations = params.required_confirmations;
+        self.base_fee = params.base_fee;
+        self.fee_bps = params.fee_bps;
+        self.min_amount = params.min_amount;
+    }
+}
+
+/**
+ * Tunable per-chain parameters
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ChainParams {
+    pub finality: Finality,
+    pub required_confirmations: u32,
+    pub base_fee: u64,
+    pub fee_bps: u16,
+    pub min_amount: u64,
+}
+
+impl ChainParams {
+    pub fn validate(&self, kind: ChainKind) -> Result<()> {
+        require!(self.fee_bps <= 10_000, ErrorCode::InvalidConfig);
+        // Only EVM rollups post batches to L1
+        require!(
+            self.finality != Finality::L1Batch || kind == ChainKind::Evm,
+            ErrorCode::InvalidConfig
+        );
+        Ok(())
+    }
+}
+
+/**
+ * Finality rule for a chain
+ *
+ * L2 blocks can be reorged (or never posted) while the sequencer
+ * misbehaves, so for rollups the count only starts once the block is
+ * included in a finalized L1 batch.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum Finality {
+    /// `required_confirmations` blocks on the chain itself
+    Confirmations,
+    /// Block must be in a finalized L1 batch, then `required_confirmations`
+    L1Batch,
+}
+
+/**
+ * Address format family of a chain
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum ChainKind {
+    /// 0x-prefixed 20-byte addresses
+    Evm,
+    /// Strkey ed25519 account IDs (G...)
+    Stellar,
+    /// Bech32 accounts with a per-chain prefix, reached over IBC
+    Cosmos,
+    /// Taproot (bech32m) addresses, paid out by the threshold-signing custodian
+    Bitcoin,
+}
+
+/// P2TR output script: OP_1 OP_PUSHBYTES_32 <output key>
+pub fn taproot_script_pubkey(output_key: &[u8; 32]) -> Vec<u8> {
+    let mut script = Vec::with_capacity(34);
+    script.push(0x51);
+    script.push(0x20);
+    script.extend_from_slice(output_key);
+    script
+}
+
+pub const MAX_CREDENTIAL_TIERS: usize = 4;
+
+pub const MAX_IBC_DENOMS: usize = 4;
+pub const MAX_IBC_DENOM_LEN: usize = 128;
+
+/**
+ * Per-token configuration (one PDA per SPL mint)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TokenConfig {
+    pub mint: Pubkey,
+    /// SPL mint <-> IBC denom, per Cosmos chain
+    #[max_len(4)]
+    pub ibc_denoms: Vec<IbcDenom>,
+    /// Lock and mint require a credential (see credential.rs)
+    pub restricted: bool,
+    /// Program that owns valid credential accounts
+    pub credential_program: Pubkey,
+    /// Per-transfer limit for credential tier 1, 2, ...
+    #[max_len(MAX_CREDENTIAL_TIERS)]
+    pub tier_limits: Vec<u64>,
+    /// Token-2022 extensions the bridge accepts (token_extensions flags)
+    pub allowed_extensions: u16,
+    /// Whether amounts cross chains raw or as shares
+    pub accounting: TokenAccounting,
+    /// Halts this token only (see pause_token)
+    pub paused: bool,
+    /// What vouches for inbound transfers (see verifiers)
+    pub trust_model: TrustModel,
+    /// Decimals the token is carried in on other chains (None = the mint's own)
+    pub bridged_decimals: Option<u8>,
+    /// What happens to the part of a lock those decimals can't carry
+    pub dust_policy: DustPolicy,
+}
+
+impl TokenConfig {
+    pub fn ibc_denom(&self, chain_id: u64) -> Option<&str> {
+        self.ibc_denoms
+            .iter()
+            .find(|d| d.chain_id == chain_id)
+            .map(|d| d.denom.as_str())
+    }
+
+    /// Local token amount -> amount carried in the cross-chain message
+    pub fn to_bridged(&self, rate: Option<&ConversionRate>, amount: u64) -> Result<u64> {
+        match self.accounting {
+            TokenAccounting::Raw => Ok(amount),
+            TokenAccounting::Shares => {
+                let rate = rate.ok_or(ErrorCode::ConversionRateRequired)?.current()?;
+                math::mul_div(amount, RATE_SCALE, rate)
+            }
+        }
+    }
+
+    /**
+     * Split what a lock escrows into the part `bridged_decimals` can
+     * carry and the dust below it; fails under DustPolicy::Reject if
+     * there is any dust
+     */
+    pub fn split_dust(&self, amount: u64, decimals: u8) -> Result<(u64, u64)> {
+        let dust = self
+            .bridged_decimals
+            .map_or(0, |bridged| math::dust(amount, decimals, bridged));
+        if self.dust_policy == DustPolicy::Reject {
+            require_ctx!(
+                dust == 0,
+                ErrorCode::AmountNotRepresentable,
+                amount = amount,
+                dust = dust
+            );
+        }
+        Ok((math::sub(amount, dust)?, dust))
+    }
+
+    /// Amount from a cross-chain message -> local token amount
+    pub fn from_bridged(&self, rate: Option<&ConversionRate>, amount: u64) -> Result<u64> {
+        match self.accounting {
+            TokenAccounting::Raw => Ok(amount),
+            TokenAccounting::Shares => {
+                let rate = rate.ok_or(ErrorCode::ConversionRateRequired)?.current()?;
+                math::mul_div(amount, rate, RATE_SCALE)
+            }
+        }
+    }
+}
+
+/**
+ * How a token's amounts are carried across chains
+ *
+ * Raw suits normal tokens, and Token-2022 interest-bearing mints too
+ * (interest only shows in the UI amount; raw balances never change).
+ * Shares is for assets whose balances rebase (stETH-style): the escrow
+ * holds raw tokens, but messages carry shares, converted at the rate the
+ * oracle posts in the token's ConversionRate account. The wrapped token
+ * on the other chain is denominated in shares.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TokenAccounting {
+    Raw,
+    Shares,
+}
+
+/**
+ * What a lock does with dust its token's bridged decimals can't carry
+ *
+ * Refund: the dust stays with the sender (only the rest is taken).
+ * Treasury: the dust is taken with the fee, into the fee vault.
+ * Reject: the lock fails unless the amount is exactly representable.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum DustPolicy {
+    Refund,
+    Treasury,
+    Reject,
+}
+
+/**
+ * What has to vouch for a token's inbound transfers
+ *
+ * OwnerSignature: the owner/relayer, or a guardian quorum in its place.
+ * GuardianQuorum: a guardian quorum only.
+ * WormholeVaa: a VAA from `emitter` (the Ethereum bridge, left-padded
+ * to 32 bytes) posted by the Wormhole core bridge.
+ * LightClient: a Groth16 proof of the Ethereum lock (mint_with_proof).
+ * Optimistic: an unchallenged attestation (execute_attestation).
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum TrustModel {
+    OwnerSignature,
+    GuardianQuorum,
+    WormholeVaa { emitter: [u8; 32] },
+    LightClient,
+    Optimistic,
+}
+
+/// Fixed-point scale of ConversionRate::rate (1 share = rate / 1e9 tokens)
+pub const RATE_SCALE: u64 = 1_000_000_000;
+
+/**
+ * Token amount per share for a share-accounted token, posted by an oracle
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ConversionRate {
+    pub mint: Pubkey,
+    /// Only key allowed to post rates
+    pub oracle: Pubkey,
+    /// Tokens per share, scaled by RATE_SCALE
+    pub rate: u64,
+    pub updated_at: i64,
+    /// Oldest rate lock/unlock will use, in seconds
+    pub max_age: i64,
+}
+
+impl ConversionRate {
+    /// The rate, if it's fresh enough to convert with
+    pub fn current(&self) -> Result<u64> {
+        let age = Clock::get()?.unix_timestamp - self.updated_at;
+        require_ctx!(
+            age <= self.max_age,
+            ErrorCode::StaleConversionRate,
+            age = age,
+            max_age = self.max_age
+        );
+        Ok(self.rate)
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct IbcDenom {
+    pub chain_id: u64,
+    #[max_len(128)]
+    pub denom: String,
+}
+
+/// Maximum number of owner multisig keys
+pub const MAX_MULTISIG_OWNERS: usize = 10;
+
+/// Largest admin instruction a proposal can carry (fits set_verifying_key)
+pub const MAX_PROPOSAL_DATA_LEN: usize = 1024;
+
+/// Most accounts an admin instruction takes
+pub const MAX_PROPOSAL_ACCOUNTS: usize = 16;
+
+/**
+ * Built-in owner multisig
+ *
+ * The bridge owner is the system-owned PDA "multisig_authority",
+ * which only signs through `execute`.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct OwnerMultisig {
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub owners: Vec<Pubkey>,
+    pub threshold: u8,
+    pub proposal_count: u64,
+}
+
+/**
+ * Pending admin instruction (seeds: "admin_proposal", id)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct AdminProposal {
+    pub id: u64,
+    pub proposer: Pubkey,
+    #[max_len(MAX_PROPOSAL_DATA_LEN)]
+    pub instruction_data: Vec<u8>,
+    #[max_len(MAX_PROPOSAL_ACCOUNTS)]
+    pub accounts: Vec<ProposedAccount>,
+    #[max_len(MAX_MULTISIG_OWNERS)]
+    pub approvals: Vec<Pubkey>,
+    pub executed: bool,
+    pub created_at: i64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct ProposedAccount {
+    pub pubkey: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+/**
+ * Relayer reward settings
+ *
+ * Epoch n covers [start_time + n * epoch_duration, start_time + (n + 1) * epoch_duration).
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RelayerRewardConfig {
+    pub start_time: i64,
+    pub epoch_duration: i64,
+    /// Lamports added to the epoch bucket per completed inbound transfer
+    pub reward_per_transfer: u64,
+    /// Most lamports one gas report is reimbursed (0 = no reimbursement)
+    pub max_gas_per_transfer: u64,
+}
+
+impl RelayerRewardConfig {
+    pub fn current_epoch(&self) -> u64 {
+        let now = Clock::get().map_or(self.start_time, |clock| clock.unix_timestamp);
+        self.epoch_at(now)
+    }
+
+    /// Epoch containing unix time `now` (for clients deriving PDAs off-chain)
+    pub fn epoch_at(&self, now: i64) -> u64 {
+        if self.epoch_duration <= 0 {
+            return 0;
+        }
+        ((now - self.start_time).max(0) / self.epoch_duration) as u64
+    }
+}
+
+/**
+ * Reward bucket for one epoch (seeds: "reward_epoch", epoch)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RewardEpoch {
+    pub epoch: u64,
+    pub total_reward: u64,
+    pub total_transfers: u64,
+}
+
+/**
+ * One relayer's performance in one epoch
+ * (seeds: "relayer_epoch", epoch, relayer)
+ *
+ * Rewards are paid on `transfers`; the rest is for SLA checks. The
+ * account closes when the epoch's reward is claimed, and
+ * `RelayerRewardsClaimed` carries its final counters.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RelayerEpochStats {
+    pub epoch: u64,
+    pub relayer: Pubkey,
+    /// Inbound transfers completed
+    pub transfers: u64,
+    /// Deliveries that landed, including duplicates that did nothing
+    pub submitted: u64,
+    /// Sum of `TransferRecord::latency_slots` over `transfers`
+    pub total_latency_slots: u64,
+    /// Duplicate deliveries, plus attestations resolved as fraud
+    pub failures: u64,
+}
+
+impl RelayerEpochStats {
+    fn begin(&mut self, config: &RelayerRewardConfig, relayer: Pubkey) {
+        self.epoch = config.current_epoch();
+        self.relayer = relayer;
+    }
+
+    /// A delivery of an already-processed nonce (idempotent mode)
+    pub fn record_duplicate(
+        &mut self,
+        config: &RelayerRewardConfig,
+        relayer: Pubkey,
+    ) -> Result<()> {
+        self.begin(config, relayer);
+        math::increment(&mut self.submitted)?;
+        math::increment(&mut self.failures)?;
+        Ok(())
+    }
+
+    /// Mean slots per completed transfer (0 before the first)
+    pub fn average_latency_slots(&self) -> u64 {
+        self.total_latency_slots.checked_div(self.transfers).unwrap_or(0)
+    }
+}
+
+/**
+ * Credit a completed inbound transfer to `relayer` in the current epoch
+ */
+pub fn accrue_relayer_reward(
+    config: &RelayerRewardConfig,
+    bucket: &mut RewardEpoch,
+    stats: &mut RelayerEpochStats,
+    relayer: Pubkey,
+    latency_slots: u64,
+) -> Result<()> {
+    let epoch = config.current_epoch();
+
+    bucket.epoch = epoch;
+    bucket.total_reward = math::add(bucket.total_reward, config.reward_per_transfer)?;
+    math::increment(&mut bucket.total_transfers)?;
+
+    stats.begin(config, relayer);
+    math::increment(&mut stats.transfers)?;
+    math::increment(&mut stats.submitted)?;
+    stats.total_latency_slots = math::add(stats.total_latency_slots, latency_slots)?;
+    Ok(())
+}
+
+/// Maximum number of volume rebate tiers
+pub const MAX_FEE_TIERS: usize = 8;
+
+/**
+ * Volume rebate schedule
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct FeeTiers {
+    #[max_len(MAX_FEE_TIERS)]
+    pub tiers: Vec<FeeTier>,
+}
+
+impl FeeTiers {
+    /// (tier number, discount) for a user's volume; tier 0 means no rebate
+    pub fn tier_for(&self, volume: u64) -> (u8, u16) {
+        let mut result = (0, 0);
+        for (i, tier) in self.tiers.iter().enumerate() {
+            if volume >= tier.min_volume {
+                result = (i as u8 + 1, tier.discount_bps);
+            }
+        }
+        result
+    }
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct FeeTier {
+    /// Cumulative bridged volume needed (token base units)
+    pub min_volume: u64,
+    pub discount_bps: u16,
+}
+
+/**
+ * Per-user bridging stats (seeds: "user_stats", user)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct UserStats {
+    pub user: Pubkey,
+    /// Gross amount locked or burned, all time
+    pub volume: u64,
+    pub transfers: u64,
+}
+
+impl UserStats {
+    /// Volume of the stats behind `info`; 0 if the user never bridged
+    pub fn volume_of(info: &AccountInfo) -> Result<u64> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(UserStats::try_deserialize(&mut &info.try_borrow_data()?[..])?.volume)
+    }
+
+    pub fn record(&mut self, user: Pubkey, amount: u64) -> Result<()> {
+        self.user = user;
+        self.volume = math::add(self.volume, amount)?;
+        math::increment(&mut self.transfers)?;
+        Ok(())
+    }
+}
+
+/**
+ * Volume attributed to a frontend or router (seeds: "integrator", id)
+ *
+ * Integrators claim an ID with `register_integrator` and tag their users'
+ * `lock` and `burn` with it; the ID also lands in LockEvent and BurnEvent.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct IntegratorStats {
+    /// Never 0, which events use for untagged transfers
+    pub integrator_id: u32,
+    /// Registered the ID
+    pub authority: Pubkey,
+    /// Gross amount locked or burned under the ID, all time
+    pub volume: u64,
+    pub transfers: u64,
+    pub last_transfer_at: i64,
+}
+
+impl IntegratorStats {
+    /**
+     * Book a transfer of `amount` to `integrator_id`, if it's tagged
+     *
+     * `stats` is the account passed for it and must be that ID's. Returns
+     * the ID for events (0 if untagged).
+     */
+    pub fn attribute(
+        stats: Option<&mut IntegratorStats>,
+        integrator_id: Option<u32>,
+        amount: u64,
+    ) -> Result<u32> {
+        let Some(integrator_id) = integrator_id else {
+            return Ok(0);
+        };
+        let stats = stats.ok_or(ErrorCode::InvalidIntegrator)?;
+        require!(stats.integrator_id == integrator_id, ErrorCode::InvalidIntegrator);
+
+        stats.volume = math::add(stats.volume, amount)?;
+        math::increment(&mut stats.transfers)?;
+        stats.last_transfer_at = Clock::get()?.unix_timestamp;
+        Ok(integrator_id)
+    }
+}
+
+/**
+ * Permissionless pause settings
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct PauseBondConfig {
+    /// Lamports anyone must post to pause (0 = disabled)
+    pub bond_amount: u64,
+    /// Paid from the insurance fund when the incident is confirmed
+    pub reward: u64,
+    /// Slots a bonded pause lasts without a guardian confirming it (0 = no limit)
+    pub expiry_slots: u64,
+}
+
+impl PauseBondConfig {
+    /// Slot a pause raised or confirmed at `slot` lapses at (0 = never)
+    pub fn expiry_slot(&self, slot: u64) -> Result<u64> {
+        if self.expiry_slots == 0 {
+            return Ok(0);
+        }
+        math::add(slot, self.expiry_slots)
+    }
+}
+
+/**
+ * Open bonded pause (seeds: "bonded_pause"); closed on resolution
+ *
+ * The bond itself sits in the "pause_bond_vault" system PDA.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct BondedPauseState {
+    pub pauser: Pubkey,
+    pub bond: u64,
+    pub paused_at: i64,
+    /// Lapses at this slot unless a guardian confirms it (0 = never)
+    pub expires_at_slot: u64,
+}
+
+impl BondedPauseState {
+    pub fn is_expired(&self, slot: u64) -> bool {
+        self.expires_at_slot != 0 && slot >= self.expires_at_slot
+    }
+}
+
+/// Metaplex limits
+pub const MAX_METADATA_NAME_LEN: usize = 32;
+pub const MAX_METADATA_SYMBOL_LEN: usize = 10;
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
+/**
+ * Source token behind a wrapped mint (seeds: "wrapped_asset", mint)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct WrappedAsset {
+    pub mint: Pubkey,
+    pub source_chain_id: u64,
+    /// ERC-20 address
+    pub source_token: [u8; 20],
+    pub source_decimals: u8,
+    pub decimals: u8,
+}
+
+/**
+ * Metaplex data for a wrapped token: "Wrapped <name>", same symbol
+ */
+pub fn wrapped_metadata(source_name: &str, source_symbol: &str, uri: String) -> Result<DataV2> {
+    let name = format!("Wrapped {}", source_name);
+    require!(
+        name.len() <= MAX_METADATA_NAME_LEN
+            && !source_symbol.is_empty()
+            && source_symbol.len() <= MAX_METADATA_SYMBOL_LEN
+            && uri.len() <= MAX_METADATA_URI_LEN,
+        ErrorCode::InvalidMetadata
+    );
+
+    Ok(DataV2 {
+        name,
+        symbol: source_symbol.to_string(),
+        uri,
+        seller_fee_basis_points: 0,
+        creators: None,
+        collection: None,
+        uses: None,
+    })
+}
+
+/**
+ * Last guardian-attested metadata sync of a wrapped mint (seeds:
+ * "metadata_sync", mint)
+ *
+ * `sync_metadata` only applies a read of the ERC-20 from a later
+ * Ethereum block than the one before it, so an old read, replayed or
+ * delivered late, can't undo a newer correction.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct MetadataSync {
+    pub mint: Pubkey,
+    /// Ethereum block the current metadata was read at
+    pub eth_block: u64,
+    pub synced_at: i64,
+}
+
+/**
+ * Permissionless token listing settings (seeds: "listing_config")
+ *
+ * Anyone can list an ERC-20 by posting `bond` lamports. Guardians have
+ * `veto_window` seconds to veto it, forfeiting the bond to the insurance
+ * fund; after that anyone can activate it, creating the wrapped mint
+ * and returning the bond. At most `max_per_window` listings open per
+ * `rate_window` seconds, so listings can't arrive faster than guardians
+ * can review them.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ListingConfig {
+    /// Lamports a listing posts (0 = permissionless listing disabled)
+    pub bond: u64,
+    pub veto_window: i64,
+    pub max_per_window: u16,
+    pub rate_window: i64,
+    /// Start of the current rate window
+    pub window_start: i64,
+    /// Listings opened since `window_start`
+    pub window_count: u16,
+}
+
+impl ListingConfig {
+    /// Count a listing opened at `now` against the rate limit
+    pub fn open_listing(&mut self, now: i64) -> Result<()> {
+        if now >= self.window_start.saturating_add(self.rate_window) {
+            self.window_start = now;
+            self.window_count = 0;
+        }
+        require_ctx!(
+            self.window_count < self.max_per_window,
+            ErrorCode::ListingRateLimited,
+            opened = self.window_count,
+            limit = self.max_per_window
+        );
+        self.window_count += 1;
+        Ok(())
+    }
+}
+
+/**
+ * A listing waiting out its veto window (seeds: "token_listing", source_token)
+ *
+ * What `create_wrapped_mint` would be called with, and the lister's
+ * bond, held in the account's own lamports. Closed to the lister on
+ * activation (bond included) or veto (bond to the insurance fund).
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TokenListing {
+    pub lister: Pubkey,
+    pub source_token: [u8; 20],
+    pub source_decimals: u8,
+    pub decimals: u8,
+    #[max_len(MAX_METADATA_NAME_LEN)]
+    pub source_name: String,
+    #[max_len(MAX_METADATA_SYMBOL_LEN)]
+    pub source_symbol: String,
+    #[max_len(MAX_METADATA_URI_LEN)]
+    pub uri: String,
+    /// Lamports, over the account's rent
+    pub bond: u64,
+    pub listed_at: i64,
+    /// Anyone can activate it from here on
+    pub activates_at: i64,
+}
+
+/**
+ * Inbound delivery order for one source chain
+ *
+ * Every inbound path (mint, mint_with_proof, execute_attestation, unlock)
+ * delivers the source nonce here. `next_sequence` is the oldest nonce not
+ * yet delivered; `delivered` has bit i set once `next_sequence + i` was,
+ * and the window slides forward as the gap at the front fills. A nonce
+ * is accepted exactly once, and never more than `window` ahead.
+ *
+ * Chains without an inbox account keep the old behaviour (any order,
+ * replay-protected by processed_nonces only).
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Inbox {
+    pub chain_id: u64,
+    pub next_sequence: u64,
+    /// How far ahead of next_sequence a nonce may arrive (0 = in order)
+    pub window: u8,
+    pub delivered: u128,
+}
+
+impl Inbox {
+    /// Deliver `sequence` to the inbox behind `info`, if it exists
+    pub fn deliver(info: &AccountInfo, sequence: u64) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let mut data = info.try_borrow_mut_data()?;
+        let mut inbox = Inbox::try_deserialize(&mut &data[..])?;
+        inbox.accept(sequence)?;
+        inbox.try_serialize(&mut &mut data[..])
+    }
+
+    pub fn accept(&mut self, sequence: u64) -> Result<()> {
+        require!(sequence >= self.next_sequence, ErrorCode::AlreadyProcessed);
+        let offset = sequence - self.next_sequence;
+        require_ctx!(
+            offset <= self.window as u64,
+            ErrorCode::OutOfOrderDelivery,
+            sequence = sequence,
+            next_sequence = self.next_sequence,
+            window = self.window
+        );
+
+        let bit = 1u128 << offset;
+        require!(self.delivered & bit == 0, ErrorCode::AlreadyProcessed);
+        self.delivered |= bit;
+
+        while self.delivered & 1 == 1 {
+            self.delivered >>= 1;
+            math::increment(&mut self.next_sequence)?;
+        }
+        Ok(())
+    }
+}
+
+/**
+ * Guardian council: last-resort kill switch, independent of the owner
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct GuardianCouncil {
+    pub threshold: u8,
+    #[max_len(MAX_GUARDIANS)]
+    pub members: Vec<Pubkey>,
+    /// Mint and unlock are blocked while set
+    pub halted: bool,
+}
+
+impl GuardianCouncil {
+    pub fn validate(members: &[Pubkey], threshold: u8) -> Result<()> {
+        require!(
+            !members.is_empty() && members.len() <= MAX_GUARDIANS,
+            ErrorCode::InvalidGuardianSet
+        );
+        require!(
+            threshold > 0 && threshold as usize <= members.len(),
+            ErrorCode::InvalidGuardianSet
+        );
+        Ok(())
+    }
+
+    /// Has the council behind `info` (if it exists) halted the bridge?
+    pub fn is_halted(info: &AccountInfo) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let council = GuardianCouncil::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(council.halted)
+    }
+
+    /// Fail if the council behind `info` exists and has halted the bridge
+    pub fn check_not_halted(info: &AccountInfo) -> Result<()> {
+        require!(!Self::is_halted(info)?, ErrorCode::CouncilHalted);
+        Ok(())
+    }
+}
+
+/**
+ * Latest attested Ethereum-side collateral for a wrapped token
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ReserveAttestation {
+    pub mint: Pubkey,
+    /// Tokens locked in the Ethereum bridge contract
+    pub eth_locked_amount: u64,
+    /// Ethereum block the amount was read at
+    pub block_number: u64,
+    /// Wrapped supply when the attestation was posted
+    pub wrapped_supply: u64,
+    pub posted_at: i64,
+    pub slot: u64,
+}
+
+impl ReserveAttestation {
+    /// The attestation behind `info`, if one was ever posted
+    pub fn load(info: &AccountInfo) -> Result<Option<ReserveAttestation>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(Some(ReserveAttestation::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+
+    pub fn status(&self, wrapped_supply: u64) -> ReserveStatus {
+        ReserveStatus {
+            wrapped_supply,
+            attested_reserve: self.eth_locked_amount,
+            block_number: self.block_number,
+            posted_at: self.posted_at,
+            deficit: wrapped_supply.saturating_sub(self.eth_locked_amount),
+        }
+    }
+}
+
+/// `RedemptionQueue::paid_ratio` of a fully covered claim
+pub const REDEMPTION_RATIO_SCALE: u64 = 1_000_000_000;
+
+/**
+ * Redemption queue for a wrapped token (seeds: "redemption_queue", mint)
+ *
+ * A reserve attestation showing less collateral on Ethereum than there
+ * is wrapped supply turns the queue on. While it's on, `burn` is refused
+ * and holders redeem through `enqueue_redemption` instead, which burns
+ * into a `RedemptionClaim`. Rather than the first burns taking all the
+ * collateral left, every claim can draw the same share of its amount,
+ * `paid_ratio`: what the collateral covers across holders and claims
+ * alike, recomputed by each attestation. As collateral comes back the
+ * ratio rises and claims draw the difference, up to their full amount.
+ * The queue turns off once every claim is paid and the reserve covers
+ * the supply again.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionQueue {
+    pub mint: Pubkey,
+    pub active: bool,
+    pub next_claim_id: u64,
+    /// Sum of the open claims' amounts
+    pub claimed: u64,
+    /// Released to open claims so far
+    pub released: u64,
+    /// Released, not yet confirmed paid on Ethereum
+    pub in_flight: u64,
+    /// Share of its amount each claim can draw, over REDEMPTION_RATIO_SCALE
+    pub paid_ratio: u64,
+    /// Ethereum block of the latest confirmed payout
+    pub confirmed_block: u64,
+}
+
+impl RedemptionQueue {
+    /// Is the queue behind `info` (if it exists) taking redemptions?
+    pub fn is_active(info: &AccountInfo) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let queue = RedemptionQueue::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(queue.active)
+    }
+
+    /**
+     * Apply a reserve attestation of `eth_locked` against `supply`
+     *
+     * Payouts still in flight are left out of the reserve and what the
+     * claims already drew counts as covered, so
+     *
+     *   paid_ratio = (eth_locked - in_flight + released) / (supply + claimed)
+     *
+     * capped at 1. An attestation from before the latest confirmed payout
+     * still counts that payout's collateral, so it leaves the ratio alone.
+     */
+    pub fn reconcile(&mut self, eth_locked: u64, supply: u64, block_number: u64) -> Result<()> {
+        if !self.active {
+            if eth_locked >= supply {
+                return Ok(());
+            }
+            self.active = true;
+        } else if self.claimed == 0 && eth_locked >= supply {
+            self.active = false;
+            self.paid_ratio = 0;
+            return Ok(());
+        }
+        if block_number < self.confirmed_block {
+            return Ok(());
+        }
+
+        let covered = math::add(eth_locked.saturating_sub(self.in_flight), self.released)?;
+        let owed = math::add(supply, self.claimed)?;
+        self.paid_ratio = if owed == 0 {
+            REDEMPTION_RATIO_SCALE
+        } else {
+            math::mul_div(covered.min(owed), REDEMPTION_RATIO_SCALE, owed)?
+        };
+        Ok(())
+    }
+}
+
+/**
+ * A burn waiting in a redemption queue
+ * (seeds: "redemption_claim", mint, claim_id)
+ *
+ * Paid out on Ethereum in installments: `release_redemption` releases
+ * what the current ratio allows beyond `released`, and the payout is
+ * confirmed once it has landed. The claim closes, rent back to `owner`,
+ * when all of `amount` is released and confirmed.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RedemptionClaim {
+    pub claim_id: u64,
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub dest_chain_id: u64,
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub recipient: String,
+    /// Wrapped tokens burned
+    pub amount: u64,
+    pub released: u64,
+    /// Of `released`, confirmed paid
+    pub confirmed: u64,
+    pub created_at: i64,
+}
+
+impl RedemptionClaim {
+    /// What `paid_ratio` lets the claim draw now
+    pub fn releasable(&self, paid_ratio: u64) -> Result<u64> {
+        let entitled = math::mul_div(self.amount, paid_ratio, REDEMPTION_RATIO_SCALE)?;
+        Ok(entitled.saturating_sub(self.released))
+    }
+}
+
+/// `TokenOracle::version` this program writes
+pub const TOKEN_ORACLE_VERSION: u8 = 1;
+
+/**
+ * Bridge health of a wrapped token, for protocols weighing it as
+ * collateral (seeds: "token_oracle", mint)
+ *
+ * Meant to be read straight from the account, without a CPI. The layout
+ * is fixed: repr(C) after the 8-byte discriminator, every field at the
+ * same offset in every version. New fields come out of `_reserved`;
+ * changing an existing one bumps `version`.
+ *
+ * Anyone can refresh it with `refresh_token_oracle`. Readers should
+ * check `slot`, or refresh it earlier in their own transaction.
+ */
+#[account(zero_copy)]
+pub struct TokenOracle {
+    /// TOKEN_ORACLE_VERSION at the last write
+    pub version: u8,
+    /// CollateralStatus
+    pub status: u8,
+    /// oracle_flags
+    pub flags: u8,
+    pub origin_decimals: u8,
+    pub _padding: [u8; 4],
+    pub mint: Pubkey,
+    /// Chain the token is native to
+    pub origin_chain_id: u64,
+    /// Token address on the origin chain, right-aligned (an ERC-20 fills the last 20 bytes)
+    pub origin_address: [u8; 32],
+    pub wrapped_supply: u64,
+    /// Latest attested collateral on the origin chain (0 if never attested)
+    pub attested_reserve: u64,
+    /// Origin-chain block the reserve was read at
+    pub reserve_block: u64,
+    pub reserve_posted_at: i64,
+    /// attested_reserve over wrapped_supply, in bps (u64::MAX with no supply)
+    pub collateral_bps: u64,
+    pub updated_at: i64,
+    pub slot: u64,
+    pub _reserved: [u8; 64],
+}
+
+impl TokenOracle {
+    /// `attested_reserve` over `wrapped_supply` in bps, saturating
+    pub fn collateral_bps(attested_reserve: u64, wrapped_supply: u64) -> u64 {
+        if wrapped_supply == 0 {
+            return u64::MAX;
+        }
+        let bps = attested_reserve as u128 * math::BPS_DENOMINATOR as u128 / wrapped_supply as u128;
+        bps.min(u64::MAX as u128) as u64
+    }
+}
+
+/// `TokenOracle::status`
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum CollateralStatus {
+    /// No reserve attestation yet
+    Unattested,
+    /// The attested reserve covers the supply
+    Healthy,
+    /// The attested reserve is short of the supply
+    Undercollateralized,
+    /// Short, and redemptions go through the redemption queue
+    Redeeming,
+}
+
+/// Bits of `TokenOracle::flags`; any of them set means transfers are stopped
+pub mod oracle_flags {
+    /// The whole bridge is paused
+    pub const BRIDGE_PAUSED: u8 = 1 << 0;
+    /// This token is paused (pause_token)
+    pub const TOKEN_PAUSED: u8 = 1 << 1;
+    /// Guardians halted the origin chain
+    pub const ORIGIN_CHAIN_HALTED: u8 = 1 << 2;
+    /// The guardian council halted the bridge
+    pub const COUNCIL_HALTED: u8 = 1 << 3;
+}
+
+/**
+ * Hot/cold split of a token's escrow (seeds: "vault", mint)
+ *
+ * Unlocks pay out of the hot vault only, and `rebalance_vault` keeps it
+ * at `hot_cap` by sweeping the excess into the cold vault. The cold vault
+ * belongs to its own PDA ("cold_vault"), which nothing that unlocks,
+ * refunds or withdraws fees signs for: the only way out of it is a
+ * refill of the hot vault, queued for `refill_delay` seconds during
+ * which the owner or guardians can cancel it. A compromised relayer
+ * quorum can then take at most what is hot.
+ *
+ * The policy (`set_vault_policy`) steers the crank: the hot vault aims
+ * for `target_bps` of the whole escrow, never over `hot_cap`, and at
+ * most `max_refill_per_epoch` moves from cold to hot per Solana epoch.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct VaultConfig {
+    pub mint: Pubkey,
+    /// Escrow token account owned by the bridge authority
+    pub hot_vault: Pubkey,
+    /// Token account owned by the cold vault authority
+    pub cold_vault: Pubkey,
+    /// Most the hot vault holds after a rebalance
+    pub hot_cap: u64,
+    /// Seconds a refill waits before it can be executed
+    pub refill_delay: i64,
+    /// Queued refill (0 = none), executable from `refill_at`
+    pub refill_amount: u64,
+    pub refill_at: i64,
+    /// Share of hot + cold the hot vault aims for (0 = just `hot_cap`)
+    pub target_bps: u16,
+    /// Most refilled per epoch (0 = no limit)
+    pub max_refill_per_epoch: u64,
+    /// Epoch `refilled_in_epoch` counts for
+    pub refill_epoch: u64,
+    pub refilled_in_epoch: u64,
+}
+
+impl VaultConfig {
+    /// Fail unless `escrow` may pay out unlocks of the token whose vault config is `info`
+    pub fn check_hot(info: &AccountInfo, escrow: &Pubkey) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let config = VaultConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_keys_eq!(*escrow, config.hot_vault, ErrorCode::NotHotVault);
+        Ok(())
+    }
+
+    /**
+     * Fail if the token's escrow is split and `amount` is over the hot cap
+     *
+     * For what pays out of an escrow other than the hot vault (OTC
+     * orders), so what one relayed proof releases is never more than
+     * the hot vault would hold.
+     */
+    pub fn check_hot_cap(info: &AccountInfo, amount: u64) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let config = VaultConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_ctx!(
+            amount <= config.hot_cap,
+            ErrorCode::AmountTooLarge,
+            amount = amount,
+            hot_cap = config.hot_cap
+        );
+        Ok(())
+    }
+
+    /// What the hot vault should hold, given both vaults' balances
+    pub fn target(&self, hot_balance: u64, cold_balance: u64) -> Result<u64> {
+        if self.target_bps == 0 {
+            return Ok(self.hot_cap);
+        }
+        let total = math::add(hot_balance, cold_balance)?;
+        Ok(math::bps_of(total, self.target_bps)?.min(self.hot_cap))
+    }
+
+    /// What may still be refilled in `epoch`
+    pub fn refill_budget(&self, epoch: u64) -> u64 {
+        match (self.max_refill_per_epoch, epoch == self.refill_epoch) {
+            (0, _) => u64::MAX,
+            (max, true) => max.saturating_sub(self.refilled_in_epoch),
+            (max, false) => max,
+        }
+    }
+
+    /// Count `amount` refilled in `epoch`
+    pub fn record_refill(&mut self, epoch: u64, amount: u64) -> Result<()> {
+        if epoch != self.refill_epoch {
+            self.refill_epoch = epoch;
+            self.refilled_in_epoch = 0;
+        }
+        self.refilled_in_epoch = math::add(self.refilled_in_epoch, amount)?;
+        Ok(())
+    }
+}
+
+/// Most yield venues a token can have
+pub const MAX_YIELD_VENUES: usize = 4;
+
+/// Most of a token's cold collateral (cold vault + deployed) that may be deployed, in bps
+pub const MAX_YIELD_BPS: u16 = 2_000;
+
+/// Seconds before a new venue, or a raised cap, takes deposits
+pub const YIELD_VENUE_DELAY: i64 = 7 * 24 * 60 * 60;
+
+/// A venue a token's cold collateral may be deployed to
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct YieldVenue {
+    /// Adapter program (see yield_adapter.rs)
+    pub adapter: Pubkey,
+    /// Most deployed to it at once (0 = winding down)
+    pub max_amount: u64,
+    /// When it takes deposits up to `max_amount`
+    pub active_at: i64,
+    /// Principal deployed and not yet recalled
+    pub deployed: u64,
+}
+
+/**
+ * A token's idle-collateral yield strategy (seeds: "yield_strategy", mint)
+ *
+ * Only a split escrow has idle collateral: what sits in the cold vault
+ * waits for refills that seldom come. The owner may deploy some of it to
+ * allowlisted venues, within each venue's `max_amount` and never more
+ * than MAX_YIELD_BPS of the cold collateral in all. Venues and raised
+ * caps take YIELD_VENUE_DELAY to apply; lowering a cap doesn't wait.
+ * The owner or a guardian quorum recalls at any time. What comes back
+ * over the principal is yield and goes to the insurance fund.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct YieldStrategy {
+    pub mint: Pubkey,
+    #[max_len(MAX_YIELD_VENUES)]
+    pub venues: Vec<YieldVenue>,
+    /// Principal deployed across venues
+    pub deployed: u64,
+    /// Yield paid to the insurance fund so far
+    pub harvested: u64,
+    /// Principal a venue failed to return on a full recall
+    pub lost: u64,
+    pub bump: u8,
+}
+
+impl YieldStrategy {
+    /// Principal deployed from the token whose strategy is `info` (0 if it has none)
+    pub fn deployed_of(info: &AccountInfo) -> Result<u64> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(YieldStrategy::try_deserialize(&mut &info.try_borrow_data()?[..])?.deployed)
+    }
+
+    pub fn venue_mut(&mut self, adapter: &Pubkey) -> Result<&mut YieldVenue> {
+        self.venues
+            .iter_mut()
+            .find(|venue| venue.adapter == *adapter)
+            .ok_or_else(|| error!(ErrorCode::YieldVenueNotAllowed))
+    }
+
+    /// Most that may still be deployed in all, with `cold_balance` in the cold vault
+    pub fn headroom(&self, cold_balance: u64) -> Result<u64> {
+        let collateral = math::add(cold_balance, self.deployed)?;
+        Ok(math::bps_of(collateral, MAX_YIELD_BPS)?.saturating_sub(self.deployed))
+    }
+
+    /// Drop venues that are winding down and have nothing left deployed
+    pub fn prune(&mut self) {
+        self.venues.retain(|venue| venue.max_amount > 0 || venue.deployed > 0);
+    }
+}
+
+/**
+ * Compliance co-signing of a restricted token's burns (seeds: "burn_approval", mint)
+ *
+ * A burn of more than `threshold` must be signed by `compliance_key` as
+ * well as the holder, in the same transaction, or it fails before
+ * anything is burned or emitted. A default `compliance_key` turns it off.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct BurnApproval {
+    pub mint: Pubkey,
+    pub compliance_key: Pubkey,
+    /// Largest burn that needs no co-signature
+    pub threshold: u64,
+}
+
+impl BurnApproval {
+    /// Fail unless a burn of `amount` is approved by `cosigner` where `info`'s policy needs it
+    pub fn check(info: &AccountInfo, amount: u64, cosigner: Option<&Signer>) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let approval = BurnApproval::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        if approval.compliance_key == Pubkey::default() || amount <= approval.threshold {
+            return Ok(());
+        }
+        let signed = cosigner.is_some_and(|signer| signer.key() == approval.compliance_key);
+        require_ctx!(
+            signed,
+            ErrorCode::ComplianceSignatureRequired,
+            amount = amount,
+            threshold = approval.threshold
+        );
+        Ok(())
+    }
+}
+
+/// Returned by `reserve_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct ReserveStatus {
+    pub wrapped_supply: u64,
+    pub attested_reserve: u64,
+    pub block_number: u64,
+    pub posted_at: i64,
+    /// Wrapped tokens not backed by the attested reserve (0 if fully backed)
+    pub deficit: u64,
+}
+
+/// Returned by `get_transfer_status`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct TransferStatusInfo {
+    pub transfer_id: [u8; 32],
+    /// None if there's no record (never created, or pruned after a checkpoint)
+    pub status: Option<TransferStatus>,
+    pub direction: Option<TransferDirection>,
+    pub nonce: u64,
+    pub amount: u64,
+    pub remote_chain_id: u64,
+    pub updated_at: i64,
+    /// Outbound only; 0 = none
+    pub deadline: i64,
+    /// Optimistic inbound transfers only
+    pub attestation_status: Option<AttestationStatus>,
+    pub executable_at: i64,
+}
+
+/// Returned by `quote_lock`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LockQuote {
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub fee: u64,
+    /// Volume rebate tier applied (0 = none)
+    pub fee_tier: u8,
+    /// What crosses, before any Token-2022 transfer fee or share conversion
+    pub net_amount: u64,
+    pub dest_chain_id: u64,
+    pub user: Pubkey,
+    /// Nonce a lock sent now would get (taken if another lands first)
+    pub next_nonce: u64,
+}
+
+/// Returned by `preview_lock`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct LockPreview {
+    pub mint: Pubkey,
+    /// Debited from the user, fee included
+    pub amount: u64,
+    pub fee: u64,
+    /// Volume rebate tier applied (0 = none)
+    pub fee_tier: u8,
+    /// Withheld by the mint's Token-2022 transfer fee on the way into escrow
+    pub token_fee: u64,
+    /// What the LockEvent will carry (shares for share-accounted tokens)
+    pub bridged_amount: u64,
+    pub dest_chain_id: u64,
+    pub user: Pubkey,
+    /// Nonce the lock would get if nothing lands before it
+    pub nonce: u64,
+    /// Transfer ID the lock would get with `nonce`
+    pub transfer_id: [u8; 32],
+}
+
+/**
+ * Returned by `get_rate_limits`
+ *
+ * There are no time windows: per-transfer limits are fixed by the user's
+ * credential tier, and throttle headroom comes back as relayers finish
+ * transfers, so nothing resets at a known time.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct RateLimitStatus {
+    pub mint: Pubkey,
+    pub dest_chain_id: u64,
+    pub user: Pubkey,
+    /// Bridge or token is paused, or the destination disabled
+    pub blocked: bool,
+    /// Smallest amount a lock to `dest_chain_id` takes
+    pub min_amount: u64,
+    /// Most the user may send in one transfer, from their credential tier (None = no limit)
+    pub max_amount: Option<u64>,
+    /// New transfers the outbound throttle admits before BridgeCongested (None = no limit)
+    pub throttle_headroom: Option<u64>,
+    /// Surcharge the next lock pays on top of its fee, 0 if the throttle would reject it
+    pub surcharge_bps: u16,
+}
+
+/**
+ * Version of the program's interface, reported by `get_bridge_info`
+ *
+ * Bumped when an instruction's accounts or arguments, or an account or
+ * event layout, change incompatibly. New instructions and trailing
+ * fields don't bump it; clients detect those with `features`.
+ */
+pub const INTERFACE_VERSION: u16 = 8;
+
+/// Most chain configs one `get_bridge_info` reports (return data is 1 KiB)
+pub const MAX_INFO_CHAINS: usize = 12;
+
+/**
+ * Bits of `BridgeInfo::features`: what this deployment has switched on
+ */
+pub mod features {
+    /// `set_idempotent_delivery`
+    pub const IDEMPOTENT_DELIVERY: u64 = 1 << 0;
+    /// `set_claim_delivery`: mints wait for `claim`
+    pub const CLAIM_DELIVERY: u64 = 1 << 1;
+    /// `set_bridge_pass`
+    pub const BRIDGE_PASS: u64 = 1 << 2;
+    /// `set_heartbeat_interval`: delivery stops without guardian heartbeats
+    pub const GUARDIAN_HEARTBEAT: u64 = 1 << 3;
+}
+
+/**
+ * Bits of `BridgeInfo::verifiers`, one per `TrustModel`
+ */
+pub mod verifier_bits {
+    pub const OWNER_SIGNATURE: u8 = 1 << 0;
+    pub const GUARDIAN_QUORUM: u8 = 1 << 1;
+    pub const WORMHOLE_VAA: u8 = 1 << 2;
+    pub const LIGHT_CLIENT: u8 = 1 << 3;
+    pub const OPTIMISTIC: u8 = 1 << 4;
+}
+
+/// Returned by `get_bridge_info`
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct BridgeInfo {
+    /// Crate version the program was built from
+    pub version: String,
+    pub interface_version: u16,
+    /// `features` bits
+    pub features: u64,
+    /// `verifier_bits` of the trust models compiled in
+    pub verifiers: u8,
+    pub paused: bool,
+    pub event_encoding: EventEncoding,
+    /// Default fee schedule; each chain's fee comes before the rebate, and a
+    /// token's fee route to a chain replaces it (see `quote_lock`)
+    pub fee_tiers: Vec<FeeTier>,
+    /// The chain configs that were passed in, in order
+    pub chains: Vec<ChainConfig>,
+}
+
+/// Most transfer records one checkpoint can commit
+pub const MAX_CHECKPOINT_BATCH: usize = 24;
+
+/**
+ * Checkpoint chain head
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct CheckpointLog {
+    /// Checkpoints taken; also the next checkpoint's index
+    pub count: u64,
+    pub last_root: [u8; 32],
+    pub last_slot: u64,
+    /// One past the highest inbound nonce in any checkpoint (0: none yet)
+    pub inbound_nonce_end: u64,
+}
+
+/**
+ * Merkle root over a batch of finished transfers
+ *
+ * Each checkpoint links to the one before through `prev_root`, so the
+ * full history can be checked from the latest one back.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Checkpoint {
+    pub index: u64,
+    pub root: [u8; 32],
+    pub prev_root: [u8; 32],
+    pub transfer_count: u32,
+    pub inbound_volume: u64,
+    pub outbound_volume: u64,
+    pub slot: u64,
+    pub created_at: i64,
+}
+
+/// Longest archive locator (an IPFS CID or Arweave transaction ID, with scheme)
+pub const MAX_ARCHIVE_LOCATOR_LEN: usize = 96;
+
+/**
+ * Archive chain head
+ *
+ * `archiver` is the key the indexer signs `record_archive` with; the
+ * owner can always record one too.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct ArchiveLog {
+    pub archiver: Pubkey,
+    /// Archives recorded; also the next archive's index
+    pub count: u64,
+    pub last_hash: [u8; 32],
+    /// Last slot the latest archive covers
+    pub last_slot: u64,
+}
+
+/**
+ * An event bundle published off-chain (seeds: "archive", index)
+ *
+ * `hash` is the SHA-256 of the bundle as published at `locator`, and
+ * each archive links to the one before through `prev_hash`, so an
+ * auditor holding the bundles can check the history is complete and
+ * unaltered from the latest one back.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Archive {
+    pub index: u64,
+    pub hash: [u8; 32],
+    pub prev_hash: [u8; 32],
+    pub first_slot: u64,
+    pub last_slot: u64,
+    /// Where the bundle lives, e.g. "ipfs://<cid>" or "ar://<tx>"
+    #[max_len(MAX_ARCHIVE_LOCATOR_LEN)]
+    pub locator: String,
+    pub recorded_at: i64,
+}
+
+/**
+ * State import from a previous deployment
+ *
+ * Imports stay open until `sealed`, which is permanent.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Migration {
+    /// Program the state came from; set by the first import_state
+    pub source_program: Pubkey,
+    pub imported_nonces: u64,
+    pub imported_users: u64,
+    pub sealed: bool,
+}
+
+impl Migration {
+    pub fn begin(&mut self, source_program: Pubkey) -> Result<()> {
+        require!(!self.sealed, ErrorCode::ImportSealed);
+        if self.source_program == Pubkey::default() {
+            self.source_program = source_program;
+        }
+        require_keys_eq!(self.source_program, source_program, ErrorCode::InvalidConfig);
+        Ok(())
+    }
+
+    /// Other imports need import_state to have run first
+    pub fn check_open(&self) -> Result<()> {
+        require!(!self.sealed, ErrorCode::ImportSealed);
+        require!(self.source_program != Pubkey::default(), ErrorCode::InvalidConfig);
+        Ok(())
+    }
+}
+
+/// Seconds a queued escrow migration waits before it can run
+pub const ESCROW_MIGRATION_DELAY: i64 = 7 * 24 * 60 * 60;
+
+/**
+ * A token's escrow moving to a successor deployment (seeds: "escrow_migration", mint)
+ *
+ * Queued by the owner, executable ESCROW_MIGRATION_DELAY later unless
+ * the owner or a guardian quorum cancels it meanwhile. Once executed it
+ * stays, recording where the escrow went and the replay-protection
+ * state the successor imports; a token's escrow migrates once.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct EscrowMigration {
+    pub mint: Pubkey,
+    pub new_bridge_program: Pubkey,
+    /// The successor's escrow, owned by its bridge authority
+    pub new_vault: Pubkey,
+    /// 0 = nothing queued
+    pub executable_at: i64,
+    /// 0 = not migrated yet
+    pub migrated_at: i64,
+    /// Escrow handed over, hot and cold
+    pub amount: u64,
+    /// BridgeState's route shards and processed floor at migration
+    pub route_shards: u32,
+    pub processed_floor: u64,
+}
+
+impl EscrowMigration {
+    /// The bridge authority `program` escrows with, as `new_vault`'s owner must be
+    pub fn successor_authority(program: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(&[b"bridge"], program).0
+    }
+}
+
+/**
+ * Escrow sent to another instance of this program (seeds:
+ * "instance_transfer", mint, id)
+ *
+ * Side-by-side instances (staging, production, a partner's) each have
+ * their own escrow. `send_to_instance` moves collateral between them
+ * directly, with no fee and no trip through Ethereum. The receiving
+ * instance reads this account to record the transfer on its side
+ * (`receive_instance_transfer`).
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct InstanceTransfer {
+    pub id: u64,
+    pub mint: Pubkey,
+    pub dest_instance: u64,
+    /// The receiving instance's escrow, owned by its bridge authority
+    pub dest_vault: Pubkey,
+    /// What the receiving escrow got
+    pub amount: u64,
+    pub sent_at: i64,
+}
+
+/**
+ * A receiving instance's record of an InstanceTransfer (seeds:
+ * "instance_receipt", source record)
+ *
+ * Its existence stops the same transfer being recorded twice.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct InstanceReceipt {
+    pub source_instance: u64,
+    /// The sending instance's InstanceTransfer
+    pub source_record: Pubkey,
+    pub amount: u64,
+    pub received_at: i64,
+}
+
+/// Longest reason an ejection records
+pub const MAX_EJECT_REASON_LEN: usize = 64;
+
+/**
+ * Liveness registry entry of one operator (seeds: "liveness", operator)
+ *
+ * Guardians and the relayer (the owner) post `heartbeat` periodically
+ * and each keeps its own entry, so monitoring can tell a slow bridge
+ * (transfers queued, operators still beating) from one whose operators
+ * are gone. Guardian heartbeats also feed the dead-man's switch
+ * (`set_heartbeat_interval`).
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct OperatorLiveness {
+    pub operator: Pubkey,
+    /// Was in the guardian set at its last heartbeat (else the owner)
+    pub guardian: bool,
+    pub first_seen: i64,
+    pub last_seen: i64,
+    pub last_slot: u64,
+    pub last_epoch: u64,
+    pub beats: u64,
+}
+
+impl OperatorLiveness {
+    /// Has the operator posted a heartbeat in the last `max_age` seconds?
+    pub fn is_live(&self, now: i64, max_age: i64) -> bool {
+        now.saturating_sub(self.last_seen) <= max_age
+    }
+}
+
+/**
+ * A relayer ejected for misbehavior (seeds: "ejected", relayer)
+ *
+ * While the account exists the relayer can't post attestations and the
+ * bonds of its pending ones are frozen. `reinstate_relayer` closes it.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct EjectedRelayer {
+    pub relayer: Pubkey,
+    #[max_len(MAX_EJECT_REASON_LEN)]
+    pub reason: String,
+    /// Attestation resolved as fraud, for an automatic ejection
+    pub fraud_nonce: Option<u64>,
+    pub ejected_at: i64,
+}
+
+impl EjectedRelayer {
+    /// Fail if the ejection record behind `info` exists
+    pub fn check_not_ejected(info: &AccountInfo) -> Result<()> {
+        require!(info.data_is_empty(), ErrorCode::RelayerEjected);
+        Ok(())
+    }
+
+    /**
+     * Eject `relayer` from inside an instruction that can't declare the
+     * record as `init` (resolve_challenge only ejects on fraud)
+     *
+     * Does nothing if the relayer is already ejected.
+     */
+    pub fn create<'info>(
+        info: &AccountInfo<'info>,
+        payer: &AccountInfo<'info>,
+        system_program_info: &AccountInfo<'info>,
+        instance: &[u8],
+        bump: u8,
+        relayer: Pubkey,
+        fraud_nonce: u64,
+    ) -> Result<bool> {
+        if !info.data_is_empty() {
+            return Ok(false);
+        }
+
+        let space = 8 + EjectedRelayer::INIT_SPACE;
+        system_program::create_account(
+            CpiContext::new_with_signer(
+                system_program_info.clone(),
+                system_program::CreateAccount {
+                    from: payer.clone(),
+                    to: info.clone(),
+                },
+                &[&[b"ejected", instance, relayer.as_ref(), &[bump]]],
+            ),
+            Rent::get()?.minimum_balance(space),
+            space as u64,
+            &crate::ID,
+        )?;
+
+        let ejection = EjectedRelayer {
+            relayer,
+            reason: "Attestation resolved as fraud".to_string(),
+            fraud_nonce: Some(fraud_nonce),
+            ejected_at: Clock::get()?.unix_timestamp,
+        };
+        ejection.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(true)
+    }
+}
+
+/**
+ * Fee for one token to one destination chain
+ * (seeds: "fee", mint, chain_id)
+ *
+ * Relayer cost depends on the asset as much as on the chain: USDC to
+ * Arbitrum is cheap to deliver, an illiquid token to mainnet isn't.
+ * Locks always pass the pair's PDA; if it doesn't exist the chain's
+ * default fee applies, so leaving it out can't dodge a pair's fee.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct FeeRoute {
+    pub mint: Pubkey,
+    pub chain_id: u64,
+    /// Flat fee per transfer, in token base units
+    pub base_fee: u64,
+    /// Proportional fee on top of `base_fee`
+    pub fee_bps: u16,
+}
+
+impl FeeRoute {
+    /// The route behind `info`, or None if the pair has none of its own
+    pub fn load(info: &AccountInfo) -> Result<Option<FeeRoute>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(Some(FeeRoute::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+}
+
+/// Decimals of SOL amounts (lamports)
+pub const SOL_DECIMALS: u8 = 9;
+
+/**
+ * Paying a token's bridge fee in SOL (seeds: "sol_fee", mint)
+ *
+ * A lock that passes this account leaves the whole amount to cross and
+ * pays the fee's value in lamports into the SOL fee vault
+ * (["sol_fee_vault"]) instead, so a user can bridge their entire
+ * balance. The fee is worked out in tokens as usual, then converted at
+ * the Pyth prices of the token and of SOL, plus `markup_bps` to cover
+ * the oracle's spread while the vault is sold down.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct SolFeeConfig {
+    pub mint: Pubkey,
+    /// The token's Pyth USD price feed; all zeros turns SOL fees off
+    pub feed_id: [u8; 32],
+    /// SOL's Pyth USD price feed
+    pub sol_feed_id: [u8; 32],
+    pub markup_bps: u16,
+}
+
+impl SolFeeConfig {
+    /// Lamports for a fee of `fee` base units of a `decimals`-decimal token
+    pub(crate) fn lamports_for(
+        &self,
+        fee: u64,
+        decimals: u8,
+        token_price: &crate::pyth::Price,
+        sol_price: &crate::pyth::Price,
+    ) -> Result<u64> {
+        let lamports = math::convert(
+            fee,
+            decimals,
+            token_price.price as u64,
+            token_price.exponent,
+            SOL_DECIMALS,
+            sol_price.price as u64,
+            sol_price.exponent,
+        )?;
+        math::add(lamports, math::bps_of(lamports, self.markup_bps)?)
+    }
+}
+
+/**
+ * Prepaying delivery on a destination chain (seeds: "delivery_fee", chain_id)
+ *
+ * A lock that passes this account also pays, in lamports, for the
+ * relayer's transaction on the destination: `gas_limit` at `gas_price`
+ * (smallest units of the chain's native token), converted at the Pyth
+ * prices of that token and of SOL, plus `markup_bps` for the gas price
+ * moving until delivery. The lamports go to the relayer reward vault,
+ * and DeliveryFeePrepaid tells relayers the delivery is paid for, so
+ * the recipient needs no gas there.
+ *
+ * The owner sets the feeds and gas limit; the owner or fee manager keeps
+ * `gas_price` current.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct DeliveryFeeConfig {
+    pub chain_id: u64,
+    /// Pyth USD feed of the chain's native token (all zeros = off)
+    pub native_feed_id: [u8; 32],
+    /// Decimals of the native token (18 for ETH)
+    pub native_decimals: u8,
+    pub sol_feed_id: [u8; 32],
+    /// Gas one delivery takes
+    pub gas_limit: u64,
+    /// Native token's smallest units per gas (0 = not set yet)
+    pub gas_price: u64,
+    pub gas_price_updated_at: i64,
+    pub markup_bps: u16,
+}
+
+impl DeliveryFeeConfig {
+    /// Lamports that pay for one delivery at today's prices
+    pub(crate) fn lamports_for(
+        &self,
+        native_price: &crate::pyth::Price,
+        sol_price: &crate::pyth::Price,
+    ) -> Result<u64> {
+        let cost = self
+            .gas_limit
+            .checked_mul(self.gas_price)
+            .ok_or_else(|| error!(ErrorCode::MathOverflow))?;
+        let lamports = math::convert(
+            cost,
+            self.native_decimals,
+            native_price.price as u64,
+            native_price.exponent,
+            SOL_DECIMALS,
+            sol_price.price as u64,
+            sol_price.exponent,
+        )?;
+        math::add(lamports, math::bps_of(lamports, self.markup_bps)?)
+    }
+}
+
+/**
+ * Per-token price circuit breaker (seeds: "price_breaker", mint)
+ *
+ * A permissionless crank (`poke_price_breaker`) feeds it Pyth prices.
+ * The first price in a window becomes its reference; a later one more
+ * than `max_move_bps` away from it trips the breaker, and lock,
+ * lock_batch and unlock of the token fail with PriceBreakerTripped for
+ * `cooldown` seconds. A fast move is when a depeg or an exploit of the
+ * token is most likely to be drained through the bridge, so transfers
+ * wait it out. Once the window has passed, the next price starts a new
+ * one.
+ *
+ * Nothing is checked without the account, or with `feed_id` all zeros.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct PriceBreaker {
+    pub mint: Pubkey,
+    /// The token's Pyth USD price feed; all zeros turns the breaker off
+    pub feed_id: [u8; 32],
+    pub max_move_bps: u16,
+    /// Seconds a reference price is compared against
+    pub window: i64,
+    /// Seconds transfers stay stopped after a trip
+    pub cooldown: i64,
+    pub reference_price: i64,
+    pub reference_exponent: i32,
+    /// When the reference was taken (0 = none yet)
+    pub reference_at: i64,
+    /// Transfers of the token fail until this time
+    pub tripped_until: i64,
+}
+
+impl PriceBreaker {
+    /// Fail with PriceBreakerTripped while the breaker behind `info`, if
+    /// any, is tripped
+    pub fn check(info: &AccountInfo, now: i64) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let breaker = PriceBreaker::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        require_ctx!(
+            breaker.feed_id == [0u8; 32] || now >= breaker.tripped_until,
+            ErrorCode::PriceBreakerTripped,
+            mint = breaker.mint,
+            until = breaker.tripped_until
+        );
+        Ok(())
+    }
+
+    /**
+     * Compare `price` with the window's reference, tripping past
+     * `max_move_bps`; returns the move (bps) if it tripped
+     *
+     * Starts a new window on the first price, once the window has
+     * passed, and if the feed's exponent changed.
+     */
+    pub fn observe(&mut self, price: &crate::pyth::Price, now: i64) -> Result<Option<u64>> {
+        let in_window = self.reference_at != 0
+            && now < math::add_seconds(self.reference_at, self.window)?
+            && price.exponent == self.reference_exponent;
+        if !in_window {
+            self.reference_price = price.price;
+            self.reference_exponent = price.exponent;
+            self.reference_at = now;
+            return Ok(None);
+        }
+
+        let reference = self.reference_price as u64;
+        let moved = (price.price as u64).abs_diff(reference);
+        let move_bps = math::mul_div(moved, math::BPS_DENOMINATOR, reference)?;
+        if move_bps <= self.max_move_bps as u64 {
+            return Ok(None);
+        }
+        self.tripped_until = math::add_seconds(now, self.cooldown)?;
+        Ok(Some(move_bps))
+    }
+}
+
+/**
+ * Terms of use a deployment requires before a wallet's first lock
+ * (seeds: "terms")
+ *
+ * For restricted deployments whose institutional users must accept the
+ * bridge's terms before using it. `terms_hash` identifies the document
+ * (e.g. keccak256 of its text); the bridge only compares versions.
+ * Nothing is required without the account, or with `version` 0.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TermsConfig {
+    /// Version a wallet must have acknowledged; 0 = none required
+    pub version: u32,
+    pub terms_hash: [u8; 32],
+    pub updated_at: i64,
+}
+
+impl TermsConfig {
+    /**
+     * Fail with TermsNotAccepted unless `wallet` acknowledged the
+     * version the terms behind `config` require
+     *
+     * `acceptance` is the wallet's TermsAcceptance PDA, empty if it
+     * never acknowledged any.
+     */
+    pub fn check(config: &AccountInfo, acceptance: &AccountInfo, wallet: &Pubkey) -> Result<()> {
+        if config.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*config.owner, crate::ID, ErrorCode::Unauthorized);
+        let required = TermsConfig::try_deserialize(&mut &config.try_borrow_data()?[..])?.version;
+        if required == 0 {
+            return Ok(());
+        }
+
+        let accepted = if acceptance.data_is_empty() {
+            0
+        } else {
+            require_keys_eq!(*acceptance.owner, crate::ID, ErrorCode::Unauthorized);
+            TermsAcceptance::try_deserialize(&mut &acceptance.try_borrow_data()?[..])?.version
+        };
+        require_ctx!(
+            accepted >= required,
+            ErrorCode::TermsNotAccepted,
+            wallet = wallet,
+            accepted = accepted,
+            required = required
+        );
+        Ok(())
+    }
+}
+
+/// A wallet's acknowledgement of the terms (seeds: "terms_ack", wallet)
+#[account]
+#[derive(InitSpace)]
+pub struct TermsAcceptance {
+    pub wallet: Pubkey,
+    /// Latest version acknowledged
+    pub version: u32,
+    /// The document acknowledged, as TermsConfig had it then
+    pub terms_hash: [u8; 32],
+    pub accepted_at: i64,
+}
+
+/// Most mints a SenderAllowlist can gate one by one
+pub const MAX_ALLOWLISTED_MINTS: usize = 16;
+
+/**
+ * Closed user group mode (seeds: "sender_allowlist")
+ *
+ * For institutional deployments that must know everyone who sends
+ * through them. With `all_tokens` set, or for the mints in `mints`,
+ * lock, lock_batch and burn only take senders the owner allowlisted
+ * (AllowedSender); mint and unlock are unaffected. Nothing is gated
+ * without the account.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct SenderAllowlist {
+    pub all_tokens: bool,
+    /// Mints gated when `all_tokens` is off
+    #[max_len(MAX_ALLOWLISTED_MINTS)]
+    pub mints: Vec<Pubkey>,
+    pub updated_at: i64,
+}
+
+impl SenderAllowlist {
+    /**
+     * Fail with SenderNotAllowlisted if the allowlist behind `config`
+     * gates `mint` and `sender` isn't on it
+     *
+     * `entry` is the sender's AllowedSender PDA, empty unless allowed.
+     */
+    pub fn check(
+        config: &AccountInfo,
+        entry: &AccountInfo,
+        mint: &Pubkey,
+        sender: &Pubkey,
+    ) -> Result<()> {
+        if config.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*config.owner, crate::ID, ErrorCode::Unauthorized);
+        let allowlist = SenderAllowlist::try_deserialize(&mut &config.try_borrow_data()?[..])?;
+        if !allowlist.all_tokens && !allowlist.mints.contains(mint) {
+            return Ok(());
+        }
+
+        let allowed = !entry.data_is_empty() && *entry.owner == crate::ID;
+        require_ctx!(
+            allowed,
+            ErrorCode::SenderNotAllowlisted,
+            sender = sender,
+            mint = mint
+        );
+        Ok(())
+    }
+}
+
+/// A sender the owner allowlisted (seeds: "allowed_sender", sender)
+#[account]
+#[derive(InitSpace)]
+pub struct AllowedSender {
+    pub sender: Pubkey,
+    pub added_at: i64,
+}
+
+/**
+ * A user's deposit address (seeds: "deposit", user)
+ *
+ * Exchange-style bridging: the user registers a destination once, then
+ * anything sent to the PDA's token accounts (associated accounts of an
+ * off-curve owner) is locked to that destination by `sweep_deposit`,
+ * which anyone can crank. The lock is the user's own, with their fees,
+ * limits and checks, and refunds go to them. Tokens the bridge doesn't
+ * list can't be swept; the user takes them back with `withdraw_deposit`.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct DepositAddress {
+    pub user: Pubkey,
+    pub dest_chain_id: u64,
+    /// In the destination chain's native format, as `lock` takes it
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub recipient: String,
+    pub registered_at: i64,
+    /// Locks swept so far
+    pub sweeps: u64,
+    pub bump: u8,
+}
+
+/// Most addresses a WithdrawalAllowlist holds
+pub const MAX_WITHDRAWAL_ADDRESSES: usize = 16;
+
+/// How long an address added to an existing WithdrawalAllowlist waits
+pub const WITHDRAWAL_ADDRESS_DELAY: i64 = 24 * 60 * 60;
+
+/// An approved destination on a WithdrawalAllowlist
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct WithdrawalAddress {
+    pub dest_chain_id: u64,
+    /// As `recipient::address_bytes` encodes it, so spelling doesn't matter
+    pub address: [u8; 32],
+    /// When transfers may start going to it
+    pub active_at: i64,
+}
+
+/**
+ * A user's approved withdrawal addresses (seeds: "withdrawal_allowlist",
+ * user)
+ *
+ * Defense in depth for large holders against wallet drainers. Once a
+ * user has one, their lock, lock_batch and burn only go to active
+ * addresses on it. The addresses the list is created with count at
+ * once; any added later wait WITHDRAWAL_ADDRESS_DELAY, so a stolen key
+ * can't add an address and drain to it in one go. Removals count at
+ * once. `authority` manages the list: the user, until they hand it to a
+ * custodian. A list can't be deleted.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct WithdrawalAllowlist {
+    pub user: Pubkey,
+    pub authority: Pubkey,
+    #[max_len(MAX_WITHDRAWAL_ADDRESSES)]
+    pub addresses: Vec<WithdrawalAddress>,
+    pub bump: u8,
+}
+
+impl WithdrawalAllowlist {
+    /**
+     * Fail with RecipientNotWhitelisted if the sender has the allowlist
+     * behind `info` and `recipient` on `chain` isn't active on it
+     */
+    pub fn check(
+        info: &AccountInfo,
+        chain: &ChainConfig,
+        recipient: &str,
+        now: i64,
+    ) -> Result<()> {
+        if info.data_is_empty() {
+            return Ok(());
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let allowlist = WithdrawalAllowlist::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        let address = recipient::address_bytes(chain, recipient)?;
+        let allowed = allowlist.addresses.iter().any(|entry| {
+            entry.dest_chain_id == chain.chain_id
+                && entry.address == address
+                && entry.active_at <= now
+        });
+        require_ctx!(
+            allowed,
+            ErrorCode::RecipientNotWhitelisted,
+            recipient = recipient,
+            dest_chain_id = chain.chain_id
+        );
+        Ok(())
+    }
+}
+
+/**
+ * Delivery-time guarantee for outbound transfers (seeds: "sla")
+ *
+ * A transfer acknowledged more than `max_latency_slots` after its lock
+ * (ack_outbound, or a delivered settle_batch leg) earns its sender
+ * `rebate_bps` of the fee it paid. The rebate is owed on the transfer's
+ * record (`sla_rebate`) until the sender claims it out of the fee vault
+ * with `claim_sla_rebate`. Nothing is owed without the account, or with
+ * `max_latency_slots` 0.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct SlaConfig {
+    pub max_latency_slots: u64,
+    /// Share of the fee rebated
+    pub rebate_bps: u16,
+}
+
+impl SlaConfig {
+    /// Rebate `record` has earned under the SLA behind `info`; 0 within it
+    pub fn rebate_for(info: &AccountInfo, record: &TransferRecord) -> Result<u64> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        let sla = SlaConfig::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        if sla.max_latency_slots == 0 || record.latency_slots() <= sla.max_latency_slots {
+            return Ok(0);
+        }
+        math::bps_of(record.fee, sla.rebate_bps)
+    }
+}
+
+/// Longest endpoint URL a relayer may register
+pub const MAX_RELAYER_ENDPOINT_LEN: usize = 128;
+
+/// Most relay routes one registration may list
+pub const MAX_RELAYER_ROUTES: usize = 16;
+
+/// What a relayer charges per delivery, on top of the bridge's own fee
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub struct RelayerFeeSchedule {
+    /// Flat fee per transfer, in token base units
+    pub base_fee: u64,
+    /// Proportional fee on top of `base_fee`
+    pub fee_bps: u16,
+}
+
+/**
+ * A relayer's self-registration (seeds: "relayer", relayer)
+ *
+ * Operator metadata, so the SDK can list available relayers and their
+ * terms from chain state and users or aggregators can pick among them.
+ * `supported_routes` are RelayRoute IDs; empty means every route. The
+ * program doesn't enforce the terms: it's a directory, not a contract.
+ * Ejected relayers can't register.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RelayerRegistration {
+    pub relayer: Pubkey,
+    #[max_len(MAX_RELAYER_ENDPOINT_LEN)]
+    pub endpoint_url: String,
+    #[max_len(MAX_RELAYER_ROUTES)]
+    pub supported_routes: Vec<u32>,
+    pub fee_schedule: RelayerFeeSchedule,
+    pub registered_at: i64,
+    pub updated_at: i64,
+}
+
+impl RelayerRegistration {
+    /// Does the relayer serve transfers on `route_id`?
+    pub fn serves(&self, route_id: u32) -> bool {
+        self.supported_routes.is_empty() || self.supported_routes.contains(&route_id)
+    }
+}
+
+/**
+ * Relay topic of one token to one destination chain
+ * (seeds: "route", mint, chain_id)
+ *
+ * Lock and burn put the pair's `route_id` in their events and outbox
+ * entry, so a relayer operator serving only some routes filters on one
+ * integer instead of decoding every transfer, and several operators can
+ * split one deployment between them. Like fee routes, transfers always
+ * pass the pair's PDA; a pair without one gets route 0.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RelayRoute {
+    pub mint: Pubkey,
+    pub chain_id: u64,
+    /// Never 0 (that's "no route")
+    pub route_id: u32,
+}
+
+impl RelayRoute {
+    /// The route ID behind `info`; 0 if the pair has no route
+    pub fn id_of(info: &AccountInfo) -> Result<u32> {
+        if info.data_is_empty() {
+            return Ok(0);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(RelayRoute::try_deserialize(&mut &info.try_borrow_data()?[..])?.route_id)
+    }
+}
+
+/**
+ * Outbound sequence of one token to one destination chain
+ * (seeds: "sequence", mint, chain_id)
+ *
+ * Lock, lock_batch and burn number the pair's transfers 1, 2, 3, ...
+ * on their record (`route_sequence`) and in a RouteSequenced event, so a
+ * relayer can deliver a route in order and spot a missing transfer
+ * without following every other token's. The sequence also makes the
+ * transfer's nonce, which addresses its record and goes into its
+ * transfer ID: the pair's shard in the high bits, the sequence in the
+ * low SEQUENCE_BITS. Each pair writes only its own account, so locks of
+ * different tokens don't contend for BridgeState.
+ *
+ * Anyone can create one with `init_route_sequence`, which hands out the
+ * shards from 1; shard 0 is the old global counter's, so those nonces
+ * stay unique. A pair without one can't lock or burn.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RouteSequence {
+    pub mint: Pubkey,
+    pub dest_chain_id: u64,
+    /// Sequence of the pair's latest transfer
+    pub last: u64,
+    /// High bits of the pair's nonces
+    pub shard: u32,
+}
+
+/// Low bits of an outbound nonce that hold the route sequence
+pub const SEQUENCE_BITS: u32 = 40;
+
+/// Shards fit in the bits the sequence leaves
+pub const MAX_ROUTE_SHARD: u32 = (1 << (64 - SEQUENCE_BITS)) - 1;
+
+impl RouteSequence {
+    /// Nonce of transfer `sequence` of the pair in `shard`
+    pub fn nonce_at(shard: u32, sequence: u64) -> u64 {
+        ((shard as u64) << SEQUENCE_BITS) | sequence
+    }
+
+    /// Nonce of the pair's next transfer, without taking it
+    pub fn next_nonce(&self) -> Result<u64> {
+        Ok(Self::nonce_at(self.shard, math::add(self.last, 1)?))
+    }
+
+    /// Take the pair's next sequence; returns (nonce, sequence)
+    pub fn take(&mut self) -> Result<(u64, u64)> {
+        let sequence = math::increment(&mut self.last)?;
+        require!(sequence < 1 << SEQUENCE_BITS, ErrorCode::SequenceExhausted);
+        Ok((Self::nonce_at(self.shard, sequence), sequence))
+    }
+}
+
+/**
+ * Health of one relay route (seeds: "route_health", route_id)
+ *
+ * Written by `report_route_health`, scored as in route_health.rs, and
+ * overwritten by each report. A score is only as fresh as `updated_at`:
+ * the SDK treats an old one as unknown, since a route nobody reports on
+ * may be the unhealthiest of all.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct RouteHealth {
+    pub route_id: u32,
+    /// 0-100; see route_health::score
+    pub score: u8,
+    pub report: RouteHealthReport,
+    /// Seconds since the relayer's last heartbeat when reported (u64::MAX if never)
+    pub heartbeat_age: u64,
+    pub reporter: Pubkey,
+    pub updated_at: i64,
+    pub updated_slot: u64,
+}
+
+impl RouteHealth {
+    pub fn status(&self) -> RouteStatus {
+        RouteStatus::of(self.score)
+    }
+}
+
+/**
+ * Outbound queue-depth throttle (seeds: "throttle")
+ *
+ * `pending` counts outbound transfers the relayers haven't finished:
+ * lock, lock_batch and burn add to it, and ack_outbound, reclaim_expired
+ * and cancel_pending take them off again. From `surcharge_at` on, new
+ * locks pay `surcharge_bps` of the amount on top of their fee; at
+ * `reject_at` new transfers fail with BridgeCongested until the queue
+ * drains. That keeps relayers from drowning in a backlog and bounds what
+ * is in flight while they are down.
+ *
+ * Nothing is counted or throttled without the account. Transfers from
+ * before it existed were never counted, so `configure_throttle` takes
+ * the depth to start from.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct Throttle {
+    pub pending: u64,
+    /// Depth from which locks pay the surcharge (0 = never)
+    pub surcharge_at: u64,
+    pub surcharge_bps: u16,
+    /// Most transfers that may be pending (0 = no limit)
+    pub reject_at: u64,
+}
+
+impl Throttle {
+    /// The throttle behind `info`, if one was configured
+    fn load(info: &AccountInfo) -> Result<Option<Throttle>> {
+        if info.data_is_empty() {
+            return Ok(None);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        Ok(Some(Throttle::try_deserialize(&mut &info.try_borrow_data()?[..])?))
+    }
+
+    /// Surcharge (bps) on `count` new transfers; BridgeCongested past `reject_at`
+    pub fn check(&self, count: u64) -> Result<u16> {
+        require_ctx!(
+            self.reject_at == 0 || math::add(self.pending, count)? <= self.reject_at,
+            ErrorCode::BridgeCongested,
+            pending = self.pending,
+            reject_at = self.reject_at
+        );
+        if self.surcharge_at != 0 && self.pending >= self.surcharge_at {
+            Ok(self.surcharge_bps)
+        } else {
+            Ok(0)
+        }
+    }
+
+    /// Is the queue behind `info` deep enough for locks to pay the surcharge?
+    pub fn is_congested(info: &AccountInfo) -> Result<bool> {
+        Ok(Throttle::load(info)?
+            .is_some_and(|t| t.surcharge_at != 0 && t.pending >= t.surcharge_at))
+    }
+
+    /// What `check` says for one transfer through the throttle behind `info`
+    pub fn surcharge_of(info: &AccountInfo) -> Result<u16> {
+        Throttle::load(info)?.map_or(Ok(0), |throttle| throttle.check(1))
+    }
+
+    /// New transfers the throttle behind `info` still admits (None = no limit)
+    pub fn headroom_of(info: &AccountInfo) -> Result<Option<u64>> {
+        Ok(Throttle::load(info)?
+            .filter(|throttle| throttle.reject_at != 0)
+            .map(|throttle| throttle.reject_at.saturating_sub(throttle.pending)))
+    }
+
+    /// Count `count` new transfers in the throttle behind `info`, returning their surcharge
+    pub fn admit(info: &AccountInfo, count: u64) -> Result<u16> {
+        let Some(mut throttle) = Throttle::load(info)? else {
+            return Ok(0);
+        };
+        let surcharge_bps = throttle.check(count)?;
+        throttle.pending = math::add(throttle.pending, count)?;
+        throttle.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])?;
+        Ok(surcharge_bps)
+    }
+
+    /**
+     * Take a finished transfer off the throttle behind `info`
+     *
+     * Saturates: a transfer from before the throttle may not have been
+     * counted.
+     */
+    pub fn release(info: &AccountInfo) -> Result<()> {
+        let Some(mut throttle) = Throttle::load(info)? else {
+            return Ok(());
+        };
+        throttle.pending = throttle.pending.saturating_sub(1);
+        throttle.try_serialize(&mut &mut info.try_borrow_mut_data()?[..])
+    }
+}
+
+/**
+ * Where priority tips go (seeds: "priority_config")
+ *
+ * `relayer_share_bps` of each collected tip goes to the relayer reward
+ * vault, paid out with the epoch rewards; the rest to `treasury`.
+ * Nobody can bid before this exists.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct PriorityConfig {
+    pub relayer_share_bps: u16,
+    pub treasury: Pubkey,
+}
+
+/**
+ * A priority bid on a queued outbound transfer (seeds: "priority_bid", nonce)
+ *
+ * While the throttle says the queue is congested, the sender can tip
+ * (in lamports, held by this account) to be relayed sooner. Relayers
+ * work through the outbox by tip, highest first; equal tips keep their
+ * queue order, so bidding is an auction and not a race. A bid can only
+ * be raised. Once the transfer is attested or delivered,
+ * `collect_priority_tip` splits the tip; if it is refunded instead, the
+ * tip goes back to the sender.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct PriorityBid {
+    pub nonce: u64,
+    pub sender: Pubkey,
+    /// Lamports, over the account's rent
+    pub tip: u64,
+    pub updated_at: i64,
+}
+
+/// Longest payload send_message takes
+pub const MAX_MESSAGE_LEN: usize = 512;
+
+/**
+ * A contract messages may be sent to
+ * (seeds: "message_target", chain_id, target)
+ *
+ * The allowlist is one PDA per pair, so send_message passes the pair's
+ * PDA and `check_allowed` fails if it was never created or was removed.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct MessageTarget {
+    pub chain_id: u64,
+    /// Contract address, left-padded to 32 bytes
+    pub target: [u8; 32],
+    pub allowed_at: i64,
+}
+
+impl MessageTarget {
+    /// Fail unless `info` is the live allowlist entry it claims to be
+    pub fn check_allowed(info: &AccountInfo) -> Result<()> {
+        require!(
+            !info.data_is_empty() && *info.owner == crate::ID,
+            ErrorCode::MessageTargetNotAllowed
+        );
+        Ok(())
+    }
+}
+
+/**
+ * How locks are reported in the logs
+ *
+ * Full: LockEvent. Compact: CompactLockEvent, fixed-size and without
+ * strings. Both: each lock emits the two, for switching relayers over.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Debug, PartialEq, Eq, InitSpace)]
+pub enum EventEncoding {
+    Full,
+    Compact,
+    Both,
+}
+
+pub const MAX_TRACKED_TOKENS: usize = 16;
+
+/**
+ * Bridge-wide TVL and flows, for dashboards and integrators
+ * (seeds: "global_stats")
+ *
+ * Updated lazily: update_global_stats prices the escrow of every tracked
+ * token with Pyth and books each escrow's change since the last update
+ * as inflow or outflow. USD amounts are micro-dollars.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct GlobalStats {
+    /// Value escrowed across tracked tokens at `updated_at`
+    pub tvl_usd: u64,
+    /// Start of the current 24h flow window
+    pub window_start: i64,
+    /// Value escrowed / released since `window_start`
+    pub inflow_usd: u64,
+    pub outflow_usd: u64,
+    /// Flows of the window before (0 if no update fell in it)
+    pub last_inflow_usd: u64,
+    pub last_outflow_usd: u64,
+    pub updated_at: i64,
+    pub slot: u64,
+    #[max_len(MAX_TRACKED_TOKENS)]
+    pub tokens: Vec<TrackedToken>,
+}
+
+impl GlobalStats {
+    /// Length of a flow window (seconds)
+    pub const WINDOW: i64 = 86_400;
+
+    /// Start a new flow window at `now` if the current one is over
+    pub fn roll_window(&mut self, now: i64) {
+        let elapsed = now.saturating_sub(self.window_start);
+        if elapsed < Self::WINDOW {
+            return;
+        }
+
+        let contiguous = elapsed < 2 * Self::WINDOW;
+        self.last_inflow_usd = if contiguous { self.inflow_usd } else { 0 };
+        self.last_outflow_usd = if contiguous { self.outflow_usd } else { 0 };
+        self.inflow_usd = 0;
+        self.outflow_usd = 0;
+        self.window_start = now;
+    }
+}
+
+/// A token counted in GlobalStats
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, InitSpace)]
+pub struct TrackedToken {
+    pub mint: Pubkey,
+    pub decimals: u8,
+    /// Bridge token account holding the escrow
+    pub escrow: Pubkey,
+    /// Pyth price feed ID (USD quote)
+    pub feed_id: [u8; 32],
+    /// Escrow balance at the last update
+    pub balance: u64,
+}
+
+/// Most inbound transfers one source transaction is looked up to
+pub const MAX_SOURCE_TX_TRANSFERS: usize = 8;
+
+/**
+ * Inbound transfers by source-chain transaction
+ * (seeds: "source_tx", tx_hash)
+ *
+ * Answers "what happened to Ethereum tx 0x...?" from Solana alone: each
+ * nonce leads to the inbound TransferRecord. Filled in by the relayer
+ * that delivered the transfers (see `record_source_tx`), so it is as
+ * good as its word, like the delivery itself.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct SourceTx {
+    pub tx_hash: [u8; 32],
+    /// Chain the transaction is on
+    pub chain_id: u64,
+    /// Inbound nonces the transaction carried, in the order recorded
+    #[max_len(MAX_SOURCE_TX_TRANSFERS)]
+    pub nonces: Vec<u64>,
+}
+
+/// Most chains a route may pass through between the destination and the final chain
+pub const MAX_ROUTE_HOPS: usize = 3;
+
+/**
+ * Where a transfer goes after its destination chain
+ *
+ * For transfers that cooperating bridge deployments relay on, e.g.
+ * Solana -> Ethereum -> an L2: the lock's destination is the first hop,
+ * `hops` are the chains after it, and `final_recipient` is in the final
+ * chain's native format.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct RoutePayload {
+    #[max_len(MAX_ROUTE_HOPS)]
+    pub hops: Vec<u64>,
+    pub final_chain_id: u64,
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub final_recipient: String,
+}
+
+impl RoutePayload {
+    /**
+     * Check the route of a transfer to `dest_chain_id`
+     *
+     * `chains` are the registry entries of every hop, then of the final
+     * chain. Each must be enabled, no chain may come up twice (Solana and
+     * the destination included), and the final recipient must be valid
+     * on the final chain.
+     */
+    pub fn validate(&self, dest_chain_id: u64, chains: &[ChainConfig]) -> Result<()> {
+        require!(
+            self.hops.len() <= MAX_ROUTE_HOPS && chains.len() == self.hops.len() + 1,
+            ErrorCode::InvalidRoute
+        );
+
+        let mut visited = vec![chain_ids::SOLANA, dest_chain_id];
+        let route = self.hops.iter().chain([&self.final_chain_id]);
+        for (chain_id, chain) in route.zip(chains) {
+            require!(
+                chain.chain_id == *chain_id && chain.enabled && !visited.contains(chain_id),
+                ErrorCode::InvalidRoute
+            );
+            visited.push(*chain_id);
+        }
+
+        recipient::validate(&chains[self.hops.len()], &self.final_recipient)
+    }
+}
+
+/**
+ * Route of an outbound transfer (seeds: "route", nonce)
+ *
+ * Set once by the sender with set_transfer_route.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct TransferRoute {
+    pub transfer_id: [u8; 32],
+    pub nonce: u64,
+    pub route: RoutePayload,
+}
+
+/**
+ * Fee exemption (seeds: "fee_exempt", address)
+ *
+ * Existence of the PDA waives the bridge fee on the address's locks,
+ * e.g. for the protocol's own market makers or the LP rebalancer. A
+ * Token-2022 transfer fee is the mint's, so it still applies.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct FeeExemption {
+    pub address: Pubkey,
+    /// Owner or fee manager that granted it
+    pub granted_by: Pubkey,
+    pub granted_at: i64,
+}
+
+impl FeeExemption {
+    /// Whether the exemption behind `info` exists; false if never granted or revoked
+    pub fn is_exempt(info: &AccountInfo) -> Result<bool> {
+        if info.data_is_empty() {
+            return Ok(false);
+        }
+        require_keys_eq!(*info.owner, crate::ID, ErrorCode::Unauthorized);
+        FeeExemption::try_deserialize(&mut &info.try_borrow_data()?[..])?;
+        Ok(true)
+    }
+}
+
+/**
+ * Gas a relayer paid delivering one inbound transfer (seeds: "gas_report", nonce)
+ *
+ * Written by `report_gas`; one per transfer, so each delivery is
+ * reimbursed once.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct GasReport {
+    pub nonce: u64,
+    pub relayer: Pubkey,
+    /// Lamports the relayer paid (fees, priority fees, account rent)
+    pub gas_paid: u64,
+    /// Lamports credited: `gas_paid` up to `max_gas_per_transfer`
+    pub reimbursed: u64,
+    pub reported_at: i64,
+}
+
+/**
+ * A relayer's gas reimbursement balance (seeds: "gas_reimbursement", relayer)
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct GasReimbursement {
+    pub relayer: Pubkey,
+    /// Lamports credited and not claimed yet
+    pub owed: u64,
+    /// Lifetime totals
+    pub reports: u64,
+    pub gas_paid: u64,
+    pub claimed: u64,
+}
+
+/**
+ * Who can upgrade the program (seeds: "upgrade_authority")
+ *
+ * Copied from the program's ProgramData account by the upgrade-authority
+ * instructions and by the permissionless `sync_upgrade_authority`, so a
+ * client assessing the bridge reads one account instead of decoding the
+ * loader's. An authority changed outside the program (`solana program
+ * set-upgrade-authority`) shows up here after the next sync.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct UpgradeAuthorityRecord {
+    /// None once the program is finalized: no one can upgrade it again
+    pub authority: Option<Pubkey>,
+    /// Whether `authority` was the bridge owner (governance) when recorded
+    pub held_by_owner: bool,
+    /// Slot the program was last deployed or upgraded at
+    pub deployed_slot: u64,
+    pub recorded_at: i64,
+}
+
+impl UpgradeAuthorityRecord {
+    /// Copy the current authority; returns the previous one
+    pub fn record(
+        &mut self,
+        authority: Option<Pubkey>,
+        owner: &Pubkey,
+        deployed_slot: u64,
+        now: i64,
+    ) -> Option<Pubkey> {
+        let previous = self.authority;
+        self.authority = authority;
+        self.held_by_owner = authority == Some(*owner);
+        self.deployed_slot = deployed_slot;
+        self.recorded_at = now;
+        previous
+    }
+}
+
+/// Longest version label a build record takes, e.g. "v1.4.2" or a git tag
+pub const MAX_BUILD_VERSION_LEN: usize = 32;
+
+/**
+ * Verified build of the deployed program (seeds: "build_record")
+ *
+ * Governance records the hash solana-verify computes for the audited
+ * source (`solana-verify get-program-hash`), together with the deploy
+ * slot it was checked against. An integrator recomputes the hash from
+ * the ProgramData account and compares; if the program has been
+ * upgraded since, `deployed_slot` no longer matches and the record is
+ * stale until governance records the new build.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct BuildRecord {
+    /// SHA-256 of the executable, trailing zero bytes trimmed (solana-verify's hash)
+    pub hash: [u8; 32],
+    #[max_len(MAX_BUILD_VERSION_LEN)]
+    pub version: String,
+    /// ProgramData slot when recorded: the deploy the hash was vouched for
+    pub deployed_slot: u64,
+    pub recorded_at: i64,
+}
+
+impl BuildRecord {
+    /// Whether the record still describes the deployment last made at `deployed_slot`
+    pub fn is_current(&self, deployed_slot: u64) -> bool {
+        self.deployed_slot == deployed_slot
+    }
+}
+
+/**
+ * Concurrent Merkle tree finished transfer records move into
+ * (seeds: "compressed_store")
+ *
+ * See compression.rs. `init_compressed_store` points it at a new tree;
+ * records already in an older tree stay provable there.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct CompressedStore {
+    pub tree: Pubkey,
+    pub max_depth: u32,
+    pub max_buffer_size: u32,
+    /// Leaves in `tree`; also the next leaf's index
+    pub leaves: u64,
+    /// Records compressed across every tree
+    pub compressed: u64,
+    pub tree_authority_bump: u8,
+}
+
+impl CompressedStore {
+    pub fn is_full(&self) -> bool {
+        self.leaves >= 1u64 << self.max_depth
+    }
+}
+
+/**
+ * OTC order counter (seeds: "otc_book")
+ *
+ * Order IDs are global, not per maker: a fill proof names the order by
+ * its ID, so no two orders may share one.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct OtcBook {
+    /// ID the next order must take
+    pub next_order_id: u64,
+    /// Orders still escrowed
+    pub open: u64,
+}
+
+/**
+ * What the maker of an OTC order wants for the escrow
+ *
+ * The price is `counter_amount` for the whole escrow; there are no
+ * partial fills. `verifier` is what has to vouch for the taker's payment
+ * (OwnerSignature, GuardianQuorum or WormholeVaa). WormholeVaa takes
+ * Ethereum as the counter chain, and `emitter` must be a settlement
+ * contract there, not the bridge, so transfer VAAs can't double as
+ * payment proofs.
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Debug, PartialEq, Eq, InitSpace)]
+pub struct OtcTerms {
+    pub counter_chain_id: u64,
+    /// Asset the maker wants, in the counter chain's format; empty = native
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub counter_asset: String,
+    /// Smallest units of `counter_asset`
+    pub counter_amount: u64,
+    /// Maker's address on the counter chain
+    #[max_len(MAX_REMOTE_ADDRESS_LEN)]
+    pub pay_to: String,
+    /// Until then the order is binding; after it the maker may cancel
+    pub expiry: i64,
+    pub verifier: TrustModel,
+}
+
+/**
+ * An escrowed OTC swap order (seeds: "otc_order", order_id)
+ *
+ * The maker's tokens sit in the mint's OTC escrow, apart from the
+ * bridge's collateral, until a taker proves they paid on the counter
+ * chain. The proof is an inbound transfer of `counter_amount` of the
+ * counter asset to the taker with the order ID as nonce, vouched for by
+ * the order's verifier.
+ */
+#[account]
+#[derive(InitSpace)]
+pub struct OtcOrder {
+    pub order_id: u64,
+    pub maker: Pubkey,
+    pub mint: Pubkey,
+    /// Escrowed, net of any Token-2022 transfer fee
+    pub amount: u64,
+    pub terms: OtcTerms,
+    pub created_at: i64,
+}
+
+impl OtcOrder {
+    /**
+     * The inbound transfer a payment proof for this order vouches for
+     *
+     * Its token is the order's payment key, which the settlement
+     * contract on the counter chain derives from the payment it saw:
+     *
+     *   keccak256(abi.encodePacked(keccak256(counterAsset), keccak256(payTo),
+     *     bytes32 maker, bytes32 mint, uint64 instanceId))
+     *
+     * So a payment of the right amount in another asset, on another
+     * chain, to someone other than the maker, or for another instance's
+     * order with the same ID proves nothing. Like any inbound evidence,
+     * it's scoped to the instance's current guardian set.
+     */
+    pub fn payment(
+        &self,
+        taker: Pubkey,
+        instance_id: u64,
+        guardian_set_index: u32,
+    ) -> verifiers::InboundTransfer {
+        verifiers::InboundTransfer {
+            source_chain: self.terms.counter_chain_id,
+            guardian_set_index,
+            token: codec::keccak256v(&[
+                &codec::keccak256(self.terms.counter_asset.as_bytes()),
+                &codec::keccak256(self.terms.pay_to.as_bytes()),
+                self.maker.as_ref(),
+                self.mint.as_ref(),
+                &instance_id.to_be_bytes(),
+            ]),
+            recipient: taker,
+            amount: self.terms.counter_amount,
+            nonce: self.order_id,
+        }
+    }
+}
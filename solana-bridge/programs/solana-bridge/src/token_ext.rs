@@ -0,0 +1,178 @@
+//! Token-2022 support for escrowed tokens
+//!
+//! Token-2022 mints can carry extensions that change what a transfer
+//! does. Each token's config allowlists the extensions the bridge accepts
+//! (`TokenConfig::allowed_extensions`); a mint with anything else is
+//! rejected before funds move.
+//!
+//! - Transfer fee: the receiver gets less than was sent. We pass the
+//!   expected fee to `transfer_checked_with_fee` so it can't change under
+//!   us, and only book what actually arrived.
+//! - Transfer hook: the hook's extra accounts come in as remaining
+//!   accounts and are resolved by `invoke_transfer_checked`.
+//!
+//! Legacy SPL Token mints have no extensions and take the plain
+//! `transfer_checked` path.
+
+use anchor_lang::prelude::*;
+use anchor_spl::token_2022::spl_token_2022::{
+    self,
+    extension::{
+        transfer_fee::TransferFeeConfig, BaseStateWithExtensions, ExtensionType,
+        StateWithExtensions,
+    },
+    onchain,
+};
+use anchor_spl::token_2022_extensions::transfer_fee::{
+    self as transfer_fee, TransferCheckedWithFee,
+};
+use anchor_spl::token_interface::{self, TransferChecked};
+
+use crate::{compute, ErrorCode};
+
+/// Allowlist flags for `TokenConfig::allowed_extensions`
+pub mod token_extensions {
+    pub const TRANSFER_FEE: u16 = 1 << 0;
+    pub const TRANSFER_HOOK: u16 = 1 << 1;
+    /// Metadata pointer and embedded token metadata
+    pub const METADATA: u16 = 1 << 2;
+    pub const INTEREST_BEARING: u16 = 1 << 3;
+    pub const MINT_CLOSE_AUTHORITY: u16 = 1 << 4;
+    pub const PERMANENT_DELEGATE: u16 = 1 << 5;
+
+    pub const ALL: u16 = TRANSFER_FEE
+        | TRANSFER_HOOK
+        | METADATA
+        | INTEREST_BEARING
+        | MINT_CLOSE_AUTHORITY
+        | PERMANENT_DELEGATE;
+}
+
+/// The extensions of a mint that change how we transfer
+#[derive(Default)]
+pub struct MintExtensions {
+    pub transfer_fee: Option<TransferFeeConfig>,
+    pub transfer_hook: bool,
+}
+
+impl MintExtensions {
+    /// Transfer fee withheld from a transfer of `amount` this epoch
+    pub fn withheld_fee(&self, amount: u64) -> Result<u64> {
+        let Some(config) = &self.transfer_fee else {
+            return Ok(0);
+        };
+        config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or_else(|| error!(ErrorCode::InvalidTransferFee))
+    }
+}
+
+/**
+ * Read the mint's extensions and check them against the allowlist
+ *
+ * Extensions without a flag (non-transferable, confidential transfers,
+ * default frozen accounts, ...) are never accepted.
+ */
+pub fn inspect(mint: &AccountInfo, allowed: u16) -> Result<MintExtensions> {
+    let mut extensions = MintExtensions::default();
+    if *mint.owner != spl_token_2022::ID {
+        return Ok(extensions);
+    }
+
+    let data = mint.try_borrow_data()?;
+    let state = StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&data)?;
+
+    for extension in state.get_extension_types()? {
+        let flag = match extension {
+            ExtensionType::TransferFeeConfig => {
+                extensions.transfer_fee = Some(*state.get_extension::<TransferFeeConfig>()?);
+                token_extensions::TRANSFER_FEE
+            }
+            ExtensionType::TransferHook => {
+                extensions.transfer_hook = true;
+                token_extensions::TRANSFER_HOOK
+            }
+            ExtensionType::MetadataPointer | ExtensionType::TokenMetadata => {
+                token_extensions::METADATA
+            }
+            ExtensionType::InterestBearingConfig => token_extensions::INTEREST_BEARING,
+            ExtensionType::MintCloseAuthority => token_extensions::MINT_CLOSE_AUTHORITY,
+            ExtensionType::PermanentDelegate => token_extensions::PERMANENT_DELEGATE,
+            _ => 0,
+        };
+        require!(flag != 0 && allowed & flag == flag, ErrorCode::ExtensionNotAllowed);
+    }
+
+    Ok(extensions)
+}
+
+/**
+ * A token transfer that works for both token programs
+ */
+pub struct TokenTransfer<'a, 'info> {
+    pub token_program: AccountInfo<'info>,
+    pub from: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub to: AccountInfo<'info>,
+    pub authority: AccountInfo<'info>,
+    /// Transfer-hook extra accounts (remaining accounts)
+    pub extra_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> TokenTransfer<'a, 'info> {
+    /**
+     * Send `amount`, returning how much the receiver actually got
+     */
+    pub fn execute(
+        self,
+        extensions: &MintExtensions,
+        amount: u64,
+        decimals: u8,
+        signer_seeds: &[&[&[u8]]],
+    ) -> Result<u64> {
+        let fee = extensions.withheld_fee(amount)?;
+
+        if extensions.transfer_hook {
+            compute::reserve(compute::Step::TransferHook)?;
+            // The token program applies any transfer fee itself
+            onchain::invoke_transfer_checked(
+                self.token_program.key,
+                self.from,
+                self.mint,
+                self.to,
+                self.authority,
+                self.extra_accounts,
+                amount,
+                decimals,
+                signer_seeds,
+            )?;
+        } else if extensions.transfer_fee.is_some() {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program.clone(),
+                TransferCheckedWithFee {
+                    token_program_id: self.token_program,
+                    source: self.from,
+                    mint: self.mint,
+                    destination: self.to,
+                    authority: self.authority,
+                },
+                signer_seeds,
+            );
+            transfer_fee::transfer_checked_with_fee(cpi_ctx, amount, decimals, fee)?;
+        } else {
+            let cpi_ctx = CpiContext::new_with_signer(
+                self.token_program,
+                TransferChecked {
+                    from: self.from,
+                    mint: self.mint,
+                    to: self.to,
+                    authority: self.authority,
+                },
+                signer_seeds,
+            );
+            token_interface::transfer_checked(cpi_ctx, amount, decimals)?;
+        }
+
+        Ok(amount - fee)
+    }
+}
@@ -0,0 +1,32 @@
+//! The program's own upgrade authority
+//!
+//! The BPF upgradeable loader keeps it in the program's ProgramData
+//! account; only the current authority can change it. Handing it to the
+//! bridge owner puts upgrades behind the same governance (multisig,
+//! Squads vault) as every other admin action, and setting it to None
+//! freezes the program for good.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::bpf_loader_upgradeable;
+use anchor_lang::solana_program::program::invoke;
+
+/**
+ * Move the upgrade authority from `authority` (a signer) to
+ * `new_authority`, or to no one
+ */
+pub fn set_authority<'info>(
+    program_data: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    new_authority: Option<&AccountInfo<'info>>,
+) -> Result<()> {
+    let ix = bpf_loader_upgradeable::set_upgrade_authority(
+        &crate::ID,
+        authority.key,
+        new_authority.map(|a| a.key),
+    );
+
+    let mut accounts = vec![program_data.clone(), authority.clone()];
+    accounts.extend(new_authority.cloned());
+    invoke(&ix, &accounts)?;
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Groth16 verification over BN254
+//!
+//! Uses the alt_bn128 syscalls (the same precompiles Ethereum exposes at
+//! 0x06/0x07/0x08), so a proof produced for a Solidity verifier can be
+//! checked here byte-for-byte.
+//!
+//! All points are big-endian encoded:
+//!   G1 = x (32) || y (32)
+//!   G2 = x_c1 (32) || x_c0 (32) || y_c1 (32) || y_c0 (32)
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::alt_bn128::prelude::{
+    alt_bn128_addition, alt_bn128_multiplication, alt_bn128_pairing,
+};
+
+use crate::ErrorCode;
+
+/// BN254 base field modulus (used to negate G1 points)
+const BASE_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x97, 0x81, 0x6a, 0x91, 0x68, 0x71, 0xca, 0x8d, 0x3c, 0x20, 0x8c, 0x16, 0xd8, 0x7c, 0xfd, 0x47,
+];
+
+/// BN254 scalar field modulus (public inputs must be below it)
+const SCALAR_FIELD_MODULUS: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5d,
+    0x28, 0x33, 0xe8, 0x48, 0x79, 0xb9, 0x70, 0x91, 0x43, 0xe1, 0xf5, 0x93, 0xf0, 0x00, 0x00, 0x01,
+];
+
+/**
+ * Proof points (A, B, C) as produced by snarkjs / gnark
+ */
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Groth16Proof {
+    pub a: [u8; 64],
+    pub b: [u8; 128],
+    pub c: [u8; 64],
+}
+
+/**
+ * Verify `proof` against the verifying key and public inputs
+ *
+ * Checks e(-A, B) * e(alpha, beta) * e(vk_x, gamma) * e(C, delta) == 1
+ * where vk_x = IC[0] + sum(input[i] * IC[i + 1]).
+ */
+pub fn verify(
+    alpha_g1: &[u8; 64],
+    beta_g2: &[u8; 128],
+    gamma_g2: &[u8; 128],
+    delta_g2: &[u8; 128],
+    ic: &[[u8; 64]],
+    proof: &Groth16Proof,
+    public_inputs: &[[u8; 32]],
+) -> Result<()> {
+    require!(
+        ic.len() == public_inputs.len() + 1,
+        ErrorCode::InvalidVerifyingKey
+    );
+
+    // vk_x = IC[0] + sum(input[i] * IC[i + 1])
+    let mut vk_x = ic[0];
+    for (input, point) in public_inputs.iter().zip(ic[1..].iter()) {
+        require!(
+            input.as_slice() < SCALAR_FIELD_MODULUS.as_slice(),
+            ErrorCode::InvalidProof
+        );
+
+        let mut mul_input = [0u8; 96];
+        mul_input[..64].copy_from_slice(point);
+        mul_input[64..].copy_from_slice(input);
+        let product = alt_bn128_multiplication(&mul_input)
+            .map_err(|_| error!(ErrorCode::InvalidProof))?;
+
+        let mut add_input = [0u8; 128];
+        add_input[..64].copy_from_slice(&vk_x);
+        add_input[64..].copy_from_slice(&product);
+        let sum = alt_bn128_addition(&add_input)
+            .map_err(|_| error!(ErrorCode::InvalidProof))?;
+        vk_x.copy_from_slice(&sum);
+    }
+
+    let neg_a = negate_g1(&proof.a)?;
+
+    let mut pairing_input = Vec::with_capacity(4 * 192);
+    pairing_input.extend_from_slice(&neg_a);
+    pairing_input.extend_from_slice(&proof.b);
+    pairing_input.extend_from_slice(alpha_g1);
+    pairing_input.extend_from_slice(beta_g2);
+    pairing_input.extend_from_slice(&vk_x);
+    pairing_input.extend_from_slice(gamma_g2);
+    pairing_input.extend_from_slice(&proof.c);
+    pairing_input.extend_from_slice(delta_g2);
+
+    let result = alt_bn128_pairing(&pairing_input)
+        .map_err(|_| error!(ErrorCode::InvalidProof))?;
+
+    // The precompile returns a 32-byte big-endian 1 on success
+    require!(
+        result.len() == 32 && result[..31].iter().all(|b| *b == 0) && result[31] == 1,
+        ErrorCode::InvalidProof
+    );
+
+    Ok(())
+}
+
+/**
+ * Split a 32-byte hash into two field elements (high and low 128 bits)
+ *
+ * A keccak256 output can exceed the scalar field, so circuits take it
+ * as two limbs instead.
+ */
+pub fn hash_to_public_inputs(hash: &[u8; 32]) -> [[u8; 32]; 2] {
+    let mut hi = [0u8; 32];
+    let mut lo = [0u8; 32];
+    hi[16..].copy_from_slice(&hash[..16]);
+    lo[16..].copy_from_slice(&hash[16..]);
+    [hi, lo]
+}
+
+/// Negate a G1 point: (x, y) -> (x, p - y)
+fn negate_g1(point: &[u8; 64]) -> Result<[u8; 64]> {
+    let mut out = *point;
+
+    // Point at infinity is its own negation
+    if point[32..].iter().all(|b| *b == 0) {
+        return Ok(out);
+    }
+
+    let mut borrow = 0i16;
+    for i in (0..32).rev() {
+        let mut diff = BASE_FIELD_MODULUS[i] as i16 - point[32 + i] as i16 - borrow;
+        if diff < 0 {
+            diff += 256;
+            borrow = 1;
+        } else {
+            borrow = 0;
+        }
+        out[32 + i] = diff as u8;
+    }
+
+    // y >= p is not a valid coordinate
+    require!(borrow == 0, ErrorCode::InvalidProof);
+
+    Ok(out)
+}
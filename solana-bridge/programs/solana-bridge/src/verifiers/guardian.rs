@@ -0,0 +1,46 @@
+//! Signature-based verifiers
+//!
+//! GuardianQuorum: enough guardian-set members sign the transaction
+//! (as signers in the remaining accounts).
+//! OwnerSignature: the owner/relayer signs, or a guardian quorum does.
+//!
+//! Neither sees the transfer itself: the signers vouch for whatever
+//! transaction they sign.
+
+use anchor_lang::prelude::*;
+
+use super::{InboundTransfer, Verifier};
+use crate::{ErrorCode, GuardianSet};
+
+pub struct GuardianQuorum<'a, 'info> {
+    /// Guardian set PDA; empty means no quorum is possible
+    pub guardian_set: &'a AccountInfo<'info>,
+    pub signers: &'a [AccountInfo<'info>],
+}
+
+impl Verifier for GuardianQuorum<'_, '_> {
+    fn verify(&self, _transfer: &InboundTransfer) -> Result<()> {
+        require!(
+            GuardianSet::has_quorum(self.guardian_set, self.signers)?,
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+}
+
+pub struct OwnerSignature<'a, 'info> {
+    /// Signer of the delivering transaction
+    pub authority: Pubkey,
+    /// Bridge owner/relayer
+    pub owner: Pubkey,
+    pub quorum: GuardianQuorum<'a, 'info>,
+}
+
+impl Verifier for OwnerSignature<'_, '_> {
+    fn verify(&self, transfer: &InboundTransfer) -> Result<()> {
+        if self.authority == self.owner {
+            return Ok(());
+        }
+        self.quorum.verify(transfer)
+    }
+}
@@ -0,0 +1,142 @@
+//! Light-client verifier: a Groth16 proof that the Ethereum lock is final
+//!
+//! The circuit proves the Lock event's inclusion in a finalized block;
+//! its two public inputs are the high and low 128 bits of the inbound
+//! message hash, which binds the proof to one source chain, mint,
+//! recipient, amount and nonce.
+
+use anchor_lang::prelude::*;
+
+use super::groth16::{self, Groth16Proof};
+use super::{InboundTransfer, Verifier};
+use crate::ZkVerifier;
+
+pub struct LightClient<'a> {
+    pub key: &'a ZkVerifier,
+    pub proof: &'a Groth16Proof,
+}
+
+impl Verifier for LightClient<'_> {
+    fn verify(&self, transfer: &InboundTransfer) -> Result<()> {
+        groth16::verify(
+            &self.key.alpha_g1,
+            &self.key.beta_g2,
+            &self.key.gamma_g2,
+            &self.key.delta_g2,
+            &self.key.ic,
+            self.proof,
+            &groth16::hash_to_public_inputs(&transfer.message_hash()),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anchor_lang::solana_program::alt_bn128::prelude::{
+        alt_bn128_addition, alt_bn128_multiplication,
+    };
+
+    /// BN254 G1 generator (1, 2)
+    fn g1() -> [u8; 64] {
+        let mut point = [0u8; 64];
+        point[31] = 1;
+        point[63] = 2;
+        point
+    }
+
+    /// BN254 G2 generator, as the precompiles encode it
+    fn g2() -> [u8; 128] {
+        let hex = "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+                   1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+                   090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+                   12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa";
+        let mut point = [0u8; 128];
+        for (i, byte) in point.iter_mut().enumerate() {
+            *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+        }
+        point
+    }
+
+    fn mul(point: &[u8; 64], scalar: &[u8; 32]) -> [u8; 64] {
+        let input = [point.as_slice(), scalar].concat();
+        alt_bn128_multiplication(&input).unwrap().try_into().unwrap()
+    }
+
+    fn add(a: &[u8; 64], b: &[u8; 64]) -> [u8; 64] {
+        let input = [a.as_slice(), b].concat();
+        alt_bn128_addition(&input).unwrap().try_into().unwrap()
+    }
+
+    fn times_g1(scalar: u8) -> [u8; 64] {
+        let mut word = [0u8; 32];
+        word[31] = scalar;
+        mul(&g1(), &word)
+    }
+
+    /**
+     * A key whose discrete logs are known: with beta = gamma = delta = G2
+     * a proof only has to satisfy A = alpha + vk_x + C, which anyone can
+     * compute. Useless as a real key; enough to test what a proof binds.
+     */
+    fn toy_key() -> ZkVerifier {
+        ZkVerifier {
+            alpha_g1: times_g1(2),
+            beta_g2: g2(),
+            gamma_g2: g2(),
+            delta_g2: g2(),
+            ic: vec![times_g1(3), times_g1(4), times_g1(5)],
+        }
+    }
+
+    fn prove(key: &ZkVerifier, transfer: &InboundTransfer) -> Groth16Proof {
+        let [hi, lo] = groth16::hash_to_public_inputs(&transfer.message_hash());
+        let vk_x = add(&add(&key.ic[0], &mul(&key.ic[1], &hi)), &mul(&key.ic[2], &lo));
+        let c = times_g1(6);
+        Groth16Proof {
+            a: add(&add(&key.alpha_g1, &vk_x), &c),
+            b: g2(),
+            c,
+        }
+    }
+
+    fn transfer() -> InboundTransfer {
+        InboundTransfer {
+            source_chain: 1,
+            guardian_set_index: 0,
+            token: [2; 32],
+            recipient: Pubkey::new_from_array([3; 32]),
+            amount: 1_000_000,
+            nonce: 7,
+        }
+    }
+
+    #[test]
+    fn accepts_a_proof_of_the_transfer() {
+        let key = toy_key();
+        let proof = prove(&key, &transfer());
+        assert!(LightClient { key: &key, proof: &proof }.verify(&transfer()).is_ok());
+    }
+
+    #[test]
+    fn rejects_the_proof_for_another_mint_amount_or_guardian_set() {
+        let key = toy_key();
+        let proof = prove(&key, &transfer());
+        let verifier = LightClient { key: &key, proof: &proof };
+        let other_mint = InboundTransfer {
+            token: [4; 32],
+            ..transfer()
+        };
+        let other_amount = InboundTransfer {
+            amount: 2_000_000,
+            ..transfer()
+        };
+        let other_set = InboundTransfer {
+            guardian_set_index: 1,
+            ..transfer()
+        };
+        assert!(verifier.verify(&other_mint).is_err());
+        assert!(verifier.verify(&other_amount).is_err());
+        assert!(verifier.verify(&other_set).is_err());
+    }
+}
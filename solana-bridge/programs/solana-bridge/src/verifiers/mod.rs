@@ -0,0 +1,225 @@
+//! Inbound verifiers, one per trust model
+//!
+//! Each token says what has to vouch for an inbound transfer before the
+//! bridge releases it (`TokenConfig::trust_model`), so one deployment can
+//! run a blue-chip token on Wormhole and a long-tail token on the
+//! relayer's signature. The inbound instructions map onto the models:
+//!
+//!   mint / unlock        OwnerSignature, GuardianQuorum, WormholeVaa
+//!   mint_with_proof      LightClient
+//!   execute_attestation  Optimistic
+//!
+//! and a transfer for a token on another model fails with WrongTrustModel.
+//!
+//! Every backend implements `Verifier` over the evidence it was handed.
+//! Adding one takes a `TrustModel` variant, a module here with its
+//! `Verifier`, and an arm in `verify_relayed` (or its own instruction,
+//! if it needs accounts mint and unlock don't take).
+
+use anchor_lang::prelude::*;
+
+use crate::{codec, verifier_bits, ErrorCode, TrustModel};
+
+pub mod groth16;
+mod guardian;
+mod light_client;
+mod optimistic;
+#[cfg(feature = "wormhole")]
+pub mod wormhole;
+
+pub use guardian::{GuardianQuorum, OwnerSignature};
+pub use light_client::LightClient;
+pub use optimistic::Optimistic;
+#[cfg(feature = "wormhole")]
+pub use wormhole::WormholeVaa;
+
+/// An inbound transfer, as every verifier sees it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InboundTransfer {
+    pub source_chain: u64,
+    /// Index of this instance's guardian set, which the evidence is scoped to
+    pub guardian_set_index: u32,
+    /// The mint it releases (for an OTC payment, the order's payment key)
+    pub token: [u8; 32],
+    pub recipient: Pubkey,
+    pub amount: u64,
+    pub nonce: u64,
+}
+
+impl InboundTransfer {
+    /// What the source chain commits to (see `codec::inbound_message_hash`)
+    pub fn message_hash(&self) -> [u8; 32] {
+        codec::inbound_message_hash(
+            self.source_chain,
+            self.guardian_set_index,
+            &self.token,
+            &self.recipient.to_bytes(),
+            self.amount,
+            self.nonce,
+        )
+    }
+}
+
+/**
+ * A backend that can vouch for inbound transfers
+ *
+ * `verify` fails unless the evidence the verifier holds covers exactly
+ * this transfer. Replays are not its concern: the inbound transfer
+ * record stops those for every model alike.
+ */
+pub trait Verifier {
+    fn verify(&self, transfer: &InboundTransfer) -> Result<()>;
+}
+
+impl TrustModel {
+    /// Is the verifier for this model compiled into the program?
+    pub fn available(&self) -> bool {
+        match self {
+            TrustModel::WormholeVaa { .. } => cfg!(feature = "wormhole"),
+            _ => true,
+        }
+    }
+}
+
+/// `verifier_bits` of every model compiled into the program
+pub fn available_bits() -> u8 {
+    let mut bits = verifier_bits::OWNER_SIGNATURE
+        | verifier_bits::GUARDIAN_QUORUM
+        | verifier_bits::LIGHT_CLIENT
+        | verifier_bits::OPTIMISTIC;
+    if cfg!(feature = "wormhole") {
+        bits |= verifier_bits::WORMHOLE_VAA;
+    }
+    bits
+}
+
+/// What a relayed mint or unlock brings as evidence
+pub struct Relayed<'a, 'info> {
+    pub authority: Pubkey,
+    pub owner: Pubkey,
+    pub guardian_set: &'a AccountInfo<'info>,
+    /// Guardian signers (the remaining accounts)
+    pub signers: &'a [AccountInfo<'info>],
+    pub vaa: Option<&'a AccountInfo<'info>>,
+}
+
+/**
+ * Check a relayed mint or unlock with the token's verifier
+ *
+ * OwnerSignature is the bridge's original model: the owner/relayer,
+ * or a guardian quorum standing in for it.
+ */
+pub fn verify_relayed(
+    model: &TrustModel,
+    relayed: Relayed,
+    transfer: &InboundTransfer,
+) -> Result<()> {
+    let quorum = GuardianQuorum {
+        guardian_set: relayed.guardian_set,
+        signers: relayed.signers,
+    };
+    match model {
+        TrustModel::OwnerSignature => OwnerSignature {
+            authority: relayed.authority,
+            owner: relayed.owner,
+            quorum,
+        }
+        .verify(transfer),
+        TrustModel::GuardianQuorum => quorum.verify(transfer),
+        TrustModel::WormholeVaa { emitter } => verify_vaa(relayed.vaa, emitter, transfer),
+        TrustModel::LightClient | TrustModel::Optimistic => err!(ErrorCode::WrongTrustModel),
+    }
+}
+
+/// Fail unless the token is on `expected` (for the single-model paths)
+pub fn require_model(model: &TrustModel, expected: TrustModel) -> Result<()> {
+    require!(*model == expected, ErrorCode::WrongTrustModel);
+    Ok(())
+}
+
+#[cfg(feature = "wormhole")]
+fn verify_vaa(
+    vaa: Option<&AccountInfo>,
+    emitter: &[u8; 32],
+    transfer: &InboundTransfer,
+) -> Result<()> {
+    let vaa = vaa.ok_or(ErrorCode::InvalidVaa)?;
+    WormholeVaa { vaa, emitter }.verify(transfer)
+}
+
+#[cfg(not(feature = "wormhole"))]
+fn verify_vaa(
+    _vaa: Option<&AccountInfo>,
+    _emitter: &[u8; 32],
+    _transfer: &InboundTransfer,
+) -> Result<()> {
+    err!(ErrorCode::TrustModelUnavailable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn evidence_names_the_token_source_chain_and_guardian_set() {
+        let transfer = InboundTransfer {
+            source_chain: 1,
+            guardian_set_index: 0,
+            token: [2; 32],
+            recipient: Pubkey::new_from_array([3; 32]),
+            amount: 1_000_000,
+            nonce: 7,
+        };
+        let other_token = InboundTransfer {
+            token: [4; 32],
+            ..transfer
+        };
+        let other_chain = InboundTransfer {
+            source_chain: 10,
+            ..transfer
+        };
+        let other_set = InboundTransfer {
+            guardian_set_index: 1,
+            ..transfer
+        };
+        assert_ne!(transfer.message_hash(), other_token.message_hash());
+        assert_ne!(transfer.message_hash(), other_chain.message_hash());
+        assert_ne!(transfer.message_hash(), other_set.message_hash());
+    }
+
+    #[test]
+    fn otc_payments_name_the_order() {
+        let order = crate::OtcOrder {
+            order_id: 7,
+            maker: Pubkey::new_from_array([1; 32]),
+            mint: Pubkey::new_from_array([2; 32]),
+            amount: 1_000_000,
+            terms: crate::OtcTerms {
+                counter_chain_id: 1,
+                counter_asset: String::new(),
+                counter_amount: 5,
+                pay_to: "0x9999999999999999999999999999999999999999".to_string(),
+                expiry: 0,
+                verifier: TrustModel::OwnerSignature,
+            },
+            created_at: 0,
+        };
+        let taker = Pubkey::new_from_array([3; 32]);
+        let payment = order.payment(taker, 0, 0).message_hash();
+
+        let mut other_payee = order.clone();
+        other_payee.terms.pay_to = "0x8888888888888888888888888888888888888888".to_string();
+        let other_maker = crate::OtcOrder {
+            maker: Pubkey::new_from_array([4; 32]),
+            ..order.clone()
+        };
+        let other_mint = crate::OtcOrder {
+            mint: Pubkey::new_from_array([5; 32]),
+            ..order.clone()
+        };
+        assert_ne!(payment, other_payee.payment(taker, 0, 0).message_hash());
+        assert_ne!(payment, other_maker.payment(taker, 0, 0).message_hash());
+        assert_ne!(payment, other_mint.payment(taker, 0, 0).message_hash());
+        assert_ne!(payment, order.payment(taker, 1, 0).message_hash());
+    }
+}
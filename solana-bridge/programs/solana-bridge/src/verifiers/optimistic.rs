@@ -0,0 +1,38 @@
+//! Optimistic verifier: a bonded attestation nobody challenged in time
+//!
+//! A relayer posts the transfer with a bond (post_attestation); watchers
+//! have until `executable_at` to challenge it. Past that, an attestation
+//! still Pending vouches for exactly what it attested, mint included.
+
+use anchor_lang::prelude::*;
+
+use super::{InboundTransfer, Verifier};
+use crate::{Attestation, AttestationStatus, ErrorCode};
+
+pub struct Optimistic<'a> {
+    pub attestation: &'a Attestation,
+    /// Cluster time to judge the challenge period by
+    pub now: i64,
+}
+
+impl Verifier for Optimistic<'_> {
+    fn verify(&self, transfer: &InboundTransfer) -> Result<()> {
+        let attestation = self.attestation;
+        require!(
+            attestation.status == AttestationStatus::Pending,
+            ErrorCode::InvalidAttestationStatus
+        );
+        require!(
+            self.now >= attestation.executable_at,
+            ErrorCode::ChallengePeriodActive
+        );
+        require!(
+            attestation.mint.to_bytes() == transfer.token
+                && attestation.recipient == transfer.recipient
+                && attestation.amount == transfer.amount
+                && attestation.nonce == transfer.nonce,
+            ErrorCode::Unauthorized
+        );
+        Ok(())
+    }
+}
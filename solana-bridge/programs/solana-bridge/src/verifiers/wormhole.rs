@@ -0,0 +1,134 @@
+//! Wormhole VAAs posted by the core bridge
+//!
+//! For tokens on the WormholeVaa trust model the Ethereum side publishes
+//! each lock through Wormhole, with the inbound message hash as payload.
+//! Once the guardians have signed it, anyone posts the VAA to Solana with
+//! the core bridge's verify_signatures + post_vaa; the core bridge only
+//! creates a PostedVAA account after checking the signatures, so here we
+//! only check who owns the account and what it says:
+//!
+//!   "vaa" (3) || version (1) || consistency (1) || vaa_time (u32)
+//!   || signature_set (32) || submission_time (u32) || nonce (u32)
+//!   || sequence (u64) || emitter_chain (u16) || emitter_address (32)
+//!   || payload (u32 length + bytes)
+//!
+//! all little-endian (Borsh).
+
+use anchor_lang::prelude::*;
+
+use super::{InboundTransfer, Verifier};
+use crate::ErrorCode;
+
+/// Wormhole core bridge on mainnet
+pub const CORE_BRIDGE_PROGRAM: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+/// Wormhole's ID for Ethereum (not the EVM chain ID)
+pub const ETHEREUM_CHAIN: u16 = 2;
+
+const MAGIC: &[u8] = b"vaa";
+
+/// Bytes before the payload
+const HEADER_LEN: usize = 3 + 1 + 1 + 4 + 32 + 4 + 4 + 8 + 2 + 32 + 4;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PostedVaa<'a> {
+    pub sequence: u64,
+    pub emitter_chain: u16,
+    pub emitter_address: [u8; 32],
+    pub payload: &'a [u8],
+}
+
+impl<'a> PostedVaa<'a> {
+    pub fn from_bytes(data: &'a [u8]) -> Option<Self> {
+        if data.len() < HEADER_LEN || &data[..3] != MAGIC {
+            return None;
+        }
+        let sequence = u64::from_le_bytes(data[49..57].try_into().unwrap());
+        let emitter_chain = u16::from_le_bytes([data[57], data[58]]);
+        let emitter_address: [u8; 32] = data[59..91].try_into().unwrap();
+        let len = u32::from_le_bytes(data[91..95].try_into().unwrap()) as usize;
+
+        Some(PostedVaa {
+            sequence,
+            emitter_chain,
+            emitter_address,
+            payload: data.get(HEADER_LEN..HEADER_LEN.checked_add(len)?)?,
+        })
+    }
+}
+
+/**
+ * A posted VAA from `emitter` on Ethereum
+ *
+ * Vouches for the transfer whose message hash is its payload.
+ */
+pub struct WormholeVaa<'a, 'info> {
+    pub vaa: &'a AccountInfo<'info>,
+    pub emitter: &'a [u8; 32],
+}
+
+impl Verifier for WormholeVaa<'_, '_> {
+    fn verify(&self, transfer: &InboundTransfer) -> Result<()> {
+        require_keys_eq!(*self.vaa.owner, CORE_BRIDGE_PROGRAM, ErrorCode::InvalidVaa);
+
+        let data = self.vaa.try_borrow_data()?;
+        let posted = PostedVaa::from_bytes(&data).ok_or(ErrorCode::InvalidVaa)?;
+        require!(
+            posted.emitter_chain == ETHEREUM_CHAIN && posted.emitter_address == *self.emitter,
+            ErrorCode::InvalidVaa
+        );
+        require!(
+            posted.payload == transfer.message_hash(),
+            ErrorCode::InvalidVaa
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn posted(payload: &[u8]) -> Vec<u8> {
+        let mut data = MAGIC.to_vec();
+        data.extend_from_slice(&[1, 1]);
+        data.extend_from_slice(&1_700_000_000u32.to_le_bytes());
+        data.extend_from_slice(&[9; 32]);
+        data.extend_from_slice(&1_700_000_005u32.to_le_bytes());
+        data.extend_from_slice(&0u32.to_le_bytes());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&ETHEREUM_CHAIN.to_le_bytes());
+        data.extend_from_slice(&[5; 32]);
+        data.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        data.extend_from_slice(payload);
+        data
+    }
+
+    #[test]
+    fn parses_posted_vaas() {
+        let data = posted(&[7; 32]);
+        assert_eq!(
+            PostedVaa::from_bytes(&data),
+            Some(PostedVaa {
+                sequence: 42,
+                emitter_chain: ETHEREUM_CHAIN,
+                emitter_address: [5; 32],
+                payload: &[7; 32],
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_other_accounts() {
+        let mut data = posted(&[7; 32]);
+        data[0] = b'm';
+        assert_eq!(PostedVaa::from_bytes(&data), None);
+    }
+
+    #[test]
+    fn rejects_truncated_payloads() {
+        let data = posted(&[7; 32]);
+        assert_eq!(PostedVaa::from_bytes(&data[..data.len() - 1]), None);
+        assert_eq!(PostedVaa::from_bytes(&data[..HEADER_LEN - 1]), None);
+    }
+}
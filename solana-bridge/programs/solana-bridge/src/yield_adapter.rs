@@ -0,0 +1,87 @@
+//! Yield venue adapters, for idle cold-vault collateral
+//!
+//! The bridge speaks one small interface to every venue. An adapter is
+//! a program, allowlisted per token (`allow_yield_venue`), that wraps a
+//! venue such as a lending program and exposes
+//!
+//!   deposit(amount: u64)    move `amount` out of the staging account
+//!                           into the venue
+//!   withdraw(amount: u64)   move up to `amount` of the underlying back
+//!                           into the staging account (u64::MAX = all)
+//!
+//! with the accounts: staging token account (writable), venue authority
+//! (signer), mint, token program, then whatever the venue needs, passed
+//! through from the remaining accounts. The discriminators are Anchor's,
+//! so an adapter can be an ordinary Anchor program.
+//!
+//! The adapter never signs for the cold vault. Each venue has its own
+//! authority (PDA "yield_venue", mint, adapter) and staging account, so
+//! a faulty adapter can reach at most what was deployed to it. The
+//! bridge doesn't take the adapter's word for amounts either: it counts
+//! what left the cold vault on the way in and what arrived in staging on
+//! the way out.
+
+use anchor_lang::prelude::*;
+use anchor_lang::solana_program::instruction::{AccountMeta, Instruction};
+use anchor_lang::solana_program::program::invoke_signed;
+
+/// Anchor discriminators of the adapter interface
+const DEPOSIT: [u8; 8] = [242, 35, 198, 137, 82, 225, 242, 182];
+const WITHDRAW: [u8; 8] = [183, 18, 70, 156, 148, 109, 161, 34];
+
+/// The accounts every adapter call takes
+pub struct AdapterAccounts<'a, 'info> {
+    pub adapter: AccountInfo<'info>,
+    pub staging: AccountInfo<'info>,
+    pub venue_authority: AccountInfo<'info>,
+    pub mint: AccountInfo<'info>,
+    pub token_program: AccountInfo<'info>,
+    /// The venue's own accounts (remaining accounts)
+    pub venue_accounts: &'a [AccountInfo<'info>],
+}
+
+impl<'a, 'info> AdapterAccounts<'a, 'info> {
+    /// Have the adapter put `amount` from staging into its venue
+    pub fn deposit(self, amount: u64, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        self.call(DEPOSIT, amount, signer_seeds)
+    }
+
+    /// Have the adapter bring up to `amount` back into staging
+    pub fn withdraw(self, amount: u64, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        self.call(WITHDRAW, amount, signer_seeds)
+    }
+
+    fn call(self, discriminator: [u8; 8], amount: u64, signer_seeds: &[&[&[u8]]]) -> Result<()> {
+        let mut data = discriminator.to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+
+        // Venue accounts go through unsigned, whoever signed this
+        // instruction: only the venue authority signs for the adapter
+        let mut accounts = vec![
+            AccountMeta::new(*self.staging.key, false),
+            AccountMeta::new_readonly(*self.venue_authority.key, true),
+            AccountMeta::new_readonly(*self.mint.key, false),
+            AccountMeta::new_readonly(*self.token_program.key, false),
+        ];
+        accounts.extend(self.venue_accounts.iter().map(|info| AccountMeta {
+            pubkey: *info.key,
+            is_signer: false,
+            is_writable: info.is_writable,
+        }));
+        let mut infos = vec![
+            self.staging,
+            self.venue_authority,
+            self.mint,
+            self.token_program,
+        ];
+        infos.extend(self.venue_accounts.iter().cloned());
+
+        let ix = Instruction {
+            program_id: *self.adapter.key,
+            accounts,
+            data,
+        };
+        invoke_signed(&ix, &infos, signer_seeds)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,159 @@
+/**
+ * Relayer status API
+ *
+ *   GET /transfers/:id                 one transfer and its status history
+ *   GET /transfers?address=...         transfers sent or received by an address
+ *   GET /health                        chain connectivity (503 if either is down)
+ *   GET /stats                         transfer counts by status and direction
+ *   GET /quote?mint=&amount=&chain=    signed fee quote for a lock (see quote.js)
+ *   GET /metrics                       ack batching and submission metrics, Prometheus
+ *                                      text (batching.js, submission.js)
+ *   POST /smart-wallets                {initCode, deployData?}: init code to deploy
+ *                                      smart-wallet recipients with (smart-wallets.js)
+ *
+ * JSON, served from the relayer's transfer store. Lets a frontend show
+ * end-to-end progress without running an indexer, and pin the fee it
+ * shows before the user signs the lock. /metrics is the one route for
+ * scrapers rather than frontends. The one write only adds init code,
+ * which is checked against the lock's committed hash before use.
+ */
+
+import http from 'http';
+import { ethers } from 'ethers';
+
+// Give up on a chain health probe after this long (ms)
+const HEALTH_TIMEOUT = 3000;
+
+// Largest request body accepted (init code is the only one)
+const MAX_BODY = 64 * 1024;
+
+function send(res, status, body) {
+  res.writeHead(status, {
+    'Content-Type': 'application/json',
+    'Access-Control-Allow-Origin': '*',
+  });
+  res.end(JSON.stringify(body, (_, value) => (typeof value === 'bigint' ? value.toString() : value)));
+}
+
+function withTimeout(promise) {
+  return Promise.race([
+    promise,
+    new Promise((_, reject) => setTimeout(() => reject(new Error('timeout')), HEALTH_TIMEOUT)),
+  ]);
+}
+
+function readJson(req) {
+  return new Promise((resolve, reject) => {
+    let body = '';
+    req.on('data', (chunk) => {
+      body += chunk;
+      if (body.length > MAX_BODY) reject(new Error('body too large'));
+    });
+    req.on('end', () => {
+      try {
+        resolve(JSON.parse(body));
+      } catch (error) {
+        reject(error);
+      }
+    });
+    req.on('error', reject);
+  });
+}
+
+/**
+ * Start the API for `relayer` on `port`; returns the http.Server
+ */
+export function startApi(relayer, port, logger) {
+  const startedAt = Date.now();
+
+  const routes = {
+    async health() {
+      const [solana, ethereum] = await Promise.allSettled([
+        withTimeout(relayer.connection.getSlot()),
+        withTimeout(relayer.ethProvider.getBlockNumber()),
+      ]);
+      const healthy = solana.status === 'fulfilled' && ethereum.status === 'fulfilled';
+
+      return [healthy ? 200 : 503, {
+        status: healthy ? 'ok' : 'degraded',
+        uptimeSeconds: Math.floor((Date.now() - startedAt) / 1000),
+        solana: solana.status === 'fulfilled'
+          ? { ok: true, slot: solana.value }
+          : { ok: false, error: solana.reason.message },
+        ethereum: ethereum.status === 'fulfilled'
+          ? { ok: true, block: ethereum.value }
+          : { ok: false, error: ethereum.reason.message },
+      }];
+    },
+
+    async stats() {
+      return [200, {
+        ...relayer.transfers.stats(),
+        reorgedEvents: relayer.getReorgedEvents().length,
+      }];
+    },
+  };
+
+  const server = http.createServer(async (req, res) => {
+    const url = new URL(req.url, 'http://localhost');
+    const parts = url.pathname.split('/').filter(Boolean);
+
+    if (req.method === 'POST' && parts[0] === 'smart-wallets' && parts.length === 1) {
+      try {
+        const { initCode, deployData } = await readJson(req);
+        if (!ethers.isHexString(initCode) || initCode === '0x'
+          || (deployData !== undefined && !ethers.isHexString(deployData))) {
+          send(res, 400, { error: 'initCode (and deployData, if given) must be hex' });
+          return;
+        }
+        send(res, 200, { initCodeHash: relayer.smartWallets.register({ initCode, deployData }) });
+      } catch (error) {
+        send(res, 400, { error: error.message });
+      }
+      return;
+    }
+    if (req.method !== 'GET') {
+      send(res, 405, { error: 'Method not allowed' });
+      return;
+    }
+
+    try {
+      if (parts[0] === 'metrics' && parts.length === 1) {
+        res.writeHead(200, { 'Content-Type': 'text/plain; version=0.0.4' });
+        res.end(relayer.batcher.metrics() + relayer.submission.metrics());
+      } else if (parts.length === 1 && Object.hasOwn(routes, parts[0])) {
+        const [status, body] = await routes[parts[0]]();
+        send(res, status, body);
+      } else if (parts[0] === 'transfers' && parts.length === 2) {
+        const transfer = relayer.transfers.get(parts[1].replace(/^0x/, '').toLowerCase());
+        if (transfer) {
+          send(res, 200, transfer);
+        } else {
+          send(res, 404, { error: 'Transfer not found' });
+        }
+      } else if (parts[0] === 'transfers' && parts.length === 1) {
+        const address = url.searchParams.get('address');
+        if (!address) {
+          send(res, 400, { error: 'address query parameter is required' });
+          return;
+        }
+        send(res, 200, { transfers: relayer.transfers.findByAddress(address) });
+      } else if (parts[0] === 'quote' && parts.length === 1) {
+        const [mint, amount, chain] = ['mint', 'amount', 'chain'].map((name) => url.searchParams.get(name));
+        if (!mint || !/^\d+$/.test(amount ?? '') || !/^\d+$/.test(chain ?? '')) {
+          send(res, 400, { error: 'mint, amount and chain query parameters are required' });
+          return;
+        }
+        send(res, 200, await relayer.quoteFee(mint, BigInt(amount), BigInt(chain)));
+      } else {
+        send(res, 404, { error: 'Not found' });
+      }
+    } catch (error) {
+      logger.error('Status API error:', error);
+      send(res, 500, { error: 'Internal error' });
+    }
+  });
+
+  server.listen(port, () => logger.info(`Status API listening on :${port}`));
+  return server;
+}
@@ -0,0 +1,495 @@
+/**
+ * Guardian signature aggregation
+ *
+ * The program accepts a mint from any payer carrying a quorum of
+ * guardian signatures (GuardianSet::has_quorum). Guardians run relayer
+ * nodes in `attestor` mode; one relayer coordinates:
+ *
+ * 1. The coordinator sees a final Ethereum Lock and builds the mint
+ *    transaction with `threshold` guardians as extra signers
+ * 2. Each of those guardians' nodes checks the Lock against its own
+ *    Ethereum RPC, checks the message mints exactly that, and signs it
+ * 3. The coordinator adds the signatures and submits one transaction
+ *
+ * A node that fails to answer is benched for a while, and the next
+ * attempt names a different quorum.
+ *
+ * Metadata syncs (metadata-sync.js) go the same way, each node reading
+ * the ERC-20 itself, and so do reserve attestations (reserve-sync.js):
+ * a node vouches only for the escrow it knows, and re-reads the EVM
+ * bridge's locked total itself.
+ *
+ * Toward the EVM bridge the same nodes sign lock proofs (lock-proof.js)
+ * with their Ethereum signer: every live node is asked, and the mint
+ * goes ahead once enough of the contract's attesters agree.
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import { ComputeBudgetProgram, Message, PublicKey } from '@solana/web3.js';
+import { ASSOCIATED_TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { ethers } from 'ethers';
+import nacl from 'tweetnacl';
+import fs from 'fs';
+import path from 'path';
+import { fileURLToPath } from 'url';
+import { verifyLockProof } from './lock-proof.js';
+import { finalBlock, readErc20Metadata } from './metadata-sync.js';
+import { resolveSolanaRecipient } from './names.js';
+import { currentTransferId, STAGES, withTransfer } from './tracing.js';
+
+const PROTO_PATH = path.join(path.dirname(fileURLToPath(import.meta.url)), '../proto/attestation.proto');
+
+// Attestors wait for finality themselves, so give them time (ms)
+const ATTEST_TIMEOUT = 120000;
+const KEY_TIMEOUT = 5000;
+
+// How long a node that failed is skipped (ms)
+const BENCH_DURATION = 5 * 60 * 1000;
+
+// Besides the bridge program, what a message a guardian signs may call
+const ALLOWED_PROGRAMS = [ASSOCIATED_TOKEN_PROGRAM_ID, ComputeBudgetProgram.programId];
+
+async function loadAttestorService() {
+  const grpc = await import('@grpc/grpc-js');
+  const protoLoader = await import('@grpc/proto-loader');
+  const definition = protoLoader.loadSync(PROTO_PATH, { longs: String, bytes: Buffer });
+  return { grpc, Attestor: grpc.loadPackageDefinition(definition).bridge.attestation.v1.Attestor };
+}
+
+/**
+ * Collects guardian signatures from attestor nodes (coordinator side)
+ */
+export class AttestationCoordinator {
+  static async create(relayer, config, logger) {
+    const { grpc, Attestor } = await loadAttestorService();
+    const credentials = config.attestorCaPath
+      ? grpc.credentials.createSsl(fs.readFileSync(config.attestorCaPath))
+      : grpc.credentials.createInsecure();
+
+    const nodes = config.attestorUrls.map((url) => {
+      const client = new Attestor(url, credentials);
+      const call = (method, request, timeout) =>
+        new Promise((resolve, reject) =>
+          client[method](request, { deadline: Date.now() + timeout }, (error, response) =>
+            (error ? reject(error) : resolve(response))
+          )
+        );
+      return { url, call, guardian: null, benchedUntil: 0 };
+    });
+
+    const coordinator = new AttestationCoordinator(relayer, nodes, logger);
+    await coordinator.resolveKeys();
+    return coordinator;
+  }
+
+  constructor(relayer, nodes, logger) {
+    this.relayer = relayer;
+    this.nodes = nodes;
+    this.logger = logger;
+    this.round = 0;
+  }
+
+  /**
+   * Ask nodes we don't know the guardian key of yet
+   */
+  async resolveKeys() {
+    await Promise.all(
+      this.nodes
+        .filter((node) => !node.guardian)
+        .map(async (node) => {
+          try {
+            const { publicKey } = await node.call('GetPublicKey', {}, KEY_TIMEOUT);
+            node.guardian = new PublicKey(publicKey);
+            this.logger.info(`Attestor ${node.url}: guardian ${node.guardian.toString()}`);
+          } catch (error) {
+            this.logger.warn(`Attestor ${node.url} unreachable: ${error.message}`);
+          }
+        })
+    );
+  }
+
+  /**
+   * Pick `threshold` guardians of the on-chain set with a live node
+   *
+   * Rotates through the available nodes so no single one signs
+   * everything.
+   */
+  async selectGuardians() {
+    const program = this.relayer.program;
+    const [guardianSetPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('guardian_set')],
+      program.programId
+    );
+    const set = await program.account.guardianSet.fetch(guardianSetPda);
+    await this.resolveKeys();
+
+    const members = new Set(set.guardians.map((g) => g.toString()));
+    const now = Date.now();
+    const available = this.nodes.filter(
+      (node) => node.guardian && node.benchedUntil <= now && members.has(node.guardian.toString())
+    );
+    if (available.length < set.threshold) {
+      throw new Error(`Only ${available.length} attestors available, quorum is ${set.threshold}`);
+    }
+
+    const start = this.round++ % available.length;
+    return [...available.slice(start), ...available.slice(0, start)]
+      .slice(0, set.threshold)
+      .map((node) => node.guardian);
+  }
+
+  /**
+   * Add `guardians`' signatures to `tx` (already signed by the payer)
+   *
+   * `log` is the Ethereum Lock event the mint is for. Throws if any
+   * guardian didn't sign; those nodes are benched.
+   */
+  async collect(tx, guardians, log) {
+    const transferId = currentTransferId() ?? '';
+    await this.gather(tx, guardians, 'Attest', (message) => ({
+      message,
+      ethereumTxHash: log.transactionHash,
+      logIndex: log.index,
+      transferId,
+    }));
+  }
+
+  /**
+   * Add `guardians`' signatures to a `sync_metadata` transaction; the
+   * nodes read the ERC-20 at the block the instruction names
+   */
+  async collectMetadata(tx, guardians) {
+    await this.gather(tx, guardians, 'AttestMetadata', (message) => ({ message }));
+  }
+
+  /**
+   * Add `guardians`' signatures to an `attest_vault_balance` or
+   * `post_reserve_attestation` transaction
+   */
+  async collectReserve(tx, guardians) {
+    await this.gather(tx, guardians, 'AttestReserve', (message) => ({ message }));
+  }
+
+  async gather(tx, guardians, method, request) {
+    const message = tx.serializeMessage();
+
+    const results = await Promise.allSettled(
+      guardians.map(async (guardian) => {
+        const node = this.nodes.find((n) => n.guardian?.equals(guardian));
+        try {
+          const { signature } = await node.call(method, request(message), ATTEST_TIMEOUT);
+          if (!nacl.sign.detached.verify(message, signature, guardian.toBytes())) {
+            throw new Error('invalid signature');
+          }
+          tx.addSignature(guardian, Buffer.from(signature));
+        } catch (error) {
+          node.benchedUntil = Date.now() + BENCH_DURATION;
+          throw new Error(`${node.url}: ${error.message}`);
+        }
+      })
+    );
+
+    const failures = results.filter((r) => r.status === 'rejected').map((r) => r.reason.message);
+    if (failures.length > 0) {
+      throw new Error(`Guardian quorum incomplete (${failures.join('; ')})`);
+    }
+  }
+
+  /**
+   * Attester signatures over `proof` for the EVM bridge's mintWithProof
+   *
+   * Asks every live node and keeps the signatures of the contract's
+   * attesters, in ascending signer order as the contract requires.
+   * Throws below its attesterThreshold; nodes that fail are benched.
+   */
+  async collectLockProof(proof) {
+    const bridge = this.relayer.ethereumBridge;
+    const threshold = Number(await bridge.attesterThreshold());
+    if (threshold === 0) {
+      throw new Error('Lock proofs are disabled on the EVM bridge (no attesters)');
+    }
+
+    const { hash, ...request } = proof;
+    const now = Date.now();
+    const results = await Promise.allSettled(
+      this.nodes
+        .filter((node) => node.benchedUntil <= now)
+        .map(async (node) => {
+          try {
+            const { ethereumAddress, signature } =
+              await node.call('AttestLock', request, ATTEST_TIMEOUT);
+            const signer = ethers.verifyMessage(ethers.getBytes(hash), ethers.hexlify(signature));
+            if (signer !== ethers.getAddress(ethereumAddress)) {
+              throw new Error('invalid signature');
+            }
+            if (!(await bridge.isAttester(signer))) {
+              throw new Error(`${signer} is not an attester`);
+            }
+            return { signer, signature: ethers.hexlify(signature) };
+          } catch (error) {
+            node.benchedUntil = Date.now() + BENCH_DURATION;
+            throw new Error(`${node.url}: ${error.message}`);
+          }
+        })
+    );
+
+    const signed = results.filter((r) => r.status === 'fulfilled').map((r) => r.value);
+    if (signed.length < threshold) {
+      const failures = results.filter((r) => r.status === 'rejected').map((r) => r.reason.message);
+      throw new Error(
+        `Only ${signed.length} of ${threshold} attesters signed the lock proof (${failures.join('; ')})`
+      );
+    }
+    return signed
+      .sort((a, b) => (BigInt(a.signer) < BigInt(b.signer) ? -1 : 1))
+      .map(({ signature }) => signature);
+  }
+}
+
+/**
+ * The one bridge instruction in `message`, decoded
+ *
+ * Throws unless the message names this node's guardian as a read-only,
+ * non-paying signer and calls nothing but the bridge program and
+ * ALLOWED_PROGRAMS.
+ */
+function bridgeInstruction(relayer, message) {
+  const decoded = Message.from(message);
+  const guardian = relayer.wallet.publicKey;
+  const index = decoded.accountKeys.findIndex((key) => key.equals(guardian));
+  if (index < 0 || !decoded.isAccountSigner(index)) {
+    throw new Error('Message does not name this guardian as a signer');
+  }
+  if (index === 0 || decoded.isAccountWritable(index)) {
+    throw new Error('Guardian must be a read-only, non-paying signer');
+  }
+
+  const programId = relayer.program.programId;
+  const bridgeInstructions = decoded.instructions.filter((ix) => {
+    const program = decoded.accountKeys[ix.programIdIndex];
+    if (program.equals(programId)) return true;
+    if (!ALLOWED_PROGRAMS.some((allowed) => allowed.equals(program))) {
+      throw new Error(`Message calls unexpected program ${program.toString()}`);
+    }
+    return false;
+  });
+  if (bridgeInstructions.length !== 1) {
+    throw new Error('Message must contain exactly one bridge instruction');
+  }
+
+  const [ix] = bridgeInstructions;
+  const decodedIx = relayer.program.coder.instruction.decode(
+    Buffer.from(anchor.utils.bytes.bs58.decode(ix.data))
+  );
+  return { decoded, ix, decodedIx };
+}
+
+/**
+ * Check an attestation request and sign it with this node's guardian key
+ *
+ * Signs only a message that mints, through the bridge program, exactly
+ * the amount, nonce and recipient of a final Lock event on the bridge
+ * contract, and that can't spend the guardian's own funds. A .sol
+ * recipient is resolved here too, so a guardian never takes the
+ * relayer's word for who owns the domain.
+ */
+async function attest(relayer, { message, ethereumTxHash, logIndex }) {
+  const receipt = await relayer.ethProvider.getTransactionReceipt(ethereumTxHash);
+  const log = receipt?.logs.find(
+    (l) => l.index === logIndex
+      && l.address.toLowerCase() === relayer.config.ethereumBridgeAddress.toLowerCase()
+  );
+  const event = log && relayer.ethereumBridge.interface.parseLog(log);
+  if (event?.name !== 'Lock') {
+    throw new Error('Lock event not found');
+  }
+  const [, amount, nonce, solanaAddress] = event.args;
+  const recipient = await resolveSolanaRecipient(relayer.connection, solanaAddress);
+
+  const chainConfig = await relayer.getChainConfig(relayer.config.ethereumChainId);
+  if (!(await relayer.waitForEvmFinality(chainConfig, log))) {
+    throw new Error('Lock event was dropped by a reorg');
+  }
+
+  const { decoded, ix, decodedIx } = bridgeInstruction(relayer, message);
+  const user = decoded.accountKeys[ix.accounts[0]];
+  if (
+    decodedIx?.name !== 'mint'
+    || decodedIx.data.amount.toString() !== amount.toString()
+    || decodedIx.data.nonce.toString() !== nonce.toString()
+    || !user.equals(recipient.address)
+  ) {
+    throw new Error('Mint does not match the Lock event');
+  }
+
+  return Buffer.from(nacl.sign.detached(message, relayer.wallet.secretKey));
+}
+
+/**
+ * Check a `sync_metadata` message and sign it with this node's guardian key
+ *
+ * Reads the wrapped asset's ERC-20 at the instruction's block, which
+ * must be final, and signs only if name, symbol and decimals match it.
+ */
+async function attestMetadata(relayer, { message }) {
+  const { decoded, ix, decodedIx } = bridgeInstruction(relayer, message);
+  if (decodedIx?.name !== 'syncMetadata') {
+    throw new Error('Message is not a metadata sync');
+  }
+  const { sourceName, sourceSymbol, sourceDecimals, ethBlock } = decodedIx.data;
+  if (ethBlock.toNumber() > (await finalBlock(relayer))) {
+    throw new Error(`Block ${ethBlock.toString()} is not final yet`);
+  }
+
+  const wrappedAsset = decoded.accountKeys[ix.accounts[2]];
+  const asset = await relayer.program.account.wrappedAsset.fetch(wrappedAsset);
+  const erc20 = await readErc20Metadata(
+    relayer.ethProvider,
+    ethers.hexlify(Uint8Array.from(asset.sourceToken)),
+    ethBlock.toNumber()
+  );
+  if (
+    erc20.name !== sourceName
+    || erc20.symbol !== sourceSymbol
+    || erc20.decimals !== sourceDecimals
+  ) {
+    throw new Error('Metadata does not match the ERC-20');
+  }
+
+  return Buffer.from(nacl.sign.detached(message, relayer.wallet.secretKey));
+}
+
+/**
+ * Check a reserve attestation and sign it with this node's guardian key
+ *
+ * The program can't tell the escrow from another account of the bridge
+ * authority, so `attest_vault_balance` is signed only for this node's
+ * own SOLANA_ESCROW_ACCOUNT. `post_reserve_attestation` is signed only
+ * for SOLANA_WRAPPED_MINT at a final block, with the EVM bridge's
+ * locked total at that block.
+ */
+async function attestReserve(relayer, { message }) {
+  const { config } = relayer;
+  const { decoded, ix, decodedIx } = bridgeInstruction(relayer, message);
+  const account = (index) => decoded.accountKeys[ix.accounts[index]];
+
+  if (decodedIx?.name === 'attestVaultBalance') {
+    // mint and escrow, in AttestVaultBalance's order
+    if (
+      !config.solanaEscrowMint
+      || !config.solanaEscrowAccount
+      || !account(4).equals(new PublicKey(config.solanaEscrowMint))
+      || !account(7).equals(new PublicKey(config.solanaEscrowAccount))
+    ) {
+      throw new Error('Not the escrow this node knows');
+    }
+  } else if (decodedIx?.name === 'postReserveAttestation') {
+    const { ethLockedAmount, blockNumber } = decodedIx.data;
+    if (!config.solanaWrappedMint || !account(2).equals(new PublicKey(config.solanaWrappedMint))) {
+      throw new Error('Not the wrapped mint this node knows');
+    }
+    if (blockNumber.toNumber() > (await finalBlock(relayer))) {
+      throw new Error(`Block ${blockNumber.toString()} is not final yet`);
+    }
+    const locked = await relayer.ethereumBridge.totalLocked({ blockTag: blockNumber.toNumber() });
+    if (locked.toString() !== ethLockedAmount.toString()) {
+      throw new Error('Locked amount does not match the EVM bridge');
+    }
+  } else {
+    throw new Error('Message is not a reserve attestation');
+  }
+
+  return Buffer.from(nacl.sign.detached(message, relayer.wallet.secretKey));
+}
+
+/**
+ * Check a lock proof against this node's Solana RPC and sign it with
+ * its Ethereum signer
+ */
+async function attestLock(relayer, request) {
+  if (!relayer.ethSigner) {
+    throw new Error('This attestor does not sign lock proofs (LOCK_PROOFS is off)');
+  }
+  const proof = await verifyLockProof(relayer, request);
+  const signature = await relayer.ethSigner.signMessage(ethers.getBytes(proof.hash));
+  return {
+    ethereumAddress: await relayer.ethSigner.getAddress(),
+    signature: Buffer.from(ethers.getBytes(signature)),
+  };
+}
+
+/**
+ * Serve attestation requests with `relayer`'s Solana key as guardian key
+ */
+export async function startAttestor(relayer, config, logger) {
+  const { grpc, Attestor } = await loadAttestorService();
+  const publicKey = relayer.wallet.publicKey.toBuffer();
+
+  const server = new grpc.Server();
+  server.addService(Attestor.service, {
+    GetPublicKey: (_, callback) => callback(null, { publicKey }),
+    Attest: async (call, callback) => {
+      try {
+        const signature = await withTransfer(
+          call.request.transferId,
+          STAGES.ATTESTATION,
+          () => attest(relayer, call.request)
+        );
+        logger.info(`Attested ${call.request.ethereumTxHash}:${call.request.logIndex}`);
+        callback(null, { publicKey, signature });
+      } catch (error) {
+        logger.warn(`Refused attestation for ${call.request.ethereumTxHash}: ${error.message}`);
+        callback({ code: grpc.status.FAILED_PRECONDITION, message: error.message });
+      }
+    },
+    AttestMetadata: async (call, callback) => {
+      try {
+        const signature = await attestMetadata(relayer, call.request);
+        logger.info('Attested metadata sync');
+        callback(null, { publicKey, signature });
+      } catch (error) {
+        logger.warn(`Refused metadata sync: ${error.message}`);
+        callback({ code: grpc.status.FAILED_PRECONDITION, message: error.message });
+      }
+    },
+    AttestReserve: async (call, callback) => {
+      try {
+        const signature = await attestReserve(relayer, call.request);
+        logger.info('Attested reserve');
+        callback(null, { publicKey, signature });
+      } catch (error) {
+        logger.warn(`Refused reserve attestation: ${error.message}`);
+        callback({ code: grpc.status.FAILED_PRECONDITION, message: error.message });
+      }
+    },
+    AttestLock: async (call, callback) => {
+      try {
+        const response = await withTransfer(
+          call.request.transferId,
+          STAGES.ATTESTATION,
+          () => attestLock(relayer, call.request)
+        );
+        logger.info(`Attested lock ${call.request.transferId} in slot ${call.request.slot}`);
+        callback(null, response);
+      } catch (error) {
+        logger.warn(`Refused lock proof for ${call.request.transferId}: ${error.message}`);
+        callback({ code: grpc.status.FAILED_PRECONDITION, message: error.message });
+      }
+    },
+  });
+
+  const credentials = config.attestorCertPath
+    ? grpc.ServerCredentials.createSsl(null, [{
+      cert_chain: fs.readFileSync(config.attestorCertPath),
+      private_key: fs.readFileSync(config.attestorKeyPath),
+    }])
+    : grpc.ServerCredentials.createInsecure();
+
+  await new Promise((resolve, reject) =>
+    server.bindAsync(`0.0.0.0:${config.attestorPort}`, credentials, (error) =>
+      (error ? reject(error) : resolve())
+    )
+  );
+  logger.info(`Attestor listening on :${config.attestorPort}`);
+  return server;
+}
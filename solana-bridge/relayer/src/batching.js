@@ -0,0 +1,260 @@
+/**
+ * Adaptive batching of outbound acknowledgements
+ *
+ * Every delivered outbound transfer is acknowledged on Solana, either on
+ * its own with `ack_outbound` or as a delivered leg of `settle_batch`,
+ * which settles up to MAX_LOCK_BATCH transfers of one mint and one
+ * refund owner in a transaction. A batch shares the signature fee and
+ * instruction overhead, but holds acks back while legs gather and only
+ * takes escrow-mint locks without a deposit receipt (what lock_batch
+ * creates).
+ *
+ * Which is cheaper depends on how many legs actually gather, so the
+ * policy measures instead of guessing: after each ack it reads the fee
+ * and compute units of the confirmed transaction back and keeps a moving
+ * average per transfer for each path. Eligible acks wait for a batch
+ * while batching has cost less per transfer, and go out alone otherwise;
+ * every EXPLORE_EVERY-th eligible ack takes the other path so neither
+ * average goes stale. Destination gas per delivery is measured too. The
+ * EVM bridge mints one transfer per call, so there is no batch to weigh
+ * it against, but it is exported with the rest.
+ *
+ * Metrics are rendered in Prometheus text format for the status API's
+ * GET /metrics.
+ */
+
+import { PublicKey } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+
+// Legs per settle_batch (MAX_LOCK_BATCH in state.rs)
+export const MAX_BATCH_LEGS = 16;
+
+// Weight of the newest sample in the moving averages
+const SMOOTHING = 0.2;
+
+// Every this many eligible acks, take the path the averages didn't pick
+const EXPLORE_EVERY = 20;
+
+const PATHS = ['single', 'batch'];
+
+function average(previous, sample) {
+  return previous === null ? sample : previous + SMOOTHING * (sample - previous);
+}
+
+export default class AckBatcher {
+  constructor(relayer, config, logger) {
+    this.relayer = relayer;
+    this.logger = logger;
+    this.waitMs = config.ackBatchWaitSeconds * 1000;
+    this.maxLegs = Math.min(config.ackBatchMaxLegs, MAX_BATCH_LEGS);
+    this.escrowMint = config.solanaEscrowMint && new PublicKey(config.solanaEscrowMint);
+
+    // Pending batches by `${mint}:${refundOwner}`: { legs, timer }
+    this.pending = new Map();
+    this.eligibleSeen = 0;
+
+    const perPath = () => ({
+      lamports: null,
+      computeUnits: null,
+      transactions: 0,
+      transfers: 0,
+      decisions: 0,
+    });
+    this.stats = { single: perPath(), batch: perPath() };
+    this.destination = { gasUsed: null, costWei: null, deliveries: 0 };
+  }
+
+  /**
+   * Whether `record` can be acknowledged as a settle_batch leg
+   */
+  eligible(record) {
+    return this.waitMs > 0
+      && Boolean(this.escrowMint)
+      && record.escrowed
+      && !record.receipt
+      && record.mint.equals(this.escrowMint);
+  }
+
+  /**
+   * 'batch' or 'single' for the ack of `record`
+   */
+  choose(record) {
+    let path = 'single';
+    if (this.eligible(record)) {
+      const { single, batch } = this.stats;
+      const cheaper = batch.lamports === null
+        || single.lamports === null
+        || batch.lamports < single.lamports
+        || (batch.lamports === single.lamports && batch.computeUnits <= single.computeUnits);
+      const explore = ++this.eligibleSeen % EXPLORE_EVERY === 0;
+      path = cheaper !== explore ? 'batch' : 'single';
+    }
+    this.stats[path].decisions += 1;
+    return path;
+  }
+
+  /**
+   * Queue the ack of `nonce` (a BN) for a batch; a no-op if it's queued
+   */
+  enqueue(nonce, transferRecord, record, destTx) {
+    const refundOwner = record.recovery.equals(PublicKey.default)
+      ? record.localAccount
+      : record.recovery;
+    const key = `${record.mint.toString()}:${refundOwner.toString()}`;
+
+    let batch = this.pending.get(key);
+    if (batch?.legs.some((leg) => leg.nonce.eq(nonce))) return;
+    if (!batch) {
+      batch = { mint: record.mint, refundOwner, legs: [] };
+      batch.timer = setTimeout(() => this.flush(key), this.waitMs);
+      this.pending.set(key, batch);
+    }
+    batch.legs.push({ nonce, transferRecord, destTx });
+    if (batch.legs.length >= this.maxLegs) this.flush(key);
+  }
+
+  /**
+   * Settle a pending batch; legs of a batch that fails are acked alone
+   */
+  async flush(key) {
+    const batch = this.pending.get(key);
+    if (!batch) return;
+    this.pending.delete(key);
+    clearTimeout(batch.timer);
+
+    const { program, connection, wallet, config } = this.relayer;
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const { mint, refundOwner, legs } = batch;
+    try {
+      const mintInfo = await connection.getAccountInfo(mint);
+      const tokenProgram = mintInfo ? mintInfo.owner : TOKEN_PROGRAM_ID;
+      const tokenConfig = pda(Buffer.from('token'), mint.toBuffer());
+      const { accounting } = await program.account.tokenConfig.fetch(tokenConfig);
+
+      const outcomes = legs.map(({ nonce, destTx }) => ({
+        nonce,
+        delivered: true,
+        destTx: [...(destTx
+          ? Buffer.from(destTx.replace(/^0x/, ''), 'hex')
+          : Buffer.alloc(32))],
+      }));
+      const signature = await program.methods
+        .settleBatch(outcomes)
+        .accounts({
+          authority: wallet.publicKey,
+          bridgeState: pda(Buffer.from('bridge_state')),
+          throttle: pda(Buffer.from('throttle')),
+          slaConfig: pda(Buffer.from('sla')),
+          mint,
+          tokenConfig,
+          conversionRate: 'shares' in accounting
+            ? pda(Buffer.from('conversion_rate'), mint.toBuffer())
+            : null,
+          refundToken: getAssociatedTokenAddressSync(mint, refundOwner, true, tokenProgram),
+          bridgeToken: new PublicKey(config.solanaEscrowAccount),
+          bridgeAuthority: pda(Buffer.from('bridge')),
+          tokenProgram,
+        })
+        .remainingAccounts(legs.map(({ transferRecord }) => ({
+          pubkey: transferRecord,
+          isSigner: false,
+          isWritable: true,
+        })))
+        .rpc();
+      this.logger.info(`Acknowledged ${legs.length} outbound transfers in a batch: ${signature}`);
+      this.observe('batch', signature, legs.length);
+    } catch (error) {
+      this.logger.warn(`Batched ack of ${legs.length} transfers failed: ${error.message}`);
+      for (const { nonce, destTx } of legs) {
+        await this.relayer.ackOutbound(nonce, destTx, { single: true });
+      }
+      return;
+    }
+    for (const { nonce } of legs) {
+      await this.relayer.collectPriorityTip(nonce);
+    }
+  }
+
+  /**
+   * Record what the ack transaction `signature` cost, over `transfers`
+   *
+   * Best effort and in the background: the ack is already confirmed.
+   */
+  observe(path, signature, transfers) {
+    const stats = this.stats[path];
+    stats.transactions += 1;
+    stats.transfers += transfers;
+
+    this.relayer.connection
+      .getTransaction(signature, { commitment: 'confirmed', maxSupportedTransactionVersion: 0 })
+      .then((tx) => {
+        if (!tx?.meta) return;
+        const { fee, computeUnitsConsumed } = tx.meta;
+        stats.lamports = average(stats.lamports, fee / transfers);
+        if (computeUnitsConsumed !== undefined) {
+          stats.computeUnits = average(stats.computeUnits, computeUnitsConsumed / transfers);
+        }
+      })
+      .catch((error) => {
+        this.logger.warn(`Could not read the cost of ack ${signature}: ${error.message}`);
+      });
+  }
+
+  /**
+   * Record the gas a delivery on the EVM side took, from its receipt
+   */
+  observeDestination(receipt) {
+    if (!receipt) return;
+    const { destination } = this;
+    destination.deliveries += 1;
+    destination.gasUsed = average(destination.gasUsed, Number(receipt.gasUsed));
+    destination.costWei = average(
+      destination.costWei,
+      Number(receipt.gasUsed * (receipt.gasPrice ?? 0n))
+    );
+  }
+
+  /**
+   * Settle every pending batch now, e.g. before shutting down
+   */
+  async drain() {
+    await Promise.all([...this.pending.keys()].map((key) => this.flush(key)));
+  }
+
+  /**
+   * The policy's measurements and decisions in Prometheus text format
+   */
+  metrics() {
+    const lines = [];
+    const metric = (name, type, help, samples) => {
+      lines.push(`# HELP ${name} ${help}`, `# TYPE ${name} ${type}`);
+      for (const [labels, value] of samples) {
+        if (value === null) continue;
+        lines.push(`${name}${labels} ${value}`);
+      }
+    };
+    const byPath = (field) => PATHS.map((path) => [`{path="${path}"}`, this.stats[path][field]]);
+
+    metric('relayer_ack_cost_lamports', 'gauge',
+      'Moving average of the Solana fee per acknowledged transfer', byPath('lamports'));
+    metric('relayer_ack_compute_units', 'gauge',
+      'Moving average of compute units per acknowledged transfer', byPath('computeUnits'));
+    metric('relayer_ack_transactions_total', 'counter',
+      'Ack transactions sent', byPath('transactions'));
+    metric('relayer_ack_transfers_total', 'counter',
+      'Transfers acknowledged', byPath('transfers'));
+    metric('relayer_ack_decisions_total', 'counter',
+      'Acks the policy sent down each path', byPath('decisions'));
+    metric('relayer_ack_batch_pending_legs', 'gauge',
+      'Acks waiting for a batch', [['', [...this.pending.values()]
+        .reduce((sum, batch) => sum + batch.legs.length, 0)]]);
+    metric('relayer_destination_gas_used', 'gauge',
+      'Moving average of gas per delivery on the EVM side', [['', this.destination.gasUsed]]);
+    metric('relayer_destination_cost_wei', 'gauge',
+      'Moving average of wei paid per delivery on the EVM side', [['', this.destination.costWei]]);
+    metric('relayer_destination_deliveries_total', 'counter',
+      'Deliveries measured on the EVM side', [['', this.destination.deliveries]]);
+
+    return `${lines.join('\n')}\n`;
+  }
+}
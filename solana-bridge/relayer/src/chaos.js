@@ -0,0 +1,162 @@
+/**
+ * Fault injection for testing the relayer (never set in production)
+ *
+ *   CHAOS=drop=0.2,duplicate=0.3,delay=0.3,rpc=0.05 CHAOS_SEED=7
+ *
+ * Each rate is the chance a fault is injected where it applies:
+ *
+ *   drop       a listener ignores an Ethereum event or outbox entry
+ *   duplicate  a listener hands an event over twice at once, so both
+ *              copies race each other to the destination
+ *   delay      a Solana confirmation comes back late, as an expired
+ *              blockhash, although the transaction may have landed
+ *   rpc        a Solana or Ethereum RPC call fails before it is sent
+ *
+ * None of these may lose a transfer or deliver one twice: replay
+ * protection on both chains rejects the duplicates, and reconciliation
+ * (SolanaRelayer.reconcile) relays whatever was dropped or failed.
+ * test-harness/tests/chaos.rs checks both against real chains. The
+ * seed is logged, so a failing run can be replayed.
+ */
+
+import { TransactionExpiredBlockheightExceededError } from '@solana/web3.js';
+
+const FAULTS = ['drop', 'duplicate', 'delay', 'rpc'];
+
+// Connection methods that may fail; the rest pass through untouched
+const SOLANA_METHODS = new Set([
+  'getAccountInfo',
+  'getAccountInfoAndContext',
+  'getMultipleAccountsInfo',
+  'getLatestBlockhash',
+  'sendRawTransaction',
+  'getSignaturesForAddress',
+  'getTransaction',
+]);
+
+// ethers needs these to come up at all
+const ETHEREUM_PASSTHROUGH = new Set(['eth_chainId', 'net_version']);
+
+// How late a delayed confirmation is (ms)
+const DELAY_MIN = 2000;
+const DELAY_MAX = 8000;
+
+/**
+ * Rates from a CHAOS spec; null if it is empty
+ */
+export function parseChaos(spec) {
+  if (!spec) return null;
+
+  const rates = Object.fromEntries(FAULTS.map((fault) => [fault, 0]));
+  for (const part of spec.split(',').filter(Boolean)) {
+    const [fault, value] = part.split('=');
+    const rate = Number(value);
+    if (!FAULTS.includes(fault) || !(rate >= 0 && rate <= 1)) {
+      throw new Error(`Bad CHAOS entry "${part}"; expected ${FAULTS.join('|')}=<0..1>`);
+    }
+    rates[fault] = rate;
+  }
+  return rates;
+}
+
+// mulberry32: small, seedable, good enough for coin flips
+function seededRandom(seed) {
+  let state = seed >>> 0;
+  return () => {
+    state = (state + 0x6d2b79f5) >>> 0;
+    let t = state;
+    t = Math.imul(t ^ (t >>> 15), t | 1);
+    t ^= t + Math.imul(t ^ (t >>> 7), t | 61);
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+}
+
+const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+
+class Chaos {
+  constructor(rates, seed, logger) {
+    this.rates = rates;
+    this.random = seededRandom(seed);
+    this.logger = logger;
+
+    // Faults injected so far, by kind
+    this.injected = Object.fromEntries(FAULTS.map((fault) => [fault, 0]));
+
+    logger.warn(`Chaos mode on: ${JSON.stringify(rates)}, seed ${seed}`);
+  }
+
+  /**
+   * Roll for `fault`; a hit is counted and logged against `what`
+   */
+  roll(fault, what) {
+    if (this.random() >= this.rates[fault]) return false;
+    this.injected[fault]++;
+    this.logger.warn(`chaos: ${fault} ${what}`);
+    return true;
+  }
+
+  /**
+   * Hand an event to `handler` the way a faulty listener would: not at
+   * all, once, or twice at the same time
+   */
+  async deliver(what, handler) {
+    if (this.roll('drop', what)) return;
+    if (this.roll('duplicate', what)) {
+      await Promise.all([handler(), handler()]);
+    } else {
+      await handler();
+    }
+  }
+
+  /**
+   * `connection`, with failing RPCs and late confirmations
+   *
+   * A proxy rather than a subclass, so everything built on it (the
+   * Anchor provider, name resolution) sees the same faults.
+   */
+  wrapConnection(connection) {
+    const chaos = this;
+    return new Proxy(connection, {
+      get(target, prop) {
+        const value = Reflect.get(target, prop, target);
+        if (typeof value !== 'function') return value;
+
+        if (prop === 'confirmTransaction') {
+          return async (strategy, commitment) => {
+            if (chaos.roll('delay', `confirmation of ${strategy.signature}`)) {
+              await sleep(DELAY_MIN + chaos.random() * (DELAY_MAX - DELAY_MIN));
+              throw new TransactionExpiredBlockheightExceededError(strategy.signature);
+            }
+            return value.call(target, strategy, commitment);
+          };
+        }
+        if (SOLANA_METHODS.has(prop)) {
+          return async (...args) => {
+            if (chaos.roll('rpc', `Solana ${prop}`)) {
+              throw new Error(`chaos: injected ${prop} failure`);
+            }
+            return value.apply(target, args);
+          };
+        }
+        return value.bind(target);
+      },
+    });
+  }
+
+  /**
+   * Make `provider`'s JSON-RPC calls fail; every ethers request,
+   * including event polling and broadcasts, goes through send()
+   */
+  wrapProvider(provider) {
+    const send = provider.send.bind(provider);
+    provider.send = async (method, params) => {
+      if (!ETHEREUM_PASSTHROUGH.has(method) && this.roll('rpc', `Ethereum ${method}`)) {
+        throw new Error(`chaos: injected ${method} failure`);
+      }
+      return send(method, params);
+    };
+    return provider;
+  }
+}
+
+export default Chaos;
@@ -0,0 +1,160 @@
+/**
+ * EVM encoding, mirroring bridge-core's codec module
+ *
+ * The hashes here must be byte-identical to core/src/codec.rs (which the
+ * program, SDK and CLI all use) and to what the EVM bridge computes with
+ * abi.encodePacked; the Rust tests pin the same vectors. Add a hash here
+ * only together with its Rust twin.
+ */
+
+import { ethers } from 'ethers';
+import { PublicKey } from '@solana/web3.js';
+
+const bytes32 = (value) =>
+  value instanceof PublicKey ? value.toBuffer() : ethers.getBytes(value);
+
+/**
+ * Message hash of an inbound transfer
+ *
+ *   keccak256(abi.encodePacked(bytes32 recipient, uint256 amount, uint256 nonce))
+ */
+export function transferMessageHash(recipient, amount, nonce) {
+  return ethers.solidityPackedKeccak256(
+    ['bytes32', 'uint256', 'uint256'],
+    [bytes32(recipient), amount, nonce]
+  );
+}
+
+/**
+ * What inbound evidence (a VAA payload, a proof's public inputs) commits to
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, uint32 guardianSetIndex,
+ *     bytes32 token, bytes32 recipient, uint256 amount, uint256 nonce))
+ */
+export function inboundMessageHash(sourceChainId, guardianSetIndex, token, recipient, amount, nonce) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'uint32', 'bytes32', 'bytes32', 'uint256', 'uint256'],
+    [sourceChainId, guardianSetIndex, bytes32(token), bytes32(recipient), amount, nonce]
+  );
+}
+
+/**
+ * What the relayer signs to mint an outbound transfer on the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(address to, uint256 amount, bytes32 transferId))
+ */
+export function mintRequestHash(to, amount, transferId) {
+  return ethers.solidityPackedKeccak256(
+    ['address', 'uint256', 'bytes32'],
+    [to, amount, bytes32(transferId)]
+  );
+}
+
+/**
+ * What the relayer signs to carry a pause or unpause to the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bool paused, uint64 sequence))
+ */
+export function bridgeStatusHash(sourceChainId, paused, sequence) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'bool', 'uint64'],
+    [sourceChainId, paused, sequence]
+  );
+}
+
+/**
+ * What the relayer signs to carry a Solana escrow balance to the EVM bridge
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 mint,
+ *     uint256 amount, uint64 slot, uint64 sequence))
+ */
+export function vaultBalanceHash(sourceChainId, mint, amount, slot, sequence) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'bytes32', 'uint256', 'uint64', 'uint64'],
+    [sourceChainId, bytes32(mint), amount, slot, sequence]
+  );
+}
+
+/**
+ * What the relayer signs to deliver a message to a contract on another chain
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 sender,
+ *     uint64 destChainId, bytes32 target, uint64 sequence, bytes payload))
+ */
+export function messageHash(sourceChainId, sender, destChainId, target, sequence, payload) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'bytes32', 'uint64', 'bytes32', 'uint64', 'bytes'],
+    [sourceChainId, bytes32(sender), destChainId, bytes32(target), sequence, payload]
+  );
+}
+
+/**
+ * What an attestor signs to vouch for a finalized Solana lock
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, bytes32 transferId,
+ *     address to, uint256 amount, uint64 slot, bytes32 blockhash,
+ *     bytes txSignature))
+ */
+export function lockProofHash(sourceChainId, transferId, to, amount, slot, blockhash, txSignature) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'bytes32', 'address', 'uint256', 'uint64', 'bytes32', 'bytes'],
+    [sourceChainId, bytes32(transferId), to, amount, slot, bytes32(blockhash), txSignature]
+  );
+}
+
+/**
+ * ID of an outbound transfer (the program's `transfer_id`)
+ *
+ *   keccak256(abi.encodePacked(uint64 sourceChainId, uint64 destChainId,
+ *     uint32 guardianSetIndex, uint64 nonce, bytes32 token, uint64 amount,
+ *     bytes32 sender, string recipient))
+ */
+export function transferId(sourceChainId, destChainId, guardianSetIndex, nonce, token, amount, sender, recipient) {
+  return ethers.solidityPackedKeccak256(
+    ['uint64', 'uint64', 'uint32', 'uint64', 'bytes32', 'uint64', 'bytes32', 'string'],
+    [sourceChainId, destChainId, guardianSetIndex, nonce, bytes32(token), amount, bytes32(sender), recipient]
+  );
+}
+
+const quantity = (hex) => (hex === '0x' ? 0n : BigInt(hex));
+
+/**
+ * Decode a receipt as stored in the receipts trie (typed or legacy)
+ */
+export function decodeReceipt(raw) {
+  const bytes = ethers.getBytes(raw);
+  const typed = bytes.length > 0 && bytes[0] <= 0x7f;
+  const [status, cumulativeGasUsed, , logs] = ethers.decodeRlp(typed ? bytes.slice(1) : bytes);
+  if (ethers.dataLength(status) > 1) {
+    throw new Error('Pre-Byzantium receipts have no status');
+  }
+  return {
+    txType: typed ? bytes[0] : 0,
+    success: quantity(status) === 1n,
+    cumulativeGasUsed: quantity(cumulativeGasUsed),
+    logs: logs.map(([address, topics, data]) => ({
+      address: ethers.getAddress(address),
+      topics,
+      data,
+    })),
+  };
+}
+
+/**
+ * Decode the block header fields a proof checks against
+ */
+export function decodeHeader(raw) {
+  const fields = ethers.decodeRlp(raw);
+  if (fields.length < 15) {
+    throw new Error('Not a block header');
+  }
+  return {
+    hash: ethers.keccak256(raw),
+    parentHash: fields[0],
+    stateRoot: fields[3],
+    transactionsRoot: fields[4],
+    receiptsRoot: fields[5],
+    number: quantity(fields[8]),
+    timestamp: quantity(fields[11]),
+  };
+}
@@ -0,0 +1,127 @@
+/**
+ * Operator commands: the escape hatches for transfers the running
+ * relayer can't finish on its own
+ *
+ *   attest <eth-tx-hash> [--priority-fee <micro-lamports>]
+ *       Deliver the Lock and Burn events of an Ethereum transaction now,
+ *       skipping the relayer's finality wait. Only after checking the
+ *       transaction by hand; guardian attestors still check it themselves.
+ *   resubmit <transfer-id> --priority-fee <micro-lamports>
+ *       Rebuild a stuck inbound delivery and send it with a priority fee
+ *   resolve <transfer-id> <note...>
+ *       Mark a transfer settled by hand, so reconciliation and alerts
+ *       leave it alone
+ *   prove <solana-tx-signature>
+ *       Print the lock proofs (see lock-proof.js) of a finalized Solana
+ *       transaction's EVM-bound locks as JSON
+ *
+ * Run as `node src/index.js <command> ...` with the relayer's usual
+ * environment. Each command updates the transfer store file; the
+ * running relayer only reads it at startup and would overwrite the
+ * change with its next snapshot, so stop it first.
+ */
+
+import { AttestationCoordinator } from './attestation.js';
+import { buildLockProofs } from './lock-proof.js';
+
+export const COMMANDS = ['attest', 'resubmit', 'resolve', 'prove'];
+
+const USAGE = `Usage:
+  attest <eth-tx-hash> [--priority-fee <micro-lamports>]
+  resubmit <transfer-id> --priority-fee <micro-lamports>
+  resolve <transfer-id> <note...>
+  prove <solana-tx-signature>`;
+
+/**
+ * Split `args` into positionals and the value of --priority-fee
+ */
+function parseArgs(args) {
+  const positional = [];
+  let priorityFee;
+  for (let i = 0; i < args.length; i++) {
+    if (args[i] === '--priority-fee') {
+      priorityFee = Number(args[++i]);
+      if (!Number.isSafeInteger(priorityFee) || priorityFee <= 0) {
+        throw new Error('--priority-fee must be a positive integer (micro-lamports per CU)');
+      }
+    } else {
+      positional.push(args[i]);
+    }
+  }
+  return { positional, priorityFee };
+}
+
+/**
+ * Transfer IDs are stored as bare hex; accept them 0x-prefixed too
+ */
+const transferIdArg = (value) => value?.replace(/^0x/, '').toLowerCase();
+
+/**
+ * Connect what delivering on Solana needs: the program, attestors for
+ * guardian-quorum mints, and the Ethereum signer for acks
+ */
+async function prepareDelivery(relayer) {
+  const { config } = relayer;
+  relayer.loadProgram();
+  if (!relayer.program) {
+    throw new Error(`Delivering needs the program IDL (SOLANA_IDL_PATH=${config.solanaIdlPath})`);
+  }
+  if (config.attestorUrls.length > 0) {
+    relayer.coordinator = await AttestationCoordinator.create(relayer, config, console);
+  }
+  if (config.ethereumAcks) {
+    await relayer.initEthereumSigner();
+  }
+}
+
+/**
+ * Run `command` with `args` against `relayer`; throws on bad usage
+ */
+export async function runCommand(relayer, command, args) {
+  const { positional, priorityFee } = parseArgs(args);
+
+  switch (command) {
+    case 'attest': {
+      const [txHash] = positional;
+      if (!/^0x[0-9a-fA-F]{64}$/.test(txHash ?? '')) throw new Error(USAGE);
+      await prepareDelivery(relayer);
+      const transferIds = await relayer.attestSourceTx(txHash, { priorityFee });
+      console.log(`Delivered ${transferIds.length} transfer(s) from ${txHash}: ${transferIds.join(', ')}`);
+      break;
+    }
+
+    case 'resubmit': {
+      const transferId = transferIdArg(positional[0]);
+      if (!transferId || !priorityFee) throw new Error(USAGE);
+      await prepareDelivery(relayer);
+      await relayer.resubmitInbound(transferId, priorityFee);
+      console.log(`Resubmitted ${transferId} at ${priorityFee} micro-lamports per CU`);
+      break;
+    }
+
+    case 'resolve': {
+      const [id, ...words] = positional;
+      const transferId = transferIdArg(id);
+      if (!transferId || words.length === 0) throw new Error(USAGE);
+      const transfer = relayer.resolveTransfer(transferId, words.join(' '));
+      console.log(`Marked ${transferId} resolved: ${transfer.resolution.note}`);
+      break;
+    }
+
+    case 'prove': {
+      const [signature] = positional;
+      if (!signature) throw new Error(USAGE);
+      relayer.loadProgram();
+      if (!relayer.program) {
+        const { solanaIdlPath } = relayer.config;
+        throw new Error(`Proving needs the program IDL (SOLANA_IDL_PATH=${solanaIdlPath})`);
+      }
+      const proofs = await buildLockProofs(relayer, signature);
+      console.log(JSON.stringify(proofs, null, 2));
+      break;
+    }
+
+    default:
+      throw new Error(USAGE);
+  }
+}
@@ -0,0 +1,98 @@
+/**
+ * Deposit address sweeps
+ *
+ * Users register a destination for their deposit address (PDA
+ * ["deposit", user]) and then simply send tokens to it, exchange-style.
+ * Every DEPOSIT_SWEEP_INTERVAL_SECONDS this crank looks for deposit
+ * addresses holding the escrow mint and locks each balance to its
+ * registered destination with `sweep_deposit`. The program does the
+ * checks and charges the user's fee; the relayer only pays the transfer
+ * record's rent. The record is addressed by the next nonce of the
+ * mint's route sequence to the destination. Other tokens are left for
+ * the user to withdraw.
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import { PublicKey } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID, getAssociatedTokenAddressSync } from '@solana/spl-token';
+
+// Must match SEQUENCE_BITS in the program: a route's shard sits above them
+const SEQUENCE_BITS = 40;
+
+/**
+ * Sweep every deposit address holding the escrow mint
+ */
+async function sweepAll(relayer, logger) {
+  const { program, connection, config } = relayer;
+  const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const mint = new PublicKey(config.solanaEscrowMint);
+  const mintInfo = await connection.getAccountInfo(mint);
+  const tokenProgram = mintInfo ? mintInfo.owner : TOKEN_PROGRAM_ID;
+  const tokenConfig = pda(Buffer.from('token'), mint.toBuffer());
+  const { accounting } = await program.account.tokenConfig.fetch(tokenConfig);
+
+  const deposits = await program.account.depositAddress.all();
+  for (const { publicKey: depositAddress, account: deposit } of deposits) {
+    const depositToken = getAssociatedTokenAddressSync(mint, depositAddress, true, tokenProgram);
+    try {
+      const balance = await connection.getTokenAccountBalance(depositToken).catch(() => null);
+      if (!balance || balance.value.amount === '0') continue;
+
+      const chain = deposit.destChainId.toArrayLike(Buffer, 'le', 8);
+      const routeSequence = pda(Buffer.from('sequence'), mint.toBuffer(), chain);
+      const route = await program.account.routeSequence.fetch(routeSequence);
+      const nonce = new anchor.BN(route.shard).shln(SEQUENCE_BITS).add(route.last.addn(1));
+
+      const signature = await program.methods
+        .sweepDeposit(deposit.user)
+        .accounts({
+          cranker: relayer.wallet.publicKey,
+          bridgeState: pda(Buffer.from('bridge_state')),
+          depositAddress,
+          depositToken,
+          mint,
+          tokenConfig,
+          routeSequence,
+          transferRecord: pda(
+            Buffer.from('transfer'),
+            Buffer.from('out'),
+            nonce.toArrayLike(Buffer, 'le', 8)
+          ),
+          credential: null,
+          conversionRate: 'shares' in accounting
+            ? pda(Buffer.from('conversion_rate'), mint.toBuffer())
+            : null,
+          bridgeToken: new PublicKey(config.solanaEscrowAccount),
+          feeVault: new PublicKey(config.solanaFeeVault),
+          tokenProgram,
+        })
+        .rpc();
+      logger.info(
+        `Swept ${balance.value.amount} from ${deposit.user.toString()}'s deposit address: ${signature}`
+      );
+    } catch (error) {
+      logger.warn(`Sweep of ${deposit.user.toString()}'s deposit address failed: ${error.message}`);
+    }
+  }
+}
+
+/**
+ * Sweep deposit addresses every DEPOSIT_SWEEP_INTERVAL_SECONDS; returns
+ * the timer
+ */
+export function startDepositSweep(relayer, config, logger) {
+  let running = false;
+
+  return setInterval(async () => {
+    if (running) return;
+    running = true;
+
+    try {
+      await sweepAll(relayer, logger);
+    } catch (error) {
+      logger.error('Deposit sweep failed:', error);
+    } finally {
+      running = false;
+    }
+  }, config.depositSweepIntervalSeconds * 1000);
+}
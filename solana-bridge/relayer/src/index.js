@@ -1,23 +1,192 @@
 /**
  * Solana Bridge Relayer Entry Point
+ *
+ * With no arguments, runs the relayer. With one of the operator
+ * commands in commands.js (attest, resubmit, resolve), runs that once
+ * with the same configuration and exits.
  */
 
 import dotenv from 'dotenv';
 import SolanaRelayer from './solana-relayer.js';
+import { COMMANDS, runCommand } from './commands.js';
+import { initTracing } from './tracing.js';
+import { applyProfile } from './profiles.js';
 
 dotenv.config();
 
+// A built-in route profile (profiles.js) fills in what .env leaves unset
+try {
+  applyProfile(process.env.BRIDGE_PROFILE);
+} catch (error) {
+  console.error(`Error: ${error.message}`);
+  process.exit(1);
+}
+
 const config = {
   // Solana configuration
   solanaRpcUrl: process.env.SOLANA_RPC_URL || 'http://127.0.0.1:8899',
   solanaProgramId: process.env.SOLANA_PROGRAM_ID,
   solanaKeypairPath: process.env.SOLANA_KEYPAIR_PATH ||
     `${process.env.HOME}/.config/solana/id.json`,
+  solanaIdlPath: process.env.SOLANA_IDL_PATH || '../target/idl/solana_bridge.json',
+  // Inbound: Ethereum locks mint SOLANA_WRAPPED_MINT; burns release
+  // SOLANA_ESCROW_MINT out of SOLANA_ESCROW_ACCOUNT
+  solanaWrappedMint: process.env.SOLANA_WRAPPED_MINT,
+  solanaEscrowMint: process.env.SOLANA_ESCROW_MINT,
+  solanaEscrowAccount: process.env.SOLANA_ESCROW_ACCOUNT,
+  solanaSubmitRetries: parseInt(process.env.SOLANA_SUBMIT_RETRIES || '5'),
+  // Compute-unit price of inbound deliveries (micro-lamports; see
+  // submission.js): a percentile of recent fees, multiplied per retry
+  priorityFeePercentile: parseInt(process.env.PRIORITY_FEE_PERCENTILE || '75'),
+  priorityFeeMin: parseInt(process.env.PRIORITY_FEE_MIN || '0'),
+  priorityFeeMax: parseInt(process.env.PRIORITY_FEE_MAX || '1000000'),
+  priorityFeeEscalation: parseFloat(process.env.PRIORITY_FEE_ESCALATION || '2'),
+  // Test clusters only: airdrop this much SOL when the relayer runs low
+  solanaAirdropSol: parseFloat(process.env.SOLANA_AIRDROP_SOL || '0'),
+  // Report each delivery's cost on-chain for gas reimbursement
+  reportGas: process.env.REPORT_GAS === 'true',
 
   // Ethereum configuration
   ethereumRpcUrl: process.env.ETHEREUM_RPC_URL || 'http://127.0.0.1:8545',
   ethereumBridgeAddress: process.env.ETHEREUM_BRIDGE_ADDRESS,
+  // Where the attestation key lives: local | remote | kms | yubihsm
+  ethereumSigner: process.env.ETHEREUM_SIGNER || 'local',
   ethereumPrivateKey: process.env.ETHEREUM_PRIVATE_KEY,
+  ethereumKeystorePath: process.env.ETHEREUM_KEYSTORE_PATH,
+  ethereumKeystorePassword: process.env.ETHEREUM_KEYSTORE_PASSWORD,
+  remoteSignerUrl: process.env.REMOTE_SIGNER_URL,
+  remoteSignerKeyId: process.env.REMOTE_SIGNER_KEY_ID,
+  remoteSignerCaPath: process.env.REMOTE_SIGNER_CA_PATH,
+  awsRegion: process.env.AWS_REGION,
+  kmsKeyId: process.env.KMS_KEY_ID,
+  yubihsmPkcs11Lib: process.env.YUBIHSM_PKCS11_LIB || '/usr/lib/x86_64-linux-gnu/pkcs11/yubihsm_pkcs11.so',
+  yubihsmPin: process.env.YUBIHSM_PIN,
+  yubihsmKeyLabel: process.env.YUBIHSM_KEY_LABEL,
+  // Chain registry id of the EVM chain ETHEREUM_RPC_URL points at
+  // (1 = Ethereum, 10 = Optimism, 8453 = Base, 42161 = Arbitrum)
+  ethereumChainId: BigInt(process.env.ETHEREUM_CHAIN_ID || '1'),
+  // EIP-155 ID ETHEREUM_RPC_URL must report, when it differs from the
+  // registry id (e.g. Sepolia, 11155111, standing in for Ethereum)
+  ethereumEvmChainId: process.env.ETHEREUM_EVM_CHAIN_ID
+    ? BigInt(process.env.ETHEREUM_EVM_CHAIN_ID)
+    : null,
+  // Where to get testnet ETH; named in low-balance alerts
+  ethereumFaucetUrl: process.env.ETHEREUM_FAUCET_URL,
+  // Used only when the IDL isn't available to read the registry
+  ethereumConfirmations: parseInt(process.env.ETHEREUM_CONFIRMATIONS || '12'),
+
+  // Stellar configuration (optional, for locks destined to Stellar)
+  stellarHorizonUrl: process.env.STELLAR_HORIZON_URL || 'https://horizon-testnet.stellar.org',
+  stellarNetworkPassphrase: process.env.STELLAR_NETWORK_PASSPHRASE ||
+    'Test SDF Network ; September 2015',
+  stellarBridgeSecret: process.env.STELLAR_BRIDGE_SECRET,
+  stellarAssetCode: process.env.STELLAR_ASSET_CODE,
+  stellarAssetIssuer: process.env.STELLAR_ASSET_ISSUER,
+  solanaTokenDecimals: parseInt(process.env.SOLANA_TOKEN_DECIMALS || '9'),
+
+  // Status API (0 disables it) and where its transfer store is kept
+  apiPort: parseInt(process.env.API_PORT || '8080'),
+  // How long fee quotes served at GET /quote stay valid
+  quoteTtlSeconds: parseInt(process.env.QUOTE_TTL_SECONDS || '120'),
+  transferStorePath: process.env.TRANSFER_STORE_PATH || './data/transfers.json',
+
+  // Deploy counterfactual smart-wallet recipients before delivering to
+  // them, with init code registered at POST /smart-wallets (smart-wallets.js)
+  deploySmartWallets: process.env.DEPLOY_SMART_WALLETS !== 'false',
+  smartWalletStorePath: process.env.SMART_WALLET_STORE_PATH || './data/smart-wallets.json',
+
+  // gRPC transfer lifecycle stream (0 disables it; see stream.js), with
+  // TLS when a certificate is given
+  streamPort: parseInt(process.env.STREAM_PORT || '0'),
+  streamCertPath: process.env.STREAM_CERT_PATH,
+  streamKeyPath: process.env.STREAM_KEY_PATH,
+
+  // Notifications (see notifier.js); all sinks are optional
+  notifyWebhookUrls: (process.env.NOTIFY_WEBHOOK_URLS || '').split(',').filter(Boolean),
+  notifyWebhookSecret: process.env.NOTIFY_WEBHOOK_SECRET,
+  notifyEvents: (process.env.NOTIFY_EVENTS || '').split(',').filter(Boolean),
+  slackWebhookUrl: process.env.SLACK_WEBHOOK_URL,
+  telegramBotToken: process.env.TELEGRAM_BOT_TOKEN,
+  telegramChatId: process.env.TELEGRAM_CHAT_ID,
+  transferSlaSeconds: parseInt(process.env.TRANSFER_SLA_SECONDS || '1800'),
+  minSolanaBalance: parseFloat(process.env.MIN_SOLANA_BALANCE || '0.5'),
+  minEthereumBalance: parseFloat(process.env.MIN_ETHEREUM_BALANCE || '0.1'),
+
+  // Post a liveness heartbeat this often (0 = never), and alert on
+  // operators (owner, guardians) silent for longer than the silence limit
+  heartbeatIntervalSeconds: parseInt(process.env.HEARTBEAT_INTERVAL_SECONDS || '0'),
+  operatorSilenceSeconds: parseInt(process.env.OPERATOR_SILENCE_SECONDS || '7200'),
+
+  // relayer | attestor. An attestor signs mints as a guardian for a
+  // coordinator relayer; a relayer with ATTESTOR_URLS is that coordinator
+  relayerMode: process.env.RELAYER_MODE || 'relayer',
+  attestorUrls: (process.env.ATTESTOR_URLS || '').split(',').filter(Boolean),
+  attestorCaPath: process.env.ATTESTOR_CA_PATH,
+  attestorPort: parseInt(process.env.ATTESTOR_PORT || '50052'),
+  attestorCertPath: process.env.ATTESTOR_CERT_PATH,
+  attestorKeyPath: process.env.ATTESTOR_KEY_PATH,
+
+  // Mint on the EVM bridge with attested lock proofs (mintWithProof, see
+  // lock-proof.js) rather than this relayer's signature alone. Attestors
+  // set it too, to sign proofs with their Ethereum signer
+  lockProofs: process.env.LOCK_PROOFS === 'true',
+
+  // Re-check both chains for transfers the listeners missed (0 disables),
+  // looking this many Ethereum blocks back
+  reconcileIntervalSeconds: parseInt(process.env.RECONCILE_INTERVAL_SECONDS || '300'),
+  reconcileLookbackBlocks: parseInt(process.env.RECONCILE_LOOKBACK_BLOCKS || '7200'),
+
+  // Sync wrapped mints' metadata with their ERC-20s this often (0
+  // disables); needs ATTESTOR_URLS for the guardian quorum
+  metadataSyncIntervalSeconds: parseInt(process.env.METADATA_SYNC_INTERVAL_SECONDS || '3600'),
+
+  // Sweep SOLANA_ESCROW_MINT deposits on users' deposit addresses into
+  // locks this often (0 disables), paying fees into SOLANA_FEE_VAULT
+  depositSweepIntervalSeconds: parseInt(process.env.DEPOSIT_SWEEP_INTERVAL_SECONDS || '0'),
+  solanaFeeVault: process.env.SOLANA_FEE_VAULT,
+
+  // Attest the escrow's balance to the EVM bridge, and the EVM bridge's
+  // locked total to the program, this often (0 disables); needs
+  // ATTESTOR_URLS for the guardian quorum
+  reserveSyncIntervalSeconds: parseInt(process.env.RESERVE_SYNC_INTERVAL_SECONDS || '0'),
+
+  // Score each served relay route's health on-chain this often (0
+  // disables; see route-health.js); the key must be the owner or a guardian
+  routeHealthIntervalSeconds: parseInt(process.env.ROUTE_HEALTH_INTERVAL_SECONDS || '0'),
+
+  // Also acknowledge inbound deliveries on the Ethereum bridge (needs
+  // acknowledge(); outbound deliveries are always acknowledged on Solana)
+  ethereumAcks: process.env.ETHEREUM_ACKS === 'true',
+
+  // Acknowledge delivered escrow-mint locks in settle_batch batches of up
+  // to this many legs, waiting at most this long for legs to gather, when
+  // measured costs favour it over one ack each (batching.js; 0 disables)
+  ackBatchWaitSeconds: parseInt(process.env.ACK_BATCH_WAIT_SECONDS || '0'),
+  ackBatchMaxLegs: parseInt(process.env.ACK_BATCH_MAX_LEGS || '16'),
+
+  // Refund locks their destination rejects (a reverted mint, an invalid
+  // Stellar address) instead of leaving them to retries and the sender
+  refundFailedDeliveries: process.env.REFUND_FAILED_DELIVERIES === 'true',
+
+  // Only relay outbound transfers on these relay route IDs (set_relay_route;
+  // 0 = transfers on no route); empty relays every route
+  relayRoutes: (process.env.RELAY_ROUTES || '').split(',').filter(Boolean).map(Number),
+
+  // Advertise this relayer on-chain (register_relayer) with these terms
+  relayerEndpointUrl: process.env.RELAYER_ENDPOINT_URL,
+  relayerBaseFee: process.env.RELAYER_BASE_FEE || '0',
+  relayerFeeBps: parseInt(process.env.RELAYER_FEE_BPS || '0'),
+
+  // Export one trace per transfer over OTLP/HTTP (see tracing.js); unset
+  // keeps spans local
+  otlpEndpoint: process.env.OTEL_EXPORTER_OTLP_ENDPOINT,
+  otelServiceName: process.env.OTEL_SERVICE_NAME || (
+    process.env.RELAYER_MODE === 'attestor' ? 'solana-bridge-attestor' : 'solana-bridge-relayer'
+  ),
+
+  // Fault injection for tests (see chaos.js); never set in production
+  chaos: process.env.CHAOS,
+  chaosSeed: parseInt(process.env.CHAOS_SEED || String(Math.floor(Math.random() * 2 ** 31))),
 };
 
 // Validate configuration
@@ -31,24 +200,72 @@ if (!config.ethereumBridgeAddress) {
   process.exit(1);
 }
 
-if (!config.ethereumPrivateKey) {
-  console.error('Error: ETHEREUM_PRIVATE_KEY is required');
+// Attestors sign with their Solana key only, unless they sign lock proofs
+const solanaOnly = config.relayerMode === 'attestor' && !config.lockProofs;
+const signerRequirements = solanaOnly ? [] : {
+  local: [
+    ['ETHEREUM_PRIVATE_KEY or ETHEREUM_KEYSTORE_PATH',
+      config.ethereumPrivateKey || config.ethereumKeystorePath],
+  ],
+  remote: [['REMOTE_SIGNER_URL', config.remoteSignerUrl], ['REMOTE_SIGNER_KEY_ID', config.remoteSignerKeyId]],
+  kms: [['AWS_REGION', config.awsRegion], ['KMS_KEY_ID', config.kmsKeyId]],
+  yubihsm: [['YUBIHSM_PIN', config.yubihsmPin], ['YUBIHSM_KEY_LABEL', config.yubihsmKeyLabel]],
+}[config.ethereumSigner];
+
+if (!['relayer', 'attestor'].includes(config.relayerMode)) {
+  console.error(`Error: unknown RELAYER_MODE "${config.relayerMode}"`);
+  process.exit(1);
+}
+
+if (config.lockProofs && config.relayerMode === 'relayer' && config.attestorUrls.length === 0) {
+  console.error('Error: LOCK_PROOFS needs ATTESTOR_URLS to collect attester signatures');
+  process.exit(1);
+}
+
+if (!signerRequirements) {
+  console.error(`Error: unknown ETHEREUM_SIGNER "${config.ethereumSigner}"`);
+  process.exit(1);
+}
+
+for (const [name, value] of signerRequirements) {
+  if (!value) {
+    console.error(`Error: ${name} is required for ETHEREUM_SIGNER=${config.ethereumSigner}`);
+    process.exit(1);
+  }
+}
+
+const [command, ...args] = process.argv.slice(2);
+if (command && !COMMANDS.includes(command)) {
+  console.error(`Error: unknown command "${command}" (expected one of ${COMMANDS.join(', ')})`);
   process.exit(1);
 }
 
 async function main() {
+  const tracing = await initTracing(config, console);
   const relayer = new SolanaRelayer(config);
 
+  if (command) {
+    try {
+      await runCommand(relayer, command, args);
+    } finally {
+      relayer.transfers.close();
+      await tracing?.shutdown();
+    }
+    process.exit(0);
+  }
+
   // Handle shutdown gracefully
   process.on('SIGINT', async () => {
     console.log('\nShutting down...');
     await relayer.stop();
+    await tracing?.shutdown();
     process.exit(0);
   });
 
   process.on('SIGTERM', async () => {
     console.log('\nShutting down...');
     await relayer.stop();
+    await tracing?.shutdown();
     process.exit(0);
   });
 
@@ -0,0 +1,150 @@
+/**
+ * Lock proofs: Solana locks the EVM bridge can check without one trusted key
+ *
+ * The EVM bridge's plain `mint` takes the relayer's signature as the
+ * whole evidence that a lock happened. A lock proof names where the lock
+ * landed instead:
+ *
+ * - the transfer (ID, EVM recipient, amount) from its LockEvent
+ * - the finalized slot, that block's blockhash and the signature of the
+ *   locking transaction
+ *
+ * and carries signatures over codec.lockProofHash of all of it from a
+ * threshold of attestors (`mintWithProof`). Each attestor rebuilds the
+ * proof from its own Solana RPC before signing (verifyLockProof), so the
+ * EVM side trusts a quorum of independent checks rather than one relayer.
+ *
+ * `node src/index.js prove <solana-tx-signature>` prints the proofs of a
+ * transaction's locks as JSON, for submitting or checking by hand.
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import { ethers } from 'ethers';
+import fs from 'fs';
+import { lockProofHash } from './codec.js';
+
+const CORE = JSON.parse(
+  fs.readFileSync(new URL('../../core/constants.json', import.meta.url), 'utf-8')
+);
+const SOLANA_CHAIN_ID = BigInt(CORE.chainIds.SOLANA);
+
+// The fields an attestor must see reproduced from its own RPC
+const PROOF_FIELDS = ['to', 'amount', 'blockhash', 'txSignature'];
+
+const hex = (bytes) => `0x${Buffer.from(bytes).toString('hex')}`;
+
+/**
+ * Locks toward the relayer's EVM chain in `logs`, by bare-hex transfer ID
+ *
+ * Reads both LockEvent and CompactLockEvent (whose recipient is the
+ * address left-padded to 32 bytes).
+ */
+function evmLocks(relayer, logs) {
+  const { program, config } = relayer;
+  const parser = new anchor.EventParser(program.programId, program.coder);
+  const locks = new Map();
+  for (const event of parser.parseLogs(logs ?? [])) {
+    const name = event.name.toLowerCase();
+    if (name !== 'lockevent' && name !== 'compactlockevent') continue;
+    if (BigInt(event.data.destChainId.toString()) !== config.ethereumChainId) continue;
+
+    const transferId = Buffer.from(event.data.transferId).toString('hex');
+    locks.set(transferId, {
+      to: name === 'lockevent'
+        ? event.data.recipient
+        : hex(Buffer.from(event.data.recipient).subarray(12)),
+      amount: event.data.amount.toString(),
+    });
+  }
+  return locks;
+}
+
+/**
+ * codec.lockProofHash of `proof`, what attestors sign (eth_sign prefixed)
+ */
+export function hashLockProof(proof) {
+  return lockProofHash(
+    SOLANA_CHAIN_ID,
+    proof.transferId,
+    proof.to,
+    proof.amount,
+    proof.slot,
+    proof.blockhash,
+    proof.txSignature
+  );
+}
+
+/**
+ * Proof of the lock with `transferId` (bare hex) in the block at `slot`
+ *
+ * Reads the block at finalized commitment, so this throws for a slot
+ * that isn't final yet or was skipped, and for a lock whose transaction
+ * failed.
+ */
+export async function buildLockProof(relayer, transferId, slot) {
+  const block = await relayer.connection.getBlock(slot, {
+    commitment: 'finalized',
+    maxSupportedTransactionVersion: 0,
+    transactionDetails: 'full',
+    rewards: false,
+  });
+  if (!block) {
+    throw new Error(`Slot ${slot} is not finalized`);
+  }
+
+  for (const { transaction, meta } of block.transactions) {
+    if (!meta || meta.err) continue;
+    const lock = evmLocks(relayer, meta.logMessages).get(transferId);
+    if (!lock) continue;
+
+    const proof = {
+      transferId: `0x${transferId}`,
+      to: ethers.getAddress(lock.to),
+      amount: lock.amount,
+      slot: slot.toString(),
+      blockhash: hex(anchor.utils.bytes.bs58.decode(block.blockhash)),
+      txSignature: hex(anchor.utils.bytes.bs58.decode(transaction.signatures[0])),
+    };
+    return { ...proof, hash: hashLockProof(proof) };
+  }
+  throw new Error(`Lock ${transferId} not found in slot ${slot}`);
+}
+
+/**
+ * Proofs of every EVM-bound lock in the transaction with `signature`
+ */
+export async function buildLockProofs(relayer, signature) {
+  const tx = await relayer.connection.getTransaction(signature, {
+    commitment: 'finalized',
+    maxSupportedTransactionVersion: 0,
+  });
+  if (!tx) {
+    throw new Error(`Transaction ${signature} not found or not finalized`);
+  }
+  if (tx.meta?.err) {
+    throw new Error(`Transaction ${signature} failed`);
+  }
+
+  const transferIds = [...evmLocks(relayer, tx.meta?.logMessages).keys()];
+  return Promise.all(transferIds.map((id) => buildLockProof(relayer, id, tx.slot)));
+}
+
+/**
+ * Check `proof` against this node's own Solana RPC
+ *
+ * Rebuilds it from the block it names and throws unless every field
+ * matches; returns the rebuilt proof, whose hash is safe to sign.
+ */
+export async function verifyLockProof(relayer, proof) {
+  const rebuilt = await buildLockProof(
+    relayer,
+    proof.transferId.replace(/^0x/, '').toLowerCase(),
+    Number(proof.slot)
+  );
+  for (const field of PROOF_FIELDS) {
+    if (String(proof[field]).toLowerCase() !== String(rebuilt[field]).toLowerCase()) {
+      throw new Error(`Lock proof ${field} does not match slot ${proof.slot}`);
+    }
+  }
+  return rebuilt;
+}
@@ -0,0 +1,171 @@
+/**
+ * Wrapped-token metadata sync
+ *
+ * ERC-20s get rebranded and occasionally have their decimals corrected,
+ * while the wrapped mint's Metaplex metadata stays what it was at
+ * listing. Every METADATA_SYNC_INTERVAL_SECONDS the coordinator reads
+ * each wrapped token's ERC-20 at a final Ethereum block and, where name,
+ * symbol or decimals differ from the mint's, submits `sync_metadata`
+ * with a guardian quorum. Each guardian re-reads the token at the same
+ * block from its own Ethereum RPC before signing (attestation.js).
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import { PublicKey, SystemProgram, Transaction } from '@solana/web3.js';
+import { ethers } from 'ethers';
+
+const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+
+const ERC20_METADATA_ABI = [
+  'function name() view returns (string)',
+  'function symbol() view returns (string)',
+  'function decimals() view returns (uint8)',
+];
+
+/**
+ * Name, symbol and decimals of the ERC-20 at `address` as of `blockTag`
+ */
+export async function readErc20Metadata(provider, address, blockTag) {
+  const token = new ethers.Contract(address, ERC20_METADATA_ABI, provider);
+  const [name, symbol, decimals] = await Promise.all([
+    token.name({ blockTag }),
+    token.symbol({ blockTag }),
+    token.decimals({ blockTag }),
+  ]);
+  return { name, symbol, decimals: Number(decimals) };
+}
+
+/**
+ * Name and symbol of `mint`'s Metaplex metadata, without the padding
+ *
+ * Layout: key (1), update authority (32), mint (32), then name and
+ * symbol as borsh strings.
+ */
+async function readWrappedMetadata(connection, mint) {
+  const [metadata] = PublicKey.findProgramAddressSync(
+    [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), mint.toBuffer()],
+    TOKEN_METADATA_PROGRAM_ID
+  );
+  const account = await connection.getAccountInfo(metadata);
+  if (!account) return null;
+
+  let offset = 65;
+  const string = () => {
+    const length = account.data.readUInt32LE(offset);
+    const value = account.data.subarray(offset + 4, offset + 4 + length).toString('utf-8');
+    offset += 4 + length;
+    return value.replace(/\0+$/, '');
+  };
+  return { metadata, name: string(), symbol: string() };
+}
+
+/**
+ * Latest Ethereum block the chain registry's finality rule counts as final
+ */
+export async function finalBlock(relayer) {
+  const chainConfig = await relayer.getChainConfig(relayer.config.ethereumChainId);
+  return 'l1Batch' in chainConfig.finality
+    ? (await relayer.ethProvider.getBlock('finalized')).number
+    : await relayer.ethProvider.getBlockNumber() - Number(chainConfig.requiredConfirmations);
+}
+
+/**
+ * Sync every wrapped token whose ERC-20 changed, as of a final block
+ */
+async function syncAll(relayer, logger) {
+  const { program, connection, coordinator } = relayer;
+  const head = await finalBlock(relayer);
+
+  const assets = await program.account.wrappedAsset.all();
+  for (const { publicKey: wrappedAsset, account: asset } of assets) {
+    try {
+      const source = ethers.hexlify(Uint8Array.from(asset.sourceToken));
+      const erc20 = await readErc20Metadata(relayer.ethProvider, source, head);
+      const current = await readWrappedMetadata(connection, asset.mint);
+      if (
+        !current
+        || (current.name === `Wrapped ${erc20.name}`
+          && current.symbol === erc20.symbol
+          && asset.sourceDecimals === erc20.decimals)
+      ) {
+        continue;
+      }
+
+      const [metadataSync] = PublicKey.findProgramAddressSync(
+        [Buffer.from('metadata_sync'), asset.mint.toBuffer()],
+        program.programId
+      );
+      const [guardianSet] = PublicKey.findProgramAddressSync(
+        [Buffer.from('guardian_set')],
+        program.programId
+      );
+      const [bridgeAuthority] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bridge')],
+        program.programId
+      );
+      const [bridgeState] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bridge_state')],
+        program.programId
+      );
+      const guardians = await coordinator.selectGuardians();
+      const ix = await program.methods
+        .syncMetadata(erc20.name, erc20.symbol, erc20.decimals, new anchor.BN(head))
+        .accounts({
+          relayer: relayer.wallet.publicKey,
+          bridgeState,
+          guardianSet,
+          wrappedAsset,
+          metadataSync,
+          metadata: current.metadata,
+          bridgeAuthority,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          guardians.map((pubkey) => ({ pubkey, isSigner: true, isWritable: false }))
+        )
+        .instruction();
+
+      const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+      const tx = new Transaction({
+        feePayer: relayer.wallet.publicKey,
+        blockhash,
+        lastValidBlockHeight,
+      }).add(ix);
+      tx.partialSign(relayer.wallet);
+      await coordinator.collectMetadata(tx, guardians);
+
+      const signature = await connection.sendRawTransaction(tx.serialize());
+      await connection.confirmTransaction(
+        { signature, blockhash, lastValidBlockHeight },
+        'confirmed'
+      );
+      logger.info(
+        `Synced metadata of ${asset.mint.toString()} at block ${head}: ${signature}`
+      );
+    } catch (error) {
+      logger.warn(`Metadata sync for ${asset.mint.toString()} failed: ${error.message}`);
+    }
+  }
+}
+
+/**
+ * Sync wrapped metadata every METADATA_SYNC_INTERVAL_SECONDS; returns
+ * the timer
+ */
+export function startMetadataSync(relayer, config, logger) {
+  let running = false;
+
+  return setInterval(async () => {
+    if (running) return;
+    running = true;
+
+    try {
+      await syncAll(relayer, logger);
+    } catch (error) {
+      logger.error('Metadata sync failed:', error);
+    } finally {
+      running = false;
+    }
+  }, config.metadataSyncIntervalSeconds * 1000);
+}
@@ -0,0 +1,57 @@
+/**
+ * .sol recipients for inbound transfers
+ *
+ * An Ethereum Lock or Burn names its Solana recipient as a string, which
+ * may be a .sol domain instead of an address. The domain resolves to the
+ * owner of its SPL Name Service account, mirroring the program's names
+ * module; pin_recipient_name re-checks that owner on-chain.
+ */
+
+import { createHash } from 'crypto';
+import { PublicKey } from '@solana/web3.js';
+
+export const NAME_SERVICE_PROGRAM = new PublicKey('namesLPneVptA9Z5rqUDD9tMTWEJwofgaYwp8cawRkX');
+export const SOL_TLD = new PublicKey('58PwtjSDuFHuUkYjH9BYnnQKHfwo9reZhC2zMJv9JPkx');
+
+const HASH_PREFIX = 'SPL Name Service';
+
+/**
+ * The `alice` of `alice.sol`, or null if `name` isn't a second-level .sol domain
+ */
+export function solLabel(name) {
+  if (!name.endsWith('.sol')) return null;
+  const label = name.slice(0, -'.sol'.length);
+  return label && !label.includes('.') ? label : null;
+}
+
+/**
+ * Name account of `label`.sol
+ */
+export function snsDomainAddress(label) {
+  const hashed = createHash('sha256').update(HASH_PREFIX + label).digest();
+  return PublicKey.findProgramAddressSync(
+    [hashed, Buffer.alloc(32), SOL_TLD.toBuffer()],
+    NAME_SERVICE_PROGRAM
+  )[0];
+}
+
+/**
+ * Resolve a recipient string to `{ address, name, domain }`
+ *
+ * A base58 address comes back as is with a null name; a .sol domain
+ * resolves to its owner. Throws if the domain isn't registered.
+ */
+export async function resolveSolanaRecipient(connection, value) {
+  const input = value.trim();
+  const label = solLabel(input);
+  if (!label) {
+    return { address: new PublicKey(input), name: null, domain: null };
+  }
+
+  const domain = snsDomainAddress(label);
+  const account = await connection.getAccountInfo(domain);
+  if (!account || !account.owner.equals(NAME_SERVICE_PROGRAM) || account.data.length < 64) {
+    throw new Error(`${input} is not a registered .sol domain`);
+  }
+  return { address: new PublicKey(account.data.subarray(32, 64)), name: input, domain };
+}
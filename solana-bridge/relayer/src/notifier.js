@@ -0,0 +1,139 @@
+/**
+ * Operator notifications
+ *
+ * Fires on:
+ *
+ *   transfer.completed  a transfer reached its destination
+ *   transfer.stuck      a transfer is still in flight past the SLA
+ *   nonce.gap           an inbound nonce (or outbox sequence) was skipped
+ *   circuit_breaker     the bridge was paused or the guardian council halted it
+ *   balance.low         a relayer wallet is below its minimum
+ *
+ * Every notification is POSTed as JSON to each webhook URL, signed with
+ * HMAC-SHA256 of the body in X-Bridge-Signature when a secret is set.
+ * Slack (incoming webhook) and Telegram (bot API) get a one-line text
+ * version. A failing sink is logged and never blocks the relayer.
+ */
+
+import crypto from 'crypto';
+
+export const NOTIFY_EVENTS = {
+  TRANSFER_COMPLETED: 'transfer.completed',
+  TRANSFER_STUCK: 'transfer.stuck',
+  NONCE_GAP: 'nonce.gap',
+  CIRCUIT_BREAKER: 'circuit_breaker',
+  BALANCE_LOW: 'balance.low',
+  OPERATOR_SILENT: 'operator.silent',
+};
+
+// Give up on a sink after this long (ms)
+const SEND_TIMEOUT = 5000;
+
+function summarize(event, data) {
+  switch (event) {
+    case NOTIFY_EVENTS.TRANSFER_COMPLETED:
+      return `✓ Transfer ${data.id} completed (${data.direction}, ${data.amount})`;
+    case NOTIFY_EVENTS.TRANSFER_STUCK:
+      return `⚠ Transfer ${data.id} stuck in "${data.status}" for ${data.ageSeconds}s`;
+    case NOTIFY_EVENTS.NONCE_GAP:
+      return `⚠ ${data.source} gap: expected ${data.expected}, got ${data.received}`;
+    case NOTIFY_EVENTS.CIRCUIT_BREAKER:
+      return `🛑 ${data.breaker} ${data.tripped ? 'tripped' : 'cleared'}`;
+    case NOTIFY_EVENTS.BALANCE_LOW:
+      return `⚠ ${data.chain} relayer wallet ${data.address} low: ${data.balance} (min ${data.minimum})`;
+    case NOTIFY_EVENTS.OPERATOR_SILENT:
+      return data.lastSeen === null
+        ? `⚠ ${data.role} ${data.operator} never posted a heartbeat`
+        : `⚠ ${data.role} ${data.operator} silent for ${data.silentSeconds}s`;
+    default:
+      return `${event}: ${JSON.stringify(data)}`;
+  }
+}
+
+class Notifier {
+  constructor(config, logger) {
+    this.logger = logger;
+    this.webhookUrls = config.notifyWebhookUrls || [];
+    this.webhookSecret = config.notifyWebhookSecret;
+    this.slackWebhookUrl = config.slackWebhookUrl;
+    this.telegramBotToken = config.telegramBotToken;
+    this.telegramChatId = config.telegramChatId;
+    // Empty means every event
+    this.events = new Set(config.notifyEvents || []);
+
+    // Alerts already sent, so a condition that persists fires once
+    this.raised = new Set();
+  }
+
+  get enabled() {
+    return this.webhookUrls.length > 0
+      || Boolean(this.slackWebhookUrl)
+      || Boolean(this.telegramBotToken && this.telegramChatId);
+  }
+
+  /**
+   * Send `event` to every configured sink
+   */
+  async notify(event, data) {
+    if (!this.enabled || (this.events.size > 0 && !this.events.has(event))) return;
+
+    const body = JSON.stringify(
+      { event, at: new Date().toISOString(), data },
+      (_, value) => (typeof value === 'bigint' ? value.toString() : value)
+    );
+    const text = summarize(event, data);
+
+    const sends = this.webhookUrls.map((url) => this.post(url, body, this.signatureHeaders(body)));
+    if (this.slackWebhookUrl) {
+      sends.push(this.post(this.slackWebhookUrl, JSON.stringify({ text })));
+    }
+    if (this.telegramBotToken && this.telegramChatId) {
+      sends.push(this.post(
+        `https://api.telegram.org/bot${this.telegramBotToken}/sendMessage`,
+        JSON.stringify({ chat_id: this.telegramChatId, text })
+      ));
+    }
+
+    for (const result of await Promise.allSettled(sends)) {
+      if (result.status === 'rejected') {
+        this.logger.warn(`Notification ${event} not delivered: ${result.reason.message}`);
+      }
+    }
+  }
+
+  /**
+   * Notify once while `key` stays raised; `clear` re-arms it
+   *
+   * For conditions polled on a timer (stuck transfers, low balances,
+   * breakers), so operators get one alert, not one per tick.
+   */
+  async raise(key, event, data) {
+    if (this.raised.has(key)) return;
+    this.raised.add(key);
+    await this.notify(event, data);
+  }
+
+  clear(key) {
+    return this.raised.delete(key);
+  }
+
+  signatureHeaders(body) {
+    if (!this.webhookSecret) return {};
+    const signature = crypto.createHmac('sha256', this.webhookSecret).update(body).digest('hex');
+    return { 'X-Bridge-Signature': `sha256=${signature}` };
+  }
+
+  async post(url, body, headers = {}) {
+    const response = await fetch(url, {
+      method: 'POST',
+      headers: { 'Content-Type': 'application/json', ...headers },
+      body,
+      signal: AbortSignal.timeout(SEND_TIMEOUT),
+    });
+    if (!response.ok) {
+      throw new Error(`${new URL(url).host} responded ${response.status}`);
+    }
+  }
+}
+
+export default Notifier;
@@ -0,0 +1,51 @@
+/**
+ * Built-in route profiles (../../profiles.json)
+ *
+ * BRIDGE_PROFILE=devnet-sepolia fills in the endpoints, chain IDs,
+ * confirmation depth, faucets and test-friendly intervals for that pair
+ * of public networks. Anything set in the environment or .env still
+ * wins, so a profile only needs the deployment's addresses and keys on
+ * top. bridge-cli and `cargo xtask demo` read the same file.
+ */
+
+import fs from 'fs';
+
+export const PROFILES = JSON.parse(
+  fs.readFileSync(new URL('../../profiles.json', import.meta.url), 'utf-8')
+);
+
+/**
+ * A profile as relayer environment variables
+ */
+export function profileEnv(profile) {
+  const { solana, ethereum } = profile;
+  return {
+    SOLANA_RPC_URL: solana.rpcUrl,
+    SOLANA_AIRDROP_SOL: String(solana.airdropSol),
+    ETHEREUM_RPC_URL: ethereum.rpcUrl,
+    ETHEREUM_CHAIN_ID: String(ethereum.registryChainId),
+    ETHEREUM_EVM_CHAIN_ID: String(ethereum.evmChainId),
+    ETHEREUM_CONFIRMATIONS: String(ethereum.confirmations),
+    ...(ethereum.faucetUrl ? { ETHEREUM_FAUCET_URL: ethereum.faucetUrl } : {}),
+    ...profile.relayer,
+  };
+}
+
+/**
+ * Fill in what the environment leaves unset from profile `name`;
+ * returns the profile, or null without a name
+ */
+export function applyProfile(name) {
+  if (!name) return null;
+  const profile = PROFILES[name];
+  if (!profile) {
+    throw new Error(
+      `unknown BRIDGE_PROFILE "${name}" (expected one of ${Object.keys(PROFILES).join(', ')})`
+    );
+  }
+
+  for (const [key, value] of Object.entries(profileEnv(profile))) {
+    process.env[key] ??= value;
+  }
+  return profile;
+}
@@ -0,0 +1,68 @@
+/**
+ * Signed fee quotes, mirroring the program's quote module
+ *
+ * The relayer prices a lock from the on-chain fee table and signs
+ *
+ *   "fee_quote" || mint (32) || amount (u64 LE) || fee (u64 LE)
+ *              || dest_chain_id (u64 LE) || expires_at (i64 LE)
+ *
+ * with its Solana key. A user who puts the matching Ed25519 instruction
+ * before `lock` pays exactly that fee until the quote expires, however
+ * the table changes meanwhile. The key must be in the guardian set.
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import nacl from 'tweetnacl';
+import { Ed25519Program, PublicKey } from '@solana/web3.js';
+
+const { bs58 } = anchor.utils.bytes;
+
+const DOMAIN = Buffer.from('fee_quote');
+
+/**
+ * The message a quote signs (`FeeQuote::to_bytes`)
+ */
+export function encodeFeeQuote({ mint, amount, fee, destChainId, expiresAt }) {
+  const numbers = Buffer.alloc(32);
+  numbers.writeBigUInt64LE(BigInt(amount), 0);
+  numbers.writeBigUInt64LE(BigInt(fee), 8);
+  numbers.writeBigUInt64LE(BigInt(destChainId), 16);
+  numbers.writeBigInt64LE(BigInt(expiresAt), 24);
+  return Buffer.concat([DOMAIN, new PublicKey(mint).toBuffer(), numbers]);
+}
+
+/**
+ * What the program would charge: `base_fee` plus `fee_bps` of `amount`
+ *
+ * No volume rebate: a quote is the whole fee, whoever ends up using it.
+ */
+export function quotedFee(amount, baseFee, feeBps) {
+  return BigInt(baseFee) + (BigInt(amount) * BigInt(feeBps)) / 10000n;
+}
+
+/**
+ * Sign `quote` with `keypair`; returns the quote as the status API serves it
+ */
+export function signFeeQuote(keypair, quote) {
+  const signature = nacl.sign.detached(encodeFeeQuote(quote), keypair.secretKey);
+  return {
+    mint: new PublicKey(quote.mint).toBase58(),
+    amount: BigInt(quote.amount).toString(),
+    fee: BigInt(quote.fee).toString(),
+    destChainId: BigInt(quote.destChainId).toString(),
+    expiresAt: BigInt(quote.expiresAt).toString(),
+    signer: keypair.publicKey.toBase58(),
+    signature: bs58.encode(signature),
+  };
+}
+
+/**
+ * Ed25519 instruction to put right before `lock` for a served quote
+ */
+export function feeQuoteInstruction(served) {
+  return Ed25519Program.createInstructionWithPublicKey({
+    publicKey: new PublicKey(served.signer).toBytes(),
+    message: encodeFeeQuote(served),
+    signature: bs58.decode(served.signature),
+  });
+}
@@ -0,0 +1,148 @@
+/**
+ * Proof of reserve, both ways
+ *
+ * Each chain holds the collateral for what the other mints. Every
+ * RESERVE_SYNC_INTERVAL_SECONDS the coordinator, with a guardian quorum
+ * (attestation.js):
+ *
+ * - has the program read the escrow of SOLANA_ESCROW_MINT and queue its
+ *   balance for Ethereum (`attest_vault_balance`). The outbox poller
+ *   carries it to the EVM bridge's setSolanaReserve, which stops mints
+ *   there while the escrow is short of the wrapped supply.
+ * - posts what the EVM bridge holds locked toward Solana, as of a final
+ *   block, for SOLANA_WRAPPED_MINT (`post_reserve_attestation`), which
+ *   turns the redemption queue on while the wrapped supply is short.
+ */
+
+import * as anchor from '@coral-xyz/anchor';
+import { PublicKey, SystemProgram, Transaction } from '@solana/web3.js';
+import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { finalBlock } from './metadata-sync.js';
+
+/**
+ * Have the guardians named in `ix`'s remaining accounts sign it, then
+ * submit it
+ */
+async function submit(relayer, ix, guardians) {
+  const { connection, coordinator } = relayer;
+  const { blockhash, lastValidBlockHeight } = await connection.getLatestBlockhash();
+  const tx = new Transaction({
+    feePayer: relayer.wallet.publicKey,
+    blockhash,
+    lastValidBlockHeight,
+  }).add(ix);
+  tx.partialSign(relayer.wallet);
+  await coordinator.collectReserve(tx, guardians);
+
+  const signature = await connection.sendRawTransaction(tx.serialize());
+  await connection.confirmTransaction({ signature, blockhash, lastValidBlockHeight }, 'confirmed');
+  return signature;
+}
+
+const asSigners = (guardians) =>
+  guardians.map((pubkey) => ({ pubkey, isSigner: true, isWritable: false }));
+
+/**
+ * Queue the escrow's balance for the EVM bridge
+ */
+async function attestEscrow(relayer, logger) {
+  const { program, connection, config } = relayer;
+  const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const mint = new PublicKey(config.solanaEscrowMint);
+
+  const mintInfo = await connection.getAccountInfo(mint);
+  const tokenProgram = mintInfo ? mintInfo.owner : TOKEN_PROGRAM_ID;
+  const tokenConfig = pda(Buffer.from('token'), mint.toBuffer());
+  const { accounting } = await program.account.tokenConfig.fetch(tokenConfig);
+  const vaultConfig = pda(Buffer.from('vault'), mint.toBuffer());
+  const vault = await program.account.vaultConfig.fetchNullable(vaultConfig);
+
+  const guardians = await relayer.coordinator.selectGuardians();
+  const ix = await program.methods
+    .attestVaultBalance()
+    .accounts({
+      relayer: relayer.wallet.publicKey,
+      guardianSet: pda(Buffer.from('guardian_set')),
+      bridgeState: pda(Buffer.from('bridge_state')),
+      outbox: pda(Buffer.from('outbox')),
+      mint,
+      tokenConfig,
+      conversionRate: 'shares' in accounting
+        ? pda(Buffer.from('conversion_rate'), mint.toBuffer())
+        : null,
+      escrow: new PublicKey(config.solanaEscrowAccount),
+      vaultConfig,
+      coldVault: vault ? vault.coldVault : null,
+      bridgeAuthority: pda(Buffer.from('bridge')),
+      coldAuthority: pda(Buffer.from('cold_vault')),
+      yieldStrategy: pda(Buffer.from('yield_strategy'), mint.toBuffer()),
+    })
+    .remainingAccounts(asSigners(guardians))
+    .instruction();
+
+  const signature = await submit(relayer, ix, guardians);
+  logger.info(`Attested the escrow balance of ${mint.toString()}: ${signature}`);
+}
+
+/**
+ * Post the EVM bridge's locked total for the wrapped mint, as of a
+ * final block, unless that block is already posted
+ */
+async function attestEthereumLocked(relayer, logger) {
+  const { program, config } = relayer;
+  const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const mint = new PublicKey(config.solanaWrappedMint);
+  const reserveAttestation = pda(Buffer.from('reserve'), mint.toBuffer());
+
+  const block = await finalBlock(relayer);
+  const posted = await program.account.reserveAttestation.fetchNullable(reserveAttestation);
+  if (posted && posted.blockNumber.toNumber() >= block) return;
+
+  const locked = await relayer.ethereumBridge.totalLocked({ blockTag: block });
+  const guardians = await relayer.coordinator.selectGuardians();
+  const ix = await program.methods
+    .postReserveAttestation(new anchor.BN(locked.toString()), new anchor.BN(block))
+    .accounts({
+      payer: relayer.wallet.publicKey,
+      bridgeState: pda(Buffer.from('bridge_state')),
+      guardianSet: pda(Buffer.from('guardian_set')),
+      mint,
+      tokenConfig: pda(Buffer.from('token'), mint.toBuffer()),
+      reserveAttestation,
+      redemptionQueue: pda(Buffer.from('redemption_queue'), mint.toBuffer()),
+      systemProgram: SystemProgram.programId,
+    })
+    .remainingAccounts(asSigners(guardians))
+    .instruction();
+
+  const signature = await submit(relayer, ix, guardians);
+  logger.info(`Posted ${locked} locked on Ethereum at block ${block}: ${signature}`);
+}
+
+/**
+ * Attest reserves both ways every RESERVE_SYNC_INTERVAL_SECONDS;
+ * returns the timer
+ */
+export function startReserveSync(relayer, config, logger) {
+  let running = false;
+
+  return setInterval(async () => {
+    if (running) return;
+    running = true;
+
+    try {
+      if (config.solanaEscrowMint && config.solanaEscrowAccount) {
+        await attestEscrow(relayer, logger).catch((error) => {
+          logger.warn(`Escrow balance attestation failed: ${error.message}`);
+        });
+      }
+      if (config.solanaWrappedMint) {
+        await attestEthereumLocked(relayer, logger).catch((error) => {
+          logger.warn(`Ethereum reserve attestation failed: ${error.message}`);
+        });
+      }
+    } finally {
+      running = false;
+    }
+  }, config.reserveSyncIntervalSeconds * 1000);
+}
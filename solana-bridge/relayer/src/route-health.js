@@ -0,0 +1,124 @@
+/**
+ * Route health crank
+ *
+ * Every ROUTE_HEALTH_INTERVAL_SECONDS, for each relay route this relayer
+ * serves (plus route 0, pairs without one), measures from the outbox
+ * ring and the transfer store:
+ *
+ * - queue depth: lock entries still waiting for delivery
+ * - ack latency: average seconds from lock to delivery of the rest
+ * - reconciliation: transfers the last pass had to relay itself, and
+ *   whether that pass finished
+ *
+ * and posts them with `report_route_health`. The program adds the age of
+ * the relayer's last heartbeat and keeps the score in the route's
+ * RouteHealth account, where the SDK (`route_health`) reads it for
+ * frontends and aggregators. The key must be the owner or a guardian.
+ */
+
+import { PublicKey, SystemProgram } from '@solana/web3.js';
+import fs from 'fs';
+import { TRANSFER_STATUS } from './transfer-store.js';
+
+// Outbox kinds, generated from bridge-core (core/constants.json)
+const { outboxKind: OUTBOX_KIND } = JSON.parse(
+  fs.readFileSync(new URL('../../core/constants.json', import.meta.url), 'utf-8')
+);
+
+const DONE = [TRANSFER_STATUS.COMPLETED, TRANSFER_STATUS.RESOLVED, TRANSFER_STATUS.REFUNDED];
+
+/**
+ * Queue depth and latency of every route in the outbox ring, by route ID
+ */
+async function measure(relayer) {
+  const { program, transfers } = relayer;
+  const [outbox] = PublicKey.findProgramAddressSync([Buffer.from('outbox')], program.programId);
+  const state = await program.account.outbox.fetch(outbox);
+  const head = BigInt(state.nextSequence.toString());
+  const capacity = BigInt(state.entries.length);
+
+  const routes = new Map();
+  for (let sequence = head > capacity ? head - capacity : 0n; sequence < head; sequence++) {
+    const entry = state.entries[Number(sequence % capacity)];
+    if (entry.kind !== OUTBOX_KIND.LOCK) continue;
+
+    if (!routes.has(entry.routeId)) routes.set(entry.routeId, { queued: 0, latencies: [] });
+    const route = routes.get(entry.routeId);
+    const transfer = transfers.get(Buffer.from(entry.transferId).toString('hex'));
+    const completed = transfer?.history?.find((h) => h.status === TRANSFER_STATUS.COMPLETED);
+    if (!transfer || !DONE.includes(transfer.status)) {
+      route.queued++;
+    } else if (completed) {
+      const seconds = Date.parse(completed.at) / 1000 - entry.timestamp.toNumber();
+      route.latencies.push(Math.max(0, seconds));
+    }
+  }
+  return routes;
+}
+
+/**
+ * Score every served route once
+ */
+async function report(relayer, logger) {
+  const { program } = relayer;
+  const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+  const bridgeState = pda(Buffer.from('bridge_state'));
+  const { owner } = await program.account.bridgeState.fetch(bridgeState);
+
+  const measured = await measure(relayer);
+  const routeIds = new Set([0, ...(await program.account.relayRoute.all())
+    .map(({ account }) => account.routeId)]);
+  const reconciliation = relayer.reconcileStatus;
+
+  for (const routeId of routeIds) {
+    if (!relayer.servesRoute(routeId)) continue;
+    const { queued, latencies } = measured.get(routeId) ?? { queued: 0, latencies: [] };
+    const latency = latencies.length
+      ? latencies.reduce((sum, l) => sum + l, 0) / latencies.length
+      : 0;
+    const routeSeed = Buffer.alloc(4);
+    routeSeed.writeUInt32LE(routeId);
+
+    try {
+      await program.methods
+        .reportRouteHealth(routeId, {
+          queueDepth: queued,
+          ackLatencySecs: Math.round(latency),
+          reconciled: reconciliation?.byRoute.get(routeId) ?? 0,
+          reconcileOk: reconciliation?.ok ?? true,
+        })
+        .accounts({
+          reporter: relayer.wallet.publicKey,
+          guardianSet: pda(Buffer.from('guardian_set')),
+          bridgeState,
+          relayerLiveness: pda(Buffer.from('liveness'), owner.toBuffer()),
+          routeHealth: pda(Buffer.from('route_health'), routeSeed),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      logger.debug(`Route ${routeId} health reported (queue ${queued}, ${Math.round(latency)}s)`);
+    } catch (error) {
+      logger.warn(`Route ${routeId} health report failed: ${error.message}`);
+    }
+  }
+}
+
+/**
+ * Report route health every ROUTE_HEALTH_INTERVAL_SECONDS; returns the timer
+ */
+export function startRouteHealth(relayer, config, logger) {
+  let running = false;
+
+  return setInterval(async () => {
+    if (running) return;
+    running = true;
+
+    try {
+      await report(relayer, logger);
+    } catch (error) {
+      logger.warn(`Route health crank failed: ${error.message}`);
+    } finally {
+      running = false;
+    }
+  }, config.routeHealthIntervalSeconds * 1000);
+}
@@ -0,0 +1,259 @@
+/**
+ * Ethereum signer backends
+ *
+ * The relayer's Ethereum key signs mint attestations (and pays for the
+ * mint transactions). It can live in:
+ *
+ * - local:   a private key or an encrypted JSON keystore file
+ * - remote:  a signing service over gRPC (proto/signer.proto)
+ * - kms:     an AWS KMS ECC_SECG_P256K1 key
+ * - yubihsm: a YubiHSM 2, through its PKCS#11 module
+ *
+ * Every backend except `local` only ever hands out signatures, so the
+ * key never sits in plaintext on the relayer host. The non-local
+ * backends' packages are optional dependencies, loaded on demand.
+ */
+
+import { ethers } from 'ethers';
+import fs from 'fs';
+import path from 'path';
+import { fileURLToPath } from 'url';
+
+const SECP256K1_N = BigInt('0xfffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141');
+
+/**
+ * Signer that delegates to something that signs 32-byte digests
+ *
+ * Subclasses implement `signDigest(digest) -> { r, s }` (bigints) and
+ * set `this.address`; transactions, messages and typed data all reduce
+ * to a digest here.
+ */
+class DigestSigner extends ethers.AbstractSigner {
+  constructor(address, provider) {
+    super(provider);
+    this.address = address;
+  }
+
+  async getAddress() {
+    return this.address;
+  }
+
+  async signDigest() {
+    throw new Error('signDigest not implemented');
+  }
+
+  /**
+   * Turn a raw (r, s) into a recoverable Ethereum signature
+   *
+   * HSMs don't report the recovery id, so try both and keep the one
+   * that recovers our address. s is normalized to the low half (EIP-2).
+   */
+  async sign(digest) {
+    const { r, s: rawS } = await this.signDigest(ethers.getBytes(digest));
+    const s = rawS > SECP256K1_N / 2n ? SECP256K1_N - rawS : rawS;
+
+    for (const v of [27, 28]) {
+      const signature = ethers.Signature.from({
+        r: ethers.toBeHex(r, 32),
+        s: ethers.toBeHex(s, 32),
+        v,
+      });
+      if (ethers.recoverAddress(digest, signature) === this.address) {
+        return signature;
+      }
+    }
+    throw new Error('Signature does not recover to the signer address');
+  }
+
+  async signTransaction(tx) {
+    const populated = await this.populateTransaction(tx);
+    delete populated.from;
+    const unsigned = ethers.Transaction.from(populated);
+    unsigned.signature = await this.sign(unsigned.unsignedHash);
+    return unsigned.serialized;
+  }
+
+  async signMessage(message) {
+    return (await this.sign(ethers.hashMessage(message))).serialized;
+  }
+
+  async signTypedData(domain, types, value) {
+    return (await this.sign(ethers.TypedDataEncoder.hash(domain, types, value))).serialized;
+  }
+}
+
+/**
+ * Remote signing service over gRPC
+ */
+class RemoteSigner extends DigestSigner {
+  static async create(config, provider) {
+    const grpc = await import('@grpc/grpc-js');
+    const protoLoader = await import('@grpc/proto-loader');
+
+    const protoPath = path.join(path.dirname(fileURLToPath(import.meta.url)), '../proto/signer.proto');
+    const definition = protoLoader.loadSync(protoPath, { longs: String, bytes: Buffer });
+    const { Signer } = grpc.loadPackageDefinition(definition).bridge.signer.v1;
+
+    const credentials = config.remoteSignerCaPath
+      ? grpc.credentials.createSsl(fs.readFileSync(config.remoteSignerCaPath))
+      : grpc.credentials.createInsecure();
+    const client = new Signer(config.remoteSignerUrl, credentials);
+
+    const call = (method, request) =>
+      new Promise((resolve, reject) =>
+        client[method](request, (error, response) => (error ? reject(error) : resolve(response)))
+      );
+
+    const { publicKey } = await call('GetPublicKey', { keyId: config.remoteSignerKeyId });
+    const signer = new RemoteSigner(ethers.computeAddress(ethers.hexlify(publicKey)), provider);
+    signer.call = call;
+    signer.keyId = config.remoteSignerKeyId;
+    return signer;
+  }
+
+  async signDigest(digest) {
+    const { r, s } = await this.call('SignDigest', { keyId: this.keyId, digest: Buffer.from(digest) });
+    return { r: ethers.toBigInt(r), s: ethers.toBigInt(s) };
+  }
+
+  connect(provider) {
+    const signer = new RemoteSigner(this.address, provider);
+    Object.assign(signer, { call: this.call, keyId: this.keyId });
+    return signer;
+  }
+}
+
+/**
+ * AWS KMS (key spec ECC_SECG_P256K1, usage SIGN_VERIFY)
+ */
+class KmsSigner extends DigestSigner {
+  static async create(config, provider) {
+    const kms = await import('@aws-sdk/client-kms');
+    const client = new kms.KMSClient({ region: config.awsRegion });
+
+    const { PublicKey } = await client.send(new kms.GetPublicKeyCommand({ KeyId: config.kmsKeyId }));
+    // SubjectPublicKeyInfo DER; the uncompressed point is the last 65 bytes
+    const point = Buffer.from(PublicKey).subarray(-65);
+
+    const signer = new KmsSigner(ethers.computeAddress(ethers.hexlify(point)), provider);
+    Object.assign(signer, { kms, client, keyId: config.kmsKeyId });
+    return signer;
+  }
+
+  async signDigest(digest) {
+    const { Signature } = await this.client.send(
+      new this.kms.SignCommand({
+        KeyId: this.keyId,
+        Message: digest,
+        MessageType: 'DIGEST',
+        SigningAlgorithm: 'ECDSA_SHA_256',
+      })
+    );
+    return parseDerSignature(Buffer.from(Signature));
+  }
+
+  connect(provider) {
+    const signer = new KmsSigner(this.address, provider);
+    Object.assign(signer, { kms: this.kms, client: this.client, keyId: this.keyId });
+    return signer;
+  }
+}
+
+/**
+ * YubiHSM 2 through yubihsm_pkcs11
+ *
+ * The key is an asymmetric secp256k1 object found by label. C_Sign with
+ * CKM_ECDSA over the digest returns r || s.
+ */
+class YubiHsmSigner extends DigestSigner {
+  static async create(config, provider) {
+    const { default: pkcs11js } = await import('pkcs11js');
+    const pkcs11 = new pkcs11js.PKCS11();
+    pkcs11.load(config.yubihsmPkcs11Lib);
+    pkcs11.C_Initialize();
+
+    const [slot] = pkcs11.C_GetSlotList(true);
+    const session = pkcs11.C_OpenSession(slot, pkcs11js.CKF_SERIAL_SESSION);
+    pkcs11.C_Login(session, pkcs11js.CKU_USER, config.yubihsmPin);
+
+    const find = (objectClass) => {
+      pkcs11.C_FindObjectsInit(session, [
+        { type: pkcs11js.CKA_CLASS, value: objectClass },
+        { type: pkcs11js.CKA_LABEL, value: config.yubihsmKeyLabel },
+      ]);
+      const [handle] = pkcs11.C_FindObjects(session, 1);
+      pkcs11.C_FindObjectsFinal(session);
+      if (!handle) throw new Error(`YubiHSM key "${config.yubihsmKeyLabel}" not found`);
+      return handle;
+    };
+
+    const privateKey = find(pkcs11js.CKO_PRIVATE_KEY);
+    const [{ value: ecPoint }] = pkcs11.C_GetAttributeValue(session, find(pkcs11js.CKO_PUBLIC_KEY), [
+      { type: pkcs11js.CKA_EC_POINT },
+    ]);
+    // DER OCTET STRING around the 65-byte uncompressed point
+    const point = ecPoint.subarray(-65);
+
+    const signer = new YubiHsmSigner(ethers.computeAddress(ethers.hexlify(point)), provider);
+    Object.assign(signer, { pkcs11, pkcs11js, session, privateKey });
+    return signer;
+  }
+
+  async signDigest(digest) {
+    this.pkcs11.C_SignInit(this.session, { mechanism: this.pkcs11js.CKM_ECDSA }, this.privateKey);
+    const raw = this.pkcs11.C_Sign(this.session, Buffer.from(digest), Buffer.alloc(64));
+    return {
+      r: ethers.toBigInt(raw.subarray(0, 32)),
+      s: ethers.toBigInt(raw.subarray(32, 64)),
+    };
+  }
+
+  connect(provider) {
+    const signer = new YubiHsmSigner(this.address, provider);
+    const { pkcs11, pkcs11js, session, privateKey } = this;
+    Object.assign(signer, { pkcs11, pkcs11js, session, privateKey });
+    return signer;
+  }
+}
+
+/**
+ * DER ECDSA signature -> { r, s }
+ *
+ *   SEQUENCE { INTEGER r, INTEGER s }
+ */
+function parseDerSignature(der) {
+  let offset = 2; // SEQUENCE tag + length (always short form here)
+  const readInteger = () => {
+    if (der[offset] !== 0x02) throw new Error('Malformed DER signature');
+    const length = der[offset + 1];
+    const value = der.subarray(offset + 2, offset + 2 + length);
+    offset += 2 + length;
+    return ethers.toBigInt(value);
+  };
+  const r = readInteger();
+  const s = readInteger();
+  return { r, s };
+}
+
+/**
+ * Build the configured Ethereum signer, connected to `provider`
+ */
+export async function createEthereumSigner(config, provider) {
+  switch (config.ethereumSigner) {
+    case 'local':
+      if (config.ethereumKeystorePath) {
+        const json = fs.readFileSync(config.ethereumKeystorePath, 'utf-8');
+        const wallet = await ethers.Wallet.fromEncryptedJson(json, config.ethereumKeystorePassword);
+        return wallet.connect(provider);
+      }
+      return new ethers.Wallet(config.ethereumPrivateKey, provider);
+    case 'remote':
+      return RemoteSigner.create(config, provider);
+    case 'kms':
+      return KmsSigner.create(config, provider);
+    case 'yubihsm':
+      return YubiHsmSigner.create(config, provider);
+    default:
+      throw new Error(`Unknown ETHEREUM_SIGNER: ${config.ethereumSigner}`);
+  }
+}
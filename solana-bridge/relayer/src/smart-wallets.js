@@ -0,0 +1,115 @@
+/**
+ * Counterfactual smart-wallet recipients (deploy-or-deliver)
+ *
+ * A lock can name an EVM smart account that doesn't exist yet, with the
+ * CREATE2 derivation (factory, salt, init code hash) the program checked
+ * against the recipient (SmartWalletRecipient, PDA ["smart_wallet",
+ * nonce]). Before minting to such a recipient the relayer tries to
+ * deploy it:
+ *
+ *   - code already at the address: nothing to do
+ *   - init code registered here (POST /smart-wallets): call the factory
+ *     with it, then deliver
+ *   - otherwise: deliver to the counterfactual address anyway; the
+ *     tokens are there when the wallet is deployed
+ *
+ * Only the init code's hash is on Solana, so the init code comes from
+ * whoever set the wallet up (a wallet SDK, a dapp). The factory is
+ * called with `deployData` when given, else with salt ++ init code, the
+ * calling convention of the deterministic deployment proxy.
+ */
+
+import { ethers } from 'ethers';
+import fs from 'fs';
+import path from 'path';
+
+export class SmartWalletRegistry {
+  constructor(filePath) {
+    this.filePath = filePath;
+    this.wallets = new Map();
+
+    if (filePath && fs.existsSync(filePath)) {
+      for (const wallet of JSON.parse(fs.readFileSync(filePath, 'utf-8'))) {
+        this.wallets.set(wallet.initCodeHash, wallet);
+      }
+    }
+  }
+
+  /**
+   * Keep init code (and factory calldata, if the factory needs its own)
+   * by its hash; returns the hash
+   */
+  register({ initCode, deployData }) {
+    const initCodeHash = ethers.keccak256(initCode);
+    this.wallets.set(initCodeHash, {
+      initCodeHash,
+      initCode: ethers.hexlify(initCode),
+      deployData: deployData ? ethers.hexlify(deployData) : undefined,
+    });
+
+    if (this.filePath) {
+      fs.mkdirSync(path.dirname(this.filePath), { recursive: true });
+      const tmp = `${this.filePath}.tmp`;
+      fs.writeFileSync(tmp, JSON.stringify([...this.wallets.values()]));
+      fs.renameSync(tmp, this.filePath);
+    }
+    return initCodeHash;
+  }
+
+  get(initCodeHash) {
+    return this.wallets.get(initCodeHash.toLowerCase());
+  }
+}
+
+/**
+ * A WalletDerivation as decoded by anchor (from the account or the
+ * SmartWalletRecipientSet event), as hex
+ */
+export function toDerivation(derivation) {
+  const { factory, salt, initCodeHash } = derivation.create2;
+  return {
+    factory: ethers.getAddress(ethers.hexlify(Uint8Array.from(factory))),
+    salt: ethers.hexlify(Uint8Array.from(salt)),
+    initCodeHash: ethers.hexlify(Uint8Array.from(initCodeHash)),
+  };
+}
+
+/**
+ * Deploy the smart wallet `recipient` if it has no code and its init
+ * code is registered
+ *
+ * Best effort: a failed deploy is logged and the transfer still goes to
+ * the counterfactual address. Returns the deploy tx hash, if any.
+ */
+export async function deploySmartWallet(relayer, derivation, recipient, logger) {
+  const { factory, salt, initCodeHash } = derivation;
+  try {
+    // The program checked this; a mismatch means a bad decode, not a bad lock
+    if (ethers.getCreate2Address(factory, salt, initCodeHash) !== ethers.getAddress(recipient)) {
+      logger.warn(`Smart wallet derivation doesn't give ${recipient}, not deploying`);
+      return undefined;
+    }
+    if ((await relayer.ethProvider.getCode(recipient)) !== '0x') return undefined;
+
+    const wallet = relayer.smartWallets.get(initCodeHash);
+    if (!wallet) {
+      logger.info(`Smart wallet ${recipient} not deployed and its init code isn't registered; delivering counterfactually`);
+      return undefined;
+    }
+
+    const tx = await relayer.ethSigner.sendTransaction({
+      to: factory,
+      data: wallet.deployData ?? ethers.concat([salt, wallet.initCode]),
+    });
+    await tx.wait();
+    if ((await relayer.ethProvider.getCode(recipient)) === '0x') {
+      logger.warn(`Factory call ${tx.hash} didn't deploy ${recipient}; delivering counterfactually`);
+      return undefined;
+    }
+    logger.info(`✓ Deployed smart wallet ${recipient}: ${tx.hash}`);
+    return tx.hash;
+  } catch (error) {
+    logger.warn(`Couldn't deploy smart wallet ${recipient}: ${error.message}`);
+    return undefined;
+  }
+}
@@ -14,15 +14,83 @@
  * 2. Wait for finality (SAME)
  * 3. Verify event (SAME)
  * 4. Mint on Ethereum (SAME)
+ *
+ * Locks targeting Stellar are paid out from the Stellar bridge account
+ * instead (same as the unlock side of the stellar-bridge service).
  */
 
 import * as anchor from '@coral-xyz/anchor';
-import { Connection, PublicKey } from '@solana/web3.js';
+import {
+  ComputeBudgetProgram,
+  Connection,
+  PublicKey,
+  SystemProgram,
+  Transaction,
+  TransactionExpiredBlockheightExceededError,
+} from '@solana/web3.js';
+import {
+  TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  createAssociatedTokenAccountIdempotentInstruction,
+  getAssociatedTokenAddressSync,
+} from '@solana/spl-token';
 import { ethers } from 'ethers';
+import * as StellarSdk from '@stellar/stellar-sdk';
 import pino from 'pino';
 import fs from 'fs';
+import { createEthereumSigner } from './signers.js';
+import TransferStore, { TRANSFER_STATUS } from './transfer-store.js';
+import { startApi } from './api.js';
+import { startTransferStream } from './stream.js';
+import Notifier, { NOTIFY_EVENTS } from './notifier.js';
+import { AttestationCoordinator, startAttestor } from './attestation.js';
+import { startMetadataSync } from './metadata-sync.js';
+import { startDepositSweep } from './deposit-sweep.js';
+import { startReserveSync } from './reserve-sync.js';
+import { startRouteHealth } from './route-health.js';
+import AckBatcher from './batching.js';
+import SubmissionEngine from './submission.js';
+import {
+  bridgeStatusHash,
+  mintRequestHash,
+  transferMessageHash,
+  vaultBalanceHash,
+} from './codec.js';
+import { buildLockProof } from './lock-proof.js';
+import { inStage, logFields, STAGES, withTransfer } from './tracing.js';
+import { resolveSolanaRecipient } from './names.js';
+import { quotedFee, signFeeQuote } from './quote.js';
+import Chaos, { parseChaos } from './chaos.js';
+import { SmartWalletRegistry, deploySmartWallet, toDerivation } from './smart-wallets.js';
+
+// Chain IDs and outbox kinds, generated from bridge-core (core/constants.json)
+const CORE = JSON.parse(
+  fs.readFileSync(new URL('../../core/constants.json', import.meta.url), 'utf-8')
+);
+const CHAIN_IDS = Object.fromEntries(
+  Object.entries(CORE.chainIds).map(([name, id]) => [name, BigInt(id)])
+);
+const OUTBOX_KIND = CORE.outboxKind;
+
+// Stellar amounts have 7 decimal places
+const STELLAR_DECIMALS = 7;
+
+// Inbound amounts and nonces are u64 on Solana
+const U64_MAX = (1n << 64n) - 1n;
+
+// How often stuck transfers, breakers and balances are checked (ms)
+const MONITOR_INTERVAL = 60000;
+
+// Transfer statuses that mean a handler is still working on it
+const ACTIVE_STATUSES = [
+  TRANSFER_STATUS.DETECTED,
+  TRANSFER_STATUS.FINALIZED,
+  TRANSFER_STATUS.SUBMITTED,
+];
 
 const logger = pino({
+  // Transfer ID and stage of the delivery a line is about (tracing.js)
+  mixin: logFields,
   transport: {
     target: 'pino-pretty',
     options: { colorize: true }
@@ -33,8 +101,18 @@ class SolanaRelayer {
   constructor(config) {
     this.config = config;
 
+    // Fault injection for tests (see chaos.js)
+    const chaosRates = parseChaos(config.chaos);
+    this.chaos = chaosRates && new Chaos(chaosRates, config.chaosSeed, logger);
+
+    // Relay route IDs this relayer serves; null serves them all
+    this.relayRoutes = config.relayRoutes?.length ? new Set(config.relayRoutes) : null;
+
     // Initialize Solana connection
     this.connection = new Connection(config.solanaRpcUrl, 'confirmed');
+    if (this.chaos) {
+      this.connection = this.chaos.wrapConnection(this.connection);
+    }
 
     // Load keypair
     const keypairData = JSON.parse(
@@ -44,43 +122,171 @@ class SolanaRelayer {
       new Uint8Array(keypairData)
     );
 
-    // Initialize Ethereum components
+    // Initialize Ethereum components (the signer is connected in start())
     this.ethProvider = new ethers.JsonRpcProvider(config.ethereumRpcUrl);
-    this.ethSigner = new ethers.Wallet(config.ethereumPrivateKey, this.ethProvider);
+    if (this.chaos) {
+      this.chaos.wrapProvider(this.ethProvider);
+    }
 
     // Initialize Ethereum bridge contract
+    // Lock and Burn toward Solana share one nonce counter: the program's
+    // Ethereum inbox delivers them in that order
     this.ethereumBridge = new ethers.Contract(
       config.ethereumBridgeAddress,
       [
         'function mint(address to, uint256 amount, bytes32 nonce, bytes signature) external',
+        'function mintWithProof((bytes32 transferId, address to, uint256 amount, uint64 slot, bytes32 blockhash, bytes txSignature) proof, bytes[] signatures) external',
+        'function attesterThreshold() view returns (uint8)',
+        'function isAttester(address) view returns (bool)',
         'function processedNonces(bytes32) view returns (bool)',
-        'event Burn(address indexed from, uint256 amount, bytes32 nonce, string solanaAddress)'
+        'function acknowledge(uint256 nonce) external',
+        'function acknowledged(uint256) view returns (bool)',
+        'function setBridgeStatus(bool paused, uint64 sequence, bytes signature) external',
+        'function nextStatusSequence() view returns (uint64)',
+        'function setSolanaReserve(bytes32 mint, uint256 amount, uint64 slot, uint64 sequence, bytes signature) external',
+        'function nextReserveSequence() view returns (uint64)',
+        'function totalLocked() view returns (uint256)',
+        'event Lock(address indexed from, uint256 amount, uint256 nonce, string solanaAddress)',
+        'event Burn(address indexed from, uint256 amount, uint256 nonce, string solanaAddress)'
       ],
-      this.ethSigner
+      this.ethProvider
     );
 
+    // Initialize Stellar components (optional, only for Stellar destinations)
+    if (config.stellarBridgeSecret) {
+      this.stellarKeypair = StellarSdk.Keypair.fromSecret(config.stellarBridgeSecret);
+      this.stellarServer = new StellarSdk.Horizon.Server(config.stellarHorizonUrl);
+      this.stellarAsset = new StellarSdk.Asset(
+        config.stellarAssetCode,
+        config.stellarAssetIssuer
+      );
+    }
+
     // Track processed events
     this.processedEvents = new Set();
 
+    // EVM events waiting for finality, by "txHash:logIndex"
+    this.pendingEvmEvents = new Map();
+
+    // Every transfer seen and how far it got (served by the status API)
+    this.transfers = new TransferStore(config.transferStorePath);
+
+    // Init code for counterfactual smart-wallet recipients (smart-wallets.js)
+    this.smartWallets = new SmartWalletRegistry(config.smartWalletStorePath);
+
+    // Webhook / Slack / Telegram alerts
+    this.notifier = new Notifier(config, logger);
+    this.transfers.on('status', (transfer) => {
+      if (transfer.status === TRANSFER_STATUS.COMPLETED) {
+        this.notifier.clear(`stuck:${transfer.id}`);
+        this.notifier.notify(NOTIFY_EVENTS.TRANSFER_COMPLETED, transfer);
+      }
+    });
+
+    // Highest Ethereum -> Solana nonce seen, for gap detection
+    this.lastInboundNonce = null;
+
+    // Chooses between single and batched acks by what each has cost
+    this.batcher = new AckBatcher(this, config, logger);
+
+    // Priority fees, blockhashes and one in-flight delivery per transfer
+    this.submission = new SubmissionEngine(this.connection, config, logger);
+
     logger.info('Solana Relayer initialized');
     logger.info(`Solana wallet: ${this.wallet.publicKey.toString()}`);
     logger.info(`Ethereum bridge: ${config.ethereumBridgeAddress}`);
   }
 
+  /**
+   * Connect the Ethereum signer (local key, remote service, KMS or HSM)
+   */
+  async initEthereumSigner() {
+    this.ethSigner = await createEthereumSigner(this.config, this.ethProvider);
+    this.ethereumBridge = this.ethereumBridge.connect(this.ethSigner);
+    logger.info(
+      `Ethereum signer (${this.config.ethereumSigner}): ${await this.ethSigner.getAddress()}`
+    );
+  }
+
   /**
    * Start the relayer
    *
    * Listen for events on both chains
    */
   async start() {
+    // Guardian node: only checks and signs mints for a coordinator
+    if (this.config.relayerMode === 'attestor') {
+      logger.info('Starting attestor...');
+      this.loadProgram();
+      if (this.config.lockProofs) {
+        await this.initEthereumSigner();
+      }
+      this.attestor = await startAttestor(this, this.config, logger);
+      this.startHeartbeat();
+      return;
+    }
+
     logger.info('Starting Solana relayer...');
 
+    await this.checkEthereumNetwork();
+    await this.initEthereumSigner();
+    await this.topUpFromFaucet();
+
+    // Mint with a guardian quorum collected from attestor nodes
+    if (this.config.attestorUrls.length > 0) {
+      this.coordinator = await AttestationCoordinator.create(this, this.config, logger);
+    }
+
     // Start listening for Solana Lock events
     await this.startSolanaListener();
+    await this.register();
 
     // Start listening for Ethereum Burn events
     this.startEthereumListener();
 
+    if (this.config.apiPort) {
+      this.api = startApi(this, this.config.apiPort, logger);
+    }
+
+    if (this.config.streamPort) {
+      this.stream = await startTransferStream(this, this.config, logger);
+    }
+
+    if (this.notifier.enabled) {
+      this.startMonitor();
+    }
+
+    if (this.config.reconcileIntervalSeconds > 0) {
+      this.startReconciler();
+    }
+
+    // Wrapped metadata syncs need the IDL and a guardian quorum
+    if (this.config.metadataSyncIntervalSeconds > 0 && this.program && this.coordinator) {
+      this.metadataSyncTimer = startMetadataSync(this, this.config, logger);
+    }
+
+    // Deposit sweeps lock into the escrow and pay into the fee vault
+    if (
+      this.config.depositSweepIntervalSeconds > 0
+      && this.program
+      && this.config.solanaEscrowAccount
+      && this.config.solanaFeeVault
+    ) {
+      this.depositSweepTimer = startDepositSweep(this, this.config, logger);
+    }
+
+    // Reserve proofs go both ways, each with a guardian quorum
+    if (this.config.reserveSyncIntervalSeconds > 0 && this.program && this.coordinator) {
+      this.reserveSyncTimer = startReserveSync(this, this.config, logger);
+    }
+
+    // Route health scores read the outbox ring and the transfer store
+    if (this.config.routeHealthIntervalSeconds > 0 && this.program) {
+      this.routeHealthTimer = startRouteHealth(this, this.config, logger);
+    }
+
+    this.startHeartbeat();
+
     logger.info('Solana relayer is running...');
   }
 
@@ -91,7 +297,78 @@ class SolanaRelayer {
    * Solana uses: program.addEventListener('LockEvent', handleEvent)
    */
   async startSolanaListener() {
-    // Load program
+    this.loadProgram();
+
+    logger.info('Listening for Solana Lock events...');
+
+    // The outbox account needs the IDL to decode; otherwise scrape logs
+    if (this.program) {
+      this.pollOutbox();
+    } else if (this.relayRoutes) {
+      // Scraped logs carry no route, so other operators' transfers would go too
+      throw new Error('RELAY_ROUTES needs the program IDL');
+    } else {
+      this.pollSolanaTransactions();
+    }
+  }
+
+  /**
+   * Advertise this relayer and its terms on-chain (RELAYER_ENDPOINT_URL)
+   *
+   * register_relayer updates an existing registration, so this runs on
+   * every start. Route 0 (transfers on no route) can't be listed; an
+   * empty list means every route.
+   */
+  async register() {
+    if (!this.config.relayerEndpointUrl || !this.program) return;
+
+    const [bridgeState] = PublicKey.findProgramAddressSync(
+      [Buffer.from('bridge_state')],
+      this.program.programId
+    );
+    const [registration] = PublicKey.findProgramAddressSync(
+      [Buffer.from('relayer'), this.wallet.publicKey.toBuffer()],
+      this.program.programId
+    );
+    const [ejection] = PublicKey.findProgramAddressSync(
+      [Buffer.from('ejected'), this.wallet.publicKey.toBuffer()],
+      this.program.programId
+    );
+    try {
+      await this.program.methods
+        .registerRelayer(
+          this.config.relayerEndpointUrl,
+          [...(this.relayRoutes ?? [])].filter((route) => route !== 0),
+          {
+            baseFee: new anchor.BN(this.config.relayerBaseFee),
+            feeBps: this.config.relayerFeeBps,
+          }
+        )
+        .accounts({
+          relayer: this.wallet.publicKey,
+          bridgeState,
+          registration,
+          ejection,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      logger.info(`Registered as a relayer at ${this.config.relayerEndpointUrl}`);
+    } catch (error) {
+      logger.warn(`Relayer registration failed: ${error.message}`);
+    }
+  }
+
+  /**
+   * Whether this relayer serves relay route `routeId` (see RELAY_ROUTES)
+   */
+  servesRoute(routeId) {
+    return !this.relayRoutes || this.relayRoutes.has(routeId);
+  }
+
+  /**
+   * Load the bridge program client from its IDL, if available
+   */
+  loadProgram() {
     const provider = new anchor.AnchorProvider(
       this.connection,
       new anchor.Wallet(this.wallet),
@@ -99,14 +376,252 @@ class SolanaRelayer {
     );
 
     // Load IDL (Interface Definition Language - like ABI in Ethereum)
-    const programId = new PublicKey(this.config.solanaProgramId);
+    // Needed to read the on-chain chain registry; without it we fall
+    // back to the confirmation depth from the relayer config
+    if (fs.existsSync(this.config.solanaIdlPath)) {
+      const idl = JSON.parse(fs.readFileSync(this.config.solanaIdlPath, 'utf-8'));
+      this.program = new anchor.Program(idl, provider);
+      logger.info(`Loaded program IDL from ${this.config.solanaIdlPath}`);
+    } else {
+      logger.warn(`IDL not found at ${this.config.solanaIdlPath}, using default finality`);
+    }
+  }
 
-    // For production, load from file or generated IDL
-    // For now, we'll simulate event listening via transaction parsing
-    logger.info('Listening for Solana Lock events...');
+  /**
+   * Poll the program's outbox account
+   *
+   * lock/burn append a compact entry with an increasing sequence number,
+   * so one account read per tick replaces log scraping. The recipient is
+   * in the transfer record PDA. On startup we replay whatever is still in
+   * the ring; the Ethereum side rejects nonces it already processed.
+   * Each tick's new entries go out by priority bid (see byPriority).
+   */
+  pollOutbox() {
+    const programId = this.program.programId;
+    const [outbox] = PublicKey.findProgramAddressSync([Buffer.from('outbox')], programId);
+    let nextSequence = null;
+    let polling = false;
+
+    setInterval(async () => {
+      if (polling) return;
+      polling = true;
+
+      try {
+        const state = await this.program.account.outbox.fetch(outbox);
+        const head = BigInt(state.nextSequence.toString());
+        const capacity = BigInt(state.entries.length);
+        const oldest = head > capacity ? head - capacity : 0n;
+
+        if (nextSequence === null) {
+          nextSequence = oldest;
+        } else if (nextSequence < oldest) {
+          logger.warn(`Outbox overran, skipped sequences ${nextSequence}..${oldest - 1n}`);
+          this.notifier.notify(NOTIFY_EVENTS.NONCE_GAP, {
+            source: 'Solana outbox sequence',
+            expected: nextSequence,
+            received: oldest,
+          });
+          nextSequence = oldest;
+        }
+
+        const batch = [];
+        for (; nextSequence < head; nextSequence++) {
+          const entry = state.entries[Number(nextSequence % capacity)];
+          if (entry.kind === OUTBOX_KIND.BRIDGE_STATUS) {
+            const sequence = nextSequence;
+            await this.deliver(`bridge status #${sequence}`, () =>
+              this.relayBridgeStatus(entry, sequence)
+            );
+            continue;
+          }
+          if (entry.kind === OUTBOX_KIND.VAULT_BALANCE) {
+            const sequence = nextSequence;
+            await this.deliver(`vault balance #${sequence}`, () =>
+              this.relayVaultBalance(entry, sequence)
+            );
+            continue;
+          }
+          if (entry.kind !== OUTBOX_KIND.LOCK || !this.servesRoute(entry.routeId)) continue;
+          batch.push({ entry, sequence: nextSequence });
+        }
+
+        for (const { entry, sequence } of await this.byPriority(batch)) {
+          await this.deliver(`outbox #${sequence}`, () => this.relayOutboxEntry(entry, sequence));
+        }
+      } catch (error) {
+        logger.error('Error polling outbox:', error);
+      } finally {
+        polling = false;
+      }
+    }, 5000);
+  }
+
+  /**
+   * Order outbox entries by priority bid, highest tip first
+   *
+   * Senders only bid while the bridge is congested (bid_priority). The
+   * sort is stable, so equal tips (and unbid entries) keep their queue
+   * order. If the bids can't be read, the queue order stands.
+   */
+  async byPriority(batch) {
+    if (batch.length < 2) return batch;
+
+    try {
+      const bids = await this.program.account.priorityBid.fetchMultiple(
+        batch.map(({ entry }) => this.priorityBidAddress(entry.nonce))
+      );
+      const tips = new Map(
+        batch.map(({ sequence }, i) => [sequence, BigInt(bids[i]?.tip.toString() ?? 0)])
+      );
+      return [...batch].sort((a, b) => {
+        const [tipA, tipB] = [tips.get(a.sequence), tips.get(b.sequence)];
+        return tipA === tipB ? 0 : tipA > tipB ? -1 : 1;
+      });
+    } catch (error) {
+      logger.warn(`Could not read priority bids: ${error.message}`);
+      return batch;
+    }
+  }
+
+  priorityBidAddress(nonce) {
+    return PublicKey.findProgramAddressSync(
+      [Buffer.from('priority_bid'), new anchor.BN(nonce.toString()).toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    )[0];
+  }
+
+  /**
+   * Pay out the priority bid on a settled outbound transfer, if it has one
+   *
+   * Anyone may: the relayers' share goes to the reward vault either way.
+   * Best effort; a bid left behind can be collected later by anyone.
+   */
+  async collectPriorityTip(nonce) {
+    const nonceBn = new anchor.BN(nonce.toString());
+    const bidAddress = this.priorityBidAddress(nonceBn);
+    try {
+      const bid = await this.program.account.priorityBid.fetchNullable(bidAddress);
+      if (!bid) return;
+
+      const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, this.program.programId)[0];
+      const priorityConfig = pda(Buffer.from('priority_config'));
+      const { treasury } = await this.program.account.priorityConfig.fetch(priorityConfig);
+      await this.program.methods
+        .collectPriorityTip(nonceBn)
+        .accounts({
+          bridgeState: pda(Buffer.from('bridge_state')),
+          transferRecord: pda(
+            Buffer.from('transfer'),
+            Buffer.from('out'),
+            nonceBn.toArrayLike(Buffer, 'le', 8)
+          ),
+          priorityConfig,
+          bid: bidAddress,
+          sender: bid.sender,
+          rewardVault: pda(Buffer.from('reward_vault')),
+          treasury,
+        })
+        .rpc();
+      logger.info(`Collected priority tip of ${bid.tip.toString()} on transfer ${nonce}`);
+    } catch (error) {
+      logger.warn(`Collecting the priority tip on transfer ${nonce} failed: ${error.message}`);
+    }
+  }
+
+  /**
+   * Carry a BridgeStatus outbox entry (a pause or resume) to Ethereum
+   *
+   * The EVM bridge ignores statuses older than its newest, so a stale
+   * one is skipped here instead of wasting gas.
+   */
+  async relayBridgeStatus(entry, sequence) {
+    const paused = entry.amount.toString() !== '0';
+    if ((await this.ethereumBridge.nextStatusSequence()) > sequence) {
+      logger.info(`Bridge status #${sequence} already superseded on Ethereum`);
+      return;
+    }
+
+    const messageHash = bridgeStatusHash(CHAIN_IDS.SOLANA, paused, sequence);
+    if (messageHash !== `0x${Buffer.from(entry.transferId).toString('hex')}`) {
+      throw new Error(`Bridge status #${sequence} hash does not match the outbox entry`);
+    }
+    const signature = await this.ethSigner.signMessage(ethers.getBytes(messageHash));
+
+    const tx = await this.ethereumBridge.setBridgeStatus(paused, sequence, signature);
+    await tx.wait();
+    logger.info(
+      `✓ Bridge ${paused ? 'paused' : 'resumed'} on Ethereum (status #${sequence}): ${tx.hash}`
+    );
+  }
+
+  /**
+   * Carry a VaultBalance outbox entry (an attested escrow balance) to
+   * Ethereum, where it backs the EVM bridge's mints
+   *
+   * Like statuses, balances older than the EVM bridge's newest are
+   * ignored there, so a superseded one is skipped.
+   */
+  async relayVaultBalance(entry, sequence) {
+    if ((await this.ethereumBridge.nextReserveSequence()) > sequence) {
+      logger.info(`Vault balance #${sequence} already superseded on Ethereum`);
+      return;
+    }
+
+    const mint = ethers.hexlify(entry.mint.toBuffer());
+    const amount = entry.amount.toString();
+    const slot = entry.slot.toString();
+    const messageHash = vaultBalanceHash(CHAIN_IDS.SOLANA, mint, amount, slot, sequence);
+    if (messageHash !== `0x${Buffer.from(entry.transferId).toString('hex')}`) {
+      throw new Error(`Vault balance #${sequence} hash does not match the outbox entry`);
+    }
+    const signature = await this.ethSigner.signMessage(ethers.getBytes(messageHash));
+
+    const tx = await this.ethereumBridge.setSolanaReserve(mint, amount, slot, sequence, signature);
+    await tx.wait();
+    logger.info(
+      `✓ Solana reserve of ${amount} (slot ${slot}) posted to Ethereum (#${sequence}): ${tx.hash}`
+    );
+  }
+
+  /**
+   * Relay one outbox lock entry, unless it was refunded on Solana
+   *
+   * The recipient isn't in the entry; it comes from the transfer record
+   * (and its smart-wallet derivation, if any, from SmartWalletRecipient).
+   */
+  async relayOutboxEntry(entry, sequence) {
+    const [recordPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('transfer'), Buffer.from('out'), entry.nonce.toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    );
+    const record = await this.program.account.transferRecord.fetch(recordPda);
+
+    // Refunded on Solana (by guardians, or reclaimed by the sender
+    // after its deadline); it must not arrive anywhere
+    if ('cancelled' in record.status || 'refunded' in record.status) {
+      logger.warn(`Skipping refunded transfer ${entry.nonce.toString()}`);
+      return;
+    }
+
+    const [walletPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('smart_wallet'), entry.nonce.toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    );
+    const wallet = await this.program.account.smartWalletRecipient.fetchNullable(walletPda);
 
-    // Poll for transactions (in production, use WebSocket or geyser plugin)
-    this.pollSolanaTransactions();
+    await this.handleSolanaLock(
+      {
+        amount: entry.amount.toString(),
+        recipient: record.remoteAddress,
+        smartWallet: wallet ? toDerivation(wallet.derivation) : undefined,
+        destChainId: BigInt(entry.destChainId.toString()),
+        nonce: entry.nonce.toString(),
+        transferId: Buffer.from(entry.transferId).toString('hex'),
+        sender: entry.sender.toString(),
+      },
+      `outbox #${sequence}`,
+      entry.slot.toNumber()
+    );
   }
 
   /**
@@ -165,13 +680,18 @@ class SolanaRelayer {
     for (const log of logs) {
       if (log.includes('Locked') && log.includes('tokens for')) {
         // Parse log message
-        // Format: "Locked {amount} tokens for {eth_recipient} (nonce: {nonce})"
-        const match = log.match(/Locked (\d+) tokens for (0x[a-fA-F0-9]{40}) \(nonce: (\d+)\)/);
+        // Format: "Locked {amount} tokens for {recipient} on chain {chain_id} (nonce: {nonce}, id: {transfer_id})"
+        const match = log.match(/Locked (\d+) tokens for (\S+) on chain (\d+) \(nonce: (\d+), id: ([0-9a-f]{64})\)/);
         if (match) {
           return {
             amount: match[1],
-            ethRecipient: match[2],
-            nonce: match[3]
+            recipient: match[2],
+            destChainId: BigInt(match[3]),
+            nonce: match[4],
+            transferId: match[5],
+            requiredConfirmations: this.parseRequiredConfirmations(logs, match[5]),
+            deliveryFee: this.parseDeliveryFee(logs, match[5]),
+            smartWallet: this.parseSmartWallet(logs, match[5])
           };
         }
       }
@@ -180,6 +700,63 @@ class SolanaRelayer {
     return null;
   }
 
+  /**
+   * Destination finality carried by the matching LockEvent
+   *
+   * Needs the IDL to decode the event; returns undefined without it (or
+   * when the lock only emitted the compact event), and callers fall back
+   * to the chain registry.
+   */
+  parseRequiredConfirmations(logs, transferId) {
+    if (!this.program) return undefined;
+
+    const parser = new anchor.EventParser(this.program.programId, this.program.coder);
+    for (const event of parser.parseLogs(logs)) {
+      if (event.name.toLowerCase() !== 'lockevent') continue;
+      if (Buffer.from(event.data.transferId).toString('hex') === transferId) {
+        return event.data.requiredConfirmations;
+      }
+    }
+    return undefined;
+  }
+
+  /**
+   * Lamports the sender prepaid for delivery (DeliveryFeePrepaid), as a
+   * string, or undefined when they didn't or without the IDL
+   *
+   * Kept on the transfer to reconcile against the gas its delivery
+   * actually cost; the reward vault it was paid into funds relayers.
+   */
+  parseDeliveryFee(logs, transferId) {
+    if (!this.program) return undefined;
+
+    const parser = new anchor.EventParser(this.program.programId, this.program.coder);
+    for (const event of parser.parseLogs(logs)) {
+      if (event.name.toLowerCase() !== 'deliveryfeeprepaid') continue;
+      if (Buffer.from(event.data.transferId).toString('hex') === transferId) {
+        return event.data.lamports.toString();
+      }
+    }
+    return undefined;
+  }
+
+  /**
+   * The lock's smart-wallet derivation (SmartWalletRecipientSet), or
+   * undefined when it has none or without the IDL
+   */
+  parseSmartWallet(logs, transferId) {
+    if (!this.program) return undefined;
+
+    const parser = new anchor.EventParser(this.program.programId, this.program.coder);
+    for (const event of parser.parseLogs(logs)) {
+      if (event.name.toLowerCase() !== 'smartwalletrecipientset') continue;
+      if (Buffer.from(event.data.transferId).toString('hex') === transferId) {
+        return toDerivation(event.data.derivation);
+      }
+    }
+    return undefined;
+  }
+
   /**
    * Handle Solana Lock event
    *
@@ -188,158 +765,1567 @@ class SolanaRelayer {
    * 2. Wait for finality
    * 3. Check not processed
    * 4. Mint on Ethereum
+   *
+   * Traced as one `relay` span per attempt (see tracing.js).
    */
   async handleSolanaLock(event, signature, slot) {
+    return withTransfer(
+      event.transferId,
+      STAGES.RELAY,
+      () => this.deliverSolanaLock(event, signature, slot),
+      { 'bridge.direction': 'outbound', 'bridge.source_tx': signature, 'solana.slot': slot }
+    );
+  }
+
+  async deliverSolanaLock(event, signature, slot) {
     logger.info(`Lock event detected: ${event.amount} tokens`);
     logger.info(`Signature: ${signature}`);
-    logger.info(`Ethereum recipient: ${event.ethRecipient}`);
+    logger.info(`Destination chain: ${event.destChainId}`);
+    logger.info(`Recipient: ${event.recipient}`);
     logger.info(`Nonce: ${event.nonce}`);
+    logger.info(`Transfer ID: ${event.transferId}`);
+    if (event.deliveryFee) logger.info(`Delivery prepaid: ${event.deliveryFee} lamports`);
+
+    inStage(STAGES.INGEST, () => this.transfers.upsert(event.transferId, {
+      direction: 'outbound',
+      destChainId: event.destChainId.toString(),
+      sender: event.sender,
+      recipient: event.recipient,
+      amount: event.amount,
+      nonce: event.nonce,
+      sourceTx: signature,
+      deliveryFee: event.deliveryFee,
+      status: TRANSFER_STATUS.DETECTED,
+    }));
+
+    if (event.destChainId === CHAIN_IDS.STELLAR) {
+      await this.handleStellarLock(event, slot);
+      return;
+    }
 
     try {
       // Wait for Solana finality (~400ms, 32 slots)
-      await this.waitForSolanaFinality(slot);
+      await inStage(STAGES.FINALITY, () => this.waitForSolanaFinality(slot));
+      this.transfers.upsert(event.transferId, { status: TRANSFER_STATUS.FINALIZED });
+      if (!(await inStage(STAGES.ATTESTATION, () => this.attestOutbound(event)))) return;
 
       // Convert amount (Solana uses u64, Ethereum uses uint256)
       const amountWei = ethers.parseUnits(event.amount, 0); // Already in smallest unit
 
-      // Create nonce for Ethereum
-      const nonce = ethers.zeroPadValue(
-        ethers.toBeHex(BigInt(event.nonce)),
-        32
-      );
+      // The transfer ID doubles as the Ethereum-side replay key (bytes32)
+      const nonce = `0x${event.transferId}`;
 
       // Check if already processed on Ethereum (SAME AS YOUR RELAYER!)
       const processed = await this.ethereumBridge.processedNonces(nonce);
       if (processed) {
         logger.warn(`Nonce already processed: ${nonce}`);
+        this.transfers.upsert(event.transferId, { status: TRANSFER_STATUS.COMPLETED });
+        await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackOutbound(event.nonce, null));
         return;
       }
 
-      // Sign mint request (SAME AS YOUR RELAYER!)
-      const signature = await this.signMintRequest(
-        event.ethRecipient,
-        amountWei,
-        nonce
-      );
+      // Deploy-or-deliver: a counterfactual smart wallet gets its code
+      // first when we can, and the tokens either way
+      if (event.smartWallet && this.config.deploySmartWallets) {
+        await inStage(
+          STAGES.SUBMISSION,
+          () => deploySmartWallet(this, event.smartWallet, event.recipient, logger)
+        );
+      }
 
-      // Mint on Ethereum (SAME AS YOUR RELAYER!)
-      logger.info('Minting on Ethereum...');
-      const tx = await this.ethereumBridge.mint(
-        event.ethRecipient,
-        amountWei,
-        nonce,
-        signature
-      );
+      let tx;
+      if (this.config.lockProofs) {
+        // Attesters vouch for the finalized lock instead of our key alone
+        const proof = await buildLockProof(this, event.transferId, slot);
+        const signatures = await inStage(
+          STAGES.ATTESTATION,
+          () => this.coordinator.collectLockProof(proof)
+        );
+        logger.info(`Minting on Ethereum with a lock proof (${signatures.length} attesters)...`);
+        tx = await inStage(
+          STAGES.SUBMISSION,
+          () => this.ethereumBridge.mintWithProof(proof, signatures)
+        );
+      } else {
+        // Sign mint request (SAME AS YOUR RELAYER!)
+        const signature = await this.signMintRequest(
+          event.recipient,
+          amountWei,
+          nonce
+        );
 
-      await tx.wait();
-      logger.info(`✓ Minted on Ethereum! Tx: ${tx.hash}`);
+        // Mint on Ethereum (SAME AS YOUR RELAYER!)
+        logger.info('Minting on Ethereum...');
+        tx = await inStage(STAGES.SUBMISSION, () => this.ethereumBridge.mint(
+          event.recipient,
+          amountWei,
+          nonce,
+          signature
+        ));
+      }
+      this.transfers.upsert(event.transferId, {
+        status: TRANSFER_STATUS.SUBMITTED,
+        destTx: tx.hash,
+      });
+
+      // The destination's finality rule is on-chain config, not a constant
+      const confirmations = event.requiredConfirmations
+        ?? (await this.getChainConfig(event.destChainId)).requiredConfirmations;
+      const receipt = await inStage(STAGES.SUBMISSION, () => tx.wait(Number(confirmations)));
+      this.batcher.observeDestination(receipt);
+      this.transfers.upsert(event.transferId, { status: TRANSFER_STATUS.COMPLETED });
+      logger.info(`✓ Minted on Ethereum after ${confirmations} confirmations! Tx: ${tx.hash}`);
+      await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackOutbound(event.nonce, tx.hash));
 
     } catch (error) {
+      this.transfers.upsert(event.transferId, {
+        status: TRANSFER_STATUS.FAILED,
+        error: error.message,
+      });
       logger.error('Failed to process lock event:', error);
+
+      // A revert is the destination saying no; anything else may pass on retry
+      if (error.code === 'CALL_EXCEPTION') {
+        await this.refundFailedDelivery(event.nonce, event.transferId);
+      }
     }
   }
 
   /**
-   * Wait for Solana finality
+   * Handle a Solana Lock destined for Stellar
    *
-   * Solana finality: ~400ms (32 slots)
-   * Much faster than Ethereum's 12 blocks!
+   * Pays the bridged asset from the Stellar bridge account (the anchor
+   * holding the Stellar-side supply). The memo hash is the transfer ID
+   * so the Stellar side can reconcile payments against locks.
    */
-  async waitForSolanaFinality(eventSlot) {
-    const FINALITY_SLOTS = 32;
+  async handleStellarLock(event, slot) {
+    if (!this.stellarKeypair) {
+      logger.warn(`Stellar not configured, skipping lock nonce ${event.nonce}`);
+      return;
+    }
 
-    while (true) {
-      const currentSlot = await this.connection.getSlot();
-      const confirmations = currentSlot - eventSlot;
+    try {
+      await inStage(STAGES.FINALITY, () => this.waitForSolanaFinality(slot));
+      this.transfers.upsert(event.transferId, { status: TRANSFER_STATUS.FINALIZED });
+      if (!(await inStage(STAGES.ATTESTATION, () => this.attestOutbound(event)))) return;
 
-      if (confirmations >= FINALITY_SLOTS) {
-        logger.debug(`Solana finality reached: ${confirmations} slots`);
-        break;
+      if (!StellarSdk.StrKey.isValidEd25519PublicKey(event.recipient)) {
+        logger.error(`Invalid Stellar address: ${event.recipient}`);
+        this.transfers.upsert(event.transferId, {
+          status: TRANSFER_STATUS.FAILED,
+          error: 'Invalid Stellar address',
+        });
+        await this.refundFailedDelivery(event.nonce, event.transferId);
+        return;
       }
 
-      logger.debug(`Waiting for finality: ${confirmations}/${FINALITY_SLOTS} slots`);
-      await new Promise(resolve => setTimeout(resolve, 500));
+      // Scale from the SPL mint's decimals to Stellar's 7 (dropping dust)
+      const units = ethers.formatUnits(BigInt(event.amount), this.config.solanaTokenDecimals);
+      const [whole, fraction = ''] = units.split('.');
+      const amountStellar = `${whole}.${fraction.padEnd(STELLAR_DECIMALS, '0').slice(0, STELLAR_DECIMALS)}`;
+
+      logger.info(`Sending ${amountStellar} ${this.config.stellarAssetCode} on Stellar to ${event.recipient}`);
+
+      const account = await this.stellarServer.loadAccount(this.stellarKeypair.publicKey());
+
+      const transaction = new StellarSdk.TransactionBuilder(account, {
+        fee: StellarSdk.BASE_FEE,
+        networkPassphrase: this.config.stellarNetworkPassphrase
+      })
+        .addOperation(
+          StellarSdk.Operation.payment({
+            destination: event.recipient,
+            asset: this.stellarAsset,
+            amount: amountStellar
+          })
+        )
+        .addMemo(StellarSdk.Memo.hash(event.transferId))
+        .setTimeout(180)
+        .build();
+
+      transaction.sign(this.stellarKeypair);
+      const result = await inStage(
+        STAGES.SUBMISSION,
+        () => this.stellarServer.submitTransaction(transaction)
+      );
+      this.transfers.upsert(event.transferId, {
+        status: TRANSFER_STATUS.COMPLETED,
+        destTx: result.hash,
+      });
+
+      logger.info(`✓ Paid out on Stellar! Tx: ${result.hash}`);
+      await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackOutbound(event.nonce, result.hash));
+
+    } catch (error) {
+      this.transfers.upsert(event.transferId, {
+        status: TRANSFER_STATUS.FAILED,
+        error: error.message,
+      });
+      logger.error('Failed to process Stellar lock event:', error);
     }
   }
 
   /**
-   * Sign mint request
+   * Attest a lock with a deadline or deposit receipt before delivering it
    *
-   * EXACT SAME as your Ethereum relayer!
+   * Once attested on Solana the sender can no longer reclaim it, so it
+   * is safe to pay out; a deposit receipt is burned at the same time.
+   * Returns false if the deadline already passed: the transfer is the
+   * sender's to reclaim and must not be delivered.
    */
-  async signMintRequest(to, amount, nonce) {
-    const messageHash = ethers.solidityPackedKeccak256(
-      ['address', 'uint256', 'bytes32'],
-      [to, amount, nonce]
-    );
+  async attestOutbound(event) {
+    if (!this.program) return true;
 
-    const signature = await this.ethSigner.signMessage(
-      ethers.getBytes(messageHash)
-    );
+    const nonce = new anchor.BN(event.nonce);
+    const { transferRecord, record } = await this.outboundRecord(nonce);
+    if ((record.deadline.isZero() && !record.receipt) || 'attested' in record.status) return true;
 
-    return signature;
+    try {
+      await this.program.methods
+        .attestOutbound(nonce)
+        .accounts(this.outboundAccounts(nonce, transferRecord, record, record.receipt))
+        .rpc();
+      return true;
+    } catch (error) {
+      if (!String(error).includes('DeadlinePassed')) throw error;
+      logger.warn(`Deadline passed for nonce ${event.nonce}, leaving it to the sender to reclaim`);
+      this.transfers.upsert(event.transferId, {
+        status: TRANSFER_STATUS.FAILED,
+        error: 'Deadline passed',
+      });
+      return false;
+    }
   }
 
   /**
-   * Listen for Ethereum Burn events
+   * Acknowledge a delivered outbound transfer on Solana
    *
-   * SAME AS: sourceBridge.on('Burn', handleBurnEvent)
+   * Marks the transfer record Completed, so the sender sees the outcome
+   * on the chain they started from and can no longer reclaim it.
+   * `destTx` is the delivering transaction's hash, if known. Best
+   * effort: the program takes one ack per transfer, and reconciliation
+   * sends any that were missed.
+   *
+   * Unless `single`, the batcher may queue it as a settle_batch leg
+   * instead (batching.js); the tip is collected once the batch lands.
    */
-  startEthereumListener() {
-    this.ethereumBridge.on('Burn', async (from, amount, nonce, solanaAddress) => {
-      try {
-        await this.handleEthereumBurn(from, amount, nonce, solanaAddress);
-      } catch (error) {
-        logger.error('Error handling burn event:', error);
+  async ackOutbound(nonce, destTx, { single = false } = {}) {
+    if (!this.program) return;
+
+    const nonceBn = new anchor.BN(nonce.toString());
+    try {
+      const { transferRecord, record } = await this.outboundRecord(nonceBn);
+      if (!('initiated' in record.status) && !('attested' in record.status)) {
+        return this.collectPriorityTip(nonceBn);
+      }
+      if (!single && this.batcher.choose(record) === 'batch') {
+        this.batcher.enqueue(nonceBn, transferRecord, record, destTx);
+        return;
       }
-    });
 
-    logger.info('Listening for Ethereum Burn events...');
+      // Attesting burned the receipt already
+      const receipt = record.receipt && 'initiated' in record.status;
+      const hash = destTx ? Buffer.from(destTx.replace(/^0x/, ''), 'hex') : Buffer.alloc(32);
+      const signature = await this.program.methods
+        .ackOutbound(nonceBn, [...hash])
+        .accounts(this.outboundAccounts(nonceBn, transferRecord, record, receipt))
+        .rpc();
+      this.batcher.observe('single', signature, 1);
+      logger.info(`Outbound transfer ${nonce} acknowledged on Solana`);
+    } catch (error) {
+      logger.warn(`Ack of outbound transfer ${nonce} failed: ${error.message}`);
+      return;
+    }
+    await this.collectPriorityTip(nonceBn);
   }
 
   /**
-   * Handle Ethereum Burn event
+   * Tell the Ethereum bridge an inbound transfer was delivered on Solana
    *
-   * Similar to your unlock function:
-   * 1. Verify burn event
-   * 2. Check if processed
-   * 3. Unlock tokens on Solana
+   * Only with ETHEREUM_ACKS, for bridge contracts with acknowledge().
+   * Best effort, like ackOutbound.
    */
-  async handleEthereumBurn(from, amount, nonce, solanaAddress) {
-    logger.info(`Burn event detected: ${ethers.formatEther(amount)} tokens`);
-    logger.info(`From: ${from}`);
-    logger.info(`Solana recipient: ${solanaAddress}`);
+  async ackInbound(nonce) {
+    if (!this.config.ethereumAcks) return;
 
-    const nonceStr = ethers.hexlify(nonce);
+    try {
+      if (await this.ethereumBridge.acknowledged(nonce)) return;
+      const tx = await this.ethereumBridge.acknowledge(nonce);
+      await tx.wait();
+      logger.info(`Inbound transfer ${nonce} acknowledged on Ethereum: ${tx.hash}`);
+    } catch (error) {
+      logger.warn(`Ack of inbound transfer ${nonce} failed: ${error.message}`);
+    }
+  }
+
+  /**
+   * Refund an outbound transfer its destination rejected
+   *
+   * Only with REFUND_FAILED_DELIVERIES. Settles the transfer as the one
+   * failed leg of a batch (settle_batch), so the sender (or the lock's
+   * recovery address) gets it back out of escrow into their associated
+   * token account while the rest of its lock_batch is delivered as
+   * usual. Locks of the escrow mint only.
+   */
+  async refundFailedDelivery(nonce, transferId) {
+    if (!this.config.refundFailedDeliveries || !this.program) return;
+
+    const programId = this.program.programId;
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, programId)[0];
+    const nonceBn = new anchor.BN(nonce.toString());
+    try {
+      const { transferRecord, record } = await this.outboundRecord(nonceBn);
+      const mint = new PublicKey(this.config.solanaEscrowMint);
+      if (!record.escrowed || record.receipt || !record.mint.equals(mint)) return;
 
-    if (this.processedEvents.has(nonceStr)) {
-      logger.debug(`Burn already processed: ${nonceStr}`);
+      const mintInfo = await this.connection.getAccountInfo(mint);
+      const tokenProgram = mintInfo ? mintInfo.owner : TOKEN_PROGRAM_ID;
+      const tokenConfig = pda(Buffer.from('token'), mint.toBuffer());
+      const { accounting } = await this.program.account.tokenConfig.fetch(tokenConfig);
+      const refundOwner = record.recovery.equals(PublicKey.default)
+        ? record.localAccount
+        : record.recovery;
+
+      await this.program.methods
+        .settleBatch([{ nonce: nonceBn, delivered: false, destTx: Array(32).fill(0) }])
+        .accounts({
+          authority: this.wallet.publicKey,
+          bridgeState: pda(Buffer.from('bridge_state')),
+          throttle: pda(Buffer.from('throttle')),
+          slaConfig: pda(Buffer.from('sla')),
+          mint,
+          tokenConfig,
+          conversionRate: 'shares' in accounting
+            ? pda(Buffer.from('conversion_rate'), mint.toBuffer())
+            : null,
+          refundToken: getAssociatedTokenAddressSync(mint, refundOwner, true, tokenProgram),
+          bridgeToken: new PublicKey(this.config.solanaEscrowAccount),
+          bridgeAuthority: pda(Buffer.from('bridge')),
+          tokenProgram,
+        })
+        .remainingAccounts([{ pubkey: transferRecord, isSigner: false, isWritable: true }])
+        .rpc();
+      this.transfers.upsert(transferId, { status: TRANSFER_STATUS.REFUNDED });
+      logger.warn(`Refunded outbound transfer ${nonce}: its destination rejected it`);
+    } catch (error) {
+      logger.warn(`Refund of outbound transfer ${nonce} failed: ${error.message}`);
       return;
     }
+    await this.collectPriorityTip(nonceBn);
+  }
 
-    try {
-      // Validate Solana address
-      const recipient = new PublicKey(solanaAddress);
+  /**
+   * The outbound transfer record for `nonce` (a BN) and its address
+   */
+  async outboundRecord(nonce) {
+    const [transferRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('transfer'), Buffer.from('out'), nonce.toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    );
+    const record = await this.program.account.transferRecord.fetch(transferRecord);
+    return { transferRecord, record };
+  }
+
+  /**
+   * Accounts of attest_outbound and ack_outbound, with the deposit
+   * receipt's if `receipt` (it gets burned)
+   */
+  outboundAccounts(nonce, transferRecord, record, receipt) {
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, this.program.programId)[0];
+    const receiptMint = pda(Buffer.from('receipt'), nonce.toArrayLike(Buffer, 'le', 8));
+    return {
+      authority: this.wallet.publicKey,
+      bridgeState: pda(Buffer.from('bridge_state')),
+      transferRecord,
+      throttle: pda(Buffer.from('throttle')),
+      slaConfig: pda(Buffer.from('sla')),
+      guardianSet: pda(Buffer.from('guardian_set')),
+      bridgeAuthority: pda(Buffer.from('bridge')),
+      receiptMint: receipt ? receiptMint : null,
+      receiptToken: receipt
+        ? getAssociatedTokenAddressSync(receiptMint, record.localAccount, false, TOKEN_2022_PROGRAM_ID)
+        : null,
+      token2022Program: receipt ? TOKEN_2022_PROGRAM_ID : null,
+    };
+  }
+
+  /**
+   * Wait for Solana finality
+   *
+   * Solana finality: ~400ms (32 slots)
+   * Much faster than Ethereum's 12 blocks!
+   */
+  async waitForSolanaFinality(eventSlot) {
+    const FINALITY_SLOTS = 32;
+
+    while (true) {
+      const currentSlot = await this.connection.getSlot();
+      const confirmations = currentSlot - eventSlot;
+
+      if (confirmations >= FINALITY_SLOTS) {
+        logger.debug(`Solana finality reached: ${confirmations} slots`);
+        break;
+      }
+
+      logger.debug(`Waiting for finality: ${confirmations}/${FINALITY_SLOTS} slots`);
+      await new Promise(resolve => setTimeout(resolve, 500));
+    }
+  }
+
+  /**
+   * Read a chain's finality rule from the on-chain registry
+   *
+   * Falls back to plain block confirmations from the relayer config
+   * when the IDL isn't available.
+   */
+  async getChainConfig(chainId) {
+    if (!this.program) {
+      return {
+        finality: { confirmations: {} },
+        requiredConfirmations: this.config.ethereumConfirmations,
+      };
+    }
+
+    const [chainConfig] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('chain'),
+        new anchor.BN(chainId.toString()).toArrayLike(Buffer, 'le', 8),
+      ],
+      new PublicKey(this.config.solanaProgramId)
+    );
+
+    return this.program.account.chainConfig.fetch(chainConfig);
+  }
+
+  /**
+   * Quote and sign the fee for locking `amount` of `mint` to `destChainId`
+   *
+   * Priced from the token's fee route if it has one, else the chain's
+   * fee; valid for QUOTE_TTL_SECONDS. Only worth anything if this
+   * relayer's key is in the guardian set.
+   */
+  async quoteFee(mint, amount, destChainId) {
+    if (!this.program) {
+      throw new Error('Fee quotes need the program IDL');
+    }
+
+    const programId = new PublicKey(this.config.solanaProgramId);
+    const [feeRoute] = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('fee'),
+        new PublicKey(mint).toBuffer(),
+        new anchor.BN(destChainId.toString()).toArrayLike(Buffer, 'le', 8),
+      ],
+      programId
+    );
+    const fees = (await this.program.account.feeRoute.fetchNullable(feeRoute))
+      ?? (await this.getChainConfig(destChainId));
+
+    return signFeeQuote(this.wallet, {
+      mint,
+      amount,
+      fee: quotedFee(amount, fees.baseFee.toString(), fees.feeBps),
+      destChainId,
+      expiresAt: Math.floor(Date.now() / 1000) + this.config.quoteTtlSeconds,
+    });
+  }
+
+  /**
+   * Wait for an EVM event to be final
+   *
+   * L1: `requiredConfirmations` blocks on top of the event.
+   * Rollups (Arbitrum, Base, Optimism): count from the L2 "finalized"
+   * head, which only advances once the batch is final on L1. Until then
+   * a sequencer reorg could drop the event and we'd double-mint.
+   *
+   * Confirmations alone don't prove the event survived: after every
+   * tick (and once more at the end) we check its block hash is still
+   * canonical. Returns false, and flags the event, if a reorg dropped
+   * it; if it was re-included elsewhere the listener sees it again.
+   */
+  async waitForEvmFinality(chainConfig, log) {
+    const required = BigInt(chainConfig.requiredConfirmations);
+    const l1Batch = 'l1Batch' in chainConfig.finality;
+    const key = `${log.transactionHash}:${log.index}`;
+
+    this.pendingEvmEvents.set(key, {
+      blockNumber: log.blockNumber,
+      blockHash: log.blockHash,
+      status: 'pending',
+    });
+
+    while (true) {
+      if (!(await this.isEvmEventCanonical(log))) {
+        this.pendingEvmEvents.set(key, { ...this.pendingEvmEvents.get(key), status: 'reorged' });
+        logger.error(
+          `Reorg dropped event ${key} (block ${log.blockNumber} ${log.blockHash}); not attesting`
+        );
+        return false;
+      }
+
+      const head = l1Batch
+        ? (await this.ethProvider.getBlock('finalized')).number
+        : await this.ethProvider.getBlockNumber();
+      const confirmations = BigInt(head) - BigInt(log.blockNumber);
+
+      if (confirmations >= required) {
+        logger.debug(`EVM finality reached: ${confirmations} confirmations`);
+        break;
+      }
+
+      logger.debug(`Waiting for EVM finality: ${confirmations}/${required} confirmations`);
+      await new Promise(resolve => setTimeout(resolve, 12000));
+    }
+
+    // The final tick may itself have raced a reorg
+    if (!(await this.isEvmEventCanonical(log))) {
+      this.pendingEvmEvents.set(key, { ...this.pendingEvmEvents.get(key), status: 'reorged' });
+      logger.error(`Reorg dropped event ${key} at finality; not attesting`);
+      return false;
+    }
+
+    this.pendingEvmEvents.delete(key);
+    return true;
+  }
+
+  /**
+   * Is the log still in the canonical chain, in the block we saw it in?
+   */
+  async isEvmEventCanonical(log) {
+    const receipt = await this.ethProvider.getTransactionReceipt(log.transactionHash);
+    if (!receipt || receipt.blockHash !== log.blockHash) return false;
+
+    return receipt.logs.some(
+      (l) => l.index === log.index && l.address.toLowerCase() === log.address.toLowerCase()
+    );
+  }
+
+  /**
+   * Events a reorg dropped before we attested them
+   *
+   * For operators: these never reached Solana, but the user may expect
+   * them to. If the transaction was re-included it was relayed again
+   * under its new block.
+   */
+  getReorgedEvents() {
+    return [...this.pendingEvmEvents.entries()]
+      .filter(([, entry]) => entry.status === 'reorged')
+      .map(([key, entry]) => ({ key, ...entry }));
+  }
+
+  /**
+   * Sign mint request
+   *
+   * EXACT SAME as your Ethereum relayer!
+   */
+  async signMintRequest(to, amount, nonce) {
+    const messageHash = mintRequestHash(to, amount, nonce);
 
-      // In production, call the Solana program to unlock tokens
-      // This would be similar to calling unlock() in your EVM bridge
+    const signature = await this.ethSigner.signMessage(
+      ethers.getBytes(messageHash)
+    );
 
-      logger.info(`Would unlock tokens on Solana for ${solanaAddress}`);
-      logger.info('(Unlock implementation requires program interaction)');
+    return signature;
+  }
 
-      this.processedEvents.add(nonceStr);
+  /**
+   * Listen for Ethereum Lock and Burn events
+   *
+   * SAME AS: sourceBridge.on('Lock', handleLockEvent)
+   * Locks mint wrapped tokens on Solana; burns release escrowed ones.
+   */
+  startEthereumListener() {
+    this.ethereumBridge.on('Lock', async (from, amount, nonce, solanaAddress, event) => {
+      try {
+        await this.deliver(`Ethereum lock ${nonce}`, () =>
+          this.handleEthereumLock(from, amount, nonce, solanaAddress, event.log)
+        );
+      } catch (error) {
+        logger.error('Error handling lock event:', error);
+      }
+    });
 
+    this.ethereumBridge.on('Burn', async (from, amount, nonce, solanaAddress, event) => {
+      try {
+        await this.deliver(`Ethereum burn ${nonce}`, () =>
+          this.handleEthereumBurn(from, amount, nonce, solanaAddress, event.log)
+        );
+      } catch (error) {
+        logger.error('Error handling burn event:', error);
+      }
+    });
+
+    logger.info('Listening for Ethereum Lock and Burn events...');
+  }
+
+  /**
+   * Handle Ethereum Lock event
+   *
+   * Mint the wrapped token on Solana once the lock is final.
+   */
+  async handleEthereumLock(from, amount, nonce, solanaAddress, log) {
+    logger.info(`Ethereum lock detected: ${amount} tokens (nonce ${nonce})`);
+    logger.info(`From: ${from}`);
+    logger.info(`Solana recipient: ${solanaAddress}`);
+
+    let transferId;
+    try {
+      const recipient = await resolveSolanaRecipient(this.connection, solanaAddress);
+      if (recipient.name) {
+        logger.info(`${recipient.name} resolves to ${recipient.address.toBase58()}`);
+      }
+      transferId = this.trackInbound('mint', from, recipient, amount, nonce, log);
+      await this.relayInbound('mint', recipient, amount, nonce, transferId, log);
+    } catch (error) {
+      if (transferId) {
+        this.transfers.upsert(transferId, {
+          status: TRANSFER_STATUS.FAILED,
+          error: error.message,
+        });
+      }
+      logger.error('Failed to process lock event:', error);
+    }
+  }
+
+  /**
+   * Handle Ethereum Burn event
+   *
+   * Similar to your unlock function:
+   * 1. Verify burn event
+   * 2. Check if processed
+   * 3. Unlock tokens on Solana
+   */
+  async handleEthereumBurn(from, amount, nonce, solanaAddress, log) {
+    logger.info(`Burn event detected: ${amount} tokens (nonce ${nonce})`);
+    logger.info(`From: ${from}`);
+    logger.info(`Solana recipient: ${solanaAddress}`);
+
+    let transferId;
+    try {
+      const recipient = await resolveSolanaRecipient(this.connection, solanaAddress);
+      if (recipient.name) {
+        logger.info(`${recipient.name} resolves to ${recipient.address.toBase58()}`);
+      }
+      transferId = this.trackInbound('unlock', from, recipient, amount, nonce, log);
+      await this.relayInbound('unlock', recipient, amount, nonce, transferId, log);
     } catch (error) {
+      if (transferId) {
+        this.transfers.upsert(transferId, {
+          status: TRANSFER_STATUS.FAILED,
+          error: error.message,
+        });
+      }
       logger.error('Failed to process burn event:', error);
     }
   }
 
+  /**
+   * Wait for a tracked inbound transfer to be final, then deliver it
+   *
+   * Never attests before the source chain's finality rule is met. Traced
+   * as one `relay` span (see tracing.js).
+   */
+  async relayInbound(kind, recipient, amount, nonce, transferId, log) {
+    const attributes = {
+      'bridge.direction': 'inbound',
+      'bridge.source_tx': log.transactionHash,
+      'ethereum.log_index': log.index,
+    };
+    return withTransfer(transferId, STAGES.RELAY, async () => {
+      const chainConfig = await this.getChainConfig(this.config.ethereumChainId);
+      const final = await inStage(STAGES.FINALITY, () => this.waitForEvmFinality(chainConfig, log));
+      if (!final) {
+        this.transfers.upsert(transferId, { status: TRANSFER_STATUS.REORGED });
+        return;
+      }
+      this.transfers.upsert(transferId, { status: TRANSFER_STATUS.FINALIZED });
+
+      await this.submitInbound(kind, recipient, amount, nonce, transferId, log);
+    }, attributes);
+  }
+
+  /**
+   * The bridge's Lock and Burn events in one Ethereum transaction
+   *
+   * Returns `{ log, event }` pairs (the raw log and its parsed event).
+   * Throws if the transaction is unknown or reverted.
+   */
+  async sourceEvents(txHash) {
+    const receipt = await this.ethProvider.getTransactionReceipt(txHash);
+    if (!receipt) throw new Error(`Ethereum transaction ${txHash} not found`);
+    if (receipt.status !== 1) throw new Error(`Ethereum transaction ${txHash} reverted`);
+
+    const bridge = this.config.ethereumBridgeAddress.toLowerCase();
+    return receipt.logs
+      .filter((log) => log.address.toLowerCase() === bridge)
+      .map((log) => ({ log, event: this.ethereumBridge.interface.parseLog(log) }))
+      .filter(({ event }) => event?.name === 'Lock' || event?.name === 'Burn');
+  }
+
+  /**
+   * Deliver the Lock and Burn events of `txHash` now (`attest` command)
+   *
+   * For an operator who checked the transaction by hand: it skips the
+   * relayer's own wait for EVM finality. Guardian attestors still run
+   * their checks, and the program still rejects a nonce twice.
+   */
+  async attestSourceTx(txHash, options = {}) {
+    const events = await this.sourceEvents(txHash);
+    if (events.length === 0) throw new Error(`No bridge Lock or Burn events in ${txHash}`);
+
+    const transferIds = [];
+    for (const { log, event } of events) {
+      transferIds.push(await this.deliverSourceEvent(log, event, { ...options, manual: true }));
+    }
+    return transferIds;
+  }
+
+  /**
+   * Send a stuck inbound delivery again with a priority fee (`resubmit` command)
+   *
+   * Only for a transfer that got past finality; the delivery is rebuilt
+   * from its Ethereum event, and a first submission that lands meanwhile
+   * makes this one a no-op.
+   */
+  async resubmitInbound(transferId, priorityFee) {
+    const transfer = this.transfers.get(transferId);
+    if (transfer?.direction !== 'inbound') {
+      throw new Error(`No inbound transfer ${transferId} in the transfer store`);
+    }
+    if (!ACTIVE_STATUSES.includes(transfer.status) && transfer.status !== TRANSFER_STATUS.FAILED) {
+      throw new Error(`Transfer ${transferId} is ${transfer.status}, not stuck`);
+    }
+    if (transfer.status === TRANSFER_STATUS.DETECTED) {
+      throw new Error(`Transfer ${transferId} isn't final yet; use attest once it is checked`);
+    }
+
+    const match = (await this.sourceEvents(transfer.sourceTx))
+      .find(({ event }) => event.args.nonce.toString() === transfer.nonce);
+    if (!match) throw new Error(`Nonce ${transfer.nonce} not found in ${transfer.sourceTx}`);
+    return this.deliverSourceEvent(match.log, match.event, { priorityFee });
+  }
+
+  /**
+   * Deliver one parsed Lock or Burn event without waiting for finality
+   *
+   * `options.manual` flags the transfer as operator-attested in the store.
+   */
+  async deliverSourceEvent(log, event, options) {
+    const [from, amount, nonce, solanaAddress] = event.args;
+    const kind = event.name === 'Lock' ? 'mint' : 'unlock';
+    const recipient = await resolveSolanaRecipient(this.connection, solanaAddress);
+    const transferId = this.trackInbound(kind, from, recipient, amount, nonce, log);
+    this.transfers.upsert(transferId, {
+      status: TRANSFER_STATUS.FINALIZED,
+      ...(options.manual && { attestedManually: new Date().toISOString() }),
+    });
+
+    const attributes = {
+      'bridge.direction': 'inbound',
+      'bridge.source_tx': log.transactionHash,
+      'bridge.manual': Boolean(options.manual),
+    };
+    try {
+      await withTransfer(
+        transferId,
+        STAGES.RELAY,
+        () => this.submitInbound(kind, recipient, amount, nonce, transferId, log, options),
+        attributes
+      );
+    } catch (error) {
+      this.transfers.upsert(transferId, {
+        status: TRANSFER_STATUS.FAILED,
+        error: error.message,
+      });
+      throw error;
+    }
+    return transferId;
+  }
+
+  /**
+   * Mark a transfer resolved out of band (`resolve` command)
+   *
+   * For one settled by hand (refunded off-chain, written off, delivered
+   * by another tool): reconciliation stops retrying it and it no longer
+   * counts as stuck. A later sighting of it on its destination still
+   * marks it completed.
+   */
+  resolveTransfer(transferId, note) {
+    if (!this.transfers.get(transferId)) {
+      throw new Error(`No transfer ${transferId} in the transfer store`);
+    }
+    return this.transfers.upsert(transferId, {
+      status: TRANSFER_STATUS.RESOLVED,
+      resolution: { note, at: new Date().toISOString() },
+    });
+  }
+
+  /**
+   * Record a newly seen inbound transfer; returns its transfer ID
+   *
+   * Same ID the program stores: transfer_message_hash(recipient, amount, nonce).
+   * `recipient` is the resolved `{ address, name }` from names.js.
+   */
+  trackInbound(kind, from, recipient, amount, nonce, log) {
+    // Lock and Burn share one counter, so any jump means a missed event
+    if (this.lastInboundNonce !== null && nonce > this.lastInboundNonce + 1n) {
+      this.notifier.notify(NOTIFY_EVENTS.NONCE_GAP, {
+        source: 'Ethereum nonce',
+        expected: this.lastInboundNonce + 1n,
+        received: nonce,
+      });
+    }
+    if (this.lastInboundNonce === null || nonce > this.lastInboundNonce) {
+      this.lastInboundNonce = nonce;
+    }
+
+    const transferId = transferMessageHash(recipient.address, amount, nonce).slice(2);
+
+    withTransfer(transferId, STAGES.INGEST, () => this.transfers.upsert(transferId, {
+      direction: 'inbound',
+      kind,
+      sourceChainId: this.config.ethereumChainId.toString(),
+      sender: from,
+      recipient: recipient.address.toBase58(),
+      recipientName: recipient.name,
+      amount: amount.toString(),
+      nonce: nonce.toString(),
+      sourceTx: log.transactionHash,
+      sourceLogIndex: log.index,
+      status: TRANSFER_STATUS.DETECTED,
+    }));
+    return transferId;
+  }
+
+  /**
+   * Submit an inbound mint or unlock on Solana
+   *
+   * Each attempt fetches a fresh blockhash and re-signs, so an expired
+   * blockhash just means another try. Before retrying we check the
+   * transfer record: if an earlier attempt landed after all, we're done.
+   *
+   * With attestor nodes configured, mints carry a guardian quorum
+   * collected per attempt (see attestation.js) instead of relying on
+   * the relayer being the owner.
+   *
+   * A .sol name the recipient was given by is pinned to the record in a
+   * separate transaction once the transfer lands (guardians attest a
+   * mint only as the sole bridge instruction).
+   *
+   * Attempts are priced and given blockhashes by the submission engine
+   * (see submission.js), which also runs one at a time per transfer.
+   * `options.priorityFee` (micro-lamports per compute unit) is the floor
+   * an operator sets pushing a stuck delivery through (`resubmit` command).
+   */
+  async submitInbound(kind, resolved, amount, nonce, transferId, log, options = {}) {
+    return this.submission.once(
+      transferId,
+      () => this.sendInbound(kind, resolved, amount, nonce, transferId, log, options)
+    );
+  }
+
+  async sendInbound(kind, resolved, amount, nonce, transferId, log, options) {
+    if (!this.program) {
+      logger.warn(`IDL not loaded, can't ${kind} nonce ${nonce} on Solana`);
+      return;
+    }
+    if (BigInt(amount) > U64_MAX || BigInt(nonce) > U64_MAX) {
+      logger.error(`Amount or nonce out of u64 range for nonce ${nonce}`);
+      return;
+    }
+
+    const recipient = resolved.address;
+    const nonceBn = new anchor.BN(nonce.toString());
+    const key = `${kind}:${nonce}`;
+    if (this.processedEvents.has(key) || (await this.inboundCompleted(nonceBn))) {
+      logger.debug(`Inbound transfer already processed: ${key}`);
+      this.transfers.upsert(transferId, { status: TRANSFER_STATUS.COMPLETED });
+      await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackInbound(nonce));
+      return;
+    }
+
+    const retries = this.config.solanaSubmitRetries;
+    for (let attempt = 1; attempt <= retries; attempt++) {
+      try {
+        const guardians = kind === 'mint' && this.coordinator
+          ? await this.coordinator.selectGuardians()
+          : [];
+        const instructions = await this.buildInboundInstructions(
+          kind,
+          recipient,
+          new anchor.BN(amount.toString()),
+          nonceBn,
+          guardians
+        );
+        const microLamports = await this.submission.computeUnitPrice(
+          instructions,
+          attempt,
+          options.priorityFee
+        );
+        if (microLamports > 0) {
+          instructions.unshift(ComputeBudgetProgram.setComputeUnitPrice({ microLamports }));
+        }
+
+        const { blockhash, lastValidBlockHeight } = await this.submission.blockhash(attempt);
+        const tx = new Transaction({ feePayer: this.wallet.publicKey, blockhash, lastValidBlockHeight })
+          .add(...instructions);
+        tx.partialSign(this.wallet);
+        if (guardians.length > 0) {
+          await inStage(STAGES.ATTESTATION, () => this.coordinator.collect(tx, guardians, log));
+        }
+
+        const signature = await inStage(STAGES.SUBMISSION, async (span) => {
+          span?.setAttribute('bridge.attempt', attempt);
+          span?.setAttribute('solana.compute_unit_price', microLamports);
+          const sent = await this.connection.sendRawTransaction(tx.serialize());
+          this.transfers.upsert(transferId, {
+            status: TRANSFER_STATUS.SUBMITTED,
+            destTx: sent,
+          });
+          await this.connection.confirmTransaction(
+            { signature: sent, blockhash, lastValidBlockHeight },
+            'confirmed'
+          );
+          return sent;
+        });
+
+        this.processedEvents.add(key);
+        this.transfers.upsert(transferId, { status: TRANSFER_STATUS.COMPLETED });
+        logger.info(`✓ ${kind === 'mint' ? 'Minted' : 'Unlocked'} on Solana! Tx: ${signature}`);
+        await this.pinRecipientName(resolved, nonceBn);
+        await this.recordSourceTx(log?.transactionHash, nonceBn);
+        await this.reportGas(signature, nonceBn);
+        await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackInbound(nonce));
+        return;
+      } catch (error) {
+        if (String(error).includes('AlreadyProcessed') || (await this.inboundCompleted(nonceBn))) {
+          this.processedEvents.add(key);
+          this.transfers.upsert(transferId, { status: TRANSFER_STATUS.COMPLETED });
+          logger.info(`Inbound transfer ${key} landed in an earlier attempt`);
+          await this.pinRecipientName(resolved, nonceBn);
+          await this.recordSourceTx(log?.transactionHash, nonceBn);
+          await inStage(STAGES.ACKNOWLEDGEMENT, () => this.ackInbound(nonce));
+          return;
+        }
+
+        const expired = error instanceof TransactionExpiredBlockheightExceededError;
+        logger.warn(
+          `${kind} nonce ${nonce} attempt ${attempt}/${retries} failed` +
+            (expired ? ' (blockhash expired)' : `: ${error.message}`)
+        );
+        if (attempt === retries) throw error;
+        await new Promise(resolve => setTimeout(resolve, 1000 * 2 ** attempt));
+      }
+    }
+  }
+
+  /**
+   * Pin the .sol name an inbound transfer was addressed to onto its record
+   *
+   * Best effort: the transfer is complete either way, and the program
+   * rejects the pin if the domain changed owner since it was resolved.
+   */
+  async pinRecipientName({ name, domain }, nonce) {
+    if (!name) return;
+    try {
+      const [bridgeState] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bridge_state')],
+        this.program.programId
+      );
+      const [transferRecord] = PublicKey.findProgramAddressSync(
+        [Buffer.from('transfer'), Buffer.from('in'), nonce.toArrayLike(Buffer, 'le', 8)],
+        this.program.programId
+      );
+      const signature = await this.program.methods
+        .pinRecipientName(name)
+        .accounts({
+          signer: this.wallet.publicKey,
+          bridgeState,
+          transferRecord,
+          snsDomain: domain,
+        })
+        .rpc();
+      logger.info(`Pinned ${name} to inbound nonce ${nonce}: ${signature}`);
+    } catch (error) {
+      logger.warn(`Could not pin ${name} to inbound nonce ${nonce}: ${error.message}`);
+    }
+  }
+
+  /**
+   * Record the Ethereum transaction an inbound transfer came from
+   *
+   * Best effort, like pinning names: it only feeds lookups by source tx
+   * hash (`bridge-cli eth-tx`). The program takes it only from the
+   * relayer that delivered the transfer, so after "landed in an earlier
+   * attempt" it fails harmlessly if another relayer got there first.
+   */
+  async recordSourceTx(txHash, nonce) {
+    if (!txHash) return;
+    try {
+      const hash = Buffer.from(txHash.replace(/^0x/, ''), 'hex');
+      const [bridgeState] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bridge_state')],
+        this.program.programId
+      );
+      const [transferRecord] = PublicKey.findProgramAddressSync(
+        [Buffer.from('transfer'), Buffer.from('in'), nonce.toArrayLike(Buffer, 'le', 8)],
+        this.program.programId
+      );
+      const [sourceTx] = PublicKey.findProgramAddressSync(
+        [Buffer.from('source_tx'), hash],
+        this.program.programId
+      );
+      const signature = await this.program.methods
+        .recordSourceTx([...hash])
+        .accounts({
+          authority: this.wallet.publicKey,
+          bridgeState,
+          transferRecord,
+          sourceTx,
+        })
+        .rpc();
+      logger.info(`Recorded ${txHash} for inbound nonce ${nonce}: ${signature}`);
+    } catch (error) {
+      logger.warn(`Could not record ${txHash} for inbound nonce ${nonce}: ${error.message}`);
+    }
+  }
+
+  /**
+   * Report what delivering an inbound transfer cost, for reimbursement
+   *
+   * Best effort, like pinning names. The cost is the fee payer's (our)
+   * balance change in the delivery transaction: fees, priority fees and
+   * rent for the recipient's token account if we created it.
+   */
+  async reportGas(signature, nonce) {
+    if (!this.config.reportGas) return;
+    try {
+      const tx = await this.connection.getTransaction(signature, {
+        commitment: 'confirmed',
+        maxSupportedTransactionVersion: 0,
+      });
+      if (!tx?.meta) return;
+      const gasPaid = tx.meta.preBalances[0] - tx.meta.postBalances[0];
+      if (gasPaid <= 0) return;
+
+      const [bridgeState] = PublicKey.findProgramAddressSync(
+        [Buffer.from('bridge_state')],
+        this.program.programId
+      );
+      const [transferRecord] = PublicKey.findProgramAddressSync(
+        [Buffer.from('transfer'), Buffer.from('in'), nonce.toArrayLike(Buffer, 'le', 8)],
+        this.program.programId
+      );
+      const reported = await this.program.methods
+        .reportGas(nonce, new anchor.BN(gasPaid))
+        .accounts({
+          relayer: this.wallet.publicKey,
+          bridgeState,
+          transferRecord,
+        })
+        .rpc();
+      logger.info(`Reported ${gasPaid} lamports for inbound nonce ${nonce}: ${reported}`);
+    } catch (error) {
+      logger.warn(`Could not report gas for inbound nonce ${nonce}: ${error.message}`);
+    }
+  }
+
+  /**
+   * Instructions for an inbound transfer
+   *
+   * The recipient's associated token account is created if missing,
+   * except for mints in claim mode, which the recipient claims into an
+   * account of their choosing. One that exists but can't take the
+   * tokens (frozen, or moved to another owner) is passed as is, and the
+   * program quarantines the transfer for the recipient to claim.
+   * `guardians` are added to a mint as quorum signers.
+   */
+  async buildInboundInstructions(kind, recipient, amount, nonce, guardians = []) {
+    const programId = this.program.programId;
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, programId)[0];
+    const ethereumId = new anchor.BN(CHAIN_IDS.ETHEREUM.toString()).toArrayLike(Buffer, 'le', 8);
+
+    const mint = new PublicKey(
+      kind === 'mint' ? this.config.solanaWrappedMint : this.config.solanaEscrowMint
+    );
+    const mintInfo = await this.connection.getAccountInfo(mint);
+    const tokenProgram = mintInfo ? mintInfo.owner : TOKEN_PROGRAM_ID;
+    const userToken = getAssociatedTokenAddressSync(mint, recipient, true, tokenProgram);
+    const bridgeState = pda(Buffer.from('bridge_state'));
+    const claimDelivery = kind === 'mint'
+      && (await this.program.account.bridgeState.fetch(bridgeState)).claimDelivery;
+
+    const accounts = {
+      user: recipient,
+      authority: this.wallet.publicKey,
+      bridgeState,
+      transferRecord: pda(Buffer.from('transfer'), Buffer.from('in'), nonce.toArrayLike(Buffer, 'le', 8)),
+      inbox: pda(Buffer.from('inbox'), ethereumId),
+      sourceChain: pda(Buffer.from('chain'), ethereumId),
+      guardianCouncil: pda(Buffer.from('guardian_council')),
+      guardianSet: pda(Buffer.from('guardian_set')),
+      ...(await this.rewardAccounts()),
+      tokenConfig: pda(Buffer.from('token'), mint.toBuffer()),
+      credential: null,
+      vaa: null,
+      userToken: claimDelivery ? null : userToken,
+      bridgeAuthority: pda(Buffer.from('bridge')),
+      tokenProgram,
+      systemProgram: SystemProgram.programId,
+    };
+
+    let instruction;
+    if (kind === 'mint') {
+      instruction = await this.program.methods
+        .mint(amount, nonce)
+        .accounts({ ...accounts, wrappedMint: mint })
+        .remainingAccounts(
+          guardians.map((pubkey) => ({ pubkey, isSigner: true, isWritable: false }))
+        )
+        .instruction();
+    } else {
+      const tokenConfig = await this.program.account.tokenConfig.fetch(accounts.tokenConfig);
+      instruction = await this.program.methods
+        .unlock(amount, nonce)
+        .accounts({
+          ...accounts,
+          mint,
+          conversionRate: 'shares' in tokenConfig.accounting
+            ? pda(Buffer.from('conversion_rate'), mint.toBuffer())
+            : null,
+          bridgeToken: new PublicKey(this.config.solanaEscrowAccount),
+        })
+        .instruction();
+    }
+
+    if (claimDelivery || (await this.connection.getAccountInfo(userToken))) return [instruction];
+
+    return [
+      createAssociatedTokenAccountIdempotentInstruction(
+        this.wallet.publicKey,
+        userToken,
+        recipient,
+        mint,
+        tokenProgram
+      ),
+      instruction,
+    ];
+  }
+
+  /**
+   * Relayer reward PDAs for the current epoch
+   */
+  async rewardAccounts() {
+    const programId = this.program.programId;
+    const [rewardConfig] = PublicKey.findProgramAddressSync([Buffer.from('reward_config')], programId);
+    const config = await this.program.account.relayerRewardConfig.fetch(rewardConfig);
+
+    // Same as RelayerRewardConfig::epoch_at, on cluster time
+    const now = await this.connection.getBlockTime(await this.connection.getSlot());
+    const duration = config.epochDuration.toNumber();
+    const epoch = duration > 0
+      ? Math.floor(Math.max(now - config.startTime.toNumber(), 0) / duration)
+      : 0;
+    const epochBytes = new anchor.BN(epoch).toArrayLike(Buffer, 'le', 8);
+
+    return {
+      rewardConfig,
+      rewardEpoch: PublicKey.findProgramAddressSync(
+        [Buffer.from('reward_epoch'), epochBytes],
+        programId
+      )[0],
+      relayerStats: PublicKey.findProgramAddressSync(
+        [Buffer.from('relayer_epoch'), epochBytes, this.wallet.publicKey.toBuffer()],
+        programId
+      )[0],
+    };
+  }
+
+  /**
+   * Has this inbound nonce already completed on Solana? Claimable
+   * transfers (claim mode, quarantined) are delivered, waiting on the
+   * recipient
+   */
+  async inboundCompleted(nonce) {
+    const [record] = PublicKey.findProgramAddressSync(
+      [Buffer.from('transfer'), Buffer.from('in'), nonce.toArrayLike(Buffer, 'le', 8)],
+      this.program.programId
+    );
+    const existing = await this.program.account.transferRecord.fetchNullable(record);
+    return existing !== null && ('completed' in existing.status || 'claimable' in existing.status);
+  }
+
+  /**
+   * Hand a listener's event to `handler`, through chaos mode if it's on
+   */
+  deliver(what, handler) {
+    return this.chaos ? this.chaos.deliver(what, handler) : handler();
+  }
+
+  /**
+   * Periodically relay transfers the listeners missed
+   *
+   * Listeners lose events: an RPC error halfway through a handler, a
+   * dropped subscription, or a log re-included after a reorg below the
+   * block the Ethereum listener had already passed. Each tick walks the
+   * outbox ring and the last `reconcileLookbackBlocks` of Ethereum
+   * Lock/Burn logs and relays whatever the destination hasn't completed.
+   *
+   * How the last pass went, and the outbound transfers it relayed per
+   * route, is kept in `reconcileStatus` for the route health crank.
+   */
+  startReconciler() {
+    let running = false;
+
+    this.reconcileTimer = setInterval(async () => {
+      if (running) return;
+      running = true;
+
+      const byRoute = new Map();
+      try {
+        await this.reconcile(byRoute);
+        this.reconcileStatus = { ok: true, byRoute, at: Date.now() };
+      } catch (error) {
+        this.reconcileStatus = { ok: false, byRoute, at: Date.now() };
+        logger.error('Reconciliation failed:', error);
+      } finally {
+        running = false;
+      }
+    }, this.config.reconcileIntervalSeconds * 1000);
+  }
+
+  async reconcile(byRoute = new Map()) {
+    if (!this.program) return;
+    await this.reconcileOutbound(byRoute);
+    await this.reconcileInbound();
+  }
+
+  /**
+   * Relay outbox locks that haven't been minted on Ethereum, and the
+   * latest bridge status if Ethereum hasn't seen it; counts what it
+   * relays into `byRoute`
+   */
+  async reconcileOutbound(byRoute) {
+    const [outbox] = PublicKey.findProgramAddressSync(
+      [Buffer.from('outbox')],
+      this.program.programId
+    );
+    const state = await this.program.account.outbox.fetch(outbox);
+    const head = BigInt(state.nextSequence.toString());
+    const capacity = BigInt(state.entries.length);
+
+    let status = null;
+    let balance = null;
+    for (let sequence = head > capacity ? head - capacity : 0n; sequence < head; sequence++) {
+      const entry = state.entries[Number(sequence % capacity)];
+      if (entry.kind === OUTBOX_KIND.BRIDGE_STATUS) status = { entry, sequence };
+      if (entry.kind === OUTBOX_KIND.VAULT_BALANCE) balance = { entry, sequence };
+      if (entry.kind !== OUTBOX_KIND.LOCK || !this.servesRoute(entry.routeId)) continue;
+      if (BigInt(entry.destChainId.toString()) === CHAIN_IDS.STELLAR) continue;
+
+      const transferId = Buffer.from(entry.transferId).toString('hex');
+      if (await this.ethereumBridge.processedNonces(`0x${transferId}`)) {
+        this.markCompleted(transferId);
+        await this.ackOutbound(entry.nonce, this.transfers.get(transferId)?.destTx);
+      } else if (!this.isBusy(transferId) && !this.isResolved(transferId)) {
+        logger.warn(`Reconciling outbound transfer ${transferId} (outbox #${sequence})`);
+        byRoute.set(entry.routeId, (byRoute.get(entry.routeId) ?? 0) + 1);
+        await this.relayOutboxEntry(entry, sequence);
+      }
+    }
+
+    // Only the newest status and balance matter; the EVM bridge drops
+    // older ones
+    if (status) {
+      await this.relayBridgeStatus(status.entry, status.sequence);
+    }
+    if (balance) {
+      await this.relayVaultBalance(balance.entry, balance.sequence);
+    }
+  }
+
+  /**
+   * Relay recent Ethereum locks and burns not completed on Solana
+   *
+   * In nonce order: the program's Ethereum inbox only accepts nonces
+   * within its delivery window.
+   */
+  async reconcileInbound() {
+    const head = await this.ethProvider.getBlockNumber();
+    const fromBlock = Math.max(0, head - this.config.reconcileLookbackBlocks);
+    const logs = [
+      ...(await this.ethereumBridge.queryFilter('Lock', fromBlock, head)),
+      ...(await this.ethereumBridge.queryFilter('Burn', fromBlock, head)),
+    ].sort((a, b) => (a.args.nonce < b.args.nonce ? -1 : 1));
+
+    for (const log of logs) {
+      const [from, amount, nonce, solanaAddress] = log.args;
+      const recipient = await resolveSolanaRecipient(this.connection, solanaAddress);
+      const transferId = transferMessageHash(recipient.address, amount, nonce).slice(2);
+
+      if (await this.inboundCompleted(new anchor.BN(nonce.toString()))) {
+        this.markCompleted(transferId);
+        await this.ackInbound(nonce);
+      } else if (!this.isBusy(transferId) && !this.isResolved(transferId)) {
+        logger.warn(`Reconciling Ethereum ${log.eventName} nonce ${nonce}`);
+        const handle = log.eventName === 'Lock' ? this.handleEthereumLock : this.handleEthereumBurn;
+        await handle.call(this, from, amount, nonce, solanaAddress, log);
+      }
+    }
+  }
+
+  /**
+   * Is a handler still working on this transfer?
+   *
+   * Active and updated within the last reconcile interval. A handler
+   * stuck longer than that is retried; if it then finishes after all,
+   * replay protection rejects the second delivery.
+   */
+  isBusy(transferId) {
+    const transfer = this.transfers.get(transferId);
+    if (!transfer || !ACTIVE_STATUSES.includes(transfer.status)) return false;
+    const age = Date.now() - Date.parse(transfer.updatedAt);
+    return age < this.config.reconcileIntervalSeconds * 1000;
+  }
+
+  /**
+   * Did an operator settle this transfer by hand? (see resolveTransfer)
+   */
+  isResolved(transferId) {
+    return this.transfers.get(transferId)?.status === TRANSFER_STATUS.RESOLVED;
+  }
+
+  /**
+   * Record that the destination has a transfer we saw fail or stall,
+   * e.g. a duplicate submission that reverted after the first landed
+   */
+  markCompleted(transferId) {
+    const transfer = this.transfers.get(transferId);
+    if (transfer && transfer.status !== TRANSFER_STATUS.COMPLETED) {
+      this.transfers.upsert(transferId, { status: TRANSFER_STATUS.COMPLETED });
+    }
+  }
+
+  /**
+   * Periodic alerts: stuck transfers, circuit breakers, wallet balances
+   */
+  startMonitor() {
+    const check = async () => {
+      for (const [name, run] of [
+        ['stuck transfers', () => this.checkStuckTransfers()],
+        ['circuit breakers', () => this.checkCircuitBreakers()],
+        ['balances', () => this.checkBalances()],
+        ['operator liveness', () => this.checkOperatorLiveness()],
+      ]) {
+        try {
+          await run();
+        } catch (error) {
+          logger.error(`Monitor check (${name}) failed:`, error);
+        }
+      }
+    };
+
+    check();
+    this.monitorTimer = setInterval(check, MONITOR_INTERVAL);
+  }
+
+  async checkStuckTransfers() {
+    const now = Date.now();
+    for (const transfer of this.transfers.inFlight()) {
+      const ageSeconds = Math.floor((now - Date.parse(transfer.createdAt)) / 1000);
+      if (ageSeconds > this.config.transferSlaSeconds) {
+        await this.notifier.raise(`stuck:${transfer.id}`, NOTIFY_EVENTS.TRANSFER_STUCK, {
+          ...transfer,
+          ageSeconds,
+        });
+      }
+    }
+  }
+
+  /**
+   * Alert when the bridge is paused or the guardian council halts it
+   */
+  async checkCircuitBreakers() {
+    if (!this.program) return;
+
+    const programId = this.program.programId;
+    const [bridgeState] = PublicKey.findProgramAddressSync([Buffer.from('bridge_state')], programId);
+    const [council] = PublicKey.findProgramAddressSync([Buffer.from('guardian_council')], programId);
+
+    const state = await this.program.account.bridgeState.fetch(bridgeState);
+    const guardians = await this.program.account.guardianCouncil.fetchNullable(council);
+
+    for (const [breaker, tripped] of [
+      ['Bridge pause', state.paused],
+      ['Guardian council halt', guardians?.halted ?? false],
+    ]) {
+      if (tripped) {
+        await this.notifier.raise(`breaker:${breaker}`, NOTIFY_EVENTS.CIRCUIT_BREAKER, {
+          breaker,
+          tripped: true,
+        });
+      } else if (this.notifier.clear(`breaker:${breaker}`)) {
+        await this.notifier.notify(NOTIFY_EVENTS.CIRCUIT_BREAKER, { breaker, tripped: false });
+      }
+    }
+  }
+
+  /**
+   * Refuse to start against the wrong EVM network, e.g. a mainnet RPC
+   * URL left in .env under a testnet profile
+   */
+  async checkEthereumNetwork() {
+    if (this.config.ethereumEvmChainId === null) return;
+
+    const { chainId } = await this.ethProvider.getNetwork();
+    if (chainId !== this.config.ethereumEvmChainId) {
+      throw new Error(
+        `ETHEREUM_RPC_URL is on chain ${chainId}, expected ${this.config.ethereumEvmChainId}`
+      );
+    }
+  }
+
+  /**
+   * Test clusters: airdrop SOLANA_AIRDROP_SOL when the relayer's balance
+   * is under MIN_SOLANA_BALANCE. Faucets rate-limit, so a failure is
+   * only logged; the low-balance alert still goes out.
+   */
+  async topUpFromFaucet() {
+    if (this.config.solanaAirdropSol <= 0) return;
+
+    const lamports = await this.connection.getBalance(this.wallet.publicKey);
+    if (lamports / anchor.web3.LAMPORTS_PER_SOL >= this.config.minSolanaBalance) return;
+
+    try {
+      const signature = await this.connection.requestAirdrop(
+        this.wallet.publicKey,
+        Math.round(this.config.solanaAirdropSol * anchor.web3.LAMPORTS_PER_SOL)
+      );
+      await this.connection.confirmTransaction(signature, 'confirmed');
+      logger.info(`Airdropped ${this.config.solanaAirdropSol} SOL to the relayer: ${signature}`);
+    } catch (error) {
+      logger.warn(`Airdrop failed: ${error.message}`);
+    }
+  }
+
+  async checkBalances() {
+    await this.topUpFromFaucet();
+
+    const address = this.wallet.publicKey.toString();
+    const lamports = await this.connection.getBalance(this.wallet.publicKey);
+    const ethAddress = await this.ethSigner.getAddress();
+    const wei = await this.ethProvider.getBalance(ethAddress);
+
+    for (const [chain, wallet, balance, minimum] of [
+      ['Solana', address, lamports / anchor.web3.LAMPORTS_PER_SOL, this.config.minSolanaBalance],
+      ['Ethereum', ethAddress, Number(ethers.formatEther(wei)), this.config.minEthereumBalance],
+    ]) {
+      if (balance < minimum) {
+        await this.notifier.raise(`balance:${chain}`, NOTIFY_EVENTS.BALANCE_LOW, {
+          chain,
+          address: wallet,
+          balance,
+          minimum,
+          ...(chain === 'Ethereum' && this.config.ethereumFaucetUrl
+            ? { faucet: this.config.ethereumFaucetUrl }
+            : {}),
+        });
+      } else {
+        this.notifier.clear(`balance:${chain}`);
+      }
+    }
+  }
+
+  /**
+   * Post liveness heartbeats as this node's Solana key
+   *
+   * Keeps its entry in the on-chain liveness registry current (and, for
+   * a guardian, the dead-man's switch). The key must be the owner or in
+   * the guardian set; a failed heartbeat is logged and retried next time.
+   */
+  startHeartbeat() {
+    if (this.config.heartbeatIntervalSeconds <= 0) return;
+
+    const programId = this.program.programId;
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, programId)[0];
+    const post = async () => {
+      try {
+        await this.program.methods
+          .heartbeat()
+          .accounts({
+            operator: this.wallet.publicKey,
+            guardianSet: pda(Buffer.from('guardian_set')),
+            bridgeState: pda(Buffer.from('bridge_state')),
+            liveness: pda(Buffer.from('liveness'), this.wallet.publicKey.toBuffer()),
+            systemProgram: SystemProgram.programId,
+          })
+          .rpc();
+        logger.debug('Heartbeat posted');
+      } catch (error) {
+        logger.warn(`Heartbeat failed: ${error.message}`);
+      }
+    };
+
+    post();
+    this.heartbeatTimer = setInterval(post, this.config.heartbeatIntervalSeconds * 1000);
+  }
+
+  /**
+   * Alert on operators (owner, guardians) gone quiet in the liveness registry
+   *
+   * Tells "bridge slow" (stuck transfers, operators beating) apart from
+   * "operators gone" (stuck transfers, nobody beating).
+   */
+  async checkOperatorLiveness() {
+    if (!this.program) return;
+
+    const programId = this.program.programId;
+    const pda = (...seeds) => PublicKey.findProgramAddressSync(seeds, programId)[0];
+    const state = await this.program.account.bridgeState.fetch(pda(Buffer.from('bridge_state')));
+    const set = await this.program.account.guardianSet.fetchNullable(pda(Buffer.from('guardian_set')));
+    const guardians = set?.guardians ?? [];
+
+    const operators = [state.owner, ...guardians.filter((g) => !g.equals(state.owner))];
+    const entries = await this.program.account.operatorLiveness.fetchMultiple(
+      operators.map((operator) => pda(Buffer.from('liveness'), operator.toBuffer()))
+    );
+    const now = await this.connection.getBlockTime(await this.connection.getSlot());
+
+    for (const [i, operator] of operators.entries()) {
+      const lastSeen = entries[i]?.lastSeen.toNumber() ?? null;
+      const silentSeconds = lastSeen === null ? null : now - lastSeen;
+      const key = `silent:${operator.toString()}`;
+      if (silentSeconds === null || silentSeconds > this.config.operatorSilenceSeconds) {
+        await this.notifier.raise(key, NOTIFY_EVENTS.OPERATOR_SILENT, {
+          operator: operator.toString(),
+          role: guardians.some((g) => g.equals(operator)) ? 'Guardian' : 'Relayer',
+          lastSeen,
+          silentSeconds,
+        });
+      } else {
+        this.notifier.clear(key);
+      }
+    }
+  }
+
   /**
    * Stop the relayer
    */
   async stop() {
     logger.info('Stopping Solana relayer...');
+    this.attestor?.forceShutdown();
     this.ethereumBridge.removeAllListeners();
+    this.api?.close();
+    this.stream?.forceShutdown();
+    clearInterval(this.monitorTimer);
+    clearInterval(this.reconcileTimer);
+    clearInterval(this.heartbeatTimer);
+    clearInterval(this.metadataSyncTimer);
+    clearInterval(this.depositSweepTimer);
+    clearInterval(this.reserveSyncTimer);
+    clearInterval(this.routeHealthTimer);
+    await this.batcher.drain();
+    this.transfers.close();
   }
 }
 
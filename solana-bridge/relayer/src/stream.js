@@ -0,0 +1,163 @@
+/**
+ * Transfer lifecycle stream (gRPC, proto/transfers.proto)
+ *
+ * Pushes each transfer's moves through the lifecycle to subscribers as
+ * they happen, off the transfer store's `status` events:
+ *
+ *   detected             -> INITIATED
+ *   finalized            -> ATTESTED
+ *   submitted            -> SUBMITTED
+ *   completed            -> FINALIZED
+ *   refunded             -> REFUNDED
+ *   failed, reorged      -> FAILED
+ *   resolved             -> RESOLVED
+ *
+ * Subscribers filter by transfer ID, address and direction, and can ask
+ * for a replay of everything updated since a timestamp to catch up
+ * after a reconnect. A subscriber that stops reading is cut off once
+ * MAX_BUFFERED live updates queue up for it, rather than growing the
+ * relayer's memory; it reconnects with `since_ms`.
+ */
+
+import fs from 'fs';
+import path from 'path';
+import { fileURLToPath } from 'url';
+import { TRANSFER_STATUS } from './transfer-store.js';
+
+const PROTO_PATH = path.join(path.dirname(fileURLToPath(import.meta.url)), '../proto/transfers.proto');
+
+// Updates queued for one subscriber before it is dropped
+const MAX_BUFFERED = 1000;
+
+const PHASES = {
+  [TRANSFER_STATUS.DETECTED]: 'PHASE_INITIATED',
+  [TRANSFER_STATUS.FINALIZED]: 'PHASE_ATTESTED',
+  [TRANSFER_STATUS.SUBMITTED]: 'PHASE_SUBMITTED',
+  [TRANSFER_STATUS.COMPLETED]: 'PHASE_FINALIZED',
+  [TRANSFER_STATUS.REFUNDED]: 'PHASE_REFUNDED',
+  [TRANSFER_STATUS.FAILED]: 'PHASE_FAILED',
+  [TRANSFER_STATUS.REORGED]: 'PHASE_FAILED',
+  [TRANSFER_STATUS.RESOLVED]: 'PHASE_RESOLVED',
+};
+
+const DIRECTIONS = {
+  outbound: 'DIRECTION_OUTBOUND',
+  inbound: 'DIRECTION_INBOUND',
+};
+
+async function loadStreamService() {
+  const grpc = await import('@grpc/grpc-js');
+  const protoLoader = await import('@grpc/proto-loader');
+  const definition = protoLoader.loadSync(PROTO_PATH, { longs: String, enums: String });
+  const { TransferStream } = grpc.loadPackageDefinition(definition).bridge.transfers.v1;
+  return { grpc, TransferStream };
+}
+
+/**
+ * The TransferUpdate for `transfer`'s current status
+ */
+function toUpdate(transfer, replay = false) {
+  return {
+    transferId: transfer.id,
+    phase: PHASES[transfer.status] ?? 'PHASE_UNSPECIFIED',
+    status: transfer.status,
+    direction: DIRECTIONS[transfer.direction] ?? 'DIRECTION_UNSPECIFIED',
+    remoteChainId: transfer.destChainId ?? transfer.sourceChainId ?? '0',
+    sender: transfer.sender ?? '',
+    recipient: transfer.recipient ?? '',
+    amount: String(transfer.amount ?? ''),
+    nonce: String(transfer.nonce ?? ''),
+    sourceTx: transfer.sourceTx ?? '',
+    destTx: transfer.destTx ?? '',
+    error: transfer.error ?? '',
+    updatedAtMs: String(Date.parse(transfer.updatedAt)),
+    replay,
+  };
+}
+
+/**
+ * Predicate for the transfers a SubscribeRequest asks for
+ */
+function matcher({ transferIds = [], addresses = [], direction = 'DIRECTION_UNSPECIFIED' }) {
+  const ids = new Set(transferIds.map((id) => id.replace(/^0x/, '').toLowerCase()));
+  const needles = new Set(addresses.map((address) => address.toLowerCase()));
+  const has = (value) => typeof value === 'string' && needles.has(value.toLowerCase());
+
+  return (transfer) =>
+    (ids.size === 0 || ids.has(transfer.id))
+    && (needles.size === 0 || has(transfer.sender) || has(transfer.recipient))
+    && (direction === 'DIRECTION_UNSPECIFIED' || DIRECTIONS[transfer.direction] === direction);
+}
+
+/**
+ * Serve the transfer stream for `relayer` on STREAM_PORT; returns the
+ * gRPC server
+ */
+export async function startTransferStream(relayer, config, logger) {
+  const { grpc, TransferStream } = await loadStreamService();
+  const subscribers = new Set();
+
+  // One store listener, fanned out, however many subscribers connect
+  relayer.transfers.on('status', (transfer) => {
+    for (const subscriber of subscribers) {
+      if (subscriber.matches(transfer)) subscriber.send(toUpdate(transfer));
+    }
+  });
+
+  const server = new grpc.Server();
+  server.addService(TransferStream.service, {
+    Subscribe: (call) => {
+      const matches = matcher(call.request);
+
+      // Live updates queued since the client last caught up
+      let behind = 0;
+      call.on('drain', () => { behind = 0; });
+
+      const subscriber = {
+        matches,
+        send: (update) => {
+          if (behind >= MAX_BUFFERED) {
+            subscribers.delete(subscriber);
+            logger.warn(`Dropping transfer stream subscriber ${call.getPeer()}: not reading`);
+            call.emit('error', {
+              code: grpc.status.RESOURCE_EXHAUSTED,
+              details: 'Too many unread updates; resubscribe with since_ms',
+            });
+            return;
+          }
+          if (!call.write(update)) behind += 1;
+        },
+      };
+
+      const unsubscribe = () => subscribers.delete(subscriber);
+      call.on('cancelled', unsubscribe);
+      call.on('close', unsubscribe);
+      call.on('error', unsubscribe);
+      subscribers.add(subscriber);
+
+      // Synchronous, so no live update lands in the middle of the replay;
+      // a long replay doesn't count against the subscriber
+      const since = Number(call.request.sinceMs ?? 0);
+      if (since > 0) {
+        for (const transfer of relayer.transfers.updatedSince(since).filter(matches)) {
+          call.write(toUpdate(transfer, true));
+        }
+      }
+    },
+  });
+
+  const credentials = config.streamCertPath
+    ? grpc.ServerCredentials.createSsl(null, [{
+      cert_chain: fs.readFileSync(config.streamCertPath),
+      private_key: fs.readFileSync(config.streamKeyPath),
+    }])
+    : grpc.ServerCredentials.createInsecure();
+
+  await new Promise((resolve, reject) =>
+    server.bindAsync(`0.0.0.0:${config.streamPort}`, credentials, (error) =>
+      (error ? reject(error) : resolve())
+    )
+  );
+  logger.info(`Transfer stream listening on :${config.streamPort}`);
+  return server;
+}
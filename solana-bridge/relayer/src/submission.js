@@ -0,0 +1,162 @@
+/**
+ * Priority-fee aware submission of inbound deliveries
+ *
+ * During congestion a mint or unlock without a priority fee can sit
+ * unscheduled until its blockhash expires, and every retry used to go
+ * out just as cheap. The engine prices each attempt instead:
+ *
+ *   - fees: getRecentPrioritizationFees for the accounts the delivery
+ *     writes, at PRIORITY_FEE_PERCENTILE, cached for FEE_CACHE_MS
+ *   - escalation: attempt n pays that times PRIORITY_FEE_ESCALATION^(n-1),
+ *     between PRIORITY_FEE_MIN and PRIORITY_FEE_MAX (micro-lamports per
+ *     compute unit); an operator's `resubmit` fee is a floor, not a cap
+ *   - blockhashes: first attempts share one fetched at most
+ *     BLOCKHASH_MAX_AGE_MS ago; retries always fetch a fresh one, so a
+ *     retry never reuses a hash that may be about to expire
+ *
+ * It also keeps one submission in flight per transfer ID. The live
+ * listener and reconciliation can both reach the same transfer; the
+ * second waits on the first instead of racing it to a double send (the
+ * program would reject one, but only after it paid its fee).
+ *
+ * Counters go out with the ack metrics at GET /metrics.
+ */
+
+import { PublicKey } from '@solana/web3.js';
+
+// Recent fees per writable-account set stay good this long (ms)
+const FEE_CACHE_MS = 10000;
+
+// Reuse a blockhash for first attempts up to this age (ms); they live ~60s
+const BLOCKHASH_MAX_AGE_MS = 20000;
+
+// getRecentPrioritizationFees takes at most this many accounts
+const MAX_FEE_ACCOUNTS = 128;
+
+export default class SubmissionEngine {
+  constructor(connection, config, logger) {
+    this.connection = connection;
+    this.logger = logger;
+    this.percentile = config.priorityFeePercentile;
+    this.minFee = config.priorityFeeMin;
+    this.maxFee = config.priorityFeeMax;
+    this.escalation = config.priorityFeeEscalation;
+
+    // Recent fee by sorted writable keys: { fee, at }
+    this.fees = new Map();
+    this.latest = null;
+
+    // Submissions in flight by transfer ID
+    this.inFlight = new Map();
+
+    this.stats = {
+      submissions: 0,
+      deduplicated: 0,
+      escalations: 0,
+      blockhashRefreshes: 0,
+      lastFee: 0,
+    };
+  }
+
+  /**
+   * Run `submit` for `transferId` unless it's already in flight, in which
+   * case wait for (and return) that one's result
+   */
+  once(transferId, submit) {
+    const pending = this.inFlight.get(transferId);
+    if (pending) {
+      this.stats.deduplicated++;
+      this.logger.debug(`Submission for ${transferId} already in flight, waiting on it`);
+      return pending;
+    }
+
+    this.stats.submissions++;
+    const promise = Promise.resolve()
+      .then(submit)
+      .finally(() => this.inFlight.delete(transferId));
+    this.inFlight.set(transferId, promise);
+    return promise;
+  }
+
+  /**
+   * Micro-lamports per compute unit for `attempt` (from 1) of a
+   * transaction with `instructions`; 0 means no fee instruction
+   */
+  async computeUnitPrice(instructions, attempt, floor = 0) {
+    const writable = [...new Set(instructions.flatMap((ix) => ix.keys
+      .filter((key) => key.isWritable)
+      .map((key) => key.pubkey.toBase58())))].sort();
+    const cap = Math.max(this.maxFee, floor);
+    const base = Math.min(Math.max(await this.recentFee(writable), this.minFee, floor), cap);
+    const price = Math.min(Math.ceil(base * this.escalation ** (attempt - 1)), cap);
+
+    if (price > base) this.stats.escalations++;
+    this.stats.lastFee = price;
+    return price;
+  }
+
+  /**
+   * The configured percentile of recent prioritization fees paid to
+   * write `writable` (base58, sorted); 0 if the node can't say
+   */
+  async recentFee(writable) {
+    const key = writable.join(',');
+    const cached = this.fees.get(key);
+    if (cached && Date.now() - cached.at < FEE_CACHE_MS) return cached.fee;
+
+    let fee = 0;
+    try {
+      const recent = await this.connection.getRecentPrioritizationFees({
+        lockedWritableAccounts: writable.slice(0, MAX_FEE_ACCOUNTS).map((k) => new PublicKey(k)),
+      });
+      const paid = recent.map((sample) => sample.prioritizationFee).sort((a, b) => a - b);
+      if (paid.length > 0) {
+        fee = paid[Math.min(paid.length - 1, Math.floor((paid.length * this.percentile) / 100))];
+      }
+    } catch (error) {
+      this.logger.warn(`Could not read recent prioritization fees: ${error.message}`);
+    }
+    this.fees.set(key, { fee, at: Date.now() });
+    return fee;
+  }
+
+  /**
+   * A blockhash for `attempt` (from 1): shared and recent for a first
+   * attempt, always fresh for a retry
+   */
+  async blockhash(attempt) {
+    const stale = !this.latest || Date.now() - this.latest.at > BLOCKHASH_MAX_AGE_MS;
+    if (attempt > 1 || stale) {
+      const { blockhash, lastValidBlockHeight } = await this.connection.getLatestBlockhash();
+      this.latest = { blockhash, lastValidBlockHeight, at: Date.now() };
+      if (attempt > 1) this.stats.blockhashRefreshes++;
+    }
+    const { blockhash, lastValidBlockHeight } = this.latest;
+    return { blockhash, lastValidBlockHeight };
+  }
+
+  /**
+   * Prometheus text for GET /metrics (appended to the ack metrics)
+   */
+  metrics() {
+    const lines = [];
+    const metric = (name, type, help, value) => {
+      lines.push(`# HELP ${name} ${help}`, `# TYPE ${name} ${type}`, `${name} ${value}`);
+    };
+
+    metric('relayer_submissions_total', 'counter',
+      'Inbound deliveries submitted to Solana', this.stats.submissions);
+    metric('relayer_submissions_deduplicated_total', 'counter',
+      'Submissions that joined one already in flight for the transfer', this.stats.deduplicated);
+    metric('relayer_submission_in_flight', 'gauge',
+      'Transfers with a submission in flight', this.inFlight.size);
+    metric('relayer_priority_fee_escalations_total', 'counter',
+      'Retries sent with a higher compute-unit price', this.stats.escalations);
+    metric('relayer_priority_fee_micro_lamports', 'gauge',
+      'Compute-unit price of the latest attempt', this.stats.lastFee);
+    metric('relayer_blockhash_refreshes_total', 'counter',
+      'Fresh blockhashes fetched for retries', this.stats.blockhashRefreshes);
+
+    return `${lines.join('\n')}\n`;
+  }
+}
@@ -0,0 +1,144 @@
+/**
+ * Tracing: one trace per transfer, across stages and nodes
+ *
+ * Each stage of a delivery runs in an OpenTelemetry span:
+ *
+ *   relay            the whole delivery, per direction
+ *   ingest           the transfer was seen on its source chain
+ *   finality         waiting for the source chain to finalize it
+ *   attestation      attesting it on Solana, or collecting guardian or
+ *                    lock proof signatures (also on each attestor node)
+ *   submission       sending it to the destination and waiting for it
+ *   acknowledgement  acking the delivery back on the source chain
+ *
+ * The trace ID is the transfer ID's first 16 bytes, so every span of a
+ * transfer lands in the same trace whenever and wherever it was
+ * recorded (a retry an hour later, an attestor node) without passing
+ * context around: in Jaeger or Tempo, look up the trace by the transfer
+ * ID's first 32 hex digits. The trace's root is that derived ID, never
+ * exported, so viewers may flag the trace as missing its root span.
+ *
+ * Log lines written inside a stage carry `transferId` and `stage` (and
+ * the span's IDs while exporting) through the pino mixin, `logFields`.
+ *
+ * Spans are only exported with OTEL_EXPORTER_OTLP_ENDPOINT set (OTLP over
+ * HTTP; the other OTEL_* variables apply as usual). Without it they cost
+ * next to nothing, and the log fields still work.
+ */
+
+import { AsyncLocalStorage } from 'async_hooks';
+import { context, trace, SpanStatusCode, TraceFlags } from '@opentelemetry/api';
+
+export const STAGES = {
+  RELAY: 'relay',
+  INGEST: 'ingest',
+  FINALITY: 'finality',
+  ATTESTATION: 'attestation',
+  SUBMISSION: 'submission',
+  ACKNOWLEDGEMENT: 'acknowledgement',
+};
+
+const tracer = trace.getTracer('solana-bridge-relayer');
+
+// The transfer and stage the current async call chain works on
+const correlation = new AsyncLocalStorage();
+
+/**
+ * Start exporting spans if OTLP is configured; returns the SDK to shut
+ * down (flushing pending spans) on exit, or null
+ */
+export async function initTracing(config, logger) {
+  if (!config.otlpEndpoint) return null;
+
+  const { NodeSDK } = await import('@opentelemetry/sdk-node');
+  const { OTLPTraceExporter } = await import('@opentelemetry/exporter-trace-otlp-http');
+  const sdk = new NodeSDK({
+    serviceName: config.otelServiceName,
+    traceExporter: new OTLPTraceExporter(),
+  });
+  sdk.start();
+  logger.info(`Exporting traces to ${config.otlpEndpoint} as ${config.otelServiceName}`);
+  return sdk;
+}
+
+/**
+ * The transfer the current call chain works on, if any
+ */
+export function currentTransferId() {
+  return correlation.getStore()?.transferId;
+}
+
+/**
+ * Fields pino adds to every log line (its `mixin`)
+ */
+export function logFields() {
+  const store = correlation.getStore();
+  if (!store) return {};
+
+  const spanContext = trace.getActiveSpan()?.spanContext();
+  return {
+    transferId: store.transferId,
+    stage: store.stage,
+    ...(spanContext && { traceId: spanContext.traceId, spanId: spanContext.spanId }),
+  };
+}
+
+/**
+ * Context whose parent is the transfer's derived root
+ */
+function transferContext(transferId) {
+  return trace.setSpanContext(context.active(), {
+    traceId: transferId.slice(0, 32),
+    spanId: transferId.slice(32, 48),
+    traceFlags: TraceFlags.SAMPLED,
+    isRemote: true,
+  });
+}
+
+/**
+ * Run `fn(span)` as `stage` of the transfer with `transferId` (hex, with
+ * or without 0x)
+ *
+ * Nests under the current span when already inside that transfer;
+ * otherwise starts from the transfer's root. `fn` may be sync or async;
+ * a throw marks the span failed and propagates. Without a well-formed
+ * transfer ID (an attestor takes it from the request), just runs `fn`.
+ */
+export function withTransfer(transferId, stage, fn, attributes = {}) {
+  const id = transferId?.replace(/^0x/, '').toLowerCase();
+  if (!/^[0-9a-f]{64}$/.test(id ?? '')) return fn();
+
+  const parent = currentTransferId() === id ? context.active() : transferContext(id);
+  const options = { attributes: { 'bridge.transfer_id': id, 'bridge.stage': stage, ...attributes } };
+
+  return correlation.run({ transferId: id, stage }, () =>
+    tracer.startActiveSpan(`bridge.${stage}`, options, parent, (span) => {
+      const fail = (error) => {
+        span.recordException(error);
+        span.setStatus({ code: SpanStatusCode.ERROR, message: error.message });
+        span.end();
+        throw error;
+      };
+      try {
+        const result = fn(span);
+        if (typeof result?.then !== 'function') {
+          span.end();
+          return result;
+        }
+        return result.then((value) => {
+          span.end();
+          return value;
+        }, fail);
+      } catch (error) {
+        return fail(error);
+      }
+    })
+  );
+}
+
+/**
+ * Run `fn` as `stage` of the transfer the call chain is already on
+ */
+export function inStage(stage, fn) {
+  return withTransfer(currentTransferId(), stage, fn);
+}
@@ -0,0 +1,157 @@
+/**
+ * Transfer store
+ *
+ * Everything the relayer has seen, keyed by transfer ID, with its
+ * progress through the pipeline:
+ *
+ *   detected -> finalized -> submitted -> completed
+ *                   \-> reorged        \-> failed -> refunded
+ *
+ * An operator can mark any of them resolved (settled by hand), which
+ * reconciliation and stuck-transfer alerts leave alone.
+ *
+ * Outbound transfers use the program's transfer ID; inbound ones use
+ * the same message hash the program stores (transfer_message_hash), so
+ * an ID means the same thing on-chain and here.
+ *
+ * Kept in memory and snapshotted to a JSON file, so the status API
+ * survives restarts without a database server. Emits `status`
+ * (transfer, previousStatus) whenever a transfer changes status.
+ */
+
+import { EventEmitter } from 'events';
+import fs from 'fs';
+import path from 'path';
+
+// Snapshot at most this often (ms)
+const SNAPSHOT_INTERVAL = 5000;
+
+export const TRANSFER_STATUS = {
+  DETECTED: 'detected',
+  FINALIZED: 'finalized',
+  SUBMITTED: 'submitted',
+  COMPLETED: 'completed',
+  FAILED: 'failed',
+  REORGED: 'reorged',
+  RESOLVED: 'resolved',
+  REFUNDED: 'refunded',
+};
+
+class TransferStore extends EventEmitter {
+  constructor(filePath) {
+    super();
+    this.filePath = filePath;
+    this.transfers = new Map();
+    this.dirty = false;
+
+    if (filePath && fs.existsSync(filePath)) {
+      for (const transfer of JSON.parse(fs.readFileSync(filePath, 'utf-8'))) {
+        this.transfers.set(transfer.id, transfer);
+      }
+    }
+
+    if (filePath) {
+      this.timer = setInterval(() => this.snapshot(), SNAPSHOT_INTERVAL);
+      this.timer.unref();
+    }
+  }
+
+  /**
+   * Create or update a transfer; `fields` are merged in
+   */
+  upsert(id, fields) {
+    const now = new Date().toISOString();
+    const existing = this.transfers.get(id);
+    const transfer = existing
+      ? { ...existing, ...fields, updatedAt: now }
+      : { id, ...fields, createdAt: now, updatedAt: now };
+
+    if (existing && fields.status && fields.status !== existing.status) {
+      transfer.history = [...(existing.history || []), { status: fields.status, at: now }];
+    } else if (!existing) {
+      transfer.history = [{ status: transfer.status, at: now }];
+    }
+
+    this.transfers.set(id, transfer);
+    this.dirty = true;
+
+    if (transfer.status !== existing?.status) {
+      this.emit('status', transfer, existing?.status ?? null);
+    }
+    return transfer;
+  }
+
+  get(id) {
+    return this.transfers.get(id) || null;
+  }
+
+  /**
+   * Transfers sent or received by `address`, newest first
+   *
+   * EVM addresses match case-insensitively.
+   */
+  findByAddress(address) {
+    const needle = address.toLowerCase();
+    const matches = (value) => typeof value === 'string' && value.toLowerCase() === needle;
+
+    return [...this.transfers.values()]
+      .filter((t) => matches(t.sender) || matches(t.recipient))
+      .sort((a, b) => b.createdAt.localeCompare(a.createdAt));
+  }
+
+  /**
+   * Transfers updated at or after `time` (Unix ms), oldest update first
+   */
+  updatedSince(time) {
+    return [...this.transfers.values()]
+      .filter((t) => Date.parse(t.updatedAt) >= time)
+      .sort((a, b) => a.updatedAt.localeCompare(b.updatedAt));
+  }
+
+  /**
+   * Transfers not yet completed, failed, reorged, resolved or refunded
+   */
+  inFlight() {
+    const done = new Set([
+      TRANSFER_STATUS.COMPLETED,
+      TRANSFER_STATUS.FAILED,
+      TRANSFER_STATUS.REORGED,
+      TRANSFER_STATUS.RESOLVED,
+      TRANSFER_STATUS.REFUNDED,
+    ]);
+    return [...this.transfers.values()].filter((t) => !done.has(t.status));
+  }
+
+  stats() {
+    const byStatus = {};
+    const byDirection = {};
+    let lastActivity = null;
+
+    for (const transfer of this.transfers.values()) {
+      byStatus[transfer.status] = (byStatus[transfer.status] || 0) + 1;
+      byDirection[transfer.direction] = (byDirection[transfer.direction] || 0) + 1;
+      if (!lastActivity || transfer.updatedAt > lastActivity) {
+        lastActivity = transfer.updatedAt;
+      }
+    }
+
+    return { total: this.transfers.size, byStatus, byDirection, lastActivity };
+  }
+
+  snapshot() {
+    if (!this.dirty || !this.filePath) return;
+
+    fs.mkdirSync(path.dirname(this.filePath), { recursive: true });
+    const tmp = `${this.filePath}.tmp`;
+    fs.writeFileSync(tmp, JSON.stringify([...this.transfers.values()]));
+    fs.renameSync(tmp, this.filePath);
+    this.dirty = false;
+  }
+
+  close() {
+    clearInterval(this.timer);
+    this.snapshot();
+  }
+}
+
+export default TransferStore;
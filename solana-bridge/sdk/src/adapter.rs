@@ -0,0 +1,260 @@
+//! Bridge aggregator adapter
+//!
+//! Routers that pick a bridge per transfer (Li.Fi / Jumper style) need
+//! the same three things from every bridge they integrate:
+//!
+//! - quote:    what arrives for an amount; deterministic for a given slot
+//!   (simulates the program's `quote_lock`, nothing is sent)
+//! - initiate: ONE instruction that starts the transfer, to splice into
+//!   the router's own transaction (`lock`)
+//! - status:   where the transfer stands (`get_transfer_status`)
+//!
+//! `BridgeAdapter` is that interface and `SolanaBridgeAdapter` this
+//! bridge's implementation of it:
+//!
+//!   let adapter = SolanaBridgeAdapter::new(&rpc, payer).route(mint, TokenRoute { .. });
+//!   let quote = adapter.quote(&request).await?;
+//!   let Initiation { instruction, handle, .. } = adapter.initiate(&request).await?;
+//!   // ... send, then poll
+//!   let info = adapter.status(&handle).await?;
+//!
+//! The `lock` instruction's account layout is stable within an
+//! interface version (see `get_bridge_info`): user (signer, writable),
+//! bridge_state, chain_config, transfer_record, outbox, guardian_set,
+//! fee_tiers, user_stats, user_token, mint, token_config, fee_route,
+//! relay_route, throttle, fee_exemption, then the optional accounts
+//! (quote_signers, instructions, credential, bridge_pass, conversion_rate),
+//! bridge_token, fee_vault, bridge_authority, token_program,
+//! system_program, the optional receipt accounts and the optional
+//! integrator stats. Omitted optional accounts are passed as the
+//! program ID. Routers tag their locks with `integrator`.
+
+use std::collections::HashMap;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_bridge::{
+    accounts, instruction, LockQuote, TransferDirection, TransferRecord, TransferStatusInfo,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::budget;
+use crate::builder::{LockAccounts, TransferBuilder};
+use crate::error::{Error, Result};
+use crate::pda;
+use crate::status::{simulate_view, transfer_status};
+
+/// A transfer a router wants priced or started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteRequest {
+    /// Sender; signs the initiating transaction
+    pub user: Pubkey,
+    /// Sender's token account for `mint`
+    pub user_token: Pubkey,
+    pub mint: Pubkey,
+    pub amount: u64,
+    pub dest_chain_id: u64,
+    /// In the destination chain's format (0x... for EVM chains)
+    pub recipient: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteQuote {
+    pub amount: u64,
+    pub fee: u64,
+    /// Volume rebate tier applied (0 = none)
+    pub fee_tier: u8,
+    /// What crosses, before any Token-2022 transfer fee or share conversion
+    pub net_amount: u64,
+    pub dest_chain_id: u64,
+    /// Slot the quote was computed at
+    pub slot: u64,
+    /// Compute units to budget for the initiating instruction
+    pub compute_units: u32,
+}
+
+/// What `status` needs to find a transfer again
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransferHandle {
+    pub nonce: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Initiation {
+    pub instruction: Instruction,
+    /// Valid once the instruction lands; another lock landing first
+    /// takes the nonce and this one fails, so initiate again
+    pub handle: TransferHandle,
+    pub compute_units: u32,
+}
+
+/// Where the bridge keeps a token: the escrow and fee vault `lock` pays into
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TokenRoute {
+    pub bridge_token: Pubkey,
+    pub fee_vault: Pubkey,
+    /// SPL Token or Token-2022, whichever owns the mint
+    pub token_program: Pubkey,
+}
+
+// Adapters are driven from one client runtime; no Send bound is promised
+#[allow(async_fn_in_trait)]
+pub trait BridgeAdapter {
+    /// Price `request` without sending anything
+    async fn quote(&self, request: &RouteRequest) -> Result<RouteQuote>;
+
+    /// The single instruction that starts `request`
+    async fn initiate(&self, request: &RouteRequest) -> Result<Initiation>;
+
+    /// Where the transfer behind `handle` stands
+    async fn status(&self, handle: &TransferHandle) -> Result<TransferStatusInfo>;
+}
+
+/// `BridgeAdapter` for this bridge's Solana program
+pub struct SolanaBridgeAdapter<'a> {
+    rpc: &'a RpcClient,
+    payer: Pubkey,
+    routes: HashMap<Pubkey, TokenRoute>,
+    integrator_id: Option<u32>,
+}
+
+impl<'a> SolanaBridgeAdapter<'a> {
+    /// `payer` only has to exist: status lookups are simulated with it
+    pub fn new(rpc: &'a RpcClient, payer: Pubkey) -> Self {
+        Self {
+            rpc,
+            payer,
+            routes: HashMap::new(),
+            integrator_id: None,
+        }
+    }
+
+    /// Support `mint`, held in `route`'s escrow
+    pub fn route(mut self, mint: Pubkey, route: TokenRoute) -> Self {
+        self.routes.insert(mint, route);
+        self
+    }
+
+    /// Attribute the locks `initiate` builds to `integrator_id`
+    pub fn integrator(mut self, integrator_id: u32) -> Self {
+        self.integrator_id = Some(integrator_id);
+        self
+    }
+
+    fn token_route(&self, mint: &Pubkey) -> Result<&TokenRoute> {
+        self.routes
+            .get(mint)
+            .ok_or_else(|| Error::UnsupportedRoute(mint.to_string()))
+    }
+}
+
+impl BridgeAdapter for SolanaBridgeAdapter<'_> {
+    async fn quote(&self, request: &RouteRequest) -> Result<RouteQuote> {
+        self.token_route(&request.mint)?;
+
+        let program_id = solana_bridge::ID;
+        let instruction = Instruction {
+            program_id,
+            accounts: accounts::QuoteLockView {
+                bridge_state: pda::bridge_state(&program_id),
+                chain_config: pda::chain_config(&program_id, request.dest_chain_id),
+                token_config: pda::token_config(&program_id, &request.mint),
+                route_sequence: pda::route_sequence(
+                    &program_id,
+                    &request.mint,
+                    request.dest_chain_id,
+                ),
+                fee_route: pda::fee_route(&program_id, &request.mint, request.dest_chain_id),
+                fee_tiers: pda::fee_tiers(&program_id),
+                user_stats: pda::user_stats(&program_id, &request.user),
+                fee_exemption: pda::fee_exemption(&program_id, &request.user),
+                throttle: pda::throttle(&program_id),
+            }
+            .to_account_metas(None),
+            data: instruction::QuoteLock {
+                mint: request.mint,
+                amount: request.amount,
+                dest_chain_id: request.dest_chain_id,
+                user: request.user,
+            }
+            .data(),
+        };
+        let (quote, slot): (LockQuote, u64) =
+            simulate_view(self.rpc, &request.user, instruction).await?;
+
+        Ok(RouteQuote {
+            amount: quote.amount,
+            fee: quote.fee,
+            fee_tier: quote.fee_tier,
+            net_amount: quote.net_amount,
+            dest_chain_id: quote.dest_chain_id,
+            slot,
+            compute_units: budget::LOCK,
+        })
+    }
+
+    async fn initiate(&self, request: &RouteRequest) -> Result<Initiation> {
+        let route = self.token_route(&request.mint)?;
+        let accounts = LockAccounts {
+            user: request.user,
+            user_token: request.user_token,
+            mint: request.mint,
+            bridge_token: route.bridge_token,
+            fee_vault: route.fee_vault,
+            token_program: route.token_program,
+            bridge_pass: None,
+        };
+
+        let mut builder = TransferBuilder::new(self.rpc, request.user);
+        if let Some(integrator_id) = self.integrator_id {
+            builder = builder.integrator(integrator_id);
+        }
+        let builder = builder
+            .lock(
+                accounts,
+                request.amount,
+                request.dest_chain_id,
+                request.recipient.clone(),
+                None,
+            )
+            .await?;
+        let nonce = builder
+            .last_lock_nonce()
+            .ok_or_else(|| Error::Compile("lock added no instruction".to_string()))?;
+
+        Ok(Initiation {
+            instruction: builder.instructions()[0].clone(),
+            handle: TransferHandle { nonce },
+            compute_units: budget::LOCK,
+        })
+    }
+
+    async fn status(&self, handle: &TransferHandle) -> Result<TransferStatusInfo> {
+        let program_id = solana_bridge::ID;
+        let address = pda::outbound_transfer(&program_id, handle.nonce);
+
+        // The view checks the ID against the record; a missing record
+        // (not landed yet, or pruned) comes back with no status
+        let account = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment());
+        let transfer_id = match account.await?.value {
+            Some(account) => {
+                TransferRecord::try_deserialize(&mut &account.data[..])
+                    .map_err(|e| Error::Decode(e.to_string()))?
+                    .transfer_id
+            }
+            None => [0; 32],
+        };
+
+        transfer_status(
+            self.rpc,
+            &self.payer,
+            TransferDirection::Outbound,
+            handle.nonce,
+            transfer_id,
+        )
+        .await
+    }
+}
@@ -0,0 +1,50 @@
+//! Compute-unit budget per instruction
+//!
+//! Ceilings for the common case: SPL Token, no credential, no transfer
+//! hook, no deposit receipt, existing user stats and reward accounts.
+//! The transaction builder requests these when no limit is set, and the
+//! compute-unit benchmark (test-harness/tests/compute_units.rs) fails if
+//! an instruction goes over its budget, so a regression is caught before
+//! it reaches a cluster. Raise a budget only together with the benchmark
+//! output that justifies it.
+//!
+//! Outside the common case, add to the budget. The program checks what
+//! is left before a heavy optional step (metadata CPI, transfer hook,
+//! oracle read). Short of it, the program fails with InsufficientCompute,
+//! and its error context names the step and what it needs. A lock short
+//! on compute for its deposit receipt skips the receipt instead.
+//!
+//! | Instruction        | Budget                     |
+//! |--------------------|----------------------------|
+//! | lock               | 120,000                    |
+//! | lock_batch         | 60,000 + 45,000 per entry  |
+//! | mint               | 100,000                    |
+//! | burn               | 80,000                     |
+//! | unlock             | 100,000                    |
+//! | attest_outbound    | 30,000                     |
+//! | pin_recipient_name | 20,000                     |
+//! | set_transfer_route | 30,000                     |
+//! | claim              | 40,000                     |
+//! | assign_refund      | 35,000                     |
+//! | report_gas         | 25,000                     |
+
+pub const LOCK: u32 = 120_000;
+pub const LOCK_BATCH_BASE: u32 = 60_000;
+pub const LOCK_BATCH_ENTRY: u32 = 45_000;
+pub const MINT: u32 = 100_000;
+pub const BURN: u32 = 80_000;
+pub const UNLOCK: u32 = 100_000;
+pub const ATTEST_OUTBOUND: u32 = 30_000;
+pub const PIN_RECIPIENT_NAME: u32 = 20_000;
+pub const SET_TRANSFER_ROUTE: u32 = 30_000;
+pub const CLAIM: u32 = 40_000;
+pub const ASSIGN_REFUND: u32 = 35_000;
+pub const REPORT_GAS: u32 = 25_000;
+
+/// Most compute units a transaction can request
+pub const MAX: u32 = 1_400_000;
+
+/// Budget for a lock_batch of `entries` entries
+pub fn lock_batch(entries: usize) -> u32 {
+    LOCK_BATCH_BASE + LOCK_BATCH_ENTRY * entries as u32
+}
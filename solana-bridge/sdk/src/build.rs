@@ -0,0 +1,129 @@
+//! Is the deployed program the audited build?
+//!
+//!   // `solana-verify get-executable-hash` on a build you reproduced
+//!   // from the audited tag, or your auditor's published hash
+//!   let verified = parse_program_hash(&solana_verify_output)?;
+//!   let check = check_build(&rpc, &PROGRAM_ID).await?;
+//!   if !check.confirms(&verified) { /* refuse to integrate */ }
+//!
+//! `check_build` recomputes solana-verify's program hash from the
+//! ProgramData account (the executable with trailing zero bytes trimmed,
+//! SHA-256) and reads the BuildRecord governance keeps with
+//! `record_build`. `confirms` needs the deployed hash, the record and
+//! `verified` to agree, and the record to be for the current deploy: an
+//! upgrade makes it stale until governance records the new build.
+
+use anchor_lang::AccountDeserialize;
+use solana_bridge::BuildRecord;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::bpf_loader_upgradeable::UpgradeableLoaderState;
+use solana_sdk::hash::hash;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::pda;
+
+/// UpgradeableLoaderState::ProgramData's enum tag
+const PROGRAM_DATA_TAG: [u8; 4] = [3, 0, 0, 0];
+
+#[derive(Clone)]
+pub struct BuildCheck {
+    /// What governance recorded, if anything yet
+    pub record: Option<BuildRecord>,
+    /// solana-verify's hash of the executable deployed right now
+    pub deployed_hash: [u8; 32],
+    /// Slot of the last deploy or upgrade
+    pub deployed_slot: u64,
+}
+
+impl BuildCheck {
+    /// Governance's record is for this deploy and matches the deployed executable
+    pub fn recorded(&self) -> bool {
+        self.record
+            .as_ref()
+            .is_some_and(|r| r.is_current(self.deployed_slot) && r.hash == self.deployed_hash)
+    }
+
+    /// `recorded`, and the deployed executable is also `verified`
+    pub fn confirms(&self, verified: &[u8; 32]) -> bool {
+        self.recorded() && self.deployed_hash == *verified
+    }
+}
+
+/// The deployed executable's hash and deploy slot, with the program's build record
+pub async fn check_build(rpc: &RpcClient, program_id: &Pubkey) -> Result<BuildCheck> {
+    let data = rpc.get_account_data(&pda::program_data(program_id)).await?;
+    let (deployed_slot, deployed_hash) = program_hash(&data)?;
+
+    let record = rpc
+        .get_account_with_commitment(&pda::build_record(program_id), rpc.commitment())
+        .await?
+        .value
+        .map(|account| BuildRecord::try_deserialize(&mut &account.data[..]))
+        .transpose()
+        .map_err(|e| Error::Decode(e.to_string()))?;
+
+    Ok(BuildCheck {
+        record,
+        deployed_hash,
+        deployed_slot,
+    })
+}
+
+/// Deploy slot and solana-verify's program hash of a ProgramData account's data
+pub fn program_hash(program_data: &[u8]) -> Result<(u64, [u8; 32])> {
+    let metadata = UpgradeableLoaderState::size_of_programdata_metadata();
+    let executable = program_data
+        .get(metadata..)
+        .filter(|_| program_data.starts_with(&PROGRAM_DATA_TAG))
+        .ok_or_else(|| Error::Decode("not a ProgramData account".to_string()))?;
+
+    let slot = u64::from_le_bytes(program_data[4..12].try_into().unwrap());
+    let end = executable.iter().rposition(|&b| b != 0).map_or(0, |i| i + 1);
+    Ok((slot, hash(&executable[..end]).to_bytes()))
+}
+
+/**
+ * The hash in solana-verify's output
+ *
+ * `get-executable-hash` and `get-program-hash` print it as 64 hex
+ * digits, possibly after log lines; the last such token is taken.
+ */
+pub fn parse_program_hash(output: &str) -> Result<[u8; 32]> {
+    output
+        .split_whitespace()
+        .rev()
+        .find(|token| token.len() == 64 && token.bytes().all(|b| b.is_ascii_hexdigit()))
+        .and_then(|token| hex::decode(token).ok()?.try_into().ok())
+        .ok_or_else(|| Error::Decode("no program hash in solana-verify output".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ABC_SHA256: &str = "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad";
+
+    #[test]
+    fn hashes_the_executable_without_trailing_zeros() {
+        let mut data = PROGRAM_DATA_TAG.to_vec();
+        data.extend_from_slice(&42u64.to_le_bytes());
+        data.extend_from_slice(&[0; 33]);
+        data.extend_from_slice(b"abc");
+        data.extend_from_slice(&[0; 100]);
+
+        let (slot, digest) = program_hash(&data).unwrap();
+        assert_eq!(slot, 42);
+        assert_eq!(hex::encode(digest), ABC_SHA256);
+
+        data[0] = 2;
+        assert!(program_hash(&data).is_err());
+    }
+
+    #[test]
+    fn parses_solana_verify_output() {
+        let output = format!("Fetching program data...\n{ABC_SHA256}\n");
+        assert_eq!(hex::encode(parse_program_hash(&output).unwrap()), ABC_SHA256);
+        assert!(parse_program_hash("Program not found").is_err());
+    }
+}
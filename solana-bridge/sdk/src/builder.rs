@@ -0,0 +1,889 @@
+//! Transaction builder for bridge transfers
+//!
+//! Assembles lock, lock_batch and mint instructions with their PDAs,
+//! then wraps them with compute-budget instructions and compiles a v0
+//! message, optionally against address lookup tables:
+//!
+//!   let tx = TransferBuilder::new(&rpc, user.pubkey())
+//!       .priority_fee(PriorityFee::Percentile(75))
+//!       .lookup_table(alt)
+//!       .lock(accounts, amount, chain_ids::ETHEREUM, recipient, None).await?
+//!       .build_signed(&[&user]).await?;
+//!
+//! State the PDAs depend on (the route's sequence, the reward epoch) is
+//! read when the instruction is added, so build and send promptly:
+//! another lock of the same token to the same chain landing first takes
+//! the nonce and this one fails. A token can only be locked to a chain
+//! once its RouteSequence exists (see the program's `init_route_sequence`).
+
+use std::collections::HashMap;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use solana_bridge::{
+    accounts, chain_ids, instruction, ChainConfig, ChainKind, LockBatchEntry,
+    RelayerRewardConfig, RoutePayload, RouteSequence, TokenAccounting, TokenConfig,
+    TransferRecord, WalletDerivation,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::address_lookup_table::AddressLookupTableAccount;
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::VersionedTransaction;
+
+use crate::budget;
+use crate::claim_auth::SignedClaimAuthorization;
+use crate::error::{Error, Result};
+use crate::ethereum::EthAddress;
+use crate::pda;
+use crate::preflight::{self, Preflight};
+use crate::quote::SignedFeeQuote;
+use crate::refund_assign::SignedRefundAssignment;
+
+/// Most compute units a transaction can request
+pub const MAX_COMPUTE_UNITS: u32 = budget::MAX;
+
+/// How to price compute units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PriorityFee {
+    /// No compute-unit price instruction
+    None,
+    /// Fixed price in micro-lamports per compute unit
+    MicroLamports(u64),
+    /// Percentile (0-100) of recent fees paid for the writable accounts
+    Percentile(u8),
+}
+
+/// Token accounts for a lock
+#[derive(Debug, Clone, Copy)]
+pub struct LockAccounts {
+    pub user: Pubkey,
+    pub user_token: Pubkey,
+    pub mint: Pubkey,
+    /// Escrow owned by the bridge authority
+    pub bridge_token: Pubkey,
+    pub fee_vault: Pubkey,
+    /// SPL Token or Token-2022, whichever owns `mint`
+    pub token_program: Pubkey,
+    /// The user's bridge pass token account, if they hold one
+    pub bridge_pass: Option<Pubkey>,
+}
+
+/// Accounts for an inbound mint
+#[derive(Debug, Clone, Copy)]
+pub struct MintAccounts {
+    /// The owner/relayer submitting the mint (any payer with a guardian quorum)
+    pub authority: Pubkey,
+    pub user: Pubkey,
+    /// None when the bridge is in claim mode (the recipient picks it in `claim`)
+    pub user_token: Option<Pubkey>,
+    pub wrapped_mint: Pubkey,
+    /// Posted Wormhole VAA, for tokens on the WormholeVaa trust model
+    pub vaa: Option<Pubkey>,
+}
+
+pub struct TransferBuilder<'a> {
+    rpc: &'a RpcClient,
+    program_id: Pubkey,
+    payer: Pubkey,
+    instructions: Vec<Instruction>,
+    estimated_units: u32,
+    compute_unit_limit: Option<u32>,
+    priority_fee: PriorityFee,
+    lookup_tables: Vec<Pubkey>,
+    /// Sequences claimed by locks already added, by (mint, destination)
+    pending_sequences: HashMap<(Pubkey, u64), u64>,
+    /// Record of the last single lock added, for `pin_recipient_name`
+    last_lock_nonce: Option<u64>,
+    /// Tags the locks added (see `integrator`)
+    integrator_id: Option<u32>,
+    /// Where the single locks added send refunds (see `recovery`)
+    recovery: Option<Pubkey>,
+    /// Sealed memo for the next single lock added (see `memo`)
+    memo: Option<Vec<u8>>,
+    /// Derivation of the next single lock's recipient (see `smart_wallet`)
+    smart_wallet: Option<WalletDerivation>,
+    /// Price updates (token, SOL) to pay single locks' fees in SOL with
+    sol_fee_prices: Option<(Pubkey, Pubkey)>,
+    /// Price updates (destination native token, SOL) to prepay single
+    /// locks' delivery with
+    delivery_prices: Option<(Pubkey, Pubkey)>,
+}
+
+impl<'a> TransferBuilder<'a> {
+    pub fn new(rpc: &'a RpcClient, payer: Pubkey) -> Self {
+        Self {
+            rpc,
+            program_id: solana_bridge::ID,
+            payer,
+            instructions: Vec::new(),
+            estimated_units: 0,
+            compute_unit_limit: None,
+            priority_fee: PriorityFee::None,
+            lookup_tables: Vec::new(),
+            pending_sequences: HashMap::new(),
+            last_lock_nonce: None,
+            integrator_id: None,
+            recovery: None,
+            memo: None,
+            smart_wallet: None,
+            sol_fee_prices: None,
+            delivery_prices: None,
+        }
+    }
+
+    /// Target a different deployment of the program
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    /// Override the estimated compute-unit limit
+    pub fn compute_unit_limit(mut self, units: u32) -> Self {
+        self.compute_unit_limit = Some(units.min(MAX_COMPUTE_UNITS));
+        self
+    }
+
+    pub fn priority_fee(mut self, fee: PriorityFee) -> Self {
+        self.priority_fee = fee;
+        self
+    }
+
+    /// Compile against this address lookup table
+    pub fn lookup_table(mut self, address: Pubkey) -> Self {
+        self.lookup_tables.push(address);
+        self
+    }
+
+    /// Attribute the single locks added after this to `integrator_id`
+    /// (claimed with `register_integrator`)
+    pub fn integrator(mut self, integrator_id: u32) -> Self {
+        self.integrator_id = Some(integrator_id);
+        self
+    }
+
+    /// Refund the single locks added after this to `address`'s token
+    /// account rather than the user's, if they are reclaimed, cancelled or
+    /// fail on the destination
+    pub fn recovery(mut self, address: Pubkey) -> Self {
+        self.recovery = Some(address);
+        self
+    }
+
+    /// Attach `memo` to the next single lock added, as is; seal it to the
+    /// recipient's key first with `memo::seal`
+    pub fn memo(mut self, memo: Vec<u8>) -> Self {
+        self.memo = Some(memo);
+        self
+    }
+
+    /// Bridge the next single lock added to a smart wallet that may not be
+    /// deployed yet; its recipient must be the address `derivation` gives
+    /// (`EthAddress(derivation.address()?)`)
+    pub fn smart_wallet(mut self, derivation: WalletDerivation) -> Self {
+        self.smart_wallet = Some(derivation);
+        self
+    }
+
+    /// Pay the fees of the single locks added after this in SOL, at the
+    /// posted Pyth price updates of the token and of SOL (see `set_sol_fee`)
+    pub fn pay_fee_in_sol(mut self, token_price: Pubkey, sol_price: Pubkey) -> Self {
+        self.sol_fee_prices = Some((token_price, sol_price));
+        self
+    }
+
+    /// Prepay delivery on the destination for the single locks added after
+    /// this, at the posted Pyth price updates of its native token and of
+    /// SOL (see `set_delivery_fee`); the same SOL update as `pay_fee_in_sol`
+    pub fn prepay_delivery(mut self, native_price: Pubkey, sol_price: Pubkey) -> Self {
+        self.delivery_prices = Some((native_price, sol_price));
+        self
+    }
+
+    /**
+     * Add any other instruction (e.g. creating the user's token account)
+     *
+     * `compute_units` is its share of the estimated limit.
+     */
+    pub fn instruction(mut self, instruction: Instruction, compute_units: u32) -> Self {
+        self.instructions.push(instruction);
+        self.estimated_units += compute_units;
+        self
+    }
+
+    /**
+     * Lock `amount` for `recipient` on `dest_chain_id`
+     *
+     * With a `deadline` (unix time) the user can reclaim the lock if
+     * the relayer hasn't attested it by then. An EVM `recipient` must
+     * parse as an `EthAddress` (a bad EIP-55 checksum fails here) and is
+     * sent checksummed; resolve ENS names with `resolve_recipient` first.
+     *
+     * Transfer-hook mints need their extra accounts as remaining
+     * accounts; build that instruction by hand and add it with
+     * `instruction`.
+     */
+    pub async fn lock(
+        self,
+        accounts: LockAccounts,
+        amount: u64,
+        dest_chain_id: u64,
+        recipient: String,
+        deadline: Option<i64>,
+    ) -> Result<Self> {
+        self.push_lock(accounts, amount, dest_chain_id, recipient, deadline, None)
+            .await
+    }
+
+    /**
+     * Lock paying exactly the fee a relayer quoted (see quote.rs)
+     *
+     * Adds the quote's Ed25519 instruction right before the lock. Fails
+     * here if the quote isn't for this lock or its signature is bad.
+     */
+    pub async fn lock_with_quote(
+        self,
+        accounts: LockAccounts,
+        recipient: String,
+        deadline: Option<i64>,
+        quote: SignedFeeQuote,
+    ) -> Result<Self> {
+        if quote.quote.mint != accounts.mint {
+            return Err(Error::InvalidFeeQuote(
+                "quote is for another mint".to_string(),
+            ));
+        }
+        if !quote.verify() {
+            return Err(Error::InvalidFeeQuote("bad signature".to_string()));
+        }
+        let (amount, dest_chain_id) = (quote.quote.amount, quote.quote.dest_chain_id);
+        self.push_lock(
+            accounts,
+            amount,
+            dest_chain_id,
+            recipient,
+            deadline,
+            Some(quote),
+        )
+        .await
+    }
+
+    async fn push_lock(
+        mut self,
+        accounts: LockAccounts,
+        amount: u64,
+        dest_chain_id: u64,
+        recipient: String,
+        deadline: Option<i64>,
+        quote: Option<SignedFeeQuote>,
+    ) -> Result<Self> {
+        let chain: ChainConfig = self
+            .fetch(&pda::chain_config(&self.program_id, dest_chain_id))
+            .await?;
+        let recipient = match chain.kind {
+            ChainKind::Evm => recipient.parse::<EthAddress>()?.to_string(),
+            _ => recipient,
+        };
+
+        let nonce = self.next_nonce(&accounts.mint, dest_chain_id, 1).await?;
+        let metas = self
+            .lock_accounts(&accounts, dest_chain_id, Some(nonce), quote.is_some())
+            .await?;
+
+        if let Some(quote) = quote {
+            self.instructions.push(quote.verify_instruction());
+        }
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: metas,
+            data: instruction::Lock {
+                amount,
+                dest_chain_id,
+                recipient,
+                deadline,
+                integrator_id: self.integrator_id,
+                recovery: self.recovery,
+                memo: self.memo.take(),
+                smart_wallet: self.smart_wallet.take(),
+            }
+            .data(),
+        });
+        self.estimated_units += budget::LOCK;
+        self.last_lock_nonce = Some(nonce);
+        Ok(self)
+    }
+
+    /**
+     * Lock to many Ethereum recipients in one instruction
+     *
+     * Account-heavy: one transfer record per entry. Use a lookup table
+     * holding the static accounts to fit more entries. Recipients are
+     * checked and checksummed as in `lock`.
+     */
+    pub async fn lock_batch(
+        mut self,
+        accounts: LockAccounts,
+        mut entries: Vec<LockBatchEntry>,
+    ) -> Result<Self> {
+        for entry in &mut entries {
+            entry.recipient = entry.recipient.parse::<EthAddress>()?.to_string();
+        }
+
+        let first = self
+            .next_nonce(&accounts.mint, chain_ids::ETHEREUM, entries.len() as u64)
+            .await?;
+        let mut metas = self
+            .lock_accounts(&accounts, chain_ids::ETHEREUM, None, false)
+            .await?;
+        for nonce in first..first + entries.len() as u64 {
+            metas.push(AccountMeta::new(
+                pda::outbound_transfer(&self.program_id, nonce),
+                false,
+            ));
+        }
+
+        self.estimated_units += budget::lock_batch(entries.len());
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: metas,
+            data: instruction::LockBatch { entries }.data(),
+        });
+        Ok(self)
+    }
+
+    /**
+     * Pin the ENS name `name` resolved to onto the last lock added
+     *
+     * `user` is the lock's sender and must sign. The program can't check
+     * ENS, so the record shows the name as the sender's claim; the
+     * recipient address next to it is what the guardians attest.
+     */
+    pub fn pin_recipient_name(mut self, user: Pubkey, name: String) -> Result<Self> {
+        let nonce = self
+            .last_lock_nonce
+            .ok_or_else(|| Error::Compile("pin_recipient_name follows a lock".to_string()))?;
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: accounts::PinRecipientName {
+                signer: user,
+                bridge_state: pda::bridge_state(&self.program_id),
+                transfer_record: pda::outbound_transfer(&self.program_id, nonce),
+                sns_domain: None,
+            }
+            .to_account_metas(None),
+            data: instruction::PinRecipientName { name }.data(),
+        });
+        self.estimated_units += budget::PIN_RECIPIENT_NAME;
+        Ok(self)
+    }
+
+    /**
+     * Route the last lock added on past its destination chain
+     *
+     * `user` is the lock's sender and must sign. The program checks every
+     * chain in the route against its registry (passed here as remaining
+     * accounts) and the final recipient's format on the final chain.
+     */
+    pub fn route_transfer(mut self, user: Pubkey, route: RoutePayload) -> Result<Self> {
+        let nonce = self
+            .last_lock_nonce
+            .ok_or_else(|| Error::Compile("route_transfer follows a lock".to_string()))?;
+        let mut metas = accounts::SetTransferRoute {
+            sender: user,
+            bridge_state: pda::bridge_state(&self.program_id),
+            transfer_record: pda::outbound_transfer(&self.program_id, nonce),
+            transfer_route: pda::transfer_route(&self.program_id, nonce),
+            system_program: solana_sdk::system_program::ID,
+        }
+        .to_account_metas(None);
+        let chains = route.hops.iter().chain([&route.final_chain_id]);
+        metas.extend(chains.map(|chain_id| {
+            AccountMeta::new_readonly(pda::chain_config(&self.program_id, *chain_id), false)
+        }));
+
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: metas,
+            data: instruction::SetTransferRoute { route }.data(),
+        });
+        self.estimated_units += budget::SET_TRANSFER_ROUTE;
+        Ok(self)
+    }
+
+    /**
+     * Claim an inbound transfer the bridge delivered in claim mode
+     *
+     * `claimer` signs. The recipient may claim into any token account of
+     * the wrapped mint; anyone else must pass one the recipient owns
+     * (creating their associated token account first if needed).
+     */
+    pub async fn claim(self, claimer: Pubkey, nonce: u64, recipient_token: Pubkey) -> Result<Self> {
+        self.push_claim(claimer, nonce, recipient_token, None).await
+    }
+
+    /**
+     * Claim on the recipient's behalf, with their signed authorization
+     *
+     * Adds the authorization's Ed25519 instruction right before the
+     * claim, which mints into the account the recipient signed for.
+     * Fails here if it isn't the recipient's or its signature is bad.
+     */
+    pub async fn claim_delegated(
+        self,
+        claimer: Pubkey,
+        nonce: u64,
+        authorization: SignedClaimAuthorization,
+    ) -> Result<Self> {
+        if !authorization.verify() {
+            return Err(Error::InvalidClaimAuthorization("bad signature".to_string()));
+        }
+        let recipient_token = authorization.authorization.recipient_token;
+        self.push_claim(claimer, nonce, recipient_token, Some(authorization))
+            .await
+    }
+
+    async fn push_claim(
+        mut self,
+        claimer: Pubkey,
+        nonce: u64,
+        recipient_token: Pubkey,
+        authorization: Option<SignedClaimAuthorization>,
+    ) -> Result<Self> {
+        let program_id = self.program_id;
+        let transfer_record = pda::inbound_transfer(&program_id, nonce);
+        let record: TransferRecord = self.fetch(&transfer_record).await?;
+
+        if let Some(authorization) = &authorization {
+            if authorization.signer != record.local_account
+                || authorization.authorization.transfer_id != record.transfer_id
+            {
+                return Err(Error::InvalidClaimAuthorization(
+                    "authorization is for another transfer".to_string(),
+                ));
+            }
+            self.instructions.push(authorization.verify_instruction());
+        }
+
+        self.instructions.push(Instruction {
+            program_id,
+            accounts: accounts::Claim {
+                claimer,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record,
+                guardian_council: pda::guardian_council(&program_id),
+                wrapped_mint: record.mint,
+                token_config: pda::token_config(&program_id, &record.mint),
+                recipient_token,
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: anchor_spl::token::ID,
+                instructions: authorization.map(|_| sysvar::instructions::ID),
+            }
+            .to_account_metas(None),
+            data: instruction::Claim { transfer_id: record.transfer_id }.data(),
+        });
+        self.estimated_units += budget::CLAIM;
+        Ok(self)
+    }
+
+    /**
+     * Buy the refund right of stuck outbound `nonce`
+     *
+     * `assignee` signs and pays the assignment's price from
+     * `assignee_token` to `holder_token`, which the refund owner must
+     * own. Adds the assignment's Ed25519 instruction right before
+     * assign_refund; fails here if it isn't the refund owner's, is for
+     * another transfer or its signature is bad.
+     */
+    pub async fn assign_refund(
+        mut self,
+        assignee: Pubkey,
+        nonce: u64,
+        assignee_token: Pubkey,
+        holder_token: Pubkey,
+        assignment: SignedRefundAssignment,
+    ) -> Result<Self> {
+        if !assignment.verify() {
+            return Err(Error::InvalidRefundAssignment("bad signature".to_string()));
+        }
+        let program_id = self.program_id;
+        let transfer_record = pda::outbound_transfer(&program_id, nonce);
+        let record: TransferRecord = self.fetch(&transfer_record).await?;
+        if assignment.signer != record.refund_owner()
+            || assignment.assignment.transfer_id != record.transfer_id
+        {
+            return Err(Error::InvalidRefundAssignment(
+                "assignment is for another transfer or holder".to_string(),
+            ));
+        }
+        let token_program = self.rpc.get_account(&record.mint).await?.owner;
+
+        self.instructions.push(assignment.verify_instruction());
+        self.instructions.push(Instruction {
+            program_id,
+            accounts: accounts::AssignRefund {
+                assignee,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record,
+                mint: record.mint,
+                token_config: pda::token_config(&program_id, &record.mint),
+                assignee_token,
+                holder_token,
+                token_program,
+                instructions: sysvar::instructions::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::AssignRefund { nonce }.data(),
+        });
+        self.estimated_units += budget::ASSIGN_REFUND;
+        Ok(self)
+    }
+
+    /**
+     * Mint wrapped tokens for an inbound transfer (owner/relayer only)
+     */
+    pub async fn mint(self, accounts: MintAccounts, amount: u64, nonce: u64) -> Result<Self> {
+        self.mint_with_guardians(accounts, amount, nonce, &[]).await
+    }
+
+    /**
+     * Mint authorized by a guardian quorum instead of the owner
+     *
+     * `guardians` become required signers of the transaction; collect
+     * their signatures over the built message before sending.
+     */
+    pub async fn mint_with_guardians(
+        mut self,
+        accounts: MintAccounts,
+        amount: u64,
+        nonce: u64,
+        guardians: &[Pubkey],
+    ) -> Result<Self> {
+        let program_id = self.program_id;
+        let reward_config_address = pda::reward_config(&program_id);
+        let reward_config: RelayerRewardConfig = self.fetch(&reward_config_address).await?;
+        let epoch = reward_config.epoch_at(self.cluster_time().await?);
+
+        let mut metas = accounts::MintWrapped {
+            user: accounts.user,
+            authority: accounts.authority,
+            bridge_state: pda::bridge_state(&program_id),
+            transfer_record: pda::inbound_transfer(&program_id, nonce),
+            inbox: pda::inbox(&program_id, chain_ids::ETHEREUM),
+            source_chain: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+            guardian_council: pda::guardian_council(&program_id),
+            guardian_set: pda::guardian_set(&program_id),
+            reward_config: reward_config_address,
+            reward_epoch: pda::reward_epoch(&program_id, epoch),
+            relayer_stats: pda::relayer_stats(&program_id, epoch, &accounts.authority),
+            wrapped_mint: accounts.wrapped_mint,
+            token_config: pda::token_config(&program_id, &accounts.wrapped_mint),
+            credential: None,
+            vaa: accounts.vaa,
+            user_token: accounts.user_token,
+            bridge_authority: pda::bridge_authority(&program_id),
+            token_program: anchor_spl::token::ID,
+            system_program: solana_sdk::system_program::ID,
+        }
+        .to_account_metas(None);
+        metas.extend(
+            guardians
+                .iter()
+                .map(|guardian| AccountMeta::new_readonly(*guardian, true)),
+        );
+
+        self.instructions.push(Instruction {
+            program_id,
+            accounts: metas,
+            data: instruction::Mint { amount, nonce }.data(),
+        });
+        self.estimated_units += budget::MINT;
+        Ok(self)
+    }
+
+    /**
+     * Report the lamports `relayer` paid delivering inbound `nonce`
+     *
+     * Goes after the mint or unlock (or in a later transaction); only the
+     * relayer that delivered the transfer can report it, once.
+     */
+    pub fn report_gas(mut self, relayer: Pubkey, nonce: u64, gas_paid: u64) -> Self {
+        self.instructions.push(Instruction {
+            program_id: self.program_id,
+            accounts: accounts::ReportGas {
+                relayer,
+                bridge_state: pda::bridge_state(&self.program_id),
+                reward_config: pda::reward_config(&self.program_id),
+                transfer_record: pda::inbound_transfer(&self.program_id, nonce),
+                gas_report: pda::gas_report(&self.program_id, nonce),
+                gas_reimbursement: pda::gas_reimbursement(&self.program_id, &relayer),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            data: instruction::ReportGas { nonce, gas_paid }.data(),
+        });
+        self.estimated_units += budget::REPORT_GAS;
+        self
+    }
+
+    /// Instructions added so far, without the compute-budget ones
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Nonce of the last single lock added, if any
+    pub fn last_lock_nonce(&self) -> Option<u64> {
+        self.last_lock_nonce
+    }
+
+    /**
+     * Compile the unsigned v0 message
+     */
+    pub async fn build(&self) -> Result<VersionedMessage> {
+        let units = self
+            .compute_unit_limit
+            .unwrap_or(self.estimated_units.min(MAX_COMPUTE_UNITS));
+        self.compile(units).await
+    }
+
+    /**
+     * Compile and sign with a fresh blockhash
+     */
+    pub async fn build_signed(&self, signers: &[&dyn Signer]) -> Result<VersionedTransaction> {
+        let message = self.build().await?;
+        VersionedTransaction::try_new(message, signers).map_err(|e| Error::Signing(e.to_string()))
+    }
+
+    /**
+     * Simulate the transaction under the maximum compute limit
+     *
+     * Fails with a `BridgeError` if it would revert. On success, pass
+     * `suggested_compute_unit_limit()` to `compute_unit_limit` before
+     * building, so the priority fee isn't paid on unused units.
+     */
+    pub async fn preflight(&self) -> Result<Preflight> {
+        let message = self.compile(MAX_COMPUTE_UNITS).await?;
+        preflight::preflight(self.rpc, &preflight::unsigned(message)).await
+    }
+
+    async fn compile(&self, units: u32) -> Result<VersionedMessage> {
+        let mut instructions = vec![ComputeBudgetInstruction::set_compute_unit_limit(units)];
+        if let Some(price) = self.compute_unit_price().await? {
+            instructions.push(ComputeBudgetInstruction::set_compute_unit_price(price));
+        }
+        instructions.extend(self.instructions.iter().cloned());
+
+        let mut lookup_tables = Vec::with_capacity(self.lookup_tables.len());
+        for address in &self.lookup_tables {
+            let account = self.rpc.get_account(address).await?;
+            let table = AddressLookupTable::deserialize(&account.data)
+                .map_err(|e| Error::Decode(e.to_string()))?;
+            lookup_tables.push(AddressLookupTableAccount {
+                key: *address,
+                addresses: table.addresses.to_vec(),
+            });
+        }
+
+        let blockhash = self.rpc.get_latest_blockhash().await?;
+        let message =
+            v0::Message::try_compile(&self.payer, &instructions, &lookup_tables, blockhash)
+                .map_err(|e| Error::Compile(e.to_string()))?;
+        Ok(VersionedMessage::V0(message))
+    }
+
+    /// Accounts shared by lock and lock_batch
+    async fn lock_accounts(
+        &self,
+        accounts: &LockAccounts,
+        dest_chain_id: u64,
+        nonce: Option<u64>,
+        quoted: bool,
+    ) -> Result<Vec<AccountMeta>> {
+        let program_id = self.program_id;
+        let token_config_address = pda::token_config(&program_id, &accounts.mint);
+        let token_config: TokenConfig = self.fetch(&token_config_address).await?;
+        let conversion_rate = (token_config.accounting == TokenAccounting::Shares)
+            .then(|| pda::conversion_rate(&program_id, &accounts.mint));
+
+        let bridge_state = pda::bridge_state(&program_id);
+        let chain_config = pda::chain_config(&program_id, dest_chain_id);
+        let outbox = pda::outbox(&program_id);
+        let guardian_set = pda::guardian_set(&program_id);
+        let fee_tiers = pda::fee_tiers(&program_id);
+        let fee_route = pda::fee_route(&program_id, &accounts.mint, dest_chain_id);
+        let relay_route = pda::relay_route(&program_id, &accounts.mint, dest_chain_id);
+        let throttle = pda::throttle(&program_id);
+        let price_breaker = pda::price_breaker(&program_id, &accounts.mint);
+        let event_history = pda::event_history(&program_id, &accounts.mint);
+        let route_sequence = pda::route_sequence(&program_id, &accounts.mint, dest_chain_id);
+        let user_stats = pda::user_stats(&program_id, &accounts.user);
+        let fee_exemption = pda::fee_exemption(&program_id, &accounts.user);
+        let terms_config = pda::terms_config(&program_id);
+        let terms_acceptance = pda::terms_acceptance(&program_id, &accounts.user);
+        let sender_allowlist = pda::sender_allowlist(&program_id);
+        let allowed_sender = pda::allowed_sender(&program_id, &accounts.user);
+        let withdrawal_allowlist = pda::withdrawal_allowlist(&program_id, &accounts.user);
+        let bridge_authority = pda::bridge_authority(&program_id);
+
+        let metas = match nonce {
+            Some(nonce) => accounts::Lock {
+                user: accounts.user,
+                bridge_state,
+                chain_config,
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                outbox,
+                guardian_set,
+                fee_tiers,
+                user_stats,
+                user_token: accounts.user_token,
+                mint: accounts.mint,
+                token_config: token_config_address,
+                fee_route,
+                relay_route,
+                throttle,
+                price_breaker,
+                event_history,
+                route_sequence,
+                fee_exemption,
+                terms_config,
+                terms_acceptance,
+                sender_allowlist,
+                allowed_sender,
+                withdrawal_allowlist,
+                quote_signers: quoted.then(|| pda::guardian_set(&program_id)),
+                instructions: quoted.then_some(sysvar::instructions::ID),
+                credential: None,
+                bridge_pass: accounts.bridge_pass,
+                conversion_rate,
+                bridge_token: accounts.bridge_token,
+                fee_vault: accounts.fee_vault,
+                bridge_authority,
+                token_program: accounts.token_program,
+                system_program: solana_sdk::system_program::ID,
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+                associated_token_program: None,
+                integrator: self.integrator_id.map(|id| pda::integrator(&program_id, id)),
+                sol_fee_config: self
+                    .sol_fee_prices
+                    .map(|_| pda::sol_fee_config(&program_id, &accounts.mint)),
+                sol_fee_vault: self.sol_fee_prices.map(|_| pda::sol_fee_vault(&program_id)),
+                token_price: self.sol_fee_prices.map(|(token_price, _)| token_price),
+                sol_price: self
+                    .sol_fee_prices
+                    .or(self.delivery_prices)
+                    .map(|(_, sol_price)| sol_price),
+                delivery_fee_config: self
+                    .delivery_prices
+                    .map(|_| pda::delivery_fee_config(&program_id, dest_chain_id)),
+                reward_vault: self.delivery_prices.map(|_| pda::reward_vault(&program_id)),
+                native_price: self.delivery_prices.map(|(native_price, _)| native_price),
+                transfer_memo: self.memo.as_ref().map(|_| pda::transfer_memo(&program_id, nonce)),
+                smart_wallet: self.smart_wallet.map(|_| pda::smart_wallet(&program_id, nonce)),
+            }
+            .to_account_metas(None),
+            None => accounts::LockBatch {
+                user: accounts.user,
+                bridge_state,
+                chain_config,
+                outbox,
+                guardian_set,
+                fee_tiers,
+                user_stats,
+                user_token: accounts.user_token,
+                mint: accounts.mint,
+                token_config: token_config_address,
+                fee_route,
+                relay_route,
+                throttle,
+                price_breaker,
+                event_history,
+                route_sequence,
+                fee_exemption,
+                terms_config,
+                terms_acceptance,
+                sender_allowlist,
+                allowed_sender,
+                withdrawal_allowlist,
+                credential: None,
+                bridge_pass: accounts.bridge_pass,
+                conversion_rate,
+                bridge_token: accounts.bridge_token,
+                fee_vault: accounts.fee_vault,
+                bridge_authority,
+                token_program: accounts.token_program,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+        };
+        Ok(metas)
+    }
+
+    /// Nonce the next lock of `mint` to `dest_chain_id` added to this
+    /// transaction will get, claiming `count` from there
+    async fn next_nonce(&mut self, mint: &Pubkey, dest_chain_id: u64, count: u64) -> Result<u64> {
+        let address = pda::route_sequence(&self.program_id, mint, dest_chain_id);
+        let exists = self
+            .rpc
+            .get_account_with_commitment(&address, self.rpc.commitment())
+            .await?
+            .value
+            .is_some();
+        if !exists {
+            return Err(Error::UnsupportedRoute(format!(
+                "{mint} to chain {dest_chain_id} (no route sequence yet)"
+            )));
+        }
+        let sequence: RouteSequence = self.fetch(&address).await?;
+        let pending = self.pending_sequences.entry((*mint, dest_chain_id)).or_default();
+        let nonce = RouteSequence::nonce_at(sequence.shard, sequence.last + *pending + 1);
+        *pending += count;
+        Ok(nonce)
+    }
+
+    async fn compute_unit_price(&self) -> Result<Option<u64>> {
+        match self.priority_fee {
+            PriorityFee::None => Ok(None),
+            PriorityFee::MicroLamports(price) => Ok(Some(price)),
+            PriorityFee::Percentile(percentile) => {
+                let writable: Vec<Pubkey> = self
+                    .instructions
+                    .iter()
+                    .flat_map(|ix| ix.accounts.iter())
+                    .filter(|meta| meta.is_writable)
+                    .map(|meta| meta.pubkey)
+                    .collect();
+                let mut fees: Vec<u64> = self
+                    .rpc
+                    .get_recent_prioritization_fees(&writable)
+                    .await?
+                    .into_iter()
+                    .map(|fee| fee.prioritization_fee)
+                    .collect();
+                if fees.is_empty() {
+                    return Ok(None);
+                }
+                fees.sort_unstable();
+                let index = (fees.len() - 1) * percentile.min(100) as usize / 100;
+                Ok(Some(fees[index]))
+            }
+        }
+    }
+
+    async fn cluster_time(&self) -> Result<i64> {
+        let account = self.rpc.get_account(&sysvar::clock::ID).await?;
+        let clock: Clock = solana_sdk::account::from_account(&account)
+            .ok_or_else(|| Error::Decode("clock sysvar".to_string()))?;
+        Ok(clock.unix_timestamp)
+    }
+
+    async fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<T> {
+        let data = self.rpc.get_account_data(address).await?;
+        T::try_deserialize(&mut data.as_slice()).map_err(|e| Error::Decode(e.to_string()))
+    }
+}
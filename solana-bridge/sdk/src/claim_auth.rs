@@ -0,0 +1,49 @@
+//! Signed claim authorizations
+//!
+//! The recipient of a claimable transfer signs one with their wallet to
+//! let a claim service execute the claim into the account they chose;
+//! the service passes it to `TransferBuilder::claim_delegated`. The
+//! tokens can only go to that account, so the service never has custody.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::quote::ed25519_instruction;
+
+pub use solana_bridge::claim_auth::{ClaimAuthorization, CLAIM_AUTH_LEN};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedClaimAuthorization {
+    pub authorization: ClaimAuthorization,
+    /// The transfer's recipient
+    pub signer: Pubkey,
+    pub signature: Signature,
+}
+
+impl SignedClaimAuthorization {
+    /// Sign `authorization` with the recipient's wallet
+    pub fn sign(authorization: ClaimAuthorization, recipient: &dyn Signer) -> Self {
+        SignedClaimAuthorization {
+            authorization,
+            signer: recipient.pubkey(),
+            signature: recipient.sign_message(&authorization.to_bytes()),
+        }
+    }
+
+    /// Does the signature check out? (the program can't tell you why it doesn't)
+    pub fn verify(&self) -> bool {
+        self.signature
+            .verify(self.signer.as_ref(), &self.authorization.to_bytes())
+    }
+
+    /**
+     * Ed25519 program instruction checking the signature
+     *
+     * Must come right before the claim.
+     */
+    pub fn verify_instruction(&self) -> Instruction {
+        ed25519_instruction(&self.signer, &self.signature, &self.authorization.to_bytes())
+    }
+}
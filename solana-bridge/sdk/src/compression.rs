@@ -0,0 +1,161 @@
+//! Compressed transfer records
+//!
+//! Finished transfer records can move out of their PDAs into a concurrent
+//! Merkle tree (see the program's compression.rs). This module sizes and
+//! creates the tree account, builds the compress and verify instructions,
+//! and rebuilds a tree from its `TransferRecordCompressed` events to
+//! prove a record, the way the indexer serves proofs.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_bridge::compression::{COMPRESSION_PROGRAM, NOOP_PROGRAM};
+use solana_bridge::{accounts, instruction};
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::keccak;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::system_instruction;
+
+use crate::pda;
+
+/// Tree account header: account type, version, then the V1 header
+/// (max_buffer_size, max_depth, authority, creation_slot, padding)
+const HEADER_SIZE: usize = 2 + 4 + 4 + 32 + 8 + 6;
+
+/**
+ * Bytes a tree account needs
+ *
+ * A larger `canopy_depth` stores the top of the tree on-chain, so
+ * proofs passed to `verify_compressed_transfer` get shorter.
+ */
+pub fn tree_account_size(max_depth: u32, max_buffer_size: u32, canopy_depth: u32) -> usize {
+    let depth = max_depth as usize;
+    // root, path, index and padding
+    let changelog = 32 + 32 * depth + 4 + 4;
+    // proof, leaf, index and padding
+    let rightmost_path = 32 * depth + 32 + 4 + 4;
+    let tree = 8 + 8 + 8 + max_buffer_size as usize * changelog + rightmost_path;
+    let canopy = ((1usize << (canopy_depth + 1)) - 2) * 32;
+    HEADER_SIZE + tree + canopy
+}
+
+/**
+ * Allocate a tree account for `init_compressed_store`
+ *
+ * `tree` must sign the transaction. `lamports` is the rent exemption for
+ * `tree_account_size`.
+ */
+pub fn create_tree(
+    payer: &Pubkey,
+    tree: &Pubkey,
+    max_depth: u32,
+    max_buffer_size: u32,
+    canopy_depth: u32,
+    lamports: u64,
+) -> Instruction {
+    let space = tree_account_size(max_depth, max_buffer_size, canopy_depth);
+    system_instruction::create_account(payer, tree, lamports, space as u64, &COMPRESSION_PROGRAM)
+}
+
+/// `compress_transfer_record` for the record at `transfer_record`
+pub fn compress(
+    program_id: &Pubkey,
+    owner: &Pubkey,
+    tree: &Pubkey,
+    transfer_record: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts: accounts::CompressTransferRecord {
+            owner: *owner,
+            bridge_state: pda::bridge_state(program_id),
+            compressed_store: pda::compressed_store(program_id),
+            merkle_tree: *tree,
+            tree_authority: pda::tree_authority(program_id),
+            compression_program: COMPRESSION_PROGRAM,
+            noop_program: NOOP_PROGRAM,
+            transfer_record: *transfer_record,
+        }
+        .to_account_metas(None),
+        data: instruction::CompressTransferRecord {}.data(),
+    }
+}
+
+/// A compressed record and what proves it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedProof {
+    pub tree: Pubkey,
+    pub leaf_index: u32,
+    /// Borsh-serialized TransferRecord
+    pub record: Vec<u8>,
+    pub root: [u8; 32],
+    /// Sibling nodes from the leaf up
+    pub proof: Vec<[u8; 32]>,
+}
+
+impl CompressedProof {
+    /**
+     * `verify_compressed_transfer` for this proof
+     *
+     * With a canopy, drop the top `canopy_depth` nodes of `proof` first.
+     */
+    pub fn verify_instruction(&self, program_id: &Pubkey) -> Instruction {
+        let mut metas = accounts::VerifyCompressedTransfer {
+            bridge_state: pda::bridge_state(program_id),
+            merkle_tree: self.tree,
+            compression_program: COMPRESSION_PROGRAM,
+        }
+        .to_account_metas(None);
+        metas.extend(
+            self.proof
+                .iter()
+                .map(|node| AccountMeta::new_readonly(Pubkey::new_from_array(*node), false)),
+        );
+
+        Instruction {
+            program_id: *program_id,
+            accounts: metas,
+            data: instruction::VerifyCompressedTransfer {
+                record: self.record.clone(),
+                root: self.root,
+                leaf_index: self.leaf_index,
+            }
+            .data(),
+        }
+    }
+}
+
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    keccak::hashv(&[left, right]).to_bytes()
+}
+
+/**
+ * Root and proof of `leaves[index]` in a tree of `max_depth`
+ *
+ * `leaves` are the tree's leaves in order (from the events'
+ * `leaf_index`); the rest of the tree is empty, as in the compression
+ * program: empty leaves are zero and an empty node hashes two empty
+ * children. None if `index` is out of range.
+ */
+pub fn prove(
+    leaves: &[[u8; 32]],
+    index: usize,
+    max_depth: u32,
+) -> Option<([u8; 32], Vec<[u8; 32]>)> {
+    if index >= leaves.len() {
+        return None;
+    }
+
+    let mut empty = [0u8; 32];
+    let mut level = leaves.to_vec();
+    let mut position = index;
+    let mut proof = Vec::with_capacity(max_depth as usize);
+    for _ in 0..max_depth {
+        proof.push(level.get(position ^ 1).copied().unwrap_or(empty));
+        level = level
+            .chunks(2)
+            .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&empty)))
+            .collect();
+        empty = hash_pair(&empty, &empty);
+        position /= 2;
+    }
+    Some((level[0], proof))
+}
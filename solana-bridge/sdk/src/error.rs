@@ -0,0 +1,76 @@
+//! SDK errors
+
+use solana_client::client_error::ClientError;
+use solana_client::nonblocking::pubsub_client::PubsubClientError;
+
+use crate::preflight::BridgeError;
+
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    // Boxed: the client errors are hundreds of bytes, and every Result
+    // would carry that much
+    #[error("RPC error: {0}")]
+    Rpc(Box<ClientError>),
+
+    #[error("WebSocket error: {0}")]
+    Pubsub(Box<PubsubClientError>),
+
+    #[error("Transaction {0} not found")]
+    TransactionNotFound(String),
+
+    #[error("Could not decode: {0}")]
+    Decode(String),
+
+    #[error("Could not compile message: {0}")]
+    Compile(String),
+
+    #[error("Could not sign transaction: {0}")]
+    Signing(String),
+
+    #[error(transparent)]
+    Bridge(#[from] BridgeError),
+
+    #[error("Simulation failed: {0}")]
+    Simulation(String),
+
+    #[error("Invalid Ethereum address {0}")]
+    InvalidEthAddress(String),
+
+    #[error("ENS: {0}")]
+    Ens(String),
+
+    #[error("SNS: {0}")]
+    Sns(String),
+
+    #[error("Fee quote: {0}")]
+    InvalidFeeQuote(String),
+
+    #[error("Claim authorization: {0}")]
+    InvalidClaimAuthorization(String),
+
+    #[error("Refund assignment: {0}")]
+    InvalidRefundAssignment(String),
+
+    #[error("Memo: {0}")]
+    Memo(String),
+
+    #[error("No route for {0}")]
+    UnsupportedRoute(String),
+
+    #[error("Route profile: {0}")]
+    Profile(String),
+}
+
+impl From<ClientError> for Error {
+    fn from(error: ClientError) -> Self {
+        Error::Rpc(Box::new(error))
+    }
+}
+
+impl From<PubsubClientError> for Error {
+    fn from(error: PubsubClientError) -> Self {
+        Error::Pubsub(Box::new(error))
+    }
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,255 @@
+//! Ethereum recipients: EIP-55 addresses and ENS names
+//!
+//! The program only checks that an EVM recipient is 42 characters, so a
+//! typo'd address locks funds for an account nobody controls. Parse
+//! recipients here before building a lock:
+//!
+//!   let ens = EnsResolver::new(&ethereum_rpc_url);
+//!   let to = resolve_recipient("vitalik.eth", Some(&ens)).await?;
+//!   builder.lock(accounts, amount, chain_ids::ETHEREUM, to.to_string(), None).await?
+//!
+//! All-lowercase and all-uppercase addresses carry no checksum and are
+//! taken as they are; mixed case must be a valid EIP-55 checksum. The
+//! zero address is always rejected. ENS names resolve through the
+//! registry's resolver `addr(bytes32)` over plain JSON-RPC (no wildcard
+//! or offchain resolvers).
+
+use std::fmt;
+use std::str::FromStr;
+
+use bridge_core::codec;
+use serde_json::{json, Value};
+
+use crate::error::{Error, Result};
+
+/// ENS registry, at the same address on mainnet and the testnets
+pub const ENS_REGISTRY: &str = "0x00000000000C2E074eC69A0dFb2997BA6C7d2e1e";
+
+/// A 20-byte Ethereum address; displays with its EIP-55 checksum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct EthAddress(pub [u8; 20]);
+
+impl EthAddress {
+    /// The EIP-55 checksummed form, `0x`-prefixed
+    pub fn to_checksum(&self) -> String {
+        let lower = hex::encode(self.0);
+        let hash = codec::keccak256(lower.as_bytes());
+        let mut out = String::with_capacity(42);
+        out.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            out.push(if nibble >= 8 { c.to_ascii_uppercase() } else { c });
+        }
+        out
+    }
+}
+
+impl FromStr for EthAddress {
+    type Err = Error;
+
+    fn from_str(input: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::InvalidEthAddress(format!("{input}: {reason}"));
+        let digits = input.strip_prefix("0x").unwrap_or(input);
+        if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("expected 40 hex digits"));
+        }
+
+        let mut bytes = [0u8; 20];
+        hex::decode_to_slice(digits, &mut bytes).map_err(|e| invalid(&e.to_string()))?;
+        let address = EthAddress(bytes);
+        if bytes == [0; 20] {
+            return Err(invalid("zero address"));
+        }
+
+        let mixed_case = digits.chars().any(|c| c.is_ascii_lowercase())
+            && digits.chars().any(|c| c.is_ascii_uppercase());
+        if mixed_case && address.to_checksum()[2..] != *digits {
+            return Err(invalid("EIP-55 checksum mismatch"));
+        }
+        Ok(address)
+    }
+}
+
+impl fmt::Display for EthAddress {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.to_checksum())
+    }
+}
+
+/// Whether `input` is meant as an ENS name rather than an address
+pub fn is_ens_name(input: &str) -> bool {
+    input.contains('.') && !input.starts_with("0x")
+}
+
+/**
+ * ENS namehash of `name`
+ *
+ * Lowercases ASCII; names with other characters need ENSIP-15
+ * normalization first and are rejected rather than hashed as typed.
+ */
+pub fn namehash(name: &str) -> Result<[u8; 32]> {
+    if !name.is_ascii() {
+        return Err(Error::Ens(format!("{name}: only ASCII names are supported")));
+    }
+    let name = name.to_ascii_lowercase();
+
+    let mut node = [0u8; 32];
+    if name.is_empty() {
+        return Ok(node);
+    }
+    for label in name.rsplit('.') {
+        if label.is_empty() {
+            return Err(Error::Ens(format!("{name}: empty label")));
+        }
+        let mut preimage = [0u8; 64];
+        preimage[..32].copy_from_slice(&node);
+        preimage[32..].copy_from_slice(&codec::keccak256(label.as_bytes()));
+        node = codec::keccak256(&preimage);
+    }
+    Ok(node)
+}
+
+/// Resolves ENS names against an Ethereum JSON-RPC endpoint
+pub struct EnsResolver {
+    url: String,
+    client: reqwest::Client,
+}
+
+impl EnsResolver {
+    /// `url` is the Ethereum RPC the relayer uses (ETHEREUM_RPC_URL)
+    pub fn new(url: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// The address `name` resolves to; fails if it has no resolver or address
+    pub async fn resolve(&self, name: &str) -> Result<EthAddress> {
+        let node = namehash(name)?;
+        let resolver = self
+            .call_address(ENS_REGISTRY, "resolver(bytes32)", &node)
+            .await?
+            .ok_or_else(|| Error::Ens(format!("{name} has no resolver")))?;
+        self.call_address(&resolver.to_string(), "addr(bytes32)", &node)
+            .await?
+            .ok_or_else(|| Error::Ens(format!("{name} has no address")))
+    }
+
+    /// `eth_call` of a `(bytes32) -> address` function; `None` for the zero address
+    async fn call_address(
+        &self,
+        to: &str,
+        signature: &str,
+        node: &[u8; 32],
+    ) -> Result<Option<EthAddress>> {
+        let data = format!(
+            "0x{}{}",
+            hex::encode(codec::selector(signature)),
+            hex::encode(node)
+        );
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "eth_call",
+            "params": [{ "to": to, "data": data }, "latest"],
+        });
+        let response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(|r| r.error_for_status())
+            .map_err(|e| Error::Ens(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| Error::Ens(e.to_string()))?;
+        if let Some(error) = response.get("error") {
+            return Err(Error::Ens(format!("eth_call {signature}: {error}")));
+        }
+
+        let result = response["result"].as_str().unwrap_or("0x");
+        let word = hex::decode(result.trim_start_matches("0x"))
+            .map_err(|e| Error::Ens(e.to_string()))?;
+        if word.len() < 32 || word[12..32].iter().all(|b| *b == 0) {
+            return Ok(None);
+        }
+        let mut address = [0u8; 20];
+        address.copy_from_slice(&word[12..32]);
+        Ok(Some(EthAddress(address)))
+    }
+}
+
+/**
+ * Parse a recipient typed by a user: an address in any accepted case,
+ * or an ENS name when a resolver is given
+ */
+pub async fn resolve_recipient(input: &str, ens: Option<&EnsResolver>) -> Result<EthAddress> {
+    let input = input.trim();
+    if !is_ens_name(input) {
+        return input.parse();
+    }
+    match ens {
+        Some(resolver) => resolver.resolve(input).await,
+        None => Err(Error::Ens(format!("{input}: no Ethereum RPC to resolve ENS names"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // From EIP-55
+    const CHECKSUMMED: [&str; 4] = [
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn checksums_round_trip() {
+        for address in CHECKSUMMED {
+            let parsed: EthAddress = address.parse().unwrap();
+            assert_eq!(parsed.to_string(), address);
+        }
+    }
+
+    #[test]
+    fn accepts_unchecksummed_case() {
+        for address in CHECKSUMMED {
+            let lower: EthAddress = address.to_lowercase().parse().unwrap();
+            let upper: EthAddress = format!("0x{}", address[2..].to_uppercase()).parse().unwrap();
+            let bare: EthAddress = address[2..].parse().unwrap();
+            assert_eq!(lower.to_string(), address);
+            assert_eq!(upper, lower);
+            assert_eq!(bare, lower);
+        }
+    }
+
+    #[test]
+    fn rejects_typos() {
+        // One letter's case flipped
+        assert!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD".parse::<EthAddress>().is_err());
+        // A digit short, non-hex, zero
+        assert!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAe".parse::<EthAddress>().is_err());
+        assert!("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg".parse::<EthAddress>().is_err());
+        assert!("0x0000000000000000000000000000000000000000".parse::<EthAddress>().is_err());
+    }
+
+    #[test]
+    fn namehash_matches_ens() {
+        assert_eq!(namehash("").unwrap(), [0; 32]);
+        assert_eq!(
+            hex::encode(namehash("eth").unwrap()),
+            "93cdeb708b7545dc668eb9280176169d1c33cfd8ed6f04690a0bcc88a93fc4ae"
+        );
+        assert_eq!(
+            hex::encode(namehash("Foo.ETH").unwrap()),
+            "de9b09fd7c5f901e23a3f19fecc54828e9c848539801e86591bd9801b019f84f"
+        );
+        assert!(namehash("foo..eth").is_err());
+        assert!(namehash("fö.eth").is_err());
+    }
+}
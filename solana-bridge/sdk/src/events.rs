@@ -0,0 +1,485 @@
+//! Typed bridge event streams
+//!
+//! Anchor events land in the transaction logs as
+//! `Program data: <base64(discriminator || borsh)>`. `EventStream` turns
+//! them back into the program's own event structs, from two sources:
+//!
+//! 1. Backfill: every program transaction after the resume cursor, read
+//!    with getSignaturesForAddress + getTransaction, oldest first.
+//! 2. Live: a logsSubscribe WebSocket, opened BEFORE the backfill so
+//!    nothing lands in the gap between the two.
+//!
+//! Delivery is at-least-once. Each item carries the cursor to persist
+//! once it's handled; resuming from it never skips an event, but a
+//! transaction can be replayed if the process dies halfway through it.
+//! If the socket drops, the stream reconnects and backfills from the
+//! last cursor.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anchor_lang::{AnchorDeserialize, Discriminator, Event};
+use base64::Engine;
+use futures::{Stream, StreamExt};
+use solana_bridge::{
+    AdminActionApproved, AdminActionExecuted, AdminActionProposed, ArchiveRecorded,
+    AttestationChallenged, AttestationExecuted, AttestationPosted, BitcoinWithdrawalEvent,
+    BondDeposited, BondedPauseConfirmed, BondedPauseExpired, BondedPauseRaised, BondedPauseResolved,
+    BridgeStatusQueued, BuildRecorded, BurnEvent, ChainStatusChanged, ChallengeResolved,
+    CheckpointCreated, CompactLockEvent, ConversionRateUpdated, CouncilHaltChanged,
+    DeliveryFeePrepaid, DeliveryGasPriceSet, DeliveryQuarantined, DepositAddressRegistered,
+    DepositSwept, DepositWithdrawn, DuplicateDelivery, DustHandled, EscrowMigrated,
+    EscrowMigrationCancelled, EscrowMigrationQueued, FeeExemptionGranted, FeeExemptionRevoked,
+    FeePaidInSol, GasReimbursementClaimed, GasReported, GlobalStatsUpdated, GuardianHeartbeatPosted,
+    HeartbeatPosted, IbcTransferEvent, InstanceTransferReceived, InstanceTransferSent,
+    IntegratorRegistered, LockEvent, MemoAttached, MessageSent, MetadataSynced, MintEvent,
+    NoncesPruned, OtcOrderCancelled, OtcOrderCreated, OtcOrderFilled, PriceBreakerTripped,
+    PriorityBidPlaced, PriorityTipCollected, ReceiptDeferred, RecipientNamePinned,
+    RecoveryAddressSet, RedemptionConfirmed, RedemptionQueueUpdated, RedemptionQueued,
+    RedemptionReleased, RefundAssigned, RelayerDeregistered, RelayerEjected, RelayerRegistered,
+    RelayerReinstated, RelayerRewardsClaimed, ReserveAttested, RouteHealthReported, RouteSequenced,
+    SenderAllowlistSet, SenderAllowlisted, SlaRebateClaimed, SlaRebateCredited, SlaSet,
+    SmartWalletRecipientSet, SourceTxRecorded, TermsAcknowledged, TermsSet, TokenListed,
+    TokenListingActivated, TokenListingVetoed, TokenOracleRefreshed, TransferAcknowledged,
+    TransferCancelled, TransferClaimable, TransferClaimed, TransferReclaimed,
+    TransferRecordCompressed, TransferReemitted, TransferRefunded, TransferRouted, TransferVetoed,
+    UnlockEvent, UpgradeAuthorityChanged, VaultBalanceQueued, VaultRefillCancelled,
+    VaultRefillQueued, VaultRefilled, VaultSwept, VetoLifted, WithdrawalAddressAdded,
+    WithdrawalAddressRemoved, WithdrawalAuthoritySet, WrappedMintCreated, YieldDeposited,
+    YieldRecalled, YieldVenueAllowed, YieldVenueRemoved,
+};
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::{
+    RpcTransactionConfig, RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status::UiTransactionEncoding;
+
+use crate::error::{Error, Result};
+
+/// Signatures per getSignaturesForAddress page (the RPC maximum)
+const SIGNATURE_PAGE_SIZE: usize = 1000;
+
+/// Every event the program emits, as `Variant(EventStruct)`
+macro_rules! bridge_events {
+    ($($variant:ident($event:ident)),* $(,)?) => {
+        /// A decoded bridge event
+        pub enum BridgeEvent {
+            $($variant($event)),*
+        }
+
+        impl BridgeEvent {
+            /**
+             * Decode one `Program data:` payload
+             *
+             * Returns `None` for data that isn't a bridge event.
+             */
+            pub fn decode(data: &[u8]) -> Result<Option<Self>> {
+                if data.len() < 8 {
+                    return Ok(None);
+                }
+                let (discriminator, mut body) = data.split_at(8);
+
+                $(
+                    if *discriminator == $event::DISCRIMINATOR {
+                        return Ok(Some(BridgeEvent::$variant(deserialize(&mut body)?)));
+                    }
+                )*
+                Ok(None)
+            }
+
+            /// The program's name for the event, e.g. "LockEvent"
+            pub fn name(&self) -> &'static str {
+                match self {
+                    $(BridgeEvent::$variant(_) => stringify!($event)),*
+                }
+            }
+
+            /// Re-encode as the `Program data:` payload (discriminator || borsh)
+            pub fn data(&self) -> Vec<u8> {
+                match self {
+                    $(BridgeEvent::$variant(event) => Event::data(event)),*
+                }
+            }
+        }
+    };
+}
+
+bridge_events! {
+    Lock(LockEvent),
+    CompactLock(CompactLockEvent),
+    Burn(BurnEvent),
+    Mint(MintEvent),
+    Unlock(UnlockEvent),
+    DuplicateDelivery(DuplicateDelivery),
+    BitcoinWithdrawal(BitcoinWithdrawalEvent),
+    IbcTransfer(IbcTransferEvent),
+    AttestationPosted(AttestationPosted),
+    AttestationChallenged(AttestationChallenged),
+    BondDeposited(BondDeposited),
+    ChallengeResolved(ChallengeResolved),
+    AttestationExecuted(AttestationExecuted),
+    TransferVetoed(TransferVetoed),
+    VetoLifted(VetoLifted),
+    TransferCancelled(TransferCancelled),
+    TransferReclaimed(TransferReclaimed),
+    TransferAcknowledged(TransferAcknowledged),
+    TransferRefunded(TransferRefunded),
+    AdminActionProposed(AdminActionProposed),
+    AdminActionApproved(AdminActionApproved),
+    AdminActionExecuted(AdminActionExecuted),
+    RelayerRewardsClaimed(RelayerRewardsClaimed),
+    BondedPauseRaised(BondedPauseRaised),
+    BondedPauseResolved(BondedPauseResolved),
+    BondedPauseConfirmed(BondedPauseConfirmed),
+    BondedPauseExpired(BondedPauseExpired),
+    GuardianHeartbeat(GuardianHeartbeatPosted),
+    Heartbeat(HeartbeatPosted),
+    WrappedMintCreated(WrappedMintCreated),
+    ConversionRateUpdated(ConversionRateUpdated),
+    CouncilHaltChanged(CouncilHaltChanged),
+    ReserveAttested(ReserveAttested),
+    CheckpointCreated(CheckpointCreated),
+    NoncesPruned(NoncesPruned),
+    RecipientNamePinned(RecipientNamePinned),
+    RelayerEjected(RelayerEjected),
+    RelayerReinstated(RelayerReinstated),
+    TransferReemitted(TransferReemitted),
+    ChainStatusChanged(ChainStatusChanged),
+    GlobalStatsUpdated(GlobalStatsUpdated),
+    TransferRouted(TransferRouted),
+    FeeExemptionGranted(FeeExemptionGranted),
+    FeeExemptionRevoked(FeeExemptionRevoked),
+    TransferClaimable(TransferClaimable),
+    TransferClaimed(TransferClaimed),
+    GasReported(GasReported),
+    GasReimbursementClaimed(GasReimbursementClaimed),
+    UpgradeAuthorityChanged(UpgradeAuthorityChanged),
+    TransferRecordCompressed(TransferRecordCompressed),
+    OtcOrderCreated(OtcOrderCreated),
+    OtcOrderFilled(OtcOrderFilled),
+    OtcOrderCancelled(OtcOrderCancelled),
+    PriorityBidPlaced(PriorityBidPlaced),
+    PriorityTipCollected(PriorityTipCollected),
+    RelayerRegistered(RelayerRegistered),
+    RelayerDeregistered(RelayerDeregistered),
+    ArchiveRecorded(ArchiveRecorded),
+    RedemptionQueueUpdated(RedemptionQueueUpdated),
+    RedemptionQueued(RedemptionQueued),
+    RedemptionReleased(RedemptionReleased),
+    RedemptionConfirmed(RedemptionConfirmed),
+    TokenOracleRefreshed(TokenOracleRefreshed),
+    IntegratorRegistered(IntegratorRegistered),
+    VaultSwept(VaultSwept),
+    VaultRefillQueued(VaultRefillQueued),
+    VaultRefilled(VaultRefilled),
+    VaultRefillCancelled(VaultRefillCancelled),
+    SourceTxRecorded(SourceTxRecorded),
+    DustHandled(DustHandled),
+    BridgeStatusQueued(BridgeStatusQueued),
+    BuildRecorded(BuildRecorded),
+    TokenListed(TokenListed),
+    TokenListingVetoed(TokenListingVetoed),
+    TokenListingActivated(TokenListingActivated),
+    FeePaidInSol(FeePaidInSol),
+    EscrowMigrationQueued(EscrowMigrationQueued),
+    EscrowMigrationCancelled(EscrowMigrationCancelled),
+    EscrowMigrated(EscrowMigrated),
+    ReceiptDeferred(ReceiptDeferred),
+    DeliveryFeePrepaid(DeliveryFeePrepaid),
+    DeliveryGasPriceSet(DeliveryGasPriceSet),
+    RecoveryAddressSet(RecoveryAddressSet),
+    PriceBreakerTripped(PriceBreakerTripped),
+    TermsSet(TermsSet),
+    TermsAcknowledged(TermsAcknowledged),
+    SlaRebateCredited(SlaRebateCredited),
+    SlaRebateClaimed(SlaRebateClaimed),
+    SlaSet(SlaSet),
+    RouteSequenced(RouteSequenced),
+    DeliveryQuarantined(DeliveryQuarantined),
+    MetadataSynced(MetadataSynced),
+    SenderAllowlistSet(SenderAllowlistSet),
+    SenderAllowlisted(SenderAllowlisted),
+    InstanceTransferSent(InstanceTransferSent),
+    InstanceTransferReceived(InstanceTransferReceived),
+    MemoAttached(MemoAttached),
+    DepositAddressRegistered(DepositAddressRegistered),
+    DepositSwept(DepositSwept),
+    DepositWithdrawn(DepositWithdrawn),
+    WithdrawalAddressAdded(WithdrawalAddressAdded),
+    WithdrawalAddressRemoved(WithdrawalAddressRemoved),
+    WithdrawalAuthoritySet(WithdrawalAuthoritySet),
+    VaultBalanceQueued(VaultBalanceQueued),
+    YieldVenueAllowed(YieldVenueAllowed),
+    YieldVenueRemoved(YieldVenueRemoved),
+    YieldDeposited(YieldDeposited),
+    YieldRecalled(YieldRecalled),
+    RefundAssigned(RefundAssigned),
+    SmartWalletRecipientSet(SmartWalletRecipientSet),
+    RouteHealthReported(RouteHealthReported),
+    MessageSent(MessageSent),
+}
+
+fn deserialize<T: AnchorDeserialize>(body: &mut &[u8]) -> Result<T> {
+    T::deserialize(body).map_err(|e| Error::Decode(e.to_string()))
+}
+
+/**
+ * Where to resume a stream: the last transaction fully handled
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub signature: Signature,
+    pub slot: u64,
+}
+
+/// An event and where it came from
+pub struct StreamedEvent {
+    pub event: BridgeEvent,
+    pub signature: Signature,
+    pub slot: u64,
+    /// Persist this once the event is handled
+    pub cursor: Option<Cursor>,
+}
+
+/**
+ * Extract the bridge's events from a transaction's logs
+ *
+ * Only `Program data:` lines emitted by `program_id` itself count;
+ * events from programs it calls (or that call it) are skipped.
+ */
+pub fn parse_logs(program_id: &Pubkey, logs: &[String]) -> Result<Vec<BridgeEvent>> {
+    let program = program_id.to_string();
+    let mut stack: Vec<&str> = Vec::new();
+    let mut events = Vec::new();
+
+    for line in logs {
+        let Some(rest) = line.strip_prefix("Program ") else {
+            continue;
+        };
+
+        if let Some(data) = rest.strip_prefix("data: ") {
+            if stack.last() != Some(&program.as_str()) {
+                continue;
+            }
+            let Ok(bytes) = base64::engine::general_purpose::STANDARD.decode(data) else {
+                continue;
+            };
+            if let Some(event) = BridgeEvent::decode(&bytes)? {
+                events.push(event);
+            }
+        } else if let Some((id, status)) = rest.split_once(' ') {
+            if status.starts_with("invoke [") {
+                stack.push(id);
+            } else if status == "success" || status.starts_with("failed") {
+                stack.pop();
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/**
+ * Stream of bridge events with backfill and resume
+ *
+ *   let stream = EventStream::new(rpc_url, ws_url).resume_from(cursor).subscribe();
+ *   futures::pin_mut!(stream);
+ *   while let Some(item) = stream.next().await { ... }
+ *
+ * Without a cursor the stream starts at the tip (no backfill), unless
+ * `from_genesis` is set.
+ */
+pub struct EventStream {
+    rpc_url: String,
+    ws_url: String,
+    program_id: Pubkey,
+    commitment: CommitmentConfig,
+    cursor: Option<Cursor>,
+    from_genesis: bool,
+}
+
+impl EventStream {
+    pub fn new(rpc_url: impl Into<String>, ws_url: impl Into<String>) -> Self {
+        Self {
+            rpc_url: rpc_url.into(),
+            ws_url: ws_url.into(),
+            program_id: solana_bridge::ID,
+            commitment: CommitmentConfig::confirmed(),
+            cursor: None,
+            from_genesis: false,
+        }
+    }
+
+    /// Watch a different deployment of the program
+    pub fn program_id(mut self, program_id: Pubkey) -> Self {
+        self.program_id = program_id;
+        self
+    }
+
+    /// At least `confirmed`; the RPC doesn't backfill `processed`
+    pub fn commitment(mut self, commitment: CommitmentConfig) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Deliver everything after `cursor` before going live
+    pub fn resume_from(mut self, cursor: Cursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+
+    /**
+     * Without a cursor, backfill the program's whole history instead of
+     * starting at the tip
+     *
+     * Limited to what the RPC node retains; point it at an archival node
+     * for a full history.
+     */
+    pub fn from_genesis(mut self) -> Self {
+        self.from_genesis = true;
+        self
+    }
+
+    /**
+     * Start streaming
+     *
+     * The stream ends after the first error; resume from the last
+     * persisted cursor.
+     */
+    pub fn subscribe(self) -> impl Stream<Item = Result<StreamedEvent>> {
+        async_stream::try_stream! {
+            let rpc = RpcClient::new_with_commitment(self.rpc_url.clone(), self.commitment);
+            let mut cursor = self.cursor;
+
+            loop {
+                let pubsub = PubsubClient::new(&self.ws_url).await?;
+                let (mut live, _unsubscribe) = pubsub
+                    .logs_subscribe(
+                        RpcTransactionLogsFilter::Mentions(vec![self.program_id.to_string()]),
+                        RpcTransactionLogsConfig {
+                            commitment: Some(self.commitment),
+                        },
+                    )
+                    .await?;
+
+                // Live notifications queue up in the subscription meanwhile
+                let mut backfilled = HashSet::new();
+                if cursor.is_some() || self.from_genesis {
+                    for (signature, slot) in self.signatures_after(&rpc, cursor.as_ref()).await? {
+                        let logs = self.transaction_logs(&rpc, &signature).await?;
+                        let events = parse_logs(&self.program_id, &logs)?;
+                        let done = Cursor { signature, slot };
+                        let count = events.len();
+                        for (i, event) in events.into_iter().enumerate() {
+                            yield StreamedEvent {
+                                event,
+                                signature,
+                                slot,
+                                cursor: if i + 1 == count { Some(done) } else { cursor },
+                            };
+                        }
+                        cursor = Some(done);
+                        backfilled.insert(signature);
+                    }
+                }
+
+                while let Some(notification) = live.next().await {
+                    let slot = notification.context.slot;
+                    let value = notification.value;
+                    if value.err.is_some() {
+                        continue;
+                    }
+                    let Ok(signature) = Signature::from_str(&value.signature) else {
+                        continue;
+                    };
+                    if backfilled.contains(&signature) {
+                        continue;
+                    }
+
+                    let events = parse_logs(&self.program_id, &value.logs)?;
+                    let done = Cursor { signature, slot };
+                    let count = events.len();
+                    for (i, event) in events.into_iter().enumerate() {
+                        yield StreamedEvent {
+                            event,
+                            signature,
+                            slot,
+                            cursor: if i + 1 == count { Some(done) } else { cursor },
+                        };
+                    }
+                    cursor = Some(done);
+                }
+
+                // Socket closed: reconnect and backfill from the cursor
+            }
+        }
+    }
+
+    /// Successful program transactions after `from` (or all), oldest first
+    async fn signatures_after(
+        &self,
+        rpc: &RpcClient,
+        from: Option<&Cursor>,
+    ) -> Result<Vec<(Signature, u64)>> {
+        let mut signatures = Vec::new();
+        let mut before = None;
+
+        loop {
+            let page = rpc
+                .get_signatures_for_address_with_config(
+                    &self.program_id,
+                    GetConfirmedSignaturesForAddress2Config {
+                        before,
+                        until: from.map(|cursor| cursor.signature),
+                        limit: Some(SIGNATURE_PAGE_SIZE),
+                        commitment: Some(self.commitment),
+                    },
+                )
+                .await?;
+
+            let full = page.len() == SIGNATURE_PAGE_SIZE;
+            for status in page {
+                let signature = Signature::from_str(&status.signature)
+                    .map_err(|e| Error::Decode(e.to_string()))?;
+                before = Some(signature);
+                if status.err.is_none() {
+                    signatures.push((signature, status.slot));
+                }
+            }
+            if !full {
+                break;
+            }
+        }
+
+        signatures.reverse();
+        Ok(signatures)
+    }
+
+    async fn transaction_logs(
+        &self,
+        rpc: &RpcClient,
+        signature: &Signature,
+    ) -> Result<Vec<String>> {
+        let transaction = rpc
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(self.commitment),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .await?;
+
+        let meta = transaction
+            .transaction
+            .meta
+            .ok_or_else(|| Error::TransactionNotFound(signature.to_string()))?;
+        Ok(Option::<Vec<String>>::from(meta.log_messages).unwrap_or_default())
+    }
+}
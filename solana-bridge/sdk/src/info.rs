@@ -0,0 +1,92 @@
+//! Deployment discovery through the program's `get_bridge_info` view
+//!
+//!   let info = bridge_info(&rpc, &payer, &[chain_ids::ETHEREUM]).await?;
+//!   if info.features & features::CLAIM_DELIVERY != 0 { ... }
+//!
+//! Check `interface_version` against INTERFACE_VERSION before relying on
+//! anything else: a different one means instructions this SDK builds may
+//! not match the deployment. Nothing is sent; `payer` only has to exist.
+//!
+//! `rate_limits` does the same for one user's limits on one route,
+//! for sizing or disabling a wallet's amount input before signing.
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use solana_bridge::{accounts, instruction, BridgeInfo, RateLimitStatus};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::Result;
+use crate::pda;
+use crate::status::simulate_view;
+
+/// The deployment's info, with the config of each of `chain_ids` (each must be registered)
+pub async fn bridge_info(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    chain_ids: &[u64],
+) -> Result<BridgeInfo> {
+    let program_id = solana_bridge::ID;
+    let fee_tiers = pda::fee_tiers(&program_id);
+    let fee_tiers = rpc
+        .get_account_with_commitment(&fee_tiers, rpc.commitment())
+        .await?
+        .value
+        .map(|_| fee_tiers);
+
+    let mut metas = accounts::BridgeInfoView {
+        bridge_state: pda::bridge_state(&program_id),
+        fee_tiers,
+    }
+    .to_account_metas(None);
+    for &chain_id in chain_ids {
+        let chain_config = pda::chain_config(&program_id, chain_id);
+        metas.push(AccountMeta::new_readonly(chain_config, false));
+    }
+
+    let instruction = Instruction {
+        program_id,
+        accounts: metas,
+        data: instruction::GetBridgeInfo {}.data(),
+    };
+    let (info, _) = simulate_view(rpc, payer, instruction).await?;
+    Ok(info)
+}
+
+/**
+ * What `user` can send of `mint` to `dest_chain_id` right now
+ *
+ * `credential` is needed for restricted tokens; `bridge_pass` is the
+ * user's pass token account, if they hold one.
+ */
+pub async fn rate_limits(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    mint: &Pubkey,
+    dest_chain_id: u64,
+    user: &Pubkey,
+    credential: Option<Pubkey>,
+    bridge_pass: Option<Pubkey>,
+) -> Result<RateLimitStatus> {
+    let program_id = solana_bridge::ID;
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::RateLimitsView {
+            bridge_state: pda::bridge_state(&program_id),
+            chain_config: pda::chain_config(&program_id, dest_chain_id),
+            token_config: pda::token_config(&program_id, mint),
+            throttle: pda::throttle(&program_id),
+            credential,
+            bridge_pass,
+        }
+        .to_account_metas(None),
+        data: instruction::GetRateLimits {
+            mint: *mint,
+            dest_chain_id,
+            user: *user,
+        }
+        .data(),
+    };
+    let (status, _) = simulate_view(rpc, payer, instruction).await?;
+    Ok(status)
+}
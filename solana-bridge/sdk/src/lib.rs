@@ -0,0 +1,46 @@
+//! Solana Bridge SDK
+//!
+//! Rust client for the bridge program: typed events and the plumbing
+//! integrators would otherwise write by hand. Account and event types
+//! come straight from the program crate, so they can't drift.
+
+pub mod adapter;
+pub mod budget;
+pub mod build;
+pub mod builder;
+pub mod claim_auth;
+pub mod compression;
+pub mod error;
+pub mod ethereum;
+pub mod events;
+pub mod info;
+pub mod liveness;
+pub mod memo;
+pub mod pda;
+pub mod preflight;
+pub mod profile;
+pub mod quote;
+pub mod refund_assign;
+pub mod relayers;
+pub mod route_health;
+pub mod sns;
+pub mod status;
+
+pub use adapter::{BridgeAdapter, SolanaBridgeAdapter};
+pub use build::{check_build, parse_program_hash, BuildCheck};
+pub use builder::{LockAccounts, MintAccounts, PriorityFee, TransferBuilder};
+pub use claim_auth::SignedClaimAuthorization;
+pub use error::{Error, Result};
+pub use ethereum::{resolve_recipient, EnsResolver, EthAddress};
+pub use events::{BridgeEvent, Cursor, EventStream, StreamedEvent};
+pub use info::{bridge_info, rate_limits};
+pub use liveness::{liveness, LivenessReport, Verdict};
+pub use preflight::{BridgeError, Preflight};
+pub use profile::{route_profile, RouteProfile};
+pub use quote::SignedFeeQuote;
+pub use refund_assign::SignedRefundAssignment;
+pub use relayers::relayers;
+pub use route_health::{route_health, route_healths, RouteHealthView, RouteVerdict};
+pub use status::{transfer_status, transfers_by_source_tx};
+
+pub use solana_bridge::ID as PROGRAM_ID;
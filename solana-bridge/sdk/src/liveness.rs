@@ -0,0 +1,119 @@
+//! Operator liveness, to tell "bridge slow" from "operators gone"
+//!
+//!   let report = liveness(&rpc, 2 * 3600).await?;
+//!   match report.verdict() {
+//!       Verdict::Live => { /* slow at worst; the transfer will move */ }
+//!       Verdict::Degraded => { /* alert, but someone can still act */ }
+//!       Verdict::OperatorsGone => { /* stop sending; reclaim what expires */ }
+//!   }
+//!
+//! Reads the owner (the relayer), the guardian set and each one's
+//! liveness registry entry (see the program's `heartbeat`). An operator
+//! that never posted a heartbeat, or none in the last `max_age` seconds,
+//! counts as gone.
+
+use anchor_lang::AccountDeserialize;
+use solana_bridge::{BridgeState, GuardianSet, OperatorLiveness};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::pda;
+
+/// One operator's last sign of life
+#[derive(Debug, Clone)]
+pub struct OperatorStatus {
+    pub operator: Pubkey,
+    pub guardian: bool,
+    /// Unix time of its last heartbeat, if it ever posted one
+    pub last_seen: Option<i64>,
+    pub live: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Verdict {
+    /// The relayer and a guardian quorum (if there is a guardian set) are beating
+    Live,
+    /// One of them is gone; the other can still deliver or cancel
+    Degraded,
+    /// Nobody is left to move transfers
+    OperatorsGone,
+}
+
+#[derive(Debug, Clone)]
+pub struct LivenessReport {
+    pub owner: Pubkey,
+    /// The owner first, then the guardians in set order
+    pub operators: Vec<OperatorStatus>,
+    /// Guardian signatures a quorum needs (0 without a guardian set)
+    pub threshold: u8,
+    /// Cluster time the report was taken at
+    pub now: i64,
+}
+
+impl LivenessReport {
+    pub fn live_guardians(&self) -> usize {
+        self.operators.iter().filter(|o| o.guardian && o.live).count()
+    }
+
+    pub fn relayer_live(&self) -> bool {
+        self.operators.iter().any(|o| o.operator == self.owner && o.live)
+    }
+
+    pub fn verdict(&self) -> Verdict {
+        let quorum = self.threshold > 0 && self.live_guardians() >= self.threshold as usize;
+        match (self.relayer_live(), quorum) {
+            (true, true) => Verdict::Live,
+            (true, false) if self.threshold == 0 => Verdict::Live,
+            (false, false) => Verdict::OperatorsGone,
+            _ => Verdict::Degraded,
+        }
+    }
+}
+
+/// Liveness of every operator, judged against a `max_age` (seconds) heartbeat interval
+pub async fn liveness(rpc: &RpcClient, max_age: i64) -> Result<LivenessReport> {
+    let program_id = solana_bridge::ID;
+    let state: BridgeState = decode(&rpc.get_account_data(&pda::bridge_state(&program_id)).await?)?;
+    let guardian_set = rpc
+        .get_account_with_commitment(&pda::guardian_set(&program_id), rpc.commitment())
+        .await?
+        .value
+        .map(|account| decode::<GuardianSet>(&account.data))
+        .transpose()?;
+    let (threshold, guardians) =
+        guardian_set.map_or((0, vec![]), |set| (set.threshold, set.guardians));
+
+    let mut operators = vec![state.owner];
+    operators.extend(guardians.iter().copied().filter(|g| *g != state.owner));
+    let entries: Vec<_> = operators.iter().map(|o| pda::liveness(&program_id, o)).collect();
+    let accounts = rpc.get_multiple_accounts(&entries).await?;
+    let now = rpc.get_block_time(rpc.get_slot().await?).await?;
+
+    let operators = operators
+        .into_iter()
+        .zip(accounts)
+        .map(|(operator, account)| {
+            let entry = account
+                .map(|account| decode::<OperatorLiveness>(&account.data))
+                .transpose()?;
+            Ok(OperatorStatus {
+                operator,
+                guardian: guardians.contains(&operator),
+                last_seen: entry.as_ref().map(|e| e.last_seen),
+                live: entry.is_some_and(|e| e.is_live(now, max_age)),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    Ok(LivenessReport {
+        owner: state.owner,
+        operators,
+        threshold,
+        now,
+    })
+}
+
+fn decode<T: AccountDeserialize>(data: &[u8]) -> Result<T> {
+    T::try_deserialize(&mut &data[..]).map_err(|e| Error::Decode(e.to_string()))
+}
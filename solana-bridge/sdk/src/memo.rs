@@ -0,0 +1,164 @@
+//! Encrypted transfer memos
+//!
+//! A lock can carry a memo (see `TransferBuilder::memo`), which the
+//! program stores and emits as is. To keep a settlement reference out of
+//! public view, seal it to the recipient's X25519 key first: an anonymous
+//! sealed box (ephemeral X25519 key, XSalsa20-Poly1305), 48 bytes longer
+//! than the text. Only the holder of the matching secret key can open it,
+//! from the `MemoAttached` event or the transfer's memo account.
+//!
+//! The box is libsodium's `crypto_box_seal`, so any NaCl binding opens it:
+//!
+//!   ephemeral public key (32) || Poly1305 tag (16) || XSalsa20 ciphertext
+//!
+//! with the nonce blake2b-24(ephemeral key || recipient key) and the key
+//! HSalsa20 of the X25519 shared secret. It's put together here from
+//! primitives that build next to solana 1.18 (zeroize < 1.4).
+
+use anchor_lang::AccountDeserialize;
+use blake2::digest::{Update, VariableOutput};
+use blake2::VarBlake2b;
+use poly1305::universal_hash::NewUniversalHash;
+use poly1305::Poly1305;
+use rand_core::OsRng;
+use salsa20::cipher::generic_array::GenericArray;
+use salsa20::cipher::{NewCipher, StreamCipher};
+use salsa20::{hsalsa20, XSalsa20};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+use x25519_dalek::{EphemeralSecret, PublicKey, SharedSecret, StaticSecret};
+
+use crate::error::{Error, Result};
+use crate::pda;
+
+pub use solana_bridge::{TransferMemo, MAX_MEMO_LEN};
+
+/// What a sealed box adds to the text (ephemeral key and tag)
+pub const SEAL_OVERHEAD: usize = 48;
+
+/// Lengths of the sealed box's ephemeral key and tag
+const KEY_LEN: usize = 32;
+const TAG_LEN: usize = 16;
+
+/// blake2b-24(ephemeral key || recipient key)
+fn nonce(ephemeral: &[u8; 32], recipient: &[u8; 32]) -> [u8; 24] {
+    let mut nonce = [0u8; 24];
+    let mut hasher = VarBlake2b::new(nonce.len()).expect("24 is a valid blake2b length");
+    hasher.update(ephemeral);
+    hasher.update(recipient);
+    hasher.finalize_variable(|out| nonce.copy_from_slice(out));
+    nonce
+}
+
+/// XSalsa20 keyed with HSalsa20 of the shared secret; its first 32 bytes
+/// of keystream are the Poly1305 key
+fn cipher(shared: &SharedSecret, nonce: &[u8; 24]) -> (XSalsa20, Poly1305) {
+    let key = hsalsa20(GenericArray::from_slice(shared.as_bytes()), &GenericArray::default());
+    let mut stream = XSalsa20::new(&key, GenericArray::from_slice(nonce));
+    let mut mac_key = [0u8; 32];
+    stream.apply_keystream(&mut mac_key);
+    (stream, Poly1305::new(GenericArray::from_slice(&mac_key)))
+}
+
+/// Seal `text` to `recipient_key`, an X25519 public key
+pub fn seal(recipient_key: &[u8; 32], text: &[u8]) -> Result<Vec<u8>> {
+    if text.len() + SEAL_OVERHEAD > MAX_MEMO_LEN {
+        return Err(Error::Memo(format!(
+            "{} bytes of text don't fit in {} once sealed",
+            text.len(),
+            MAX_MEMO_LEN
+        )));
+    }
+    let ephemeral = EphemeralSecret::new(OsRng);
+    let ephemeral_key = PublicKey::from(&ephemeral).to_bytes();
+    let nonce = nonce(&ephemeral_key, recipient_key);
+    let shared = ephemeral.diffie_hellman(&PublicKey::from(*recipient_key));
+    let (mut stream, mac) = cipher(&shared, &nonce);
+
+    let mut ciphertext = text.to_vec();
+    stream.apply_keystream(&mut ciphertext);
+    let tag = mac.compute_unpadded(&ciphertext).into_bytes();
+
+    let mut sealed = Vec::with_capacity(text.len() + SEAL_OVERHEAD);
+    sealed.extend_from_slice(&ephemeral_key);
+    sealed.extend_from_slice(&tag);
+    sealed.extend_from_slice(&ciphertext);
+    Ok(sealed)
+}
+
+/// Open a memo sealed to the public key of `secret_key`
+pub fn open(secret_key: &[u8; 32], memo: &[u8]) -> Result<Vec<u8>> {
+    let altered = || Error::Memo("not sealed to this key, or altered".to_string());
+    if memo.len() < SEAL_OVERHEAD {
+        return Err(altered());
+    }
+    let (ephemeral_key, rest) = memo.split_at(KEY_LEN);
+    let (tag, ciphertext) = rest.split_at(TAG_LEN);
+    let ephemeral_key: [u8; 32] = ephemeral_key.try_into().expect("split at 32");
+
+    let secret = StaticSecret::from(*secret_key);
+    let nonce = nonce(&ephemeral_key, PublicKey::from(&secret).as_bytes());
+    let shared = secret.diffie_hellman(&PublicKey::from(ephemeral_key));
+    let (mut stream, mac) = cipher(&shared, &nonce);
+    // Output's equality is constant time
+    if mac.compute_unpadded(ciphertext) != poly1305::Tag::new(*GenericArray::from_slice(tag)) {
+        return Err(altered());
+    }
+
+    let mut text = ciphertext.to_vec();
+    stream.apply_keystream(&mut text);
+    Ok(text)
+}
+
+/// The memo outbound transfer `nonce` carries, if it has one
+pub async fn fetch(rpc: &RpcClient, program_id: &Pubkey, nonce: u64) -> Result<Option<Vec<u8>>> {
+    let address = pda::transfer_memo(program_id, nonce);
+    let Some(account) = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await?
+        .value
+    else {
+        return Ok(None);
+    };
+    let memo = TransferMemo::try_deserialize(&mut &account.data[..])
+        .map_err(|e| Error::Decode(e.to_string()))?;
+    Ok(Some(memo.memo))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECRET: [u8; 32] = [7; 32];
+
+    fn public() -> [u8; 32] {
+        PublicKey::from(&StaticSecret::from(SECRET)).to_bytes()
+    }
+
+    #[test]
+    fn opens_what_it_seals() {
+        let sealed = seal(&public(), b"invoice 4471").unwrap();
+        assert_eq!(sealed.len(), 12 + SEAL_OVERHEAD);
+        assert_eq!(open(&SECRET, &sealed).unwrap(), b"invoice 4471");
+        assert!(open(&[8; 32], &sealed).is_err());
+    }
+
+    #[test]
+    fn opens_a_libsodium_sealed_box() {
+        // crypto_box_seal("invoice 4471") to the public key of [7; 32]
+        let sealed = hex::decode(
+            "83ca05fc3e4245cde8083e0b52e97d344a4a1074145a2479e054b3e6b2e50a10\
+             887a6cf8f6314a84d18f37bcee4f50593cba0cd70c906fb3317cc637",
+        )
+        .unwrap();
+        assert_eq!(open(&SECRET, &sealed).unwrap(), b"invoice 4471");
+    }
+
+    #[test]
+    fn rejects_an_altered_memo() {
+        let mut sealed = seal(&public(), b"invoice 4471").unwrap();
+        *sealed.last_mut().unwrap() ^= 1;
+        assert!(open(&SECRET, &sealed).is_err());
+        assert!(open(&SECRET, &sealed[..SEAL_OVERHEAD - 1]).is_err());
+    }
+}
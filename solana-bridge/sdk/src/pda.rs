@@ -0,0 +1,302 @@
+//! Program-derived addresses
+//!
+//! Seeds mirror the `seeds = [...]` constraints in the program, for the
+//! default instance (whose instance seed is empty; see the program's
+//! `instance` module).
+
+use solana_sdk::bpf_loader_upgradeable;
+use solana_sdk::pubkey::Pubkey;
+
+fn find(seeds: &[&[u8]], program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(seeds, program_id).0
+}
+
+pub fn bridge_state(program_id: &Pubkey) -> Pubkey {
+    find(&[b"bridge_state"], program_id)
+}
+
+pub fn admin_log(program_id: &Pubkey) -> Pubkey {
+    find(&[b"admin_log"], program_id)
+}
+
+pub fn bridge_authority(program_id: &Pubkey) -> Pubkey {
+    find(&[b"bridge"], program_id)
+}
+
+pub fn outbox(program_id: &Pubkey) -> Pubkey {
+    find(&[b"outbox"], program_id)
+}
+
+pub fn inbox(program_id: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"inbox", &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn guardian_council(program_id: &Pubkey) -> Pubkey {
+    find(&[b"guardian_council"], program_id)
+}
+
+pub fn guardian_set(program_id: &Pubkey) -> Pubkey {
+    find(&[b"guardian_set"], program_id)
+}
+
+pub fn chain_config(program_id: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"chain", &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn token_config(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"token", mint.as_ref()], program_id)
+}
+
+pub fn conversion_rate(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"conversion_rate", mint.as_ref()], program_id)
+}
+
+pub fn fee_tiers(program_id: &Pubkey) -> Pubkey {
+    find(&[b"fee_tiers"], program_id)
+}
+
+pub fn fee_route(program_id: &Pubkey, mint: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"fee", mint.as_ref(), &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn relay_route(program_id: &Pubkey, mint: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"route", mint.as_ref(), &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn throttle(program_id: &Pubkey) -> Pubkey {
+    find(&[b"throttle"], program_id)
+}
+
+pub fn priority_config(program_id: &Pubkey) -> Pubkey {
+    find(&[b"priority_config"], program_id)
+}
+
+pub fn priority_bid(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"priority_bid", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn liveness(program_id: &Pubkey, operator: &Pubkey) -> Pubkey {
+    find(&[b"liveness", operator.as_ref()], program_id)
+}
+
+pub fn otc_book(program_id: &Pubkey) -> Pubkey {
+    find(&[b"otc_book"], program_id)
+}
+
+pub fn otc_order(program_id: &Pubkey, order_id: u64) -> Pubkey {
+    find(&[b"otc_order", &order_id.to_le_bytes()], program_id)
+}
+
+pub fn otc_escrow_authority(program_id: &Pubkey) -> Pubkey {
+    find(&[b"otc_escrow"], program_id)
+}
+
+pub fn user_stats(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    find(&[b"user_stats", user.as_ref()], program_id)
+}
+
+pub fn fee_exemption(program_id: &Pubkey, address: &Pubkey) -> Pubkey {
+    find(&[b"fee_exempt", address.as_ref()], program_id)
+}
+
+pub fn outbound_transfer(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"transfer", b"out", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn inbound_transfer(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"transfer", b"in", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn transfer_route(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"route", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn attestation(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"attestation", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn receipt(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"receipt", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn transfer_memo(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"memo", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn smart_wallet(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"smart_wallet", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn reward_config(program_id: &Pubkey) -> Pubkey {
+    find(&[b"reward_config"], program_id)
+}
+
+pub fn reward_epoch(program_id: &Pubkey, epoch: u64) -> Pubkey {
+    find(&[b"reward_epoch", &epoch.to_le_bytes()], program_id)
+}
+
+pub fn relayer_stats(program_id: &Pubkey, epoch: u64, relayer: &Pubkey) -> Pubkey {
+    find(&[b"relayer_epoch", &epoch.to_le_bytes(), relayer.as_ref()], program_id)
+}
+
+pub fn gas_report(program_id: &Pubkey, nonce: u64) -> Pubkey {
+    find(&[b"gas_report", &nonce.to_le_bytes()], program_id)
+}
+
+pub fn gas_reimbursement(program_id: &Pubkey, relayer: &Pubkey) -> Pubkey {
+    find(&[b"gas_reimbursement", relayer.as_ref()], program_id)
+}
+
+pub fn ejected_relayer(program_id: &Pubkey, relayer: &Pubkey) -> Pubkey {
+    find(&[b"ejected", relayer.as_ref()], program_id)
+}
+
+pub fn relayer_registration(program_id: &Pubkey, relayer: &Pubkey) -> Pubkey {
+    find(&[b"relayer", relayer.as_ref()], program_id)
+}
+
+pub fn migration(program_id: &Pubkey) -> Pubkey {
+    find(&[b"migration"], program_id)
+}
+
+pub fn global_stats(program_id: &Pubkey) -> Pubkey {
+    find(&[b"global_stats"], program_id)
+}
+
+pub fn upgrade_authority(program_id: &Pubkey) -> Pubkey {
+    find(&[b"upgrade_authority"], program_id)
+}
+
+pub fn build_record(program_id: &Pubkey) -> Pubkey {
+    find(&[b"build_record"], program_id)
+}
+
+/// The loader's ProgramData account, which holds the upgrade authority
+pub fn program_data(program_id: &Pubkey) -> Pubkey {
+    find(&[program_id.as_ref()], &bpf_loader_upgradeable::id())
+}
+
+pub fn compressed_store(program_id: &Pubkey) -> Pubkey {
+    find(&[b"compressed_store"], program_id)
+}
+
+pub fn tree_authority(program_id: &Pubkey) -> Pubkey {
+    find(&[b"tree_authority"], program_id)
+}
+
+pub fn archive_log(program_id: &Pubkey) -> Pubkey {
+    find(&[b"archive_log"], program_id)
+}
+
+pub fn archive(program_id: &Pubkey, index: u64) -> Pubkey {
+    find(&[b"archive", &index.to_le_bytes()], program_id)
+}
+
+pub fn redemption_queue(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"redemption_queue", mint.as_ref()], program_id)
+}
+
+pub fn redemption_claim(program_id: &Pubkey, mint: &Pubkey, claim_id: u64) -> Pubkey {
+    find(&[b"redemption_claim", mint.as_ref(), &claim_id.to_le_bytes()], program_id)
+}
+
+pub fn token_oracle(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"token_oracle", mint.as_ref()], program_id)
+}
+
+pub fn integrator(program_id: &Pubkey, integrator_id: u32) -> Pubkey {
+    find(&[b"integrator", &integrator_id.to_le_bytes()], program_id)
+}
+
+pub fn vault_config(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"vault", mint.as_ref()], program_id)
+}
+
+pub fn cold_vault_authority(program_id: &Pubkey) -> Pubkey {
+    find(&[b"cold_vault"], program_id)
+}
+
+pub fn source_tx(program_id: &Pubkey, tx_hash: &[u8; 32]) -> Pubkey {
+    find(&[b"source_tx", tx_hash.as_ref()], program_id)
+}
+
+pub fn burn_approval(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"burn_approval", mint.as_ref()], program_id)
+}
+
+pub fn sol_fee_config(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"sol_fee", mint.as_ref()], program_id)
+}
+
+pub fn sol_fee_vault(program_id: &Pubkey) -> Pubkey {
+    find(&[b"sol_fee_vault"], program_id)
+}
+
+pub fn reward_vault(program_id: &Pubkey) -> Pubkey {
+    find(&[b"reward_vault"], program_id)
+}
+
+pub fn delivery_fee_config(program_id: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"delivery_fee", &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn price_breaker(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"price_breaker", mint.as_ref()], program_id)
+}
+
+pub fn event_history(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"event_history", mint.as_ref()], program_id)
+}
+
+pub fn route_sequence(program_id: &Pubkey, mint: &Pubkey, chain_id: u64) -> Pubkey {
+    find(&[b"sequence", mint.as_ref(), &chain_id.to_le_bytes()], program_id)
+}
+
+pub fn metadata_sync(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"metadata_sync", mint.as_ref()], program_id)
+}
+
+pub fn terms_config(program_id: &Pubkey) -> Pubkey {
+    find(&[b"terms"], program_id)
+}
+
+pub fn terms_acceptance(program_id: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    find(&[b"terms_ack", wallet.as_ref()], program_id)
+}
+
+pub fn sender_allowlist(program_id: &Pubkey) -> Pubkey {
+    find(&[b"sender_allowlist"], program_id)
+}
+
+pub fn allowed_sender(program_id: &Pubkey, sender: &Pubkey) -> Pubkey {
+    find(&[b"allowed_sender", sender.as_ref()], program_id)
+}
+
+/// A user's deposit address; deposits go to its associated token accounts
+pub fn deposit_address(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    find(&[b"deposit", user.as_ref()], program_id)
+}
+
+pub fn withdrawal_allowlist(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    find(&[b"withdrawal_allowlist", user.as_ref()], program_id)
+}
+
+pub fn sla_config(program_id: &Pubkey) -> Pubkey {
+    find(&[b"sla"], program_id)
+}
+
+pub fn escrow_migration(program_id: &Pubkey, mint: &Pubkey) -> Pubkey {
+    find(&[b"escrow_migration", mint.as_ref()], program_id)
+}
+
+pub fn instance_transfer(program_id: &Pubkey, mint: &Pubkey, id: u64) -> Pubkey {
+    find(&[b"instance_transfer", mint.as_ref(), &id.to_le_bytes()], program_id)
+}
+
+pub fn instance_receipt(program_id: &Pubkey, source_record: &Pubkey) -> Pubkey {
+    find(&[b"instance_receipt", source_record.as_ref()], program_id)
+}
+
+pub fn route_health(program_id: &Pubkey, route_id: u32) -> Pubkey {
+    find(&[b"route_health", &route_id.to_le_bytes()], program_id)
+}
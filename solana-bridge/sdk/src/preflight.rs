@@ -0,0 +1,402 @@
+//! Simulation-based preflight
+//!
+//! Simulates a transaction before it's sent and turns a failure into a
+//! `BridgeError` a wallet can show as-is, instead of "custom program
+//! error: 0x1770". Errors come from the Anchor log line
+//!
+//!   AnchorError thrown in ... Error Code: <Name>. Error Number: <n>. Error Message: <msg>.
+//!
+//! matched by name, so they don't shift when the program adds codes.
+//! When the failing check logged its numbers first (`require_ctx!`),
+//!
+//!   Error context: category=limits amount=500 limit=400
+//!
+//! they fill the error's fields; against a program that doesn't log them
+//! the fields are None. A successful simulation reports the compute
+//! units used.
+
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use solana_bridge::errors::ERROR_CONTEXT_PREFIX;
+use solana_bridge::{ErrorCategory, ErrorCode};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSimulateTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::{TransactionError, VersionedTransaction};
+
+use crate::builder::MAX_COMPUTE_UNITS;
+use crate::error::{Error, Result};
+
+/// Headroom over simulated usage when suggesting a compute-unit limit
+const COMPUTE_UNIT_MARGIN_PERCENT: u64 = 10;
+
+/// Bridge program errors a user can act on
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum BridgeError {
+    #[error("The bridge is paused")]
+    BridgePaused,
+
+    #[error("Bridging this token is paused")]
+    TokenPaused,
+
+    #[error("The guardian council has halted the bridge")]
+    CouncilHalted,
+
+    #[error("Guardians missed their heartbeat; mint and unlock resume once one is posted")]
+    GuardiansUnresponsive,
+
+    #[error("Transfers to this chain are disabled")]
+    ChainDisabled,
+
+    #[error("Amount is below the minimum or doesn't cover the bridge fee")]
+    AmountTooSmall {
+        amount: Option<u64>,
+        minimum: Option<u64>,
+        fee: Option<u64>,
+    },
+
+    #[error("Not enough tokens in the source account")]
+    InsufficientFunds,
+
+    #[error("Recipient is not a valid address on the destination chain")]
+    InvalidRecipient,
+
+    #[error("This token requires a credential")]
+    CredentialRequired,
+
+    #[error("Your credential has expired")]
+    CredentialExpired,
+
+    #[error("Amount is over your credential tier's limit")]
+    TierLimitExceeded {
+        amount: Option<u64>,
+        limit: Option<u64>,
+    },
+
+    #[error("The token's share price is out of date; try again after the next update")]
+    StaleConversionRate {
+        /// Seconds
+        age: Option<i64>,
+        max_age: Option<i64>,
+    },
+
+    #[error("The bridge doesn't support one of this token's extensions")]
+    ExtensionNotAllowed,
+
+    #[error("Transfer was already processed")]
+    AlreadyProcessed,
+
+    #[error("Transfer arrived ahead of the delivery window")]
+    OutOfOrderDelivery {
+        sequence: Option<u64>,
+        /// Lowest sequence the inbox accepts
+        next_sequence: Option<u64>,
+        window: Option<u64>,
+    },
+
+    #[error("Too many transfers are pending; try again later")]
+    BridgeCongested {
+        pending: Option<u64>,
+        reject_at: Option<u64>,
+    },
+
+    #[error("Not enough guardian signatures")]
+    InsufficientGuardianSignatures {
+        signed: Option<u64>,
+        threshold: Option<u64>,
+    },
+
+    #[error("The fee quote has expired; get a new one")]
+    FeeQuoteExpired { expires_at: Option<i64> },
+
+    #[error("The token is short of collateral; redeem through the redemption queue instead")]
+    RedemptionQueueActive,
+
+    #[error("{message} ({name}, error {code})")]
+    Other {
+        code: u32,
+        name: String,
+        message: String,
+        context: ErrorContext,
+    },
+}
+
+/// The category and `key=value` pairs a failed check logged
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    pub category: Option<ErrorCategory>,
+    pub values: BTreeMap<String, String>,
+}
+
+impl ErrorContext {
+    fn parse(line: &str) -> Option<Self> {
+        let rest = line
+            .strip_prefix("Program log: ")?
+            .strip_prefix(ERROR_CONTEXT_PREFIX)?;
+        let mut context = ErrorContext::default();
+        for (key, value) in rest.split_whitespace().filter_map(|pair| pair.split_once('=')) {
+            if key == "category" {
+                context.category = ErrorCategory::parse(value);
+            } else {
+                context.values.insert(key.to_string(), value.to_string());
+            }
+        }
+        Some(context)
+    }
+
+    /// The value logged for `key`, if there is one and it parses as `T`
+    pub fn get<T: FromStr>(&self, key: &str) -> Option<T> {
+        self.values.get(key)?.parse().ok()
+    }
+}
+
+impl BridgeError {
+    fn from_anchor(code: u32, name: &str, message: &str, context: ErrorContext) -> Self {
+        match name {
+            "BridgePaused" => BridgeError::BridgePaused,
+            "TokenPaused" => BridgeError::TokenPaused,
+            "CouncilHalted" => BridgeError::CouncilHalted,
+            "GuardiansUnresponsive" => BridgeError::GuardiansUnresponsive,
+            "ChainDisabled" => BridgeError::ChainDisabled,
+            "AmountTooSmall" => BridgeError::AmountTooSmall {
+                amount: context.get("amount"),
+                minimum: context.get("minimum"),
+                fee: context.get("fee"),
+            },
+            "InvalidEthAddress"
+            | "InvalidStellarAddress"
+            | "InvalidCosmosAddress"
+            | "InvalidBitcoinAddress" => BridgeError::InvalidRecipient,
+            "CredentialRequired" => BridgeError::CredentialRequired,
+            "CredentialExpired" => BridgeError::CredentialExpired,
+            "TierLimitExceeded" => BridgeError::TierLimitExceeded {
+                amount: context.get("amount"),
+                limit: context.get("limit"),
+            },
+            "StaleConversionRate" => BridgeError::StaleConversionRate {
+                age: context.get("age"),
+                max_age: context.get("max_age"),
+            },
+            "ExtensionNotAllowed" => BridgeError::ExtensionNotAllowed,
+            "AlreadyProcessed" => BridgeError::AlreadyProcessed,
+            "OutOfOrderDelivery" => BridgeError::OutOfOrderDelivery {
+                sequence: context.get("sequence"),
+                next_sequence: context.get("next_sequence"),
+                window: context.get("window"),
+            },
+            "BridgeCongested" => BridgeError::BridgeCongested {
+                pending: context.get("pending"),
+                reject_at: context.get("reject_at"),
+            },
+            "InsufficientGuardianSignatures" => BridgeError::InsufficientGuardianSignatures {
+                signed: context.get("signed"),
+                threshold: context.get("threshold"),
+            },
+            "FeeQuoteExpired" => BridgeError::FeeQuoteExpired {
+                expires_at: context.get("expires_at"),
+            },
+            "RedemptionQueueActive" => BridgeError::RedemptionQueueActive,
+            _ => BridgeError::Other {
+                code,
+                name: name.to_string(),
+                message: message.to_string(),
+                context,
+            },
+        }
+    }
+
+    /**
+     * Which kind of check failed
+     *
+     * None for failures outside the bridge program (InsufficientFunds),
+     * and for `Other` errors whose check didn't log a context.
+     */
+    pub fn category(&self) -> Option<ErrorCategory> {
+        let code = match self {
+            BridgeError::BridgePaused => ErrorCode::BridgePaused,
+            BridgeError::TokenPaused => ErrorCode::TokenPaused,
+            BridgeError::CouncilHalted => ErrorCode::CouncilHalted,
+            BridgeError::GuardiansUnresponsive => ErrorCode::GuardiansUnresponsive,
+            BridgeError::ChainDisabled => ErrorCode::ChainDisabled,
+            BridgeError::AmountTooSmall { .. } => ErrorCode::AmountTooSmall,
+            BridgeError::InsufficientFunds => return None,
+            BridgeError::InvalidRecipient => ErrorCode::InvalidEthAddress,
+            BridgeError::CredentialRequired => ErrorCode::CredentialRequired,
+            BridgeError::CredentialExpired => ErrorCode::CredentialExpired,
+            BridgeError::TierLimitExceeded { .. } => ErrorCode::TierLimitExceeded,
+            BridgeError::StaleConversionRate { .. } => ErrorCode::StaleConversionRate,
+            BridgeError::ExtensionNotAllowed => ErrorCode::ExtensionNotAllowed,
+            BridgeError::AlreadyProcessed => ErrorCode::AlreadyProcessed,
+            BridgeError::OutOfOrderDelivery { .. } => ErrorCode::OutOfOrderDelivery,
+            BridgeError::BridgeCongested { .. } => ErrorCode::BridgeCongested,
+            BridgeError::InsufficientGuardianSignatures { .. } => {
+                ErrorCode::InsufficientGuardianSignatures
+            }
+            BridgeError::FeeQuoteExpired { .. } => ErrorCode::FeeQuoteExpired,
+            BridgeError::RedemptionQueueActive => ErrorCode::RedemptionQueueActive,
+            BridgeError::Other { context, .. } => return context.category,
+        };
+        Some(code.category())
+    }
+}
+
+/// A simulation that succeeded
+#[derive(Debug, Clone)]
+pub struct Preflight {
+    pub units_consumed: u64,
+    pub logs: Vec<String>,
+}
+
+impl Preflight {
+    /// Simulated usage plus a margin, for `TransferBuilder::compute_unit_limit`
+    pub fn suggested_compute_unit_limit(&self) -> u32 {
+        let units = self.units_consumed * (100 + COMPUTE_UNIT_MARGIN_PERCENT) / 100;
+        units.min(MAX_COMPUTE_UNITS as u64) as u32
+    }
+}
+
+/**
+ * Simulate `transaction`
+ *
+ * Signatures aren't checked and the blockhash is replaced, so an
+ * unsigned transaction works too.
+ */
+pub async fn preflight(rpc: &RpcClient, transaction: &VersionedTransaction) -> Result<Preflight> {
+    let result = rpc
+        .simulate_transaction_with_config(
+            transaction,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(CommitmentConfig::confirmed()),
+                ..RpcSimulateTransactionConfig::default()
+            },
+        )
+        .await?
+        .value;
+
+    let logs = result.logs.unwrap_or_default();
+    if let Some(err) = result.err {
+        return Err(decode_failure(&err, &logs));
+    }
+
+    Ok(Preflight {
+        units_consumed: result.units_consumed.unwrap_or_default(),
+        logs,
+    })
+}
+
+/// Wrap an unsigned message for simulation
+pub fn unsigned(message: solana_sdk::message::VersionedMessage) -> VersionedTransaction {
+    let signatures = vec![Signature::default(); message.header().num_required_signatures as usize];
+    VersionedTransaction {
+        signatures,
+        message,
+    }
+}
+
+/**
+ * Explain a failed transaction from its error and logs
+ */
+pub fn decode_failure(err: &TransactionError, logs: &[String]) -> Error {
+    // A context line comes right before the AnchorError line it belongs to
+    let mut context = None;
+    for line in logs {
+        if let Some(parsed) = ErrorContext::parse(line) {
+            context = Some(parsed);
+            continue;
+        }
+        if let Some(error) = parse_anchor_error(line, context.take().unwrap_or_default()) {
+            return Error::Bridge(error);
+        }
+        // SPL Token / Token-2022 balance check
+        if line.ends_with("Error: insufficient funds") {
+            return Error::Bridge(BridgeError::InsufficientFunds);
+        }
+    }
+    Error::Simulation(err.to_string())
+}
+
+fn parse_anchor_error(line: &str, context: ErrorContext) -> Option<BridgeError> {
+    let rest = line.strip_prefix("Program log: AnchorError")?;
+    let name = field(rest, "Error Code: ")?;
+    let code = field(rest, "Error Number: ")?.parse().ok()?;
+    let message = rest
+        .split_once("Error Message: ")
+        .map_or("", |(_, message)| message.trim_end_matches('.'));
+    Some(BridgeError::from_anchor(code, name, message, context))
+}
+
+/// The text after `label` up to the next ". " (or the end)
+fn field<'a>(line: &'a str, label: &str) -> Option<&'a str> {
+    let start = line.find(label)? + label.len();
+    let value = &line[start..];
+    let end = value.find(". ").unwrap_or(value.len());
+    Some(value[..end].trim_end_matches('.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn failure(logs: &[&str]) -> BridgeError {
+        let err = TransactionError::AccountInUse;
+        let logs: Vec<String> = logs.iter().map(|line| line.to_string()).collect();
+        match decode_failure(&err, &logs) {
+            Error::Bridge(error) => error,
+            other => panic!("not a bridge error: {other}"),
+        }
+    }
+
+    #[test]
+    fn fills_fields_from_context() {
+        let error = failure(&[
+            "Program log: Instruction: Lock",
+            "Program log: Error context: category=limits amount=500 limit=400",
+            "Program log: AnchorError thrown in src/credential.rs:82. Error Code: \
+             TierLimitExceeded. Error Number: 6029. Error Message: Amount exceeds the limit \
+             for this credential tier.",
+        ]);
+        assert_eq!(
+            error,
+            BridgeError::TierLimitExceeded {
+                amount: Some(500),
+                limit: Some(400)
+            }
+        );
+        assert_eq!(error.category(), Some(ErrorCategory::Limits));
+    }
+
+    #[test]
+    fn works_without_context() {
+        let error = failure(&[
+            "Program log: AnchorError occurred. Error Code: AmountTooSmall. Error Number: 6015. \
+             Error Message: Amount does not cover the bridge fee.",
+        ]);
+        assert_eq!(
+            error,
+            BridgeError::AmountTooSmall {
+                amount: None,
+                minimum: None,
+                fee: None
+            }
+        );
+    }
+
+    #[test]
+    fn keeps_context_on_other_errors() {
+        let error = failure(&[
+            "Program log: Error context: category=fees age=90 max_age=60",
+            "Program log: AnchorError occurred. Error Code: StalePrice. Error Number: 6077. \
+             Error Message: Price update is too old.",
+        ]);
+        let BridgeError::Other { name, context, .. } = &error else {
+            panic!("expected Other, got {error:?}");
+        };
+        assert_eq!(name, "StalePrice");
+        assert_eq!(context.get::<i64>("age"), Some(90));
+        assert_eq!(error.category(), Some(ErrorCategory::Fees));
+    }
+}
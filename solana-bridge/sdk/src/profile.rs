@@ -0,0 +1,91 @@
+//! Built-in route profiles
+//!
+//!   let profile = route_profile("devnet-sepolia")?;
+//!   let rpc = RpcClient::new(profile.solana.rpc_url.clone());
+//!
+//! A profile is everything a client needs to reach one pair of public
+//! networks besides its keys and the deployment's addresses: endpoints,
+//! the EVM chain's EIP-155 ID next to the ID it is registered under in
+//! the bridge, its confirmation depth, and where testnet funds come from.
+//! They live in profiles.json at the workspace root, which the relayer
+//! (BRIDGE_PROFILE), bridge-cli (--profile) and `cargo xtask demo` all
+//! read, so the three agree on what "devnet-sepolia" means.
+
+use serde::Deserialize;
+
+use crate::error::{Error, Result};
+
+const PROFILES: &str = include_str!("../../profiles.json");
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteProfile {
+    pub description: String,
+    pub solana: SolanaProfile,
+    pub ethereum: EthereumProfile,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SolanaProfile {
+    /// Where `cargo xtask deploy` wrote the deployment (deployments/<cluster>.json)
+    pub cluster: String,
+    pub rpc_url: String,
+    /// SOL to airdrop to a wallet that runs low (0 = no faucet)
+    pub airdrop_sol: f64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EthereumProfile {
+    pub network: String,
+    pub rpc_url: String,
+    /// What eth_chainId must return
+    pub evm_chain_id: u64,
+    /// The chain's ID in the bridge's registry (chain_ids)
+    pub registry_chain_id: u64,
+    pub confirmations: u32,
+    /// No API for it; printed where ETH runs low
+    pub faucet_url: Option<String>,
+}
+
+/// The profile called `name`
+pub fn route_profile(name: &str) -> Result<RouteProfile> {
+    route_profiles()?
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, profile)| profile)
+        .ok_or_else(|| Error::Profile(format!("no profile called {name}")))
+}
+
+/// Every built-in profile, by name
+pub fn route_profiles() -> Result<Vec<(String, RouteProfile)>> {
+    let profiles: serde_json::Map<String, serde_json::Value> =
+        serde_json::from_str(PROFILES).map_err(|e| Error::Profile(e.to_string()))?;
+    profiles
+        .into_iter()
+        .map(|(name, value)| {
+            let profile = serde_json::from_value(value)
+                .map_err(|e| Error::Profile(format!("{name}: {e}")))?;
+            Ok((name, profile))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn devnet_sepolia_pairs_sepolia_with_the_ethereum_registration() {
+        let profile = route_profile("devnet-sepolia").unwrap();
+        assert_eq!(profile.solana.cluster, "devnet");
+        assert_eq!(profile.ethereum.evm_chain_id, 11_155_111);
+        assert_eq!(profile.ethereum.registry_chain_id, solana_bridge::chain_ids::ETHEREUM);
+    }
+
+    #[test]
+    fn unknown_profiles_are_errors() {
+        assert!(route_profile("mainnet-sepolia").is_err());
+    }
+}
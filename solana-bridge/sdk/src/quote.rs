@@ -0,0 +1,147 @@
+//! Signed fee quotes
+//!
+//! A relayer quotes the fee for one lock and signs it with its guardian
+//! key; `TransferBuilder::lock_with_quote` then pays exactly that fee,
+//! however the fee table changes before the lock lands. Fetch a quote
+//! from the relayer's status API (`GET /quote`) with `fetch`.
+
+use std::str::FromStr;
+
+use serde_json::Value;
+use solana_sdk::ed25519_program;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+
+use crate::error::{Error, Result};
+
+pub use solana_bridge::quote::{FeeQuote, QUOTE_LEN};
+
+/// Ed25519 instruction header: count (1), padding (1), 7 u16 offsets
+const ED25519_HEADER_LEN: u16 = 16;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedFeeQuote {
+    pub quote: FeeQuote,
+    /// Guardian that signed it
+    pub signer: Pubkey,
+    pub signature: Signature,
+}
+
+impl SignedFeeQuote {
+    /// Does the signature check out? (the program can't tell you why it doesn't)
+    pub fn verify(&self) -> bool {
+        self.signature
+            .verify(self.signer.as_ref(), &self.quote.to_bytes())
+    }
+
+    /**
+     * Ed25519 program instruction checking the signature
+     *
+     * Must come right before the lock.
+     */
+    pub fn verify_instruction(&self) -> Instruction {
+        ed25519_instruction(&self.signer, &self.signature, &self.quote.to_bytes())
+    }
+}
+
+/**
+ * Ed25519 program instruction checking one signature
+ *
+ * Key, signature and message all live in its own data, the only layout
+ * the program accepts.
+ */
+pub(crate) fn ed25519_instruction(
+    signer: &Pubkey,
+    signature: &Signature,
+    message: &[u8],
+) -> Instruction {
+    let key_at = ED25519_HEADER_LEN;
+    let signature_at = key_at + 32;
+    let message_at = signature_at + 64;
+
+    let mut data = vec![1, 0];
+    for value in [
+        signature_at,
+        u16::MAX,
+        key_at,
+        u16::MAX,
+        message_at,
+        message.len() as u16,
+        u16::MAX,
+    ] {
+        data.extend_from_slice(&value.to_le_bytes());
+    }
+    data.extend_from_slice(signer.as_ref());
+    data.extend_from_slice(signature.as_ref());
+    data.extend_from_slice(message);
+
+    Instruction {
+        program_id: ed25519_program::ID,
+        accounts: vec![],
+        data,
+    }
+}
+
+/**
+ * Ask the relayer at `api_url` for a quote
+ *
+ * Fails if the answer isn't for this lock or isn't signed by the key
+ * it names; whether that key is a guardian is checked on-chain.
+ */
+pub async fn fetch(
+    api_url: &str,
+    mint: &Pubkey,
+    amount: u64,
+    dest_chain_id: u64,
+) -> Result<SignedFeeQuote> {
+    let url = format!(
+        "{}/quote?mint={mint}&amount={amount}&chain={dest_chain_id}",
+        api_url.trim_end_matches('/')
+    );
+    let response: Value = reqwest::get(&url)
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| Error::InvalidFeeQuote(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| Error::InvalidFeeQuote(e.to_string()))?;
+
+    let field = |name: &str| {
+        response[name]
+            .as_str()
+            .ok_or_else(|| Error::InvalidFeeQuote(format!("missing {name}")))
+    };
+    let number = |name: &str| {
+        field(name)?
+            .parse::<u64>()
+            .map_err(|e| Error::InvalidFeeQuote(format!("{name}: {e}")))
+    };
+    let key = |name: &str| {
+        Pubkey::from_str(field(name)?).map_err(|e| Error::InvalidFeeQuote(format!("{name}: {e}")))
+    };
+
+    let signed = SignedFeeQuote {
+        quote: FeeQuote {
+            mint: key("mint")?,
+            amount: number("amount")?,
+            fee: number("fee")?,
+            dest_chain_id: number("destChainId")?,
+            expires_at: number("expiresAt")? as i64,
+        },
+        signer: key("signer")?,
+        signature: Signature::from_str(field("signature")?)
+            .map_err(|e| Error::InvalidFeeQuote(format!("signature: {e}")))?,
+    };
+
+    let quote = &signed.quote;
+    if quote.mint != *mint || quote.amount != amount || quote.dest_chain_id != dest_chain_id {
+        return Err(Error::InvalidFeeQuote(
+            "quote is for another lock".to_string(),
+        ));
+    }
+    if !signed.verify() {
+        return Err(Error::InvalidFeeQuote("bad signature".to_string()));
+    }
+    Ok(signed)
+}
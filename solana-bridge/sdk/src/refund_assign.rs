@@ -0,0 +1,49 @@
+//! Signed refund assignments
+//!
+//! The refund owner of a stuck outbound transfer signs one to sell its
+//! refund right; the buyer passes it to `TransferBuilder::assign_refund`,
+//! which pays the holder the signed price and makes the buyer the one
+//! reclaim_expired and cancellations pay.
+
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+
+use crate::quote::ed25519_instruction;
+
+pub use solana_bridge::refund_assign::{RefundAssignment, REFUND_ASSIGNMENT_LEN};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignedRefundAssignment {
+    pub assignment: RefundAssignment,
+    /// The transfer's refund owner
+    pub signer: Pubkey,
+    pub signature: Signature,
+}
+
+impl SignedRefundAssignment {
+    /// Sign `assignment` with the refund owner's wallet
+    pub fn sign(assignment: RefundAssignment, holder: &dyn Signer) -> Self {
+        SignedRefundAssignment {
+            assignment,
+            signer: holder.pubkey(),
+            signature: holder.sign_message(&assignment.to_bytes()),
+        }
+    }
+
+    /// Does the signature check out?
+    pub fn verify(&self) -> bool {
+        self.signature
+            .verify(self.signer.as_ref(), &self.assignment.to_bytes())
+    }
+
+    /**
+     * Ed25519 program instruction checking the signature
+     *
+     * Must come right before assign_refund.
+     */
+    pub fn verify_instruction(&self) -> Instruction {
+        ed25519_instruction(&self.signer, &self.signature, &self.assignment.to_bytes())
+    }
+}
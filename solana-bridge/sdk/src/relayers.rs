@@ -0,0 +1,63 @@
+//! Relayer discovery from the program's registrations
+//!
+//!   let relayers = relayers(&rpc).await?;
+//!   let cheapest = relayers
+//!       .iter()
+//!       .filter(|r| r.serves(route_id))
+//!       .min_by_key(|r| r.fee_schedule.fee_bps);
+//!
+//! Relayers register themselves (`register_relayer`) with an endpoint,
+//! the relay routes they serve and their fees. Ejected relayers are left
+//! out. The terms are what the relayer advertises; the program doesn't
+//! enforce them.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_account_decoder::UiAccountEncoding;
+use solana_bridge::RelayerRegistration;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+use crate::error::{Error, Result};
+use crate::pda;
+
+/// Every registered relayer that isn't ejected
+pub async fn relayers(rpc: &RpcClient) -> Result<Vec<RelayerRegistration>> {
+    let program_id = solana_bridge::ID;
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &RelayerRegistration::DISCRIMINATOR,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let registrations = rpc
+        .get_program_accounts_with_config(&program_id, config)
+        .await?
+        .into_iter()
+        .map(|(_, account)| {
+            RelayerRegistration::try_deserialize(&mut account.data.as_slice())
+                .map_err(|e| Error::Decode(e.to_string()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let ejections: Vec<_> = registrations
+        .iter()
+        .map(|r| pda::ejected_relayer(&program_id, &r.relayer))
+        .collect();
+    let mut ejected = Vec::with_capacity(ejections.len());
+    // getMultipleAccounts takes at most 100 addresses
+    for chunk in ejections.chunks(100) {
+        ejected.extend(rpc.get_multiple_accounts(chunk).await?.into_iter().map(|a| a.is_some()));
+    }
+
+    Ok(registrations
+        .into_iter()
+        .zip(ejected)
+        .filter_map(|(registration, ejected)| (!ejected).then_some(registration))
+        .collect())
+}
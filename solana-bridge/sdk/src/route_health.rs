@@ -0,0 +1,111 @@
+//! Relay route health, to warn before a transfer gets stuck
+//!
+//!   let health = route_health(&rpc, route_id, 15 * 60).await?;
+//!   match health.verdict {
+//!       RouteVerdict::Healthy => { /* send */ }
+//!       RouteVerdict::Degraded => { /* warn: delivery may be slow */ }
+//!       RouteVerdict::Unhealthy | RouteVerdict::Unknown => { /* warn, or pick another route */ }
+//!   }
+//!
+//! Reads the route's RouteHealth account (see the program's
+//! `report_route_health`). A route nobody has scored, or whose score is
+//! older than `max_age` seconds, is Unknown: the crank that reports it
+//! may be as stuck as the route. Aggregators can rank every route with
+//! `route_healths`.
+
+use anchor_lang::{AccountDeserialize, Discriminator};
+use solana_account_decoder::UiAccountEncoding;
+use solana_bridge::{RouteHealth, RouteStatus};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+
+use crate::error::{Error, Result};
+use crate::pda;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RouteVerdict {
+    Healthy,
+    Degraded,
+    Unhealthy,
+    /// Never scored, or not recently
+    Unknown,
+}
+
+#[derive(Clone)]
+pub struct RouteHealthView {
+    pub route_id: u32,
+    /// The latest report, if the route was ever scored
+    pub health: Option<RouteHealth>,
+    pub verdict: RouteVerdict,
+}
+
+impl RouteHealthView {
+    fn new(route_id: u32, health: Option<RouteHealth>, now: i64, max_age: i64) -> Self {
+        let verdict = match &health {
+            Some(h) if now.saturating_sub(h.updated_at) <= max_age => match h.status() {
+                RouteStatus::Healthy => RouteVerdict::Healthy,
+                RouteStatus::Degraded => RouteVerdict::Degraded,
+                RouteStatus::Unhealthy => RouteVerdict::Unhealthy,
+            },
+            _ => RouteVerdict::Unknown,
+        };
+        RouteHealthView {
+            route_id,
+            health,
+            verdict,
+        }
+    }
+
+    /// The score, or None when the verdict is Unknown
+    pub fn score(&self) -> Option<u8> {
+        self.health
+            .as_ref()
+            .filter(|_| self.verdict != RouteVerdict::Unknown)
+            .map(|h| h.score)
+    }
+}
+
+/// Health of relay route `route_id`, judged stale after `max_age` seconds
+pub async fn route_health(rpc: &RpcClient, route_id: u32, max_age: i64) -> Result<RouteHealthView> {
+    let program_id = solana_bridge::ID;
+    let health = rpc
+        .get_account_with_commitment(&pda::route_health(&program_id, route_id), rpc.commitment())
+        .await?
+        .value
+        .map(|account| decode(&account.data))
+        .transpose()?;
+    let now = rpc.get_block_time(rpc.get_slot().await?).await?;
+    Ok(RouteHealthView::new(route_id, health, now, max_age))
+}
+
+/// Every scored route, healthiest first (stale ones last)
+pub async fn route_healths(rpc: &RpcClient, max_age: i64) -> Result<Vec<RouteHealthView>> {
+    let config = RpcProgramAccountsConfig {
+        filters: Some(vec![RpcFilterType::Memcmp(Memcmp::new_base58_encoded(
+            0,
+            &RouteHealth::DISCRIMINATOR,
+        ))]),
+        account_config: RpcAccountInfoConfig {
+            encoding: Some(UiAccountEncoding::Base64),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let accounts = rpc.get_program_accounts_with_config(&solana_bridge::ID, config).await?;
+    let now = rpc.get_block_time(rpc.get_slot().await?).await?;
+
+    let mut views = accounts
+        .into_iter()
+        .map(|(_, account)| {
+            let health = decode(&account.data)?;
+            Ok(RouteHealthView::new(health.route_id, Some(health), now, max_age))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    views.sort_by_key(|view| std::cmp::Reverse(view.score()));
+    Ok(views)
+}
+
+fn decode(data: &[u8]) -> Result<RouteHealth> {
+    RouteHealth::try_deserialize(&mut &data[..]).map_err(|e| Error::Decode(e.to_string()))
+}
@@ -0,0 +1,43 @@
+//! .sol recipients through the Solana Name Service
+//!
+//! A .sol domain resolves to the owner of its name account, the same
+//! check `pin_recipient_name` makes on-chain for inbound transfers:
+//!
+//!   let recipient = sns::resolve(&rpc, "alice.sol").await?;
+
+use solana_bridge::names;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+
+/// Whether `input` is a .sol domain rather than a base58 address
+pub fn is_sol_domain(input: &str) -> bool {
+    names::sol_label(input).is_some()
+}
+
+/// Owner of the .sol domain `name`
+pub async fn resolve(rpc: &RpcClient, name: &str) -> Result<Pubkey> {
+    let label = names::sol_label(name)
+        .ok_or_else(|| Error::Sns(format!("{name}: not a second-level .sol domain")))?;
+    let account = rpc
+        .get_account(&names::sns_domain_address(label))
+        .await
+        .map_err(|_| Error::Sns(format!("{name} is not registered")))?;
+    if account.owner != names::NAME_SERVICE_PROGRAM || account.data.len() < 64 {
+        return Err(Error::Sns(format!("{name}: not a name service account")));
+    }
+    let owner: [u8; 32] = account.data[32..64].try_into().expect("32 bytes");
+    Ok(Pubkey::new_from_array(owner))
+}
+
+/// A base58 address as is, or the owner of a .sol domain
+pub async fn resolve_recipient(rpc: &RpcClient, input: &str) -> Result<Pubkey> {
+    let input = input.trim();
+    if is_sol_domain(input) {
+        return resolve(rpc, input).await;
+    }
+    input
+        .parse()
+        .map_err(|_| Error::Sns(format!("{input} is neither an address nor a .sol domain")))
+}
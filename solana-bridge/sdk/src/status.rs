@@ -0,0 +1,142 @@
+//! Transfer status through the program's view instruction
+//!
+//! Simulates `get_transfer_status` and decodes its return data, so a
+//! client needs no account layouts, just the transfer's direction, nonce
+//! and ID:
+//!
+//!   let info = transfer_status(&rpc, &payer, TransferDirection::Outbound, nonce, id).await?;
+//!
+//! Nothing is sent; `payer` only has to exist.
+//!
+//! `transfers_by_source_tx` goes from an Ethereum transaction hash to the
+//! inbound transfers it carried, with account reads only.
+
+use anchor_lang::{AccountDeserialize, AnchorDeserialize, InstructionData, ToAccountMetas};
+use base64::Engine;
+use solana_bridge::{
+    accounts, instruction, SourceTx, TransferDirection, TransferRecord, TransferStatusInfo,
+};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, VersionedMessage};
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::{Error, Result};
+use crate::pda;
+use crate::preflight::{decode_failure, unsigned};
+
+/**
+ * Where the transfer stands; `status` is `None` if it has no record
+ * (never created, pruned after a checkpoint or compressed; see
+ * `compression`)
+ */
+pub async fn transfer_status(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    direction: TransferDirection,
+    nonce: u64,
+    transfer_id: [u8; 32],
+) -> Result<TransferStatusInfo> {
+    let program_id = solana_bridge::ID;
+    let transfer_record = match direction {
+        TransferDirection::Outbound => pda::outbound_transfer(&program_id, nonce),
+        TransferDirection::Inbound => pda::inbound_transfer(&program_id, nonce),
+    };
+    let attestation = match direction {
+        TransferDirection::Outbound => None,
+        TransferDirection::Inbound => Some(pda::attestation(&program_id, nonce)),
+    };
+
+    let instruction = Instruction {
+        program_id,
+        accounts: accounts::TransferStatusView {
+            bridge_state: pda::bridge_state(&program_id),
+            transfer_record,
+            attestation,
+        }
+        .to_account_metas(None),
+        data: instruction::GetTransferStatus { transfer_id }.data(),
+    };
+    let (info, _) = simulate_view(rpc, payer, instruction).await?;
+    Ok(info)
+}
+
+/**
+ * The inbound transfers a source-chain transaction carried, by nonce
+ *
+ * From the SourceTx the delivering relayer recorded (see
+ * `record_source_tx`); empty if none was. A transfer whose record is
+ * gone (pruned or compressed) comes back as `None`.
+ */
+pub async fn transfers_by_source_tx(
+    rpc: &RpcClient,
+    tx_hash: &[u8; 32],
+) -> Result<Vec<(u64, Option<TransferRecord>)>> {
+    let program_id = solana_bridge::ID;
+    let address = pda::source_tx(&program_id, tx_hash);
+    let Some(account) = rpc
+        .get_account_with_commitment(&address, rpc.commitment())
+        .await?
+        .value
+    else {
+        return Ok(Vec::new());
+    };
+    let source_tx = SourceTx::try_deserialize(&mut &account.data[..])
+        .map_err(|e| Error::Decode(e.to_string()))?;
+
+    let records: Vec<Pubkey> = source_tx
+        .nonces
+        .iter()
+        .map(|&nonce| pda::inbound_transfer(&program_id, nonce))
+        .collect();
+    let accounts = rpc
+        .get_multiple_accounts_with_commitment(&records, rpc.commitment())
+        .await?
+        .value;
+
+    source_tx
+        .nonces
+        .iter()
+        .zip(accounts)
+        .map(|(&nonce, account)| {
+            let record = account
+                .map(|account| TransferRecord::try_deserialize(&mut &account.data[..]))
+                .transpose()
+                .map_err(|e| Error::Decode(e.to_string()))?;
+            Ok((nonce, record))
+        })
+        .collect()
+}
+
+/**
+ * Simulate a view instruction and decode what it returned
+ *
+ * Also returns the slot the simulation ran at.
+ */
+pub(crate) async fn simulate_view<T: AnchorDeserialize>(
+    rpc: &RpcClient,
+    payer: &Pubkey,
+    instruction: Instruction,
+) -> Result<(T, u64)> {
+    let program_id = instruction.program_id;
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let message = v0::Message::try_compile(payer, &[instruction], &[], blockhash)
+        .map_err(|e| Error::Compile(e.to_string()))?;
+    let response = rpc
+        .simulate_transaction(&unsigned(VersionedMessage::V0(message)))
+        .await?;
+    let (slot, result) = (response.context.slot, response.value);
+
+    if let Some(err) = result.err {
+        return Err(decode_failure(&err, &result.logs.unwrap_or_default()));
+    }
+    let return_data = result
+        .return_data
+        .filter(|data| data.program_id == program_id.to_string())
+        .ok_or_else(|| Error::Decode("no return data".to_string()))?;
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(&return_data.data.0)
+        .map_err(|e| Error::Decode(e.to_string()))?;
+    let value = T::deserialize(&mut &bytes[..]).map_err(|e| Error::Decode(e.to_string()))?;
+    Ok((value, slot))
+}
@@ -0,0 +1,272 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+/**
+ * @title MockSolanaBridge
+ * @notice Ethereum side of the Solana bridge, as the relayer sees it, for
+ *         end-to-end tests on anvil
+ * @dev Also the wrapped token itself (bare balances, no ERC20 extras).
+ *      Lock and Burn share one nonce counter, like the real contract the
+ *      program's Ethereum inbox expects. Mints take either the relayer's
+ *      signature or a lock proof signed by a threshold of attesters, and
+ *      stop while the Solana escrow last attested falls short of supply.
+ */
+contract MockSolanaBridge {
+    /// @notice Key whose signatures authorize mints
+    address public immutable relayer;
+
+    /// @notice Last nonce handed to a Lock or Burn toward Solana
+    uint256 public nonce;
+
+    /// @notice Solana transfer IDs already minted (replay protection)
+    mapping(bytes32 => bool) public processedNonces;
+
+    /// @notice Lock/Burn nonces the relayer confirmed delivered on Solana
+    mapping(uint256 => bool) public acknowledged;
+
+    mapping(address => uint256) public balanceOf;
+
+    /// @notice Wrapped tokens outstanding, backed by the Solana escrow
+    uint256 public totalSupply;
+
+    /// @notice Value locked here toward Solana, which the program's
+    ///         post_reserve_attestation weighs against its wrapped supply
+    uint256 public totalLocked;
+
+    /// @notice Solana's chain ID in the program's `chain_ids`
+    uint64 public constant SOLANA_CHAIN_ID = uint64(1) << 32;
+
+    /// @notice Mirrors the Solana bridge's pause, carried by BridgeStatus messages
+    bool public paused;
+
+    /// @notice Lowest outbox sequence a BridgeStatus may still have
+    uint64 public nextStatusSequence;
+
+    /// @notice Keys whose lock proof signatures count toward attesterThreshold
+    mapping(address => bool) public isAttester;
+    address[] private attesters;
+
+    /// @notice Attester signatures mintWithProof needs (0 = proofs disabled)
+    uint8 public attesterThreshold;
+
+    /// @notice A Solana escrow balance, carried by VaultBalance messages
+    struct SolanaReserve {
+        uint256 amount;
+        uint64 slot;
+    }
+
+    /// @notice Latest attested escrow balance per Solana mint
+    mapping(bytes32 => SolanaReserve) public solanaReserves;
+
+    /// @notice Lowest outbox sequence a VaultBalance may still have
+    uint64 public nextReserveSequence;
+
+    /// @notice Solana mint whose escrow backs this token (zero = unchecked)
+    bytes32 public reserveMint;
+
+    /// @notice Whether reserveMint's last attested escrow was below totalSupply
+    bool public reserveShortfall;
+
+    /// @notice A finalized Solana lock, as the relayer exports it (lock-proof.js)
+    struct LockProof {
+        bytes32 transferId;
+        address to;
+        uint256 amount;
+        uint64 slot;
+        bytes32 blockhash;
+        bytes txSignature;
+    }
+
+    event Lock(address indexed from, uint256 amount, uint256 nonce, string solanaAddress);
+    event Burn(address indexed from, uint256 amount, uint256 nonce, string solanaAddress);
+    event Mint(address indexed to, uint256 amount, bytes32 nonce);
+    event Acknowledged(uint256 indexed nonce);
+    event BridgeStatusChanged(bool paused, uint64 sequence);
+    event AttestersChanged(address[] attesters, uint8 threshold);
+    event SolanaReserveUpdated(bytes32 indexed mint, uint256 amount, uint64 slot, uint64 sequence);
+    event ReserveMintChanged(bytes32 mint);
+
+    modifier whenNotPaused() {
+        require(!paused, "Bridge paused");
+        _;
+    }
+
+    modifier whenCollateralized() {
+        require(!reserveShortfall, "Solana reserve short");
+        _;
+    }
+
+    constructor(address _relayer) {
+        relayer = _relayer;
+    }
+
+    /**
+     * @notice Mint for a Solana lock, signed by the relayer over
+     *         keccak256(to, amount, nonce) (eth_sign prefixed)
+     */
+    function mint(address to, uint256 amount, bytes32 transferId, bytes calldata signature)
+        external
+        whenNotPaused
+        whenCollateralized
+    {
+        require(!processedNonces[transferId], "Already processed");
+
+        bytes32 messageHash = keccak256(abi.encodePacked(to, amount, transferId));
+        bytes32 signed = keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", messageHash));
+        require(recover(signed, signature) == relayer, "Invalid signature");
+
+        processedNonces[transferId] = true;
+        balanceOf[to] += amount;
+        totalSupply += amount;
+        emit Mint(to, amount, transferId);
+    }
+
+    /**
+     * @notice Mint for a Solana lock proven by `threshold` attesters, each
+     *         signing keccak256(SOLANA_CHAIN_ID, transferId, to, amount, slot,
+     *         blockhash, txSignature) (eth_sign prefixed)
+     * @dev Signatures must come from distinct signers in ascending address
+     *      order; signers outside the attester set don't count
+     */
+    function mintWithProof(LockProof calldata proof, bytes[] calldata signatures)
+        external
+        whenNotPaused
+        whenCollateralized
+    {
+        require(attesterThreshold > 0, "Proofs disabled");
+        require(!processedNonces[proof.transferId], "Already processed");
+
+        bytes32 messageHash = keccak256(
+            abi.encodePacked(
+                SOLANA_CHAIN_ID,
+                proof.transferId,
+                proof.to,
+                proof.amount,
+                proof.slot,
+                proof.blockhash,
+                proof.txSignature
+            )
+        );
+        bytes32 signed = keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", messageHash));
+
+        address last;
+        uint256 valid;
+        for (uint256 i; i < signatures.length; i++) {
+            address signer = recover(signed, signatures[i]);
+            require(signer > last, "Signers not ascending");
+            last = signer;
+            if (isAttester[signer]) valid++;
+        }
+        require(valid >= attesterThreshold, "Not enough attesters");
+
+        processedNonces[proof.transferId] = true;
+        balanceOf[proof.to] += proof.amount;
+        totalSupply += proof.amount;
+        emit Mint(proof.to, proof.amount, proof.transferId);
+    }
+
+    /// @notice Burn wrapped tokens to release the originals on Solana
+    function burn(uint256 amount, string calldata solanaAddress) external whenNotPaused {
+        require(balanceOf[msg.sender] >= amount, "Insufficient balance");
+        balanceOf[msg.sender] -= amount;
+        totalSupply -= amount;
+        emit Burn(msg.sender, amount, ++nonce, solanaAddress);
+    }
+
+    /// @notice Lock ETH-side value to mint wrapped tokens on Solana
+    function lock(uint256 amount, string calldata solanaAddress) external whenNotPaused {
+        totalLocked += amount;
+        emit Lock(msg.sender, amount, ++nonce, solanaAddress);
+    }
+
+    /**
+     * @notice Record that the Lock or Burn with `transferNonce` was
+     *         delivered on Solana, once (relayer only)
+     */
+    function acknowledge(uint256 transferNonce) external {
+        require(msg.sender == relayer, "Not relayer");
+        require(transferNonce > 0 && transferNonce <= nonce, "Unknown nonce");
+        require(!acknowledged[transferNonce], "Already acknowledged");
+        acknowledged[transferNonce] = true;
+        emit Acknowledged(transferNonce);
+    }
+
+    /**
+     * @notice Pause or resume with the Solana bridge, signed by the relayer
+     *         over keccak256(SOLANA_CHAIN_ID, paused, sequence) (eth_sign
+     *         prefixed); a status older than the last one is rejected
+     */
+    function setBridgeStatus(bool _paused, uint64 sequence, bytes calldata signature) external {
+        require(sequence >= nextStatusSequence, "Stale status");
+
+        bytes32 messageHash = keccak256(abi.encodePacked(SOLANA_CHAIN_ID, _paused, sequence));
+        bytes32 signed = keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", messageHash));
+        require(recover(signed, signature) == relayer, "Invalid signature");
+
+        nextStatusSequence = sequence + 1;
+        paused = _paused;
+        emit BridgeStatusChanged(_paused, sequence);
+    }
+
+    /**
+     * @notice Record the Solana escrow of `mint` at `slot`, signed by the
+     *         relayer over keccak256(SOLANA_CHAIN_ID, mint, amount, slot,
+     *         sequence) (eth_sign prefixed); an older balance is rejected
+     * @dev For reserveMint, compares it with totalSupply now: mints stop
+     *      while the escrow is short and resume with the next balance
+     *      that covers the supply
+     */
+    function setSolanaReserve(
+        bytes32 mint,
+        uint256 amount,
+        uint64 slot,
+        uint64 sequence,
+        bytes calldata signature
+    ) external {
+        require(sequence >= nextReserveSequence, "Stale reserve");
+
+        bytes32 messageHash =
+            keccak256(abi.encodePacked(SOLANA_CHAIN_ID, mint, amount, slot, sequence));
+        bytes32 signed = keccak256(abi.encodePacked("\x19Ethereum Signed Message:\n32", messageHash));
+        require(recover(signed, signature) == relayer, "Invalid signature");
+
+        nextReserveSequence = sequence + 1;
+        solanaReserves[mint] = SolanaReserve(amount, slot);
+        if (mint == reserveMint) {
+            reserveShortfall = amount < totalSupply;
+        }
+        emit SolanaReserveUpdated(mint, amount, slot, sequence);
+    }
+
+    /// @notice Choose the Solana mint backing this token (relayer only)
+    function setReserveMint(bytes32 mint) external {
+        require(msg.sender == relayer, "Not relayer");
+        reserveMint = mint;
+        reserveShortfall = mint != bytes32(0) && solanaReserves[mint].amount < totalSupply;
+        emit ReserveMintChanged(mint);
+    }
+
+    /// @notice Replace the attester set and threshold (relayer only)
+    function setAttesters(address[] calldata _attesters, uint8 threshold) external {
+        require(msg.sender == relayer, "Not relayer");
+        require(threshold <= _attesters.length, "Threshold above set size");
+        for (uint256 i; i < attesters.length; i++) {
+            isAttester[attesters[i]] = false;
+        }
+        for (uint256 i; i < _attesters.length; i++) {
+            isAttester[_attesters[i]] = true;
+        }
+        attesters = _attesters;
+        attesterThreshold = threshold;
+        emit AttestersChanged(_attesters, threshold);
+    }
+
+    function recover(bytes32 hash, bytes calldata signature) private pure returns (address) {
+        require(signature.length == 65, "Invalid signature length");
+        bytes32 r = bytes32(signature[0:32]);
+        bytes32 s = bytes32(signature[32:64]);
+        uint8 v = uint8(signature[64]);
+        if (v < 27) v += 27;
+        return ecrecover(hash, v, r, s);
+    }
+}
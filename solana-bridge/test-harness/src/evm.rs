@@ -0,0 +1,169 @@
+//! The Ethereum side: anvil with MockSolanaBridge deployed
+//!
+//! Driven through Foundry's CLIs (forge to deploy, cast for calls and
+//! transactions) rather than an Ethereum client library.
+
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{Context, Result};
+use tokio::process::Command;
+
+use crate::process::{self, Service};
+
+/// anvil's first default account: deploys the bridge and relays
+pub const RELAYER_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+pub const RELAYER_ADDRESS: &str = "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266";
+
+/// anvil's second default account: the user on the Ethereum side
+pub const USER_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+pub const USER_ADDRESS: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct Evm {
+    pub rpc_url: String,
+    pub bridge: String,
+    _anvil: Service,
+}
+
+impl Evm {
+    /// Start anvil as chain 1 (what the relayer expects) and deploy the bridge
+    pub async fn start(log_dir: &Path) -> Result<Self> {
+        let port = process::free_port()?;
+        let rpc_url = format!("http://127.0.0.1:{port}");
+
+        let mut anvil = Command::new("anvil");
+        anvil.args(["--port", &port.to_string(), "--chain-id", "1", "--block-time", "1"]);
+        let mut anvil = Service::spawn("anvil", anvil, log_dir)?;
+
+        process::wait_for("anvil", STARTUP_TIMEOUT, || {
+            let running = anvil.check_running();
+            let rpc_url = rpc_url.clone();
+            async move {
+                running?;
+                Ok(cast_block_number(&rpc_url).await.ok())
+            }
+        })
+        .await?;
+
+        let mut forge = Command::new("forge");
+        forge
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .args(["create", "contracts/MockSolanaBridge.sol:MockSolanaBridge"])
+            .args(["--rpc-url", &rpc_url, "--private-key", RELAYER_KEY])
+            .args(["--broadcast", "--json", "--constructor-args", RELAYER_ADDRESS]);
+        let deployed: serde_json::Value = serde_json::from_str(&process::output(forge).await?)?;
+        let bridge = deployed["deployedTo"]
+            .as_str()
+            .context("forge create printed no deployedTo")?
+            .to_string();
+
+        Ok(Self {
+            rpc_url,
+            bridge,
+            _anvil: anvil,
+        })
+    }
+
+    /// Whether the bridge has minted the Solana transfer `transfer_id`
+    pub async fn is_processed(&self, transfer_id: &[u8; 32]) -> Result<bool> {
+        let id = format!("0x{}", hex(transfer_id));
+        Ok(self.call("processedNonces(bytes32)(bool)", &[&id]).await? == "true")
+    }
+
+    /// Wrapped balance of `address`
+    pub async fn balance_of(&self, address: &str) -> Result<u64> {
+        parse_uint(&self.call("balanceOf(address)(uint256)", &[address]).await?)
+    }
+
+    /// Nonce of the latest Lock or Burn
+    pub async fn nonce(&self) -> Result<u64> {
+        parse_uint(&self.call("nonce()(uint256)", &[]).await?)
+    }
+
+    /// Burn `amount` of the user's wrapped tokens toward `solana_address`
+    pub async fn burn(&self, amount: u64, solana_address: &str) -> Result<()> {
+        let mut cast = Command::new("cast");
+        cast.args(["send", &self.bridge, "burn(uint256,string)"])
+            .args([&amount.to_string(), solana_address])
+            .args(["--rpc-url", &self.rpc_url, "--private-key", USER_KEY]);
+        process::output(cast).await?;
+        Ok(())
+    }
+
+    /// Send a burn without waiting for it to be mined (e.g. while mining is paused)
+    pub async fn submit_burn(&self, amount: u64, solana_address: &str) -> Result<()> {
+        let mut cast = Command::new("cast");
+        cast.args(["send", "--async", &self.bridge, "burn(uint256,string)"])
+            .args([&amount.to_string(), solana_address])
+            .args(["--rpc-url", &self.rpc_url, "--private-key", USER_KEY]);
+        process::output(cast).await?;
+        Ok(())
+    }
+
+    /**
+     * Snapshot the chain; `revert` to it later to drop every block since,
+     * which is what a reorg looks like to the relayer
+     */
+    pub async fn snapshot(&self) -> Result<String> {
+        let id = self.rpc("evm_snapshot", &[]).await?;
+        Ok(id.trim_matches('"').to_string())
+    }
+
+    pub async fn revert(&self, snapshot: &str) -> Result<()> {
+        self.rpc("evm_revert", &[snapshot]).await?;
+        Ok(())
+    }
+
+    /// Stop the 1s block timer, so nothing new gets confirmations
+    pub async fn pause_mining(&self) -> Result<()> {
+        self.rpc("evm_setIntervalMining", &["0"]).await?;
+        Ok(())
+    }
+
+    pub async fn resume_mining(&self) -> Result<()> {
+        self.rpc("evm_setIntervalMining", &["1"]).await?;
+        Ok(())
+    }
+
+    /// Mine one block now
+    pub async fn mine(&self) -> Result<()> {
+        self.rpc("evm_mine", &[]).await?;
+        Ok(())
+    }
+
+    async fn rpc(&self, method: &str, params: &[&str]) -> Result<String> {
+        let mut cast = Command::new("cast");
+        cast.args(["rpc", method])
+            .args(params)
+            .args(["--rpc-url", &self.rpc_url]);
+        process::output(cast).await
+    }
+
+    async fn call(&self, signature: &str, args: &[&str]) -> Result<String> {
+        let mut cast = Command::new("cast");
+        cast.args(["call", &self.bridge, signature])
+            .args(args)
+            .args(["--rpc-url", &self.rpc_url]);
+        process::output(cast).await
+    }
+}
+
+async fn cast_block_number(rpc_url: &str) -> Result<String> {
+    let mut cast = Command::new("cast");
+    cast.args(["block-number", "--rpc-url", rpc_url]);
+    process::output(cast).await
+}
+
+/// cast prints large uints as "1000000 [1e6]"
+fn parse_uint(text: &str) -> Result<u64> {
+    let digits = text.split_whitespace().next().unwrap_or_default();
+    digits
+        .parse()
+        .with_context(|| format!("Not a u64: {text}"))
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,220 @@
+//! End-to-end harness for the bridge
+//!
+//! Starts anvil with MockSolanaBridge (contracts/), a solana-test-validator
+//! running the built program, and the JS relayer between them, so tests
+//! drive real transfers across both chains:
+//!
+//!   let harness = Harness::start(1_000_000).await?;
+//!   let trip = harness.bridge_round_trip(250_000).await?;
+//!
+//! Needs anvil, forge and cast (Foundry), solana-test-validator, node with
+//! the relayer's npm dependencies, and `anchor build` output in target/.
+//! Every process is killed when the harness is dropped; their logs stay
+//! in `Harness::work_dir` until then.
+
+pub mod evm;
+pub mod process;
+pub mod relayer;
+pub mod solana;
+
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context as _, Result};
+use solana_bridge::{chain_ids, RouteSequence, TransferRecord, TransferStatus};
+use solana_bridge_sdk::{pda, LockAccounts, TransferBuilder};
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+use tempfile::TempDir;
+
+use evm::Evm;
+use relayer::Relayer;
+use solana::{Bridge, Validator};
+
+/// How long the relayer gets to deliver each leg
+const LEG_TIMEOUT: Duration = Duration::from_secs(120);
+
+pub struct Harness {
+    pub bridge: Bridge,
+    pub evm: Evm,
+    relayer: Relayer,
+    _validator: Validator,
+    work_dir: TempDir,
+}
+
+/// What a round trip observed
+#[derive(Debug)]
+pub struct RoundTrip {
+    /// Outbound nonce of the Solana lock
+    pub outbound_nonce: u64,
+    pub transfer_id: [u8; 32],
+    /// Nonce of the Ethereum burn, i.e. the inbound nonce on Solana
+    pub inbound_nonce: u64,
+    pub to_ethereum: Duration,
+    pub to_solana: Duration,
+}
+
+impl Harness {
+    /// Start both chains and the relayer; the Solana user holds `balance`
+    pub async fn start(balance: u64) -> Result<Self> {
+        Self::start_with_env(balance, &[]).await
+    }
+
+    /// `start`, with extra environment for the relayer
+    pub async fn start_with_env(balance: u64, relayer_env: &[(&str, &str)]) -> Result<Self> {
+        let work_dir = tempfile::tempdir()?;
+        let owner = Keypair::new();
+        let keypair_path = work_dir.path().join("owner.json");
+        write_keypair_file(&owner, &keypair_path)
+            .map_err(|e| anyhow::anyhow!("{}: {e}", keypair_path.display()))?;
+
+        let validator =
+            Validator::start(&owner.pubkey(), &work_dir.path().join("ledger"), work_dir.path())
+                .await?;
+        let bridge = Bridge::setup(&validator.rpc_url, owner, balance).await?;
+        let evm = Evm::start(work_dir.path()).await?;
+        let relayer = Relayer::start(
+            &validator.rpc_url,
+            &bridge,
+            &keypair_path,
+            &evm,
+            work_dir.path(),
+            relayer_env,
+        )
+        .await?;
+
+        Ok(Self {
+            bridge,
+            evm,
+            relayer,
+            _validator: validator,
+            work_dir,
+        })
+    }
+
+    /// Where the services' logs and ledger live
+    pub fn work_dir(&self) -> &Path {
+        self.work_dir.path()
+    }
+
+    pub fn relayer_api_url(&self) -> &str {
+        &self.relayer.api_url
+    }
+
+    /// The relayer's log so far
+    pub fn relayer_log(&self) -> Result<String> {
+        Ok(std::fs::read_to_string(self.work_dir().join("relayer.log"))?)
+    }
+
+    /// The relayer's transfer store, as of its last snapshot (every 5s)
+    pub fn relayer_transfers(&self) -> Result<Vec<serde_json::Value>> {
+        let path = self.work_dir().join("transfers.json");
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+        let text = std::fs::read_to_string(&path)?;
+        serde_json::from_str(&text).with_context(|| path.display().to_string())
+    }
+
+    /**
+     * Lock `amount` on Solana toward the Ethereum user
+     *
+     * Returns the outbound nonce and transfer ID once the lock is
+     * confirmed; doesn't wait for the relayer.
+     */
+    pub async fn lock(&self, amount: u64) -> Result<(u64, [u8; 32])> {
+        let bridge = &self.bridge;
+        let program_id = solana_bridge::ID;
+        let user = bridge.user.pubkey();
+
+        let lock = TransferBuilder::new(&bridge.rpc, user)
+            .lock(
+                LockAccounts {
+                    user,
+                    user_token: bridge.user_token,
+                    mint: bridge.mint,
+                    bridge_token: bridge.escrow,
+                    fee_vault: bridge.fee_vault,
+                    token_program: anchor_spl::token::ID,
+                    bridge_pass: None,
+                },
+                amount,
+                chain_ids::ETHEREUM,
+                evm::USER_ADDRESS.to_string(),
+                None,
+            )
+            .await?
+            .build_signed(&[&bridge.user])
+            .await?;
+        bridge.rpc.send_and_confirm_transaction(&lock).await?;
+
+        let route = pda::route_sequence(&program_id, &bridge.mint, chain_ids::ETHEREUM);
+        let sequence: Option<RouteSequence> = bridge.fetch(&route).await?;
+        let nonce = sequence
+            .map(|sequence| RouteSequence::nonce_at(sequence.shard, sequence.last))
+            .unwrap_or_default();
+        let record: TransferRecord = bridge
+            .fetch(&pda::outbound_transfer(&program_id, nonce))
+            .await?
+            .ok_or_else(|| anyhow::anyhow!("No record for outbound nonce {nonce}"))?;
+        Ok((nonce, record.transfer_id))
+    }
+
+    /// Whether Solana has completed the inbound transfer `nonce`
+    pub async fn inbound_completed(&self, nonce: u64) -> Result<bool> {
+        let inbound = pda::inbound_transfer(&solana_bridge::ID, nonce);
+        let record: Option<TransferRecord> = self.bridge.fetch(&inbound).await?;
+        Ok(record.is_some_and(|r| r.status == TransferStatus::Completed))
+    }
+
+    /**
+     * Send `amount` to Ethereum and back
+     *
+     * Locks on Solana toward the Ethereum user, waits for the relayer's
+     * mint there, burns the same amount back to the Solana user, and waits
+     * for the unlock. Fees are zero, so the user ends with what they
+     * started with; anything else is an error.
+     */
+    pub async fn bridge_round_trip(&self, amount: u64) -> Result<RoundTrip> {
+        let bridge = &self.bridge;
+        let user = bridge.user.pubkey();
+        let solana_before = bridge.token_balance(&bridge.user_token).await?;
+        let evm_before = self.evm.balance_of(evm::USER_ADDRESS).await?;
+
+        let started = Instant::now();
+        let (outbound_nonce, transfer_id) = self.lock(amount).await?;
+
+        process::wait_for("the Ethereum mint", LEG_TIMEOUT, || async {
+            Ok(self.evm.is_processed(&transfer_id).await?.then_some(()))
+        })
+        .await?;
+        let to_ethereum = started.elapsed();
+
+        let evm_after = self.evm.balance_of(evm::USER_ADDRESS).await?;
+        if evm_after != evm_before + amount {
+            bail!("Ethereum balance went {evm_before} -> {evm_after}, expected +{amount}");
+        }
+
+        let started = Instant::now();
+        self.evm.burn(amount, &user.to_string()).await?;
+        let inbound_nonce = self.evm.nonce().await?;
+
+        process::wait_for("the Solana unlock", LEG_TIMEOUT, || async {
+            Ok(self.inbound_completed(inbound_nonce).await?.then_some(()))
+        })
+        .await?;
+        let to_solana = started.elapsed();
+
+        let solana_after = bridge.token_balance(&bridge.user_token).await?;
+        if solana_after != solana_before {
+            bail!("Solana balance went {solana_before} -> {solana_after}, expected no change");
+        }
+
+        Ok(RoundTrip {
+            outbound_nonce,
+            transfer_id,
+            inbound_nonce,
+            to_ethereum,
+            to_solana,
+        })
+    }
+}
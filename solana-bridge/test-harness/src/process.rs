@@ -0,0 +1,99 @@
+//! Child processes the harness owns, and waiting on them
+
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Context, Result};
+use tokio::process::{Child, Command};
+
+/// How often `wait_for` re-checks
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/**
+ * A spawned service, killed when dropped
+ *
+ * Output goes to `<log_dir>/<name>.log` so a failed run can be
+ * inspected.
+ */
+pub struct Service {
+    name: &'static str,
+    child: Child,
+}
+
+impl Service {
+    pub fn spawn(name: &'static str, mut command: Command, log_dir: &Path) -> Result<Self> {
+        let log = std::fs::File::create(log_dir.join(format!("{name}.log")))?;
+        let child = command
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .kill_on_drop(true)
+            .spawn()
+            .with_context(|| format!("Failed to start {name}; is it installed?"))?;
+        Ok(Self { name, child })
+    }
+
+    /// Fails if the service has already exited
+    pub fn check_running(&mut self) -> Result<()> {
+        if let Some(status) = self.child.try_wait()? {
+            bail!("{} exited early ({status})", self.name);
+        }
+        Ok(())
+    }
+}
+
+/// Run a command to completion and return its trimmed stdout
+pub async fn output(mut command: Command) -> Result<String> {
+    let program = format!("{:?}", command.as_std().get_program());
+    let output = command
+        .output()
+        .await
+        .with_context(|| format!("Failed to run {program}; is it installed?"))?;
+    if !output.status.success() {
+        bail!(
+            "{program} failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8(output.stdout)?.trim().to_string())
+}
+
+/// Poll `check` until it yields a value or `timeout` passes
+pub async fn wait_for<T, F, Fut>(what: &str, timeout: Duration, mut check: F) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<Option<T>>>,
+{
+    let deadline = Instant::now() + timeout;
+    let mut last_error = None;
+    loop {
+        match check().await {
+            Ok(Some(value)) => return Ok(value),
+            Ok(None) => {}
+            Err(e) => last_error = Some(e),
+        }
+        if Instant::now() >= deadline {
+            match last_error {
+                Some(e) => bail!("Timed out waiting for {what}: {e:#}"),
+                None => bail!("Timed out waiting for {what}"),
+            }
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// A local port nothing is listening on
+pub fn free_port() -> Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+/// The solana-bridge workspace directory
+pub fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("harness lives inside the workspace")
+        .to_path_buf()
+}
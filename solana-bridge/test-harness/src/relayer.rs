@@ -0,0 +1,74 @@
+//! The JS relayer (../relayer), pointed at both local chains
+
+use std::net::TcpStream;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::{bail, Result};
+use tokio::process::Command;
+
+use crate::evm::{self, Evm};
+use crate::process::{self, Service};
+use crate::solana::Bridge;
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub struct Relayer {
+    pub api_url: String,
+    _process: Service,
+}
+
+impl Relayer {
+    /**
+     * `keypair_path` holds the bridge owner, which signs unlocks; `env`
+     * is passed on over the defaults (e.g. CHAOS, see chaos.js)
+     */
+    pub async fn start(
+        solana_rpc_url: &str,
+        bridge: &Bridge,
+        keypair_path: &Path,
+        evm: &Evm,
+        work_dir: &Path,
+        env: &[(&str, &str)],
+    ) -> Result<Self> {
+        let root = process::workspace_root();
+        let relayer_dir = root.join("relayer");
+        if !relayer_dir.join("node_modules").exists() {
+            bail!("Relayer dependencies missing; run `npm install` in {}", relayer_dir.display());
+        }
+
+        let port = process::free_port()?;
+        let mut node = Command::new("node");
+        node.current_dir(&relayer_dir)
+            .arg("src/index.js")
+            .env("SOLANA_RPC_URL", solana_rpc_url)
+            .env("SOLANA_PROGRAM_ID", solana_bridge::ID.to_string())
+            .env("SOLANA_KEYPAIR_PATH", keypair_path)
+            .env("SOLANA_IDL_PATH", root.join("target/idl/solana_bridge.json"))
+            .env("SOLANA_WRAPPED_MINT", bridge.wrapped_mint.to_string())
+            .env("SOLANA_ESCROW_MINT", bridge.mint.to_string())
+            .env("SOLANA_ESCROW_ACCOUNT", bridge.escrow.to_string())
+            .env("ETHEREUM_RPC_URL", &evm.rpc_url)
+            .env("ETHEREUM_BRIDGE_ADDRESS", &evm.bridge)
+            .env("ETHEREUM_PRIVATE_KEY", evm::RELAYER_KEY)
+            .env("ETHEREUM_CHAIN_ID", "1")
+            .env("ETHEREUM_CONFIRMATIONS", "1")
+            .env("API_PORT", port.to_string())
+            .env("TRANSFER_STORE_PATH", work_dir.join("transfers.json"))
+            .envs(env.iter().copied());
+        let mut relayer = Service::spawn("relayer", node, work_dir)?;
+
+        process::wait_for("the relayer API", STARTUP_TIMEOUT, || {
+            let ready = relayer
+                .check_running()
+                .map(|_| TcpStream::connect(("127.0.0.1", port)).ok().map(|_| ()));
+            async move { ready }
+        })
+        .await?;
+
+        Ok(Self {
+            api_url: format!("http://127.0.0.1:{port}"),
+            _process: relayer,
+        })
+    }
+}
@@ -0,0 +1,388 @@
+//! The Solana side: a local validator running the bridge program, set up
+//! for one SPL token bridged to Ethereum
+
+use std::path::Path;
+use std::time::Duration;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction as ata_instruction;
+use anchor_spl::token::spl_token;
+use anyhow::{anyhow, bail, Result};
+use solana_bridge::{accounts, chain_ids, instruction, ChainKind, ChainParams, Finality};
+use solana_bridge_sdk::pda;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::rent::Rent;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::sysvar;
+use solana_sdk::transaction::Transaction;
+use tokio::process::Command;
+
+use crate::process::{self, Service};
+
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// SOL the user gets for fees and rent
+const USER_LAMPORTS: u64 = 10_000_000_000;
+
+const DECIMALS: u8 = 9;
+
+pub struct Validator {
+    pub rpc_url: String,
+    _process: Service,
+}
+
+impl Validator {
+    /**
+     * Start solana-test-validator with the built program preloaded at
+     * its declared ID; `payer` is funded at genesis
+     */
+    pub async fn start(payer: &Pubkey, ledger: &Path, log_dir: &Path) -> Result<Self> {
+        let program = process::workspace_root().join("target/deploy/solana_bridge.so");
+        if !program.exists() {
+            bail!("{} not found; run `anchor build` first", program.display());
+        }
+
+        let port = process::free_port()?;
+        let rpc_url = format!("http://127.0.0.1:{port}");
+
+        let mut validator = Command::new("solana-test-validator");
+        validator
+            .arg("--reset")
+            .arg("--ledger")
+            .arg(ledger)
+            .args(["--rpc-port", &port.to_string()])
+            .args(["--faucet-port", &process::free_port()?.to_string()])
+            .args(["--mint", &payer.to_string()])
+            .args(["--bpf-program", &solana_bridge::ID.to_string()])
+            .arg(&program);
+        let mut validator = Service::spawn("solana-test-validator", validator, log_dir)?;
+
+        let rpc = RpcClient::new(rpc_url.clone());
+        process::wait_for("solana-test-validator", STARTUP_TIMEOUT, || {
+            let running = validator.check_running();
+            let rpc = &rpc;
+            async move {
+                running?;
+                Ok(rpc.get_health().await.ok())
+            }
+        })
+        .await?;
+
+        Ok(Self {
+            rpc_url,
+            _process: validator,
+        })
+    }
+}
+
+/**
+ * Bridge state for the round trip
+ *
+ * `owner` also relays: the relayer signs unlocks with it. `mint` is the
+ * token locked toward Ethereum and released on the way back;
+ * `wrapped_mint` is registered so the relayer's Ethereum-lock path has
+ * something to mint, but the round trip doesn't use it.
+ */
+pub struct Bridge {
+    pub rpc: RpcClient,
+    pub owner: Keypair,
+    pub user: Keypair,
+    pub mint: Pubkey,
+    pub wrapped_mint: Pubkey,
+    /// The user's associated token account, where unlocks land
+    pub user_token: Pubkey,
+    pub escrow: Pubkey,
+    pub fee_vault: Pubkey,
+}
+
+impl Bridge {
+    /**
+     * Initialize the program, register Ethereum with one confirmation and
+     * no fees, and give the user `balance` of a fresh token
+     */
+    pub async fn setup(rpc_url: &str, owner: Keypair, balance: u64) -> Result<Self> {
+        let rpc =
+            RpcClient::new_with_commitment(rpc_url.to_string(), CommitmentConfig::confirmed());
+        let o = owner.pubkey();
+        send(&rpc, &owner, config_instructions(&o), &[]).await?;
+
+        let keys = SetupKeys::generate();
+        let rent_account = rpc.get_account(&sysvar::rent::ID).await?;
+        let rent: Rent = solana_sdk::account::from_account(&rent_account)
+            .ok_or_else(|| anyhow!("Can't decode the rent sysvar"))?;
+        let tokens = token_instructions(&o, &keys, &rent, balance)?;
+        send(&rpc, &owner, tokens, &keys.signers()).await?;
+        send(&rpc, &owner, register_instructions(&o, &keys), &[]).await?;
+
+        Ok(Self {
+            rpc,
+            owner,
+            user_token: get_associated_token_address(&keys.user.pubkey(), &keys.mint.pubkey()),
+            mint: keys.mint.pubkey(),
+            wrapped_mint: keys.wrapped_mint.pubkey(),
+            escrow: keys.escrow.pubkey(),
+            fee_vault: keys.fee_vault.pubkey(),
+            user: keys.user,
+        })
+    }
+
+    pub async fn token_balance(&self, account: &Pubkey) -> Result<u64> {
+        Ok(self.rpc.get_token_account_balance(account).await?.amount.parse()?)
+    }
+
+    /// An account of the program's, if it exists yet
+    pub async fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Result<Option<T>> {
+        let account = self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())
+            .await?
+            .value;
+        match account {
+            Some(account) => Ok(Some(T::try_deserialize(&mut account.data.as_slice())?)),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Accounts `Bridge::setup` creates besides the program's own
+pub struct SetupKeys {
+    pub user: Keypair,
+    pub mint: Keypair,
+    pub wrapped_mint: Keypair,
+    pub escrow: Keypair,
+    pub fee_vault: Keypair,
+}
+
+impl SetupKeys {
+    pub fn generate() -> Self {
+        Self {
+            user: Keypair::new(),
+            mint: Keypair::new(),
+            wrapped_mint: Keypair::new(),
+            escrow: Keypair::new(),
+            fee_vault: Keypair::new(),
+        }
+    }
+
+    /// Signers `token_instructions` needs besides the owner
+    pub fn signers(&self) -> [&Keypair; 4] {
+        [&self.mint, &self.wrapped_mint, &self.escrow, &self.fee_vault]
+    }
+}
+
+fn program_ix(metas: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: solana_bridge::ID,
+        accounts: metas,
+        data,
+    }
+}
+
+/**
+ * Initialize the program and register Ethereum with one confirmation,
+ * no fees, no fee tiers and no relayer rewards
+ */
+pub fn config_instructions(owner: &Pubkey) -> Vec<Instruction> {
+    let program_id = solana_bridge::ID;
+    let bridge_state = pda::bridge_state(&program_id);
+    let admin_log = pda::admin_log(&program_id);
+    let system_program = solana_sdk::system_program::ID;
+    let o = *owner;
+
+    vec![
+        program_ix(
+            accounts::Initialize {
+                bridge_state,
+                admin_log,
+                outbox: pda::outbox(&program_id),
+                owner: o,
+                system_program,
+            }
+            .to_account_metas(None),
+            instruction::Initialize {
+                instance_id: solana_bridge::instance::DEFAULT_INSTANCE,
+            }
+            .data(),
+        ),
+        program_ix(
+            accounts::RegisterChain {
+                owner: o,
+                bridge_state,
+                admin_log,
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                system_program,
+            }
+            .to_account_metas(None),
+            instruction::RegisterChain {
+                chain_id: chain_ids::ETHEREUM,
+                kind: ChainKind::Evm,
+                params: ChainParams {
+                    finality: Finality::Confirmations,
+                    required_confirmations: 1,
+                    base_fee: 0,
+                    fee_bps: 0,
+                    min_amount: 0,
+                },
+            }
+            .data(),
+        ),
+        program_ix(
+            accounts::SetFeeTiers {
+                owner: o,
+                bridge_state,
+                admin_log,
+                fee_tiers: pda::fee_tiers(&program_id),
+                system_program,
+            }
+            .to_account_metas(None),
+            instruction::SetFeeTiers { tiers: vec![] }.data(),
+        ),
+        program_ix(
+            accounts::SetRelayerRewards {
+                owner: o,
+                bridge_state,
+                admin_log,
+                reward_config: pda::reward_config(&program_id),
+                system_program,
+            }
+            .to_account_metas(None),
+            instruction::SetRelayerRewards {
+                epoch_duration: 86_400,
+                reward_per_transfer: 0,
+            }
+            .data(),
+        ),
+    ]
+}
+
+/**
+ * Fund the user, create `mint` (owner is the mint authority) and
+ * `wrapped_mint` (the bridge authority is), the escrow and fee vault
+ * for `mint`, and the user's associated account holding `balance`
+ */
+pub fn token_instructions(
+    owner: &Pubkey,
+    keys: &SetupKeys,
+    rent: &Rent,
+    balance: u64,
+) -> Result<Vec<Instruction>> {
+    let bridge_authority = pda::bridge_authority(&solana_bridge::ID);
+    let o = *owner;
+    let user = keys.user.pubkey();
+    let mint = keys.mint.pubkey();
+
+    let create = |account: &Keypair, space: usize| {
+        system_instruction::create_account(
+            &o,
+            &account.pubkey(),
+            rent.minimum_balance(space),
+            space as u64,
+            &spl_token::ID,
+        )
+    };
+
+    let mut tokens = vec![system_instruction::transfer(&o, &user, USER_LAMPORTS)];
+    for (account, authority) in [(&keys.mint, o), (&keys.wrapped_mint, bridge_authority)] {
+        tokens.push(create(account, spl_token::state::Mint::LEN));
+        tokens.push(spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &account.pubkey(),
+            &authority,
+            None,
+            DECIMALS,
+        )?);
+    }
+    for account in [&keys.escrow, &keys.fee_vault] {
+        tokens.push(create(account, spl_token::state::Account::LEN));
+        tokens.push(spl_token::instruction::initialize_account3(
+            &spl_token::ID,
+            &account.pubkey(),
+            &mint,
+            &bridge_authority,
+        )?);
+    }
+    tokens.push(ata_instruction::create_associated_token_account(
+        &o,
+        &user,
+        &mint,
+        &spl_token::ID,
+    ));
+    tokens.push(spl_token::instruction::mint_to(
+        &spl_token::ID,
+        &mint,
+        &get_associated_token_address(&user, &mint),
+        &o,
+        &[],
+        balance,
+    )?);
+    Ok(tokens)
+}
+
+/// Register both of `keys`' mints with the bridge and open their route
+/// sequences to Ethereum (shards 1 and 2 on a fresh bridge)
+pub fn register_instructions(owner: &Pubkey, keys: &SetupKeys) -> Vec<Instruction> {
+    let program_id = solana_bridge::ID;
+    [keys.mint.pubkey(), keys.wrapped_mint.pubkey()]
+        .iter()
+        .flat_map(|token| {
+            [
+                program_ix(
+                    accounts::RegisterToken {
+                        owner: *owner,
+                        bridge_state: pda::bridge_state(&program_id),
+                        admin_log: pda::admin_log(&program_id),
+                        mint: *token,
+                        token_config: pda::token_config(&program_id, token),
+                        system_program: solana_sdk::system_program::ID,
+                    }
+                    .to_account_metas(None),
+                    instruction::RegisterToken {}.data(),
+                ),
+                program_ix(
+                    accounts::InitRouteSequence {
+                        payer: *owner,
+                        bridge_state: pda::bridge_state(&program_id),
+                        mint: *token,
+                        token_config: pda::token_config(&program_id, token),
+                        chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                        route_sequence: pda::route_sequence(
+                            &program_id,
+                            token,
+                            chain_ids::ETHEREUM,
+                        ),
+                        system_program: solana_sdk::system_program::ID,
+                    }
+                    .to_account_metas(None),
+                    instruction::InitRouteSequence {
+                        dest_chain_id: chain_ids::ETHEREUM,
+                    }
+                    .data(),
+                ),
+            ]
+        })
+        .collect()
+}
+
+async fn send(
+    rpc: &RpcClient,
+    payer: &Keypair,
+    instructions: Vec<Instruction>,
+    signers: &[&Keypair],
+) -> Result<()> {
+    let mut all: Vec<&Keypair> = vec![payer];
+    all.extend_from_slice(signers);
+    let transaction = Transaction::new_signed_with_payer(
+        &instructions,
+        Some(&payer.pubkey()),
+        &all,
+        rpc.get_latest_blockhash().await?,
+    );
+    rpc.send_and_confirm_transaction(&transaction).await?;
+    Ok(())
+}
@@ -0,0 +1,295 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.20;
+
+import {MockSolanaBridge} from "../contracts/MockSolanaBridge.sol";
+
+/// @dev The Foundry cheatcodes used below (no forge-std dependency)
+interface Vm {
+    struct Log {
+        bytes32[] topics;
+        bytes data;
+        address emitter;
+    }
+
+    function projectRoot() external view returns (string memory);
+    function readFile(string calldata path) external view returns (string memory);
+    function keyExistsJson(string calldata json, string calldata key) external view returns (bool);
+    function parseJsonUint(string calldata json, string calldata key) external pure returns (uint256);
+    function parseJsonBool(string calldata json, string calldata key) external pure returns (bool);
+    function parseJsonBytes32(string calldata json, string calldata key) external pure returns (bytes32);
+    function parseJsonBytes(string calldata json, string calldata key) external pure returns (bytes memory);
+    function parseJsonAddress(string calldata json, string calldata key) external pure returns (address);
+    function parseJsonString(string calldata json, string calldata key) external pure returns (string memory);
+    function toString(uint256 value) external pure returns (string memory);
+    function addr(uint256 privateKey) external pure returns (address);
+    function sign(uint256 privateKey, bytes32 digest) external pure returns (uint8 v, bytes32 r, bytes32 s);
+    function prank(address sender) external;
+    function recordLogs() external;
+    function getRecordedLogs() external returns (Log[] memory);
+}
+
+/**
+ * @title VectorsTest
+ * @notice Runs the program's shared test vectors (test-vectors/vectors.json)
+ *         through Solidity, so the EVM side can't drift from the program on
+ *         transfer hashes, event encodings or fee math
+ * @dev `forge test` from test-harness. The Rust side checks the same file
+ *      in test-vectors/tests/vectors.rs.
+ */
+contract VectorsTest {
+    Vm private constant vm = Vm(address(uint160(uint256(keccak256("hevm cheat code")))));
+
+    uint256 private constant RELAYER_KEY = 0xB0B;
+
+    string private json;
+
+    function setUp() public {
+        json = vm.readFile(string.concat(vm.projectRoot(), "/../test-vectors/vectors.json"));
+    }
+
+    function test_transferMessageHash() public view {
+        for (uint256 i; has("transfer_message_hash", i); i++) {
+            string memory v = at("transfer_message_hash", i);
+            bytes32 hash = keccak256(
+                abi.encodePacked(
+                    vm.parseJsonBytes32(json, key(v, "recipient")),
+                    vm.parseJsonUint(json, key(v, "amount")),
+                    vm.parseJsonUint(json, key(v, "nonce"))
+                )
+            );
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+        }
+    }
+
+    function test_inboundMessageHash() public view {
+        for (uint256 i; has("inbound_message_hash", i); i++) {
+            string memory v = at("inbound_message_hash", i);
+            bytes32 hash = keccak256(
+                abi.encodePacked(
+                    uint64(vm.parseJsonUint(json, key(v, "source_chain_id"))),
+                    uint32(vm.parseJsonUint(json, key(v, "guardian_set_index"))),
+                    vm.parseJsonBytes32(json, key(v, "token")),
+                    vm.parseJsonBytes32(json, key(v, "recipient")),
+                    vm.parseJsonUint(json, key(v, "amount")),
+                    vm.parseJsonUint(json, key(v, "nonce"))
+                )
+            );
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+        }
+    }
+
+    /// Signs each vector's hash and mints with it: the mock bridge only
+    /// recovers the relayer if it hashes the request the same way
+    function test_mintRequestHash() public {
+        MockSolanaBridge bridge = new MockSolanaBridge(vm.addr(RELAYER_KEY));
+        for (uint256 i; has("mint_request_hash", i); i++) {
+            string memory v = at("mint_request_hash", i);
+            address to = vm.parseJsonAddress(json, key(v, "to"));
+            uint256 amount = vm.parseJsonUint(json, key(v, "amount"));
+            bytes32 transferId = vm.parseJsonBytes32(json, key(v, "transfer_id"));
+
+            bytes32 hash = keccak256(abi.encodePacked(to, amount, transferId));
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+
+            (uint8 sv, bytes32 r, bytes32 s) =
+                vm.sign(RELAYER_KEY, vm.parseJsonBytes32(json, key(v, "eth_signed_hash")));
+            uint256 before = bridge.balanceOf(to);
+            bridge.mint(to, amount, transferId, abi.encodePacked(r, s, sv));
+            require(bridge.balanceOf(to) == before + amount, v);
+        }
+    }
+
+    /// Applies each signed status in order: the mock bridge only recovers the
+    /// relayer, and pauses, if it hashes the status the same way
+    function test_bridgeStatusHash() public {
+        MockSolanaBridge bridge = new MockSolanaBridge(vm.addr(RELAYER_KEY));
+        for (uint256 i; has("bridge_status_hash", i); i++) {
+            string memory v = at("bridge_status_hash", i);
+            uint64 sourceChainId = uint64(vm.parseJsonUint(json, key(v, "source_chain_id")));
+            bool paused = vm.parseJsonBool(json, key(v, "paused"));
+            uint64 sequence = uint64(vm.parseJsonUint(json, key(v, "sequence")));
+
+            require(sourceChainId == bridge.SOLANA_CHAIN_ID(), v);
+            bytes32 hash = keccak256(abi.encodePacked(sourceChainId, paused, sequence));
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+
+            (uint8 sv, bytes32 r, bytes32 s) =
+                vm.sign(RELAYER_KEY, vm.parseJsonBytes32(json, key(v, "eth_signed_hash")));
+            bridge.setBridgeStatus(paused, sequence, abi.encodePacked(r, s, sv));
+            require(bridge.paused() == paused && bridge.nextStatusSequence() == sequence + 1, v);
+        }
+    }
+
+    /// Applies each signed escrow balance in order: the mock bridge only
+    /// recovers the relayer, and records it, if it hashes the balance the
+    /// same way
+    function test_vaultBalanceHash() public {
+        MockSolanaBridge bridge = new MockSolanaBridge(vm.addr(RELAYER_KEY));
+        for (uint256 i; has("vault_balance_hash", i); i++) {
+            string memory v = at("vault_balance_hash", i);
+            uint64 sourceChainId = uint64(vm.parseJsonUint(json, key(v, "source_chain_id")));
+            bytes32 mint = vm.parseJsonBytes32(json, key(v, "mint"));
+            uint256 amount = vm.parseJsonUint(json, key(v, "amount"));
+            uint64 slot = uint64(vm.parseJsonUint(json, key(v, "slot")));
+            uint64 sequence = uint64(vm.parseJsonUint(json, key(v, "sequence")));
+
+            require(sourceChainId == bridge.SOLANA_CHAIN_ID(), v);
+            bytes32 hash = keccak256(abi.encodePacked(sourceChainId, mint, amount, slot, sequence));
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+
+            (uint8 sv, bytes32 r, bytes32 s) =
+                vm.sign(RELAYER_KEY, vm.parseJsonBytes32(json, key(v, "eth_signed_hash")));
+            bridge.setSolanaReserve(mint, amount, slot, sequence, abi.encodePacked(r, s, sv));
+            (uint256 recorded, uint64 recordedSlot) = bridge.solanaReserves(mint);
+            require(recorded == amount && recordedSlot == slot, v);
+            require(bridge.nextReserveSequence() == sequence + 1, v);
+        }
+    }
+
+    /// Mints each vector's lock through mintWithProof with two attesters'
+    /// signatures over its hash: recovery only succeeds if the mock bridge
+    /// hashes the proof the same way
+    function test_lockProofHash() public {
+        MockSolanaBridge bridge = new MockSolanaBridge(vm.addr(RELAYER_KEY));
+        uint256[2] memory keys = [uint256(0xA11CE), 0xCA201];
+        if (vm.addr(keys[0]) > vm.addr(keys[1])) keys = [keys[1], keys[0]];
+        address[] memory set = new address[](2);
+        set[0] = vm.addr(keys[0]);
+        set[1] = vm.addr(keys[1]);
+        vm.prank(vm.addr(RELAYER_KEY));
+        bridge.setAttesters(set, 2);
+
+        for (uint256 i; has("lock_proof_hash", i); i++) {
+            string memory v = at("lock_proof_hash", i);
+            MockSolanaBridge.LockProof memory proof = MockSolanaBridge.LockProof({
+                transferId: vm.parseJsonBytes32(json, key(v, "transfer_id")),
+                to: vm.parseJsonAddress(json, key(v, "to")),
+                amount: vm.parseJsonUint(json, key(v, "amount")),
+                slot: uint64(vm.parseJsonUint(json, key(v, "slot"))),
+                blockhash: vm.parseJsonBytes32(json, key(v, "blockhash")),
+                txSignature: vm.parseJsonBytes(json, key(v, "tx_signature"))
+            });
+
+            require(uint64(vm.parseJsonUint(json, key(v, "source_chain_id"))) == bridge.SOLANA_CHAIN_ID(), v);
+            bytes32 hash = keccak256(
+                abi.encodePacked(
+                    bridge.SOLANA_CHAIN_ID(),
+                    proof.transferId,
+                    proof.to,
+                    proof.amount,
+                    proof.slot,
+                    proof.blockhash,
+                    proof.txSignature
+                )
+            );
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+
+            bytes[] memory signatures = new bytes[](2);
+            for (uint256 k; k < 2; k++) {
+                (uint8 sv, bytes32 r, bytes32 s) =
+                    vm.sign(keys[k], vm.parseJsonBytes32(json, key(v, "eth_signed_hash")));
+                signatures[k] = abi.encodePacked(r, s, sv);
+            }
+            uint256 before = bridge.balanceOf(proof.to);
+            bridge.mintWithProof(proof, signatures);
+            require(bridge.balanceOf(proof.to) == before + proof.amount, v);
+        }
+    }
+
+    function test_messageHash() public view {
+        for (uint256 i; has("message_hash", i); i++) {
+            string memory v = at("message_hash", i);
+            bytes32 hash = keccak256(
+                abi.encodePacked(
+                    uint64(vm.parseJsonUint(json, key(v, "source_chain_id"))),
+                    vm.parseJsonBytes32(json, key(v, "sender")),
+                    uint64(vm.parseJsonUint(json, key(v, "dest_chain_id"))),
+                    vm.parseJsonBytes32(json, key(v, "target")),
+                    uint64(vm.parseJsonUint(json, key(v, "sequence"))),
+                    vm.parseJsonBytes(json, key(v, "payload"))
+                )
+            );
+            require(hash == vm.parseJsonBytes32(json, key(v, "hash")), v);
+        }
+    }
+
+    function test_transferId() public view {
+        for (uint256 i; has("transfer_id", i); i++) {
+            string memory v = at("transfer_id", i);
+            bytes memory packed = abi.encodePacked(
+                uint64(vm.parseJsonUint(json, key(v, "source_chain_id"))),
+                uint64(vm.parseJsonUint(json, key(v, "dest_chain_id"))),
+                uint32(vm.parseJsonUint(json, key(v, "guardian_set_index"))),
+                uint64(vm.parseJsonUint(json, key(v, "nonce")))
+            );
+            packed = abi.encodePacked(
+                packed,
+                vm.parseJsonBytes32(json, key(v, "token")),
+                uint64(vm.parseJsonUint(json, key(v, "amount"))),
+                vm.parseJsonBytes32(json, key(v, "sender")),
+                vm.parseJsonString(json, key(v, "recipient"))
+            );
+            require(keccak256(packed) == vm.parseJsonBytes32(json, key(v, "id")), v);
+        }
+    }
+
+    function test_eventTopics() public view {
+        require(vm.parseJsonBytes32(json, ".event_topics.Lock") == MockSolanaBridge.Lock.selector, "Lock");
+        require(vm.parseJsonBytes32(json, ".event_topics.Burn") == MockSolanaBridge.Burn.selector, "Burn");
+        require(vm.parseJsonBytes32(json, ".event_topics.Mint") == MockSolanaBridge.Mint.selector, "Mint");
+    }
+
+    /// Consecutive locks on a fresh bridge log exactly the vectors' topics and data
+    function test_lockEvents() public {
+        MockSolanaBridge bridge = new MockSolanaBridge(vm.addr(RELAYER_KEY));
+        for (uint256 i; has("lock_events", i); i++) {
+            string memory v = at("lock_events", i);
+            vm.recordLogs();
+            vm.prank(vm.parseJsonAddress(json, key(v, "from")));
+            bridge.lock(vm.parseJsonUint(json, key(v, "amount")), vm.parseJsonString(json, key(v, "solana_address")));
+
+            Vm.Log[] memory logs = vm.getRecordedLogs();
+            require(logs.length == 1 && logs[0].topics.length == 2, v);
+            require(logs[0].topics[0] == vm.parseJsonBytes32(json, key(v, "topics[0]")), v);
+            require(logs[0].topics[1] == vm.parseJsonBytes32(json, key(v, "topics[1]")), v);
+            require(keccak256(logs[0].data) == keccak256(vm.parseJsonBytes(json, key(v, "data"))), v);
+            require(bridge.nonce() == vm.parseJsonUint(json, key(v, "nonce")), v);
+        }
+    }
+
+    function test_transferFee() public view {
+        for (uint256 i; has("transfer_fee", i); i++) {
+            string memory v = at("transfer_fee", i);
+            uint256 fee = transferFee(
+                vm.parseJsonUint(json, key(v, "amount")),
+                vm.parseJsonUint(json, key(v, "base_fee")),
+                vm.parseJsonUint(json, key(v, "fee_bps")),
+                vm.parseJsonUint(json, key(v, "discount_bps"))
+            );
+            require(fee == vm.parseJsonUint(json, key(v, "fee")), v);
+        }
+    }
+
+    /// The program's lock fee (math::transfer_fee) as an EVM contract computes it:
+    /// base fee plus fee_bps of the amount, less discount_bps of that, rounding down
+    function transferFee(uint256 amount, uint256 baseFee, uint256 feeBps, uint256 discountBps)
+        private
+        pure
+        returns (uint256)
+    {
+        uint256 fee = baseFee + (amount * feeBps) / 10_000;
+        return fee - (fee * discountBps) / 10_000;
+    }
+
+    function has(string memory section, uint256 i) private view returns (bool) {
+        return vm.keyExistsJson(json, at(section, i));
+    }
+
+    function at(string memory section, uint256 i) private pure returns (string memory) {
+        return string.concat(".", section, "[", vm.toString(i), "]");
+    }
+
+    function key(string memory vector, string memory field) private pure returns (string memory) {
+        return string.concat(vector, ".", field);
+    }
+}
@@ -0,0 +1,156 @@
+//! Relayer under injected faults; `cargo test -p solana-bridge-test-harness --features chaos`
+//!
+//! The relayer runs with chaos mode on (relayer/src/chaos.js): events are
+//! dropped and duplicated, confirmations come back late and RPC calls
+//! fail. Separately, an Ethereum burn is reorged out from under it. Either
+//! way every transfer must arrive exactly once, and nothing else may.
+
+#![cfg(feature = "chaos")]
+
+use std::time::Duration;
+
+use anyhow::Result;
+use solana_bridge_test_harness::evm::USER_ADDRESS;
+use solana_bridge_test_harness::process::wait_for;
+use solana_bridge_test_harness::Harness;
+use solana_sdk::signature::Signer;
+
+const CHAOS: &str = "drop=0.2,duplicate=0.3,delay=0.3,rpc=0.05";
+
+/// Transfers each way
+const TRANSFERS: u64 = 5;
+const AMOUNT: u64 = 10_000;
+
+/// Dropped events wait for a reconciliation tick, failed ones for a few
+const CONVERGE_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// The relayer's entry for inbound `nonce`, as of its last snapshot
+fn inbound_status(harness: &Harness, nonce: u64) -> Result<Option<String>> {
+    Ok(harness
+        .relayer_transfers()?
+        .into_iter()
+        .find(|t| {
+            t["direction"] == "inbound"
+                && t["nonce"].as_str().and_then(|n| n.parse().ok()) == Some(nonce)
+        })
+        .and_then(|t| t["status"].as_str().map(str::to_string)))
+}
+
+#[tokio::test]
+async fn converges_under_faults() {
+    let harness = Harness::start_with_env(
+        1_000_000,
+        &[
+            ("CHAOS", CHAOS),
+            ("CHAOS_SEED", "7"),
+            ("RECONCILE_INTERVAL_SECONDS", "10"),
+        ],
+    )
+    .await
+    .unwrap();
+    let bridge = &harness.bridge;
+    let user = bridge.user.pubkey().to_string();
+    let solana_before = bridge.token_balance(&bridge.user_token).await.unwrap();
+
+    let mut transfer_ids = Vec::new();
+    for _ in 0..TRANSFERS {
+        transfer_ids.push(harness.lock(AMOUNT).await.unwrap().1);
+    }
+    wait_for("every Ethereum mint", CONVERGE_TIMEOUT, || async {
+        for id in &transfer_ids {
+            if !harness.evm.is_processed(id).await? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(()))
+    })
+    .await
+    .unwrap();
+    // Each transfer minted once, however many times it was submitted
+    assert_eq!(harness.evm.balance_of(USER_ADDRESS).await.unwrap(), TRANSFERS * AMOUNT);
+
+    for _ in 0..TRANSFERS {
+        harness.evm.burn(AMOUNT, &user).await.unwrap();
+    }
+    wait_for("every Solana unlock", CONVERGE_TIMEOUT, || async {
+        for nonce in 1..=TRANSFERS {
+            if !harness.inbound_completed(nonce).await? {
+                return Ok(None);
+            }
+        }
+        Ok(Some(()))
+    })
+    .await
+    .unwrap();
+    // Each burn unlocked once: the user is whole and the escrow empty
+    assert_eq!(bridge.token_balance(&bridge.user_token).await.unwrap(), solana_before);
+    assert_eq!(bridge.token_balance(&bridge.escrow).await.unwrap(), 0);
+    assert_eq!(harness.evm.balance_of(USER_ADDRESS).await.unwrap(), 0);
+
+    // Failed and duplicate attempts are reconciled to completed
+    wait_for("the relayer's transfer store", CONVERGE_TIMEOUT, || async {
+        let transfers = harness.relayer_transfers()?;
+        let settled = transfers.len() as u64 == 2 * TRANSFERS
+            && transfers.iter().all(|t| t["status"] == "completed");
+        Ok(settled.then_some(()))
+    })
+    .await
+    .unwrap();
+
+    let log = harness.relayer_log().unwrap();
+    assert!(log.contains("chaos: "), "no faults were injected");
+}
+
+#[tokio::test]
+async fn reorged_burn_is_never_delivered() {
+    let harness = Harness::start_with_env(1_000_000, &[("RECONCILE_INTERVAL_SECONDS", "10")])
+        .await
+        .unwrap();
+    let bridge = &harness.bridge;
+    let user = bridge.user.pubkey().to_string();
+    let solana_before = bridge.token_balance(&bridge.user_token).await.unwrap();
+
+    let (_, transfer_id) = harness.lock(AMOUNT).await.unwrap();
+    wait_for("the Ethereum mint", CONVERGE_TIMEOUT, || async {
+        Ok(harness.evm.is_processed(&transfer_id).await?.then_some(()))
+    })
+    .await
+    .unwrap();
+
+    // Burn into a block that never gets a confirmation, let the relayer
+    // see it, then drop the block
+    let snapshot = harness.evm.snapshot().await.unwrap();
+    harness.evm.pause_mining().await.unwrap();
+    harness.evm.submit_burn(AMOUNT, &user).await.unwrap();
+    harness.evm.mine().await.unwrap();
+    wait_for("the relayer to see the burn", CONVERGE_TIMEOUT, || async {
+        Ok(inbound_status(&harness, 1)?.map(|_| ()))
+    })
+    .await
+    .unwrap();
+    harness.evm.revert(&snapshot).await.unwrap();
+    harness.evm.resume_mining().await.unwrap();
+
+    wait_for("the relayer to flag the reorg", CONVERGE_TIMEOUT, || async {
+        let status = inbound_status(&harness, 1)?;
+        Ok((status.as_deref() == Some("reorged")).then_some(()))
+    })
+    .await
+    .unwrap();
+    assert!(!harness.inbound_completed(1).await.unwrap());
+    assert_eq!(
+        bridge.token_balance(&bridge.user_token).await.unwrap(),
+        solana_before - AMOUNT
+    );
+    assert_eq!(harness.evm.balance_of(USER_ADDRESS).await.unwrap(), AMOUNT);
+
+    // Re-included: the same nonce, now canonical, arrives once
+    harness.evm.burn(AMOUNT, &user).await.unwrap();
+    wait_for("the Solana unlock", CONVERGE_TIMEOUT, || async {
+        Ok(harness.inbound_completed(1).await?.then_some(()))
+    })
+    .await
+    .unwrap();
+    assert_eq!(bridge.token_balance(&bridge.user_token).await.unwrap(), solana_before);
+    assert_eq!(bridge.token_balance(&bridge.escrow).await.unwrap(), 0);
+}
@@ -0,0 +1,384 @@
+//! Compute units per instruction against `solana_bridge_sdk::budget`;
+//! `anchor build`, then `cargo test -p solana-bridge-test-harness --features compute-units`
+
+#![cfg(feature = "compute-units")]
+
+use std::path::Path;
+
+use anchor_lang::{InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction as ata_instruction;
+use anchor_spl::token::spl_token;
+use solana_bridge::{accounts, chain_ids, instruction, RelayerRewardConfig, RouteSequence};
+use solana_bridge_sdk::{budget, pda};
+use solana_bridge_test_harness::solana::{
+    config_instructions, register_instructions, token_instructions, SetupKeys,
+};
+use solana_program_test::{BanksClient, ProgramTest};
+use solana_sdk::clock::Clock;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::transaction::Transaction;
+
+const BALANCE: u64 = 1_000_000_000;
+const RECIPIENT: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+struct Bench {
+    banks: BanksClient,
+    payer: Keypair,
+    blockhash: Hash,
+    keys: SetupKeys,
+}
+
+impl Bench {
+    async fn start() -> Self {
+        let deploy = Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy");
+        assert!(
+            deploy.join("solana_bridge.so").exists(),
+            "{} has no solana_bridge.so; run `anchor build` first",
+            deploy.display()
+        );
+        std::env::set_var("SBF_OUT_DIR", &deploy);
+
+        let mut program = ProgramTest::new("solana_bridge", solana_bridge::ID, None);
+        program.prefer_bpf(true);
+        let (mut banks, payer, blockhash) = program.start().await;
+
+        let keys = SetupKeys::generate();
+        let o = payer.pubkey();
+        let rent = banks.get_rent().await.unwrap();
+        let tokens = token_instructions(&o, &keys, &rent, BALANCE).unwrap();
+        let wrapped_token = ata_instruction::create_associated_token_account(
+            &o,
+            &keys.user.pubkey(),
+            &keys.wrapped_mint.pubkey(),
+            &spl_token::ID,
+        );
+
+        let mut bench = Self {
+            banks,
+            payer,
+            blockhash,
+            keys,
+        };
+        bench.send(config_instructions(&o), &[]).await;
+        let signers = bench.keys.signers().map(|k| k.insecure_clone());
+        bench.send(tokens, &signers.iter().collect::<Vec<_>>()).await;
+        bench.send(register_instructions(&o, &bench.keys), &[]).await;
+        bench.send(vec![wrapped_token], &[]).await;
+        bench
+    }
+
+    fn user(&self) -> Pubkey {
+        self.keys.user.pubkey()
+    }
+
+    fn user_token(&self) -> Pubkey {
+        get_associated_token_address(&self.user(), &self.keys.mint.pubkey())
+    }
+
+    fn user_wrapped_token(&self) -> Pubkey {
+        get_associated_token_address(&self.user(), &self.keys.wrapped_mint.pubkey())
+    }
+
+    async fn send(&mut self, instructions: Vec<Instruction>, signers: &[&Keypair]) {
+        self.consume(instructions, signers).await;
+    }
+
+    /// Units the transaction consumed, with the maximum limit requested
+    async fn consume(&mut self, instructions: Vec<Instruction>, signers: &[&Keypair]) -> u64 {
+        let mut all = vec![ComputeBudgetInstruction::set_compute_unit_limit(budget::MAX)];
+        all.extend(instructions);
+        let mut keypairs = vec![&self.payer];
+        keypairs.extend_from_slice(signers);
+        self.blockhash = self
+            .banks
+            .get_new_latest_blockhash(&self.blockhash)
+            .await
+            .unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &all,
+            Some(&self.payer.pubkey()),
+            &keypairs,
+            self.blockhash,
+        );
+
+        let result = self.banks.process_transaction_with_metadata(tx).await.unwrap();
+        let metadata = result.metadata.expect("no transaction metadata");
+        if let Err(e) = result.result {
+            panic!("transaction failed: {e}\n{}", metadata.log_messages.join("\n"));
+        }
+        metadata.compute_units_consumed
+    }
+
+    /// Units for a bridge instruction signed by the user
+    async fn consume_as_user(&mut self, instruction: Instruction) -> u64 {
+        let user = self.keys.user.insecure_clone();
+        self.consume(vec![instruction], &[&user]).await
+    }
+
+    async fn reward_epoch(&mut self) -> u64 {
+        let config = self
+            .banks
+            .get_account(pda::reward_config(&solana_bridge::ID))
+            .await
+            .unwrap()
+            .unwrap();
+        let config: RelayerRewardConfig =
+            anchor_lang::AccountDeserialize::try_deserialize(&mut config.data.as_slice()).unwrap();
+        let clock: Clock = self.banks.get_sysvar().await.unwrap();
+        config.epoch_at(clock.unix_timestamp)
+    }
+
+    fn lock(&self, nonce: u64, amount: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let mint = self.keys.mint.pubkey();
+        program_ix(
+            accounts::Lock {
+                user: self.user(),
+                bridge_state: pda::bridge_state(&program_id),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                outbox: pda::outbox(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                fee_tiers: pda::fee_tiers(&program_id),
+                user_stats: pda::user_stats(&program_id, &self.user()),
+                user_token: self.user_token(),
+                mint,
+                token_config: pda::token_config(&program_id, &mint),
+                fee_route: pda::fee_route(&program_id, &mint, chain_ids::ETHEREUM),
+                relay_route: pda::relay_route(&program_id, &mint, chain_ids::ETHEREUM),
+                throttle: pda::throttle(&program_id),
+                price_breaker: pda::price_breaker(&program_id, &mint),
+                event_history: pda::event_history(&program_id, &mint),
+                route_sequence: pda::route_sequence(&program_id, &mint, chain_ids::ETHEREUM),
+                fee_exemption: pda::fee_exemption(&program_id, &self.user()),
+                terms_config: pda::terms_config(&program_id),
+                terms_acceptance: pda::terms_acceptance(&program_id, &self.user()),
+                sender_allowlist: pda::sender_allowlist(&program_id),
+                allowed_sender: pda::allowed_sender(&program_id, &self.user()),
+                withdrawal_allowlist: pda::withdrawal_allowlist(&program_id, &self.user()),
+                quote_signers: None,
+                instructions: None,
+                credential: None,
+                bridge_pass: None,
+                conversion_rate: None,
+                bridge_token: self.keys.escrow.pubkey(),
+                fee_vault: self.keys.fee_vault.pubkey(),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+                associated_token_program: None,
+                integrator: None,
+                sol_fee_config: None,
+                sol_fee_vault: None,
+                token_price: None,
+                sol_price: None,
+                delivery_fee_config: None,
+                reward_vault: None,
+                native_price: None,
+                transfer_memo: None,
+                smart_wallet: None,
+            }
+            .to_account_metas(None),
+            instruction::Lock {
+                amount,
+                dest_chain_id: chain_ids::ETHEREUM,
+                recipient: RECIPIENT.to_string(),
+                deadline: None,
+                integrator_id: None,
+                recovery: None,
+                memo: None,
+                smart_wallet: None,
+            }
+            .data(),
+        )
+    }
+
+    fn attest_outbound(&self, nonce: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        program_ix(
+            accounts::AttestOutbound {
+                authority: self.payer.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                throttle: pda::throttle(&program_id),
+                sla_config: pda::sla_config(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                bridge_authority: pda::bridge_authority(&program_id),
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+            }
+            .to_account_metas(None),
+            instruction::AttestOutbound { nonce }.data(),
+        )
+    }
+
+    fn mint(&self, nonce: u64, amount: u64, epoch: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let authority = self.payer.pubkey();
+        let wrapped_mint = self.keys.wrapped_mint.pubkey();
+        program_ix(
+            accounts::MintWrapped {
+                user: self.user(),
+                authority,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::inbound_transfer(&program_id, nonce),
+                inbox: pda::inbox(&program_id, chain_ids::ETHEREUM),
+                source_chain: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                guardian_council: pda::guardian_council(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                reward_config: pda::reward_config(&program_id),
+                reward_epoch: pda::reward_epoch(&program_id, epoch),
+                relayer_stats: pda::relayer_stats(&program_id, epoch, &authority),
+                wrapped_mint,
+                token_config: pda::token_config(&program_id, &wrapped_mint),
+                credential: None,
+                vaa: None,
+                user_token: Some(self.user_wrapped_token()),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::Mint { amount, nonce }.data(),
+        )
+    }
+
+    fn burn(&self, nonce: u64, amount: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let wrapped_mint = self.keys.wrapped_mint.pubkey();
+        program_ix(
+            accounts::BurnTokens {
+                user: self.user(),
+                bridge_state: pda::bridge_state(&program_id),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                outbox: pda::outbox(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                fee_tiers: pda::fee_tiers(&program_id),
+                user_stats: pda::user_stats(&program_id, &self.user()),
+                wrapped_mint,
+                token_config: pda::token_config(&program_id, &wrapped_mint),
+                relay_route: pda::relay_route(&program_id, &wrapped_mint, chain_ids::ETHEREUM),
+                throttle: pda::throttle(&program_id),
+                redemption_queue: pda::redemption_queue(&program_id, &wrapped_mint),
+                route_sequence: pda::route_sequence(
+                    &program_id,
+                    &wrapped_mint,
+                    chain_ids::ETHEREUM,
+                ),
+                sender_allowlist: pda::sender_allowlist(&program_id),
+                allowed_sender: pda::allowed_sender(&program_id, &self.user()),
+                withdrawal_allowlist: pda::withdrawal_allowlist(&program_id, &self.user()),
+                user_token: self.user_wrapped_token(),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+                integrator: None,
+                burn_approval: pda::burn_approval(&program_id, &wrapped_mint),
+                compliance_signer: None,
+            }
+            .to_account_metas(None),
+            instruction::Burn {
+                amount,
+                dest_chain_id: chain_ids::ETHEREUM,
+                recipient: RECIPIENT.to_string(),
+                deadline: None,
+                integrator_id: None,
+            }
+            .data(),
+        )
+    }
+
+    fn unlock(&self, nonce: u64, amount: u64, epoch: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let authority = self.payer.pubkey();
+        let mint = self.keys.mint.pubkey();
+        program_ix(
+            accounts::Unlock {
+                user: self.user(),
+                authority,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::inbound_transfer(&program_id, nonce),
+                inbox: pda::inbox(&program_id, chain_ids::ETHEREUM),
+                source_chain: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                guardian_council: pda::guardian_council(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                reward_config: pda::reward_config(&program_id),
+                reward_epoch: pda::reward_epoch(&program_id, epoch),
+                relayer_stats: pda::relayer_stats(&program_id, epoch, &authority),
+                mint,
+                token_config: pda::token_config(&program_id, &mint),
+                credential: None,
+                vaa: None,
+                conversion_rate: None,
+                bridge_token: self.keys.escrow.pubkey(),
+                vault_config: pda::vault_config(&program_id, &mint),
+                price_breaker: pda::price_breaker(&program_id, &mint),
+                event_history: pda::event_history(&program_id, &mint),
+                user_token: self.user_token(),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::Unlock { amount, nonce }.data(),
+        )
+    }
+}
+
+fn program_ix(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: solana_bridge::ID,
+        accounts,
+        data,
+    }
+}
+
+fn check(name: &str, used: u64, budget: u32) {
+    println!("{name:<16} {used:>9} / {budget:>9}");
+    assert!(
+        used <= budget as u64,
+        "{name} used {used} compute units, over its budget of {budget}"
+    );
+}
+
+/**
+ * Each instruction runs twice and the second run is measured: the first
+ * creates the user stats and reward accounts, which the budgets leave out
+ */
+#[tokio::test]
+async fn instructions_stay_within_budget() {
+    let mut bench = Bench::start().await;
+    let epoch = bench.reward_epoch().await;
+    // register_instructions gave the mint shard 1 and the wrapped mint shard 2
+    let locked = |sequence| RouteSequence::nonce_at(1, sequence);
+    let burned = |sequence| RouteSequence::nonce_at(2, sequence);
+
+    bench.consume_as_user(bench.lock(locked(1), 100_000)).await;
+    let used = bench.consume_as_user(bench.lock(locked(2), 100_000)).await;
+    check("lock", used, budget::LOCK);
+
+    bench.send(vec![bench.attest_outbound(locked(1))], &[]).await;
+    let used = bench.consume(vec![bench.attest_outbound(locked(2))], &[]).await;
+    check("attest_outbound", used, budget::ATTEST_OUTBOUND);
+
+    bench.send(vec![bench.mint(1, 50_000, epoch)], &[]).await;
+    let used = bench.consume(vec![bench.mint(2, 50_000, epoch)], &[]).await;
+    check("mint", used, budget::MINT);
+
+    bench.consume_as_user(bench.burn(burned(1), 10_000)).await;
+    let used = bench.consume_as_user(bench.burn(burned(2), 10_000)).await;
+    check("burn", used, budget::BURN);
+
+    bench.send(vec![bench.unlock(3, 10_000, epoch)], &[]).await;
+    let used = bench.consume(vec![bench.unlock(4, 10_000, epoch)], &[]).await;
+    check("unlock", used, budget::UNLOCK);
+}
@@ -0,0 +1,25 @@
+//! Full cross-chain flow; `cargo test -p solana-bridge-test-harness --features e2e`
+
+#![cfg(feature = "e2e")]
+
+use solana_bridge_test_harness::Harness;
+
+#[tokio::test]
+async fn round_trip_returns_tokens() {
+    let harness = Harness::start(1_000_000).await.unwrap();
+
+    let trip = harness.bridge_round_trip(250_000).await.unwrap();
+    assert_eq!(trip.outbound_nonce, 1);
+    assert_eq!(trip.inbound_nonce, 1);
+}
+
+#[tokio::test]
+async fn repeated_round_trips_use_fresh_nonces() {
+    let harness = Harness::start(1_000_000).await.unwrap();
+
+    let first = harness.bridge_round_trip(100_000).await.unwrap();
+    let second = harness.bridge_round_trip(100_000).await.unwrap();
+    assert_eq!(second.outbound_nonce, first.outbound_nonce + 1);
+    assert_eq!(second.inbound_nonce, first.inbound_nonce + 1);
+    assert_ne!(first.transfer_id, second.transfer_id);
+}
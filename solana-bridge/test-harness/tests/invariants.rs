@@ -0,0 +1,944 @@
+//! No value created across random instruction sequences (proptest + LiteSVM);
+//! `anchor build`, then `cargo test -p solana-bridge-test-harness --features invariants`
+
+#![cfg(feature = "invariants")]
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::get_associated_token_address;
+use anchor_spl::associated_token::spl_associated_token_account::instruction as ata_instruction;
+use anchor_spl::token::spl_token;
+use litesvm::types::TransactionResult;
+use litesvm::LiteSVM;
+use proptest::prelude::*;
+use solana_bridge::{
+    accounts, chain_ids, instruction, ChainParams, Finality, RelayerRewardConfig, RouteSequence,
+    TransferRecord, TransferStatus,
+};
+use solana_bridge_sdk::pda;
+use solana_bridge_test_harness::solana::config_instructions;
+use solana_sdk::clock::Clock;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::Transaction;
+
+const TOKENS: usize = 2;
+const USERS: usize = 3;
+/// Each user's starting balance of every native token
+const BALANCE: u64 = 1_000_000_000;
+const DECIMALS: u8 = 9;
+const RECIPIENT: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+/// Seconds before a lock or burn with a deadline can be reclaimed
+const DEADLINE: i64 = 120;
+
+/**
+ * One step of a run
+ *
+ * `pick` chooses among the transfers so far, so steps stay meaningful
+ * whatever came before them. Unlock amounts are capped by what Ethereum
+ * could send back (attested locks less earlier unlocks), since the
+ * program trusts the owner's word for them.
+ */
+#[derive(Clone, Debug)]
+enum Op {
+    Lock { token: usize, user: usize, amount: u64, deadline: bool },
+    Burn { token: usize, user: usize, amount: u64, deadline: bool },
+    Attest { pick: usize },
+    Mint { token: usize, user: usize, amount: u64 },
+    Unlock { token: usize, user: usize, amount: u64 },
+    Reclaim { pick: usize },
+    /// Deliver an inbound nonce again
+    Replay { pick: usize },
+    WithdrawFees { token: usize, amount: u64 },
+    Warp { seconds: i64 },
+}
+
+fn op() -> impl Strategy<Value = Op> {
+    let token = 0..TOKENS;
+    let user = 0..USERS;
+    let amount = 1..20_000_000u64;
+    prop_oneof![
+        3 => (token.clone(), user.clone(), amount.clone(), any::<bool>()).prop_map(
+            |(token, user, amount, deadline)| Op::Lock { token, user, amount, deadline }
+        ),
+        2 => (token.clone(), user.clone(), amount.clone(), any::<bool>()).prop_map(
+            |(token, user, amount, deadline)| Op::Burn { token, user, amount, deadline }
+        ),
+        2 => any::<usize>().prop_map(|pick| Op::Attest { pick }),
+        3 => (token.clone(), user.clone(), amount.clone())
+            .prop_map(|(token, user, amount)| Op::Mint { token, user, amount }),
+        2 => (token.clone(), user, amount.clone())
+            .prop_map(|(token, user, amount)| Op::Unlock { token, user, amount }),
+        1 => any::<usize>().prop_map(|pick| Op::Reclaim { pick }),
+        1 => any::<usize>().prop_map(|pick| Op::Replay { pick }),
+        1 => (token, amount).prop_map(|(token, amount)| Op::WithdrawFees { token, amount }),
+        1 => (0..2 * DEADLINE).prop_map(|seconds| Op::Warp { seconds }),
+    ]
+}
+
+/// A native token: the owner mints it, the bridge escrows it
+struct Native {
+    mint: Keypair,
+    escrow: Keypair,
+    fee_vault: Keypair,
+    /// The owner's account, where fees are withdrawn to
+    treasury: Pubkey,
+}
+
+/// Inbound nonces delivered so far, for replays
+#[derive(Clone, Copy)]
+struct Delivery {
+    unlock: bool,
+    token: usize,
+    user: usize,
+    amount: u64,
+    nonce: u64,
+}
+
+struct Bridge {
+    svm: LiteSVM,
+    owner: Keypair,
+    users: Vec<Keypair>,
+    natives: Vec<Native>,
+    wrapped: Vec<Keypair>,
+}
+
+impl Bridge {
+    fn start() -> Self {
+        let program =
+            Path::new(env!("CARGO_MANIFEST_DIR")).join("../target/deploy/solana_bridge.so");
+        assert!(
+            program.exists(),
+            "{} not found; run `anchor build` first",
+            program.display()
+        );
+
+        let mut svm = LiteSVM::new();
+        svm.add_program_from_file(solana_bridge::ID, &program).unwrap();
+        let owner = Keypair::new();
+        svm.airdrop(&owner.pubkey(), 1_000_000_000_000).unwrap();
+
+        let mut bridge = Self {
+            svm,
+            owner,
+            users: (0..USERS).map(|_| Keypair::new()).collect(),
+            natives: Vec::new(),
+            wrapped: (0..TOKENS).map(|_| Keypair::new()).collect(),
+        };
+        for user in &bridge.users {
+            bridge.svm.airdrop(&user.pubkey(), 100_000_000_000).unwrap();
+        }
+        let o = bridge.owner.pubkey();
+        for ix in config_instructions(&o) {
+            bridge.expect(ix, &[]);
+        }
+        bridge.expect(bridge.set_fees(), &[]);
+
+        for _ in 0..TOKENS {
+            let mint = Keypair::new();
+            let native = Native {
+                treasury: get_associated_token_address(&o, &mint.pubkey()),
+                mint,
+                escrow: Keypair::new(),
+                fee_vault: Keypair::new(),
+            };
+            bridge.create_mint(&native.mint, &o);
+            let authority = pda::bridge_authority(&solana_bridge::ID);
+            bridge.create_token_account(&native.escrow, &native.mint.pubkey(), &authority);
+            bridge.create_token_account(&native.fee_vault, &native.mint.pubkey(), &authority);
+            bridge.create_ata(&o, &native.mint.pubkey());
+            for user in 0..USERS {
+                let user = bridge.users[user].pubkey();
+                bridge.create_ata(&user, &native.mint.pubkey());
+                let ix = spl_token::instruction::mint_to(
+                    &spl_token::ID,
+                    &native.mint.pubkey(),
+                    &get_associated_token_address(&user, &native.mint.pubkey()),
+                    &o,
+                    &[],
+                    BALANCE,
+                )
+                .unwrap();
+                bridge.expect(ix, &[]);
+            }
+            bridge.expect(bridge.register(&native.mint.pubkey()), &[]);
+            bridge.expect(bridge.init_route_sequence(&native.mint.pubkey()), &[]);
+            bridge.natives.push(native);
+        }
+
+        let authority = pda::bridge_authority(&solana_bridge::ID);
+        for token in 0..TOKENS {
+            let wrapped = bridge.wrapped[token].insecure_clone();
+            bridge.create_mint(&wrapped, &authority);
+            for user in 0..USERS {
+                let user = bridge.users[user].pubkey();
+                bridge.create_ata(&user, &wrapped.pubkey());
+            }
+            bridge.expect(bridge.register(&wrapped.pubkey()), &[]);
+            bridge.expect(bridge.init_route_sequence(&wrapped.pubkey()), &[]);
+        }
+        bridge
+    }
+
+    /// Send as the owner, plus `signers`
+    fn try_send(&mut self, ix: Instruction, signers: &[&Keypair]) -> TransactionResult {
+        self.svm.expire_blockhash();
+        let mut all = vec![&self.owner];
+        all.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&self.owner.pubkey()),
+            &all,
+            self.svm.latest_blockhash(),
+        );
+        self.svm.send_transaction(tx)
+    }
+
+    /// Whether the step succeeded
+    fn send(&mut self, ix: Instruction, signers: &[&Keypair]) -> bool {
+        self.try_send(ix, signers).is_ok()
+    }
+
+    /// Send, failing the test unless it succeeds (setup)
+    fn expect(&mut self, ix: Instruction, signers: &[&Keypair]) {
+        if let Err(failed) = self.try_send(ix, signers) {
+            panic!("setup failed: {:?}\n{}", failed.err, failed.meta.logs.join("\n"));
+        }
+    }
+
+    fn create_mint(&mut self, mint: &Keypair, authority: &Pubkey) {
+        let space = spl_token::state::Mint::LEN;
+        let create = system_instruction::create_account(
+            &self.owner.pubkey(),
+            &mint.pubkey(),
+            self.svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &spl_token::ID,
+        );
+        self.expect(create, &[mint]);
+        let init = spl_token::instruction::initialize_mint2(
+            &spl_token::ID,
+            &mint.pubkey(),
+            authority,
+            None,
+            DECIMALS,
+        )
+        .unwrap();
+        self.expect(init, &[]);
+    }
+
+    fn create_token_account(&mut self, account: &Keypair, mint: &Pubkey, owner: &Pubkey) {
+        let space = spl_token::state::Account::LEN;
+        let create = system_instruction::create_account(
+            &self.owner.pubkey(),
+            &account.pubkey(),
+            self.svm.minimum_balance_for_rent_exemption(space),
+            space as u64,
+            &spl_token::ID,
+        );
+        self.expect(create, &[account]);
+        let init = spl_token::instruction::initialize_account3(
+            &spl_token::ID,
+            &account.pubkey(),
+            mint,
+            owner,
+        )
+        .unwrap();
+        self.expect(init, &[]);
+    }
+
+    fn create_ata(&mut self, owner: &Pubkey, mint: &Pubkey) {
+        let ix = ata_instruction::create_associated_token_account(
+            &self.owner.pubkey(),
+            owner,
+            mint,
+            &spl_token::ID,
+        );
+        self.expect(ix, &[]);
+    }
+
+    fn fetch<T: AccountDeserialize>(&self, address: &Pubkey) -> Option<T> {
+        let account = self.svm.get_account(address)?;
+        Some(T::try_deserialize(&mut account.data.as_slice()).unwrap())
+    }
+
+    fn token_balance(&self, account: &Pubkey) -> u64 {
+        let account = self.svm.get_account(account).unwrap();
+        spl_token::state::Account::unpack(&account.data).unwrap().amount
+    }
+
+    fn supply(&self, mint: &Pubkey) -> u64 {
+        let account = self.svm.get_account(mint).unwrap();
+        spl_token::state::Mint::unpack(&account.data).unwrap().supply
+    }
+
+    fn now(&self) -> i64 {
+        self.svm.get_sysvar::<Clock>().unix_timestamp
+    }
+
+    fn warp(&mut self, seconds: i64) {
+        let mut clock = self.svm.get_sysvar::<Clock>();
+        clock.unix_timestamp += seconds;
+        clock.slot += 1;
+        self.svm.set_sysvar(&clock);
+    }
+
+    /// Nonce the next lock or burn of `mint` to Ethereum gets
+    fn next_outbound_nonce(&self, mint: &Pubkey) -> u64 {
+        let address = pda::route_sequence(&solana_bridge::ID, mint, chain_ids::ETHEREUM);
+        let sequence: RouteSequence = self.fetch(&address).unwrap();
+        sequence.next_nonce().unwrap()
+    }
+
+    fn reward_epoch(&self) -> u64 {
+        let config: RelayerRewardConfig =
+            self.fetch(&pda::reward_config(&solana_bridge::ID)).unwrap();
+        config.epoch_at(self.now())
+    }
+
+    fn user_token(&self, user: usize, mint: &Pubkey) -> Pubkey {
+        get_associated_token_address(&self.users[user].pubkey(), mint)
+    }
+
+    fn set_fees(&self) -> Instruction {
+        let program_id = solana_bridge::ID;
+        program_ix(
+            accounts::UpdateChain {
+                owner: self.owner.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                admin_log: pda::admin_log(&program_id),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+            }
+            .to_account_metas(None),
+            instruction::SetChainParams {
+                chain_id: chain_ids::ETHEREUM,
+                params: ChainParams {
+                    finality: Finality::Confirmations,
+                    required_confirmations: 1,
+                    base_fee: 1_000,
+                    fee_bps: 30,
+                    min_amount: 0,
+                },
+            }
+            .data(),
+        )
+    }
+
+    fn register(&self, mint: &Pubkey) -> Instruction {
+        let program_id = solana_bridge::ID;
+        program_ix(
+            accounts::RegisterToken {
+                owner: self.owner.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                admin_log: pda::admin_log(&program_id),
+                mint: *mint,
+                token_config: pda::token_config(&program_id, mint),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::RegisterToken {}.data(),
+        )
+    }
+
+    fn init_route_sequence(&self, mint: &Pubkey) -> Instruction {
+        let program_id = solana_bridge::ID;
+        program_ix(
+            accounts::InitRouteSequence {
+                payer: self.owner.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                mint: *mint,
+                token_config: pda::token_config(&program_id, mint),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                route_sequence: pda::route_sequence(&program_id, mint, chain_ids::ETHEREUM),
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::InitRouteSequence {
+                dest_chain_id: chain_ids::ETHEREUM,
+            }
+            .data(),
+        )
+    }
+
+    fn lock(&self, token: usize, user: usize, amount: u64, deadline: Option<i64>) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let native = &self.natives[token];
+        let mint = native.mint.pubkey();
+        let u = self.users[user].pubkey();
+        program_ix(
+            accounts::Lock {
+                user: u,
+                bridge_state: pda::bridge_state(&program_id),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                transfer_record: pda::outbound_transfer(
+                    &program_id,
+                    self.next_outbound_nonce(&mint),
+                ),
+                outbox: pda::outbox(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                fee_tiers: pda::fee_tiers(&program_id),
+                user_stats: pda::user_stats(&program_id, &u),
+                user_token: self.user_token(user, &mint),
+                mint,
+                token_config: pda::token_config(&program_id, &mint),
+                fee_route: pda::fee_route(&program_id, &mint, chain_ids::ETHEREUM),
+                relay_route: pda::relay_route(&program_id, &mint, chain_ids::ETHEREUM),
+                throttle: pda::throttle(&program_id),
+                price_breaker: pda::price_breaker(&program_id, &mint),
+                event_history: pda::event_history(&program_id, &mint),
+                route_sequence: pda::route_sequence(&program_id, &mint, chain_ids::ETHEREUM),
+                fee_exemption: pda::fee_exemption(&program_id, &u),
+                terms_config: pda::terms_config(&program_id),
+                terms_acceptance: pda::terms_acceptance(&program_id, &u),
+                sender_allowlist: pda::sender_allowlist(&program_id),
+                allowed_sender: pda::allowed_sender(&program_id, &u),
+                withdrawal_allowlist: pda::withdrawal_allowlist(&program_id, &u),
+                quote_signers: None,
+                instructions: None,
+                credential: None,
+                bridge_pass: None,
+                conversion_rate: None,
+                bridge_token: native.escrow.pubkey(),
+                fee_vault: native.fee_vault.pubkey(),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+                associated_token_program: None,
+                integrator: None,
+                sol_fee_config: None,
+                sol_fee_vault: None,
+                token_price: None,
+                sol_price: None,
+                delivery_fee_config: None,
+                reward_vault: None,
+                native_price: None,
+                transfer_memo: None,
+                smart_wallet: None,
+            }
+            .to_account_metas(None),
+            instruction::Lock {
+                amount,
+                dest_chain_id: chain_ids::ETHEREUM,
+                recipient: RECIPIENT.to_string(),
+                deadline,
+                integrator_id: None,
+                recovery: None,
+                memo: None,
+                smart_wallet: None,
+            }
+            .data(),
+        )
+    }
+
+    fn burn(&self, token: usize, user: usize, amount: u64, deadline: Option<i64>) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let wrapped_mint = self.wrapped[token].pubkey();
+        let u = self.users[user].pubkey();
+        program_ix(
+            accounts::BurnTokens {
+                user: u,
+                bridge_state: pda::bridge_state(&program_id),
+                chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                transfer_record: pda::outbound_transfer(
+                    &program_id,
+                    self.next_outbound_nonce(&wrapped_mint),
+                ),
+                outbox: pda::outbox(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                fee_tiers: pda::fee_tiers(&program_id),
+                user_stats: pda::user_stats(&program_id, &u),
+                wrapped_mint,
+                token_config: pda::token_config(&program_id, &wrapped_mint),
+                relay_route: pda::relay_route(&program_id, &wrapped_mint, chain_ids::ETHEREUM),
+                throttle: pda::throttle(&program_id),
+                redemption_queue: pda::redemption_queue(&program_id, &wrapped_mint),
+                route_sequence: pda::route_sequence(
+                    &program_id,
+                    &wrapped_mint,
+                    chain_ids::ETHEREUM,
+                ),
+                sender_allowlist: pda::sender_allowlist(&program_id),
+                allowed_sender: pda::allowed_sender(&program_id, &u),
+                withdrawal_allowlist: pda::withdrawal_allowlist(&program_id, &u),
+                user_token: self.user_token(user, &wrapped_mint),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+                integrator: None,
+                burn_approval: pda::burn_approval(&program_id, &wrapped_mint),
+                compliance_signer: None,
+            }
+            .to_account_metas(None),
+            instruction::Burn {
+                amount,
+                dest_chain_id: chain_ids::ETHEREUM,
+                recipient: RECIPIENT.to_string(),
+                deadline,
+                integrator_id: None,
+            }
+            .data(),
+        )
+    }
+
+    fn attest(&self, nonce: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        program_ix(
+            accounts::AttestOutbound {
+                authority: self.owner.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                throttle: pda::throttle(&program_id),
+                sla_config: pda::sla_config(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                bridge_authority: pda::bridge_authority(&program_id),
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+            }
+            .to_account_metas(None),
+            instruction::AttestOutbound { nonce }.data(),
+        )
+    }
+
+    fn mint(&self, delivery: Delivery) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let authority = self.owner.pubkey();
+        let epoch = self.reward_epoch();
+        let wrapped_mint = self.wrapped[delivery.token].pubkey();
+        program_ix(
+            accounts::MintWrapped {
+                user: self.users[delivery.user].pubkey(),
+                authority,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::inbound_transfer(&program_id, delivery.nonce),
+                inbox: pda::inbox(&program_id, chain_ids::ETHEREUM),
+                source_chain: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                guardian_council: pda::guardian_council(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                reward_config: pda::reward_config(&program_id),
+                reward_epoch: pda::reward_epoch(&program_id, epoch),
+                relayer_stats: pda::relayer_stats(&program_id, epoch, &authority),
+                wrapped_mint,
+                token_config: pda::token_config(&program_id, &wrapped_mint),
+                credential: None,
+                vaa: None,
+                user_token: Some(self.user_token(delivery.user, &wrapped_mint)),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::Mint {
+                amount: delivery.amount,
+                nonce: delivery.nonce,
+            }
+            .data(),
+        )
+    }
+
+    fn unlock(&self, delivery: Delivery) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let authority = self.owner.pubkey();
+        let epoch = self.reward_epoch();
+        let native = &self.natives[delivery.token];
+        let mint = native.mint.pubkey();
+        program_ix(
+            accounts::Unlock {
+                user: self.users[delivery.user].pubkey(),
+                authority,
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::inbound_transfer(&program_id, delivery.nonce),
+                inbox: pda::inbox(&program_id, chain_ids::ETHEREUM),
+                source_chain: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                guardian_council: pda::guardian_council(&program_id),
+                guardian_set: pda::guardian_set(&program_id),
+                reward_config: pda::reward_config(&program_id),
+                reward_epoch: pda::reward_epoch(&program_id, epoch),
+                relayer_stats: pda::relayer_stats(&program_id, epoch, &authority),
+                mint,
+                token_config: pda::token_config(&program_id, &mint),
+                credential: None,
+                vaa: None,
+                conversion_rate: None,
+                bridge_token: native.escrow.pubkey(),
+                vault_config: pda::vault_config(&program_id, &mint),
+                price_breaker: pda::price_breaker(&program_id, &mint),
+                event_history: pda::event_history(&program_id, &mint),
+                user_token: self.user_token(delivery.user, &mint),
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                system_program: solana_sdk::system_program::ID,
+            }
+            .to_account_metas(None),
+            instruction::Unlock {
+                amount: delivery.amount,
+                nonce: delivery.nonce,
+            }
+            .data(),
+        )
+    }
+
+    /// `record` is an outbound transfer of `user`'s in `mint`
+    fn reclaim(&self, nonce: u64, record: &TransferRecord, user: usize) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let escrow = self
+            .natives
+            .iter()
+            .find(|native| native.mint.pubkey() == record.mint)
+            .map(|native| native.escrow.pubkey());
+        program_ix(
+            accounts::ReclaimExpired {
+                sender: self.users[user].pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                transfer_record: pda::outbound_transfer(&program_id, nonce),
+                throttle: pda::throttle(&program_id),
+                mint: record.mint,
+                token_config: pda::token_config(&program_id, &record.mint),
+                conversion_rate: None,
+                refund_token: self.user_token(user, &record.mint),
+                bridge_token: escrow,
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+                receipt_mint: None,
+                receipt_token: None,
+                token_2022_program: None,
+            }
+            .to_account_metas(None),
+            instruction::ReclaimExpired { nonce }.data(),
+        )
+    }
+
+    fn withdraw_fees(&self, token: usize, amount: u64) -> Instruction {
+        let program_id = solana_bridge::ID;
+        let native = &self.natives[token];
+        let mint = native.mint.pubkey();
+        program_ix(
+            accounts::WithdrawFees {
+                owner: self.owner.pubkey(),
+                bridge_state: pda::bridge_state(&program_id),
+                admin_log: pda::admin_log(&program_id),
+                mint,
+                token_config: pda::token_config(&program_id, &mint),
+                fee_vault: native.fee_vault.pubkey(),
+                destination: native.treasury,
+                bridge_authority: pda::bridge_authority(&program_id),
+                token_program: spl_token::ID,
+            }
+            .to_account_metas(None),
+            instruction::WithdrawFees { amount }.data(),
+        )
+    }
+}
+
+/**
+ * What the token accounts should hold, and running totals per token
+ *
+ * Built only from steps that succeeded, and checked against the chain
+ * after every step, failed ones included.
+ */
+#[derive(Default)]
+struct Model {
+    balances: HashMap<Pubkey, u64>,
+    /// Net of fees, by successful locks
+    locked: [u64; TOKENS],
+    fees: [u64; TOKENS],
+    withdrawn: [u64; TOKENS],
+    unlocked: [u64; TOKENS],
+    /// Locks paid back out of escrow
+    refunded: [u64; TOKENS],
+    /// Net of attested locks: what Ethereum can send back
+    delivered: [u64; TOKENS],
+    minted: [u64; TOKENS],
+    burned: [u64; TOKENS],
+    /// Burns minted back on reclaim
+    reminted: [u64; TOKENS],
+    outbound: Vec<u64>,
+    inbound: Vec<Delivery>,
+    next_inbound: u64,
+}
+
+impl Model {
+    fn new(bridge: &Bridge) -> Self {
+        let mut model = Self {
+            next_inbound: 1,
+            ..Self::default()
+        };
+        for native in &bridge.natives {
+            let mint = native.mint.pubkey();
+            model.balances.insert(native.escrow.pubkey(), 0);
+            model.balances.insert(native.fee_vault.pubkey(), 0);
+            model.balances.insert(native.treasury, 0);
+            for user in 0..USERS {
+                model.balances.insert(bridge.user_token(user, &mint), BALANCE);
+            }
+        }
+        for wrapped in &bridge.wrapped {
+            for user in 0..USERS {
+                model.balances.insert(bridge.user_token(user, &wrapped.pubkey()), 0);
+            }
+        }
+        model
+    }
+
+    fn add(&mut self, account: Pubkey, amount: u64) {
+        *self.balances.get_mut(&account).unwrap() += amount;
+    }
+
+    fn sub(&mut self, account: Pubkey, amount: u64) {
+        let balance = self.balances.get_mut(&account).unwrap();
+        *balance = balance.checked_sub(amount).expect("model balance went negative");
+    }
+}
+
+fn user_of(bridge: &Bridge, record: &TransferRecord) -> usize {
+    bridge
+        .users
+        .iter()
+        .position(|user| user.pubkey() == record.local_account)
+        .unwrap()
+}
+
+fn apply(bridge: &mut Bridge, model: &mut Model, op: &Op) {
+    match *op {
+        Op::Lock { token, user, amount, deadline } => {
+            let nonce = bridge.next_outbound_nonce(&bridge.natives[token].mint.pubkey());
+            let deadline = deadline.then(|| bridge.now() + DEADLINE);
+            let ix = bridge.lock(token, user, amount, deadline);
+            let signer = bridge.users[user].insecure_clone();
+            if bridge.send(ix, &[&signer]) {
+                let record: TransferRecord = bridge
+                    .fetch(&pda::outbound_transfer(&solana_bridge::ID, nonce))
+                    .unwrap();
+                let net = record.amount;
+                let fee = amount - net;
+                let native = &bridge.natives[token];
+                model.sub(bridge.user_token(user, &native.mint.pubkey()), amount);
+                model.add(native.escrow.pubkey(), net);
+                model.add(native.fee_vault.pubkey(), fee);
+                model.locked[token] += net;
+                model.fees[token] += fee;
+                model.outbound.push(nonce);
+            }
+        }
+        Op::Burn { token, user, amount, deadline } => {
+            let nonce = bridge.next_outbound_nonce(&bridge.wrapped[token].pubkey());
+            let deadline = deadline.then(|| bridge.now() + DEADLINE);
+            let ix = bridge.burn(token, user, amount, deadline);
+            let signer = bridge.users[user].insecure_clone();
+            if bridge.send(ix, &[&signer]) {
+                let record: TransferRecord = bridge
+                    .fetch(&pda::outbound_transfer(&solana_bridge::ID, nonce))
+                    .unwrap();
+                assert_eq!(record.amount, amount, "burn recorded a different amount");
+                model.sub(bridge.user_token(user, &bridge.wrapped[token].pubkey()), amount);
+                model.burned[token] += amount;
+                model.outbound.push(nonce);
+            }
+        }
+        Op::Attest { pick } => {
+            let Some(&nonce) = pick_from(&model.outbound, pick) else {
+                return;
+            };
+            let ix = bridge.attest(nonce);
+            if bridge.send(ix, &[]) {
+                let record: TransferRecord = bridge
+                    .fetch(&pda::outbound_transfer(&solana_bridge::ID, nonce))
+                    .unwrap();
+                if let Some(token) = native_index(bridge, &record.mint) {
+                    model.delivered[token] += record.amount;
+                }
+            }
+        }
+        Op::Mint { token, user, amount } => {
+            let delivery = Delivery {
+                unlock: false,
+                token,
+                user,
+                amount,
+                nonce: model.next_inbound,
+            };
+            let ix = bridge.mint(delivery);
+            if bridge.send(ix, &[]) {
+                model.add(bridge.user_token(user, &bridge.wrapped[token].pubkey()), amount);
+                model.minted[token] += amount;
+                model.inbound.push(delivery);
+                model.next_inbound += 1;
+            }
+        }
+        Op::Unlock { token, user, amount } => {
+            let available = model.delivered[token] - model.unlocked[token];
+            if available == 0 {
+                return;
+            }
+            let delivery = Delivery {
+                unlock: true,
+                token,
+                user,
+                amount: 1 + (amount - 1) % available,
+                nonce: model.next_inbound,
+            };
+            let ix = bridge.unlock(delivery);
+            if bridge.send(ix, &[]) {
+                let native = &bridge.natives[token];
+                model.sub(native.escrow.pubkey(), delivery.amount);
+                model.add(bridge.user_token(user, &native.mint.pubkey()), delivery.amount);
+                model.unlocked[token] += delivery.amount;
+                model.inbound.push(delivery);
+                model.next_inbound += 1;
+            }
+        }
+        Op::Reclaim { pick } => {
+            let Some(&nonce) = pick_from(&model.outbound, pick) else {
+                return;
+            };
+            let record: TransferRecord = bridge
+                .fetch(&pda::outbound_transfer(&solana_bridge::ID, nonce))
+                .unwrap();
+            let user = user_of(bridge, &record);
+            let ix = bridge.reclaim(nonce, &record, user);
+            let signer = bridge.users[user].insecure_clone();
+            if bridge.send(ix, &[&signer]) {
+                model.add(bridge.user_token(user, &record.mint), record.amount);
+                match native_index(bridge, &record.mint) {
+                    Some(token) => {
+                        model.sub(bridge.natives[token].escrow.pubkey(), record.amount);
+                        model.refunded[token] += record.amount;
+                    }
+                    None => {
+                        let token = bridge
+                            .wrapped
+                            .iter()
+                            .position(|wrapped| wrapped.pubkey() == record.mint)
+                            .unwrap();
+                        model.reminted[token] += record.amount;
+                    }
+                }
+            }
+        }
+        Op::Replay { pick } => {
+            let Some(&delivery) = pick_from(&model.inbound, pick) else {
+                return;
+            };
+            let ix = if delivery.unlock {
+                bridge.unlock(delivery)
+            } else {
+                bridge.mint(delivery)
+            };
+            // Rejected, or accepted as a no-op with idempotent delivery;
+            // `check` finds any tokens it moved
+            bridge.send(ix, &[]);
+        }
+        Op::WithdrawFees { token, amount } => {
+            let ix = bridge.withdraw_fees(token, amount);
+            if bridge.send(ix, &[]) {
+                let native = &bridge.natives[token];
+                model.sub(native.fee_vault.pubkey(), amount);
+                model.add(native.treasury, amount);
+                model.withdrawn[token] += amount;
+            }
+        }
+        Op::Warp { seconds } => bridge.warp(seconds),
+    }
+}
+
+fn pick_from<T>(items: &[T], pick: usize) -> Option<&T> {
+    (!items.is_empty()).then(|| &items[pick % items.len()])
+}
+
+fn native_index(bridge: &Bridge, mint: &Pubkey) -> Option<usize> {
+    bridge.natives.iter().position(|native| native.mint.pubkey() == *mint)
+}
+
+/// The conservation laws, and every watched account against the model
+fn check(bridge: &Bridge, model: &Model) {
+    for (account, expected) in &model.balances {
+        assert_eq!(bridge.token_balance(account), *expected, "balance of {account}");
+    }
+
+    for (token, native) in bridge.natives.iter().enumerate() {
+        let mint = native.mint.pubkey();
+        let escrow = bridge.token_balance(&native.escrow.pubkey());
+        let fee_vault = bridge.token_balance(&native.fee_vault.pubkey());
+        assert_eq!(
+            escrow + model.unlocked[token] + model.refunded[token],
+            model.locked[token],
+            "escrow + unlocked + refunded != locked for native token {token}"
+        );
+        assert_eq!(
+            fee_vault + model.withdrawn[token],
+            model.fees[token],
+            "fee vault + withdrawn != fees for native token {token}"
+        );
+        assert!(
+            model.unlocked[token] <= model.delivered[token],
+            "native token {token} unlocked more than reached Ethereum"
+        );
+
+        let supply = BALANCE * USERS as u64;
+        assert_eq!(bridge.supply(&mint), supply, "native token {token} supply changed");
+        let held: u64 = (0..USERS)
+            .map(|user| bridge.token_balance(&bridge.user_token(user, &mint)))
+            .sum::<u64>()
+            + escrow
+            + fee_vault
+            + bridge.token_balance(&native.treasury);
+        assert_eq!(held, supply, "native token {token} moved outside the bridge");
+    }
+
+    for (token, wrapped) in bridge.wrapped.iter().enumerate() {
+        let supply = bridge.supply(&wrapped.pubkey());
+        assert_eq!(
+            supply + model.burned[token],
+            model.minted[token] + model.reminted[token],
+            "wrapped supply != minted - burned + reminted for token {token}"
+        );
+        let held: u64 = (0..USERS)
+            .map(|user| bridge.token_balance(&bridge.user_token(user, &wrapped.pubkey())))
+            .sum();
+        assert_eq!(held, supply, "wrapped token {token} held outside user accounts");
+    }
+}
+
+fn program_ix(accounts: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: solana_bridge::ID,
+        accounts,
+        data,
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(64))]
+
+    /**
+     * Any interleaving of locks, burns, attestations, mints, unlocks,
+     * reclaims, replays and fee withdrawals over several tokens and
+     * users conserves value
+     */
+    #[test]
+    fn no_value_created(ops in prop::collection::vec(op(), 1..48)) {
+        let mut bridge = Bridge::start();
+        let mut model = Model::new(&bridge);
+        for op in &ops {
+            apply(&mut bridge, &mut model, op);
+            check(&bridge, &model);
+        }
+        // Outbound records end up in a known state
+        for nonce in &model.outbound {
+            let record: TransferRecord = bridge
+                .fetch(&pda::outbound_transfer(&solana_bridge::ID, *nonce))
+                .unwrap();
+            prop_assert!(matches!(
+                record.status,
+                TransferStatus::Initiated | TransferStatus::Attested | TransferStatus::Refunded
+            ));
+        }
+    }
+}
@@ -0,0 +1,361 @@
+//! Test vectors shared with the Solidity side
+//!
+//! The program and the EVM contracts each compute transfer hashes, event
+//! encodings and fees their own way, and a drift between them fails
+//! quietly: signatures stop recovering, or replay protection keys on an
+//! ID the other side never produces. `generate` runs the program's own
+//! functions over fixed inputs; the output is checked in as vectors.json.
+//! tests/vectors.rs fails if the program no longer reproduces the file,
+//! and test-harness/test/Vectors.t.sol runs the same file through
+//! Solidity (`forge test`), so either side drifting breaks a build.
+//!
+//! After an intended change, regenerate and commit the file:
+//!
+//!   cargo run -p solana-bridge-test-vectors > test-vectors/vectors.json
+//!
+//! Integers are decimal strings and bytes 0x-prefixed hex, so u64s survive
+//! JavaScript and Foundry's JSON cheatcodes intact.
+
+use bridge_core::{chain_ids, codec};
+use serde_json::{json, Value};
+use solana_bridge::math;
+
+/// The EVM events the relayer and light client read (MockSolanaBridge)
+pub const EVM_EVENTS: [(&str, &str); 3] = [
+    ("Lock", "Lock(address,uint256,uint256,string)"),
+    ("Burn", "Burn(address,uint256,uint256,string)"),
+    ("Mint", "Mint(address,uint256,bytes32)"),
+];
+
+/// anvil's first account, which sends the locks below
+const ANVIL_ACCOUNT: [u8; 20] = [
+    0xf3, 0x9f, 0xd6, 0xe5, 0x1a, 0xad, 0x88, 0xf6, 0xf4, 0xce, 0x6a, 0xb8, 0x82, 0x72, 0x79, 0xcf,
+    0xff, 0xb9, 0x22, 0x66,
+];
+
+/// anvil's second account, the relayer in the e2e harness
+const RELAYER: [u8; 20] = [
+    0x70, 0x99, 0x79, 0x70, 0xc5, 0x18, 0x12, 0xdc, 0x3a, 0x01, 0x0c, 0x7d, 0x01, 0xb5, 0x0e, 0x0d,
+    0x17, 0xdc, 0x79, 0xc8,
+];
+
+pub fn generate() -> Value {
+    json!({
+        "transfer_message_hash": transfer_message_hashes(),
+        "inbound_message_hash": inbound_message_hashes(),
+        "mint_request_hash": mint_request_hashes(),
+        "bridge_status_hash": bridge_status_hashes(),
+        "lock_proof_hash": lock_proof_hashes(),
+        "transfer_id": transfer_ids(),
+        "event_topics": event_topics(),
+        "lock_events": lock_events(),
+        "message_hash": message_hashes(),
+        "transfer_fee": transfer_fees(),
+        "vault_balance_hash": vault_balance_hashes(),
+    })
+}
+
+fn hex(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// 1, 2, 3, ... 32: a key whose byte order is visible in the hash input
+fn counting() -> [u8; 32] {
+    std::array::from_fn(|i| i as u8 + 1)
+}
+
+fn transfer_message_hashes() -> Value {
+    let cases = [
+        (counting(), 1_000_000, 7),
+        ([0u8; 32], 0, 0),
+        ([0xff; 32], u64::MAX, u64::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(recipient, amount, nonce)| {
+            json!({
+                "recipient": hex(recipient),
+                "amount": amount.to_string(),
+                "nonce": nonce.to_string(),
+                "hash": hex(&codec::transfer_message_hash(recipient, *amount, *nonce)),
+            })
+        })
+        .collect()
+}
+
+fn inbound_message_hashes() -> Value {
+    let cases = [
+        (chain_ids::ETHEREUM, 0, [2u8; 32], counting(), 1_000_000, 7),
+        (chain_ids::ETHEREUM, 3, [2u8; 32], counting(), 1_000_000, 7),
+        (chain_ids::ARBITRUM, 1, counting(), [0u8; 32], 0, 0),
+        (u64::MAX, u32::MAX, [0xff; 32], [0xff; 32], u64::MAX, u64::MAX),
+    ];
+    cases
+        .iter()
+        .map(|(source_chain_id, guardian_set_index, token, recipient, amount, nonce)| {
+            let hash = codec::inbound_message_hash(
+                *source_chain_id,
+                *guardian_set_index,
+                token,
+                recipient,
+                *amount,
+                *nonce,
+            );
+            json!({
+                "source_chain_id": source_chain_id.to_string(),
+                "guardian_set_index": guardian_set_index.to_string(),
+                "token": hex(token),
+                "recipient": hex(recipient),
+                "amount": amount.to_string(),
+                "nonce": nonce.to_string(),
+                "hash": hex(&hash),
+            })
+        })
+        .collect()
+}
+
+fn mint_request_hashes() -> Value {
+    let cases = [(1_000_000, [0xab; 32]), (u64::MAX, counting())];
+    cases
+        .iter()
+        .map(|(amount, transfer_id)| {
+            let hash = codec::mint_request_hash(&RELAYER, *amount, transfer_id);
+            json!({
+                "to": hex(&RELAYER),
+                "amount": amount.to_string(),
+                "transfer_id": hex(transfer_id),
+                "hash": hex(&hash),
+                "eth_signed_hash": hex(&codec::eth_signed_message_hash(&hash)),
+            })
+        })
+        .collect()
+}
+
+/// In sequence order, so the Solidity test can apply each to one bridge
+fn bridge_status_hashes() -> Value {
+    let cases = [(true, 0), (false, 1), (true, 7)];
+    cases
+        .iter()
+        .map(|&(paused, sequence)| {
+            let hash = codec::bridge_status_hash(chain_ids::SOLANA, paused, sequence);
+            json!({
+                "source_chain_id": chain_ids::SOLANA.to_string(),
+                "paused": paused,
+                "sequence": sequence.to_string(),
+                "hash": hex(&hash),
+                "eth_signed_hash": hex(&codec::eth_signed_message_hash(&hash)),
+            })
+        })
+        .collect()
+}
+
+/// In sequence order, so the Solidity test can apply each to one bridge
+fn vault_balance_hashes() -> Value {
+    let cases = [
+        (counting(), 1_000_000, 4242, 0),
+        ([0xff; 32], u64::MAX, u64::MAX, 1),
+    ];
+    cases
+        .iter()
+        .map(|(mint, amount, slot, sequence)| {
+            let hash =
+                codec::vault_balance_hash(chain_ids::SOLANA, mint, *amount, *slot, *sequence);
+            json!({
+                "source_chain_id": chain_ids::SOLANA.to_string(),
+                "mint": hex(mint),
+                "amount": amount.to_string(),
+                "slot": slot.to_string(),
+                "sequence": sequence.to_string(),
+                "hash": hex(&hash),
+                "eth_signed_hash": hex(&codec::eth_signed_message_hash(&hash)),
+            })
+        })
+        .collect()
+}
+
+fn message_hashes() -> Value {
+    let cases = [
+        (counting(), chain_ids::ETHEREUM, [0x12; 32], 0, b"hello".to_vec()),
+        ([0u8; 32], chain_ids::ARBITRUM, counting(), 1, vec![]),
+        ([0xff; 32], u64::MAX, [0xff; 32], u64::MAX, vec![0xff; 64]),
+    ];
+    cases
+        .iter()
+        .map(|(sender, dest_chain_id, target, sequence, payload)| {
+            let hash = codec::message_hash(
+                chain_ids::SOLANA,
+                sender,
+                *dest_chain_id,
+                target,
+                *sequence,
+                payload,
+            );
+            json!({
+                "source_chain_id": chain_ids::SOLANA.to_string(),
+                "sender": hex(sender),
+                "dest_chain_id": dest_chain_id.to_string(),
+                "target": hex(target),
+                "sequence": sequence.to_string(),
+                "payload": hex(payload),
+                "hash": hex(&hash),
+            })
+        })
+        .collect()
+}
+
+fn lock_proof_hashes() -> Value {
+    let cases = [
+        ([0xab; 32], 1_000_000, 4242, [0x11; 32], [0x22; 64]),
+        (counting(), u64::MAX, u64::MAX, counting(), [0xff; 64]),
+    ];
+    cases
+        .iter()
+        .map(|(transfer_id, amount, slot, blockhash, tx_signature)| {
+            let hash = codec::lock_proof_hash(
+                chain_ids::SOLANA,
+                transfer_id,
+                &RELAYER,
+                *amount,
+                *slot,
+                blockhash,
+                tx_signature,
+            );
+            json!({
+                "source_chain_id": chain_ids::SOLANA.to_string(),
+                "transfer_id": hex(transfer_id),
+                "to": hex(&RELAYER),
+                "amount": amount.to_string(),
+                "slot": slot.to_string(),
+                "blockhash": hex(blockhash),
+                "tx_signature": hex(tx_signature),
+                "hash": hex(&hash),
+                "eth_signed_hash": hex(&codec::eth_signed_message_hash(&hash)),
+            })
+        })
+        .collect()
+}
+
+fn transfer_ids() -> Value {
+    let cases = [
+        (900, chain_ids::ETHEREUM, 0, 7, "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"),
+        (
+            chain_ids::SOLANA,
+            chain_ids::ARBITRUM,
+            3,
+            u64::MAX,
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266",
+        ),
+        (
+            chain_ids::SOLANA,
+            chain_ids::STELLAR,
+            1,
+            42,
+            "GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7",
+        ),
+    ];
+    let (token, sender) = ([2u8; 32], [3u8; 32]);
+    cases
+        .iter()
+        .map(|(source, dest, guardian_set_index, nonce, recipient)| {
+            let id = codec::transfer_id(
+                *source,
+                *dest,
+                *guardian_set_index,
+                *nonce,
+                &token,
+                1_000_000,
+                &sender,
+                recipient,
+            );
+            json!({
+                "source_chain_id": source.to_string(),
+                "dest_chain_id": dest.to_string(),
+                "guardian_set_index": guardian_set_index.to_string(),
+                "nonce": nonce.to_string(),
+                "token": hex(&token),
+                "amount": "1000000",
+                "sender": hex(&sender),
+                "recipient": recipient,
+                "id": hex(&id),
+            })
+        })
+        .collect()
+}
+
+fn event_topics() -> Value {
+    EVM_EVENTS
+        .iter()
+        .map(|(name, signature)| (name.to_string(), hex(&codec::event_topic(signature)).into()))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+/**
+ * `Lock` logs as the EVM bridge emits them, for consecutive locks on a
+ * fresh contract (nonces from 1): the sender is the indexed topic, and
+ * the data is abi.encode(uint256 amount, uint256 nonce, string solanaAddress)
+ */
+fn lock_events() -> Value {
+    let cases = [
+        (1_000_000_000_000_000_000, "So11111111111111111111111111111111111111112"),
+        (1, "wallet.sol"),
+        (u64::MAX, ""),
+    ];
+    cases
+        .iter()
+        .zip(1u64..)
+        .map(|((amount, solana_address), nonce)| {
+            let mut sender = [0u8; 32];
+            sender[12..].copy_from_slice(&ANVIL_ACCOUNT);
+            json!({
+                "from": hex(&ANVIL_ACCOUNT),
+                "amount": amount.to_string(),
+                "nonce": nonce.to_string(),
+                "solana_address": solana_address,
+                "topics": [hex(&codec::event_topic(EVM_EVENTS[0].1)), hex(&sender)],
+                "data": hex(&abi_encode_transfer(*amount, nonce, solana_address)),
+            })
+        })
+        .collect()
+}
+
+/// abi.encode(uint256, uint256, string): two words, the string's offset,
+/// then its length and bytes padded to a whole word
+fn abi_encode_transfer(amount: u64, nonce: u64, text: &str) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(&codec::uint256(amount));
+    out.extend_from_slice(&codec::uint256(nonce));
+    out.extend_from_slice(&codec::uint256(3 * 32));
+    out.extend_from_slice(&codec::uint256(text.len() as u64));
+    out.extend_from_slice(text.as_bytes());
+    out.resize(out.len().next_multiple_of(32), 0);
+    out
+}
+
+fn transfer_fees() -> Value {
+    // (amount, base_fee, fee_bps, discount_bps): the Arbitrum route from
+    // the tests, rebates up to a full waiver, and rounding at the edges
+    let cases = [
+        (1_000_000, 1_000, 5, 0),
+        (1_000_000, 1_000, 5, 2_500),
+        (1_000_000, 1_000, 5, 10_000),
+        (1_999, 0, 5, 0),
+        (12_345_678, 7, 30, 3_333),
+        (u64::MAX, 0, 10_000, 0),
+        (u64::MAX, 0, 9_999, 1),
+    ];
+    cases
+        .iter()
+        .map(|(amount, base_fee, fee_bps, discount_bps)| {
+            let fee = math::transfer_fee(*amount, *base_fee, *fee_bps, *discount_bps)
+                .expect("vector fees fit in u64");
+            json!({
+                "amount": amount.to_string(),
+                "base_fee": base_fee.to_string(),
+                "fee_bps": fee_bps.to_string(),
+                "discount_bps": discount_bps.to_string(),
+                "fee": fee.to_string(),
+            })
+        })
+        .collect()
+}
@@ -0,0 +1,6 @@
+//! Print the vectors; redirect into vectors.json to regenerate it
+
+fn main() {
+    let vectors = solana_bridge_test_vectors::generate();
+    println!("{}", serde_json::to_string_pretty(&vectors).expect("vectors serialize"));
+}
@@ -0,0 +1,15 @@
+//! The program against the checked-in vectors (see src/lib.rs)
+
+use serde_json::Value;
+
+#[test]
+fn program_reproduces_checked_in_vectors() {
+    let checked_in: Value = serde_json::from_str(include_str!("../vectors.json")).unwrap();
+    assert_eq!(
+        solana_bridge_test_vectors::generate(),
+        checked_in,
+        "the program's encoding or fee math changed; if intended, regenerate vectors.json \
+         (`cargo run -p solana-bridge-test-vectors > test-vectors/vectors.json`) and \
+         check that `forge test` in test-harness still passes"
+    );
+}
@@ -7,15 +7,36 @@
 
 import * as anchor from '@coral-xyz/anchor';
 import { Program } from '@coral-xyz/anchor';
-import { PublicKey, Keypair, SystemProgram } from '@solana/web3.js';
+import {
+  PublicKey,
+  Keypair,
+  SystemProgram,
+  Ed25519Program,
+  SYSVAR_INSTRUCTIONS_PUBKEY,
+  TransactionInstruction,
+  TransactionMessage,
+} from '@solana/web3.js';
 import {
   TOKEN_PROGRAM_ID,
+  TOKEN_2022_PROGRAM_ID,
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
   createMint,
+  AuthorityType,
   createAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  getMint,
+  getMintLen,
+  getExtensionTypes,
+  getAssociatedTokenAddressSync,
   mintTo,
   getAccount,
+  setAuthority,
 } from '@solana/spl-token';
+import * as multisig from '@sqds/multisig';
 import { assert } from 'chai';
+import { ethers } from 'ethers';
 
 describe('Solana Bridge', () => {
   // Configure the client to use the local cluster
@@ -25,13 +46,133 @@ describe('Solana Bridge', () => {
   const program = anchor.workspace.SolanaBridge;
 
   let bridgeState: PublicKey;
+  let adminLog: PublicKey;
   let bridgeAuthority: PublicKey;
   let mint: PublicKey;
   let wrappedMint: PublicKey;
   let userTokenAccount: PublicKey;
   let bridgeTokenAccount: PublicKey;
+  let feeVaultAccount: PublicKey;
   let user: Keypair;
 
+  // Must match chain_ids in the program
+  const ETHEREUM_CHAIN_ID = new anchor.BN(1);
+  const STELLAR_CHAIN_ID = new anchor.BN('4294967297');
+
+  const tokenConfigPda = (tokenMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('token'), tokenMint.toBuffer()],
+      program.programId
+    )[0];
+
+  const chainConfigPda = (chainId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('chain'), chainId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+
+  const inboxPda = (chainId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('inbox'), chainId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+  const guardianCouncilPda = () =>
+    PublicKey.findProgramAddressSync([Buffer.from('guardian_council')], program.programId)[0];
+
+  const guardianSetPda = () =>
+    PublicKey.findProgramAddressSync([Buffer.from('guardian_set')], program.programId)[0];
+
+  const outboxPda = () =>
+    PublicKey.findProgramAddressSync([Buffer.from('outbox')], program.programId)[0];
+
+  const feeTiersPda = () =>
+    PublicKey.findProgramAddressSync([Buffer.from('fee_tiers')], program.programId)[0];
+
+  const feeRoutePda = (mint: PublicKey, chainId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('fee'), mint.toBuffer(), chainId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+
+  const userStatsPda = (owner: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('user_stats'), owner.toBuffer()],
+      program.programId
+    )[0];
+
+  const feeExemptionPda = (address: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('fee_exempt'), address.toBuffer()],
+      program.programId
+    )[0];
+
+  const vaultConfigPda = (tokenMint: PublicKey) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('vault'), tokenMint.toBuffer()],
+      program.programId
+    )[0];
+
+  const transferPda = (direction: 'in' | 'out', nonce: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('transfer'), Buffer.from(direction), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+
+  const routeSequencePda = (tokenMint: PublicKey, chainId: anchor.BN) =>
+    PublicKey.findProgramAddressSync(
+      [Buffer.from('sequence'), tokenMint.toBuffer(), chainId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+
+  // Must match SEQUENCE_BITS in the program: a route's shard sits above them
+  const SEQUENCE_BITS = 40;
+
+  // Nonce of a token's `sequence`th lock or burn to a chain
+  const outNonce = async (tokenMint: PublicKey, chainId: anchor.BN, sequence: number) => {
+    const route = await program.account.routeSequence.fetch(routeSequencePda(tokenMint, chainId));
+    return new anchor.BN(route.shard).shln(SEQUENCE_BITS).addn(sequence);
+  };
+
+  // Nonce of the `ahead`th next lock or burn of a token to a chain
+  const nextOutNonce = async (tokenMint: PublicKey, chainId: anchor.BN, ahead = 1) => {
+    const route = await program.account.routeSequence.fetch(routeSequencePda(tokenMint, chainId));
+    return new anchor.BN(route.shard).shln(SEQUENCE_BITS).add(route.last.addn(ahead));
+  };
+
+  // Lets a token be locked or burned to a chain
+  const openRoute = (tokenMint: PublicKey, chainId: anchor.BN) =>
+    program.methods
+      .initRouteSequence(chainId)
+      .accounts({
+        bridgeState: bridgeState,
+        payer: provider.wallet.publicKey,
+        mint: tokenMint,
+        tokenConfig: tokenConfigPda(tokenMint),
+        chainConfig: chainConfigPda(chainId),
+        routeSequence: routeSequencePda(tokenMint, chainId),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+  // All tests run inside relayer reward epoch 0
+  const rewardAccounts = (relayer: PublicKey) => {
+    const epoch = new anchor.BN(0).toArrayLike(Buffer, 'le', 8);
+    return {
+      rewardConfig: PublicKey.findProgramAddressSync(
+        [Buffer.from('reward_config')],
+        program.programId
+      )[0],
+      rewardEpoch: PublicKey.findProgramAddressSync(
+        [Buffer.from('reward_epoch'), epoch],
+        program.programId
+      )[0],
+      relayerStats: PublicKey.findProgramAddressSync(
+        [Buffer.from('relayer_epoch'), epoch, relayer.toBuffer()],
+        program.programId
+      )[0],
+    };
+  };
+
   before(async () => {
     // Similar to your EVM test setup!
 
@@ -50,6 +191,12 @@ describe('Solana Bridge', () => {
       program.programId
     );
 
+    // Find admin log PDA
+    [adminLog] = await PublicKey.findProgramAddress(
+      [Buffer.from('admin_log')],
+      program.programId
+    );
+
     // Find bridge authority PDA
     [bridgeAuthority] = await PublicKey.findProgramAddress(
       [Buffer.from('bridge')],
@@ -89,6 +236,14 @@ describe('Solana Bridge', () => {
       bridgeAuthority
     );
 
+    feeVaultAccount = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      bridgeAuthority,
+      Keypair.generate()
+    );
+
     // Mint some tokens to user
     await mintTo(
       provider.connection,
@@ -106,9 +261,11 @@ describe('Solana Bridge', () => {
     // Similar to deploying your EVM bridge!
 
     await program.methods
-      .initialize()
+      .initialize(new anchor.BN(0))
       .accounts({
         bridgeState: bridgeState,
+        adminLog: adminLog,
+        outbox: outboxPda(),
         owner: provider.wallet.publicKey,
         systemProgram: SystemProgram.programId,
       })
@@ -121,192 +278,8416 @@ describe('Solana Bridge', () => {
       state.owner.toString(),
       provider.wallet.publicKey.toString()
     );
+    // Instance 0 keeps the addresses from before instances existed
+    assert.equal(state.instanceId.toString(), '0');
+    assert.equal(state.instanceSeed.length, 0);
 
     console.log('✓ Bridge initialized');
   });
 
-  it('Locks tokens (same as your EVM bridge lock!)', async () => {
-    const amount = new anchor.BN(100000000); // 100 tokens
-    const ethRecipient = '0x1234567890123456789012345678901234567890';
+  it('Runs a second instance beside the first', async () => {
+    const seed = new anchor.BN(1).toArrayLike(Buffer, 'le', 8);
+    const pda = (prefix: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(prefix), seed], program.programId)[0];
+    const staging = {
+      bridgeState: pda('bridge_state'),
+      adminLog: pda('admin_log'),
+      outbox: pda('outbox'),
+    };
+    assert.ok(!staging.bridgeState.equals(bridgeState));
+    assert.ok(!staging.adminLog.equals(adminLog));
 
-    // Call lock (SAME CONCEPT as calling lock() in your EVM bridge!)
     await program.methods
-      .lock(amount, ethRecipient)
+      .initialize(new anchor.BN(1))
       .accounts({
-        user: user.publicKey,
-        bridgeState: bridgeState,
-        userToken: userTokenAccount,
-        bridgeToken: bridgeTokenAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        ...staging,
+        owner: provider.wallet.publicKey,
+        systemProgram: SystemProgram.programId,
       })
-      .signers([user])
       .rpc();
 
-    // Verify bridge received tokens
-    const bridgeAccount = await getAccount(
-      provider.connection,
-      bridgeTokenAccount
-    );
-    assert.equal(
-      bridgeAccount.amount.toString(),
-      amount.toString()
-    );
+    let state = await program.account.bridgeState.fetch(staging.bridgeState);
+    assert.equal(state.instanceId.toString(), '1');
+    assert.deepEqual([...state.instanceSeed], [...seed]);
+    assert.equal(state.authorityBump, PublicKey.findProgramAddressSync(
+      [Buffer.from('bridge'), seed],
+      program.programId
+    )[1]);
 
-    // Verify nonce incremented (SAME AS: checking nonce in your EVM bridge)
-    const state = await program.account.bridgeState.fetch(bridgeState);
-    assert.equal(state.nonce.toString(), '1');
+    // An instance's accounts only work with its own bridge state
+    try {
+      await program.methods
+        .pause()
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: staging.bridgeState,
+          adminLog: adminLog,
+          outbox: outboxPda(),
+        })
+        .rpc();
 
-    console.log('✓ Tokens locked successfully');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ConstraintSeeds'));
+    }
+
+    // Pausing staging leaves instance 0 running
+    await program.methods
+      .pause()
+      .accounts({ owner: provider.wallet.publicKey, ...staging })
+      .rpc();
+    state = await program.account.bridgeState.fetch(staging.bridgeState);
+    assert.equal(state.paused, true);
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, false);
+
+    console.log('✓ Instances keep separate state in one deployment');
   });
 
-  it('Mints wrapped tokens (same as your EVM bridge mint!)', async () => {
-    const amount = new anchor.BN(100000000);
-    const nonce = new anchor.BN(1);
+  it('Stores the PDA bumps the hot paths check against', async () => {
+    const bumpOf = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed)], program.programId)[1];
 
-    // Create wrapped token account for user
-    const userWrappedAccount = await createAccount(
-      provider.connection,
-      user,
-      wrappedMint,
-      user.publicKey
-    );
+    const check = async () => {
+      const state = await program.account.bridgeState.fetch(bridgeState);
+      assert.equal(state.bump, bumpOf('bridge_state'));
+      assert.equal(state.authorityBump, bumpOf('bridge'));
+      assert.equal(state.outboxBump, bumpOf('outbox'));
+    };
+    await check();
 
-    // Call mint (SAME CONCEPT as calling mint() in your EVM bridge!)
+    // What an upgraded deployment runs once; harmless to repeat
     await program.methods
-      .mint(amount, nonce)
+      .cacheBumps()
       .accounts({
-        user: user.publicKey,
-        authority: provider.wallet.publicKey,
         bridgeState: bridgeState,
-        wrappedMint: wrappedMint,
-        userToken: userWrappedAccount,
         bridgeAuthority: bridgeAuthority,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        outbox: outboxPda(),
       })
       .rpc();
+    await check();
 
-    // Verify tokens minted
-    const account = await getAccount(
-      provider.connection,
-      userWrappedAccount
-    );
-    assert.equal(account.amount.toString(), amount.toString());
-
-    // Verify nonce marked as processed (SAME AS: processedNonces check)
-    const state = await program.account.bridgeState.fetch(bridgeState);
-    assert.ok(state.processedNonces.some(n => n.eq(nonce)));
-
-    console.log('✓ Wrapped tokens minted successfully');
+    console.log('✓ PDA bumps cached');
   });
 
-  it('Prevents duplicate mints (same as your EVM bridge!)', async () => {
-    const amount = new anchor.BN(100000000);
-    const nonce = new anchor.BN(1); // Same nonce as before
+  it('Registers destination chains', async () => {
+    await program.methods
+      .registerChain(ETHEREUM_CHAIN_ID, { evm: {} }, {
+        finality: { confirmations: {} },
+        requiredConfirmations: 12,
+        baseFee: new anchor.BN(0),
+        feeBps: 0,
+        minAmount: new anchor.BN(0),
+      })
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
 
-    const userWrappedAccount = await createAccount(
-      provider.connection,
-      user,
-      wrappedMint,
-      user.publicKey
-    );
+    await program.methods
+      .registerChain(STELLAR_CHAIN_ID, { stellar: {} }, {
+        finality: { confirmations: {} },
+        requiredConfirmations: 1,
+        baseFee: new anchor.BN(0),
+        feeBps: 0,
+        minAmount: new anchor.BN(0),
+      })
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
 
-    try {
-      // Try to mint with same nonce
-      await program.methods
-        .mint(amount, nonce)
-        .accounts({
-          user: user.publicKey,
-          authority: provider.wallet.publicKey,
-          bridgeState: bridgeState,
-          wrappedMint: wrappedMint,
-          userToken: userWrappedAccount,
-          bridgeAuthority: bridgeAuthority,
-          tokenProgram: TOKEN_PROGRAM_ID,
-        })
-        .rpc();
+    const chain = await program.account.chainConfig.fetch(
+      chainConfigPda(STELLAR_CHAIN_ID)
+    );
+    assert.equal(chain.enabled, true);
 
-      assert.fail('Should have thrown error');
-    } catch (error) {
-      // Should fail with AlreadyProcessed error
-      assert.ok(error.toString().includes('AlreadyProcessed'));
-      console.log('✓ Duplicate mint prevented');
-    }
+    console.log('✓ Ethereum and Stellar registered');
   });
 
-  it('Burns wrapped tokens (same as your EVM bridge burn!)', async () => {
-    const amount = new anchor.BN(50000000); // 50 tokens
-    const ethRecipient = '0x1234567890123456789012345678901234567890';
+  it('Registers the bridged token', async () => {
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
 
-    // First mint some wrapped tokens
-    const nonce = new anchor.BN(2);
-    const userWrappedAccount = await createAccount(
-      provider.connection,
-      user,
-      wrappedMint,
-      user.publicKey
-    );
+    const config = await program.account.tokenConfig.fetch(tokenConfigPda(mint));
+    assert.equal(config.mint.toString(), mint.toString());
 
+    // The wrapped mint needs a config too (mint paths check restrictions)
     await program.methods
-      .mint(amount, nonce)
+      .registerToken()
       .accounts({
-        user: user.publicKey,
-        authority: provider.wallet.publicKey,
+        owner: provider.wallet.publicKey,
         bridgeState: bridgeState,
-        wrappedMint: wrappedMint,
-        userToken: userWrappedAccount,
-        bridgeAuthority: bridgeAuthority,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        adminLog: adminLog,
+        mint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
 
-    // Now burn them (SAME AS: calling burn() in your EVM bridge!)
+    // Locks and burns take their nonces from the route's sequence
+    await openRoute(mint, ETHEREUM_CHAIN_ID);
+    await openRoute(mint, STELLAR_CHAIN_ID);
+    await openRoute(wrappedMint, ETHEREUM_CHAIN_ID);
+
+    console.log('✓ Token registered');
+  });
+
+  it('Sets volume rebate tiers', async () => {
     await program.methods
-      .burn(amount, ethRecipient)
+      .setFeeTiers([
+        { minVolume: new anchor.BN(1000000000), discountBps: 2500 },
+        { minVolume: new anchor.BN(10000000000), discountBps: 5000 },
+      ])
       .accounts({
-        user: user.publicKey,
+        owner: provider.wallet.publicKey,
         bridgeState: bridgeState,
-        wrappedMint: wrappedMint,
-        userToken: userWrappedAccount,
-        tokenProgram: TOKEN_PROGRAM_ID,
+        adminLog: adminLog,
+        feeTiers: feeTiersPda(),
+        systemProgram: SystemProgram.programId,
       })
-      .signers([user])
       .rpc();
 
-    // Verify tokens burned
-    const account = await getAccount(
-      provider.connection,
-      userWrappedAccount
-    );
-    assert.equal(account.amount.toString(), '0');
+    const config = await program.account.feeTiers.fetch(feeTiersPda());
+    assert.equal(config.tiers.length, 2);
 
-    console.log('✓ Wrapped tokens burned successfully');
+    console.log('✓ Fee tiers set');
   });
 
-  it('Can pause and unpause bridge', async () => {
-    // Pause
+  it('Configures relayer rewards', async () => {
     await program.methods
-      .pause()
+      .setRelayerRewards(new anchor.BN(86400), new anchor.BN(5000))
       .accounts({
         owner: provider.wallet.publicKey,
         bridgeState: bridgeState,
+        adminLog: adminLog,
+        rewardConfig: rewardAccounts(provider.wallet.publicKey).rewardConfig,
+        systemProgram: SystemProgram.programId,
       })
       .rpc();
 
-    let state = await program.account.bridgeState.fetch(bridgeState);
-    assert.equal(state.paused, true);
+    console.log('✓ Relayer rewards configured');
+  });
+
+  it('Creates a wrapped mint with Metaplex metadata', async () => {
+    const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+    const sourceToken = Buffer.from('a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48', 'hex');
+
+    const [wrappedUsdc] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_mint'), sourceToken],
+      program.programId
+    );
+    const [wrappedAsset] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_asset'), wrappedUsdc.toBuffer()],
+      program.programId
+    );
+    const [metadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), wrappedUsdc.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
 
-    // Unpause
     await program.methods
-      .unpause()
+      .createWrappedMint([...sourceToken], 6, 6, 'USD Coin', 'USDC', '')
       .accounts({
         owner: provider.wallet.publicKey,
         bridgeState: bridgeState,
+        adminLog: adminLog,
+        wrappedMint: wrappedUsdc,
+        wrappedAsset: wrappedAsset,
+        metadata: metadata,
+        bridgeAuthority: bridgeAuthority,
+        tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        rent: anchor.web3.SYSVAR_RENT_PUBKEY,
       })
       .rpc();
 
-    state = await program.account.bridgeState.fetch(bridgeState);
-    assert.equal(state.paused, false);
+    const asset = await program.account.wrappedAsset.fetch(wrappedAsset);
+    assert.equal(asset.sourceDecimals, 6);
+    assert.ok(await provider.connection.getAccountInfo(metadata));
 
-    console.log('✓ Pause/unpause working correctly');
+    console.log('✓ Wrapped mint created with metadata');
+  });
+
+  it('Freezes and thaws a wrapped token account', async () => {
+    const sourceToken = Buffer.from('a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48', 'hex');
+    const [wrappedUsdc] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_mint'), sourceToken],
+      program.programId
+    );
+    const [wrappedAsset] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_asset'), wrappedUsdc.toBuffer()],
+      program.programId
+    );
+
+    const mintInfo = await getMint(provider.connection, wrappedUsdc);
+    assert.ok(mintInfo.freezeAuthority.equals(bridgeAuthority));
+
+    const holder = await createAccount(
+      provider.connection,
+      user,
+      wrappedUsdc,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const setFrozen = (frozen: boolean, owner = provider.wallet.publicKey) =>
+      (frozen ? program.methods.freezeWrappedAccount() : program.methods.thawWrappedAccount())
+        .accounts({
+          owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          wrappedAsset: wrappedAsset,
+          wrappedMint: wrappedUsdc,
+          tokenAccount: holder,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        });
+
+    try {
+      await setFrozen(true, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await setFrozen(true).rpc();
+    assert.equal((await getAccount(provider.connection, holder)).isFrozen, true);
+
+    await setFrozen(false).rpc();
+    assert.equal((await getAccount(provider.connection, holder)).isFrozen, false);
+
+    console.log('✓ Wrapped token account frozen and thawed');
+  });
+
+  it('Registers an L2 that waits for L1 batch finality', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+
+    await program.methods
+      .registerChain(ARBITRUM_CHAIN_ID, { evm: {} }, {
+        finality: { l1Batch: {} },
+        requiredConfirmations: 0,
+        baseFee: new anchor.BN(1000),
+        feeBps: 5,
+        minAmount: new anchor.BN(0),
+      })
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const chain = await program.account.chainConfig.fetch(
+      chainConfigPda(ARBITRUM_CHAIN_ID)
+    );
+    assert.ok('l1Batch' in chain.finality);
+    assert.equal(chain.feeBps, 5);
+    await openRoute(mint, ARBITRUM_CHAIN_ID);
+
+    console.log('✓ Arbitrum registered with L1 batch finality');
+  });
+
+  it('Sets a fee per token and destination chain', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+    const route = feeRoutePda(mint, ARBITRUM_CHAIN_ID);
+    const setRoute = (baseFee: number, feeBps: number) =>
+      program.methods
+        .setFeeRoute(mint, ARBITRUM_CHAIN_ID, new anchor.BN(baseFee), feeBps)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: route,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    try {
+      await setRoute(0, 10001);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidConfig'));
+    }
+
+    // Cheaper than the chain's default of 1000 + 5 bps
+    await setRoute(250, 2);
+    const fee = await program.account.feeRoute.fetch(route);
+    assert.ok(fee.mint.equals(mint));
+    assert.equal(fee.baseFee.toString(), '250');
+    assert.equal(fee.feeBps, 2);
+
+    // What an aggregator sees before routing a transfer here
+    const quote = () =>
+      program.methods
+        .quoteLock(mint, new anchor.BN(1000000), ARBITRUM_CHAIN_ID, user.publicKey)
+        .accounts({
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          tokenConfig: tokenConfigPda(mint),
+          routeSequence: routeSequencePda(mint, ARBITRUM_CHAIN_ID),
+          feeRoute: route,
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+        })
+        .view();
+    const routed = await quote();
+    assert.equal(routed.fee.toString(), '450');
+    assert.equal(routed.netAmount.toString(), '999550');
+    assert.equal(routed.feeTier, 0);
+
+    await program.methods
+      .removeFeeRoute(mint, ARBITRUM_CHAIN_ID)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        feeRoute: route,
+      })
+      .rpc();
+    assert.equal(await provider.connection.getAccountInfo(route), null);
+    assert.equal((await quote()).fee.toString(), '1500');
+
+    console.log('✓ Fee route set and removed');
+  });
+
+  it('Keeps an allowlist of message targets', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+    const target = Buffer.alloc(32);
+    Buffer.from('1234567890123456789012345678901234567890', 'hex').copy(target, 12);
+    const messageTarget = PublicKey.findProgramAddressSync(
+      [
+        Buffer.from('message_target'),
+        ARBITRUM_CHAIN_ID.toArrayLike(Buffer, 'le', 8),
+        target,
+      ],
+      program.programId
+    )[0];
+
+    // Only the owner manages it
+    try {
+      await program.methods
+        .allowMessageTarget(ARBITRUM_CHAIN_ID, [...target])
+        .accounts({
+          owner: user.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          messageTarget,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await program.methods
+      .allowMessageTarget(ARBITRUM_CHAIN_ID, [...target])
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+        messageTarget,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const allowed = await program.account.messageTarget.fetch(messageTarget);
+    assert.equal(allowed.chainId.toString(), '42161');
+    assert.deepEqual(Buffer.from(allowed.target), target);
+
+    const sendMessage = () =>
+      program.methods
+        .sendMessage(ARBITRUM_CHAIN_ID, [...target], Buffer.from('hello'))
+        .accounts({
+          sender: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          messageTarget,
+          outbox: outboxPda(),
+        })
+        .signers([user])
+        .rpc();
+
+    // An allowed target gets a Message entry in the outbox
+    await sendMessage();
+    const outbox = await program.account.outbox.fetch(outboxPda());
+    assert.equal(outbox.nextSequence.toString(), '1');
+    const entry = outbox.entries[0];
+    assert.equal(entry.kind, 4);
+    assert.equal(entry.destChainId.toString(), '42161');
+    assert.equal(entry.amount.toString(), '5');
+    assert.ok(entry.sender.equals(user.publicKey));
+    assert.deepEqual(entry.mint.toBuffer(), target);
+
+    await program.methods
+      .removeMessageTarget(ARBITRUM_CHAIN_ID, [...target])
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        messageTarget,
+      })
+      .rpc();
+    assert.equal(await provider.connection.getAccountInfo(messageTarget), null);
+
+    // A removed target no longer takes messages
+    try {
+      await sendMessage();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('MessageTargetNotAllowed'));
+    }
+
+    console.log('✓ Message target allowed, messaged and removed');
+  });
+
+  it('Locks tokens (same as your EVM bridge lock!)', async () => {
+    const amount = new anchor.BN(100000000); // 100 tokens
+    const ethRecipient = '0x1234567890123456789012345678901234567890';
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+
+    // Call lock (SAME CONCEPT as calling lock() in your EVM bridge!)
+    await program.methods
+      .lock(amount, ETHEREUM_CHAIN_ID, ethRecipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    // Verify bridge received tokens
+    const bridgeAccount = await getAccount(
+      provider.connection,
+      bridgeTokenAccount
+    );
+    assert.equal(
+      bridgeAccount.amount.toString(),
+      amount.toString()
+    );
+
+    // Verify nonce incremented (SAME AS: checking nonce in your EVM bridge,
+    // one counter per token and destination)
+    const route = await program.account.routeSequence.fetch(
+      routeSequencePda(mint, ETHEREUM_CHAIN_ID)
+    );
+    assert.equal(route.last.toString(), '1');
+    assert.equal(nonce.shrn(SEQUENCE_BITS).toNumber(), route.shard);
+
+    // The transfer record tracks the lifecycle from here on
+    const record = await program.account.transferRecord.fetch(transferPda('out', nonce));
+    assert.ok('initiated' in record.status);
+    assert.equal(record.remoteAddress, ethRecipient);
+    assert.ok(record.transferId.some((b: number) => b !== 0));
+
+    // Relayers can pick it up from the outbox without parsing logs
+    // (entry 0 is the message sent to the allowed target)
+    const outbox = await program.account.outbox.fetch(outboxPda());
+    assert.equal(outbox.nextSequence.toString(), '2');
+    assert.equal(outbox.entries[1].nonce.toString(), nonce.toString());
+    assert.deepEqual(outbox.entries[1].transferId, record.transferId);
+
+    const stats = await program.account.userStats.fetch(userStatsPda(user.publicKey));
+    assert.equal(stats.volume.toString(), amount.toString());
+
+    console.log('✓ Tokens locked successfully');
+  });
+
+  it('Locks tokens to a Stellar address', async () => {
+    const amount = new anchor.BN(10000000);
+    const stellarRecipient = 'GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7';
+
+    await program.methods
+      .lock(amount, STELLAR_CHAIN_ID, stellarRecipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, STELLAR_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', await outNonce(mint, STELLAR_CHAIN_ID, 1)),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    // Stellar has its own sequence; Ethereum's is untouched
+    const route = await program.account.routeSequence.fetch(
+      routeSequencePda(mint, STELLAR_CHAIN_ID)
+    );
+    assert.equal(route.last.toString(), '1');
+
+    // Corrupted checksum must be rejected
+    try {
+      await program.methods
+        .lock(amount, STELLAR_CHAIN_ID, stellarRecipient.slice(0, -1) + 'A', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, STELLAR_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', await outNonce(mint, STELLAR_CHAIN_ID, 2)),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+        })
+        .signers([user])
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidStellarAddress'));
+    }
+
+    console.log('✓ Stellar lock validated');
+  });
+
+  it('Mints wrapped tokens (same as your EVM bridge mint!)', async () => {
+    const amount = new anchor.BN(100000000);
+    const nonce = new anchor.BN(1);
+
+    // Create wrapped token account for user
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey
+    );
+
+    // Call mint (SAME CONCEPT as calling mint() in your EVM bridge!)
+    await program.methods
+      .mint(amount, nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: userWrappedAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('in', nonce),
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Verify tokens minted
+    const account = await getAccount(
+      provider.connection,
+      userWrappedAccount
+    );
+    assert.equal(account.amount.toString(), amount.toString());
+
+    // Verify nonce marked as processed (SAME AS: processedNonces check)
+    const state = await program.account.bridgeState.fetch(bridgeState);
+    assert.ok(state.processedNonces.some(n => n.eq(nonce)));
+
+    const record = await program.account.transferRecord.fetch(transferPda('in', nonce));
+    assert.ok('completed' in record.status);
+    assert.ok(record.processedBy.equals(provider.wallet.publicKey));
+    assert.ok(record.processedSlot.gtn(0));
+
+    // The relayer earned this epoch's reward share
+    const stats = await program.account.relayerEpochStats.fetch(
+      rewardAccounts(provider.wallet.publicKey).relayerStats
+    );
+    assert.equal(stats.transfers.toString(), '1');
+    assert.equal(stats.submitted.toString(), '1');
+    assert.equal(stats.failures.toString(), '0');
+    // A direct mint opens and completes its record in the same slot
+    assert.ok(record.createdSlot.eq(record.processedSlot));
+    assert.equal(stats.totalLatencySlots.toString(), '0');
+
+    console.log('✓ Wrapped tokens minted successfully');
+  });
+
+  it('Prevents duplicate mints (same as your EVM bridge!)', async () => {
+    const amount = new anchor.BN(100000000);
+    const nonce = new anchor.BN(1); // Same nonce as before
+
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey
+    );
+
+    try {
+      // Try to mint with same nonce
+      await program.methods
+        .mint(amount, nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      // Should fail with AlreadyProcessed error
+      assert.ok(error.toString().includes('AlreadyProcessed'));
+      console.log('✓ Duplicate mint prevented');
+    }
+  });
+
+  it("Rejects mints into a token account the user doesn't own", async () => {
+    const stranger = Keypair.generate();
+    const strangerAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      stranger.publicKey
+    );
+    const nonce = new anchor.BN(2);
+
+    try {
+      await program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: strangerAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ConstraintTokenOwner'));
+      console.log("✓ Mint into someone else's account rejected");
+    }
+  });
+
+  it('Reports transfer status through a view instruction', async () => {
+    const status = async (direction: 'out' | 'in', nonce: anchor.BN, transferId: number[]) =>
+      program.methods
+        .getTransferStatus(transferId)
+        .accounts({
+          bridgeState: bridgeState,
+          transferRecord: transferPda(direction, nonce),
+          attestation: null,
+        })
+        .view();
+
+    const firstLock = await outNonce(mint, ETHEREUM_CHAIN_ID, 1);
+    const outbound = await program.account.transferRecord.fetch(transferPda('out', firstLock));
+    const lock = await status('out', firstLock, outbound.transferId);
+    assert.ok('initiated' in lock.status);
+    assert.ok('outbound' in lock.direction);
+    assert.equal(lock.amount.toString(), outbound.amount.toString());
+
+    const inbound = await program.account.transferRecord.fetch(
+      transferPda('in', new anchor.BN(1))
+    );
+    const mint = await status('in', new anchor.BN(1), inbound.transferId);
+    assert.ok('completed' in mint.status);
+    assert.isNull(mint.attestationStatus);
+
+    // No record: no status, not an error
+    const missing = await status('in', new anchor.BN(999), Array(32).fill(0));
+    assert.isNull(missing.status);
+
+    try {
+      await status('out', firstLock, inbound.transferId);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TransferMismatch'));
+    }
+
+    console.log('✓ Transfer status read with one simulated instruction');
+  });
+
+  it('Ignores duplicate mints in idempotent delivery mode', async () => {
+    const setIdempotent = (enabled: boolean) =>
+      program.methods
+        .setIdempotentDelivery(enabled)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+        })
+        .rpc();
+
+    const nonce = new anchor.BN(1); // Already minted above
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    await setIdempotent(true);
+    try {
+      await program.methods
+        .mint(new anchor.BN(100000000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    } finally {
+      await setIdempotent(false);
+    }
+
+    // Succeeded, but nothing was minted
+    const account = await getAccount(provider.connection, userWrappedAccount);
+    assert.equal(account.amount.toString(), '0');
+
+    // The relayer's stats count it as a failed submission
+    const stats = await program.account.relayerEpochStats.fetch(
+      rewardAccounts(provider.wallet.publicKey).relayerStats
+    );
+    assert.equal(stats.failures.toString(), '1');
+    assert.equal(stats.submitted.sub(stats.transfers).toString(), '1');
+
+    console.log('✓ Duplicate mint ignored');
+  });
+
+  it('Requires a credential for restricted tokens', async () => {
+    const setRestrictions = (restricted: boolean) =>
+      program.methods
+        .setTokenRestrictions(restricted, Keypair.generate().publicKey, [new anchor.BN(1000000000)])
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(wrappedMint),
+        })
+        .rpc();
+
+    await setRestrictions(true);
+
+    const nonce = new anchor.BN(99);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey
+    );
+
+    try {
+      await program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('CredentialRequired'));
+    }
+
+    await setRestrictions(false);
+
+    console.log('✓ Restricted token requires a credential');
+  });
+
+  it('Burns wrapped tokens (same as your EVM bridge burn!)', async () => {
+    const amount = new anchor.BN(50000000); // 50 tokens
+    const ethRecipient = '0x1234567890123456789012345678901234567890';
+
+    // First mint some wrapped tokens
+    const nonce = new anchor.BN(2);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey
+    );
+
+    await program.methods
+      .mint(amount, nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: userWrappedAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('in', nonce),
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Now burn them (SAME AS: calling burn() in your EVM bridge!)
+    await program.methods
+      .burn(amount, ETHEREUM_CHAIN_ID, ethRecipient, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        transferRecord: transferPda('out', await nextOutNonce(wrappedMint, ETHEREUM_CHAIN_ID)),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        userToken: userWrappedAccount,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    // Verify tokens burned
+    const account = await getAccount(
+      provider.connection,
+      userWrappedAccount
+    );
+    assert.equal(account.amount.toString(), '0');
+
+    console.log('✓ Wrapped tokens burned successfully');
+  });
+
+  it('Requires a compliance co-signature on large burns of a restricted token', async () => {
+    const ethRecipient = '0x1234567890123456789012345678901234567890';
+    const compliance = Keypair.generate();
+    const burnApproval = PublicKey.findProgramAddressSync(
+      [Buffer.from('burn_approval'), wrappedMint.toBuffer()],
+      program.programId
+    )[0];
+    const setRestrictions = (restricted: boolean) =>
+      program.methods
+        .setTokenRestrictions(restricted, Keypair.generate().publicKey, [new anchor.BN(1000000000)])
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(wrappedMint),
+        })
+        .rpc();
+    const setApproval = (complianceKey: PublicKey, threshold: number) =>
+      program.methods
+        .setBurnApproval(complianceKey, new anchor.BN(threshold))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          mint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          burnApproval,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // Only restricted tokens take a policy
+    try {
+      await setApproval(compliance.publicKey, 1000);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidConfig'));
+    }
+    await setRestrictions(true);
+    await setApproval(compliance.publicKey, 1000);
+    await setRestrictions(false);
+
+    const nonce = new anchor.BN(3);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    await program.methods
+      .mint(new anchor.BN(3000), nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: userWrappedAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('in', nonce),
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const burn = async (amount: number, cosigner?: Keypair) => {
+      const nonce = await nextOutNonce(wrappedMint, ETHEREUM_CHAIN_ID);
+      return program.methods
+        .burn(new anchor.BN(amount), ETHEREUM_CHAIN_ID, ethRecipient, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          userToken: userWrappedAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          burnApproval,
+          complianceSigner: cosigner ? cosigner.publicKey : null,
+        })
+        .signers(cosigner ? [user, cosigner] : [user])
+        .rpc();
+    };
+
+    // Up to the threshold the holder alone can burn
+    await burn(1000);
+
+    try {
+      await burn(2000);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ComplianceSignatureRequired'));
+    }
+    try {
+      await burn(2000, Keypair.generate());
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ComplianceSignatureRequired'));
+    }
+    await burn(2000, compliance);
+
+    const account = await getAccount(provider.connection, userWrappedAccount);
+    assert.equal(account.amount.toString(), '0');
+
+    // A default key turns the requirement off
+    await setRestrictions(true);
+    await setApproval(PublicKey.default, 0);
+    await setRestrictions(false);
+
+    console.log('✓ Large restricted burns need the compliance key');
+  });
+
+  it('Can pause and unpause bridge', async () => {
+    // Pause
+    await program.methods
+      .pause()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    let state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, true);
+
+    // The EVM side hears about it through the outbox (kind 2 = BridgeStatus)
+    const latestStatus = async () => {
+      const outbox = await program.account.outbox.fetch(outboxPda());
+      const sequence = outbox.nextSequence.subn(1);
+      return outbox.entries[sequence.modn(outbox.entries.length)];
+    };
+    let status = await latestStatus();
+    assert.equal(status.kind, 2);
+    assert.equal(status.amount.toString(), '1');
+    const pausedAt = status.sequence;
+
+    // Unpause
+    await program.methods
+      .unpause()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, false);
+
+    status = await latestStatus();
+    assert.equal(status.kind, 2);
+    assert.equal(status.amount.toString(), '0');
+    assert.equal(status.sequence.toString(), pausedAt.addn(1).toString());
+
+    // Both actions are in the admin log
+    const log = await program.account.adminLog.fetch(adminLog);
+    const [paused, unpaused] = log.entries.slice(-2);
+    assert.ok('pause' in paused.action);
+    assert.ok('unpause' in unpaused.action);
+    assert.ok(unpaused.actor.equals(provider.wallet.publicKey));
+
+    console.log('✓ Pause/unpause working correctly');
+  });
+
+  it('Lets anyone pause with a bond, refunded only for real incidents', async () => {
+    const pda = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed)], program.programId)[0];
+    const guardian = Keypair.generate();
+    const bond = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10);
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: pda('guardian_set'),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .setPauseBondConfig(bond, new anchor.BN(0), new anchor.BN(0))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        pauseBondConfig: pda('pause_bond_config'),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await program.methods
+      .bondedPause()
+      .accounts({
+        pauser: user.publicKey,
+        bridgeState: bridgeState,
+        pauseBondConfig: pda('pause_bond_config'),
+        bondedPause: pda('bonded_pause'),
+        bondVault: pda('pause_bond_vault'),
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    let state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, true);
+
+    // Guardians find nothing wrong: bond goes to the insurance fund
+    await program.methods
+      .resolveBondedPause(false)
+      .accounts({
+        guardianSet: pda('guardian_set'),
+        bridgeState: bridgeState,
+        pauseBondConfig: pda('pause_bond_config'),
+        bondedPause: pda('bonded_pause'),
+        pauser: user.publicKey,
+        bondVault: pda('pause_bond_vault'),
+        insuranceFund: pda('insurance_fund'),
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, false);
+    assert.equal(
+      await provider.connection.getBalance(pda('insurance_fund')),
+      bond.toNumber()
+    );
+
+    console.log('✓ Frivolous bonded pause forfeited');
+  });
+
+  it('Lets unconfirmed bonded pauses lapse and tracks guardian heartbeats', async () => {
+    const pda = (seed: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(seed)], program.programId)[0];
+    const guardian = Keypair.generate();
+    const bond = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10);
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: pda('guardian_set'),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const configure = (expirySlots: number) =>
+      program.methods
+        .setPauseBondConfig(bond, new anchor.BN(0), new anchor.BN(expirySlots))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          pauseBondConfig: pda('pause_bond_config'),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const pause = () =>
+      program.methods
+        .bondedPause()
+        .accounts({
+          pauser: user.publicKey,
+          bridgeState: bridgeState,
+          pauseBondConfig: pda('pause_bond_config'),
+          bondedPause: pda('bonded_pause'),
+          bondVault: pda('pause_bond_vault'),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    const expire = () =>
+      program.methods
+        .expireBondedPause()
+        .accounts({
+          bridgeState: bridgeState,
+          bondedPause: pda('bonded_pause'),
+          pauser: user.publicKey,
+          bondVault: pda('pause_bond_vault'),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // A long expiry: too early to lift, and a guardian pushes it out further
+    await configure(1_000_000);
+    await pause();
+    const raised = await program.account.bondedPauseState.fetch(pda('bonded_pause'));
+
+    try {
+      await expire();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('PauseNotExpired'));
+    }
+
+    await program.methods
+      .confirmBondedPause()
+      .accounts({
+        bridgeState: bridgeState,
+        guardianSet: pda('guardian_set'),
+        pauseBondConfig: pda('pause_bond_config'),
+        bondedPause: pda('bonded_pause'),
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+    const confirmed = await program.account.bondedPauseState.fetch(pda('bonded_pause'));
+    assert.ok(confirmed.expiresAtSlot.gt(raised.expiresAtSlot));
+
+    await program.methods
+      .resolveBondedPause(false)
+      .accounts({
+        guardianSet: pda('guardian_set'),
+        bridgeState: bridgeState,
+        pauseBondConfig: pda('pause_bond_config'),
+        bondedPause: pda('bonded_pause'),
+        pauser: user.publicKey,
+        bondVault: pda('pause_bond_vault'),
+        insuranceFund: pda('insurance_fund'),
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+
+    // A one-slot expiry nobody confirms: anyone lifts it and the bond is returned
+    await configure(1);
+    await pause();
+    const { expiresAtSlot } = await program.account.bondedPauseState.fetch(pda('bonded_pause'));
+    while ((await provider.connection.getSlot()) < expiresAtSlot.toNumber()) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+    const before = await provider.connection.getBalance(user.publicKey);
+    await expire();
+
+    const state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, false);
+    assert.ok((await provider.connection.getBalance(user.publicKey)) >= before + bond.toNumber());
+    assert.equal(await provider.connection.getAccountInfo(pda('bonded_pause')), null);
+
+    // The owner's own pause outlives a bonded pause that lapses under it
+    const ownerPause = (paused: boolean) =>
+      (paused ? program.methods.pause() : program.methods.unpause())
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          outbox: outboxPda(),
+        })
+        .rpc();
+    await pause();
+    await ownerPause(true);
+    const lapsing = await program.account.bondedPauseState.fetch(pda('bonded_pause'));
+    while ((await provider.connection.getSlot()) < lapsing.expiresAtSlot.toNumber()) {
+      await new Promise((resolve) => setTimeout(resolve, 200));
+    }
+    await expire();
+    const held = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(held.paused, true);
+    assert.equal(held.ownerPaused, true);
+    await ownerPause(false);
+    assert.equal((await program.account.bridgeState.fetch(bridgeState)).paused, false);
+
+    // Heartbeats: only guardians can post one
+    await program.methods
+      .setHeartbeatInterval(new anchor.BN(1))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    const heartbeat = (signer: Keypair) =>
+      program.methods
+        .guardianHeartbeat()
+        .accounts({
+          guardian: signer.publicKey,
+          guardianSet: pda('guardian_set'),
+          bridgeState: bridgeState,
+        })
+        .signers([signer])
+        .rpc();
+
+    try {
+      await heartbeat(user);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await heartbeat(guardian);
+
+    const { epoch } = await provider.connection.getEpochInfo();
+    const live = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(live.heartbeatEpochs.toNumber(), 1);
+    assert.equal(live.lastHeartbeatEpoch.toNumber(), epoch);
+
+    // Back off, so later tests don't depend on heartbeats
+    await program.methods
+      .setHeartbeatInterval(new anchor.BN(0))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    console.log('✓ Bonded pause expired and guardian heartbeat posted');
+  });
+
+  it('Lists tokens permissionlessly behind a bond and a guardian veto window', async () => {
+    const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+    const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const guardian = Keypair.generate();
+    const bond = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 10);
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: pda(Buffer.from('guardian_set')),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Two listings an hour, each vetoable for 2 seconds
+    await program.methods
+      .setListingConfig(bond, new anchor.BN(2), 2, new anchor.BN(3600))
+      .accounts({ owner: provider.wallet.publicKey, bridgeState })
+      .rpc();
+
+    const list = (sourceToken: Buffer, symbol: string) =>
+      program.methods
+        .listToken([...sourceToken], 18, 8, symbol, symbol, '')
+        .accounts({ lister: user.publicKey, bridgeState })
+        .signers([user])
+        .rpc();
+    const [kept, vetoed, third] = ['11', '22', '33'].map((b) => Buffer.alloc(20, b, 'hex'));
+    await list(kept, 'KEEP');
+    await list(vetoed, 'VETO');
+
+    try {
+      await list(third, 'LATE');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ListingRateLimited'));
+    }
+
+    const wrappedMint = pda(Buffer.from('wrapped_mint'), kept);
+    const activate = () =>
+      program.methods
+        .activateListing()
+        .accounts({
+          bridgeState: bridgeState,
+          payer: provider.wallet.publicKey,
+          listing: pda(Buffer.from('token_listing'), kept),
+          lister: user.publicKey,
+          wrappedMint,
+          metadata: PublicKey.findProgramAddressSync(
+            [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), wrappedMint.toBuffer()],
+            TOKEN_METADATA_PROGRAM_ID
+          )[0],
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+
+    try {
+      await activate();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ListingNotReady'));
+    }
+
+    // Vetoed: the bond goes to the insurance fund
+    const fundBefore = await provider.connection.getBalance(pda(Buffer.from('insurance_fund')));
+    await program.methods
+      .vetoListing()
+      .accounts({
+        bridgeState: bridgeState,
+        listing: pda(Buffer.from('token_listing'), vetoed),
+        lister: user.publicKey,
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+    assert.equal(
+      await provider.connection.getBalance(pda(Buffer.from('insurance_fund'))),
+      fundBefore + bond.toNumber()
+    );
+    assert.isNull(
+      await provider.connection.getAccountInfo(pda(Buffer.from('token_listing'), vetoed))
+    );
+
+    // Past the window: anyone activates it and the lister gets the bond back
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+    const listerBefore = await provider.connection.getBalance(user.publicKey);
+    await activate();
+
+    const asset = await program.account.wrappedAsset.fetch(
+      pda(Buffer.from('wrapped_asset'), wrappedMint.toBuffer())
+    );
+    assert.deepEqual(asset.sourceToken, [...kept]);
+    assert.equal(asset.decimals, 8);
+    assert.isAbove(
+      await provider.connection.getBalance(user.publicKey),
+      listerBefore + bond.toNumber() - 1
+    );
+
+    // Activation registered the token: it bridges with no owner step
+    const config = await program.account.tokenConfig.fetch(tokenConfigPda(wrappedMint));
+    assert.ok(config.mint.equals(wrappedMint));
+
+    const amount = new anchor.BN(100000000);
+    const nonce = new anchor.BN(700);
+    const userListed = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey
+    );
+    await program.methods
+      .mint(amount, nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: userListed,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('in', nonce),
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    await openRoute(wrappedMint, ETHEREUM_CHAIN_ID);
+    const vault = () =>
+      createAccount(provider.connection, user, wrappedMint, bridgeAuthority, Keypair.generate());
+    const escrow = await vault();
+    const fees = await vault();
+    const lockNonce = await nextOutNonce(wrappedMint, ETHEREUM_CHAIN_ID);
+    await program.methods
+      .lock(amount, ETHEREUM_CHAIN_ID, '0x1234567890123456789012345678901234567890',
+        null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userListed,
+        mint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        feeRoute: feeRoutePda(wrappedMint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: escrow,
+        feeVault: fees,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', lockNonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const escrowed = await getAccount(provider.connection, escrow);
+    assert.equal(escrowed.amount.toString(), amount.toString());
+    const record = await program.account.transferRecord.fetch(transferPda('out', lockNonce));
+    assert.ok('initiated' in record.status);
+
+    console.log('✓ Listing vetoed, another activated after its window and bridged');
+  });
+
+  it('Records operator heartbeats in the liveness registry', async () => {
+    const operator = provider.wallet.publicKey;
+    const [liveness] = PublicKey.findProgramAddressSync(
+      [Buffer.from('liveness'), operator.toBuffer()],
+      program.programId
+    );
+    const heartbeat = (signer: PublicKey, signers: Keypair[]) =>
+      program.methods
+        .heartbeat()
+        .accounts({
+          operator: signer,
+          guardianSet: PublicKey.findProgramAddressSync(
+            [Buffer.from('guardian_set')],
+            program.programId
+          )[0],
+          bridgeState: bridgeState,
+        })
+        .signers(signers)
+        .rpc();
+
+    try {
+      await heartbeat(user.publicKey, [user]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await heartbeat(operator, []);
+    await heartbeat(operator, []);
+
+    const entry = await program.account.operatorLiveness.fetch(liveness);
+    assert.ok(entry.operator.equals(operator));
+    assert.equal(entry.beats.toNumber(), 2);
+    assert.ok(entry.lastSeen.toNumber() >= entry.firstSeen.toNumber());
+
+    console.log('✓ Owner heartbeat recorded in the liveness registry');
+  });
+
+  it('Scores a relay route\'s health from the relayer\'s report', async () => {
+    const owner = provider.wallet.publicKey;
+    const routeSeed = Buffer.alloc(4);
+    routeSeed.writeUInt32LE(7);
+    const [routeHealth] = PublicKey.findProgramAddressSync(
+      [Buffer.from('route_health'), routeSeed],
+      program.programId
+    );
+    const report = (signer: PublicKey, signers: Keypair[], reconcileOk: boolean) =>
+      program.methods
+        .reportRouteHealth(7, { queueDepth: 136, ackLatencySecs: 60, reconciled: 0, reconcileOk })
+        .accounts({
+          reporter: signer,
+          guardianSet: PublicKey.findProgramAddressSync(
+            [Buffer.from('guardian_set')],
+            program.programId
+          )[0],
+          bridgeState: bridgeState,
+          relayerLiveness: PublicKey.findProgramAddressSync(
+            [Buffer.from('liveness'), owner.toBuffer()],
+            program.programId
+          )[0],
+          routeHealth,
+        })
+        .signers(signers)
+        .rpc();
+
+    try {
+      await report(user.publicKey, [user], true);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    // The owner just beat: only the half-full queue costs points
+    await report(owner, [], true);
+    let health = await program.account.routeHealth.fetch(routeHealth);
+    assert.equal(health.routeId, 7);
+    assert.equal(health.score, 88);
+    assert.equal(health.report.queueDepth, 136);
+    assert.ok(health.reporter.equals(owner));
+
+    // A failed reconciliation pass costs its whole weight
+    await report(owner, [], false);
+    health = await program.account.routeHealth.fetch(routeHealth);
+    assert.equal(health.score, 73);
+
+    console.log('✓ Route health scored and kept for the SDK');
+  });
+
+  it('Registers a relayer with its terms for discovery', async () => {
+    const relayer = user;
+    const [registration] = PublicKey.findProgramAddressSync(
+      [Buffer.from('relayer'), relayer.publicKey.toBuffer()],
+      program.programId
+    );
+    const register = (url: string, routes: number[], feeBps: number) =>
+      program.methods
+        .registerRelayer(url, routes, { baseFee: new anchor.BN(1000), feeBps })
+        .accounts({
+          bridgeState: bridgeState,
+          relayer: relayer.publicKey,
+          registration,
+          ejection: PublicKey.findProgramAddressSync(
+            [Buffer.from('ejected'), relayer.publicKey.toBuffer()],
+            program.programId
+          )[0],
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([relayer])
+        .rpc();
+
+    try {
+      await register('ftp://relayer.example.com', [], 5);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidRelayerRegistration'));
+    }
+
+    await register('https://relayer.example.com', [1, 2], 5);
+    await register('https://relayer.example.com/v2', [2], 7);
+
+    const entry = await program.account.relayerRegistration.fetch(registration);
+    assert.equal(entry.endpointUrl, 'https://relayer.example.com/v2');
+    assert.deepEqual(entry.supportedRoutes, [2]);
+    assert.equal(entry.feeSchedule.feeBps, 7);
+    assert.ok(entry.registeredAt.toNumber() <= entry.updatedAt.toNumber());
+
+    await program.methods
+      .deregisterRelayer()
+      .accounts({ relayer: relayer.publicKey, bridgeState, registration })
+      .signers([relayer])
+      .rpc();
+    assert.equal(await provider.connection.getAccountInfo(registration), null);
+
+    console.log('✓ Relayer registered, updated and deregistered');
+  });
+
+  it('Bridges a Token-2022 mint with a transfer fee', async () => {
+    const connection = provider.connection;
+    const feeMint = Keypair.generate();
+    const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+
+    // 1% transfer fee, capped at 1 token
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: feeMint.publicKey,
+          space: mintLen,
+          lamports: await connection.getMinimumBalanceForRentExemption(mintLen),
+          programId: TOKEN_2022_PROGRAM_ID,
+        }),
+        createInitializeTransferFeeConfigInstruction(
+          feeMint.publicKey,
+          provider.wallet.publicKey,
+          provider.wallet.publicKey,
+          100,
+          BigInt(1000000),
+          TOKEN_2022_PROGRAM_ID
+        ),
+        createInitializeMintInstruction(
+          feeMint.publicKey,
+          6,
+          user.publicKey,
+          null,
+          TOKEN_2022_PROGRAM_ID
+        )
+      ),
+      [feeMint]
+    );
+
+    const tokenAccount = (owner: PublicKey) =>
+      createAccount(
+        connection,
+        user,
+        feeMint.publicKey,
+        owner,
+        Keypair.generate(),
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+    const userToken = await tokenAccount(user.publicKey);
+    const escrow = await tokenAccount(bridgeAuthority);
+    const feeVault = await tokenAccount(bridgeAuthority);
+    await mintTo(
+      connection,
+      user,
+      feeMint.publicKey,
+      userToken,
+      user,
+      10000000,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: feeMint.publicKey,
+        tokenConfig: tokenConfigPda(feeMint.publicKey),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await openRoute(feeMint.publicKey, ETHEREUM_CHAIN_ID);
+    const lockNonce = await outNonce(feeMint.publicKey, ETHEREUM_CHAIN_ID, 1);
+
+    const lock = () =>
+      program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x1234567890123456789012345678901234567890', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userToken,
+          mint: feeMint.publicKey,
+          tokenConfig: tokenConfigPda(feeMint.publicKey),
+          feeRoute: feeRoutePda(feeMint.publicKey, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: escrow,
+          feeVault: feeVault,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', lockNonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+        })
+        .signers([user])
+        .rpc();
+
+    // Transfer fees aren't allowlisted by default
+    try {
+      await lock();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ExtensionNotAllowed'));
+    }
+
+    await program.methods
+      .setAllowedExtensions(1) // token_extensions::TRANSFER_FEE
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        tokenConfig: tokenConfigPda(feeMint.publicKey),
+      })
+      .rpc();
+
+    await lock();
+
+    // Only what reached the escrow is bridged
+    const record = await program.account.transferRecord.fetch(transferPda('out', lockNonce));
+    assert.equal(record.amount.toString(), '990000');
+    const escrowAccount = await getAccount(connection, escrow, undefined, TOKEN_2022_PROGRAM_ID);
+    assert.equal(escrowAccount.amount.toString(), '990000');
+
+    // Release it again; the recipient pays the fee on the way out
+    const nonce = new anchor.BN(100);
+    await program.methods
+      .unlock(new anchor.BN(990000), nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: transferPda('in', nonce),
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        mint: feeMint.publicKey,
+        tokenConfig: tokenConfigPda(feeMint.publicKey),
+        credential: null,
+        vaa: null,
+        conversionRate: null,
+        bridgeToken: escrow,
+        vaultConfig: vaultConfigPda(feeMint.publicKey),
+        userToken: userToken,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_2022_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const unlocked = await program.account.transferRecord.fetch(transferPda('in', nonce));
+    assert.ok('completed' in unlocked.status);
+    assert.equal(unlocked.amount.toString(), '980100');
+
+    console.log('✓ Token-2022 transfer fee accounted for');
+  });
+
+  it('Bridges rebasing tokens in shares', async () => {
+    const conversionRate = PublicKey.findProgramAddressSync(
+      [Buffer.from('conversion_rate'), mint.toBuffer()],
+      program.programId
+    )[0];
+    const setAccounting = (accounting: object) =>
+      program.methods
+        .setTokenAccounting(accounting, provider.wallet.publicKey, new anchor.BN(3600))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(mint),
+          conversionRate: conversionRate,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    await setAccounting({ shares: {} });
+
+    // Each share is now worth 2 tokens
+    await program.methods
+      .updateConversionRate(new anchor.BN(2000000000))
+      .accounts({
+        bridgeState: bridgeState,
+        oracle: provider.wallet.publicKey,
+        conversionRate: conversionRate,
+      })
+      .rpc();
+
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x1234567890123456789012345678901234567890', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: conversionRate,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const record = await program.account.transferRecord.fetch(transferPda('out', nonce));
+    assert.equal(record.amount.toString(), '500000');
+
+    await setAccounting({ raw: {} });
+
+    console.log('✓ Rebasing token bridged in shares');
+  });
+
+  it('Delivers inbound nonces through the inbox window', async () => {
+    await program.methods
+      .configureInbox(ETHEREUM_CHAIN_ID, new anchor.BN(200), 2)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const mintNonce = (nonce: anchor.BN) =>
+      program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // Within the window: fine, even ahead of 200
+    await mintNonce(new anchor.BN(201));
+
+    // Too far ahead of the oldest undelivered nonce
+    try {
+      await mintNonce(new anchor.BN(203));
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('OutOfOrderDelivery'));
+    }
+
+    // Filling the gap slides the window past both
+    await mintNonce(new anchor.BN(200));
+    const inbox = await program.account.inbox.fetch(inboxPda(ETHEREUM_CHAIN_ID));
+    assert.equal(inbox.nextSequence.toString(), '202');
+
+    console.log('✓ Inbox enforces the delivery window');
+  });
+
+  it('Locks to many Ethereum recipients in one transaction', async () => {
+    const recipients = [
+      '0x1111111111111111111111111111111111111111',
+      '0x2222222222222222222222222222222222222222',
+    ];
+    const nonces = [
+      await nextOutNonce(mint, ETHEREUM_CHAIN_ID, 1),
+      await nextOutNonce(mint, ETHEREUM_CHAIN_ID, 2),
+    ];
+
+    await program.methods
+      .lockBatch(recipients.map((recipient) => ({ amount: new anchor.BN(1000000), recipient })))
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        nonces.map((nonce) => ({
+          pubkey: transferPda('out', nonce),
+          isSigner: false,
+          isWritable: true,
+        }))
+      )
+      .signers([user])
+      .rpc();
+
+    // Consecutive nonces, one record per recipient
+    for (const [i, nonce] of nonces.entries()) {
+      const record = await program.account.transferRecord.fetch(transferPda('out', nonce));
+      assert.equal(record.remoteAddress, recipients[i]);
+      assert.ok('initiated' in record.status);
+    }
+
+    const route = await program.account.routeSequence.fetch(
+      routeSequencePda(mint, ETHEREUM_CHAIN_ID)
+    );
+    assert.equal(route.last.toString(), '4');
+
+    console.log('✓ Batched lock created one transfer per recipient');
+  });
+
+  it('Lets guardians cancel a pending outbound transfer', async () => {
+    const guardian = Keypair.generate();
+    const guardianSet = PublicKey.findProgramAddressSync(
+      [Buffer.from('guardian_set')],
+      program.programId
+    )[0];
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // The second batched lock (the route's 4th) hasn't been relayed yet
+    const recordPda = transferPda('out', await outNonce(mint, ETHEREUM_CHAIN_ID, 4));
+    const record = await program.account.transferRecord.fetch(recordPda);
+    const before = await getAccount(provider.connection, userTokenAccount);
+
+    await program.methods
+      .cancelPending(record.transferId, 'Recipient on a sanctions list')
+      .accounts({
+        bridgeState: bridgeState,
+        guardianSet: guardianSet,
+        transferRecord: recordPda,
+        attestation: null,
+        bondVault: null,
+        relayer: null,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        conversionRate: null,
+        refundToken: userTokenAccount,
+        bridgeToken: bridgeTokenAccount,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+
+    const cancelled = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('cancelled' in cancelled.status);
+
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), record.amount.toString());
+
+    console.log('✓ Pending transfer cancelled and refunded');
+  });
+
+  it('Pins the recipient name a transfer was addressed by', async () => {
+    const pin = (signer: Keypair, record: PublicKey, name: string) =>
+      program.methods
+        .pinRecipientName(name)
+        .accounts({
+          signer: signer.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: record,
+          snsDomain: null,
+        })
+        .signers([signer])
+        .rpc();
+
+    // Only the sender vouches for the ENS name of an outbound transfer
+    const outbound = transferPda('out', await outNonce(mint, ETHEREUM_CHAIN_ID, 3));
+    try {
+      await pin(Keypair.generate(), outbound, 'alice.eth');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await pin(user, outbound, 'alice.eth');
+
+    const record = await program.account.transferRecord.fetch(outbound);
+    assert.equal(record.recipientName, 'alice.eth');
+
+    try {
+      await pin(user, outbound, 'bob.eth');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NameAlreadyPinned'));
+    }
+
+    // An inbound .sol name must come with its SNS name account
+    try {
+      await pin(user, transferPda('in', new anchor.BN(1)), 'alice.sol');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidRecipientName'));
+    }
+
+    console.log('✓ Recipient name pinned to the transfer record');
+  });
+
+  it('Looks inbound transfers up by their Ethereum transaction hash', async () => {
+    const txHash = Buffer.alloc(32, 0xab);
+    const [sourceTx] = PublicKey.findProgramAddressSync(
+      [Buffer.from('source_tx'), txHash],
+      program.programId
+    );
+    const record = (authority: PublicKey) =>
+      program.methods
+        .recordSourceTx([...txHash])
+        .accounts({
+          authority,
+          bridgeState,
+          transferRecord: transferPda('in', new anchor.BN(1)),
+          sourceTx,
+        });
+
+    // Only the relayer that delivered it (or the owner) vouches for the source tx
+    try {
+      await record(user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await record(provider.wallet.publicKey).rpc();
+    // Recording it again is a no-op
+    await record(provider.wallet.publicKey).rpc();
+
+    const lookup = await program.account.sourceTx.fetch(sourceTx);
+    assert.deepEqual([...lookup.txHash], [...txHash]);
+    assert.equal(lookup.chainId.toString(), ETHEREUM_CHAIN_ID.toString());
+    assert.deepEqual(lookup.nonces.map(n => n.toString()), ['1']);
+
+    console.log('✓ Inbound transfer recorded under its source tx hash');
+  });
+
+  it('Ejects a misbehaving relayer from the guardian set', async () => {
+    const [kept, ejected] = [Keypair.generate(), Keypair.generate()];
+    const guardianSet = guardianSetPda();
+    const ejectionPda = (relayer: PublicKey) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('ejected'), relayer.toBuffer()],
+        program.programId
+      )[0];
+
+    await program.methods
+      .setGuardians([kept.publicKey, ejected.publicKey], 2)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const eject = (relayer: PublicKey) =>
+      program.methods
+        .ejectRelayer(relayer, 'Signed a mint for an unfinalized lock')
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          guardianSet: guardianSet,
+          ejection: ejectionPda(relayer),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    await eject(ejected.publicKey);
+
+    const set = await program.account.guardianSet.fetch(guardianSet);
+    assert.deepEqual(set.guardians.map((g) => g.toBase58()), [kept.publicKey.toBase58()]);
+    assert.equal(set.threshold, 1);
+
+    const ejection = await program.account.ejectedRelayer.fetch(ejectionPda(ejected.publicKey));
+    assert.ok(ejection.relayer.equals(ejected.publicKey));
+    assert.equal(ejection.fraudNonce, null);
+
+    // The last guardian stays; replace the set instead
+    try {
+      await eject(kept.publicKey);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidGuardianSet'));
+    }
+
+    await program.methods
+      .reinstateRelayer(ejected.publicKey)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        ejection: ejectionPda(ejected.publicKey),
+      })
+      .rpc();
+    assert.equal(await provider.connection.getAccountInfo(ejectionPda(ejected.publicKey)), null);
+
+    console.log('✓ Relayer ejected and reinstated');
+  });
+
+  it('Lets the sender reclaim a lock the relayer missed the deadline on', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+
+    await program.methods
+      .lock(
+        new anchor.BN(1000000),
+        ETHEREUM_CHAIN_ID,
+        '0x3333333333333333333333333333333333333333',
+        new anchor.BN(now + 2),
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.equal(record.deadline.toNumber(), now + 2);
+    const before = await getAccount(provider.connection, userTokenAccount);
+
+    const reclaim = () =>
+      program.methods
+        .reclaimExpired(nonce)
+        .accounts({
+          sender: user.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          conversionRate: null,
+          refundToken: userTokenAccount,
+          bridgeToken: bridgeTokenAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+        })
+        .signers([user])
+        .rpc();
+
+    try {
+      await reclaim();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NotExpired'));
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    // Too late for the relayer to take it on
+    try {
+      await program.methods
+        .attestOutbound(nonce)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          guardianSet: PublicKey.findProgramAddressSync(
+            [Buffer.from('guardian_set')],
+            program.programId
+          )[0],
+          bridgeAuthority: bridgeAuthority,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+        })
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('DeadlinePassed'));
+    }
+
+    await reclaim();
+
+    const refunded = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('refunded' in refunded.status);
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), record.amount.toString());
+
+    console.log('✓ Expired lock reclaimed by the sender');
+  });
+
+  it('Refunds a reclaimed lock to its recovery address', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const custodian = Keypair.generate();
+    const recoveryToken = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      custodian.publicKey,
+      Keypair.generate()
+    );
+
+    await program.methods
+      .lock(
+        new anchor.BN(1000000),
+        ETHEREUM_CHAIN_ID,
+        '0x3434343434343434343434343434343434343434',
+        new anchor.BN(now + 2),
+        null,
+        custodian.publicKey,
+        null,
+        null
+      )
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok(record.recovery.equals(custodian.publicKey));
+
+    const reclaim = (refundToken: PublicKey) =>
+      program.methods
+        .reclaimExpired(nonce)
+        .accounts({
+          sender: user.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          conversionRate: null,
+          refundToken,
+          bridgeToken: bridgeTokenAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+        })
+        .signers([user])
+        .rpc();
+
+    await new Promise((resolve) => setTimeout(resolve, 4000));
+
+    // Not back to the sender's own account
+    try {
+      await reclaim(userTokenAccount);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TransferMismatch'));
+    }
+
+    await reclaim(recoveryToken);
+    const refunded = await getAccount(provider.connection, recoveryToken);
+    assert.equal(refunded.amount.toString(), record.amount.toString());
+
+    console.log('✓ Expired lock refunded to its recovery address');
+  });
+
+  it('Sells the refund right of a stuck lock', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const buyer = Keypair.generate();
+    const buyerToken = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      buyer.publicKey,
+      Keypair.generate()
+    );
+    await mintTo(provider.connection, user, mint, buyerToken, user.publicKey, 1000000);
+
+    await program.methods
+      .lock(
+        new anchor.BN(1000000),
+        ETHEREUM_CHAIN_ID,
+        '0x3535353535353535353535353535353535353535',
+        new anchor.BN(now + 3),
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+    const record = await program.account.transferRecord.fetch(recordPda);
+    const price = new anchor.BN(900000);
+
+    // "refund_assign" || transfer_id || assignee || price || expires_at
+    const assignment = Ed25519Program.createInstructionWithPrivateKey({
+      privateKey: user.secretKey,
+      message: Buffer.concat([
+        Buffer.from('refund_assign'),
+        Buffer.from(record.transferId),
+        buyer.publicKey.toBuffer(),
+        price.toArrayLike(Buffer, 'le', 8),
+        new anchor.BN(now + 600).toArrayLike(Buffer, 'le', 8),
+      ]),
+    });
+    const assign = () =>
+      program.methods
+        .assignRefund(nonce)
+        .accounts({
+          assignee: buyer.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          assigneeToken: buyerToken,
+          holderToken: userTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions([assignment])
+        .signers([buyer])
+        .rpc();
+
+    const before = await getAccount(provider.connection, userTokenAccount);
+    await assign();
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), price.toString());
+    assert.ok(
+      (await program.account.transferRecord.fetch(recordPda)).recovery.equals(buyer.publicKey)
+    );
+
+    // The sender no longer holds the right, so their signature is spent
+    try {
+      await assign();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await new Promise((resolve) => setTimeout(resolve, 5000));
+    await program.methods
+      .reclaimExpired(nonce)
+      .accounts({
+        sender: buyer.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: recordPda,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        conversionRate: null,
+        refundToken: buyerToken,
+        bridgeToken: bridgeTokenAccount,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+      })
+      .signers([buyer])
+      .rpc();
+    const refunded = await getAccount(provider.connection, buyerToken);
+    assert.equal(refunded.amount.toString(), new anchor.BN(100000).add(record.amount).toString());
+
+    console.log('✓ Refund right sold and reclaimed by the buyer');
+  });
+
+  it('Issues a deposit receipt for a lock and burns it on attestation', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const receiptMint = PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    const receiptToken = getAssociatedTokenAddressSync(
+      receiptMint,
+      user.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x4444444444444444444444444444444444444444', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint,
+        receiptToken,
+        token2022Program: TOKEN_2022_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+      })
+      .signers([user])
+      .rpc();
+
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.isTrue(record.receipt);
+
+    // One non-transferable token, with no way to mint more
+    const receipt = await getMint(provider.connection, receiptMint, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    assert.equal(receipt.supply.toString(), '1');
+    assert.isNull(receipt.mintAuthority);
+    assert.include(getExtensionTypes(receipt.tlvData), ExtensionType.NonTransferable);
+    assert.include(getExtensionTypes(receipt.tlvData), ExtensionType.PermanentDelegate);
+    const held = await getAccount(provider.connection, receiptToken, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    assert.equal(held.amount.toString(), '1');
+
+    const attest = (withReceipt: boolean) =>
+      program.methods
+        .attestOutbound(nonce)
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          guardianSet: PublicKey.findProgramAddressSync(
+            [Buffer.from('guardian_set')],
+            program.programId
+          )[0],
+          bridgeAuthority: bridgeAuthority,
+          receiptMint: withReceipt ? receiptMint : null,
+          receiptToken: withReceipt ? receiptToken : null,
+          token2022Program: withReceipt ? TOKEN_2022_PROGRAM_ID : null,
+        })
+        .rpc();
+
+    try {
+      await attest(false);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ReceiptRequired'));
+    }
+
+    await attest(true);
+
+    const attested = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('attested' in attested.status);
+    const burned = await getAccount(provider.connection, receiptToken, 'confirmed', TOKEN_2022_PROGRAM_ID);
+    assert.equal(burned.amount.toString(), '0');
+
+    console.log('✓ Deposit receipt issued and burned');
+  });
+
+  it('Issues a lock\'s receipt afterwards', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const receiptMint = PublicKey.findProgramAddressSync(
+      [Buffer.from('receipt'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    const receiptToken = getAssociatedTokenAddressSync(
+      receiptMint,
+      user.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    // As a lock short of compute for its receipt goes through
+    await program.methods
+      .lock(
+        new anchor.BN(1000000),
+        ETHEREUM_CHAIN_ID,
+        '0x4545454545454545454545454545454545454545',
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+    assert.isFalse((await program.account.transferRecord.fetch(recordPda)).receipt);
+
+    const issue = (sender: Keypair) =>
+      program.methods
+        .issueReceipt(nonce)
+        .accounts({
+          sender: sender.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          receiptMint,
+          receiptToken,
+          bridgeAuthority: bridgeAuthority,
+          token2022Program: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([sender])
+        .rpc();
+
+    try {
+      await issue(Keypair.generate());
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await issue(user);
+    assert.isTrue((await program.account.transferRecord.fetch(recordPda)).receipt);
+    const held = await getAccount(
+      provider.connection,
+      receiptToken,
+      'confirmed',
+      TOKEN_2022_PROGRAM_ID
+    );
+    assert.equal(held.amount.toString(), '1');
+
+    try {
+      await issue(user);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidReceipt'));
+    }
+
+    console.log('✓ Deferred deposit receipt issued');
+  });
+
+  it('Acknowledges a delivered outbound transfer exactly once', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x5555555555555555555555555555555555555555', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const ack = () =>
+      program.methods
+        .ackOutbound(nonce, Array(32).fill(7))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          guardianSet: guardianSetPda(),
+          bridgeAuthority: bridgeAuthority,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+        })
+        .rpc();
+
+    await ack();
+
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('completed' in record.status);
+    assert.ok(record.processedBy.equals(provider.wallet.publicKey));
+
+    try {
+      await ack();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidTransferStatus'));
+    }
+
+    console.log('✓ Outbound transfer acknowledged once');
+  });
+
+  it('Rejects locks past the throttle until the queue drains', async () => {
+    const throttle = PublicKey.findProgramAddressSync([Buffer.from('throttle')], program.programId)[0];
+    const configure = (rejectAt: number) =>
+      program.methods
+        .configureThrottle(new anchor.BN(0), new anchor.BN(0), 0, new anchor.BN(rejectAt))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          throttle,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const lock = async () => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x6666666666666666666666666666666666666666', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          throttle,
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+        })
+        .signers([user])
+        .rpc();
+      return nonce;
+    };
+
+    await configure(1);
+    const nonce = await lock();
+    assert.equal((await program.account.throttle.fetch(throttle)).pending.toNumber(), 1);
+
+    try {
+      await lock();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('BridgeCongested'));
+    }
+
+    await program.methods
+      .ackOutbound(nonce, Array(32).fill(0))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: transferPda('out', nonce),
+        throttle,
+        guardianSet: guardianSetPda(),
+        bridgeAuthority: bridgeAuthority,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+      })
+      .rpc();
+    assert.equal((await program.account.throttle.fetch(throttle)).pending.toNumber(), 0);
+    await lock();
+
+    // Back to unthrottled for the tests after this one
+    await configure(0);
+
+    console.log('✓ Throttle rejected a lock until one was acknowledged');
+  });
+
+  it('Auctions priority while congested and splits the tip', async () => {
+    const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const throttle = pda(Buffer.from('throttle'));
+    const priorityConfig = pda(Buffer.from('priority_config'));
+    const treasury = Keypair.generate().publicKey;
+    const configureThrottle = (surchargeAt: number) =>
+      program.methods
+        .configureThrottle(new anchor.BN(0), new anchor.BN(surchargeAt), 0, new anchor.BN(0))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          throttle,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    await configureThrottle(1);
+    await program.methods
+      .configurePriorityTips(5000, treasury)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        priorityConfig,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x5555555555555555555555555555555555555555', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        throttle,
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    // One pending transfer reaches surcharge_at: the queue is congested
+    const bid = pda(Buffer.from('priority_bid'), nonce.toArrayLike(Buffer, 'le', 8));
+    const raise = (tip: number) =>
+      program.methods
+        .bidPriority(nonce, new anchor.BN(tip))
+        .accounts({
+          sender: user.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: transferPda('out', nonce),
+          throttle,
+          priorityConfig,
+          bid,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    await raise(1000000);
+    await raise(1000000);
+    assert.equal((await program.account.priorityBid.fetch(bid)).tip.toNumber(), 2000000);
+
+    await program.methods
+      .ackOutbound(nonce, Array(32).fill(0))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: transferPda('out', nonce),
+        throttle,
+        guardianSet: guardianSetPda(),
+        bridgeAuthority: bridgeAuthority,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+      })
+      .rpc();
+
+    const rewardVault = pda(Buffer.from('reward_vault'));
+    const vaultBefore = await provider.connection.getBalance(rewardVault);
+    await program.methods
+      .collectPriorityTip(nonce)
+      .accounts({
+        bridgeState: bridgeState,
+        transferRecord: transferPda('out', nonce),
+        priorityConfig,
+        bid,
+        sender: user.publicKey,
+        rewardVault,
+        treasury,
+      })
+      .rpc();
+
+    assert.equal(await provider.connection.getBalance(treasury), 1000000);
+    assert.equal((await provider.connection.getBalance(rewardVault)) - vaultBefore, 1000000);
+    assert.equal(await provider.connection.getAccountInfo(bid), null);
+
+    await configureThrottle(0);
+
+    console.log('✓ Priority tip split between the relayers and the treasury');
+  });
+
+  it('Refunds only the failed legs of a batch', async () => {
+    const nonces = [
+      await nextOutNonce(mint, ETHEREUM_CHAIN_ID, 1),
+      await nextOutNonce(mint, ETHEREUM_CHAIN_ID, 2),
+    ];
+
+    await program.methods
+      .lockBatch([
+        { amount: new anchor.BN(1000000), recipient: '0x7777777777777777777777777777777777777777' },
+        { amount: new anchor.BN(1000000), recipient: '0x8888888888888888888888888888888888888888' },
+      ])
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts(
+        nonces.map((nonce) => ({ pubkey: transferPda('out', nonce), isSigner: false, isWritable: true }))
+      )
+      .signers([user])
+      .rpc();
+
+    const failed = await program.account.transferRecord.fetch(transferPda('out', nonces[1]));
+    const before = await getAccount(provider.connection, userTokenAccount);
+
+    await program.methods
+      .settleBatch([
+        { nonce: nonces[0], delivered: true, destTx: Array(32).fill(1) },
+        { nonce: nonces[1], delivered: false, destTx: Array(32).fill(0) },
+      ])
+      .accounts({
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        conversionRate: null,
+        refundToken: userTokenAccount,
+        bridgeToken: bridgeTokenAccount,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+      })
+      .remainingAccounts(
+        nonces.map((nonce) => ({ pubkey: transferPda('out', nonce), isSigner: false, isWritable: true }))
+      )
+      .rpc();
+
+    const delivered = await program.account.transferRecord.fetch(transferPda('out', nonces[0]));
+    assert.ok('completed' in delivered.status);
+    const refunded = await program.account.transferRecord.fetch(transferPda('out', nonces[1]));
+    assert.ok('refunded' in refunded.status);
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), failed.amount.toString());
+
+    console.log('✓ Batch settled with only the failed leg refunded');
+  });
+
+  it('Swaps an escrowed OTC order once the payment is vouched for', async () => {
+    const taker = Keypair.generate();
+    const takerToken = await createAccount(provider.connection, user, mint, taker.publicKey);
+    const [otcBook] = PublicKey.findProgramAddressSync([Buffer.from('otc_book')], program.programId);
+    const book = await program.account.otcBook.fetchNullable(otcBook);
+    const orderId = book ? book.nextOrderId : new anchor.BN(0);
+    const [order] = PublicKey.findProgramAddressSync(
+      [Buffer.from('otc_order'), orderId.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+    const amount = new anchor.BN(1000000);
+
+    // Orders escrow apart from the bridge's collateral
+    const [otcAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from('otc_escrow')],
+      program.programId
+    );
+    const escrow = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      otcAuthority,
+      Keypair.generate()
+    );
+    const collateral = await getAccount(provider.connection, bridgeTokenAccount);
+
+    const createAccounts = (counterChainId: anchor.BN) => ({
+      maker: user.publicKey,
+      bridgeState: bridgeState,
+      otcBook: otcBook,
+      order: order,
+      counterChain: chainConfigPda(counterChainId),
+      mint: mint,
+      tokenConfig: tokenConfigPda(mint),
+      makerToken: userTokenAccount,
+      escrow: escrow,
+      otcAuthority: otcAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+      systemProgram: SystemProgram.programId,
+    });
+
+    // Wormhole payment proofs only come from Ethereum
+    try {
+      await program.methods
+        .createOtcOrder(orderId, amount, {
+          counterChainId: STELLAR_CHAIN_ID,
+          counterAsset: '',
+          counterAmount: new anchor.BN(1000),
+          payTo: 'GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7',
+          expiry: new anchor.BN(now + 3600),
+          verifier: { wormholeVaa: { emitter: [...Buffer.alloc(32, 1)] } },
+        })
+        .accounts(createAccounts(STELLAR_CHAIN_ID))
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('WrongTrustModel'));
+    }
+
+    await program.methods
+      .createOtcOrder(orderId, amount, {
+        counterChainId: ETHEREUM_CHAIN_ID,
+        counterAsset: '',
+        counterAmount: new anchor.BN(500000000000000),
+        payTo: '0x9999999999999999999999999999999999999999',
+        expiry: new anchor.BN(now + 3600),
+        verifier: { ownerSignature: {} },
+      })
+      .accounts(createAccounts(ETHEREUM_CHAIN_ID))
+      .signers([user])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, escrow)).amount.toString(), amount.toString());
+    assert.equal(
+      (await getAccount(provider.connection, bridgeTokenAccount)).amount.toString(),
+      collateral.amount.toString()
+    );
+
+    // Binding until the expiry
+    try {
+      await program.methods
+        .cancelOtcOrder(orderId)
+        .accounts({
+          maker: user.publicKey,
+          bridgeState: bridgeState,
+          otcBook: otcBook,
+          order: order,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          makerToken: userTokenAccount,
+          escrow: escrow,
+          otcAuthority: otcAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NotExpired'));
+    }
+
+    const fill = (authority: Keypair | null) =>
+      program.methods
+        .fillOtcOrder(orderId)
+        .accounts({
+          authority: authority ? authority.publicKey : provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          otcBook: otcBook,
+          order: order,
+          maker: user.publicKey,
+          taker: taker.publicKey,
+          counterChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          vaa: null,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          takerToken: takerToken,
+          escrow: escrow,
+          otcAuthority: otcAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers(authority ? [authority] : [])
+        .rpc();
+
+    // Only the order's verifier (here the relayer) can vouch for the payment
+    try {
+      await fill(taker);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    // Nor while the token is paused
+    const setPaused = (paused: boolean) =>
+      (paused ? program.methods.pauseToken() : program.methods.unpauseToken())
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(mint),
+        })
+        .rpc();
+    await setPaused(true);
+    try {
+      await fill(null);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TokenPaused'));
+    }
+    await setPaused(false);
+    await fill(null);
+
+    assert.equal((await getAccount(provider.connection, takerToken)).amount.toString(), amount.toString());
+    assert.equal(await provider.connection.getAccountInfo(order), null);
+
+    console.log('✓ OTC order filled and escrow released to the taker');
+  });
+
+  it('Charges exactly the fee a guardian quoted', async () => {
+    const quoter = Keypair.generate();
+    const amount = new anchor.BN(1000000);
+    const fee = new anchor.BN(777);
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+
+    await program.methods
+      .setGuardians([quoter.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // "fee_quote" || mint || amount || fee || dest_chain_id || expires_at
+    const quote = (signer: Keypair, quotedAmount: anchor.BN, expiresAt: number) =>
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: Buffer.concat([
+          Buffer.from('fee_quote'),
+          mint.toBuffer(),
+          quotedAmount.toArrayLike(Buffer, 'le', 8),
+          fee.toArrayLike(Buffer, 'le', 8),
+          ETHEREUM_CHAIN_ID.toArrayLike(Buffer, 'le', 8),
+          new anchor.BN(expiresAt).toArrayLike(Buffer, 'le', 8),
+        ]),
+      });
+
+    const lock = async (preInstructions: TransactionInstruction[]) => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      return program.methods
+        .lock(amount, ETHEREUM_CHAIN_ID, '0x5555555555555555555555555555555555555555', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: guardianSetPda(),
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+        })
+        .preInstructions(preInstructions)
+        .signers([user])
+        .rpc();
+    };
+
+    for (const [preInstructions, expected] of [
+      [[], 'FeeQuoteRequired'],
+      [[quote(Keypair.generate(), amount, now + 600)], 'Unauthorized'],
+      [[quote(quoter, amount.addn(1), now + 600)], 'InvalidFeeQuote'],
+      [[quote(quoter, amount, now - 1)], 'FeeQuoteExpired'],
+    ] as [TransactionInstruction[], string][]) {
+      try {
+        await lock(preInstructions);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    }
+
+    const before = await getAccount(provider.connection, feeVaultAccount);
+    await lock([quote(quoter, amount, now + 600)]);
+    const after = await getAccount(provider.connection, feeVaultAccount);
+    assert.equal((after.amount - before.amount).toString(), fee.toString());
+
+    console.log('✓ Lock charged the quoted fee');
+  });
+
+  it('Pauses a single token without halting the bridge', async () => {
+    const setPaused = (paused: boolean) =>
+      (paused ? program.methods.pauseToken() : program.methods.unpauseToken())
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(wrappedMint),
+        })
+        .rpc();
+
+    await setPaused(true);
+
+    const nonce = new anchor.BN(300);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    try {
+      await program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TokenPaused'));
+    }
+
+    // The bridge itself keeps running
+    const state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, false);
+
+    await setPaused(false);
+
+    console.log('✓ Token paused independently');
+  });
+
+  it('Rejects inbound delivery from a halted source chain', async () => {
+    const chainStatus = (halted: boolean, authority = provider.wallet.publicKey) =>
+      program.methods.chainStatus(ETHEREUM_CHAIN_ID, halted).accounts({
+        authority,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+      });
+
+    // Not a guardian
+    try {
+      await chainStatus(true, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await chainStatus(true).rpc();
+    const chain = await program.account.chainConfig.fetch(chainConfigPda(ETHEREUM_CHAIN_ID));
+    assert.equal(chain.halted, true);
+
+    const nonce = new anchor.BN(302);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    try {
+      await program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('SourceChainHalted'));
+    }
+
+    await chainStatus(false).rpc();
+    const resumed = await program.account.chainConfig.fetch(chainConfigPda(ETHEREUM_CHAIN_ID));
+    assert.equal(resumed.halted, false);
+
+    console.log('✓ Halted chain blocks inbound delivery');
+  });
+
+  it('Tracks bridge-wide TVL in GlobalStats', async () => {
+    const [globalStats] = PublicKey.findProgramAddressSync(
+      [Buffer.from('global_stats')],
+      program.programId
+    );
+    const usdFeed = Buffer.alloc(32, 0xef);
+    const setPriceFeed = (feedId: Buffer, owner = provider.wallet.publicKey) =>
+      program.methods.setPriceFeed([...feedId]).accounts({
+        owner,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        globalStats: globalStats,
+        mint: mint,
+        escrow: bridgeTokenAccount,
+        bridgeAuthority: bridgeAuthority,
+        systemProgram: SystemProgram.programId,
+      });
+    const update = (remainingAccounts: PublicKey[]) =>
+      program.methods
+        .updateGlobalStats()
+        .accounts({ bridgeState: bridgeState, globalStats: globalStats })
+        .remainingAccounts(
+          remainingAccounts.map((pubkey) => ({ pubkey, isSigner: false, isWritable: false }))
+        )
+        .rpc();
+
+    try {
+      await setPriceFeed(usdFeed, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await setPriceFeed(usdFeed).rpc();
+    const escrow = await getAccount(provider.connection, bridgeTokenAccount);
+    let stats = await program.account.globalStats.fetch(globalStats);
+    assert.equal(stats.tokens.length, 1);
+    assert.equal(stats.tokens[0].balance.toString(), escrow.amount.toString());
+
+    // Every tracked token needs its escrow and a price
+    for (const [accounts, expected] of [
+      [[], 'TrackedTokenMismatch'],
+      [[userTokenAccount, mint], 'TrackedTokenMismatch'],
+      [[bridgeTokenAccount, mint], 'InvalidPriceFeed'],
+    ] as [PublicKey[], string][]) {
+      try {
+        await update(accounts);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    }
+
+    await setPriceFeed(Buffer.alloc(32)).rpc();
+    await update([]);
+    stats = await program.account.globalStats.fetch(globalStats);
+    assert.equal(stats.tokens.length, 0);
+    assert.equal(stats.tvlUsd.toString(), '0');
+    assert.ok(stats.updatedAt.toNumber() > 0);
+
+    console.log('✓ GlobalStats tracks priced escrows');
+  });
+
+  it('Emits fixed-size lock events in compact mode', async () => {
+    const setEventEncoding = (encoding: object, owner = provider.wallet.publicKey) =>
+      program.methods
+        .setEventEncoding(encoding)
+        .accounts({ owner, bridgeState: bridgeState, adminLog: adminLog });
+
+    try {
+      await setEventEncoding({ compact: {} }, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await setEventEncoding({ compact: {} }).rpc();
+
+    const recipient = '0x6666666666666666666666666666666666666666';
+    const state = await program.account.bridgeState.fetch(bridgeState);
+    assert.ok('compact' in state.eventEncoding);
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+
+    const signature = await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, recipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc({ commitment: 'confirmed' });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const events = Array.from(parser.parseLogs(tx.meta.logMessages));
+
+    // Only the compact event, with the address as raw bytes
+    assert.deepEqual(
+      events.map((event) => event.name.toLowerCase()),
+      ['compactlockevent']
+    );
+    assert.deepEqual(
+      Buffer.from(events[0].data.recipient),
+      Buffer.concat([Buffer.alloc(12), Buffer.from(recipient.slice(2), 'hex')])
+    );
+    assert.equal(events[0].data.nonce.toString(), nonce.toString());
+
+    await setEventEncoding({ full: {} }).rpc();
+
+    console.log('✓ Lock emitted a CompactLockEvent');
+  });
+
+  it('Attributes tagged locks to their integrator', async () => {
+    const integratorPda = (id: number) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('integrator'), new anchor.BN(id).toArrayLike(Buffer, 'le', 4)],
+        program.programId
+      )[0];
+    const register = (id: number) =>
+      program.methods
+        .registerIntegrator(id)
+        .accounts({
+          bridgeState: bridgeState,
+          authority: user.publicKey,
+          integrator: integratorPda(id),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+
+    // 0 stands for untagged
+    try {
+      await register(0);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidIntegrator'));
+    }
+    await register(7);
+
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const lock = (integrator: PublicKey | null) =>
+      program.methods
+        .lock(
+          new anchor.BN(1000000),
+          ETHEREUM_CHAIN_ID,
+          '0x7777777777777777777777777777777777777777',
+          null,
+          7,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          integrator,
+        })
+        .signers([user])
+        .rpc({ commitment: 'confirmed' });
+
+    // A tag needs its integrator's stats
+    try {
+      await lock(null);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidIntegrator'));
+    }
+
+    const signature = await lock(integratorPda(7));
+    const stats = await program.account.integratorStats.fetch(integratorPda(7));
+    assert.equal(stats.integratorId, 7);
+    assert.ok(stats.authority.equals(user.publicKey));
+    assert.equal(stats.volume.toString(), '1000000');
+    assert.equal(stats.transfers.toNumber(), 1);
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const event = Array.from(parser.parseLogs(tx.meta.logMessages)).find(
+      (event) => event.name.toLowerCase() === 'lockevent'
+    );
+    assert.equal(event.data.integratorId, 7);
+
+    console.log('✓ Lock attributed to integrator 7');
+  });
+
+  it('Previews a lock exactly without moving funds', async () => {
+    const amount = new anchor.BN(1000000);
+    const recipient = '0x7777777777777777777777777777777777777777';
+    const preview = (to: string) =>
+      program.methods
+        .previewLock(amount, mint, ETHEREUM_CHAIN_ID, to, user.publicKey)
+        .accounts({
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          tokenMint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          routeSequence: routeSequencePda(mint, ETHEREUM_CHAIN_ID),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          guardianSet: guardianSetPda(),
+        })
+        .view();
+
+    // Fails the way the lock would
+    try {
+      await preview('0x1234');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidEthAddress'));
+    }
+
+    const before = await getAccount(provider.connection, userTokenAccount);
+    const previewed = await preview(recipient);
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal(after.amount.toString(), before.amount.toString());
+
+    assert.equal(
+      previewed.nonce.toString(),
+      (await nextOutNonce(mint, ETHEREUM_CHAIN_ID)).toString()
+    );
+
+    const signature = await program.methods
+      .lock(amount, ETHEREUM_CHAIN_ID, recipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', previewed.nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc({ commitment: 'confirmed' });
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const lockEvent = Array.from(parser.parseLogs(tx.meta.logMessages)).find(
+      (event) => event.name.toLowerCase() === 'lockevent'
+    );
+    assert.deepEqual(lockEvent.data.transferId, previewed.transferId);
+    assert.equal(lockEvent.data.amount.toString(), previewed.bridgedAmount.toString());
+    assert.equal(lockEvent.data.fee.toString(), previewed.fee.toString());
+    assert.equal(lockEvent.data.feeTier, previewed.feeTier);
+    const destination = await program.account.chainConfig.fetch(chainConfigPda(ETHEREUM_CHAIN_ID));
+    assert.equal(lockEvent.data.requiredConfirmations, destination.requiredConfirmations);
+    assert.equal(previewed.tokenFee.toString(), '0');
+
+    console.log('✓ Preview matched the lock');
+  });
+
+  it('Reports the limits a wallet should size its input to', async () => {
+    const throttle = PublicKey.findProgramAddressSync([Buffer.from('throttle')], program.programId)[0];
+    const limits = await program.methods
+      .getRateLimits(mint, ETHEREUM_CHAIN_ID, user.publicKey)
+      .accounts({
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        tokenConfig: tokenConfigPda(mint),
+        throttle,
+        credential: null,
+        bridgePass: null,
+      })
+      .view();
+
+    const destination = await program.account.chainConfig.fetch(chainConfigPda(ETHEREUM_CHAIN_ID));
+    assert.equal(limits.blocked, false);
+    assert.equal(limits.minAmount.toString(), destination.minAmount.toString());
+    // Unrestricted token: no per-transfer limit
+    assert.equal(limits.maxAmount, null);
+
+    console.log('✓ Rate limits reported');
+  });
+
+  it('Routes a lock on to an L2 past its destination', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recipient = '0x8888888888888888888888888888888888888888';
+
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, recipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+
+    const [transferRoute] = PublicKey.findProgramAddressSync(
+      [Buffer.from('route'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+    const setRoute = (finalChainId: anchor.BN, finalRecipient: string, sender = user) =>
+      program.methods
+        .setTransferRoute({ hops: [], finalChainId, finalRecipient })
+        .accounts({
+          bridgeState: bridgeState,
+          sender: sender.publicKey,
+          transferRecord: transferPda('out', nonce),
+          transferRoute: transferRoute,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([
+          { pubkey: chainConfigPda(finalChainId), isSigner: false, isWritable: false },
+        ])
+        .signers([sender])
+        .rpc();
+    const finalRecipient = '0x9999999999999999999999999999999999999999';
+
+    // Not the lock's sender
+    const other = (provider.wallet as anchor.Wallet).payer;
+    for (const [args, expected] of [
+      [[ARBITRUM_CHAIN_ID, finalRecipient, other], 'Unauthorized'],
+      // Back to the destination it's already going to
+      [[ETHEREUM_CHAIN_ID, finalRecipient, user], 'InvalidRoute'],
+      [[ARBITRUM_CHAIN_ID, '0x1234', user], 'InvalidEthAddress'],
+    ] as [[anchor.BN, string, Keypair], string][]) {
+      try {
+        await setRoute(...args);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    }
+
+    await setRoute(ARBITRUM_CHAIN_ID, finalRecipient);
+    const route = await program.account.transferRoute.fetch(transferRoute);
+    assert.equal(route.nonce.toString(), nonce.toString());
+    assert.equal(route.route.finalChainId.toString(), '42161');
+    assert.equal(route.route.finalRecipient, finalRecipient);
+
+    console.log('✓ Lock routed on to Arbitrum');
+  });
+
+  it('Re-emits a stored transfer for relayers that missed it', async () => {
+    const recordPda = transferPda('out', await outNonce(mint, ETHEREUM_CHAIN_ID, 1));
+    const record = await program.account.transferRecord.fetch(recordPda);
+    const reemit = (transferId: number[]) =>
+      program.methods.reemit(transferId).accounts({ bridgeState, transferRecord: recordPda });
+
+    try {
+      await reemit(Array(32).fill(0)).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TransferMismatch'));
+    }
+
+    const signature = await reemit(record.transferId).rpc({ commitment: 'confirmed' });
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: 'confirmed',
+      maxSupportedTransactionVersion: 0,
+    });
+    const parser = new anchor.EventParser(program.programId, program.coder);
+    const [event] = Array.from(parser.parseLogs(tx.meta.logMessages));
+
+    assert.equal(event.name.toLowerCase(), 'transferreemitted');
+    assert.deepEqual(event.data.transferId, record.transferId);
+    assert.equal(event.data.remoteAddress, record.remoteAddress);
+    assert.equal(event.data.amount.toString(), record.amount.toString());
+
+    // The line log-parsing relayers match on, as the lock logged it
+    const id = Buffer.from(record.transferId).toString('hex');
+    assert.ok(
+      tx.meta.logMessages.some(
+        (log) => log.includes(`Locked ${record.amount} tokens for`) && log.includes(`id: ${id}`)
+      )
+    );
+
+    console.log('✓ Transfer re-emitted from its record');
+  });
+
+  it('Waives the bridge fee for exempted addresses', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+    const feeManager = Keypair.generate();
+    const exemptionPda = feeExemptionPda(user.publicKey);
+    const recipient = '0x9999999999999999999999999999999999999999';
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(feeManager.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const setFeeManager = (feeManager: PublicKey, owner = provider.wallet.publicKey) =>
+      program.methods
+        .setFeeManager(feeManager)
+        .accounts({ owner, bridgeState: bridgeState, adminLog: adminLog });
+    const grant = (authority: Keypair) =>
+      program.methods
+        .grantFeeExemption(user.publicKey)
+        .accounts({
+          authority: authority.publicKey,
+          bridgeState: bridgeState,
+          feeExemption: exemptionPda,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority]);
+    // Arbitrum charges 1000 + 5 bps, less any volume rebate
+    const quote = () =>
+      program.methods
+        .quoteLock(mint, new anchor.BN(1000000), ARBITRUM_CHAIN_ID, user.publicKey)
+        .accounts({
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          tokenConfig: tokenConfigPda(mint),
+          routeSequence: routeSequencePda(mint, ARBITRUM_CHAIN_ID),
+          feeRoute: feeRoutePda(mint, ARBITRUM_CHAIN_ID),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          feeExemption: exemptionPda,
+        })
+        .view();
+
+    try {
+      await setFeeManager(user.publicKey, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await setFeeManager(feeManager.publicKey).rpc();
+
+    try {
+      await grant(user).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await grant(feeManager).rpc();
+
+    const exemption = await program.account.feeExemption.fetch(exemptionPda);
+    assert.ok(exemption.grantedBy.equals(feeManager.publicKey));
+    assert.equal((await quote()).fee.toString(), '0');
+
+    const nonce = await nextOutNonce(mint, ARBITRUM_CHAIN_ID);
+    const before = await getAccount(provider.connection, feeVaultAccount);
+    await program.methods
+      .lock(new anchor.BN(1000000), ARBITRUM_CHAIN_ID, recipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ARBITRUM_CHAIN_ID),
+        feeExemption: exemptionPda,
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+    const after = await getAccount(provider.connection, feeVaultAccount);
+    assert.equal(after.amount.toString(), before.amount.toString());
+
+    const record = await program.account.transferRecord.fetch(transferPda('out', nonce));
+    assert.equal(record.amount.toString(), '1000000');
+
+    await program.methods
+      .revokeFeeExemption(user.publicKey)
+      .accounts({
+        authority: feeManager.publicKey,
+        bridgeState: bridgeState,
+        feeExemption: exemptionPda,
+      })
+      .signers([feeManager])
+      .rpc();
+    assert.isNull(await program.account.feeExemption.fetchNullable(exemptionPda));
+    assert.ok((await quote()).fee.gtn(0));
+
+    await setFeeManager(PublicKey.default).rpc();
+
+    console.log('✓ Exempted lock paid no bridge fee');
+  });
+
+  it('Waives the bridge fee for bridge pass holders', async () => {
+    const ARBITRUM_CHAIN_ID = new anchor.BN(42161);
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const recipient = '0x9999999999999999999999999999999999999999';
+
+    // A Token-2022 NFT collection's mint, and the user's pass
+    const passMint = await createMint(
+      provider.connection,
+      payer,
+      payer.publicKey,
+      null,
+      0,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const pass = await createAccount(
+      provider.connection,
+      payer,
+      passMint,
+      user.publicKey,
+      undefined,
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+    await mintTo(
+      provider.connection,
+      payer,
+      passMint,
+      pass,
+      payer,
+      1,
+      [],
+      undefined,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    const setBridgePass = (
+      passMint: PublicKey,
+      discountBps: number,
+      owner = provider.wallet.publicKey
+    ) =>
+      program.methods
+        .setBridgePass(passMint, discountBps, 0)
+        .accounts({ owner, bridgeState: bridgeState, adminLog: adminLog });
+    // Arbitrum charges 1000 + 5 bps, less any volume rebate
+    const preview = (bridgePass: PublicKey | null) =>
+      program.methods
+        .previewLock(new anchor.BN(1000000), mint, ARBITRUM_CHAIN_ID, recipient, user.publicKey)
+        .accounts({
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+          tokenMint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          routeSequence: routeSequencePda(mint, ARBITRUM_CHAIN_ID),
+          feeRoute: feeRoutePda(mint, ARBITRUM_CHAIN_ID),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          credential: null,
+          bridgePass,
+          conversionRate: null,
+          guardianSet: guardianSetPda(),
+        })
+        .view();
+
+    try {
+      await setBridgePass(passMint, 10000, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await setBridgePass(passMint, 10000).rpc();
+
+    assert.equal((await preview(pass)).fee.toString(), '0');
+    assert.ok((await preview(null)).fee.gtn(0));
+
+    // Only a token account of the pass mint counts
+    try {
+      await preview(userTokenAccount);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ConstraintTokenMint'));
+    }
+
+    const nonce = await nextOutNonce(mint, ARBITRUM_CHAIN_ID);
+    const before = await getAccount(provider.connection, feeVaultAccount);
+    await program.methods
+      .lock(new anchor.BN(1000000), ARBITRUM_CHAIN_ID, recipient, null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ARBITRUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ARBITRUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: pass,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: transferPda('out', nonce),
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+    const after = await getAccount(provider.connection, feeVaultAccount);
+    assert.equal(after.amount.toString(), before.amount.toString());
+
+    await setBridgePass(PublicKey.default, 0).rpc();
+
+    console.log('✓ Pass holder paid no bridge fee');
+  });
+
+  it('Delivers inbound mints as claims in claim mode', async () => {
+    const setClaimDelivery = (enabled: boolean, owner = provider.wallet.publicKey) =>
+      program.methods
+        .setClaimDelivery(enabled)
+        .accounts({ owner, bridgeState: bridgeState, adminLog: adminLog });
+
+    try {
+      await setClaimDelivery(true, user.publicKey).signers([user]).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await setClaimDelivery(true).rpc();
+
+    // The relayer passes no token account
+    const nonce = new anchor.BN(303);
+    const recordPda = transferPda('in', nonce);
+    await program.methods
+      .mint(new anchor.BN(1000), nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: null,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('claimable' in record.status);
+    assert.ok(record.mint.equals(wrappedMint));
+
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const payerWrappedAccount = await createAccount(
+      provider.connection,
+      payer,
+      wrappedMint,
+      payer.publicKey,
+      Keypair.generate()
+    );
+    const claim = (recipientToken: PublicKey) =>
+      program.methods.claim(record.transferId).accounts({
+        claimer: payer.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: recordPda,
+        guardianCouncil: guardianCouncilPda(),
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        recipientToken,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        instructions: null,
+      });
+
+    // Someone else may claim, but only into the recipient's account
+    try {
+      await claim(payerWrappedAccount).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await claim(userWrappedAccount).rpc();
+
+    const account = await getAccount(provider.connection, userWrappedAccount);
+    assert.equal(account.amount.toString(), '1000');
+    const claimed = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('completed' in claimed.status);
+
+    try {
+      await claim(userWrappedAccount).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NotClaimable'));
+    }
+
+    await setClaimDelivery(false).rpc();
+
+    console.log('✓ Inbound mint claimed by its recipient');
+  });
+
+  it('Lets a claim service execute a claim the recipient signed for', async () => {
+    const setClaimDelivery = (enabled: boolean) =>
+      program.methods.setClaimDelivery(enabled).accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      });
+    await setClaimDelivery(true).rpc();
+
+    const nonce = new anchor.BN(304);
+    const recordPda = transferPda('in', nonce);
+    await program.methods
+      .mint(new anchor.BN(2000), nonce)
+      .accounts({
+        user: user.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: null,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    const record = await program.account.transferRecord.fetch(recordPda);
+
+    // The recipient wants the tokens in a wallet other than their own
+    const service = (provider.wallet as anchor.Wallet).payer;
+    const savings = Keypair.generate();
+    const savingsAccount = await createAccount(
+      provider.connection,
+      service,
+      wrappedMint,
+      savings.publicKey,
+      Keypair.generate()
+    );
+    const now = await provider.connection.getBlockTime(await provider.connection.getSlot());
+
+    // "claim_auth" || transfer_id || recipient_token || executor || expires_at
+    const authorization = (signer: Keypair, recipientToken: PublicKey) =>
+      Ed25519Program.createInstructionWithPrivateKey({
+        privateKey: signer.secretKey,
+        message: Buffer.concat([
+          Buffer.from('claim_auth'),
+          Buffer.from(record.transferId),
+          recipientToken.toBuffer(),
+          service.publicKey.toBuffer(),
+          new anchor.BN(now + 600).toArrayLike(Buffer, 'le', 8),
+        ]),
+      });
+    const claim = (preInstructions: TransactionInstruction[]) =>
+      program.methods
+        .claim(record.transferId)
+        .accounts({
+          claimer: service.publicKey,
+          bridgeState: bridgeState,
+          transferRecord: recordPda,
+          guardianCouncil: guardianCouncilPda(),
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          recipientToken: savingsAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          instructions: SYSVAR_INSTRUCTIONS_PUBKEY,
+        })
+        .preInstructions(preInstructions)
+        .rpc();
+
+    for (const [preInstructions, expected] of [
+      [[], 'InvalidClaimAuthorization'],
+      [[authorization(service, savingsAccount)], 'Unauthorized'],
+      [[authorization(user, Keypair.generate().publicKey)], 'InvalidClaimAuthorization'],
+    ] as [TransactionInstruction[], string][]) {
+      try {
+        await claim(preInstructions);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    }
+
+    await claim([authorization(user, savingsAccount)]);
+    const account = await getAccount(provider.connection, savingsAccount);
+    assert.equal(account.amount.toString(), '2000');
+
+    await setClaimDelivery(false).rpc();
+
+    console.log('✓ Claim service delivered to the account the recipient signed for');
+  });
+
+  it('Reimburses relayers for the gas they report', async () => {
+    const relayer = provider.wallet.publicKey;
+    const { rewardConfig } = rewardAccounts(relayer);
+    const [rewardVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from('reward_vault')],
+      program.programId
+    );
+    const [balancePda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('gas_reimbursement'), relayer.toBuffer()],
+      program.programId
+    );
+    const setGasReimbursement = (max: number) =>
+      program.methods
+        .setGasReimbursement(new anchor.BN(max))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          rewardConfig,
+          systemProgram: SystemProgram.programId,
+        });
+    // Delivered by the provider wallet in claim mode, then claimed
+    const nonce = new anchor.BN(303);
+    const reportGas = (signer: Keypair | null) =>
+      program.methods
+        .reportGas(nonce, new anchor.BN(8000))
+        .accounts({
+          bridgeState: bridgeState,
+          relayer: signer ? signer.publicKey : relayer,
+          rewardConfig,
+          transferRecord: transferPda('in', nonce),
+        })
+        .signers(signer ? [signer] : []);
+    const claim = () =>
+      program.methods
+        .claimGasReimbursement()
+        .accounts({ relayer, bridgeState, gasReimbursement: balancePda, rewardVault })
+        .rpc();
+
+    await setGasReimbursement(5000).rpc();
+
+    // Only the relayer that delivered it can report
+    try {
+      await reportGas(user).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await reportGas(null).rpc();
+    const [reportPda] = PublicKey.findProgramAddressSync(
+      [Buffer.from('gas_report'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    );
+    const report = await program.account.gasReport.fetch(reportPda);
+    assert.equal(report.gasPaid.toString(), '8000');
+    assert.equal(report.reimbursed.toString(), '5000');
+
+    // Once per transfer
+    try {
+      await reportGas(null).rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('already in use'));
+    }
+
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: relayer,
+          toPubkey: rewardVault,
+          lamports: anchor.web3.LAMPORTS_PER_SOL / 100,
+        })
+      )
+    );
+    const owed = (await program.account.gasReimbursement.fetch(balancePda)).owed;
+    const vaultBefore = await provider.connection.getBalance(rewardVault);
+    await claim();
+    const vaultAfter = await provider.connection.getBalance(rewardVault);
+    assert.equal(vaultBefore - vaultAfter, owed.toNumber());
+
+    const balance = await program.account.gasReimbursement.fetch(balancePda);
+    assert.equal(balance.owed.toString(), '0');
+    assert.equal(balance.claimed.toString(), owed.toString());
+
+    try {
+      await claim();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NothingToReimburse'));
+    }
+
+    await setGasReimbursement(0).rpc();
+
+    console.log('✓ Relayer claimed its reported gas');
+  });
+
+  it('Records who can upgrade the program', async () => {
+    const [upgradeRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('upgrade_authority')],
+      program.programId
+    );
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    );
+
+    // Permissionless; anchor deploys with the provider wallet, also the owner
+    await program.methods
+      .syncUpgradeAuthority()
+      .accounts({ payer: user.publicKey, bridgeState, programData })
+      .signers([user])
+      .rpc();
+    const record = await program.account.upgradeAuthorityRecord.fetch(upgradeRecord);
+    assert.ok(record.authority.equals(provider.wallet.publicKey));
+    assert.isTrue(record.heldByOwner);
+    assert.ok(record.deployedSlot.toNumber() > 0);
+
+    // The owner already holds it
+    try {
+      await program.methods
+        .transferUpgradeAuthority()
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          owner: provider.wallet.publicKey,
+          programData,
+        })
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidConfig'));
+    }
+
+    // Only the owner can finalize
+    try {
+      await program.methods
+        .finalizeProgram()
+        .accounts({ owner: user.publicKey, bridgeState, programData })
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+  });
+
+  it('Records the verified build of the deployed program', async () => {
+    const [buildRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('build_record')],
+      program.programId
+    );
+    const [upgradeRecord] = PublicKey.findProgramAddressSync(
+      [Buffer.from('upgrade_authority')],
+      program.programId
+    );
+    const [programData] = PublicKey.findProgramAddressSync(
+      [program.programId.toBuffer()],
+      new PublicKey('BPFLoaderUpgradeab1e11111111111111111111111')
+    );
+    const hash = Array.from({ length: 32 }, (_, i) => i + 1);
+
+    await program.methods
+      .recordBuild(hash, 'v1.0.0')
+      .accounts({ owner: provider.wallet.publicKey, bridgeState, programData })
+      .rpc();
+    const record = await program.account.buildRecord.fetch(buildRecord);
+    const upgrade = await program.account.upgradeAuthorityRecord.fetch(upgradeRecord);
+    assert.deepEqual(record.hash, hash);
+    assert.equal(record.version, 'v1.0.0');
+    assert.ok(record.deployedSlot.eq(upgrade.deployedSlot));
+
+    // Only the owner vouches for a build
+    try {
+      await program.methods
+        .recordBuild(hash, 'v1.0.0')
+        .accounts({ owner: user.publicKey, bridgeState, programData })
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    // A zero hash records nothing
+    try {
+      await program.methods
+        .recordBuild(new Array(32).fill(0), 'v1.0.1')
+        .accounts({ owner: provider.wallet.publicKey, bridgeState, programData })
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidConfig'));
+    }
+
+    console.log('✓ Build hash recorded for the current deploy');
+  });
+
+  it('Moves finished transfer records into the compressed store', async () => {
+    const connection = provider.connection;
+    const compressionProgram = new PublicKey('cmtDvXumGCrqC1Age74AVPhSRVXJMd8PJS91L8KbNCK');
+    const noopProgram = new PublicKey('noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV');
+    const [compressedStore] = PublicKey.findProgramAddressSync(
+      [Buffer.from('compressed_store')],
+      program.programId
+    );
+    const [treeAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from('tree_authority')],
+      program.programId
+    );
+
+    // Depth 3, buffer 8, no canopy: header, counters, changelog, rightmost path
+    const tree = Keypair.generate();
+    const [depth, buffer] = [3, 8];
+    const space = 56 + 24 + buffer * (32 + 32 * depth + 8) + (32 * depth + 32 + 8);
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.createAccount({
+          fromPubkey: provider.wallet.publicKey,
+          newAccountPubkey: tree.publicKey,
+          space,
+          lamports: await connection.getMinimumBalanceForRentExemption(space),
+          programId: compressionProgram,
+        })
+      ),
+      [tree]
+    );
+    await program.methods
+      .initCompressedStore(depth, buffer)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        merkleTree: tree.publicKey,
+        compressionProgram,
+        noopProgram,
+      })
+      .rpc();
+
+    // Completed by the delegated claim
+    const recordPda = transferPda('in', new anchor.BN(304));
+    await program.methods
+      .compressTransferRecord()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        merkleTree: tree.publicKey,
+        treeAuthority,
+        compressionProgram,
+        noopProgram,
+        transferRecord: recordPda,
+      })
+      .rpc();
+
+    assert.isNull(await connection.getAccountInfo(recordPda));
+    const store = await program.account.compressedStore.fetch(compressedStore);
+    assert.ok(store.tree.equals(tree.publicKey));
+    assert.equal(store.leaves.toNumber(), 1);
+    assert.equal(store.compressed.toNumber(), 1);
+
+    console.log('✓ Transfer record compressed into leaf 0');
+  });
+
+  it('Delivers each token only through its trust model', async () => {
+    const setTrustModel = (model: object) =>
+      program.methods
+        .setTrustModel(model)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(wrappedMint),
+        })
+        .rpc();
+
+    const nonce = new anchor.BN(301);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const ownerMint = (vaa: PublicKey | null) =>
+      program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // A light-client token only mints with a proof
+    await setTrustModel({ lightClient: {} });
+    try {
+      await ownerMint(null);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('WrongTrustModel'));
+    }
+
+    // A Wormhole token needs a VAA posted by the core bridge
+    const emitter = Array(32).fill(7);
+    await setTrustModel({ wormholeVaa: { emitter } });
+    const tokenConfig = await program.account.tokenConfig.fetch(tokenConfigPda(wrappedMint));
+    assert.deepEqual(tokenConfig.trustModel, { wormholeVaa: { emitter } });
+
+    for (const vaa of [null, user.publicKey]) {
+      try {
+        await ownerMint(vaa);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes('InvalidVaa'));
+      }
+    }
+
+    await setTrustModel({ ownerSignature: {} });
+    await ownerMint(null);
+
+    console.log('✓ Trust model enforced per token');
+  });
+
+  it('Mints with a Groth16 proof bound to the mint, recipient, amount and nonce', async () => {
+    const owner = provider.wallet.publicKey;
+    const zkVerifier = PublicKey.findProgramAddressSync([Buffer.from('zk_verifier')], program.programId)[0];
+
+    // BN254 G1 arithmetic, just enough to prove against a toy key
+    const P = BigInt('21888242871839275222246405745257275088696311157297823662689037894645226208583');
+    const mod = (x: bigint) => ((x % P) + P) % P;
+    const inv = (x: bigint) => {
+      let [result, base, exp] = [BigInt(1), mod(x), P - BigInt(2)];
+      for (; exp > BigInt(0); exp >>= BigInt(1), base = mod(base * base)) {
+        if (exp & BigInt(1)) result = mod(result * base);
+      }
+      return result;
+    };
+    type G1 = [bigint, bigint] | null;
+    const add = (a: G1, b: G1): G1 => {
+      if (!a) return b;
+      if (!b) return a;
+      if (a[0] === b[0] && mod(a[1] + b[1]) === BigInt(0)) return null;
+      const slope = a[0] === b[0]
+        ? mod(BigInt(3) * a[0] * a[0] * inv(BigInt(2) * a[1]))
+        : mod((b[1] - a[1]) * inv(b[0] - a[0]));
+      const x = mod(slope * slope - a[0] - b[0]);
+      return [x, mod(slope * (a[0] - x) - a[1])];
+    };
+    const mul = (point: G1, scalar: bigint) => {
+      let result: G1 = null;
+      for (; scalar > BigInt(0); scalar >>= BigInt(1), point = add(point, point)) {
+        if (scalar & BigInt(1)) result = add(result, point);
+      }
+      return result;
+    };
+    const g1 = (k: number) => mul([BigInt(1), BigInt(2)], BigInt(k));
+    const encode = (point: G1) =>
+      Array.from(Buffer.from(point!.map((c) => c.toString(16).padStart(64, '0')).join(''), 'hex'));
+    const g2 = Array.from(Buffer.from(
+      '198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2' +
+      '1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed' +
+      '090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b' +
+      '12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa',
+      'hex'
+    ));
+
+    // With beta = gamma = delta = G2 a proof only needs A = alpha + vk_x + C
+    const ic = [g1(3), g1(4), g1(5)];
+    const setKey = (icPoints: G1[], signer?: Keypair) =>
+      program.methods
+        .setVerifyingKey(encode(g1(2)), g2, g2, g2, icPoints.map(encode))
+        .accounts({
+          owner: signer ? signer.publicKey : owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          zkVerifier,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(signer ? [signer] : [])
+        .rpc();
+    // Proofs are scoped to the guardian set they were produced under
+    const guardianSetIndex =
+      (await program.account.guardianSet.fetchNullable(guardianSetPda()))?.index ?? 0;
+    const prove = (
+      token: PublicKey,
+      recipient: PublicKey,
+      amount: anchor.BN,
+      nonce: anchor.BN,
+      setIndex = guardianSetIndex
+    ) => {
+      const hash = BigInt(ethers.solidityPackedKeccak256(
+        ['uint64', 'uint32', 'bytes32', 'bytes32', 'uint256', 'uint256'],
+        [ETHEREUM_CHAIN_ID.toString(), setIndex, token.toBuffer(), recipient.toBuffer(), amount.toString(), nonce.toString()]
+      ));
+      const [hi, lo] = [hash >> BigInt(128), hash & ((BigInt(1) << BigInt(128)) - BigInt(1))];
+      const vkX = add(add(ic[0], mul(ic[1], hi)), mul(ic[2], lo));
+      return { a: encode(add(add(g1(2), vkX), g1(6))), b: g2, c: encode(g1(6)) };
+    };
+
+    // The key needs one IC point per public input plus one, and the owner
+    try {
+      await setKey(ic.slice(0, 2));
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidVerifyingKey'));
+    }
+    const stranger = Keypair.generate();
+    const sig = await provider.connection.requestAirdrop(stranger.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+    await provider.connection.confirmTransaction(sig);
+    try {
+      await setKey(ic, stranger);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    await setKey(ic);
+    const key = await program.account.zkVerifier.fetch(zkVerifier);
+    assert.equal(key.ic.length, 3);
+
+    const setTrustModel = (model: object) =>
+      program.methods
+        .setTrustModel(model)
+        .accounts({
+          owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(wrappedMint),
+        })
+        .rpc();
+    await setTrustModel({ lightClient: {} });
+    const inbox = await program.account.inbox.fetch(inboxPda(ETHEREUM_CHAIN_ID));
+    const configureInbox = (next: anchor.BN, window: number) =>
+      program.methods
+        .configureInbox(ETHEREUM_CHAIN_ID, next, window)
+        .accounts({
+          owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    await configureInbox(new anchor.BN(500), 8);
+
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const amount = new anchor.BN(1000);
+    const nonce = new anchor.BN(500);
+    const mintWithProof = (proof: object, proofAmount = amount) =>
+      program.methods
+        .mintWithProof(proofAmount, nonce, proof)
+        .accounts({
+          submitter: owner,
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          zkVerifier,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(owner),
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    // A proof of the same lock for another mint, of another amount, or
+    // under another guardian set fails
+    for (const [proof, proofAmount] of [
+      [prove(mint, user.publicKey, amount, nonce), amount],
+      [prove(wrappedMint, user.publicKey, amount, nonce), new anchor.BN(1001)],
+      [prove(wrappedMint, user.publicKey, amount, nonce, guardianSetIndex + 1), amount],
+    ] as [object, anchor.BN][]) {
+      try {
+        await mintWithProof(proof, proofAmount);
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes('InvalidProof'));
+      }
+    }
+
+    const chainStatus = (halted: boolean) =>
+      program.methods
+        .chainStatus(ETHEREUM_CHAIN_ID, halted)
+        .accounts({
+          authority: owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          guardianSet: guardianSetPda(),
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        })
+        .rpc();
+
+    // Nor does a valid one while Ethereum is halted
+    await chainStatus(true);
+    try {
+      await mintWithProof(prove(wrappedMint, user.publicKey, amount, nonce));
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('SourceChainHalted'));
+    }
+    await chainStatus(false);
+
+    await mintWithProof(prove(wrappedMint, user.publicKey, amount, nonce));
+    const account = await getAccount(provider.connection, userWrappedAccount);
+    assert.equal(account.amount, BigInt(1000));
+    const record = await program.account.transferRecord.fetch(transferPda('in', nonce));
+    assert.ok(record.localAccount.equals(user.publicKey));
+
+    await setTrustModel({ ownerSignature: {} });
+    await configureInbox(inbox.nextSequence, inbox.window);
+
+    console.log('✓ Proof minted only for the transfer it commits to');
+  });
+
+  it('Lets the guardian council halt and clear inbound transfers', async () => {
+    const members = [Keypair.generate(), Keypair.generate()];
+    const guardianCouncil = guardianCouncilPda();
+
+    await program.methods
+      .createGuardianCouncil(
+        members.map((m) => m.publicKey),
+        2
+      )
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianCouncil: guardianCouncil,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const councilAction = (action: 'councilHalt' | 'councilClear', signers: Keypair[]) =>
+      program.methods[action]()
+        .accounts({ bridgeState, guardianCouncil })
+        .remainingAccounts(
+          signers.map((s) => ({ pubkey: s.publicKey, isSigner: true, isWritable: false }))
+        )
+        .signers(signers)
+        .rpc();
+
+    // One member alone can't halt
+    try {
+      await councilAction('councilHalt', [members[0]]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InsufficientGuardianSignatures'));
+    }
+
+    await councilAction('councilHalt', members);
+
+    const nonce = new anchor.BN(202);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    try {
+      await program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncil,
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(provider.wallet.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('CouncilHalted'));
+    }
+
+    await councilAction('councilClear', members);
+
+    const council = await program.account.guardianCouncil.fetch(guardianCouncil);
+    assert.equal(council.halted, false);
+
+    console.log('✓ Guardian council halt cleared');
+  });
+
+  it('Executes an optimistic attestation only after its challenge window', async () => {
+    const pda = (...seeds: Buffer[]) => PublicKey.findProgramAddressSync(seeds, program.programId)[0];
+    const nonceSeed = (nonce: anchor.BN) => nonce.toArrayLike(Buffer, 'le', 8);
+    const relayer = provider.wallet.publicKey;
+    const watcher = Keypair.generate();
+    const guardian = Keypair.generate();
+    const fraudster = Keypair.generate();
+    const treasury = Keypair.generate().publicKey;
+    const bond = new anchor.BN(anchor.web3.LAMPORTS_PER_SOL / 100);
+    for (const funded of [watcher, fraudster]) {
+      const sig = await provider.connection.requestAirdrop(funded.publicKey, anchor.web3.LAMPORTS_PER_SOL);
+      await provider.connection.confirmTransaction(sig);
+    }
+
+    await program.methods
+      .setTrustModel({ optimistic: {} })
+      .accounts({
+        owner: relayer,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        tokenConfig: tokenConfigPda(wrappedMint),
+      })
+      .rpc();
+    // 2 second challenge period, 10% of slashed bonds to the treasury
+    await program.methods
+      .setOptimisticConfig(new anchor.BN(2), bond, 1000, treasury)
+      .accounts({
+        owner: relayer,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        optimisticConfig: pda(Buffer.from('optimistic_config')),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .addWatcher(watcher.publicKey)
+      .accounts({
+        owner: relayer,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        watcherInfo: pda(Buffer.from('watcher'), watcher.publicKey.toBuffer()),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    // The later reserve test sets its own guardians
+    await program.methods
+      .setGuardians([guardian.publicKey, fraudster.publicKey], 1)
+      .accounts({
+        owner: relayer,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    // Attestations get inbox sequences of their own; put it back after
+    const inbox = await program.account.inbox.fetch(inboxPda(ETHEREUM_CHAIN_ID));
+    const configureInbox = (next: anchor.BN, window: number) =>
+      program.methods
+        .configureInbox(ETHEREUM_CHAIN_ID, next, window)
+        .accounts({
+          owner: relayer,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    await configureInbox(new anchor.BN(400), 8);
+
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const post = (nonce: anchor.BN, poster?: Keypair) =>
+      program.methods
+        .postAttestation(nonce, user.publicKey, new anchor.BN(1000))
+        .accounts({
+          relayer: poster ? poster.publicKey : relayer,
+          bridgeState: bridgeState,
+          optimisticConfig: pda(Buffer.from('optimistic_config')),
+          tokenConfig: tokenConfigPda(wrappedMint),
+          attestation: pda(Buffer.from('attestation'), nonceSeed(nonce)),
+          transferRecord: transferPda('in', nonce),
+          bondVault: pda(Buffer.from('bond_vault'), nonceSeed(nonce)),
+          ejection: pda(Buffer.from('ejected'), (poster ? poster.publicKey : relayer).toBuffer()),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers(poster ? [poster] : [])
+        .rpc();
+    const challenge = (nonce: anchor.BN, evidence: string) =>
+      program.methods
+        .challenge(nonce, Buffer.from(evidence))
+        .accounts({
+          bridgeState: bridgeState,
+          challenger: watcher.publicKey,
+          attestation: pda(Buffer.from('attestation'), nonceSeed(nonce)),
+          transferRecord: transferPda('in', nonce),
+          bondVault: pda(Buffer.from('bond_vault'), nonceSeed(nonce)),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([watcher])
+        .rpc();
+    const resolve = (nonce: anchor.BN, outcome: object, poster: PublicKey) =>
+      program.methods
+        .resolveChallenge(nonce, outcome)
+        .accounts({
+          bridgeState: bridgeState,
+          resolver: relayer,
+          guardianSet: guardianSetPda(),
+          optimisticConfig: pda(Buffer.from('optimistic_config')),
+          attestation: pda(Buffer.from('attestation'), nonceSeed(nonce)),
+          transferRecord: transferPda('in', nonce),
+          relayer: poster,
+          challenger: watcher.publicKey,
+          bondVault: pda(Buffer.from('bond_vault'), nonceSeed(nonce)),
+          treasury,
+          relayerStats: null,
+          ejection: pda(Buffer.from('ejected'), poster.toBuffer()),
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+        .signers([guardian])
+        .rpc();
+    const balance = (account: PublicKey) => provider.connection.getBalance(account);
+    const execute = (nonce: anchor.BN, mintToUse = wrappedMint) =>
+      program.methods
+        .executeAttestation(nonce)
+        .accounts({
+          executor: relayer,
+          bridgeState: bridgeState,
+          attestation: pda(Buffer.from('attestation'), nonceSeed(nonce)),
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(relayer),
+          relayer,
+          ejection: pda(Buffer.from('ejected'), relayer.toBuffer()),
+          bondVault: pda(Buffer.from('bond_vault'), nonceSeed(nonce)),
+          user: user.publicKey,
+          wrappedMint: mintToUse,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+
+    const [settled, vetoed] = [new anchor.BN(400), new anchor.BN(401)];
+    await post(settled);
+
+    const attestation = await program.account.attestation.fetch(
+      pda(Buffer.from('attestation'), nonceSeed(settled))
+    );
+    assert.ok(attestation.mint.equals(wrappedMint));
+    assert.ok(attestation.recipient.equals(user.publicKey));
+    assert.ok('pending' in attestation.status);
+    assert.equal(
+      await provider.connection.getBalance(pda(Buffer.from('bond_vault'), nonceSeed(settled))),
+      bond.toNumber()
+    );
+    const record = await program.account.transferRecord.fetch(transferPda('in', settled));
+    assert.ok('attested' in record.status);
+
+    // Inside the window nothing executes
+    try {
+      await execute(settled);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ChallengePeriodActive'));
+    }
+
+    // A watcher freezes the other one
+    await post(vetoed);
+    await program.methods
+      .veto(vetoed)
+      .accounts({
+        bridgeState: bridgeState,
+        watcher: watcher.publicKey,
+        watcherInfo: pda(Buffer.from('watcher'), watcher.publicKey.toBuffer()),
+        attestation: pda(Buffer.from('attestation'), nonceSeed(vetoed)),
+        transferRecord: transferPda('in', vetoed),
+      })
+      .signers([watcher])
+      .rpc();
+    const frozen = await program.account.attestation.fetch(
+      pda(Buffer.from('attestation'), nonceSeed(vetoed))
+    );
+    assert.ok('vetoed' in frozen.status);
+
+    // Challenges escrow the challenger's matching bond next to the relayer's
+    const [upheld, fraudulent] = [new anchor.BN(402), new anchor.BN(403)];
+    await post(upheld);
+    await challenge(upheld, 'wrong amount');
+    await post(fraudulent, fraudster);
+    await challenge(fraudulent, 'no such lock');
+    for (const nonce of [upheld, fraudulent]) {
+      const challenged = await program.account.attestation.fetch(
+        pda(Buffer.from('attestation'), nonceSeed(nonce))
+      );
+      assert.ok('challenged' in challenged.status);
+      assert.ok(challenged.challenger.equals(watcher.publicKey));
+      assert.equal(challenged.challengerBond.toNumber(), bond.toNumber());
+      assert.equal(await balance(pda(Buffer.from('bond_vault'), nonceSeed(nonce))), 2 * bond.toNumber());
+    }
+
+    // Upheld: the relayer takes the challenger's bond less the 10% cut,
+    // its own stays escrowed until execution
+    const cut = bond.toNumber() / 10;
+    await resolve(upheld, { valid: {} }, relayer);
+    assert.equal(await balance(treasury), cut);
+    assert.equal(await balance(pda(Buffer.from('bond_vault'), nonceSeed(upheld))), bond.toNumber());
+    const reinstated = await program.account.attestation.fetch(
+      pda(Buffer.from('attestation'), nonceSeed(upheld))
+    );
+    assert.ok('pending' in reinstated.status);
+
+    // Fraud: the challenger takes both bonds less the cut, and the
+    // relayer is ejected and leaves the guardian set
+    const watcherBefore = await balance(watcher.publicKey);
+    await resolve(fraudulent, { fraud: {} }, fraudster.publicKey);
+    assert.equal(await balance(treasury), 2 * cut);
+    assert.equal(await balance(pda(Buffer.from('bond_vault'), nonceSeed(fraudulent))), 0);
+    assert.isAtLeast(await balance(watcher.publicKey), watcherBefore + 2 * bond.toNumber() - cut);
+    assert.isNull(
+      await provider.connection.getAccountInfo(pda(Buffer.from('attestation'), nonceSeed(fraudulent)))
+    );
+    assert.isNotNull(
+      await provider.connection.getAccountInfo(pda(Buffer.from('ejected'), fraudster.publicKey.toBuffer()))
+    );
+    const guardians = await program.account.guardianSet.fetch(guardianSetPda());
+    assert.deepEqual(guardians.guardians.map((g: PublicKey) => g.toBase58()), [guardian.publicKey.toBase58()]);
+    const rejected = await program.account.transferRecord.fetch(transferPda('in', fraudulent));
+    assert.ok('vetoed' in rejected.status);
+
+    await new Promise((resolve) => setTimeout(resolve, 3000));
+
+    // Past the window it can't be challenged any more
+    try {
+      await challenge(settled, 'late');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ChallengePeriodOver'));
+    }
+
+    // A veto outlasts the window
+    try {
+      await execute(vetoed);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidAttestationStatus'));
+    }
+
+    // Only the attested mint is minted
+    const otherMint = await createMint(provider.connection, user, bridgeAuthority, null, 9);
+    try {
+      await execute(settled, otherMint);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('TransferMismatch'));
+    }
+
+    await execute(settled);
+    assert.equal((await getAccount(provider.connection, userWrappedAccount)).amount.toString(), '1000');
+    const executed = await program.account.attestation.fetch(
+      pda(Buffer.from('attestation'), nonceSeed(settled))
+    );
+    assert.ok('executed' in executed.status);
+    // The relayer's bond went back to it
+    assert.equal(
+      await provider.connection.getBalance(pda(Buffer.from('bond_vault'), nonceSeed(settled))),
+      0
+    );
+
+    // Guardians lift the veto; it and the upheld one execute
+    await program.methods
+      .liftVeto(vetoed)
+      .accounts({
+        bridgeState: bridgeState,
+        guardianSet: guardianSetPda(),
+        attestation: pda(Buffer.from('attestation'), nonceSeed(vetoed)),
+        transferRecord: transferPda('in', vetoed),
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+    const lifted = await program.account.attestation.fetch(
+      pda(Buffer.from('attestation'), nonceSeed(vetoed))
+    );
+    assert.ok('pending' in lifted.status);
+    await execute(vetoed);
+    await execute(upheld);
+    assert.equal((await getAccount(provider.connection, userWrappedAccount)).amount.toString(), '3000');
+    assert.equal(await balance(pda(Buffer.from('bond_vault'), nonceSeed(upheld))), 0);
+
+    await program.methods
+      .setTrustModel({ ownerSignature: {} })
+      .accounts({
+        owner: relayer,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        tokenConfig: tokenConfigPda(wrappedMint),
+      })
+      .rpc();
+    await configureInbox(inbox.nextSequence, inbox.window);
+
+    console.log('✓ Attestations vetoed, challenged, resolved and executed after their window');
+  });
+
+  it('Posts proof-of-reserve attestations for a wrapped token', async () => {
+    const guardian = Keypair.generate();
+    const guardianSet = PublicKey.findProgramAddressSync(
+      [Buffer.from('guardian_set')],
+      program.programId
+    )[0];
+    const reserveAttestation = PublicKey.findProgramAddressSync(
+      [Buffer.from('reserve'), wrappedMint.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const post = (locked: number, block: number) =>
+      program.methods
+        .postReserveAttestation(new anchor.BN(locked), new anchor.BN(block))
+        .accounts({
+          bridgeState: bridgeState,
+          payer: provider.wallet.publicKey,
+          guardianSet: guardianSet,
+          mint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          reserveAttestation: reserveAttestation,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+        .signers([guardian])
+        .rpc();
+
+    await post(1000, 19000000);
+
+    // Older blocks can't overwrite a newer attestation
+    try {
+      await post(5000, 18999999);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('StaleReserveAttestation'));
+    }
+
+    const status = await program.methods
+      .reserveStatus()
+      .accounts({
+        bridgeState: bridgeState,
+        mint: wrappedMint,
+        reserveAttestation: reserveAttestation,
+      })
+      .view();
+    const supply = (await getMint(provider.connection, wrappedMint)).supply;
+
+    assert.equal(status.attestedReserve.toString(), '1000');
+    assert.equal(status.blockNumber.toString(), '19000000');
+    assert.equal(status.wrappedSupply.toString(), supply.toString());
+    const deficit = supply > BigInt(1000) ? supply - BigInt(1000) : BigInt(0);
+    assert.equal(status.deficit.toString(), deficit.toString());
+
+    console.log('✓ Reserve attested');
+  });
+
+  it('Attests the escrow balance to the EVM side through the outbox', async () => {
+    const guardian = Keypair.generate();
+    const guardianSet = PublicKey.findProgramAddressSync(
+      [Buffer.from('guardian_set')],
+      program.programId
+    )[0];
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const attest = (signers: Keypair[]) =>
+      program.methods
+        .attestVaultBalance()
+        .accounts({
+          relayer: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          mint: mint,
+          conversionRate: null,
+          escrow: bridgeTokenAccount,
+          coldVault: null,
+        })
+        .remainingAccounts(
+          signers.map((s) => ({ pubkey: s.publicKey, isSigner: true, isWritable: false }))
+        )
+        .signers(signers)
+        .rpc();
+
+    // The program can't tell the escrow apart; guardians vouch for it
+    try {
+      await attest([]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InsufficientGuardianSignatures'));
+    }
+
+    await attest([guardian]);
+
+    // Queued for Ethereum as kind 3 = VaultBalance
+    const outbox = await program.account.outbox.fetch(outboxPda());
+    const sequence = outbox.nextSequence.subn(1);
+    const entry = outbox.entries[sequence.modn(outbox.entries.length)];
+    const escrow = await getAccount(provider.connection, bridgeTokenAccount);
+    assert.equal(entry.kind, 3);
+    assert.ok(entry.mint.equals(mint));
+    assert.ok(entry.sender.equals(bridgeTokenAccount));
+    assert.equal(entry.amount.toString(), escrow.amount.toString());
+
+    console.log('✓ Escrow balance queued for Ethereum');
+  });
+
+  it('Checkpoints finished transfers and prunes their records', async () => {
+    const index = new anchor.BN(0);
+    const checkpointLog = PublicKey.findProgramAddressSync(
+      [Buffer.from('checkpoint_log')],
+      program.programId
+    )[0];
+    const checkpoint = PublicKey.findProgramAddressSync(
+      [Buffer.from('checkpoint'), index.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    const recordPda = transferPda('in', new anchor.BN(1));
+    const record = await program.account.transferRecord.fetch(recordPda);
+
+    await program.methods
+      .checkpoint()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        checkpointLog: checkpointLog,
+        checkpoint: checkpoint,
+        systemProgram: SystemProgram.programId,
+      })
+      .remainingAccounts([{ pubkey: recordPda, isSigner: false, isWritable: false }])
+      .rpc();
+
+    const committed = await program.account.checkpoint.fetch(checkpoint);
+    assert.equal(committed.transferCount, 1);
+    assert.equal(committed.inboundVolume.toString(), record.amount.toString());
+
+    const prune = (proof: number[][]) =>
+      program.methods
+        .pruneTransferRecord(index, proof)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          checkpoint: checkpoint,
+          transferRecord: recordPda,
+        })
+        .rpc();
+
+    try {
+      await prune([Array(32).fill(0)]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidMerkleProof'));
+    }
+
+    // A one-leaf tree's root is the leaf itself
+    await prune([]);
+    assert.isNull(await provider.connection.getAccountInfo(recordPda));
+
+    console.log('✓ Transfer record pruned after checkpoint');
+
+    // Replay records: inbound nonce 1 is checkpointed, nonce 0 was never delivered
+    const log = await program.account.checkpointLog.fetch(checkpointLog);
+    assert.equal(log.inboundNonceEnd.toNumber(), 2);
+
+    const pruneNonces = (beforeNonce: number) =>
+      program.methods
+        .prune(new anchor.BN(beforeNonce), 10)
+        .accounts({ bridgeState: bridgeState, checkpointLog: checkpointLog })
+        .rpc();
+
+    try {
+      await pruneNonces(3);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NotCheckpointed'));
+    }
+
+    try {
+      await pruneNonces(2);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('UnprocessedNonce'));
+    }
+
+    try {
+      await pruneNonces(0);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NothingToPrune'));
+    }
+
+    const state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.processedFloor.toNumber(), 0);
+    assert.ok(state.processedNonces.some((n) => n.toNumber() === 1));
+
+    console.log('✓ Nonce pruning refuses uncheckpointed and undelivered ranges');
+  });
+
+  it('Anchors event archives in a hash chain', async () => {
+    const archiver = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(archiver.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const archiveLog = PublicKey.findProgramAddressSync(
+      [Buffer.from('archive_log')],
+      program.programId
+    )[0];
+    const archivePda = (index: number) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('archive'), new anchor.BN(index).toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      )[0];
+
+    await program.methods
+      .setArchiver(archiver.publicKey)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        archiveLog: archiveLog,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const record = (
+      index: number,
+      hash: number[],
+      first: number,
+      last: number,
+      signer = archiver
+    ) =>
+      program.methods
+        .recordArchive(hash, new anchor.BN(first), new anchor.BN(last), `ipfs://bundle-${index}`)
+        .accounts({
+          authority: signer.publicKey,
+          bridgeState: bridgeState,
+          archiveLog: archiveLog,
+          archive: archivePda(index),
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([signer])
+        .rpc();
+
+    const first = Array(32).fill(1);
+    const second = Array(32).fill(2);
+    await record(0, first, 0, 100);
+
+    // Only the archiver (or owner) records, and ranges can't overlap
+    try {
+      await record(1, second, 101, 200, user);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+    try {
+      await record(1, second, 100, 200);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidArchive'));
+    }
+
+    await record(1, second, 101, 200);
+
+    const archive = await program.account.archive.fetch(archivePda(1));
+    assert.deepEqual(archive.hash, second);
+    assert.deepEqual(archive.prevHash, first);
+    assert.equal(archive.locator, 'ipfs://bundle-1');
+    const log = await program.account.archiveLog.fetch(archiveLog);
+    assert.equal(log.count.toNumber(), 2);
+    assert.equal(log.lastSlot.toNumber(), 200);
+
+    console.log('✓ Event archives chained on-chain');
+  });
+
+  it('Mints with a guardian quorum instead of the owner', async () => {
+    const guardians = [Keypair.generate(), Keypair.generate()];
+    const coordinator = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(coordinator.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+
+    await program.methods
+      .setGuardians(guardians.map((g) => g.publicKey), 2)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const nonce = new anchor.BN(202);
+    const userWrappedAccount = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    // The coordinator isn't the owner; only the guardians' signatures
+    // authorize the mint
+    const mintWith = (signers: Keypair[]) =>
+      program.methods
+        .mint(new anchor.BN(1000), nonce)
+        .accounts({
+          user: user.publicKey,
+          authority: coordinator.publicKey,
+          bridgeState: bridgeState,
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          credential: null,
+          vaa: null,
+          userToken: userWrappedAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('in', nonce),
+          inbox: inboxPda(ETHEREUM_CHAIN_ID),
+          sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          guardianSet: guardianSetPda(),
+          ...rewardAccounts(coordinator.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          signers.map((s) => ({ pubkey: s.publicKey, isSigner: true, isWritable: false }))
+        )
+        .signers([coordinator, ...signers])
+        .rpc();
+
+    try {
+      await mintWith([guardians[0]]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await mintWith(guardians);
+
+    const account = await getAccount(provider.connection, userWrappedAccount);
+    assert.equal(account.amount.toString(), '1000');
+
+    console.log('✓ Minted with a guardian quorum');
+  });
+
+  it('Queues redemptions pro rata while collateral is short', async () => {
+    const guardian = Keypair.generate();
+    const guardianSet = guardianSetPda();
+    const ethRecipient = '0x1234567890123456789012345678901234567890';
+    const scale = new anchor.BN(1_000_000_000);
+    const redemptionQueue = PublicKey.findProgramAddressSync(
+      [Buffer.from('redemption_queue'), wrappedMint.toBuffer()],
+      program.programId
+    )[0];
+    const claimPda = (id: anchor.BN) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('redemption_claim'), wrappedMint.toBuffer(), id.toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      )[0];
+
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSet,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const post = (locked: anchor.BN, block: number) =>
+      program.methods
+        .postReserveAttestation(locked, new anchor.BN(block))
+        .accounts({
+          bridgeState: bridgeState,
+          payer: provider.wallet.publicKey,
+          guardianSet: guardianSet,
+          mint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          redemptionQueue,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+        .signers([guardian])
+        .rpc();
+    const supplyNow = async () =>
+      new anchor.BN((await getMint(provider.connection, wrappedMint)).supply.toString());
+
+    // The user's 1000 wrapped tokens from the guardian-quorum mint
+    const accounts = await provider.connection.getTokenAccountsByOwner(user.publicKey, {
+      mint: wrappedMint,
+    });
+    let userToken: PublicKey;
+    for (const { pubkey } of accounts.value) {
+      if ((await getAccount(provider.connection, pubkey)).amount === BigInt(1000)) {
+        userToken = pubkey;
+      }
+    }
+
+    // Half the supply is backed: the queue turns on at a ratio of 1/2
+    const supply = await supplyNow();
+    await post(supply.divn(2), 19000100);
+    let queue = await program.account.redemptionQueue.fetch(redemptionQueue);
+    assert.equal(queue.active, true);
+    const ratio = supply.divn(2).mul(scale).div(supply);
+    assert.equal(queue.paidRatio.toString(), ratio.toString());
+
+    // Plain burns are refused while the queue is on
+    const nonce = await nextOutNonce(wrappedMint, ETHEREUM_CHAIN_ID);
+    try {
+      await program.methods
+        .burn(new anchor.BN(1000), ETHEREUM_CHAIN_ID, ethRecipient, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSet,
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          wrappedMint: wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          redemptionQueue,
+          userToken,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('RedemptionQueueActive'));
+    }
+
+    const claimId = queue.nextClaimId;
+    const claim = claimPda(claimId);
+    await program.methods
+      .enqueueRedemption(new anchor.BN(1000), ETHEREUM_CHAIN_ID, ethRecipient)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        redemptionQueue,
+        claim,
+        userToken,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+    assert.equal((await getAccount(provider.connection, userToken)).amount.toString(), '0');
+
+    const release = () =>
+      program.methods
+        .releaseRedemption(claimId)
+        .accounts({ bridgeState: bridgeState, redemptionQueue, claim })
+        .rpc();
+    const confirm = (block: number) =>
+      program.methods
+        .confirmRedemption(claimId, new anchor.BN(block))
+        .accounts({
+          authority: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          guardianSet: guardianSet,
+          redemptionQueue,
+          claim,
+          claimOwner: user.publicKey,
+        })
+        .rpc();
+
+    // The claim draws its share now, and no more until the ratio rises
+    await release();
+    const firstPayout = new anchor.BN(1000).mul(ratio).div(scale);
+    let claimed = await program.account.redemptionClaim.fetch(claim);
+    assert.equal(claimed.released.toString(), firstPayout.toString());
+    try {
+      await release();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NothingToRedeem'));
+    }
+    await confirm(19000150);
+
+    // Collateral restored: the rest is released, and the claim closes once paid
+    const remaining = await supplyNow();
+    await post(remaining.add(firstPayout), 19000200);
+    queue = await program.account.redemptionQueue.fetch(redemptionQueue);
+    assert.equal(queue.paidRatio.toString(), scale.toString());
+    await release();
+    claimed = await program.account.redemptionClaim.fetch(claim);
+    assert.equal(claimed.released.toString(), '1000');
+    await confirm(19000250);
+    assert.equal(await provider.connection.getAccountInfo(claim), null);
+
+    // With every claim paid and the supply covered, the queue turns off
+    await post(remaining, 19000300);
+    queue = await program.account.redemptionQueue.fetch(redemptionQueue);
+    assert.equal(queue.active, false);
+    assert.equal(queue.claimed.toNumber(), 0);
+
+    console.log('✓ Redemptions queued and paid pro rata');
+  });
+
+  it('Publishes a wrapped token\'s health in its token oracle', async () => {
+    const sourceToken = Buffer.from('a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48', 'hex');
+    const [wrappedUsdc] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_mint'), sourceToken],
+      program.programId
+    );
+    const [tokenOracle] = PublicKey.findProgramAddressSync(
+      [Buffer.from('token_oracle'), wrappedUsdc.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: wrappedUsdc,
+        tokenConfig: tokenConfigPda(wrappedUsdc),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .initTokenOracle()
+      .accounts({
+        bridgeState: bridgeState,
+        payer: user.publicKey,
+        wrappedMint: wrappedUsdc,
+        tokenOracle,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    const refresh = () =>
+      program.methods
+        .refreshTokenOracle()
+        .accounts({
+          bridgeState: bridgeState,
+          wrappedMint: wrappedUsdc,
+          tokenConfig: tokenConfigPda(wrappedUsdc),
+          originChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+          guardianCouncil: guardianCouncilPda(),
+          tokenOracle,
+        })
+        .rpc();
+    await refresh();
+
+    let oracle = await program.account.tokenOracle.fetch(tokenOracle);
+    assert.equal(oracle.version, 1);
+    assert.equal(oracle.originChainId.toString(), ETHEREUM_CHAIN_ID.toString());
+    assert.deepEqual(Buffer.from(oracle.originAddress).subarray(12), sourceToken);
+    assert.equal(oracle.originDecimals, 6);
+    assert.equal(oracle.status, 0); // Unattested
+    assert.equal(oracle.flags, 0);
+    assert.equal(oracle.collateralBps.toString(), '18446744073709551615'); // no supply
+
+    // A pause shows up on the next refresh
+    const setPaused = (paused: boolean) =>
+      (paused ? program.methods.pause() : program.methods.unpause())
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+        })
+        .rpc();
+    await setPaused(true);
+    await refresh();
+    oracle = await program.account.tokenOracle.fetch(tokenOracle);
+    assert.equal(oracle.flags & 1, 1); // BRIDGE_PAUSED
+    await setPaused(false);
+    await refresh();
+
+    console.log('✓ Token oracle published');
+  });
+
+  it('Keeps escrow over the hot vault cap in the cold vault', async () => {
+    const connection = provider.connection;
+    const coldAuthority = PublicKey.findProgramAddressSync(
+      [Buffer.from('cold_vault')],
+      program.programId
+    )[0];
+    const vaultMint = await createMint(connection, user, user.publicKey, null, 6);
+    const hotVault = await createAccount(
+      connection,
+      user,
+      vaultMint,
+      bridgeAuthority,
+      Keypair.generate()
+    );
+    const coldVault = await createAccount(
+      connection,
+      user,
+      vaultMint,
+      coldAuthority,
+      Keypair.generate()
+    );
+    await mintTo(connection, user, vaultMint, hotVault, user, 1500000);
+    await mintTo(connection, user, vaultMint, coldVault, user, 3000000);
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: vaultMint,
+        tokenConfig: tokenConfigPda(vaultMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .configureVaults(new anchor.BN(2000000), new anchor.BN(3600))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: vaultMint,
+        hotVault,
+        coldVault,
+        bridgeAuthority: bridgeAuthority,
+        coldAuthority,
+        vaultConfig: vaultConfigPda(vaultMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const vaultAccounts = {
+      bridgeState: bridgeState,
+      mint: vaultMint,
+      tokenConfig: tokenConfigPda(vaultMint),
+      vaultConfig: vaultConfigPda(vaultMint),
+      hotVault,
+      coldVault,
+      bridgeAuthority: bridgeAuthority,
+      tokenProgram: TOKEN_PROGRAM_ID,
+    };
+    const rebalance = () =>
+      program.methods.rebalanceVault().accounts(vaultAccounts).rpc();
+
+    // Under the cap: a refill is queued, and can't run before its delay
+    await rebalance();
+    let config = await program.account.vaultConfig.fetch(vaultConfigPda(vaultMint));
+    assert.equal(config.refillAmount.toString(), '500000');
+
+    try {
+      await program.methods
+        .executeRefill()
+        .accounts({
+          bridgeState: bridgeState,
+          mint: vaultMint,
+          tokenConfig: tokenConfigPda(vaultMint),
+          vaultConfig: vaultConfigPda(vaultMint),
+          hotVault,
+          coldVault,
+          coldAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('RefillNotReady'));
+    }
+
+    await program.methods
+      .cancelRefill()
+      .accounts({
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        guardianSet: guardianSetPda(),
+        vaultConfig: vaultConfigPda(vaultMint),
+      })
+      .rpc();
+    config = await program.account.vaultConfig.fetch(vaultConfigPda(vaultMint));
+    assert.equal(config.refillAmount.toString(), '0');
+
+    // Over it: the excess goes cold at once
+    await mintTo(connection, user, vaultMint, hotVault, user, 2000000);
+    await rebalance();
+    assert.equal((await getAccount(connection, hotVault)).amount.toString(), '2000000');
+    assert.equal((await getAccount(connection, coldVault)).amount.toString(), '4500000');
+
+    // The policy steers the crank: a share of the escrow, refills limited per epoch
+    const setPolicy = (targetBps: number, maxRefillPerEpoch: number) =>
+      program.methods
+        .setVaultPolicy(targetBps, new anchor.BN(maxRefillPerEpoch))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          vaultConfig: vaultConfigPda(vaultMint),
+        })
+        .rpc();
+
+    await setPolicy(2000, 100000);
+    await rebalance();
+    assert.equal((await getAccount(connection, hotVault)).amount.toString(), '1300000');
+
+    await setPolicy(5000, 100000);
+    await rebalance();
+    config = await program.account.vaultConfig.fetch(vaultConfigPda(vaultMint));
+    assert.equal(config.refillAmount.toString(), '100000');
+
+    console.log('✓ Hot vault capped, cold refills timelocked and rate-limited');
+  });
+
+  it('Timelocks yield venues for cold-vault collateral', async () => {
+    const connection = provider.connection;
+    const coldAuthority = PublicKey.findProgramAddressSync(
+      [Buffer.from('cold_vault')],
+      program.programId
+    )[0];
+    const yieldMint = await createMint(connection, user, user.publicKey, null, 6);
+    const hotVault = await createAccount(
+      connection,
+      user,
+      yieldMint,
+      bridgeAuthority,
+      Keypair.generate()
+    );
+    const coldVault = await createAccount(
+      connection,
+      user,
+      yieldMint,
+      coldAuthority,
+      Keypair.generate()
+    );
+    await mintTo(connection, user, yieldMint, coldVault, user, 1000000);
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: yieldMint,
+        tokenConfig: tokenConfigPda(yieldMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .configureVaults(new anchor.BN(500000), new anchor.BN(3600))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: yieldMint,
+        hotVault,
+        coldVault,
+        bridgeAuthority: bridgeAuthority,
+        coldAuthority,
+        vaultConfig: vaultConfigPda(yieldMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Any executable stands in for an adapter; nothing reaches it here
+    const adapter = TOKEN_PROGRAM_ID;
+    const yieldStrategy = PublicKey.findProgramAddressSync(
+      [Buffer.from('yield_strategy'), yieldMint.toBuffer()],
+      program.programId
+    )[0];
+    const venueAuthority = PublicKey.findProgramAddressSync(
+      [Buffer.from('yield_venue'), yieldMint.toBuffer(), adapter.toBuffer()],
+      program.programId
+    )[0];
+    const staging = await createAccount(
+      connection,
+      user,
+      yieldMint,
+      venueAuthority,
+      Keypair.generate()
+    );
+
+    await program.methods
+      .allowYieldVenue(new anchor.BN(150000))
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        vaultConfig: vaultConfigPda(yieldMint),
+        adapter,
+      })
+      .rpc();
+    let strategy = await program.account.yieldStrategy.fetch(yieldStrategy);
+    assert.equal(strategy.venues.length, 1);
+    assert.ok(strategy.venues[0].activeAt.toNumber() > Date.now() / 1000);
+
+    // Not before the timelock is over
+    try {
+      await program.methods
+        .depositYield(new anchor.BN(100000))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          mint: yieldMint,
+          vaultConfig: vaultConfigPda(yieldMint),
+          coldVault,
+          adapter,
+          staging,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('YieldVenueNotAllowed'));
+    }
+    assert.equal((await getAccount(connection, coldVault)).amount.toString(), '1000000');
+
+    // Guardians can take a venue off the list without the owner
+    const guardian = Keypair.generate();
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+    await program.methods
+      .removeYieldVenue(adapter)
+      .accounts({
+        authority: guardian.publicKey,
+        bridgeState: bridgeState,
+        guardianSet: guardianSetPda(),
+        yieldStrategy,
+      })
+      .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+      .signers([guardian])
+      .rpc();
+    strategy = await program.account.yieldStrategy.fetch(yieldStrategy);
+    assert.equal(strategy.venues.length, 0);
+
+    console.log('✓ Yield venues timelocked and removable by guardians');
+  });
+
+  it('Refunds, keeps or rejects dust below the bridged decimals', async () => {
+    const stellarRecipient = 'GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7';
+    const setDustPolicy = (bridgedDecimals: number | null, policy: object) =>
+      program.methods
+        .setDustPolicy(bridgedDecimals, policy)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          tokenConfig: tokenConfigPda(mint),
+        })
+        .rpc();
+    const lock = async (amount: number) => {
+      const nonce = await nextOutNonce(mint, STELLAR_CHAIN_ID);
+      const transferRecord = transferPda('out', nonce);
+      await program.methods
+        .lock(new anchor.BN(amount), STELLAR_CHAIN_ID, stellarRecipient, null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, STELLAR_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord,
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+        })
+        .signers([user])
+        .rpc();
+      return program.account.transferRecord.fetch(transferRecord);
+    };
+    const balance = async (account: PublicKey) =>
+      (await getAccount(provider.connection, account)).amount;
+
+    // The 9-decimal token crosses to Stellar in 7, so 10000050 has 50 of dust
+    await setDustPolicy(7, { reject: {} });
+    try {
+      await lock(10000050);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('AmountNotRepresentable'));
+    }
+
+    // Refund: the dust never leaves the sender (Stellar locks carry no fee)
+    await setDustPolicy(7, { refund: {} });
+    let userBefore = await balance(userTokenAccount);
+    let record = await lock(10000050);
+    assert.equal(record.amount.toString(), '10000000');
+    assert.equal((userBefore - (await balance(userTokenAccount))).toString(), '10000000');
+
+    // Treasury: the dust goes to the fee vault
+    await setDustPolicy(7, { treasury: {} });
+    userBefore = await balance(userTokenAccount);
+    const vaultBefore = await balance(feeVaultAccount);
+    record = await lock(10000050);
+    assert.equal(record.amount.toString(), '10000000');
+    assert.equal((userBefore - (await balance(userTokenAccount))).toString(), '10000050');
+    assert.equal(((await balance(feeVaultAccount)) - vaultBefore).toString(), '50');
+
+    await setDustPolicy(null, { refund: {} });
+
+    console.log('✓ Dust policy applied to locks');
+  });
+
+  it('Takes lock fees in SOL at oracle prices', async () => {
+    const stellarRecipient = 'GAAZI4TCR3TY5OJHCTJC2A4QSY6CJWJH5IAJTGKIN2ER7LBNVKOCCWN7';
+    const [solFeeConfig] = PublicKey.findProgramAddressSync(
+      [Buffer.from('sol_fee'), mint.toBuffer()],
+      program.programId
+    );
+    const [solFeeVault] = PublicKey.findProgramAddressSync(
+      [Buffer.from('sol_fee_vault')],
+      program.programId
+    );
+    const tokenFeed = Buffer.alloc(32, 0xef);
+    const solFeed = Buffer.alloc(32, 0xab);
+    const setSolFee = (feedId: Buffer, markupBps: number, owner = provider.wallet.publicKey) =>
+      program.methods.setSolFee([...feedId], [...solFeed], markupBps).accounts({
+        owner,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        solFeeConfig: solFeeConfig,
+        systemProgram: SystemProgram.programId,
+      });
+    const lock = async (solFee: object) => {
+      const nonce = await nextOutNonce(mint, STELLAR_CHAIN_ID);
+      await program.methods
+        .lock(new anchor.BN(10000000), STELLAR_CHAIN_ID, stellarRecipient, null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, STELLAR_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          solFeeConfig: solFeeConfig,
+          solFeeVault: null,
+          tokenPrice: null,
+          solPrice: null,
+          ...solFee,
+        })
+        .signers([user])
+        .rpc();
+    };
+    const withdraw = (amount: number) =>
+      program.methods
+        .withdrawSolFees(new anchor.BN(amount))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          solFeeVault: solFeeVault,
+          destination: provider.wallet.publicKey,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await expectError(
+      () => setSolFee(tokenFeed, 100, user.publicKey).signers([user]).rpc(),
+      'Unauthorized'
+    );
+    await expectError(() => setSolFee(tokenFeed, 10001).rpc(), 'InvalidConfig');
+
+    await setSolFee(tokenFeed, 100).rpc();
+    const config = await program.account.solFeeConfig.fetch(solFeeConfig);
+    assert.ok(config.mint.equals(mint));
+    assert.deepEqual(Buffer.from(config.feedId), tokenFeed);
+    assert.equal(config.markupBps, 100);
+
+    // The vault and both prices come with the config, and prices must be Pyth's
+    await expectError(() => lock({}), 'SolFeeAccountsRequired');
+    const priced = { solFeeVault, tokenPrice: mint, solPrice: mint };
+    await expectError(() => lock(priced), 'InvalidPriceFeed');
+
+    await setSolFee(Buffer.alloc(32), 0).rpc();
+    await expectError(() => lock(priced), 'SolFeeDisabled');
+
+    // Withdrawals leave the vault rent exempt
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(0);
+    await expectError(() => withdraw(1), 'InsufficientSolFees');
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        SystemProgram.transfer({
+          fromPubkey: provider.wallet.publicKey,
+          toPubkey: solFeeVault,
+          lamports: rent + 5000,
+        })
+      )
+    );
+    await withdraw(5000);
+    assert.equal(await provider.connection.getBalance(solFeeVault), rent);
+    await expectError(() => withdraw(1), 'InsufficientSolFees');
+
+    console.log('✓ SOL fee mode configured, checked and withdrawn');
+  });
+
+  it('Prepays delivery on the destination at oracle prices', async () => {
+    const ethRecipient = '0x742d35Cc6634C0532925a3b844Bc9e7595f0bEb1';
+    const deliveryFeeConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from('delivery_fee'), ETHEREUM_CHAIN_ID.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    const rewardVault = PublicKey.findProgramAddressSync(
+      [Buffer.from('reward_vault')],
+      program.programId
+    )[0];
+    const ethFeed = Buffer.alloc(32, 0xee);
+    const solFeed = Buffer.alloc(32, 0xab);
+    const setDeliveryFee = (markupBps: number, owner = provider.wallet.publicKey) =>
+      program.methods
+        .setDeliveryFee(
+          ETHEREUM_CHAIN_ID, [...ethFeed], 18, [...solFeed], new anchor.BN(150000), markupBps
+        )
+        .accounts({
+          owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          deliveryFeeConfig: deliveryFeeConfig,
+          systemProgram: SystemProgram.programId,
+        });
+    const setGasPrice = (gasPrice: number, authority = provider.wallet.publicKey) =>
+      program.methods
+        .setDeliveryGasPrice(ETHEREUM_CHAIN_ID, new anchor.BN(gasPrice))
+        .accounts({
+          authority,
+          bridgeState: bridgeState,
+          deliveryFeeConfig: deliveryFeeConfig,
+        });
+    const lock = async (delivery: object) => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .lock(new anchor.BN(10000000), ETHEREUM_CHAIN_ID, ethRecipient, null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          solFeeConfig: null,
+          solFeeVault: null,
+          tokenPrice: null,
+          solPrice: null,
+          deliveryFeeConfig: deliveryFeeConfig,
+          rewardVault: null,
+          nativePrice: null,
+          ...delivery,
+        })
+        .signers([user])
+        .rpc();
+    };
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await expectError(
+      () => setDeliveryFee(100, user.publicKey).signers([user]).rpc(),
+      'Unauthorized'
+    );
+    await expectError(() => setDeliveryFee(10001).rpc(), 'InvalidConfig');
+
+    await setDeliveryFee(500).rpc();
+    let config = await program.account.deliveryFeeConfig.fetch(deliveryFeeConfig);
+    assert.ok(config.chainId.eq(ETHEREUM_CHAIN_ID));
+    assert.deepEqual(Buffer.from(config.nativeFeedId), ethFeed);
+    assert.equal(config.nativeDecimals, 18);
+    assert.equal(config.gasLimit.toNumber(), 150000);
+    assert.equal(config.gasPrice.toNumber(), 0);
+
+    // The vault and both prices come with the config, and nothing is
+    // quoted before a gas price is set
+    await expectError(() => lock({}), 'DeliveryFeeAccountsRequired');
+    const priced = { rewardVault, nativePrice: mint, solPrice: mint };
+    await expectError(() => lock(priced), 'DeliveryFeeDisabled');
+
+    // The owner or fee manager keeps the gas price current
+    await expectError(
+      () => setGasPrice(20_000_000_000, user.publicKey).signers([user]).rpc(),
+      'Unauthorized'
+    );
+    await setGasPrice(20_000_000_000).rpc();
+    config = await program.account.deliveryFeeConfig.fetch(deliveryFeeConfig);
+    assert.equal(config.gasPrice.toNumber(), 20_000_000_000);
+    assert.ok(config.gasPriceUpdatedAt.toNumber() > 0);
+
+    // Prices must be Pyth's
+    await expectError(() => lock(priced), 'InvalidPriceFeed');
+
+    console.log('✓ Delivery prepayment configured and checked');
+  });
+
+  it('Configures a price circuit breaker for a token', async () => {
+    const priceBreaker = PublicKey.findProgramAddressSync(
+      [Buffer.from('price_breaker'), mint.toBuffer()],
+      program.programId
+    )[0];
+    const feed = Buffer.alloc(32, 0xef);
+    const setBreaker = (feedId: Buffer, maxMoveBps: number, owner = provider.wallet.publicKey) =>
+      program.methods
+        .setPriceBreaker([...feedId], maxMoveBps, new anchor.BN(300), new anchor.BN(3600))
+        .accounts({
+          owner,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          priceBreaker: priceBreaker,
+          systemProgram: SystemProgram.programId,
+        });
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await expectError(
+      () => setBreaker(feed, 1000, user.publicKey).signers([user]).rpc(),
+      'Unauthorized'
+    );
+    await expectError(() => setBreaker(feed, 0).rpc(), 'InvalidConfig');
+
+    await setBreaker(feed, 1000).rpc();
+    const breaker = await program.account.priceBreaker.fetch(priceBreaker);
+    assert.ok(breaker.mint.equals(mint));
+    assert.equal(breaker.maxMoveBps, 1000);
+    assert.equal(breaker.window.toNumber(), 300);
+    assert.equal(breaker.trippedUntil.toNumber(), 0);
+
+    // Only Pyth's price updates feed it
+    await expectError(
+      () =>
+        program.methods
+          .pokePriceBreaker()
+          .accounts({
+            bridgeState: bridgeState,
+            mint: mint,
+            priceBreaker: priceBreaker,
+            priceUpdate: mint,
+          })
+          .rpc(),
+      'InvalidPriceFeed'
+    );
+
+    // A feed of all zeros turns it off
+    await setBreaker(Buffer.alloc(32), 1000).rpc();
+
+    console.log('✓ Price breaker configured and checked');
+  });
+
+  it('Records a token\'s recent transfers in its event history', async () => {
+    const eventHistory = PublicKey.findProgramAddressSync(
+      [Buffer.from('event_history'), mint.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .initEventHistory()
+      .accounts({
+        bridgeState: bridgeState,
+        payer: provider.wallet.publicKey,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        eventHistory,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x6666666666666666666666666666666666666666', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        eventHistory,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+        solFeeConfig: null,
+        solFeeVault: null,
+        tokenPrice: null,
+        solPrice: null,
+        deliveryFeeConfig: null,
+        rewardVault: null,
+        nativePrice: null,
+      })
+      .signers([user])
+      .rpc();
+
+    // The lock is entry 0, with the same ID as its transfer record
+    const history = await program.account.eventHistory.fetch(eventHistory);
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok(history.mint.equals(mint));
+    assert.equal(history.nextSequence.toNumber(), 1);
+    const entry = history.entries[0];
+    assert.equal(entry.nonce.toString(), nonce.toString());
+    assert.deepEqual(entry.transferId, record.transferId);
+    assert.ok(entry.account.equals(user.publicKey));
+    assert.equal(entry.direction, 0);
+
+    console.log('✓ Lock recorded in the token\'s event history');
+  });
+
+  it('Rejects devnet_force_mint in default builds', async () => {
+    // Only `anchor build -- --features devnet` enables it
+    const recipientToken = await createAccount(
+      provider.connection,
+      user,
+      wrappedMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+
+    try {
+      await program.methods
+        .devnetForceMint(new anchor.BN(1_000), new anchor.BN(990_001))
+        .accounts({
+          integrator: provider.wallet.publicKey,
+          user: user.publicKey,
+          bridgeState,
+          wrappedMint,
+          tokenConfig: tokenConfigPda(wrappedMint),
+          userToken: recipientToken,
+          bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+      assert.fail('Should have rejected a simulated mint');
+    } catch (error) {
+      assert.include(error.message, 'DevnetOnly');
+    }
+    assert.equal((await getAccount(provider.connection, recipientToken)).amount, BigInt(0));
+
+    console.log('✓ No simulated mints in the default build');
+  });
+
+  it('Requires acknowledged terms of use before a lock', async () => {
+    const termsConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from('terms')],
+      program.programId
+    )[0];
+    const termsAcceptance = PublicKey.findProgramAddressSync(
+      [Buffer.from('terms_ack'), user.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const setTerms = (version: number) =>
+      program.methods
+        .setTerms(version, Array(32).fill(0x7e))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          termsConfig,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const acknowledge = (version: number) =>
+      program.methods
+        .acknowledgeTerms(version)
+        .accounts({
+          bridgeState: bridgeState,
+          wallet: user.publicKey,
+          termsConfig,
+          termsAcceptance,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([user])
+        .rpc();
+    const lock = async () => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x7777777777777777777777777777777777777777', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          termsConfig,
+          termsAcceptance,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          solFeeConfig: null,
+          solFeeVault: null,
+          tokenPrice: null,
+          solPrice: null,
+          deliveryFeeConfig: null,
+          rewardVault: null,
+          nativePrice: null,
+        })
+        .signers([user])
+        .rpc();
+    };
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await setTerms(1);
+    await expectError(lock, 'TermsNotAccepted');
+
+    // Only the version currently required can be acknowledged
+    await expectError(() => acknowledge(2), 'TermsVersionMismatch');
+    await acknowledge(1);
+    const acceptance = await program.account.termsAcceptance.fetch(termsAcceptance);
+    assert.ok(acceptance.wallet.equals(user.publicKey));
+    assert.equal(acceptance.version, 1);
+    await lock();
+
+    // New terms need a new acknowledgement
+    await setTerms(2);
+    await expectError(lock, 'TermsNotAccepted');
+    await acknowledge(2);
+    await lock();
+
+    // Version 0 requires nothing
+    await setTerms(0);
+
+    console.log('✓ Locks gated on acknowledged terms');
+  });
+
+  it('Rebates part of the fee on a transfer acknowledged past the SLA', async () => {
+    const slaConfig = PublicKey.findProgramAddressSync(
+      [Buffer.from('sla')],
+      program.programId
+    )[0];
+    const setSla = (maxLatencySlots: number, rebateBps: number) =>
+      program.methods
+        .setSla(new anchor.BN(maxLatencySlots), rebateBps)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          slaConfig,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await expectError(() => setSla(1, 10001), 'InvalidConfig');
+    await setSla(1, 5000);
+
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    await program.methods
+      .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x8888888888888888888888888888888888888888', null, null, null, null, null)
+      .accounts({
+        user: user.publicKey,
+        bridgeState: bridgeState,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: tokenConfigPda(mint),
+        feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: bridgeTokenAccount,
+        feeVault: feeVaultAccount,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        outbox: outboxPda(),
+        guardianSet: guardianSetPda(),
+        feeTiers: feeTiersPda(),
+        userStats: userStatsPda(user.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+        solFeeConfig: null,
+        solFeeVault: null,
+        tokenPrice: null,
+        solPrice: null,
+        deliveryFeeConfig: null,
+        rewardVault: null,
+        nativePrice: null,
+      })
+      .signers([user])
+      .rpc();
+
+    // Let a few slots pass, past the one-slot SLA
+    await new Promise((resolve) => setTimeout(resolve, 2000));
+    await program.methods
+      .ackOutbound(nonce, Array(32).fill(8))
+      .accounts({
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: recordPda,
+        slaConfig,
+        guardianSet: guardianSetPda(),
+        bridgeAuthority: bridgeAuthority,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+      })
+      .rpc();
+
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok(record.processedSlot.sub(record.createdSlot).gtn(1));
+    assert.ok(record.fee.gtn(0));
+    assert.equal(record.slaRebate.toString(), record.fee.divn(2).toString());
+
+    const claim = () =>
+      program.methods
+        .claimSlaRebate(nonce)
+        .accounts({
+          bridgeState: bridgeState,
+          sender: user.publicKey,
+          transferRecord: recordPda,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeVault: feeVaultAccount,
+          senderToken: userTokenAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .signers([user])
+        .rpc();
+    const before = await getAccount(provider.connection, userTokenAccount);
+    await claim();
+    const after = await getAccount(provider.connection, userTokenAccount);
+    assert.equal((after.amount - before.amount).toString(), record.slaRebate.toString());
+
+    // Paid once
+    await expectError(claim, 'NoSlaRebate');
+    await setSla(0, 0);
+
+    console.log('✓ Late transfer rebated');
+  });
+
+  it('Numbers a token\'s transfers to each chain in its route sequence', async () => {
+    const routeSequence = routeSequencePda(mint, ETHEREUM_CHAIN_ID);
+    const before = await program.account.routeSequence.fetch(routeSequence);
+
+    const lock = async () => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      const recordPda = transferPda('out', nonce);
+      await program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, '0x7777777777777777777777777777777777777777', null, null, null, null, null)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: recordPda,
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          routeSequence,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          solFeeConfig: null,
+          solFeeVault: null,
+          tokenPrice: null,
+          solPrice: null,
+          deliveryFeeConfig: null,
+          rewardVault: null,
+          nativePrice: null,
+        })
+        .signers([user])
+        .rpc();
+      return program.account.transferRecord.fetch(recordPda);
+    };
+
+    // Numbered on from the route's last transfer, in the route's shard
+    const first = await lock();
+    const second = await lock();
+    assert.equal(first.routeSequence.toNumber(), before.last.toNumber() + 1);
+    assert.equal(second.routeSequence.toNumber(), before.last.toNumber() + 2);
+    assert.equal(second.nonce.toString(), first.nonce.addn(1).toString());
+    assert.equal(second.nonce.shrn(SEQUENCE_BITS).toNumber(), before.shard);
+
+    const sequence = await program.account.routeSequence.fetch(routeSequence);
+    assert.ok(sequence.mint.equals(mint));
+    assert.equal(sequence.destChainId.toString(), ETHEREUM_CHAIN_ID.toString());
+    assert.equal(sequence.last.toNumber(), before.last.toNumber() + 2);
+
+    // Every route has its own shard, so their nonces never meet
+    const stellar = await program.account.routeSequence.fetch(
+      routeSequencePda(mint, STELLAR_CHAIN_ID)
+    );
+    assert.notEqual(stellar.shard, sequence.shard);
+
+    // A pair nobody opened a route for can't lock
+    try {
+      await program.methods
+        .quoteLock(wrappedMint, new anchor.BN(1000000), STELLAR_CHAIN_ID, user.publicKey)
+        .accounts({
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(STELLAR_CHAIN_ID),
+          tokenConfig: tokenConfigPda(wrappedMint),
+          routeSequence: routeSequencePda(wrappedMint, STELLAR_CHAIN_ID),
+          feeRoute: feeRoutePda(wrappedMint, STELLAR_CHAIN_ID),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+        })
+        .view();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('AccountNotInitialized'));
+    }
+
+    console.log('✓ Locks numbered per token and destination');
+  });
+
+  it('Quarantines a mint whose recipient account changed owner', async () => {
+    const payer = (provider.wallet as anchor.Wallet).payer;
+    const recipient = Keypair.generate();
+    const recipientAta = await createAccount(
+      provider.connection,
+      payer,
+      wrappedMint,
+      recipient.publicKey
+    );
+
+    // The recipient hands their associated account to someone else
+    await setAuthority(
+      provider.connection,
+      payer,
+      recipientAta,
+      recipient,
+      AuthorityType.AccountOwner,
+      Keypair.generate().publicKey
+    );
+
+    const nonce = new anchor.BN(203);
+    const recordPda = transferPda('in', nonce);
+    await program.methods
+      .mint(new anchor.BN(1000), nonce)
+      .accounts({
+        user: recipient.publicKey,
+        authority: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        credential: null,
+        vaa: null,
+        userToken: recipientAta,
+        bridgeAuthority: bridgeAuthority,
+        transferRecord: recordPda,
+        inbox: inboxPda(ETHEREUM_CHAIN_ID),
+        sourceChain: chainConfigPda(ETHEREUM_CHAIN_ID),
+        guardianCouncil: guardianCouncilPda(),
+        guardianSet: guardianSetPda(),
+        ...rewardAccounts(provider.wallet.publicKey),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    // Delivered, but nothing went to the account it no longer owns
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('claimable' in record.status);
+    assert.equal(record.escrowed, false);
+    const ata = await getAccount(provider.connection, recipientAta);
+    assert.equal(ata.amount.toString(), '0');
+
+    // Claimed into a new account the recipient owns
+    const recipientToken = await createAccount(
+      provider.connection,
+      payer,
+      wrappedMint,
+      recipient.publicKey,
+      Keypair.generate()
+    );
+    await program.methods
+      .claim(record.transferId)
+      .accounts({
+        claimer: payer.publicKey,
+        bridgeState: bridgeState,
+        transferRecord: recordPda,
+        guardianCouncil: guardianCouncilPda(),
+        wrappedMint: wrappedMint,
+        tokenConfig: tokenConfigPda(wrappedMint),
+        recipientToken,
+        bridgeAuthority: bridgeAuthority,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        instructions: null,
+      })
+      .rpc();
+
+    const account = await getAccount(provider.connection, recipientToken);
+    assert.equal(account.amount.toString(), '1000');
+    const claimed = await program.account.transferRecord.fetch(recordPda);
+    assert.ok('completed' in claimed.status);
+
+    console.log('✓ Quarantined mint claimed by its recipient');
+  });
+
+  it('Syncs a wrapped mint\'s metadata with a guardian quorum', async () => {
+    const TOKEN_METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s');
+    const sourceToken = Buffer.from('a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48', 'hex');
+    const [wrappedUsdc] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_mint'), sourceToken],
+      program.programId
+    );
+    const [wrappedAsset] = PublicKey.findProgramAddressSync(
+      [Buffer.from('wrapped_asset'), wrappedUsdc.toBuffer()],
+      program.programId
+    );
+    const [metadataSync] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata_sync'), wrappedUsdc.toBuffer()],
+      program.programId
+    );
+    const [metadata] = PublicKey.findProgramAddressSync(
+      [Buffer.from('metadata'), TOKEN_METADATA_PROGRAM_ID.toBuffer(), wrappedUsdc.toBuffer()],
+      TOKEN_METADATA_PROGRAM_ID
+    );
+
+    const guardian = Keypair.generate();
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const sync = (ethBlock: number) =>
+      program.methods
+        .syncMetadata('USD Coin v2', 'USDC2', 6, new anchor.BN(ethBlock))
+        .accounts({
+          bridgeState: bridgeState,
+          relayer: provider.wallet.publicKey,
+          guardianSet: guardianSetPda(),
+          wrappedAsset,
+          metadataSync,
+          metadata,
+          bridgeAuthority: bridgeAuthority,
+          tokenMetadataProgram: TOKEN_METADATA_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts([{ pubkey: guardian.publicKey, isSigner: true, isWritable: false }])
+        .signers([guardian])
+        .rpc();
+
+    await sync(100);
+
+    // key, update authority and mint, then the name as a borsh string
+    const { data } = await provider.connection.getAccountInfo(metadata);
+    const name = data.subarray(69, 69 + data.readUInt32LE(65)).toString().replace(/\0+$/, '');
+    assert.equal(name, 'Wrapped USD Coin v2');
+    const synced = await program.account.metadataSync.fetch(metadataSync);
+    assert.equal(synced.ethBlock.toNumber(), 100);
+
+    // A read from the same or an older block can't be applied again
+    try {
+      await sync(100);
+      assert.fail('Stale metadata sync should have failed');
+    } catch (error) {
+      assert.include(error.toString(), 'StaleMetadataSync');
+    }
+
+    console.log('✓ Wrapped metadata synced from Ethereum');
+  });
+
+  it('Only takes locks from allowlisted senders in closed user group mode', async () => {
+    const senderAllowlist = PublicKey.findProgramAddressSync(
+      [Buffer.from('sender_allowlist')],
+      program.programId
+    )[0];
+    const allowedSender = PublicKey.findProgramAddressSync(
+      [Buffer.from('allowed_sender'), user.publicKey.toBuffer()],
+      program.programId
+    )[0];
+    const admin = {
+      owner: provider.wallet.publicKey,
+      bridgeState: bridgeState,
+      adminLog: adminLog,
+    };
+    const setAllowlist = (allTokens: boolean, mints: PublicKey[]) =>
+      program.methods
+        .setSenderAllowlist(allTokens, mints)
+        .accounts({ ...admin, senderAllowlist, systemProgram: SystemProgram.programId })
+        .rpc();
+    const lock = async () => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .lock(
+          new anchor.BN(1000000),
+          ETHEREUM_CHAIN_ID,
+          '0x7777777777777777777777777777777777777777',
+          null,
+          null,
+          null,
+          null,
+          null
+        )
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          senderAllowlist,
+          allowedSender,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          solFeeConfig: null,
+          solFeeVault: null,
+          tokenPrice: null,
+          solPrice: null,
+          deliveryFeeConfig: null,
+          rewardVault: null,
+          nativePrice: null,
+        })
+        .signers([user])
+        .rpc();
+    };
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    // Gate just this token; other tokens stay open
+    await setAllowlist(false, [mint]);
+    await expectError(lock, 'SenderNotAllowlisted');
+
+    await program.methods
+      .allowSender(user.publicKey)
+      .accounts({ ...admin, allowedSender, systemProgram: SystemProgram.programId })
+      .rpc();
+    await lock();
+
+    await program.methods
+      .disallowSender(user.publicKey)
+      .accounts({ ...admin, allowedSender })
+      .rpc();
+    assert.isNull(await provider.connection.getAccountInfo(allowedSender));
+    await expectError(lock, 'SenderNotAllowlisted');
+
+    // No mints and all_tokens off gates nothing
+    await setAllowlist(false, []);
+    await lock();
+
+    console.log('✓ Locks gated on the sender allowlist');
+  });
+
+  it('Sends escrow to another instance with the owner and a guardian quorum', async () => {
+    const connection = provider.connection;
+    const instanceMint = await createMint(connection, user, user.publicKey, null, 6);
+    const escrow = await createAccount(
+      connection,
+      user,
+      instanceMint,
+      bridgeAuthority,
+      Keypair.generate()
+    );
+    await mintTo(connection, user, instanceMint, escrow, user, 1000);
+
+    // Instance 1 (staging, from the instances test) with its own escrow
+    const seed = new anchor.BN(1).toArrayLike(Buffer, 'le', 8);
+    const stagingPda = (prefix: string) =>
+      PublicKey.findProgramAddressSync([Buffer.from(prefix), seed], program.programId)[0];
+    const staging = {
+      bridgeState: stagingPda('bridge_state'),
+      adminLog: stagingPda('admin_log'),
+      bridgeAuthority: stagingPda('bridge'),
+    };
+    const destVault = await createAccount(
+      connection,
+      user,
+      instanceMint,
+      staging.bridgeAuthority,
+      Keypair.generate()
+    );
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: instanceMint,
+        tokenConfig: tokenConfigPda(instanceMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const guardian = Keypair.generate();
+    await program.methods
+      .setGuardians([guardian.publicKey], 1)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        guardianSet: guardianSetPda(),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const instanceTransfer = (id: number) =>
+      PublicKey.findProgramAddressSync(
+        [
+          Buffer.from('instance_transfer'),
+          instanceMint.toBuffer(),
+          new anchor.BN(id).toArrayLike(Buffer, 'le', 8),
+        ],
+        program.programId
+      )[0];
+    const send = (id: number, destInstance: number, guardians: Keypair[]) =>
+      program.methods
+        .sendToInstance(new anchor.BN(id), new anchor.BN(400), new anchor.BN(destInstance))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          guardianSet: guardianSetPda(),
+          mint: instanceMint,
+          tokenConfig: tokenConfigPda(instanceMint),
+          instanceTransfer: instanceTransfer(id),
+          escrow,
+          vaultConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from('vault'), instanceMint.toBuffer()],
+            program.programId
+          )[0],
+          destVault,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .remainingAccounts(
+          guardians.map((g) => ({ pubkey: g.publicKey, isSigner: true, isWritable: false }))
+        )
+        .signers(guardians)
+        .rpc();
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    // The owner alone can't move collateral
+    await expectError(() => send(1, 1, []), 'Unauthorized');
+    // Not to itself, and the vault must belong to the named instance
+    await expectError(() => send(1, 0, [guardian]), 'InvalidInstanceTransfer');
+    await expectError(() => send(1, 2, [guardian]), 'InvalidInstanceTransfer');
+
+    await send(1, 1, [guardian]);
+    assert.equal((await getAccount(connection, destVault)).amount.toString(), '400');
+    assert.equal((await getAccount(connection, escrow)).amount.toString(), '600');
+    const record = await program.account.instanceTransfer.fetch(instanceTransfer(1));
+    assert.equal(record.destInstance.toNumber(), 1);
+    assert.equal(record.amount.toNumber(), 400);
+
+    // Instance 1 records it, reading instance 0's record
+    const instanceReceipt = PublicKey.findProgramAddressSync(
+      [Buffer.from('instance_receipt'), seed, instanceTransfer(1).toBuffer()],
+      program.programId
+    )[0];
+    const receive = (sourceInstance: number) =>
+      program.methods
+        .receiveInstanceTransfer(new anchor.BN(sourceInstance), instanceMint, new anchor.BN(1))
+        .accounts({
+          owner: provider.wallet.publicKey,
+          ...staging,
+          sourceRecord: instanceTransfer(1),
+          escrow: destVault,
+          instanceReceipt,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+    // The record has to sit at the source instance's address
+    await expectError(() => receive(2), 'ConstraintSeeds');
+
+    await receive(0);
+    const receipt = await program.account.instanceReceipt.fetch(instanceReceipt);
+    assert.equal(receipt.sourceInstance.toNumber(), 0);
+    assert.ok(receipt.sourceRecord.equals(instanceTransfer(1)));
+    assert.equal(receipt.amount.toNumber(), 400);
+
+    // Once per transfer
+    await expectError(() => receive(0), 'already in use');
+
+    console.log('✓ Escrow sent to instance 1 and recorded there');
+  });
+
+  it('Keeps a lock\'s sealed memo as opaque bytes', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const memoPda = PublicKey.findProgramAddressSync(
+      [Buffer.from('memo'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    // Stands in for a 16-byte reference in a sealed box; the program
+    // never looks inside
+    const memo = Buffer.from(Array.from({ length: 64 }, (_, i) => (i * 37) % 256));
+
+    const lock = (memoBytes: Buffer, transferMemo: PublicKey | null) =>
+      program.methods
+        .lock(
+          new anchor.BN(1000000),
+          ETHEREUM_CHAIN_ID,
+          '0x5656565656565656565656565656565656565656',
+          null,
+          null,
+          null,
+          memoBytes,
+          null
+        )
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: recordPda,
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          transferMemo,
+        })
+        .signers([user])
+        .rpc();
+
+    // A memo needs its account
+    try {
+      await lock(memo, null);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidMemo'));
+    }
+
+    await lock(memo, memoPda);
+    const stored = await program.account.transferMemo.fetch(memoPda);
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok(Buffer.from(stored.memo).equals(memo));
+    assert.ok(stored.nonce.eq(nonce));
+    assert.deepEqual(stored.transferId, record.transferId);
+
+    // Sized to the memo, not to the longest one allowed
+    const account = await provider.connection.getAccountInfo(memoPda);
+    assert.equal(account.data.length, 8 + 32 + 8 + 4 + memo.length);
+
+    console.log('✓ Sealed memo stored with the lock');
+  });
+
+  it('Locks to a counterfactual smart wallet', async () => {
+    const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+    const recordPda = transferPda('out', nonce);
+    const walletPda = PublicKey.findProgramAddressSync(
+      [Buffer.from('smart_wallet'), nonce.toArrayLike(Buffer, 'le', 8)],
+      program.programId
+    )[0];
+    // EIP-1014's second example: init code 0x00 from factory 0xdeadbeef
+    const factory = Buffer.alloc(20);
+    factory.write('deadbeef', 'hex');
+    const derivation = {
+      create2: {
+        factory: Array.from(factory),
+        salt: Array(32).fill(0),
+        initCodeHash: Array.from(
+          Buffer.from('bc36789e7a1e281436464229828f817d6612f7b477d66591ff96a9e064bcc98a', 'hex')
+        ),
+      },
+    };
+
+    const lock = (recipient: string) =>
+      program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, recipient, null, null, null, null, derivation)
+        .accounts({
+          user: user.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: userTokenAccount,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: recordPda,
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          transferMemo: null,
+          smartWallet: walletPda,
+        })
+        .signers([user])
+        .rpc();
+
+    // The derivation has to give the recipient
+    try {
+      await lock('0x5858585858585858585858585858585858585858');
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidSmartWallet'));
+    }
+
+    await lock('0xB928f69Bb1D91Cd65274e3c79d8986362984fDA3');
+    const stored = await program.account.smartWalletRecipient.fetch(walletPda);
+    const record = await program.account.transferRecord.fetch(recordPda);
+    assert.ok(stored.nonce.eq(nonce));
+    assert.deepEqual(stored.transferId, record.transferId);
+    assert.deepEqual(stored.derivation, derivation);
+
+    console.log('✓ Smart wallet derivation stored with the lock');
+  });
+
+  it('Sweeps a deposit address into a lock to its destination', async () => {
+    const recipient = '0x5757575757575757575757575757575757575757';
+    const depositAddress = PublicKey.findProgramAddressSync(
+      [Buffer.from('deposit'), user.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    await program.methods
+      .registerDepositAddress(ETHEREUM_CHAIN_ID, recipient)
+      .accounts({
+        bridgeState: bridgeState,
+        user: user.publicKey,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        depositAddress,
+        systemProgram: SystemProgram.programId,
+      })
+      .signers([user])
+      .rpc();
+
+    // Anyone can pay into it; here the user mints straight to it
+    const depositToken = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      depositAddress,
+      Keypair.generate()
+    );
+    await mintTo(provider.connection, user, mint, depositToken, user.publicKey, 2000000);
+
+    const sweep = async () => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .sweepDeposit(user.publicKey)
+        .accounts({
+          cranker: provider.wallet.publicKey,
+          depositAddress,
+          depositToken,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(user.publicKey),
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          routeSequence: routeSequencePda(mint, ETHEREUM_CHAIN_ID),
+          credential: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+        })
+        .rpc();
+      return nonce;
+    };
+
+    // Cranked by someone other than the user, for the user
+    const nonce = await sweep();
+    const record = await program.account.transferRecord.fetch(transferPda('out', nonce));
+    assert.ok(record.localAccount.equals(user.publicKey));
+    assert.equal(record.remoteAddress, recipient);
+    assert.ok(record.escrowed);
+    assert.equal((await getAccount(provider.connection, depositToken)).amount, BigInt(0));
+
+    const deposit = await program.account.depositAddress.fetch(depositAddress);
+    assert.equal(deposit.sweeps.toNumber(), 1);
+
+    try {
+      await sweep();
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('NothingToSweep'));
+    }
+
+    console.log('✓ Deposit address swept into a lock');
+  });
+
+  it('Only locks to a holder\'s whitelisted withdrawal addresses', async () => {
+    const holder = Keypair.generate();
+    const custodian = Keypair.generate();
+    await provider.connection.confirmTransaction(
+      await provider.connection.requestAirdrop(holder.publicKey, anchor.web3.LAMPORTS_PER_SOL)
+    );
+    const holderToken = await createAccount(
+      provider.connection,
+      user,
+      mint,
+      holder.publicKey,
+      Keypair.generate()
+    );
+    await mintTo(provider.connection, user, mint, holderToken, user.publicKey, 10000000);
+
+    const approved = '0x5858585858585858585858585858585858585858';
+    const later = '0x5959595959595959595959595959595959595959';
+    const withdrawalAllowlist = PublicKey.findProgramAddressSync(
+      [Buffer.from('withdrawal_allowlist'), holder.publicKey.toBuffer()],
+      program.programId
+    )[0];
+
+    const lockTo = async (recipient: string) => {
+      const nonce = await nextOutNonce(mint, ETHEREUM_CHAIN_ID);
+      await program.methods
+        .lock(new anchor.BN(1000000), ETHEREUM_CHAIN_ID, recipient, null, null, null, null, null)
+        .accounts({
+          user: holder.publicKey,
+          bridgeState: bridgeState,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          userToken: holderToken,
+          mint: mint,
+          tokenConfig: tokenConfigPda(mint),
+          feeRoute: feeRoutePda(mint, ETHEREUM_CHAIN_ID),
+          quoteSigners: null,
+          instructions: null,
+          credential: null,
+          bridgePass: null,
+          conversionRate: null,
+          bridgeToken: bridgeTokenAccount,
+          feeVault: feeVaultAccount,
+          bridgeAuthority: bridgeAuthority,
+          transferRecord: transferPda('out', nonce),
+          outbox: outboxPda(),
+          guardianSet: guardianSetPda(),
+          feeTiers: feeTiersPda(),
+          userStats: userStatsPda(holder.publicKey),
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: SystemProgram.programId,
+          receiptMint: null,
+          receiptToken: null,
+          token2022Program: null,
+          associatedTokenProgram: null,
+          transferMemo: null,
+        })
+        .signers([holder])
+        .rpc();
+    };
+    const expectRefused = async (recipient: string, error: string) => {
+      try {
+        await lockTo(recipient);
+        assert.fail('Should have thrown error');
+      } catch (e) {
+        assert.ok(e.toString().includes(error));
+      }
+    };
+    const add = (recipient: string, authority: Keypair) =>
+      program.methods
+        .addWithdrawalAddress(holder.publicKey, ETHEREUM_CHAIN_ID, recipient)
+        .accounts({
+          bridgeState: bridgeState,
+          authority: authority.publicKey,
+          chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+          withdrawalAllowlist,
+          systemProgram: SystemProgram.programId,
+        })
+        .signers([authority])
+        .rpc();
+
+    // The address the list is created with counts at once
+    await add(approved, holder);
+    await lockTo(approved);
+    await expectRefused(later, 'RecipientNotWhitelisted');
+
+    // Later additions wait out the delay
+    await add(later, holder);
+    const list = await program.account.withdrawalAllowlist.fetch(withdrawalAllowlist);
+    assert.equal(list.addresses.length, 2);
+    const [first, second] = list.addresses.map((entry) => entry.activeAt.toNumber());
+    assert.ok(second - first >= 24 * 60 * 60);
+    await expectRefused(later, 'RecipientNotWhitelisted');
+
+    // Handed to a custodian, the holder's key can't change the list
+    await program.methods
+      .setWithdrawalAuthority(holder.publicKey, custodian.publicKey)
+      .accounts({ authority: holder.publicKey, bridgeState, withdrawalAllowlist })
+      .signers([holder])
+      .rpc();
+    try {
+      await add('0x6060606060606060606060606060606060606060', holder);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('Unauthorized'));
+    }
+
+    await program.methods
+      .removeWithdrawalAddress(holder.publicKey, ETHEREUM_CHAIN_ID, approved)
+      .accounts({
+        bridgeState: bridgeState,
+        authority: custodian.publicKey,
+        chainConfig: chainConfigPda(ETHEREUM_CHAIN_ID),
+        withdrawalAllowlist,
+      })
+      .signers([custodian])
+      .rpc();
+    await expectRefused(approved, 'RecipientNotWhitelisted');
+
+    console.log('✓ Withdrawal allowlist enforced on locks');
+  });
+
+  it('Imports state from a previous deployment, then seals', async () => {
+    // A fresh instance stands in for the new deployment, since routes
+    // have to come over before any is opened there
+    const seed = new anchor.BN(2).toArrayLike(Buffer, 'le', 8);
+    const pda = (prefix: string, ...rest: Buffer[]) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from(prefix), seed, ...rest],
+        program.programId
+      )[0];
+    const ethereum = ETHEREUM_CHAIN_ID.toArrayLike(Buffer, 'le', 8);
+    const successor = { bridgeState: pda('bridge_state'), adminLog: pda('admin_log') };
+    const owner = provider.wallet.publicKey;
+    const systemProgram = SystemProgram.programId;
+    const migration = pda('migration');
+
+    await program.methods
+      .initialize(new anchor.BN(2))
+      .accounts({ ...successor, outbox: pda('outbox'), owner, systemProgram })
+      .rpc();
+    await program.methods
+      .registerChain(ETHEREUM_CHAIN_ID, { evm: {} }, {
+        finality: { confirmations: {} },
+        requiredConfirmations: 12,
+        baseFee: new anchor.BN(0),
+        feeBps: 0,
+        minAmount: new anchor.BN(0),
+      })
+      .accounts({ owner, ...successor, chainConfig: pda('chain', ethereum), systemProgram })
+      .rpc();
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner,
+        ...successor,
+        mint: mint,
+        tokenConfig: pda('token', mint.toBuffer()),
+        systemProgram,
+      })
+      .rpc();
+    await program.methods
+      .setFeeTiers([])
+      .accounts({ owner, ...successor, feeTiers: pda('fee_tiers'), systemProgram })
+      .rpc();
+
+    // The old deployment handed out 5 shards; its mint -> Ethereum
+    // route has shard 3 and reached sequence 41
+    const oldProgram = Keypair.generate().publicKey;
+    const importState = (nonces: number[]) =>
+      program.methods
+        .importState(oldProgram, 5, new anchor.BN(0), nonces.map((n) => new anchor.BN(n)))
+        .accounts({ owner, ...successor, migration, systemProgram })
+        .rpc();
+    const routeSequence = pda('sequence', mint.toBuffer(), ethereum);
+    const importRoute = (shard: number) =>
+      program.methods
+        .importRouteSequence(mint, ETHEREUM_CHAIN_ID, shard, new anchor.BN(41))
+        .accounts({ owner, ...successor, migration, routeSequence, systemProgram })
+        .rpc();
+
+    await importState([900001, 900002]);
+    const imported = await program.account.bridgeState.fetch(successor.bridgeState);
+    assert.ok(imported.processedNonces.some((n) => n.toNumber() === 900001));
+    assert.ok(imported.processedNonces.some((n) => n.toNumber() === 900002));
+    assert.equal(imported.routeShards, 5);
+
+    // Only shards the old deployment handed out
+    try {
+      await importRoute(6);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InvalidConfig'));
+    }
+    await importRoute(3);
+
+    // The route's next lock takes the nonce the old deployment would have
+    const nonce = new anchor.BN(3).shln(SEQUENCE_BITS).addn(42);
+    const bridgeAuthority2 = pda('bridge');
+    const vault = () =>
+      createAccount(provider.connection, user, mint, bridgeAuthority2, Keypair.generate());
+    const transferRecord = pda('transfer', Buffer.from('out'), nonce.toArrayLike(Buffer, 'le', 8));
+    await program.methods
+      .lock(
+        new anchor.BN(1000000),
+        ETHEREUM_CHAIN_ID,
+        '0x1234567890123456789012345678901234567890',
+        null,
+        null,
+        null,
+        null,
+        null
+      )
+      .accounts({
+        user: user.publicKey,
+        bridgeState: successor.bridgeState,
+        chainConfig: pda('chain', ethereum),
+        routeSequence,
+        userToken: userTokenAccount,
+        mint: mint,
+        tokenConfig: pda('token', mint.toBuffer()),
+        feeRoute: pda('fee', mint.toBuffer(), ethereum),
+        quoteSigners: null,
+        instructions: null,
+        credential: null,
+        bridgePass: null,
+        conversionRate: null,
+        bridgeToken: await vault(),
+        feeVault: await vault(),
+        bridgeAuthority: bridgeAuthority2,
+        transferRecord,
+        outbox: pda('outbox'),
+        guardianSet: pda('guardian_set'),
+        feeTiers: pda('fee_tiers'),
+        userStats: pda('user_stats', user.publicKey.toBuffer()),
+        tokenProgram: TOKEN_PROGRAM_ID,
+        systemProgram,
+        receiptMint: null,
+        receiptToken: null,
+        token2022Program: null,
+        associatedTokenProgram: null,
+      })
+      .signers([user])
+      .rpc();
+    const record = await program.account.transferRecord.fetch(transferRecord);
+    assert.equal(record.nonce.toString(), nonce.toString());
+    const route = await program.account.routeSequence.fetch(routeSequence);
+    assert.equal(route.shard, 3);
+    assert.equal(route.last.toNumber(), 42);
+
+    const oldUser = Keypair.generate().publicKey;
+    const oldUserStats = pda('user_stats', oldUser.toBuffer());
+    await program.methods
+      .importUserStats(oldUser, new anchor.BN(5000), new anchor.BN(3))
+      .accounts({
+        owner,
+        bridgeState: successor.bridgeState,
+        migration,
+        userStats: oldUserStats,
+        systemProgram,
+      })
+      .rpc();
+    const stats = await program.account.userStats.fetch(oldUserStats);
+    assert.equal(stats.volume.toString(), '5000');
+    assert.equal(stats.transfers.toString(), '3');
+
+    await program.methods
+      .sealImport()
+      .accounts({ owner, ...successor, migration, systemProgram })
+      .rpc();
+
+    try {
+      await importState([900003]);
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('ImportSealed'));
+    }
+
+    console.log('✓ State imported, a route continued and the import sealed');
+  });
+
+  it('Timelocks moving an escrow to a successor deployment', async () => {
+    const connection = provider.connection;
+    const migrationMint = await createMint(connection, user, user.publicKey, null, 6);
+    const escrow = await createAccount(
+      connection,
+      user,
+      migrationMint,
+      bridgeAuthority,
+      Keypair.generate()
+    );
+    const successor = Keypair.generate().publicKey;
+    const successorAuthority = PublicKey.findProgramAddressSync(
+      [Buffer.from('bridge')],
+      successor
+    )[0];
+    const newVault = await createAccount(
+      connection,
+      user,
+      migrationMint,
+      successorAuthority,
+      Keypair.generate()
+    );
+    const strayVault = await createAccount(
+      connection,
+      user,
+      migrationMint,
+      user.publicKey,
+      Keypair.generate()
+    );
+    const [escrowMigration] = PublicKey.findProgramAddressSync(
+      [Buffer.from('escrow_migration'), migrationMint.toBuffer()],
+      program.programId
+    );
+
+    await program.methods
+      .registerToken()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: migrationMint,
+        tokenConfig: tokenConfigPda(migrationMint),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const queue = (vault: PublicKey, owner = provider.wallet.publicKey) =>
+      program.methods.queueEscrowMigration(successor).accounts({
+        owner,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        mint: migrationMint,
+        tokenConfig: tokenConfigPda(migrationMint),
+        newVault: vault,
+        escrowMigration,
+        systemProgram: SystemProgram.programId,
+      });
+    const cancel = (authority = provider.wallet.publicKey) =>
+      program.methods.cancelEscrowMigration().accounts({
+        authority,
+        bridgeState: bridgeState,
+        guardianSet: guardianSetPda(),
+        escrowMigration,
+      });
+    const migrate = () =>
+      program.methods
+        .migrateEscrow(successor)
+        .accounts({
+          owner: provider.wallet.publicKey,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+          mint: migrationMint,
+          tokenConfig: tokenConfigPda(migrationMint),
+          escrowMigration,
+          escrow,
+          newVault,
+          vaultConfig: PublicKey.findProgramAddressSync(
+            [Buffer.from('vault'), migrationMint.toBuffer()],
+            program.programId
+          )[0],
+          coldVault: null,
+          bridgeAuthority: bridgeAuthority,
+          coldAuthority: PublicKey.findProgramAddressSync(
+            [Buffer.from('cold_vault')],
+            program.programId
+          )[0],
+          tokenProgram: TOKEN_PROGRAM_ID,
+        })
+        .rpc();
+    const expectError = async (action: () => Promise<unknown>, expected: string) => {
+      try {
+        await action();
+        assert.fail('Should have thrown error');
+      } catch (error) {
+        assert.ok(error.toString().includes(expected), expected);
+      }
+    };
+
+    await expectError(() => queue(newVault, user.publicKey).signers([user]).rpc(), 'Unauthorized');
+    // The new vault has to belong to the successor's bridge authority
+    await expectError(() => queue(strayVault).rpc(), 'InvalidMigrationTarget');
+
+    await queue(newVault).rpc();
+    const queued = await program.account.escrowMigration.fetch(escrowMigration);
+    assert.ok(queued.newBridgeProgram.equals(successor));
+    assert.ok(queued.newVault.equals(newVault));
+    assert.ok(queued.executableAt.toNumber() > Date.now() / 1000 + 6 * 24 * 3600);
+    await expectError(() => queue(newVault).rpc(), 'EscrowMigrationPending');
+
+    // Nothing moves before the timelock is over
+    await expectError(() => migrate(), 'MigrationNotReady');
+
+    await expectError(() => cancel(user.publicKey).signers([user]).rpc(), 'Unauthorized');
+    await cancel().rpc();
+    const cancelled = await program.account.escrowMigration.fetch(escrowMigration);
+    assert.equal(cancelled.executableAt.toNumber(), 0);
+    await expectError(() => cancel().rpc(), 'MigrationNotReady');
+    await expectError(() => migrate(), 'MigrationNotReady');
+
+    console.log('✓ Escrow migration queued, held by its timelock and cancelled');
+  });
+
+  it('Runs admin instructions through a Squads vault', async () => {
+    const connection = provider.connection;
+    const payer = (provider.wallet as anchor.Wallet).payer;
+
+    // 1-of-1 Squads multisig; a real deployment would use more members
+    const createKey = Keypair.generate();
+    const [multisigPda] = multisig.getMultisigPda({ createKey: createKey.publicKey });
+    const [vaultPda] = multisig.getVaultPda({ multisigPda, index: 0 });
+    const [programConfigPda] = multisig.getProgramConfigPda({});
+    const programConfig = await multisig.accounts.ProgramConfig.fromAccountAddress(
+      connection,
+      programConfigPda
+    );
+
+    await connection.confirmTransaction(
+      await multisig.rpc.multisigCreateV2({
+        connection,
+        createKey,
+        creator: payer,
+        multisigPda,
+        configAuthority: null,
+        timeLock: 0,
+        members: [{ key: payer.publicKey, permissions: multisig.types.Permissions.all() }],
+        threshold: 1,
+        rentCollector: null,
+        treasury: programConfig.treasury,
+      })
+    );
+
+    // Create, approve and execute a vault transaction wrapping `ix`
+    const executeThroughSquads = async (ix: TransactionInstruction) => {
+      const account = await multisig.accounts.Multisig.fromAccountAddress(connection, multisigPda);
+      const transactionIndex = BigInt(Number(account.transactionIndex) + 1);
+      const transactionMessage = new TransactionMessage({
+        payerKey: vaultPda,
+        recentBlockhash: (await connection.getLatestBlockhash()).blockhash,
+        instructions: [ix],
+      });
+
+      await connection.confirmTransaction(
+        await multisig.rpc.vaultTransactionCreate({
+          connection,
+          feePayer: payer,
+          multisigPda,
+          transactionIndex,
+          creator: payer.publicKey,
+          vaultIndex: 0,
+          ephemeralSigners: 0,
+          transactionMessage,
+        })
+      );
+      await connection.confirmTransaction(
+        await multisig.rpc.proposalCreate({
+          connection,
+          feePayer: payer,
+          multisigPda,
+          transactionIndex,
+          creator: payer,
+        })
+      );
+      await connection.confirmTransaction(
+        await multisig.rpc.proposalApprove({
+          connection,
+          feePayer: payer,
+          multisigPda,
+          transactionIndex,
+          member: payer,
+        })
+      );
+      await connection.confirmTransaction(
+        await multisig.rpc.vaultTransactionExecute({
+          connection,
+          feePayer: payer,
+          multisigPda,
+          transactionIndex,
+          member: payer.publicKey,
+          signers: [payer],
+        })
+      );
+    };
+
+    // Hand the bridge to the vault
+    await program.methods
+      .transferOwnership(vaultPda)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    await executeThroughSquads(
+      await program.methods
+        .acceptOwnership()
+        .accounts({
+          newOwner: vaultPda,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+        })
+        .instruction()
+    );
+
+    let state = await program.account.bridgeState.fetch(bridgeState);
+    assert.ok(state.owner.equals(vaultPda));
+
+    // Pause through a Squads proposal
+    await executeThroughSquads(
+      await program.methods
+        .pause()
+        .accounts({
+          owner: vaultPda,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+        })
+        .instruction()
+    );
+
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, true);
+
+    // Config change through Squads: hand ownership back to the wallet
+    await executeThroughSquads(
+      await program.methods
+        .transferOwnership(provider.wallet.publicKey)
+        .accounts({
+          owner: vaultPda,
+          bridgeState: bridgeState,
+          adminLog: adminLog,
+        })
+        .instruction()
+    );
+
+    await program.methods
+      .acceptOwnership()
+      .accounts({
+        newOwner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    await program.methods
+      .unpause()
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .rpc();
+
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.ok(state.owner.equals(provider.wallet.publicKey));
+
+    console.log('✓ Squads vault executed admin instructions');
+  });
+
+  // Runs last: the bridge owner becomes the multisig authority PDA
+  it('Executes admin actions through the owner multisig', async () => {
+    const coOwner = Keypair.generate();
+    const airdrop = await provider.connection.requestAirdrop(
+      coOwner.publicKey,
+      anchor.web3.LAMPORTS_PER_SOL
+    );
+    await provider.connection.confirmTransaction(airdrop);
+
+    const [ownerMultisig] = PublicKey.findProgramAddressSync(
+      [Buffer.from('owner_multisig')],
+      program.programId
+    );
+    const [multisigAuthority] = PublicKey.findProgramAddressSync(
+      [Buffer.from('multisig_authority')],
+      program.programId
+    );
+    const proposalPda = (id: number) =>
+      PublicKey.findProgramAddressSync(
+        [Buffer.from('admin_proposal'), new anchor.BN(id).toArrayLike(Buffer, 'le', 8)],
+        program.programId
+      )[0];
+
+    await program.methods
+      .createOwnerMultisig([provider.wallet.publicKey, coOwner.publicKey], 2)
+      .accounts({
+        owner: provider.wallet.publicKey,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+        ownerMultisig: ownerMultisig,
+        multisigAuthority: multisigAuthority,
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    let state = await program.account.bridgeState.fetch(bridgeState);
+    assert.ok(state.owner.equals(multisigAuthority));
+
+    // Propose pause()
+    const pauseIx = await program.methods
+      .pause()
+      .accounts({
+        owner: multisigAuthority,
+        bridgeState: bridgeState,
+        adminLog: adminLog,
+      })
+      .instruction();
+
+    await program.methods
+      .proposeAdminAction(pauseIx.data, pauseIx.keys)
+      .accounts({
+        bridgeState: bridgeState,
+        proposer: provider.wallet.publicKey,
+        ownerMultisig: ownerMultisig,
+        proposal: proposalPda(0),
+        systemProgram: SystemProgram.programId,
+      })
+      .rpc();
+
+    const executeAccounts = {
+      bridgeState: bridgeState,
+      ownerMultisig: ownerMultisig,
+      proposal: proposalPda(0),
+      multisigAuthority: multisigAuthority,
+    };
+    const remainingAccounts = [
+      ...pauseIx.keys.map(key => ({ ...key, isSigner: false })),
+      { pubkey: program.programId, isSigner: false, isWritable: false },
+    ];
+
+    // One approval is not enough for 2-of-2
+    try {
+      await program.methods
+        .execute(new anchor.BN(0))
+        .accounts(executeAccounts)
+        .remainingAccounts(remainingAccounts)
+        .rpc();
+
+      assert.fail('Should have thrown error');
+    } catch (error) {
+      assert.ok(error.toString().includes('InsufficientApprovals'));
+    }
+
+    await program.methods
+      .approve(new anchor.BN(0))
+      .accounts({
+        bridgeState: bridgeState,
+        owner: coOwner.publicKey,
+        ownerMultisig: ownerMultisig,
+        proposal: proposalPda(0),
+      })
+      .signers([coOwner])
+      .rpc();
+
+    await program.methods
+      .execute(new anchor.BN(0))
+      .accounts(executeAccounts)
+      .remainingAccounts(remainingAccounts)
+      .rpc();
+
+    state = await program.account.bridgeState.fetch(bridgeState);
+    assert.equal(state.paused, true);
+
+    console.log('✓ Multisig pause executed');
   });
 });
@@ -0,0 +1,84 @@
+//! What each cluster gets
+//!
+//! Everything a deployment depends on besides the keypairs lives here,
+//! so two runs against the same cluster configure it the same way.
+
+use clap::ValueEnum;
+use solana_bridge::{ChainParams, Finality};
+use solana_bridge_sdk::route_profile;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Cluster {
+    Localnet,
+    Devnet,
+}
+
+impl Cluster {
+    pub fn name(self) -> &'static str {
+        match self {
+            Cluster::Localnet => "localnet",
+            Cluster::Devnet => "devnet",
+        }
+    }
+
+    pub fn default_url(self) -> &'static str {
+        match self {
+            Cluster::Localnet => "http://127.0.0.1:8899",
+            Cluster::Devnet => "https://api.devnet.solana.com",
+        }
+    }
+
+    /**
+     * Ethereum's registration
+     *
+     * Devnet pairs with Sepolia, at the devnet-sepolia profile's depth,
+     * so the relayer and the registry agree on it.
+     */
+    pub fn ethereum(self) -> ChainParams {
+        match self {
+            Cluster::Localnet => ChainParams {
+                finality: Finality::Confirmations,
+                required_confirmations: 1,
+                base_fee: 0,
+                fee_bps: 0,
+                min_amount: 0,
+            },
+            Cluster::Devnet => ChainParams {
+                finality: Finality::Confirmations,
+                required_confirmations: route_profile("devnet-sepolia")
+                    .expect("profiles.json has devnet-sepolia")
+                    .ethereum
+                    .confirmations,
+                base_fee: 0,
+                fee_bps: 10,
+                min_amount: 0,
+            },
+        }
+    }
+
+    /// Whole demo tokens per faucet drip, and seconds between drips
+    pub fn faucet(self) -> (u64, i64) {
+        match self {
+            Cluster::Localnet => (1_000, 0),
+            Cluster::Devnet => (100, 3_600),
+        }
+    }
+
+    /// SOL the relayer is topped up to
+    pub fn relayer_lamports(self) -> u64 {
+        match self {
+            Cluster::Localnet => 100 * LAMPORTS_PER_SOL,
+            Cluster::Devnet => LAMPORTS_PER_SOL,
+        }
+    }
+
+    /// The deployer is airdropped up to this before deploying
+    pub fn deployer_lamports(self) -> u64 {
+        match self {
+            Cluster::Localnet => 500 * LAMPORTS_PER_SOL,
+            // The devnet faucet hands out at most 2 SOL per request
+            Cluster::Devnet => 2 * LAMPORTS_PER_SOL,
+        }
+    }
+}
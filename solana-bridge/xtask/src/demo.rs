@@ -0,0 +1,283 @@
+//! One real transfer across a pair of public testnets
+//!
+//!   cargo xtask deploy --cluster devnet --keypair deployer.json
+//!   BRIDGE_PROFILE=devnet-sepolia npm start --prefix relayer   # with the printed .env
+//!   cargo xtask demo --profile devnet-sepolia --keypair wallet.json \
+//!       --ethereum-bridge 0x... --recipient 0x...
+//!
+//! Checks the profile's EVM endpoint is on the chain it names, tops the
+//! wallet up from the Solana faucet, drips it demo tokens, locks them
+//! toward `recipient` and waits for the EVM bridge to mark the transfer
+//! processed. It reads the deployment `cargo xtask deploy` wrote for the
+//! profile's cluster and needs a relayer serving it; it doesn't start
+//! one. On a timeout it says how far the transfer got.
+
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::associated_token::{self, get_associated_token_address};
+use anchor_spl::token::spl_token;
+use anyhow::{bail, Context as _, Result};
+use serde_json::{json, Value};
+use solana_bridge::{codec, RouteSequence, TransferRecord};
+use solana_bridge_sdk::{pda, route_profile, LockAccounts, RouteProfile, TransferBuilder};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::native_token::LAMPORTS_PER_SOL;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{Keypair, Signer};
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use crate::steps::read_keypair;
+
+/// How often the wait polls both chains
+const POLL_INTERVAL: Duration = Duration::from_secs(10);
+
+pub struct Demo {
+    pub profile: String,
+    pub keypair: PathBuf,
+    pub ethereum_bridge: String,
+    pub recipient: String,
+    /// Whole demo tokens
+    pub amount: u64,
+    pub timeout: Duration,
+}
+
+pub fn run(root: &Path, demo: Demo) -> Result<()> {
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?
+        .block_on(transfer(root, demo))
+}
+
+async fn transfer(root: &Path, demo: Demo) -> Result<()> {
+    let profile = route_profile(&demo.profile)?;
+    let user = read_keypair(&demo.keypair)?;
+    let deployment = read_deployment(root, &profile)?;
+    let mint = address(&deployment, "/tokens/demo")?;
+    let amount = demo.amount * 10u64.pow(demo_faucet::DECIMALS as u32);
+
+    let ethereum = Ethereum::new(&profile.ethereum.rpc_url, &demo.ethereum_bridge)?;
+    let chain_id = ethereum.chain_id().await?;
+    if chain_id != profile.ethereum.evm_chain_id {
+        bail!(
+            "{} is on chain {chain_id}, not {} ({})",
+            profile.ethereum.rpc_url,
+            profile.ethereum.network,
+            profile.ethereum.evm_chain_id
+        );
+    }
+
+    let rpc = RpcClient::new_with_commitment(
+        profile.solana.rpc_url.clone(),
+        CommitmentConfig::confirmed(),
+    );
+    println!("Demo on {} as {}", demo.profile, user.pubkey());
+    fund(&rpc, &profile, &user.pubkey()).await?;
+
+    let user_token = get_associated_token_address(&user.pubkey(), &mint);
+    let held = match rpc.get_token_account_balance(&user_token).await {
+        Ok(balance) => balance.amount.parse::<u64>()?,
+        Err(_) => 0,
+    };
+    if held < amount {
+        drip(&rpc, &user, &mint, &user_token).await?;
+    }
+
+    let lock = TransferBuilder::new(&rpc, user.pubkey())
+        .lock(
+            LockAccounts {
+                user: user.pubkey(),
+                user_token,
+                mint,
+                bridge_token: address(&deployment, "/vaults/escrow")?,
+                fee_vault: address(&deployment, "/vaults/feeVault")?,
+                token_program: spl_token::ID,
+                bridge_pass: None,
+            },
+            amount,
+            profile.ethereum.registry_chain_id,
+            demo.recipient.clone(),
+            None,
+        )
+        .await?
+        .build_signed(&[&user])
+        .await?;
+    let signature = rpc.send_and_confirm_transaction(&lock).await?;
+
+    let program_id = solana_bridge::ID;
+    let route = pda::route_sequence(&program_id, &mint, profile.ethereum.registry_chain_id);
+    let sequence: RouteSequence = fetch(&rpc, &route).await?;
+    let nonce = RouteSequence::nonce_at(sequence.shard, sequence.last);
+    let record_address = pda::outbound_transfer(&program_id, nonce);
+    let record: TransferRecord = fetch(&rpc, &record_address).await?;
+    println!(
+        "Locked {} demo tokens toward {} (nonce {}, transfer 0x{}): {signature}",
+        demo.amount,
+        demo.recipient,
+        nonce,
+        hex::encode(record.transfer_id)
+    );
+
+    let started = Instant::now();
+    loop {
+        if ethereum.is_processed(&record.transfer_id).await? {
+            println!(
+                "Minted on {} after {}s",
+                profile.ethereum.network,
+                started.elapsed().as_secs()
+            );
+            return Ok(());
+        }
+        if started.elapsed() >= demo.timeout {
+            let record: TransferRecord = fetch(&rpc, &record_address).await?;
+            bail!(
+                "Not minted on {} after {}s; the Solana record is {:?}. Is a relayer \
+                 running with BRIDGE_PROFILE={}? `bridge-cli trace {}` shows where it stalled",
+                profile.ethereum.network,
+                demo.timeout.as_secs(),
+                record.status,
+                demo.profile,
+                nonce
+            );
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}
+
+/// deployments/<cluster>.json, from `cargo xtask deploy`
+fn read_deployment(root: &Path, profile: &RouteProfile) -> Result<Value> {
+    let path = root.join(format!("deployments/{}.json", profile.solana.cluster));
+    let text = std::fs::read_to_string(&path).with_context(|| {
+        format!(
+            "{}; run `cargo xtask deploy --cluster {}` first",
+            path.display(),
+            profile.solana.cluster
+        )
+    })?;
+    serde_json::from_str(&text).with_context(|| path.display().to_string())
+}
+
+fn address(deployment: &Value, pointer: &str) -> Result<Pubkey> {
+    let text = deployment
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .with_context(|| format!("deployment has no {pointer}"))?;
+    Ok(text.parse()?)
+}
+
+/// Airdrop the wallet up to the profile's faucet amount
+async fn fund(rpc: &RpcClient, profile: &RouteProfile, user: &Pubkey) -> Result<()> {
+    let target = (profile.solana.airdrop_sol * LAMPORTS_PER_SOL as f64) as u64;
+    let balance = rpc.get_balance(user).await?;
+    if balance >= target {
+        return Ok(());
+    }
+    let signature = rpc
+        .request_airdrop(user, target - balance)
+        .await
+        .context("Airdrop failed (the faucet rate-limits); fund the wallet by hand")?;
+    rpc.poll_for_signature(&signature).await?;
+    println!("Airdropped {} lamports to {user}", target - balance);
+    Ok(())
+}
+
+/// Mint the wallet a drip of the demo token (see bridge-cli faucet)
+async fn drip(rpc: &RpcClient, user: &Keypair, mint: &Pubkey, user_token: &Pubkey) -> Result<()> {
+    let faucet = Pubkey::find_program_address(&[b"faucet"], &demo_faucet::ID).0;
+    let instruction = Instruction {
+        program_id: demo_faucet::ID,
+        accounts: demo_faucet::accounts::Drip {
+            user: user.pubkey(),
+            faucet,
+            mint: *mint,
+            drip_record: Pubkey::find_program_address(
+                &[b"drip", user.pubkey().as_ref()],
+                &demo_faucet::ID,
+            )
+            .0,
+            user_token_account: *user_token,
+            token_program: spl_token::ID,
+            associated_token_program: associated_token::ID,
+            system_program: system_program::ID,
+        }
+        .to_account_metas(None),
+        data: demo_faucet::instruction::Drip {}.data(),
+    };
+    let blockhash = rpc.get_latest_blockhash().await?;
+    let transaction = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&user.pubkey()),
+        &[user],
+        blockhash,
+    );
+    let signature = rpc
+        .send_and_confirm_transaction(&transaction)
+        .await
+        .context("Drip failed; the faucet allows one per cooldown")?;
+    println!("Dripped demo tokens into {user_token}: {signature}");
+    Ok(())
+}
+
+async fn fetch<T: AccountDeserialize>(rpc: &RpcClient, address: &Pubkey) -> Result<T> {
+    let data = rpc.get_account_data(address).await?;
+    Ok(T::try_deserialize(&mut data.as_slice())?)
+}
+
+/// Just enough JSON-RPC to watch the EVM bridge
+struct Ethereum {
+    url: String,
+    bridge: String,
+    client: reqwest::Client,
+}
+
+impl Ethereum {
+    fn new(url: &str, bridge: &str) -> Result<Self> {
+        Ok(Self {
+            url: url.to_string(),
+            bridge: bridge.to_lowercase(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+        })
+    }
+
+    async fn rpc(&self, method: &str, params: Value) -> Result<Value> {
+        let body = json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let mut response: Value = self
+            .client
+            .post(&self.url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        if let Some(error) = response.get("error") {
+            bail!("{method}: {error}");
+        }
+        Ok(response["result"].take())
+    }
+
+    async fn chain_id(&self) -> Result<u64> {
+        let result = self.rpc("eth_chainId", json!([])).await?;
+        let text = result.as_str().context("eth_chainId: not a string")?;
+        Ok(u64::from_str_radix(text.trim_start_matches("0x"), 16)?)
+    }
+
+    /// Whether the bridge has minted the Solana transfer `transfer_id`
+    async fn is_processed(&self, transfer_id: &[u8; 32]) -> Result<bool> {
+        let data = format!(
+            "0x{}{}",
+            hex::encode(codec::selector("processedNonces(bytes32)")),
+            hex::encode(transfer_id)
+        );
+        let call = json!([{ "to": self.bridge, "data": data }, "latest"]);
+        let result = self.rpc("eth_call", call).await?;
+        let text = result.as_str().context("eth_call: not a string")?;
+        Ok(!text.trim_start_matches("0x").trim_start_matches('0').is_empty())
+    }
+}
@@ -0,0 +1,206 @@
+//! Deployment orchestration
+//!
+//!   cargo xtask deploy --cluster localnet
+//!   cargo xtask deploy --cluster devnet --keypair deployer.json --upgrade
+//!   cargo xtask demo --profile devnet-sepolia --keypair wallet.json ...
+//!
+//! Deploys the bridge and demo-faucet programs built by `anchor build`,
+//! initializes the bridge with the deployer as owner, registers Ethereum
+//! with the cluster's parameters (see cluster.rs), registers the demo
+//! token and a wrapped demo token, creates the demo token's escrow and
+//! fee vault, and funds a relayer. Every step is idempotent (see
+//! steps.rs), and the result goes to deployments/<cluster>.json along
+//! with the relayer's environment. `demo` then sends one transfer through
+//! a deployment and its relayer on public testnets (see demo.rs).
+
+mod cluster;
+mod demo;
+mod steps;
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context as _, Result};
+use clap::{Parser, Subcommand};
+use serde_json::json;
+use solana_sdk::signature::{write_keypair_file, Keypair, Signer};
+
+use cluster::Cluster;
+use demo::Demo;
+use steps::Deployer;
+
+#[derive(Parser)]
+#[command(name = "xtask", about = "Bridge deployment tasks")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Deploy and configure the bridge on a cluster
+    Deploy {
+        #[arg(long, value_enum, default_value_t = Cluster::Localnet)]
+        cluster: Cluster,
+
+        /// Defaults to the cluster's public endpoint
+        #[arg(long, env = "SOLANA_RPC_URL")]
+        url: Option<String>,
+
+        /// Deployer, upgrade authority and bridge owner
+        #[arg(long, env = "SOLANA_KEYPAIR_PATH")]
+        keypair: Option<PathBuf>,
+
+        /// Relayer keypair; created if it doesn't exist
+        #[arg(long)]
+        relayer: Option<PathBuf>,
+
+        /// Redeploy programs that are already deployed
+        #[arg(long)]
+        upgrade: bool,
+    },
+
+    /// Lock demo tokens on Solana and wait for the mint on the EVM testnet
+    Demo {
+        /// Built-in route profile (profiles.json)
+        #[arg(long, env = "BRIDGE_PROFILE", default_value = "devnet-sepolia")]
+        profile: String,
+
+        /// Sends the lock; airdropped SOL and dripped demo tokens if short
+        #[arg(long, env = "SOLANA_KEYPAIR_PATH")]
+        keypair: PathBuf,
+
+        #[arg(long, env = "ETHEREUM_BRIDGE_ADDRESS")]
+        ethereum_bridge: String,
+
+        /// EVM address the demo tokens are minted to
+        #[arg(long)]
+        recipient: String,
+
+        /// Whole demo tokens
+        #[arg(long, default_value_t = 1)]
+        amount: u64,
+
+        /// Seconds to wait for the relayer
+        #[arg(long, default_value_t = 900)]
+        timeout: u64,
+    },
+}
+
+fn main() -> Result<()> {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Deploy {
+            cluster,
+            url,
+            keypair,
+            relayer,
+            upgrade,
+        } => deploy(cluster, url, keypair, relayer, upgrade),
+        Command::Demo {
+            profile,
+            keypair,
+            ethereum_bridge,
+            recipient,
+            amount,
+            timeout,
+        } => demo::run(
+            &workspace_root(),
+            Demo {
+                profile,
+                keypair,
+                ethereum_bridge,
+                recipient,
+                amount,
+                timeout: Duration::from_secs(timeout),
+            },
+        ),
+    }
+}
+
+fn deploy(
+    cluster: Cluster,
+    url: Option<String>,
+    keypair: Option<PathBuf>,
+    relayer: Option<PathBuf>,
+    upgrade: bool,
+) -> Result<()> {
+    let root = workspace_root();
+    let keypair = match keypair {
+        Some(path) => path,
+        None => PathBuf::from(std::env::var("HOME")?).join(".config/solana/id.json"),
+    };
+    let relayer_path = relayer
+        .unwrap_or_else(|| root.join(format!("target/deploy/relayer-{}.json", cluster.name())));
+    let relayer = relayer_keypair(&relayer_path)?;
+
+    let url = url.unwrap_or_else(|| cluster.default_url().to_string());
+    let deployer = Deployer::new(cluster, url.clone(), keypair, root.clone())?;
+    println!("Deploying to {} ({url}) as {}", cluster.name(), deployer.payer.pubkey());
+
+    deployer.fund_deployer()?;
+    deployer.deploy_program("solana_bridge", &solana_bridge::ID, upgrade)?;
+    deployer.deploy_program("demo_faucet", &demo_faucet::ID, upgrade)?;
+    deployer.initialize_bridge()?;
+    let (demo_mint, wrapped_mint) = deployer.demo_tokens()?;
+    let (escrow, fee_vault) = deployer.vaults(&demo_mint)?;
+    deployer.fund_relayer(&relayer.pubkey())?;
+
+    let manifest = json!({
+        "cluster": cluster.name(),
+        "rpcUrl": url,
+        "owner": deployer.payer.pubkey().to_string(),
+        "programs": {
+            "solanaBridge": solana_bridge::ID.to_string(),
+            "demoFaucet": demo_faucet::ID.to_string(),
+        },
+        "tokens": {
+            "demo": demo_mint.to_string(),
+            "wrappedDemo": wrapped_mint.to_string(),
+        },
+        "vaults": {
+            "escrow": escrow.to_string(),
+            "feeVault": fee_vault.to_string(),
+        },
+        "relayer": relayer.pubkey().to_string(),
+    });
+    let path = root.join(format!("deployments/{}.json", cluster.name()));
+    std::fs::create_dir_all(path.parent().expect("has a parent"))?;
+    std::fs::write(&path, serde_json::to_string_pretty(&manifest)? + "\n")
+        .with_context(|| path.display().to_string())?;
+    println!("Wrote {}", path.display());
+
+    println!("\nRelayer environment:");
+    println!("SOLANA_RPC_URL={url}");
+    println!("SOLANA_PROGRAM_ID={}", solana_bridge::ID);
+    // Under the OwnerSignature trust model only the owner delivers; the
+    // relayer key delivers once it is in the guardian set
+    println!("SOLANA_KEYPAIR_PATH={}", relayer_path.display());
+    println!("SOLANA_WRAPPED_MINT={wrapped_mint}");
+    println!("SOLANA_ESCROW_MINT={demo_mint}");
+    println!("SOLANA_ESCROW_ACCOUNT={escrow}");
+    println!("SOLANA_FEE_VAULT={fee_vault}");
+    Ok(())
+}
+
+/// The relayer's keypair at `path`, generated on first use
+fn relayer_keypair(path: &Path) -> Result<Keypair> {
+    if path.exists() {
+        return steps::read_keypair(path);
+    }
+    let keypair = Keypair::new();
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    write_keypair_file(&keypair, path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))?;
+    println!("Generated relayer keypair {}", path.display());
+    Ok(keypair)
+}
+
+/// solana-bridge/, where target/ and deployments/ live
+fn workspace_root() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("xtask is inside the workspace")
+        .to_path_buf()
+}
@@ -0,0 +1,395 @@
+//! Deployment steps
+//!
+//! Each step checks the chain first and only sends what is missing, so a
+//! run that failed halfway is finished by running it again, and a run
+//! against a configured cluster changes nothing.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anchor_lang::{AccountDeserialize, InstructionData, ToAccountMetas};
+use anchor_spl::token::spl_token;
+use anyhow::{bail, Context as _, Result};
+use solana_bridge::{accounts, chain_ids, instruction, BridgeState, ChainKind};
+use solana_bridge_sdk::pda;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::instruction::{AccountMeta, Instruction};
+use solana_sdk::program_pack::Pack;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use solana_sdk::system_instruction;
+use solana_sdk::system_program;
+use solana_sdk::transaction::Transaction;
+
+use crate::cluster::Cluster;
+
+/// Seed of the wrapped demo token, created with the deployer as base
+const WRAPPED_SEED: &str = "demo-wrapped";
+
+pub struct Deployer {
+    pub cluster: Cluster,
+    url: String,
+    pub payer: Keypair,
+    payer_path: PathBuf,
+    rpc: RpcClient,
+    root: PathBuf,
+}
+
+impl Deployer {
+    pub fn new(cluster: Cluster, url: String, payer_path: PathBuf, root: PathBuf) -> Result<Self> {
+        Ok(Self {
+            rpc: RpcClient::new_with_commitment(url.clone(), CommitmentConfig::confirmed()),
+            cluster,
+            url,
+            payer: read_keypair(&payer_path)?,
+            payer_path,
+            root,
+        })
+    }
+
+    /// Airdrop the deployer up to the cluster's deployer balance
+    pub fn fund_deployer(&self) -> Result<()> {
+        let target = self.cluster.deployer_lamports();
+        let balance = self.rpc.get_balance(&self.payer.pubkey())?;
+        if balance >= target {
+            return Ok(());
+        }
+        let signature = self
+            .rpc
+            .request_airdrop(&self.payer.pubkey(), target - balance)
+            .context("Airdrop failed; fund the deployer by hand")?;
+        self.rpc.poll_for_signature(&signature)?;
+        println!("Airdropped {} lamports to {}", target - balance, self.payer.pubkey());
+        Ok(())
+    }
+
+    /**
+     * Deploy target/deploy/<name>.so at its declared ID
+     *
+     * Skipped if the program is already there, unless `upgrade`. Deploys
+     * through the solana CLI, which handles buffer writes and retries.
+     */
+    pub fn deploy_program(&self, name: &str, program_id: &Pubkey, upgrade: bool) -> Result<()> {
+        let deployed = self
+            .rpc
+            .get_account_with_commitment(program_id, self.rpc.commitment())?
+            .value
+            .is_some_and(|account| account.executable);
+        if deployed && !upgrade {
+            println!("{name} already deployed at {program_id}");
+            return Ok(());
+        }
+
+        let target = self.root.join("target/deploy");
+        let program = target.join(format!("{name}.so"));
+        let program_keypair = target.join(format!("{name}-keypair.json"));
+        if !program.exists() {
+            bail!("{} not found; run `anchor build` first", program.display());
+        }
+        let key = read_keypair(&program_keypair)?.pubkey();
+        if key != *program_id {
+            bail!(
+                "{} is {key} but {name} declares {program_id}; run `anchor keys sync` and rebuild",
+                program_keypair.display()
+            );
+        }
+
+        let status = Command::new("solana")
+            .args(["program", "deploy", "--url", &self.url])
+            .arg("--keypair")
+            .arg(&self.payer_path)
+            .arg("--program-id")
+            .arg(&program_keypair)
+            .arg(&program)
+            .status()
+            .context("Failed to run solana; is it installed?")?;
+        if !status.success() {
+            bail!("Deploying {name} failed ({status})");
+        }
+        println!("{name} deployed at {program_id}");
+        Ok(())
+    }
+
+    /// Initialize the bridge with the deployer as owner and register Ethereum
+    pub fn initialize_bridge(&self) -> Result<()> {
+        let program_id = solana_bridge::ID;
+        let bridge_state = pda::bridge_state(&program_id);
+        let admin_log = pda::admin_log(&program_id);
+        let owner = self.payer.pubkey();
+
+        let mut instructions = Vec::new();
+        if !self.exists(&bridge_state)? {
+            instructions.push(program_ix(
+                accounts::Initialize {
+                    bridge_state,
+                    admin_log,
+                    outbox: pda::outbox(&program_id),
+                    owner,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                instruction::Initialize {
+                    instance_id: solana_bridge::instance::DEFAULT_INSTANCE,
+                }
+                .data(),
+            ));
+        } else {
+            self.check_owner()?;
+        }
+
+        let chain_config = pda::chain_config(&program_id, chain_ids::ETHEREUM);
+        if !self.exists(&chain_config)? {
+            instructions.push(program_ix(
+                accounts::RegisterChain {
+                    owner,
+                    bridge_state,
+                    admin_log,
+                    chain_config,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                instruction::RegisterChain {
+                    chain_id: chain_ids::ETHEREUM,
+                    kind: ChainKind::Evm,
+                    params: self.cluster.ethereum(),
+                }
+                .data(),
+            ));
+        }
+
+        let fee_tiers = pda::fee_tiers(&program_id);
+        if !self.exists(&fee_tiers)? {
+            instructions.push(program_ix(
+                accounts::SetFeeTiers {
+                    owner,
+                    bridge_state,
+                    admin_log,
+                    fee_tiers,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                instruction::SetFeeTiers { tiers: vec![] }.data(),
+            ));
+        }
+
+        let reward_config = pda::reward_config(&program_id);
+        if !self.exists(&reward_config)? {
+            instructions.push(program_ix(
+                accounts::SetRelayerRewards {
+                    owner,
+                    bridge_state,
+                    admin_log,
+                    reward_config,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                instruction::SetRelayerRewards {
+                    epoch_duration: 86_400,
+                    reward_per_transfer: 0,
+                }
+                .data(),
+            ));
+        }
+
+        self.send("Bridge initialized", &instructions)
+    }
+
+    /**
+     * The demo token, created and registered through the faucet, and a
+     * wrapped demo token the bridge mints for Ethereum locks, both with
+     * route sequences to Ethereum so they can be locked and burned
+     */
+    pub fn demo_tokens(&self) -> Result<(Pubkey, Pubkey)> {
+        let program_id = solana_bridge::ID;
+        let owner = self.payer.pubkey();
+        let faucet = Pubkey::find_program_address(&[b"faucet"], &demo_faucet::ID).0;
+        let demo_mint = Pubkey::find_program_address(&[b"demo_mint"], &demo_faucet::ID).0;
+
+        if !self.exists(&faucet)? {
+            let (drip, cooldown) = self.cluster.faucet();
+            let instruction = Instruction {
+                program_id: demo_faucet::ID,
+                accounts: demo_faucet::accounts::Initialize {
+                    owner,
+                    faucet,
+                    mint: demo_mint,
+                    bridge_state: pda::bridge_state(&program_id),
+                    admin_log: pda::admin_log(&program_id),
+                    token_config: pda::token_config(&program_id, &demo_mint),
+                    bridge_program: program_id,
+                    token_program: spl_token::ID,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                data: demo_faucet::instruction::Initialize {
+                    drip_amount: drip * 10u64.pow(demo_faucet::DECIMALS as u32),
+                    cooldown,
+                }
+                .data(),
+            };
+            self.send("Demo token registered", &[instruction])?;
+        }
+
+        let wrapped_mint = Pubkey::create_with_seed(&owner, WRAPPED_SEED, &spl_token::ID)?;
+        let mut instructions = Vec::new();
+        if !self.exists(&wrapped_mint)? {
+            instructions.push(self.create_with_seed(
+                &wrapped_mint,
+                WRAPPED_SEED,
+                spl_token::state::Mint::LEN,
+            )?);
+            instructions.push(spl_token::instruction::initialize_mint2(
+                &spl_token::ID,
+                &wrapped_mint,
+                &pda::bridge_authority(&program_id),
+                None,
+                demo_faucet::DECIMALS,
+            )?);
+        }
+        let token_config = pda::token_config(&program_id, &wrapped_mint);
+        if !self.exists(&token_config)? {
+            instructions.push(program_ix(
+                accounts::RegisterToken {
+                    owner,
+                    bridge_state: pda::bridge_state(&program_id),
+                    admin_log: pda::admin_log(&program_id),
+                    mint: wrapped_mint,
+                    token_config,
+                    system_program: system_program::ID,
+                }
+                .to_account_metas(None),
+                instruction::RegisterToken {}.data(),
+            ));
+        }
+        self.send("Wrapped demo token registered", &instructions)?;
+
+        let mut instructions = Vec::new();
+        for mint in [demo_mint, wrapped_mint] {
+            let route_sequence = pda::route_sequence(&program_id, &mint, chain_ids::ETHEREUM);
+            if !self.exists(&route_sequence)? {
+                instructions.push(program_ix(
+                    accounts::InitRouteSequence {
+                        payer: owner,
+                        bridge_state: pda::bridge_state(&program_id),
+                        mint,
+                        token_config: pda::token_config(&program_id, &mint),
+                        chain_config: pda::chain_config(&program_id, chain_ids::ETHEREUM),
+                        route_sequence,
+                        system_program: system_program::ID,
+                    }
+                    .to_account_metas(None),
+                    instruction::InitRouteSequence {
+                        dest_chain_id: chain_ids::ETHEREUM,
+                    }
+                    .data(),
+                ));
+            }
+        }
+        self.send("Demo route sequences created", &instructions)?;
+
+        Ok((demo_mint, wrapped_mint))
+    }
+
+    /// The escrow and fee vault for `mint`, owned by the bridge authority
+    pub fn vaults(&self, mint: &Pubkey) -> Result<(Pubkey, Pubkey)> {
+        let bridge_authority = pda::bridge_authority(&solana_bridge::ID);
+        // Seeds are at most 32 bytes; a prefix of the mint keeps them apart
+        let short = &mint.to_string()[..16];
+        let mut addresses = Vec::new();
+        let mut instructions = Vec::new();
+        for kind in ["escrow", "fees"] {
+            let seed = format!("{kind}-{short}");
+            let address = Pubkey::create_with_seed(&self.payer.pubkey(), &seed, &spl_token::ID)?;
+            if !self.exists(&address)? {
+                instructions.push(self.create_with_seed(
+                    &address,
+                    &seed,
+                    spl_token::state::Account::LEN,
+                )?);
+                instructions.push(spl_token::instruction::initialize_account3(
+                    &spl_token::ID,
+                    &address,
+                    mint,
+                    &bridge_authority,
+                )?);
+            }
+            addresses.push(address);
+        }
+        self.send("Vaults created", &instructions)?;
+        Ok((addresses[0], addresses[1]))
+    }
+
+    /// Top the relayer's SOL up to the cluster's relayer balance
+    pub fn fund_relayer(&self, relayer: &Pubkey) -> Result<()> {
+        let target = self.cluster.relayer_lamports();
+        let balance = self.rpc.get_balance(relayer)?;
+        if balance >= target {
+            return Ok(());
+        }
+        let transfer =
+            system_instruction::transfer(&self.payer.pubkey(), relayer, target - balance);
+        self.send("Relayer funded", &[transfer])
+    }
+
+    fn check_owner(&self) -> Result<()> {
+        let account = self.rpc.get_account(&pda::bridge_state(&solana_bridge::ID))?;
+        let state = BridgeState::try_deserialize(&mut account.data.as_slice())?;
+        if state.owner != self.payer.pubkey() {
+            bail!(
+                "The bridge is owned by {}, not the deployer; run with its keypair",
+                state.owner
+            );
+        }
+        Ok(())
+    }
+
+    fn create_with_seed(&self, address: &Pubkey, seed: &str, space: usize) -> Result<Instruction> {
+        let payer = self.payer.pubkey();
+        Ok(system_instruction::create_account_with_seed(
+            &payer,
+            address,
+            &payer,
+            seed,
+            self.rpc.get_minimum_balance_for_rent_exemption(space)?,
+            space as u64,
+            &spl_token::ID,
+        ))
+    }
+
+    fn exists(&self, address: &Pubkey) -> Result<bool> {
+        Ok(self
+            .rpc
+            .get_account_with_commitment(address, self.rpc.commitment())?
+            .value
+            .is_some())
+    }
+
+    /// Send `instructions` if there are any, and say `done`
+    fn send(&self, done: &str, instructions: &[Instruction]) -> Result<()> {
+        if instructions.is_empty() {
+            return Ok(());
+        }
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&self.payer.pubkey()),
+            &[&self.payer],
+            self.rpc.get_latest_blockhash()?,
+        );
+        let signature = self.rpc.send_and_confirm_transaction(&transaction)?;
+        println!("{done}: {signature}");
+        Ok(())
+    }
+}
+
+fn program_ix(metas: Vec<AccountMeta>, data: Vec<u8>) -> Instruction {
+    Instruction {
+        program_id: solana_bridge::ID,
+        accounts: metas,
+        data,
+    }
+}
+
+pub fn read_keypair(path: &Path) -> Result<Keypair> {
+    read_keypair_file(path).map_err(|e| anyhow::anyhow!("{}: {e}", path.display()))
+}